@@ -0,0 +1,194 @@
+// JSON Parser Benchmark
+// A JSON parser written in pure Haxe, scanning a generated JSON Lines document
+// Same program as haxe/BMJsonCode.hx, which runs it on the official Haxe targets
+//
+// Tests: String scanning (charCodeAt, indexOf, split, substring), recursion, number parsing
+
+package benchmarks;
+
+class JsonParser {
+    static inline var TAB = 9;
+    static inline var LF = 10;
+    static inline var CR = 13;
+    static inline var SPACE = 32;
+    static inline var QUOTE = 34;
+    static inline var PLUS = 43;
+    static inline var COMMA = 44;
+    static inline var MINUS = 45;
+    static inline var DOT = 46;
+    static inline var ZERO = 48;
+    static inline var NINE = 57;
+    static inline var UPPER_E = 69;
+    static inline var LBRACKET = 91;
+    static inline var BACKSLASH = 92;
+    static inline var RBRACKET = 93;
+    static inline var LOWER_E = 101;
+    static inline var LOWER_F = 102;
+    static inline var LOWER_N = 110;
+    static inline var LOWER_T = 116;
+    static inline var LBRACE = 123;
+    static inline var RBRACE = 125;
+
+    var src:String;
+    var pos:Int;
+
+    public var objects:Int;
+    public var arrays:Int;
+    public var strings:Int;
+    public var stringBytes:Int;
+    public var numbers:Int;
+    public var numberSum:Float;
+    public var literals:Int;
+
+    public function new() {
+        src = "";
+        pos = 0;
+        objects = 0;
+        arrays = 0;
+        strings = 0;
+        stringBytes = 0;
+        numbers = 0;
+        numberSum = 0.0;
+        literals = 0;
+    }
+
+    public function parse(text:String) {
+        src = text;
+        pos = 0;
+        skipWhitespace();
+        parseValue();
+    }
+
+    function skipWhitespace() {
+        while (true) {
+            var c = src.charCodeAt(pos);
+            if (c == SPACE || c == LF || c == CR || c == TAB) {
+                pos++;
+            } else {
+                break;
+            }
+        }
+    }
+
+    function parseValue() {
+        var c = src.charCodeAt(pos);
+        if (c == LBRACE) {
+            parseObject();
+        } else if (c == LBRACKET) {
+            parseArray();
+        } else if (c == QUOTE) {
+            parseString();
+        } else if (c == LOWER_T || c == LOWER_N) {
+            pos += 4; // true, null
+            literals++;
+        } else if (c == LOWER_F) {
+            pos += 5; // false
+            literals++;
+        } else {
+            parseNumber();
+        }
+    }
+
+    function parseObject() {
+        objects++;
+        pos++;
+        skipWhitespace();
+        if (src.charCodeAt(pos) == RBRACE) {
+            pos++;
+            return;
+        }
+        while (true) {
+            skipWhitespace();
+            parseString();
+            skipWhitespace();
+            pos++; // ':'
+            skipWhitespace();
+            parseValue();
+            skipWhitespace();
+            var c = src.charCodeAt(pos);
+            pos++;
+            if (c != COMMA) {
+                return;
+            }
+        }
+    }
+
+    function parseArray() {
+        arrays++;
+        pos++;
+        skipWhitespace();
+        if (src.charCodeAt(pos) == RBRACKET) {
+            pos++;
+            return;
+        }
+        while (true) {
+            skipWhitespace();
+            parseValue();
+            skipWhitespace();
+            var c = src.charCodeAt(pos);
+            pos++;
+            if (c != COMMA) {
+                return;
+            }
+        }
+    }
+
+    function parseString() {
+        strings++;
+        var start = pos + 1;
+        // Jump to the closing quote, skipping escaped ones
+        var end = src.indexOf("\"", start);
+        while (end > 0 && src.charCodeAt(end - 1) == BACKSLASH) {
+            end = src.indexOf("\"", end + 1);
+        }
+        if (end < 0) {
+            end = src.length;
+        }
+        stringBytes += end - start;
+        pos = end + 1;
+    }
+
+    function parseNumber() {
+        var start = pos;
+        while (true) {
+            var c = src.charCodeAt(pos);
+            if ((c >= ZERO && c <= NINE) || c == MINUS || c == PLUS || c == DOT || c == LOWER_E || c == UPPER_E) {
+                pos++;
+            } else {
+                break;
+            }
+        }
+        numbers++;
+        numberSum += Std.parseFloat(src.substring(start, pos));
+    }
+}
+
+class JsonBench {
+    static inline var RECORDS = 2000;
+    static inline var ROUNDS = 50;
+
+    static function makeRecord(i:Int):String {
+        return "{\"id\": " + i + ", \"name\": \"user" + i + "\", \"email\": \"user" + i + "@example.com\", \"active\": "
+            + (i % 3 == 0 ? "true" : "false") + ", \"score\": " + ((i * 7) % 1000) / 10
+            + ", \"tags\": [\"alpha\", \"beta\", \"gamma" + (i % 10) + "\"], \"address\": {\"street\": \"" + i
+            + " Main St\", \"city\": \"Springfield\", \"zip\": \"" + (10000 + i)
+            + "\"}, \"note\": \"said \\\"hi\\\"\", \"parent\": null, \"history\": []}";
+    }
+
+    public static function main() {
+        var lines = new Array<String>();
+        for (i in 0...RECORDS) {
+            lines.push(makeRecord(i));
+        }
+        var doc = lines.join("\n");
+
+        var parser = new JsonParser();
+        for (round in 0...ROUNDS) {
+            for (line in doc.split("\n")) {
+                parser.parse(line);
+            }
+        }
+        trace("Objects: " + parser.objects + ", strings: " + parser.strings + ", checksum: "
+            + Std.int(parser.numberSum + parser.stringBytes));
+    }
+}
@@ -1,6 +1,80 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
 fn main() {
     // On Linux, export symbols for dynamically loaded shared libraries
     if cfg!(target_os = "linux") {
         println!("cargo:rustc-link-arg=-Wl,--export-dynamic");
     }
+
+    embed_stdlib_bundle();
+    stdlib_fingerprint();
+}
+
+/// Copy the precompiled stdlib bundle (written by `rayzor preblade`) into
+/// OUT_DIR so `stdlib::embedded` can `include_bytes!` it. Without a bundle an
+/// empty file is embedded and the compiler builds the stdlib from source.
+///
+/// Lookup order:
+/// 1. RAYZOR_STDLIB_BUNDLE environment variable
+/// 2. .rayzor/blade/stdlib/stdlib.rzb in the workspace root
+fn embed_stdlib_bundle() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=RAYZOR_STDLIB_BUNDLE");
+
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let bundle_dir = manifest_dir.join("../.rayzor/blade/stdlib");
+    let bundle = match std::env::var_os("RAYZOR_STDLIB_BUNDLE") {
+        Some(path) => PathBuf::from(path),
+        None => bundle_dir.join("stdlib.rzb"),
+    };
+    // Watching the directory (not the file) avoids rerunning on every build
+    // while no bundle exists yet
+    if bundle_dir.exists() {
+        println!("cargo:rerun-if-changed={}", bundle_dir.display());
+    }
+    if bundle.exists() {
+        println!("cargo:rerun-if-changed={}", bundle.display());
+    }
+
+    let out = Path::new(&std::env::var("OUT_DIR").unwrap()).join("stdlib.rzb");
+    let bytes = std::fs::read(&bundle).unwrap_or_default();
+    std::fs::write(&out, bytes).expect("failed to write embedded stdlib bundle");
+}
+
+/// Hash of everything the stdlib bundle is derived from (Rust stdlib
+/// builders, MIR definitions, haxe-std sources). Stored in the bundle so a
+/// stale one is ignored instead of loading MIR that no longer matches.
+fn stdlib_fingerprint() {
+    let mut files = Vec::new();
+    for dir in ["src/stdlib", "src/ir", "haxe-std"] {
+        println!("cargo:rerun-if-changed={}", dir);
+        collect_files(Path::new(dir), &mut files);
+    }
+    files.sort();
+
+    let mut hasher = DefaultHasher::new();
+    for file in &files {
+        file.hash(&mut hasher);
+        std::fs::read(file).unwrap_or_default().hash(&mut hasher);
+    }
+    println!(
+        "cargo:rustc-env=RAYZOR_STDLIB_FINGERPRINT={:016x}",
+        hasher.finish()
+    );
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
 }
@@ -198,6 +198,8 @@ fn create_interp_config() -> TieredConfig {
         start_interpreted: true,
         bailout_strategy: compiler::codegen::BailoutStrategy::Quick,
         max_tier_promotions: 0,
+        function_tiers: Default::default(),
+        pass_size_limits: compiler::ir::optimization::PassSizeLimits::defaults(),
     }
 }
 
@@ -678,6 +678,7 @@ fn get_haxe_source(bench_name: &str) -> Option<(&'static str, &'static str)> {
     match bench_name {
         "nbody" => Some(("BMNBodyCode.hx", "BMNBodyCode")),
         "mandelbrot" => Some(("BMMandelbrotCode.hx", "BMMandelbrotCode")),
+        "json" => Some(("BMJsonCode.hx", "BMJsonCode")),
         _ => None,
     }
 }
@@ -142,6 +142,9 @@ fn main() {
         enable_enhanced_flow_analysis: false,
         enable_memory_safety_analysis: false,
         enable_macro_expansion: true,
+        emit_ownership_report: false,
+        emit_symbol_index: false,
+        allowed_warnings: Vec::new(),
     };
 
     let mut pipeline = HaxeCompilationPipeline::with_config(config);
@@ -147,6 +147,8 @@ fn execute_bundle_interpreted(bundle: &RayzorBundle) -> Result<std::time::Durati
         start_interpreted: true,
         bailout_strategy: compiler::codegen::BailoutStrategy::Quick,
         max_tier_promotions: 0,
+        function_tiers: Default::default(),
+        pass_size_limits: compiler::ir::optimization::PassSizeLimits::defaults(),
     };
 
     let mut backend = TieredBackend::with_symbols(config, &symbols_ref)
@@ -216,6 +218,8 @@ fn compile_and_run(source: &str) -> Result<(), String> {
         start_interpreted: true,
         bailout_strategy: compiler::codegen::BailoutStrategy::Quick,
         max_tier_promotions: 0,
+        function_tiers: Default::default(),
+        pass_size_limits: compiler::ir::optimization::PassSizeLimits::defaults(),
     };
 
     let mut backend = TieredBackend::with_symbols(config, &symbols_ref)
@@ -36,6 +36,10 @@
 //! - Field read/write via byte offsets
 //! - cdef() static method returning C typedef string
 //! - Interop with TinyCC JIT (Haxe→C and C→Haxe)
+//! - @:struct value types: copy semantics, inline nesting, contiguous arrays
+//! - @:fixed(N) inline arrays with a compile-time element count
+//! - layout() shared layout descriptors
+//! - @:struct field packing and the @:keepLayout opt-out
 
 use compiler::codegen::CraneliftBackend;
 use compiler::compilation::{CompilationConfig, CompilationUnit};
@@ -745,6 +749,283 @@ class Main {
 "#,
     ));
 
+    // ============================================================================
+    // TEST 20: @:struct value semantics — assignment, parameters and returns copy
+    // ============================================================================
+    tests.push(E2ETestCase::new(
+        "value_struct_copy",
+        r#"
+package test;
+
+@:struct
+class Vec2 {
+    public var x:Float;
+    public var y:Float;
+
+    public function new(x:Float, y:Float) {
+        this.x = x;
+        this.y = y;
+    }
+}
+
+class Main {
+    static function bump(v:Vec2):Vec2 {
+        v.x += 1.0;
+        return v;
+    }
+
+    static function main() {
+        var a = new Vec2(1.0, 2.0);
+        var b = a;
+        b.x = 10.0;
+        trace(a.x);  // 1.0
+        trace(b.x);  // 10.0
+
+        var c = bump(a);
+        trace(a.x);  // 1.0
+        trace(c.x);  // 2.0
+    }
+}
+"#,
+    ));
+
+    // ============================================================================
+    // TEST 21: @:struct nested inline in another @:struct
+    // ============================================================================
+    tests.push(E2ETestCase::new(
+        "value_struct_nested",
+        r#"
+package test;
+
+@:struct
+class Vec2 {
+    public var x:Float;
+    public var y:Float;
+
+    public function new(x:Float, y:Float) {
+        this.x = x;
+        this.y = y;
+    }
+}
+
+@:struct
+class Particle {
+    public var pos:Vec2;
+    public var life:Int;
+
+    public function new() {}
+}
+
+class Main {
+    static function main() {
+        var p = new Particle();
+        var start = new Vec2(3.0, 4.0);
+        p.pos = start;
+        start.x = 100.0;
+        p.pos.y = 5.0;
+        p.life = 7;
+        trace(p.pos.x);  // 3.0
+        trace(p.pos.y);  // 5.0
+        trace(p.life);   // 7
+    }
+}
+"#,
+    ));
+
+    // ============================================================================
+    // TEST 22: Array<@:struct> stores elements contiguously
+    // ============================================================================
+    tests.push(E2ETestCase::new(
+        "value_struct_array",
+        r#"
+package test;
+
+@:struct
+class Vec2 {
+    public var x:Float;
+    public var y:Float;
+
+    public function new(x:Float, y:Float) {
+        this.x = x;
+        this.y = y;
+    }
+}
+
+class Main {
+    static function main() {
+        var points = [new Vec2(1.0, 2.0), new Vec2(3.0, 4.0)];
+        var v = new Vec2(5.0, 6.0);
+        points.push(v);
+        v.x = 50.0;
+        points[0] = new Vec2(7.0, 8.0);
+        points[1].y = 40.0;
+
+        var sum = 0.0;
+        for (p in points) {
+            sum += p.x + p.y;
+        }
+        trace(points[2].x);  // 5.0
+        trace(sum);          // 7 + 8 + 3 + 40 + 5 + 6 = 69.0
+    }
+}
+"#,
+    ));
+
+    // ============================================================================
+    // TEST 23: @:fixed(N) inline arrays — indexing, constant length, cdef()
+    // ============================================================================
+    tests.push(E2ETestCase::new(
+        "fixed_array_field",
+        r#"
+package test;
+
+@:cstruct
+class Samples {
+    @:fixed(4) public var data:Array<Float>;
+    @:fixed(3) public var ids:Array<Int>;
+    public var count:Int;
+
+    public function new() {}
+}
+
+class Main {
+    static function main() {
+        var s = new Samples();
+        for (i in 0...s.data.length) {
+            s.data[i] = i * 1.5;
+        }
+        s.ids[2] = 42;
+        s.count = s.ids.length;
+
+        var sum = 0.0;
+        for (i in 0...4) {
+            sum += s.data[i];
+        }
+        trace(sum);       // 0 + 1.5 + 3 + 4.5 = 9.0
+        trace(s.ids[2]);  // 42
+        trace(s.ids[0]);  // 0 (zero-initialized)
+        trace(s.count);   // 3
+        trace(Samples.cdef());  // typedef struct { double data[4]; int ids[3]; long count; } test_Samples;
+    }
+}
+"#,
+    ));
+
+    // ============================================================================
+    // TEST 24: @:fixed(N) field of a @:struct is copied with its owner
+    // ============================================================================
+    tests.push(E2ETestCase::new(
+        "fixed_array_value_copy",
+        r#"
+package test;
+
+@:struct
+class Mat2 {
+    @:fixed(4) public var m:Array<Float>;
+
+    public function new() {}
+}
+
+class Main {
+    static function main() {
+        var a = new Mat2();
+        a.m[0] = 1.0;
+        a.m[3] = 1.0;
+        var b = a;
+        b.m[0] = 5.0;
+
+        var c = new Mat2();
+        c.m = a.m;
+        a.m[3] = 9.0;
+
+        trace(a.m[0]);  // 1.0
+        trace(b.m[0]);  // 5.0
+        trace(c.m[3]);  // 1.0
+    }
+}
+"#,
+    ));
+
+    // ============================================================================
+    // TEST 25: layout() — shared layout descriptor for @:cstruct and @:struct
+    // ============================================================================
+    tests.push(E2ETestCase::new(
+        "struct_layout_descriptor",
+        r#"
+package test;
+
+@:cstruct
+class Sample {
+    public var flag:Bool;
+    public var value:Float;
+    @:fixed(3) public var ids:Array<Int>;
+
+    public function new() {}
+}
+
+@:struct
+class Vec2 {
+    public var x:Float;
+    public var y:Float;
+
+    public function new(x:Float, y:Float) {
+        this.x = x;
+        this.y = y;
+    }
+}
+
+class Main {
+    static function main() {
+        trace(Sample.layout());  // test_Sample,32,8;flag,i32,0,4,1;value,f64,8,8,1;ids,i32,16,12,3
+        trace(Vec2.layout());    // test_Vec2,16,8;x,f64,0,8,1;y,f64,8,8,1
+    }
+}
+"#,
+    ));
+
+    // ============================================================================
+    // TEST 26: @:struct fields packed by alignment unless @:keepLayout
+    // ============================================================================
+    tests.push(E2ETestCase::new(
+        "value_struct_packing",
+        r#"
+package test;
+
+@:struct
+class Tagged {
+    public var flag:Bool;
+    public var value:Float;
+    public var ready:Bool;
+
+    public function new() {}
+}
+
+@:struct
+@:keepLayout
+class TaggedFfi {
+    public var flag:Bool;
+    public var value:Float;
+    public var ready:Bool;
+
+    public function new() {}
+}
+
+class Main {
+    static function main() {
+        var t = new Tagged();
+        t.flag = true;
+        t.value = 2.5;
+        var copy = t;
+        trace(copy.flag);              // true
+        trace(copy.ready);             // false
+        trace(copy.value);             // 2.5
+        trace(Tagged.layout());        // test_Tagged,16,8;value,f64,0,8,1;flag,i32,8,4,1;ready,i32,12,4,1
+        trace(TaggedFfi.layout());     // test_TaggedFfi,24,8;flag,i32,0,4,1;value,f64,8,8,1;ready,i32,16,4,1
+    }
+}
+"#,
+    ));
+
     // Run all tests
     println!("╔══════════════════════════════════════════════════════════════════════╗");
     println!("║             @:cstruct Metadata — E2E Test Suite                    ║");
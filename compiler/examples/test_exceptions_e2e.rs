@@ -0,0 +1,224 @@
+//! Exception handling end-to-end test suite
+//!
+//! Compiles and runs programs that throw, and checks their trace output:
+//! - class catches match subclasses, by the thrown object's runtime class
+//! - `Any` and `Dynamic` catch everything
+//! - an exception no clause matches propagates to the enclosing try
+//! - `finally` runs before the exception moves on
+
+use compiler::codegen::CraneliftBackend;
+use compiler::compilation::{CompilationConfig, CompilationUnit};
+use rayzor_runtime::stdout::StdoutCapture;
+
+/// Classes shared by the test programs
+const FAILURES: &str = r#"
+interface Coded {
+    function code():Int;
+}
+
+class Failure implements Coded {
+    var status:Int;
+
+    public function new(status:Int) {
+        this.status = status;
+    }
+
+    public function code():Int {
+        return status;
+    }
+}
+
+class NotFound extends Failure {
+    public function new() {
+        super(404);
+    }
+}
+"#;
+
+/// A single end-to-end test case
+struct E2ETestCase {
+    name: String,
+    /// Body of `Main.main()`
+    main_body: String,
+    /// Lines the program traces, in order
+    expected_output: Vec<String>,
+}
+
+impl E2ETestCase {
+    fn new(name: &str, main_body: &str, expected_output: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            main_body: main_body.to_string(),
+            expected_output: expected_output.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn source(&self) -> String {
+        format!(
+            "{}\nclass Main {{\n    static function main() {{\n{}\n    }}\n}}\n",
+            FAILURES, self.main_body
+        )
+    }
+
+    fn run(&self) -> Result<(), String> {
+        println!("\n{}", "=".repeat(70));
+        println!("TEST: {}", self.name);
+        println!("{}", "=".repeat(70));
+
+        let mut unit = CompilationUnit::new(CompilationConfig::fast());
+        unit.load_stdlib()
+            .map_err(|e| format!("Failed to load stdlib: {}", e))?;
+        unit.add_file(&self.source(), &format!("{}.hx", self.name))
+            .map_err(|e| format!("Failed to add file: {}", e))?;
+        unit.lower_to_tast()
+            .map_err(|errors| format!("TAST failed: {:?}", errors))?;
+
+        let mir_modules = unit.get_mir_modules();
+        if mir_modules.is_empty() {
+            return Err("No MIR modules generated".to_string());
+        }
+
+        let plugin = rayzor_runtime::plugin_impl::get_plugin();
+        let symbols = plugin.runtime_symbols();
+        let symbols_ref: Vec<(&str, *const u8)> = symbols.iter().map(|(n, p)| (*n, *p)).collect();
+        let mut backend = CraneliftBackend::with_symbols(&symbols_ref)
+            .map_err(|e| format!("Backend init failed: {}", e))?;
+        for module in &mir_modules {
+            backend
+                .compile_module(module)
+                .map_err(|e| format!("Codegen failed: {}", e))?;
+        }
+
+        let capture = StdoutCapture::start(true);
+        let ran = mir_modules
+            .iter()
+            .rev()
+            .any(|module| backend.call_main(module).is_ok());
+        let output = capture.finish();
+        if !ran {
+            return Err("Failed to execute main".to_string());
+        }
+
+        let lines: Vec<&str> = output.lines().collect();
+        if lines != self.expected_output {
+            return Err(format!(
+                "expected output {:?}, got {:?}",
+                self.expected_output, lines
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), String> {
+    println!("=== Rayzor Exceptions E2E Test Suite ===\n");
+
+    let tests = vec![
+        E2ETestCase::new(
+            "subclass_caught_by_parent",
+            r#"
+        try {
+            throw new NotFound();
+        } catch (e:String) {
+            trace("should not print");
+        } catch (e:Failure) {
+            trace(e.code());
+        }
+"#,
+            &["404"],
+        ),
+        E2ETestCase::new(
+            "runtime_class_decides",
+            r#"
+        var failure:Failure = new NotFound();
+        try {
+            throw failure;
+        } catch (e:NotFound) {
+            trace("not found");
+        }
+"#,
+            &["not found"],
+        ),
+        E2ETestCase::new(
+            "interface_value_caught_by_class",
+            r#"
+        var coded:Coded = new Failure(418);
+        try {
+            throw coded;
+        } catch (e:Failure) {
+            trace(e.code());
+        }
+"#,
+            &["418"],
+        ),
+        E2ETestCase::new(
+            "unmatched_propagates",
+            r#"
+        try {
+            try {
+                throw "oops";
+            } catch (e:Int) {
+                trace("should not print");
+            }
+            trace("should not print");
+        } catch (e:String) {
+            trace(e);
+        }
+"#,
+            &["oops"],
+        ),
+        E2ETestCase::new(
+            "any_and_dynamic_catch_everything",
+            r#"
+        try {
+            throw new Failure(500);
+        } catch (e:Any) {
+            trace("any");
+        }
+        try {
+            throw 7;
+        } catch (e:Dynamic) {
+            trace("dynamic");
+        }
+"#,
+            &["any", "dynamic"],
+        ),
+        E2ETestCase::new(
+            "finally_before_propagation",
+            r#"
+        try {
+            try {
+                throw new Failure(503);
+            } catch (e:String) {
+                trace("should not print");
+            } finally {
+                trace("finally");
+            }
+        } catch (e:Failure) {
+            trace(e.code());
+        }
+"#,
+            &["finally", "503"],
+        ),
+    ];
+
+    let mut failed = 0;
+    for test in &tests {
+        match test.run() {
+            Ok(()) => println!("\n✅ {} PASSED", test.name),
+            Err(error) => {
+                println!("\n❌ {} FAILED: {}", test.name, error);
+                failed += 1;
+            }
+        }
+    }
+
+    println!("\n{}", "=".repeat(70));
+    println!("Passed: {}/{}", tests.len() - failed, tests.len());
+    if failed == 0 {
+        println!("\n🎉 All tests passed!");
+        Ok(())
+    } else {
+        Err(format!("{} test(s) failed", failed))
+    }
+}
@@ -225,6 +225,8 @@ fn compile_and_run_interpreted(source: &str, name: &str) -> Result<(), String> {
         start_interpreted: true, // Start in interpreter mode
         bailout_strategy: compiler::codegen::BailoutStrategy::Quick,
         max_tier_promotions: 0,
+        function_tiers: Default::default(),
+        pass_size_limits: compiler::ir::optimization::PassSizeLimits::defaults(),
     };
 
     let mut backend = TieredBackend::with_symbols(config, &symbols_ref)
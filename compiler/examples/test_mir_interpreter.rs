@@ -138,6 +138,8 @@ fn test_tiered_with_interpreter() {
         start_interpreted: true, // Start in interpreter mode
         bailout_strategy: compiler::codegen::BailoutStrategy::Quick,
         max_tier_promotions: 0,
+        function_tiers: Default::default(),
+        pass_size_limits: compiler::ir::optimization::PassSizeLimits::defaults(),
     };
 
     // Create tiered backend
@@ -35,7 +35,9 @@
 //! - SIMD4f.splat(): broadcast scalar to all 4 lanes
 //! - SIMD4f.make(): construct from 4 individual values
 //! - Arithmetic operators: +, -, *, /
-//! - Lane access: extract, insert
+//! - Lane access: extract, insert (runtime lane index)
+//! - Lane permutation: shuffle
+//! - rayzor.simd.Float32x4: portable name for the same f32x4 type
 //! - Reductions: sum, dot
 
 use compiler::codegen::CraneliftBackend;
@@ -478,6 +480,81 @@ class Main {
 "#,
     ));
 
+    // ============================================================================
+    // TEST 17: SIMD4f lane access with a runtime index
+    // ============================================================================
+    tests.push(E2ETestCase::new(
+        "simd4f_dynamic_lane",
+        r#"
+package test;
+
+import rayzor.SIMD4f;
+
+class Main {
+    static function main() {
+        var a = SIMD4f.make(1.0, 2.0, 3.0, 4.0);
+        var total = 0.0;
+        for (i in 0...4) {
+            total += a[i] * (i + 1);
+        }
+        trace(total);  // 1+4+9+16 = 30.0
+        var b = a.set(2, 10.0);
+        trace(b[2]);   // 10.0
+        trace(b.sum()); // 1+2+10+4 = 17.0
+    }
+}
+"#,
+    ));
+
+    // ============================================================================
+    // TEST 18: SIMD4f.shuffle — lane permutation
+    // ============================================================================
+    tests.push(E2ETestCase::new(
+        "simd4f_shuffle",
+        r#"
+package test;
+
+import rayzor.SIMD4f;
+
+class Main {
+    static function main() {
+        var a = SIMD4f.make(1.0, 2.0, 3.0, 4.0);
+        var r = a.shuffle(3, 2, 1, 0);
+        trace(r[0]);  // 4.0
+        trace(r[3]);  // 1.0
+        var w = a.shuffle(0, 0, 1, 1);
+        trace(w.dot(SIMD4f.make(1.0, 10.0, 100.0, 1000.0)));  // 1+10+200+2000 = 2211.0
+    }
+}
+"#,
+    ));
+
+    // ============================================================================
+    // TEST 19: rayzor.simd.Float32x4 — portable name for the same vector type
+    // ============================================================================
+    tests.push(E2ETestCase::new(
+        "float32x4_portable",
+        r#"
+package test;
+
+import rayzor.simd.Float32x4;
+
+class Main {
+    static function main() {
+        var a = Float32x4.make(1.0, 2.0, 3.0, 4.0);
+        var b = Float32x4.splat(2.0);
+        var c = (a + b) * b;          // [6, 8, 10, 12]
+        var r = c.shuffle(3, 2, 1, 0); // [12, 10, 8, 6]
+        var i = 1;
+        trace(r[i]);                  // 10.0
+        trace(r.max(Float32x4.splat(9.0)).sum());  // 12+10+9+9 = 40.0
+        var v:Float32x4 = [0.5, 0.5, 0.5, 0.5];
+        trace(v.dot(c));              // 18.0
+    }
+}
+"#,
+    ));
+
     // Run all tests
     println!("╔══════════════════════════════════════════════════════════════════════╗");
     println!("║            SIMD4f — E2E Test Suite                                 ║");
@@ -38,6 +38,7 @@
 //! - Semaphore: Counting semaphores
 //! - Deque<T>: Thread-safe double-ended queue
 //! - Condition: Condition variables for thread synchronization
+//! - Tls<T>: Thread-local storage
 //!
 //! ## Closure Capture Semantics (IMPORTANT)
 //!
@@ -1121,6 +1122,153 @@ class Main {
         .expect_mir_calls(vec!["rayzor_semaphore_acquire", "rayzor_semaphore_release"]),
     );
 
+    // ============================================================================
+    // TLS TESTS
+    // ============================================================================
+
+    // TEST 22: Each thread sees its own Tls value
+    suite.add_test(
+        E2ETestCase::new(
+            "tls_per_thread",
+            "Tls value is confined to the thread that set it",
+            r#"
+package test;
+
+import sys.thread.Thread;
+import sys.thread.Deque;
+import sys.thread.Tls;
+
+class Main {
+    static function main() {
+        var tls = new Tls<Int>();
+        var results = new Deque<Int>();
+        tls.value = 1;
+
+        var worker = Thread.create(() -> {
+            tls.value = 2;
+            results.add(tls.value);
+        });
+        worker.join();
+
+        trace(results.pop(false));
+        trace(tls.value);
+    }
+}
+"#,
+        )
+        .expect_mir_calls(vec!["rayzor_tls_alloc", "rayzor_tls_set", "rayzor_tls_get"]),
+    );
+
+    // ============================================================================
+    // COROUTINE TESTS
+    // ============================================================================
+
+    // TEST 23: Coroutine yields values to resume
+    suite.add_test(
+        E2ETestCase::new(
+            "coroutine_generator",
+            "Coroutine yields a sequence of values to resume",
+            r#"
+package test;
+
+import sys.thread.Coroutine;
+
+class Main {
+    static function main() {
+        var counter = Coroutine.create(() -> {
+            for (i in 0...3) {
+                Coroutine.yield(i);
+            }
+        });
+
+        var sum = 0;
+        while (true) {
+            var value:Null<Int> = counter.resume();
+            if (counter.isDead()) break;
+            sum += value;
+        }
+
+        trace(sum);
+        trace(counter.status());
+    }
+}
+"#,
+        )
+        .expect_mir_calls(vec![
+            "Coroutine_create",
+            "rayzor_coroutine_resume",
+            "rayzor_coroutine_yield",
+            "rayzor_coroutine_is_dead",
+        ]),
+    );
+
+    // TEST 24: @:yield generator iterated with for
+    suite.add_test(
+        E2ETestCase::new(
+            "generator_for_loop",
+            "Generator method with @:yield iterated lazily by a for loop",
+            r#"
+package test;
+
+class Main {
+    static function evens(limit:Int):Iterator<Int> {
+        var i = 0;
+        while (i < limit) {
+            @:yield i;
+            i += 2;
+        }
+    }
+
+    static function main() {
+        var sum = 0;
+        for (n in evens(10)) {
+            sum += n;
+        }
+        trace(sum);
+    }
+}
+"#,
+        )
+        .expect_mir_calls(vec!["Coroutine_create", "rayzor_coroutine_yield"]),
+    );
+
+    // TEST 25: generator stopped early and disposed
+    suite.add_test(
+        E2ETestCase::new(
+            "generator_dispose",
+            "Generator abandoned before its body returns frees its coroutine",
+            r#"
+package test;
+
+import rayzor.Generator;
+
+class Main {
+    static function naturals():Generator<Int> {
+        var i = 0;
+        while (true) {
+            @:yield i;
+            i++;
+        }
+    }
+
+    static function main() {
+        var gen = naturals();
+        var sum = 0;
+        while (gen.hasNext()) {
+            var n = gen.next();
+            if (n == 5) break;
+            sum += n;
+        }
+        gen.dispose();
+        trace(sum);
+        trace(gen.hasNext());
+    }
+}
+"#,
+        )
+        .expect_mir_calls(vec!["rayzor_coroutine_destroy"]),
+    );
+
     // Run all tests
     let results = suite.run_all();
     suite.print_summary(&results);
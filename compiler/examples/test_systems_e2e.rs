@@ -32,7 +32,8 @@
 //!
 //! Tests the complete pipeline for rayzor systems types:
 //! - Box<T>: single-owner heap allocation
-//! - Ptr<T>: raw mutable pointer
+//! - Ptr<T>: raw mutable pointer, including owned alloc/get/set/free memory
+//! - Unsafe: memcpy/memset over pointers
 //! - Ref<T>: read-only reference
 //! - Usize: unsigned pointer-sized integer
 //! - Arc.asPtrTyped() / Arc.asRef(): typed pointer access to Arc data
@@ -451,6 +452,47 @@ class Main {
         boxed.free();
     }
 }
+"#,
+        ),
+        // ============================================================================
+        // TEST 10: Ptr — alloc, get/set, free + Unsafe memcpy/memset
+        // ============================================================================
+        E2ETestCase::new(
+            "ptr_alloc_unsafe",
+            r#"
+package test;
+
+import rayzor.Ptr;
+import rayzor.Unsafe;
+
+class Main {
+    static function main() {
+        // Own raw heap memory for 4 elements
+        var src:Ptr<Int> = Ptr.alloc(4);
+        var dst:Ptr<Int> = Ptr.alloc(4);
+
+        // Zero it, then fill by index
+        Unsafe.memset(src, 0, 4 * 8);
+        var i = 0;
+        while (i < 4) {
+            src.set(i, i * 10);
+            i++;
+        }
+
+        // Bulk copy and read back
+        Unsafe.memcpy(dst, src, 4 * 8);
+        var third = dst.get(2);  // 20
+        var last = dst.offset(3).deref();  // 30
+
+        // Non-positive sizes give a null pointer, which free ignores
+        var empty:Ptr<Int> = Ptr.alloc(0);
+        var isNull = empty.isNull();  // true
+        empty.free();
+
+        src.free();
+        dst.free();
+    }
+}
 "#,
         ),
     ];
@@ -78,6 +78,8 @@ fn main() {
         start_interpreted: false,
         bailout_strategy: compiler::codegen::BailoutStrategy::Quick,
         max_tier_promotions: 3,
+        function_tiers: Default::default(),
+        pass_size_limits: compiler::ir::optimization::PassSizeLimits::defaults(),
     };
 
     // Create tiered backend and compile module
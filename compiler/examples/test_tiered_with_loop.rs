@@ -127,6 +127,8 @@ class Math {
         start_interpreted: false,
         bailout_strategy: compiler::codegen::BailoutStrategy::Quick,
         max_tier_promotions: 3,
+        function_tiers: Default::default(),
+        pass_size_limits: compiler::ir::optimization::PassSizeLimits::defaults(),
     };
 
     let mut backend = TieredBackend::new(config)?;
@@ -146,6 +146,9 @@ extern class Array<T> {
 		order of equal elements may not be retained. For a stable Array sorting
 		algorithm, `haxe.ds.ArraySort.sort()` can be used instead.
 
+		(rayzor) The sort is stable. In debug builds, a comparator that is not
+		transitive or not antisymmetric is reported as a runtime error.
+
 		If `f` is null, the result is unspecified.
 	**/
 	function sort(f:T->T->Int):Void;
@@ -328,6 +331,17 @@ extern class Array<T> {
 		- always null on dynamic targets
 		- 0, 0.0 or false for Int, Float and Bool respectively on static targets
 		- null for other types on static targets
+
+		If `fill` is given, new elements are set to `fill` instead.
+	**/
+	function resize(len:Int, ?fill:T):Void;
+
+	/**
+		Release unused capacity, so the Array only holds memory for its
+		current elements.
+
+		Arrays grow geometrically to keep `push` cheap; call this after
+		building a large Array that will not grow further.
 	**/
-	function resize(len:Int):Void;
+	function shrinkToFit():Void;
 }
@@ -20,7 +20,6 @@
  * DEALINGS IN THE SOFTWARE.
  */
 
-import haxe.ds.List;
 
 /**
 	The `Lambda` class is a collection of methods to support functional
@@ -34,68 +33,52 @@ import haxe.ds.List;
 	If the first argument to any of the methods is null, the result is
 	unspecified.
 
+	(rayzor) Implemented natively: Array and List arguments are handled by the
+	runtime, with callbacks passed as closures.
+
 	@see https://haxe.org/manual/std-Lambda.html
 **/
-class Lambda {
+extern class Lambda {
 	/**
 		Creates an Array from Iterable `it`.
 
 		If `it` is an Array, this function returns a copy of it.
 	**/
-	public static function array<A>(it:Iterable<A>):Array<A> {
-		var a = new Array<A>();
-		for (i in it)
-			a.push(i);
-		return a;
-	}
+	static function array<A>(it:Iterable<A>):Array<A>;
 
 	/**
 		Creates a List form Iterable `it`.
 
 		If `it` is a List, this function returns a copy of it.
 	**/
-	public static function list<A>(it:Iterable<A>):List<A> {
-		var l = new List<A>();
-		for (i in it)
-			l.add(i);
-		return l;
-	}
+	static function list<A>(it:Iterable<A>):List<A>;
 
 	/**
 		Creates a new Array by applying function `f` to all elements of `it`.
 		The order of elements is preserved.
 		If `f` is null, the result is unspecified.
 	**/
-	public static inline function map<A, B>(it:Iterable<A>, f:(item:A) -> B):Array<B> {
-		return [for (x in it) f(x)];
-	}
+	static function map<A, B>(it:Iterable<A>, f:A->B):Array<B>;
 
 	/**
 		Similar to map, but also passes the index of each element to `f`.
 		The order of elements is preserved.
 		If `f` is null, the result is unspecified.
 	**/
-	public static inline function mapi<A, B>(it:Iterable<A>, f:(index:Int, item:A) -> B):Array<B> {
-		var i = 0;
-		return [for (x in it) f(i++, x)];
-	}
+	static function mapi<A, B>(it:Iterable<A>, f:Int->A->B):Array<B>;
 
 	/**
 		Concatenate a list of iterables.
 		The order of elements is preserved.
 	**/
-	public static inline function flatten<A>(it:Iterable<Iterable<A>>):Array<A> {
-		return [for (e in it) for (x in e) x];
-	}
+	static function flatten<A>(it:Iterable<Iterable<A>>):Array<A>;
 
 	/**
 		A composition of map and flatten.
 		The order of elements is preserved.
 		If `f` is null, the result is unspecified.
 	**/
-	public static inline function flatMap<A, B>(it:Iterable<A>, f:(item:A) -> Iterable<B>):Array<B> {
-		return Lambda.flatten(Lambda.map(it, f));
-	}
+	static function flatMap<A, B>(it:Iterable<A>, f:A->Iterable<B>):Array<B>;
 
 	/**
 		Tells if `it` contains `elt`.
@@ -105,12 +88,7 @@ class Lambda {
 
 		If no such element is found, the result is false.
 	**/
-	public static function has<A>(it:Iterable<A>, elt:A):Bool {
-		for (x in it)
-			if (x == elt)
-				return true;
-		return false;
-	}
+	static function has<A>(it:Iterable<A>, elt:A):Bool;
 
 	/**
 		Tells if `it` contains an element for which `f` is true.
@@ -122,12 +100,7 @@ class Lambda {
 
 		If `f` is null, the result is unspecified.
 	**/
-	public static function exists<A>(it:Iterable<A>, f:(item:A) -> Bool) {
-		for (x in it)
-			if (f(x))
-				return true;
-		return false;
-	}
+	static function exists<A>(it:Iterable<A>, f:A->Bool):Bool;
 
 	/**
 		Tells if `f` is true for all elements of `it`.
@@ -141,22 +114,14 @@ class Lambda {
 
 		If `f` is null, the result is unspecified.
 	**/
-	public static function foreach<A>(it:Iterable<A>, f:(item:A) -> Bool) {
-		for (x in it)
-			if (!f(x))
-				return false;
-		return true;
-	}
+	static function foreach<A>(it:Iterable<A>, f:A->Bool):Bool;
 
 	/**
 		Calls `f` on all elements of `it`, in order.
 
 		If `f` is null, the result is unspecified.
 	**/
-	public static function iter<A>(it:Iterable<A>, f:(item:A) -> Void) {
-		for (x in it)
-			f(x);
-	}
+	static function iter<A>(it:Iterable<A>, f:A->Void):Void;
 
 	/**
 		Returns a Array containing those elements of `it` for which `f` returned
@@ -164,9 +129,7 @@ class Lambda {
 		If `it` is empty, the result is the empty Array even if `f` is null.
 		Otherwise if `f` is null, the result is unspecified.
 	**/
-	public static function filter<A>(it:Iterable<A>, f:(item:A) -> Bool) {
-		return [for (x in it) if (f(x)) x];
-	}
+	static function filter<A>(it:Iterable<A>, f:A->Bool):Array<A>;
 
 	/**
 		Functional fold on Iterable `it`, using function `f` with start argument
@@ -180,25 +143,14 @@ class Lambda {
 
 		If `it` or `f` are null, the result is unspecified.
 	**/
-	public static function fold<A, B>(it:Iterable<A>, f:(item:A, result:B) -> B, first:B):B {
-		for (x in it)
-			first = f(x, first);
-		return first;
-	}
+	static function fold<A, B>(it:Iterable<A>, f:A->B->B, first:B):B;
 
 	/**
 		Similar to fold, but also passes the index of each element to `f`.
 
 		If `it` or `f` are null, the result is unspecified.
 	**/
-	public static function foldi<A, B>(it:Iterable<A>, f:(item:A, result:B, index:Int) -> B, first:B):B {
-		var i = 0;
-		for (x in it) {
-			first = f(x, first, i);
-			++i;
-		}
-		return first;
-	}
+	static function foldi<A, B>(it:Iterable<A>, f:A->B->Int->B, first:B):B;
 
 	/**
 		Returns the number of elements in `it` for which `pred` is true, or the
@@ -206,24 +158,12 @@ class Lambda {
 
 		This function traverses all elements.
 	**/
-	public static function count<A>(it:Iterable<A>, ?pred:(item:A) -> Bool) {
-		var n = 0;
-		if (pred == null)
-			for (_ in it)
-				n++;
-		else
-			for (x in it)
-				if (pred(x))
-					n++;
-		return n;
-	}
+	static function count<A>(it:Iterable<A>, ?pred:A->Bool):Int;
 
 	/**
 		Tells if Iterable `it` does not contain any element.
 	**/
-	public static function empty<T>(it:Iterable<T>):Bool {
-		return !it.iterator().hasNext();
-	}
+	static function empty<T>(it:Iterable<T>):Bool;
 
 	/**
 		Returns the index of the first element `v` within Iterable `it`.
@@ -232,15 +172,7 @@ class Lambda {
 
 		If `v` does not exist in `it`, the result is -1.
 	**/
-	public static function indexOf<T>(it:Iterable<T>, v:T):Int {
-		var i = 0;
-		for (v2 in it) {
-			if (v == v2)
-				return i;
-			i++;
-		}
-		return -1;
-	}
+	static function indexOf<T>(it:Iterable<T>, v:T):Int;
 
 	/**
 		Returns the first element of `it` for which `f` is true.
@@ -252,13 +184,7 @@ class Lambda {
 
 		If `f` is null, the result is unspecified.
 	**/
-	public static function find<T>(it:Iterable<T>, f:(item:T) -> Bool):Null<T> {
-		for (v in it) {
-			if (f(v))
-				return v;
-		}
-		return null;
-	}
+	static function find<T>(it:Iterable<T>, f:T->Bool):Null<T>;
 
 	/**
 		Returns the index of the first element of `it` for which `f` is true.
@@ -270,15 +196,7 @@ class Lambda {
 
 		If `f` is null, the result is unspecified.
 	**/
-	public static function findIndex<T>(it:Iterable<T>, f:(item:T) -> Bool):Int {
-		var i = 0;
-		for (v in it) {
-			if (f(v))
-				return i;
-			i++;
-		}
-		return -1;
-	}
+	static function findIndex<T>(it:Iterable<T>, f:T->Bool):Int;
 
 	/**
 		Returns a new Array containing all elements of Iterable `a` followed by
@@ -286,12 +204,5 @@ class Lambda {
 
 		If `a` or `b` are null, the result is unspecified.
 	**/
-	public static function concat<T>(a:Iterable<T>, b:Iterable<T>):Array<T> {
-		var l = new Array();
-		for (x in a)
-			l.push(x);
-		for (x in b)
-			l.push(x);
-		return l;
-	}
+	static function concat<T>(a:Iterable<T>, b:Iterable<T>):Array<T>;
 }
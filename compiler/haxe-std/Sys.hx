@@ -47,6 +47,7 @@ extern class Sys {
 
 		(cs) Non-ASCII Unicode arguments will not work correctly.
 	**/
+	@:native("haxe_sys_args")
 	static function args():Array<String>;
 
 	/**
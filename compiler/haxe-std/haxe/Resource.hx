@@ -75,6 +75,10 @@ class Resource {
 	}
 
 	static function __init__() {
+		#if rayzor
+		content = [];
+		#else
 		content = untyped __resources__();
+		#end
 	}
 }
@@ -189,6 +189,8 @@ class Timer {
 		return untyped __global__.__time_stamp();
 		#elseif python
 		return Sys.cpuTime();
+		#elseif rayzor
+		return rayzor.runtime.Clock.stamp();
 		#elseif sys
 		return Sys.time();
 		#else
@@ -25,46 +25,70 @@ package haxe.ds;
 /**
 	EnumValueMap allows mapping of enum value keys to arbitrary values.
 
-	Keys are compared by value and recursively over their parameters. If any
-	parameter is not an enum value, `Reflect.compare` is used to compare them.
+	Keys are compared by value and recursively over their parameters.
+
+	(rayzor) Implemented natively by the runtime, sharing the representation
+	of `ObjectMap`. Keys are hashed and compared structurally from the enum's
+	constructor metadata: enum parameters recursively, Int, Float, Bool and
+	String parameters by value, and any other parameter by identity.
+
+	See `Map` for documentation details.
+
+	@see https://haxe.org/manual/std-Map.html
 **/
-class EnumValueMap<K:EnumValue, V> extends haxe.ds.BalancedTree<K, V> {
-	override function compare(k1:EnumValue, k2:EnumValue):Int {
-		var d = k1.getIndex() - k2.getIndex();
-		if (d != 0)
-			return d;
-		var p1 = k1.getParameters();
-		var p2 = k2.getParameters();
-		if (p1.length == 0 && p2.length == 0)
-			return 0;
-		return compareArgs(p1, p2);
-	}
-
-	function compareArgs(a1:Array<Dynamic>, a2:Array<Dynamic>):Int {
-		var ld = a1.length - a2.length;
-		if (ld != 0)
-			return ld;
-		for (i in 0...a1.length) {
-			var d = compareArg(a1[i], a2[i]);
-			if (d != 0)
-				return d;
-		}
-		return 0;
-	}
-
-	function compareArg(v1:Dynamic, v2:Dynamic):Int {
-		return if (Reflect.isEnumValue(v1) && Reflect.isEnumValue(v2)) {
-			compare(v1, v2);
-		} else if (Std.isOfType(v1, Array) && Std.isOfType(v2, Array)) {
-			compareArgs(v1, v2);
-		} else {
-			Reflect.compare(v1, v2);
-		}
-	}
-
-	override function copy():EnumValueMap<K, V> {
-		var copied = new EnumValueMap<K, V>();
-		copied.root = root;
-		return copied;
-	}
+extern class EnumValueMap<K:EnumValue, V> implements haxe.Constraints.IMap<K, V> {
+	/**
+		Creates a new EnumValueMap.
+	**/
+	function new():Void;
+
+	/**
+		See `Map.set`
+	**/
+	function set(key:K, value:V):Void;
+
+	/**
+		See `Map.get`
+	**/
+	function get(key:K):Null<V>;
+
+	/**
+		See `Map.exists`
+	**/
+	function exists(key:K):Bool;
+
+	/**
+		See `Map.remove`
+	**/
+	function remove(key:K):Bool;
+
+	/**
+		See `Map.keys`
+	**/
+	function keys():Iterator<K>;
+
+	/**
+		See `Map.iterator`
+	**/
+	function iterator():Iterator<V>;
+
+	/**
+		See `Map.keyValueIterator`
+	**/
+	function keyValueIterator():KeyValueIterator<K, V>;
+
+	/**
+		See `Map.copy`
+	**/
+	function copy():EnumValueMap<K, V>;
+
+	/**
+		See `Map.toString`
+	**/
+	function toString():String;
+
+	/**
+		See `Map.clear`
+	**/
+	function clear():Void;
 }
@@ -27,92 +27,61 @@ package haxe.ds;
 	that are chained together. It is optimized so that adding or removing an
 	element does not imply copying the whole list content every time.
 
+	(rayzor) Implemented natively by the runtime as a double-ended queue of
+	type-erased element slots.
+
 	@see https://haxe.org/manual/std-List.html
 **/
-class List<T> {
-	private var h:ListNode<T>;
-	private var q:ListNode<T>;
-
+extern class List<T> {
 	/**
 		The length of `this` List.
 	**/
-	public var length(default, null):Int;
+	var length(default, null):Int;
 
 	/**
 		Creates a new empty list.
 	**/
-	public function new() {
-		length = 0;
-	}
+	function new():Void;
 
 	/**
 		Adds element `item` at the end of `this` List.
 
 		`this.length` increases by 1.
 	**/
-	public function add(item:T) {
-		var x = new ListNode(item, null);
-		if (h == null)
-			h = x;
-		else
-			q.next = x;
-		q = x;
-		length++;
-	}
+	function add(item:T):Void;
 
 	/**
 		Adds element `item` at the beginning of `this` List.
 
 		`this.length` increases by 1.
 	**/
-	public function push(item:T) {
-		var x = new ListNode(item, h);
-		h = x;
-		if (q == null)
-			q = x;
-		length++;
-	}
+	function push(item:T):Void;
 
 	/**
 		Returns the first element of `this` List, or null if no elements exist.
 
 		This function does not modify `this` List.
 	**/
-	public function first():Null<T> {
-		return if (h == null) null else h.item;
-	}
+	function first():Null<T>;
 
 	/**
 		Returns the last element of `this` List, or null if no elements exist.
 
 		This function does not modify `this` List.
 	**/
-	public function last():Null<T> {
-		return if (q == null) null else q.item;
-	}
+	function last():Null<T>;
 
 	/**
 		Returns the first element of `this` List, or null if no elements exist.
 
 		The element is removed from `this` List.
 	**/
-	public function pop():Null<T> {
-		if (h == null)
-			return null;
-		var x = h.item;
-		h = h.next;
-		if (h == null)
-			q = null;
-		length--;
-		return x;
-	}
+	function pop():Null<T>;
 
 	/**
 		Tells if `this` List is empty.
 	**/
-	public function isEmpty():Bool {
-		return (h == null);
-	}
+	function isEmpty():Bool;
 
 	/**
 		Empties `this` List.
@@ -120,11 +89,7 @@ class List<T> {
 		This function does not traverse the elements, but simply sets the
 		internal references to null and `this.length` to 0.
 	**/
-	public function clear():Void {
-		h = null;
-		q = null;
-		length = 0;
-	}
+	function clear():Void;
 
 	/**
 		Removes the first occurrence of `v` in `this` List.
@@ -134,39 +99,12 @@ class List<T> {
 
 		Otherwise, false is returned.
 	**/
-	public function remove(v:T):Bool {
-		var prev:ListNode<T> = null;
-		var l = h;
-		while (l != null) {
-			if (l.item == v) {
-				if (prev == null)
-					h = l.next;
-				else
-					prev.next = l.next;
-				if (q == l)
-					q = prev;
-				length--;
-				return true;
-			}
-			prev = l;
-			l = l.next;
-		}
-		return false;
-	}
+	function remove(v:T):Bool;
 
 	/**
 		Returns an iterator on the elements of the list.
 	**/
-	public inline function iterator():ListIterator<T> {
-		return new ListIterator<T>(h);
-	}
-
-	/**
-		Returns an iterator of the List indices and values.
-	**/
-	@:pure @:runtime public inline function keyValueIterator():ListKeyValueIterator<T> {
-		return new ListKeyValueIterator(h);
-	}
+	function iterator():Iterator<T>;
 
 	/**
 		Returns a string representation of `this` List.
@@ -174,140 +112,23 @@ class List<T> {
 		The result is enclosed in { } with the individual elements being
 		separated by a comma.
 	**/
-	public function toString() {
-		var s = new StringBuf();
-		var first = true;
-		var l = h;
-		s.add("{");
-		while (l != null) {
-			if (first)
-				first = false;
-			else
-				s.add(", ");
-			s.add(Std.string(l.item));
-			l = l.next;
-		}
-		s.add("}");
-		return s.toString();
-	}
+	function toString():String;
 
 	/**
 		Returns a string representation of `this` List, with `sep` separating
 		each element.
 	**/
-	public function join(sep:String) {
-		var s = new StringBuf();
-		var first = true;
-		var l = h;
-		while (l != null) {
-			if (first)
-				first = false;
-			else
-				s.add(sep);
-			s.add(l.item);
-			l = l.next;
-		}
-		return s.toString();
-	}
+	function join(sep:String):String;
 
 	/**
 		Returns a list filtered with `f`. The returned list will contain all
 		elements for which `f(x) == true`.
 	**/
-	public function filter(f:T->Bool) {
-		var l2 = new List();
-		var l = h;
-		while (l != null) {
-			var v = l.item;
-			l = l.next;
-			if (f(v))
-				l2.add(v);
-		}
-		return l2;
-	}
+	function filter(f:T->Bool):List<T>;
 
 	/**
 		Returns a new list where all elements have been converted by the
 		function `f`.
 	**/
-	public function map<X>(f:T->X):List<X> {
-		var b = new List();
-		var l = h;
-		while (l != null) {
-			var v = l.item;
-			l = l.next;
-			b.add(f(v));
-		}
-		return b;
-	}
-}
-
-#if neko
-private extern class ListNode<T> extends neko.NativeArray<Dynamic> {
-	var item(get, set):T;
-	var next(get, set):ListNode<T>;
-	private inline function get_item():T
-		return this[0];
-	private inline function set_item(v:T):T
-		return this[0] = v;
-	private inline function get_next():ListNode<T>
-		return this[1];
-	private inline function set_next(v:ListNode<T>):ListNode<T>
-		return this[1] = v;
-	inline static function create<T>(item:T, next:ListNode<T>):ListNode<T> {
-		return untyped __dollar__array(item, next);
-	}
-}
-#else
-private class ListNode<T> {
-	public var item:T;
-	public var next:ListNode<T>;
-
-	public function new(item:T, next:ListNode<T>) {
-		this.item = item;
-		this.next = next;
-	}
-
-	extern public inline static function create<T>(item:T, next:ListNode<T>):ListNode<T> {
-		return new ListNode(item, next);
-	}
-}
-#end
-
-private class ListIterator<T> {
-	var head:ListNode<T>;
-
-	public inline function new(head:ListNode<T>) {
-		this.head = head;
-	}
-
-	public inline function hasNext():Bool {
-		return head != null;
-	}
-
-	public inline function next():T {
-		var val = head.item;
-		head = head.next;
-		return val;
-	}
-}
-
-private class ListKeyValueIterator<T> {
-	var idx:Int;
-	var head:ListNode<T>;
-
-	public inline function new(head:ListNode<T>) {
-		this.head = head;
-		this.idx = 0;
-	}
-
-	public inline function hasNext():Bool {
-		return head != null;
-	}
-
-	public inline function next():{key:Int, value:T} {
-		var val = head.item;
-		head = head.next;
-		return {value: val, key: idx++};
-	}
+	function map<X>(f:T->X):List<X>;
 }
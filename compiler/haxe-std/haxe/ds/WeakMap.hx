@@ -27,81 +27,67 @@ package haxe.ds;
 
 	The keys are considered to be weak references on static targets.
 
+	(rayzor) Implemented natively by the runtime, sharing the representation
+	of `ObjectMap`. Keys are hashed by identity, and entries whose key object
+	has been freed are dropped instead of keeping the key alive.
+
 	See `Map` for documentation details.
 
 	@see https://haxe.org/manual/std-Map.html
 **/
-class WeakMap<K:{}, V> implements haxe.Constraints.IMap<K, V> {
+extern class WeakMap<K:{}, V> implements haxe.Constraints.IMap<K, V> {
 	/**
 		Creates a new WeakMap.
 	**/
-	public function new():Void {
-		throw new haxe.exceptions.NotImplementedException("Not implemented for this platform");
-	}
+	function new():Void;
 
 	/**
 		See `Map.set`
 	**/
-	public function set(key:K, value:V):Void {}
+	function set(key:K, value:V):Void;
 
 	/**
 		See `Map.get`
 	**/
-	public function get(key:K):Null<V> {
-		return null;
-	}
+	function get(key:K):Null<V>;
 
 	/**
 		See `Map.exists`
 	**/
-	public function exists(key:K):Bool {
-		return false;
-	}
+	function exists(key:K):Bool;
 
 	/**
 		See `Map.remove`
 	**/
-	public function remove(key:K):Bool {
-		return false;
-	}
+	function remove(key:K):Bool;
 
 	/**
 		See `Map.keys`
 	**/
-	public function keys():Iterator<K> {
-		return null;
-	}
+	function keys():Iterator<K>;
 
 	/**
 		See `Map.iterator`
 	**/
-	public function iterator():Iterator<V> {
-		return null;
-	}
+	function iterator():Iterator<V>;
 
 	/**
 		See `Map.keyValueIterator`
 	**/
-	public inline function keyValueIterator():KeyValueIterator<K, V> {
-		return null;
-	}
+	function keyValueIterator():KeyValueIterator<K, V>;
 
 	/**
 		See `Map.copy`
 	**/
-	public function copy():WeakMap<K, V> {
-		return null;
-	}
+	function copy():WeakMap<K, V>;
 
 	/**
 		See `Map.toString`
 	**/
-	public function toString():String {
-		return null;
-	}
+	function toString():String;
 
 	/**
 		See `Map.clear`
 	**/
-	public function clear():Void {}
+	function clear():Void;
 }
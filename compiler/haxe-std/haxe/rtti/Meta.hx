@@ -22,72 +22,27 @@
 
 package haxe.rtti;
 
-private typedef MetaObject = {
-	?fields:Dynamic<Dynamic<Null<Array<Dynamic>>>>,
-	?statics:Dynamic<Dynamic<Null<Array<Dynamic>>>>,
-	?obj:Dynamic<Null<Array<Dynamic>>>
-};
-
 /**
 	An API to access classes and enums metadata at runtime.
 
+	Metadata with constant arguments is kept by the compiler and registered
+	with the runtime per class/enum. Metadata without arguments maps to `null`.
+
 	@see <https://haxe.org/manual/cr-rtti.html>
 **/
-class Meta {
+extern class Meta {
 	/**
 		Returns the metadata that were declared for the given type (class or enum)
 	**/
-	public static function getType(t:Dynamic):Dynamic<Array<Dynamic>> {
-		var meta = getMeta(t);
-		return (meta == null || meta.obj == null) ? {} : meta.obj;
-	}
-
-	// Could move this to Type.hx?
-	private static function isInterface(t:Dynamic):Bool {
-		#if java
-		return java.Lib.toNativeType(t).isInterface();
-		#elseif cs
-		return cs.Lib.toNativeType(t).IsInterface;
-		#else
-		throw "Something went wrong";
-		#end
-	}
-
-	private static function getMeta(t:Dynamic):MetaObject {
-		#if php
-		return php.Boot.getMeta(t.phpClassName);
-		#elseif (java || cs)
-		var ret = Reflect.field(t, "__meta__");
-		if (ret == null && Std.isOfType(t, Class)) {
-			if (isInterface(t)) {
-				var name = Type.getClassName(t),
-					cls = Type.resolveClass(name + '_HxMeta');
-				if (cls != null)
-					return Reflect.field(cls, "__meta__");
-			}
-		}
-		return ret;
-		#elseif hl
-		var t:hl.BaseType = t;
-		return t.__meta__;
-		#else
-		return untyped t.__meta__;
-		#end
-	}
+	static function getType(t:Dynamic):Dynamic<Array<Dynamic>>;
 
 	/**
 		Returns the metadata that were declared for the given class static fields
 	**/
-	public static function getStatics(t:Dynamic):Dynamic<Dynamic<Array<Dynamic>>> {
-		var meta = getMeta(t);
-		return (meta == null || meta.statics == null) ? {} : meta.statics;
-	}
+	static function getStatics(t:Dynamic):Dynamic<Dynamic<Array<Dynamic>>>;
 
 	/**
 		Returns the metadata that were declared for the given class fields or enum constructors
 	**/
-	public static function getFields(t:Dynamic):Dynamic<Dynamic<Array<Dynamic>>> {
-		var meta = getMeta(t);
-		return (meta == null || meta.fields == null) ? {} : meta.fields;
-	}
+	static function getFields(t:Dynamic):Dynamic<Dynamic<Array<Dynamic>>>;
 }
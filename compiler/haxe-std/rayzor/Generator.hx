@@ -0,0 +1,88 @@
+package rayzor;
+
+import sys.thread.Coroutine;
+
+/**
+ * Iterator over the values a generator function yields.
+ *
+ * A method whose body contains `@:yield value` is compiled into one that
+ * returns a Generator running the body on a coroutine. The body runs lazily:
+ * each `hasNext()` continues it up to its next yield.
+ *
+ * Example:
+ * ```haxe
+ * static function evens(limit:Int):Iterator<Int> {
+ *     var i = 0;
+ *     while (i < limit) {
+ *         @:yield i;
+ *         i += 2;
+ *     }
+ * }
+ *
+ * for (n in evens(10)) trace(n); // 0, 2, 4, 6, 8
+ * ```
+ *
+ * A generator abandoned before its body returned keeps its coroutine stack
+ * until `dispose()` is called.
+ */
+class Generator<T> {
+    /** Null once the body returned or the generator was disposed */
+    var coroutine:Null<Coroutine<T>>;
+    var pending:Null<T>;
+    var buffered:Bool;
+
+    /**
+     * Create a generator running `body`, which yields with `@:yield`
+     */
+    public function new(body:Void->Void) {
+        coroutine = Coroutine.create(body);
+        pending = null;
+        buffered = false;
+    }
+
+    /**
+     * Run the body up to its next yield, if not done already; false once
+     * the body returned. The coroutine is freed as soon as it is seen dead.
+     */
+    public function hasNext():Bool {
+        if (coroutine == null) {
+            return false;
+        }
+        if (!buffered) {
+            pending = coroutine.resume();
+            buffered = true;
+        }
+        if (coroutine.isDead()) {
+            dispose();
+            return false;
+        }
+        return true;
+    }
+
+    /**
+     * The next yielded value
+     */
+    public function next():T {
+        hasNext();
+        buffered = false;
+        return pending;
+    }
+
+    /**
+     * Free the coroutine without running the rest of the body, when
+     * stopping before the generator is exhausted. Afterwards `hasNext()`
+     * is false. Does nothing from inside the generator's own body.
+     */
+    public function dispose():Void {
+        if (coroutine != null && coroutine.dispose()) {
+            coroutine = null;
+        }
+    }
+
+    /**
+     * A generator is its own iterator, so it can be used as an `Iterable<T>`
+     */
+    public function iterator():Generator<T> {
+        return this;
+    }
+}
@@ -13,11 +13,21 @@ package rayzor;
  * With `@:cstruct` classes, the memory layout matches C exactly,
  * so pointers are directly interoperable.
  *
+ * Ptr can also own raw heap memory, for data structures that want to
+ * avoid Array/Bytes: `alloc`, `get`/`set` and `free` compile to direct
+ * loads, stores and libc malloc/free. Elements are 8 bytes wide and
+ * indices are not bounds-checked. See `Unsafe` for bulk copies and fills.
+ *
  * Example:
  * ```haxe
  * var ptr:Ptr<Vec3> = Ptr.fromRaw(address);
  * var v = ptr.deref();
  * ptr.write(newValue);
+ *
+ * var slots:Ptr<Int> = Ptr.alloc(16);
+ * slots.set(0, 42);
+ * trace(slots.get(0));
+ * slots.free();
  * ```
  */
 @:native("rayzor::Ptr")
@@ -45,4 +55,24 @@ extern abstract Ptr<T>(Int) {
     /** Check if this pointer is null */
     @:native("isNull")
     public function isNull():Bool;
+
+    /**
+     * Allocate uninitialized heap memory for `n` elements.
+     * Returns a null pointer if `n` is not positive or allocation fails.
+     * Release it with `free()`.
+     */
+    @:native("alloc")
+    public static function alloc<T>(n:Int):Ptr<T>;
+
+    /** Read the element at `index` (not bounds-checked) */
+    @:native("get")
+    public function get(index:Int):T;
+
+    /** Write the element at `index` (not bounds-checked) */
+    @:native("set")
+    public function set(index:Int, value:T):Void;
+
+    /** Free memory from `alloc()`. Freeing a null pointer does nothing. */
+    @:native("free")
+    public function free():Void;
 }
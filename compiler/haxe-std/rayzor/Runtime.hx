@@ -0,0 +1,44 @@
+package rayzor;
+
+/**
+ * Loading additional bundles into the running program.
+ *
+ * A bundle (`.rzb`, built with `rayzor bundle`) is JIT-compiled when it is
+ * loaded. Its imports resolve against the modules the program already runs,
+ * so a plugin shares the host's classes and statics, and modules loaded later
+ * can call into it. Bundles can declare several named entry points
+ * (`rayzor bundle --entry name=Class.method`); loading runs one of them.
+ *
+ * ```haxe
+ * for (file in sys.FileSystem.readDirectory("plugins")) {
+ *     if (!Runtime.loadModuleEntry('plugins/$file', "register"))
+ *         trace('skipping $file: ${Runtime.loadError()}');
+ * }
+ * ```
+ *
+ * Loading needs a host that supports it: `rayzor run` does, and embedding
+ * applications can install their own loader.
+ */
+@:native("rayzor::Runtime")
+extern class Runtime {
+    /**
+     * Load the bundle at `path` and run its default entry point.
+     * Returns false if the bundle can't be loaded; see `loadError()`.
+     */
+    @:native("loadModule")
+    public static function loadModule(path:String):Bool;
+
+    /**
+     * Load the bundle at `path` and run its entry point named `entry`.
+     * Returns false if the bundle can't be loaded or has no such entry
+     * point; see `loadError()`.
+     */
+    @:native("loadModuleEntry")
+    public static function loadModuleEntry(path:String, entry:String):Bool;
+
+    /**
+     * Why the last load on this thread failed, or null if it succeeded.
+     */
+    @:native("loadError")
+    public static function loadError():String;
+}
@@ -71,6 +71,10 @@ extern abstract SIMD4f {
     @:native("insert")
     public function set(lane:Int, value:Float):SIMD4f;
 
+    /** Rearrange lanes: result[i] = this[index_i] (indices are taken modulo 4) */
+    @:native("shuffle")
+    public function shuffle(x:Int, y:Int, z:Int, w:Int):SIMD4f;
+
     /** Horizontal sum of all 4 lanes */
     @:native("sum")
     public function sum():Float;
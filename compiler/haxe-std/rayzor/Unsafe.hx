@@ -0,0 +1,29 @@
+package rayzor;
+
+/**
+ * Raw memory operations on pointers.
+ *
+ * Both functions compile to a single MIR memory instruction (a memcpy or
+ * memset call in native code). Nothing is checked: the ranges must be
+ * valid, and `memcpy` ranges must not overlap.
+ *
+ * Example:
+ * ```haxe
+ * var src:Ptr<Int> = Ptr.alloc(4);
+ * var dst:Ptr<Int> = Ptr.alloc(4);
+ * Unsafe.memset(src, 0, 4 * 8);
+ * src.set(2, 7);
+ * Unsafe.memcpy(dst, src, 4 * 8);
+ * trace(dst.get(2)); // 7
+ * ```
+ */
+@:native("rayzor::Unsafe")
+extern class Unsafe {
+    /** Copy `bytes` bytes from `src` to `dest` */
+    @:native("memcpy")
+    public static function memcpy<T>(dest:Ptr<T>, src:Ptr<T>, bytes:Int):Void;
+
+    /** Fill `bytes` bytes at `dest` with the low byte of `value` */
+    @:native("memset")
+    public static function memset<T>(dest:Ptr<T>, value:Int, bytes:Int):Void;
+}
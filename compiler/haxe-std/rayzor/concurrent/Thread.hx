@@ -38,6 +38,20 @@ extern class Thread<T> {
     @:native("spawn")
     public static function spawn<T>(fn: Void -> T): Thread<T>;
 
+    /**
+     * Spawn a new thread with a stack of `stackSize` bytes.
+     *
+     * Use this for deeply recursive work that would overflow the default
+     * thread stack. A stack overflow aborts the program with a report
+     * naming the function that overflowed.
+     *
+     * @param fn The closure to execute in the new thread
+     * @param stackSize Stack size in bytes (the default size if <= 0)
+     * @return A handle to the spawned thread
+     */
+    @:native("spawn_with_stack_size")
+    public static function spawnWithStackSize<T>(fn: Void -> T, stackSize: Int): Thread<T>;
+
     /**
      * Wait for the thread to complete and return its result.
      *
@@ -0,0 +1,31 @@
+package rayzor.gpu;
+
+/**
+ * Two staging/device buffer pairs for streaming per-frame data.
+ *
+ * Fill `back()`, call `GPUCompute.present()` to start its upload and swap,
+ * then compute on `front()` while the next frame is written:
+ *
+ * ```haxe
+ * var db = gpu.createDoubleBuffer(count, F32);
+ * while (running) {
+ *     var staging = db.back();
+ *     for (i in 0...count) staging.setFloat(i, positions[i]);
+ *     gpu.present(db);
+ *     trace(gpu.sum(db.front()));
+ * }
+ * gpu.freeDoubleBuffer(db);
+ * ```
+ *
+ * Both buffers are owned by the DoubleBuffer — don't free them directly.
+ */
+@:native("rayzor::gpu::DoubleBuffer")
+extern class DoubleBuffer {
+    /** Staging buffer for the next frame. Waits for its previous upload. */
+    @:native("gpu_double_buffer_back")
+    public function back():PinnedBuffer;
+
+    /** Device buffer holding the most recently presented frame. */
+    @:native("gpu_double_buffer_front")
+    public function front():GpuBuffer;
+}
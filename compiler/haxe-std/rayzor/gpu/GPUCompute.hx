@@ -39,6 +39,28 @@ extern class GPUCompute {
     @:native("gpu_compute_isAvailable")
     public static function isAvailable():Bool;
 
+    /** List the GPUs this system exposes, across all enabled backends. */
+    @:native("gpu_compute_enumerateDevices")
+    public static function enumerateDevices():Array<GpuDeviceInfo>;
+
+    /**
+     * Create a context on the device at `index` in `enumerateDevices()`.
+     * Returns null if there is no such device.
+     *
+     * Buffers belong to the context that created them; using one with
+     * another context fails and sets `lastError()`.
+     */
+    @:native("gpu_compute_createOnDevice")
+    public static function createOnDevice(index:Int):GPUCompute;
+
+    /** Describe the device this context runs on. */
+    @:native("gpu_compute_device")
+    public function device():GpuDeviceInfo;
+
+    /** Most recent error for this context (e.g. a buffer from another device), or null. */
+    @:native("gpu_compute_lastError")
+    public function lastError():String;
+
     /** Create a GPU buffer by copying data from a CPU tensor. */
     @:native("gpu_compute_createBuffer")
     public function createBuffer(tensor:rayzor.ds.Tensor):GpuBuffer;
@@ -127,6 +149,59 @@ extern class GPUCompute {
     @:native("gpu_compute_matmul")
     public function matmul(a:GpuBuffer, b:GpuBuffer, m:Int, k:Int, n:Int):GpuBuffer;
 
+    // -- Primitives: index reductions, scans, histogram, segments ------------
+
+    /** Index of the largest element (lowest index on ties), or -1 on error. */
+    @:native("gpu_compute_argmax")
+    public function argmax(buf:GpuBuffer):Int;
+
+    /** Index of the smallest element (lowest index on ties), or -1 on error. */
+    @:native("gpu_compute_argmin")
+    public function argmin(buf:GpuBuffer):Int;
+
+    /** Inclusive prefix sum: out[i] = buf[0] + ... + buf[i]. */
+    @:native("gpu_compute_inclusiveScan")
+    public function inclusiveScan(buf:GpuBuffer):GpuBuffer;
+
+    /** Exclusive prefix sum: out[0] = 0, out[i] = buf[0] + ... + buf[i - 1]. */
+    @:native("gpu_compute_exclusiveScan")
+    public function exclusiveScan(buf:GpuBuffer):GpuBuffer;
+
+    /**
+     * Count values into `bins` equal-width bins over [min, max].
+     * Returns an Int32 buffer; values outside the range are ignored.
+     */
+    @:native("gpu_compute_histogram")
+    public function histogram(buf:GpuBuffer, bins:Int, min:Float, max:Float):GpuBuffer;
+
+    /**
+     * Sum each segment of `values`. `offsets` is an Int32 buffer of
+     * numSegments + 1 entries; segment s covers [offsets[s], offsets[s + 1]).
+     */
+    @:native("gpu_compute_segmentedSum")
+    public function segmentedSum(values:GpuBuffer, offsets:GpuBuffer):GpuBuffer;
+
+    /** Maximum of each segment (see `segmentedSum`). */
+    @:native("gpu_compute_segmentedMax")
+    public function segmentedMax(values:GpuBuffer, offsets:GpuBuffer):GpuBuffer;
+
+    /** Minimum of each segment (see `segmentedSum`). */
+    @:native("gpu_compute_segmentedMin")
+    public function segmentedMin(values:GpuBuffer, offsets:GpuBuffer):GpuBuffer;
+
+    // -- Random fills ---------------------------------------------------------
+
+    /**
+     * Overwrite a Float32 buffer with uniform values in [0, 1).
+     * Counter-based (Philox): the same seed always yields the same values.
+     */
+    @:native("gpu_compute_randomUniform")
+    public function randomUniform(buf:GpuBuffer, seed:Int):Void;
+
+    /** Overwrite a Float32 buffer with standard normal values (mean 0, stddev 1). */
+    @:native("gpu_compute_randomNormal")
+    public function randomNormal(buf:GpuBuffer, seed:Int):Void;
+
     // -- Structured buffer ops (@:gpuStruct) -----------------------------------
 
     /** Create a GPU buffer from an array of @:gpuStruct instances. */
@@ -144,4 +219,86 @@ extern class GPUCompute {
     /** Read an int field from a structured buffer (returns Int). */
     @:native("gpu_compute_readStructInt")
     public function readStructInt(buffer:GpuBuffer, index:Int, structSize:Int, fieldOffset:Int):Int;
+
+    // -- Layout-descriptor variants (pass `MyStruct.layout()`) ----------------
+
+    /** Create a GPU buffer from an array of structs described by `layout`. */
+    @:native("gpu_compute_createLayoutBuffer")
+    public function createLayoutBuffer(array:Dynamic, count:Int, layout:String):GpuBuffer;
+
+    /** Allocate an empty GPU buffer for `count` structs described by `layout`. */
+    @:native("gpu_compute_allocLayoutBuffer")
+    public function allocLayoutBuffer(count:Int, layout:String):GpuBuffer;
+
+    /** Read the named field of struct `index`, looked up in `layout`. */
+    @:native("gpu_compute_readStructField")
+    public function readStructField(buffer:GpuBuffer, index:Int, layout:String, field:String):Float;
+
+    // -- Textures ----------------------------------------------------------
+
+    /** Upload `width * height` pixels of `format` from `bytes`. */
+    @:native("gpu_compute_createTexture")
+    public function createTexture(bytes:haxe.io.Bytes, width:Int, height:Int, format:TextureFormat):GpuTexture;
+
+    /** Allocate a zero-filled texture. */
+    @:native("gpu_compute_allocTexture")
+    public function allocTexture(width:Int, height:Int, format:TextureFormat):GpuTexture;
+
+    /** Read a texture back into new Bytes in its own format. */
+    @:native("gpu_compute_readTexture")
+    public function readTexture(texture:GpuTexture):haxe.io.Bytes;
+
+    /** Read channel `c` of pixel (x, y). RGBA8 channels read as 0..1. */
+    @:native("gpu_compute_getPixel")
+    public function getPixel(texture:GpuTexture, x:Int, y:Int, c:Int):Float;
+
+    /** Write channel `c` of pixel (x, y). RGBA8 channels take 0..1. */
+    @:native("gpu_compute_setPixel")
+    public function setPixel(texture:GpuTexture, x:Int, y:Int, c:Int, value:Float):Void;
+
+    /** Convolve with a `kw * kh` F32 weight buffer, clamping at the edges. */
+    @:native("gpu_compute_convolve")
+    public function convolve(texture:GpuTexture, weights:GpuBuffer, kw:Int, kh:Int):GpuTexture;
+
+    /** Resize to `width * height` with bilinear filtering. */
+    @:native("gpu_compute_resize")
+    public function resize(texture:GpuTexture, width:Int, height:Int):GpuTexture;
+
+    /** Release a texture's GPU memory. */
+    @:native("gpu_compute_freeTexture")
+    public function freeTexture(texture:GpuTexture):Void;
+
+    // -- Pinned memory and async transfers ----------------------------------
+
+    /** Allocate `bytes` of page-aligned, page-locked host memory. */
+    @:native("gpu_compute_allocPinned")
+    public function allocPinned(bytes:Int):PinnedBuffer;
+
+    /** Free pinned memory. Transfers using it must have completed. */
+    @:native("gpu_compute_freePinned")
+    public function freePinned(pinned:PinnedBuffer):Void;
+
+    /** Start copying `src` into `dst` (up to the smaller size) without blocking. */
+    @:native("gpu_compute_uploadAsync")
+    public function uploadAsync(src:PinnedBuffer, dst:GpuBuffer):GpuEvent;
+
+    /** Start copying `src` into `dst`; `dst` is valid once the event completes. */
+    @:native("gpu_compute_downloadAsync")
+    public function downloadAsync(src:GpuBuffer, dst:PinnedBuffer):GpuEvent;
+
+    /** Release an event. */
+    @:native("gpu_compute_freeEvent")
+    public function freeEvent(event:GpuEvent):Void;
+
+    /** Create a double buffer of two `numel`-element device buffers. */
+    @:native("gpu_compute_createDoubleBuffer")
+    public function createDoubleBuffer(numel:Int, dtype:rayzor.ds.DType):DoubleBuffer;
+
+    /** Start uploading `db.back()` and swap it to `db.front()`. */
+    @:native("gpu_compute_present")
+    public function present(db:DoubleBuffer):Bool;
+
+    /** Release a double buffer and its staging and device buffers. */
+    @:native("gpu_compute_freeDoubleBuffer")
+    public function freeDoubleBuffer(db:DoubleBuffer):Void;
 }
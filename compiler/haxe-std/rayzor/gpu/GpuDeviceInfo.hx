@@ -0,0 +1,26 @@
+package rayzor.gpu;
+
+/**
+ * Description of a GPU a `GPUCompute` context can run on.
+ *
+ * Returned by `GPUCompute.enumerateDevices()` (pass its index to
+ * `GPUCompute.createOnDevice()`) and by `GPUCompute.device()`.
+ */
+@:native("rayzor::gpu::GpuDeviceInfo")
+extern class GpuDeviceInfo {
+    /** Adapter name reported by the driver. */
+    @:native("gpu_device_name")
+    public function name():String;
+
+    /** Integrated, discrete, virtual or CPU (software) device. */
+    @:native("gpu_device_deviceType")
+    public function deviceType():GpuDeviceType;
+
+    /** Recommended working-set size in bytes, or 0 if the backend can't tell. */
+    @:native("gpu_device_memory")
+    public function memory():Int;
+
+    /** Backend driving the device, e.g. "metal" or "wgpu/Vulkan". */
+    @:native("gpu_device_backend")
+    public function backend():String;
+}
@@ -0,0 +1,14 @@
+package rayzor.gpu;
+
+/**
+ * Kind of GPU reported by `GpuDeviceInfo.deviceType()`.
+ *
+ * Maps to runtime device type tags (i64 constants) at MIR level.
+ */
+enum GpuDeviceType {
+    Other;
+    Integrated;
+    Discrete;
+    Virtual;
+    Cpu;
+}
@@ -0,0 +1,18 @@
+package rayzor.gpu;
+
+/**
+ * Completion handle for an async transfer started by
+ * `GPUCompute.uploadAsync()` or `GPUCompute.downloadAsync()`.
+ *
+ * Release with `GPUCompute.freeEvent()`.
+ */
+@:native("rayzor::gpu::GpuEvent")
+extern class GpuEvent {
+    /** Check for completion without blocking. */
+    @:native("gpu_event_isComplete")
+    public function isComplete():Bool;
+
+    /** Block until the transfer completes. Returns false if it failed. */
+    @:native("gpu_event_wait")
+    public function wait():Bool;
+}
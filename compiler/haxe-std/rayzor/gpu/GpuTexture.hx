@@ -0,0 +1,26 @@
+package rayzor.gpu;
+
+/**
+ * Opaque handle to a GPU-resident 2D image.
+ *
+ * Created via `GPUCompute.createTexture()` or `GPUCompute.allocTexture()`.
+ * Pixels can be read back via `GPUCompute.readTexture()` or accessed one
+ * channel at a time with `getPixel()`/`setPixel()`.
+ *
+ * GpuTexture is an opaque pointer — all operations go through the
+ * GPUCompute context that created it.
+ */
+@:native("rayzor::gpu::GpuTexture")
+extern class GpuTexture {
+    /** Width in pixels. */
+    @:native("gpu_texture_width")
+    public function width():Int;
+
+    /** Height in pixels. */
+    @:native("gpu_texture_height")
+    public function height():Int;
+
+    /** Pixel format of this texture. */
+    @:native("gpu_texture_format")
+    public function format():TextureFormat;
+}
@@ -0,0 +1,35 @@
+package rayzor.gpu;
+
+/**
+ * Page-aligned host memory for async GPU transfers.
+ *
+ * Created via `GPUCompute.allocPinned()` and released with
+ * `GPUCompute.freePinned()`. The OS is asked to page-lock it; check
+ * `isPageLocked()` if the distinction matters. Elements are 4 bytes wide.
+ */
+@:native("rayzor::gpu::PinnedBuffer")
+extern class PinnedBuffer {
+    /** Size in bytes. */
+    @:native("gpu_pinned_length")
+    public function length():Int;
+
+    /** Whether the allocation is page-locked. */
+    @:native("gpu_pinned_isPageLocked")
+    public function isPageLocked():Bool;
+
+    /** Read f32 element `index`. */
+    @:native("gpu_pinned_getFloat")
+    public function getFloat(index:Int):Float;
+
+    /** Write f32 element `index`. */
+    @:native("gpu_pinned_setFloat")
+    public function setFloat(index:Int, value:Float):Void;
+
+    /** Read i32 element `index`. */
+    @:native("gpu_pinned_getInt")
+    public function getInt(index:Int):Int;
+
+    /** Write i32 element `index`. */
+    @:native("gpu_pinned_setInt")
+    public function setInt(index:Int, value:Int):Void;
+}
@@ -0,0 +1,15 @@
+package rayzor.gpu;
+
+/**
+ * Pixel format of a GpuTexture.
+ *
+ * Textures are stored on the GPU as f32 texels either way; the format decides
+ * the channel count and how `Bytes` are converted on upload and readback.
+ * Maps to runtime format tags (i64 constants) at MIR level.
+ */
+enum TextureFormat {
+    /** 4 channels, one byte each; normalized to 0..1 on the GPU. */
+    RGBA8;
+    /** 1 channel of 32-bit float. */
+    F32;
+}
@@ -0,0 +1,30 @@
+package rayzor.runtime;
+
+/**
+ * Monotonic high-resolution clock.
+ *
+ * Unlike `Sys.time()`, which reads the wall clock and may jump when the
+ * system time changes, this clock never goes backwards. Only differences
+ * between two readings are meaningful, which makes it the clock to use for
+ * benchmarks. `haxe.Timer.stamp()` and `haxe.Timer.measure()` use it.
+ *
+ * ```haxe
+ * var t0 = Clock.monotonicNs();
+ * work();
+ * trace('took ${(Clock.monotonicNs() - t0) / 1e6} ms');
+ * ```
+ */
+@:native("rayzor::runtime::Clock")
+extern class Clock {
+    /**
+     * Nanoseconds elapsed on the monotonic clock.
+     */
+    @:native("monotonicNs")
+    public static function monotonicNs():Int;
+
+    /**
+     * Seconds elapsed on the monotonic clock, with fractions.
+     */
+    @:native("stamp")
+    public static function stamp():Float;
+}
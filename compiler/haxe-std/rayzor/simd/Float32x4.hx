@@ -0,0 +1,114 @@
+package rayzor.simd;
+
+import rayzor.Ptr;
+
+/**
+ * Portable 128-bit SIMD vector of 4 × Float (f32).
+ *
+ * Float32x4 lowers to the MIR `vec<f32; 4>` type, which the Cranelift and
+ * LLVM backends emit as native vector registers (SSE on x86, NEON on ARM).
+ * The MIR interpreter executes the same operations lane by lane, so code
+ * written against Float32x4 runs unchanged before JIT tier-up.
+ *
+ * Use it for hot math where auto-vectorization doesn't apply: arithmetic
+ * operators are single vector instructions with no call overhead.
+ *
+ * Example:
+ * ```haxe
+ * import rayzor.simd.Float32x4;
+ *
+ * var a = Float32x4.make(1.0, 2.0, 3.0, 4.0);
+ * var b = Float32x4.splat(2.0);
+ * var c = a * b;                 // [2, 4, 6, 8]
+ * var r = c.shuffle(3, 2, 1, 0); // [8, 6, 4, 2]
+ * var i = 1;
+ * trace(r[i]);                   // 6.0 (lane index may be a runtime value)
+ * ```
+ */
+@:coreType
+@:notNull
+@:native("rayzor::simd::Float32x4")
+extern abstract Float32x4 {
+    /** Broadcast a single value to all 4 lanes */
+    @:native("splat")
+    public static function splat(v:Float):Float32x4;
+
+    /** Construct from 4 individual values */
+    @:native("make")
+    public static function make(x:Float, y:Float, z:Float, w:Float):Float32x4;
+
+    /** Load 4 contiguous floats from a pointer */
+    @:native("load")
+    public static function load(ptr:Ptr<Float>):Float32x4;
+
+    /** Store 4 floats to a pointer */
+    @:native("store")
+    public function store(ptr:Ptr<Float>):Void;
+
+    /** Implicit conversion from array literal: var v:Float32x4 = [1.0, 2.0, 3.0, 4.0]; */
+    @:from
+    static function fromArray(arr:Array<Float>):Float32x4;
+
+    /** Element-wise addition */
+    @:native("add")
+    @:op(A + B)
+    public function add(other:Float32x4):Float32x4;
+
+    /** Element-wise subtraction */
+    @:native("sub")
+    @:op(A - B)
+    public function sub(other:Float32x4):Float32x4;
+
+    /** Element-wise multiplication */
+    @:native("mul")
+    @:op(A * B)
+    public function mul(other:Float32x4):Float32x4;
+
+    /** Element-wise division */
+    @:native("div")
+    @:op(A / B)
+    public function div(other:Float32x4):Float32x4;
+
+    /** Read lane: v[i] (index taken modulo 4) */
+    @:arrayAccess
+    @:native("extract")
+    public function get(lane:Int):Float;
+
+    /** Write lane: v[i] = x (index taken modulo 4) */
+    @:arrayAccess
+    @:native("insert")
+    public function set(lane:Int, value:Float):Float32x4;
+
+    /** Rearrange lanes: result[i] = this[index_i] (indices are taken modulo 4) */
+    @:native("shuffle")
+    public function shuffle(x:Int, y:Int, z:Int, w:Int):Float32x4;
+
+    /** Horizontal sum of all 4 lanes */
+    @:native("sum")
+    public function sum():Float;
+
+    /** Dot product: sum(a[i] * b[i]) */
+    @:native("dot")
+    public function dot(other:Float32x4):Float;
+
+    /** Element-wise square root */
+    @:native("sqrt")
+    public function sqrt():Float32x4;
+
+    /** Element-wise absolute value */
+    @:native("abs")
+    public function abs():Float32x4;
+
+    /** Element-wise negation */
+    @:native("neg")
+    @:op(-A)
+    public function neg():Float32x4;
+
+    /** Element-wise minimum */
+    @:native("min")
+    public function min(other:Float32x4):Float32x4;
+
+    /** Element-wise maximum */
+    @:native("max")
+    public function max(other:Float32x4):Float32x4;
+}
@@ -0,0 +1,47 @@
+package rayzor.text;
+
+/**
+ * Locale-aware string comparison and case folding.
+ *
+ * String operators (`<`, `==`) and `Reflect.compare` compare code units, so
+ * `"Zebra" < "apple"` and `"école" > "zoo"`. Collator orders strings for
+ * display the way the language-neutral root locale does:
+ *
+ * 1. base letters first, ignoring case and accents
+ *    (spaces and punctuation < digits < letters)
+ * 2. then accents (`e` < `é` < `è`)
+ * 3. then case, lowercase first (`a` < `A`)
+ *
+ * Accents are understood for Western and Central European Latin letters;
+ * `ß`, `æ` and `œ` compare as `ss`, `ae` and `oe`.
+ *
+ * Example:
+ * ```haxe
+ * var names = ["Zoë", "émile", "Adam", "zoe"];
+ * names.sort((a, b) -> Collator.compare(a, b));
+ * trace(names); // [Adam, émile, zoe, Zoë]
+ *
+ * trace(Collator.compareIgnoreCase("STRASSE", "Straße")); // 0
+ * trace(Collator.foldCase("Straße")); // strasse
+ * ```
+ */
+@:native("rayzor::text::Collator")
+extern class Collator {
+    /**
+     * Compare two strings for sorting.
+     * Returns a negative number if `a` sorts first, positive if `b` does,
+     * and 0 only for identical strings.
+     */
+    public static function compare(a:String, b:String):Int;
+
+    /**
+     * Like `compare`, but strings that differ only in case compare equal.
+     */
+    public static function compareIgnoreCase(a:String, b:String):Int;
+
+    /**
+     * Locale-independent case folding: a form in which all case variants of
+     * a string are equal (`"Straße"` and `"STRASSE"` both give `"strasse"`).
+     */
+    public static function foldCase(s:String):String;
+}
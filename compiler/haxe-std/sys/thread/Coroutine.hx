@@ -0,0 +1,71 @@
+package sys.thread;
+
+/**
+	A stackful coroutine: a function running on its own small stack, that
+	can suspend itself with `Coroutine.yield` and be continued later with
+	`resume`. Unlike a `Thread`, a coroutine only runs while it is resumed,
+	on the thread that resumed it, so it needs no locking.
+
+	Coroutines suit generators and per-entity game scripts:
+
+	```haxe
+	var counter = Coroutine.create(() -> {
+		for (i in 0...3)
+			Coroutine.yield(i);
+	});
+	while (!counter.isDead())
+		trace(counter.resume()); // 0, 1, 2, then null
+	counter.dispose();
+	```
+
+	(rayzor) Backed by `rayzor_coroutine_*`. Each coroutine gets a 256 KiB
+	stack ending in a guard page; overflowing it aborts with a stack overflow
+	report. Exceptions must be caught inside the coroutine body. A
+	coroutine is not garbage collected: call `dispose` when done with it.
+**/
+extern class Coroutine<T> {
+	/**
+		Creates a suspended coroutine that runs `fn` when first resumed.
+		Returns `null` on platforms without coroutine support.
+	**/
+	static function create<T>(fn:Void->Void):Coroutine<T>;
+
+	/**
+		Runs `this` coroutine until it yields or returns.
+
+		Returns the value passed to `Coroutine.yield`, or `null` once the
+		function returned. Resuming a running or dead coroutine returns `null`.
+	**/
+	function resume():Null<T>;
+
+	/**
+		Suspends the innermost coroutine running on the current thread, making
+		its `resume` call return `value`. Does nothing outside a coroutine.
+	**/
+	static function yield(value:Dynamic):Void;
+
+	/**
+		The state of `this` coroutine: 0 if suspended (or not started yet),
+		1 if running, 2 if its function returned.
+	**/
+	function status():Int;
+
+	/**
+		Tells if the function of `this` coroutine returned.
+	**/
+	function isDead():Bool;
+
+	/**
+		Frees `this` coroutine and its stack. A suspended coroutine is
+		dropped without running the rest of its function. `this` must not be
+		used afterwards.
+
+		Returns `false`, and does nothing, if `this` coroutine is running.
+	**/
+	function dispose():Bool;
+
+	/**
+		Tells if the calling code runs inside a coroutine.
+	**/
+	static function isInside():Bool;
+}
@@ -31,8 +31,15 @@ package sys.thread;
 
 	(hl) Warning: At the moment `Tls` does not protect the value from being
 	garbage collected. Keep the value reachable to avoid crashes.
+
+	(rayzor) Backed by the runtime's per-thread slots (`rayzor_tls_alloc`,
+	`rayzor_tls_get`, `rayzor_tls_set`). A `Tls` can be shared with any
+	thread: its contents are thread-confined, so `T` need not be `Send`.
 **/
 extern class Tls<T> {
+	/**
+		The value for the current thread; `null` until this thread sets it.
+	**/
 	var value(get, set):T;
 
 	/**
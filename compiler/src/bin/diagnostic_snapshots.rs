@@ -0,0 +1,49 @@
+//! Golden snapshot runner for diagnostics output
+//!
+//! This is a thin wrapper around `compiler::tools::diagnostic_snapshots`.
+//!
+//! Usage:
+//!   cargo run --bin diagnostic_snapshots -- [fixtures-dir]
+//!   cargo run --bin diagnostic_snapshots -- --bless [fixtures-dir]
+
+use std::path::PathBuf;
+
+use compiler::tools::diagnostic_snapshots::{run_snapshots, SnapshotConfig};
+
+fn main() {
+    let mut bless = false;
+    let mut fixtures_dir: Option<PathBuf> = None;
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--bless" => bless = true,
+            "--help" | "-h" => {
+                println!("Usage: diagnostic_snapshots [--bless] [fixtures-dir]");
+                println!();
+                println!("Compiles every .hx fixture and compares its diagnostics against");
+                println!("the neighbouring .stderr snapshot. --bless rewrites the snapshots.");
+                return;
+            }
+            other => fixtures_dir = Some(PathBuf::from(other)),
+        }
+    }
+
+    let fixtures_dir = fixtures_dir
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/diagnostics"));
+
+    let mut config = SnapshotConfig::new(fixtures_dir).bless_from_env();
+    config.bless |= bless;
+
+    match run_snapshots(&config) {
+        Ok(report) => {
+            print!("{}", report.render());
+            if !report.is_success() {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(2);
+        }
+    }
+}
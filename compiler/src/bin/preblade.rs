@@ -28,6 +28,7 @@ fn main() {
     let mut out_path: Option<PathBuf> = None;
     let mut bundle_path: Option<PathBuf> = None;
     let mut source_files: Vec<String> = Vec::new();
+    let mut rpkg_files: Vec<PathBuf> = Vec::new();
     let mut list_only = false;
     let mut verbose = false;
     let mut opt_level: Option<OptimizationLevel> = None;
@@ -49,6 +50,12 @@ fn main() {
                     bundle_path = Some(PathBuf::from(&args[i]));
                 }
             }
+            "--rpkg" => {
+                i += 1;
+                if i < args.len() {
+                    rpkg_files.push(PathBuf::from(&args[i]));
+                }
+            }
             "--optimize" | "-O" => {
                 i += 1;
                 if i < args.len() {
@@ -98,6 +105,10 @@ fn main() {
             compress,
             enable_cache: false,
             cache_dir: None,
+            embed_sources: false,
+            obfuscate: false,
+            encryption_key: None,
+            entry_points: Vec::new(),
         };
 
         match preblade::create_bundle(&config) {
@@ -133,6 +144,7 @@ fn main() {
         list_only,
         verbose,
         cache_dir: None,
+        rpkg_files,
     };
 
     match preblade::extract_stdlib_symbols(&config) {
@@ -168,6 +180,7 @@ fn print_usage() {
     println!("Options:");
     println!("  --out, -o <PATH>      Output directory for .bsym files");
     println!("  --bundle, -b <FILE>   Create a .rzb bundle from source files");
+    println!("  --rpkg <FILE>         Also index an .rpkg package (repeatable)");
     println!("  --optimize, -O <N>    Apply MIR optimizations (0-3, default: 2)");
     println!("  -O0, -O1, -O2, -O3   Shorthand for --optimize N");
     println!("  --strip               Enable dead-code stripping (for AOT/size-optimized bundles)");
@@ -19,6 +19,12 @@ fn main() {
     let mut opt_level = OptimizationLevel::O2;
     let mut strip = true; // tree-shake by default for AOT
     let mut strip_symbols = false;
+    let mut gc_sections = true;
+    let mut pie = true;
+    let mut relro = true;
+    let mut stack_protector = true;
+    let mut nx = true;
+    let mut static_link = false;
     let mut verbose = false;
     let mut linker: Option<String> = None;
     let mut runtime_dir: Option<PathBuf> = None;
@@ -50,9 +56,10 @@ fn main() {
                         "llvm-ir" => OutputFormat::LlvmIr,
                         "llvm-bc" => OutputFormat::LlvmBitcode,
                         "asm" => OutputFormat::Assembly,
+                        "size-report" => OutputFormat::SizeReport,
                         other => {
                             eprintln!(
-                                "Unknown emit format: {}. Use: exe, obj, llvm-ir, llvm-bc, asm",
+                                "Unknown emit format: {}. Use: exe, obj, llvm-ir, llvm-bc, asm, size-report",
                                 other
                             );
                             std::process::exit(1);
@@ -74,6 +81,12 @@ fn main() {
             "-O3" => opt_level = OptimizationLevel::O3,
             "--no-strip" => strip = false,
             "--strip" => strip_symbols = true,
+            "--no-gc-sections" => gc_sections = false,
+            "--no-pie" => pie = false,
+            "--no-relro" => relro = false,
+            "--no-stack-protector" => stack_protector = false,
+            "--no-nx" => nx = false,
+            "--static" => static_link = true,
             "--runtime-dir" => {
                 i += 1;
                 if i < args.len() {
@@ -121,6 +134,12 @@ fn main() {
         opt_level,
         strip,
         strip_symbols,
+        gc_sections,
+        pie,
+        relro,
+        stack_protector,
+        nx,
+        static_link,
         verbose,
         linker,
         runtime_dir,
@@ -147,11 +166,17 @@ fn print_usage() {
     println!("    -o, --output <FILE>       Output path (default: <source>.out)");
     println!("    --target <TRIPLE>         Target triple (default: host)");
     println!(
-        "    --emit <FORMAT>           Output: exe, obj, llvm-ir, llvm-bc, asm (default: exe)"
+        "    --emit <FORMAT>           Output: exe, obj, llvm-ir, llvm-bc, asm, size-report (default: exe)"
     );
     println!("    -O0, -O1, -O2, -O3       Optimization level (default: O2)");
     println!("    --no-strip                Disable dead-code stripping");
     println!("    --strip                   Strip debug symbols from binary");
+    println!("    --no-gc-sections          Keep unreferenced sections when linking");
+    println!("    --no-pie                  Link a position-dependent executable");
+    println!("    --no-relro                Leave relocated data writable (ELF)");
+    println!("    --no-stack-protector      Don't add stack canaries");
+    println!("    --no-nx                   Allow an executable stack");
+    println!("    --static                  Fully static executable (musl target)");
     println!("    --runtime-dir <DIR>       Path to librayzor_runtime.a");
     println!("    --linker <PATH>           Override linker path");
     println!("    --sysroot <PATH>          Sysroot for cross-compilation");
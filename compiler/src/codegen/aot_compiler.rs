@@ -8,11 +8,18 @@ use inkwell::context::Context;
 #[cfg(feature = "llvm-backend")]
 use inkwell::targets::RelocMode;
 
+use crate::codegen::aot_symbols::{self, SymbolCheck};
+use crate::codegen::size_report::SizeReport;
+use crate::codegen::wasi_runtime;
 use crate::compilation::{CompilationConfig, CompilationUnit};
-use crate::ir::optimization::{OptimizationLevel, PassManager};
+use crate::ir::escape_summary::EscapeSummaries;
+use crate::ir::module_init;
+use crate::ir::optimization::{OptimizationLevel, PassManager, PassSizeLimits};
 use crate::ir::tree_shake;
+use crate::ir::IrModule;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Arc;
 
 /// Output format for AOT compilation
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -27,6 +34,15 @@ pub enum OutputFormat {
     LlvmBitcode,
     /// Native assembly (.s)
     Assembly,
+    /// Linked executable plus a breakdown of its size by package and class
+    SizeReport,
+}
+
+impl OutputFormat {
+    /// Whether this format produces a linked executable
+    pub fn is_linked(self) -> bool {
+        matches!(self, OutputFormat::Executable | OutputFormat::SizeReport)
+    }
 }
 
 /// Result of AOT compilation
@@ -35,6 +51,8 @@ pub struct AotOutput {
     pub format: OutputFormat,
     pub target_triple: String,
     pub code_size: u64,
+    /// Size breakdown, for `OutputFormat::SizeReport`
+    pub size_report: Option<SizeReport>,
 }
 
 /// AOT compiler configuration
@@ -57,6 +75,23 @@ pub struct AotCompiler {
     pub sysroot: Option<PathBuf>,
     /// Strip debug symbols from binary
     pub strip_symbols: bool,
+    /// Emit each function/global in its own section and let the linker
+    /// drop unreferenced ones (--gc-sections / -dead_strip)
+    pub gc_sections: bool,
+    /// Link a position-independent executable, so ASLR can place it
+    pub pie: bool,
+    /// Make relocated data read-only after startup (full RELRO, ELF only)
+    pub relro: bool,
+    /// Add stack canaries to functions with local arrays or address-taken
+    /// locals (`-fstack-protector-strong`)
+    pub stack_protector: bool,
+    /// Mark the stack non-executable (NX / DEP)
+    pub nx: bool,
+    /// Link everything, including the C library, statically. Needs a musl
+    /// target.
+    pub static_link: bool,
+    /// Function size limits of the MIR optimization passes
+    pub pass_size_limits: PassSizeLimits,
 }
 
 impl Default for AotCompiler {
@@ -71,6 +106,13 @@ impl Default for AotCompiler {
             runtime_dir: None,
             sysroot: None,
             strip_symbols: false,
+            gc_sections: true,
+            pie: true,
+            relro: true,
+            stack_protector: true,
+            nx: true,
+            static_link: false,
+            pass_size_limits: PassSizeLimits::defaults(),
         }
     }
 }
@@ -85,35 +127,18 @@ impl AotCompiler {
     ) -> Result<AotOutput, String> {
         use crate::codegen::llvm_aot_backend;
         use crate::codegen::llvm_jit_backend::LLVMJitBackend;
+        use crate::codegen::size_report;
+        use crate::ir::multiversion::{self, TargetArch};
         use std::time::Instant;
 
         let t0 = Instant::now();
 
-        // --- Phase 1: Parse and compile to MIR ---
-        if self.verbose {
-            println!("  Parsing and lowering to MIR...");
-        }
-
-        let mut unit = CompilationUnit::new(CompilationConfig::default());
-        unit.load_stdlib()
-            .map_err(|e| format!("Failed to load stdlib: {}", e))?;
-
-        for source_file in source_files {
-            let source = std::fs::read_to_string(source_file)
-                .map_err(|e| format!("Failed to read {}: {}", source_file, e))?;
-            unit.add_file(&source, source_file)
-                .map_err(|e| format!("Failed to add {}: {}", source_file, e))?;
-        }
-
-        unit.lower_to_tast()
-            .map_err(|errors| format!("Compilation failed: {:?}", errors))?;
-
-        let mir_modules = unit.get_mir_modules();
-        if mir_modules.is_empty() {
-            return Err("No MIR modules generated".to_string());
+        if self.output_format.is_linked() {
+            self.check_link_options()?;
         }
 
-        let mut modules: Vec<_> = mir_modules.iter().map(|m| (**m).clone()).collect();
+        // --- Phase 1: Parse and compile to MIR ---
+        let mut modules = self.lower_to_mir(source_files)?;
 
         // --- Phase 2: MIR optimizations ---
         // Check if system LLVM tools are available for optimization.
@@ -121,8 +146,12 @@ impl AotCompiler {
         // operation ordering, and system LLVM (newer version) optimizes differently
         // with the reordered ops, producing different FP results. System opt -O3
         // handles GVN/vectorization/etc. natively anyway.
-        let has_system_tools = (self.output_format == OutputFormat::Executable
+        // WebAssembly goes through the built-in LLVM, which sets the target
+        // triple the wasm backend needs (the system tools path leaves it out).
+        let is_wasm = self.is_wasm_target();
+        let has_system_tools = (self.output_format.is_linked()
             || self.output_format == OutputFormat::ObjectFile)
+            && !is_wasm
             && llvm_aot_backend::has_system_llvm_tools();
         let mir_opt = if has_system_tools && self.opt_level == OptimizationLevel::O3 {
             OptimizationLevel::O2
@@ -133,46 +162,47 @@ impl AotCompiler {
             if self.verbose {
                 println!("  Applying MIR optimizations ({:?})...", mir_opt);
             }
-            let mut pass_manager = PassManager::for_level(mir_opt);
+            let escapes = Arc::new(EscapeSummaries::for_modules(&modules));
+            let mut pass_manager = PassManager::for_program(mir_opt, None, Some(escapes))
+                .with_size_limits(self.pass_size_limits.clone());
             for module in &mut modules {
                 let _ = pass_manager.run(module);
             }
+            pass_manager.report_demotions();
         }
 
-        // --- Phase 3: Find entry point ---
-        let (entry_module_name, entry_function_name) = find_entry_point(&modules)?;
-        if self.verbose {
-            println!(
-                "  Entry point: {}::{}",
-                entry_module_name, entry_function_name
-            );
-        }
+        // --- Phases 3-4: Entry point and tree-shaking ---
+        let (entry_module_name, entry_function_name) = self.prepare_entry(&mut modules)?;
 
-        // --- Phase 4: Tree-shake ---
-        if self.strip {
-            if self.verbose {
-                println!("  Tree-shaking...");
+        // Catch externs the runtime library (or the wasm shim) lacks before
+        // spending time in LLVM, rather than as a linker error afterwards
+        if self.output_format.is_linked() {
+            let check = self.check_runtime_symbols(&modules)?;
+            if !check.is_ok() {
+                return Err(check.to_string());
             }
-            let stats = tree_shake::tree_shake_bundle(
-                &mut modules,
-                &entry_module_name,
-                &entry_function_name,
-            );
             if self.verbose {
-                println!(
-                    "    Removed: {} functions, {} externs, {} globals, {} empty modules",
-                    stats.functions_removed,
-                    stats.extern_functions_removed,
-                    stats.globals_removed,
-                    stats.modules_removed
-                );
-                println!(
-                    "    Kept: {} functions, {} externs",
-                    stats.functions_kept, stats.extern_functions_kept
-                );
+                println!("  Runtime symbols: {}", check);
             }
         }
 
+        // Clone @:targetFeature functions once per feature set of the target
+        let target_arch = match &self.target_triple {
+            Some(triple) => TargetArch::from_triple(triple),
+            None => TargetArch::from_triple(
+                &inkwell::targets::TargetMachine::get_default_triple()
+                    .as_str()
+                    .to_string_lossy(),
+            ),
+        };
+        let multiversioned = multiversion::create_versions(&mut modules, target_arch)?;
+        if self.verbose && !multiversioned.is_empty() {
+            println!(
+                "  Multi-versioned {} functions for CPU features",
+                multiversioned.len()
+            );
+        }
+
         // --- Phase 5: LLVM compilation ---
         if self.verbose {
             println!("  Compiling to LLVM IR...");
@@ -201,9 +231,61 @@ impl AotCompiler {
         // Find the LLVM function name for the entry point
         let entry_llvm_name = find_entry_llvm_name(&backend, &modules, &entry_function_name)?;
 
+        // Dispatch targets by LLVM name. Functions returning through a
+        // hidden sret pointer keep only their baseline version.
+        let dispatch_targets: Vec<_> = {
+            let symbols = backend.get_function_symbols();
+            let uses_sret = |id| {
+                modules
+                    .iter()
+                    .find_map(|m| m.functions.get(&id))
+                    .is_some_and(|f| f.signature.uses_sret)
+            };
+            multiversioned
+                .iter()
+                .filter(|mv| !uses_sret(mv.default))
+                .filter_map(|mv| {
+                    Some(llvm_aot_backend::DispatchTarget {
+                        function: symbols.get(&mv.default)?.clone(),
+                        versions: mv
+                            .versions
+                            .iter()
+                            .filter_map(|v| {
+                                Some((v.features.clone(), symbols.get(&v.func_id)?.clone()))
+                            })
+                            .collect(),
+                    })
+                })
+                .collect()
+        };
+
+        // Symbol -> class map for the size report, taken before the entry
+        // function may be renamed to `_haxe_main`
+        let symbol_owners = if self.output_format == OutputFormat::SizeReport {
+            let mut owners = size_report::symbol_owners(&modules, &backend.get_function_symbols());
+            if let Some(owner) = owners.get(&entry_llvm_name).cloned() {
+                owners.insert("_haxe_main".to_string(), owner);
+            }
+            owners
+        } else {
+            Default::default()
+        };
+
         // --- Phase 6: AOT-specific emit via llvm_aot_backend ---
         let module = backend.get_module();
         let target_triple_str = self.target_triple.as_deref();
+        llvm_aot_backend::emit_multiversion_dispatch(module, &dispatch_targets)?;
+        // wasm has no native stack to smash: its locals and return addresses
+        // live outside linear memory
+        if self.stack_protector && !is_wasm {
+            llvm_aot_backend::add_stack_protector(module);
+        }
+        if is_wasm {
+            let redirected = llvm_aot_backend::redirect_externs(module, wasi_runtime::wrapper_name);
+            if self.verbose && redirected > 0 {
+                println!("  Redirected {} libc calls to the WASI shim", redirected);
+            }
+        }
 
         // For executables and object files, try system LLVM tools (opt + llc) first.
         // System LLVM (typically v19-21) has better inlining heuristics than the
@@ -215,9 +297,7 @@ impl AotCompiler {
             OptimizationLevel::O3 => "-O3",
         };
 
-        if self.output_format == OutputFormat::Executable
-            || self.output_format == OutputFormat::ObjectFile
-        {
+        if self.output_format.is_linked() || self.output_format == OutputFormat::ObjectFile {
             // Dump IR WITHOUT main wrapper — optimization should see user code only.
             // The main wrapper will be linked separately as a tiny C file so that
             // system opt doesn't inline the entry into the C main() (which changes
@@ -227,7 +307,7 @@ impl AotCompiler {
             llvm_aot_backend::set_module_target(module, target_triple_str)?;
             let ir_text = module.print_to_string().to_string();
 
-            let obj_path = if self.output_format == OutputFormat::Executable {
+            let obj_path = if self.output_format.is_linked() {
                 output_path.with_extension("o")
             } else {
                 output_path.to_path_buf()
@@ -237,17 +317,19 @@ impl AotCompiler {
                 println!("  Optimizing and emitting object file...");
             }
 
-            let rename_entry = if self.output_format == OutputFormat::Executable {
+            let rename_entry = if self.output_format.is_linked() {
                 Some(entry_llvm_name.as_str())
             } else {
                 None
             };
-            let used_system = llvm_aot_backend::compile_ir_with_system_tools(
-                &ir_text,
-                &obj_path,
-                opt_flag,
-                rename_entry,
-            )?;
+            let used_system = has_system_tools
+                && llvm_aot_backend::compile_ir_with_system_tools(
+                    &ir_text,
+                    &obj_path,
+                    opt_flag,
+                    rename_entry,
+                    self.gc_sections,
+                )?;
 
             if !used_system {
                 // Fall back to inkwell optimization + codegen
@@ -255,25 +337,38 @@ impl AotCompiler {
                     println!("  (using built-in LLVM optimization)");
                 }
                 llvm_aot_backend::optimize_module(module, target_triple_str, llvm_opt)?;
+                // wasm objects always get one section per function and
+                // global, and the shim provides main()
+                if self.gc_sections && !is_wasm {
+                    llvm_aot_backend::split_sections(module, target_triple_str);
+                }
                 // Generate main() wrapper after inkwell optimization
-                if self.output_format == OutputFormat::Executable {
+                if self.output_format.is_linked() && !is_wasm {
                     llvm_aot_backend::generate_main_wrapper(module, &entry_llvm_name)?;
                 }
+                let reloc_mode = if is_wasm {
+                    RelocMode::Static
+                } else {
+                    RelocMode::PIC
+                };
                 llvm_aot_backend::compile_to_object_file(
                     module,
                     &obj_path,
                     target_triple_str,
-                    RelocMode::PIC,
+                    reloc_mode,
                     llvm_opt,
                 )?;
             }
 
-            if self.output_format == OutputFormat::Executable {
+            if self.output_format.is_linked() {
                 if self.verbose {
                     println!("  Linking...");
                 }
                 // When using system tools, link a C main() wrapper separately
-                if used_system {
+                if is_wasm {
+                    let entry = llvm_aot_backend::rename_entry(module, &entry_llvm_name)?;
+                    self.link_wasm_module(&obj_path, output_path, &entry)?;
+                } else if used_system {
                     self.link_executable_with_entry(&obj_path, output_path, &entry_llvm_name)?;
                 } else {
                     self.link_executable(&obj_path, output_path)?;
@@ -284,7 +379,7 @@ impl AotCompiler {
             // For IR/bitcode/asm output, use inkwell directly
             llvm_aot_backend::optimize_module(module, target_triple_str, llvm_opt)?;
 
-            if self.output_format == OutputFormat::Executable {
+            if self.output_format.is_linked() {
                 llvm_aot_backend::generate_main_wrapper(module, &entry_llvm_name)?;
             }
 
@@ -316,6 +411,12 @@ impl AotCompiler {
             }
         }
 
+        let size_report = if self.output_format == OutputFormat::SizeReport {
+            Some(SizeReport::from_binary(output_path, &symbol_owners)?)
+        } else {
+            None
+        };
+
         let elapsed = t0.elapsed();
         let code_size = std::fs::metadata(output_path).map(|m| m.len()).unwrap_or(0);
 
@@ -343,9 +444,113 @@ impl AotCompiler {
             format: self.output_format,
             target_triple: actual_triple,
             code_size,
+            size_report,
         })
     }
 
+    /// Check the externs the program in `source_files` references against
+    /// librayzor_runtime.a, without generating code or linking. Runs the MIR
+    /// pipeline of [`compile`](Self::compile) (optimization, entry point,
+    /// tree-shaking), so it works without the LLVM backend.
+    pub fn check_symbols(&self, source_files: &[String]) -> Result<SymbolCheck, String> {
+        let mut modules = self.lower_to_mir(source_files)?;
+        if self.opt_level != OptimizationLevel::O0 {
+            let escapes = Arc::new(EscapeSummaries::for_modules(&modules));
+            let mut pass_manager = PassManager::for_program(self.opt_level, None, Some(escapes))
+                .with_size_limits(self.pass_size_limits.clone());
+            for module in &mut modules {
+                let _ = pass_manager.run(module);
+            }
+        }
+        self.prepare_entry(&mut modules)?;
+        self.check_runtime_symbols(&modules)
+    }
+
+    /// Parse `source_files` with the stdlib and lower them to MIR
+    fn lower_to_mir(&self, source_files: &[String]) -> Result<Vec<IrModule>, String> {
+        if self.verbose {
+            println!("  Parsing and lowering to MIR...");
+        }
+
+        let mut unit = CompilationUnit::new(CompilationConfig::default());
+        unit.load_stdlib()
+            .map_err(|e| format!("Failed to load stdlib: {}", e))?;
+
+        for source_file in source_files {
+            let source = std::fs::read_to_string(source_file)
+                .map_err(|e| format!("Failed to read {}: {}", source_file, e))?;
+            unit.add_file(&source, source_file)
+                .map_err(|e| format!("Failed to add {}: {}", source_file, e))?;
+        }
+
+        unit.lower_to_tast()
+            .map_err(|errors| format!("Compilation failed: {:?}", errors))?;
+
+        let mir_modules = unit.get_mir_modules();
+        if mir_modules.is_empty() {
+            return Err("No MIR modules generated".to_string());
+        }
+
+        Ok(mir_modules.iter().map(|m| (**m).clone()).collect())
+    }
+
+    /// Find the entry point, make it run the module initializers and
+    /// tree-shake what it can't reach. Returns the entry (module, function).
+    fn prepare_entry(&self, modules: &mut Vec<IrModule>) -> Result<(String, String), String> {
+        let (entry_module_name, entry_function_name) = find_entry_point(modules)?;
+        if self.verbose {
+            println!(
+                "  Entry point: {}::{}",
+                entry_module_name, entry_function_name
+            );
+        }
+
+        // No host calls the initializers of an executable: make the entry
+        // point run them (this also keeps them alive through tree-shaking)
+        if let Some(module) = modules.iter_mut().find(|m| m.name == entry_module_name) {
+            let entry_id = module
+                .functions
+                .values()
+                .find(|f| f.name == entry_function_name)
+                .map(|f| f.id);
+            if let Some(entry_id) = entry_id {
+                module_init::call_initializers_from(module, entry_id);
+            }
+        }
+
+        if self.strip {
+            if self.verbose {
+                println!("  Tree-shaking...");
+            }
+            let stats =
+                tree_shake::tree_shake_bundle(modules, &entry_module_name, &entry_function_name);
+            if self.verbose {
+                println!(
+                    "    Removed: {} functions, {} externs, {} globals, {} empty modules",
+                    stats.functions_removed,
+                    stats.extern_functions_removed,
+                    stats.globals_removed,
+                    stats.modules_removed
+                );
+                println!(
+                    "    Kept: {} functions, {} externs",
+                    stats.functions_kept, stats.extern_functions_kept
+                );
+            }
+        }
+
+        Ok((entry_module_name, entry_function_name))
+    }
+
+    /// Check the externs `modules` reference against librayzor_runtime.a,
+    /// or the shim and wasi-libc for a wasm target
+    fn check_runtime_symbols(&self, modules: &[IrModule]) -> Result<SymbolCheck, String> {
+        if self.is_wasm_target() {
+            return Ok(wasi_runtime::check_symbols(modules));
+        }
+        aot_symbols::check_symbols(modules, &self.find_runtime()?)
+    }
+
     /// Link an object file into a native executable
     fn link_executable(&self, obj_path: &Path, output_path: &Path) -> Result<(), String> {
         let linker = self.find_linker()?;
@@ -372,7 +577,11 @@ impl AotCompiler {
         cmd.arg(opt_flag);
 
         // Cross-compilation target
-        if let Some(ref triple) = self.target_triple {
+        if let Some(triple) = self
+            .target_triple
+            .as_ref()
+            .filter(|_| takes_target(&linker))
+        {
             cmd.arg(format!("--target={}", triple));
         }
 
@@ -382,18 +591,8 @@ impl AotCompiler {
         }
 
         // Platform-specific linker flags
-        let triple_str = self.target_triple.as_deref().unwrap_or("");
-        if triple_str.contains("darwin") || triple_str.is_empty() && cfg!(target_os = "macos") {
-            // macOS
-            cmd.args(["-lSystem", "-lc", "-lm", "-lpthread"]);
-            cmd.args(["-framework", "CoreFoundation", "-framework", "Security"]);
-        } else if triple_str.contains("windows") {
-            // Windows
-            cmd.args(["kernel32.lib", "ws2_32.lib", "userenv.lib", "bcrypt.lib"]);
-        } else {
-            // Linux / other Unix
-            cmd.args(["-lc", "-lm", "-lpthread", "-ldl"]);
-        }
+        self.add_platform_link_args(&mut cmd);
+        self.add_hardening_args(&mut cmd);
 
         // Strip debug symbols
         if self.strip_symbols {
@@ -449,6 +648,15 @@ impl AotCompiler {
         cmd.arg(&main_c_path);
         cmd.arg(&runtime_path);
 
+        // The wrapper is compiled here, so it needs the compile-side
+        // hardening flags too
+        if !self.is_windows_target() {
+            cmd.arg(if self.pie { "-fPIE" } else { "-fno-pie" });
+            if self.stack_protector {
+                cmd.arg("-fstack-protector-strong");
+            }
+        }
+
         let opt_flag = match self.opt_level {
             OptimizationLevel::O0 => "-O0",
             OptimizationLevel::O1 => "-O1",
@@ -457,22 +665,19 @@ impl AotCompiler {
         };
         cmd.arg(opt_flag);
 
-        if let Some(ref triple) = self.target_triple {
+        if let Some(triple) = self
+            .target_triple
+            .as_ref()
+            .filter(|_| takes_target(&linker))
+        {
             cmd.arg(format!("--target={}", triple));
         }
         if let Some(ref sysroot) = self.sysroot {
             cmd.arg(format!("--sysroot={}", sysroot.display()));
         }
 
-        let triple_str = self.target_triple.as_deref().unwrap_or("");
-        if triple_str.contains("darwin") || triple_str.is_empty() && cfg!(target_os = "macos") {
-            cmd.args(["-lSystem", "-lc", "-lm", "-lpthread"]);
-            cmd.args(["-framework", "CoreFoundation", "-framework", "Security"]);
-        } else if triple_str.contains("windows") {
-            cmd.args(["kernel32.lib", "ws2_32.lib", "userenv.lib", "bcrypt.lib"]);
-        } else {
-            cmd.args(["-lc", "-lm", "-lpthread", "-ldl"]);
-        }
+        self.add_platform_link_args(&mut cmd);
+        self.add_hardening_args(&mut cmd);
 
         if self.strip_symbols {
             cmd.arg("-s");
@@ -497,14 +702,178 @@ impl AotCompiler {
         Ok(())
     }
 
+    /// Link a wasm object file with the WASI runtime shim into a module that
+    /// calls `entry_func_name` from `_start`
+    fn link_wasm_module(
+        &self,
+        obj_path: &Path,
+        output_path: &Path,
+        entry_func_name: &str,
+    ) -> Result<(), String> {
+        let shim_path = output_path.with_extension("_wasi.c");
+        std::fs::write(&shim_path, wasi_runtime::SHIM_SOURCE)
+            .map_err(|e| format!("Failed to write WASI runtime shim: {}", e))?;
+
+        let clang = wasi_runtime::find_clang(self.linker.as_deref());
+        let sysroot = wasi_runtime::find_sysroot(self.sysroot.as_ref())?;
+        let triple = self.target_triple.as_deref().unwrap_or("wasm32-wasi");
+
+        let mut cmd = Command::new(&clang);
+        cmd.arg(format!("--target={}", triple));
+        cmd.arg(format!("--sysroot={}", sysroot.display()));
+        cmd.arg("-o").arg(output_path);
+        cmd.arg(obj_path);
+        cmd.arg(&shim_path);
+        cmd.arg(format!("-DRAYZOR_ENTRY={}", entry_func_name));
+        cmd.arg(match self.opt_level {
+            OptimizationLevel::O0 => "-O0",
+            OptimizationLevel::O1 => "-O1",
+            OptimizationLevel::O2 => "-O2",
+            OptimizationLevel::O3 => "-O3",
+        });
+        cmd.arg("-lm");
+        if self.gc_sections {
+            cmd.arg("-Wl,--gc-sections");
+        }
+        if self.strip_symbols {
+            cmd.arg("-Wl,--strip-all");
+        }
+
+        if self.verbose {
+            println!("    {}", format_command(&cmd));
+        }
+
+        let output = cmd
+            .output()
+            .map_err(|e| format!("Failed to run {}: {}", clang, e))?;
+
+        let _ = std::fs::remove_file(&shim_path);
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Linking failed:\n{}", stderr));
+        }
+
+        Ok(())
+    }
+
+    /// System libraries and dead-stripping flags for the target platform
+    fn add_platform_link_args(&self, cmd: &mut Command) {
+        if self.is_macos_target() {
+            // macOS
+            cmd.args(["-lSystem", "-lc", "-lm", "-lpthread"]);
+            cmd.args(["-framework", "CoreFoundation", "-framework", "Security"]);
+            if self.gc_sections {
+                cmd.arg("-Wl,-dead_strip");
+            }
+        } else if self.is_windows_target() {
+            // Windows
+            cmd.args(["kernel32.lib", "ws2_32.lib", "userenv.lib", "bcrypt.lib"]);
+            if self.gc_sections {
+                cmd.arg("-Wl,/OPT:REF");
+            }
+        } else {
+            // Linux / other Unix
+            cmd.args(["-lc", "-lm", "-lpthread", "-ldl"]);
+            if self.gc_sections {
+                cmd.arg("-Wl,--gc-sections");
+            }
+        }
+    }
+
+    /// PIE, RELRO, non-executable stack and static linking flags for the
+    /// target platform
+    fn add_hardening_args(&self, cmd: &mut Command) {
+        if self.is_macos_target() {
+            // Mach-O executables are always PIE with a non-executable stack
+            // (arm64 requires it); there is no RELRO
+        } else if self.is_windows_target() {
+            cmd.arg(if self.pie {
+                "-Wl,/DYNAMICBASE"
+            } else {
+                "-Wl,/DYNAMICBASE:NO"
+            });
+            if self.pie {
+                cmd.arg("-Wl,/HIGHENTROPYVA");
+            }
+            cmd.arg(if self.nx {
+                "-Wl,/NXCOMPAT"
+            } else {
+                "-Wl,/NXCOMPAT:NO"
+            });
+        } else {
+            cmd.arg(match (self.static_link, self.pie) {
+                (true, true) => "-static-pie",
+                (true, false) => "-static",
+                (false, true) => "-pie",
+                (false, false) => "-no-pie",
+            });
+            cmd.arg(if self.relro {
+                "-Wl,-z,relro,-z,now"
+            } else {
+                "-Wl,-z,norelro"
+            });
+            cmd.arg(if self.nx {
+                "-Wl,-z,noexecstack"
+            } else {
+                "-Wl,-z,execstack"
+            });
+        }
+    }
+
+    /// Reject link options the target can't honour before compiling
+    fn check_link_options(&self) -> Result<(), String> {
+        if self.is_wasm_target() {
+            if self.output_format == OutputFormat::SizeReport {
+                return Err("size-report reads native executables; \
+                            it doesn't support wasm targets"
+                    .to_string());
+            }
+            // wasm modules are always self-contained
+            return Ok(());
+        }
+        if self.static_link {
+            let triple = self.target_triple.as_deref().unwrap_or("");
+            if !(triple.contains("linux") && triple.contains("musl")) {
+                return Err(format!(
+                    "--static needs a musl target (e.g. --target {}-unknown-linux-musl); \
+                     glibc, macOS and Windows executables can't be fully static",
+                    std::env::consts::ARCH
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn is_macos_target(&self) -> bool {
+        let triple = self.target_triple.as_deref().unwrap_or("");
+        triple.contains("darwin") || triple.is_empty() && cfg!(target_os = "macos")
+    }
+
+    fn is_windows_target(&self) -> bool {
+        let triple = self.target_triple.as_deref().unwrap_or("");
+        triple.contains("windows") || triple.is_empty() && cfg!(target_os = "windows")
+    }
+
+    fn is_wasm_target(&self) -> bool {
+        self.target_triple
+            .as_deref()
+            .is_some_and(wasi_runtime::is_wasm_triple)
+    }
+
     /// Find a suitable linker
-    fn find_linker(&self) -> Result<String, String> {
+    pub fn find_linker(&self) -> Result<String, String> {
         if let Some(ref linker) = self.linker {
             return Ok(linker.clone());
         }
 
-        // Try common linkers in order of preference
-        for candidate in &["clang", "gcc", "cc"] {
+        // musl-gcc links against musl without a separate sysroot
+        let candidates: &[&str] = if self.static_link {
+            &["musl-gcc", "clang", "gcc", "cc"]
+        } else {
+            &["clang", "gcc", "cc"]
+        };
+        for candidate in candidates {
             if Command::new(candidate)
                 .arg("--version")
                 .output()
@@ -519,7 +888,7 @@ impl AotCompiler {
     }
 
     /// Find the runtime static library
-    fn find_runtime(&self) -> Result<PathBuf, String> {
+    pub fn find_runtime(&self) -> Result<PathBuf, String> {
         // 1. Explicit --runtime-dir
         if let Some(ref dir) = self.runtime_dir {
             let path = dir.join("librayzor_runtime.a");
@@ -540,7 +909,18 @@ impl AotCompiler {
             }
         }
 
-        // 3. Check relative to cargo workspace (target/release and target/debug)
+        // 3. Check relative to cargo workspace: target/<triple>/{release,debug}
+        // when cross-compiling (e.g. a musl runtime for --static), then
+        // target/release and target/debug
+        if let Some(ref triple) = self.target_triple {
+            for profile in &["release", "debug"] {
+                let path =
+                    PathBuf::from(format!("target/{}/{}/librayzor_runtime.a", triple, profile));
+                if path.exists() {
+                    return Ok(path);
+                }
+            }
+        }
         for profile in &["release", "debug"] {
             let path = PathBuf::from(format!("target/{}/librayzor_runtime.a", profile));
             if path.exists() {
@@ -622,6 +1002,14 @@ fn find_entry_llvm_name(
     ))
 }
 
+/// Whether `linker` is a clang driver, which takes `--target` (gcc
+/// drivers, including musl-gcc, are built for one target and reject it)
+fn takes_target(linker: &str) -> bool {
+    Path::new(linker)
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().contains("clang"))
+}
+
 fn format_command(cmd: &Command) -> String {
     let prog = cmd.get_program().to_string_lossy().to_string();
     let args: Vec<_> = cmd
@@ -630,3 +1018,116 @@ fn format_command(cmd: &Command) -> String {
         .collect();
     format!("{} {}", prog, args.join(" "))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINUX: &str = "x86_64-unknown-linux-gnu";
+    const MUSL: &str = "x86_64-unknown-linux-musl";
+    const MACOS: &str = "aarch64-apple-darwin";
+    const WINDOWS: &str = "x86_64-pc-windows-msvc";
+    const WASM: &str = "wasm32-wasi";
+
+    fn compiler(triple: &str) -> AotCompiler {
+        AotCompiler {
+            target_triple: Some(triple.to_string()),
+            ..AotCompiler::default()
+        }
+    }
+
+    fn hardening_args(compiler: &AotCompiler) -> Vec<String> {
+        let mut cmd = Command::new("cc");
+        compiler.add_hardening_args(&mut cmd);
+        cmd.get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_elf_hardening_args() {
+        assert_eq!(
+            hardening_args(&compiler(LINUX)),
+            ["-pie", "-Wl,-z,relro,-z,now", "-Wl,-z,noexecstack"]
+        );
+        let unhardened = AotCompiler {
+            pie: false,
+            relro: false,
+            nx: false,
+            ..compiler(LINUX)
+        };
+        assert_eq!(
+            hardening_args(&unhardened),
+            ["-no-pie", "-Wl,-z,norelro", "-Wl,-z,execstack"]
+        );
+
+        let static_pie = AotCompiler {
+            static_link: true,
+            ..compiler(MUSL)
+        };
+        assert_eq!(
+            hardening_args(&static_pie),
+            ["-static-pie", "-Wl,-z,relro,-z,now", "-Wl,-z,noexecstack"]
+        );
+        let static_no_pie = AotCompiler {
+            pie: false,
+            ..static_pie
+        };
+        assert_eq!(hardening_args(&static_no_pie)[0], "-static");
+    }
+
+    #[test]
+    fn test_windows_hardening_args() {
+        assert_eq!(
+            hardening_args(&compiler(WINDOWS)),
+            ["-Wl,/DYNAMICBASE", "-Wl,/HIGHENTROPYVA", "-Wl,/NXCOMPAT"]
+        );
+        let unhardened = AotCompiler {
+            pie: false,
+            nx: false,
+            ..compiler(WINDOWS)
+        };
+        assert_eq!(
+            hardening_args(&unhardened),
+            ["-Wl,/DYNAMICBASE:NO", "-Wl,/NXCOMPAT:NO"]
+        );
+    }
+
+    #[test]
+    fn test_macos_hardening_args() {
+        // Always PIE with a non-executable stack; nothing to pass
+        assert!(hardening_args(&compiler(MACOS)).is_empty());
+        let unhardened = AotCompiler {
+            pie: false,
+            relro: false,
+            nx: false,
+            ..compiler(MACOS)
+        };
+        assert!(hardening_args(&unhardened).is_empty());
+    }
+
+    #[test]
+    fn test_check_link_options() {
+        let static_link = |triple| AotCompiler {
+            static_link: true,
+            ..compiler(triple)
+        };
+
+        assert_eq!(compiler(LINUX).check_link_options(), Ok(()));
+        assert_eq!(static_link(MUSL).check_link_options(), Ok(()));
+        for triple in [LINUX, MACOS, WINDOWS] {
+            let err = static_link(triple).check_link_options().unwrap_err();
+            assert!(err.contains("--static needs a musl target"), "{}", err);
+        }
+
+        // wasm modules are always self-contained, but have no size report
+        assert_eq!(static_link(WASM).check_link_options(), Ok(()));
+        let size_report = AotCompiler {
+            output_format: OutputFormat::SizeReport,
+            ..compiler(WASM)
+        };
+        assert!(size_report
+            .check_link_options()
+            .is_err_and(|err| err.contains("size-report")));
+    }
+}
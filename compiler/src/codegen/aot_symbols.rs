@@ -0,0 +1,345 @@
+//! Checking a program's externs against the runtime library before linking
+//!
+//! The JIT resolves externs against the runtime's symbol registry and binds
+//! any that nothing provides to diagnostic stubs. An AOT binary links
+//! librayzor_runtime.a instead, so a symbol the archive lacks used to show up
+//! as a linker error only after the whole program had gone through LLVM.
+//! [`check_symbols`] reads the archive's symbol index and reports every
+//! extern the MIR references that neither the archive nor the system C
+//! library provides. Symbols the runtime registers (see
+//! `rayzor_runtime::symbol_manifest`) but the archive lacks mean the archive
+//! is stale.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::cranelift_backend::CraneliftBackend;
+use crate::ir::IrModule;
+use rayzor_runtime::missing_extern::likely_provider;
+use rayzor_runtime::symbol_manifest;
+
+/// C library and libm functions compiled code calls directly; the linker
+/// takes these from the system libraries rather than the runtime archive
+pub(crate) const C_LIBRARY_SYMBOLS: &[&str] = &[
+    "_setjmp", "abort", "acos", "asin", "atan", "atan2", "calloc", "ceil", "cos", "exit", "exp",
+    "fabs", "floor", "fmod", "free", "log", "malloc", "memcmp", "memcpy", "memmove", "memset",
+    "pow", "realloc", "round", "sin", "sleep", "sqrt", "strlen", "tan", "time", "trunc", "write",
+];
+
+/// An extern the runtime archive doesn't provide
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingSymbol {
+    /// Native symbol name
+    pub symbol: String,
+    /// Haxe declaration that references it, if known
+    pub declaration: Option<String>,
+    /// The runtime registers the symbol for the JIT, so the archive is older
+    /// than the runtime sources
+    pub registered: bool,
+}
+
+/// Result of checking a program against a runtime archive
+#[derive(Debug, Clone)]
+pub struct SymbolCheck {
+    /// The archive checked against
+    pub archive: PathBuf,
+    /// Number of distinct externs the program references
+    pub referenced: usize,
+    /// Externs nothing provides, sorted by name
+    pub missing: Vec<MissingSymbol>,
+}
+
+impl SymbolCheck {
+    /// Whether every referenced extern can be linked
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+impl fmt::Display for SymbolCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_ok() {
+            return write!(
+                f,
+                "all {} externs are provided by {}",
+                self.referenced,
+                self.archive.display()
+            );
+        }
+        write!(
+            f,
+            "{} of {} externs are missing from {}:",
+            self.missing.len(),
+            self.referenced,
+            self.archive.display()
+        )?;
+        for missing in &self.missing {
+            write!(f, "\n  {}", missing.symbol)?;
+            if let Some(declaration) = &missing.declaration {
+                write!(f, " (required by {})", declaration)?;
+            }
+            if missing.registered {
+                write!(
+                    f,
+                    "\n    note: the runtime registers it for the JIT; the archive is stale, \
+                     rebuild it with `cargo build --release -p rayzor-runtime`"
+                )?;
+            } else {
+                write!(
+                    f,
+                    "\n    note: probably provided by {}",
+                    likely_provider(&missing.symbol)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check the externs `modules` reference against the runtime archive at
+/// `archive`
+pub fn check_symbols(modules: &[IrModule], archive: &Path) -> Result<SymbolCheck, String> {
+    let data = std::fs::read(archive)
+        .map_err(|e| format!("Failed to read {}: {}", archive.display(), e))?;
+    let provided = archive_symbols(&data).map_err(|e| format!("{}: {}", archive.display(), e))?;
+
+    let referenced = referenced_externs(modules);
+    let missing = referenced
+        .iter()
+        .filter(|(symbol, _)| {
+            // Mach-O symbol names carry a leading underscore
+            !provided.contains(symbol.as_str())
+                && !provided.contains(&format!("_{}", symbol))
+                && !is_system_symbol(symbol)
+        })
+        .map(|(symbol, declaration)| MissingSymbol {
+            symbol: symbol.clone(),
+            declaration: declaration.clone(),
+            registered: symbol_manifest::is_runtime_symbol(symbol),
+        })
+        .collect();
+
+    Ok(SymbolCheck {
+        archive: archive.to_path_buf(),
+        referenced: referenced.len(),
+        missing,
+    })
+}
+
+/// Externs `modules` reference by native symbol name, with the Haxe
+/// declaration behind each where known. Functions without a body that
+/// another module defines are calls between modules, not externs.
+pub fn referenced_externs(modules: &[IrModule]) -> BTreeMap<String, Option<String>> {
+    let defined: HashSet<String> = modules
+        .iter()
+        .flat_map(|m| m.functions.values())
+        .filter(|f| !f.cfg.blocks.is_empty())
+        .map(|f| link_symbol(&f.name))
+        .collect();
+
+    let mut externs = BTreeMap::new();
+    for module in modules {
+        for extern_fn in module.extern_functions.values() {
+            externs
+                .entry(link_symbol(&extern_fn.name))
+                .or_insert_with(|| CraneliftBackend::extern_declaration(&extern_fn.name, None));
+        }
+        for function in module.functions.values() {
+            if function.cfg.blocks.is_empty() {
+                externs
+                    .entry(link_symbol(&function.name))
+                    .or_insert_with(|| {
+                        CraneliftBackend::extern_declaration(
+                            &function.name,
+                            function.qualified_name.as_deref(),
+                        )
+                    });
+            }
+        }
+    }
+    externs.retain(|symbol, _| !defined.contains(symbol));
+    externs
+}
+
+/// Symbol the LLVM backend emits for a function name
+fn link_symbol(name: &str) -> String {
+    name.replace("::", "_")
+        .replace('<', "_L_")
+        .replace('>', "_R_")
+        .replace(',', "_C_")
+        .replace(' ', "_S_")
+}
+
+fn is_system_symbol(symbol: &str) -> bool {
+    C_LIBRARY_SYMBOLS.contains(&symbol) || symbol_manifest::SYSTEM_SYMBOLS.contains(&symbol)
+}
+
+/// Global symbols defined by the members of an `ar` archive, read from its
+/// symbol index (GNU `/` or `/SYM64/`, or BSD `__.SYMDEF`)
+pub fn archive_symbols(data: &[u8]) -> Result<HashSet<String>, String> {
+    const MAGIC: &[u8] = b"!<arch>\n";
+    const HEADER_LEN: usize = 60;
+
+    if !data.starts_with(MAGIC) {
+        return Err("not an ar archive".to_string());
+    }
+    let mut offset = MAGIC.len();
+    while offset + HEADER_LEN <= data.len() {
+        let header = &data[offset..offset + HEADER_LEN];
+        let name = std::str::from_utf8(&header[..16])
+            .map_err(|_| "malformed member header".to_string())?
+            .trim_end();
+        let size: usize = std::str::from_utf8(&header[48..58])
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .ok_or_else(|| "malformed member size".to_string())?;
+        let body_start = offset + HEADER_LEN;
+        let body = data
+            .get(body_start..body_start + size)
+            .ok_or_else(|| "truncated member".to_string())?;
+
+        match name {
+            "/" => return gnu_symbol_index(body, 4),
+            "/SYM64/" => return gnu_symbol_index(body, 8),
+            _ => {
+                // BSD long names follow the header: `#1/<length>`
+                if let Some(len) = name.strip_prefix("#1/").and_then(|l| l.parse().ok()) {
+                    let long_name = body.get(..len).ok_or("truncated member name")?;
+                    let long_name = String::from_utf8_lossy(long_name);
+                    let long_name = long_name.trim_end_matches('\0');
+                    if long_name.starts_with("__.SYMDEF") {
+                        let wide = long_name.starts_with("__.SYMDEF_64");
+                        return bsd_symbol_index(&body[len..], if wide { 8 } else { 4 });
+                    }
+                } else if name.starts_with("__.SYMDEF") {
+                    return bsd_symbol_index(body, 4);
+                }
+            }
+        }
+        // Members are aligned to even offsets
+        offset = body_start + size + size % 2;
+    }
+    Err("archive has no symbol index; run `ranlib` on it".to_string())
+}
+
+/// GNU index: big-endian count, one member offset per symbol, then the
+/// NUL-terminated names
+fn gnu_symbol_index(body: &[u8], word: usize) -> Result<HashSet<String>, String> {
+    let count = read_word(body, 0, word, false)?;
+    let names = body
+        .get(word * (count + 1)..)
+        .ok_or("truncated symbol index")?;
+    Ok(names
+        .split(|b| *b == 0)
+        .filter(|name| !name.is_empty())
+        .take(count)
+        .map(|name| String::from_utf8_lossy(name).into_owned())
+        .collect())
+}
+
+/// BSD index: little-endian byte size of the (name offset, member offset)
+/// pairs, the pairs, the string table size, then the string table
+fn bsd_symbol_index(body: &[u8], word: usize) -> Result<HashSet<String>, String> {
+    let ranlib_size = read_word(body, 0, word, true)?;
+    let strings_at = word + ranlib_size;
+    let strings_size = read_word(body, strings_at, word, true)?;
+    let strings = body
+        .get(strings_at + word..strings_at + word + strings_size)
+        .ok_or("truncated symbol index")?;
+
+    let mut symbols = HashSet::new();
+    for pair in 0..ranlib_size / (2 * word) {
+        let name_at = read_word(body, word + pair * 2 * word, word, true)?;
+        let name = strings.get(name_at..).ok_or("bad symbol name offset")?;
+        let end = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+        symbols.insert(String::from_utf8_lossy(&name[..end]).into_owned());
+    }
+    Ok(symbols)
+}
+
+fn read_word(data: &[u8], at: usize, word: usize, little_endian: bool) -> Result<usize, String> {
+    let bytes = data.get(at..at + word).ok_or("truncated symbol index")?;
+    let mut value = 0u64;
+    for i in 0..word {
+        let byte = if little_endian {
+            bytes[word - 1 - i]
+        } else {
+            bytes[i]
+        };
+        value = (value << 8) | byte as u64;
+    }
+    Ok(value as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(name: &str, body: &[u8]) -> Vec<u8> {
+        let mut out = format!(
+            "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+            name,
+            0,
+            0,
+            0,
+            644,
+            body.len()
+        )
+        .into_bytes();
+        out.extend_from_slice(body);
+        if body.len() % 2 == 1 {
+            out.push(b'\n');
+        }
+        out
+    }
+
+    fn archive(members: &[Vec<u8>]) -> Vec<u8> {
+        let mut out = b"!<arch>\n".to_vec();
+        for m in members {
+            out.extend_from_slice(m);
+        }
+        out
+    }
+
+    #[test]
+    fn test_gnu_symbol_index() {
+        let mut index = 2u32.to_be_bytes().to_vec();
+        index.extend_from_slice(&100u32.to_be_bytes());
+        index.extend_from_slice(&100u32.to_be_bytes());
+        index.extend_from_slice(b"haxe_string_concat\0rayzor_malloc\0");
+        let data = archive(&[member("/", &index), member("runtime.o/", b"\x7fELF")]);
+
+        let symbols = archive_symbols(&data).unwrap();
+        assert_eq!(symbols.len(), 2);
+        assert!(symbols.contains("haxe_string_concat"));
+        assert!(symbols.contains("rayzor_malloc"));
+    }
+
+    #[test]
+    fn test_bsd_symbol_index() {
+        let strings = b"_haxe_trace\0_rayzor_free\0";
+        let mut index = 16u32.to_le_bytes().to_vec();
+        for (name_at, member_at) in [(0u32, 8u32), (12, 8)] {
+            index.extend_from_slice(&name_at.to_le_bytes());
+            index.extend_from_slice(&member_at.to_le_bytes());
+        }
+        index.extend_from_slice(&(strings.len() as u32).to_le_bytes());
+        index.extend_from_slice(strings);
+        let mut body = b"__.SYMDEF SORTED\0\0\0\0".to_vec();
+        body.extend_from_slice(&index);
+        let data = archive(&[member("#1/20", &body)]);
+
+        let symbols = archive_symbols(&data).unwrap();
+        assert_eq!(symbols.len(), 2);
+        assert!(symbols.contains("_haxe_trace"));
+        assert!(symbols.contains("_rayzor_free"));
+    }
+
+    #[test]
+    fn test_archive_without_index() {
+        let data = archive(&[member("runtime.o/", b"\x7fELF")]);
+        assert!(archive_symbols(&data).unwrap_err().contains("ranlib"));
+        assert!(archive_symbols(b"not an archive").is_err());
+    }
+}
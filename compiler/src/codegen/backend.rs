@@ -0,0 +1,236 @@
+//! Pluggable code generation backends
+//!
+//! `CodegenBackend` is the interface shared by the native backends (Cranelift,
+//! LLVM). Backends are created by name from a `BackendRegistry`, which holds
+//! the built-in ones plus any contributed by compiler plugins through
+//! `CompilerPlugin::codegen_backends`, so experimental backends (a
+//! copy-and-patch baseline, Wasm, embedded targets) can be added without
+//! touching the driver and selected with `rayzor run --backend <name>`.
+//!
+//! The tiered JIT (`TieredBackend`) stays the default execution engine; it
+//! is not a `CodegenBackend` itself but promotes functions between them.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use super::cranelift_backend::CraneliftBackend;
+use super::tiered_backend::OptimizationTier;
+use crate::compiler_plugin::CompilerPlugin;
+use crate::ir::{IrFunctionId, IrModule};
+
+/// A code generator from MIR to native code
+pub trait CodegenBackend {
+    /// Backend name as used with `--backend`
+    fn name(&self) -> &str;
+
+    /// Compile every function of `module`
+    fn compile_module(&mut self, module: &IrModule) -> Result<(), String>;
+
+    /// Native entry point of a compiled function
+    fn get_function_ptr(&mut self, func_id: IrFunctionId) -> Result<*const u8, String>;
+
+    /// Whether this backend can serve the given optimization tier
+    fn supports_tier(&self, tier: OptimizationTier) -> bool;
+
+    /// Write the compiled module to an object file (AOT)
+    fn emit_object(&mut self, _path: &Path) -> Result<(), String> {
+        Err(format!("{} backend cannot emit object files", self.name()))
+    }
+
+    /// Run the module's init functions and `main`
+    fn call_main(&mut self, module: &IrModule) -> Result<(), String>;
+}
+
+/// Creates a backend linked against the given runtime symbols
+pub type BackendFactory =
+    fn(symbols: &[(&str, *const u8)]) -> Result<Box<dyn CodegenBackend>, String>;
+
+/// Named backend factories: built-in backends first, then plugin ones
+pub struct BackendRegistry {
+    factories: Vec<(String, BackendFactory)>,
+}
+
+impl BackendRegistry {
+    /// Registry with the backends compiled into this build
+    pub fn new() -> Self {
+        let mut registry = Self {
+            factories: Vec::new(),
+        };
+        registry.register("cranelift", |symbols| {
+            Ok(Box::new(CraneliftBackend::with_symbols(symbols)?))
+        });
+        #[cfg(feature = "llvm-backend")]
+        registry.register("llvm", |symbols| {
+            use super::llvm_jit_backend::LLVMJitBackend;
+            // The backend borrows its context for its whole lifetime
+            let context = Box::leak(Box::new(inkwell::context::Context::create()));
+            Ok(Box::new(LLVMJitBackend::with_symbols(context, symbols)?))
+        });
+        registry
+    }
+
+    /// Register a backend; a later registration under the same name wins
+    pub fn register(&mut self, name: &str, factory: BackendFactory) {
+        self.factories.retain(|(n, _)| n != name);
+        self.factories.push((name.to_string(), factory));
+    }
+
+    /// Add the backends a compiler plugin provides
+    pub fn add_plugin(&mut self, plugin: &dyn CompilerPlugin) {
+        for (name, factory) in plugin.codegen_backends() {
+            self.register(name, factory);
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.iter().any(|(n, _)| n == name)
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.factories.iter().map(|(n, _)| n.as_str()).collect()
+    }
+
+    /// Instantiate the backend registered as `name`
+    pub fn create(
+        &self,
+        name: &str,
+        symbols: &[(&str, *const u8)],
+    ) -> Result<Box<dyn CodegenBackend>, String> {
+        let (_, factory) = self
+            .factories
+            .iter()
+            .find(|(n, _)| n == name)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown backend '{}' (available: {})",
+                    name,
+                    self.names().join(", ")
+                )
+            })?;
+        factory(symbols)
+    }
+}
+
+impl Default for BackendRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compile `module` with a single backend and run it: registers the
+/// module's RTTI and metadata with the runtime, then calls its init
+/// functions and `main`
+pub fn run_module(backend: &mut dyn CodegenBackend, module: &IrModule) -> Result<(), String> {
+    let modules = [Arc::new(module.clone())];
+    CraneliftBackend::register_enum_rtti_from_modules(&modules);
+    CraneliftBackend::register_class_rtti_from_modules(&modules);
+    CraneliftBackend::register_meta_from_modules(&modules);
+
+    backend.compile_module(module)?;
+    backend.call_main(module)
+}
+
+impl CodegenBackend for CraneliftBackend {
+    fn name(&self) -> &str {
+        "cranelift"
+    }
+
+    fn compile_module(&mut self, module: &IrModule) -> Result<(), String> {
+        CraneliftBackend::compile_module(self, module)
+    }
+
+    fn get_function_ptr(&mut self, func_id: IrFunctionId) -> Result<*const u8, String> {
+        CraneliftBackend::get_function_ptr(self, func_id)
+    }
+
+    fn supports_tier(&self, tier: OptimizationTier) -> bool {
+        tier != OptimizationTier::Interpreted
+    }
+
+    fn call_main(&mut self, module: &IrModule) -> Result<(), String> {
+        CraneliftBackend::call_main(self, module)
+    }
+}
+
+#[cfg(feature = "llvm-backend")]
+impl<'ctx> CodegenBackend for super::llvm_jit_backend::LLVMJitBackend<'ctx> {
+    fn name(&self) -> &str {
+        "llvm"
+    }
+
+    fn compile_module(&mut self, module: &IrModule) -> Result<(), String> {
+        Self::compile_module(self, module)
+    }
+
+    fn get_function_ptr(&mut self, func_id: IrFunctionId) -> Result<*const u8, String> {
+        self.finalize()?;
+        Self::get_function_ptr(self, func_id)
+    }
+
+    fn supports_tier(&self, tier: OptimizationTier) -> bool {
+        tier == OptimizationTier::Maximum
+    }
+
+    fn emit_object(&mut self, path: &Path) -> Result<(), String> {
+        self.compile_to_object_file(path)
+    }
+
+    fn call_main(&mut self, module: &IrModule) -> Result<(), String> {
+        Self::call_main(self, module)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NullBackend;
+
+    impl CodegenBackend for NullBackend {
+        fn name(&self) -> &str {
+            "null"
+        }
+
+        fn compile_module(&mut self, _module: &IrModule) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn get_function_ptr(&mut self, _func_id: IrFunctionId) -> Result<*const u8, String> {
+            Err("null backend has no code".to_string())
+        }
+
+        fn supports_tier(&self, _tier: OptimizationTier) -> bool {
+            false
+        }
+
+        fn call_main(&mut self, _module: &IrModule) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_builtin_backends() {
+        let registry = BackendRegistry::new();
+        assert!(registry.contains("cranelift"));
+        assert_eq!(registry.contains("llvm"), cfg!(feature = "llvm-backend"));
+
+        let backend = registry.create("cranelift", &[]).unwrap();
+        assert_eq!(backend.name(), "cranelift");
+        assert!(backend.supports_tier(OptimizationTier::Baseline));
+        assert!(!backend.supports_tier(OptimizationTier::Interpreted));
+    }
+
+    #[test]
+    fn test_register_custom_backend() {
+        let mut registry = BackendRegistry::new();
+        registry.register("null", |_| Ok(Box::new(NullBackend)));
+        assert_eq!(registry.names().last(), Some(&"null"));
+
+        let mut backend = registry.create("null", &[]).unwrap();
+        let err = backend.emit_object(Path::new("out.o")).unwrap_err();
+        assert!(err.contains("null backend cannot emit object files"));
+
+        let err = registry.create("wasm", &[]).err().unwrap();
+        assert!(err.contains("Unknown backend 'wasm'"));
+    }
+}
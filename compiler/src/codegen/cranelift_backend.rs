@@ -24,6 +24,10 @@ use crate::ir::{
 };
 use tracing::{debug, info, trace, warn};
 
+use super::instruction_lowering::{convert_int, float_to_int_sat, rayzor_fmod};
+use super::native_listing::{ListingFilter, MirPoint, NativeListing};
+use rayzor_runtime::missing_extern::{self, MissingExtern};
+
 /// Cranelift JIT backend for compiling MIR to native code
 pub struct CraneliftBackend {
     /// Cranelift JIT module
@@ -74,6 +78,24 @@ pub struct CraneliftBackend {
     /// Used to link forward references to actual implementations
     /// Key is qualified name (e.g., "StringTools.unsafeCodeAt")
     qualified_name_to_func: HashMap<String, FuncId>,
+
+    /// Functions defined since the last finalize, described for crash
+    /// reports. Only filled when crash reporting is enabled.
+    crash_functions: Vec<(FuncId, rayzor_runtime::crash::CrashFunction)>,
+
+    /// Functions to record machine code listings for (`rayzor dump --emit native`)
+    listing_filter: Option<ListingFilter>,
+
+    /// Listings recorded so far
+    listings: Vec<NativeListing>,
+
+    /// Symbols registered with the JIT builder. Externs outside this set that
+    /// the process can't resolve either are bound to diagnostic stubs.
+    known_symbols: HashSet<String>,
+
+    /// Functions already compiled by another backend, declared as imports of
+    /// the symbol their code was registered under (see [`Self::import_compiled`])
+    imported_functions: HashMap<IrFunctionId, String>,
 }
 
 impl CraneliftBackend {
@@ -208,6 +230,15 @@ impl CraneliftBackend {
         for (name, ptr) in symbols {
             builder.symbol(*name, *ptr);
         }
+        builder.symbol("rayzor_fmod", rayzor_fmod as *const u8);
+        builder.symbol(
+            "rayzor_missing_extern",
+            missing_extern::rayzor_missing_extern as *const u8,
+        );
+        let mut known_symbols: HashSet<String> =
+            symbols.iter().map(|(name, _)| name.to_string()).collect();
+        known_symbols.insert("rayzor_fmod".to_string());
+        known_symbols.insert("rayzor_missing_extern".to_string());
 
         // Create JIT module
         let mut module = JITModule::new(builder);
@@ -230,6 +261,11 @@ impl CraneliftBackend {
             string_data: HashMap::new(),
             string_counter: 0,
             qualified_name_to_func: HashMap::new(),
+            crash_functions: Vec::new(),
+            listing_filter: None,
+            listings: Vec::new(),
+            known_symbols,
+            imported_functions: HashMap::new(),
         })
     }
 
@@ -453,6 +489,7 @@ impl CraneliftBackend {
             }
             self.declare_function(*func_id, function)?;
         }
+        self.bind_missing_externs(mir_module)?;
 
         // Declare memory management functions ONCE (across ALL modules)
         // Use libc malloc/free for best performance (tracked allocator was for debugging)
@@ -479,6 +516,13 @@ impl CraneliftBackend {
             debug!("Declaring runtime function: rayzor_global_store");
             self.declare_runtime_function("rayzor_global_store", &[types::I64, types::I64], None)?;
         }
+        if !self.runtime_functions.contains_key("rayzor_fmod") {
+            self.declare_runtime_function(
+                "rayzor_fmod",
+                &[types::F64, types::F64],
+                Some(types::F64),
+            )?;
+        }
 
         // Map MIR function IDs for malloc/realloc/free to their tracked Cranelift IDs
         // This ensures that when MIR code calls these functions, they resolve to tracked versions
@@ -545,16 +589,10 @@ impl CraneliftBackend {
             match self.compile_function(*func_id, mir_module, function) {
                 Ok(()) => {}
                 Err(e) => {
-                    eprintln!(
-                        "[WARN] Skipping function '{}' ({}): {}",
-                        function.name, func_id, e
-                    );
+                    warn!("Skipping function '{}' ({}): {}", function.name, func_id, e);
                     // Define a trap stub so finalize_definitions doesn't panic
                     if let Err(e2) = self.define_trap_stub(*func_id, function) {
-                        eprintln!(
-                            "[WARN] Failed to define trap stub for '{}': {}",
-                            function.name, e2
-                        );
+                        warn!("Failed to define trap stub for '{}': {}", function.name, e2);
                     }
                 }
             }
@@ -564,6 +602,7 @@ impl CraneliftBackend {
         self.module
             .finalize_definitions()
             .map_err(|e| format!("Failed to finalize definitions: {}", e))?;
+        self.register_crash_functions();
 
         Ok(())
     }
@@ -606,6 +645,7 @@ impl CraneliftBackend {
             }
             self.declare_function(*func_id, function)?;
         }
+        self.bind_missing_externs(mir_module)?;
 
         // Declare memory management functions ONCE (across ALL modules)
         // Use libc malloc/free for best performance
@@ -631,6 +671,13 @@ impl CraneliftBackend {
             debug!("Declaring runtime function: rayzor_global_store");
             self.declare_runtime_function("rayzor_global_store", &[types::I64, types::I64], None)?;
         }
+        if !self.runtime_functions.contains_key("rayzor_fmod") {
+            self.declare_runtime_function(
+                "rayzor_fmod",
+                &[types::F64, types::F64],
+                Some(types::F64),
+            )?;
+        }
 
         // Map MIR function IDs for malloc/realloc/free to their libc Cranelift IDs
         for (func_id, function) in &mir_module.functions {
@@ -669,15 +716,9 @@ impl CraneliftBackend {
             match self.compile_function(*func_id, mir_module, function) {
                 Ok(()) => {}
                 Err(e) => {
-                    eprintln!(
-                        "[WARN] Skipping function '{}' ({}): {}",
-                        function.name, func_id, e
-                    );
+                    warn!("Skipping function '{}' ({}): {}", function.name, func_id, e);
                     if let Err(e2) = self.define_trap_stub(*func_id, function) {
-                        eprintln!(
-                            "[WARN] Failed to define trap stub for '{}': {}",
-                            function.name, e2
-                        );
+                        warn!("Failed to define trap stub for '{}': {}", function.name, e2);
                     }
                 }
             }
@@ -694,7 +735,28 @@ impl CraneliftBackend {
     pub fn finalize(&mut self) -> Result<(), String> {
         self.module
             .finalize_definitions()
-            .map_err(|e| format!("Failed to finalize definitions: {}", e))
+            .map_err(|e| format!("Failed to finalize definitions: {}", e))?;
+        self.register_crash_functions();
+        Ok(())
+    }
+
+    /// Record machine code listings for the functions matching `filter`
+    /// compiled from now on (see [`Self::take_listings`])
+    pub fn set_listing_filter(&mut self, filter: ListingFilter) {
+        self.listing_filter = Some(filter);
+    }
+
+    /// Listings recorded since the last call
+    pub fn take_listings(&mut self) -> Vec<NativeListing> {
+        std::mem::take(&mut self.listings)
+    }
+
+    /// Hand the functions finalized since the last call to the crash reporter
+    fn register_crash_functions(&mut self) {
+        for (func_id, mut function) in self.crash_functions.drain(..) {
+            function.start = self.module.get_finalized_function(func_id) as usize;
+            rayzor_runtime::crash::register_function(function);
+        }
     }
 
     /// Declare all functions from a module WITHOUT compiling their bodies.
@@ -712,6 +774,7 @@ impl CraneliftBackend {
             }
             self.declare_function(*func_id, function)?;
         }
+        self.bind_missing_externs(mir_module)?;
 
         // Declare C standard library memory functions ONCE (across ALL modules)
         if !self.runtime_functions.contains_key("malloc") {
@@ -755,6 +818,39 @@ impl CraneliftBackend {
         Ok(())
     }
 
+    /// Link `functions` to code another backend has compiled instead of
+    /// compiling them here. Each is declared as an import of its symbol,
+    /// which must be among the symbols this backend was created with. Call
+    /// before declaring the modules.
+    pub fn import_compiled(&mut self, functions: HashMap<IrFunctionId, String>) {
+        self.imported_functions = functions;
+    }
+
+    /// Compile the bodies of `functions` from `mir_module` WITHOUT calling
+    /// finalize_definitions. Everything they reference must already be
+    /// declared (see `declare_module_functions`).
+    pub fn compile_functions_without_finalize(
+        &mut self,
+        mir_module: &IrModule,
+        functions: &[IrFunctionId],
+    ) -> Result<(), String> {
+        for func_id in functions {
+            let Some(function) = mir_module.functions.get(func_id) else {
+                continue;
+            };
+            if function.cfg.blocks.is_empty() {
+                continue;
+            }
+            if let Err(e) = self.compile_function(*func_id, mir_module, function) {
+                warn!("Skipping function '{}' ({}): {}", function.name, func_id, e);
+                if let Err(e2) = self.define_trap_stub(*func_id, function) {
+                    warn!("Failed to define trap stub for '{}': {}", function.name, e2);
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Compile a single function (for tiered compilation)
     ///
     /// This method declares, compiles, and finalizes a single function.
@@ -777,6 +873,7 @@ impl CraneliftBackend {
         self.module
             .finalize_definitions()
             .map_err(|e| format!("Failed to finalize function: {}", e))?;
+        self.register_crash_functions();
 
         Ok(())
     }
@@ -989,6 +1086,19 @@ impl CraneliftBackend {
             }
         };
 
+        let (func_name, linkage) = match self.imported_functions.get(&mir_func_id) {
+            Some(symbol) => (symbol.clone(), Linkage::Import),
+            None => (func_name, linkage),
+        };
+
+        if is_extern && self.is_missing_extern(&function.name) {
+            let declaration =
+                Self::extern_declaration(&function.name, function.qualified_name.as_deref());
+            let func_id = self.define_missing_extern_stub(&function.name, &sig, declaration)?;
+            self.function_map.insert(mir_func_id, func_id);
+            return Ok(());
+        }
+
         let func_id = self
             .module
             .declare_function(&func_name, linkage, &sig)
@@ -1112,6 +1222,96 @@ impl CraneliftBackend {
         Ok(func_id)
     }
 
+    /// Whether an extern can't be linked: no plugin registered it and the
+    /// process doesn't export it either.
+    fn is_missing_extern(&self, name: &str) -> bool {
+        !self.known_symbols.contains(name) && !resolves_in_process(name)
+    }
+
+    /// Haxe declaration an extern symbol belongs to, for missing-extern diagnostics
+    pub(crate) fn extern_declaration(symbol: &str, qualified_name: Option<&str>) -> Option<String> {
+        if let Some(name) = qualified_name {
+            return Some(name.to_string());
+        }
+        let mapping = crate::stdlib::runtime_mapping::StdlibMapping::new();
+        let sig = mapping.find_method_by_runtime_name(symbol)?;
+        Some(if sig.is_constructor {
+            format!("new {}", sig.class)
+        } else {
+            format!("{}.{}", sig.class, sig.method)
+        })
+    }
+
+    /// Bind externs from `mir_module.extern_functions` that nothing provides
+    /// to diagnostic stubs, before call sites declare them as imports.
+    fn bind_missing_externs(&mut self, mir_module: &IrModule) -> Result<(), String> {
+        for extern_func in mir_module.extern_functions.values() {
+            if self.runtime_functions.contains_key(&extern_func.name)
+                || !self.is_missing_extern(&extern_func.name)
+            {
+                continue;
+            }
+            let sig = Self::extern_signature(&self.module, extern_func)?;
+            let declaration = Self::extern_declaration(&extern_func.name, None);
+            self.define_missing_extern_stub(&extern_func.name, &sig, declaration)?;
+        }
+        Ok(())
+    }
+
+    /// Define `name` as a local stub that reports the missing extern through
+    /// `rayzor_missing_extern` and traps.
+    ///
+    /// Linking succeeds and the program only fails if the extern is actually
+    /// called, with a diagnostic naming the symbol, the Haxe declaration and
+    /// the plugin or rpkg that probably provides it.
+    fn define_missing_extern_stub(
+        &mut self,
+        name: &str,
+        sig: &Signature,
+        declaration: Option<String>,
+    ) -> Result<FuncId, String> {
+        let missing = MissingExtern::new(name, declaration);
+        warn!(
+            "extern '{}' is not provided by any loaded plugin; calls to it will abort (probably provided by {})",
+            name,
+            missing.provider()
+        );
+        let index = missing_extern::register(missing);
+
+        let report_id =
+            self.declare_runtime_function("rayzor_missing_extern", &[types::I64], None)?;
+        let func_id = self
+            .module
+            .declare_function(name, Linkage::Local, sig)
+            .map_err(|e| format!("Failed to declare missing extern stub {}: {}", name, e))?;
+
+        self.ctx.func.clear();
+        self.ctx.func.signature = sig.clone();
+
+        let mut builder_ctx = FunctionBuilderContext::new();
+        let mut builder = FunctionBuilder::new(&mut self.ctx.func, &mut builder_ctx);
+        let block = builder.create_block();
+        builder.append_block_params_for_function_params(block);
+        builder.switch_to_block(block);
+        builder.seal_block(block);
+        let report = self.module.declare_func_in_func(report_id, builder.func);
+        let index = builder.ins().iconst(types::I64, index);
+        builder.ins().call(report, &[index]);
+        builder
+            .ins()
+            .trap(cranelift_codegen::ir::TrapCode::user(1).unwrap());
+        builder.finalize();
+
+        self.module
+            .define_function(func_id, &mut self.ctx)
+            .map_err(|e| format!("Failed to define missing extern stub {}: {}", name, e))?;
+        self.defined_functions.insert(func_id);
+        self.module.clear_context(&mut self.ctx);
+
+        self.runtime_functions.insert(name.to_string(), func_id);
+        Ok(func_id)
+    }
+
     /// Define a minimal trap stub for a function that failed compilation.
     /// This prevents cranelift's finalize_definitions from panicking on
     /// declared-but-uncompiled functions.
@@ -1283,6 +1483,20 @@ impl CraneliftBackend {
         let mut builder_ctx = FunctionBuilderContext::new();
         let mut builder = FunctionBuilder::new(&mut self.ctx.func, &mut builder_ctx);
 
+        // For listings, each Cranelift instruction's source location is the
+        // index of the MIR construct it was lowered from
+        let mut listing_points: Option<Vec<MirPoint>> = self
+            .listing_filter
+            .as_ref()
+            .filter(|f| f.matches(mir_module, function))
+            .map(|_| Vec::new());
+        let mut mark = |builder: &mut FunctionBuilder, point: MirPoint| {
+            if let Some(points) = listing_points.as_mut() {
+                builder.set_srcloc(cranelift_codegen::ir::SourceLoc::new(points.len() as u32));
+                points.push(point);
+            }
+        };
+
         // Clear value map for new function
         self.value_map.clear();
 
@@ -1344,6 +1558,9 @@ impl CraneliftBackend {
         } else {
             None
         };
+        // Register built directly in the caller's slot (ReturnSlotPass)
+        let return_slot = sret_ptr
+            .and_then(|sret| crate::ir::return_slot::return_slot(function).map(|reg| (reg, sret)));
 
         // Note: Don't seal entry block yet, we need to add instructions first
 
@@ -1358,6 +1575,11 @@ impl CraneliftBackend {
                 block_map.insert(*mir_block_id, entry_block);
             } else {
                 let cl_block = builder.create_block();
+                // Cold blocks are laid out after the hot code so the likely
+                // side of each branch falls through
+                if crate::ir::branch_hints::is_cold(mir_block) {
+                    builder.set_cold_block(cl_block);
+                }
                 block_map.insert(*mir_block_id, cl_block);
             }
         }
@@ -1427,6 +1649,7 @@ impl CraneliftBackend {
 
             // Translate phi nodes first
             // debug!("Cranelift: Block {:?} has {} phi nodes", mir_block_id, mir_block.phi_nodes.len());
+            mark(&mut builder, MirPoint::BlockEntry(mir_block_id));
             for phi_node in &mir_block.phi_nodes {
                 // eprintln!("  Phi node: dest={:?}, ty={:?}", phi_node.dest, phi_node.ty);
                 // eprintln!("  Incoming edges ({}):", phi_node.incoming.len());
@@ -1444,7 +1667,8 @@ impl CraneliftBackend {
             }
 
             // Translate instructions
-            for instruction in &mir_block.instructions {
+            for (index, instruction) in mir_block.instructions.iter().enumerate() {
+                mark(&mut builder, MirPoint::Instruction(mir_block_id, index));
                 Self::translate_instruction(
                     &mut self.value_map,
                     &mut builder,
@@ -1456,6 +1680,7 @@ impl CraneliftBackend {
                     &mut self.module,
                     &mut self.closure_environments,
                     self.current_env_param,
+                    return_slot,
                     &mut self.string_data,
                     &mut self.string_counter,
                 )?;
@@ -1463,6 +1688,7 @@ impl CraneliftBackend {
 
             // Translate terminator
             // debug!("Cranelift: MIR terminator for block {:?}: {:?}", mir_block_id, mir_block.terminator);
+            mark(&mut builder, MirPoint::Terminator(mir_block_id));
             if let Err(e) = Self::translate_terminator_static(
                 &mut self.value_map,
                 &mut builder,
@@ -1509,11 +1735,70 @@ impl CraneliftBackend {
             return Err(format!("Verifier errors in {}: {}", function.name, errors));
         }
 
+        // Keep the disassembly for crash reports and listings
+        let crash_report = rayzor_runtime::crash::is_enabled();
+        if crash_report || listing_points.is_some() {
+            self.ctx.set_disasm(true);
+        }
+
         // Define the function in the module
         self.module
             .define_function(func_id, &mut self.ctx)
             .map_err(|e| format!("Failed to define function: {}", e))?;
 
+        if rayzor_runtime::crash::has_function_table() {
+            if let Some(code) = self.ctx.compiled_code() {
+                let line = function.source_location.line;
+                let location = if line > 0 {
+                    format!("{}:{}", mir_module.source_file, line)
+                } else {
+                    mir_module.source_file.clone()
+                };
+                self.crash_functions.push((
+                    func_id,
+                    rayzor_runtime::crash::CrashFunction {
+                        name: function
+                            .qualified_name
+                            .clone()
+                            .unwrap_or_else(|| function.name.clone()),
+                        location,
+                        start: 0,
+                        size: code.code_buffer().len(),
+                        mir: if crash_report {
+                            crate::ir::dump::dump_function(function)
+                        } else {
+                            String::new()
+                        },
+                        disassembly: if crash_report {
+                            code.vcode.clone().unwrap_or_default()
+                        } else {
+                            String::new()
+                        },
+                    },
+                ));
+            }
+        }
+
+        if let (Some(points), Some(code)) = (listing_points, self.ctx.compiled_code()) {
+            let base = self.ctx.func.params.base_srcloc();
+            let ranges = code
+                .buffer
+                .get_srclocs_sorted()
+                .iter()
+                .filter_map(|range| {
+                    let loc = range.loc.expand(base);
+                    (!loc.is_default()).then(|| (range.start..range.end, loc.bits() as usize))
+                })
+                .collect();
+            self.listings.push(NativeListing {
+                function: mir_func_id,
+                code: code.code_buffer().to_vec(),
+                points,
+                ranges,
+                disassembly: code.vcode.clone().unwrap_or_default(),
+            });
+        }
+
         // Track that this function has been defined to prevent duplicate definitions
         self.defined_functions.insert(func_id);
         debug!(
@@ -1569,8 +1854,8 @@ impl CraneliftBackend {
 
             // Get the expected Cranelift type from the phi node's MIR type
             let expected_cl_type = match &phi_node.ty {
-                crate::ir::IrType::I8 => types::I8,
-                crate::ir::IrType::I16 => types::I16,
+                crate::ir::IrType::I8 | crate::ir::IrType::U8 => types::I8,
+                crate::ir::IrType::I16 | crate::ir::IrType::U16 => types::I16,
                 crate::ir::IrType::I32 => types::I32,
                 crate::ir::IrType::I64 => types::I64,
                 crate::ir::IrType::F32 => types::F32,
@@ -1590,13 +1875,14 @@ impl CraneliftBackend {
                 );
                 // Coerce the value
                 match (actual_type, expected_cl_type) {
-                    // i64 -> i32 truncation
-                    (types::I64, types::I32) => builder.ins().ireduce(types::I32, cl_value),
-                    // i32 -> i64 extension
-                    (types::I32, types::I64) => builder.ins().sextend(types::I64, cl_value),
-                    // i8 -> i32/i64
-                    (types::I8, types::I32) => builder.ins().sextend(types::I32, cl_value),
-                    (types::I8, types::I64) => builder.ins().sextend(types::I64, cl_value),
+                    // Integer width change: extend by the incoming value's MIR type, or truncate
+                    (from, to) if from.is_int() && to.is_int() => {
+                        let src_ty = function
+                            .register_types
+                            .get(incoming_value)
+                            .unwrap_or(&phi_node.ty);
+                        convert_int(builder, cl_value, src_ty, to)
+                    }
                     // Same type - no conversion needed
                     (from, to) if from == to => cl_value,
                     // Fallback: log warning and use as-is (may cause verifier error)
@@ -1633,8 +1919,8 @@ impl CraneliftBackend {
         // Get the Cranelift type for the phi node
         // For static methods, we need to use a simple type mapping
         let cl_type = match &phi_node.ty {
-            crate::ir::IrType::I8 => cranelift_codegen::ir::types::I8,
-            crate::ir::IrType::I16 => cranelift_codegen::ir::types::I16,
+            crate::ir::IrType::I8 | crate::ir::IrType::U8 => cranelift_codegen::ir::types::I8,
+            crate::ir::IrType::I16 | crate::ir::IrType::U16 => cranelift_codegen::ir::types::I16,
             crate::ir::IrType::I32 => cranelift_codegen::ir::types::I32,
             crate::ir::IrType::I64 => cranelift_codegen::ir::types::I64,
             crate::ir::IrType::F32 => cranelift_codegen::ir::types::F32,
@@ -1671,6 +1957,7 @@ impl CraneliftBackend {
         module: &mut JITModule,
         closure_environments: &mut HashMap<IrId, Value>,
         current_env_param: Option<Value>,
+        return_slot: Option<(IrId, Value)>,
         string_data: &mut HashMap<String, DataId>,
         string_counter: &mut usize,
     ) -> Result<(), String> {
@@ -1830,8 +2117,25 @@ impl CraneliftBackend {
                     .or_else(|| function.locals.get(dest).map(|local| &local.ty))
                     .ok_or_else(|| format!("Type not found for BinOp dest {:?}", dest))?;
 
-                let value =
-                    Self::lower_binary_op_static(value_map, builder, op, ty, *left, *right)?;
+                let is_float_operand = |id: &IrId| {
+                    value_map
+                        .get(id)
+                        .is_some_and(|v| builder.func.dfg.value_type(*v).is_float())
+                };
+                let is_float_rem = match op {
+                    crate::ir::BinaryOp::FRem => true,
+                    crate::ir::BinaryOp::Rem => {
+                        ty.is_float() || is_float_operand(left) || is_float_operand(right)
+                    }
+                    _ => false,
+                };
+
+                let value = match runtime_functions.get("rayzor_fmod") {
+                    Some(&fmod) if is_float_rem => Self::lower_float_rem_static(
+                        value_map, builder, module, fmod, ty, *left, *right,
+                    )?,
+                    _ => Self::lower_binary_op_static(value_map, builder, op, ty, *left, *right)?,
+                };
                 value_map.insert(*dest, value);
             }
 
@@ -1984,39 +2288,7 @@ impl CraneliftBackend {
                         id
                     } else {
                         // Declare the external runtime function dynamically
-                        let mut sig = module.make_signature();
-
-                        // Add parameters using actual types from the extern function signature
-                        // Apply C ABI integer promotion for non-Windows platforms
-                        for param in &extern_func.signature.parameters {
-                            let mut cranelift_type = Self::mir_type_to_cranelift_static(&param.ty)?;
-
-                            // For C calling convention externs on non-Windows platforms, extend i32/u32 to i64
-                            if !cfg!(target_os = "windows")
-                                && extern_func.signature.calling_convention
-                                    == crate::ir::CallingConvention::C
-                            {
-                                match param.ty {
-                                    crate::ir::IrType::I32 | crate::ir::IrType::U32 => {
-                                        debug!("!!! [DYNAMIC DECL] Extending {} param '{}' from {:?} to i64", extern_func.name, param.name, param.ty);
-                                        cranelift_type = types::I64;
-                                    }
-                                    _ => {}
-                                }
-                            }
-
-                            sig.params.push(AbiParam::new(cranelift_type));
-                        }
-
-                        // Add return type using actual type from the extern function signature
-                        if extern_func.signature.return_type != crate::ir::IrType::Void {
-                            let return_type = Self::mir_type_to_cranelift_static(
-                                &extern_func.signature.return_type,
-                            )?;
-                            if return_type != types::INVALID {
-                                sig.returns.push(AbiParam::new(return_type));
-                            }
-                        }
+                        let sig = Self::extern_signature(module, extern_func)?;
 
                         let id = module
                             .declare_function(&extern_func.name, Linkage::Import, &sig)
@@ -2103,7 +2375,7 @@ impl CraneliftBackend {
                     let intrinsic_result = match extern_func.name.as_str() {
                         // Math intrinsics: replace with native Cranelift instructions
                         "haxe_math_sqrt" | "haxe_math_abs" | "haxe_math_floor"
-                        | "haxe_math_ceil" | "haxe_math_round"
+                        | "haxe_math_ceil" | "haxe_math_round" | "haxe_math_fround"
                             if arg_values.len() == 1 =>
                         {
                             let arg = arg_values[0];
@@ -2125,7 +2397,12 @@ impl CraneliftBackend {
                                     "haxe_math_abs" => builder.ins().fabs(float_arg),
                                     "haxe_math_floor" => builder.ins().floor(float_arg),
                                     "haxe_math_ceil" => builder.ins().ceil(float_arg),
-                                    "haxe_math_round" => builder.ins().nearest(float_arg),
+                                    // Haxe rounds ties up: floor(x + 0.5), not ties-to-even
+                                    "haxe_math_round" | "haxe_math_fround" => {
+                                        let half = builder.ins().f64const(0.5);
+                                        let shifted = builder.ins().fadd(float_arg, half);
+                                        builder.ins().floor(shifted)
+                                    }
                                     _ => unreachable!(),
                                 };
                                 debug!(
@@ -2147,7 +2424,7 @@ impl CraneliftBackend {
                             let arg = arg_values[0];
                             let arg_type = builder.func.dfg.value_type(arg);
                             let result = if arg_type.is_float() {
-                                // fcvt_to_sint_sat: saturating conversion (avoids UB on overflow)
+                                // Saturates to Int32 bounds, NaN -> 0 (see IrInstruction::Cast)
                                 builder.ins().fcvt_to_sint_sat(types::I32, arg)
                             } else if arg_type == types::I64 {
                                 // Already an int, just narrow to i32
@@ -2156,6 +2433,13 @@ impl CraneliftBackend {
                                 // Already i32, return as-is
                                 arg
                             };
+                            // Std.int is declared as returning Int64 in the runtime mapping
+                            let result = if matches!(extern_func.signature.return_type, IrType::I64)
+                            {
+                                builder.ins().sextend(types::I64, result)
+                            } else {
+                                result
+                            };
                             debug!("Std intrinsic: haxe_std_int → {:?} to i32", arg_type);
                             Some(result)
                         }
@@ -2253,12 +2537,15 @@ impl CraneliftBackend {
                     let is_extern_func = called_func.cfg.blocks.is_empty();
                     let uses_sret = called_func.signature.uses_sret && !is_extern_func;
 
-                    // Allocate stack space for sret if needed
-                    let sret_slot = if uses_sret {
-                        let ret_ty = &called_func.signature.return_type;
-                        Some(Self::lower_alloca_static(builder, ret_ty, None)?)
-                    } else {
-                        None
+                    // Allocate stack space for sret if needed. A result we
+                    // return ourselves goes straight into our caller's slot.
+                    let sret_slot = match return_slot {
+                        _ if !uses_sret => None,
+                        Some((reg, slot)) if *dest == Some(reg) => Some(slot),
+                        _ => {
+                            let ret_ty = &called_func.signature.return_type;
+                            Some(Self::lower_alloca_static(builder, ret_ty, None)?)
+                        }
                     };
 
                     // Translate arguments (prepend sret pointer if needed)
@@ -2405,11 +2692,11 @@ impl CraneliftBackend {
                         let decl = module.declarations().get_function_decl(cl_func_id);
                         let expected_params = decl.signature.params.len();
                         if expected_params != call_args.len() {
-                            eprintln!("[CALL MISMATCH] In '{}': calling '{}' (MIR {:?}, CL {:?}): expected {} params, providing {} args, is_extern={}, env_added={}, sret={}",
+                            warn!("[CALL MISMATCH] In '{}': calling '{}' (MIR {:?}, CL {:?}): expected {} params, providing {} args, is_extern={}, env_added={}, sret={}",
                                 function.name, called_func.name, func_id, cl_func_id,
                                 expected_params, call_args.len(), is_extern_func, should_add_env, uses_sret);
                             for (pi, p) in called_func.signature.parameters.iter().enumerate() {
-                                eprintln!("  MIR param[{}] '{}': {:?}", pi, p.name, p.ty);
+                                debug!("  MIR param[{}] '{}': {:?}", pi, p.name, p.ty);
                             }
                             true
                         } else {
@@ -2804,32 +3091,34 @@ impl CraneliftBackend {
                     // Same type - just copy (no conversion needed)
                     (from, to) if from == to => src_val,
 
-                    // Int to Float conversions
-                    (types::I32, types::F64) => builder.ins().fcvt_from_sint(types::F64, src_val),
-                    (types::I64, types::F64) => builder.ins().fcvt_from_sint(types::F64, src_val),
-                    (types::I32, types::F32) => builder.ins().fcvt_from_sint(types::F32, src_val),
-                    (types::I64, types::F32) => builder.ins().fcvt_from_sint(types::F32, src_val),
+                    // Int to Int: extend by the signedness of from_ty, or truncate
+                    (from, to) if from.is_int() && to.is_int() => {
+                        convert_int(builder, src_val, from_ty, to)
+                    }
+
+                    // Int to Float conversions (small ints are widened first)
+                    (from, to) if from.is_int() && to.is_float() => {
+                        let wide = if from.bits() < 32 {
+                            convert_int(builder, src_val, from_ty, types::I32)
+                        } else {
+                            src_val
+                        };
+                        if matches!(from_ty, IrType::U32 | IrType::U64) {
+                            builder.ins().fcvt_from_uint(to, wide)
+                        } else {
+                            builder.ins().fcvt_from_sint(to, wide)
+                        }
+                    }
 
-                    // Float to Int conversions
-                    (types::F64, types::I32) => builder.ins().fcvt_to_sint(types::I32, src_val),
-                    (types::F64, types::I64) => builder.ins().fcvt_to_sint(types::I64, src_val),
-                    (types::F32, types::I32) => builder.ins().fcvt_to_sint(types::I32, src_val),
-                    (types::F32, types::I64) => builder.ins().fcvt_to_sint(types::I64, src_val),
+                    // Float to Int conversions: truncate, NaN -> 0, saturate
+                    (from, to) if from.is_float() && to.is_int() => {
+                        float_to_int_sat(builder, src_val, to_ty, to)
+                    }
 
                     // Float to Float conversions
                     (types::F32, types::F64) => builder.ins().fpromote(types::F64, src_val),
                     (types::F64, types::F32) => builder.ins().fdemote(types::F32, src_val),
 
-                    // Int to Int conversions (sign extension or truncation)
-                    (types::I32, types::I64) => builder.ins().sextend(types::I64, src_val),
-                    (types::I64, types::I32) => builder.ins().ireduce(types::I32, src_val),
-
-                    // Bool (I8) to integer conversions (zero-extend for raw value storage)
-                    (types::I8, types::I32) => builder.ins().uextend(types::I32, src_val),
-                    (types::I8, types::I64) => builder.ins().uextend(types::I64, src_val),
-                    (types::I32, types::I8) => builder.ins().ireduce(types::I8, src_val),
-                    (types::I64, types::I8) => builder.ins().ireduce(types::I8, src_val),
-
                     _ => {
                         return Err(format!(
                             "Unsupported cast from {:?} ({:?}) to {:?}",
@@ -2868,9 +3157,11 @@ impl CraneliftBackend {
                         builder.ins().bitcast(types::F32, MemFlags::new(), src_val)
                     }
 
-                    // Int width conversions (sign-extend or truncate)
-                    (types::I32, types::I64) => builder.ins().sextend(types::I64, src_val),
-                    (types::I64, types::I32) => builder.ins().ireduce(types::I32, src_val),
+                    // Int width conversions (extend by the source's MIR type, or truncate)
+                    (from, to) if from.is_int() && to.is_int() && from != to => {
+                        let src_mir_ty = function.register_types.get(src).unwrap_or(&IrType::Any);
+                        convert_int(builder, src_val, src_mir_ty, to)
+                    }
 
                     // Same type - just copy
                     (from, to) if from == to => src_val,
@@ -3111,14 +3402,19 @@ impl CraneliftBackend {
                     _ => return Err(format!("CreateStruct with non-struct type: {:?}", ty)),
                 };
 
-                // Create stack slot for struct
-                let struct_slot = builder.create_sized_stack_slot(StackSlotData::new(
-                    StackSlotKind::ExplicitSlot,
-                    struct_size as u32,
-                    8, // 8-byte alignment
-                ));
-
-                let slot_addr = builder.ins().stack_addr(types::I64, struct_slot, 0);
+                // Build the returned struct in the caller's slot, anything
+                // else in a stack slot of its own
+                let slot_addr = match return_slot {
+                    Some((reg, sret)) if reg == *dest => sret,
+                    _ => {
+                        let struct_slot = builder.create_sized_stack_slot(StackSlotData::new(
+                            StackSlotKind::ExplicitSlot,
+                            struct_size as u32,
+                            8, // 8-byte alignment
+                        ));
+                        builder.ins().stack_addr(types::I64, struct_slot, 0)
+                    }
+                };
 
                 // Store each field at its offset
                 if let IrType::Struct {
@@ -3238,6 +3534,40 @@ impl CraneliftBackend {
                 }
             }
 
+            IrInstruction::MemCopy { dest, src, size } => {
+                let dest_val = *value_map
+                    .get(dest)
+                    .ok_or_else(|| format!("MemCopy dest {:?} not found", dest))?;
+                let src_val = *value_map
+                    .get(src)
+                    .ok_or_else(|| format!("MemCopy src {:?} not found", src))?;
+                let size_val = *value_map
+                    .get(size)
+                    .ok_or_else(|| format!("MemCopy size {:?} not found", size))?;
+                // Lowered to a libc memcpy call
+                builder.call_memcpy(module.target_config(), dest_val, src_val, size_val);
+            }
+
+            IrInstruction::MemSet { dest, value, size } => {
+                let dest_val = *value_map
+                    .get(dest)
+                    .ok_or_else(|| format!("MemSet dest {:?} not found", dest))?;
+                let value_val = *value_map
+                    .get(value)
+                    .ok_or_else(|| format!("MemSet value {:?} not found", value))?;
+                let size_val = *value_map
+                    .get(size)
+                    .ok_or_else(|| format!("MemSet size {:?} not found", size))?;
+                // memset takes the fill byte widened to a C int; narrow wider
+                // values to their low byte first
+                let byte_val = if builder.func.dfg.value_type(value_val).bits() > 8 {
+                    builder.ins().ireduce(types::I8, value_val)
+                } else {
+                    value_val
+                };
+                builder.call_memset(module.target_config(), dest_val, byte_val, size_val);
+            }
+
             // === SIMD Vector Operations ===
             IrInstruction::VectorLoad { dest, ptr, vec_ty } => {
                 let ptr_val = *value_map
@@ -3593,8 +3923,11 @@ impl CraneliftBackend {
                         };
 
                         // Copy struct from source (val is a pointer to stack) to sret destination
-                        // We need to do a memcpy-style copy of each field
-                        if let IrType::Struct { fields, .. } = struct_ty {
+                        // We need to do a memcpy-style copy of each field, unless
+                        // the value was already built in place (ReturnSlotPass)
+                        if val == sret {
+                            // Nothing to copy
+                        } else if let IrType::Struct { fields, .. } = struct_ty {
                             let mut offset = 0;
                             for field in fields {
                                 let field_ty =
@@ -3625,9 +3958,9 @@ impl CraneliftBackend {
                     if let Some(val_id) = value {
                         // debug!("Cranelift: Looking up return value {:?} in value_map", val_id);
                         let val = *value_map.get(val_id).ok_or_else(|| {
-                            eprintln!("ERROR: Return value {:?} NOT FOUND in value_map!", val_id);
-                            eprintln!(
-                                "ERROR: Available values: {:?}",
+                            debug!(
+                                "Return value {:?} not found in value_map; available: {:?}",
+                                val_id,
                                 value_map.keys().collect::<Vec<_>>()
                             );
                             format!("Return value {:?} not found", val_id)
@@ -3732,7 +4065,32 @@ impl CraneliftBackend {
                     .trap(cranelift_codegen::ir::TrapCode::unwrap_user(100));
             }
 
-            // TODO: Implement Switch and NoReturn
+            IrTerminator::Switch {
+                value,
+                cases,
+                default,
+            } => {
+                let current_block_id = function
+                    .cfg
+                    .blocks
+                    .iter()
+                    .find(|(_, block)| std::ptr::eq(&block.terminator, terminator))
+                    .map(|(id, _)| *id)
+                    .ok_or_else(|| "Cannot find current block".to_string())?;
+
+                Self::translate_switch_static(
+                    value_map,
+                    builder,
+                    block_map,
+                    function,
+                    current_block_id,
+                    *value,
+                    cases,
+                    *default,
+                )?;
+            }
+
+            // TODO: Implement NoReturn
             _ => {
                 return Err(format!("Unsupported terminator: {:?}", terminator));
             }
@@ -3741,6 +4099,135 @@ impl CraneliftBackend {
         Ok(())
     }
 
+    /// Translate a Switch terminator: a `br_table` jump table when the case
+    /// values are dense (see `jump_table_bounds`), otherwise a binary search
+    /// over the case values (`cranelift_frontend::Switch`)
+    fn translate_switch_static(
+        value_map: &HashMap<IrId, Value>,
+        builder: &mut FunctionBuilder,
+        block_map: &HashMap<IrBlockId, Block>,
+        function: &IrFunction,
+        current_block_id: IrBlockId,
+        value: IrId,
+        cases: &[(i64, IrBlockId)],
+        default: IrBlockId,
+    ) -> Result<(), String> {
+        use cranelift_codegen::ir::{BlockCall, JumpTableData};
+
+        let mut scrutinee = *value_map
+            .get(&value)
+            .ok_or_else(|| format!("Switch value {:?} not found", value))?;
+        let value_ty = builder.func.dfg.value_type(scrutinee);
+        if value_ty.is_float() {
+            scrutinee = builder.ins().fcvt_to_sint_sat(types::I64, scrutinee);
+        } else if value_ty.bits() < 32 {
+            let mir_ty = function.register_types.get(&value).unwrap_or(&IrType::I32);
+            scrutinee = convert_int(builder, scrutinee, mir_ty, types::I32);
+        }
+        let ty = builder.func.dfg.value_type(scrutinee);
+
+        // Case values a 32-bit scrutinee cannot hold never match
+        let cases: Vec<(i64, IrBlockId)> = cases
+            .iter()
+            .filter(|(case, _)| ty == types::I64 || i32::try_from(*case).is_ok())
+            .copied()
+            .collect();
+
+        // Switch edges cannot carry block arguments, so targets with phi
+        // nodes are reached through an edge block that passes them
+        let mut edges: HashMap<IrBlockId, Block> = HashMap::new();
+        let mut edge_jumps = Vec::new();
+        for target in cases
+            .iter()
+            .map(|(_, t)| *t)
+            .chain(std::iter::once(default))
+        {
+            if edges.contains_key(&target) {
+                continue;
+            }
+            let cl_block = *block_map
+                .get(&target)
+                .ok_or_else(|| format!("Switch target {:?} not found", target))?;
+            let phi_args = Self::collect_phi_args_with_coercion(
+                value_map,
+                function,
+                target,
+                current_block_id,
+                builder,
+            )?;
+            if phi_args.is_empty() {
+                edges.insert(target, cl_block);
+            } else {
+                let edge = builder.create_block();
+                edges.insert(target, edge);
+                edge_jumps.push((edge, cl_block, phi_args));
+            }
+        }
+        let default_edge = edges[&default];
+
+        let values: Vec<i64> = cases.iter().map(|(case, _)| *case).collect();
+        if let Some((min, len)) = jump_table_bounds(&values) {
+            let mut table = vec![None; len];
+            for (case, target) in &cases {
+                let slot = &mut table[case.wrapping_sub(min) as usize];
+                // The first case listing a value wins
+                if slot.is_none() {
+                    *slot = Some(edges[target]);
+                }
+            }
+
+            let mut index = builder.ins().iadd_imm(scrutinee, min.wrapping_neg());
+            if ty == types::I64 {
+                // br_table takes an i32 index: send out-of-range values to
+                // the default before narrowing
+                let in_range = builder
+                    .ins()
+                    .icmp_imm(IntCC::UnsignedLessThan, index, len as i64);
+                let table_block = builder.create_block();
+                builder
+                    .ins()
+                    .brif(in_range, table_block, &[], default_edge, &[]);
+                builder.seal_block(table_block);
+                builder.switch_to_block(table_block);
+                index = builder.ins().ireduce(types::I32, index);
+            }
+
+            let default_call = builder.func.dfg.block_call(default_edge, &[]);
+            let table: Vec<BlockCall> = table
+                .into_iter()
+                .map(|slot| {
+                    builder
+                        .func
+                        .dfg
+                        .block_call(slot.unwrap_or(default_edge), &[])
+                })
+                .collect();
+            let jump_table = builder.create_jump_table(JumpTableData::new(default_call, &table));
+            builder.ins().br_table(index, jump_table);
+        } else {
+            let mut switch = cranelift_frontend::Switch::new();
+            let mut seen = HashSet::new();
+            for (case, target) in &cases {
+                if seen.insert(*case) {
+                    let entry = if ty == types::I64 {
+                        *case as u64 as u128
+                    } else {
+                        *case as u32 as u128
+                    };
+                    switch.set_entry(entry, edges[target]);
+                }
+            }
+            switch.emit(builder, scrutinee, default_edge);
+        }
+
+        for (edge, target, phi_args) in edge_jumps {
+            builder.switch_to_block(edge);
+            builder.seal_block(edge);
+            builder.ins().jump(target, &phi_args);
+        }
+        Ok(())
+    }
+
     /// Translate a constant value to Cranelift IR (static method)
     fn translate_const_value(
         builder: &mut FunctionBuilder,
@@ -3843,6 +4330,52 @@ impl CraneliftBackend {
         Ok(cl_value)
     }
 
+    /// Cranelift signature for a MIR extern function.
+    ///
+    /// Uses the actual types from the extern function signature and applies C ABI
+    /// integer promotion on non-Windows platforms.
+    fn extern_signature(
+        module: &JITModule,
+        extern_func: &crate::ir::modules::IrExternFunction,
+    ) -> Result<Signature, String> {
+        let mut sig = module.make_signature();
+
+        // Add parameters using actual types from the extern function signature
+        // Apply C ABI integer promotion for non-Windows platforms
+        for param in &extern_func.signature.parameters {
+            let mut cranelift_type = Self::mir_type_to_cranelift_static(&param.ty)?;
+
+            // For C calling convention externs on non-Windows platforms, extend i32/u32 to i64
+            if !cfg!(target_os = "windows")
+                && extern_func.signature.calling_convention == crate::ir::CallingConvention::C
+            {
+                match param.ty {
+                    crate::ir::IrType::I32 | crate::ir::IrType::U32 => {
+                        debug!(
+                            "!!! [DYNAMIC DECL] Extending {} param '{}' from {:?} to i64",
+                            extern_func.name, param.name, param.ty
+                        );
+                        cranelift_type = types::I64;
+                    }
+                    _ => {}
+                }
+            }
+
+            sig.params.push(AbiParam::new(cranelift_type));
+        }
+
+        // Add return type using actual type from the extern function signature
+        if extern_func.signature.return_type != crate::ir::IrType::Void {
+            let return_type =
+                Self::mir_type_to_cranelift_static(&extern_func.signature.return_type)?;
+            if return_type != types::INVALID {
+                sig.returns.push(AbiParam::new(return_type));
+            }
+        }
+
+        Ok(sig)
+    }
+
     /// Convert MIR type to Cranelift type (static version for use without self)
     pub(super) fn mir_type_to_cranelift_static(ty: &IrType) -> Result<Type, String> {
         match ty {
@@ -3928,9 +4461,65 @@ impl CraneliftBackend {
     ) -> Result<(), String> {
         Self::register_enum_rtti_from_modules(modules);
         Self::register_class_rtti_from_modules(modules);
+        Self::register_meta_from_modules(modules);
         Ok(())
     }
 
+    /// Register user metadata (haxe.rtti.Meta) of classes and enums.
+    pub fn register_meta_from_modules(modules: &[std::sync::Arc<crate::ir::IrModule>]) {
+        for module in modules {
+            for (_id, typedef) in &module.types {
+                if !typedef.metadata.is_empty() {
+                    rayzor_runtime::meta::register_meta_from_mir(
+                        typedef.type_id.0,
+                        Self::ir_metadata_to_runtime(&typedef.metadata),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Map MIR type metadata to the runtime's `haxe.rtti.Meta` table entry.
+    pub fn ir_metadata_to_runtime(
+        metadata: &crate::ir::IrTypeMetadata,
+    ) -> rayzor_runtime::meta::TypeMeta {
+        use crate::ir::{IrMetaValue, IrMetadata};
+        use rayzor_runtime::meta::{MetaEntries, MetaValue, TypeMeta};
+
+        fn value(v: &IrMetaValue) -> MetaValue {
+            match v {
+                IrMetaValue::Null => MetaValue::Null,
+                IrMetaValue::Bool(b) => MetaValue::Bool(*b),
+                IrMetaValue::Int(i) => MetaValue::Int(*i),
+                IrMetaValue::Float(f) => MetaValue::Float(*f),
+                IrMetaValue::String(s) => MetaValue::String(s.clone()),
+                IrMetaValue::Array(items) => MetaValue::Array(items.iter().map(value).collect()),
+                IrMetaValue::Object(fields) => MetaValue::Object(
+                    fields
+                        .iter()
+                        .map(|(name, v)| (name.clone(), value(v)))
+                        .collect(),
+                ),
+            }
+        }
+        fn entries(meta: &[IrMetadata]) -> MetaEntries {
+            meta.iter()
+                .map(|m| (m.name.clone(), m.args.iter().map(value).collect()))
+                .collect()
+        }
+        let members = |list: &[(String, Vec<IrMetadata>)]| -> Vec<(String, MetaEntries)> {
+            list.iter()
+                .map(|(name, meta)| (name.clone(), entries(meta)))
+                .collect()
+        };
+
+        TypeMeta {
+            obj: entries(&metadata.type_meta),
+            fields: members(&metadata.fields),
+            statics: members(&metadata.statics),
+        }
+    }
+
     /// Register class RTTI by walking MIR module type definitions directly.
     pub fn register_class_rtti_from_modules(modules: &[std::sync::Arc<crate::ir::IrModule>]) {
         use crate::ir::modules::IrTypeDefinition;
@@ -3943,11 +4532,14 @@ impl CraneliftBackend {
                         fields.iter().map(|f| f.name.clone()).collect();
                     let static_fields: Vec<String> = Vec::new();
                     let super_type_id = typedef.super_type_id.map(|t| t.0);
+                    let interfaces: Vec<u32> =
+                        typedef.interface_type_ids.iter().map(|t| t.0).collect();
 
                     register_class_from_mir(
                         typedef.type_id.0,
                         &typedef.name,
                         super_type_id,
+                        &interfaces,
                         &instance_fields,
                         &static_fields,
                     );
@@ -3965,7 +4557,7 @@ impl CraneliftBackend {
         for module in modules {
             for (_id, typedef) in &module.types {
                 if let IrTypeDefinition::Enum { variants, .. } = &typedef.definition {
-                    let variant_data: Vec<(String, usize, Vec<ParamType>)> = variants
+                    let variant_data: Vec<(String, usize, Vec<ParamType>, Vec<u32>)> = variants
                         .iter()
                         .map(|v| {
                             let param_types: Vec<ParamType> = v
@@ -3973,7 +4565,17 @@ impl CraneliftBackend {
                                 .iter()
                                 .map(|f| Self::ir_type_to_param_type(&f.ty))
                                 .collect();
-                            (v.name.clone(), v.fields.len(), param_types)
+                            let param_enum_types: Vec<u32> = v
+                                .field_enum_types
+                                .iter()
+                                .map(|t| t.map_or(0, |t| t.0))
+                                .collect();
+                            (
+                                v.name.clone(),
+                                v.fields.len(),
+                                param_types,
+                                param_enum_types,
+                            )
                         })
                         .collect();
 
@@ -4107,13 +4709,641 @@ impl CraneliftBackend {
     }
 }
 
+/// Fewest distinct case values worth a jump table; below this a couple of
+/// compares is as fast
+const MIN_JUMP_TABLE_CASES: usize = 4;
+
+/// Largest jump table emitted, in entries
+const MAX_JUMP_TABLE_LEN: usize = 4096;
+
+/// `(min, len)` of a jump table covering `values`, if they are dense
+/// enough for one: at least `MIN_JUMP_TABLE_CASES` distinct values filling
+/// at least half of the range they span. Other switches get a binary search.
+fn jump_table_bounds(values: &[i64]) -> Option<(i64, usize)> {
+    let distinct: std::collections::BTreeSet<i64> = values.iter().copied().collect();
+    if distinct.len() < MIN_JUMP_TABLE_CASES {
+        return None;
+    }
+    let (min, max) = (*distinct.first()?, *distinct.last()?);
+    let len = usize::try_from(max.abs_diff(min)).ok()?.checked_add(1)?;
+    if len > MAX_JUMP_TABLE_LEN || len > distinct.len() * 2 {
+        return None;
+    }
+    Some((min, len))
+}
+
+/// Whether the dynamic linker finds `name` in the running process, which is
+/// where the JIT looks up symbols nobody registered explicitly.
+#[cfg(unix)]
+fn resolves_in_process(name: &str) -> bool {
+    let Ok(name) = std::ffi::CString::new(name) else {
+        return false;
+    };
+    unsafe { !libc::dlsym(libc::RTLD_DEFAULT, name.as_ptr()).is_null() }
+}
+
+#[cfg(not(unix))]
+fn resolves_in_process(_name: &str) -> bool {
+    // Leave resolution (and link errors) to the JIT
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::codegen::mir_interpreter::{InterpValue, MirInterpreter};
+    use crate::ir::mir_builder::MirBuilder;
+    use crate::ir::{BinaryOp, CallingConvention, UnaryOp};
+
     #[test]
     fn test_cranelift_backend_creation() {
         let backend = CraneliftBackend::new().unwrap();
         assert!(backend.function_map.is_empty());
     }
+
+    const SMALL_INTS: [IrType; 4] = [IrType::I8, IrType::U8, IrType::I16, IrType::U16];
+
+    /// Inputs near the 8/16-bit boundaries (truncated to the operand type)
+    const SMALL_INT_INPUTS: [i32; 12] = [0, 1, 2, 7, -1, -7, 127, -128, 255, 32767, -32768, 65535];
+
+    /// `fn(a: i32, b: i32) -> i32`, C convention so no closure env param
+    fn begin_i32_fn(builder: &mut MirBuilder, params: usize) -> (IrFunctionId, Vec<IrId>) {
+        let mut func = builder.begin_function("f");
+        for i in 0..params {
+            func = func.param(format!("p{}", i), IrType::I32);
+        }
+        let func_id = func
+            .returns(IrType::I32)
+            .calling_convention(CallingConvention::C)
+            .build();
+        builder.set_current_function(func_id);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let params = (0..params).map(|i| builder.get_param(i)).collect();
+        (func_id, params)
+    }
+
+    /// `(a as ty) op (b as ty)`, extended back to i32 by the signedness of `ty`
+    fn binop_module(op: BinaryOp, ty: &IrType) -> (IrModule, IrFunctionId) {
+        let mut builder = MirBuilder::new("SmallInt");
+        let (func_id, params) = begin_i32_fn(&mut builder, 2);
+        let a = builder.cast(params[0], IrType::I32, ty.clone());
+        let b = builder.cast(params[1], IrType::I32, ty.clone());
+        let result = builder.bin_op(op, a, b);
+        let widened = builder.cast(result, ty.clone(), IrType::I32);
+        builder.ret(Some(widened));
+        (builder.finish(), func_id)
+    }
+
+    /// `-(a as ty)` and `a as from as to`, extended back to i32
+    fn unary_module(op: Option<UnaryOp>, from: &IrType, to: &IrType) -> (IrModule, IrFunctionId) {
+        let mut builder = MirBuilder::new("SmallInt");
+        let (func_id, params) = begin_i32_fn(&mut builder, 1);
+        let value = builder.cast(params[0], IrType::I32, from.clone());
+        let value = match op {
+            Some(op) => builder.un_op(op, value),
+            None => builder.cast(value, from.clone(), to.clone()),
+        };
+        let widened = builder.cast(value, to.clone(), IrType::I32);
+        builder.ret(Some(widened));
+        (builder.finish(), func_id)
+    }
+
+    /// A test function compiled with Cranelift, runnable on both engines
+    struct Conformance {
+        module: IrModule,
+        func_id: IrFunctionId,
+        interp: MirInterpreter,
+        // Owns the JIT memory `native` points into
+        _backend: CraneliftBackend,
+        native: *const u8,
+    }
+
+    impl Conformance {
+        fn new((module, func_id): (IrModule, IrFunctionId)) -> Self {
+            let mut backend = CraneliftBackend::new().unwrap();
+            backend.compile_module(&module).unwrap();
+            let native = backend.get_function_ptr(func_id).unwrap();
+            Self {
+                module,
+                func_id,
+                interp: MirInterpreter::new(),
+                _backend: backend,
+                native,
+            }
+        }
+
+        /// (interpreter result, native result)
+        fn run(&mut self, args: &[i32]) -> (i32, i32) {
+            let interp_args = args.iter().map(|&a| InterpValue::I32(a)).collect();
+            let interpreted = match self.interp.execute(&self.module, self.func_id, interp_args) {
+                Ok(InterpValue::I32(n)) => n,
+                other => panic!("expected i32 from interpreter, got {:?}", other),
+            };
+            let native = unsafe {
+                match args {
+                    [a] => {
+                        std::mem::transmute::<*const u8, extern "C" fn(i32) -> i32>(self.native)(*a)
+                    }
+                    [a, b] => std::mem::transmute::<*const u8, extern "C" fn(i32, i32) -> i32>(
+                        self.native,
+                    )(*a, *b),
+                    _ => unreachable!(),
+                }
+            };
+            (interpreted, native)
+        }
+    }
+
+    fn truncate(value: i32, ty: &IrType) -> i32 {
+        match ty {
+            IrType::I8 => value as i8 as i32,
+            IrType::U8 => value as u8 as i32,
+            IrType::I16 => value as i16 as i32,
+            IrType::U16 => value as u16 as i32,
+            _ => value,
+        }
+    }
+
+    #[test]
+    fn test_small_int_binops_match_interpreter() {
+        let ops = [
+            BinaryOp::Add,
+            BinaryOp::Sub,
+            BinaryOp::Mul,
+            BinaryOp::Div,
+            BinaryOp::Rem,
+            BinaryOp::And,
+            BinaryOp::Or,
+            BinaryOp::Xor,
+            BinaryOp::Shl,
+            BinaryOp::Shr,
+        ];
+        for ty in &SMALL_INTS {
+            for op in ops {
+                let mut f = Conformance::new(binop_module(op, ty));
+                for a in SMALL_INT_INPUTS {
+                    for b in SMALL_INT_INPUTS {
+                        let divides = matches!(op, BinaryOp::Div | BinaryOp::Rem);
+                        if divides && truncate(b, ty) == 0 {
+                            continue;
+                        }
+                        // Shift amounts beyond the promoted width are masked differently
+                        if matches!(op, BinaryOp::Shl | BinaryOp::Shr) && !(0..8).contains(&b) {
+                            continue;
+                        }
+                        let (interpreted, native) = f.run(&[a, b]);
+                        assert_eq!(
+                            interpreted, native,
+                            "{:?} {:?}: {} op {} (interpreter vs cranelift)",
+                            ty, op, a, b
+                        );
+                        assert_eq!(
+                            native,
+                            truncate(native, ty),
+                            "{:?} {:?} not wrapped",
+                            ty,
+                            op
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_small_int_wrapping() {
+        let cases = [
+            (BinaryOp::Add, IrType::I8, 127, 1, -128),
+            (BinaryOp::Add, IrType::U8, 255, 1, 0),
+            (BinaryOp::Sub, IrType::U8, 0, 1, 255),
+            (BinaryOp::Sub, IrType::I16, -32768, 1, 32767),
+            (BinaryOp::Mul, IrType::U16, 65535, 2, 65534),
+            (BinaryOp::Div, IrType::I8, -128, -1, -128),
+            (BinaryOp::Shr, IrType::I8, -128, 1, -64),
+            (BinaryOp::Shr, IrType::U8, 128, 1, 64),
+            (BinaryOp::Shl, IrType::I8, 64, 1, -128),
+        ];
+        for (op, ty, a, b, expected) in cases {
+            let mut f = Conformance::new(binop_module(op, &ty));
+            assert_eq!(
+                f.run(&[a, b]),
+                (expected, expected),
+                "{:?} {:?} {} {}",
+                ty,
+                op,
+                a,
+                b
+            );
+        }
+    }
+
+    #[test]
+    fn test_small_int_casts_match_interpreter() {
+        let targets = [
+            IrType::I8,
+            IrType::U8,
+            IrType::I16,
+            IrType::U16,
+            IrType::I32,
+            IrType::I64,
+        ];
+        for from in &SMALL_INTS {
+            for to in &targets {
+                let mut f = Conformance::new(unary_module(None, from, to));
+                for a in SMALL_INT_INPUTS {
+                    let (interpreted, native) = f.run(&[a]);
+                    assert_eq!(interpreted, native, "cast {} as {:?} as {:?}", a, from, to);
+                    assert_eq!(native, truncate(truncate(a, from), to));
+                }
+            }
+
+            let mut f = Conformance::new(unary_module(Some(UnaryOp::Neg), from, from));
+            for a in SMALL_INT_INPUTS {
+                let (interpreted, native) = f.run(&[a]);
+                assert_eq!(interpreted, native, "-({} as {:?})", a, from);
+                assert_eq!(native, truncate(-truncate(a, from), from));
+            }
+        }
+    }
+
+    /// Float edge cases, run on the interpreter and on Cranelift at every
+    /// optimization level, all linked against the runtime
+    struct Tiers {
+        module: IrModule,
+        func_id: IrFunctionId,
+        interp: MirInterpreter,
+        // Backends own the JIT memory their function pointers point into
+        natives: Vec<(&'static str, CraneliftBackend, *const u8)>,
+    }
+
+    impl Tiers {
+        fn new((module, func_id): (IrModule, IrFunctionId)) -> Self {
+            use rayzor_plugin::RuntimePlugin;
+
+            let symbols = rayzor_runtime::plugin_impl::get_plugin().runtime_symbols();
+            let mut interp = MirInterpreter::new();
+            for (name, ptr) in &symbols {
+                interp.register_symbol(name, *ptr);
+            }
+            let natives = ["none", "speed", "speed_and_size"]
+                .into_iter()
+                .map(|opt| {
+                    let mut backend =
+                        CraneliftBackend::with_symbols_and_opt(opt, &symbols).unwrap();
+                    backend.compile_module(&module).unwrap();
+                    let native = backend.get_function_ptr(func_id).unwrap();
+                    (opt, backend, native)
+                })
+                .collect();
+            Self {
+                module,
+                func_id,
+                interp,
+                natives,
+            }
+        }
+
+        /// Result of each tier, labelled: `fn(f64) -> i32`, `fn(f64) -> f64`
+        /// or `fn(f64, f64) -> f64`
+        fn run(&mut self, args: &[f64]) -> Vec<(&'static str, InterpValue)> {
+            let interp_args = args.iter().map(|&a| InterpValue::F64(a)).collect();
+            let interpreted = self
+                .interp
+                .execute(&self.module, self.func_id, interp_args)
+                .unwrap();
+            let mut results = vec![("interpreter", interpreted)];
+
+            let returns_int =
+                self.module.functions[&self.func_id].signature.return_type == IrType::I32;
+            for (opt, _, native) in &self.natives {
+                let value = unsafe {
+                    match (args, returns_int) {
+                        ([a], true) => InterpValue::I32(std::mem::transmute::<
+                            *const u8,
+                            extern "C" fn(f64) -> i32,
+                        >(*native)(*a)),
+                        ([a], false) => InterpValue::F64(std::mem::transmute::<
+                            *const u8,
+                            extern "C" fn(f64) -> f64,
+                        >(*native)(*a)),
+                        ([a, b], false) => {
+                            InterpValue::F64(std::mem::transmute::<
+                                *const u8,
+                                extern "C" fn(f64, f64) -> f64,
+                            >(*native)(*a, *b))
+                        }
+                        _ => unreachable!(),
+                    }
+                };
+                results.push((opt, value));
+            }
+            results
+        }
+
+        /// Assert every tier produces `expected` (NaN matches any NaN)
+        fn check(&mut self, args: &[f64], expected: InterpValue, what: &str) {
+            for (tier, value) in self.run(args) {
+                let same = match (&value, &expected) {
+                    (InterpValue::I32(a), InterpValue::I32(b)) => a == b,
+                    (InterpValue::F64(a), InterpValue::F64(b)) => {
+                        (a.is_nan() && b.is_nan()) || a.to_bits() == b.to_bits()
+                    }
+                    _ => false,
+                };
+                assert!(
+                    same,
+                    "{} {:?} on {}: got {:?}, expected {:?}",
+                    what, args, tier, value, expected
+                );
+            }
+        }
+    }
+
+    /// `fn(x: f64) -> i32` computing `x as ty as i32`
+    fn float_cast_module(ty: &IrType) -> (IrModule, IrFunctionId) {
+        let mut builder = MirBuilder::new("FloatEdge");
+        let func_id = builder
+            .begin_function("f")
+            .param("x", IrType::F64)
+            .returns(IrType::I32)
+            .calling_convention(CallingConvention::C)
+            .build();
+        builder.set_current_function(func_id);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let x = builder.get_param(0);
+        let value = builder.cast(x, IrType::F64, ty.clone());
+        let value = builder.cast(value, ty.clone(), IrType::I32);
+        builder.ret(Some(value));
+        (builder.finish(), func_id)
+    }
+
+    /// `fn(a: f64, b: f64) -> f64` computing `a % b`
+    fn float_rem_module() -> (IrModule, IrFunctionId) {
+        let mut builder = MirBuilder::new("FloatEdge");
+        let func_id = builder
+            .begin_function("f")
+            .param("a", IrType::F64)
+            .param("b", IrType::F64)
+            .returns(IrType::F64)
+            .calling_convention(CallingConvention::C)
+            .build();
+        builder.set_current_function(func_id);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let (a, b) = (builder.get_param(0), builder.get_param(1));
+        let value = builder.bin_op(BinaryOp::FRem, a, b);
+        builder.ret(Some(value));
+        (builder.finish(), func_id)
+    }
+
+    /// `fn(x: f64) -> ret` calling the runtime function `name`, declared
+    /// as a module extern the way stdlib calls are
+    fn runtime_call_module(name: &str, ret: IrType) -> (IrModule, IrFunctionId) {
+        let mut builder = MirBuilder::new("FloatEdge");
+        let callee = builder
+            .begin_function(name)
+            .param("x", IrType::F64)
+            .returns(ret.clone())
+            .calling_convention(CallingConvention::C)
+            .build();
+        builder.mark_as_extern(callee);
+        let func_id = builder
+            .begin_function("f")
+            .param("x", IrType::F64)
+            .returns(ret.clone())
+            .calling_convention(CallingConvention::C)
+            .build();
+        builder.set_current_function(func_id);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let x = builder.get_param(0);
+        let value = builder.call(callee, vec![x]).unwrap();
+        builder.ret(Some(value));
+
+        let mut module = builder.finish();
+        let callee_fn = module.functions.remove(&callee).unwrap();
+        module.add_extern_function(crate::ir::modules::IrExternFunction {
+            id: callee,
+            name: callee_fn.name,
+            symbol_id: crate::tast::SymbolId::from_raw(0),
+            signature: callee_fn.signature,
+            source: "runtime".to_string(),
+        });
+        (module, func_id)
+    }
+
+    const FLOAT_INPUTS: [f64; 12] = [
+        f64::NAN,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        3e9,
+        -3e9,
+        -0.0,
+        2.7,
+        -2.7,
+        200.5,
+        -200.5,
+        70000.0,
+        -1e300,
+    ];
+
+    #[test]
+    fn test_float_to_int_saturates() {
+        // Truncate toward zero, NaN -> 0, saturate to the target's bounds
+        let casts: [(IrType, fn(f64) -> i32); 5] = [
+            (IrType::I32, |x| x as i32),
+            (IrType::I8, |x| x as i8 as i32),
+            (IrType::U8, |x| x as u8 as i32),
+            (IrType::I16, |x| x as i16 as i32),
+            (IrType::U16, |x| x as u16 as i32),
+        ];
+        for (ty, expected) in casts {
+            let mut f = Tiers::new(float_cast_module(&ty));
+            for x in FLOAT_INPUTS {
+                f.check(
+                    &[x],
+                    InterpValue::I32(expected(x)),
+                    &format!("{:?} cast", ty),
+                );
+            }
+        }
+
+        let mut f = Tiers::new(float_cast_module(&IrType::I32));
+        f.check(&[f64::NAN], InterpValue::I32(0), "Std.int");
+        f.check(&[3e9], InterpValue::I32(i32::MAX), "Std.int");
+        f.check(&[f64::NEG_INFINITY], InterpValue::I32(i32::MIN), "Std.int");
+        f.check(&[-2.7], InterpValue::I32(-2), "Std.int");
+    }
+
+    #[test]
+    fn test_float_rem_edge_cases() {
+        let nan = f64::NAN;
+        let inf = f64::INFINITY;
+        // C fmod: sign of the dividend, exact for large quotients
+        let cases = [
+            (5.5, 2.0, 1.5),
+            (-5.5, 2.0, -1.5),
+            (5.5, -2.0, 1.5),
+            (-0.0, 1.0, -0.0),
+            (4.0, 2.0, 0.0),
+            (-4.0, 2.0, -0.0),
+            (1.0, 0.0, nan),
+            (inf, 1.0, nan),
+            (1.0, inf, 1.0),
+            (nan, 1.0, nan),
+            (1e20, 3.0, 1e20 % 3.0),
+        ];
+        let mut f = Tiers::new(float_rem_module());
+        for (a, b, expected) in cases {
+            f.check(&[a, b], InterpValue::F64(expected), "%");
+        }
+    }
+
+    #[test]
+    fn test_missing_extern_binds_stub() {
+        let (module, func_id) = runtime_call_module("testpkg_no_such_fn", IrType::F64);
+        let mut backend = CraneliftBackend::new().unwrap();
+        backend.compile_module(&module).unwrap();
+        assert!(backend.get_function_ptr(func_id).is_ok());
+
+        let stub = backend.runtime_functions["testpkg_no_such_fn"];
+        assert!(backend.defined_functions.contains(&stub));
+        let missing = (0..)
+            .map_while(missing_extern::get)
+            .find(|m| m.symbol == "testpkg_no_such_fn")
+            .unwrap();
+        assert!(missing.provider().starts_with("an rpkg named 'testpkg'"));
+
+        // Externs the process exports are still imported
+        let (module, _) = runtime_call_module("sqrt", IrType::F64);
+        let mut backend = CraneliftBackend::new().unwrap();
+        backend.compile_module(&module).unwrap();
+        assert!(!backend
+            .runtime_functions
+            .get("sqrt")
+            .is_some_and(|id| backend.defined_functions.contains(id)));
+    }
+
+    #[test]
+    fn test_math_round_and_std_int() {
+        // Haxe rounds ties toward positive infinity
+        let mut round = Tiers::new(runtime_call_module("haxe_math_round", IrType::F64));
+        for (x, expected) in [
+            (2.5, 3.0),
+            (-2.5, -2.0),
+            (-0.5, 0.0),
+            (0.4, 0.0),
+            (-1.6, -2.0),
+            (1e300, 1e300),
+        ] {
+            round.check(&[x], InterpValue::F64(expected), "Math.round");
+        }
+
+        let mut std_int = Tiers::new(runtime_call_module("haxe_std_int", IrType::I32));
+        for x in FLOAT_INPUTS {
+            std_int.check(&[x], InterpValue::I32(x as i32), "Std.int");
+        }
+    }
+
+    #[test]
+    fn test_jump_table_bounds() {
+        assert_eq!(jump_table_bounds(&[3, 0, 2, 1]), Some((0, 4)));
+        assert_eq!(jump_table_bounds(&[-2, -1, 0, 1, 1]), Some((-2, 4)));
+        // Half full is still dense
+        assert_eq!(jump_table_bounds(&[0, 2, 4, 6]), Some((0, 7)));
+        assert_eq!(jump_table_bounds(&[0, 1, 2]), None);
+        assert_eq!(jump_table_bounds(&[0, 1, 2, 100]), None);
+        assert_eq!(jump_table_bounds(&[i64::MIN, 0, 1, i64::MAX]), None);
+    }
+
+    /// `fn(x: i32) -> i32` switching on `x as ty`: each case returns
+    /// `10 * case` through a phi in a join block, which the default and
+    /// the cases listed in `to_join` reach straight from the switch with -1
+    fn switch_module(ty: &IrType, values: &[i64], to_join: &[i64]) -> (IrModule, IrFunctionId) {
+        let mut builder = MirBuilder::new("Switch");
+        let (func_id, params) = begin_i32_fn(&mut builder, 1);
+        let entry = builder.create_block("entry");
+        let join = builder.create_block("join");
+        // begin_i32_fn left the insert point in its own entry block
+        builder.br(entry);
+
+        builder.set_insert_point(entry);
+        let scrutinee = builder.cast(params[0], IrType::I32, ty.clone());
+        let fallback = builder.const_i32(-1);
+        let mut incoming = vec![(entry, fallback)];
+        let mut cases = Vec::new();
+        for &value in values {
+            if to_join.contains(&value) {
+                cases.push((value, join));
+                continue;
+            }
+            let block = builder.create_block(format!("case_{}", value));
+            cases.push((value, block));
+            builder.set_insert_point(block);
+            let result = builder.const_i32((value * 10) as i32);
+            builder.br(join);
+            incoming.push((block, result));
+        }
+        builder.set_insert_point(entry);
+        builder.switch(scrutinee, cases, join);
+
+        builder.set_insert_point(join);
+        let result = builder.alloc_reg();
+        builder.ret(Some(result));
+
+        let mut module = builder.finish();
+        let function = module.functions.get_mut(&func_id).unwrap();
+        function.register_types.insert(result, IrType::I32);
+        function
+            .cfg
+            .blocks
+            .get_mut(&join)
+            .unwrap()
+            .phi_nodes
+            .push(crate::ir::IrPhiNode {
+                dest: result,
+                incoming,
+                ty: IrType::I32,
+            });
+        (module, func_id)
+    }
+
+    #[test]
+    fn test_switch_matches_interpreter() {
+        let dense: Vec<i64> = (0..12).collect();
+        let sparse = [-1000, -1, 3, 77, 4096, 100_000];
+        let case_sets: [(&[i64], &[i64]); 4] = [
+            (&dense, &[]),
+            (&dense, &[5]),
+            (&sparse, &[77]),
+            (&[2, 9], &[]),
+        ];
+        for ty in [IrType::I32, IrType::I64, IrType::I8] {
+            for (values, to_join) in case_sets {
+                let mut f = Conformance::new(switch_module(&ty, values, to_join));
+                let inputs = values
+                    .iter()
+                    .flat_map(|&v| [v - 1, v, v + 1])
+                    .map(|v| v as i32)
+                    .chain([i32::MIN, i32::MAX, 255, -129]);
+                for input in inputs {
+                    let (interpreted, native) = f.run(&[input]);
+                    assert_eq!(
+                        interpreted, native,
+                        "switch on {:?} over {:?}: input {}",
+                        ty, values, input
+                    );
+                    let value = truncate(input, &ty) as i64;
+                    let expected = match values.iter().find(|&&v| v == value) {
+                        Some(v) if !to_join.contains(v) => (v * 10) as i32,
+                        _ => -1,
+                    };
+                    assert_eq!(native, expected, "switch on {:?}: input {}", ty, input);
+                }
+            }
+        }
+    }
 }
@@ -4,6 +4,8 @@
 /// Based on tested implementation from Zyntax compiler.
 use cranelift::prelude::*;
 use cranelift_codegen::ir::condcodes::{FloatCC, IntCC};
+use cranelift_jit::JITModule;
+use cranelift_module::{FuncId, Module};
 use std::collections::HashMap;
 
 use crate::ir::{BinaryOp, CompareOp, IrId, IrInstruction, IrType, UnaryOp};
@@ -37,6 +39,8 @@ impl CraneliftBackend {
             IrType::I64 => types::I64,
             IrType::U32 => types::I32,
             IrType::U64 => types::I64,
+            IrType::I8 | IrType::U8 => types::I8,
+            IrType::I16 | IrType::U16 => types::I16,
             IrType::F32 => types::F32,
             IrType::F64 => types::F64,
             IrType::Bool => types::I32,
@@ -64,7 +68,8 @@ impl CraneliftBackend {
             // Operand type is larger than expected - use larger to prevent truncation
             larger_operand_ty
         } else if expected_ty.is_int() {
-            expected_ty
+            // 8/16-bit arithmetic is done at 32 bits and wrapped back below
+            promote_small_int(expected_ty)
         } else {
             // For non-integer operations (float), use expected type
             expected_ty
@@ -173,11 +178,11 @@ impl CraneliftBackend {
                 let actual_rhs_ty = builder.func.dfg.value_type(rhs);
 
                 if actual_lhs_ty.is_float() || actual_rhs_ty.is_float() {
-                    // Float modulo: a % b = a - floor(a/b) * b
+                    // Float modulo: a % b = a - trunc(a/b) * b (sign of the dividend)
                     // lhs and rhs are already converted to float by the general coercion above
                     let div = builder.ins().fdiv(lhs, rhs);
-                    let floored = builder.ins().floor(div);
-                    let mul = builder.ins().fmul(floored, rhs);
+                    let truncated = builder.ins().trunc(div);
+                    let mul = builder.ins().fmul(truncated, rhs);
                     builder.ins().fsub(lhs, mul)
                 } else if ty.is_signed() {
                     builder.ins().srem(lhs, rhs)
@@ -202,7 +207,7 @@ impl CraneliftBackend {
             BinaryOp::FMul => builder.ins().fmul(lhs, rhs),
             BinaryOp::FDiv => builder.ins().fdiv(lhs, rhs),
             BinaryOp::FRem => {
-                // Float modulo: a % b = a - floor(a/b) * b
+                // Float modulo: a % b = a - trunc(a/b) * b
                 // Get actual types AFTER coercion (lhs/rhs may have been converted above)
                 let actual_lhs_ty = builder.func.dfg.value_type(lhs);
                 let actual_rhs_ty = builder.func.dfg.value_type(rhs);
@@ -229,8 +234,8 @@ impl CraneliftBackend {
                 };
 
                 let div = builder.ins().fdiv(lhs_f, rhs_f);
-                let floored = builder.ins().floor(div);
-                let mul = builder.ins().fmul(floored, rhs_f);
+                let truncated = builder.ins().trunc(div);
+                let mul = builder.ins().fmul(truncated, rhs_f);
                 builder.ins().fsub(lhs_f, mul)
             }
         };
@@ -359,7 +364,7 @@ impl CraneliftBackend {
             UnaryOp::FNeg => builder.ins().fneg(val),
         };
 
-        Ok(value)
+        Ok(fit_small_int(builder, value, ty))
     }
 
     /// Lower a load instruction to Cranelift IR
@@ -429,8 +434,8 @@ impl CraneliftBackend {
         right: IrId,
     ) -> Result<Value, String> {
         let lhs = *value_map.get(&left).ok_or_else(|| {
-            eprintln!(
-                "ERROR: Left operand {:?} not found. Available keys: {:?}",
+            log::debug!(
+                "Left operand {:?} not found. Available keys: {:?}",
                 left,
                 value_map.keys().collect::<Vec<_>>()
             );
@@ -453,6 +458,8 @@ impl CraneliftBackend {
             IrType::I64 => types::I64,
             IrType::U32 => types::I32,
             IrType::U64 => types::I64,
+            IrType::I8 | IrType::U8 => types::I8,
+            IrType::I16 | IrType::U16 => types::I16,
             IrType::F32 => types::F32,
             IrType::F64 => types::F64,
             IrType::Bool => types::I32,
@@ -491,7 +498,8 @@ impl CraneliftBackend {
             // Operand type is larger than expected - use larger to prevent truncation
             larger_operand_ty
         } else if expected_ty.is_int() {
-            expected_ty
+            // 8/16-bit arithmetic is done at 32 bits and wrapped back below
+            promote_small_int(expected_ty)
         } else {
             // For non-integer operations (float), use expected type
             expected_ty
@@ -600,11 +608,11 @@ impl CraneliftBackend {
                 let actual_rhs_ty = builder.func.dfg.value_type(rhs);
 
                 if actual_lhs_ty.is_float() || actual_rhs_ty.is_float() {
-                    // Float modulo: a % b = a - floor(a/b) * b
+                    // Float modulo: a % b = a - trunc(a/b) * b (sign of the dividend)
                     // lhs and rhs are already converted to float by the general coercion above
                     let div = builder.ins().fdiv(lhs, rhs);
-                    let floored = builder.ins().floor(div);
-                    let mul = builder.ins().fmul(floored, rhs);
+                    let truncated = builder.ins().trunc(div);
+                    let mul = builder.ins().fmul(truncated, rhs);
                     builder.ins().fsub(lhs, mul)
                 } else if ty.is_signed() {
                     builder.ins().srem(lhs, rhs)
@@ -628,7 +636,7 @@ impl CraneliftBackend {
             BinaryOp::FMul => builder.ins().fmul(lhs, rhs),
             BinaryOp::FDiv => builder.ins().fdiv(lhs, rhs),
             BinaryOp::FRem => {
-                // Float modulo: a % b = a - floor(a/b) * b
+                // Float modulo: a % b = a - trunc(a/b) * b
                 // Get actual types AFTER coercion (lhs/rhs may have been converted above)
                 let actual_lhs_ty = builder.func.dfg.value_type(lhs);
                 let actual_rhs_ty = builder.func.dfg.value_type(rhs);
@@ -655,8 +663,8 @@ impl CraneliftBackend {
                 };
 
                 let div = builder.ins().fdiv(lhs_f, rhs_f);
-                let floored = builder.ins().floor(div);
-                let mul = builder.ins().fmul(floored, rhs_f);
+                let truncated = builder.ins().trunc(div);
+                let mul = builder.ins().fmul(truncated, rhs_f);
                 builder.ins().fsub(lhs_f, mul)
             }
         };
@@ -700,8 +708,8 @@ impl CraneliftBackend {
         right: IrId,
     ) -> Result<Value, String> {
         let lhs = *value_map.get(&left).ok_or_else(|| {
-            eprintln!(
-                "ERROR: Left operand {:?} not found. Available keys: {:?}",
+            log::debug!(
+                "Left operand {:?} not found. Available keys: {:?}",
                 left,
                 value_map.keys().collect::<Vec<_>>()
             );
@@ -868,7 +876,7 @@ impl CraneliftBackend {
             UnaryOp::FNeg => builder.ins().fneg(val),
         };
 
-        Ok(value)
+        Ok(fit_small_int(builder, value, ty))
     }
 
     /// Lower a load operation (static version)
@@ -926,6 +934,48 @@ impl CraneliftBackend {
         Ok(addr)
     }
 
+    /// Lower a float remainder (`FRem`, or `Rem` on floats) as a call to
+    /// `fmod`; the inline `a - trunc(a/b) * b` loses precision for large
+    /// quotients and gets `x % inf` and `-0.0 % y` wrong
+    pub(super) fn lower_float_rem_static(
+        value_map: &HashMap<IrId, Value>,
+        builder: &mut FunctionBuilder,
+        module: &mut JITModule,
+        fmod: FuncId,
+        ty: &IrType,
+        left: IrId,
+        right: IrId,
+    ) -> Result<Value, String> {
+        let mut operand = |id: IrId| -> Result<Value, String> {
+            let value = *value_map
+                .get(&id)
+                .ok_or_else(|| format!("Remainder operand {:?} not found in value_map", id))?;
+            let value_ty = builder.func.dfg.value_type(value);
+            Ok(if value_ty == types::F64 {
+                value
+            } else if value_ty.is_float() {
+                builder.ins().fpromote(types::F64, value)
+            } else {
+                builder.ins().fcvt_from_sint(types::F64, value)
+            })
+        };
+        let lhs = operand(left)?;
+        let rhs = operand(right)?;
+
+        let fmod_ref = module.declare_func_in_func(fmod, builder.func);
+        let call = builder.ins().call(fmod_ref, &[lhs, rhs]);
+        let result = builder.inst_results(call)[0];
+
+        Ok(match ty {
+            IrType::F32 => builder.ins().fdemote(types::F32, result),
+            _ if ty.is_integer() => {
+                let int_ty = Self::mir_type_to_cranelift_static(ty)?;
+                float_to_int_sat(builder, result, ty, int_ty)
+            }
+            _ => result,
+        })
+    }
+
     /// Check if a Cranelift value was produced by an fmul instruction in the same block.
     /// Returns the two operands if so, enabling FMA fusion.
     ///
@@ -970,6 +1020,106 @@ fn type_size(ty: &IrType) -> Result<u32, String> {
     })
 }
 
+// =========================================================================
+// Small integer policy
+// =========================================================================
+//
+// I8/U8/I16/U16 values live in Cranelift's i8/i16 types. Arithmetic on them
+// is done at 32 bits after extending each operand by the signedness of the
+// MIR type, then wrapped (ireduce) back to the small type, like C integer
+// promotion. This gives two's-complement wrapping for add/sub/mul/shl,
+// logical shifts for unsigned types and no trap for `-128 / -1`. The MIR
+// interpreter applies the same rule when writing small-typed registers.
+
+/// Widen 8/16-bit integer types to the 32-bit type they are computed in
+fn promote_small_int(ty: Type) -> Type {
+    if ty.is_int() && ty.bits() < 32 {
+        types::I32
+    } else {
+        ty
+    }
+}
+
+/// Bring a unary result back to its small integer MIR type, if it has one
+fn fit_small_int(builder: &mut FunctionBuilder, value: Value, ty: &IrType) -> Value {
+    let small_ty = match ty {
+        IrType::I8 | IrType::U8 => types::I8,
+        IrType::I16 | IrType::U16 => types::I16,
+        _ => return value,
+    };
+    if builder.func.dfg.value_type(value).is_int() {
+        convert_int(builder, value, ty, small_ty)
+    } else {
+        value
+    }
+}
+
+/// Float to integer conversion: truncate toward zero, NaN to 0, saturate
+/// out-of-range values to the bounds of `to_ty` (see `IrInstruction::Cast`)
+pub(super) fn float_to_int_sat(
+    builder: &mut FunctionBuilder,
+    value: Value,
+    to_ty: &IrType,
+    to: Type,
+) -> Value {
+    let unsigned = matches!(to_ty, IrType::U8 | IrType::U16 | IrType::U32 | IrType::U64);
+    if to.bits() >= 32 {
+        return if unsigned {
+            builder.ins().fcvt_to_uint_sat(to, value)
+        } else {
+            builder.ins().fcvt_to_sint_sat(to, value)
+        };
+    }
+    // Saturating conversions only go down to 32 bits; clamp into the small range
+    let (min, max) = match (to.bits(), unsigned) {
+        (8, false) => (i8::MIN as i64, i8::MAX as i64),
+        (8, true) => (0, u8::MAX as i64),
+        (_, false) => (i16::MIN as i64, i16::MAX as i64),
+        (_, true) => (0, u16::MAX as i64),
+    };
+    let wide = builder.ins().fcvt_to_sint_sat(types::I32, value);
+    let min = builder.ins().iconst(types::I32, min);
+    let max = builder.ins().iconst(types::I32, max);
+    let clamped = builder.ins().smax(wide, min);
+    let clamped = builder.ins().smin(clamped, max);
+    builder.ins().ireduce(to, clamped)
+}
+
+/// `fmod` for float remainders, registered with every JIT module
+pub(super) extern "C" fn rayzor_fmod(a: f64, b: f64) -> f64 {
+    a % b
+}
+
+/// Extend or truncate an integer value to `to`.
+///
+/// Extension is signed iff `from_ty` is a signed MIR integer type. When the
+/// MIR type is not an integer (Bool, or a generic resolved to Ptr(Void)),
+/// 8-bit values are zero-extended (booleans) and wider ones sign-extended.
+pub(super) fn convert_int(
+    builder: &mut FunctionBuilder,
+    value: Value,
+    from_ty: &IrType,
+    to: Type,
+) -> Value {
+    let from = builder.func.dfg.value_type(value);
+    if from == to {
+        return value;
+    }
+    if from.bits() > to.bits() {
+        return builder.ins().ireduce(to, value);
+    }
+    let signed = match from_ty {
+        IrType::I8 | IrType::I16 | IrType::I32 | IrType::I64 => true,
+        IrType::U8 | IrType::U16 | IrType::U32 | IrType::U64 => false,
+        _ => from.bits() > 8,
+    };
+    if signed {
+        builder.ins().sextend(to, value)
+    } else {
+        builder.ins().uextend(to, value)
+    }
+}
+
 // Helper trait to check type properties
 pub trait TypeProperties {
     fn is_float(&self) -> bool;
@@ -78,7 +78,7 @@ fn get_host_cpu_info() -> (String, String) {
 
 /// Find a system LLVM tool binary.
 /// Checks unversioned name first, then versioned variants (21, 20, 19).
-fn find_llvm_tool(name: &str) -> Option<String> {
+pub(crate) fn find_llvm_tool(name: &str) -> Option<String> {
     use std::process::Command;
     let candidates: Vec<String> = std::iter::once(name.to_string())
         .chain((19..=21).rev().map(|v| format!("{}-{}", name, v)))
@@ -104,11 +104,14 @@ pub fn has_system_llvm_tools() -> bool {
 ///
 /// If `rename_entry` is Some, the named function is renamed to `_haxe_<name>` in the
 /// IR text before optimization (to avoid conflicts with the C main() added later).
+/// `split_sections` gives every function and global its own section so the
+/// linker can garbage-collect unreferenced ones.
 pub fn compile_ir_with_system_tools(
     ir_text: &str,
     output_obj: &std::path::Path,
     opt_flag: &str,
     rename_entry: Option<&str>,
+    split_sections: bool,
 ) -> Result<bool, String> {
     use std::process::Command;
 
@@ -172,10 +175,14 @@ pub fn compile_ir_with_system_tools(
     // llc: optimized bitcode → object file
     // Use PIC relocation model so the object can be linked into a PIE executable
     // (modern Linux defaults to PIE).
-    let llc_out = Command::new(&llc_bin)
-        .arg(opt_flag)
+    let mut llc = Command::new(&llc_bin);
+    llc.arg(opt_flag)
         .arg("--relocation-model=pic")
-        .arg("-filetype=obj")
+        .arg("-filetype=obj");
+    if split_sections {
+        llc.arg("--function-sections").arg("--data-sections");
+    }
+    let llc_out = llc
         .arg("-o")
         .arg(output_obj)
         .arg(&bc_path)
@@ -290,6 +297,286 @@ pub fn optimize_module(
     Ok(())
 }
 
+/// Put each defined function and global in its own section, the equivalent of
+/// `-ffunction-sections -fdata-sections`, so `--gc-sections` can drop the
+/// unreferenced ones. Only ELF needs this: Mach-O already dead-strips per
+/// symbol and COFF output is left as is.
+#[cfg(feature = "llvm-backend")]
+pub fn split_sections(module: &Module, target_triple: Option<&str>) {
+    let triple = match target_triple {
+        Some(t) => t.to_string(),
+        None => TargetMachine::get_default_triple()
+            .as_str()
+            .to_string_lossy()
+            .to_string(),
+    };
+    if triple.contains("darwin") || triple.contains("apple") || triple.contains("windows") {
+        return;
+    }
+
+    for func in module.get_functions() {
+        if func.count_basic_blocks() == 0 || has_section(func.get_section()) {
+            continue;
+        }
+        let name = func.get_name().to_string_lossy().to_string();
+        func.set_section(Some(&format!(".text.{}", name)));
+    }
+    for global in module.get_globals() {
+        let Some(init) = global.get_initializer() else {
+            continue;
+        };
+        if has_section(global.get_section()) {
+            continue;
+        }
+        let prefix = if global.is_constant() {
+            ".rodata"
+        } else if unsafe { llvm_sys::core::LLVMIsNull(init.as_value_ref()) } != 0 {
+            ".bss"
+        } else {
+            ".data"
+        };
+        let name = global.get_name().to_string_lossy().to_string();
+        global.set_section(Some(&format!("{}.{}", prefix, name)));
+    }
+}
+
+/// A multi-versioned function by LLVM name: the baseline body plus the
+/// specialized clones with their feature lists
+#[cfg(feature = "llvm-backend")]
+pub struct DispatchTarget {
+    pub function: String,
+    /// (features, clone name), tried in order
+    pub versions: Vec<(Vec<String>, String)>,
+}
+
+/// Runtime check used by the resolver (`runtime/src/cpu_features.rs`)
+#[cfg(feature = "llvm-backend")]
+const CPU_SUPPORTS_FN: &str = "rayzor_cpu_supports";
+
+/// Route calls to multi-versioned functions through a function pointer
+/// chosen once at startup, ifunc style but portable to every object format.
+///
+/// For each target `f`, the compiled body is renamed `f.default` and a
+/// dispatcher named `f` tail-calls through `f.resolved`, which starts out
+/// pointing at the baseline. A constructor in `llvm.global_ctors` asks the
+/// runtime which feature sets the CPU supports and stores the first
+/// matching clone. Clones get `target-features` so LLVM may use the
+/// extensions in them and in code inlined into them. Returns the number of
+/// functions dispatched.
+#[cfg(feature = "llvm-backend")]
+pub fn emit_multiversion_dispatch(
+    module: &Module,
+    targets: &[DispatchTarget],
+) -> Result<usize, String> {
+    use inkwell::attributes::AttributeLoc;
+    use inkwell::module::Linkage;
+    use inkwell::values::BasicMetadataValueEnum;
+    use inkwell::AddressSpace;
+
+    let context = module.get_context();
+    let builder = context.create_builder();
+    let ptr_type = context.ptr_type(AddressSpace::default());
+    let i32_type = context.i32_type();
+    let err = |e: inkwell::builder::BuilderError| format!("Multi-versioning: {}", e);
+
+    let mut resolver = None;
+    let mut dispatched = 0;
+    for target in targets {
+        let Some(original) = module.get_function(&target.function) else {
+            continue;
+        };
+        let clones: Vec<_> = target
+            .versions
+            .iter()
+            .filter_map(|(features, name)| Some((features, module.get_function(name)?)))
+            .collect();
+        if original.count_basic_blocks() == 0 || clones.is_empty() {
+            continue;
+        }
+
+        // The dispatcher takes over the original's callers and name
+        let fn_type = original.get_type();
+        let dispatcher = module.add_function(
+            &format!("{}.dispatch", target.function),
+            fn_type,
+            Some(original.get_linkage()),
+        );
+        unsafe {
+            llvm_sys::core::LLVMReplaceAllUsesWith(
+                original.as_value_ref(),
+                dispatcher.as_value_ref(),
+            );
+        }
+        set_value_name(
+            original.as_value_ref(),
+            &format!("{}.default", target.function),
+        );
+        set_value_name(dispatcher.as_value_ref(), &target.function);
+        original.set_linkage(Linkage::Internal);
+
+        let slot = module.add_global(ptr_type, None, &format!("{}.resolved", target.function));
+        slot.set_linkage(Linkage::Internal);
+        slot.set_initializer(&original.as_global_value().as_pointer_value());
+
+        let entry = context.append_basic_block(dispatcher, "entry");
+        builder.position_at_end(entry);
+        let callee = builder
+            .build_load(ptr_type, slot.as_pointer_value(), "callee")
+            .map_err(err)?
+            .into_pointer_value();
+        let args: Vec<BasicMetadataValueEnum> =
+            dispatcher.get_param_iter().map(|p| p.into()).collect();
+        let call = builder
+            .build_indirect_call(fn_type, callee, &args, "")
+            .map_err(err)?;
+        call.set_tail_call(true);
+        match call.try_as_basic_value().left() {
+            Some(value) => builder.build_return(Some(&value)),
+            None => builder.build_return(None),
+        }
+        .map_err(err)?;
+
+        // Resolver: the first version whose features are all present wins
+        let (resolver_fn, current) = *resolver.get_or_insert_with(|| {
+            let f = module.add_function(
+                "rayzor_multiversion_init",
+                context.void_type().fn_type(&[], false),
+                Some(Linkage::Internal),
+            );
+            (f, context.append_basic_block(f, "entry"))
+        });
+        let cpu_supports = module.get_function(CPU_SUPPORTS_FN).unwrap_or_else(|| {
+            module.add_function(
+                CPU_SUPPORTS_FN,
+                i32_type.fn_type(&[ptr_type.into()], false),
+                Some(Linkage::External),
+            )
+        });
+        builder.position_at_end(current);
+        let done = context.append_basic_block(resolver_fn, "resolved");
+        for (features, clone) in clones {
+            clone.set_linkage(Linkage::Internal);
+            clone.add_attribute(
+                AttributeLoc::Function,
+                context.create_string_attribute(
+                    "target-features",
+                    &crate::ir::multiversion::llvm_target_features(features),
+                ),
+            );
+
+            let names = builder
+                .build_global_string_ptr(&features.join(","), "features")
+                .map_err(err)?;
+            let supported = builder
+                .build_call(
+                    cpu_supports,
+                    &[names.as_pointer_value().into()],
+                    "supported",
+                )
+                .map_err(err)?
+                .try_as_basic_value()
+                .left()
+                .ok_or("Multi-versioning: rayzor_cpu_supports returned void")?
+                .into_int_value();
+            let is_supported = builder
+                .build_int_compare(
+                    inkwell::IntPredicate::NE,
+                    supported,
+                    i32_type.const_zero(),
+                    "is_supported",
+                )
+                .map_err(err)?;
+            let select = context.append_basic_block(resolver_fn, "select");
+            let next = context.append_basic_block(resolver_fn, "next");
+            builder
+                .build_conditional_branch(is_supported, select, next)
+                .map_err(err)?;
+            builder.position_at_end(select);
+            builder
+                .build_store(
+                    slot.as_pointer_value(),
+                    clone.as_global_value().as_pointer_value(),
+                )
+                .map_err(err)?;
+            builder.build_unconditional_branch(done).map_err(err)?;
+            builder.position_at_end(next);
+        }
+        builder.build_unconditional_branch(done).map_err(err)?;
+        resolver = Some((resolver_fn, done));
+        dispatched += 1;
+    }
+
+    let Some((resolver_fn, last)) = resolver else {
+        return Ok(0);
+    };
+    builder.position_at_end(last);
+    builder.build_return(None).map_err(err)?;
+    add_global_ctor(module, resolver_fn)?;
+    Ok(dispatched)
+}
+
+/// Register `function` to run before `main` via `llvm.global_ctors`
+#[cfg(feature = "llvm-backend")]
+fn add_global_ctor(
+    module: &Module,
+    function: inkwell::values::FunctionValue,
+) -> Result<(), String> {
+    if module.get_global("llvm.global_ctors").is_some() {
+        return Err("llvm.global_ctors is already defined".to_string());
+    }
+    let context = module.get_context();
+    let i32_type = context.i32_type();
+    let ptr_type = context.ptr_type(inkwell::AddressSpace::default());
+    let entry_type =
+        context.struct_type(&[i32_type.into(), ptr_type.into(), ptr_type.into()], false);
+    let entry = entry_type.const_named_struct(&[
+        i32_type.const_int(65535, false).into(),
+        function.as_global_value().as_pointer_value().into(),
+        ptr_type.const_null().into(),
+    ]);
+    let ctors = entry_type.const_array(&[entry]);
+    let global = module.add_global(ctors.get_type(), None, "llvm.global_ctors");
+    global.set_linkage(inkwell::module::Linkage::Appending);
+    global.set_initializer(&ctors);
+    Ok(())
+}
+
+#[cfg(feature = "llvm-backend")]
+fn set_value_name(value: llvm_sys::prelude::LLVMValueRef, name: &str) {
+    unsafe {
+        llvm_sys::core::LLVMSetValueName2(value, name.as_ptr() as *const _, name.len());
+    }
+}
+
+/// Give every defined function a stack canary where it has local arrays or
+/// address-taken locals (`sspstrong`, as `-fstack-protector-strong`).
+/// Functions that already choose a protection level keep it.
+#[cfg(feature = "llvm-backend")]
+pub fn add_stack_protector(module: &Module) {
+    use inkwell::attributes::{Attribute, AttributeLoc};
+
+    let context = module.get_context();
+    let ssp_kinds = ["ssp", "sspstrong", "sspreq"].map(Attribute::get_named_enum_kind_id);
+    let sspstrong = context.create_enum_attribute(ssp_kinds[1], 0);
+    for func in module.get_functions() {
+        if func.count_basic_blocks() == 0
+            || ssp_kinds.iter().any(|kind| {
+                func.get_enum_attribute(AttributeLoc::Function, *kind)
+                    .is_some()
+            })
+        {
+            continue;
+        }
+        func.add_attribute(AttributeLoc::Function, sspstrong);
+    }
+}
+
+/// Whether a value already has an explicit (non-empty) section
+#[cfg(feature = "llvm-backend")]
+fn has_section(section: Option<&std::ffi::CStr>) -> bool {
+    section.is_some_and(|s| !s.to_bytes().is_empty())
+}
+
 /// Compile to object file with configurable target and relocation mode.
 /// The module should already be optimized via `optimize_module`.
 #[cfg(feature = "llvm-backend")]
@@ -386,28 +673,7 @@ pub fn emit_assembly(
 /// If entry is named "main", renames it to "_haxe_main" first.
 #[cfg(feature = "llvm-backend")]
 pub fn generate_main_wrapper(module: &Module, entry_func_name: &str) -> Result<(), String> {
-    let entry_func = module.get_function(entry_func_name).ok_or_else(|| {
-        format!(
-            "Entry function '{}' not found in LLVM module",
-            entry_func_name
-        )
-    })?;
-
-    // Rename if collides with C main
-    let actual_name = if entry_func_name == "main" {
-        unsafe {
-            use std::ffi::CString;
-            let new_name = CString::new("_haxe_main").unwrap();
-            llvm_sys::core::LLVMSetValueName2(
-                entry_func.as_value_ref(),
-                new_name.as_ptr(),
-                "_haxe_main".len(),
-            );
-        }
-        "_haxe_main"
-    } else {
-        entry_func_name
-    };
+    let actual_name = rename_entry(module, entry_func_name)?;
 
     let context = module.get_context();
     let i32_type = context.i32_type();
@@ -422,7 +688,7 @@ pub fn generate_main_wrapper(module: &Module, entry_func_name: &str) -> Result<(
     builder.position_at_end(entry_bb);
 
     let haxe_entry = module
-        .get_function(actual_name)
+        .get_function(&actual_name)
         .ok_or_else(|| format!("Renamed entry function '{}' not found", actual_name))?;
 
     let zero = i64_type.const_int(0, false);
@@ -436,3 +702,48 @@ pub fn generate_main_wrapper(module: &Module, entry_func_name: &str) -> Result<(
 
     Ok(())
 }
+
+/// Rename the entry function to `_haxe_main` if it is called `main`, so a C
+/// `main()` can call it. Returns the entry's name afterwards.
+#[cfg(feature = "llvm-backend")]
+pub fn rename_entry(module: &Module, entry_func_name: &str) -> Result<String, String> {
+    let entry_func = module.get_function(entry_func_name).ok_or_else(|| {
+        format!(
+            "Entry function '{}' not found in LLVM module",
+            entry_func_name
+        )
+    })?;
+    if entry_func_name != "main" {
+        return Ok(entry_func_name.to_string());
+    }
+    set_value_name(entry_func.as_value_ref(), "_haxe_main");
+    Ok("_haxe_main".to_string())
+}
+
+/// Point calls to external functions at replacements: each declaration
+/// `redirect` maps to a new name is renamed, or merged into an existing
+/// declaration of that name. Returns how many were redirected.
+#[cfg(feature = "llvm-backend")]
+pub fn redirect_externs(module: &Module, redirect: impl Fn(&str) -> Option<String>) -> usize {
+    let mut redirected = 0;
+    for func in module.get_functions() {
+        if func.count_basic_blocks() != 0 {
+            continue;
+        }
+        let name = func.get_name().to_string_lossy().to_string();
+        let Some(target) = redirect(&name) else {
+            continue;
+        };
+        match module.get_function(&target) {
+            Some(existing) => {
+                func.as_global_value()
+                    .as_pointer_value()
+                    .replace_all_uses_with(existing.as_global_value().as_pointer_value());
+                unsafe { func.delete() };
+            }
+            None => set_value_name(func.as_value_ref(), &target),
+        }
+        redirected += 1;
+    }
+    redirected
+}
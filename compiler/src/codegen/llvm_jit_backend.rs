@@ -37,6 +37,7 @@ use inkwell::{
     AddressSpace, FloatPredicate, IntPredicate, OptimizationLevel,
 };
 
+use crate::ir::module_init::{INIT_FUNCTION, VTABLE_INIT_FUNCTION};
 use crate::ir::{
     BinaryOp, CompareOp, IrBasicBlock, IrBlockId, IrFunction, IrFunctionId, IrGlobalId, IrId,
     IrInstruction, IrModule, IrPhiNode, IrTerminator, IrType, IrValue, UnaryOp, VectorMinMaxKind,
@@ -668,13 +669,13 @@ impl<'ctx> LLVMJitBackend<'ctx> {
             "haxe_math_abs" => "llvm.fabs.f64",
             "haxe_math_floor" => "llvm.floor.f64",
             "haxe_math_ceil" => "llvm.ceil.f64",
-            "haxe_math_round" => "llvm.round.f64",
+            "haxe_math_round" => "llvm.floor.f64",
             "haxe_math_sin" => "llvm.sin.f64",
             "haxe_math_cos" => "llvm.cos.f64",
             "haxe_math_exp" => "llvm.exp.f64",
             "haxe_math_log" => "llvm.log.f64",
             "haxe_math_pow" => "llvm.pow.f64",
-            "haxe_math_fround" => "llvm.round.f64",
+            "haxe_math_fround" => "llvm.floor.f64",
             _ => return Ok(None),
         };
 
@@ -705,13 +706,23 @@ impl<'ctx> LLVMJitBackend<'ctx> {
         builder.position_at_end(bb);
 
         // Collect non-void args (the intrinsic takes only the f64 args, not env)
-        let params: Vec<inkwell::values::BasicMetadataValueEnum> = wrapper
+        let mut params: Vec<inkwell::values::BasicMetadataValueEnum> = wrapper
             .get_params()
             .into_iter()
             .filter(|p| p.is_float_value())
             .map(|p| p.into())
             .collect();
 
+        // Haxe rounds ties up (Math.round(-2.5) == -2): floor(x + 0.5)
+        if matches!(func_name, "haxe_math_round" | "haxe_math_fround") {
+            if let Some(inkwell::values::BasicMetadataValueEnum::FloatValue(x)) = params.first() {
+                let shifted = builder
+                    .build_float_add(*x, f64_type.const_float(0.5), "round_shift")
+                    .map_err(|e| format!("Failed to build round shift: {}", e))?;
+                params[0] = shifted.into();
+            }
+        }
+
         let result = builder
             .build_call(intrinsic_func, &params, "result")
             .map_err(|e| format!("Failed to build intrinsic call: {}", e))?
@@ -771,10 +782,14 @@ impl<'ctx> LLVMJitBackend<'ctx> {
             .unwrap_or(i32_type);
 
         let i32_result: inkwell::values::IntValue = if param.is_float_value() {
-            // Convert f64 to i32 using fptosi (truncation toward zero)
-            builder
-                .build_float_to_signed_int(param.into_float_value(), i32_type, "int_result")
-                .map_err(|e| format!("Failed to build fptosi: {}", e))?
+            // Truncate toward zero; NaN -> 0, out of range saturates to Int32 bounds
+            self.build_float_to_int_sat(
+                &builder,
+                param.into_float_value(),
+                i32_type,
+                true,
+                "int_result",
+            )?
         } else {
             // Already an int, truncate/extend to i32 if needed
             let int_val = param.into_int_value();
@@ -1286,6 +1301,30 @@ impl<'ctx> LLVMJitBackend<'ctx> {
         // Finalize if needed
         self.finalize()?;
 
+        let engine = self
+            .execution_engine
+            .as_ref()
+            .ok_or("Execution engine not initialized")?;
+
+        // Register vtables, then run module initializers, as the Cranelift
+        // backend does before main
+        for init_name in [VTABLE_INIT_FUNCTION, INIT_FUNCTION] {
+            let Some(init_func) = module
+                .functions
+                .values()
+                .find(|f| f.name == init_name && !f.cfg.blocks.is_empty())
+            else {
+                continue;
+            };
+            let func_name = Self::mangle_function_name(&init_func.name);
+            if let Ok(init_ptr) = engine.get_function_address(&func_name) {
+                unsafe {
+                    let init_fn: extern "C" fn(i64) = std::mem::transmute(init_ptr);
+                    init_fn(0);
+                }
+            }
+        }
+
         // Find main function by name since IDs may not match between modules
         let main_func = module
             .functions
@@ -1296,11 +1335,6 @@ impl<'ctx> LLVMJitBackend<'ctx> {
 
         // Get function pointer by name (MCJIT compilation already happened in finalize)
         let func_name = Self::mangle_function_name(&main_func.name);
-        let engine = self
-            .execution_engine
-            .as_ref()
-            .ok_or("Execution engine not initialized")?;
-
         let fn_ptr = engine
             .get_function_address(&func_name)
             .map_err(|e| format!("Failed to get main function '{}': {}", func_name, e))?;
@@ -1661,6 +1695,26 @@ impl<'ctx> LLVMJitBackend<'ctx> {
         Ok(llvm_func)
     }
 
+    /// Attach `!prof` branch_weights metadata to the conditional branch
+    /// ending `block`
+    fn set_branch_weights(&self, block: BasicBlock<'ctx>, (true_weight, false_weight): (u32, u32)) {
+        let Some(branch) = block.get_terminator() else {
+            return;
+        };
+        if branch.get_opcode() != inkwell::values::InstructionOpcode::Br
+            || branch.get_num_operands() != 3
+        {
+            return;
+        }
+        let i32_type = self.context.i32_type();
+        let weights = self.context.metadata_node(&[
+            self.context.metadata_string("branch_weights").into(),
+            i32_type.const_int(true_weight as u64, false).into(),
+            i32_type.const_int(false_weight as u64, false).into(),
+        ]);
+        let _ = branch.set_metadata(weights, self.context.get_kind_id("prof"));
+    }
+
     /// Mangle function name to be LLVM-safe
     pub fn mangle_function_name(name: &str) -> String {
         // Replace characters that might cause issues in LLVM
@@ -1738,6 +1792,37 @@ impl<'ctx> LLVMJitBackend<'ctx> {
         // Check if this function uses C calling convention (no hidden env param)
         let is_c_abi = self.extern_function_ids.contains(&func_id);
 
+        // @:cold functions are optimized for size and kept out of hot callers
+        if function
+            .attributes
+            .custom
+            .contains_key(crate::ir::branch_hints::COLD_ATTRIBUTE)
+        {
+            llvm_func.add_attribute(
+                inkwell::attributes::AttributeLoc::Function,
+                self.context.create_enum_attribute(
+                    inkwell::attributes::Attribute::get_named_enum_kind_id("cold"),
+                    0,
+                ),
+            );
+        }
+
+        // Verified @:noThrow functions never unwind, so LLVM can drop their
+        // unwind tables and treat calls to them as non-throwing
+        if function
+            .attributes
+            .custom
+            .contains_key(crate::ir::NO_THROW_ATTRIBUTE)
+        {
+            llvm_func.add_attribute(
+                inkwell::attributes::AttributeLoc::Function,
+                self.context.create_enum_attribute(
+                    inkwell::attributes::Attribute::get_named_enum_kind_id("nounwind"),
+                    0,
+                ),
+            );
+        }
+
         // Map function parameters to LLVM values using their actual IrIds
         // Note: we filter out void parameters but need to handle IrIds correctly
         let non_void_params: Vec<_> = function
@@ -1927,6 +2012,9 @@ impl<'ctx> LLVMJitBackend<'ctx> {
 
             // Compile terminator (pass llvm_func for return type checking)
             self.compile_terminator(&mir_block.terminator, llvm_func)?;
+            if let Some(weights) = crate::ir::branch_hints::branch_weights(mir_block) {
+                self.set_branch_weights(llvm_block, weights);
+            }
         }
 
         // Pass 3: Fill in phi node incoming values
@@ -3265,14 +3353,18 @@ impl<'ctx> LLVMJitBackend<'ctx> {
                     .builder
                     .build_load(vec_llvm_ty, ptr_val, &format!("vload_{}", dest.as_u32()))
                     .map_err(|e| format!("Failed to build vector load: {}", e))?;
+                // Only element alignment is guaranteed: the pointer may be a scalar
+                // buffer or a lane-addressing stack slot, not a 16-byte vector slot
+                if let (Some(inst), Some(elem)) =
+                    (loaded.as_instruction_value(), vec_ty.vector_element())
+                {
+                    inst.set_alignment(elem.size() as u32)
+                        .map_err(|e| format!("Failed to set vector load alignment: {}", e))?;
+                }
                 self.value_map.insert(*dest, loaded);
             }
 
-            IrInstruction::VectorStore {
-                ptr,
-                value,
-                vec_ty: _,
-            } => {
+            IrInstruction::VectorStore { ptr, value, vec_ty } => {
                 let raw_ptr = self.get_value(*ptr)?;
                 let ptr_val = if raw_ptr.is_pointer_value() {
                     raw_ptr.into_pointer_value()
@@ -3290,9 +3382,15 @@ impl<'ctx> LLVMJitBackend<'ctx> {
                     return Err(format!("VectorStore ptr {:?} has unexpected type", ptr));
                 };
                 let vec_val = self.get_value(*value)?;
-                self.builder
+                let store = self
+                    .builder
                     .build_store(ptr_val, vec_val)
                     .map_err(|e| format!("Failed to build vector store: {}", e))?;
+                if let Some(elem) = vec_ty.vector_element() {
+                    store
+                        .set_alignment(elem.size() as u32)
+                        .map_err(|e| format!("Failed to set vector store alignment: {}", e))?;
+                }
             }
 
             IrInstruction::VectorBinOp {
@@ -4081,7 +4179,8 @@ impl<'ctx> LLVMJitBackend<'ctx> {
                         let case_block = self.block_map.get(case_target).ok_or_else(|| {
                             format!("Case target block {:?} not found", case_target)
                         })?;
-                        let const_val = self.context.i64_type().const_int(*case_val as u64, false);
+                        // Case constants must have the scrutinee's type
+                        let const_val = switch_val.get_type().const_int(*case_val as u64, true);
                         Ok((const_val, *case_block))
                     })
                     .collect();
@@ -5249,6 +5348,35 @@ impl<'ctx> LLVMJitBackend<'ctx> {
         }
     }
 
+    /// Float to integer conversion with Haxe semantics: truncate toward zero,
+    /// NaN to 0, saturate out-of-range values (plain fptosi is poison there)
+    fn build_float_to_int_sat(
+        &self,
+        builder: &Builder<'ctx>,
+        value: inkwell::values::FloatValue<'ctx>,
+        int_ty: inkwell::types::IntType<'ctx>,
+        signed: bool,
+        name: &str,
+    ) -> Result<inkwell::values::IntValue<'ctx>, String> {
+        let intrinsic_name = if signed {
+            "llvm.fptosi.sat"
+        } else {
+            "llvm.fptoui.sat"
+        };
+        let intrinsic = inkwell::intrinsics::Intrinsic::find(intrinsic_name)
+            .ok_or_else(|| format!("LLVM intrinsic {} not found", intrinsic_name))?;
+        let func = intrinsic
+            .get_declaration(&self.module, &[int_ty.into(), value.get_type().into()])
+            .ok_or_else(|| format!("Failed to get {} declaration", intrinsic_name))?;
+        Ok(builder
+            .build_call(func, &[value.into()], name)
+            .map_err(|e| format!("Failed to build {}: {}", intrinsic_name, e))?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| format!("{} returned void", intrinsic_name))?
+            .into_int_value())
+    }
+
     /// Compile type cast
     fn compile_cast(
         &self,
@@ -5270,18 +5398,13 @@ impl<'ctx> LLVMJitBackend<'ctx> {
 
         // If actual value is float but target is int, convert float->int
         if actual_is_float && to_ty.is_integer() {
-            let target_int_ty = target_llvm_ty.into_int_type();
-            let result = if to_ty.is_signed_integer() {
-                self.builder
-                    .build_float_to_signed_int(src.into_float_value(), target_int_ty, &name)
-            } else {
-                self.builder.build_float_to_unsigned_int(
-                    src.into_float_value(),
-                    target_int_ty,
-                    &name,
-                )
-            }
-            .map_err(|e| format!("Failed to build float-to-int cast: {}", e))?;
+            let result = self.build_float_to_int_sat(
+                &self.builder,
+                src.into_float_value(),
+                target_llvm_ty.into_int_type(),
+                to_ty.is_signed_integer(),
+                &name,
+            )?;
             return Ok(result.into());
         }
 
@@ -5376,18 +5499,13 @@ impl<'ctx> LLVMJitBackend<'ctx> {
 
         // Float to int
         if from_ty.is_float() && to_ty.is_integer() {
-            let src_float = src.into_float_value();
-            let target_int_ty = target_llvm_ty.into_int_type();
-
-            let result = if to_ty.is_signed_integer() {
-                self.builder
-                    .build_float_to_signed_int(src_float, target_int_ty, &name)
-            } else {
-                self.builder
-                    .build_float_to_unsigned_int(src_float, target_int_ty, &name)
-            }
-            .map_err(|e| format!("Failed to build float to int: {}", e))?;
-
+            let result = self.build_float_to_int_sat(
+                &self.builder,
+                src.into_float_value(),
+                target_llvm_ty.into_int_type(),
+                to_ty.is_signed_integer(),
+                &name,
+            )?;
             return Ok(result.into());
         }
 
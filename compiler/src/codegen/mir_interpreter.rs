@@ -27,10 +27,11 @@
 //! - When exponent bits != 0x7FF, it's a regular f64
 //! - Otherwise, tag bits identify: Ptr, I32, Bool, Null, etc.
 
+use super::profiling::ProfileData;
 use crate::ir::{
     BinaryOp, CompareOp, FunctionKind, IrBasicBlock, IrBlockId, IrExternFunction, IrFunction,
     IrFunctionId, IrFunctionSignature, IrId, IrInstruction, IrModule, IrTerminator, IrType,
-    IrValue, UnaryOp,
+    IrValue, UnaryOp, VectorMinMaxKind, VectorUnaryOpKind,
 };
 // SmallVec disabled temporarily - reverting to Vec to debug Linux CI heap corruption
 use std::collections::HashMap;
@@ -119,9 +120,16 @@ impl NanBoxedValue {
         Self(Self::NAN_TAG | Self::TAG_NULL)
     }
 
+    /// NaN representation outside the tagged space (quiet bit clear): a
+    /// hardware quiet NaN would otherwise read back as a tagged value
+    const CANONICAL_NAN: u64 = 0x7FF0_0000_0000_0001;
+
     /// Create from f64 (stored directly as IEEE 754 double)
     #[inline(always)]
     pub fn from_f64(v: f64) -> Self {
+        if v.is_nan() {
+            return Self(Self::CANONICAL_NAN);
+        }
         Self(v.to_bits())
     }
 
@@ -705,6 +713,22 @@ impl InterpValue {
         }
     }
 
+    /// Integer or Bool value, for profiling constant arguments
+    pub fn as_constant(&self) -> Option<i64> {
+        match self {
+            InterpValue::I8(_)
+            | InterpValue::I16(_)
+            | InterpValue::I32(_)
+            | InterpValue::I64(_)
+            | InterpValue::U8(_)
+            | InterpValue::U16(_)
+            | InterpValue::U32(_)
+            | InterpValue::U64(_)
+            | InterpValue::Bool(_) => self.to_i64().ok(),
+            _ => None,
+        }
+    }
+
     /// Convert to i64 (for integer operations)
     pub fn to_i64(&self) -> Result<i64, InterpError> {
         match self {
@@ -1002,6 +1026,10 @@ pub struct MirInterpreter {
     /// Global variable store - maps global IDs to their values
     /// Used for static class fields and module-level variables
     global_store: HashMap<crate::ir::IrGlobalId, NanBoxedValue>,
+
+    /// Where to record conditional branch outcomes (tiered mode), used to
+    /// lay out hot paths when the function is recompiled
+    branch_profile: Option<ProfileData>,
 }
 
 // Safety: MirInterpreter can be sent across threads
@@ -1025,6 +1053,7 @@ impl MirInterpreter {
             iteration_count: 0,
             max_iterations: 10_000, // Trigger JIT bailout after 10k iterations
             global_store: HashMap::new(),
+            branch_profile: None,
         }
     }
 
@@ -1062,6 +1091,12 @@ impl MirInterpreter {
         self.runtime_symbols.insert(name.to_string(), ptr);
     }
 
+    /// Record conditional branch outcomes and call argument values into
+    /// `profile`
+    pub fn set_branch_profile(&mut self, profile: ProfileData) {
+        self.branch_profile = Some(profile);
+    }
+
     /// Set the maximum iterations before triggering JIT bailout
     pub fn set_max_iterations(&mut self, max: u64) {
         self.max_iterations = max;
@@ -1277,6 +1312,7 @@ impl MirInterpreter {
 
                 // Try fast NaN-boxed path first
                 if let Some(result) = l.binary_op(*op, r) {
+                    let result = wrap_small_int(function, *dest, result);
                     self.current_frame_mut().registers.set(*dest, result);
                 } else {
                     // Fall back to InterpValue slow path
@@ -1284,6 +1320,7 @@ impl MirInterpreter {
                     let r_interp = InterpValue::from_nan_boxed(r, &self.object_heap);
                     let result = self.eval_binary_op(*op, l_interp, r_interp)?;
                     let boxed = result.to_nan_boxed(&mut self.object_heap);
+                    let boxed = wrap_small_int(function, *dest, boxed);
                     self.current_frame_mut().registers.set(*dest, boxed);
                 }
             }
@@ -1293,12 +1330,14 @@ impl MirInterpreter {
 
                 // Try fast NaN-boxed path first
                 if let Some(result) = val.unary_op(*op) {
+                    let result = wrap_small_int(function, *dest, result);
                     self.current_frame_mut().registers.set(*dest, result);
                 } else {
                     // Fall back to InterpValue slow path
                     let val_interp = InterpValue::from_nan_boxed(val, &self.object_heap);
                     let result = self.eval_unary_op(*op, val_interp)?;
                     let boxed = result.to_nan_boxed(&mut self.object_heap);
+                    let boxed = wrap_small_int(function, *dest, boxed);
                     self.current_frame_mut().registers.set(*dest, boxed);
                 }
             }
@@ -1341,7 +1380,11 @@ impl MirInterpreter {
                 let ptr_val = self.current_frame().registers.get(*ptr);
                 let val = self.current_frame().registers.get(*value);
                 let ptr_interp = InterpValue::from_nan_boxed(ptr_val, &self.object_heap);
-                let val_interp = InterpValue::from_nan_boxed(val, &self.object_heap);
+                let mut val_interp = InterpValue::from_nan_boxed(val, &self.object_heap);
+                // f32 registers are boxed as f64; narrow so only 4 bytes are written
+                if let Some(IrType::F32) = function.register_types.get(value) {
+                    val_interp = self.cast_value(val_interp, &IrType::F32)?;
+                }
                 self.store_to_ptr(ptr_interp, val_interp)?;
             }
 
@@ -1462,6 +1505,12 @@ impl MirInterpreter {
                         }
                     } else {
                         // Regular user function - execute recursively
+                        if let Some(profile) = &self.branch_profile {
+                            profile.record_call_args(
+                                *func_id,
+                                arg_values.iter().map(InterpValue::as_constant),
+                            );
+                        }
                         self.execute(module, *func_id, arg_values)?
                     }
                 } else if let Some(extern_fn) = module.extern_functions.get(func_id) {
@@ -1903,50 +1952,162 @@ impl MirInterpreter {
                 }
             }
 
-            // === SIMD Vector Operations (not yet supported in interpreter) ===
-            // These are lowered by the JIT compiler; interpreter falls back to void
-            IrInstruction::VectorLoad { dest, .. } => {
-                self.current_frame_mut()
-                    .registers
-                    .set(*dest, NanBoxedValue::void());
-            }
-            IrInstruction::VectorStore { .. } => {
-                // Vector store has no dest, nothing to do
-            }
-            IrInstruction::VectorBinOp { dest, .. } => {
-                self.current_frame_mut()
-                    .registers
-                    .set(*dest, NanBoxedValue::void());
+            // === SIMD Vector Operations (scalar fallback) ===
+            // Vectors are held as an array of lanes and every operation is applied
+            // lane by lane, matching what the JIT backends emit as native vector ops.
+            IrInstruction::VectorLoad { dest, ptr, vec_ty } => {
+                let (elem_ty, count) = vector_shape(vec_ty)?;
+                let ptr_val = self.current_frame().registers.get(*ptr);
+                let base = InterpValue::from_nan_boxed(ptr_val, &self.object_heap).to_usize()?;
+                let mut lanes = Vec::with_capacity(count);
+                for i in 0..count {
+                    let addr = InterpValue::Ptr(base + i * elem_ty.size());
+                    lanes.push(self.load_from_ptr(addr, &elem_ty)?);
+                }
+                self.set_vector(*dest, lanes);
             }
-            IrInstruction::VectorSplat { dest, .. } => {
-                self.current_frame_mut()
-                    .registers
-                    .set(*dest, NanBoxedValue::void());
+            IrInstruction::VectorStore { ptr, value, vec_ty } => {
+                let (elem_ty, _) = vector_shape(vec_ty)?;
+                let ptr_val = self.current_frame().registers.get(*ptr);
+                let base = InterpValue::from_nan_boxed(ptr_val, &self.object_heap).to_usize()?;
+                for (i, lane) in self.vector_lanes(*value)?.into_iter().enumerate() {
+                    let lane = self.cast_value(lane, &elem_ty)?;
+                    self.store_to_ptr(InterpValue::Ptr(base + i * elem_ty.size()), lane)?;
+                }
             }
-            IrInstruction::VectorExtract { dest, .. } => {
-                self.current_frame_mut()
-                    .registers
-                    .set(*dest, NanBoxedValue::void());
+            IrInstruction::VectorBinOp {
+                dest,
+                op,
+                left,
+                right,
+                vec_ty,
+            } => {
+                let (elem_ty, _) = vector_shape(vec_ty)?;
+                let lhs = self.vector_lanes(*left)?;
+                let rhs = self.vector_lanes(*right)?;
+                let mut lanes = Vec::with_capacity(lhs.len());
+                for (l, r) in lhs.into_iter().zip(rhs) {
+                    let lane = self.eval_binary_op(*op, l, r)?;
+                    lanes.push(self.cast_value(lane, &elem_ty)?);
+                }
+                self.set_vector(*dest, lanes);
             }
-            IrInstruction::VectorInsert { dest, .. } => {
-                self.current_frame_mut()
-                    .registers
-                    .set(*dest, NanBoxedValue::void());
+            IrInstruction::VectorSplat {
+                dest,
+                scalar,
+                vec_ty,
+            } => {
+                let (elem_ty, count) = vector_shape(vec_ty)?;
+                let val = self.current_frame().registers.get(*scalar);
+                let lane = self.cast_value(
+                    InterpValue::from_nan_boxed(val, &self.object_heap),
+                    &elem_ty,
+                )?;
+                self.set_vector(*dest, vec![lane; count]);
+            }
+            IrInstruction::VectorExtract {
+                dest,
+                vector,
+                index,
+            } => {
+                let lanes = self.vector_lanes(*vector)?;
+                let lane = lanes.into_iter().nth(*index as usize).ok_or_else(|| {
+                    InterpError::RuntimeError(format!("Vector lane {} out of range", index))
+                })?;
+                let lane = match function.register_types.get(dest) {
+                    Some(ty) => self.cast_value(lane, ty)?,
+                    None => lane,
+                };
+                let boxed = lane.to_nan_boxed(&mut self.object_heap);
+                self.current_frame_mut().registers.set(*dest, boxed);
             }
-            IrInstruction::VectorReduce { dest, .. } => {
-                self.current_frame_mut()
-                    .registers
-                    .set(*dest, NanBoxedValue::void());
+            IrInstruction::VectorInsert {
+                dest,
+                vector,
+                scalar,
+                index,
+            } => {
+                let mut lanes = self.vector_lanes(*vector)?;
+                let val = self.current_frame().registers.get(*scalar);
+                let slot = lanes.get_mut(*index as usize).ok_or_else(|| {
+                    InterpError::RuntimeError(format!("Vector lane {} out of range", index))
+                })?;
+                *slot = InterpValue::from_nan_boxed(val, &self.object_heap);
+                self.set_vector(*dest, lanes);
+            }
+            IrInstruction::VectorReduce { dest, op, vector } => {
+                let elem_ty = function.register_types.get(dest).cloned();
+                let mut lanes = self.vector_lanes(*vector)?.into_iter();
+                let mut acc = lanes.next().unwrap_or(InterpValue::Void);
+                for lane in lanes {
+                    acc = self.eval_binary_op(*op, acc, lane)?;
+                    if let Some(ty) = &elem_ty {
+                        acc = self.cast_value(acc, ty)?;
+                    }
+                }
+                let boxed = acc.to_nan_boxed(&mut self.object_heap);
+                self.current_frame_mut().registers.set(*dest, boxed);
             }
-            IrInstruction::VectorUnaryOp { dest, .. } => {
-                self.current_frame_mut()
-                    .registers
-                    .set(*dest, NanBoxedValue::void());
+            IrInstruction::VectorUnaryOp {
+                dest,
+                op,
+                operand,
+                vec_ty,
+            } => {
+                let (elem_ty, _) = vector_shape(vec_ty)?;
+                let mut lanes = Vec::new();
+                for lane in self.vector_lanes(*operand)? {
+                    let lane = if elem_ty.is_float() {
+                        let f = lane.to_f64()?;
+                        InterpValue::F64(match op {
+                            VectorUnaryOpKind::Sqrt => f.sqrt(),
+                            VectorUnaryOpKind::Abs => f.abs(),
+                            VectorUnaryOpKind::Neg => -f,
+                            VectorUnaryOpKind::Ceil => f.ceil(),
+                            VectorUnaryOpKind::Floor => f.floor(),
+                            VectorUnaryOpKind::Trunc => f.trunc(),
+                            VectorUnaryOpKind::Round => f.round_ties_even(),
+                        })
+                    } else {
+                        let n = lane.to_i64()?;
+                        InterpValue::I64(match op {
+                            VectorUnaryOpKind::Abs => n.wrapping_abs(),
+                            VectorUnaryOpKind::Neg => n.wrapping_neg(),
+                            _ => n,
+                        })
+                    };
+                    lanes.push(self.cast_value(lane, &elem_ty)?);
+                }
+                self.set_vector(*dest, lanes);
             }
-            IrInstruction::VectorMinMax { dest, .. } => {
-                self.current_frame_mut()
-                    .registers
-                    .set(*dest, NanBoxedValue::void());
+            IrInstruction::VectorMinMax {
+                dest,
+                op,
+                left,
+                right,
+                vec_ty,
+            } => {
+                let (elem_ty, _) = vector_shape(vec_ty)?;
+                let lhs = self.vector_lanes(*left)?;
+                let rhs = self.vector_lanes(*right)?;
+                let mut lanes = Vec::with_capacity(lhs.len());
+                for (l, r) in lhs.into_iter().zip(rhs) {
+                    let lane = if elem_ty.is_float() {
+                        let (l, r) = (l.to_f64()?, r.to_f64()?);
+                        InterpValue::F64(match op {
+                            VectorMinMaxKind::Min => l.min(r),
+                            VectorMinMaxKind::Max => l.max(r),
+                        })
+                    } else {
+                        let (l, r) = (l.to_i64()?, r.to_i64()?);
+                        InterpValue::I64(match op {
+                            VectorMinMaxKind::Min => l.min(r),
+                            VectorMinMaxKind::Max => l.max(r),
+                        })
+                    };
+                    lanes.push(self.cast_value(lane, &elem_ty)?);
+                }
+                self.set_vector(*dest, lanes);
             }
 
             // === Global Variable Access ===
@@ -1988,7 +2149,7 @@ impl MirInterpreter {
     fn execute_terminator(
         &mut self,
         _module: &IrModule,
-        _function: &IrFunction,
+        function: &IrFunction,
         terminator: &IrTerminator,
     ) -> Result<TerminatorResult, InterpError> {
         match terminator {
@@ -2007,6 +2168,9 @@ impl MirInterpreter {
                     // Slow path: convert to InterpValue
                     InterpValue::from_nan_boxed(cond, &self.object_heap).to_bool()?
                 };
+                if let Some(profile) = &self.branch_profile {
+                    profile.record_branch(function.id, self.current_frame().current_block, is_true);
+                }
                 if is_true {
                     Ok(TerminatorResult::Continue(*true_target))
                 } else {
@@ -2424,6 +2588,22 @@ impl MirInterpreter {
 
     /// Cast a value to a target type
     fn cast_value(&self, val: InterpValue, to_ty: &IrType) -> Result<InterpValue, InterpError> {
+        // Float -> int truncates, saturates and maps NaN to 0 (see IrInstruction::Cast),
+        // which is exactly Rust's `as`
+        if let InterpValue::F32(_) | InterpValue::F64(_) = val {
+            let f = val.to_f64()?;
+            match to_ty {
+                IrType::I8 => return Ok(InterpValue::I8(f as i8)),
+                IrType::I16 => return Ok(InterpValue::I16(f as i16)),
+                IrType::I32 => return Ok(InterpValue::I32(f as i32)),
+                IrType::I64 => return Ok(InterpValue::I64(f as i64)),
+                IrType::U8 => return Ok(InterpValue::U8(f as u8)),
+                IrType::U16 => return Ok(InterpValue::U16(f as u16)),
+                IrType::U32 => return Ok(InterpValue::U32(f as u32)),
+                IrType::U64 => return Ok(InterpValue::U64(f as u64)),
+                _ => {}
+            }
+        }
         match to_ty {
             IrType::Bool => Ok(InterpValue::Bool(val.to_bool()?)),
             IrType::I8 => Ok(InterpValue::I8(val.to_i64()? as i8)),
@@ -2477,6 +2657,24 @@ impl MirInterpreter {
         }
     }
 
+    /// Read a vector register as its lanes
+    fn vector_lanes(&self, reg: IrId) -> Result<Vec<InterpValue>, InterpError> {
+        let val = self.current_frame().registers.get(reg);
+        match InterpValue::from_nan_boxed(val, &self.object_heap) {
+            InterpValue::Array(lanes) => Ok(lanes),
+            other => Err(InterpError::TypeError(format!(
+                "Expected vector value, got {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// Write lanes to a vector register
+    fn set_vector(&mut self, dest: IrId, lanes: Vec<InterpValue>) {
+        let boxed = InterpValue::Array(lanes).to_nan_boxed(&mut self.object_heap);
+        self.current_frame_mut().registers.set(dest, boxed);
+    }
+
     /// Store a value to a pointer
     /// Uses raw pointer access since alloc_heap returns system allocator pointers
     fn store_to_ptr(&mut self, ptr: InterpValue, val: InterpValue) -> Result<(), InterpError> {
@@ -3328,6 +3526,37 @@ impl NativeValue {
     }
 }
 
+/// Element type and lane count of a SIMD vector type
+fn vector_shape(vec_ty: &IrType) -> Result<(IrType, usize), InterpError> {
+    match (vec_ty.vector_element(), vec_ty.vector_count()) {
+        (Some(elem), Some(count)) => Ok((elem.clone(), count)),
+        _ => Err(InterpError::TypeError(format!(
+            "Expected vector type, got {:?}",
+            vec_ty
+        ))),
+    }
+}
+
+/// Wrap an int result to the width of a small (8/16-bit) destination type.
+///
+/// Small ints are held as i32 registers, so arithmetic on them runs at 32
+/// bits; truncating on write matches the Cranelift backend, which computes
+/// at 32 bits and reduces to i8/i16.
+#[inline(always)]
+fn wrap_small_int(function: &IrFunction, dest: IrId, value: NanBoxedValue) -> NanBoxedValue {
+    if !value.is_i32() {
+        return value;
+    }
+    let n = value.as_i32();
+    match function.register_types.get(&dest) {
+        Some(IrType::I8) => NanBoxedValue::from_i32(n as i8 as i32),
+        Some(IrType::U8) => NanBoxedValue::from_i32(n as u8 as i32),
+        Some(IrType::I16) => NanBoxedValue::from_i32(n as i16 as i32),
+        Some(IrType::U16) => NanBoxedValue::from_i32(n as u16 as i32),
+        _ => value,
+    }
+}
+
 impl Default for MirInterpreter {
     fn default() -> Self {
         Self::new()
@@ -3382,6 +3611,99 @@ mod tests {
         assert!((f64_val.to_f64().unwrap() - 3.14).abs() < 0.001);
     }
 
+    #[test]
+    fn test_nan_survives_boxing() {
+        let mut heap = ObjectHeap::new();
+        for value in [f64::NAN, -f64::NAN, f64::INFINITY, -0.0] {
+            let boxed = InterpValue::F64(value).to_nan_boxed(&mut heap);
+            assert!(boxed.is_f64(), "{} boxed as a tagged value", value);
+            match InterpValue::from_nan_boxed(boxed, &heap) {
+                InterpValue::F64(v) if value.is_nan() => assert!(v.is_nan()),
+                InterpValue::F64(v) => assert_eq!(v.to_bits(), value.to_bits()),
+                other => panic!("{} read back as {:?}", value, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_branch_profile() {
+        use crate::ir::mir_builder::MirBuilder;
+
+        let mut builder = MirBuilder::new("test");
+        let func = builder
+            .begin_function("sign")
+            .param("c", IrType::Bool)
+            .returns(IrType::I32)
+            .build();
+        builder.set_current_function(func);
+        let entry = builder.create_block("entry");
+        let then_block = builder.create_block("then");
+        let else_block = builder.create_block("else");
+        builder.set_insert_point(entry);
+        let c = builder.get_param(0);
+        builder.cond_br(c, then_block, else_block);
+        builder.set_insert_point(then_block);
+        let one = builder.const_i32(1);
+        builder.ret(Some(one));
+        builder.set_insert_point(else_block);
+        let zero = builder.const_i32(0);
+        builder.ret(Some(zero));
+        let module = builder.finish();
+
+        let profile = ProfileData::new(Default::default());
+        let mut interp = MirInterpreter::new();
+        interp.set_branch_profile(profile.clone());
+        for taken in [true, true, false] {
+            interp
+                .execute(&module, func, vec![InterpValue::Bool(taken)])
+                .unwrap();
+        }
+        assert_eq!(profile.branch_counts_for(func)[&entry], (2, 1));
+    }
+
+    #[test]
+    fn test_vector_ops_scalar_fallback() {
+        use crate::ir::mir_builder::MirBuilder;
+        use crate::stdlib::systems::build_systems_types;
+
+        let mut builder = MirBuilder::new("test");
+        build_systems_types(&mut builder);
+        let make = builder.get_function_by_name("SIMD4f_make").unwrap();
+        let shuffle = builder.get_function_by_name("SIMD4f_shuffle").unwrap();
+        let extract = builder.get_function_by_name("SIMD4f_extract").unwrap();
+
+        // lane(i) = ([1, 2, 3, 4] * [1, 2, 3, 4]).shuffle(3, 2, 1, 0)[i]
+        let func = builder
+            .begin_function("lane")
+            .param("i", IrType::I32)
+            .returns(IrType::F64)
+            .build();
+        builder.set_current_function(func);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let i = builder.get_param(0);
+        let xs: Vec<IrId> = (1..=4)
+            .map(|n| builder.const_value(IrValue::F32(n as f32)))
+            .collect();
+        let v = builder.call(make, xs).unwrap();
+        let sq = builder.vector_bin_op(BinaryOp::Mul, v, v, IrType::vector(IrType::F32, 4));
+        let idx: Vec<IrId> = [3, 2, 1, 0].iter().map(|&n| builder.const_i32(n)).collect();
+        let mut args = vec![sq];
+        args.extend(idx);
+        let r = builder.call(shuffle, args).unwrap();
+        let lane = builder.call(extract, vec![r, i]).unwrap();
+        builder.ret(Some(lane));
+        let module = builder.finish();
+
+        let mut interp = MirInterpreter::new();
+        for (i, expected) in [16.0, 9.0, 4.0, 1.0, 16.0].iter().enumerate() {
+            let result = interp
+                .execute(&module, func, vec![InterpValue::I32(i as i32)])
+                .unwrap();
+            assert_eq!(result.to_f64().unwrap(), *expected, "lane {}", i);
+        }
+    }
+
     #[test]
     fn test_register_file() {
         let mut regs = RegisterFile::new(10);
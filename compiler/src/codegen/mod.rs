@@ -5,20 +5,27 @@
 /// - MIR Interpreter (instant startup, Phase 0)
 /// - Cranelift (JIT with tiered compilation, Phases 1-3)
 /// - LLVM (maximum optimization, Phase 4)
-/// - WebAssembly (cross-platform AOT - future)
+/// - WebAssembly (LLVM AOT to wasm32-wasi)
 pub mod aot_compiler;
+pub mod aot_symbols;
+pub mod backend;
 pub mod cranelift_backend;
 mod instruction_lowering;
 pub mod llvm_aot_backend;
 pub mod llvm_jit_backend;
 pub mod mir_interpreter;
+pub mod module_loader;
+pub mod native_listing;
 pub mod profiling;
+pub mod size_report;
 pub mod tiered_backend;
+pub mod wasi_runtime;
 
 // Apple Silicon-specific JIT memory management
 #[cfg(all(target_arch = "aarch64", target_os = "macos"))]
 pub mod apple_jit_memory;
 
+pub use backend::{BackendFactory, BackendRegistry, CodegenBackend};
 pub use cranelift_backend::CraneliftBackend;
 pub use mir_interpreter::{
     DecodedBlock, DecodedInstruction, HeapObject, InterpError, InterpValue, MirInterpreter,
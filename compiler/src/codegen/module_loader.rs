@@ -0,0 +1,260 @@
+//! Loading bundles into a running program (`rayzor.Runtime.loadModule`)
+//!
+//! Each loaded bundle is JIT-compiled by a backend of its own: function ids
+//! are only unique within one compilation, so separately built bundles can't
+//! share a backend. They are linked by symbol instead. The host registers
+//! the functions it has compiled with [`ModuleHost::register`], each loaded
+//! bundle adds its own, and a bundle's references into a module that is
+//! already loaded resolve to the running copy. A plugin therefore shares
+//! classes and statics with the program that loaded it, and bundles loaded
+//! later can call into it.
+//!
+//! A bundle module is skipped only when every function it defines is
+//! already loaded; otherwise (e.g. the host was tree-shaken, or runs
+//! interpreted and has no compiled functions to link against) the bundle's
+//! own copy is loaded. Loaded code is never unloaded.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use super::tiered_backend::{TieredBackend, TieredConfig};
+use crate::ir::blade::{load_bundle, AbiCompatibility, BundleAbi, RayzorBundle};
+use crate::ir::module_init::{INIT_FUNCTION, VTABLE_INIT_FUNCTION};
+use crate::ir::{IrFunction, IrModule};
+
+/// Loads bundles on behalf of a running program
+pub struct ModuleHost {
+    config: TieredConfig,
+    /// Runtime and plugin symbols the host was linked with
+    symbols: Vec<(String, usize)>,
+    /// Compiled functions of the host and of loaded bundles, by the names
+    /// modules link against
+    exports: Mutex<HashMap<String, usize>>,
+}
+
+impl ModuleHost {
+    /// A host whose bundles are compiled with `config` and linked against
+    /// `symbols` (the host's own runtime and plugin symbols)
+    pub fn new(config: TieredConfig, symbols: &[(&str, *const u8)]) -> Self {
+        Self {
+            config,
+            symbols: symbols
+                .iter()
+                .map(|(name, ptr)| (name.to_string(), *ptr as usize))
+                .collect(),
+            exports: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Make the functions `backend` has JIT-compiled available to bundles
+    /// loaded later. Call after `TieredBackend::ensure_compiled`.
+    pub fn register(&self, backend: &TieredBackend) {
+        let mut exports = self.exports.lock().unwrap();
+        for (name, addr) in backend.exported_symbols() {
+            exports.entry(name).or_insert(addr);
+        }
+    }
+
+    /// Route `rayzor.Runtime.loadModule` to this host
+    pub fn install(self: Arc<Self>) {
+        rayzor_runtime::module_loader::set_loader(Arc::new(
+            move |path: &str, entry: Option<&str>| self.load(Path::new(path), entry),
+        ));
+    }
+
+    /// Load the bundle at `path`, run the initializers of the modules it
+    /// adds, then run `entry` (a named entry point) or the bundle's default
+    /// entry point, if it has one.
+    pub fn load(&self, path: &Path, entry: Option<&str>) -> Result<(), String> {
+        let bundle = open_bundle(path)?;
+        let entry = find_entry(&bundle, entry).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        // The lock is not held while compiled code runs: initializers and
+        // entry points may load further bundles
+        let exports = self.exports.lock().unwrap().clone();
+        let (loaded, new): (Vec<&IrModule>, Vec<&IrModule>) = bundle
+            .modules()
+            .iter()
+            .partition(|module| is_loaded(module, &exports));
+        log::debug!(
+            "[module_loader] {}: {} new modules, {} already loaded",
+            path.display(),
+            new.len(),
+            loaded.len()
+        );
+
+        let backend = if new.is_empty() {
+            None
+        } else {
+            let backend = self.compile(&new, &exports)?;
+            for module in &new {
+                for name in [VTABLE_INIT_FUNCTION, INIT_FUNCTION] {
+                    if let Some(init) = find_function(module, name) {
+                        backend.execute_function(init.id, vec![]).map_err(|e| {
+                            format!(
+                                "{}: {} of {} failed: {}",
+                                path.display(),
+                                name,
+                                module.name,
+                                e
+                            )
+                        })?;
+                    }
+                }
+            }
+            self.register(backend);
+            Some(backend)
+        };
+
+        let Some((module, function)) = entry else {
+            return Ok(());
+        };
+        match backend {
+            Some(backend) if new.iter().any(|m| m.name == module.name) => backend
+                .execute_function(function.id, vec![])
+                .map(|_| ())
+                .map_err(|e| format!("{}: entry point failed: {}", path.display(), e)),
+            _ => {
+                // The entry point's module was already loaded: call the
+                // running copy
+                let name = link_name(function);
+                let addr = *self.exports.lock().unwrap().get(name).ok_or_else(|| {
+                    format!("{}: entry point {} is not compiled", path.display(), name)
+                })?;
+                unsafe {
+                    // Haxe calling convention: null environment pointer
+                    let entry_fn: extern "C" fn(i64) = std::mem::transmute(addr);
+                    entry_fn(0);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// JIT-compile `modules`, linked against the host's symbols and
+    /// everything loaded so far. The backend is leaked: its code stays
+    /// reachable through the exports for the rest of the program.
+    fn compile(
+        &self,
+        modules: &[&IrModule],
+        exports: &HashMap<String, usize>,
+    ) -> Result<&'static mut TieredBackend, String> {
+        let mut symbols: Vec<(&str, *const u8)> = self
+            .symbols
+            .iter()
+            .map(|(name, addr)| (name.as_str(), *addr as *const u8))
+            .collect();
+        let shadowed: std::collections::HashSet<&str> =
+            symbols.iter().map(|(name, _)| *name).collect();
+        symbols.extend(
+            exports
+                .iter()
+                .filter(|(name, _)| !shadowed.contains(name.as_str()))
+                .map(|(name, addr)| (name.as_str(), *addr as *const u8)),
+        );
+
+        // Exports are function addresses, so the bundle is compiled up
+        // front rather than interpreted
+        let mut config = self.config.clone();
+        config.start_interpreted = false;
+        let mut backend = TieredBackend::with_symbols(config, &symbols)?;
+        for module in modules {
+            backend
+                .compile_module((*module).clone())
+                .map_err(|e| format!("Failed to compile module '{}': {}", module.name, e))?;
+        }
+        backend.ensure_compiled()?;
+        Ok(Box::leak(Box::new(backend)))
+    }
+}
+
+/// Load a bundle, recompiling it from embedded sources if only the stdlib
+/// changed since it was built
+fn open_bundle(path: &Path) -> Result<RayzorBundle, String> {
+    let bundle =
+        load_bundle(path).map_err(|e| format!("Failed to load {}: {}", path.display(), e))?;
+    match bundle.build_info().abi.check(&BundleAbi::current()) {
+        AbiCompatibility::Compatible => Ok(bundle),
+        AbiCompatibility::StdlibChanged if !bundle.sources().is_empty() => {
+            crate::tools::preblade::recompile_bundle(&bundle, false)
+        }
+        AbiCompatibility::StdlibChanged => Err(format!(
+            "{} was built against a different stdlib and has no embedded sources",
+            path.display()
+        )),
+        AbiCompatibility::Incompatible(reason) => {
+            Err(format!("{} can't be loaded: {}", path.display(), reason))
+        }
+    }
+}
+
+/// The entry point to run: the named one, or the default if the bundle has
+/// one. Entry points run without arguments.
+fn find_entry<'a>(
+    bundle: &'a RayzorBundle,
+    name: Option<&str>,
+) -> Result<Option<(&'a IrModule, &'a IrFunction)>, String> {
+    let (module, function) = match name {
+        Some(name) => {
+            let entry = bundle.find_entry_point(name).ok_or_else(|| {
+                let names: Vec<&str> = bundle
+                    .entry_points()
+                    .iter()
+                    .map(|e| e.name.as_str())
+                    .collect();
+                if names.is_empty() {
+                    format!("no entry point '{}' (the bundle has none)", name)
+                } else {
+                    format!("no entry point '{}' (has: {})", name, names.join(", "))
+                }
+            })?;
+            (entry.module.as_str(), entry.function.as_str())
+        }
+        None => match bundle.entry_module() {
+            Some(module) => (module.name.as_str(), bundle.entry_function()),
+            None => return Ok(None),
+        },
+    };
+
+    let Some(module) = bundle.get_module(module) else {
+        return Err(format!("entry point module {} is missing", module));
+    };
+    let Some(function) = find_function(module, function) else {
+        return match name {
+            Some(_) => Err(format!("entry point {} is missing", function)),
+            None => Ok(None),
+        };
+    };
+    if !function.signature.parameters.is_empty() {
+        return Err(format!(
+            "entry point {} takes arguments",
+            link_name(function)
+        ));
+    }
+    Ok(Some((module, function)))
+}
+
+/// Function with a body named `name`
+fn find_function<'a>(module: &'a IrModule, name: &str) -> Option<&'a IrFunction> {
+    module
+        .functions
+        .values()
+        .find(|f| f.name == name && !f.cfg.blocks.is_empty())
+}
+
+/// Name other modules link against a function by
+fn link_name(function: &IrFunction) -> &str {
+    function.qualified_name.as_deref().unwrap_or(&function.name)
+}
+
+/// Whether every function `module` defines is already loaded
+fn is_loaded(module: &IrModule, exports: &HashMap<String, usize>) -> bool {
+    let mut defined = module
+        .functions
+        .values()
+        .filter(|f| !f.cfg.blocks.is_empty())
+        .filter_map(|f| f.qualified_name.as_deref())
+        .peekable();
+    defined.peek().is_some() && defined.all(|name| exports.contains_key(name))
+}
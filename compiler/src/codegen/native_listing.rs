@@ -0,0 +1,256 @@
+//! Annotated machine code listings (`rayzor dump --emit native`)
+//!
+//! When listings are enabled, the Cranelift backend tags every Cranelift
+//! instruction with the MIR instruction it was lowered from (as a Cranelift
+//! source location) and records, per function, the machine code, the code
+//! ranges belonging to each MIR instruction, and Cranelift's own
+//! disassembly. [`format_listing`] then prints the code in address order,
+//! like `objdump -S`: the Haxe source line and MIR instruction each range
+//! came from, followed by its bytes. Code that belongs to no MIR instruction
+//! (prologue, epilogue, register moves inserted by the allocator) is shown
+//! under `<no MIR instruction>`.
+
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::ir::dump::{dump_instruction, dump_phi, dump_terminator};
+use crate::ir::{IrBlockId, IrFunction, IrFunctionId, IrModule};
+
+/// Bytes per line of the hex dump
+const BYTES_PER_LINE: usize = 8;
+
+/// Which functions get a listing
+#[derive(Debug, Clone)]
+pub struct ListingFilter {
+    /// Only functions of this MIR module
+    pub module: String,
+    /// Only functions whose name contains this string
+    pub function: Option<String>,
+}
+
+impl ListingFilter {
+    pub fn matches(&self, module: &IrModule, function: &IrFunction) -> bool {
+        module.name == self.module
+            && self
+                .function
+                .as_ref()
+                .is_none_or(|name| function.name.contains(name.as_str()))
+    }
+}
+
+/// The MIR construct a range of machine code was lowered from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MirPoint {
+    /// Phi nodes at the start of a block
+    BlockEntry(IrBlockId),
+    /// Instruction at an index of a block
+    Instruction(IrBlockId, usize),
+    Terminator(IrBlockId),
+}
+
+impl MirPoint {
+    fn block(self) -> IrBlockId {
+        match self {
+            MirPoint::BlockEntry(block)
+            | MirPoint::Instruction(block, _)
+            | MirPoint::Terminator(block) => block,
+        }
+    }
+}
+
+/// Machine code of one compiled function
+#[derive(Debug, Clone)]
+pub struct NativeListing {
+    pub function: IrFunctionId,
+    pub code: Vec<u8>,
+    /// MIR constructs in lowering order; the Cranelift source location of
+    /// each instruction is an index into this list
+    pub points: Vec<MirPoint>,
+    /// Code ranges and the index of the point they came from, by offset
+    pub ranges: Vec<(Range<u32>, usize)>,
+    /// Cranelift's disassembly of the function (empty if unavailable)
+    pub disassembly: String,
+}
+
+/// Text of a MIR point
+fn point_text(function: &IrFunction, point: MirPoint) -> String {
+    let Some(block) = function.cfg.blocks.get(&point.block()) else {
+        return "<unknown block>".to_string();
+    };
+    match point {
+        MirPoint::BlockEntry(id) => {
+            let phis: Vec<String> = block.phi_nodes.iter().map(dump_phi).collect();
+            if phis.is_empty() {
+                format!("{}:", id)
+            } else {
+                format!("{}: {}", id, phis.join("; "))
+            }
+        }
+        MirPoint::Instruction(id, index) => match block.instructions.get(index) {
+            Some(inst) => format!("{}: {}", id, dump_instruction(inst)),
+            None => format!("{}: <instruction {}>", id, index),
+        },
+        MirPoint::Terminator(id) => format!("{}: {}", id, dump_terminator(&block.terminator)),
+    }
+}
+
+/// Haxe source line of a MIR point: its block's line, else the function's
+fn point_line(function: &IrFunction, point: MirPoint) -> u32 {
+    function
+        .cfg
+        .blocks
+        .get(&point.block())
+        .map(|b| b.source_location.line)
+        .filter(|&line| line > 0)
+        .unwrap_or(function.source_location.line)
+}
+
+fn write_bytes(out: &mut String, code: &[u8], range: Range<u32>) {
+    let mut offset = range.start as usize;
+    let end = (range.end as usize).min(code.len());
+    while offset < end {
+        let line_end = (offset + BYTES_PER_LINE).min(end);
+        let bytes: Vec<String> = code[offset..line_end]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let _ = writeln!(out, "  {:06x}:  {}", offset, bytes.join(" "));
+        offset = line_end;
+    }
+}
+
+/// Format a listing of `function`. `source` is the Haxe file the function
+/// came from, used to print source lines.
+pub fn format_listing(
+    listing: &NativeListing,
+    function: &IrFunction,
+    source: Option<&str>,
+) -> String {
+    let source_lines: Vec<&str> = source.map(|s| s.lines().collect()).unwrap_or_default();
+    let mut out = String::new();
+
+    // Fill the gaps between attributed ranges
+    let mut ranges: Vec<(Range<u32>, Option<usize>)> = Vec::new();
+    let mut offset = 0u32;
+    for (range, point) in &listing.ranges {
+        if range.start > offset {
+            ranges.push((offset..range.start, None));
+        }
+        ranges.push((range.clone(), Some(*point)));
+        offset = offset.max(range.end);
+    }
+    if (offset as usize) < listing.code.len() {
+        ranges.push((offset..listing.code.len() as u32, None));
+    }
+
+    let mut last_line = 0;
+    let mut last_point = None;
+    for (range, point) in ranges {
+        let point = point.and_then(|p| listing.points.get(p).copied());
+        if let Some(point) = point {
+            let line = point_line(function, point);
+            if line > 0 && line != last_line {
+                let text = source_lines
+                    .get(line as usize - 1)
+                    .map(|l| l.trim())
+                    .unwrap_or("");
+                let _ = writeln!(out, "; line {}: {}", line, text);
+                last_line = line;
+            }
+        }
+        if point != last_point || point.is_none() {
+            match point {
+                Some(point) => {
+                    let _ = writeln!(out, ";   {}", point_text(function, point));
+                }
+                None => {
+                    let _ = writeln!(out, ";   <no MIR instruction>");
+                }
+            }
+            last_point = point;
+        }
+        write_bytes(&mut out, &listing.code, range);
+    }
+
+    if !listing.disassembly.is_empty() {
+        let _ = writeln!(out, "\n; Cranelift disassembly");
+        for line in listing.disassembly.lines() {
+            let _ = writeln!(out, "; {}", line);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::mir_builder::MirBuilder;
+    use crate::ir::IrType;
+
+    fn function() -> (IrFunction, IrBlockId) {
+        let mut builder = MirBuilder::new("Main");
+        let func = builder
+            .begin_function("add")
+            .param("x", IrType::I32)
+            .returns(IrType::I32)
+            .build();
+        builder.set_current_function(func);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let x = builder.get_param(0);
+        let sum = builder.add(x, x, IrType::I32);
+        builder.ret(Some(sum));
+        let mut module = builder.finish();
+        let mut function = module.functions.remove(&func).unwrap();
+        function.source_location.line = 2;
+        (function, entry)
+    }
+
+    #[test]
+    fn test_filter() {
+        let module = IrModule::new("Main".to_string(), "Main.hx".to_string());
+        let (function, _) = function();
+        let mut filter = ListingFilter {
+            module: "Main".to_string(),
+            function: None,
+        };
+        assert!(filter.matches(&module, &function));
+        filter.function = Some("ad".to_string());
+        assert!(filter.matches(&module, &function));
+        filter.function = Some("main".to_string());
+        assert!(!filter.matches(&module, &function));
+    }
+
+    #[test]
+    fn test_format_listing() {
+        let (function, entry) = function();
+        let listing = NativeListing {
+            function: function.id,
+            code: (0u8..20).collect(),
+            points: vec![
+                MirPoint::BlockEntry(entry),
+                MirPoint::Instruction(entry, 0),
+                MirPoint::Terminator(entry),
+            ],
+            ranges: vec![(4..14, 1), (14..16, 2)],
+            disassembly: "block0:\n  add w0, w0, w0\n  ret\n".to_string(),
+        };
+        let source = "class Main {\n    static function add(x:Int) return x + x;\n}\n";
+        let text = format_listing(&listing, &function, Some(source));
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines[0], ";   <no MIR instruction>");
+        assert_eq!(lines[1], "  000000:  00 01 02 03");
+        assert_eq!(
+            lines[2],
+            "; line 2: static function add(x:Int) return x + x;"
+        );
+        assert!(lines[3].starts_with(&format!(";   {}: ", entry)));
+        assert_eq!(lines[4], "  000004:  04 05 06 07 08 09 0a 0b");
+        assert_eq!(lines[5], "  00000c:  0c 0d");
+        assert!(lines[6].starts_with(&format!(";   {}: ret", entry)));
+        assert_eq!(lines[8], ";   <no MIR instruction>");
+        assert_eq!(lines[9], "  000010:  10 11 12 13");
+        assert!(text.contains("; Cranelift disassembly\n; block0:\n;   add w0, w0, w0"));
+    }
+}
@@ -8,10 +8,16 @@
 //! - Configurable thresholds for warm/hot detection
 //! - Sample-based profiling to reduce overhead
 //! - Per-function execution tracking
-
-use crate::ir::IrFunctionId;
+//! - Per-branch taken/not-taken counts for branch hinting and call-site
+//!   weighted inlining on recompiles
+//! - Per-parameter leading values for constant argument specialization
+
+use crate::ir::branch_hints::BranchCounts;
+use crate::ir::inlining::CallProfile;
+use crate::ir::specialization::{ArgValueCount, ArgValues};
+use crate::ir::{IrBlockId, IrFunctionId};
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 
 /// Runtime profiling data collector
@@ -20,10 +26,56 @@ pub struct ProfileData {
     /// Per-function execution counters (lock-free atomic)
     function_counts: Arc<RwLock<HashMap<IrFunctionId, Arc<AtomicU64>>>>,
 
+    /// Per-conditional-branch counters: [taken, not taken], keyed by the
+    /// block ending in the branch
+    branch_counts: Arc<RwLock<HashMap<(IrFunctionId, IrBlockId), Arc<[AtomicU64; 2]>>>>,
+
+    /// Leading integer/Bool value of each function parameter, by function
+    arg_values: Arc<RwLock<HashMap<IrFunctionId, Arc<[ArgCounter]>>>>,
+
     /// Configuration for hotness detection
     config: ProfileConfig,
 }
 
+/// Lock-free [`ArgValueCount`] of one parameter. Its fields are updated
+/// separately, so racing calls can skew the counts slightly, which only
+/// affects which functions get specialized.
+#[derive(Default)]
+struct ArgCounter {
+    value: AtomicI64,
+    lead: AtomicU64,
+    total: AtomicU64,
+}
+
+impl ArgCounter {
+    /// Count one call that passed `value`
+    fn record(&self, value: i64) {
+        self.total.fetch_add(1, Ordering::Relaxed);
+        if self.lead.load(Ordering::Relaxed) == 0 {
+            self.value.store(value, Ordering::Relaxed);
+            self.lead.store(1, Ordering::Relaxed);
+        } else if self.value.load(Ordering::Relaxed) == value {
+            self.lead.fetch_add(1, Ordering::Relaxed);
+        } else {
+            let _ = self
+                .lead
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |lead| {
+                    lead.checked_sub(1)
+                });
+        }
+    }
+
+    /// The counts so far, if any call passed a constant
+    fn snapshot(&self) -> Option<ArgValueCount> {
+        let total = self.total.load(Ordering::Relaxed);
+        (total > 0).then(|| ArgValueCount {
+            value: self.value.load(Ordering::Relaxed),
+            lead: self.lead.load(Ordering::Relaxed),
+            total,
+        })
+    }
+}
+
 /// Configuration for profiling and hotness detection (5-tier system with interpreter)
 #[derive(Debug, Clone, Copy)]
 pub struct ProfileConfig {
@@ -87,6 +139,8 @@ impl ProfileData {
     pub fn new(config: ProfileConfig) -> Self {
         Self {
             function_counts: Arc::new(RwLock::new(HashMap::new())),
+            branch_counts: Arc::new(RwLock::new(HashMap::new())),
+            arg_values: Arc::new(RwLock::new(HashMap::new())),
             config,
         }
     }
@@ -109,6 +163,95 @@ impl ProfileData {
             .unwrap_or(0)
     }
 
+    /// Record the outcome of the conditional branch ending `block`
+    pub fn record_branch(&self, func_id: IrFunctionId, block: IrBlockId, taken: bool) {
+        let key = (func_id, block);
+        let existing = self.branch_counts.read().unwrap().get(&key).cloned();
+        let counter = existing.unwrap_or_else(|| {
+            self.branch_counts
+                .write()
+                .unwrap()
+                .entry(key)
+                .or_insert_with(|| Arc::new([AtomicU64::new(0), AtomicU64::new(0)]))
+                .clone()
+        });
+        let side = if taken { 0 } else { 1 };
+        counter[side].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Taken/not-taken counts of every profiled branch in a function
+    pub fn branch_counts_for(&self, func_id: IrFunctionId) -> BranchCounts {
+        let counts = self.branch_counts.read().unwrap();
+        counts
+            .iter()
+            .filter(|((func, _), _)| *func == func_id)
+            .map(|((_, block), c)| {
+                let taken = c[0].load(Ordering::Relaxed);
+                let not_taken = c[1].load(Ordering::Relaxed);
+                (*block, (taken, not_taken))
+            })
+            .collect()
+    }
+
+    /// Record the arguments of a call to `func_id`; None for arguments that
+    /// aren't integers or Bools
+    pub fn record_call_args(
+        &self,
+        func_id: IrFunctionId,
+        args: impl ExactSizeIterator<Item = Option<i64>>,
+    ) {
+        let existing = self.arg_values.read().unwrap().get(&func_id).cloned();
+        let counters = existing.unwrap_or_else(|| {
+            self.arg_values
+                .write()
+                .unwrap()
+                .entry(func_id)
+                .or_insert_with(|| (0..args.len()).map(|_| ArgCounter::default()).collect())
+                .clone()
+        });
+        for (counter, arg) in counters.iter().zip(args) {
+            if let Some(value) = arg {
+                counter.record(value);
+            }
+        }
+    }
+
+    /// Leading argument values of every profiled call, for constant
+    /// argument specialization on recompiles
+    pub fn arg_values(&self) -> ArgValues {
+        let counters = self.arg_values.read().unwrap();
+        counters
+            .iter()
+            .flat_map(|(func_id, params)| {
+                params
+                    .iter()
+                    .enumerate()
+                    .filter_map(move |(index, counter)| {
+                        counter.snapshot().map(|count| ((*func_id, index), count))
+                    })
+            })
+            .collect()
+    }
+
+    /// Branch counts of every profiled function, for weighing call sites
+    /// when inlining on recompiles
+    pub fn call_profile(&self) -> CallProfile {
+        let counts = self.branch_counts.read().unwrap();
+        let mut branch_counts: HashMap<IrFunctionId, BranchCounts> = HashMap::new();
+        for ((func, block), c) in counts.iter() {
+            let taken = c[0].load(Ordering::Relaxed);
+            let not_taken = c[1].load(Ordering::Relaxed);
+            branch_counts
+                .entry(*func)
+                .or_default()
+                .insert(*block, (taken, not_taken));
+        }
+        CallProfile {
+            branch_counts,
+            hot_threshold: self.config.hot_threshold,
+        }
+    }
+
     /// Check if a function should be JIT compiled (executed enough in interpreter)
     /// This promotes from Phase 0 (Interpreted) to Phase 1 (Baseline JIT)
     pub fn should_jit_compile(&self, func_id: IrFunctionId) -> bool {
@@ -182,6 +325,8 @@ impl ProfileData {
     pub fn reset(&self) {
         let mut counts = self.function_counts.write().unwrap();
         counts.clear();
+        self.branch_counts.write().unwrap().clear();
+        self.arg_values.write().unwrap().clear();
     }
 
     /// Get a function's counter reference for direct instrumentation
@@ -375,6 +520,53 @@ mod tests {
         assert_eq!(stats.total_executions, 550);
     }
 
+    #[test]
+    fn test_branch_counts() {
+        let profile = ProfileData::new(ProfileConfig::default());
+        let func1 = IrFunctionId(SymbolId(1).into());
+        let func2 = IrFunctionId(SymbolId(2).into());
+
+        for i in 0..10 {
+            profile.record_branch(func1, IrBlockId(0), i < 7);
+        }
+        profile.record_branch(func1, IrBlockId(3), false);
+        profile.record_branch(func2, IrBlockId(0), true);
+
+        let counts = profile.branch_counts_for(func1);
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[&IrBlockId(0)], (7, 3));
+        assert_eq!(counts[&IrBlockId(3)], (0, 1));
+        assert_eq!(profile.branch_counts_for(func2)[&IrBlockId(0)], (1, 0));
+
+        profile.reset();
+        assert!(profile.branch_counts_for(func1).is_empty());
+    }
+
+    #[test]
+    fn test_record_call_args() {
+        let profile = ProfileData::new(ProfileConfig::default());
+        let func_id = IrFunctionId(SymbolId(7).into());
+
+        for flag in [1, 1, 0, 1] {
+            profile.record_call_args(func_id, [Some(flag), None].into_iter());
+        }
+
+        let values = profile.arg_values();
+        assert_eq!(
+            values[&(func_id, 0)],
+            ArgValueCount {
+                value: 1,
+                lead: 2,
+                total: 4
+            }
+        );
+        // Never passed a constant
+        assert!(!values.contains_key(&(func_id, 1)));
+
+        profile.reset();
+        assert!(profile.arg_values().is_empty());
+    }
+
     #[test]
     fn test_atomic_counter_thread_safety() {
         let profile = ProfileData::new(ProfileConfig::default());
@@ -0,0 +1,299 @@
+//! Binary size breakdown for AOT executables (`--emit size-report`)
+//!
+//! Reads symbol sizes from the linked binary with `nm` and attributes each
+//! symbol to the Haxe class it was compiled from, using the LLVM names the
+//! backend assigned to MIR functions. Symbols with no Haxe origin are
+//! reported as `<runtime>` (Rayzor runtime) or `<native>` (libc, Rust std).
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+use crate::ir::{IrFunctionId, IrModule};
+
+/// Owner of symbols from the Rayzor runtime library
+pub const RUNTIME_OWNER: &str = "<runtime>";
+/// Owner of symbols from the C library, Rust std and other native code
+pub const NATIVE_OWNER: &str = "<native>";
+
+/// Bytes attributed to one Haxe class (or runtime/native bucket)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizeEntry {
+    /// Dotted package path; empty for the default package
+    pub package: String,
+    /// Class name, or the bucket name for non-Haxe symbols
+    pub class: String,
+    pub bytes: u64,
+    pub symbols: usize,
+}
+
+/// Size breakdown of a linked binary
+#[derive(Debug, Clone)]
+pub struct SizeReport {
+    /// Size of the binary file on disk
+    pub binary_size: u64,
+    /// Entries sorted by size, largest first
+    pub entries: Vec<SizeEntry>,
+}
+
+impl SizeReport {
+    /// Read the symbols of `binary` and group them by owner
+    pub fn from_binary(
+        binary: &Path,
+        owners: &HashMap<String, String>,
+    ) -> Result<SizeReport, String> {
+        let binary_size = std::fs::metadata(binary)
+            .map_err(|e| format!("Failed to read {}: {}", binary.display(), e))?
+            .len();
+        let symbols = read_symbol_sizes(binary)?;
+        Ok(Self::from_symbols(binary_size, &symbols, owners))
+    }
+
+    /// Group `(symbol, size)` pairs by the owner recorded in `owners`
+    pub fn from_symbols(
+        binary_size: u64,
+        symbols: &[(String, u64)],
+        owners: &HashMap<String, String>,
+    ) -> SizeReport {
+        let mut groups: HashMap<&str, (u64, usize)> = HashMap::new();
+        for (name, size) in symbols {
+            // Mach-O prefixes C symbols with an underscore
+            let owner = owners
+                .get(name)
+                .or_else(|| name.strip_prefix('_').and_then(|n| owners.get(n)))
+                .map(String::as_str)
+                .unwrap_or_else(|| native_owner(name));
+            let group = groups.entry(owner).or_default();
+            group.0 += size;
+            group.1 += 1;
+        }
+
+        let mut entries: Vec<SizeEntry> = groups
+            .into_iter()
+            .map(|(owner, (bytes, symbols))| {
+                let (package, class) = split_owner(owner);
+                SizeEntry {
+                    package: package.to_string(),
+                    class: class.to_string(),
+                    bytes,
+                    symbols,
+                }
+            })
+            .collect();
+        entries.sort_by(|a, b| {
+            b.bytes
+                .cmp(&a.bytes)
+                .then_with(|| (&a.package, &a.class).cmp(&(&b.package, &b.class)))
+        });
+        SizeReport {
+            binary_size,
+            entries,
+        }
+    }
+
+    /// Total bytes of all sized symbols
+    pub fn symbol_bytes(&self) -> u64 {
+        self.entries.iter().map(|e| e.bytes).sum()
+    }
+
+    /// Bytes per package, largest first
+    pub fn packages(&self) -> Vec<(&str, u64)> {
+        let mut totals: HashMap<&str, u64> = HashMap::new();
+        for entry in &self.entries {
+            *totals.entry(self.package_label(entry)).or_default() += entry.bytes;
+        }
+        let mut packages: Vec<(&str, u64)> = totals.into_iter().collect();
+        packages.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        packages
+    }
+
+    fn package_label<'a>(&self, entry: &'a SizeEntry) -> &'a str {
+        if entry.class.starts_with('<') {
+            &entry.class
+        } else if entry.package.is_empty() {
+            "(default)"
+        } else {
+            &entry.package
+        }
+    }
+}
+
+impl fmt::Display for SizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.symbol_bytes().max(1);
+        let percent = |bytes: u64| bytes as f64 * 100.0 / total as f64;
+
+        writeln!(
+            f,
+            "Binary size: {} bytes ({} bytes in sized symbols)",
+            self.binary_size,
+            self.symbol_bytes()
+        )?;
+        writeln!(f, "{:>10}  {:>6}  package / class", "bytes", "%")?;
+        for (package, bytes) in self.packages() {
+            writeln!(f, "{:>10}  {:>5.1}%  {}", bytes, percent(bytes), package)?;
+            for entry in &self.entries {
+                if entry.class.starts_with('<') || self.package_label(entry) != package {
+                    continue;
+                }
+                writeln!(
+                    f,
+                    "{:>10}  {:>5.1}%    {} ({} symbols)",
+                    entry.bytes,
+                    percent(entry.bytes),
+                    entry.class,
+                    entry.symbols
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Map LLVM symbol names to their owning class (`pkg.Class`)
+pub fn symbol_owners(
+    modules: &[IrModule],
+    llvm_names: &HashMap<IrFunctionId, String>,
+) -> HashMap<String, String> {
+    let mut owners = HashMap::new();
+    for module in modules {
+        for (func_id, func) in &module.functions {
+            let Some(llvm_name) = llvm_names.get(func_id) else {
+                continue;
+            };
+            let owner = match func.qualified_name.as_deref() {
+                Some(qualified) => match qualified.rsplit_once('.') {
+                    Some((class, _method)) => class.to_string(),
+                    None => module.name.clone(),
+                },
+                None => module.name.clone(),
+            };
+            owners.insert(llvm_name.clone(), owner);
+        }
+    }
+    owners
+}
+
+/// Sized symbols of a binary, via `llvm-nm` or the system `nm`
+fn read_symbol_sizes(binary: &Path) -> Result<Vec<(String, u64)>, String> {
+    let nm = super::llvm_aot_backend::find_llvm_tool("llvm-nm").unwrap_or_else(|| "nm".into());
+    let output = Command::new(&nm)
+        .arg("--print-size")
+        .arg("--size-sort")
+        .arg(binary)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", nm, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            nm,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_nm_line)
+        .collect())
+}
+
+/// Parse `<address> <size> <type> <name>` (sizes are hex)
+fn parse_nm_line(line: &str) -> Option<(String, u64)> {
+    let mut parts = line.split_whitespace();
+    let _address = parts.next()?;
+    let size = u64::from_str_radix(parts.next()?, 16).ok()?;
+    let _kind = parts.next()?;
+    let name = parts.next()?;
+    Some((name.to_string(), size))
+}
+
+fn native_owner(symbol: &str) -> &'static str {
+    let name = symbol.trim_start_matches('_');
+    if name.starts_with("haxe_") || name.starts_with("rayzor_") || symbol.contains("rayzor_runtime")
+    {
+        RUNTIME_OWNER
+    } else {
+        NATIVE_OWNER
+    }
+}
+
+/// `pkg.sub.Class` -> (`pkg.sub`, `Class`)
+fn split_owner(owner: &str) -> (&str, &str) {
+    if owner.starts_with('<') {
+        return ("", owner);
+    }
+    owner.rsplit_once('.').unwrap_or(("", owner))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::mir_builder::MirBuilder;
+
+    #[test]
+    fn test_parse_nm_line() {
+        assert_eq!(
+            parse_nm_line("0000000000401000 0000000000000040 T Main_main"),
+            Some(("Main_main".to_string(), 0x40))
+        );
+        assert_eq!(parse_nm_line("                 U malloc"), None);
+    }
+
+    #[test]
+    fn test_symbol_owners() {
+        let mut builder = MirBuilder::new("Main");
+        let main = builder.begin_function("main").build();
+        let helper = builder.begin_function("helper").build();
+        let mut module = builder.finish();
+        module.functions.get_mut(&main).unwrap().qualified_name =
+            Some("app.net.Client.main".to_string());
+
+        let llvm_names = HashMap::from([
+            (main, "Client_main".to_string()),
+            (helper, "helper".to_string()),
+        ]);
+        let owners = symbol_owners(&[module], &llvm_names);
+        assert_eq!(owners["Client_main"], "app.net.Client");
+        assert_eq!(owners["helper"], "Main");
+    }
+
+    #[test]
+    fn test_report_groups_by_package_and_class() {
+        let owners = HashMap::from([
+            ("Client_main".to_string(), "app.net.Client".to_string()),
+            ("Client_send".to_string(), "app.net.Client".to_string()),
+            ("Server_run".to_string(), "app.net.Server".to_string()),
+            ("Main_main".to_string(), "Main".to_string()),
+        ]);
+        let symbols: Vec<(String, u64)> = [
+            ("Client_main", 100),
+            ("_Client_send", 50),
+            ("Server_run", 30),
+            ("Main_main", 20),
+            ("haxe_string_concat", 400),
+            ("memcpy", 10),
+        ]
+        .iter()
+        .map(|(n, s)| (n.to_string(), *s))
+        .collect();
+
+        let report = SizeReport::from_symbols(4096, &symbols, &owners);
+        assert_eq!(report.symbol_bytes(), 610);
+        assert_eq!(report.entries[0].class, RUNTIME_OWNER);
+
+        let client = report.entries.iter().find(|e| e.class == "Client").unwrap();
+        assert_eq!(client.package, "app.net");
+        assert_eq!((client.bytes, client.symbols), (150, 2));
+
+        assert_eq!(
+            report.packages(),
+            vec![
+                (RUNTIME_OWNER, 400),
+                ("app.net", 180),
+                ("(default)", 20),
+                (NATIVE_OWNER, 10)
+            ]
+        );
+        assert!(report.to_string().contains("Client (2 symbols)"));
+    }
+}
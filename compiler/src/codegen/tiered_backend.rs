@@ -21,7 +21,7 @@
 //! - Lock-free atomic counters: Minimal overhead profiling
 //! - RwLock for function pointer map: Fast reads, infrequent writes
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
 use std::sync::{Arc, Condvar, Mutex, RwLock};
@@ -284,6 +284,8 @@ impl<'a> Drop for ExecutionGuard<'a> {
 use super::cranelift_backend::CraneliftBackend;
 use super::mir_interpreter::{InterpError, InterpValue, MirInterpreter};
 use super::profiling::{ProfileConfig, ProfileData, ProfileStatistics};
+use crate::ir::escape_summary::EscapeSummaries;
+use crate::ir::optimization::PassSizeLimits;
 use crate::ir::{IrFunction, IrFunctionId, IrInstruction, IrModule};
 
 #[cfg(feature = "llvm-backend")]
@@ -418,6 +420,31 @@ impl OptimizationTier {
         }
     }
 
+    /// Parse a tier name as used in `rayzor.toml` (`interpreted`, `baseline`,
+    /// `standard`, `optimized`, `maximum`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "interpreted" => Some(OptimizationTier::Interpreted),
+            "baseline" => Some(OptimizationTier::Baseline),
+            "standard" => Some(OptimizationTier::Standard),
+            "optimized" => Some(OptimizationTier::Optimized),
+            "maximum" => Some(OptimizationTier::Maximum),
+            _ => None,
+        }
+    }
+
+    /// Compiled tier by number, as in `rayzor jit --tier` and `rayzor dump
+    /// --tier` (0 = baseline .. 3 = maximum)
+    pub fn from_level(level: u8) -> Option<Self> {
+        match level {
+            0 => Some(OptimizationTier::Baseline),
+            1 => Some(OptimizationTier::Standard),
+            2 => Some(OptimizationTier::Optimized),
+            3 => Some(OptimizationTier::Maximum),
+            _ => None,
+        }
+    }
+
     /// Get a human-readable description
     pub fn description(&self) -> &'static str {
         match self {
@@ -538,6 +565,19 @@ pub enum TierPreset {
 }
 
 impl TierPreset {
+    /// Parse a preset name as used on the command line and in `rayzor.toml`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "script" => Some(TierPreset::Script),
+            "application" => Some(TierPreset::Application),
+            "server" => Some(TierPreset::Server),
+            "benchmark" => Some(TierPreset::Benchmark),
+            "development" => Some(TierPreset::Development),
+            "embedded" => Some(TierPreset::Embedded),
+            _ => None,
+        }
+    }
+
     /// Convert preset to a TieredConfig
     pub fn to_config(self) -> TieredConfig {
         match self {
@@ -556,6 +596,8 @@ impl TierPreset {
                 start_interpreted: true,
                 bailout_strategy: BailoutStrategy::Quick,
                 max_tier_promotions: 4,
+                function_tiers: HashMap::new(),
+                pass_size_limits: PassSizeLimits::defaults(),
             },
 
             TierPreset::Application => TieredConfig {
@@ -573,6 +615,8 @@ impl TierPreset {
                 start_interpreted: true,
                 bailout_strategy: BailoutStrategy::Quick,
                 max_tier_promotions: 10,
+                function_tiers: HashMap::new(),
+                pass_size_limits: PassSizeLimits::defaults(),
             },
 
             TierPreset::Server => TieredConfig {
@@ -590,6 +634,8 @@ impl TierPreset {
                 start_interpreted: true,
                 bailout_strategy: BailoutStrategy::Immediate,
                 max_tier_promotions: 15,
+                function_tiers: HashMap::new(),
+                pass_size_limits: PassSizeLimits::defaults(),
             },
 
             TierPreset::Benchmark => TieredConfig {
@@ -607,6 +653,8 @@ impl TierPreset {
                 start_interpreted: true, // Start with interpreter for instant startup
                 bailout_strategy: BailoutStrategy::Immediate,
                 max_tier_promotions: 8,
+                function_tiers: HashMap::new(),
+                pass_size_limits: PassSizeLimits::defaults(),
             },
 
             TierPreset::Development => TieredConfig {
@@ -618,6 +666,8 @@ impl TierPreset {
                 start_interpreted: true,
                 bailout_strategy: BailoutStrategy::Immediate,
                 max_tier_promotions: 6,
+                function_tiers: HashMap::new(),
+                pass_size_limits: PassSizeLimits::defaults(),
             },
 
             TierPreset::Embedded => TieredConfig {
@@ -635,6 +685,8 @@ impl TierPreset {
                 start_interpreted: true,
                 bailout_strategy: BailoutStrategy::Slow, // High threshold before bailout
                 max_tier_promotions: 0,                  // Interpreter only
+                function_tiers: HashMap::new(),
+                pass_size_limits: PassSizeLimits::defaults(),
             },
         }
     }
@@ -725,6 +777,16 @@ pub struct TieredConfig {
     /// LLVM promotion is not counted since it has its own singleton guard.
     /// Set to 0 to disable tier promotion entirely.
     pub max_tier_promotions: u64,
+
+    /// Per-function tier overrides, by function name (`Class.method` or the
+    /// plain name): such functions are queued for that tier as soon as they
+    /// are loaded instead of waiting for their call count. Promotions
+    /// recompile whole modules, so other functions move up with them.
+    pub function_tiers: HashMap<String, OptimizationTier>,
+
+    /// Per-pass function size limits for the MIR optimizations of the
+    /// higher tiers (`[optimization.size-limits]` in rayzor.toml)
+    pub pass_size_limits: PassSizeLimits,
 }
 
 impl Default for TieredConfig {
@@ -738,6 +800,8 @@ impl Default for TieredConfig {
             start_interpreted: true, // Enable interpreter by default for instant startup
             bailout_strategy: BailoutStrategy::Quick, // Good balance for most apps
             max_tier_promotions: 10,
+            function_tiers: HashMap::new(),
+            pass_size_limits: PassSizeLimits::defaults(),
         }
     }
 }
@@ -770,6 +834,7 @@ impl TieredConfig {
             start_interpreted: true, // Instant startup for quick iteration
             bailout_strategy: BailoutStrategy::Immediate, // Quick bailout for testing
             max_tier_promotions: 6,
+            function_tiers: HashMap::new(),
         }
     }
 
@@ -784,6 +849,7 @@ impl TieredConfig {
             start_interpreted: true, // Instant startup, then promote hot functions
             bailout_strategy: BailoutStrategy::Quick, // Quick bailout
             max_tier_promotions: 10,
+            function_tiers: HashMap::new(),
         }
     }
 
@@ -799,6 +865,7 @@ impl TieredConfig {
             start_interpreted: false, // Skip interpreter, start at Phase 1
             bailout_strategy: BailoutStrategy::Quick, // Not used when start_interpreted=false
             max_tier_promotions: 10,
+            function_tiers: HashMap::new(),
         }
     }
 }
@@ -845,6 +912,7 @@ impl TieredBackend {
         // Create interpreter with configured bailout threshold
         let mut interp = MirInterpreter::new();
         interp.set_max_iterations(config.bailout_strategy.threshold());
+        interp.set_branch_profile(profile_data.clone());
 
         Ok(Self {
             interpreter: Arc::new(Mutex::new(interp)),
@@ -894,6 +962,7 @@ impl TieredBackend {
         // Create interpreter with configured bailout threshold and register symbols
         let mut interp = MirInterpreter::new();
         interp.set_max_iterations(config.bailout_strategy.threshold());
+        interp.set_branch_profile(profile_data.clone());
         for (name, ptr) in symbols {
             interp.register_symbol(name, *ptr);
         }
@@ -992,6 +1061,18 @@ impl TieredBackend {
                 initial_tier
             };
             self.function_tiers.write().unwrap().insert(*func_id, tier);
+
+            // Configured overrides skip the call-count warmup
+            let override_tier = func
+                .qualified_name
+                .as_ref()
+                .and_then(|name| self.config.function_tiers.get(name))
+                .or_else(|| self.config.function_tiers.get(&func.name));
+            if let Some(&target) = override_tier {
+                if target as u8 > tier as u8 {
+                    self.enqueue_for_optimization(*func_id, target);
+                }
+            }
         }
 
         // Store module for later recompilation/interpretation
@@ -1136,6 +1217,13 @@ impl TieredBackend {
                 }
                 // _exec_guard drops here, calling exit_execution()
                 Ok(InterpValue::Void)
+            } else if let [InterpValue::Ptr(arg)] = args.as_slice() {
+                // A single pointer argument, e.g. `main(args:Array<String>)`
+                unsafe {
+                    let jit_fn: extern "C" fn(i64, i64) = std::mem::transmute(func_ptr);
+                    jit_fn(0, *arg as i64);
+                }
+                Ok(InterpValue::Void)
             } else {
                 // TODO: Implement argument marshaling for JIT calls
                 // For now, fall back to interpreter for functions with args
@@ -1166,6 +1254,55 @@ impl TieredBackend {
             .map(|addr| *addr as *const u8)
     }
 
+    /// JIT-compile all loaded modules now instead of on the first call.
+    /// Does nothing when starting interpreted or when already compiled.
+    pub fn ensure_compiled(&mut self) -> Result<(), String> {
+        if !self.start_interpreted && self.function_pointers.read().unwrap().is_empty() {
+            self.compile_all_modules_jit()?;
+        }
+        Ok(())
+    }
+
+    /// Names of the loaded modules
+    pub fn module_names(&self) -> Vec<String> {
+        self.modules
+            .read()
+            .unwrap()
+            .iter()
+            .map(|m| m.name.clone())
+            .collect()
+    }
+
+    /// Addresses of the JIT-compiled functions, by the names other modules
+    /// reference them with (qualified name, or the runtime name of stdlib
+    /// MIR wrappers), for linking modules compiled by another backend against this
+    /// one (see `codegen::module_loader`). Interpreted functions have no
+    /// address and are left out.
+    pub fn exported_symbols(&self) -> Vec<(String, usize)> {
+        let stdlib_mapping = crate::stdlib::runtime_mapping::StdlibMapping::new();
+        let modules = self.modules.read().unwrap();
+        let pointers = self.function_pointers.read().unwrap();
+        let mut symbols = Vec::new();
+        for module in modules.iter() {
+            for (func_id, function) in &module.functions {
+                let Some(&addr) = pointers.get(func_id) else {
+                    continue;
+                };
+                if let Some(qualified) = &function.qualified_name {
+                    symbols.push((qualified.clone(), addr));
+                }
+                // Stdlib MIR wrappers are referenced by their runtime name
+                if stdlib_mapping
+                    .find_by_runtime_name(&function.name)
+                    .is_some()
+                {
+                    symbols.push((function.name.clone(), addr));
+                }
+            }
+        }
+        symbols
+    }
+
     /// Get the current optimization tier for a function
     pub fn get_function_tier(&self, func_id: IrFunctionId) -> OptimizationTier {
         self.function_tiers
@@ -1437,6 +1574,214 @@ impl TieredBackend {
         Ok(())
     }
 
+    /// Replace the MIR of one function and recompile only what the change
+    /// affects, rather than whole modules. See [`Self::recompile_functions`].
+    pub fn recompile_function(
+        &mut self,
+        func_id: IrFunctionId,
+        new_mir: IrFunction,
+    ) -> Result<Vec<IrFunctionId>, String> {
+        self.recompile_functions(vec![(func_id, new_mir)])
+    }
+
+    /// Replace the MIR of several loaded functions and re-JIT them, together
+    /// with every compiled function that references one of them directly or
+    /// through other re-JITed functions: compiled code calls its callees at
+    /// fixed addresses, so a caller keeps running the old callee until it is
+    /// recompiled itself. All other functions keep their code and are linked
+    /// against it.
+    ///
+    /// Returns the re-JITed functions; none while the program still runs
+    /// interpreted, since the interpreter picks up the new MIR on the next
+    /// call. The new MIR must have been through the same MIR passes as the
+    /// module it goes into. Functions reached through vtables or closures
+    /// created before the change keep running the old code.
+    pub fn recompile_functions(
+        &mut self,
+        changes: Vec<(IrFunctionId, IrFunction)>,
+    ) -> Result<Vec<IrFunctionId>, String> {
+        let changed: Vec<IrFunctionId> = changes.iter().map(|(func_id, _)| *func_id).collect();
+        {
+            let mut modules = self.modules.write().unwrap();
+            for func_id in &changed {
+                if !modules.iter().any(|m| m.functions.contains_key(func_id)) {
+                    return Err(format!("Function {:?} not found in any module", func_id));
+                }
+            }
+            for (func_id, mut function) in changes {
+                function.id = func_id;
+                if let Some(module) = modules
+                    .iter_mut()
+                    .find(|m| m.functions.contains_key(&func_id))
+                {
+                    module.functions.insert(func_id, function);
+                }
+            }
+        }
+        self.rejit_functions(&changed)
+    }
+
+    /// Replace a loaded module with a new compile of it (matched by name) and
+    /// re-JIT the functions whose MIR changed, as [`Self::recompile_functions`]
+    /// does. Functions are compared through the dependency records the BLADE
+    /// cache stores (see [`functions_to_recompile`]). Class RTTI is not
+    /// registered again, so changing a class's fields still needs a full
+    /// compile.
+    ///
+    /// [`functions_to_recompile`]: crate::ir::blade::functions_to_recompile
+    pub fn recompile_changed(&mut self, module: IrModule) -> Result<Vec<IrFunctionId>, String> {
+        use crate::ir::blade::{function_dependencies, functions_to_recompile};
+
+        let name_of = |function: &IrFunction| {
+            function
+                .qualified_name
+                .clone()
+                .unwrap_or_else(|| function.name.clone())
+        };
+        let changed: Vec<IrFunctionId> = {
+            let mut modules = self.modules.write().unwrap();
+            let loaded = modules
+                .iter_mut()
+                .find(|m| m.name == module.name)
+                .ok_or_else(|| format!("Module '{}' is not loaded", module.name))?;
+            let stale: HashSet<String> = functions_to_recompile(
+                &function_dependencies(loaded),
+                &function_dependencies(&module),
+            )
+            .into_iter()
+            .collect();
+            let changed = module
+                .functions
+                .iter()
+                .filter(|(_, function)| !function.cfg.blocks.is_empty())
+                .filter(|(func_id, function)| {
+                    // A function that moved to another id has no code there
+                    let name = name_of(function);
+                    stale.contains(&name)
+                        || loaded.functions.get(*func_id).map(name_of) != Some(name)
+                })
+                .map(|(func_id, _)| *func_id)
+                .collect();
+            *loaded = module;
+            changed
+        };
+        if self.config.verbosity >= 1 {
+            debug!(
+                "[TieredBackend] {} function(s) changed: {:?}",
+                changed.len(),
+                changed
+            );
+        }
+        self.rejit_functions(&changed)
+    }
+
+    /// Re-JIT `changed` and the compiled functions that reference them into
+    /// a backend of their own, linked against the existing code of the rest
+    fn rejit_functions(&mut self, changed: &[IrFunctionId]) -> Result<Vec<IrFunctionId>, String> {
+        let pointers = self.function_pointers.read().unwrap().clone();
+        if changed.is_empty() || pointers.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let modules = self.modules.read().unwrap();
+
+        // Everything that references stale code is stale too
+        let references: Vec<(IrFunctionId, BTreeSet<IrFunctionId>)> = modules
+            .iter()
+            .flat_map(|m| m.functions.iter())
+            .filter(|(func_id, _)| pointers.contains_key(*func_id))
+            .map(|(func_id, function)| (*func_id, function.referenced_functions()))
+            .collect();
+        let mut stale: BTreeSet<IrFunctionId> = changed.iter().copied().collect();
+        loop {
+            let before = stale.len();
+            for (func_id, referenced) in &references {
+                if !stale.contains(func_id) && referenced.iter().any(|r| stale.contains(r)) {
+                    stale.insert(*func_id);
+                }
+            }
+            if stale.len() == before {
+                break;
+            }
+        }
+
+        // Recompile at the highest Cranelift tier among them
+        let tier = stale
+            .iter()
+            .map(|func_id| self.get_function_tier(*func_id))
+            .max()
+            .unwrap_or(OptimizationTier::Baseline)
+            .clamp(OptimizationTier::Baseline, OptimizationTier::Optimized);
+
+        if self.config.verbosity >= 1 {
+            debug!(
+                "[TieredBackend] Re-JITing {} function(s) at {} ({} changed)",
+                stale.len(),
+                tier.description(),
+                changed.len()
+            );
+        }
+
+        // The rest is imported from its current code
+        let imports: HashMap<IrFunctionId, String> = pointers
+            .keys()
+            .filter(|func_id| !stale.contains(*func_id))
+            .map(|func_id| (*func_id, format!("rayzor_jit_fn_{}", func_id.0)))
+            .collect();
+        let mut symbols: Vec<(&str, *const u8)> = self
+            .runtime_symbols
+            .iter()
+            .map(|(name, ptr)| (name.as_str(), *ptr as *const u8))
+            .collect();
+        symbols.extend(
+            imports
+                .iter()
+                .map(|(func_id, name)| (name.as_str(), pointers[func_id] as *const u8)),
+        );
+
+        let mut backend =
+            CraneliftBackend::with_symbols_and_opt(tier.cranelift_opt_level(), &symbols)?;
+        backend.import_compiled(imports.clone());
+        for module in modules.iter() {
+            backend.declare_module_functions(module)?;
+        }
+        for module in modules.iter() {
+            let functions: Vec<IrFunctionId> = module
+                .functions
+                .keys()
+                .filter(|func_id| stale.contains(*func_id))
+                .copied()
+                .collect();
+            if !functions.is_empty() {
+                backend.compile_functions_without_finalize(module, &functions)?;
+            }
+        }
+        backend.finalize()?;
+
+        let mut new_pointers = HashMap::new();
+        for func_id in &stale {
+            if let Ok(ptr) = backend.get_function_ptr(*func_id) {
+                new_pointers.insert(*func_id, ptr as usize);
+            }
+        }
+        drop(modules);
+
+        // Code that is running or may be called keeps the old version alive
+        Box::leak(Box::new(backend));
+
+        {
+            let mut fp_lock = self.function_pointers.write().unwrap();
+            let mut ft_lock = self.function_tiers.write().unwrap();
+            for (&func_id, &ptr) in &new_pointers {
+                fp_lock.insert(func_id, ptr);
+                ft_lock.insert(func_id, tier);
+            }
+        }
+        let mut rejitted: Vec<IrFunctionId> = new_pointers.into_keys().collect();
+        rejitted.sort();
+        Ok(rejitted)
+    }
+
     /// Compile all modules to Cranelift in JIT mode (lazy compilation on first execution)
     ///
     /// This is called when `start_interpreted: false` and we need to compile all modules
@@ -1468,6 +1813,7 @@ impl TieredBackend {
         for module in modules.iter() {
             Self::register_enum_rtti_from_module(module);
             Self::register_class_rtti_from_module(module);
+            Self::register_meta_from_module(module);
         }
 
         // Finalize all modules at once (must be done before getting function pointers)
@@ -1514,7 +1860,7 @@ impl TieredBackend {
 
         for (_id, typedef) in &module.types {
             if let IrTypeDefinition::Enum { variants, .. } = &typedef.definition {
-                let variant_data: Vec<(String, usize, Vec<ParamType>)> = variants
+                let variant_data: Vec<(String, usize, Vec<ParamType>, Vec<u32>)> = variants
                     .iter()
                     .map(|v| {
                         let param_types: Vec<ParamType> = v
@@ -1522,7 +1868,17 @@ impl TieredBackend {
                             .iter()
                             .map(|f| CraneliftBackend::ir_type_to_param_type(&f.ty))
                             .collect();
-                        (v.name.clone(), v.fields.len(), param_types)
+                        let param_enum_types: Vec<u32> = v
+                            .field_enum_types
+                            .iter()
+                            .map(|t| t.map_or(0, |t| t.0))
+                            .collect();
+                        (
+                            v.name.clone(),
+                            v.fields.len(),
+                            param_types,
+                            param_enum_types,
+                        )
                     })
                     .collect();
 
@@ -1545,11 +1901,13 @@ impl TieredBackend {
                 let static_fields: Vec<String> = Vec::new();
 
                 let super_type_id = typedef.super_type_id.map(|t| t.0);
+                let interfaces: Vec<u32> = typedef.interface_type_ids.iter().map(|t| t.0).collect();
 
                 register_class_from_mir(
                     typedef.type_id.0,
                     &typedef.name,
                     super_type_id,
+                    &interfaces,
                     &instance_fields,
                     &static_fields,
                 );
@@ -1557,6 +1915,18 @@ impl TieredBackend {
         }
     }
 
+    /// Register user metadata (haxe.rtti.Meta) from a single MIR module.
+    fn register_meta_from_module(module: &IrModule) {
+        for (_id, typedef) in &module.types {
+            if !typedef.metadata.is_empty() {
+                rayzor_runtime::meta::register_meta_from_mir(
+                    typedef.type_id.0,
+                    CraneliftBackend::ir_metadata_to_runtime(&typedef.metadata),
+                );
+            }
+        }
+    }
+
     /// Compile ALL modules with Cranelift backend at the specified tier
     ///
     /// This method recompiles ALL modules at the target optimization tier and returns
@@ -1582,18 +1952,38 @@ impl TieredBackend {
         let mut backend =
             CraneliftBackend::with_symbols_and_opt(target_tier.cranelift_opt_level(), &symbols)?;
 
-        // Apply MIR-level optimizations for higher tiers
+        // Apply MIR-level optimizations for higher tiers; Tier 2/3 weigh
+        // inlining by the call-site counts collected so far and specialize
+        // functions for their profiled constant arguments
         let mir_opt_level = target_tier.mir_opt_level();
+        let profile = matches!(
+            target_tier,
+            OptimizationTier::Optimized | OptimizationTier::Maximum
+        )
+        .then(|| self.profile_data.call_profile());
         let optimized_modules: Vec<IrModule>;
         let modules_to_compile: &[IrModule] =
             if mir_opt_level != crate::ir::optimization::OptimizationLevel::O0 {
                 // Clone all modules and apply MIR optimizations
-                optimized_modules = all_modules
-                    .iter()
-                    .map(|m| {
-                        let mut module = m.clone();
-                        let mut pass_manager = PassManager::for_level(mir_opt_level);
+                let mut modules = all_modules.to_vec();
+                if profile.is_some() {
+                    Self::apply_arg_profile(&mut modules, &self.profile_data);
+                }
+                // Summarized across modules so calls between them keep
+                // their allocations freeable
+                let escapes = Arc::new(EscapeSummaries::for_modules(&modules));
+                optimized_modules = modules
+                    .into_iter()
+                    .map(|mut module| {
+                        let mut pass_manager = PassManager::for_program(
+                            mir_opt_level,
+                            profile.clone(),
+                            Some(escapes.clone()),
+                        )
+                        .with_size_limits(self.config.pass_size_limits.clone());
                         let _ = pass_manager.run(&mut module);
+                        pass_manager.report_demotions();
+                        Self::apply_branch_profile(&mut module, &self.profile_data);
                         module
                     })
                     .collect();
@@ -1631,6 +2021,37 @@ impl TieredBackend {
         Ok(pointers)
     }
 
+    /// Weight branches and mark never-taken paths cold using the branch
+    /// counts the interpreter collected, so recompiles lay out hot paths
+    /// as fall-throughs
+    fn apply_branch_profile(module: &mut IrModule, profile_data: &ProfileData) {
+        let cold_callees = crate::ir::branch_hints::cold_functions(module);
+        for (func_id, function) in module.functions.iter_mut() {
+            let counts = profile_data.branch_counts_for(*func_id);
+            if !counts.is_empty() {
+                crate::ir::branch_hints::annotate_function(function, &cold_callees, Some(&counts));
+            }
+        }
+    }
+
+    /// Clone functions that are nearly always called with the same
+    /// integer/Bool arguments, specialized for those constants
+    fn apply_arg_profile(modules: &mut [IrModule], profile_data: &ProfileData) {
+        use crate::ir::specialization::{specialize_const_args, SpecializationLimits};
+
+        let specialized = specialize_const_args(
+            modules,
+            &profile_data.arg_values(),
+            &SpecializationLimits::default(),
+        );
+        for spec in &specialized {
+            debug!(
+                "[TieredBackend] Specialized {:?} for constant args {:?} as {:?} ({} direct, {} guarded calls)",
+                spec.function, spec.constants, spec.clone, spec.direct_calls, spec.guarded_calls
+            );
+        }
+    }
+
     /// Apply MIR-level optimizations to a function
     fn apply_mir_optimizations(
         function: IrFunction,
@@ -2492,8 +2913,13 @@ impl TieredBackend {
             }
 
             // Compile ALL modules at the highest tier
-            let compile_result =
-                Self::compile_all_at_tier_static(&modules_lock[..], max_tier, runtime_symbols);
+            let compile_result = Self::compile_all_at_tier_static(
+                &modules_lock[..],
+                max_tier,
+                runtime_symbols,
+                profile_data,
+                &config.pass_size_limits,
+            );
 
             // Drop modules lock before installing results
             drop(modules_lock);
@@ -2590,6 +3016,8 @@ impl TieredBackend {
         all_modules: &[IrModule],
         target_tier: OptimizationTier,
         runtime_symbols: &Arc<Vec<(String, usize)>>,
+        profile_data: &ProfileData,
+        pass_size_limits: &PassSizeLimits,
     ) -> Result<HashMap<IrFunctionId, usize>, String> {
         use crate::ir::optimization::PassManager;
 
@@ -2602,18 +3030,38 @@ impl TieredBackend {
         let mut backend =
             CraneliftBackend::with_symbols_and_opt(target_tier.cranelift_opt_level(), &symbols)?;
 
-        // Apply MIR-level optimizations for higher tiers
+        // Apply MIR-level optimizations for higher tiers; Tier 2/3 weigh
+        // inlining by the call-site counts collected so far and specialize
+        // functions for their profiled constant arguments
         let mir_opt_level = target_tier.mir_opt_level();
+        let profile = matches!(
+            target_tier,
+            OptimizationTier::Optimized | OptimizationTier::Maximum
+        )
+        .then(|| profile_data.call_profile());
         let optimized_modules: Vec<IrModule>;
         let modules_to_compile: &[IrModule] =
             if mir_opt_level != crate::ir::optimization::OptimizationLevel::O0 {
                 // Clone all modules and apply MIR optimizations
-                optimized_modules = all_modules
-                    .iter()
-                    .map(|m| {
-                        let mut module = m.clone();
-                        let mut pass_manager = PassManager::for_level(mir_opt_level);
+                let mut modules = all_modules.to_vec();
+                if profile.is_some() {
+                    Self::apply_arg_profile(&mut modules, profile_data);
+                }
+                // Summarized across modules so calls between them keep
+                // their allocations freeable
+                let escapes = Arc::new(EscapeSummaries::for_modules(&modules));
+                optimized_modules = modules
+                    .into_iter()
+                    .map(|mut module| {
+                        let mut pass_manager = PassManager::for_program(
+                            mir_opt_level,
+                            profile.clone(),
+                            Some(escapes.clone()),
+                        )
+                        .with_size_limits(pass_size_limits.clone());
                         let _ = pass_manager.run(&mut module);
+                        pass_manager.report_demotions();
+                        Self::apply_branch_profile(&mut module, profile_data);
                         module
                     })
                     .collect();
@@ -2802,3 +3250,81 @@ impl TieredStatistics {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ir::mir_builder::MirBuilder;
+    use crate::ir::{BinaryOp, CallingConvention, IrType};
+
+    /// `fn() -> i32`, C convention so it can be called through its pointer
+    fn begin_fn(builder: &mut MirBuilder, name: &str) -> IrFunctionId {
+        let func_id = builder
+            .begin_function(name)
+            .returns(IrType::I32)
+            .calling_convention(CallingConvention::C)
+            .build();
+        builder.set_current_function(func_id);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        func_id
+    }
+
+    /// `base()` returns `base_value`, `caller()` returns `base() + 10` and
+    /// `other()` returns 5
+    fn hot_module(base_value: i32) -> (IrModule, [IrFunctionId; 3]) {
+        let mut builder = MirBuilder::new("Hot");
+        let base = begin_fn(&mut builder, "base");
+        let value = builder.const_i32(base_value);
+        builder.ret(Some(value));
+
+        let caller = begin_fn(&mut builder, "caller");
+        let result = builder.call(base, Vec::new()).unwrap();
+        let ten = builder.const_i32(10);
+        let sum = builder.bin_op(BinaryOp::Add, result, ten);
+        builder.ret(Some(sum));
+
+        let other = begin_fn(&mut builder, "other");
+        let five = builder.const_i32(5);
+        builder.ret(Some(five));
+
+        (builder.finish(), [base, caller, other])
+    }
+
+    fn call(backend: &TieredBackend, func_id: IrFunctionId) -> i32 {
+        let ptr = backend.get_function_pointer(func_id).unwrap();
+        unsafe { std::mem::transmute::<*const u8, extern "C" fn() -> i32>(ptr)() }
+    }
+
+    #[test]
+    fn test_recompile_changed_rejits_edited_function_and_callers() {
+        let config = TieredConfig {
+            start_interpreted: false,
+            enable_background_optimization: false,
+            ..TieredConfig::default()
+        };
+        let mut backend = TieredBackend::new(config).unwrap();
+        let (module, [base, caller, other]) = hot_module(1);
+        backend.compile_module(module).unwrap();
+        backend.ensure_compiled().unwrap();
+        assert_eq!(call(&backend, caller), 11);
+        let other_ptr = backend.get_function_pointer(other);
+
+        // Same module with only base() edited
+        let (module, _) = hot_module(2);
+        let rejitted = backend.recompile_changed(module).unwrap();
+
+        let mut expected = vec![base, caller];
+        expected.sort();
+        assert_eq!(rejitted, expected);
+        assert_eq!(call(&backend, base), 2);
+        assert_eq!(call(&backend, caller), 12);
+        assert_eq!(backend.get_function_pointer(other), other_ptr);
+        assert_eq!(call(&backend, other), 5);
+
+        // Nothing changed: nothing to re-JIT
+        let (module, _) = hot_module(2);
+        assert!(backend.recompile_changed(module).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+/*
+ * Rayzor runtime shim for wasm32-wasi
+ *
+ * librayzor_runtime.a is built for the host, so `rayzor aot --target
+ * wasm32-wasi` links this file instead. It covers tracing, printing,
+ * string conversion and concatenation, time and allocation; programs
+ * using other runtime functions are rejected before code generation (see
+ * wasi_runtime.rs).
+ *
+ * Compiled code passes sizes as 64-bit integers, so the libc functions it
+ * calls directly are redirected to the rayzor_wasi_* wrappers below, which
+ * take i64 sizes and narrow them to wasm32's size_t.
+ *
+ * RAYZOR_ENTRY is defined on the command line as the Haxe entry point.
+ */
+
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <math.h>
+#include <time.h>
+#include <unistd.h>
+
+/* Same field offsets as the runtime's 64-bit HaxeString */
+typedef struct {
+    uint8_t *ptr;
+    uint64_t len;
+    uint64_t cap; /* 0: static or borrowed bytes, never freed */
+} HaxeString;
+
+/* ---- libc with 64-bit sizes -------------------------------------------- */
+
+void *rayzor_wasi_malloc(int64_t size) { return malloc((size_t)size); }
+
+void *rayzor_wasi_calloc(int64_t count, int64_t size) {
+    return calloc((size_t)count, (size_t)size);
+}
+
+void *rayzor_wasi_realloc(void *ptr, int64_t size) {
+    return realloc(ptr, (size_t)size);
+}
+
+void *rayzor_wasi_memcpy(void *dst, const void *src, int64_t n) {
+    return memcpy(dst, src, (size_t)n);
+}
+
+void *rayzor_wasi_memmove(void *dst, const void *src, int64_t n) {
+    return memmove(dst, src, (size_t)n);
+}
+
+void *rayzor_wasi_memset(void *dst, int32_t c, int64_t n) {
+    return memset(dst, c, (size_t)n);
+}
+
+int32_t rayzor_wasi_memcmp(const void *a, const void *b, int64_t n) {
+    return memcmp(a, b, (size_t)n);
+}
+
+int64_t rayzor_wasi_strlen(const char *s) { return (int64_t)strlen(s); }
+
+int64_t rayzor_wasi_write(int32_t fd, const void *buf, int64_t n) {
+    return (int64_t)write(fd, buf, (size_t)n);
+}
+
+/* ---- Allocation -------------------------------------------------------- */
+
+void *rayzor_alloc_failed(uint64_t size) {
+    void *ptr = malloc((size_t)size);
+    if (!ptr) {
+        fprintf(stderr, "fatal: out of memory allocating %llu bytes\n",
+                (unsigned long long)size);
+        abort();
+    }
+    return ptr;
+}
+
+void *rayzor_realloc_failed(void *old, uint64_t size) {
+    void *ptr = realloc(old, (size_t)size);
+    if (!ptr) {
+        fprintf(stderr, "fatal: out of memory allocating %llu bytes\n",
+                (unsigned long long)size);
+        abort();
+    }
+    return ptr;
+}
+
+void *rayzor_malloc_aligned(uint64_t size, uint64_t align) {
+    if (size == 0 || (align & (align - 1)) != 0) {
+        return NULL;
+    }
+    return aligned_alloc((size_t)align, (size_t)((size + align - 1) & ~(align - 1)));
+}
+
+/* ---- Strings ----------------------------------------------------------- */
+
+static HaxeString *new_string(uint8_t *ptr, uint64_t len, uint64_t cap) {
+    HaxeString *s = rayzor_alloc_failed(sizeof(HaxeString));
+    s->ptr = ptr;
+    s->len = len;
+    s->cap = cap;
+    return s;
+}
+
+static HaxeString *copy_string(const char *text, size_t len) {
+    uint8_t *bytes = rayzor_alloc_failed(len + 1);
+    memcpy(bytes, text, len);
+    bytes[len] = 0;
+    return new_string(bytes, len, len + 1);
+}
+
+/* Haxe's Float formatting: shortest round-trip digits, no exponent below
+ * 1e21, "NaN" and "Infinity" spelled out */
+static int format_float(char *out, size_t size, double value) {
+    if (isnan(value)) {
+        return snprintf(out, size, "NaN");
+    }
+    if (isinf(value)) {
+        return snprintf(out, size, value > 0 ? "Infinity" : "-Infinity");
+    }
+    if (value == floor(value) && fabs(value) < 1e21) {
+        return snprintf(out, size, "%.0f", value);
+    }
+    for (int precision = 1; precision <= 17; precision++) {
+        int n = snprintf(out, size, "%.*g", precision, value);
+        if (strtod(out, NULL) == value) {
+            return n;
+        }
+    }
+    return snprintf(out, size, "%.17g", value);
+}
+
+HaxeString *haxe_string_literal(const uint8_t *ptr, int64_t len) {
+    return new_string((uint8_t *)ptr, (uint64_t)len, 0);
+}
+
+HaxeString *haxe_string_from_int(int64_t value) {
+    char buf[24];
+    int n = snprintf(buf, sizeof buf, "%lld", (long long)value);
+    return copy_string(buf, (size_t)n);
+}
+
+HaxeString *haxe_string_from_float(double value) {
+    char buf[40];
+    int n = format_float(buf, sizeof buf, value);
+    return copy_string(buf, (size_t)n);
+}
+
+HaxeString *haxe_string_from_bool(bool value) {
+    return value ? new_string((uint8_t *)"true", 4, 0)
+                 : new_string((uint8_t *)"false", 5, 0);
+}
+
+HaxeString *haxe_string_from_null(void) {
+    return new_string((uint8_t *)"null", 4, 0);
+}
+
+HaxeString *haxe_string_concat(const HaxeString *a, const HaxeString *b) {
+    uint64_t a_len = a ? a->len : 0;
+    uint64_t b_len = b ? b->len : 0;
+    uint8_t *bytes = rayzor_alloc_failed((size_t)(a_len + b_len + 1));
+    if (a_len) {
+        memcpy(bytes, a->ptr, (size_t)a_len);
+    }
+    if (b_len) {
+        memcpy(bytes + a_len, b->ptr, (size_t)b_len);
+    }
+    bytes[a_len + b_len] = 0;
+    return new_string(bytes, a_len + b_len, a_len + b_len + 1);
+}
+
+int32_t haxe_string_len(const HaxeString *s) { return s ? (int32_t)s->len : 0; }
+
+/* ---- Output ------------------------------------------------------------ */
+
+static void write_line(const uint8_t *ptr, uint64_t len) {
+    fwrite(ptr, 1, (size_t)len, stdout);
+    fputc('\n', stdout);
+}
+
+void haxe_trace_string(const uint8_t *ptr, int64_t len) {
+    if (!ptr) {
+        write_line((const uint8_t *)"null", 4);
+    } else {
+        write_line(ptr, (uint64_t)len);
+    }
+}
+
+void haxe_trace_string_struct(const HaxeString *s) {
+    if (!s) {
+        write_line((const uint8_t *)"null", 4);
+    } else {
+        haxe_trace_string(s->ptr, (int64_t)s->len);
+    }
+}
+
+void haxe_trace_int(int64_t value) { printf("%lld\n", (long long)value); }
+
+void haxe_trace_float(double value) {
+    char buf[40];
+    format_float(buf, sizeof buf, value);
+    puts(buf);
+}
+
+void haxe_trace_bool(bool value) { puts(value ? "true" : "false"); }
+
+/* type_tag: 1 and 3 Int, 2 Bool, 4 Float (bits), 5 String */
+void haxe_trace_typed(int64_t value, int32_t type_tag) {
+    switch (type_tag) {
+    case 2:
+        haxe_trace_bool(value != 0);
+        break;
+    case 4: {
+        double f;
+        memcpy(&f, &value, sizeof f);
+        haxe_trace_float(f);
+        break;
+    }
+    case 5:
+        haxe_trace_string_struct((const HaxeString *)(intptr_t)value);
+        break;
+    default:
+        haxe_trace_int(value);
+        break;
+    }
+}
+
+/* Trace positions are a host runtime option; the shim never shows them */
+void haxe_trace_pos(const HaxeString *pos) { (void)pos; }
+
+void haxe_sys_print_int(int64_t value) { printf("%lld", (long long)value); }
+
+void haxe_sys_print_float(double value) {
+    char buf[40];
+    format_float(buf, sizeof buf, value);
+    fputs(buf, stdout);
+}
+
+void haxe_sys_print_bool(bool value) { fputs(value ? "true" : "false", stdout); }
+
+void haxe_sys_println(void) { fputc('\n', stdout); }
+
+/* ---- Sys --------------------------------------------------------------- */
+
+void haxe_sys_exit(int32_t code) {
+    fflush(stdout);
+    exit(code);
+}
+
+double haxe_sys_time(void) {
+    struct timespec ts;
+    clock_gettime(CLOCK_REALTIME, &ts);
+    return (double)ts.tv_sec + (double)ts.tv_nsec / 1e9;
+}
+
+int64_t rayzor_time_monotonic_ns(void) {
+    struct timespec ts;
+    clock_gettime(CLOCK_MONOTONIC, &ts);
+    return (int64_t)ts.tv_sec * 1000000000 + ts.tv_nsec;
+}
+
+double haxe_timer_stamp(void) { return (double)rayzor_time_monotonic_ns() / 1e9; }
+
+/* ---- Entry ------------------------------------------------------------- */
+
+extern void RAYZOR_ENTRY(int64_t);
+
+int main(void) {
+    RAYZOR_ENTRY(0);
+    fflush(stdout);
+    return 0;
+}
@@ -0,0 +1,189 @@
+//! WebAssembly (wasm32-wasi) support for AOT builds
+//!
+//! `rayzor aot --target wasm32-wasi` compiles through LLVM's WebAssembly
+//! backend and links with clang against a WASI sysroot (wasi-sdk). The host
+//! librayzor_runtime.a can't be linked into a wasm module, so the build
+//! compiles the C shim in `wasi_runtime.c` alongside the program instead:
+//! tracing and printing, string conversion and concatenation, time and
+//! allocation. [`check_symbols`] rejects programs that need more of the
+//! runtime before any code is generated.
+//!
+//! Compiled code passes sizes as 64-bit integers while wasm32's `size_t`
+//! is 32 bits, so calls to the libc functions in [`LIBC_WRAPPERS`] are
+//! redirected to `rayzor_wasi_<name>` wrappers in the shim.
+
+use std::path::PathBuf;
+
+use super::aot_symbols::{self, MissingSymbol, SymbolCheck, C_LIBRARY_SYMBOLS};
+use crate::ir::IrModule;
+
+/// Source of the runtime shim, compiled with `-DRAYZOR_ENTRY=<entry>`
+pub const SHIM_SOURCE: &str = include_str!("wasi_runtime.c");
+
+/// Runtime symbols the shim defines
+pub const SHIM_SYMBOLS: &[&str] = &[
+    "haxe_string_concat",
+    "haxe_string_from_bool",
+    "haxe_string_from_float",
+    "haxe_string_from_int",
+    "haxe_string_from_null",
+    "haxe_string_len",
+    "haxe_string_literal",
+    "haxe_sys_exit",
+    "haxe_sys_print_bool",
+    "haxe_sys_print_float",
+    "haxe_sys_print_int",
+    "haxe_sys_println",
+    "haxe_sys_time",
+    "haxe_timer_stamp",
+    "haxe_trace_bool",
+    "haxe_trace_float",
+    "haxe_trace_int",
+    "haxe_trace_pos",
+    "haxe_trace_string",
+    "haxe_trace_string_struct",
+    "haxe_trace_typed",
+    "rayzor_alloc_failed",
+    "rayzor_malloc_aligned",
+    "rayzor_realloc_failed",
+    "rayzor_time_monotonic_ns",
+];
+
+/// libc functions taking or returning a `size_t`, called through
+/// `rayzor_wasi_<name>`
+pub const LIBC_WRAPPERS: &[&str] = &[
+    "calloc", "malloc", "memcmp", "memcpy", "memmove", "memset", "realloc", "strlen", "write",
+];
+
+/// libc functions wasi-libc doesn't provide
+const UNSUPPORTED_LIBC: &[&str] = &["_setjmp"];
+
+/// Whether `triple` names a WebAssembly target
+pub fn is_wasm_triple(triple: &str) -> bool {
+    triple.starts_with("wasm32") || triple.starts_with("wasm64")
+}
+
+/// The shim function a call to libc's `name` goes to, if it is redirected
+pub fn wrapper_name(name: &str) -> Option<String> {
+    LIBC_WRAPPERS
+        .contains(&name)
+        .then(|| format!("rayzor_wasi_{}", name))
+}
+
+/// Check the externs `modules` reference against the shim and wasi-libc
+pub fn check_symbols(modules: &[IrModule]) -> SymbolCheck {
+    let referenced = aot_symbols::referenced_externs(modules);
+    let missing = referenced
+        .iter()
+        .filter(|(symbol, _)| !is_provided(symbol))
+        .map(|(symbol, declaration)| MissingSymbol {
+            symbol: symbol.clone(),
+            declaration: declaration.clone(),
+            registered: false,
+        })
+        .collect();
+
+    SymbolCheck {
+        archive: PathBuf::from("the wasm32-wasi runtime shim"),
+        referenced: referenced.len(),
+        missing,
+    }
+}
+
+fn is_provided(symbol: &str) -> bool {
+    SHIM_SYMBOLS.contains(&symbol)
+        || (C_LIBRARY_SYMBOLS.contains(&symbol) && !UNSUPPORTED_LIBC.contains(&symbol))
+}
+
+/// WASI sysroot to link against: `explicit` (`--sysroot`), else
+/// `$WASI_SYSROOT`, else the one in `$WASI_SDK_PATH` or /opt/wasi-sdk
+pub fn find_sysroot(explicit: Option<&PathBuf>) -> Result<PathBuf, String> {
+    if let Some(sysroot) = explicit {
+        return Ok(sysroot.clone());
+    }
+    if let Some(sysroot) = std::env::var_os("WASI_SYSROOT") {
+        return Ok(PathBuf::from(sysroot));
+    }
+    let sdk = std::env::var_os("WASI_SDK_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("/opt/wasi-sdk"));
+    let sysroot = sdk.join("share").join("wasi-sysroot");
+    if sysroot.exists() {
+        return Ok(sysroot);
+    }
+    Err(
+        "No WASI sysroot found. Install wasi-sdk and set WASI_SDK_PATH, \
+         or pass --sysroot <wasi-sysroot>"
+            .to_string(),
+    )
+}
+
+/// clang to link with: `explicit` (`--linker`), else wasi-sdk's, else
+/// `clang` on the PATH (which needs the WebAssembly target and wasm-ld)
+pub fn find_clang(explicit: Option<&str>) -> String {
+    if let Some(linker) = explicit {
+        return linker.to_string();
+    }
+    let sdk = std::env::var_os("WASI_SDK_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("/opt/wasi-sdk"));
+    let clang = sdk.join("bin").join("clang");
+    if clang.exists() {
+        return clang.to_string_lossy().to_string();
+    }
+    "clang".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Whether the shim has a top-level definition of `name`
+    fn defines(name: &str) -> bool {
+        let call = format!("{}(", name);
+        SHIM_SOURCE.lines().any(|line| {
+            !line.starts_with([' ', '\t', '/', '#', '}']) && {
+                line.split([' ', '*']).any(|word| word.starts_with(&call))
+            }
+        })
+    }
+
+    #[test]
+    fn test_shim_defines_its_symbols() {
+        for symbol in SHIM_SYMBOLS {
+            assert!(
+                defines(symbol),
+                "{} is listed but not defined in wasi_runtime.c",
+                symbol
+            );
+            assert!(
+                rayzor_runtime::symbol_manifest::is_runtime_symbol(symbol),
+                "{} is not a runtime symbol",
+                symbol
+            );
+        }
+        for name in LIBC_WRAPPERS {
+            assert!(defines(&wrapper_name(name).unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_wasm_triples() {
+        assert!(is_wasm_triple("wasm32-wasi"));
+        assert!(is_wasm_triple("wasm32-wasip1"));
+        assert!(!is_wasm_triple("x86_64-unknown-linux-gnu"));
+        assert_eq!(
+            wrapper_name("malloc").as_deref(),
+            Some("rayzor_wasi_malloc")
+        );
+        assert_eq!(wrapper_name("sqrt"), None);
+    }
+
+    #[test]
+    fn test_libc_is_provided() {
+        assert!(is_provided("haxe_trace_int"));
+        assert!(is_provided("sqrt"));
+        assert!(!is_provided("_setjmp"));
+        assert!(!is_provided("haxe_array_push"));
+    }
+}
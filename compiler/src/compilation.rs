@@ -7,14 +7,17 @@ use crate::compiler_plugin::CompilerPluginRegistry;
 use crate::dependency_graph::{CircularDependency, DependencyAnalysis, DependencyGraph};
 use crate::ir::{
     blade::{
-        load_blade, load_symbol_manifest, save_blade, BladeAbstractInfo, BladeClassInfo,
-        BladeEnumInfo, BladeMetadata, BladeMethodInfo, BladeSymbolManifest, BladeTypeAliasInfo,
+        function_dependencies, load_blade, load_symbol_manifest, save_blade, verify_blade,
+        BladeAbstractInfo, BladeClassInfo, BladeEnumInfo, BladeError, BladeMetadata,
+        BladeMethodInfo, BladeSymbolManifest, BladeTypeAliasInfo,
     },
+    blade_cache::RemoteCache,
     IrInstruction, IrModule, Monomorphizer,
 };
 use crate::pipeline::{
     CompilationError, CompilationResult, ErrorCategory, HaxeCompilationPipeline, PipelineConfig,
 };
+use crate::stdlib::embedded::{embedded_stdlib, EmbeddedStdlib, STDLIB_VERSION};
 use crate::stdlib::hdll_plugin::HdllPlugin;
 use crate::tast::{
     namespace::{ImportResolver, NamespaceResolver},
@@ -23,14 +26,20 @@ use crate::tast::{
     AstLowering, ScopeId, ScopeTree, SourceLocation, StringInterner, SymbolId, SymbolTable, TypeId,
     TypeKind, TypeTable, TypedFile,
 };
+use crate::workspace::manifest::RemoteCacheConfig;
 use log::{debug, info, trace, warn};
-use parser::{parse_haxe_file, parse_haxe_file_with_debug, HaxeFile};
+use parser::preprocessor::PreprocessorConfig;
+use parser::{parse_haxe_file_with_config, HaxeFile};
 use std::cell::RefCell;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Message prefix of an unresolved type lowering error, as produced by
+/// `LoweringError::to_compilation_error`; the type name follows in quotes.
+const UNRESOLVED_TYPE_MESSAGE: &str = "Cannot find type '";
+
 /// Represents a complete compilation unit with multiple source files
 pub struct CompilationUnit {
     /// Stdlib files (loaded first with haxe.* package)
@@ -81,6 +90,10 @@ pub struct CompilationUnit {
     /// being stored as separate modules, because their function IDs would collide.
     import_mir_modules: Vec<crate::ir::IrModule>,
 
+    /// Renamed `__init__` functions of the import modules, in dependency order.
+    /// The merged module's `__init__` runs them before its own initializers.
+    import_init_functions: Vec<crate::ir::IrFunctionId>,
+
     /// Stdlib typed files loaded on-demand (typedefs, etc. that need to be in HIR)
     loaded_stdlib_typed_files: Vec<TypedFile>,
 
@@ -125,6 +138,11 @@ pub struct CompilationUnit {
 
     /// Set of already-loaded HDLL library names to avoid duplicate loading
     loaded_hdlls: HashSet<String>,
+
+    /// Stdlib type name -> module path (e.g. "StringTools" -> "StringTools",
+    /// "haxe.io.Bytes" -> "haxe.io.Bytes"), read from the .bsym manifest in
+    /// lazy stdlib mode to find which modules user code actually needs
+    stdlib_module_index: BTreeMap<String, String>,
 }
 
 /// Configuration for compilation
@@ -151,16 +169,46 @@ pub struct CompilationConfig {
     /// Directory for BLADE cache files
     pub cache_dir: Option<PathBuf>,
 
+    /// Shared cache behind the cache directory (`[cache.remote]`)
+    pub remote_cache: Option<RemoteCacheConfig>,
+
     /// Lazy stdlib loading - skip upfront symbol registration for faster cold start
-    /// When enabled, stdlib symbols are loaded on-demand when first referenced
+    /// When enabled, only the stdlib modules reachable from types the user code
+    /// references are parsed and lowered (resolved through the .bsym index first).
     /// This trades first-access latency for faster initial startup
     pub lazy_stdlib: bool,
 
+    /// Ignore the stdlib bundle embedded in the compiler binary and build the
+    /// stdlib from haxe-std sources (`-D stdlib-src`)
+    pub stdlib_from_source: bool,
+
     /// Pipeline configuration for analysis and optimization
     pub pipeline_config: PipelineConfig,
 
     /// Directories to search for .hdll files (referenced by @:hlNative metadata)
     pub hdll_search_paths: Vec<PathBuf>,
+
+    /// Emit debug-mode runtime checks (e.g. Array modification during for-in)
+    pub debug_checks: bool,
+
+    /// Report instance field accesses to ThreadSanitizer
+    /// (`rayzor run --sanitize thread`)
+    pub sanitize_thread: bool,
+
+    /// Pass each `trace()` call's position to the runtime, which prints it
+    /// before the value (`rayzor run --trace-pos`)
+    pub trace_positions: bool,
+
+    /// Run imported modules' initializers on first use of their globals
+    /// instead of before `main` (`-D lazy-init`)
+    pub lazy_module_init: bool,
+
+    /// Conditional compilation defines (`#if sys`, `#if (haxe_ver >= 4)`):
+    /// the Rayzor target's, the build mode's `debug`, and `-D` defines
+    pub preprocessor: PreprocessorConfig,
+
+    /// How `print_compilation_errors` writes diagnostics (`--format json`)
+    pub diagnostic_format: diagnostics::DiagnosticFormat,
 }
 
 impl Default for CompilationConfig {
@@ -184,9 +232,17 @@ impl Default for CompilationConfig {
             global_import_hx_files: Vec::new(),            // No global import.hx by default
             enable_cache: true, // Cache enabled - BLADE manifest now includes Math, Std, Date, etc.
             cache_dir: None,    // Auto-discover cache directory when needed
+            remote_cache: None,
             lazy_stdlib: false, // Default to eager loading for compatibility
+            stdlib_from_source: false,
             pipeline_config: PipelineConfig::default(),
             hdll_search_paths: vec![PathBuf::from(".")],
+            debug_checks: false,
+            sanitize_thread: false,
+            trace_positions: false,
+            lazy_module_init: false,
+            preprocessor: PreprocessorConfig::default(),
+            diagnostic_format: diagnostics::DiagnosticFormat::Human,
         }
     }
 }
@@ -388,6 +444,19 @@ impl CompilationConfig {
         }
     }
 
+    /// Create a bare-metal compilation config without the Haxe standard library
+    ///
+    /// Nothing from haxe-std is parsed, registered or importable; only the
+    /// compiler built-ins (trace, String, Array, ...) and runtime externs remain.
+    /// Intended for embedded targets that ship a minimal runtime.
+    pub fn bare_metal() -> Self {
+        Self {
+            load_stdlib: false,
+            default_stdlib_imports: Vec::new(),
+            ..Default::default()
+        }
+    }
+
     /// Create a strict compilation config with full type checking
     ///
     /// This is the default behavior - all symbols loaded upfront,
@@ -426,6 +495,7 @@ impl CompilationUnit {
             pipeline,
             mir_modules: Vec::new(),
             import_mir_modules: Vec::new(),
+            import_init_functions: Vec::new(),
             loaded_stdlib_typed_files: Vec::new(),
             stdlib_function_map: BTreeMap::new(),
             stdlib_function_name_map: BTreeMap::new(),
@@ -438,6 +508,7 @@ impl CompilationUnit {
             compiler_plugin_registry: CompilerPluginRegistry::new(),
             hdll_symbols: Vec::new(),
             loaded_hdlls: HashSet::new(),
+            stdlib_module_index: BTreeMap::new(),
         }
     }
 
@@ -459,6 +530,17 @@ impl CompilationUnit {
         self.namespace_resolver
             .set_stdlib_paths(self.config.stdlib_paths.clone());
 
+        // Symbols from the stdlib bundle embedded in the binary: no haxe-std
+        // sources are parsed upfront, not even the default imports
+        if !self.config.lazy_stdlib {
+            if let Some(embedded) = self.embedded_stdlib() {
+                debug!("[BLADE] Using embedded stdlib ({})", STDLIB_VERSION);
+                self.register_symbols_from_manifest(embedded.symbols());
+                self.register_builtin_globals();
+                return Ok(());
+            }
+        }
+
         // Load pre-compiled symbols from BLADE manifest if caching is enabled
         // Skip if lazy_stdlib is enabled (for faster cold start)
         if self.config.enable_cache && !self.config.lazy_stdlib {
@@ -469,8 +551,11 @@ impl CompilationUnit {
             }
         } else if self.config.lazy_stdlib {
             debug!("Lazy stdlib enabled - skipping upfront symbol registration for faster startup");
+            self.load_stdlib_module_index();
             // Still register builtin globals like 'trace' which are always needed
             self.register_builtin_globals();
+            // Default imports are parsed on demand like any other module
+            return Ok(());
         }
 
         // Load default stdlib imports (Math, Std, Array, String, etc.)
@@ -518,7 +603,7 @@ impl CompilationUnit {
     }
 
     /// Compute hash of source content for cache validation
-    fn hash_source(source: &str) -> u64 {
+    pub(crate) fn hash_source(source: &str) -> u64 {
         use std::collections::hash_map::DefaultHasher;
         use std::hash::{Hash, Hasher};
         let mut hasher = DefaultHasher::new();
@@ -526,6 +611,95 @@ impl CompilationUnit {
         hasher.finish()
     }
 
+    /// Hash the BLADE cache validates a module's source against. Defines
+    /// other than the default ones are part of it, since they change what
+    /// the source compiles to.
+    fn cache_hash(&self, source: &str) -> u64 {
+        let preprocessor = &self.config.preprocessor;
+        if *preprocessor == PreprocessorConfig::default() {
+            return Self::hash_source(source);
+        }
+        let defines: BTreeMap<&str, Option<&str>> = preprocessor
+            .defines
+            .iter()
+            .map(|name| {
+                (
+                    name.as_str(),
+                    preprocessor.values.get(name).map(String::as_str),
+                )
+            })
+            .collect();
+        Self::hash_source(&format!("{}\0{:?}", source, defines))
+    }
+
+    /// The remote cache layer, if one is configured
+    fn remote_cache(&self) -> Option<RemoteCache> {
+        let config = self.config.remote_cache.as_ref()?;
+        match RemoteCache::open(config) {
+            Ok(remote) => Some(remote),
+            Err(e) => {
+                debug!("[BLADE] Remote cache disabled: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Fetch a module missing from (or stale in) the cache directory from
+    /// the remote cache into `blade_path`
+    fn fetch_remote_blade(
+        &self,
+        module: &str,
+        blade_path: &Path,
+        source: &str,
+    ) -> Option<IrModule> {
+        self.remote_cache()?
+            .fetch(module, self.cache_hash(source), blade_path)
+            .map(|(mir, _)| mir)
+    }
+
+    /// Upload a module just written to `blade_path` to the remote cache
+    fn store_remote_blade(&self, module: &str, blade_path: &Path, source_hash: u64) {
+        if let Some(remote) = self.remote_cache() {
+            remote.store(module, source_hash, blade_path);
+        }
+    }
+
+    /// Remote cache name of a module: the file stem of its cache file (the
+    /// dotted module path) or, for a file compiled on its own, of its source
+    /// file, since that cache file name embeds the machine-specific path
+    fn remote_module_name(path: &Path) -> String {
+        path.file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Remove a cache entry that failed to load (truncated, corrupted, or
+    /// from another compiler), so the module is recompiled and re-cached
+    fn discard_blade(blade_path: &Path, error: &BladeError) {
+        match error {
+            // Unreadable for an external reason; leave the file alone
+            BladeError::Io(e) => {
+                trace!(
+                    "[BLADE] Cache read error for {}: {}",
+                    blade_path.display(),
+                    e
+                );
+            }
+            BladeError::StampMismatch(_) | BladeError::UnsupportedVersion(_) => {
+                debug!("[BLADE] Invalidating {}: {}", blade_path.display(), error);
+                let _ = std::fs::remove_file(blade_path);
+            }
+            _ => {
+                warn!(
+                    "Discarding corrupted BLADE cache entry {}: {}",
+                    blade_path.display(),
+                    error
+                );
+                let _ = std::fs::remove_file(blade_path);
+            }
+        }
+    }
+
     /// Try to load a cached MIR module from BLADE cache
     /// Returns Some(IrModule) if cache is valid, None otherwise
     fn try_load_blade_cached(&self, source_path: &str, source: &str) -> Option<IrModule> {
@@ -536,13 +710,17 @@ impl CompilationUnit {
         let blade_path = self.blade_cache_path(source_path)?;
         if !blade_path.exists() {
             trace!("[BLADE] Cache miss (no file): {}", source_path);
-            return None;
+            return self.fetch_remote_blade(
+                &Self::remote_module_name(&blade_path),
+                &blade_path,
+                source,
+            );
         }
 
         match load_blade(&blade_path) {
             Ok((mir, metadata)) => {
                 // Validate cache by checking source hash
-                let current_hash = Self::hash_source(source);
+                let current_hash = self.cache_hash(source);
                 if metadata.source_hash == current_hash {
                     debug!(
                         "[BLADE] Cache hit: {} -> {}",
@@ -552,12 +730,16 @@ impl CompilationUnit {
                     Some(mir)
                 } else {
                     trace!("[BLADE] Cache stale (hash mismatch): {}", source_path);
-                    None
+                    self.fetch_remote_blade(
+                        &Self::remote_module_name(&blade_path),
+                        &blade_path,
+                        source,
+                    )
                 }
             }
             Err(e) => {
-                trace!("[BLADE] Cache read error for {}: {}", source_path, e);
-                None
+                Self::discard_blade(&blade_path, &e);
+                self.fetch_remote_blade(&Self::remote_module_name(&blade_path), &blade_path, source)
             }
         }
     }
@@ -594,14 +776,16 @@ impl CompilationUnit {
             .map(|d| d.as_secs())
             .unwrap_or(0);
 
+        let source_hash = self.cache_hash(source);
         let metadata = BladeMetadata {
             name: mir.name.clone(),
             source_path: source_path.to_string(),
-            source_hash: Self::hash_source(source),
+            source_hash,
             source_timestamp: now, // We use hash for validation, not timestamp
             compile_timestamp: now,
             dependencies,
             compiler_version: env!("CARGO_PKG_VERSION").to_string(),
+            functions: function_dependencies(mir),
         };
 
         match save_blade(&blade_path, mir, metadata) {
@@ -611,6 +795,11 @@ impl CompilationUnit {
                     source_path,
                     blade_path.display()
                 );
+                self.store_remote_blade(
+                    &Self::remote_module_name(&blade_path),
+                    &blade_path,
+                    source_hash,
+                );
             }
             Err(e) => {
                 trace!("[BLADE] Failed to cache {}: {}", source_path, e);
@@ -650,6 +839,135 @@ impl CompilationUnit {
         }
     }
 
+    /// The stdlib bundle embedded in the binary, unless disabled with
+    /// `stdlib_from_source` (`-D stdlib-src`)
+    fn embedded_stdlib(&self) -> Option<&'static EmbeddedStdlib> {
+        if self.config.stdlib_from_source {
+            return None;
+        }
+        embedded_stdlib()
+    }
+
+    /// Build the stdlib type -> module index from the .bsym manifest without
+    /// registering any symbols (lazy stdlib mode). Root-package types are keyed
+    /// by their short name, every type also by its qualified name.
+    fn load_stdlib_module_index(&mut self) {
+        let loaded;
+        let manifest = match self.embedded_stdlib() {
+            Some(embedded) => embedded.symbols(),
+            None => {
+                let manifest_path = PathBuf::from(".rayzor/blade/stdlib/stdlib.bsym");
+                match load_symbol_manifest(&manifest_path) {
+                    Ok(manifest) => {
+                        loaded = manifest;
+                        &loaded
+                    }
+                    Err(e) => {
+                        debug!(
+                            "[BLADE] No symbol index for lazy stdlib ({}), resolving by path",
+                            e
+                        );
+                        return;
+                    }
+                }
+            }
+        };
+
+        for module in &manifest.modules {
+            let types = &module.types;
+            let names = types
+                .classes
+                .iter()
+                .map(|c| (&c.package, &c.name))
+                .chain(types.enums.iter().map(|e| (&e.package, &e.name)))
+                .chain(types.type_aliases.iter().map(|a| (&a.package, &a.name)))
+                .chain(types.abstracts.iter().map(|a| (&a.package, &a.name)));
+            for (package, name) in names {
+                let key = if package.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}.{}", package.join("."), name)
+                };
+                self.stdlib_module_index
+                    .entry(key)
+                    .or_insert_with(|| module.name.clone());
+            }
+        }
+        debug!(
+            "[BLADE] Lazy stdlib index: {} types in {} modules",
+            self.stdlib_module_index.len(),
+            manifest.modules.len()
+        );
+    }
+
+    /// Find the stdlib module declaring `type_name`: the .bsym index first,
+    /// then a module file of that name under the stdlib paths.
+    fn resolve_stdlib_module(&self, type_name: &str) -> Option<String> {
+        if let Some(module) = self.stdlib_module_index.get(type_name) {
+            return Some(module.clone());
+        }
+        let path = self
+            .namespace_resolver
+            .resolve_qualified_path_to_file_force(type_name)?;
+        self.config
+            .stdlib_paths
+            .iter()
+            .any(|root| path.starts_with(root))
+            .then(|| type_name.to_string())
+    }
+
+    /// Stdlib modules reachable from the types user code references (lazy
+    /// stdlib mode). Follows references inside each module found, so a module
+    /// using e.g. `StringBuf` without an import pulls that in too.
+    /// User-declared types shadow stdlib ones and are never looked up.
+    fn collect_reachable_stdlib_modules(
+        &self,
+        declared: &HashSet<String>,
+        user_sources: &[&str],
+    ) -> Vec<String> {
+        let mut modules = Vec::new();
+        let mut seen_names: HashSet<String> = declared.clone();
+        let mut pending: Vec<String> = Vec::new();
+
+        for source in user_sources {
+            for name in referenced_type_names(source) {
+                if seen_names.insert(name.clone()) {
+                    pending.push(name);
+                }
+            }
+        }
+
+        let mut seen_modules = HashSet::new();
+        while let Some(name) = pending.pop() {
+            let Some(module) = self.resolve_stdlib_module(&name) else {
+                continue;
+            };
+            if !seen_modules.insert(module.clone()) {
+                continue;
+            }
+            if let Some(source) = self
+                .namespace_resolver
+                .resolve_qualified_path_to_file_force(&module)
+                .and_then(|path| std::fs::read_to_string(path).ok())
+            {
+                for name in referenced_type_names(&source) {
+                    if seen_names.insert(name.clone()) {
+                        pending.push(name);
+                    }
+                }
+            }
+            modules.push(module);
+        }
+
+        modules.sort();
+        debug!(
+            "Lazy stdlib: {} reachable modules {:?}",
+            modules.len(),
+            modules
+        );
+        modules
+    }
+
     /// Register built-in global symbols like 'trace' that aren't in the BLADE manifest
     fn register_builtin_globals(&mut self) {
         use crate::tast::{
@@ -718,6 +1036,7 @@ impl CompilationUnit {
                 c_includes: None,
                 c_sources: None,
                 c_libs: None,
+                deprecation: None,
             };
 
             // Add symbol to symbol table
@@ -1586,7 +1905,8 @@ impl CompilationUnit {
                 | ExprKind::Meta { expr: e, .. }
                 | ExprKind::Macro(e)
                 | ExprKind::Inline(e)
-                | ExprKind::Reify(e) => {
+                | ExprKind::Reify(e)
+                | ExprKind::StaticVar(e) => {
                     extract_expr_deps(e, deps);
                 }
                 ExprKind::Tuple(elements) => {
@@ -1880,7 +2200,13 @@ impl CompilationUnit {
             };
 
             let filename = file_path_str;
-            let deps = match parser::parse_haxe_file(&filename, &source, false) {
+            let deps = match parser::parse_haxe_file_with_config(
+                &filename,
+                &source,
+                false,
+                false,
+                &self.config.preprocessor,
+            ) {
                 Ok(ast) => Self::extract_all_dependencies(&ast),
                 Err(_) => Vec::new(),
             };
@@ -1949,8 +2275,8 @@ impl CompilationUnit {
         // Handle cycle: if compile_order is empty but all_files is not, there's a cycle.
         // Append remaining files in any order (they'll still compile, just without guaranteed dep order).
         if compile_order.is_empty() && !all_files.is_empty() {
-            eprintln!(
-                "[LOAD_IMPORTS] WARNING: cycle detected, {} files stuck. in_degrees: {:?}",
+            warn!(
+                "[LOAD_IMPORTS] cycle detected, {} files stuck. in_degrees: {:?}",
                 all_files.len(),
                 in_degree.iter().filter(|(_, &d)| d > 0).collect::<Vec<_>>()
             );
@@ -2013,6 +2339,16 @@ impl CompilationUnit {
                                 id_map.insert(*old_id, IrFunctionId(old_id.0 + import_base));
                             }
 
+                            // Globals are stored by ID at runtime, so they get the same offset
+                            let old_globals = std::mem::take(&mut import_mir.globals);
+                            let mut global_id_map = std::collections::HashMap::new();
+                            for (old_id, mut global) in old_globals {
+                                let new_id = crate::ir::IrGlobalId(old_id.0 + import_base);
+                                global.id = new_id;
+                                global_id_map.insert(old_id, new_id);
+                                import_mir.globals.insert(new_id, global);
+                            }
+
                             // Renumber functions in the import module
                             let old_functions: std::collections::BTreeMap<_, _> =
                                 std::mem::take(&mut import_mir.functions);
@@ -2020,7 +2356,14 @@ impl CompilationUnit {
                                 let new_id = *id_map.get(&old_id).unwrap();
                                 func.id = new_id;
 
-                                // Update internal CallDirect/FunctionRef
+                                // The merged module keeps a single `__init__`, which
+                                // calls this one (see merge_import_globals)
+                                if func.name == "__init__" {
+                                    func.name = format!("__init__{}", import_mir.name);
+                                    self.import_init_functions.push(new_id);
+                                }
+
+                                // Update internal CallDirect/FunctionRef and global accesses
                                 use crate::ir::IrInstruction;
                                 for block in func.cfg.blocks.values_mut() {
                                     for inst in &mut block.instructions {
@@ -2032,6 +2375,14 @@ impl CompilationUnit {
                                                     *func_id = *new_func_id;
                                                 }
                                             }
+                                            IrInstruction::LoadGlobal { global_id, .. }
+                                            | IrInstruction::StoreGlobal { global_id, .. } => {
+                                                if let Some(new_global_id) =
+                                                    global_id_map.get(global_id)
+                                                {
+                                                    *global_id = *new_global_id;
+                                                }
+                                            }
                                             _ => {}
                                         }
                                     }
@@ -2461,17 +2812,10 @@ impl CompilationUnit {
     /// Extract type name from UnresolvedType error messages
     /// Returns Some(type_name) if this is an UnresolvedType error, None otherwise
     fn extract_unresolved_type_from_error(error_msg: &str) -> Option<String> {
-        // Match pattern: "UnresolvedType { type_name: \"SomeType\", ..."
-        // Find the start of type_name: \" marker
-        if let Some(type_name_start) = error_msg.find("type_name: \"") {
-            // Move past 'type_name: "' to get to the actual name
-            let after_marker = &error_msg[type_name_start + 12..]; // 12 = length of 'type_name: "'
-                                                                   // Find the closing quote
-            if let Some(end) = after_marker.find('"') {
-                return Some(after_marker[..end].to_string());
-            }
-        }
-        None
+        // Match pattern: "Cannot find type 'SomeType'"
+        let start = error_msg.find(UNRESOLVED_TYPE_MESSAGE)? + UNRESOLVED_TYPE_MESSAGE.len();
+        let end = error_msg[start..].find('\'')?;
+        Some(error_msg[start..start + end].to_string())
     }
 
     /// Check if a type name looks like a generic type parameter
@@ -2506,11 +2850,15 @@ impl CompilationUnit {
     /// so they can be referenced by other files during full compilation
     fn pre_register_file_types(&mut self, filename: &str, source: &str) -> Result<(), String> {
         use crate::tast::ast_lowering::AstLowering;
-        use parser::parse_haxe_file_with_diagnostics;
+        use parser::parse_haxe_file_with_diagnostics_and_config;
 
         // Parse the file
-        let parse_result = parse_haxe_file_with_diagnostics(filename, source)
-            .map_err(|e| format!("Parse error in {}: {}", filename, e))?;
+        let parse_result = parse_haxe_file_with_diagnostics_and_config(
+            filename,
+            source,
+            &self.config.preprocessor,
+        )
+        .map_err(|e| format!("Parse error in {}: {}", filename, e))?;
 
         let ast_file = parse_result.file;
 
@@ -2542,9 +2890,13 @@ impl CompilationUnit {
     /// can resolve imported enum types and their variants.
     fn register_enums_from_source(&mut self, filename: &str, source: &str) {
         use crate::tast::ast_lowering::AstLowering;
-        use parser::parse_haxe_file_with_diagnostics;
+        use parser::parse_haxe_file_with_diagnostics_and_config;
 
-        let parse_result = match parse_haxe_file_with_diagnostics(filename, source) {
+        let parse_result = match parse_haxe_file_with_diagnostics_and_config(
+            filename,
+            source,
+            &self.config.preprocessor,
+        ) {
             Ok(r) => r,
             Err(_) => return,
         };
@@ -2595,9 +2947,14 @@ impl CompilationUnit {
             let source = fs::read_to_string(import_path)
                 .map_err(|e| format!("Failed to read import.hx at {:?}: {}", import_path, e))?;
 
-            let haxe_file =
-                parse_haxe_file(import_path.to_str().unwrap_or("import.hx"), &source, true)
-                    .map_err(|e| format!("Parse error in {:?}: {}", import_path, e))?;
+            let haxe_file = parse_haxe_file_with_config(
+                import_path.to_str().unwrap_or("import.hx"),
+                &source,
+                true,
+                false,
+                &self.config.preprocessor,
+            )
+            .map_err(|e| format!("Parse error in {:?}: {}", import_path, e))?;
 
             self.import_hx_files.push(haxe_file);
         }
@@ -2608,8 +2965,9 @@ impl CompilationUnit {
     /// Add a user source file to the compilation unit
     pub fn add_file(&mut self, source: &str, file_path: &str) -> Result<(), String> {
         // Parse the file (file_name, input, recovery mode=true, debug=true to preserve source)
-        let haxe_file = parse_haxe_file_with_debug(file_path, source, true, true)
-            .map_err(|e| format!("Parse error in {}: {}", file_path, e))?;
+        let haxe_file =
+            parse_haxe_file_with_config(file_path, source, true, true, &self.config.preprocessor)
+                .map_err(|e| format!("Parse error in {}: {}", file_path, e))?;
 
         self.user_files.push(haxe_file);
         Ok(())
@@ -2754,7 +3112,7 @@ impl CompilationUnit {
         skip_pre_registration: bool,
     ) -> Result<TypedFile, Vec<CompilationError>> {
         use crate::tast::ast_lowering::AstLowering;
-        use parser::parse_haxe_file_with_diagnostics;
+        use parser::parse_haxe_file_with_diagnostics_and_config;
 
         // Skip if already successfully compiled - return cached TypedFile
         if let Some(cached) = self.compiled_files.get(filename) {
@@ -2762,7 +3120,12 @@ impl CompilationUnit {
         }
 
         // Parse the file
-        let parse_result = parse_haxe_file_with_diagnostics(filename, source).map_err(|e| {
+        let parse_result = parse_haxe_file_with_diagnostics_and_config(
+            filename,
+            source,
+            &self.config.preprocessor,
+        )
+        .map_err(|e| {
             vec![CompilationError {
                 message: format!("Parse error: {}", e),
                 location: SourceLocation::unknown(),
@@ -2777,7 +3140,7 @@ impl CompilationUnit {
         let file_id = diagnostics::FileId::new(0);
 
         // Stage 1.5: Macro expansion (if enabled)
-        let ast_file = if self.config.pipeline_config.enable_macro_expansion {
+        let mut ast_file = if self.config.pipeline_config.enable_macro_expansion {
             let expansion = crate::macro_system::expand_macros(ast_file);
             // Log macro diagnostics as warnings (non-fatal in multi-file context)
             for diag in &expansion.diagnostics {
@@ -2796,6 +3159,11 @@ impl CompilationUnit {
             ast_file
         };
 
+        // Stage 1.6: Plugin AST transforms
+        self.compiler_plugin_registry
+            .run_ast_hooks(&mut ast_file)
+            .map_err(|e| vec![plugin_hook_error(e)])?;
+
         // Lower to TAST using the SHARED state
         // NOTE: AstLowering needs an Rc<RefCell<StringInterner>> for TypedFile
         // We create a dummy one here - the actual interning happens via the &mut reference
@@ -2816,8 +3184,9 @@ impl CompilationUnit {
         lowering.set_skip_pre_registration(skip_pre_registration);
 
         // Skip stdlib loading during lowering if BLADE cache is enabled
-        // (methods and types were already registered from the BLADE manifest)
-        if self.config.enable_cache {
+        // (methods and types were already registered from the BLADE manifest),
+        // or when the stdlib is loaded lazily / not at all
+        if self.config.enable_cache || self.config.lazy_stdlib || !self.config.load_stdlib {
             lowering.set_skip_stdlib_loading(true);
         }
 
@@ -2827,16 +3196,20 @@ impl CompilationUnit {
             filename.to_string(),
         );
 
-        let typed_file = lowering.lower_file(&ast_file).map_err(|e| {
-            vec![CompilationError {
-                message: format!("Lowering error: {:?}", e),
-                location: SourceLocation::unknown(),
-                category: ErrorCategory::TypeError,
-                suggestion: None,
-                related_errors: Vec::new(),
-            }]
+        let mut typed_file = lowering.lower_file(&ast_file).map_err(|e| {
+            // Lowering locations all use file id 0; point them at this file's
+            // entry in the diagnostics source map
+            let mut error = e.to_compilation_error();
+            if let Some(file_id) = self.diagnostic_file_id(filename) {
+                error.location.file_id = file_id;
+            }
+            vec![error]
         })?;
 
+        self.compiler_plugin_registry
+            .run_tast_hooks(&mut typed_file)
+            .map_err(|e| vec![plugin_hook_error(e)])?;
+
         // Lower to HIR
         use crate::ir::tast_to_hir::lower_tast_to_hir;
         let hir_module = lower_tast_to_hir(
@@ -2940,6 +3313,9 @@ impl CompilationUnit {
             self.import_class_alloc_sizes.clone(),
             self.import_class_method_symbols.clone(),
             self.import_class_type_to_symbol.clone(),
+            self.config.debug_checks,
+            self.config.sanitize_thread,
+            self.config.trace_positions,
         )
         .map_err(|errors| {
             errors
@@ -2955,6 +3331,9 @@ impl CompilationUnit {
         })?;
 
         let mut mir_module = mir_result.module;
+        self.compiler_plugin_registry
+            .run_mir_hooks(&mut mir_module)
+            .map_err(|e| vec![plugin_hook_error(e)])?;
 
         // Collect SymbolId-based function mappings from ALL files (stdlib + imports)
         // This enables cross-file method calls: user file can call import file methods
@@ -3032,8 +3411,14 @@ impl CompilationUnit {
             // Merge stdlib MIR (extern functions for Thread, Channel, Mutex, Arc, etc.)
             // This ensures extern runtime functions are available.
             // Uses build_stdlib_with_plugins to include HDLL extern declarations from loaded plugins.
+            // Without plugins this is exactly the prebuilt MIR of the embedded stdlib.
             use crate::stdlib::build_stdlib_with_plugins;
-            let mut stdlib_mir = build_stdlib_with_plugins(&self.compiler_plugin_registry);
+            let mut stdlib_mir = match self.embedded_stdlib() {
+                Some(embedded) if self.compiler_plugin_registry.is_empty() => {
+                    (**embedded.mir()).clone()
+                }
+                _ => build_stdlib_with_plugins(&self.compiler_plugin_registry),
+            };
 
             // Merge on-demand imported MIR modules (e.g., BalancedTree.hx) into the
             // user module. These were already renumbered to high IDs (100000+) during
@@ -3043,7 +3428,14 @@ impl CompilationUnit {
                 for (func_id, func) in import_module.functions {
                     mir_module.functions.insert(func_id, func);
                 }
+                mir_module.globals.extend(import_module.globals);
             }
+            let import_inits = std::mem::take(&mut self.import_init_functions);
+            Self::merge_import_globals(
+                &mut mir_module,
+                &import_inits,
+                self.config.lazy_module_init,
+            );
 
             // CRITICAL FIX: Renumber stdlib function IDs to avoid collisions with user functions
             // Each MIR module starts function IDs from 0, so when merging stdlib and user modules,
@@ -3297,6 +3689,86 @@ impl CompilationUnit {
         Ok(typed_file)
     }
 
+    /// Bind each `External` global (a static lowered with another module) to
+    /// the global defining the same symbol, and make the module's `__init__`
+    /// run the import modules' initializers, dependencies first, before its own.
+    /// With `lazy`, each import initializer that runs no class `__init__`
+    /// instead runs on first use of its globals (see `ir::module_init`).
+    fn merge_import_globals(
+        module: &mut IrModule,
+        import_inits: &[crate::ir::IrFunctionId],
+        lazy: bool,
+    ) {
+        use crate::ir::module_init;
+        use crate::ir::{IrGlobalId, Linkage};
+
+        let defined: HashMap<_, IrGlobalId> = module
+            .globals
+            .values()
+            .filter(|global| global.linkage != Linkage::External)
+            .map(|global| (global.symbol_id, global.id))
+            .collect();
+        let resolved: HashMap<IrGlobalId, IrGlobalId> = module
+            .globals
+            .values()
+            .filter(|global| global.linkage == Linkage::External)
+            .filter_map(|global| Some((global.id, *defined.get(&global.symbol_id)?)))
+            .collect();
+        if !resolved.is_empty() {
+            for func in module.functions.values_mut() {
+                for block in func.cfg.blocks.values_mut() {
+                    for inst in &mut block.instructions {
+                        if let IrInstruction::LoadGlobal { global_id, .. }
+                        | IrInstruction::StoreGlobal { global_id, .. } = inst
+                        {
+                            if let Some(&definition) = resolved.get(global_id) {
+                                *global_id = definition;
+                            }
+                        }
+                    }
+                }
+            }
+            module.globals.retain(|id, _| !resolved.contains_key(id));
+        }
+
+        let import_inits = match module_init::order_initializers(module, import_inits) {
+            Ok(order) => order,
+            Err(cycle) => {
+                warn!("{}; running them in import order", cycle);
+                import_inits.to_vec()
+            }
+        };
+        let import_inits: Vec<_> = if lazy {
+            let (eager, deferred): (Vec<_>, Vec<_>) = import_inits
+                .into_iter()
+                .partition(|&init| module_init::runs_class_initializers(module, init));
+            module_init::make_lazy(module, &deferred);
+            eager
+        } else {
+            import_inits
+        };
+
+        let Some((&last_init, earlier_inits)) = import_inits.split_last() else {
+            return;
+        };
+        let (init_id, prelude) = match module
+            .functions
+            .values()
+            .find(|f| f.name == module_init::INIT_FUNCTION)
+        {
+            Some(init) => (init.id, import_inits.as_slice()),
+            None => {
+                // No initializers of its own: the last import's becomes the
+                // module's `__init__`, running the others first
+                if let Some(init) = module.functions.get_mut(&last_init) {
+                    init.name = module_init::INIT_FUNCTION.to_string();
+                }
+                (last_init, earlier_inits)
+            }
+        };
+        module_init::prepend_calls(module, init_id, prelude);
+    }
+
     /// Compile a single file using shared state (backward-compatible wrapper)
 
     fn compile_file_with_shared_state(
@@ -3356,7 +3828,13 @@ impl CompilationUnit {
             .fold(
                 (Vec::new(), Vec::new()),
                 |(mut imports, mut usings), (filename, source)| {
-                    if let Ok(ast) = parser::parse_haxe_file(&filename, &source, false) {
+                    if let Ok(ast) = parser::parse_haxe_file_with_config(
+                        &filename,
+                        &source,
+                        false,
+                        false,
+                        &self.config.preprocessor,
+                    ) {
                         // Collect imports
                         for import in &ast.imports {
                             if !import.path.is_empty() {
@@ -3382,6 +3860,31 @@ impl CompilationUnit {
         // Pre-load imports using efficient topological loading (avoids retry loops)
         let mut all_imports = imports_to_load;
         all_imports.extend(usings_to_load);
+
+        // Lazy stdlib: nothing was registered upfront, so also load the stdlib
+        // modules behind implicitly available types the user code references
+        // (Math, StringTools, Lambda, ...)
+        if self.config.lazy_stdlib && self.config.load_stdlib {
+            let mut declared = HashSet::new();
+            let mut sources = Vec::new();
+            for file in &self.user_files {
+                let Some(source) = file.input.as_ref() else {
+                    continue;
+                };
+                if let Ok(ast) = parser::parse_haxe_file_with_config(
+                    &file.filename,
+                    source,
+                    false,
+                    false,
+                    &self.config.preprocessor,
+                ) {
+                    declared.extend(ast.declarations.iter().filter_map(declared_type_name));
+                }
+                sources.push(source.as_str());
+            }
+            all_imports.extend(self.collect_reachable_stdlib_modules(&declared, &sources));
+        }
+
         let _ = self.load_imports_efficiently(&all_imports);
 
         // Step 3: Compile import.hx files using SHARED state
@@ -3424,7 +3927,7 @@ impl CompilationUnit {
                     // Check if any errors are unresolved types that we can try to load on-demand
                     let (loadable, other): (Vec<_>, Vec<_>) = errors.into_iter().partition(|e| {
                         e.message.contains("Unresolved type")
-                            || e.message.contains("UnresolvedType")
+                            || e.message.contains(UNRESOLVED_TYPE_MESSAGE)
                     });
 
                     // Try to load unresolved types on-demand
@@ -3462,7 +3965,7 @@ impl CompilationUnit {
                                 let (retry_loadable, retry_other): (Vec<_>, Vec<_>) =
                                     retry_errors.into_iter().partition(|e| {
                                         e.message.contains("Unresolved type")
-                                            || e.message.contains("UnresolvedType")
+                                            || e.message.contains(UNRESOLVED_TYPE_MESSAGE)
                                     });
 
                                 let mut retry_loaded = false;
@@ -3536,15 +4039,10 @@ impl CompilationUnit {
     /// Extract the type name from an unresolved type error message
     fn extract_type_name_from_error(&self, message: &str) -> Option<String> {
         // Try to extract type name from error message formats:
-        // "UnresolvedType { type_name: \"haxe.iterators.ArrayIterator\", ... }"
+        // "Cannot find type 'haxe.iterators.ArrayIterator'"
         // "Unresolved type: haxe.iterators.ArrayIterator"
-        let type_name = if let Some(start) = message.find("type_name: \"") {
-            let start = start + "type_name: \"".len();
-            if let Some(end) = message[start..].find('"') {
-                Some(message[start..start + end].to_string())
-            } else {
-                None
-            }
+        let type_name = if message.contains(UNRESOLVED_TYPE_MESSAGE) {
+            Self::extract_unresolved_type_from_error(message)
         } else if let Some(start) = message.find("Unresolved type: ") {
             let start = start + "Unresolved type: ".len();
             let end = message[start..]
@@ -3599,14 +4097,19 @@ impl CompilationUnit {
 
         let cache_path = self.config.get_cache_path(source_path);
         if !cache_path.exists() {
-            return None;
+            let source = std::fs::read_to_string(source_path).ok()?;
+            return self.fetch_remote_blade(
+                &Self::remote_module_name(source_path),
+                &cache_path,
+                &source,
+            );
         }
 
         // Load BLADE file
         let (mir_module, metadata) = match load_blade(&cache_path) {
             Ok(data) => data,
             Err(e) => {
-                warn!("Failed to load cache for {:?}: {}", source_path, e);
+                Self::discard_blade(&cache_path, &e);
                 return None;
             }
         };
@@ -3619,8 +4122,14 @@ impl CompilationUnit {
                     .unwrap_or_default()
                     .as_secs();
 
-                // Cache is stale if source was modified after cache was created
-                if source_timestamp > metadata.compile_timestamp {
+                // Cache is stale if source was modified after cache was created,
+                // unless the content is unchanged (e.g. a fresh checkout, or an
+                // artifact fetched from the remote cache)
+                let unchanged = || {
+                    std::fs::read_to_string(source_path)
+                        .is_ok_and(|s| self.cache_hash(&s) == metadata.source_hash)
+                };
+                if source_timestamp > metadata.compile_timestamp && !unchanged() {
                     if self.config.enable_cache {
                         debug!(
                             "Cache stale for {:?} (source: {}, cache: {})",
@@ -3673,7 +4182,7 @@ impl CompilationUnit {
 
         // Read source for hash computation
         let source_hash = std::fs::read_to_string(source_path)
-            .map(|s| Self::hash_source(&s))
+            .map(|s| self.cache_hash(&s))
             .unwrap_or(0);
 
         let compile_timestamp = SystemTime::now()
@@ -3690,11 +4199,17 @@ impl CompilationUnit {
             compile_timestamp,
             dependencies: Vec::new(), // TODO: Track dependencies for proper invalidation
             compiler_version: env!("CARGO_PKG_VERSION").to_string(),
+            functions: function_dependencies(module),
         };
 
         // Save to BLADE file
         save_blade(&cache_path, module, metadata)
             .map_err(|e| format!("Failed to save cache: {}", e))?;
+        self.store_remote_blade(
+            &Self::remote_module_name(source_path),
+            &cache_path,
+            source_hash,
+        );
 
         if self.config.enable_cache {
             debug!("Cached MIR for {:?} -> {:?}", source_path, cache_path);
@@ -3719,39 +4234,73 @@ impl CompilationUnit {
     /// Print compilation errors with formatted diagnostics to stderr.
     /// Uses the diagnostics crate's ErrorFormatter for consistent formatting.
     pub fn print_compilation_errors(&self, errors: &[CompilationError]) {
-        use diagnostics::{ErrorFormatter, SourceMap};
+        eprint!("{}", self.format_compilation_errors(errors, true));
+    }
 
-        // Build source map with all parsed files
-        let mut source_map = SourceMap::new();
+    /// Files with preserved source, in the order `format_compilation_errors`
+    /// adds them to its source map: stdlib, import.hx, then user files.
+    fn diagnostic_files(&self) -> impl Iterator<Item = &HaxeFile> {
+        self.stdlib_files
+            .iter()
+            .chain(&self.import_hx_files)
+            .chain(&self.user_files)
+            .filter(|file| file.input.is_some())
+    }
 
-        // Add stdlib files
-        for stdlib_file in &self.stdlib_files {
-            if let Some(ref source) = stdlib_file.input {
-                source_map.add_file(stdlib_file.filename.clone(), source.clone());
-            }
+    /// The source map file id `format_compilation_errors` gives `filename`.
+    fn diagnostic_file_id(&self, filename: &str) -> Option<u32> {
+        self.diagnostic_files()
+            .position(|file| file.filename == filename)
+            .map(|index| index as u32)
+    }
+
+    /// Format compilation errors into a single string, as printed by
+    /// `print_compilation_errors`. Pass `use_colors = false` for plain text
+    /// (e.g. when snapshotting diagnostics output). With
+    /// `DiagnosticFormat::Json` each error is a line of JSON and
+    /// `use_colors` is ignored.
+    pub fn format_compilation_errors(
+        &self,
+        errors: &[CompilationError],
+        use_colors: bool,
+    ) -> String {
+        use diagnostics::json::JsonFormatter;
+        use diagnostics::{DiagnosticFormat, ErrorFormatter, SourceMap};
+
+        // Nothing to format: skip copying every parsed source into a map
+        if errors.is_empty() {
+            return String::new();
         }
 
-        // Add import.hx files
-        for import_file in &self.import_hx_files {
-            if let Some(ref source) = import_file.input {
-                source_map.add_file(import_file.filename.clone(), source.clone());
+        // Build source map with all parsed files
+        let mut source_map = SourceMap::new();
+        for file in self.diagnostic_files() {
+            if let Some(ref source) = file.input {
+                source_map.add_file(file.filename.clone(), source.clone());
             }
         }
 
-        // Add user files
-        for user_file in &self.user_files {
-            if let Some(ref source) = user_file.input {
-                source_map.add_file(user_file.filename.clone(), source.clone());
+        let mut output = String::new();
+        if self.config.diagnostic_format == DiagnosticFormat::Json {
+            let formatter = JsonFormatter::new();
+            for error in errors {
+                let diagnostic = error.to_diagnostic(&source_map);
+                output.push_str(&formatter.format_diagnostic(&diagnostic, &source_map));
+                output.push('\n');
             }
+            return output;
         }
 
-        let formatter = ErrorFormatter::with_colors();
-
+        let formatter = if use_colors {
+            ErrorFormatter::with_colors()
+        } else {
+            ErrorFormatter::new()
+        };
         for error in errors {
             let diagnostic = error.to_diagnostic(&source_map);
-            let formatted = formatter.format_diagnostic(&diagnostic, &source_map);
-            eprint!("{}", formatted);
+            output.push_str(&formatter.format_diagnostic(&diagnostic, &source_map));
         }
+        output
     }
 
     /// Get cache statistics
@@ -3778,12 +4327,58 @@ impl CompilationUnit {
         stats
     }
 
+    /// Check every entry of the cache directory (header, checksum, compiler
+    /// stamp and payload), removing bad entries when `prune` is set
+    pub fn verify_cache(&self, prune: bool) -> CacheVerification {
+        let cache_dir = self.config.get_cache_dir();
+        let mut report = CacheVerification::default();
+
+        let Ok(entries) = std::fs::read_dir(&cache_dir) else {
+            return report;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("blade"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            report.checked += 1;
+            if let Err(e) = verify_blade(&path) {
+                let pruned = prune && std::fs::remove_file(&path).is_ok();
+                report.bad.push(BadCacheEntry {
+                    path,
+                    error: e.to_string(),
+                    pruned,
+                });
+            }
+        }
+        report
+    }
+
     /// Get the MIR modules that were generated during compilation.
     /// Returns a vector of MIR modules corresponding to the compiled files.
     pub fn get_mir_modules(&self) -> Vec<std::sync::Arc<crate::ir::IrModule>> {
         self.mir_modules.clone()
     }
 
+    /// Source files outside the stdlib that the compile read: files added
+    /// directly, import.hx files and modules loaded through imports
+    pub fn user_source_files(&self) -> Vec<PathBuf> {
+        let stdlib_paths = &self.config.stdlib_paths;
+        let files: BTreeSet<PathBuf> = self
+            .user_files
+            .iter()
+            .chain(&self.import_hx_files)
+            .map(|file| PathBuf::from(&file.filename))
+            .chain(self.namespace_resolver.loaded_files().iter().cloned())
+            .filter(|path| !stdlib_paths.iter().any(|dir| path.starts_with(dir)))
+            .filter(|path| path.is_file())
+            .collect();
+        files.into_iter().collect()
+    }
+
     /// Get HDLL function pointers for JIT linking.
     ///
     /// Returns symbol name and pointer pairs collected from all loaded HDLL plugins.
@@ -3975,10 +4570,83 @@ impl CacheStats {
     }
 }
 
+/// Result of [`CompilationUnit::verify_cache`]
+#[derive(Debug, Default)]
+pub struct CacheVerification {
+    pub checked: usize,
+    pub bad: Vec<BadCacheEntry>,
+}
+
+/// A cache entry that failed verification
+#[derive(Debug)]
+pub struct BadCacheEntry {
+    pub path: PathBuf,
+    pub error: String,
+    /// Whether the entry was removed
+    pub pruned: bool,
+}
+
 /// Collect qualified type references from a parsed AST.
 /// Walks all type declarations and their type references, collecting any
 /// TypePath with a non-empty package as an implicit import.
 /// For example, `new haxe.ds.BalancedTree<Int, String>()` yields "haxe.ds.BalancedTree".
+/// Name of a top-level type declaration (None for conditional blocks)
+fn declared_type_name(decl: &parser::haxe_ast::TypeDeclaration) -> Option<String> {
+    use parser::haxe_ast::TypeDeclaration;
+    match decl {
+        TypeDeclaration::Class(c) => Some(c.name.clone()),
+        TypeDeclaration::Interface(i) => Some(i.name.clone()),
+        TypeDeclaration::Enum(e) => Some(e.name.clone()),
+        TypeDeclaration::Typedef(t) => Some(t.name.clone()),
+        TypeDeclaration::Abstract(a) => Some(a.name.clone()),
+        TypeDeclaration::Conditional(_) => None,
+    }
+}
+
+/// Capitalized identifiers in a source file, i.e. candidate type references.
+/// A lexical scan is enough here: comments and string contents only produce
+/// names that either don't resolve to a stdlib module or cost one extra load.
+fn referenced_type_names(source: &str) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    let bytes = source.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c.is_ascii_alphabetic() || c == b'_' {
+            let start = i;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            // Skip member accesses (`x.Foo`); qualified paths come from imports
+            let after_dot = start > 0 && bytes[start - 1] == b'.';
+            if c.is_ascii_uppercase() && !after_dot {
+                names.insert(source[start..i].to_string());
+            }
+        } else if c.is_ascii_digit() {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    names
+}
+
+/// Report a failed plugin transform hook, attributed to the plugin.
+fn plugin_hook_error(err: crate::compiler_plugin::PluginHookError) -> CompilationError {
+    CompilationError {
+        suggestion: Some(format!(
+            "the '{}' compiler plugin rejected this file; update or remove the plugin",
+            err.plugin
+        )),
+        message: err.to_string(),
+        location: SourceLocation::unknown(),
+        category: ErrorCategory::PluginError,
+        related_errors: Vec::new(),
+    }
+}
+
 fn collect_qualified_type_refs_from_ast(ast: &parser::HaxeFile, out: &mut Vec<String>) {
     use parser::haxe_ast::{BlockElement, ClassFieldKind, Expr, ExprKind, Type, TypeDeclaration};
     use std::collections::HashSet;
@@ -4140,7 +4808,8 @@ fn collect_qualified_type_refs_from_ast(ast: &parser::HaxeFile, out: &mut Vec<St
             ExprKind::Paren(e)
             | ExprKind::Throw(e)
             | ExprKind::Untyped(e)
-            | ExprKind::Meta { expr: e, .. } => {
+            | ExprKind::Meta { expr: e, .. }
+            | ExprKind::StaticVar(e) => {
                 collect_from_expr(e, seen, out);
             }
             ExprKind::Ternary {
@@ -4190,6 +4859,14 @@ fn collect_qualified_type_refs_from_ast(ast: &parser::HaxeFile, out: &mut Vec<St
                 }
             }
             ClassFieldKind::Function(func) => {
+                // Generators are rewritten to use these during lowering
+                if crate::tast::generators::is_generator(func) {
+                    for dependency in crate::tast::generators::GENERATOR_DEPENDENCIES {
+                        if seen.insert(dependency.to_string()) {
+                            out.push(dependency.to_string());
+                        }
+                    }
+                }
                 if let Some(ret) = &func.return_type {
                     collect_from_type(ret, seen, out);
                 }
@@ -4253,7 +4930,11 @@ mod tests {
 
     #[test]
     fn test_compilation_unit_with_stdlib() {
-        let mut unit = CompilationUnit::new(CompilationConfig::default());
+        // From source: with an embedded stdlib no default imports are parsed
+        let mut unit = CompilationUnit::new(CompilationConfig {
+            stdlib_from_source: true,
+            ..Default::default()
+        });
 
         // Load stdlib
         unit.load_stdlib().expect("Failed to load stdlib");
@@ -4309,4 +4990,155 @@ mod tests {
 
         assert!(typed_files.len() > 0, "Should have typed files");
     }
+
+    #[test]
+    fn test_interface_catch_is_rejected() {
+        let mut unit = CompilationUnit::new(CompilationConfig::default());
+        unit.load_stdlib().expect("Failed to load stdlib");
+
+        let source = r#"
+            interface Failure {}
+            class Main {
+                static function main() {
+                    try {
+                        trace("try");
+                    } catch (e:Failure) {
+                        trace("caught");
+                    }
+                }
+            }
+        "#;
+        unit.add_file(source, "Main.hx")
+            .expect("Failed to add file");
+
+        let errors = unit
+            .lower_to_tast()
+            .expect_err("catching an interface should not compile");
+        assert!(errors
+            .iter()
+            .any(|e| e.message.contains("Catching an interface type")));
+    }
+
+    #[test]
+    fn test_stdlib_from_source_ignores_embedded_stdlib() {
+        let unit = CompilationUnit::new(CompilationConfig {
+            stdlib_from_source: true,
+            ..Default::default()
+        });
+        assert!(unit.embedded_stdlib().is_none());
+
+        let unit = CompilationUnit::new(CompilationConfig::default());
+        assert_eq!(
+            unit.embedded_stdlib().is_some(),
+            embedded_stdlib().is_some()
+        );
+    }
+
+    #[test]
+    fn test_bare_metal_skips_stdlib() {
+        let mut unit = CompilationUnit::new(CompilationConfig::bare_metal());
+        unit.load_stdlib().expect("Failed to load stdlib");
+        assert_eq!(unit.stdlib_files.len(), 0);
+    }
+
+    #[test]
+    fn test_trace_positions_compiled_only_when_enabled() {
+        let source = r#"
+            class Main {
+                static function main() {
+                    trace("hello");
+                }
+            }
+        "#;
+
+        for trace_positions in [false, true] {
+            let mut unit = CompilationUnit::new(CompilationConfig {
+                trace_positions,
+                ..Default::default()
+            });
+            unit.load_stdlib().expect("Failed to load stdlib");
+            unit.add_file(source, "Main.hx")
+                .expect("Failed to add file");
+            unit.lower_to_tast().expect("Failed to compile");
+
+            let emits_positions = unit.get_mir_modules().iter().any(|module| {
+                module
+                    .functions
+                    .values()
+                    .any(|f| f.name == "haxe_trace_pos")
+                    || module
+                        .extern_functions
+                        .values()
+                        .any(|f| f.name == "haxe_trace_pos")
+            });
+            assert_eq!(emits_positions, trace_positions);
+        }
+    }
+
+    #[test]
+    fn test_json_diagnostic_format() {
+        let mut unit = CompilationUnit::new(CompilationConfig {
+            diagnostic_format: diagnostics::DiagnosticFormat::Json,
+            ..CompilationConfig::bare_metal()
+        });
+        unit.add_file("class Main {}\n", "Main.hx")
+            .expect("Failed to add file");
+        let error = CompilationError {
+            message: "Type 'Main' is unused".to_string(),
+            location: SourceLocation::new(0, 1, 7, 6),
+            category: ErrorCategory::TypeError,
+            suggestion: Some("Remove it".to_string()),
+            related_errors: Vec::new(),
+        };
+
+        let output = unit.format_compilation_errors(&[error.clone(), error], true);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(
+            "{\"severity\":\"error\",\"code\":\"E0100\",\
+             \"message\":\"Type 'Main' is unused\",\
+             \"spans\":[{\"file\":\"Main.hx\",\"line_start\":1,"
+        ));
+        assert!(lines[0].contains("\"help\":[\"Remove it\"]"));
+        assert!(!output.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_extract_unresolved_type() {
+        use crate::tast::ast_lowering::LoweringError;
+
+        let error = LoweringError::UnresolvedType {
+            type_name: "haxe.io.Bytes".to_string(),
+            location: SourceLocation::new(0, 3, 9, 40),
+        }
+        .to_compilation_error();
+        assert_eq!(
+            CompilationUnit::extract_unresolved_type_from_error(&error.message),
+            Some("haxe.io.Bytes".to_string())
+        );
+
+        let unit = CompilationUnit::new(CompilationConfig::bare_metal());
+        assert_eq!(
+            unit.extract_type_name_from_error(&error.message),
+            Some("haxe.io.Bytes".to_string())
+        );
+        assert_eq!(
+            unit.extract_type_name_from_error("Unresolved type: haxe.ds.StringMap"),
+            Some("haxe.ds.StringMap".to_string())
+        );
+        assert_eq!(
+            CompilationUnit::extract_unresolved_type_from_error("Cannot find name 'y'"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_referenced_type_names() {
+        let names = referenced_type_names(
+            "import haxe.io.Bytes;\n\
+             class Main { static function main() { trace(StringTools.trim(Std.string(0x1F))); } }",
+        );
+        let names: Vec<&str> = names.iter().map(|s| s.as_str()).collect();
+        assert_eq!(names, ["Main", "Std", "StringTools"]);
+    }
 }
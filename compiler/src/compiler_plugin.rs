@@ -8,6 +8,8 @@
 //! - Register method mappings (Haxe method -> runtime function)
 //! - Declare extern function signatures in MIR
 //! - Build MIR wrapper functions
+//! - Optionally provide code generation backends (`codegen::backend`)
+//! - Optionally transform each file's AST, TAST or MIR (`on_ast`/`on_tast`/`on_mir`)
 //!
 //! **Runtime plugins** (`rayzor_plugin` crate):
 //! - Provide function pointers for JIT linking
@@ -44,9 +46,17 @@
 //! let mapping = registry.build_combined_mapping();
 //! ```
 
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use parser::HaxeFile;
+
+use crate::codegen::backend::{BackendFactory, BackendRegistry};
 use crate::ir::mir_builder::MirBuilder;
+use crate::ir::IrModule;
 use crate::stdlib::{array, channel, memory, stdtypes, string, sync, thread, vec, vec_u8};
 use crate::stdlib::{MethodSignature, RuntimeFunctionCall, StdlibMapping};
+use crate::tast::TypedFile;
 
 /// Trait for compiler plugins that provide stdlib method mappings.
 ///
@@ -61,6 +71,12 @@ use crate::stdlib::{MethodSignature, RuntimeFunctionCall, StdlibMapping};
 /// 2. During compilation, `method_mappings()` provides Haxe → runtime function mapping
 /// 3. During MIR building, `declare_externs()` registers extern function signatures
 /// 4. Optionally, `build_mir_wrappers()` creates MIR wrapper functions
+/// 5. For every compiled file, `on_ast()` runs after macro expansion,
+///    `on_tast()` after type checking and `on_mir()` after MIR lowering
+///
+/// Transform hooks run in the same order mappings are combined: ascending
+/// priority, then registration order. A hook error (or panic) aborts the
+/// file's compilation with a diagnostic naming the plugin and stage.
 pub trait CompilerPlugin: Send + Sync {
     /// Returns the plugin name for debugging and identification
     fn name(&self) -> &str;
@@ -90,6 +106,67 @@ pub trait CompilerPlugin: Send + Sync {
     fn priority(&self) -> i32 {
         0
     }
+
+    /// Code generation backends this plugin provides, by `--backend` name.
+    ///
+    /// Most plugins only add stdlib mappings and return nothing here.
+    fn codegen_backends(&self) -> Vec<(&'static str, BackendFactory)> {
+        Vec::new()
+    }
+
+    /// Transform a parsed file after macro expansion, before type checking.
+    fn on_ast(&self, _file: &mut HaxeFile) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Transform a type-checked file before HIR lowering.
+    fn on_tast(&self, _file: &mut TypedFile) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Transform a file's MIR after lowering, before it is merged with the
+    /// stdlib and monomorphized.
+    fn on_mir(&self, _module: &mut IrModule) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Compilation stage a transform hook runs at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    Ast,
+    Tast,
+    Mir,
+}
+
+impl HookStage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HookStage::Ast => "on_ast",
+            HookStage::Tast => "on_tast",
+            HookStage::Mir => "on_mir",
+        }
+    }
+}
+
+/// A transform hook failure, attributed to the plugin that raised it.
+#[derive(Debug, Clone)]
+pub struct PluginHookError {
+    pub plugin: String,
+    pub stage: HookStage,
+    pub message: String,
+}
+
+impl fmt::Display for PluginHookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "plugin '{}' failed in {}: {}",
+            self.plugin,
+            self.stage.as_str(),
+            self.message
+        )
+    }
 }
 
 /// Registry for managing multiple runtime plugins.
@@ -155,6 +232,70 @@ impl CompilerPluginRegistry {
         }
     }
 
+    /// Add the code generation backends of all registered plugins.
+    pub fn register_codegen_backends(&self, backends: &mut BackendRegistry) {
+        for plugin in &self.plugins {
+            backends.add_plugin(plugin.as_ref());
+        }
+    }
+
+    /// Plugins in hook order: ascending priority, then registration order.
+    fn ordered(&self) -> Vec<&dyn CompilerPlugin> {
+        let mut sorted: Vec<&dyn CompilerPlugin> =
+            self.plugins.iter().map(|p| p.as_ref()).collect();
+        sorted.sort_by_key(|p| p.priority());
+        sorted
+    }
+
+    /// Run one plugin's hook, turning an error or panic into a `PluginHookError`.
+    fn run_hook(
+        plugin: &dyn CompilerPlugin,
+        stage: HookStage,
+        hook: impl FnOnce() -> Result<(), String>,
+    ) -> Result<(), PluginHookError> {
+        let message = match catch_unwind(AssertUnwindSafe(hook)) {
+            Ok(Ok(())) => return Ok(()),
+            Ok(Err(message)) => message,
+            Err(payload) => {
+                let detail = payload
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string());
+                format!("panicked: {}", detail)
+            }
+        };
+        Err(PluginHookError {
+            plugin: plugin.name().to_string(),
+            stage,
+            message,
+        })
+    }
+
+    /// Run every plugin's `on_ast` hook on a parsed file.
+    pub fn run_ast_hooks(&self, file: &mut HaxeFile) -> Result<(), PluginHookError> {
+        for plugin in self.ordered() {
+            Self::run_hook(plugin, HookStage::Ast, || plugin.on_ast(file))?;
+        }
+        Ok(())
+    }
+
+    /// Run every plugin's `on_tast` hook on a type-checked file.
+    pub fn run_tast_hooks(&self, file: &mut TypedFile) -> Result<(), PluginHookError> {
+        for plugin in self.ordered() {
+            Self::run_hook(plugin, HookStage::Tast, || plugin.on_tast(file))?;
+        }
+        Ok(())
+    }
+
+    /// Run every plugin's `on_mir` hook on a lowered module.
+    pub fn run_mir_hooks(&self, module: &mut IrModule) -> Result<(), PluginHookError> {
+        for plugin in self.ordered() {
+            Self::run_hook(plugin, HookStage::Mir, || plugin.on_mir(module))?;
+        }
+        Ok(())
+    }
+
     /// Get the names of all registered plugins.
     pub fn plugin_names(&self) -> Vec<&str> {
         self.plugins.iter().map(|p| p.name()).collect()
@@ -543,6 +684,104 @@ mod tests {
         assert_eq!(registry.plugin_names(), vec!["test1", "test2"]);
     }
 
+    /// Appends its name to every class in the AST, or fails/panics on demand.
+    struct RenamePlugin {
+        name: &'static str,
+        priority: i32,
+        fail: Option<&'static str>,
+    }
+
+    impl CompilerPlugin for RenamePlugin {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn method_mappings(&self) -> Vec<(MethodSignature, RuntimeFunctionCall)> {
+            vec![]
+        }
+
+        fn declare_externs(&self, _builder: &mut MirBuilder) {}
+
+        fn build_mir_wrappers(&self, _builder: &mut MirBuilder) {}
+
+        fn priority(&self) -> i32 {
+            self.priority
+        }
+
+        fn on_ast(&self, file: &mut HaxeFile) -> Result<(), String> {
+            match self.fail {
+                Some("panic") => panic!("bad rewrite"),
+                Some(msg) => return Err(msg.to_string()),
+                None => {}
+            }
+            for decl in &mut file.declarations {
+                if let parser::TypeDeclaration::Class(class) = decl {
+                    class.name.push_str(self.name);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn parse(source: &str) -> HaxeFile {
+        parser::parse_haxe_file("Test.hx", source, false).expect("parse")
+    }
+
+    fn class_name(file: &HaxeFile) -> String {
+        match &file.declarations[0] {
+            parser::TypeDeclaration::Class(class) => class.name.clone(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_ast_hooks_run_in_priority_order() {
+        let mut registry = CompilerPluginRegistry::new();
+        for (name, priority) in [("_b", 10), ("_a", 0), ("_c", 10)] {
+            registry.register(Box::new(RenamePlugin {
+                name,
+                priority,
+                fail: None,
+            }));
+        }
+        let mut file = parse("class Main {}");
+        registry.run_ast_hooks(&mut file).unwrap();
+        assert_eq!(class_name(&file), "Main_a_b_c");
+    }
+
+    #[test]
+    fn test_hook_errors_are_attributed() {
+        for (fail, expected) in [
+            (
+                "unsupported syntax",
+                "plugin 'bad' failed in on_ast: unsupported syntax",
+            ),
+            (
+                "panic",
+                "plugin 'bad' failed in on_ast: panicked: bad rewrite",
+            ),
+        ] {
+            let mut registry = CompilerPluginRegistry::new();
+            registry.register(Box::new(RenamePlugin {
+                name: "bad",
+                priority: 0,
+                fail: Some(fail),
+            }));
+            registry.register(Box::new(RenamePlugin {
+                name: "_later",
+                priority: 5,
+                fail: None,
+            }));
+            let mut file = parse("class Main {}");
+            let err = registry.run_ast_hooks(&mut file).unwrap_err();
+            assert_eq!(err.plugin, "bad");
+            assert_eq!(err.stage, HookStage::Ast);
+            assert_eq!(err.to_string(), expected);
+            // Later plugins do not run once one fails
+            assert_eq!(class_name(&file), "Main");
+        }
+    }
+
     #[test]
     fn test_builtin_plugin() {
         let plugin = BuiltinPlugin::new();
@@ -421,6 +421,16 @@ impl ErrorCodeRegistry {
             Some("Import paths must use valid module naming conventions"),
         ));
 
+        // ===== METADATA AND ANNOTATION ERRORS (E6000-E6999) =====
+
+        // Annotation control flow errors (E6400-E6499)
+        self.register(ErrorCode::new(
+            6401,
+            "Metadata",
+            "@:noThrow function can throw",
+            Some("Catch the exception inside the function or remove @:noThrow"),
+        ));
+
         // ===== INTERNAL COMPILER ERRORS (E9000-E9999) =====
 
         // Internal errors (E9000-E9099)
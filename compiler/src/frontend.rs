@@ -0,0 +1,196 @@
+//! MIR as a compilation target for alternate frontends
+//!
+//! Everything else in this crate starts from Haxe source. A frontend for
+//! another language or a DSL can instead build an `IrModule` itself (with
+//! `ir::mir_builder::MirBuilder`, or by hand) and run it through
+//! [`MirProgram`], which validates the module and executes it on the tiered
+//! JIT with the Rayzor runtime linked in.
+//!
+//! Contract for externally built modules:
+//! - The module must pass `ir::validation::validate_module`: SSA form,
+//!   terminated blocks, consistent types, calls only to functions of the module.
+//! - Runtime functions (`haxe_string_concat`, `rayzor_thread_spawn`, ...) are
+//!   declared as bodiless functions (`MirBuilder::mark_as_extern`) and linked
+//!   by name against the runtime symbols, plus any extra symbols supplied.
+//! - `__vtable_init__` and `__init__`, if present, run before the entry function.
+//!
+//! [`MIR_ABI_VERSION`] is bumped whenever MIR changes in a way that breaks
+//! modules built against an earlier version.
+//!
+//! ```rust,ignore
+//! use compiler::codegen::tiered_backend::TieredConfig;
+//! use compiler::frontend::MirProgram;
+//! use compiler::ir::mir_builder::MirBuilder;
+//!
+//! let mut builder = MirBuilder::new("MyDsl");
+//! let main = builder.begin_function("main").build();
+//! builder.set_current_function(main);
+//! let entry = builder.create_block("entry");
+//! builder.set_insert_point(entry);
+//! builder.ret(None);
+//!
+//! MirProgram::new(builder.finish(), "main")?.run(TieredConfig::default())?;
+//! ```
+
+use std::fmt;
+
+use crate::codegen::tiered_backend::{TieredBackend, TieredConfig};
+use crate::ir::validation::{validate_module, ValidationError};
+use crate::ir::{IrFunctionId, IrModule};
+
+/// Version of the MIR contract external frontends build against
+pub const MIR_ABI_VERSION: u32 = 1;
+
+/// Error from loading or running an externally built module
+#[derive(Debug)]
+pub enum FrontendError {
+    /// The module failed MIR validation
+    Invalid(Vec<ValidationError>),
+    /// No function with a body has the requested entry name
+    MissingEntry(String),
+    /// JIT compilation or execution failed
+    Backend(String),
+}
+
+impl fmt::Display for FrontendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrontendError::Invalid(errors) => {
+                write!(f, "invalid MIR module ({} error(s))", errors.len())?;
+                for error in errors {
+                    write!(f, "\n  {:?}", error.kind)?;
+                }
+                Ok(())
+            }
+            FrontendError::MissingEntry(name) => write!(f, "entry function '{}' not found", name),
+            FrontendError::Backend(msg) => write!(f, "backend error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for FrontendError {}
+
+/// A validated MIR module ready to run
+pub struct MirProgram {
+    module: IrModule,
+    entry: IrFunctionId,
+}
+
+impl MirProgram {
+    /// Validate `module` and resolve its entry function by name
+    pub fn new(module: IrModule, entry: &str) -> Result<Self, FrontendError> {
+        validate_module(&module).map_err(FrontendError::Invalid)?;
+        let entry = find_function(&module, entry)
+            .ok_or_else(|| FrontendError::MissingEntry(entry.to_string()))?;
+        Ok(Self { module, entry })
+    }
+
+    pub fn module(&self) -> &IrModule {
+        &self.module
+    }
+
+    pub fn entry(&self) -> IrFunctionId {
+        self.entry
+    }
+
+    /// Run on the tiered JIT with the Rayzor runtime
+    pub fn run(self, config: TieredConfig) -> Result<(), FrontendError> {
+        self.run_with_symbols(config, &[])
+    }
+
+    /// Run with additional native symbols for the module's extern functions
+    /// (e.g. a frontend's own runtime), on top of the Rayzor runtime
+    pub fn run_with_symbols(
+        self,
+        config: TieredConfig,
+        extra_symbols: &[(&str, *const u8)],
+    ) -> Result<(), FrontendError> {
+        use rayzor_plugin::RuntimePlugin;
+
+        let plugin = rayzor_runtime::plugin_impl::get_plugin();
+        let mut symbols: Vec<(&str, *const u8)> = plugin.runtime_symbols();
+        symbols.extend_from_slice(extra_symbols);
+
+        let inits: Vec<IrFunctionId> = ["__vtable_init__", "__init__"]
+            .iter()
+            .filter_map(|name| find_function(&self.module, name))
+            .collect();
+
+        let mut backend =
+            TieredBackend::with_symbols(config, &symbols).map_err(FrontendError::Backend)?;
+        backend
+            .compile_module(self.module)
+            .map_err(FrontendError::Backend)?;
+
+        let result = inits
+            .into_iter()
+            .chain(std::iter::once(self.entry))
+            .try_for_each(|func_id| backend.execute_function(func_id, vec![]).map(|_| ()))
+            .map_err(FrontendError::Backend);
+        backend.shutdown();
+        result
+    }
+}
+
+/// Function with a body named `name` (extern declarations are skipped)
+fn find_function(module: &IrModule, name: &str) -> Option<IrFunctionId> {
+    module
+        .functions
+        .iter()
+        .find(|(_, f)| f.name == name && !f.cfg.blocks.is_empty())
+        .map(|(id, _)| *id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::mir_builder::MirBuilder;
+    use crate::ir::IrType;
+
+    fn module_with_main(terminate: bool) -> IrModule {
+        let mut builder = MirBuilder::new("Dsl");
+        let print = builder
+            .begin_function("haxe_trace_int")
+            .param("value", IrType::I64)
+            .build();
+        builder.mark_as_extern(print);
+
+        let main = builder.begin_function("main").build();
+        builder.set_current_function(main);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let value = builder.const_i64(42);
+        builder.call(print, vec![value]);
+        if terminate {
+            builder.ret(None);
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn test_valid_module_with_extern() {
+        let program = MirProgram::new(module_with_main(true), "main").unwrap();
+        assert_eq!(program.module().functions[&program.entry()].name, "main");
+    }
+
+    #[test]
+    fn test_missing_entry() {
+        let err = MirProgram::new(module_with_main(true), "start")
+            .err()
+            .unwrap();
+        assert!(matches!(err, FrontendError::MissingEntry(ref name) if name == "start"));
+        // Extern declarations are not entry points
+        let err = MirProgram::new(module_with_main(true), "haxe_trace_int")
+            .err()
+            .unwrap();
+        assert!(matches!(err, FrontendError::MissingEntry(_)));
+    }
+
+    #[test]
+    fn test_invalid_module_is_rejected() {
+        let err = MirProgram::new(module_with_main(false), "main")
+            .err()
+            .unwrap();
+        assert!(matches!(err, FrontendError::Invalid(ref errors) if !errors.is_empty()));
+    }
+}
@@ -0,0 +1,593 @@
+//! Alias Analysis — can two memory accesses touch the same bytes?
+//!
+//! Passes that move or remove loads and stores (redundant load elimination,
+//! dead store elimination, LICM of loads, GVN across stores) ask
+//! [`AliasAnalysis::alias`] about pairs of accesses in one function.
+//!
+//! Every pointer is resolved to a base and, where the chain of `GetElementPtr`
+//! / `PtrAdd` indices is constant, a byte offset from it. Bases are allocation
+//! sites (`Alloc` stack slots, `malloc` results), parameters, or anything else
+//! (loaded pointers, call results, phis). The answer then follows from:
+//!
+//! - **Field sensitivity**: accesses off the same base at known offsets alias
+//!   exactly when their byte ranges overlap, so `this.x` and `this.y` don't.
+//! - **Allocation sites**: two distinct sites never alias, and a site can't
+//!   alias a parameter. As long as its pointer doesn't escape (it is only
+//!   loaded from, stored to, compared or freed) nothing else can reach it.
+//! - **Types**: an integer, a float and a pointer access never share memory
+//!   in Haxe code. Byte-sized, aggregate and untyped accesses alias anything.
+//!
+//! Functions with inline assembly, and passes that ask for it
+//! ([`AliasAnalysis::conservative`]), get the fallback: only accesses through
+//! the same pointer register are known to alias, everything else may.
+
+use super::blocks::IrTerminator;
+use super::functions::IrFunctionId;
+use super::instructions::IrInstruction;
+use super::{IrFunction, IrId, IrModule, IrType, IrValue};
+use std::collections::{HashMap, HashSet};
+
+/// Answer to an alias query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AliasResult {
+    /// The accesses never overlap
+    NoAlias,
+    /// The accesses may overlap
+    MayAlias,
+    /// The accesses cover exactly the same bytes
+    MustAlias,
+}
+
+/// Where a pointer ultimately points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerBase {
+    /// A stack slot (`Alloc`)
+    Stack(IrId),
+    /// A heap object fresh from an allocator (`malloc`)
+    Heap(IrId),
+    /// A function parameter
+    Param(IrId),
+    /// A loaded pointer, call result, phi or anything else
+    Unknown(IrId),
+}
+
+impl PointerBase {
+    /// Whether the base is an allocation site of the function
+    pub fn is_allocation(&self) -> bool {
+        matches!(self, PointerBase::Stack(_) | PointerBase::Heap(_))
+    }
+}
+
+/// A pointer as a base plus a byte offset, if constant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointerInfo {
+    pub base: PointerBase,
+    pub offset: Option<i64>,
+}
+
+/// A load or store: the address and the type accessed, if known
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryAccess {
+    pub ptr: IrId,
+    pub ty: Option<IrType>,
+}
+
+impl MemoryAccess {
+    /// The memory `inst` reads or writes, for loads and stores
+    pub fn of(inst: &IrInstruction, function: &IrFunction) -> Option<Self> {
+        let (ptr, ty) = match inst {
+            IrInstruction::Load { ptr, ty, .. } | IrInstruction::VectorLoad { ptr, ty, .. } => {
+                (*ptr, Some(ty.clone()))
+            }
+            IrInstruction::Store { ptr, value } | IrInstruction::VectorStore { ptr, value, .. } => {
+                (*ptr, function.register_types.get(value).cloned())
+            }
+            _ => return None,
+        };
+        Some(Self { ptr, ty })
+    }
+
+    fn size(&self) -> Option<i64> {
+        self.ty.as_ref().and_then(scalar_size)
+    }
+}
+
+/// Functions of `module` whose result is a fresh heap object
+pub fn allocator_ids(module: &IrModule) -> HashSet<IrFunctionId> {
+    let is_allocator = |name: &str| matches!(name, "malloc" | "rayzor_malloc_aligned");
+    let locals = module
+        .functions
+        .iter()
+        .filter(|(_, f)| is_allocator(&f.name))
+        .map(|(&id, _)| id);
+    let externs = module
+        .extern_functions
+        .iter()
+        .filter(|(_, f)| is_allocator(&f.name))
+        .map(|(&id, _)| id);
+    locals.chain(externs).collect()
+}
+
+/// Alias information for the pointers of one function
+#[derive(Debug, Clone, Default)]
+pub struct AliasAnalysis {
+    /// Answer only the trivial queries (same pointer register)
+    conservative: bool,
+    pointers: HashMap<IrId, PointerInfo>,
+    /// Allocation sites whose pointer is reachable from outside
+    escaped: HashSet<PointerBase>,
+}
+
+impl AliasAnalysis {
+    /// Analyze `function`; `allocators` are the functions returning fresh
+    /// heap objects ([`allocator_ids`])
+    pub fn new(function: &IrFunction, allocators: &HashSet<IrFunctionId>) -> Self {
+        let has_asm = function.cfg.blocks.values().any(|block| {
+            block
+                .instructions
+                .iter()
+                .any(|inst| matches!(inst, IrInstruction::InlineAsm { .. }))
+        });
+        if has_asm {
+            return Self::conservative();
+        }
+
+        let mut analysis = Self {
+            conservative: false,
+            pointers: resolve_pointers(function, allocators),
+            escaped: HashSet::new(),
+        };
+        analysis.escaped = analysis.escaped_sites(function);
+        analysis
+    }
+
+    /// The fallback analysis, knowing nothing about any pointer
+    pub fn conservative() -> Self {
+        Self {
+            conservative: true,
+            ..Self::default()
+        }
+    }
+
+    /// Base and offset of `ptr`
+    pub fn pointer(&self, ptr: IrId) -> PointerInfo {
+        self.pointers.get(&ptr).copied().unwrap_or(PointerInfo {
+            base: PointerBase::Unknown(ptr),
+            offset: Some(0),
+        })
+    }
+
+    /// Whether `base` is an allocation site nothing outside the function
+    /// can reach
+    pub fn is_local(&self, base: PointerBase) -> bool {
+        !self.conservative && base.is_allocation() && !self.escaped.contains(&base)
+    }
+
+    /// Whether accesses `a` and `b` may touch the same memory
+    pub fn alias(&self, a: &MemoryAccess, b: &MemoryAccess) -> AliasResult {
+        let same_size = a.size().is_some() && a.size() == b.size();
+        if a.ptr == b.ptr {
+            return if same_size {
+                AliasResult::MustAlias
+            } else {
+                AliasResult::MayAlias
+            };
+        }
+        if self.conservative {
+            return AliasResult::MayAlias;
+        }
+
+        let (pa, pb) = (self.pointer(a.ptr), self.pointer(b.ptr));
+        if pa.base == pb.base {
+            return match (pa.offset, pb.offset, a.size(), b.size()) {
+                (Some(oa), Some(ob), _, _) if oa == ob && same_size => AliasResult::MustAlias,
+                (Some(oa), Some(ob), Some(sa), Some(sb)) if oa + sa <= ob || ob + sb <= oa => {
+                    AliasResult::NoAlias
+                }
+                _ => AliasResult::MayAlias,
+            };
+        }
+
+        // Different bases: distinct sites, or a site and a parameter, are
+        // different objects; a site nobody else can reach is apart from all
+        let (ba, bb) = (pa.base, pb.base);
+        let site_vs_param = |x: PointerBase, y: PointerBase| {
+            x.is_allocation() && matches!(y, PointerBase::Param(_))
+        };
+        if (ba.is_allocation() && bb.is_allocation())
+            || site_vs_param(ba, bb)
+            || site_vs_param(bb, ba)
+            || self.is_local(ba)
+            || self.is_local(bb)
+        {
+            return AliasResult::NoAlias;
+        }
+
+        match (
+            a.ty.as_ref().and_then(type_class),
+            b.ty.as_ref().and_then(type_class),
+        ) {
+            (Some(ca), Some(cb)) if ca != cb => AliasResult::NoAlias,
+            _ => AliasResult::MayAlias,
+        }
+    }
+
+    /// Allocation sites whose pointer is stored, passed, returned, merged in
+    /// a phi or otherwise leaves the reach of this analysis
+    fn escaped_sites(&self, function: &IrFunction) -> HashSet<PointerBase> {
+        let mut escaped = HashSet::new();
+        let mut escape = |reg: &IrId| {
+            let base = self.pointer(*reg).base;
+            if base.is_allocation() {
+                escaped.insert(base);
+            }
+        };
+
+        for block in function.cfg.blocks.values() {
+            for phi in &block.phi_nodes {
+                phi.incoming.iter().for_each(|(_, value)| escape(value));
+            }
+            for inst in &block.instructions {
+                match inst {
+                    IrInstruction::Load { .. }
+                    | IrInstruction::VectorLoad { .. }
+                    | IrInstruction::Cmp { .. }
+                    | IrInstruction::Copy { .. }
+                    | IrInstruction::Move { .. }
+                    | IrInstruction::Cast { .. }
+                    | IrInstruction::BitCast { .. }
+                    | IrInstruction::Free { .. } => {}
+                    IrInstruction::Store { value, .. }
+                    | IrInstruction::VectorStore { value, .. } => escape(value),
+                    IrInstruction::GetElementPtr { indices, .. } => {
+                        indices.iter().for_each(&mut escape)
+                    }
+                    IrInstruction::PtrAdd { offset, .. } => escape(offset),
+                    other => other.uses().iter().for_each(&mut escape),
+                }
+            }
+            if let IrTerminator::Return { value: Some(value) } = &block.terminator {
+                escape(value);
+            }
+        }
+        escaped
+    }
+}
+
+/// Base and offset of every register of `function`
+fn resolve_pointers(
+    function: &IrFunction,
+    allocators: &HashSet<IrFunctionId>,
+) -> HashMap<IrId, PointerInfo> {
+    let mut resolver = Resolver {
+        allocators,
+        constants: HashMap::new(),
+        defs: HashMap::new(),
+        pointers: HashMap::new(),
+    };
+    for param in &function.signature.parameters {
+        let info = PointerInfo {
+            base: PointerBase::Param(param.reg),
+            offset: Some(0),
+        };
+        resolver.pointers.insert(param.reg, info);
+    }
+    for block in function.cfg.blocks.values() {
+        for inst in &block.instructions {
+            if let IrInstruction::Const { dest, value } = inst {
+                let value = match value {
+                    IrValue::I32(v) => Some(*v as i64),
+                    IrValue::I64(v) => Some(*v),
+                    IrValue::U32(v) => Some(*v as i64),
+                    IrValue::U64(v) => Some(*v as i64),
+                    _ => None,
+                };
+                if let Some(value) = value {
+                    resolver.constants.insert(*dest, value);
+                }
+            }
+            if let Some(dest) = inst.dest() {
+                resolver.defs.insert(dest, inst);
+            }
+        }
+    }
+
+    let regs: Vec<IrId> = resolver.defs.keys().copied().collect();
+    for reg in regs {
+        resolver.resolve(reg, 0);
+    }
+    resolver.pointers
+}
+
+/// Longest chain of pointer arithmetic followed back to its base
+const MAX_CHAIN: usize = 64;
+
+struct Resolver<'a> {
+    allocators: &'a HashSet<IrFunctionId>,
+    constants: HashMap<IrId, i64>,
+    defs: HashMap<IrId, &'a IrInstruction>,
+    pointers: HashMap<IrId, PointerInfo>,
+}
+
+impl Resolver<'_> {
+    fn resolve(&mut self, reg: IrId, depth: usize) -> PointerInfo {
+        if let Some(info) = self.pointers.get(&reg) {
+            return *info;
+        }
+        let root = PointerInfo {
+            base: PointerBase::Unknown(reg),
+            offset: Some(0),
+        };
+        let info = match self.defs.get(&reg).copied() {
+            _ if depth > MAX_CHAIN => root,
+            Some(IrInstruction::Alloc { .. }) => PointerInfo {
+                base: PointerBase::Stack(reg),
+                offset: Some(0),
+            },
+            Some(IrInstruction::CallDirect { func_id, .. })
+                if self.allocators.contains(func_id) =>
+            {
+                PointerInfo {
+                    base: PointerBase::Heap(reg),
+                    offset: Some(0),
+                }
+            }
+            Some(
+                IrInstruction::Copy { src, .. }
+                | IrInstruction::Move { src, .. }
+                | IrInstruction::Cast { src, .. }
+                | IrInstruction::BitCast { src, .. },
+            ) => self.resolve(*src, depth + 1),
+            Some(IrInstruction::GetElementPtr {
+                ptr, indices, ty, ..
+            }) => {
+                // Scaled as Cranelift lowers a GEP: bytes for byte pointers,
+                // 8-byte slots otherwise
+                let scale = match ty {
+                    IrType::Ptr(inner) if matches!(**inner, IrType::U8 | IrType::I8) => 1,
+                    _ => 8,
+                };
+                let bytes = match indices.as_slice() {
+                    [index] => self.constants.get(index).map(|index| index * scale),
+                    _ => None,
+                };
+                self.offset_by(*ptr, bytes, depth)
+            }
+            Some(IrInstruction::PtrAdd {
+                ptr, offset, ty, ..
+            }) => {
+                let scale = match ty {
+                    IrType::Ptr(inner) => scalar_size(inner),
+                    _ => None,
+                };
+                let bytes = self
+                    .constants
+                    .get(offset)
+                    .zip(scale)
+                    .map(|(n, size)| n * size);
+                self.offset_by(*ptr, bytes, depth)
+            }
+            _ => root,
+        };
+        self.pointers.insert(reg, info);
+        info
+    }
+
+    /// `ptr` moved by `bytes`, if known
+    fn offset_by(&mut self, ptr: IrId, bytes: Option<i64>, depth: usize) -> PointerInfo {
+        let info = self.resolve(ptr, depth + 1);
+        PointerInfo {
+            base: info.base,
+            offset: info.offset.zip(bytes).map(|(offset, bytes)| offset + bytes),
+        }
+    }
+}
+
+/// Size in bytes of a scalar access of type `ty`
+fn scalar_size(ty: &IrType) -> Option<i64> {
+    match ty {
+        IrType::Bool
+        | IrType::I8
+        | IrType::U8
+        | IrType::I16
+        | IrType::U16
+        | IrType::I32
+        | IrType::U32
+        | IrType::F32
+        | IrType::I64
+        | IrType::U64
+        | IrType::F64
+        | IrType::Ptr(_)
+        | IrType::Ref(_)
+        | IrType::Vector { .. } => Some(ty.size() as i64),
+        _ => None,
+    }
+}
+
+/// Kinds of value that never share memory (type-based aliasing)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TypeClass {
+    Int,
+    Float,
+    Pointer,
+}
+
+/// The class of an access of type `ty`; None for bytes and anything else
+/// that may view memory of any type
+fn type_class(ty: &IrType) -> Option<TypeClass> {
+    match ty {
+        IrType::I16 | IrType::U16 | IrType::I32 | IrType::U32 | IrType::I64 | IrType::U64 => {
+            Some(TypeClass::Int)
+        }
+        IrType::F32 | IrType::F64 => Some(TypeClass::Float),
+        IrType::Ptr(_) | IrType::Ref(_) => Some(TypeClass::Pointer),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::mir_builder::MirBuilder;
+
+    fn access(ptr: IrId, ty: IrType) -> MemoryAccess {
+        MemoryAccess { ptr, ty: Some(ty) }
+    }
+
+    /// Registers of `f(p, q, n)`, which allocates `a` and `b` and stores
+    /// `a` through `q` when `escape_a`
+    struct Fixture {
+        analysis: AliasAnalysis,
+        p: IrId,
+        q: IrId,
+        a: IrId,
+        a_field: IrId,
+        a_field_copy: IrId,
+        a_unknown: IrId,
+        b: IrId,
+        loaded: IrId,
+    }
+
+    fn fixture(escape_a: bool) -> Fixture {
+        let mut builder = MirBuilder::new("test");
+        let byte_ptr = builder.ptr_type(IrType::U8);
+        let ptr = builder.ptr_type(IrType::I64);
+        let ptr_ptr = builder.ptr_type(ptr.clone());
+        let malloc = builder
+            .begin_function("malloc")
+            .param("size", IrType::I64)
+            .returns(byte_ptr)
+            .build();
+        builder.mark_as_extern(malloc);
+
+        let f = builder
+            .begin_function("f")
+            .param("p", ptr.clone())
+            .param("q", ptr_ptr)
+            .param("n", IrType::I64)
+            .build();
+        builder.set_current_function(f);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let (p, q, n) = (
+            builder.get_param(0),
+            builder.get_param(1),
+            builder.get_param(2),
+        );
+        let size = builder.const_i64(16);
+        let a = builder.call(malloc, vec![size]).unwrap();
+        let b = builder.call(malloc, vec![size]).unwrap();
+        let one = builder.const_i64(1);
+        let a_field = builder.ptr_add(a, one, ptr.clone());
+        let a_field_copy = builder.cast(a_field, ptr.clone(), ptr.clone());
+        let a_unknown = builder.ptr_add(a, n, ptr.clone());
+        if escape_a {
+            builder.store(q, a);
+        }
+        let loaded = builder.load(q, ptr);
+        builder.ret(None);
+
+        let module = builder.finish();
+        let analysis = AliasAnalysis::new(&module.functions[&f], &allocator_ids(&module));
+        Fixture {
+            analysis,
+            p,
+            q,
+            a,
+            a_field,
+            a_field_copy,
+            a_unknown,
+            b,
+            loaded,
+        }
+    }
+
+    #[test]
+    fn test_field_sensitivity() {
+        let f = fixture(false);
+        let alias = |x, y| {
+            f.analysis
+                .alias(&access(x, IrType::I64), &access(y, IrType::I64))
+        };
+
+        assert_eq!(f.analysis.pointer(f.a_field).offset, Some(8));
+        assert_eq!(alias(f.a, f.a_field), AliasResult::NoAlias);
+        assert_eq!(alias(f.a_field, f.a_field_copy), AliasResult::MustAlias);
+        // A 16-byte access after the first field, then one covering it
+        assert_eq!(
+            f.analysis.alias(
+                &access(f.a, IrType::I64),
+                &access(
+                    f.a_field,
+                    IrType::Vector {
+                        element: Box::new(IrType::I64),
+                        count: 2,
+                    }
+                ),
+            ),
+            AliasResult::NoAlias
+        );
+        assert_eq!(
+            f.analysis.alias(
+                &access(
+                    f.a,
+                    IrType::Vector {
+                        element: Box::new(IrType::I64),
+                        count: 2,
+                    }
+                ),
+                &access(f.a_field, IrType::I64),
+            ),
+            AliasResult::MayAlias
+        );
+        // Unknown index into the same object
+        assert_eq!(alias(f.a, f.a_unknown), AliasResult::MayAlias);
+    }
+
+    #[test]
+    fn test_allocation_sites_and_types() {
+        let f = fixture(false);
+        let alias = |x, y| {
+            f.analysis
+                .alias(&access(x, IrType::I64), &access(y, IrType::I64))
+        };
+
+        assert_eq!(alias(f.a, f.b), AliasResult::NoAlias);
+        assert_eq!(alias(f.a_unknown, f.p), AliasResult::NoAlias);
+        assert_eq!(alias(f.a, f.loaded), AliasResult::NoAlias);
+        assert_eq!(alias(f.p, f.loaded), AliasResult::MayAlias);
+        assert_eq!(
+            f.analysis
+                .alias(&access(f.p, IrType::I64), &access(f.q, IrType::F64)),
+            AliasResult::NoAlias
+        );
+        assert_eq!(
+            f.analysis
+                .alias(&access(f.p, IrType::I64), &access(f.q, IrType::U8)),
+            AliasResult::MayAlias
+        );
+    }
+
+    #[test]
+    fn test_escaped_site_and_fallback() {
+        let f = fixture(true);
+        // Stored through `q`, so a pointer loaded back may be `a`
+        assert!(!f.analysis.is_local(PointerBase::Heap(f.a)));
+        assert_eq!(
+            f.analysis
+                .alias(&access(f.a, IrType::I64), &access(f.loaded, IrType::I64)),
+            AliasResult::MayAlias
+        );
+        // Still apart from the other site and the parameters
+        assert_eq!(
+            f.analysis
+                .alias(&access(f.a, IrType::I64), &access(f.b, IrType::I64)),
+            AliasResult::NoAlias
+        );
+
+        let conservative = AliasAnalysis::conservative();
+        let alias = |x, y| conservative.alias(&access(x, IrType::I64), &access(y, IrType::I64));
+        assert_eq!(alias(f.a, f.b), AliasResult::MayAlias);
+        assert_eq!(alias(f.a, f.a), AliasResult::MustAlias);
+    }
+}
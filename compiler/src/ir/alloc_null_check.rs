@@ -0,0 +1,530 @@
+//! Allocation Null Check Pass — guards heap allocation results in checked mode.
+//!
+//! Generated code calls libc `malloc`/`realloc` directly, so an allocation
+//! failure would otherwise surface as a null dereference somewhere later. With
+//! `--safety checked`, this pass routes null results through the runtime's
+//! `rayzor_alloc_failed` (after `malloc`) or `rayzor_realloc_failed` (after
+//! `realloc`), which run the installed OOM handler (`rayzor_set_oom_handler`)
+//! and abort with a report if no memory can be recovered. The slow path takes
+//! the allocator's own arguments, so a retried `realloc` keeps the contents of
+//! the old block.
+//!
+//! ## Transformation
+//!
+//! ```text
+//! B:  ...                            B:  ...
+//!     p = malloc(size)                   p = malloc(size)
+//!     <rest>                  =>         null = const null
+//!     <terminator>                       c = cmp eq p, null
+//!                                        condbr c, F, C
+//!                                    F:  p2 = rayzor_alloc_failed(size)
+//!                                        br C
+//!                                    C:  p' = phi [B: p], [F: p2]
+//!                                        <rest>            (p renamed to p')
+//!                                        <terminator>
+//! ```
+//!
+//! B dominates C, so every use of `p` after the call can be renamed to `p'`.
+
+use super::blocks::{IrBlockId, IrPhiNode, IrTerminator, OptimizationHint};
+use super::functions::IrFunctionId;
+use super::instructions::{CompareOp, IrInstruction};
+use super::optimization::{replace_terminator_uses, InstructionExt};
+use super::optimization::{OptimizationPass, OptimizationResult};
+use super::{IrFunction, IrId, IrModule, IrType, IrValue};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Runtime slow path called when `malloc` returns null.
+pub const ALLOC_FAILED_FN: &str = "rayzor_alloc_failed";
+
+/// Runtime slow path called when `realloc` returns null.
+pub const REALLOC_FAILED_FN: &str = "rayzor_realloc_failed";
+
+pub struct AllocNullCheckPass;
+
+impl AllocNullCheckPass {
+    pub fn new() -> Self {
+        AllocNullCheckPass
+    }
+}
+
+impl Default for AllocNullCheckPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Allocation functions found in a module
+#[derive(Default)]
+struct AllocFuncs {
+    malloc: Vec<IrFunctionId>,
+    realloc: Vec<IrFunctionId>,
+    alloc_failed: Option<IrFunctionId>,
+    realloc_failed: Option<IrFunctionId>,
+}
+
+impl OptimizationPass for AllocNullCheckPass {
+    fn name(&self) -> &'static str {
+        "AllocNullCheck"
+    }
+
+    fn run_on_module(&mut self, module: &mut IrModule) -> OptimizationResult {
+        let mut funcs = AllocFuncs::default();
+
+        let names = module
+            .functions
+            .iter()
+            .map(|(&fid, f)| (fid, f.name.as_str()))
+            .chain(
+                module
+                    .extern_functions
+                    .iter()
+                    .map(|(&fid, f)| (fid, f.name.as_str())),
+            );
+        for (fid, name) in names {
+            match name {
+                "malloc" => funcs.malloc.push(fid),
+                "realloc" => funcs.realloc.push(fid),
+                ALLOC_FAILED_FN => funcs.alloc_failed = Some(fid),
+                REALLOC_FAILED_FN => funcs.realloc_failed = Some(fid),
+                _ => {}
+            }
+        }
+
+        // Each allocator maps to the slow path that takes its arguments
+        let mut slow_paths = HashMap::new();
+        if !funcs.malloc.is_empty() {
+            let alloc_failed = funcs
+                .alloc_failed
+                .unwrap_or_else(|| declare_slow_path(module, ALLOC_FAILED_FN, false));
+            slow_paths.extend(funcs.malloc.iter().map(|&fid| (fid, alloc_failed)));
+        }
+        if !funcs.realloc.is_empty() {
+            let realloc_failed = funcs
+                .realloc_failed
+                .unwrap_or_else(|| declare_slow_path(module, REALLOC_FAILED_FN, true));
+            slow_paths.extend(funcs.realloc.iter().map(|&fid| (fid, realloc_failed)));
+        }
+
+        if slow_paths.is_empty() {
+            return OptimizationResult::unchanged();
+        }
+
+        let mut total_checks = 0;
+        let func_ids: Vec<_> = module.functions.keys().cloned().collect();
+        for func_id in func_ids {
+            if let Some(function) = module.functions.get_mut(&func_id) {
+                total_checks += check_function(function, &slow_paths);
+            }
+        }
+
+        if total_checks > 0 {
+            let mut stats = HashMap::new();
+            stats.insert("alloc_null_checks_inserted".to_string(), total_checks);
+            OptimizationResult {
+                modified: true,
+                instructions_eliminated: 0,
+                stats,
+                blocks_eliminated: 0,
+            }
+        } else {
+            OptimizationResult::unchanged()
+        }
+    }
+}
+
+/// Declare a slow path as a runtime extern: `rayzor_alloc_failed(size: u64)`
+/// or, `with_old_ptr`, `rayzor_realloc_failed(old: *u8, size: u64)`,
+/// returning `*u8`.
+fn declare_slow_path(module: &mut IrModule, name: &str, with_old_ptr: bool) -> IrFunctionId {
+    let mut parameters = Vec::new();
+    if with_old_ptr {
+        parameters.push(super::functions::IrParameter {
+            name: "old".to_string(),
+            ty: IrType::Ptr(Box::new(IrType::U8)),
+            reg: IrId(0),
+            by_ref: false,
+        });
+    }
+    parameters.push(super::functions::IrParameter {
+        name: "size".to_string(),
+        ty: IrType::U64,
+        reg: IrId(parameters.len() as u32),
+        by_ref: false,
+    });
+
+    let id = module.alloc_function_id();
+    module.extern_functions.insert(
+        id,
+        super::modules::IrExternFunction {
+            id,
+            name: name.to_string(),
+            symbol_id: crate::tast::SymbolId::from_raw(0),
+            signature: super::IrFunctionSignature {
+                parameters,
+                return_type: IrType::Ptr(Box::new(IrType::U8)),
+                calling_convention: super::CallingConvention::C,
+                can_throw: false,
+                type_params: vec![],
+                uses_sret: false,
+            },
+            source: "runtime".to_string(),
+        },
+    );
+    id
+}
+
+/// Insert null checks after every allocation call in a function.
+/// Returns the number of checks inserted.
+fn check_function(
+    function: &mut IrFunction,
+    slow_paths: &HashMap<IrFunctionId, IrFunctionId>,
+) -> usize {
+    let mut inserted = 0;
+    // Worklist of blocks to scan; continuation blocks are pushed back so
+    // several allocations in one block are each checked.
+    let mut worklist: Vec<IrBlockId> = function.cfg.blocks.keys().cloned().collect();
+    let mut failure_blocks: HashSet<IrBlockId> = HashSet::new();
+
+    while let Some(block_id) = worklist.pop() {
+        if failure_blocks.contains(&block_id) {
+            continue;
+        }
+        let Some(block) = function.cfg.get_block(block_id) else {
+            continue;
+        };
+
+        let found = block
+            .instructions
+            .iter()
+            .enumerate()
+            .find_map(|(idx, inst)| match inst {
+                IrInstruction::CallDirect {
+                    dest: Some(dest),
+                    func_id,
+                    args,
+                    ..
+                } => slow_paths
+                    .get(func_id)
+                    .map(|&slow_path| (idx, *dest, args.clone(), slow_path)),
+                _ => None,
+            });
+
+        let Some((call_idx, ptr, args, slow_path)) = found else {
+            continue;
+        };
+
+        let (fail_block, cont_block) =
+            split_after_allocation(function, block_id, call_idx, ptr, args, slow_path);
+        failure_blocks.insert(fail_block);
+        worklist.push(cont_block);
+        inserted += 1;
+    }
+
+    inserted
+}
+
+/// Split `block_id` after the allocation at `call_idx` and insert the null
+/// check. Returns the (failure, continuation) block IDs.
+fn split_after_allocation(
+    function: &mut IrFunction,
+    block_id: IrBlockId,
+    call_idx: usize,
+    ptr: IrId,
+    args: Vec<IrId>,
+    slow_path: IrFunctionId,
+) -> (IrBlockId, IrBlockId) {
+    let ptr_ty = function
+        .register_types
+        .get(&ptr)
+        .cloned()
+        .unwrap_or_else(|| IrType::Ptr(Box::new(IrType::U8)));
+
+    let null_reg = function.alloc_reg();
+    let is_null = function.alloc_reg();
+    let recovered = function.alloc_reg();
+    let checked = function.alloc_reg();
+    function.register_types.insert(null_reg, ptr_ty.clone());
+    function.register_types.insert(is_null, IrType::Bool);
+    function.register_types.insert(recovered, ptr_ty.clone());
+    function.register_types.insert(checked, ptr_ty.clone());
+
+    let fail_block = function.cfg.create_block();
+    let cont_block = function.cfg.create_block();
+
+    // Move everything after the call into the continuation block
+    let block = function.cfg.get_block_mut(block_id).unwrap();
+    let successors = block.successors();
+    let rest = block.instructions.split_off(call_idx + 1);
+    let terminator = std::mem::replace(
+        &mut block.terminator,
+        IrTerminator::CondBranch {
+            condition: is_null,
+            true_target: fail_block,
+            false_target: cont_block,
+        },
+    );
+    block.instructions.push(IrInstruction::Const {
+        dest: null_reg,
+        value: IrValue::Null,
+    });
+    block.instructions.push(IrInstruction::Cmp {
+        dest: is_null,
+        op: CompareOp::Eq,
+        left: ptr,
+        right: null_reg,
+    });
+
+    let fail = function.cfg.get_block_mut(fail_block).unwrap();
+    fail.instructions.push(IrInstruction::CallDirect {
+        dest: Some(recovered),
+        func_id: slow_path,
+        args,
+        arg_ownership: vec![],
+        type_args: vec![],
+        is_tail_call: false,
+    });
+    fail.terminator = IrTerminator::Branch { target: cont_block };
+    fail.metadata
+        .optimization_hints
+        .push(OptimizationHint::ColdPath);
+
+    let cont = function.cfg.get_block_mut(cont_block).unwrap();
+    cont.instructions = rest;
+    cont.terminator = terminator;
+    cont.phi_nodes.push(IrPhiNode {
+        dest: checked,
+        incoming: vec![(block_id, ptr), (fail_block, recovered)],
+        ty: ptr_ty,
+    });
+
+    // Successors of the original block are now reached from the continuation
+    for succ in successors {
+        if let Some(succ_block) = function.cfg.get_block_mut(succ) {
+            for pred in succ_block.predecessors.iter_mut() {
+                if *pred == block_id {
+                    *pred = cont_block;
+                }
+            }
+            for phi in succ_block.phi_nodes.iter_mut() {
+                for (pred, _) in phi.incoming.iter_mut() {
+                    if *pred == block_id {
+                        *pred = cont_block;
+                    }
+                }
+            }
+        }
+    }
+    function.cfg.connect_blocks(block_id, fail_block);
+    function.cfg.connect_blocks(block_id, cont_block);
+    function.cfg.connect_blocks(fail_block, cont_block);
+
+    // Every later use of the pointer is dominated by the continuation block
+    let mut replacements = BTreeMap::new();
+    replacements.insert(ptr, checked);
+    for (&id, block) in function.cfg.blocks.iter_mut() {
+        if id == block_id || id == fail_block {
+            continue;
+        }
+        for phi in block.phi_nodes.iter_mut() {
+            if phi.dest == checked {
+                continue;
+            }
+            for (_, value) in phi.incoming.iter_mut() {
+                if *value == ptr {
+                    *value = checked;
+                }
+            }
+        }
+        for inst in block.instructions.iter_mut() {
+            inst.replace_uses(&replacements);
+        }
+        replace_terminator_uses(&mut block.terminator, &replacements);
+    }
+
+    (fail_block, cont_block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::optimization::OptimizationPass;
+    use crate::ir::{CallingConvention, IrFunctionSignature, IrParameter};
+    use crate::tast::SymbolId;
+
+    /// A module whose function `f` returns `malloc(16)`, or `realloc(null, 16)`
+    /// when `allocator` is "realloc"
+    fn alloc_module(allocator: &str) -> (IrModule, IrFunctionId) {
+        let mut module = IrModule::new("test".to_string(), "test.hx".to_string());
+
+        let mut parameters = Vec::new();
+        if allocator == "realloc" {
+            parameters.push(IrParameter {
+                name: "old".to_string(),
+                ty: IrType::Ptr(Box::new(IrType::U8)),
+                reg: IrId(0),
+                by_ref: false,
+            });
+        }
+        parameters.push(IrParameter {
+            name: "size".to_string(),
+            ty: IrType::U64,
+            reg: IrId(parameters.len() as u32),
+            by_ref: false,
+        });
+        let alloc_id = module.alloc_function_id();
+        module.extern_functions.insert(
+            alloc_id,
+            super::super::modules::IrExternFunction {
+                id: alloc_id,
+                name: allocator.to_string(),
+                symbol_id: SymbolId::from_raw(0),
+                signature: IrFunctionSignature {
+                    parameters,
+                    return_type: IrType::Ptr(Box::new(IrType::U8)),
+                    calling_convention: CallingConvention::C,
+                    can_throw: false,
+                    type_params: vec![],
+                    uses_sret: false,
+                },
+                source: "libc".to_string(),
+            },
+        );
+
+        let func_id = module.alloc_function_id();
+        let signature = IrFunctionSignature {
+            parameters: vec![],
+            return_type: IrType::Ptr(Box::new(IrType::U8)),
+            calling_convention: CallingConvention::Haxe,
+            can_throw: false,
+            type_params: vec![],
+            uses_sret: false,
+        };
+        let mut function =
+            IrFunction::new(func_id, SymbolId::from_raw(1), "f".to_string(), signature);
+        let old = function.alloc_reg();
+        let size = function.alloc_reg();
+        let ptr = function.alloc_reg();
+        function
+            .register_types
+            .insert(old, IrType::Ptr(Box::new(IrType::U8)));
+        function.register_types.insert(size, IrType::U64);
+        function
+            .register_types
+            .insert(ptr, IrType::Ptr(Box::new(IrType::U8)));
+        let entry = function.cfg.entry_block;
+        let block = function.cfg.get_block_mut(entry).unwrap();
+        block.instructions.push(IrInstruction::Const {
+            dest: old,
+            value: IrValue::Null,
+        });
+        block.instructions.push(IrInstruction::Const {
+            dest: size,
+            value: IrValue::U64(16),
+        });
+        let args = if allocator == "realloc" {
+            vec![old, size]
+        } else {
+            vec![size]
+        };
+        block.instructions.push(IrInstruction::CallDirect {
+            dest: Some(ptr),
+            func_id: alloc_id,
+            args,
+            arg_ownership: vec![],
+            type_args: vec![],
+            is_tail_call: false,
+        });
+        block.terminator = IrTerminator::Return { value: Some(ptr) };
+        module.functions.insert(func_id, function);
+
+        (module, func_id)
+    }
+
+    #[test]
+    fn test_inserts_null_check_after_malloc() {
+        let (mut module, func_id) = alloc_module("malloc");
+
+        let result = AllocNullCheckPass::new().run_on_module(&mut module);
+        assert!(result.modified);
+        assert!(module
+            .extern_functions
+            .values()
+            .any(|f| f.name == ALLOC_FAILED_FN));
+
+        let function = &module.functions[&func_id];
+        assert_eq!(function.cfg.blocks.len(), 3);
+
+        let entry = &function.cfg.blocks[&function.cfg.entry_block];
+        let IrTerminator::CondBranch {
+            true_target: fail,
+            false_target: cont,
+            ..
+        } = entry.terminator
+        else {
+            panic!("expected null check branch, got {:?}", entry.terminator);
+        };
+
+        // Continuation merges the checked pointer and returns it
+        let cont = &function.cfg.blocks[&cont];
+        assert_eq!(cont.phi_nodes.len(), 1);
+        let phi = &cont.phi_nodes[0];
+        assert!(matches!(
+            cont.terminator,
+            IrTerminator::Return { value: Some(v) } if v == phi.dest
+        ));
+        assert!(phi.incoming.iter().any(|(b, _)| *b == fail));
+        assert!(cont.predecessors.contains(&fail));
+    }
+
+    #[test]
+    fn test_realloc_retries_with_old_pointer() {
+        let (mut module, func_id) = alloc_module("realloc");
+
+        let result = AllocNullCheckPass::new().run_on_module(&mut module);
+        assert!(result.modified);
+        let slow_path = module
+            .extern_functions
+            .values()
+            .find(|f| f.name == REALLOC_FAILED_FN)
+            .map(|f| f.id)
+            .expect("rayzor_realloc_failed declared");
+        assert!(!module
+            .extern_functions
+            .values()
+            .any(|f| f.name == ALLOC_FAILED_FN));
+
+        // The slow path gets realloc's own (old, size) arguments, not just the size
+        let function = &module.functions[&func_id];
+        let entry = &function.cfg.blocks[&function.cfg.entry_block];
+        let Some(IrInstruction::CallDirect {
+            args: realloc_args, ..
+        }) = entry
+            .instructions
+            .iter()
+            .find(|inst| matches!(inst, IrInstruction::CallDirect { .. }))
+        else {
+            panic!("realloc call missing");
+        };
+        let IrTerminator::CondBranch {
+            true_target: fail, ..
+        } = entry.terminator
+        else {
+            panic!("expected null check branch, got {:?}", entry.terminator);
+        };
+        let fail = &function.cfg.blocks[&fail];
+        assert!(matches!(
+            &fail.instructions[..],
+            [IrInstruction::CallDirect { func_id, args, .. }]
+                if *func_id == slow_path && args == realloc_args
+        ));
+    }
+
+    #[test]
+    fn test_no_allocations_unchanged() {
+        let mut module = IrModule::new("empty".to_string(), "empty.hx".to_string());
+        let result = AllocNullCheckPass::new().run_on_module(&mut module);
+        assert!(!result.modified);
+        assert!(module.extern_functions.is_empty());
+    }
+}
@@ -36,6 +36,8 @@
 //! let (mir_module, metadata) = load_blade("output.blade")?;
 //! ```
 
+use crate::ir::bundle_crypt::{self, BundleKey};
+use crate::ir::optimization::OptimizationLevel;
 use crate::ir::IrModule;
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -44,8 +46,13 @@ use std::path::Path;
 /// BLADE file magic number (first 4 bytes)
 const BLADE_MAGIC: &[u8; 4] = b"BLAD";
 
-/// Current BLADE format version
-const BLADE_VERSION: u32 = 1;
+/// Current BLADE format version (2: checksummed header with an ABI stamp,
+/// 3: per-function dependency table in the metadata)
+const BLADE_VERSION: u32 = 3;
+
+/// Size of the fixed part of the .blade header: magic, version, payload
+/// length, payload CRC-32 and stamp length
+const BLADE_HEADER_LEN: usize = 24;
 
 /// Metadata about the compiled module
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,9 +78,123 @@ pub struct BladeMetadata {
 
     /// Compiler version that created this BLADE file
     pub compiler_version: String,
+
+    /// Body hash and references of each function the module defines, to
+    /// tell which functions an edit changed (see [`functions_to_recompile`])
+    pub functions: Vec<BladeFunctionDeps>,
+}
+
+/// What one compiled function depends on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BladeFunctionDeps {
+    /// Qualified name, or the plain name if the function has none
+    pub name: String,
+
+    /// Hash of the function's signature and body (see [`function_body_hash`])
+    pub body_hash: u64,
+
+    /// Functions it calls or takes the address of, by name
+    pub references: Vec<String>,
+}
+
+/// Hash of a function's signature and MIR body. Source locations are part of
+/// the body, so a function that moved is reported as changed.
+pub fn function_body_hash(function: &crate::ir::IrFunction) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    // Locals and register types are hash maps with no stable order; the
+    // signature and CFG determine them
+    let bytes = postcard::to_allocvec(&(&function.signature, &function.cfg)).unwrap_or_default();
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The dependency records of the functions `module` defines; extern
+/// declarations have none
+pub fn function_dependencies(module: &IrModule) -> Vec<BladeFunctionDeps> {
+    let name_of = |func_id: crate::ir::IrFunctionId| {
+        module
+            .functions
+            .get(&func_id)
+            .map(|f| f.qualified_name.as_deref().unwrap_or(&f.name))
+            .or_else(|| {
+                module
+                    .extern_functions
+                    .get(&func_id)
+                    .map(|f| f.name.as_str())
+            })
+    };
+    module
+        .functions
+        .values()
+        .filter(|function| !function.cfg.blocks.is_empty())
+        .map(|function| BladeFunctionDeps {
+            name: function
+                .qualified_name
+                .clone()
+                .unwrap_or_else(|| function.name.clone()),
+            body_hash: function_body_hash(function),
+            references: function
+                .referenced_functions()
+                .into_iter()
+                .filter_map(|func_id| name_of(func_id).map(str::to_string))
+                .collect(),
+        })
+        .collect()
+}
+
+/// Functions of `new` to recompile when a module goes from `old` to `new`:
+/// the ones that are new or whose body changed, and every function that
+/// references one of those, directly or through other such functions.
+/// Compiled code calls its callees at fixed addresses, so a caller keeps
+/// running the old callee until it is recompiled itself. Sorted by name.
+pub fn functions_to_recompile(old: &[BladeFunctionDeps], new: &[BladeFunctionDeps]) -> Vec<String> {
+    use std::collections::{BTreeSet, HashMap};
+
+    let old_hashes: HashMap<&str, u64> = old
+        .iter()
+        .map(|deps| (deps.name.as_str(), deps.body_hash))
+        .collect();
+    let mut stale: BTreeSet<&str> = new
+        .iter()
+        .filter(|deps| old_hashes.get(deps.name.as_str()) != Some(&deps.body_hash))
+        .map(|deps| deps.name.as_str())
+        .collect();
+
+    // Propagate to referencing functions until nothing changes
+    loop {
+        let before = stale.len();
+        for deps in new {
+            if deps
+                .references
+                .iter()
+                .any(|callee| stale.contains(callee.as_str()))
+            {
+                stale.insert(deps.name.as_str());
+            }
+        }
+        if stale.len() == before {
+            break;
+        }
+    }
+    stale.into_iter().map(str::to_string).collect()
 }
 
 /// A complete BLADE module ready for serialization
+///
+/// On disk it is preceded by a header, so a truncated, corrupted or foreign
+/// file is rejected before the payload is deserialized:
+///
+/// ```text
+/// 0   magic "BLAD"
+/// 4   format version (u32 LE)
+/// 8   payload length (u64 LE)
+/// 16  CRC-32 of the payload (u32 LE)
+/// 20  stamp length (u32 LE)
+/// 24  ABI stamp (UTF-8, see `blade_abi_stamp`)
+/// ..  payload: this struct, postcard-encoded
+/// ```
 #[derive(Debug, Serialize, Deserialize)]
 struct BladeModule {
     /// Magic number for validation
@@ -106,6 +227,24 @@ pub enum BladeError {
 
     /// Compression/decompression error
     Compression(String),
+
+    /// File is shorter than its header says
+    Truncated,
+
+    /// Payload doesn't match its checksum
+    ChecksumMismatch,
+
+    /// Written by a different compiler version or target (the stamp found)
+    StampMismatch(String),
+
+    /// Encrypted bundle loaded without a key
+    KeyRequired,
+
+    /// Encrypted bundle loaded with the wrong key
+    WrongKey,
+
+    /// Malformed bundle key (e.g. in `RAYZOR_BUNDLE_KEY`)
+    InvalidKey(String),
 }
 
 impl std::fmt::Display for BladeError {
@@ -116,6 +255,21 @@ impl std::fmt::Display for BladeError {
             BladeError::Compression(e) => write!(f, "Compression error: {}", e),
             BladeError::InvalidMagic => write!(f, "Invalid BLADE magic number"),
             BladeError::UnsupportedVersion(v) => write!(f, "Unsupported BLADE version: {}", v),
+            BladeError::Truncated => write!(f, "Truncated BLADE file"),
+            BladeError::ChecksumMismatch => write!(f, "BLADE checksum mismatch (corrupted file)"),
+            BladeError::StampMismatch(stamp) => write!(
+                f,
+                "BLADE file was built by another compiler ({}, expected {})",
+                stamp,
+                blade_abi_stamp()
+            ),
+            BladeError::KeyRequired => write!(
+                f,
+                "Bundle is encrypted; set {} to its key",
+                bundle_crypt::BUNDLE_KEY_ENV
+            ),
+            BladeError::WrongKey => write!(f, "Wrong key for encrypted bundle"),
+            BladeError::InvalidKey(e) => write!(f, "Invalid bundle key: {}", e),
         }
     }
 }
@@ -160,6 +314,22 @@ pub fn save_blade(
     module: &IrModule,
     metadata: BladeMetadata,
 ) -> Result<(), BladeError> {
+    let bytes = encode_blade(module, metadata)?;
+
+    // Write through a temporary file so an interrupted write never leaves a
+    // truncated entry behind
+    let path = path.as_ref();
+    let tmp = path.with_extension(format!("blade.tmp{}", std::process::id()));
+    fs::write(&tmp, bytes)?;
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })?;
+
+    Ok(())
+}
+
+/// Encode a MIR module as the contents of a .blade file
+pub fn encode_blade(module: &IrModule, metadata: BladeMetadata) -> Result<Vec<u8>, BladeError> {
     let blade = BladeModule {
         magic: *BLADE_MAGIC,
         version: BLADE_VERSION,
@@ -168,12 +338,56 @@ pub fn save_blade(
     };
 
     // Serialize using postcard
-    let bytes = postcard::to_allocvec(&blade)?;
+    let payload = postcard::to_allocvec(&blade)?;
+    let stamp = blade_abi_stamp();
+
+    let mut bytes = Vec::with_capacity(BLADE_HEADER_LEN + stamp.len() + payload.len());
+    bytes.extend_from_slice(BLADE_MAGIC);
+    bytes.extend_from_slice(&BLADE_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&crc32(&payload).to_le_bytes());
+    bytes.extend_from_slice(&(stamp.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(stamp.as_bytes());
+    bytes.extend_from_slice(&payload);
+    Ok(bytes)
+}
 
-    // Write to file
-    fs::write(path, bytes)?;
+/// Compiler and target a .blade file must come from to be loaded: MIR is
+/// only valid for the compiler version and target that produced it
+pub fn blade_abi_stamp() -> String {
+    format!(
+        "rayzor {} {}-{} {}-bit",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::ARCH,
+        std::env::consts::OS,
+        usize::BITS
+    )
+}
 
-    Ok(())
+/// CRC-32 (IEEE) of `bytes`
+fn crc32(bytes: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 != 0 {
+                    0xEDB8_8320 ^ (crc >> 1)
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+    !bytes.iter().fold(!0u32, |crc, &b| {
+        TABLE[((crc ^ b as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
 }
 
 /// Load a MIR module from a .blade file
@@ -195,9 +409,57 @@ pub fn save_blade(
 pub fn load_blade(path: impl AsRef<Path>) -> Result<(IrModule, BladeMetadata), BladeError> {
     // Read file
     let bytes = fs::read(path)?;
+    decode_blade(&bytes)
+}
+
+/// Check a .blade file's integrity without keeping its contents, returning
+/// its metadata
+pub fn verify_blade(path: impl AsRef<Path>) -> Result<BladeMetadata, BladeError> {
+    load_blade(path).map(|(_, metadata)| metadata)
+}
+
+/// Decode the contents of a .blade file (e.g. one fetched from a remote cache)
+///
+/// The header is checked before anything is deserialized: a file from
+/// another format version, compiler or target, a truncated file, or one
+/// whose payload fails its checksum is an error.
+pub fn decode_blade(bytes: &[u8]) -> Result<(IrModule, BladeMetadata), BladeError> {
+    if bytes.len() < 8 {
+        return Err(BladeError::Truncated);
+    }
+    if &bytes[0..4] != BLADE_MAGIC {
+        return Err(BladeError::InvalidMagic);
+    }
+    let version = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+    if version != BLADE_VERSION {
+        return Err(BladeError::UnsupportedVersion(version));
+    }
+    if bytes.len() < BLADE_HEADER_LEN {
+        return Err(BladeError::Truncated);
+    }
+    let payload_len = u64::from_le_bytes(bytes[8..16].try_into().unwrap());
+    let checksum = u32::from_le_bytes(bytes[16..20].try_into().unwrap());
+    let stamp_len = u32::from_le_bytes(bytes[20..24].try_into().unwrap()) as usize;
+
+    let stamp_end = BLADE_HEADER_LEN
+        .checked_add(stamp_len)
+        .filter(|&end| end <= bytes.len())
+        .ok_or(BladeError::Truncated)?;
+    let stamp = String::from_utf8_lossy(&bytes[BLADE_HEADER_LEN..stamp_end]);
+    if stamp != blade_abi_stamp() {
+        return Err(BladeError::StampMismatch(stamp.into_owned()));
+    }
+
+    let payload = &bytes[stamp_end..];
+    if (payload.len() as u64) < payload_len {
+        return Err(BladeError::Truncated);
+    }
+    if payload.len() as u64 != payload_len || crc32(payload) != checksum {
+        return Err(BladeError::ChecksumMismatch);
+    }
 
     // Deserialize using postcard
-    let blade: BladeModule = postcard::from_bytes(&bytes)?;
+    let blade: BladeModule = postcard::from_bytes(payload)?;
 
     // Validate magic number
     if &blade.magic != BLADE_MAGIC {
@@ -219,8 +481,8 @@ pub fn load_blade(path: impl AsRef<Path>) -> Result<(IrModule, BladeMetadata), B
 /// Magic number for symbol manifest files
 const SYMBOL_MAGIC: &[u8; 4] = b"BSYM";
 
-/// Current symbol format version
-const SYMBOL_VERSION: u32 = 1;
+/// Current symbol format version (2: doc comments on classes and methods)
+const SYMBOL_VERSION: u32 = 2;
 
 /// Complete symbol information for a field
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -269,6 +531,8 @@ pub struct BladeMethodInfo {
     pub is_inline: bool,
     /// Type parameters for generic methods
     pub type_params: Vec<String>,
+    /// Doc comment, for completions and hover
+    pub doc: Option<String>,
 }
 
 /// Complete symbol information for a class
@@ -303,6 +567,8 @@ pub struct BladeClassInfo {
     /// Native name from @:native metadata (e.g., "rayzor::concurrent::Arc")
     /// Lowered form replaces :: with _ for symbol resolution
     pub native_name: Option<String>,
+    /// Doc comment, for completions and hover
+    pub doc: Option<String>,
 }
 
 /// Enum variant information
@@ -493,13 +759,21 @@ pub fn load_symbol_manifest(path: impl AsRef<Path>) -> Result<BladeSymbolManifes
 //     - Serialized IrModules (postcard format)
 //   [Symbol Manifest]
 //     - Embedded BladeSymbolManifest
+//   [Entry Points]
+//     - Named entry points (name, module, function), for plugin bundles and
+//       bundles with several programs
+//
+// An encrypted bundle wraps the whole file (after compression) in an
+// "RZBE" container; see `ir::bundle_crypt`.
 //
 
 /// Magic number for Rayzor Bundle files
 const BUNDLE_MAGIC: &[u8; 4] = b"RZBF";
 
 /// Current bundle format version
-const BUNDLE_VERSION: u32 = 1;
+/// Bundle format version (2: ABI stamps and optional embedded sources,
+/// 3: named entry points, 4: build pipeline)
+const BUNDLE_VERSION: u32 = 4;
 
 /// Bundle flags
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -558,6 +832,31 @@ pub struct RayzorBundle {
     symbols: Option<BladeSymbolManifest>,
     /// Build metadata
     build_info: BundleBuildInfo,
+    /// Haxe sources the modules were compiled from, if embedded
+    /// (`rayzor bundle --embed-sources`)
+    sources: Vec<BundledSource>,
+    /// Named entry points besides the default one (`rayzor bundle --entry`)
+    entry_points: Vec<BundleEntryPoint>,
+}
+
+/// A named entry point of a bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleEntryPoint {
+    /// Name the entry point is selected by (`rayzor run app.rzb --entry
+    /// <name>`, `rayzor.Runtime.loadModuleEntry`)
+    pub name: String,
+    pub module: String,
+    pub function: String,
+    /// Pre-computed at bundle creation, like the default entry point's
+    pub function_id: Option<crate::ir::IrFunctionId>,
+}
+
+/// A Haxe source file embedded in a bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledSource {
+    /// Path the file was compiled from
+    pub path: String,
+    pub content: String,
 }
 
 /// Build information for the bundle
@@ -571,6 +870,147 @@ pub struct BundleBuildInfo {
     pub target_platform: String,
     /// Original source files (for debugging)
     pub source_files: Vec<String>,
+    /// What the bundled MIR was built against
+    pub abi: BundleAbi,
+    /// MIR optimization level the modules were built at (None: unoptimized)
+    pub opt_level: Option<OptimizationLevel>,
+    /// Whether code no entry point reaches was tree-shaken away
+    pub tree_shaken: bool,
+}
+
+/// What a bundle's MIR depends on outside the bundle: the compiler that
+/// lowered it, the runtime functions it calls, and the stdlib compiled in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleAbi {
+    /// Semantic version of the compiler
+    pub compiler_version: String,
+    /// Hash of the runtime's exported symbols and the signatures the
+    /// compiler calls them with
+    pub runtime_abi: u64,
+    /// Fingerprint of the stdlib sources
+    pub stdlib: String,
+}
+
+/// Whether a bundle can run on this compiler and runtime
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiCompatibility {
+    Compatible,
+    /// Only the stdlib differs: the bundle runs correctly once its modules
+    /// are recompiled against this stdlib
+    StdlibChanged,
+    /// The bundle can't run here; the reason says why
+    Incompatible(String),
+}
+
+impl BundleAbi {
+    /// ABI of this compiler and the runtime linked into it
+    pub fn current() -> Self {
+        static CURRENT: std::sync::OnceLock<BundleAbi> = std::sync::OnceLock::new();
+        CURRENT
+            .get_or_init(|| {
+                let symbols = rayzor_runtime::plugin_impl::get_plugin().runtime_symbols();
+                let signatures = runtime_signatures(symbols.iter().map(|(name, _)| *name));
+                Self {
+                    compiler_version: env!("CARGO_PKG_VERSION").to_string(),
+                    runtime_abi: runtime_abi_hash(signatures.iter().map(String::as_str)),
+                    stdlib: env!("RAYZOR_STDLIB_FINGERPRINT").to_string(),
+                }
+            })
+            .clone()
+    }
+
+    /// Check a bundle built with this ABI against `current`. Compiler
+    /// versions are compatible when they share the major version (the
+    /// minor version while the major version is 0).
+    pub fn check(&self, current: &BundleAbi) -> AbiCompatibility {
+        if self.runtime_abi != current.runtime_abi {
+            return AbiCompatibility::Incompatible(format!(
+                "it calls runtime ABI {:016x}, but this runtime provides {:016x}",
+                self.runtime_abi, current.runtime_abi
+            ));
+        }
+        if !semver_compatible(&self.compiler_version, &current.compiler_version) {
+            return AbiCompatibility::Incompatible(format!(
+                "its MIR was produced by rayzor {}, which is not compatible with rayzor {}",
+                self.compiler_version, current.compiler_version
+            ));
+        }
+        if self.stdlib != current.stdlib {
+            return AbiCompatibility::StdlibChanged;
+        }
+        AbiCompatibility::Compatible
+    }
+}
+
+/// `name(params) -> return` for each runtime symbol, with the types the
+/// compiler calls it with: from the runtime mapping, else the stdlib's
+/// extern declarations. Symbols declared in neither are just their name.
+pub fn runtime_signatures<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    use crate::ir::functions::FunctionKind;
+    use std::collections::HashMap;
+
+    let mapping = crate::stdlib::StdlibMapping::new();
+    let stdlib = crate::stdlib::build_stdlib();
+    let externs: HashMap<&str, &super::IrFunctionSignature> = stdlib
+        .functions
+        .values()
+        .filter(|f| matches!(f.kind, FunctionKind::ExternC))
+        .map(|f| (f.name.as_str(), &f.signature))
+        .chain(
+            stdlib
+                .extern_functions
+                .values()
+                .map(|f| (f.name.as_str(), &f.signature)),
+        )
+        .collect();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let signature = mapping.get_function_signature(name).or_else(|| {
+                externs.get(name).map(|sig| {
+                    let params = sig.parameters.iter().map(|p| p.ty.clone()).collect();
+                    (params, sig.return_type.clone())
+                })
+            });
+            match signature {
+                Some((params, ret)) => {
+                    let params: Vec<String> = params.iter().map(ToString::to_string).collect();
+                    format!("{}({}) -> {}", name, params.join(", "), ret)
+                }
+                None => name.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Stable hash (FNV-1a) of a set of runtime symbol signatures (see
+/// [`runtime_signatures`]), independent of their order
+pub fn runtime_abi_hash<'a>(signatures: impl IntoIterator<Item = &'a str>) -> u64 {
+    let mut signatures: Vec<&str> = signatures.into_iter().collect();
+    signatures.sort_unstable();
+    signatures.dedup();
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    for signature in signatures {
+        for byte in signature.bytes().chain(std::iter::once(0)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// Whether two `major.minor.patch` versions are semver-compatible
+fn semver_compatible(a: &str, b: &str) -> bool {
+    let parts = |v: &str| -> Option<(u64, u64)> {
+        let mut it = v.split(['.', '-', '+']);
+        Some((it.next()?.parse().ok()?, it.next()?.parse().ok()?))
+    };
+    match (parts(a), parts(b)) {
+        (Some((0, minor_a)), Some((0, minor_b))) => minor_a == minor_b,
+        (Some((major_a, _)), Some((major_b, _))) => major_a == major_b,
+        _ => a == b,
+    }
 }
 
 impl RayzorBundle {
@@ -629,10 +1069,74 @@ impl RayzorBundle {
                 build_timestamp: now,
                 target_platform: std::env::consts::ARCH.to_string(),
                 source_files,
+                abi: BundleAbi::current(),
+                opt_level: None,
+                tree_shaken: false,
             },
+            sources: Vec::new(),
+            entry_points: Vec::new(),
         }
     }
 
+    /// Add a named entry point: `function` in `module`
+    pub fn with_entry_point(mut self, name: &str, module: &str, function: &str) -> Self {
+        let function_id = self.get_module(module).and_then(|m| {
+            m.functions
+                .iter()
+                .find(|(_, f)| f.name == function)
+                .map(|(id, _)| *id)
+        });
+        self.entry_points.retain(|e| e.name != name);
+        self.entry_points.push(BundleEntryPoint {
+            name: name.to_string(),
+            module: module.to_string(),
+            function: function.to_string(),
+            function_id,
+        });
+        self
+    }
+
+    /// Named entry points (empty unless built with `--entry`)
+    pub fn entry_points(&self) -> &[BundleEntryPoint] {
+        &self.entry_points
+    }
+
+    /// Look up a named entry point
+    pub fn find_entry_point(&self, name: &str) -> Option<&BundleEntryPoint> {
+        self.entry_points.iter().find(|e| e.name == name)
+    }
+
+    /// Embed the Haxe sources the modules were compiled from, so the bundle
+    /// can be recompiled when the stdlib changes
+    pub fn with_sources(mut self, sources: Vec<BundledSource>) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    /// Embedded Haxe sources (empty unless built with `--embed-sources`)
+    pub fn sources(&self) -> &[BundledSource] {
+        &self.sources
+    }
+
+    /// Record how the modules were built, so a recompile can rebuild them
+    /// the same way
+    pub fn with_pipeline(
+        mut self,
+        opt_level: Option<OptimizationLevel>,
+        tree_shaken: bool,
+    ) -> Self {
+        self.build_info.opt_level = opt_level;
+        self.build_info.tree_shaken = tree_shaken;
+        self
+    }
+
+    /// Override the recorded compiler version (e.g. to add a content
+    /// fingerprint for bundles tied to one compiler build)
+    pub fn with_compiler_version(mut self, version: &str) -> Self {
+        self.build_info.compiler_version = version.to_string();
+        self
+    }
+
     /// Get the entry module (O(1) using pre-computed index)
     pub fn entry_module(&self) -> Option<&IrModule> {
         self.entry_module_index
@@ -690,15 +1194,32 @@ impl RayzorBundle {
 /// save_bundle("app.rzb", &bundle)?;
 /// ```
 pub fn save_bundle(path: impl AsRef<Path>, bundle: &RayzorBundle) -> Result<(), BladeError> {
+    fs::write(path, encode_bundle(bundle)?)?;
+    Ok(())
+}
+
+/// Save a Rayzor Bundle encrypted with `key` (see `ir::bundle_crypt`).
+/// Hosts load it with [`load_bundle_with_key`], or [`load_bundle`] with the
+/// key in `RAYZOR_BUNDLE_KEY`.
+pub fn save_bundle_encrypted(
+    path: impl AsRef<Path>,
+    bundle: &RayzorBundle,
+    key: &BundleKey,
+) -> Result<(), BladeError> {
+    let bytes = encode_bundle(bundle)?;
+    fs::write(path, bundle_crypt::seal(&bytes, key))?;
+    Ok(())
+}
+
+/// Serialize a bundle, compressed if its flags ask for it
+fn encode_bundle(bundle: &RayzorBundle) -> Result<Vec<u8>, BladeError> {
     let bytes = postcard::to_allocvec(bundle)?;
-    let output = if bundle.flags.compressed {
+    if bundle.flags.compressed {
         zstd::encode_all(bytes.as_slice(), 3)
-            .map_err(|e| BladeError::Compression(format!("zstd compress: {}", e)))?
+            .map_err(|e| BladeError::Compression(format!("zstd compress: {}", e)))
     } else {
-        bytes
-    };
-    fs::write(path, output)?;
-    Ok(())
+        Ok(bytes)
+    }
 }
 
 /// Load a Rayzor Bundle from file
@@ -709,7 +1230,8 @@ pub fn save_bundle(path: impl AsRef<Path>, bundle: &RayzorBundle) -> Result<(),
 ///
 /// # Returns
 ///
-/// The loaded RayzorBundle
+/// The loaded RayzorBundle. An encrypted bundle is decrypted with the key
+/// in `RAYZOR_BUNDLE_KEY`.
 ///
 /// # Example
 ///
@@ -720,32 +1242,53 @@ pub fn save_bundle(path: impl AsRef<Path>, bundle: &RayzorBundle) -> Result<(),
 /// ```
 pub fn load_bundle(path: impl AsRef<Path>) -> Result<RayzorBundle, BladeError> {
     let raw = fs::read(path)?;
-    // Detect zstd compression via magic bytes (0x28 0xB5 0x2F 0xFD)
-    let bytes =
-        if raw.len() >= 4 && raw[0] == 0x28 && raw[1] == 0xB5 && raw[2] == 0x2F && raw[3] == 0xFD {
-            zstd::decode_all(raw.as_slice())
-                .map_err(|e| BladeError::Compression(format!("zstd decompress: {}", e)))?
-        } else {
-            raw
-        };
-    let bundle: RayzorBundle = postcard::from_bytes(&bytes)?;
-
-    // Validate magic
-    if &bundle.magic != BUNDLE_MAGIC {
-        return Err(BladeError::InvalidMagic);
-    }
-
-    // Check version
-    if bundle.version != BUNDLE_VERSION {
-        return Err(BladeError::UnsupportedVersion(bundle.version));
-    }
+    load_bundle_from_bytes(&raw)
+}
 
-    Ok(bundle)
+/// Load a Rayzor Bundle, decrypting it with `key` if it is encrypted.
+/// Without a key, an encrypted bundle falls back to `RAYZOR_BUNDLE_KEY`.
+pub fn load_bundle_with_key(
+    path: impl AsRef<Path>,
+    key: Option<&BundleKey>,
+) -> Result<RayzorBundle, BladeError> {
+    let raw = fs::read(path)?;
+    load_bundle_from_bytes_with_key(&raw, key)
 }
 
 /// Load a Rayzor Bundle from bytes (for embedded bundles)
 pub fn load_bundle_from_bytes(bytes: &[u8]) -> Result<RayzorBundle, BladeError> {
-    // Detect zstd compression via magic bytes
+    load_bundle_from_bytes_with_key(bytes, None)
+}
+
+/// Load a Rayzor Bundle from bytes, decrypting it with `key` (or the key
+/// in `RAYZOR_BUNDLE_KEY`) if it is encrypted
+pub fn load_bundle_from_bytes_with_key(
+    bytes: &[u8],
+    key: Option<&BundleKey>,
+) -> Result<RayzorBundle, BladeError> {
+    let decrypted;
+    let bytes = if bundle_crypt::is_encrypted(bytes) {
+        let env_key;
+        let key = match key {
+            Some(key) => key,
+            None => {
+                env_key = BundleKey::from_env()
+                    .map_err(BladeError::InvalidKey)?
+                    .ok_or(BladeError::KeyRequired)?;
+                &env_key
+            }
+        };
+        decrypted = bundle_crypt::open(bytes, key).map_err(|e| match e {
+            bundle_crypt::OpenError::Truncated => BladeError::Truncated,
+            bundle_crypt::OpenError::UnsupportedVersion(v) => BladeError::UnsupportedVersion(v),
+            bundle_crypt::OpenError::WrongKey => BladeError::WrongKey,
+        })?;
+        &decrypted[..]
+    } else {
+        bytes
+    };
+
+    // Detect zstd compression via magic bytes (0x28 0xB5 0x2F 0xFD)
     let decompressed;
     let data = if bytes.len() >= 4
         && bytes[0] == 0x28
@@ -761,10 +1304,12 @@ pub fn load_bundle_from_bytes(bytes: &[u8]) -> Result<RayzorBundle, BladeError>
     };
     let bundle: RayzorBundle = postcard::from_bytes(data)?;
 
+    // Validate magic
     if &bundle.magic != BUNDLE_MAGIC {
         return Err(BladeError::InvalidMagic);
     }
 
+    // Check version
     if bundle.version != BUNDLE_VERSION {
         return Err(BladeError::UnsupportedVersion(bundle.version));
     }
@@ -797,6 +1342,7 @@ mod tests {
             compile_timestamp: now,
             dependencies: vec![],
             compiler_version: env!("CARGO_PKG_VERSION").to_string(),
+            functions: vec![],
         };
 
         // Serialize to bytes
@@ -817,4 +1363,225 @@ mod tests {
         assert_eq!(decoded.metadata.name, "test_module");
         assert_eq!(decoded.mir.name, "test_module");
     }
+
+    fn encoded_module() -> Vec<u8> {
+        let module = IrModule::new("Main".to_string(), "Main.hx".to_string());
+        let metadata = BladeMetadata {
+            name: "Main".to_string(),
+            source_path: "Main.hx".to_string(),
+            source_hash: 42,
+            source_timestamp: 0,
+            compile_timestamp: 0,
+            dependencies: vec![],
+            compiler_version: env!("CARGO_PKG_VERSION").to_string(),
+            functions: vec![],
+        };
+        encode_blade(&module, metadata).unwrap()
+    }
+
+    #[test]
+    fn test_semver_compatible() {
+        assert!(semver_compatible("0.1.0", "0.1.7"));
+        assert!(!semver_compatible("0.1.0", "0.2.0"));
+        assert!(semver_compatible("1.2.0", "1.9.3"));
+        assert!(!semver_compatible("1.2.0", "2.0.0"));
+        assert!(semver_compatible("0.3.1+abc", "0.3.0"));
+    }
+
+    #[test]
+    fn test_bundle_abi_check() {
+        let current = BundleAbi {
+            compiler_version: "0.3.0".to_string(),
+            runtime_abi: runtime_abi_hash(["haxe_trace", "rayzor_alloc"]),
+            stdlib: "aaaa".to_string(),
+        };
+        assert_eq!(current.check(&current), AbiCompatibility::Compatible);
+
+        // Symbol order doesn't matter
+        let reordered = BundleAbi {
+            runtime_abi: runtime_abi_hash(["rayzor_alloc", "haxe_trace"]),
+            compiler_version: "0.3.2".to_string(),
+            ..current.clone()
+        };
+        assert_eq!(reordered.check(&current), AbiCompatibility::Compatible);
+
+        let stdlib = BundleAbi {
+            stdlib: "bbbb".to_string(),
+            ..current.clone()
+        };
+        assert_eq!(stdlib.check(&current), AbiCompatibility::StdlibChanged);
+
+        let runtime = BundleAbi {
+            runtime_abi: runtime_abi_hash(["haxe_trace"]),
+            stdlib: "bbbb".to_string(),
+            ..current.clone()
+        };
+        assert!(matches!(
+            runtime.check(&current),
+            AbiCompatibility::Incompatible(reason) if reason.contains("runtime ABI")
+        ));
+
+        let compiler = BundleAbi {
+            compiler_version: "0.2.9".to_string(),
+            ..current.clone()
+        };
+        assert!(matches!(
+            compiler.check(&current),
+            AbiCompatibility::Incompatible(reason) if reason.contains("0.2.9")
+        ));
+    }
+
+    #[test]
+    fn test_runtime_signatures() {
+        assert_eq!(
+            runtime_signatures(["haxe_array_sort", "no_such_symbol"]),
+            [
+                "haxe_array_sort(*void, i64, *void, i32) -> void",
+                "no_such_symbol"
+            ]
+        );
+
+        // A symbol that gains a parameter changes the ABI
+        assert_ne!(
+            runtime_abi_hash(["haxe_array_sort(*void, i64, *void) -> void"]),
+            runtime_abi_hash(["haxe_array_sort(*void, i64, *void, i32) -> void"])
+        );
+    }
+
+    #[test]
+    fn test_bundle_sources_roundtrip() {
+        let module = IrModule::new("Main".to_string(), "Main.hx".to_string());
+        let bundle = RayzorBundle::new(vec![module], "Main", "main", None)
+            .with_pipeline(Some(OptimizationLevel::O2), true)
+            .with_sources(vec![BundledSource {
+                path: "src/Main.hx".to_string(),
+                content: "class Main {}".to_string(),
+            }]);
+        let path = std::env::temp_dir().join(format!("rayzor_sources_{}.rzb", std::process::id()));
+        save_bundle(&path, &bundle).unwrap();
+        let loaded = load_bundle(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded.sources().len(), 1);
+        assert_eq!(loaded.sources()[0].content, "class Main {}");
+        assert_eq!(loaded.build_info().abi, BundleAbi::current());
+        assert_eq!(loaded.build_info().opt_level, Some(OptimizationLevel::O2));
+        assert!(loaded.build_info().tree_shaken);
+    }
+
+    #[test]
+    fn test_bundle_entry_points_roundtrip() {
+        let module = IrModule::new("Tools".to_string(), "Tools.hx".to_string());
+        let bundle = RayzorBundle::new(vec![module], "Tools", "Tools_register", None)
+            .with_entry_point("register", "Tools", "Tools_register")
+            .with_entry_point("lint", "Tools", "Tools_lint")
+            .with_entry_point("register", "Tools", "Tools_init");
+        let bytes = encode_bundle(&bundle).unwrap();
+        let loaded = load_bundle_from_bytes(&bytes).unwrap();
+
+        let names: Vec<&str> = loaded
+            .entry_points()
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(names, ["lint", "register"]);
+        assert_eq!(
+            loaded.find_entry_point("register").unwrap().function,
+            "Tools_init"
+        );
+        assert!(loaded.find_entry_point("missing").is_none());
+    }
+
+    #[test]
+    fn test_encrypted_bundle_roundtrip() {
+        let module = IrModule::new("Main".to_string(), "Main.hx".to_string());
+        let mut bundle = RayzorBundle::new(vec![module], "Main", "main", None);
+        bundle.flags.compressed = true;
+        let key = BundleKey::from_bytes([3; 32]);
+        let path =
+            std::env::temp_dir().join(format!("rayzor_encrypted_{}.rzb", std::process::id()));
+        save_bundle_encrypted(&path, &bundle, &key).unwrap();
+
+        let loaded = load_bundle_with_key(&path, Some(&key));
+        let wrong = load_bundle_with_key(&path, Some(&BundleKey::from_bytes([4; 32])));
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded.unwrap().modules()[0].name, "Main");
+        assert!(matches!(wrong, Err(BladeError::WrongKey)));
+    }
+
+    #[test]
+    fn test_functions_to_recompile() {
+        let deps = |name: &str, body_hash: u64, references: &[&str]| BladeFunctionDeps {
+            name: name.to_string(),
+            body_hash,
+            references: references.iter().map(|r| r.to_string()).collect(),
+        };
+        let old = vec![
+            deps("leaf", 1, &[]),
+            deps("mid", 2, &["leaf"]),
+            deps("top", 3, &["mid"]),
+            deps("other", 4, &[]),
+        ];
+        assert!(functions_to_recompile(&old, &old).is_empty());
+
+        let new = vec![
+            deps("leaf", 10, &[]),
+            deps("mid", 2, &["leaf"]),
+            deps("top", 3, &["mid"]),
+            deps("other", 4, &[]),
+            deps("added", 5, &["other"]),
+        ];
+        assert_eq!(
+            functions_to_recompile(&old, &new),
+            vec!["added", "leaf", "mid", "top"]
+        );
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_decode_checks_integrity() {
+        let bytes = encoded_module();
+        let (module, metadata) = decode_blade(&bytes).unwrap();
+        assert_eq!(module.name, "Main");
+        assert_eq!(metadata.source_hash, 42);
+
+        assert!(matches!(
+            decode_blade(&bytes[..bytes.len() - 3]),
+            Err(BladeError::Truncated)
+        ));
+        assert!(matches!(
+            decode_blade(&bytes[..10]),
+            Err(BladeError::Truncated)
+        ));
+
+        let mut flipped = bytes.clone();
+        let last = flipped.len() - 1;
+        flipped[last] ^= 0x40;
+        assert!(matches!(
+            decode_blade(&flipped),
+            Err(BladeError::ChecksumMismatch)
+        ));
+
+        // A different compiler version or target
+        let mut foreign = bytes.clone();
+        foreign[BLADE_HEADER_LEN] = b'X';
+        assert!(matches!(
+            decode_blade(&foreign),
+            Err(BladeError::StampMismatch(_))
+        ));
+
+        // Files written before the header existed
+        let mut legacy = bytes.clone();
+        legacy[4..8].copy_from_slice(&1u32.to_le_bytes());
+        assert!(matches!(
+            decode_blade(&legacy),
+            Err(BladeError::UnsupportedVersion(1))
+        ));
+    }
 }
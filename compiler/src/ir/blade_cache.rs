@@ -0,0 +1,494 @@
+//! Remote BLADE cache backends
+//!
+//! The local BLADE cache is a directory of `.blade` files named after their
+//! module. A remote cache (`[cache.remote]` in rayzor.toml) sits behind it so
+//! CI fleets and teams share compiled stdlib and module artifacts: on a local
+//! miss the artifact is fetched from the remote and written to the local
+//! directory, and freshly compiled artifacts are uploaded when `upload` is set.
+//!
+//! Remote artifacts are content-addressed: the key is the module name and a
+//! hash of the module's source text and the compiler version, so a stale
+//! artifact is never fetched. Fetched artifacts are still checked against the
+//! source hash in their metadata before use.
+//!
+//! Backends:
+//! - `http://host[:port]/prefix`: `GET`/`PUT` of `<prefix>/<key>.blade`, the
+//!   layout of S3-compatible object stores and of generic HTTP cache servers.
+//!   An optional bearer token is read from the environment variable named by
+//!   `token_env`. There is no TLS client in the compiler; use an `http://`
+//!   endpoint or a TLS-terminating proxy.
+//! - `file:///path` or a plain path: a shared directory (e.g. a network mount).
+
+use super::blade::{decode_blade, BladeMetadata};
+use super::IrModule;
+use crate::workspace::manifest::RemoteCacheConfig;
+use log::{debug, trace};
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default timeout of one remote request
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// Largest artifact accepted from a remote cache
+const MAX_ARTIFACT_SIZE: usize = 256 * 1024 * 1024;
+
+/// Storage for BLADE artifacts, addressed by [`content_key`]
+pub trait CacheBackend: Send + Sync {
+    /// Human-readable location, for logs
+    fn describe(&self) -> String;
+
+    /// Bytes stored under `key`, or None if there are none
+    fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>>;
+
+    /// Store `bytes` under `key`
+    fn put(&self, key: &str, bytes: &[u8]) -> io::Result<()>;
+}
+
+/// Remote cache key of a module: its name and a hash of its source text and
+/// the compiler version
+pub fn content_key(module: &str, source_hash: u64) -> String {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let mut hasher = DefaultHasher::new();
+    source_hash.hash(&mut hasher);
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    let name: String = module
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}-{:016x}", name, hasher.finish())
+}
+
+/// A directory of `<key>.blade` files
+pub struct DirectoryCache {
+    dir: PathBuf,
+}
+
+impl DirectoryCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.blade", key))
+    }
+}
+
+impl CacheBackend for DirectoryCache {
+    fn describe(&self) -> String {
+        self.dir.display().to_string()
+    }
+
+    fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        match std::fs::read(self.path(key)) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn put(&self, key: &str, bytes: &[u8]) -> io::Result<()> {
+        write_atomic(&self.path(key), bytes)
+    }
+}
+
+/// An HTTP/1.1 object store: `GET`/`PUT` of `<prefix>/<key>.blade`
+pub struct HttpCache {
+    host: String,
+    port: u16,
+    prefix: String,
+    token: Option<String>,
+    timeout: Duration,
+}
+
+impl HttpCache {
+    /// Parse an `http://host[:port][/prefix]` URL
+    pub fn new(url: &str, token: Option<String>, timeout: Duration) -> Result<Self, String> {
+        if url.starts_with("https://") {
+            return Err(format!(
+                "Remote cache {}: https is not supported, use an http:// endpoint or a TLS-terminating proxy",
+                url
+            ));
+        }
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| format!("Remote cache URL must start with http://: {}", url))?;
+        let (authority, prefix) = match rest.find('/') {
+            Some(slash) => (&rest[..slash], rest[slash..].trim_end_matches('/')),
+            None => (rest, ""),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse()
+                    .map_err(|_| format!("Invalid port in remote cache URL: {}", url))?,
+            ),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(format!("Missing host in remote cache URL: {}", url));
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            prefix: prefix.to_string(),
+            token,
+            timeout,
+        })
+    }
+
+    fn request(&self, method: &str, key: &str, body: &[u8]) -> io::Result<HttpResponse> {
+        let addr = (self.host.as_str(), self.port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "host has no address"))?;
+        let mut stream = TcpStream::connect_timeout(&addr, self.timeout)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+
+        let mut head = format!(
+            "{} {}/{}.blade HTTP/1.1\r\nHost: {}\r\nUser-Agent: rayzor/{}\r\nConnection: close\r\nContent-Length: {}\r\n",
+            method,
+            self.prefix,
+            key,
+            self.host,
+            env!("CARGO_PKG_VERSION"),
+            body.len()
+        );
+        if method == "PUT" {
+            head.push_str("Content-Type: application/octet-stream\r\n");
+        }
+        if let Some(token) = &self.token {
+            head.push_str(&format!("Authorization: Bearer {}\r\n", token));
+        }
+        head.push_str("\r\n");
+        let mut request = head.into_bytes();
+        request.extend_from_slice(body);
+        stream.write_all(&request)?;
+        stream.flush()?;
+
+        let mut raw = Vec::new();
+        stream
+            .take(MAX_ARTIFACT_SIZE as u64 + 64 * 1024)
+            .read_to_end(&mut raw)?;
+        HttpResponse::parse(&raw)
+    }
+}
+
+impl CacheBackend for HttpCache {
+    fn describe(&self) -> String {
+        format!("http://{}:{}{}", self.host, self.port, self.prefix)
+    }
+
+    fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        let response = self.request("GET", key, &[])?;
+        match response.status {
+            200 => Ok(Some(response.body)),
+            404 => Ok(None),
+            status => Err(io::Error::other(format!("GET returned HTTP {}", status))),
+        }
+    }
+
+    fn put(&self, key: &str, bytes: &[u8]) -> io::Result<()> {
+        let response = self.request("PUT", key, bytes)?;
+        if (200..300).contains(&response.status) {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!(
+                "PUT returned HTTP {}",
+                response.status
+            )))
+        }
+    }
+}
+
+/// Status and body of an HTTP/1.1 response
+#[derive(Debug, PartialEq)]
+struct HttpResponse {
+    status: u16,
+    body: Vec<u8>,
+}
+
+impl HttpResponse {
+    fn parse(raw: &[u8]) -> io::Result<Self> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+        let head_end = raw
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .ok_or_else(|| invalid("truncated HTTP response"))?;
+        let head = std::str::from_utf8(&raw[..head_end])
+            .map_err(|_| invalid("HTTP response head is not UTF-8"))?;
+        let mut lines = head.split("\r\n");
+        let status = lines
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| invalid("malformed HTTP status line"))?;
+
+        let mut content_length = None;
+        let mut chunked = false;
+        for line in lines {
+            let Some((name, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.parse::<usize>().ok();
+            } else if name.eq_ignore_ascii_case("transfer-encoding") {
+                chunked = value.eq_ignore_ascii_case("chunked");
+            }
+        }
+
+        let rest = &raw[head_end + 4..];
+        let body = if chunked {
+            decode_chunked(rest).ok_or_else(|| invalid("malformed chunked HTTP body"))?
+        } else if let Some(len) = content_length {
+            if rest.len() < len {
+                return Err(invalid("truncated HTTP body"));
+            }
+            rest[..len].to_vec()
+        } else {
+            rest.to_vec()
+        };
+        Ok(Self { status, body })
+    }
+}
+
+fn decode_chunked(mut data: &[u8]) -> Option<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let line_end = data.windows(2).position(|w| w == b"\r\n")?;
+        let size_text = std::str::from_utf8(&data[..line_end]).ok()?;
+        let size_text = size_text.split(';').next()?.trim();
+        let size = usize::from_str_radix(size_text, 16).ok()?;
+        data = &data[line_end + 2..];
+        if size == 0 {
+            return Some(body);
+        }
+        if data.len() < size + 2 {
+            return None;
+        }
+        body.extend_from_slice(&data[..size]);
+        data = &data[size + 2..];
+    }
+}
+
+/// Write via a temporary file, so concurrent readers never see a partial file
+fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })
+}
+
+/// The remote layer of the BLADE cache
+pub struct RemoteCache {
+    backend: Box<dyn CacheBackend>,
+    upload: bool,
+}
+
+impl RemoteCache {
+    pub fn new(backend: Box<dyn CacheBackend>, upload: bool) -> Self {
+        Self { backend, upload }
+    }
+
+    /// Open the backend a `[cache.remote]` section names
+    pub fn open(config: &RemoteCacheConfig) -> Result<Self, String> {
+        let upload = config.upload.unwrap_or(false);
+        let url = config.url.as_str();
+        if url.starts_with("http://") || url.starts_with("https://") {
+            let token = match &config.token_env {
+                Some(var) => Some(
+                    std::env::var(var)
+                        .map_err(|_| format!("Remote cache token variable {} is not set", var))?,
+                ),
+                None => None,
+            };
+            let timeout = Duration::from_secs(config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+            let backend = HttpCache::new(url, token, timeout)?;
+            return Ok(Self::new(Box::new(backend), upload));
+        }
+        if url.contains("://") && !url.starts_with("file://") {
+            return Err(format!("Unsupported remote cache URL: {}", url));
+        }
+        let dir = url.strip_prefix("file://").unwrap_or(url);
+        Ok(Self::new(Box::new(DirectoryCache::new(dir)), upload))
+    }
+
+    /// Fetch the artifact of `module` compiled from source with `source_hash`
+    /// and write it to `local_path`. Returns None on a miss, a remote error,
+    /// or an artifact that doesn't match the source.
+    pub fn fetch(
+        &self,
+        module: &str,
+        source_hash: u64,
+        local_path: &Path,
+    ) -> Option<(IrModule, BladeMetadata)> {
+        let key = content_key(module, source_hash);
+        let bytes = match self.backend.get(&key) {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => {
+                trace!("[BLADE] Remote miss: {} ({})", key, self.backend.describe());
+                return None;
+            }
+            Err(e) => {
+                debug!(
+                    "[BLADE] Remote cache {} error: {}",
+                    self.backend.describe(),
+                    e
+                );
+                return None;
+            }
+        };
+
+        let (mir, metadata) = match decode_blade(&bytes) {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                debug!("[BLADE] Remote artifact {} is invalid: {}", key, e);
+                return None;
+            }
+        };
+        if metadata.source_hash != source_hash
+            || metadata.compiler_version != env!("CARGO_PKG_VERSION")
+        {
+            debug!("[BLADE] Remote artifact {} does not match its source", key);
+            return None;
+        }
+
+        if let Err(e) = write_atomic(local_path, &bytes) {
+            trace!(
+                "[BLADE] Failed to store {} locally: {}",
+                local_path.display(),
+                e
+            );
+        }
+        debug!("[BLADE] Remote hit: {} -> {}", key, local_path.display());
+        Some((mir, metadata))
+    }
+
+    /// Upload the local artifact at `local_path`, if uploads are enabled
+    pub fn store(&self, module: &str, source_hash: u64, local_path: &Path) {
+        if !self.upload {
+            return;
+        }
+        let key = content_key(module, source_hash);
+        let result = std::fs::read(local_path).and_then(|bytes| self.backend.put(&key, &bytes));
+        match result {
+            Ok(()) => debug!("[BLADE] Uploaded {} to {}", key, self.backend.describe()),
+            Err(e) => debug!(
+                "[BLADE] Failed to upload {} to {}: {}",
+                key,
+                self.backend.describe(),
+                e
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_content_key() {
+        let key = content_key("haxe.io.Bytes", 42);
+        assert!(key.starts_with("haxe.io.Bytes-"));
+        assert_eq!(key, content_key("haxe.io.Bytes", 42));
+        assert_ne!(key, content_key("haxe.io.Bytes", 43));
+        assert!(content_key("src/Main", 1).starts_with("src_Main-"));
+    }
+
+    #[test]
+    fn test_parse_url() {
+        let cache = HttpCache::new("http://cache.local:9090/blade/", None, Duration::ZERO).unwrap();
+        assert_eq!(cache.host, "cache.local");
+        assert_eq!(cache.port, 9090);
+        assert_eq!(cache.prefix, "/blade");
+        assert_eq!(
+            HttpCache::new("http://cache.local", None, Duration::ZERO)
+                .unwrap()
+                .port,
+            80
+        );
+        assert!(HttpCache::new("https://s3.example.com", None, Duration::ZERO).is_err());
+        assert!(HttpCache::new("ftp://x", None, Duration::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let response =
+            HttpResponse::parse(b"HTTP/1.1 200 OK\r\nContent-Length: 3\r\n\r\nabcdef").unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"abc");
+
+        let chunked = HttpResponse::parse(
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n2;x=y\r\nde\r\n0\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(chunked.body, b"abcde");
+
+        let missing = HttpResponse::parse(b"HTTP/1.1 404 Not Found\r\n\r\n").unwrap();
+        assert_eq!(missing.status, 404);
+        assert!(HttpResponse::parse(b"HTTP/1.1 200 OK\r\n").is_err());
+    }
+
+    #[test]
+    fn test_directory_cache() {
+        let dir = std::env::temp_dir().join(format!("rayzor-blade-remote-{}", std::process::id()));
+        let cache = DirectoryCache::new(&dir);
+        assert_eq!(cache.get("Main-00").unwrap(), None);
+        cache.put("Main-00", b"blade").unwrap();
+        assert_eq!(cache.get("Main-00").unwrap(), Some(b"blade".to_vec()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_http_get_put() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for reply in [
+                "HTTP/1.1 201 Created\r\nContent-Length: 0\r\n\r\n",
+                "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nblade",
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n",
+            ] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 4096];
+                let n = stream.read(&mut buf).unwrap();
+                requests.push(String::from_utf8_lossy(&buf[..n]).to_string());
+                stream.write_all(reply.as_bytes()).unwrap();
+            }
+            requests
+        });
+
+        let cache = HttpCache::new(
+            &format!("http://127.0.0.1:{}/ci", port),
+            Some("secret".to_string()),
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        cache.put("Main-01", b"blade").unwrap();
+        assert_eq!(cache.get("Main-01").unwrap(), Some(b"blade".to_vec()));
+        assert_eq!(cache.get("Main-02").unwrap(), None);
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("PUT /ci/Main-01.blade HTTP/1.1\r\n"));
+        assert!(requests[0].contains("Authorization: Bearer secret\r\n"));
+        assert!(requests[0].ends_with("\r\n\r\nblade"));
+        assert!(requests[1].starts_with("GET /ci/Main-01.blade HTTP/1.1\r\n"));
+    }
+}
@@ -91,6 +91,10 @@ pub enum IrTerminator {
     },
 
     /// Switch/jump table
+    ///
+    /// Case values are distinct. Backends lower dense case sets to a jump
+    /// table (`br_table` in Cranelift and Wasm) and sparse ones to a binary
+    /// search.
     Switch {
         value: IrId,
         cases: Vec<(i64, IrBlockId)>,
@@ -140,6 +144,13 @@ pub enum OptimizationHint {
 
     /// Custom hint with string description
     Custom(String),
+
+    /// Relative weights of the true/false targets of this block's
+    /// conditional branch (from profile counts or static hints)
+    BranchWeights { true_weight: u32, false_weight: u32 },
+
+    /// `@:unroll(N)` on the loop with this header (`None`: unroll fully)
+    Unroll { factor: Option<u32> },
 }
 
 impl IrBasicBlock {
@@ -0,0 +1,436 @@
+//! Branch Hints — branch probabilities and cold blocks for the backends.
+//!
+//! Marks blocks that are rarely executed with `OptimizationHint::ColdPath`
+//! and attaches `OptimizationHint::BranchWeights` to blocks ending in a
+//! conditional branch. Cranelift places cold blocks after the hot code, so
+//! the likely side of a branch falls through; LLVM receives the weights as
+//! `!prof` branch metadata.
+//!
+//! Sources, strongest first:
+//! - Profile counts from the interpreter, on tiered recompiles
+//!   (`ProfileData::branch_counts_for`)
+//! - `@:likely` / `@:unlikely` on an `if` condition (`LikelyPath` /
+//!   `UnlikelyPath` on the branch targets)
+//! - Error paths: exception handlers, `throw`, panics, unreachable code,
+//!   calls that never return and calls to `@:cold` functions
+//!
+//! Coldness then spreads to blocks that only lead to cold blocks and to
+//! blocks only reachable from cold blocks. The entry block is never cold.
+
+use super::blocks::{IrBasicBlock, IrBlockId, IrTerminator, OptimizationHint};
+use super::functions::IrFunctionId;
+use super::instructions::IrInstruction;
+use super::optimization::{OptimizationPass, OptimizationResult};
+use super::{IrFunction, IrModule};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Function attribute (`attributes.custom`) set by `@:cold`
+pub const COLD_ATTRIBUTE: &str = "cold";
+
+/// Taken/not-taken counts of the conditional branch ending each block
+pub type BranchCounts = HashMap<IrBlockId, (u64, u64)>;
+
+/// Weight of the expected side of a statically hinted branch (as with
+/// `__builtin_expect`, the other side gets 1)
+pub const LIKELY_WEIGHT: u32 = 2000;
+
+/// Profiled executions of a branch before a never-taken side is treated
+/// as cold
+pub const MIN_PROFILED_BRANCHES: u64 = 100;
+
+/// Whether `block` was marked cold
+pub fn is_cold(block: &IrBasicBlock) -> bool {
+    block
+        .metadata
+        .optimization_hints
+        .iter()
+        .any(|h| matches!(h, OptimizationHint::ColdPath))
+}
+
+/// (true, false) weights of the conditional branch ending `block`
+pub fn branch_weights(block: &IrBasicBlock) -> Option<(u32, u32)> {
+    block
+        .metadata
+        .optimization_hints
+        .iter()
+        .find_map(|h| match h {
+            OptimizationHint::BranchWeights {
+                true_weight,
+                false_weight,
+            } => Some((*true_weight, *false_weight)),
+            _ => None,
+        })
+}
+
+/// Annotate one function; returns whether any hint was added or changed
+pub fn annotate_function(
+    function: &mut IrFunction,
+    cold_callees: &HashSet<IrFunctionId>,
+    counts: Option<&BranchCounts>,
+) -> bool {
+    let entry = function.cfg.entry_block;
+    let successors: BTreeMap<IrBlockId, Vec<IrBlockId>> = function
+        .cfg
+        .blocks
+        .iter()
+        .map(|(&id, block)| (id, block.successors()))
+        .collect();
+    let mut predecessors: HashMap<IrBlockId, Vec<IrBlockId>> = HashMap::new();
+    for (&id, succs) in &successors {
+        for succ in succs {
+            predecessors.entry(*succ).or_default().push(id);
+        }
+    }
+
+    let mut cold: HashSet<IrBlockId> = function
+        .cfg
+        .blocks
+        .iter()
+        .filter(|(_, block)| is_cold_seed(block, cold_callees))
+        .map(|(&id, _)| id)
+        .collect();
+
+    // Measured weights; a side never taken in a well-exercised branch is cold
+    let mut weights: HashMap<IrBlockId, (u32, u32)> = HashMap::new();
+    for (&id, block) in &function.cfg.blocks {
+        let IrTerminator::CondBranch {
+            true_target,
+            false_target,
+            ..
+        } = block.terminator
+        else {
+            continue;
+        };
+        let Some(&(taken, not_taken)) = counts.and_then(|c| c.get(&id)) else {
+            continue;
+        };
+        if taken + not_taken == 0 {
+            continue;
+        }
+        weights.insert(id, scale_weights(taken, not_taken));
+        if taken + not_taken >= MIN_PROFILED_BRANCHES && true_target != false_target {
+            for (target, count) in [(true_target, taken), (false_target, not_taken)] {
+                if count == 0 && predecessors.get(&target).is_some_and(|p| p == &[id]) {
+                    cold.insert(target);
+                }
+            }
+        }
+    }
+
+    cold.remove(&entry);
+    loop {
+        let mut changed = false;
+        for (&id, succs) in &successors {
+            if id == entry || cold.contains(&id) {
+                continue;
+            }
+            let leads_to_cold = !succs.is_empty() && succs.iter().all(|s| cold.contains(s));
+            let reached_from_cold = predecessors
+                .get(&id)
+                .is_some_and(|p| p.iter().all(|b| cold.contains(b)));
+            if leads_to_cold || reached_from_cold {
+                cold.insert(id);
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    // Static weights where one side is hinted and the profile says nothing
+    for (&id, block) in &function.cfg.blocks {
+        if weights.contains_key(&id) {
+            continue;
+        }
+        let IrTerminator::CondBranch {
+            true_target,
+            false_target,
+            ..
+        } = block.terminator
+        else {
+            continue;
+        };
+        let bias = |target: IrBlockId| -> i32 {
+            let hints = function
+                .cfg
+                .blocks
+                .get(&target)
+                .map(|b| b.metadata.optimization_hints.as_slice())
+                .unwrap_or(&[]);
+            if cold.contains(&target)
+                || hints
+                    .iter()
+                    .any(|h| matches!(h, OptimizationHint::UnlikelyPath))
+            {
+                -1
+            } else if hints
+                .iter()
+                .any(|h| matches!(h, OptimizationHint::LikelyPath))
+            {
+                1
+            } else {
+                0
+            }
+        };
+        match bias(true_target).cmp(&bias(false_target)) {
+            std::cmp::Ordering::Greater => {
+                weights.insert(id, (LIKELY_WEIGHT, 1));
+            }
+            std::cmp::Ordering::Less => {
+                weights.insert(id, (1, LIKELY_WEIGHT));
+            }
+            std::cmp::Ordering::Equal => {}
+        }
+    }
+
+    let mut modified = false;
+    for (&id, block) in function.cfg.blocks.iter_mut() {
+        if cold.contains(&id) && !is_cold(block) {
+            block
+                .metadata
+                .optimization_hints
+                .push(OptimizationHint::ColdPath);
+            modified = true;
+        }
+        let new_weights = weights.get(&id).copied();
+        if branch_weights(block) != new_weights {
+            let hints = &mut block.metadata.optimization_hints;
+            hints.retain(|h| !matches!(h, OptimizationHint::BranchWeights { .. }));
+            if let Some((true_weight, false_weight)) = new_weights {
+                hints.push(OptimizationHint::BranchWeights {
+                    true_weight,
+                    false_weight,
+                });
+            }
+            modified = true;
+        }
+    }
+    modified
+}
+
+/// Blocks that are cold on their own, before propagation
+fn is_cold_seed(block: &IrBasicBlock, cold_callees: &HashSet<IrFunctionId>) -> bool {
+    if is_cold(block)
+        || block.metadata.in_exception_handler
+        || matches!(
+            block.terminator,
+            IrTerminator::Unreachable | IrTerminator::NoReturn { .. }
+        )
+    {
+        return true;
+    }
+    block
+        .metadata
+        .optimization_hints
+        .iter()
+        .any(|h| matches!(h, OptimizationHint::UnlikelyPath))
+        || block.instructions.iter().any(|inst| match inst {
+            IrInstruction::Throw { .. } | IrInstruction::Panic { .. } => true,
+            IrInstruction::CallDirect { func_id, .. } => cold_callees.contains(func_id),
+            _ => false,
+        })
+}
+
+/// Counts scaled down to fit `u32` while keeping their ratio
+fn scale_weights(taken: u64, not_taken: u64) -> (u32, u32) {
+    let shift = 64 - taken.max(not_taken).leading_zeros();
+    let shift = shift.saturating_sub(32);
+    ((taken >> shift) as u32, (not_taken >> shift) as u32)
+}
+
+/// Annotates every function of a module from static hints. Runs at all
+/// optimization levels; profile counts are applied separately by the
+/// tiered backend on recompiles.
+pub struct BranchHintPass;
+
+impl BranchHintPass {
+    pub fn new() -> Self {
+        BranchHintPass
+    }
+}
+
+impl Default for BranchHintPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Functions marked `@:cold` in a module
+pub fn cold_functions(module: &IrModule) -> HashSet<IrFunctionId> {
+    module
+        .functions
+        .iter()
+        .filter(|(_, f)| f.attributes.custom.contains_key(COLD_ATTRIBUTE))
+        .map(|(&id, _)| id)
+        .collect()
+}
+
+impl OptimizationPass for BranchHintPass {
+    fn name(&self) -> &'static str {
+        "branch-hints"
+    }
+
+    fn run_on_module(&mut self, module: &mut IrModule) -> OptimizationResult {
+        let cold_callees = cold_functions(module);
+        let mut annotated = 0;
+        for function in module.functions.values_mut() {
+            if annotate_function(function, &cold_callees, None) {
+                annotated += 1;
+            }
+        }
+
+        if annotated == 0 {
+            return OptimizationResult::unchanged();
+        }
+        let mut stats = HashMap::new();
+        stats.insert("branch_hint_functions".to_string(), annotated);
+        OptimizationResult {
+            modified: true,
+            instructions_eliminated: 0,
+            blocks_eliminated: 0,
+            stats,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::mir_builder::MirBuilder;
+    use crate::ir::IrType;
+
+    /// entry: condbr c, then, else; then: <then_body>; ret 1; else: ret 0
+    fn diamond(throw_in_then: bool) -> (IrModule, IrFunctionId, [IrBlockId; 3]) {
+        let mut builder = MirBuilder::new("test");
+        let func = builder
+            .begin_function("f")
+            .param("c", IrType::Bool)
+            .returns(IrType::I32)
+            .build();
+        builder.set_current_function(func);
+        let entry = builder.create_block("entry");
+        let then_block = builder.create_block("then");
+        let else_block = builder.create_block("else");
+
+        builder.set_insert_point(entry);
+        let c = builder.get_param(0);
+        builder.cond_br(c, then_block, else_block);
+
+        builder.set_insert_point(then_block);
+        if throw_in_then {
+            builder.unreachable();
+        } else {
+            let one = builder.const_i32(1);
+            builder.ret(Some(one));
+        }
+
+        builder.set_insert_point(else_block);
+        let zero = builder.const_i32(0);
+        builder.ret(Some(zero));
+
+        (builder.finish(), func, [entry, then_block, else_block])
+    }
+
+    #[test]
+    fn test_unreachable_path_is_cold() {
+        let (mut module, func, [entry, then_block, else_block]) = diamond(true);
+        let function = module.functions.get_mut(&func).unwrap();
+        assert!(annotate_function(function, &HashSet::new(), None));
+
+        let blocks = &function.cfg.blocks;
+        assert!(is_cold(&blocks[&then_block]));
+        assert!(!is_cold(&blocks[&else_block]));
+        assert_eq!(branch_weights(&blocks[&entry]), Some((1, LIKELY_WEIGHT)));
+
+        // Idempotent
+        assert!(!annotate_function(function, &HashSet::new(), None));
+    }
+
+    #[test]
+    fn test_unhinted_branch_has_no_weights() {
+        let (mut module, func, [entry, then_block, _]) = diamond(false);
+        let function = module.functions.get_mut(&func).unwrap();
+        assert!(!annotate_function(function, &HashSet::new(), None));
+        assert_eq!(branch_weights(&function.cfg.blocks[&entry]), None);
+        assert!(!is_cold(&function.cfg.blocks[&then_block]));
+    }
+
+    #[test]
+    fn test_likely_hint() {
+        let (mut module, func, [entry, _, else_block]) = diamond(false);
+        let function = module.functions.get_mut(&func).unwrap();
+        function
+            .cfg
+            .get_block_mut(else_block)
+            .unwrap()
+            .metadata
+            .optimization_hints
+            .push(OptimizationHint::LikelyPath);
+        annotate_function(function, &HashSet::new(), None);
+        assert_eq!(
+            branch_weights(&function.cfg.blocks[&entry]),
+            Some((1, LIKELY_WEIGHT))
+        );
+    }
+
+    #[test]
+    fn test_profile_counts() {
+        let (mut module, func, [entry, then_block, else_block]) = diamond(false);
+        let function = module.functions.get_mut(&func).unwrap();
+
+        // Too few samples to call the else side cold
+        let counts = BranchCounts::from([(entry, (40, 0))]);
+        annotate_function(function, &HashSet::new(), Some(&counts));
+        assert_eq!(branch_weights(&function.cfg.blocks[&entry]), Some((40, 0)));
+        assert!(!is_cold(&function.cfg.blocks[&else_block]));
+
+        let counts = BranchCounts::from([(entry, (5000, 0))]);
+        assert!(annotate_function(function, &HashSet::new(), Some(&counts)));
+        assert_eq!(
+            branch_weights(&function.cfg.blocks[&entry]),
+            Some((5000, 0))
+        );
+        assert!(is_cold(&function.cfg.blocks[&else_block]));
+        assert!(!is_cold(&function.cfg.blocks[&then_block]));
+    }
+
+    #[test]
+    fn test_cold_callee_and_pass() {
+        let (mut module, func, [entry, then_block, _]) = diamond(false);
+        let function = module.functions.get_mut(&func).unwrap();
+        function
+            .attributes
+            .custom
+            .insert(COLD_ATTRIBUTE.to_string(), String::new());
+        // f calls itself from the then block: a call to a @:cold function
+        function
+            .cfg
+            .get_block_mut(then_block)
+            .unwrap()
+            .instructions
+            .push(IrInstruction::CallDirect {
+                dest: None,
+                func_id: func,
+                args: vec![],
+                arg_ownership: vec![],
+                type_args: vec![],
+                is_tail_call: false,
+            });
+
+        let result = BranchHintPass::new().run_on_module(&mut module);
+        assert!(result.modified);
+        let blocks = &module.functions[&func].cfg.blocks;
+        assert!(is_cold(&blocks[&then_block]));
+        assert!(!is_cold(&blocks[&entry]));
+        assert_eq!(branch_weights(&blocks[&entry]), Some((1, LIKELY_WEIGHT)));
+    }
+
+    #[test]
+    fn test_scale_weights() {
+        assert_eq!(scale_weights(3, 1), (3, 1));
+        let (t, f) = scale_weights(u64::MAX, u64::MAX / 4);
+        assert_eq!(t, u32::MAX);
+        assert_eq!(f, u32::MAX / 4);
+    }
+}
@@ -48,6 +48,11 @@ impl IrBuilder {
         self.current_source_location = loc;
     }
 
+    /// The current source location
+    pub fn source_location(&self) -> IrSourceLocation {
+        self.current_source_location
+    }
+
     // === Module Building ===
 
     /// Start building a new function
@@ -599,6 +604,16 @@ impl IrBuilder {
         self.add_instruction(IrInstruction::Free { ptr })
     }
 
+    /// Build a copy of `size` bytes from `src` to `dest`
+    pub fn build_memcpy(&mut self, dest: IrId, src: IrId, size: IrId) -> Option<()> {
+        self.add_instruction(IrInstruction::MemCopy { dest, src, size })
+    }
+
+    /// Build a fill of `size` bytes at `dest` with the byte `value`
+    pub fn build_memset(&mut self, dest: IrId, value: IrId, size: IrId) -> Option<()> {
+        self.add_instruction(IrInstruction::MemSet { dest, value, size })
+    }
+
     /// Build a heap free by calling the free function
     /// This is used for explicit deallocation of heap-allocated objects (Rust-style drop)
     pub fn build_heap_free(&mut self, ptr: IrId) -> Option<()> {
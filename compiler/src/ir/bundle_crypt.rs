@@ -0,0 +1,272 @@
+//! Payload encryption for .rzb bundles (`rayzor bundle --encrypt`)
+//!
+//! Encrypts the serialized (and compressed) bundle with ChaCha20 (RFC 8439)
+//! under a 256-bit key that the host supplies when loading it, through
+//! `RAYZOR_BUNDLE_KEY` or [`crate::ir::blade::load_bundle_with_key`].
+//!
+//! This is obfuscation, not DRM: the key has to be present on the machine
+//! that runs the bundle, so anyone able to run it can recover the MIR. It
+//! keeps casual inspection out of shipped game data, nothing more. There is
+//! no MAC; a tampered payload fails to deserialize.
+//!
+//! Encrypted file layout:
+//!
+//! ```text
+//! "RZBE" | version: u32 LE | nonce: [u8; 12] | key check: [u8; 8] | ciphertext
+//! ```
+//!
+//! The key check is the first 8 bytes of keystream block 0, so a wrong key
+//! is reported as such; the payload is encrypted from block 1.
+
+use std::fmt;
+
+/// Magic number of an encrypted bundle
+pub const ENCRYPTED_MAGIC: &[u8; 4] = b"RZBE";
+
+/// Encrypted container version
+const ENCRYPTED_VERSION: u32 = 1;
+
+/// Header size: magic, version, nonce and key check
+const HEADER_LEN: usize = 4 + 4 + 12 + 8;
+
+/// Environment variable holding the bundle key as 64 hex digits
+pub const BUNDLE_KEY_ENV: &str = "RAYZOR_BUNDLE_KEY";
+
+/// A 256-bit bundle encryption key
+#[derive(Clone, PartialEq, Eq)]
+pub struct BundleKey([u8; 32]);
+
+impl BundleKey {
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Parse a key written as 64 hex digits (e.g. `openssl rand -hex 32`)
+    pub fn from_hex(hex: &str) -> Result<Self, String> {
+        let hex = hex.trim();
+        if hex.len() != 64 {
+            return Err(format!(
+                "bundle key must be 64 hex digits (256 bits), got {} characters",
+                hex.len()
+            ));
+        }
+        let mut key = [0u8; 32];
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+                .map_err(|_| "bundle key must be 64 hex digits (256 bits)".to_string())?;
+        }
+        Ok(Self(key))
+    }
+
+    /// The key in `var`, or None when it is unset
+    pub fn from_env_var(var: &str) -> Result<Option<Self>, String> {
+        match std::env::var(var) {
+            Ok(hex) => Self::from_hex(&hex)
+                .map(Some)
+                .map_err(|e| format!("{}: {}", var, e)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// The key in `RAYZOR_BUNDLE_KEY`, or None when it is unset
+    pub fn from_env() -> Result<Option<Self>, String> {
+        Self::from_env_var(BUNDLE_KEY_ENV)
+    }
+}
+
+impl fmt::Debug for BundleKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BundleKey(..)")
+    }
+}
+
+/// Why an encrypted bundle could not be opened
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenError {
+    /// Shorter than the header
+    Truncated,
+    /// Written by a newer container version
+    UnsupportedVersion(u32),
+    /// The key check doesn't match
+    WrongKey,
+}
+
+/// Whether `bytes` is an encrypted bundle
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(ENCRYPTED_MAGIC)
+}
+
+/// Encrypt a serialized bundle
+pub fn seal(payload: &[u8], key: &BundleKey) -> Vec<u8> {
+    let nonce = fresh_nonce();
+    let mut out = Vec::with_capacity(HEADER_LEN + payload.len());
+    out.extend_from_slice(ENCRYPTED_MAGIC);
+    out.extend_from_slice(&ENCRYPTED_VERSION.to_le_bytes());
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&key_check(key, &nonce));
+    let start = out.len();
+    out.extend_from_slice(payload);
+    chacha20_xor(&key.0, &nonce, 1, &mut out[start..]);
+    out
+}
+
+/// Decrypt an encrypted bundle back to its serialized form
+pub fn open(bytes: &[u8], key: &BundleKey) -> Result<Vec<u8>, OpenError> {
+    if bytes.len() < HEADER_LEN || !is_encrypted(bytes) {
+        return Err(OpenError::Truncated);
+    }
+    let version = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+    if version != ENCRYPTED_VERSION {
+        return Err(OpenError::UnsupportedVersion(version));
+    }
+    let nonce: [u8; 12] = bytes[8..20].try_into().unwrap();
+    if bytes[20..28] != key_check(key, &nonce) {
+        return Err(OpenError::WrongKey);
+    }
+    let mut payload = bytes[HEADER_LEN..].to_vec();
+    chacha20_xor(&key.0, &nonce, 1, &mut payload);
+    Ok(payload)
+}
+
+fn key_check(key: &BundleKey, nonce: &[u8; 12]) -> [u8; 8] {
+    let block = chacha20_block(&key.0, nonce, 0);
+    block[..8].try_into().unwrap()
+}
+
+/// A nonce that is never reused in practice: the time, the process id and
+/// the OS-seeded per-process hash keys of `RandomState`
+fn fresh_nonce() -> [u8; 12] {
+    use std::hash::{BuildHasher, Hasher};
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let mut nonce = [0u8; 12];
+    for (i, chunk) in nonce.chunks_mut(8).enumerate() {
+        let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+        hasher.write_u128(nanos);
+        hasher.write_u32(std::process::id());
+        hasher.write_usize(i);
+        let hash = hasher.finish().to_le_bytes();
+        chunk.copy_from_slice(&hash[..chunk.len()]);
+    }
+    nonce
+}
+
+fn quarter_round(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    s[a] = s[a].wrapping_add(s[b]);
+    s[d] = (s[d] ^ s[a]).rotate_left(16);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_left(12);
+    s[a] = s[a].wrapping_add(s[b]);
+    s[d] = (s[d] ^ s[a]).rotate_left(8);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_left(7);
+}
+
+/// One 64-byte ChaCha20 keystream block (RFC 8439 section 2.3)
+fn chacha20_block(key: &[u8; 32], nonce: &[u8; 12], counter: u32) -> [u8; 64] {
+    let word = |bytes: &[u8]| u32::from_le_bytes(bytes.try_into().unwrap());
+    let mut state = [0u32; 16];
+    state[..4].copy_from_slice(&[0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574]);
+    for i in 0..8 {
+        state[4 + i] = word(&key[i * 4..i * 4 + 4]);
+    }
+    state[12] = counter;
+    for i in 0..3 {
+        state[13 + i] = word(&nonce[i * 4..i * 4 + 4]);
+    }
+
+    let mut working = state;
+    for _ in 0..10 {
+        quarter_round(&mut working, 0, 4, 8, 12);
+        quarter_round(&mut working, 1, 5, 9, 13);
+        quarter_round(&mut working, 2, 6, 10, 14);
+        quarter_round(&mut working, 3, 7, 11, 15);
+        quarter_round(&mut working, 0, 5, 10, 15);
+        quarter_round(&mut working, 1, 6, 11, 12);
+        quarter_round(&mut working, 2, 7, 8, 13);
+        quarter_round(&mut working, 3, 4, 9, 14);
+    }
+
+    let mut out = [0u8; 64];
+    for i in 0..16 {
+        let value = working[i].wrapping_add(state[i]);
+        out[i * 4..i * 4 + 4].copy_from_slice(&value.to_le_bytes());
+    }
+    out
+}
+
+/// XOR `data` with the ChaCha20 keystream starting at block `counter`
+fn chacha20_xor(key: &[u8; 32], nonce: &[u8; 12], counter: u32, data: &mut [u8]) {
+    for (i, chunk) in data.chunks_mut(64).enumerate() {
+        let block = chacha20_block(key, nonce, counter.wrapping_add(i as u32));
+        for (byte, k) in chunk.iter_mut().zip(block.iter()) {
+            *byte ^= k;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_key() -> [u8; 32] {
+        std::array::from_fn(|i| i as u8)
+    }
+
+    #[test]
+    fn test_chacha20_rfc8439_vectors() {
+        // Section 2.3.2: block function
+        let nonce = [0, 0, 0, 0x09, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+        let block = chacha20_block(&test_key(), &nonce, 1);
+        assert_eq!(
+            block[..16],
+            [
+                0x10, 0xf1, 0xe7, 0xe4, 0xd1, 0x3b, 0x59, 0x15, 0x50, 0x0f, 0xdd, 0x1f, 0xa3, 0x20,
+                0x71, 0xc4
+            ]
+        );
+
+        // Section 2.4.2: encryption
+        let nonce = [0, 0, 0, 0, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+        let mut text = b"Ladies and Gentlemen of the class of '99: If I could offer you only one \
+                         tip for the future, sunscreen would be it."
+            .to_vec();
+        chacha20_xor(&test_key(), &nonce, 1, &mut text);
+        assert_eq!(
+            text[..16],
+            [
+                0x6e, 0x2e, 0x35, 0x9a, 0x25, 0x68, 0xf9, 0x80, 0x41, 0xba, 0x07, 0x28, 0xdd, 0x0d,
+                0x69, 0x81
+            ]
+        );
+        assert_eq!(text[text.len() - 2..], [0x87, 0x4d]);
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        let key = BundleKey::from_bytes(test_key());
+        let payload: Vec<u8> = (0..200u8).collect();
+        let sealed = seal(&payload, &key);
+        assert!(is_encrypted(&sealed));
+        assert_ne!(sealed[HEADER_LEN..], payload[..]);
+        assert_eq!(open(&sealed, &key), Ok(payload));
+
+        let other = BundleKey::from_bytes([7; 32]);
+        assert_eq!(open(&sealed, &other), Err(OpenError::WrongKey));
+        assert_eq!(open(&sealed[..10], &key), Err(OpenError::Truncated));
+    }
+
+    #[test]
+    fn test_key_from_hex() {
+        let hex: String = (0..32).map(|i| format!("{:02x}", i)).collect();
+        assert_eq!(
+            BundleKey::from_hex(&hex),
+            Ok(BundleKey::from_bytes(test_key()))
+        );
+        assert!(BundleKey::from_hex("abcd").is_err());
+        assert!(BundleKey::from_hex(&"zz".repeat(32)).is_err());
+    }
+}
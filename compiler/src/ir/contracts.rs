@@ -0,0 +1,600 @@
+//! Contract Checks — runtime checks for `@:requires` / `@:ensures`.
+//!
+//! Each contract condition is lowered into a synthetic Bool method beside the
+//! method it constrains, taking the same parameters (plus `result` for
+//! postconditions), and HIR→MIR lists a method's contracts in its
+//! [`CONTRACTS_ATTRIBUTE`]. In debug and `--safety checked` builds this pass
+//! calls the predicates: preconditions on entry, postconditions before every
+//! `return`. A failed check calls the runtime's `rayzor_contract_failed`,
+//! which reports the contract in the diagnostics format and aborts. Release
+//! builds don't run the pass, so the predicates are never called.
+//!
+//! ## Transformation
+//!
+//! ```text
+//! B0: <body>                 B0: ok = requires_pred(params)
+//!                     =>         condbr ok, B1, F
+//!                            B1: <body>
+//!                            F:  rayzor_contract_failed(...)    (cold)
+//!                                unreachable
+//!
+//! B:  ...                    B:  ...
+//!     return v        =>         ok = ensures_pred(params, v)
+//!                                condbr ok, R, F'
+//!                            R:  return v
+//! ```
+//!
+//! The entry block keeps its id (backends treat block 0 as the entry).
+//! Postconditions get the parameters as passed, even if the body reassigned
+//! them.
+
+use super::blocks::{IrBasicBlock, IrBlockId, IrTerminator, OptimizationHint};
+use super::functions::IrFunctionId;
+use super::instructions::IrInstruction;
+use super::optimization::{OptimizationPass, OptimizationResult};
+use super::{IrFunction, IrId, IrModule, IrType, IrValue};
+use crate::tast::node::ContractKind;
+use crate::tast::SymbolId;
+use std::collections::HashMap;
+
+/// Function attribute (`attributes.custom`) listing a function's contracts,
+/// one per line (see [`encode`])
+pub const CONTRACTS_ATTRIBUTE: &str = "contracts";
+
+/// Runtime report for a failed contract; aborts
+pub const CONTRACT_FAILED_FN: &str = "rayzor_contract_failed";
+
+/// One contract of a function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contract {
+    pub kind: ContractKind,
+    /// The synthetic predicate method
+    pub predicate: SymbolId,
+    /// Condition source text, on one line
+    pub condition: String,
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+/// `contracts` as a [`CONTRACTS_ATTRIBUTE`] value: a tab-separated line
+/// each, ending with the condition
+pub fn encode(contracts: &[Contract]) -> String {
+    contracts
+        .iter()
+        .map(|c| {
+            format!(
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                c.kind.name(),
+                c.predicate.as_raw(),
+                c.line,
+                c.column,
+                c.file,
+                c.condition
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The contracts listed in the [`CONTRACTS_ATTRIBUTE`] of `function`
+pub fn contracts(function: &IrFunction) -> Vec<Contract> {
+    let Some(value) = function.attributes.custom.get(CONTRACTS_ATTRIBUTE) else {
+        return Vec::new();
+    };
+    value.lines().filter_map(decode).collect()
+}
+
+fn decode(line: &str) -> Option<Contract> {
+    let mut parts = line.splitn(6, '\t');
+    let kind = match parts.next()? {
+        "requires" => ContractKind::Requires,
+        "ensures" => ContractKind::Ensures,
+        _ => return None,
+    };
+    Some(Contract {
+        kind,
+        predicate: SymbolId::from_raw(parts.next()?.parse().ok()?),
+        line: parts.next()?.parse().ok()?,
+        column: parts.next()?.parse().ok()?,
+        file: parts.next()?.to_string(),
+        condition: parts.next()?.to_string(),
+    })
+}
+
+pub struct ContractCheckPass;
+
+impl ContractCheckPass {
+    pub fn new() -> Self {
+        ContractCheckPass
+    }
+}
+
+impl Default for ContractCheckPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OptimizationPass for ContractCheckPass {
+    fn name(&self) -> &'static str {
+        "ContractCheck"
+    }
+
+    fn run_on_module(&mut self, module: &mut IrModule) -> OptimizationResult {
+        let contracted: Vec<(IrFunctionId, Vec<Contract>)> = module
+            .functions
+            .iter()
+            .map(|(&id, f)| (id, contracts(f)))
+            .filter(|(_, contracts)| !contracts.is_empty())
+            .collect();
+        if contracted.is_empty() {
+            return OptimizationResult::unchanged();
+        }
+
+        let predicates: HashMap<SymbolId, IrFunctionId> = module
+            .functions
+            .iter()
+            .filter(|(_, f)| !f.cfg.blocks.is_empty())
+            .map(|(&id, f)| (f.symbol_id, id))
+            .collect();
+        let contract_failed = module
+            .extern_functions
+            .iter()
+            .find(|(_, f)| f.name == CONTRACT_FAILED_FN)
+            .map(|(&id, _)| id)
+            .unwrap_or_else(|| declare_contract_failed(module));
+
+        let mut total_checks = 0;
+        for (func_id, contracts) in contracted {
+            // Preconditions are each placed in front of the previous ones, so
+            // go backwards to check them in declaration order
+            let ordered = contracts
+                .iter()
+                .filter(|c| c.kind == ContractKind::Requires)
+                .rev()
+                .chain(contracts.iter().filter(|c| c.kind == ContractKind::Ensures));
+            for contract in ordered {
+                let Some(&predicate) = predicates.get(&contract.predicate) else {
+                    continue;
+                };
+                let arity = module.functions[&predicate].signature.parameters.len();
+                let Some(function) = module.functions.get_mut(&func_id) else {
+                    continue;
+                };
+                total_checks +=
+                    insert_checks(function, contract, predicate, arity, contract_failed);
+            }
+        }
+
+        if total_checks > 0 {
+            let mut stats = HashMap::new();
+            stats.insert("contract_checks_inserted".to_string(), total_checks);
+            OptimizationResult {
+                modified: true,
+                instructions_eliminated: 0,
+                stats,
+                blocks_eliminated: 0,
+            }
+        } else {
+            OptimizationResult::unchanged()
+        }
+    }
+}
+
+/// Declare `rayzor_contract_failed(kind: i32, function: String,
+/// condition: String, file: String, line: i32, column: i32)` as a runtime
+/// extern.
+fn declare_contract_failed(module: &mut IrModule) -> IrFunctionId {
+    let param = |name: &str, ty: IrType, i: u32| super::functions::IrParameter {
+        name: name.to_string(),
+        ty,
+        reg: IrId(i),
+        by_ref: false,
+    };
+    let string = IrType::Ptr(Box::new(IrType::String));
+    let id = module.alloc_function_id();
+    module.extern_functions.insert(
+        id,
+        super::modules::IrExternFunction {
+            id,
+            name: CONTRACT_FAILED_FN.to_string(),
+            symbol_id: SymbolId::from_raw(0),
+            signature: super::IrFunctionSignature {
+                parameters: vec![
+                    param("kind", IrType::I32, 0),
+                    param("function", string.clone(), 1),
+                    param("condition", string.clone(), 2),
+                    param("file", string, 3),
+                    param("line", IrType::I32, 4),
+                    param("column", IrType::I32, 5),
+                ],
+                return_type: IrType::Void,
+                calling_convention: super::CallingConvention::C,
+                can_throw: false,
+                type_params: vec![],
+                uses_sret: false,
+            },
+            source: "runtime".to_string(),
+        },
+    );
+    id
+}
+
+/// Check `contract` in `function` by calling `predicate`, which takes
+/// `arity` arguments. Returns the number of checks inserted: one for a
+/// precondition, one per `return` for a postcondition.
+fn insert_checks(
+    function: &mut IrFunction,
+    contract: &Contract,
+    predicate: IrFunctionId,
+    arity: usize,
+    contract_failed: IrFunctionId,
+) -> usize {
+    let params: Vec<IrId> = function
+        .signature
+        .parameters
+        .iter()
+        .map(|p| p.reg)
+        .collect();
+
+    match contract.kind {
+        ContractKind::Requires => {
+            if params.len() != arity {
+                return 0;
+            }
+            let body = split_entry(function);
+            let entry = function.cfg.entry_block;
+            guard(
+                function,
+                entry,
+                body,
+                params,
+                contract,
+                predicate,
+                contract_failed,
+            );
+            1
+        }
+        ContractKind::Ensures => {
+            let returns: Vec<(IrBlockId, Option<IrId>)> = function
+                .cfg
+                .blocks
+                .iter()
+                .filter_map(|(&id, block)| match block.terminator {
+                    IrTerminator::Return { value } => Some((id, value)),
+                    _ => None,
+                })
+                .collect();
+            let mut inserted = 0;
+            for (block_id, value) in returns {
+                let mut args = params.clone();
+                args.extend(value);
+                if args.len() != arity {
+                    continue;
+                }
+                let ret = function.cfg.create_block();
+                function.cfg.get_block_mut(ret).unwrap().terminator =
+                    IrTerminator::Return { value };
+                guard(
+                    function,
+                    block_id,
+                    ret,
+                    args,
+                    contract,
+                    predicate,
+                    contract_failed,
+                );
+                inserted += 1;
+            }
+            inserted
+        }
+    }
+}
+
+/// Move the code of the entry block into a new block and return its id,
+/// leaving the entry empty. Edges into the entry now lead to the new block.
+fn split_entry(function: &mut IrFunction) -> IrBlockId {
+    let entry = function.cfg.entry_block;
+    let body_id = function.cfg.create_block();
+    let entry_block = function.cfg.blocks.get_mut(&entry).unwrap();
+    let mut body = std::mem::replace(entry_block, IrBasicBlock::new(entry));
+    body.id = body_id;
+
+    for block in function.cfg.blocks.values_mut() {
+        retarget(&mut block.terminator, entry, body_id);
+        for phi in &mut block.phi_nodes {
+            for (pred, _) in &mut phi.incoming {
+                if *pred == entry {
+                    *pred = body_id;
+                }
+            }
+        }
+        for pred in &mut block.predecessors {
+            if *pred == entry {
+                *pred = body_id;
+            }
+        }
+    }
+    retarget(&mut body.terminator, entry, body_id);
+    for pred in &mut body.predecessors {
+        if *pred == entry {
+            *pred = body_id;
+        }
+    }
+    body.predecessors.push(entry);
+    function.cfg.blocks.insert(body_id, body);
+    body_id
+}
+
+/// End `block` (whose terminator is replaced) with a call of `predicate`
+/// on `args`, continuing to `next` if it holds and to a new cold block that
+/// reports the contract otherwise
+fn guard(
+    function: &mut IrFunction,
+    block: IrBlockId,
+    next: IrBlockId,
+    args: Vec<IrId>,
+    contract: &Contract,
+    predicate: IrFunctionId,
+    contract_failed: IrFunctionId,
+) {
+    let holds = function.alloc_reg();
+    function.register_types.insert(holds, IrType::Bool);
+    let fail = function.cfg.create_block();
+
+    let b = function.cfg.get_block_mut(block).unwrap();
+    b.instructions.push(IrInstruction::CallDirect {
+        dest: Some(holds),
+        func_id: predicate,
+        args,
+        arg_ownership: vec![],
+        type_args: vec![],
+        is_tail_call: false,
+    });
+    b.terminator = IrTerminator::CondBranch {
+        condition: holds,
+        true_target: next,
+        false_target: fail,
+    };
+    function.cfg.connect_blocks(block, next);
+    function.cfg.connect_blocks(block, fail);
+
+    let function_name = function
+        .qualified_name
+        .clone()
+        .unwrap_or_else(|| function.name.clone());
+    let kind = match contract.kind {
+        ContractKind::Requires => 0,
+        ContractKind::Ensures => 1,
+    };
+    let values = [
+        (IrValue::I32(kind), IrType::I32),
+        (IrValue::String(function_name), IrType::String),
+        (IrValue::String(contract.condition.clone()), IrType::String),
+        (IrValue::String(contract.file.clone()), IrType::String),
+        (IrValue::I32(contract.line as i32), IrType::I32),
+        (IrValue::I32(contract.column as i32), IrType::I32),
+    ];
+    let mut report_args = Vec::with_capacity(values.len());
+    let mut instructions = Vec::with_capacity(values.len() + 1);
+    for (value, ty) in values {
+        let dest = function.alloc_reg();
+        function.register_types.insert(dest, ty);
+        instructions.push(IrInstruction::Const { dest, value });
+        report_args.push(dest);
+    }
+    instructions.push(IrInstruction::CallDirect {
+        dest: None,
+        func_id: contract_failed,
+        args: report_args,
+        arg_ownership: vec![],
+        type_args: vec![],
+        is_tail_call: false,
+    });
+
+    let f = function.cfg.get_block_mut(fail).unwrap();
+    f.instructions = instructions;
+    f.terminator = IrTerminator::Unreachable;
+    f.metadata
+        .optimization_hints
+        .push(OptimizationHint::ColdPath);
+}
+
+fn retarget(terminator: &mut IrTerminator, from: IrBlockId, to: IrBlockId) {
+    let fix = |target: &mut IrBlockId| {
+        if *target == from {
+            *target = to;
+        }
+    };
+    match terminator {
+        IrTerminator::Branch { target } => fix(target),
+        IrTerminator::CondBranch {
+            true_target,
+            false_target,
+            ..
+        } => {
+            fix(true_target);
+            fix(false_target);
+        }
+        IrTerminator::Switch { cases, default, .. } => {
+            cases.iter_mut().for_each(|(_, target)| fix(target));
+            fix(default);
+        }
+        IrTerminator::Return { .. } | IrTerminator::Unreachable | IrTerminator::NoReturn { .. } => {
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{CallingConvention, IrFunctionSignature, IrParameter};
+
+    fn signature(params: &[IrType], return_type: IrType) -> IrFunctionSignature {
+        IrFunctionSignature {
+            parameters: params
+                .iter()
+                .enumerate()
+                .map(|(i, ty)| IrParameter {
+                    name: format!("p{}", i),
+                    ty: ty.clone(),
+                    reg: IrId(i as u32),
+                    by_ref: false,
+                })
+                .collect(),
+            return_type,
+            calling_convention: CallingConvention::Haxe,
+            can_throw: false,
+            type_params: vec![],
+            uses_sret: false,
+        }
+    }
+
+    /// `abs(x:Int):Int` returning `x` from two blocks, with a precondition
+    /// and a postcondition predicate
+    fn contracted_module() -> (IrModule, IrFunctionId) {
+        let mut module = IrModule::new("test".to_string(), "Main.hx".to_string());
+
+        for (symbol, params) in [(10, vec![IrType::I32]), (11, vec![IrType::I32; 2])] {
+            let id = module.alloc_function_id();
+            let mut predicate = IrFunction::new(
+                id,
+                SymbolId::from_raw(symbol),
+                format!("pred{}", symbol),
+                signature(&params, IrType::Bool),
+            );
+            let t = predicate.alloc_reg();
+            let entry = predicate.cfg.entry_block;
+            let block = predicate.cfg.get_block_mut(entry).unwrap();
+            block.instructions.push(IrInstruction::Const {
+                dest: t,
+                value: IrValue::Bool(true),
+            });
+            block.terminator = IrTerminator::Return { value: Some(t) };
+            module.functions.insert(id, predicate);
+        }
+
+        let func_id = module.alloc_function_id();
+        let mut function = IrFunction::new(
+            func_id,
+            SymbolId::from_raw(1),
+            "abs".to_string(),
+            signature(&[IrType::I32], IrType::I32),
+        );
+        let entry = function.cfg.entry_block;
+        let other = function.cfg.create_block();
+        function.cfg.get_block_mut(entry).unwrap().terminator = IrTerminator::CondBranch {
+            condition: IrId(0),
+            true_target: other,
+            false_target: other,
+        };
+        function.cfg.connect_blocks(entry, other);
+        function.cfg.get_block_mut(other).unwrap().terminator = IrTerminator::Return {
+            value: Some(IrId(0)),
+        };
+        let contract = |kind, predicate| Contract {
+            kind,
+            predicate: SymbolId::from_raw(predicate),
+            condition: "x >= 0".to_string(),
+            file: "Main.hx".to_string(),
+            line: 3,
+            column: 5,
+        };
+        function.attributes.custom.insert(
+            CONTRACTS_ATTRIBUTE.to_string(),
+            encode(&[
+                contract(ContractKind::Requires, 10),
+                contract(ContractKind::Ensures, 11),
+            ]),
+        );
+        module.functions.insert(func_id, function);
+
+        (module, func_id)
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let (module, func_id) = contracted_module();
+        let listed = contracts(&module.functions[&func_id]);
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].kind, ContractKind::Requires);
+        assert_eq!(listed[1].predicate, SymbolId::from_raw(11));
+        assert_eq!(listed[1].condition, "x >= 0");
+        assert_eq!((listed[1].line, listed[1].column), (3, 5));
+    }
+
+    #[test]
+    fn test_inserts_entry_and_return_checks() {
+        let (mut module, func_id) = contracted_module();
+
+        let result = ContractCheckPass::new().run_on_module(&mut module);
+        assert!(result.modified);
+        assert_eq!(result.stats["contract_checks_inserted"], 2);
+        assert!(module
+            .extern_functions
+            .values()
+            .any(|f| f.name == CONTRACT_FAILED_FN));
+
+        let function = &module.functions[&func_id];
+        let calls = |block: &IrBasicBlock| {
+            block
+                .instructions
+                .iter()
+                .filter(|i| matches!(i, IrInstruction::CallDirect { .. }))
+                .count()
+        };
+
+        // The entry only checks the precondition, then runs the old body
+        let entry = &function.cfg.blocks[&function.cfg.entry_block];
+        assert_eq!(calls(entry), 1);
+        let IrTerminator::CondBranch {
+            true_target: body,
+            false_target: fail,
+            ..
+        } = entry.terminator
+        else {
+            panic!("expected contract branch, got {:?}", entry.terminator);
+        };
+        assert!(matches!(
+            function.cfg.blocks[&body].terminator,
+            IrTerminator::CondBranch { .. }
+        ));
+        assert!(function.cfg.blocks[&body]
+            .predecessors
+            .contains(&function.cfg.entry_block));
+        let fail = &function.cfg.blocks[&fail];
+        assert!(matches!(fail.terminator, IrTerminator::Unreachable));
+        assert!(crate::ir::branch_hints::is_cold(fail));
+
+        // The one return is now behind the postcondition, which gets `result`
+        let returns: Vec<_> = function
+            .cfg
+            .blocks
+            .values()
+            .filter(|b| matches!(b.terminator, IrTerminator::Return { .. }))
+            .collect();
+        assert_eq!(returns.len(), 1);
+        let check = function
+            .cfg
+            .blocks
+            .values()
+            .find(|b| {
+                matches!(b.terminator, IrTerminator::CondBranch { true_target, .. }
+                    if true_target == returns[0].id)
+            })
+            .expect("postcondition check");
+        assert!(check.instructions.iter().any(|i| matches!(
+            i,
+            IrInstruction::CallDirect { args, .. } if args == &[IrId(0), IrId(0)]
+        )));
+    }
+
+    #[test]
+    fn test_no_contracts_unchanged() {
+        let mut module = IrModule::new("empty".to_string(), "empty.hx".to_string());
+        let result = ContractCheckPass::new().run_on_module(&mut module);
+        assert!(!result.modified);
+        assert!(module.extern_functions.is_empty());
+    }
+}
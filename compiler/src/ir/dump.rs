@@ -3,9 +3,10 @@
 //! Pretty-prints MIR in a human-readable format similar to LLVM IR / Cranelift CLIF.
 //! Useful for debugging optimization passes.
 
+use super::loop_analysis::DominatorTree;
 use super::{
     BinaryOp, CompareOp, IrBasicBlock, IrBlockId, IrControlFlowGraph, IrFunction, IrId,
-    IrInstruction, IrModule, IrPhiNode, IrTerminator, IrType, IrValue,
+    IrInstruction, IrModule, IrPhiNode, IrTerminator, IrType, IrTypeDefinition, IrValue,
 };
 use std::fmt::Write;
 
@@ -72,6 +73,128 @@ pub fn dump_cfg(cfg: &IrControlFlowGraph) -> String {
     out
 }
 
+/// Instructions shown per block in [`dump_cfg_dot`]; the rest are counted.
+const DOT_MAX_INSTRUCTIONS: usize = 8;
+
+/// Dump a function's CFG as a Graphviz dot digraph.
+///
+/// Each block is a node listing its phis, first instructions and terminator.
+/// Edges carry the branch condition (`$c` / `!$c`) or switch cases, and loop
+/// back-edges (to a block dominating the source) are drawn bold red.
+pub fn dump_cfg_dot(func: &IrFunction) -> String {
+    let mut out = String::new();
+    writeln!(out, "digraph {} {{", dot_quote(&func.name)).unwrap();
+    writeln!(
+        out,
+        "    label={};",
+        dot_quote(&format!("fn @{}", func.name))
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    node [shape=box, fontname=\"Courier\", fontsize=10];"
+    )
+    .unwrap();
+
+    let mut block_ids: Vec<_> = func.cfg.blocks.keys().copied().collect();
+    block_ids.sort_by_key(|id| id.0);
+
+    for &block_id in &block_ids {
+        let block = &func.cfg.blocks[&block_id];
+        let mut lines = vec![match &block.label {
+            Some(label) => format!("{}: ; {}", block.id, label),
+            None => format!("{}:", block.id),
+        }];
+        lines.extend(block.phi_nodes.iter().map(dump_phi));
+        lines.extend(
+            block
+                .instructions
+                .iter()
+                .take(DOT_MAX_INSTRUCTIONS)
+                .map(dump_instruction),
+        );
+        if block.instructions.len() > DOT_MAX_INSTRUCTIONS {
+            lines.push(format!(
+                "... {} more",
+                block.instructions.len() - DOT_MAX_INSTRUCTIONS
+            ));
+        }
+        lines.push(dump_terminator(&block.terminator));
+
+        let label: String = lines
+            .iter()
+            .map(|line| format!("{}\\l", dot_escape(line)))
+            .collect();
+        let style = if block_id == func.cfg.entry_block {
+            ", penwidth=2"
+        } else {
+            ""
+        };
+        writeln!(out, "    {} [label=\"{}\"{}];", block.id, label, style).unwrap();
+    }
+
+    let domtree = DominatorTree::compute(func);
+    for &block_id in &block_ids {
+        let block = &func.cfg.blocks[&block_id];
+        let edges: Vec<(IrBlockId, String)> = match &block.terminator {
+            IrTerminator::Branch { target } => vec![(*target, String::new())],
+            IrTerminator::CondBranch {
+                condition,
+                true_target,
+                false_target,
+            } => vec![
+                (*true_target, format!("{}", condition)),
+                (*false_target, format!("!{}", condition)),
+            ],
+            IrTerminator::Switch { cases, default, .. } => {
+                // One edge per target, listing all of its case values
+                let mut edges: Vec<(IrBlockId, String)> = Vec::new();
+                for (value, target) in cases {
+                    match edges.iter_mut().find(|(t, _)| t == target) {
+                        Some((_, label)) => write!(label, ", {}", value).unwrap(),
+                        None => edges.push((*target, value.to_string())),
+                    }
+                }
+                edges.push((*default, "default".to_string()));
+                edges
+            }
+            IrTerminator::Return { .. }
+            | IrTerminator::Unreachable
+            | IrTerminator::NoReturn { .. } => Vec::new(),
+        };
+
+        for (target, label) in edges {
+            let mut attrs = Vec::new();
+            if !label.is_empty() {
+                attrs.push(format!("label={}", dot_quote(&label)));
+            }
+            if domtree.dominates(target, block_id) {
+                attrs.push("color=red, penwidth=2".to_string());
+            }
+            write!(out, "    {} -> {}", block_id, target).unwrap();
+            if !attrs.is_empty() {
+                write!(out, " [{}]", attrs.join(", ")).unwrap();
+            }
+            writeln!(out, ";").unwrap();
+        }
+    }
+
+    writeln!(out, "}}").unwrap();
+    out
+}
+
+/// Escape text for a double-quoted dot string
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// A dot ID in double quotes
+fn dot_quote(text: &str) -> String {
+    format!("\"{}\"", dot_escape(text))
+}
+
 /// Dump a basic block to a string.
 pub fn dump_block(block: &IrBasicBlock) -> String {
     let mut out = String::new();
@@ -584,3 +707,104 @@ pub fn dump_function_by_name(module: &IrModule, name: &str) -> Option<String> {
     }
     None
 }
+
+/// Dump the memory layout of each class in a module: size, alignment and
+/// field offsets. Flat classes (@:cstruct, @:struct) also show the size
+/// their fields would take in declaration order.
+pub fn dump_layouts(module: &IrModule) -> String {
+    let mut out = String::new();
+    let mut types: Vec<_> = module
+        .types
+        .values()
+        .filter_map(|t| match &t.definition {
+            IrTypeDefinition::Struct { fields, .. } => Some((t, fields)),
+            _ => None,
+        })
+        .collect();
+    types.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+    writeln!(out, "; Layouts: {} classes", types.len()).unwrap();
+
+    for (typedef, fields) in types {
+        writeln!(out).unwrap();
+        let mut placed: Vec<(u32, &str, &IrType)> = Vec::new();
+        match typedef.layout {
+            Some(layout) => {
+                write!(
+                    out,
+                    "{}: {} bytes, align {}",
+                    typedef.name, layout.size, layout.align
+                )
+                .unwrap();
+                if layout.declared_size != layout.size {
+                    write!(out, " ({} in declaration order)", layout.declared_size).unwrap();
+                }
+                writeln!(out).unwrap();
+                // The object header is not part of a flat class
+                for field in fields {
+                    if let Some(offset) = field.offset {
+                        placed.push((offset, &field.name, &field.ty));
+                    }
+                }
+            }
+            None => {
+                // Every field, the type id header included, is an 8-byte slot
+                writeln!(out, "{}: {} bytes, align 8", typedef.name, fields.len() * 8).unwrap();
+                for (i, field) in fields.iter().enumerate() {
+                    let offset = field.offset.unwrap_or(i as u32 * 8);
+                    placed.push((offset, &field.name, &field.ty));
+                }
+            }
+        }
+        placed.sort_by_key(|(offset, _, _)| *offset);
+        for (offset, name, ty) in placed {
+            writeln!(out, "  +{:<5} {}: {}", offset, name, dump_type(ty)).unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::builder::*;
+    use crate::tast::SymbolId;
+
+    #[test]
+    fn test_cfg_dot() {
+        // entry -> header <-> body, header -> exit
+        let mut builder = IrBuilder::new("test".to_string(), "test.hx".to_string());
+        let sig = FunctionSignatureBuilder::new()
+            .returns(IrType::Void)
+            .build();
+        builder.start_function(SymbolId::from_raw(1), "loop_test".to_string(), sig);
+
+        let header = builder.create_block().unwrap();
+        builder.build_branch(header);
+
+        builder.switch_to_block(header);
+        let cond = builder.build_bool(true).unwrap();
+        let body = builder.create_block().unwrap();
+        let exit = builder.create_block().unwrap();
+        builder.build_cond_branch(cond, body, exit);
+
+        builder.switch_to_block(body);
+        builder.build_branch(header);
+
+        builder.switch_to_block(exit);
+        builder.build_return(None);
+        builder.finish_function();
+
+        let function = builder.module.functions.values().next().unwrap();
+        let dot = dump_cfg_dot(function);
+        assert!(dot.starts_with("digraph \"loop_test\" {\n"));
+        assert!(dot.contains(&format!("    {} [label=\"{}:\\l", header, header)));
+        assert!(dot.contains(&format!("{} -> {};\n", IrBlockId::entry(), header)));
+        assert!(dot.contains(&format!("{} -> {} [label=\"{}\"];\n", header, body, cond)));
+        assert!(dot.contains(&format!("{} -> {} [label=\"!{}\"];\n", header, exit, cond)));
+        assert!(dot.contains(&format!(
+            "{} -> {} [color=red, penwidth=2];\n",
+            body, header
+        )));
+        assert!(dot.ends_with("}\n"));
+    }
+}
@@ -0,0 +1,309 @@
+//! Escape Summary — which parameters each function may let escape.
+//!
+//! `InsertFreePass` frees an allocation before the return of the function
+//! that made it, unless the pointer escapes. Handing the pointer to another
+//! function used to count as escaping, so every object passed to a
+//! constructor or a small helper (`p.init(...)`, `v.length()`) stayed on the
+//! heap for good.
+//!
+//! A summary records, per function and parameter, whether the callee may let
+//! the pointer outlive the call. A parameter is kept only when each use of it
+//! (and of pointers derived from it by GEP, `PtrAdd`, casts and copies) is a
+//! load through it, a store *to* it, a comparison, or an argument to a
+//! parameter that is itself kept. Returning it, storing it as a value,
+//! freeing it, capturing it or handing it to an extern or indirect call makes
+//! it escape.
+//!
+//! Summaries are computed bottom-up over the call graph as an optimistic
+//! fixpoint: every parameter starts out kept and is marked escaping until
+//! nothing changes, so recursive calls don't pessimize each other. Function
+//! IDs are global, so summaries built from all modules of a program
+//! ([`EscapeSummaries::for_modules`]) also cover calls across modules.
+
+use super::blocks::IrTerminator;
+use super::functions::IrFunctionId;
+use super::instructions::IrInstruction;
+use super::{IrFunction, IrId, IrModule};
+use std::collections::{HashMap, HashSet};
+
+/// Per-parameter escape summaries of functions with a body
+#[derive(Debug, Clone, Default)]
+pub struct EscapeSummaries {
+    /// Whether each parameter may escape, in signature order
+    escaping: HashMap<IrFunctionId, Vec<bool>>,
+}
+
+impl EscapeSummaries {
+    /// Summaries of the functions defined in `module`
+    pub fn for_module(module: &IrModule) -> Self {
+        Self::for_functions(module.functions.iter())
+    }
+
+    /// Summaries of the functions defined in all of `modules`, so calls
+    /// between modules are covered too
+    pub fn for_modules(modules: &[IrModule]) -> Self {
+        Self::for_functions(modules.iter().flat_map(|module| module.functions.iter()))
+    }
+
+    fn for_functions<'a>(
+        functions: impl Iterator<Item = (&'a IrFunctionId, &'a IrFunction)>,
+    ) -> Self {
+        // Declarations (externs, other modules' stubs) have no summary and
+        // let all their arguments escape
+        let functions: HashMap<IrFunctionId, &IrFunction> = functions
+            .filter(|(_, function)| !function.cfg.blocks.is_empty())
+            .map(|(&id, function)| (id, function))
+            .collect();
+        let mut summaries = Self {
+            escaping: functions
+                .iter()
+                .map(|(&id, function)| (id, vec![false; function.signature.parameters.len()]))
+                .collect(),
+        };
+
+        // Parameters only ever go from kept to escaping, so this terminates
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (id, function) in &functions {
+                let escaping: Vec<bool> = function
+                    .signature
+                    .parameters
+                    .iter()
+                    .map(|param| pointer_escapes(function, param.reg, &summaries))
+                    .collect();
+                if summaries.escaping[id] != escaping {
+                    summaries.escaping.insert(*id, escaping);
+                    changed = true;
+                }
+            }
+        }
+        summaries
+    }
+
+    /// Whether argument `index` of a call to `callee` with `arg_count`
+    /// arguments may escape the call
+    pub fn arg_escapes(&self, callee: IrFunctionId, arg_count: usize, index: usize) -> bool {
+        match self.escaping.get(&callee) {
+            Some(params) if params.len() == arg_count => params[index],
+            _ => true,
+        }
+    }
+}
+
+/// Whether `reg` may outlive a call of `function` (see the module docs)
+fn pointer_escapes(function: &IrFunction, reg: IrId, summaries: &EscapeSummaries) -> bool {
+    let derived = derived_pointers(function, reg);
+    let is_derived = |id: &IrId| derived.contains(id);
+
+    for block in function.cfg.blocks.values() {
+        if block
+            .phi_nodes
+            .iter()
+            .any(|phi| phi.incoming.iter().any(|(_, value)| is_derived(value)))
+        {
+            return true;
+        }
+
+        for inst in &block.instructions {
+            let escapes = match inst {
+                IrInstruction::Load { .. }
+                | IrInstruction::VectorLoad { .. }
+                | IrInstruction::Cmp { .. }
+                | IrInstruction::Copy { .. }
+                | IrInstruction::Move { .. }
+                | IrInstruction::Cast { .. }
+                | IrInstruction::BitCast { .. } => false,
+                IrInstruction::Store { value, .. } | IrInstruction::VectorStore { value, .. } => {
+                    is_derived(value)
+                }
+                IrInstruction::GetElementPtr { indices, .. } => indices.iter().any(is_derived),
+                IrInstruction::PtrAdd { offset, .. } => is_derived(offset),
+                IrInstruction::CallDirect {
+                    func_id,
+                    args,
+                    is_tail_call,
+                    ..
+                } => args.iter().enumerate().any(|(index, arg)| {
+                    is_derived(arg)
+                        && (*is_tail_call || summaries.arg_escapes(*func_id, args.len(), index))
+                }),
+                other => other.uses().iter().any(is_derived),
+            };
+            if escapes {
+                return true;
+            }
+        }
+
+        if let IrTerminator::Return { value: Some(value) } = &block.terminator {
+            if is_derived(value) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// `reg` and the pointers computed from it
+fn derived_pointers(function: &IrFunction, reg: IrId) -> HashSet<IrId> {
+    let mut derived = HashSet::from([reg]);
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for block in function.cfg.blocks.values() {
+            for inst in &block.instructions {
+                let (dest, src) = match inst {
+                    IrInstruction::GetElementPtr { dest, ptr, .. }
+                    | IrInstruction::PtrAdd { dest, ptr, .. } => (*dest, *ptr),
+                    IrInstruction::Cast { dest, src, .. }
+                    | IrInstruction::BitCast { dest, src, .. }
+                    | IrInstruction::Copy { dest, src }
+                    | IrInstruction::Move { dest, src } => (*dest, *src),
+                    _ => continue,
+                };
+                if derived.contains(&src) && derived.insert(dest) {
+                    changed = true;
+                }
+            }
+        }
+    }
+    derived
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::insert_free::InsertFreePass;
+    use crate::ir::mir_builder::MirBuilder;
+    use crate::ir::optimization::OptimizationPass;
+    use crate::ir::IrType;
+
+    struct Callees {
+        /// `init(this, v)`: stores `v` into `this`
+        init: IrFunctionId,
+        /// `stash(p, slot)`: stores `p` into `slot`
+        stash: IrFunctionId,
+        /// `identity(p)`: returns `p`
+        identity: IrFunctionId,
+        /// `walk(p, n)`: loads through `p` and calls itself
+        walk: IrFunctionId,
+    }
+
+    fn callees(builder: &mut MirBuilder) -> Callees {
+        let ptr = builder.ptr_type(IrType::I64);
+        let ptr_ptr = builder.ptr_type(ptr.clone());
+
+        let init = builder
+            .begin_function("init")
+            .param("this", ptr.clone())
+            .param("v", IrType::I64)
+            .build();
+        builder.set_current_function(init);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let (this, v) = (builder.get_param(0), builder.get_param(1));
+        builder.store(this, v);
+        builder.ret(None);
+
+        let stash = builder
+            .begin_function("stash")
+            .param("p", ptr.clone())
+            .param("slot", ptr_ptr)
+            .build();
+        builder.set_current_function(stash);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let (p, slot) = (builder.get_param(0), builder.get_param(1));
+        builder.store(slot, p);
+        builder.ret(None);
+
+        let identity = builder
+            .begin_function("identity")
+            .param("p", ptr.clone())
+            .returns(ptr.clone())
+            .build();
+        builder.set_current_function(identity);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let p = builder.get_param(0);
+        builder.ret(Some(p));
+
+        let walk = builder
+            .begin_function("walk")
+            .param("p", ptr)
+            .param("n", IrType::I64)
+            .build();
+        builder.set_current_function(walk);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let p = builder.get_param(0);
+        let n = builder.load(p, IrType::I64);
+        builder.call(walk, vec![p, n]);
+        builder.ret(None);
+
+        Callees {
+            init,
+            stash,
+            identity,
+            walk,
+        }
+    }
+
+    #[test]
+    fn test_parameter_summaries() {
+        let mut builder = MirBuilder::new("test");
+        let callees = callees(&mut builder);
+        let summaries = EscapeSummaries::for_module(&builder.finish());
+
+        assert!(!summaries.arg_escapes(callees.init, 2, 0));
+        assert!(summaries.arg_escapes(callees.stash, 2, 0));
+        // Used as the store target, not stored
+        assert!(!summaries.arg_escapes(callees.stash, 2, 1));
+        assert!(summaries.arg_escapes(callees.identity, 1, 0));
+        // Recursion alone doesn't make a parameter escape
+        assert!(!summaries.arg_escapes(callees.walk, 2, 0));
+        // Mismatched arity and unknown callees are conservative
+        assert!(summaries.arg_escapes(callees.init, 3, 0));
+        assert!(summaries.arg_escapes(IrFunctionId(u32::MAX), 1, 0));
+    }
+
+    #[test]
+    fn test_free_after_non_escaping_calls() {
+        let mut builder = MirBuilder::new("test");
+        let Callees { init, stash, .. } = callees(&mut builder);
+        let byte_ptr = builder.ptr_type(IrType::U8);
+        let malloc = builder
+            .begin_function("malloc")
+            .param("size", IrType::I64)
+            .returns(byte_ptr)
+            .build();
+        builder.mark_as_extern(malloc);
+
+        // `kept` only reaches `init` and is freed; `leaked` reaches `stash`
+        let main = builder.begin_function("main").build();
+        builder.set_current_function(main);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let size = builder.const_i64(8);
+        let kept = builder.call(malloc, vec![size]).unwrap();
+        let leaked = builder.call(malloc, vec![size]).unwrap();
+        let one = builder.const_i64(1);
+        builder.call(init, vec![kept, one]);
+        builder.call(init, vec![leaked, one]);
+        builder.call(stash, vec![leaked, kept]);
+        builder.ret(None);
+        let mut module = builder.finish();
+
+        InsertFreePass::new().run_on_module(&mut module);
+        let main = &module.functions[&main];
+        let freed: Vec<IrId> = main.cfg.blocks[&main.cfg.entry_block]
+            .instructions
+            .iter()
+            .filter_map(|inst| match inst {
+                IrInstruction::Free { ptr } => Some(*ptr),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(freed, vec![kept]);
+    }
+}
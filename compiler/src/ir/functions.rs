@@ -8,7 +8,7 @@ use super::{
 };
 use crate::tast::SymbolId;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 /// HIR function representation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -152,6 +152,11 @@ pub enum AllocationHint {
     Auto,
 }
 
+/// Function attribute (`attributes.custom`) set by `@:noThrow`, which effect
+/// analysis has verified: the function never throws, so backends may mark it
+/// as not unwinding
+pub const NO_THROW_ATTRIBUTE: &str = "noThrow";
+
 /// Function attributes and metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionAttributes {
@@ -306,6 +311,28 @@ impl IrFunction {
         true
     }
 
+    /// Functions this one calls directly or takes the address of
+    /// (`FunctionRef`, `MakeClosure`). Their addresses are fixed into the
+    /// function's machine code.
+    pub fn referenced_functions(&self) -> BTreeSet<IrFunctionId> {
+        use super::IrInstruction;
+
+        let mut referenced = BTreeSet::new();
+        for block in self.cfg.blocks.values() {
+            for inst in &block.instructions {
+                match inst {
+                    IrInstruction::CallDirect { func_id, .. }
+                    | IrInstruction::FunctionRef { func_id, .. }
+                    | IrInstruction::MakeClosure { func_id, .. } => {
+                        referenced.insert(*func_id);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        referenced
+    }
+
     /// Get all registers used in this function
     pub fn used_registers(&self) -> Vec<IrId> {
         let mut regs = Vec::new();
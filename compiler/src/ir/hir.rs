@@ -88,6 +88,7 @@ pub struct HirEnumVariant {
     pub name: InternedString,
     pub fields: Vec<HirEnumField>,
     pub discriminant: Option<i32>,
+    pub metadata: Vec<HirAttribute>,
 }
 
 /// Abstract type declaration
@@ -582,6 +583,9 @@ pub struct HirAttribute {
 pub enum HirAttributeArg {
     Literal(HirLiteral),
     Named(InternedString, HirLiteral),
+    Null,
+    Array(Vec<HirAttributeArg>),
+    Object(Vec<(InternedString, HirAttributeArg)>),
 }
 
 /// Additional HIR types for completeness
@@ -613,6 +617,7 @@ pub struct HirClassField {
     pub is_static: bool,
     pub is_final: bool,
     pub property_access: Option<crate::tast::PropertyAccessInfo>, // Property accessor info from TAST
+    pub metadata: Vec<HirAttribute>,
 }
 
 #[derive(Debug, Clone)]
@@ -622,6 +627,8 @@ pub struct HirMethod {
     pub is_static: bool,
     pub is_override: bool,
     pub is_abstract: bool,
+    /// User metadata (compiler hints stay on `function.metadata`)
+    pub metadata: Vec<HirAttribute>,
 }
 
 #[derive(Debug, Clone)]
@@ -730,6 +737,12 @@ pub enum HirOptimizationHint {
     PureFunction(SymbolId),
     HotPath(Vec<SymbolId>),
     ColdPath(Vec<SymbolId>),
+    /// `@:likely` (true) / `@:unlikely` (false) on the condition at this
+    /// location
+    BranchHint(SourceLocation, bool),
+    /// `@:unroll(N)` (`None` without an argument) on the loop whose
+    /// condition is at this location
+    LoopUnroll(SourceLocation, Option<u32>),
 }
 
 impl HirExpr {
@@ -12,12 +12,14 @@
 
 use crate::ir::drop_analysis::{DropBehavior, DropPointAnalyzer, DropPoints};
 use crate::ir::hir::*;
+use crate::ir::module_init;
 use crate::ir::{
     BinaryOp, CallingConvention, CompareOp, EnvironmentLayout, FunctionKind,
     FunctionSignatureBuilder, IrBasicBlock, IrBlockId, IrBuilder, IrEnumVariant, IrField,
-    IrFunction, IrFunctionId, IrFunctionSignature, IrGlobal, IrGlobalId, IrId, IrInstruction,
-    IrLocal, IrModule, IrParameter, IrPhiNode, IrSourceLocation, IrTerminator, IrType, IrTypeDef,
-    IrTypeDefId, IrTypeDefinition, IrValue, Linkage, UnaryOp,
+    IrFlatLayout, IrFunction, IrFunctionId, IrFunctionSignature, IrGlobal, IrGlobalId, IrId,
+    IrInstruction, IrLocal, IrMetaValue, IrMetadata, IrModule, IrParameter, IrPhiNode,
+    IrSourceLocation, IrTerminator, IrType, IrTypeDef, IrTypeDefId, IrTypeDefinition,
+    IrTypeMetadata, IrValue, Linkage, UnaryOp,
 };
 use crate::stdlib::{MethodSignature, StdlibMapping};
 use crate::tast::{
@@ -25,11 +27,15 @@ use crate::tast::{
     TypeTable,
 };
 use log::{debug, trace, warn};
+use rayzor_plugin::layout::{packed_order, FieldKind, LayoutBuilder, LayoutRules, StructLayout};
 use std::cell::RefCell;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::rc::Rc;
 
-/// Layout information for a single field in a @:cstruct class
+/// Alignment guaranteed by libc malloc on 64-bit targets (`max_align_t`).
+const MALLOC_ALIGNMENT: u64 = 16;
+
+/// Layout information for a single field in a @:cstruct or @:struct class
 #[derive(Debug, Clone)]
 struct CStructFieldLayout {
     symbol_id: SymbolId,
@@ -37,14 +43,33 @@ struct CStructFieldLayout {
     byte_offset: u32,
     ir_type: IrType,
     c_type: String, // "double", "long", "int", "void*", "Vec2", etc.
+    /// Size of a nested struct stored inline at `byte_offset`; such a field
+    /// is accessed through its address rather than loaded
+    embedded_size: Option<u32>,
+    /// Element count of an inline `@:fixed(N)` array; `ir_type` is then the
+    /// element type and `embedded_size` the whole array
+    fixed_len: Option<u32>,
+}
+
+impl CStructFieldLayout {
+    /// C declarator for the cdef typedef, e.g. `double x` or `double data[16]`
+    fn c_decl(&self) -> String {
+        match self.fixed_len {
+            Some(n) => format!("{} {}[{}]", self.c_type, self.name, n),
+            None => format!("{} {}", self.c_type, self.name),
+        }
+    }
 }
 
-/// Precomputed C-compatible layout for a @:cstruct class
+/// Precomputed C-compatible layout for a @:cstruct or @:struct class
 #[derive(Debug, Clone)]
 struct CStructLayout {
+    /// In placement order (byte offset)
     fields: Vec<CStructFieldLayout>,
     total_size: u32,
     alignment: u32,
+    /// `total_size` with the fields in declaration order
+    declared_size: u32,
     c_name: String,
     /// All dependency typedefs (for nested cstructs), in topological order
     dep_cdefs: Vec<String>,
@@ -52,6 +77,8 @@ struct CStructLayout {
     own_cdef: String,
     /// Full cdef: deps + own typedef
     cdef_string: String,
+    /// Shared descriptor (C rules) returned by `layout()`
+    descriptor: StructLayout,
 }
 
 /// Layout information for a single field in a @:gpuStruct class
@@ -79,6 +106,8 @@ struct GpuStructLayout {
     msl_typedef: String,
     /// Dependency typedefs for nested @:gpuStruct (in topological order)
     dep_typedefs: Vec<String>,
+    /// Shared descriptor (GPU rules) returned by `layout()`
+    descriptor: StructLayout,
 }
 
 /// Context for lowering HIR to MIR
@@ -102,6 +131,10 @@ pub struct HirToMirContext<'a> {
     /// Used for static class fields and module-level variables
     global_symbol_map: BTreeMap<SymbolId, IrGlobalId>,
 
+    /// Function-local `static var`s: runtime slot key and declared type.
+    /// The key is baked into the MIR so tier re-compilation reuses the slot.
+    static_locals: BTreeMap<SymbolId, (i64, TypeId)>,
+
     /// External function map from previously compiled modules (e.g., stdlib)
     /// These are functions defined in other modules that can be called from this module
     external_function_map: BTreeMap<SymbolId, crate::ir::IrFunctionId>,
@@ -126,12 +159,22 @@ pub struct HirToMirContext<'a> {
     /// These are queried from DFG during HIR lowering and passed to MIR
     ssa_hints: SsaOptimizationHints,
 
+    /// `@:likely` (true) / `@:unlikely` (false) hints by condition location
+    branch_hints: HashMap<SourceLocation, bool>,
+
+    /// `@:unroll(N)` factors by loop condition location
+    unroll_hints: HashMap<SourceLocation, Option<u32>>,
+
     /// Counter for generating unique lambda names
     lambda_counter: u32,
 
     /// Dynamic global initializers (globals needing runtime initialization)
     dynamic_globals: Vec<(SymbolId, HirExpr)>,
 
+    /// Static `__init__` methods of the module's classes, in declaration
+    /// order; the module initializer runs them first
+    class_initializers: Vec<IrFunctionId>,
+
     /// String interner for resolving InternedString to actual strings
     string_interner: &'a StringInterner,
 
@@ -309,6 +352,9 @@ pub struct HirToMirContext<'a> {
     /// base method SymbolId → (slot_index, defining_class) — checked at call sites
     virtual_dispatch_info: BTreeMap<SymbolId, (u32, SymbolId)>,
 
+    /// instance method SymbolId → trampoline used for bound-method closures (`obj.method`)
+    bound_method_trampolines: BTreeMap<SymbolId, IrFunctionId>,
+
     /// Parameter default expressions for functions/constructors.
     /// Keyed by IrFunctionId, value is Vec matching user-visible params (excludes implicit 'this').
     /// Only populated for functions that have at least one parameter with a default value.
@@ -327,6 +373,15 @@ pub struct HirToMirContext<'a> {
     /// a different runtime representation (class pointer or wider anonymous object).
     /// Field access is redirected at compile time; materialization only at escape points.
     anon_views: BTreeMap<SymbolId, AnonBacking>,
+
+    /// Emit debug-mode runtime checks (iterator invalidation in Array for-in)
+    debug_checks: bool,
+
+    /// Report instance field reads/writes to ThreadSanitizer
+    sanitize_thread: bool,
+
+    /// Emit each `trace()` call's position for the runtime to print
+    trace_positions: bool,
 }
 
 /// Tracks the backing representation of an anonymous-typed variable.
@@ -384,6 +439,17 @@ struct SsaOptimizationHints {
     cse_opportunities: BTreeSet<SymbolId>,
 }
 
+/// How a catch clause decides whether it handles the current exception
+#[derive(Debug)]
+enum CatchTest {
+    /// `Dynamic` and `Any` catch everything
+    All,
+    /// The exception's runtime type_id is one of these
+    TypeIds(Vec<u32>),
+    /// The exception is an instance of this class or of a subclass
+    Instance(SymbolId),
+}
+
 #[derive(Debug)]
 struct LoopContext {
     continue_block: IrBlockId,
@@ -453,6 +519,7 @@ impl<'a> HirToMirContext<'a> {
             symbol_type_ids: BTreeMap::new(),
             function_map: BTreeMap::new(),
             global_symbol_map: BTreeMap::new(),
+            static_locals: BTreeMap::new(),
             external_function_map: BTreeMap::new(),
             external_function_name_map: BTreeMap::new(),
             block_map: BTreeMap::new(),
@@ -460,8 +527,11 @@ impl<'a> HirToMirContext<'a> {
             current_module: Some(module_name),
             errors: Vec::new(),
             ssa_hints: SsaOptimizationHints::default(),
+            branch_hints: HashMap::new(),
+            unroll_hints: HashMap::new(),
             lambda_counter: 0,
             dynamic_globals: Vec::new(),
+            class_initializers: Vec::new(),
             string_interner,
             type_table,
             closure_environments: BTreeMap::new(),
@@ -509,10 +579,14 @@ impl<'a> HirToMirContext<'a> {
             class_virtual_slots: BTreeMap::new(),
             class_vtables: BTreeMap::new(),
             virtual_dispatch_info: BTreeMap::new(),
+            bound_method_trampolines: BTreeMap::new(),
             function_param_defaults: BTreeMap::new(),
             function_param_hir_types: BTreeMap::new(),
             current_function_return_type: None,
             anon_views: BTreeMap::new(),
+            debug_checks: false,
+            sanitize_thread: false,
+            trace_positions: false,
         };
 
         // Pre-declare malloc so it's available for heap allocations during lowering
@@ -890,7 +964,28 @@ impl<'a> HirToMirContext<'a> {
 
     /// Build a heap allocation (via malloc) for class instances
     /// This is used for class instances that may escape the current function
-    fn build_heap_alloc(&mut self, size: u64) -> Option<IrId> {
+    ///
+    /// `align` is the natural alignment of the allocated type. libc malloc
+    /// already guarantees `MALLOC_ALIGNMENT`; anything stricter (SIMD fields,
+    /// GPU-shared structs) goes through `rayzor_malloc_aligned`.
+    fn build_heap_alloc(&mut self, size: u64, align: u64) -> Option<IrId> {
+        let ptr_u8_ty = IrType::Ptr(Box::new(IrType::U8));
+
+        if align > MALLOC_ALIGNMENT {
+            let aligned_alloc = self.get_or_register_extern_function(
+                "rayzor_malloc_aligned",
+                vec![IrType::U64, IrType::U64],
+                ptr_u8_ty.clone(),
+            );
+            let size_reg = self.builder.build_const(IrValue::U64(size))?;
+            let align_reg = self.builder.build_const(IrValue::U64(align))?;
+            return self.builder.build_call_direct(
+                aligned_alloc,
+                vec![size_reg, align_reg],
+                ptr_u8_ty,
+            );
+        }
+
         // Look up malloc in extern_functions (where declare_malloc adds it)
         let malloc_id = self
             .builder
@@ -908,7 +1003,6 @@ impl<'a> HirToMirContext<'a> {
         let size_reg = self.builder.build_const(IrValue::U64(size))?;
 
         // Call malloc - returns Ptr(U8)
-        let ptr_u8_ty = IrType::Ptr(Box::new(IrType::U8));
         self.builder
             .build_call_direct(malloc_id, vec![size_reg], ptr_u8_ty)
     }
@@ -926,6 +1020,25 @@ impl<'a> HirToMirContext<'a> {
         false
     }
 
+    /// Check if a class symbol has the @:struct (value type) flag
+    fn is_value_struct_class(&self, type_id: TypeId) -> bool {
+        let type_table = self.type_table.borrow();
+        if let Some(type_info) = type_table.get(type_id) {
+            if let Some(symbol_id) = type_info.symbol_id() {
+                if let Some(sym) = self.symbol_table.get_symbol(symbol_id) {
+                    return sym.flags.is_value_struct();
+                }
+            }
+        }
+        false
+    }
+
+    /// Check if a class is laid out without an object header, with its
+    /// fields at C-compatible byte offsets (@:cstruct and @:struct classes)
+    fn has_flat_layout(&self, type_id: TypeId) -> bool {
+        self.is_cstruct_class(type_id) || self.is_value_struct_class(type_id)
+    }
+
     /// Get or compute the CStruct layout for a class type
     fn get_or_compute_cstruct_layout(&mut self, type_id: TypeId) -> Option<CStructLayout> {
         if let Some(layout) = self.cstruct_layouts.get(&type_id) {
@@ -996,12 +1109,10 @@ impl<'a> HirToMirContext<'a> {
             class_name.replace('.', "_")
         };
 
-        let mut byte_offset: u32 = 0;
-        let mut max_align: u32 = 1;
-        let mut layout_fields = Vec::new();
-
         let mut dep_cdefs: Vec<String> = Vec::new();
 
+        // (field, size, align) in declaration order
+        let mut declared = Vec::new();
         for (field_sym_id, _idx) in &fields_with_index {
             let sym = self.symbol_table.get_symbol(*field_sym_id)?;
             let field_name = self.string_interner.get(sym.name)?.to_string();
@@ -1009,37 +1120,68 @@ impl<'a> HirToMirContext<'a> {
 
             // Resolve field type to determine C type mapping.
             // Check the original Haxe type (before IR conversion) for rich type info.
-            let (size, align, c_type, ir_type) =
-                self.resolve_cstruct_field_type(sym.type_id, ir_type, &mut dep_cdefs);
-
-            // Align offset
-            if align > 0 {
-                byte_offset = (byte_offset + align - 1) & !(align - 1);
-            }
-            if align > max_align {
-                max_align = align;
-            }
+            let fixed_len = self.fixed_array_len(*field_sym_id);
+            let (size, align, c_type, ir_type) = match fixed_len {
+                Some(n) => {
+                    let (elem_size, c_type, elem_ty) = self.fixed_array_element(sym.type_id);
+                    (elem_size * n, elem_size, c_type, elem_ty)
+                }
+                None => self.resolve_cstruct_field_type(sym.type_id, ir_type, &mut dep_cdefs),
+            };
+            let embedded_size =
+                (fixed_len.is_some() || self.has_flat_layout(sym.type_id)).then_some(size);
 
-            layout_fields.push(CStructFieldLayout {
+            let field = CStructFieldLayout {
                 symbol_id: *field_sym_id,
                 name: field_name,
-                byte_offset,
+                byte_offset: 0,
                 ir_type,
                 c_type,
-            });
-
-            byte_offset += size;
-        }
-
-        // Pad total size to alignment
-        if max_align > 0 {
-            byte_offset = (byte_offset + max_align - 1) & !(max_align - 1);
-        }
+                embedded_size,
+                fixed_len,
+            };
+            declared.push((field, size, align));
+        }
+
+        // @:struct fields are packed by alignment; @:cstruct and @:keepLayout
+        // classes are seen by C code, which expects declaration order
+        let place = |order: &[usize]| {
+            let mut placement = LayoutBuilder::new(c_name.clone(), LayoutRules::C);
+            let mut fields = Vec::new();
+            for &i in order {
+                let (field, size, align) = &declared[i];
+                let byte_offset = placement.push(
+                    field.name.clone(),
+                    FieldKind::from_c_type(&field.c_type),
+                    *size,
+                    *align,
+                    field.fixed_len.unwrap_or(1),
+                );
+                fields.push(CStructFieldLayout {
+                    byte_offset,
+                    ..field.clone()
+                });
+            }
+            (fields, placement.finish())
+        };
+        let in_order: Vec<usize> = (0..declared.len()).collect();
+        let (declared_fields, declared_descriptor) = place(&in_order);
+        let flags = self
+            .symbol_table
+            .get_symbol(symbol_id)
+            .map(|sym| sym.flags)
+            .unwrap_or_default();
+        let (layout_fields, descriptor) = if flags.is_value_struct() && !flags.is_keep_layout() {
+            let aligns: Vec<u32> = declared.iter().map(|(_, _, align)| *align).collect();
+            place(&packed_order(&aligns))
+        } else {
+            (declared_fields, declared_descriptor.clone())
+        };
 
         // Build cdef string — own typedef only
         let mut own_cdef = format!("typedef struct {{ ");
         for f in &layout_fields {
-            own_cdef.push_str(&format!("{} {}; ", f.c_type, f.name));
+            own_cdef.push_str(&format!("{}; ", f.c_decl()));
         }
         own_cdef.push_str(&format!("}} {};\n", c_name));
 
@@ -1052,18 +1194,192 @@ impl<'a> HirToMirContext<'a> {
 
         let layout = CStructLayout {
             fields: layout_fields,
-            total_size: byte_offset,
-            alignment: max_align,
+            total_size: descriptor.size,
+            alignment: descriptor.align,
+            declared_size: declared_descriptor.size,
             c_name,
             dep_cdefs,
             own_cdef,
             cdef_string: full_cdef,
+            descriptor,
         };
 
         self.cstruct_layouts.insert(type_id, layout.clone());
         Some(layout)
     }
 
+    /// Copy the placement of each @:cstruct / @:struct class of the module
+    /// into its type definition (`rayzor dump --layouts`)
+    fn record_flat_layouts(&mut self, hir_module: &HirModule) {
+        for (&type_id, decl) in &hir_module.types {
+            if !matches!(decl, HirTypeDecl::Class(_)) || !self.has_flat_layout(type_id) {
+                continue;
+            }
+            let Some(layout) = self.get_or_compute_cstruct_layout(type_id) else {
+                continue;
+            };
+            let Some(typedef) = self
+                .builder
+                .module
+                .types
+                .values_mut()
+                .find(|t| t.type_id == type_id)
+            else {
+                continue;
+            };
+            if let IrTypeDefinition::Struct { fields, .. } = &mut typedef.definition {
+                for field in fields.iter_mut() {
+                    field.offset = layout
+                        .fields
+                        .iter()
+                        .find(|f| f.name == field.name)
+                        .map(|f| f.byte_offset);
+                }
+            }
+            typedef.layout = Some(IrFlatLayout {
+                size: layout.total_size,
+                align: layout.alignment,
+                declared_size: layout.declared_size,
+            });
+        }
+    }
+
+    /// Encoded shared layout descriptor returned by the synthetic `layout()`:
+    /// GPU rules for @:gpuStruct classes, C rules for @:cstruct and @:struct
+    fn struct_layout_descriptor(&mut self, class_symbol: SymbolId) -> Option<String> {
+        let flags = self.symbol_table.get_symbol(class_symbol)?.flags;
+        if !(flags.is_gpu_struct() || flags.is_cstruct() || flags.is_value_struct()) {
+            return None;
+        }
+        let type_id = {
+            let type_table = self.type_table.borrow();
+            type_table.iter().find_map(|(_, t)| match &t.kind {
+                TypeKind::Class { symbol_id, .. } if *symbol_id == class_symbol => Some(t.id),
+                _ => None,
+            })?
+        };
+        if flags.is_gpu_struct() {
+            self.get_or_compute_gpu_struct_layout(type_id)
+                .map(|l| l.descriptor.encode())
+        } else {
+            self.get_or_compute_cstruct_layout(type_id)
+                .map(|l| l.descriptor.encode())
+        }
+    }
+
+    /// Element count from a field's `@:fixed(N)` metadata (validated during
+    /// AST lowering)
+    fn fixed_array_len(&self, field: SymbolId) -> Option<u32> {
+        let class_field = self
+            .current_hir_types
+            .values()
+            .find_map(|decl| match decl {
+                HirTypeDecl::Class(c) => c.fields.iter().find(|f| f.symbol_id == field),
+                _ => None,
+            })?;
+        let attr = class_field
+            .metadata
+            .iter()
+            .find(|a| self.string_interner.get(a.name) == Some("fixed"))?;
+        match attr.args.first() {
+            Some(HirAttributeArg::Literal(HirLiteral::Int(n))) => u32::try_from(*n).ok(),
+            _ => None,
+        }
+    }
+
+    /// Element (size_bytes, c_type, ir_type) of a `@:fixed(N)` Array field.
+    /// Int elements are C `int`, unlike scalar Int fields which widen to `long`.
+    fn fixed_array_element(&self, array_ty: TypeId) -> (u32, String, IrType) {
+        let elem_kind = {
+            let type_table = self.type_table.borrow();
+            match type_table.get(array_ty).map(|t| &t.kind) {
+                Some(TypeKind::Array { element_type }) => {
+                    type_table.get(*element_type).map(|t| t.kind.clone())
+                }
+                _ => None,
+            }
+        };
+        match elem_kind {
+            Some(TypeKind::Int) => (4, "int".to_string(), IrType::I32),
+            _ => (8, "double".to_string(), IrType::F64),
+        }
+    }
+
+    /// Layout of the `@:fixed(N)` array field that `expr` reads, if any
+    fn fixed_array_field(&mut self, expr: &HirExpr) -> Option<CStructFieldLayout> {
+        match &expr.kind {
+            HirExprKind::Field { object, field } => self.fixed_array_field_of(object, *field),
+            _ => None,
+        }
+    }
+
+    /// Layout of `object.field` if it is a `@:fixed(N)` array field
+    fn fixed_array_field_of(
+        &mut self,
+        object: &HirExpr,
+        field: SymbolId,
+    ) -> Option<CStructFieldLayout> {
+        if !self.has_flat_layout(object.ty) {
+            return None;
+        }
+        let layout = self.get_or_compute_cstruct_layout(object.ty)?;
+        let field_name = self
+            .symbol_table
+            .get_symbol(field)
+            .and_then(|s| self.string_interner.get(s.name));
+        layout
+            .fields
+            .into_iter()
+            .find(|f| f.symbol_id == field || Some(f.name.as_str()) == field_name)
+            .filter(|f| f.fixed_len.is_some())
+    }
+
+    /// Address of element `index` of the `@:fixed(N)` array `array`.
+    /// Constant indices are bounds-checked here; runtime indices are not.
+    fn lower_fixed_array_elem_ptr(
+        &mut self,
+        array: &HirExpr,
+        index: &HirExpr,
+        layout: &CStructFieldLayout,
+    ) -> Option<IrId> {
+        let len = layout.fixed_len?;
+        if let HirExprKind::Literal(HirLiteral::Int(i)) = &index.kind {
+            if *i < 0 || *i >= len as i64 {
+                self.add_error(
+                    &format!(
+                        "Index {} is out of bounds for @:fixed({}) field '{}'",
+                        i, len, layout.name
+                    ),
+                    index.source_location,
+                );
+                return None;
+            }
+        }
+        let base = self.lower_expression(array)?;
+        let idx = self.lower_expression(index)?;
+        let idx = match self.builder.get_register_type(idx) {
+            Some(IrType::I64) => idx,
+            Some(from) => self.builder.build_cast(idx, from, IrType::I64)?,
+            None => idx,
+        };
+        let elem_ptr_ty = IrType::Ptr(Box::new(layout.ir_type.clone()));
+        self.builder.build_ptr_add(base, idx, elem_ptr_ty)
+    }
+
+    /// Load element `index` of the `@:fixed(N)` array `array`
+    fn lower_fixed_array_read(
+        &mut self,
+        array: &HirExpr,
+        index: &HirExpr,
+        layout: &CStructFieldLayout,
+    ) -> Option<IrId> {
+        let elem_ptr = self.lower_fixed_array_elem_ptr(array, index, layout)?;
+        let value = self.builder.build_load(elem_ptr, layout.ir_type.clone())?;
+        self.builder
+            .set_register_type(value, layout.ir_type.clone());
+        Some(value)
+    }
+
     /// Resolve a field's Haxe type to C type info for @:cstruct layout.
     ///
     /// Returns (size_bytes, alignment, c_type_string, ir_type).
@@ -1155,7 +1471,7 @@ impl<'a> HirToMirContext<'a> {
                     let is_nested_cstruct = self
                         .symbol_table
                         .get_symbol(*symbol_id)
-                        .map(|s| s.flags.is_cstruct())
+                        .map(|s| s.flags.is_cstruct() || s.flags.is_value_struct())
                         .unwrap_or(false);
                     if is_nested_cstruct {
                         // Recursively compute nested layout
@@ -1174,7 +1490,7 @@ impl<'a> HirToMirContext<'a> {
                                 let own_cdef = {
                                     let mut s = format!("typedef struct {{ ");
                                     for f in &nested_layout.fields {
-                                        s.push_str(&format!("{} {}; ", f.c_type, f.name));
+                                        s.push_str(&format!("{}; ", f.c_decl()));
                                     }
                                     s.push_str(&format!("}} {};\n", nested_layout.c_name));
                                     s
@@ -1254,7 +1570,7 @@ impl<'a> HirToMirContext<'a> {
                     let own_cdef = {
                         let mut s = format!("typedef struct {{ ");
                         for f in &layout.fields {
-                            s.push_str(&format!("{} {}; ", f.c_type, f.name));
+                            s.push_str(&format!("{}; ", f.c_decl()));
                         }
                         s.push_str(&format!("}} {};\n", layout.c_name));
                         s
@@ -1344,8 +1660,7 @@ impl<'a> HirToMirContext<'a> {
             .unwrap_or(&class_name)
             .to_string();
 
-        let mut byte_offset: u32 = 0;
-        let mut max_align: u32 = 1;
+        let mut placement = LayoutBuilder::new(msl_name.clone(), LayoutRules::Gpu);
         let mut layout_fields = Vec::new();
         let mut dep_typedefs: Vec<String> = Vec::new();
 
@@ -1357,13 +1672,13 @@ impl<'a> HirToMirContext<'a> {
             let (size, align, msl_type, gpu_ir_type) =
                 self.resolve_gpu_struct_field_type(sym.type_id, ir_type, &mut dep_typedefs);
 
-            // Align offset
-            if align > 0 {
-                byte_offset = (byte_offset + align - 1) & !(align - 1);
-            }
-            if align > max_align {
-                max_align = align;
-            }
+            let byte_offset = placement.push(
+                field_name.clone(),
+                FieldKind::from_c_type(&msl_type),
+                size,
+                align,
+                1,
+            );
 
             layout_fields.push(GpuStructFieldLayout {
                 symbol_id: *field_sym_id,
@@ -1373,14 +1688,9 @@ impl<'a> HirToMirContext<'a> {
                 msl_type,
                 size,
             });
-
-            byte_offset += size;
         }
 
-        // Pad total size to alignment
-        if max_align > 0 {
-            byte_offset = (byte_offset + max_align - 1) & !(max_align - 1);
-        }
+        let descriptor = placement.finish();
 
         // Build MSL typedef string
         let mut msl_typedef = format!("struct {} {{ ", msl_name);
@@ -1391,11 +1701,12 @@ impl<'a> HirToMirContext<'a> {
 
         let layout = GpuStructLayout {
             fields: layout_fields,
-            total_size: byte_offset,
-            alignment: max_align,
+            total_size: descriptor.size,
+            alignment: descriptor.align,
             msl_name,
             msl_typedef,
             dep_typedefs,
+            descriptor,
         };
 
         self.gpu_struct_layouts.insert(type_id, layout.clone());
@@ -1520,6 +1831,17 @@ impl<'a> HirToMirContext<'a> {
         // Extract SSA optimization hints from HIR metadata
         // These were populated during HIR lowering by querying DFG/SSA
         self.extract_ssa_hints_from_hir(hir_module);
+        for hint in &hir_module.metadata.optimization_hints {
+            match hint {
+                HirOptimizationHint::BranchHint(location, likely) => {
+                    self.branch_hints.insert(*location, *likely);
+                }
+                HirOptimizationHint::LoopUnroll(location, factor) => {
+                    self.unroll_hints.insert(*location, *factor);
+                }
+                _ => {}
+            }
+        }
 
         // Set module metadata
         self.builder.module.metadata.language_version =
@@ -1576,6 +1898,7 @@ impl<'a> HirToMirContext<'a> {
 
         // Build class vtables after all type metadata is registered
         self.build_class_vtables();
+        self.record_flat_layouts(hir_module);
 
         // CRITICAL: Two-pass lowering to avoid non-deterministic function ordering issues
         // HashMap iteration over hir_module.types is random, so class methods might be
@@ -1588,10 +1911,10 @@ impl<'a> HirToMirContext<'a> {
         for (type_id, type_decl) in &hir_module.types {
             match type_decl {
                 HirTypeDecl::Class(class) => {
-                    // eprintln!(
-                    //     "DEBUG Pass1a: Registering methods for class {:?}",
-                    //     self.string_interner.get(class.name).unwrap_or("<unknown>")
-                    // );
+                    trace!(
+                        "Pass1a: Registering methods for class {:?}",
+                        self.string_interner.get(class.name).unwrap_or("<unknown>")
+                    );
                     for method in &class.methods {
                         let this_type = if !method.is_static {
                             Some(*type_id)
@@ -1605,6 +1928,7 @@ impl<'a> HirToMirContext<'a> {
                             this_type,
                             &class.type_params,
                         );
+                        self.register_class_initializer(class, method);
                     }
 
                     // Register constructor signature with class type params
@@ -1761,6 +2085,11 @@ impl<'a> HirToMirContext<'a> {
                                             continue;
                                         }
                                     }
+                                    if method_name == "layout"
+                                        && self.struct_layout_descriptor(class.symbol_id).is_some()
+                                    {
+                                        continue;
+                                    }
                                     if matches!(method_name, "gpuDef" | "gpuSize" | "gpuAlignment")
                                     {
                                         let is_gpu_struct = self
@@ -1893,21 +2222,23 @@ impl<'a> HirToMirContext<'a> {
         }
 
         // Generate __vtable_init__ function for class virtual dispatch tables
-        if !self.class_vtables.is_empty() {
+        // and toString() registration
+        if !self.class_vtables.is_empty() || !self.class_tostring_methods().is_empty() {
             self.generate_vtable_init_function();
         }
 
-        // Generate __init__ function for dynamic global initialization
-        if !self.dynamic_globals.is_empty() {
+        // Generate __init__ function for dynamic global initialization and
+        // class `__init__` methods
+        if !self.dynamic_globals.is_empty() || !self.class_initializers.is_empty() {
             self.generate_module_init_function();
         }
 
         if self.errors.is_empty() {
-            // eprintln!(
-            //     "  ℹ️  Returning MIR module with {} functions, {} extern_functions",
-            //     self.builder.module.functions.len(),
-            //     self.builder.module.extern_functions.len()
-            // );
+            trace!(
+                "  ℹ️  Returning MIR module with {} functions, {} extern_functions",
+                self.builder.module.functions.len(),
+                self.builder.module.extern_functions.len()
+            );
 
             Ok(std::mem::replace(
                 &mut self.builder.module,
@@ -1977,6 +2308,7 @@ impl<'a> HirToMirContext<'a> {
                 func.attributes.inline = super::InlineHint::Always;
             }
         }
+        self.apply_function_hints(func_id, hir_func);
 
         self.builder.finish_function(); // Close to allow next function to start
     }
@@ -2055,6 +2387,7 @@ impl<'a> HirToMirContext<'a> {
                 func.attributes.inline = super::InlineHint::Always;
             }
         }
+        self.apply_function_hints(func_id, hir_func);
 
         self.builder.finish_function();
     }
@@ -2300,6 +2633,7 @@ impl<'a> HirToMirContext<'a> {
             // Enter function-level scope for variables declared at function level
             // This ensures they're freed on function exit via cleanup_all_scopes()
             self.enter_drop_scope();
+            self.copy_value_struct_params(&hir_func.params);
             self.lower_block(body);
             // Note: cleanup_all_scopes() is called in Return handling
             // For functions that don't explicitly return, ensure_terminator adds a return
@@ -2430,7 +2764,12 @@ impl<'a> HirToMirContext<'a> {
                     }
 
                     // Fill in default values for any missing optional parameters
-                    self.fill_default_args(parent_ctor_id, &mut arg_regs, true);
+                    self.fill_default_args(
+                        parent_ctor_id,
+                        &mut arg_regs,
+                        true,
+                        SourceLocation::unknown(),
+                    );
 
                     // Call parent constructor (returns void)
                     self.builder.build_call_direct(
@@ -2528,6 +2867,7 @@ impl<'a> HirToMirContext<'a> {
                 func.attributes.inline = super::InlineHint::Always;
             }
         }
+        self.apply_function_hints(func_id, hir_func);
 
         if self.ssa_hints.straight_line_functions.contains(&symbol_id) {
             // Mark for optimization (no branches, from CFG analysis)
@@ -2597,14 +2937,18 @@ impl<'a> HirToMirContext<'a> {
             // debug!("Lowering function body for {} (symbol {:?})", hir_func.name, symbol_id);
             let stmt_count = body.statements.len();
             let has_expr = body.expr.is_some();
-            // eprintln!("  Body has {} statements, trailing expr: {}", stmt_count, has_expr);
+            trace!(
+                "  Body has {} statements, trailing expr: {}",
+                stmt_count,
+                has_expr
+            );
 
             self.lower_block(body);
-            // eprintln!("  After lower_block");
+            trace!("  After lower_block");
 
             // Add implicit return if needed
             self.ensure_terminator();
-            // eprintln!("  After ensure_terminator");
+            trace!("  After ensure_terminator");
         } else {
             // debug!("Function {} has no body", hir_func.name);
         }
@@ -2645,10 +2989,11 @@ impl<'a> HirToMirContext<'a> {
             .get(hir_func.name)
             .map(|s| s.to_string())
             .unwrap_or_else(|| format!("func_{}", symbol_id.as_raw()));
-        // eprintln!(
-        //     "DEBUG ===== STARTING FUNCTION: {} (symbol {:?}) =====",
-        //     func_name, symbol_id
-        // );
+        trace!(
+            "===== STARTING FUNCTION: {} (symbol {:?}) =====",
+            func_name,
+            symbol_id
+        );
         let func_id = self.builder.start_function(symbol_id, func_name, signature);
         // debug!("  Function ID: {:?}, Entry block created", func_id);
 
@@ -2934,6 +3279,13 @@ impl<'a> HirToMirContext<'a> {
     /// Lower a HIR statement to MIR instructions
     fn lower_statement(&mut self, stmt: &HirStatement) {
         match stmt {
+            HirStatement::Let {
+                pattern: HirPattern::Variable { symbol, .. },
+                init,
+                ..
+            } if self.is_static_variable(*symbol) => {
+                self.lower_static_local(*symbol, init.as_ref());
+            }
             HirStatement::Let {
                 pattern,
                 type_hint,
@@ -2959,47 +3311,9 @@ impl<'a> HirToMirContext<'a> {
                         ..
                     } = &init_expr.kind
                     {
-                        let class_name_str =
-                            class_name.and_then(|interned| self.string_interner.get(interned));
-                        if class_name_str == Some("Vec") && !type_args.is_empty() {
-                            // Determine the monomorphized Vec variant from type args
-                            let first_arg = type_args[0];
-                            let type_table = self.type_table.borrow();
-                            let suffix = if let Some(arg_type) = type_table.get(first_arg) {
-                                match &arg_type.kind {
-                                    TypeKind::Int => Some("I32"),
-                                    TypeKind::Float => Some("F64"),
-                                    TypeKind::Bool => Some("Bool"),
-                                    TypeKind::String => Some("Ptr"),
-                                    TypeKind::Class { symbol_id, .. } => {
-                                        if let Some(class_info) =
-                                            self.symbol_table.get_symbol(*symbol_id)
-                                        {
-                                            if let Some(name) =
-                                                self.string_interner.get(class_info.name)
-                                            {
-                                                if name == "Int64" {
-                                                    Some("I64")
-                                                } else {
-                                                    Some("Ptr")
-                                                }
-                                            } else {
-                                                Some("Ptr")
-                                            }
-                                        } else {
-                                            Some("Ptr")
-                                        }
-                                    }
-                                    _ => Some("Ptr"),
-                                }
-                            } else {
-                                Some("Ptr")
-                            };
-                            drop(type_table);
-                            suffix.map(|s| format!("Vec{}", s))
-                        } else {
-                            None
-                        }
+                        class_name
+                            .and_then(|interned| self.string_interner.get(interned))
+                            .and_then(|name| self.monomorphized_class_name(name, type_args))
                     }
                     // Also check for stdlib class method calls like Arc.init() or arc.clone()
                     // These methods return the same stdlib class type (e.g., Arc.init() -> Arc, Arc.clone() -> Arc)
@@ -3174,12 +3488,20 @@ impl<'a> HirToMirContext<'a> {
                             final_value
                         };
 
+                        // @:struct values are copied, so the new variable owns its own storage
+                        let copies_value_struct = self.needs_value_struct_copy(init_expr);
+                        let final_value = if copies_value_struct {
+                            self.copy_value_struct(final_value, init_expr.ty)
+                        } else {
+                            final_value
+                        };
+
                         self.bind_pattern_with_type(pattern, final_value, var_type, *is_mutable);
 
                         // Register heap-allocated value for drop tracking
                         // Only register AutoDrop types (user-defined classes), not RuntimeManaged
                         // extern types (Thread, Channel, Arc, Mutex) or NoDrop types
-                        if is_heap_alloc {
+                        if is_heap_alloc || copies_value_struct {
                             let needs_drop = self.type_needs_drop(init_expr.ty);
                             if needs_drop {
                                 if let HirPattern::Variable { symbol, .. } = pattern {
@@ -3248,6 +3570,27 @@ impl<'a> HirToMirContext<'a> {
             }
 
             HirStatement::Assign { lhs, rhs, op } => {
+                // A @:fixed(N) array is copied byte-wise from another one of the
+                // same length; anything else has no inline storage to copy
+                if let HirLValue::Field { object, field } = lhs {
+                    if let Some(target) = self.fixed_array_field_of(object, *field) {
+                        let source_len = self.fixed_array_field(rhs).and_then(|f| f.fixed_len);
+                        if op.is_some() || source_len != target.fixed_len {
+                            self.add_error(
+                                &format!(
+                                    "@:fixed({}) field '{}' can only be assigned another \
+                                     @:fixed({}) array",
+                                    target.fixed_len.unwrap_or(0),
+                                    target.name,
+                                    target.fixed_len.unwrap_or(0)
+                                ),
+                                rhs.source_location,
+                            );
+                            return;
+                        }
+                    }
+                }
+
                 // Check if RHS produces a heap-allocated value
                 // This includes:
                 // 1. Direct `new` expressions (e.g., `new Point(1, 2)`)
@@ -3375,16 +3718,39 @@ impl<'a> HirToMirContext<'a> {
                             value
                         };
 
+                        // @:struct values: fields of flat-layout objects and array elements
+                        // store the bytes inline; anything else gets its own copy
+                        let stores_value_struct_inline = self.is_value_struct_class(rhs.ty)
+                            && match lhs {
+                                HirLValue::Variable(_) => false,
+                                HirLValue::Field { object, .. } => self.has_flat_layout(object.ty),
+                                HirLValue::Index { .. } => true,
+                            };
+                        let copies_value_struct = op.is_none()
+                            && !stores_value_struct_inline
+                            && self.needs_value_struct_copy(rhs);
+                        let value = if copies_value_struct {
+                            self.copy_value_struct(value, rhs.ty)
+                        } else {
+                            value
+                        };
+
                         self.lower_lvalue_write(lhs, value);
 
                         // Register heap-allocated value for drop tracking.
                         // Only track when the RHS actually creates a NEW allocation (New or Call).
                         // Field access and variable reads produce borrowed references that must
                         // NOT be freed — they point into existing objects.
-                        let rhs_is_owned_allocation = matches!(
-                            &rhs.kind,
-                            HirExprKind::New { .. } | HirExprKind::Call { .. }
-                        );
+                        let rhs_is_owned_allocation = copies_value_struct
+                            || matches!(
+                                &rhs.kind,
+                                HirExprKind::New { .. } | HirExprKind::Call { .. }
+                            );
+
+                        // A fresh @:struct value whose bytes were stored inline is garbage now
+                        if stores_value_struct_inline {
+                            self.free_fresh_value_struct(rhs, value);
+                        }
 
                         // When assigning to a Field/ArrayIndex lvalue, the RHS value escapes
                         // to another object. If the RHS is a variable that we're tracking as
@@ -3392,7 +3758,7 @@ impl<'a> HirToMirContext<'a> {
                         // lives inside another object and must NOT be freed at scope exit.
                         let lhs_is_field =
                             matches!(lhs, HirLValue::Field { .. } | HirLValue::Index { .. });
-                        if lhs_is_field {
+                        if lhs_is_field && !stores_value_struct_inline {
                             if let HirExprKind::Variable {
                                 symbol: rhs_sym, ..
                             } = &rhs.kind
@@ -3462,7 +3828,13 @@ impl<'a> HirToMirContext<'a> {
                             return Some(boxed);
                         }
                     }
-                    result
+                    // Returned @:struct values must outlive the locals freed below
+                    match result {
+                        Some(val) if self.needs_value_struct_copy(e) => {
+                            Some(self.copy_value_struct(val, e.ty))
+                        }
+                        _ => result,
+                    }
                 });
                 // Cleanup all scopes before returning - free all owned heap values
                 self.cleanup_all_scopes();
@@ -3568,7 +3940,32 @@ impl<'a> HirToMirContext<'a> {
 
             HirStatement::Throw(expr) => {
                 let thrown_type_id = self.runtime_type_id(expr.ty);
-                if let Some(exception_reg) = self.lower_expression(expr) {
+                let throws_interface = {
+                    let resolved = self.resolve_through_aliases(expr.ty);
+                    let type_table = self.type_table.borrow();
+                    matches!(
+                        type_table.get(resolved).map(|t| &t.kind),
+                        Some(TypeKind::Interface { .. })
+                    )
+                };
+                if throws_interface {
+                    // Throw the object behind the fat pointer, typed by its
+                    // class, so class catch clauses can match it
+                    if let Some(fat_ptr) = self.lower_expression(expr) {
+                        let ptr_u8 = IrType::Ptr(Box::new(IrType::U8));
+                        let object = self.build_interface_object(fat_ptr);
+                        let throw_fn = self.get_or_register_extern_function(
+                            "rayzor_throw_object",
+                            vec![ptr_u8],
+                            IrType::Void,
+                        );
+                        if let Some(object) = object {
+                            self.builder
+                                .build_call_direct(throw_fn, vec![object], IrType::Void);
+                        }
+                        self.builder.build_unreachable();
+                    }
+                } else if let Some(exception_reg) = self.lower_expression(expr) {
                     // Cast exception to i64 for uniform storage
                     let reg_type = self
                         .builder
@@ -3873,6 +4270,65 @@ impl<'a> HirToMirContext<'a> {
         None
     }
 
+    /// Monomorphized runtime class for a generic extern collection, from its
+    /// first type argument: `Vec<Int>` -> "VecI32", `Vector<Float>` -> "VectorF64".
+    /// Element types without a specialization use the "Ptr" variant.
+    fn monomorphized_class_name(&self, base_name: &str, type_args: &[TypeId]) -> Option<String> {
+        let specializations: &[&str] = match base_name {
+            "Vec" => &["I32", "I64", "F64", "Bool"],
+            "Vector" => &["I32", "F64"],
+            _ => return None,
+        };
+        let first_arg = *type_args.first()?;
+        let suffix = match self.type_table.borrow().get(first_arg).map(|t| &t.kind) {
+            Some(TypeKind::Int) => "I32",
+            Some(TypeKind::Float) => "F64",
+            Some(TypeKind::Bool) => "Bool",
+            // Int64 is a class type representing a 64-bit int
+            Some(TypeKind::Class { symbol_id, .. })
+                if self
+                    .symbol_table
+                    .get_symbol(*symbol_id)
+                    .and_then(|sym| self.string_interner.get(sym.name))
+                    == Some("Int64") =>
+            {
+                "I64"
+            }
+            _ => "Ptr",
+        };
+        let suffix = if specializations.contains(&suffix) {
+            suffix
+        } else {
+            "Ptr"
+        };
+        Some(format!("{}{}", base_name, suffix))
+    }
+
+    /// Stdlib mapping for a method on a class that is still a `Placeholder`,
+    /// trying the qualified name ("rayzor.Bytes" -> "rayzor_Bytes") and then the
+    /// short name (`haxe.ds.List` is registered as "List").
+    fn find_stdlib_method_for_placeholder(
+        &self,
+        placeholder_name: InternedString,
+        method_name: &str,
+    ) -> Option<(&MethodSignature, &crate::stdlib::RuntimeFunctionCall)> {
+        let target_name = self.string_interner.get(placeholder_name)?;
+        let short_name = target_name.rsplit('.').next().unwrap_or(target_name);
+        self.stdlib_mapping
+            .find_by_name(&target_name.replace('.', "_"), method_name)
+            .or_else(|| self.stdlib_mapping.find_by_name(short_name, method_name))
+    }
+
+    /// Runtime setter of a stdlib extern property, mapped as `set_<name>` next to
+    /// the property's getter (e.g. `sys.thread.Tls.value`).
+    fn stdlib_property_setter(&self, field: SymbolId, receiver_ty: TypeId) -> Option<&'static str> {
+        let (class, property, _) = self.get_stdlib_runtime_info(field, receiver_ty, None, None)?;
+        let setter = format!("set_{}", property);
+        self.stdlib_mapping
+            .find_by_name(class, &setter)
+            .map(|(_, call)| call.runtime_name)
+    }
+
     /// Check if a method symbol corresponds to a stdlib method with runtime mapping
     ///
     /// Returns (class_name, method_name, runtime_function_name) if this is a stdlib method
@@ -4016,8 +4472,12 @@ impl<'a> HirToMirContext<'a> {
                         {
                             matches.push(m);
                         }
-                    } else if let Some(m) =
-                        self.stdlib_mapping.find_by_name(class_name, method_name)
+                    } else if let Some(m) = self
+                        .stdlib_mapping
+                        .find_by_name(class_name, method_name)
+                        // Property reads never resolve to static methods (e.g. an
+                        // `array` field must not match `Lambda.array`)
+                        .filter(|(sig, _)| !sig.is_static)
                     {
                         matches.push(m);
                     }
@@ -4179,17 +4639,12 @@ impl<'a> HirToMirContext<'a> {
                             // The typedef target wasn't resolved at compile time - try to look it up by name
                             // This handles cases like `typedef Bytes = rayzor.Bytes` where the target was loaded
                             // after the typedef was initially compiled
-                            if let Some(target_name) = self.string_interner.get(*placeholder_name) {
-                                // Convert "rayzor.Bytes" to "rayzor_Bytes" for stdlib mapping lookup
-                                let qualified_name = target_name.replace(".", "_");
-                                if let Some((_sig, mapping)) = self
-                                    .stdlib_mapping
-                                    .find_by_name(&qualified_name, method_name)
-                                {
-                                    // Early return with the mapping
-                                    drop(type_table);
-                                    return Some((_sig.class, _sig.method, mapping));
-                                }
+                            if let Some((sig, mapping)) = self
+                                .find_stdlib_method_for_placeholder(*placeholder_name, method_name)
+                            {
+                                // Early return with the mapping
+                                drop(type_table);
+                                return Some((sig.class, sig.method, mapping));
                             }
                             (None, None, Vec::new())
                         }
@@ -4276,6 +4731,15 @@ impl<'a> HirToMirContext<'a> {
             // The caller has context-specific handlers (Dynamic method handler, function_map, etc.)
             // that work better than brute-force stdlib search for these cases.
             TypeKind::TypeParameter { .. } | TypeKind::Dynamic | TypeKind::Placeholder { .. } => {
+                // Unresolved stdlib class (e.g. `haxe.ds.List` reached through a typedef)
+                if let TypeKind::Placeholder { name } = &type_info.kind {
+                    if let Some((sig, mapping)) =
+                        self.find_stdlib_method_for_placeholder(*name, method_name)
+                    {
+                        drop(type_table);
+                        return Some((sig.class, sig.method, mapping));
+                    }
+                }
                 drop(type_table);
                 // Try qualified_name if available (e.g., for user-class methods like "test.Counter.increment")
                 if let Some(qname) = qualified_name {
@@ -4314,44 +4778,11 @@ impl<'a> HirToMirContext<'a> {
 
         let base_class_name = base_class_name?;
 
+        drop(type_table);
+
         // MONOMORPHIZATION: For generic extern classes like Vec<T>, monomorphize the class name
         // based on type arguments. Vec<Int> -> VecI32, Vec<Float> -> VecF64, etc.
-        let monomorphized_class_name: Option<String> =
-            if base_class_name == "Vec" && !type_args.is_empty() {
-                let first_arg = type_args[0];
-                let suffix = if let Some(arg_type) = type_table.get(first_arg) {
-                    match &arg_type.kind {
-                        TypeKind::Int => Some("I32"),
-                        TypeKind::Float => Some("F64"),
-                        TypeKind::Bool => Some("Bool"),
-                        TypeKind::String => Some("Ptr"), // Strings are reference types
-                        TypeKind::Class { symbol_id, .. } => {
-                            // Check if it's Int64 (a class type representing 64-bit int)
-                            if let Some(class_info) = self.symbol_table.get_symbol(*symbol_id) {
-                                if let Some(name) = self.string_interner.get(class_info.name) {
-                                    if name == "Int64" {
-                                        Some("I64")
-                                    } else {
-                                        Some("Ptr") // Other classes are reference types
-                                    }
-                                } else {
-                                    Some("Ptr")
-                                }
-                            } else {
-                                Some("Ptr")
-                            }
-                        }
-                        _ => Some("Ptr"), // Default to pointer for other types
-                    }
-                } else {
-                    Some("Ptr") // Unknown type, use pointer
-                };
-                suffix.map(|s| format!("Vec{}", s))
-            } else {
-                None
-            };
-
-        drop(type_table);
+        let monomorphized_class_name = self.monomorphized_class_name(base_class_name, &type_args);
 
         // Use monomorphized name if available, otherwise use base name
         let class_name = monomorphized_class_name
@@ -4570,6 +5001,686 @@ impl<'a> HirToMirContext<'a> {
         func_id
     }
 
+    /// Array methods whose runtime behaviour depends on the element or callback
+    /// types, with their Haxe parameter count.
+    ///
+    /// These are lowered by `lower_typed_array_method` instead of the generic
+    /// stdlib call path, which only knows the type-erased i64 slot ABI.
+    fn typed_array_method_arity(runtime_func: &str) -> Option<usize> {
+        match runtime_func {
+            "array_map"
+            | "array_filter"
+            | "array_sort"
+            | "array_join"
+            | "array_index_of"
+            | "haxe_array_contains" => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Element type tag for typed Array runtime calls (same tags as
+    /// `haxe_reflect_compare_typed`): 1=Int, 2=Bool, 4=Float, 5=String, 0=other.
+    fn array_element_type_tag(&self, array_type: TypeId) -> i32 {
+        self.collection_element_type(array_type)
+            .map_or(0, |element_type| self.element_type_tag(element_type))
+    }
+
+    /// Type tag (see `array_element_type_tag`) for an element type.
+    fn element_type_tag(&self, element_type: TypeId) -> i32 {
+        match self.type_table.borrow().get(element_type).map(|t| &t.kind) {
+            Some(TypeKind::Int) => 1,
+            Some(TypeKind::Bool) => 2,
+            Some(TypeKind::Float) => 4,
+            Some(TypeKind::String) => 5,
+            _ => 0,
+        }
+    }
+
+    /// Element type of an `Array<T>` or a (possibly aliased) `List<T>`.
+    fn collection_element_type(&self, collection_type: TypeId) -> Option<TypeId> {
+        if let Some(element_type) = self.get_array_element_type(collection_type) {
+            return Some(element_type);
+        }
+        self.list_element_type(collection_type)
+    }
+
+    /// Element type of a `haxe.ds.List<T>`, looking through typedefs such as the
+    /// top-level `List<T>`. Returns `None` if the type is not a List.
+    fn list_element_type(&self, ty: TypeId) -> Option<TypeId> {
+        let type_table = self.type_table.borrow();
+        let mut current = ty;
+        let mut alias_arg = None;
+        for _ in 0..10 {
+            match type_table.get(current).map(|t| &t.kind)? {
+                TypeKind::TypeAlias {
+                    target_type,
+                    type_args,
+                    ..
+                } => {
+                    alias_arg = alias_arg.or(type_args.first().copied());
+                    current = *target_type;
+                }
+                TypeKind::Class {
+                    symbol_id,
+                    type_args,
+                } => {
+                    let name = self
+                        .symbol_table
+                        .get_symbol(*symbol_id)
+                        .and_then(|sym| self.string_interner.get(sym.name));
+                    if name != Some("List") {
+                        return None;
+                    }
+                    // The alias target still has the typedef's own type parameter
+                    let class_arg = type_args.first().copied().filter(|arg| {
+                        !matches!(
+                            type_table.get(*arg).map(|t| &t.kind),
+                            Some(TypeKind::TypeParameter { .. })
+                        )
+                    });
+                    return Some(
+                        class_arg
+                            .or(alias_arg)
+                            .unwrap_or_else(|| type_table.dynamic_type()),
+                    );
+                }
+                // Typedef target not resolved yet (e.g. when `Lambda` pulls in List first)
+                TypeKind::Placeholder { name } => {
+                    let is_list = self
+                        .string_interner
+                        .get(*name)
+                        .is_some_and(|n| n == "List" || n == "haxe.ds.List");
+                    return is_list.then(|| alias_arg.unwrap_or_else(|| type_table.dynamic_type()));
+                }
+                _ => return None,
+            }
+        }
+        None
+    }
+
+    /// Callback ABI flags (`CALLBACK_*` in runtime/src/haxe_array.rs) for a closure.
+    ///
+    /// Prefers the signature of the lambda the closure was made from, since the
+    /// lambda's return type is inferred from its body; otherwise falls back to
+    /// the HIR function type.
+    fn array_callback_kind(&self, closure: IrId, closure_type: TypeId) -> i32 {
+        const F64_ARGS: i32 = 1;
+        const F64_RESULT: i32 = 2;
+        const I32_RESULT: i32 = 4;
+        const BOOL_RESULT: i32 = 8;
+        const F64_ARG2: i32 = 16;
+
+        let lambda_signature = self.builder.current_function().and_then(|func| {
+            func.cfg.blocks.values().find_map(|block| {
+                block.instructions.iter().find_map(|inst| match inst {
+                    IrInstruction::MakeClosure { dest, func_id, .. } if *dest == closure => self
+                        .builder
+                        .module
+                        .functions
+                        .get(func_id)
+                        .map(|f| &f.signature),
+                    _ => None,
+                })
+            })
+        });
+
+        let (params, ret) = if let Some(sig) = lambda_signature {
+            // Skip the env pointer every lambda takes first
+            let params = sig
+                .parameters
+                .iter()
+                .skip(1)
+                .map(|p| p.ty.clone())
+                .collect::<Vec<_>>();
+            (params, sig.return_type.clone())
+        } else {
+            let function_type = self
+                .type_table
+                .borrow()
+                .get(closure_type)
+                .map(|t| t.kind.clone());
+            match function_type {
+                Some(TypeKind::Function {
+                    params,
+                    return_type,
+                    ..
+                }) => (
+                    params.iter().map(|p| self.convert_type(*p)).collect(),
+                    self.convert_type(return_type),
+                ),
+                _ => return 0,
+            }
+        };
+
+        let is_float = |p: Option<&IrType>| matches!(p, Some(IrType::F64 | IrType::F32));
+        let mut kind = 0;
+        if is_float(params.first()) {
+            kind |= F64_ARGS;
+        }
+        if is_float(params.get(1)) {
+            kind |= F64_ARG2;
+        }
+        kind |= match ret {
+            IrType::F64 | IrType::F32 => F64_RESULT,
+            IrType::I32 => I32_RESULT,
+            IrType::Bool => BOOL_RESULT,
+            _ => 0,
+        };
+        kind
+    }
+
+    /// Lower `Vector.fromArrayCopy(array)`, creating the Vector variant for the
+    /// array's element type.
+    fn lower_vector_from_array(&mut self, array: &HirExpr) -> Option<IrId> {
+        let element_type = match self.type_table.borrow().get(array.ty).map(|t| &t.kind) {
+            Some(TypeKind::Array { element_type }) => Some(*element_type),
+            _ => None,
+        };
+        let runtime_func = match element_type
+            .and_then(|elem| self.monomorphized_class_name("Vector", &[elem]))
+            .as_deref()
+        {
+            Some("VectorI32") => "haxe_vector_i32_from_array",
+            Some("VectorF64") => "haxe_vector_f64_from_array",
+            _ => "haxe_vector_from_array",
+        };
+        let ptr_void = IrType::Ptr(Box::new(IrType::Void));
+        let arr = self.lower_expression(array)?;
+        let func_id = self.get_or_register_extern_function(
+            runtime_func,
+            vec![ptr_void.clone()],
+            ptr_void.clone(),
+        );
+        self.builder.build_call_direct(func_id, vec![arr], ptr_void)
+    }
+
+    /// Lower `arr.map/filter/sort/join/indexOf/contains` with type information
+    /// the runtime needs: callback ABI flags for closures, and the element type
+    /// tag for formatting and equality.
+    fn lower_typed_array_method(
+        &mut self,
+        runtime_func: &str,
+        receiver: &HirExpr,
+        arg: &HirExpr,
+        result_type: &IrType,
+    ) -> Option<IrId> {
+        let ptr_void = IrType::Ptr(Box::new(IrType::Void));
+        let arr = self.lower_expression(receiver)?;
+        let value = self.lower_expression(arg)?;
+
+        match runtime_func {
+            "array_map" | "array_filter" | "array_sort" => {
+                let kind = self.array_callback_kind(value, arg.ty);
+                let kind_reg = self.builder.build_const(IrValue::I32(kind))?;
+                let wrapper = format!("{}_typed", runtime_func);
+                let return_type = if runtime_func == "array_sort" {
+                    IrType::Void
+                } else {
+                    ptr_void.clone()
+                };
+                let func_id = self.register_stdlib_mir_forward_ref(
+                    &wrapper,
+                    vec![IrType::Any, IrType::Any, IrType::I32],
+                    return_type.clone(),
+                );
+                let result = self.builder.build_call_direct(
+                    func_id,
+                    vec![arr, value, kind_reg],
+                    return_type,
+                );
+                if runtime_func == "array_sort" && self.debug_checks {
+                    self.build_sort_comparator_check(arr, value, kind_reg, &arg.source_location);
+                }
+                result
+            }
+            "array_join" => {
+                let tag = self.array_element_type_tag(receiver.ty);
+                let tag_reg = self.builder.build_const(IrValue::I32(tag))?;
+                let join_fn = self.get_or_register_extern_function(
+                    "haxe_array_join_typed",
+                    vec![ptr_void.clone(), ptr_void.clone(), IrType::I32],
+                    ptr_void.clone(),
+                );
+                self.builder
+                    .build_call_direct(join_fn, vec![arr, value, tag_reg], ptr_void)
+            }
+            _ => {
+                // indexOf / contains: pass the value in its raw slot representation
+                let tag = self.array_element_type_tag(receiver.ty);
+                let raw = self.value_to_raw_slot(value, arg.ty)?;
+                let tag_reg = self.builder.build_const(IrValue::I32(tag))?;
+                let result = if runtime_func == "array_index_of" {
+                    let index_of_fn = self.get_or_register_extern_function(
+                        "haxe_array_index_of_typed",
+                        vec![ptr_void, IrType::I64, IrType::I64, IrType::I32],
+                        IrType::I64,
+                    );
+                    let from = self.builder.build_const(IrValue::I64(0))?;
+                    self.builder.build_call_direct(
+                        index_of_fn,
+                        vec![arr, raw, from, tag_reg],
+                        IrType::I64,
+                    )?
+                } else {
+                    let contains_fn = self.get_or_register_extern_function(
+                        "haxe_array_contains_typed",
+                        vec![ptr_void, IrType::I64, IrType::I32],
+                        IrType::I64,
+                    );
+                    self.builder.build_call_direct(
+                        contains_fn,
+                        vec![arr, raw, tag_reg],
+                        IrType::I64,
+                    )?
+                };
+                match result_type {
+                    IrType::I64 => Some(result),
+                    _ => self
+                        .builder
+                        .build_cast(result, IrType::I64, result_type.clone()),
+                }
+            }
+        }
+    }
+
+    /// Lower `arr.push(value)` on an `Array<T>` of a @:struct class, copying
+    /// the value's bytes into the buffer.
+    fn lower_value_struct_array_push(&mut self, receiver: &HirExpr, arg: &HirExpr) -> Option<IrId> {
+        let arr = self.lower_expression(receiver)?;
+        let value = self.lower_expression(arg)?;
+        let push_id = self.get_or_register_extern_function(
+            "haxe_array_push",
+            vec![
+                IrType::Ptr(Box::new(IrType::Void)),
+                IrType::Ptr(Box::new(IrType::U8)),
+            ],
+            IrType::Void,
+        );
+        let result = self
+            .builder
+            .build_call_direct(push_id, vec![arr, value], IrType::Void);
+        self.free_fresh_value_struct(arg, value);
+        result
+    }
+
+    /// Convert a value to its raw 8-byte slot representation (f64 bits for Float,
+    /// sign-extended integers, pointers as integers).
+    fn value_to_raw_slot(&mut self, value: IrId, value_type: TypeId) -> Option<IrId> {
+        let value_ty = self.convert_type(value_type);
+        self.ir_value_to_raw_slot(value, value_ty)
+    }
+
+    /// [`Self::value_to_raw_slot`] for a value whose IR type is already known.
+    fn ir_value_to_raw_slot(&mut self, value: IrId, value_ty: IrType) -> Option<IrId> {
+        match &value_ty {
+            IrType::F64 => self.builder.build_bitcast(value, IrType::I64),
+            IrType::I64 => Some(value),
+            _ => self.builder.build_cast(value, value_ty, IrType::I64),
+        }
+    }
+
+    /// Convert a raw slot value (I64) back to `ty`.
+    fn raw_slot_to_value(&mut self, raw: IrId, ty: &IrType) -> Option<IrId> {
+        match ty {
+            IrType::I64 => Some(raw),
+            IrType::F64 => self.builder.build_bitcast(raw, IrType::F64),
+            IrType::F32 => {
+                let wide = self.builder.build_bitcast(raw, IrType::F64)?;
+                self.builder.build_cast(wide, IrType::F64, IrType::F32)
+            }
+            IrType::Void => Some(raw),
+            _ => self.builder.build_cast(raw, IrType::I64, ty.clone()),
+        }
+    }
+
+    /// Load `(fn_ptr, env_ptr)` from a closure struct `{ fn_ptr: i64, env_ptr: i64 }`.
+    fn closure_parts(&mut self, closure: IrId) -> Option<(IrId, IrId)> {
+        let fn_ptr = self.builder.build_load(closure, IrType::I64)?;
+        let offset_8 = self.builder.build_const(IrValue::I64(8))?;
+        let env_slot =
+            self.builder
+                .build_binop(crate::ir::instructions::BinaryOp::Add, closure, offset_8)?;
+        let env_ptr = self.builder.build_load(env_slot, IrType::I64)?;
+        Some((fn_ptr, env_ptr))
+    }
+
+    /// Call a runtime function that takes `(collection, fn_ptr, env_ptr, [extra...,] kind)`
+    /// for a closure argument.
+    fn call_with_callback(
+        &mut self,
+        runtime_func: &str,
+        collection: IrId,
+        callback: &HirExpr,
+        extra: Vec<(IrId, IrType)>,
+        return_type: IrType,
+    ) -> Option<IrId> {
+        let ptr_void = IrType::Ptr(Box::new(IrType::Void));
+        let closure = self.lower_expression(callback)?;
+        let kind = self.array_callback_kind(closure, callback.ty);
+        let (fn_ptr, env_ptr) = self.closure_parts(closure)?;
+        let kind_reg = self.builder.build_const(IrValue::I32(kind))?;
+
+        let mut param_types = vec![ptr_void, IrType::I64, IrType::I64];
+        let mut call_args = vec![collection, fn_ptr, env_ptr];
+        for (reg, ty) in extra {
+            call_args.push(reg);
+            param_types.push(ty);
+        }
+        call_args.push(kind_reg);
+        param_types.push(IrType::I32);
+
+        let func_id =
+            self.get_or_register_extern_function(runtime_func, param_types, return_type.clone());
+        self.builder
+            .build_call_direct(func_id, call_args, return_type)
+    }
+
+    /// List methods whose runtime behaviour depends on the element or callback
+    /// types, with their Haxe parameter count (see `lower_typed_list_method`).
+    fn typed_list_method_arity(runtime_func: &str) -> Option<usize> {
+        match runtime_func {
+            "haxe_list_remove_typed" | "haxe_list_join" | "haxe_list_filter" | "haxe_list_map" => {
+                Some(1)
+            }
+            "haxe_list_to_string" => Some(0),
+            _ => None,
+        }
+    }
+
+    /// Lower `list.remove/join/toString/filter/map`, passing the element type tag
+    /// or the callback's ABI flags to the runtime.
+    fn lower_typed_list_method(
+        &mut self,
+        runtime_func: &str,
+        receiver: &HirExpr,
+        args: &[HirExpr],
+        result_type: &IrType,
+    ) -> Option<IrId> {
+        let ptr_void = IrType::Ptr(Box::new(IrType::Void));
+        let list = self.lower_expression(receiver)?;
+        let tag = self
+            .list_element_type(receiver.ty)
+            .map_or(0, |element_type| self.element_type_tag(element_type));
+
+        match runtime_func {
+            "haxe_list_filter" | "haxe_list_map" => {
+                self.call_with_callback(runtime_func, list, &args[0], Vec::new(), ptr_void)
+            }
+            "haxe_list_remove_typed" => {
+                let value = self.lower_expression(&args[0])?;
+                let raw = self.value_to_raw_slot(value, args[0].ty)?;
+                let tag_reg = self.builder.build_const(IrValue::I32(tag))?;
+                let remove_fn = self.get_or_register_extern_function(
+                    runtime_func,
+                    vec![ptr_void, IrType::I64, IrType::I32],
+                    IrType::Bool,
+                );
+                let removed = self.builder.build_call_direct(
+                    remove_fn,
+                    vec![list, raw, tag_reg],
+                    IrType::Bool,
+                )?;
+                match result_type {
+                    IrType::Bool => Some(removed),
+                    _ => self
+                        .builder
+                        .build_cast(removed, IrType::Bool, result_type.clone()),
+                }
+            }
+            "haxe_list_join" => {
+                let sep = self.lower_expression(&args[0])?;
+                let tag_reg = self.builder.build_const(IrValue::I32(tag))?;
+                let join_fn = self.get_or_register_extern_function(
+                    runtime_func,
+                    vec![ptr_void.clone(), ptr_void.clone(), IrType::I32],
+                    ptr_void.clone(),
+                );
+                self.builder
+                    .build_call_direct(join_fn, vec![list, sep, tag_reg], ptr_void)
+            }
+            _ => {
+                let tag_reg = self.builder.build_const(IrValue::I32(tag))?;
+                let to_string_fn = self.get_or_register_extern_function(
+                    runtime_func,
+                    vec![ptr_void.clone(), IrType::I32],
+                    ptr_void.clone(),
+                );
+                self.builder
+                    .build_call_direct(to_string_fn, vec![list, tag_reg], ptr_void)
+            }
+        }
+    }
+
+    /// Lower an Array or List expression to a HaxeArray pointer (for-in loops and
+    /// `Lambda` functions).
+    ///
+    /// Arrays are passed as-is; Lists are copied with `haxe_list_to_array`.
+    fn lower_iterable_as_array(&mut self, iterable: &HirExpr) -> Option<IrId> {
+        let ptr_void = IrType::Ptr(Box::new(IrType::Void));
+        let value = self.lower_expression(iterable)?;
+        if self.list_element_type(iterable.ty).is_none() {
+            return Some(value);
+        }
+        let to_array_fn = self.get_or_register_extern_function(
+            "haxe_list_to_array",
+            vec![ptr_void.clone()],
+            ptr_void.clone(),
+        );
+        self.builder
+            .build_call_direct(to_array_fn, vec![value], ptr_void)
+    }
+
+    /// Lower a call to a `Lambda` function (`Lambda.count(it)`, or `it.count()`
+    /// with `using Lambda`) to the native Array/List implementation.
+    fn lower_lambda_call(
+        &mut self,
+        method_name: &str,
+        args: &[HirExpr],
+        result_type: &IrType,
+    ) -> Option<IrId> {
+        let ptr_void = IrType::Ptr(Box::new(IrType::Void));
+        let iterable = args.first()?;
+        let arr = self.lower_iterable_as_array(iterable)?;
+
+        // Runtime results are i64/i32/bool slots; narrow or widen to the Haxe type
+        let convert = |this: &mut Self, reg: IrId, from: IrType| -> Option<IrId> {
+            if &from == result_type || matches!(result_type, IrType::Void) {
+                Some(reg)
+            } else {
+                this.builder.build_cast(reg, from, result_type.clone())
+            }
+        };
+
+        match method_name {
+            "exists" | "foreach" => {
+                let runtime_func = format!("haxe_lambda_{}", method_name);
+                let found = self.call_with_callback(
+                    &runtime_func,
+                    arr,
+                    args.get(1)?,
+                    Vec::new(),
+                    IrType::Bool,
+                )?;
+                convert(self, found, IrType::Bool)
+            }
+            "iter" => self.call_with_callback(
+                "haxe_lambda_iter",
+                arr,
+                args.get(1)?,
+                Vec::new(),
+                IrType::Void,
+            ),
+            "count" if args.len() < 2 => {
+                let zero = self.builder.build_const(IrValue::I64(0))?;
+                let kind = self.builder.build_const(IrValue::I32(0))?;
+                let count_fn = self.get_or_register_extern_function(
+                    "haxe_lambda_count",
+                    vec![ptr_void, IrType::I64, IrType::I64, IrType::I32],
+                    IrType::I32,
+                );
+                let count = self.builder.build_call_direct(
+                    count_fn,
+                    vec![arr, zero, zero, kind],
+                    IrType::I32,
+                )?;
+                convert(self, count, IrType::I32)
+            }
+            "count" | "findIndex" => {
+                let runtime_func = if method_name == "count" {
+                    "haxe_lambda_count"
+                } else {
+                    "haxe_lambda_find_index"
+                };
+                let result = self.call_with_callback(
+                    runtime_func,
+                    arr,
+                    args.get(1)?,
+                    Vec::new(),
+                    IrType::I32,
+                )?;
+                convert(self, result, IrType::I32)
+            }
+            "find" => {
+                let raw = self.call_with_callback(
+                    "haxe_lambda_find",
+                    arr,
+                    args.get(1)?,
+                    Vec::new(),
+                    IrType::I64,
+                )?;
+                self.raw_slot_to_value(raw, result_type)
+            }
+            "fold" | "foldi" => {
+                let first = args.get(2)?;
+                let first_value = self.lower_expression(first)?;
+                let first_raw = self.value_to_raw_slot(first_value, first.ty)?;
+                let runtime_func = format!("haxe_lambda_{}", method_name);
+                let raw = self.call_with_callback(
+                    &runtime_func,
+                    arr,
+                    args.get(1)?,
+                    vec![(first_raw, IrType::I64)],
+                    IrType::I64,
+                )?;
+                // The accumulator type is the type of `first` when B is not resolved
+                let acc_type = match result_type {
+                    IrType::Any | IrType::Void => self.convert_type(first.ty),
+                    IrType::Ptr(inner) if matches!(inner.as_ref(), IrType::Void) => {
+                        self.convert_type(first.ty)
+                    }
+                    _ => result_type.clone(),
+                };
+                self.raw_slot_to_value(raw, &acc_type)
+            }
+            "map" | "filter" | "flatMap" => {
+                let callback = args.get(1)?;
+                let closure = self.lower_expression(callback)?;
+                let kind = self.array_callback_kind(closure, callback.ty);
+                let kind_reg = self.builder.build_const(IrValue::I32(kind))?;
+                let wrapper = if method_name == "filter" {
+                    "array_filter_typed"
+                } else {
+                    "array_map_typed"
+                };
+                let func_id = self.register_stdlib_mir_forward_ref(
+                    wrapper,
+                    vec![IrType::Any, IrType::Any, IrType::I32],
+                    ptr_void.clone(),
+                );
+                let mapped = self.builder.build_call_direct(
+                    func_id,
+                    vec![arr, closure, kind_reg],
+                    ptr_void.clone(),
+                )?;
+                if method_name != "flatMap" {
+                    return Some(mapped);
+                }
+                let flatten_fn = self.get_or_register_extern_function(
+                    "haxe_lambda_flatten",
+                    vec![ptr_void.clone()],
+                    ptr_void.clone(),
+                );
+                self.builder
+                    .build_call_direct(flatten_fn, vec![mapped], ptr_void)
+            }
+            "mapi" => {
+                self.call_with_callback("haxe_lambda_mapi", arr, args.get(1)?, Vec::new(), ptr_void)
+            }
+            "has" | "indexOf" => {
+                let value_expr = args.get(1)?;
+                let value = self.lower_expression(value_expr)?;
+                let raw = self.value_to_raw_slot(value, value_expr.ty)?;
+                let tag = self
+                    .collection_element_type(iterable.ty)
+                    .map_or(0, |element_type| self.element_type_tag(element_type));
+                let tag_reg = self.builder.build_const(IrValue::I32(tag))?;
+                let result = if method_name == "has" {
+                    let contains_fn = self.get_or_register_extern_function(
+                        "haxe_array_contains_typed",
+                        vec![ptr_void, IrType::I64, IrType::I32],
+                        IrType::I64,
+                    );
+                    self.builder.build_call_direct(
+                        contains_fn,
+                        vec![arr, raw, tag_reg],
+                        IrType::I64,
+                    )?
+                } else {
+                    let index_of_fn = self.get_or_register_extern_function(
+                        "haxe_array_index_of_typed",
+                        vec![ptr_void, IrType::I64, IrType::I64, IrType::I32],
+                        IrType::I64,
+                    );
+                    let from = self.builder.build_const(IrValue::I64(0))?;
+                    self.builder.build_call_direct(
+                        index_of_fn,
+                        vec![arr, raw, from, tag_reg],
+                        IrType::I64,
+                    )?
+                };
+                convert(self, result, IrType::I64)
+            }
+            "empty" => {
+                let empty_fn = self.get_or_register_extern_function(
+                    "haxe_lambda_empty",
+                    vec![ptr_void],
+                    IrType::Bool,
+                );
+                let empty = self
+                    .builder
+                    .build_call_direct(empty_fn, vec![arr], IrType::Bool)?;
+                convert(self, empty, IrType::Bool)
+            }
+            "concat" => {
+                let other = self.lower_iterable_as_array(args.get(1)?)?;
+                let concat_fn = self.get_or_register_extern_function(
+                    "haxe_lambda_concat",
+                    vec![ptr_void.clone(), ptr_void.clone()],
+                    ptr_void.clone(),
+                );
+                self.builder
+                    .build_call_direct(concat_fn, vec![arr, other], ptr_void)
+            }
+            "array" | "list" | "flatten" => {
+                let runtime_func = match method_name {
+                    "array" => "haxe_lambda_array",
+                    "list" => "haxe_list_from_array",
+                    _ => "haxe_lambda_flatten",
+                };
+                let func_id = self.get_or_register_extern_function(
+                    runtime_func,
+                    vec![ptr_void.clone()],
+                    ptr_void.clone(),
+                );
+                self.builder.build_call_direct(func_id, vec![arr], ptr_void)
+            }
+            _ => {
+                warn!("Lambda.{} is not supported", method_name);
+                None
+            }
+        }
+    }
+
     /// Get or register an external runtime function, returning its ID
     ///
     /// This allows calling external runtime functions (like haxe_math_abs) from MIR
@@ -4780,11 +5891,11 @@ impl<'a> HirToMirContext<'a> {
             .extern_functions
             .insert(func_id, extern_func);
 
-        // eprintln!(
-        //     "  ℹ️  After registration: module has {} functions, {} extern_functions",
-        //     self.builder.module.functions.len(),
-        //     self.builder.module.extern_functions.len()
-        // );
+        trace!(
+            "  ℹ️  After registration: module has {} functions, {} extern_functions",
+            self.builder.module.functions.len(),
+            self.builder.module.extern_functions.len()
+        );
 
         func_id
     }
@@ -4926,6 +6037,88 @@ impl<'a> HirToMirContext<'a> {
             })
     }
 
+    /// Enum symbol of `ty` if it is an enum type (plain or generic instance).
+    ///
+    /// Unlike `resolve_enum_symbol`, returns None for every other kind of type.
+    fn enum_symbol_of_type(&self, ty: TypeId) -> Option<SymbolId> {
+        let type_table = self.type_table.borrow();
+        match &type_table.get(ty)?.kind {
+            TypeKind::Enum { symbol_id, .. } => Some(*symbol_id),
+            TypeKind::GenericInstance { base_type, .. } => {
+                match &type_table.get(*base_type)?.kind {
+                    TypeKind::Enum { symbol_id, .. } => Some(*symbol_id),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Runtime type id of the enum `ty` (as registered in the runtime type
+    /// registry), if `ty` is an enum type.
+    fn enum_runtime_type_id(&self, ty: TypeId) -> Option<u32> {
+        let enum_symbol = self.enum_symbol_of_type(ty)?;
+        Some(self.symbol_table.get_symbol(enum_symbol)?.type_id.0)
+    }
+
+    /// Lower `==`/`!=` between two values of a boxed enum (one with
+    /// parameterized constructors) to a structural comparison in the runtime.
+    ///
+    /// Returns None when the operands are not both values of the same boxed
+    /// enum, or one side is `null`; those keep the plain comparison (unboxed
+    /// enums are constructor indices, where it is already structural).
+    fn lower_enum_equality(
+        &mut self,
+        op: HirBinaryOp,
+        lhs: &HirExpr,
+        rhs: &HirExpr,
+    ) -> Option<Option<IrId>> {
+        if matches!(lhs.kind, HirExprKind::Null) || matches!(rhs.kind, HirExprKind::Null) {
+            return None;
+        }
+        let enum_symbol = self.enum_symbol_of_type(lhs.ty)?;
+        if self.enum_symbol_of_type(rhs.ty) != Some(enum_symbol) || !self.enum_is_boxed(enum_symbol)
+        {
+            return None;
+        }
+        let type_id = self.enum_runtime_type_id(lhs.ty)?;
+
+        Some(self.build_enum_eq_call(type_id, lhs, rhs, matches!(op, HirBinaryOp::Ne)))
+    }
+
+    /// `haxe_type_enum_eq(lhs, rhs, type_id)`, negated for `!=`.
+    fn build_enum_eq_call(
+        &mut self,
+        type_id: u32,
+        lhs: &HirExpr,
+        rhs: &HirExpr,
+        negate: bool,
+    ) -> Option<IrId> {
+        let mut operands = Vec::with_capacity(3);
+        for operand in [lhs, rhs] {
+            let reg = self.lower_expression(operand)?;
+            let reg = match self.builder.get_register_type(reg) {
+                Some(ty) if ty != IrType::I64 => self.builder.build_cast(reg, ty, IrType::I64)?,
+                _ => reg,
+            };
+            operands.push(reg);
+        }
+        operands.push(self.builder.build_const(IrValue::I32(type_id as i32))?);
+        let eq_func = self.get_or_register_extern_function(
+            "haxe_type_enum_eq",
+            vec![IrType::I64, IrType::I64, IrType::I32],
+            IrType::Bool,
+        );
+        let eq = self
+            .builder
+            .build_call_direct(eq_func, operands, IrType::Bool)?;
+        if negate {
+            self.builder.build_unop(UnaryOp::Not, eq)
+        } else {
+            Some(eq)
+        }
+    }
+
     /// Allocate a boxed enum struct with only a tag (no fields).
     /// Used for parameterless variants of enums that have other parameterized variants.
     /// Layout: [tag:i32][pad:i32] = 8 bytes, returned as ptr bitcast to i64.
@@ -5588,8 +6781,12 @@ impl<'a> HirToMirContext<'a> {
                         }
                     }
 
+                    if let Some(&(key, ty)) = self.static_locals.get(symbol) {
+                        return self.build_static_local_load(key, ty);
+                    }
+
                     // Check if this is a global variable (static class field, module-level var)
-                    if let Some(&global_id) = self.global_symbol_map.get(symbol) {
+                    if let Some(global_id) = self.static_global(*symbol) {
                         debug!(
                             "[GLOBAL ACCESS] Found global {:?} -> {:?}",
                             symbol, global_id
@@ -5648,6 +6845,18 @@ impl<'a> HirToMirContext<'a> {
                     }
                 }
 
+                // `.length` of a @:fixed(N) array is the constant N
+                if let Some(layout) = self.fixed_array_field(object) {
+                    let field_name = self
+                        .symbol_table
+                        .get_symbol(*field)
+                        .and_then(|s| self.string_interner.get(s.name));
+                    if field_name == Some("length") {
+                        let len = layout.fixed_len.unwrap_or(0);
+                        return self.builder.build_const(IrValue::I32(len as i32));
+                    }
+                }
+
                 // Regular field access
                 debug!("[Field expression] About to lower object");
                 let obj_reg = self.lower_expression(object)?;
@@ -5735,6 +6944,11 @@ impl<'a> HirToMirContext<'a> {
                     }
                 }
 
+                // Instance method used as a value: `var f = obj.method;`
+                if let Some(trampoline) = self.bound_method_trampoline(*field) {
+                    return self.lower_bound_method(obj_reg, trampoline);
+                }
+
                 let result = self.lower_field_access(obj_reg, *field, receiver_ty, expr.ty);
                 debug!(
                     "[Field expression] lower_field_access returned {:?}",
@@ -5744,6 +6958,9 @@ impl<'a> HirToMirContext<'a> {
             }
 
             HirExprKind::Index { object, index } => {
+                if let Some(layout) = self.fixed_array_field(object) {
+                    return self.lower_fixed_array_read(object, index, &layout);
+                }
                 let obj_reg = self.lower_expression(object)?;
                 let idx_reg = self.lower_expression(index)?;
                 self.lower_index_access(obj_reg, idx_reg, expr.ty)
@@ -5759,6 +6976,21 @@ impl<'a> HirToMirContext<'a> {
                 self.builder.call_label = Some("CALL_START".to_string());
                 let result_type = self.convert_type(expr.ty);
 
+                // @:fixed(N) arrays are inline storage, not Array objects
+                if let HirExprKind::Field { object, .. } = &callee.kind {
+                    if let Some(layout) = self.fixed_array_field(object) {
+                        self.add_error(
+                            &format!(
+                                "@:fixed({}) field '{}' only supports indexing and .length",
+                                layout.fixed_len.unwrap_or(0),
+                                layout.name
+                            ),
+                            expr.source_location,
+                        );
+                        return None;
+                    }
+                }
+
                 // Convert HIR type_args to IrType for use in CallDirect
                 let converted_hir_type_args: Vec<IrType> = hir_type_args
                     .iter()
@@ -5778,6 +7010,23 @@ impl<'a> HirToMirContext<'a> {
                         .get_symbol(*symbol)
                         .and_then(|s| self.string_interner.get(s.name))
                         .map(|s| s.to_string());
+                    // Synthetic layout(): shared layout descriptor of the owning class
+                    if callee_name.as_deref() == Some("layout") {
+                        let owner = self.current_hir_types.values().find_map(|decl| match decl {
+                            HirTypeDecl::Class(c)
+                                if c.methods.iter().any(|m| m.function.symbol_id == *symbol) =>
+                            {
+                                Some(c.symbol_id)
+                            }
+                            _ => None,
+                        });
+                        if let Some(descriptor) =
+                            owner.and_then(|class| self.struct_layout_descriptor(class))
+                        {
+                            return self.builder.build_const(IrValue::String(descriptor));
+                        }
+                    }
+
                     // @:gpuStruct synthetic static methods: gpuDef/gpuSize/gpuAlignment
                     if matches!(
                         callee_name.as_deref(),
@@ -5929,6 +7178,22 @@ impl<'a> HirToMirContext<'a> {
                         method_name, field, in_local, in_external
                     );
 
+                    // Synthetic layout() — shared layout descriptor string
+                    if method_name == Some("layout") {
+                        let class = {
+                            let type_table = self.type_table.borrow();
+                            type_table.get(object.ty).and_then(|t| match &t.kind {
+                                TypeKind::Class { symbol_id, .. } => Some(*symbol_id),
+                                _ => None,
+                            })
+                        };
+                        if let Some(descriptor) =
+                            class.and_then(|class| self.struct_layout_descriptor(class))
+                        {
+                            return self.builder.build_const(IrValue::String(descriptor));
+                        }
+                    }
+
                     // @:cstruct synthetic cdef() method — return C typedef string
                     if method_name == Some("cdef") {
                         let obj_type = object.ty;
@@ -6312,7 +7577,16 @@ impl<'a> HirToMirContext<'a> {
                             };
 
                             // Register and call the function (MIR wrapper or extern)
-                            let call_result = if is_mir_wrapper {
+                            let call_result = if runtime_func == "haxe_string_char_code_at_ptr"
+                                && arg_regs.len() == 2
+                            {
+                                // charCodeAt/cca: inline byte load, no call
+                                self.build_string_char_code_at(
+                                    arg_regs[0],
+                                    arg_regs[1],
+                                    actual_return_type.clone(),
+                                )?
+                            } else if is_mir_wrapper {
                                 let mir_func_id = self.register_stdlib_mir_forward_ref(
                                     runtime_func,
                                     param_types,
@@ -7037,6 +8311,17 @@ impl<'a> HirToMirContext<'a> {
                                                 result_type.clone()
                                             };
 
+                                            if runtime_func == "haxe_string_char_code_at_ptr"
+                                                && method_arg_regs.len() == 1
+                                                && matches!(return_type, IrType::I32 | IrType::I64)
+                                            {
+                                                return self.build_string_char_code_at(
+                                                    obj_reg,
+                                                    method_arg_regs[0],
+                                                    return_type,
+                                                );
+                                            }
+
                                             let runtime_func_id = self
                                                 .get_or_register_extern_function(
                                                     runtime_func,
@@ -7201,7 +8486,7 @@ impl<'a> HirToMirContext<'a> {
                         }
                         drop(type_table);
 
-                        // eprintln!("WARNING: Method {:?} not found in function_map", field);
+                        trace!("Method {:?} not found in function_map", field);
                     }
                 }
 
@@ -7525,6 +8810,35 @@ impl<'a> HirToMirContext<'a> {
                                     class_name, method_name, runtime_func, is_instance_method, is_mir_wrapper
                                 );
 
+                                if class_name == "Array"
+                                    && Self::typed_array_method_arity(runtime_func)
+                                        == Some(param_count)
+                                {
+                                    return self.lower_typed_array_method(
+                                        runtime_func,
+                                        receiver,
+                                        &args[1],
+                                        &result_type,
+                                    );
+                                }
+                                if class_name == "Array"
+                                    && runtime_func == "array_push"
+                                    && self.array_holds_value_structs(receiver.ty)
+                                {
+                                    return self.lower_value_struct_array_push(receiver, &args[1]);
+                                }
+                                if class_name == "List"
+                                    && Self::typed_list_method_arity(runtime_func)
+                                        == Some(param_count)
+                                {
+                                    return self.lower_typed_list_method(
+                                        runtime_func,
+                                        receiver,
+                                        &args[1..],
+                                        &result_type,
+                                    );
+                                }
+
                                 // MIR wrapper path: use register_stdlib_mir_forward_ref
                                 // MIR wrappers (SIMD4f, Thread, Channel, etc.) are compiled by
                                 // Cranelift alongside user code. They must NOT be registered as
@@ -7724,6 +9038,11 @@ impl<'a> HirToMirContext<'a> {
                                                 result_type.clone(),
                                             )
                                         }
+                                        IrType::String => self.builder.build_cast(
+                                            call_result,
+                                            IrType::U64,
+                                            IrType::String,
+                                        ),
                                         _ => {
                                             // Unresolved T or Dynamic: keep as I64
                                             self.builder.build_cast(
@@ -7750,8 +9069,8 @@ impl<'a> HirToMirContext<'a> {
                     if symbol_name == "trace" && args.len() == 1 {
                         let arg = &args[0];
 
-                        // Check if arg is a class or enum type
-                        // For classes: try to call toString() method
+                        // Check if arg is a class, interface or enum type
+                        // For classes and interfaces: convert via toString()
                         // For enums: for now, fall through to traceAny (enum toString not yet implemented)
                         let type_table = self.type_table.borrow();
                         let type_kind = type_table.get(arg.ty).map(|ti| ti.kind.clone());
@@ -7795,6 +9114,10 @@ impl<'a> HirToMirContext<'a> {
                                 } else {
                                     Some(class_name_str.to_string())
                                 }
+                            } else if let Some(crate::tast::core::TypeKind::Interface { .. }) =
+                                &type_kind
+                            {
+                                Some(String::new())
                             } else {
                                 None
                             };
@@ -7834,10 +9157,10 @@ impl<'a> HirToMirContext<'a> {
                                                 );
 
                                             // Trace the string
-                                            return self.builder.build_call_direct(
+                                            return self.build_trace_call(
                                                 string_trace_id,
                                                 vec![string_ptr],
-                                                IrType::Void,
+                                                &expr.source_location,
                                             );
                                         }
                                     }
@@ -7849,7 +9172,7 @@ impl<'a> HirToMirContext<'a> {
                         // Full variant name lookup for variables would require runtime RTTI
                         // Direct enum variant expressions (Color.Red) are handled above
 
-                        // If this is a class type, try to call toString() on it
+                        // If this is a class or interface type, convert it via toString()
                         if class_info.is_some() {
                             let obj_reg = self.lower_expression(arg)?;
                             if let Some(string_reg) = self.try_call_tostring(obj_reg, arg.ty)? {
@@ -7859,10 +9182,10 @@ impl<'a> HirToMirContext<'a> {
                                     vec![string_ptr_ty],
                                     IrType::Void,
                                 );
-                                return self.builder.build_call_direct(
+                                return self.build_trace_call(
                                     string_trace_id,
                                     vec![string_reg],
-                                    IrType::Void,
+                                    &expr.source_location,
                                 );
                             }
                         }
@@ -8010,7 +9333,7 @@ impl<'a> HirToMirContext<'a> {
                                             IrValue::I64(concrete_type_args.len() as i64),
                                         )?;
 
-                                        return self.builder.build_call_direct(
+                                        return self.build_trace_call(
                                             trace_typed_id,
                                             vec![
                                                 type_id_const,
@@ -8018,7 +9341,7 @@ impl<'a> HirToMirContext<'a> {
                                                 param_types_data,
                                                 param_count,
                                             ],
-                                            IrType::Void,
+                                            &expr.source_location,
                                         );
                                     }
 
@@ -8034,10 +9357,10 @@ impl<'a> HirToMirContext<'a> {
                                         IrType::Ptr(Box::new(IrType::I8)),
                                     )?;
 
-                                    return self.builder.build_call_direct(
+                                    return self.build_trace_call(
                                         trace_enum_boxed_id,
                                         vec![type_id_const, ptr_reg],
-                                        IrType::Void,
+                                        &expr.source_location,
                                     );
                                 } else {
                                     // Unboxed enum: arg_reg holds the discriminant (i64)
@@ -8048,10 +9371,10 @@ impl<'a> HirToMirContext<'a> {
                                         IrType::Void,
                                     );
 
-                                    return self.builder.build_call_direct(
+                                    return self.build_trace_call(
                                         trace_enum_id,
                                         vec![type_id_const, arg_reg],
-                                        IrType::Void,
+                                        &expr.source_location,
                                     );
                                 }
                             }
@@ -8101,10 +9424,10 @@ impl<'a> HirToMirContext<'a> {
                                 vec![IrType::Ptr(Box::new(IrType::Void))],
                                 IrType::Void,
                             );
-                            return self.builder.build_call_direct(
+                            return self.build_trace_call(
                                 trace_array_id,
                                 vec![arg_reg],
-                                IrType::Void,
+                                &expr.source_location,
                             );
                         }
 
@@ -8142,10 +9465,10 @@ impl<'a> HirToMirContext<'a> {
                                         IrType::Void,
                                     );
 
-                                    return self.builder.build_call_direct(
+                                    return self.build_trace_call(
                                         trace_typed_id,
                                         vec![arg_reg, tag_reg],
-                                        IrType::Void,
+                                        &expr.source_location,
                                     );
                                 }
                             }
@@ -8212,10 +9535,10 @@ impl<'a> HirToMirContext<'a> {
                                 param_types,
                                 IrType::Void,
                             );
-                            return self.builder.build_call_direct(
+                            return self.build_trace_call(
                                 string_trace_id,
                                 vec![arg_reg],
-                                IrType::Void,
+                                &expr.source_location,
                             );
                         }
 
@@ -8255,10 +9578,10 @@ impl<'a> HirToMirContext<'a> {
                         );
 
                         // Generate the call
-                        return self.builder.build_call_direct(
+                        return self.build_trace_call(
                             runtime_func_id,
                             vec![final_arg_reg],
-                            IrType::Void,
+                            &expr.source_location,
                         );
                     }
 
@@ -9017,6 +10340,35 @@ impl<'a> HirToMirContext<'a> {
                                 let needs_out_param = runtime_call.needs_out_param;
                                 let has_return = runtime_call.has_return; // Copy for use in fallback closure
 
+                                if class_name == "Array"
+                                    && Self::typed_array_method_arity(runtime_func)
+                                        == Some(method_param_count)
+                                {
+                                    return self.lower_typed_array_method(
+                                        runtime_func,
+                                        &args[0],
+                                        &args[1],
+                                        &result_type,
+                                    );
+                                }
+                                if class_name == "Array"
+                                    && runtime_func == "array_push"
+                                    && self.array_holds_value_structs(args[0].ty)
+                                {
+                                    return self.lower_value_struct_array_push(&args[0], &args[1]);
+                                }
+                                if class_name == "List"
+                                    && Self::typed_list_method_arity(runtime_func)
+                                        == Some(method_param_count)
+                                {
+                                    return self.lower_typed_list_method(
+                                        runtime_func,
+                                        &args[0],
+                                        &args[1..],
+                                        &result_type,
+                                    );
+                                }
+
                                 // SPECIAL CASE: Instance methods that need out parameter (like Array.slice, String.split)
                                 // These have void return but write result to first out parameter
                                 // Generate inline wrapper: allocate + call runtime + return pointer
@@ -9575,6 +10927,11 @@ impl<'a> HirToMirContext<'a> {
                                                     resolved_return_type.clone(),
                                                 )
                                             }
+                                            IrType::String => self.builder.build_cast(
+                                                raw_reg,
+                                                IrType::U64,
+                                                IrType::String,
+                                            ),
                                             _ => {
                                                 // Unresolved T (Ptr(Void)/Dynamic) or unknown: keep as I64
                                                 // so the raw value isn't misinterpreted as a pointer
@@ -10531,6 +11888,30 @@ impl<'a> HirToMirContext<'a> {
                                             debug!("[STATIC METHOD] Found stdlib runtime func: {}.{} -> {}, args.len()={}",
                                                 qual_name_str, method_name, runtime_func, args.len());
 
+                                            if parts.len() >= 2
+                                                && parts[parts.len() - 2] == "Lambda"
+                                            {
+                                                return self.lower_lambda_call(
+                                                    method_name,
+                                                    args,
+                                                    &result_type,
+                                                );
+                                            }
+                                            if runtime_func == "array_sort" && args.len() == 2 {
+                                                // haxe.ds.ArraySort.sort(a, cmp)
+                                                return self.lower_typed_array_method(
+                                                    runtime_func,
+                                                    &args[0],
+                                                    &args[1],
+                                                    &result_type,
+                                                );
+                                            }
+                                            if runtime_func == "haxe_vector_from_array"
+                                                && args.len() == 1
+                                            {
+                                                return self.lower_vector_from_array(&args[0]);
+                                            }
+
                                             // Get the expected signature from our registered extern functions
                                             // This ensures we use the correct types (e.g., I64 for Std.random)
                                             let (expected_param_types, expected_return_type) = self
@@ -10598,7 +11979,8 @@ impl<'a> HirToMirContext<'a> {
                                             // Inject type_id for enum methods that need it
                                             let mut final_arg_regs = final_arg_regs;
                                             if (runtime_func == "haxe_type_enum_constructor"
-                                                || runtime_func == "haxe_type_enum_parameters")
+                                                || runtime_func == "haxe_type_enum_parameters"
+                                                || runtime_func == "haxe_type_enum_eq")
                                                 && !args.is_empty()
                                             {
                                                 if let Some(type_id) =
@@ -10664,6 +12046,14 @@ impl<'a> HirToMirContext<'a> {
                                         }
                                     }
 
+                                    if runtime_func_name == "haxe_reflect_compare_methods" {
+                                        self.unbox_compare_methods_args(
+                                            args,
+                                            &mut arg_regs,
+                                            &mut arg_types,
+                                        );
+                                    }
+
                                     // Special case: Reflect.compare → haxe_reflect_compare_typed
                                     // Same logic as the qualified-name path: detect argument type and
                                     // append a type_tag parameter to avoid boxing.
@@ -10775,7 +12165,8 @@ impl<'a> HirToMirContext<'a> {
                                     // Inject type_id for enum methods that need it
                                     let mut final_arg_regs = final_arg_regs;
                                     if (runtime_func_name == "haxe_type_enum_constructor"
-                                        || runtime_func_name == "haxe_type_enum_parameters")
+                                        || runtime_func_name == "haxe_type_enum_parameters"
+                                        || runtime_func_name == "haxe_type_enum_eq")
                                         && !args.is_empty()
                                     {
                                         if let Some(type_id) =
@@ -11100,7 +12491,12 @@ impl<'a> HirToMirContext<'a> {
                             }
 
                             // Fill in default values for any missing optional parameters
-                            self.fill_default_args(func_id, &mut arg_regs, true);
+                            self.fill_default_args(
+                                func_id,
+                                &mut arg_regs,
+                                true,
+                                expr.source_location,
+                            );
 
                             // Extract type_args from receiver's class type for generic method calls
                             let ir_type_args = if !args.is_empty() {
@@ -11281,7 +12677,12 @@ impl<'a> HirToMirContext<'a> {
                             }
 
                             // Fill in default values for any missing optional parameters
-                            self.fill_default_args(func_id, &mut arg_regs, false);
+                            self.fill_default_args(
+                                func_id,
+                                &mut arg_regs,
+                                false,
+                                expr.source_location,
+                            );
 
                             // Auto-box arguments when expected type is Ptr(U8) but actual is primitive
                             // This handles cases like Type.enumIndex(Color.Red) where the enum discriminant
@@ -11326,7 +12727,8 @@ impl<'a> HirToMirContext<'a> {
                                     .map(|f| f.name.clone())
                                     .unwrap_or_default();
                                 if (func_name == "haxe_type_enum_constructor"
-                                    || func_name == "haxe_type_enum_parameters")
+                                    || func_name == "haxe_type_enum_parameters"
+                                    || func_name == "haxe_type_enum_eq")
                                     && !args.is_empty()
                                 {
                                     if let Some(type_id) =
@@ -11489,10 +12891,12 @@ impl<'a> HirToMirContext<'a> {
                                     self.builder.call_label =
                                         Some(format!("STATIC_SEARCH:{}", class_name));
                                     let runtime_name = mapping.runtime_name;
-                                    // eprintln!(
-                                    //     "INFO: {} static method detected: {} (runtime: {})",
-                                    //     class_name, method_name, runtime_name
-                                    // );
+                                    trace!(
+                                        "INFO: {} static method detected: {} (runtime: {})",
+                                        class_name,
+                                        method_name,
+                                        runtime_name
+                                    );
 
                                     // Lower arguments and get their types
                                     let mut arg_regs = Vec::new();
@@ -11504,6 +12908,14 @@ impl<'a> HirToMirContext<'a> {
                                         }
                                     }
 
+                                    if runtime_name == "haxe_reflect_compare_methods" {
+                                        self.unbox_compare_methods_args(
+                                            args,
+                                            &mut arg_regs,
+                                            &mut arg_types,
+                                        );
+                                    }
+
                                     // Reflect.compare: use haxe_reflect_compare_typed which accepts
                                     // raw type-erased i64 values + a type tag, avoiding boxing.
                                     // For generic code, the type tag is a placeholder resolved at
@@ -11931,50 +13343,8 @@ impl<'a> HirToMirContext<'a> {
                 // MONOMORPHIZATION: For generic extern classes like Vec<T>, monomorphize the class name
                 // based on type arguments. Vec<Int> -> VecI32, Vec<Float> -> VecF64, etc.
                 // Use hir_type_args directly (from HIR) instead of type_table lookup (which may fail for extern classes)
-                let monomorphized_class_name: Option<String> = if let Some(base_name) = class_name {
-                    if base_name == "Vec" && !hir_type_args.is_empty() {
-                        // Get the first type argument and determine the monomorphized suffix
-                        let first_arg = hir_type_args[0];
-                        let type_table = self.type_table.borrow();
-                        let suffix = if let Some(arg_type) = type_table.get(first_arg) {
-                            match &arg_type.kind {
-                                crate::tast::TypeKind::Int => Some("I32"),
-                                crate::tast::TypeKind::Float => Some("F64"),
-                                crate::tast::TypeKind::Bool => Some("Bool"),
-                                crate::tast::TypeKind::String => Some("Ptr"),
-                                crate::tast::TypeKind::Class { symbol_id, .. } => {
-                                    // Check if it's Int64 (a class type representing 64-bit int)
-                                    if let Some(class_info) =
-                                        self.symbol_table.get_symbol(*symbol_id)
-                                    {
-                                        if let Some(name) =
-                                            self.string_interner.get(class_info.name)
-                                        {
-                                            if name == "Int64" {
-                                                Some("I64")
-                                            } else {
-                                                Some("Ptr") // Other classes are reference types
-                                            }
-                                        } else {
-                                            Some("Ptr")
-                                        }
-                                    } else {
-                                        Some("Ptr")
-                                    }
-                                }
-                                _ => Some("Ptr"),
-                            }
-                        } else {
-                            Some("Ptr") // If type not found, default to Ptr variant
-                        };
-                        drop(type_table);
-                        suffix.map(|s| format!("Vec{}", s))
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                };
+                let monomorphized_class_name = class_name
+                    .and_then(|base_name| self.monomorphized_class_name(base_name, hir_type_args));
 
                 // Use monomorphized name if available, otherwise use original class name
                 let final_class_name = monomorphized_class_name.as_deref().or(class_name);
@@ -12053,11 +13423,30 @@ impl<'a> HirToMirContext<'a> {
                     if let Some((wrapper_name, needs_out_param, is_mir_wrapper)) = constructor_info
                     {
                         // Lower arguments
-                        let arg_regs: Vec<_> = args
+                        let mut arg_regs: Vec<_> = args
                             .iter()
                             .filter_map(|a| self.lower_expression(a))
                             .collect();
 
+                        // EnumValueMap hashes its keys structurally: pass the key
+                        // enum's runtime type id
+                        if wrapper_name == "haxe_enummap_new" {
+                            let key_type = hir_type_args.first().copied().or_else(|| {
+                                let type_table = self.type_table.borrow();
+                                match &type_table.get(*class_type)?.kind {
+                                    crate::tast::TypeKind::Class { type_args, .. }
+                                    | crate::tast::TypeKind::GenericInstance {
+                                        type_args, ..
+                                    } => type_args.first().copied(),
+                                    _ => None,
+                                }
+                            });
+                            let type_id = key_type
+                                .and_then(|ty| self.enum_runtime_type_id(ty))
+                                .unwrap_or(0);
+                            arg_regs.push(self.builder.build_const(IrValue::I32(type_id as i32))?);
+                        }
+
                         // Register forward ref if not already present
                         let param_types: Vec<IrType> = arg_regs
                             .iter()
@@ -12207,8 +13596,12 @@ impl<'a> HirToMirContext<'a> {
                                 self.builder.build_store(cap_field, zero_i64);
                             }
                         }
-                        // Set elem_size field to 8 bytes (offset 24) - assume pointer size for now
-                        if let Some(elem_size_val) = self.builder.build_const(IrValue::I64(8)) {
+                        // Set elem_size field (offset 24): 8 bytes, or the struct size
+                        // for @:struct elements, which are stored inline
+                        let elem_size = self.array_elem_size(*class_type);
+                        if let Some(elem_size_val) =
+                            self.builder.build_const(IrValue::I64(elem_size))
+                        {
                             if let Some(index_3) = self.builder.build_const(IrValue::I32(3)) {
                                 if let Some(elem_size_field) =
                                     self.builder
@@ -12224,10 +13617,11 @@ impl<'a> HirToMirContext<'a> {
                     return Some(array_ptr);
                 }
 
-                // @:cstruct CLASS: flat C-compatible allocation (no object header)
-                if self.is_cstruct_class(*class_type) {
+                // @:cstruct / @:struct CLASS: flat C-compatible allocation (no object header)
+                if self.has_flat_layout(*class_type) {
                     if let Some(layout) = self.get_or_compute_cstruct_layout(*class_type) {
-                        let obj_ptr = self.build_heap_alloc(layout.total_size as u64)?;
+                        let obj_ptr = self
+                            .build_heap_alloc(layout.total_size as u64, layout.alignment as u64)?;
 
                         // Zero-initialize all bytes via first field store of 0
                         // (memset-style zero init would be better but this works)
@@ -12241,6 +13635,13 @@ impl<'a> HirToMirContext<'a> {
                                     offset_const,
                                     IrType::Ptr(Box::new(IrType::U8)),
                                 )?;
+                                // Nested structs are embedded inline: zero all their bytes
+                                if let Some(size) = field.embedded_size {
+                                    let size_const =
+                                        self.builder.build_const(IrValue::I64(size as i64))?;
+                                    self.builder.build_memset(field_ptr, zero, size_const);
+                                    continue;
+                                }
                                 let zero_val = match &field.ir_type {
                                     IrType::F64 => self.builder.build_const(IrValue::F64(0.0))?,
                                     IrType::I64 => self.builder.build_const(IrValue::I64(0))?,
@@ -12276,7 +13677,8 @@ impl<'a> HirToMirContext<'a> {
                 // Uses 4-byte floats, 4-byte ints — matches Metal/CUDA struct layout
                 if self.is_gpu_struct_class(*class_type) {
                     if let Some(layout) = self.get_or_compute_gpu_struct_layout(*class_type) {
-                        let obj_ptr = self.build_heap_alloc(layout.total_size as u64)?;
+                        let obj_ptr = self
+                            .build_heap_alloc(layout.total_size as u64, layout.alignment as u64)?;
 
                         // Zero-initialize all fields
                         if let Some(_zero) = self.builder.build_const(IrValue::I32(0)) {
@@ -12329,8 +13731,9 @@ impl<'a> HirToMirContext<'a> {
                     .and_then(|tid| self.class_alloc_sizes.get(&tid).copied())
                     .unwrap_or_else(|| ((args.len() as u64 + 1) * 8).max(16));
 
-                // Use heap allocation (malloc) for class instances
-                let obj_ptr = self.build_heap_alloc(obj_size);
+                // Use heap allocation (malloc) for class instances.
+                // Instance fields are stored in 8-byte slots.
+                let obj_ptr = self.build_heap_alloc(obj_size, 8);
                 let obj_ptr = obj_ptr?;
 
                 // Store object header: runtime type_id at GEP index 0
@@ -12375,7 +13778,12 @@ impl<'a> HirToMirContext<'a> {
                         .collect();
 
                     // Fill in default values for any missing optional parameters
-                    self.fill_default_args(constructor_func_id, &mut arg_regs, true);
+                    self.fill_default_args(
+                        constructor_func_id,
+                        &mut arg_regs,
+                        true,
+                        expr.source_location,
+                    );
 
                     // Constructor returns void, so we ignore the result
                     self.builder
@@ -12392,7 +13800,7 @@ impl<'a> HirToMirContext<'a> {
                         }
                     }
                 } else {
-                    // eprintln!("WARNING: Constructor not found for TypeId {:?}", class_type);
+                    trace!("Constructor not found for TypeId {:?}", class_type);
                 }
 
                 Some(obj_ptr)
@@ -12439,6 +13847,20 @@ impl<'a> HirToMirContext<'a> {
 
                         // Store the new value back to the operand
                         match &operand.kind {
+                            HirExprKind::Variable { symbol, .. }
+                                if self.static_locals.contains_key(symbol) =>
+                            {
+                                let (key, ty) = self.static_locals[symbol];
+                                self.build_static_local_store(key, ty, new_value);
+                            }
+                            HirExprKind::Variable { symbol, .. }
+                                if !self.symbol_map.contains_key(symbol)
+                                    && self.global_symbol_map.contains_key(symbol) =>
+                            {
+                                // Static field or module-level variable
+                                self.builder
+                                    .build_store_global(self.global_symbol_map[symbol], new_value);
+                            }
                             HirExprKind::Variable { symbol, .. } => {
                                 // If we're inside a lambda with captured variables, also store back to environment
                                 if let Some(ref env_layout) = self.current_env_layout {
@@ -12543,6 +13965,13 @@ impl<'a> HirToMirContext<'a> {
                     _ => {}
                 }
 
+                // Enum values with parameters compare by constructor and parameters
+                if matches!(op, HirBinaryOp::Eq | HirBinaryOp::Ne) {
+                    if let Some(result) = self.lower_enum_equality(*op, lhs, rhs) {
+                        return result;
+                    }
+                }
+
                 // Special handling for string concatenation with +
                 if matches!(op, HirBinaryOp::Add) {
                     let lhs_type_raw = self.convert_type(lhs.ty);
@@ -13118,30 +14547,32 @@ impl<'a> HirToMirContext<'a> {
                             self.builder.build_cast(value_reg, from_type, to_type)
                         } else {
                             // Downcast or unrelated: runtime check via object header.
-                            // haxe_safe_downcast_class reads type_id from offset 0,
-                            // walks the hierarchy, returns obj_ptr or null.
-                            // Use SymbolId-based TypeId to match TYPE_REGISTRY and object header.
-                            let target_type_id = tgt_sym.as_raw() as i64;
-                            let type_id_const =
-                                self.builder.build_const(IrValue::I64(target_type_id))?;
-                            let ptr_u8 = IrType::Ptr(Box::new(IrType::U8));
-                            let downcast_func = self.get_or_register_extern_function(
-                                "haxe_safe_downcast_class",
-                                vec![ptr_u8.clone(), IrType::I64],
-                                ptr_u8.clone(),
-                            );
-                            self.builder.build_call_direct(
-                                downcast_func,
-                                vec![value_reg, type_id_const],
-                                ptr_u8,
-                            )
+                            // Throws a catchable error if the object is not an instance.
+                            self.build_checked_class_cast(value_reg, tgt_sym)
                         }
                     }
 
-                    // Class/Interface: pass-through (interface dispatch handles vtable)
-                    (Some(TypeKind::Class { .. }), Some(TypeKind::Interface { .. }))
-                    | (Some(TypeKind::Interface { .. }), Some(TypeKind::Class { .. }))
-                    | (Some(TypeKind::Interface { .. }), Some(TypeKind::Interface { .. })) => {
+                    // Interface → Class: check the object behind the fat pointer
+                    (
+                        Some(TypeKind::Interface { .. }),
+                        Some(TypeKind::Class {
+                            symbol_id: tgt_sym, ..
+                        }),
+                    ) => {
+                        let tgt_sym = *tgt_sym;
+                        let value_reg = self.lower_expression(expr)?;
+                        let obj_reg = self.build_interface_object(value_reg)?;
+                        self.build_checked_class_cast(obj_reg, tgt_sym)
+                    }
+
+                    // Class → Interface: wrap in the interface's fat pointer
+                    (Some(TypeKind::Class { .. }), Some(TypeKind::Interface { .. })) => {
+                        let value_reg = self.lower_expression(expr)?;
+                        Some(self.maybe_wrap_for_interface(value_reg, expr.ty, *target))
+                    }
+
+                    // Interface → Interface: pass-through (interface dispatch handles vtable)
+                    (Some(TypeKind::Interface { .. }), Some(TypeKind::Interface { .. })) => {
                         let value_reg = self.lower_expression(expr)?;
                         self.builder.build_cast(value_reg, from_type, to_type)
                     }
@@ -13233,29 +14664,50 @@ impl<'a> HirToMirContext<'a> {
                             // Target is subclass of source (downcast) →
                             // runtime check via object header
                             let value_reg = self.lower_expression(expr)?;
-                            let target_type_id = tgt_sym.as_raw() as i64;
-                            let type_id_const =
-                                self.builder.build_const(IrValue::I64(target_type_id))?;
-                            let ptr_void = IrType::Ptr(Box::new(IrType::Void));
-                            let is_func = self.get_or_register_extern_function(
-                                "haxe_object_is_instance",
-                                vec![ptr_void, IrType::I64],
-                                IrType::I64,
-                            );
-                            let result_i64 = self.builder.build_call_direct(
-                                is_func,
-                                vec![value_reg, type_id_const],
-                                IrType::I64,
-                            )?;
-                            // Convert i64 (0/1) to Bool
-                            let zero = self.builder.build_const(IrValue::I64(0))?;
-                            self.builder.build_cmp(CompareOp::Ne, result_i64, zero)
+                            self.build_instance_check(value_reg, tgt_sym)
                         } else {
                             // Unrelated classes → false
                             let _value = self.lower_expression(expr);
                             self.builder.build_const(IrValue::Bool(false))
                         }
                     }
+                    // Class → Interface: statically known unless only a subclass
+                    // could implement it
+                    (
+                        Some(TypeKind::Class {
+                            symbol_id: src_sym, ..
+                        }),
+                        Some(TypeKind::Interface {
+                            symbol_id: iface_sym,
+                            ..
+                        }),
+                    ) => {
+                        let (src_sym, iface_sym) = (*src_sym, *iface_sym);
+                        let value_reg = self.lower_expression(expr)?;
+                        if self.class_implements_interface(src_sym, iface_sym) {
+                            self.builder.build_const(IrValue::Bool(true))
+                        } else {
+                            self.build_instance_check(value_reg, iface_sym)
+                        }
+                    }
+                    // Interface source: check the object behind the fat pointer
+                    (
+                        Some(TypeKind::Interface { .. }),
+                        Some(TypeKind::Class {
+                            symbol_id: tgt_sym, ..
+                        }),
+                    )
+                    | (
+                        Some(TypeKind::Interface { .. }),
+                        Some(TypeKind::Interface {
+                            symbol_id: tgt_sym, ..
+                        }),
+                    ) => {
+                        let tgt_sym = *tgt_sym;
+                        let value_reg = self.lower_expression(expr)?;
+                        let obj_reg = self.build_interface_object(value_reg)?;
+                        self.build_instance_check(obj_reg, tgt_sym)
+                    }
                     // Class vs primitive or other unrelated → false
                     (Some(TypeKind::Class { .. }), Some(TypeKind::Int))
                     | (Some(TypeKind::Class { .. }), Some(TypeKind::Float))
@@ -13269,7 +14721,7 @@ impl<'a> HirToMirContext<'a> {
                         self.builder.build_const(IrValue::Bool(false))
                     }
                     // Fallback: lower the expression (for side effects) and return true
-                    // (trust static types — proper runtime checks need object headers)
+                    // (trust static types)
                     _ => {
                         let _value = self.lower_expression(expr);
                         self.builder.build_const(IrValue::Bool(true))
@@ -13299,7 +14751,7 @@ impl<'a> HirToMirContext<'a> {
                 self.lower_lambda(params, body, captures, expr.ty)
             }
 
-            HirExprKind::Array { elements } => self.lower_array_literal(elements),
+            HirExprKind::Array { elements } => self.lower_array_literal(elements, expr.ty),
 
             HirExprKind::Map { entries } => self.lower_map_literal(entries),
 
@@ -13325,8 +14777,8 @@ impl<'a> HirToMirContext<'a> {
                 // 'super' should only appear in constructor super calls, which are handled
                 // specially in lower_constructor_body. If we reach here, it's likely being
                 // used incorrectly (e.g., super.method() which isn't supported yet)
-                // eprintln!("WARNING: HirExprKind::Super encountered in expression lowering");
-                // eprintln!("  This might be super.field or super.method() which isn't implemented yet");
+                trace!("HirExprKind::Super encountered in expression lowering");
+                trace!("  This might be super.field or super.method() which isn't implemented yet");
                 // For now, treat it like 'this' (same object, but calling parent methods)
                 self.symbol_map.get(&SymbolId::from_raw(0)).copied()
             }
@@ -13436,16 +14888,7 @@ impl<'a> HirToMirContext<'a> {
                 if !catch_handlers.is_empty() {
                     let mut next_test_block: Option<IrBlockId> = None;
 
-                    for (i, handler) in catch_handlers.iter().enumerate() {
-                        let catch_type_kind = {
-                            let type_table = self.type_table.borrow();
-                            type_table
-                                .get(handler.exception_type)
-                                .map(|t| t.kind.clone())
-                        };
-                        let is_dynamic =
-                            matches!(catch_type_kind, Some(crate::tast::TypeKind::Dynamic));
-
+                    for handler in catch_handlers {
                         let catch_body_block = match self.builder.create_block() {
                             Some(b) => b,
                             None => return None,
@@ -13455,27 +14898,22 @@ impl<'a> HirToMirContext<'a> {
                             self.builder.switch_to_block(test_block);
                         }
 
-                        if is_dynamic || i == catch_handlers.len() - 1 {
-                            self.builder.build_branch(catch_body_block);
-                            next_test_block = None;
-                        } else {
-                            let expected_type_id = self.runtime_type_id(handler.exception_type);
-                            let expected_const = self
-                                .builder
-                                .build_const(IrValue::I32(expected_type_id as i32))
-                                .expect("const");
-                            let type_match = self
-                                .builder
-                                .build_cmp(CompareOp::Eq, exc_type_id, expected_const)
-                                .expect("cmp");
-
-                            let next_block = self.builder.create_block().expect("create block");
-                            self.builder.build_cond_branch(
-                                type_match,
-                                catch_body_block,
-                                next_block,
-                            );
-                            next_test_block = Some(next_block);
+                        match self.catch_test(handler.exception_type, handler.exception_var) {
+                            CatchTest::All => {
+                                self.builder.build_branch(catch_body_block);
+                                next_test_block = None;
+                            }
+                            test => {
+                                let type_match =
+                                    self.build_catch_test(exc_type_id, &test).expect("cmp");
+                                let next_block = self.builder.create_block().expect("create block");
+                                self.builder.build_cond_branch(
+                                    type_match,
+                                    catch_body_block,
+                                    next_block,
+                                );
+                                next_test_block = Some(next_block);
+                            }
                         }
 
                         // --- catch body ---
@@ -13489,19 +14927,19 @@ impl<'a> HirToMirContext<'a> {
                         self.builder.build_branch(continuation_block);
                     }
 
-                    // Fallthrough if no catch matched
+                    // No catch matched: run finally, then propagate
                     if let Some(fallthrough_block) = next_test_block {
                         self.builder.switch_to_block(fallthrough_block);
                         if let Some(finally_body) = &finally_expr {
                             self.lower_expression(finally_body);
                         }
-                        self.builder.build_branch(continuation_block);
+                        self.build_rethrow();
                     }
                 } else {
                     if let Some(finally_body) = &finally_expr {
                         self.lower_expression(finally_body);
                     }
-                    self.builder.build_branch(continuation_block);
+                    self.build_rethrow();
                 }
 
                 // --- continuation ---
@@ -13585,6 +15023,20 @@ impl<'a> HirToMirContext<'a> {
         if let Some(cond_reg) = self.lower_expression(condition) {
             self.builder
                 .build_cond_branch(cond_reg, then_block, else_block);
+            if let Some(&likely) = self.branch_hints.get(&condition.source_location) {
+                let hint = if likely {
+                    super::OptimizationHint::LikelyPath
+                } else {
+                    super::OptimizationHint::UnlikelyPath
+                };
+                if let Some(block) = self
+                    .builder
+                    .current_function_mut()
+                    .and_then(|f| f.cfg.get_block_mut(then_block))
+                {
+                    block.metadata.optimization_hints.push(hint);
+                }
+            }
 
             // Lower then branch
             self.builder.switch_to_block(then_block);
@@ -13753,6 +15205,20 @@ impl<'a> HirToMirContext<'a> {
             cond_block, body_block, exit_block
         );
 
+        // LoopUnrollPass reads `@:unroll(N)` off the loop header
+        if let Some(&factor) = self.unroll_hints.get(&condition.source_location) {
+            if let Some(block) = self
+                .builder
+                .current_function_mut()
+                .and_then(|f| f.cfg.get_block_mut(cond_block))
+            {
+                block
+                    .metadata
+                    .optimization_hints
+                    .push(super::OptimizationHint::Unroll { factor });
+            }
+        }
+
         // Save the entry block (current block before loop)
         let entry_block = if let Some(block_id) = self.builder.current_block() {
             block_id
@@ -14471,7 +15937,9 @@ impl<'a> HirToMirContext<'a> {
                     });
                     if let Some(ref nn) = native_name {
                         match nn.as_str() {
-                            "rayzor::SIMD4f" => return IrType::vector(IrType::F32, 4),
+                            "rayzor::SIMD4f" | "rayzor::simd::Float32x4" => {
+                                return IrType::vector(IrType::F32, 4)
+                            }
                             _ => {}
                         }
                     }
@@ -14660,36 +16128,196 @@ impl<'a> HirToMirContext<'a> {
     /// than the function expects. `arg_regs` already contains the lowered arguments
     /// (possibly including implicit 'this' for methods/constructors).
     /// `has_implicit_this` indicates whether arg_regs[0] is an implicit 'this' pointer
-    /// that is NOT part of the user-visible params.
+    /// that is NOT part of the user-visible params. `location` is the call site,
+    /// used for an implicit `haxe.PosInfos` argument.
     fn fill_default_args(
         &mut self,
         func_id: IrFunctionId,
         arg_regs: &mut Vec<IrId>,
         has_implicit_this: bool,
+        location: SourceLocation,
     ) {
         // Clone defaults to release immutable borrow before calling lower_expression
-        let defaults = match self.function_param_defaults.get(&func_id) {
-            Some(d) => d.clone(),
-            None => return,
-        };
+        let defaults = self
+            .function_param_defaults
+            .get(&func_id)
+            .cloned()
+            .unwrap_or_default();
 
-        let user_arg_count = if has_implicit_this {
-            arg_regs.len().saturating_sub(1)
-        } else {
-            arg_regs.len()
-        };
+        // A trailing `?pos:haxe.PosInfos` is filled with the call site's position
+        let pos_infos_param = self
+            .function_param_hir_types
+            .get(&func_id)
+            .and_then(|types| types.last().map(|&ty| (types.len() - 1, ty)))
+            .filter(|&(_, ty)| self.is_pos_infos_type(ty));
+
+        let this_count = usize::from(has_implicit_this);
+        let user_arg_count = arg_regs.len().saturating_sub(this_count);
+        let param_count = defaults
+            .len()
+            .max(pos_infos_param.map_or(0, |(index, _)| index + 1));
 
-        if user_arg_count >= defaults.len() {
+        if user_arg_count >= param_count {
             return; // All args provided
         }
 
-        for i in user_arg_count..defaults.len() {
-            if let Some(ref default_expr) = defaults[i] {
+        for i in user_arg_count..param_count {
+            if let Some(Some(default_expr)) = defaults.get(i) {
                 if let Some(reg) = self.lower_expression(default_expr) {
                     arg_regs.push(reg);
                 }
+            } else if let Some((index, ty)) = pos_infos_param {
+                // Only if every earlier argument is present
+                if i == index && arg_regs.len().saturating_sub(this_count) == index {
+                    if let Some(reg) = self.build_pos_infos(ty, location) {
+                        arg_regs.push(reg);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether `ty` is `haxe.PosInfos` (or `Null<haxe.PosInfos>`), the magic
+    /// type of an implicit call-site position argument
+    fn is_pos_infos_type(&self, ty: TypeId) -> bool {
+        let type_table = self.type_table.borrow();
+        let mut current = ty;
+        if let Some(TypeKind::Optional { inner_type }) = type_table.get(current).map(|t| &t.kind) {
+            current = *inner_type;
+        }
+        let Some(TypeKind::TypeAlias { symbol_id, .. }) = type_table.get(current).map(|t| &t.kind)
+        else {
+            return false;
+        };
+        self.symbol_table.get_symbol(*symbol_id).is_some_and(|sym| {
+            match sym.qualified_name.and_then(|q| self.string_interner.get(q)) {
+                Some(qualified) => qualified == "haxe.PosInfos",
+                None => self.string_interner.get(sym.name) == Some("PosInfos"),
+            }
+        })
+    }
+
+    /// `(class, method)` names of the function being lowered, as in `haxe.PosInfos`
+    fn current_pos_names(&self) -> (String, String) {
+        let Some(sym) = self
+            .current_function_symbol
+            .and_then(|id| self.symbol_table.get_symbol(id))
+        else {
+            return (String::new(), String::new());
+        };
+        let method = self.string_interner.get(sym.name).unwrap_or("").to_string();
+        let class = sym
+            .qualified_name
+            .and_then(|q| self.string_interner.get(q))
+            .and_then(|q| q.rsplit_once('.'))
+            .map(|(class, _)| class.to_string())
+            .unwrap_or_default();
+        (class, method)
+    }
+
+    /// Build the `haxe.PosInfos` object of the current call site
+    fn build_pos_infos(&mut self, ty: TypeId, location: SourceLocation) -> Option<IrId> {
+        let (class_name, method_name) = self.current_pos_names();
+        let file_name = self.builder.module.source_file.clone();
+
+        let (alias_ty, string_ty, int_ty) = {
+            let type_table = self.type_table.borrow();
+            let alias_ty = match type_table.get(ty).map(|t| &t.kind) {
+                Some(TypeKind::Optional { inner_type }) => *inner_type,
+                _ => ty,
+            };
+            (alias_ty, type_table.string_type(), type_table.int_type())
+        };
+        let lifetime = crate::tast::LifetimeId::from_raw(1); // Static lifetime for constants
+        let literal = |kind: HirLiteral, ty: TypeId| HirExpr {
+            kind: HirExprKind::Literal(kind),
+            ty,
+            lifetime,
+            source_location: location,
+        };
+        let string = |value: &str| {
+            literal(
+                HirLiteral::String(self.string_interner.intern(value)),
+                string_ty,
+            )
+        };
+        let fields = vec![
+            (self.string_interner.intern("fileName"), string(&file_name)),
+            (
+                self.string_interner.intern("lineNumber"),
+                literal(HirLiteral::Int(location.line as i64), int_ty),
+            ),
+            (
+                self.string_interner.intern("className"),
+                string(&class_name),
+            ),
+            (
+                self.string_interner.intern("methodName"),
+                string(&method_name),
+            ),
+        ];
+        self.lower_object_literal(&fields, alias_ty)
+    }
+
+    /// `Class.method:line` of `line` in the current function, or `file:line`
+    /// outside a class
+    fn position_string(&self, line: u32) -> String {
+        let (class_name, method_name) = self.current_pos_names();
+        if class_name.is_empty() {
+            format!("{}:{}", self.builder.module.source_file, line)
+        } else {
+            format!("{}.{}:{}", class_name, method_name, line)
+        }
+    }
+
+    /// Call the runtime trace function `trace_fn` for a `trace()` at
+    /// `location`. With trace positions compiled in, the position is handed
+    /// over right before the call, after the argument was evaluated, so a
+    /// trace nested in the argument can't take it.
+    fn build_trace_call(
+        &mut self,
+        trace_fn: IrFunctionId,
+        args: Vec<IrId>,
+        location: &SourceLocation,
+    ) -> Option<IrId> {
+        if self.trace_positions {
+            let position = self.position_string(location.line);
+            let trace_pos = self.get_or_register_extern_function(
+                "haxe_trace_pos",
+                vec![IrType::Ptr(Box::new(IrType::String))],
+                IrType::Void,
+            );
+            if let Some(position) = self.builder.build_string(position) {
+                self.builder
+                    .build_call_direct(trace_pos, vec![position], IrType::Void);
             }
         }
+        self.builder.build_call_direct(trace_fn, args, IrType::Void)
+    }
+
+    /// Report a field access at `field_ptr` to ThreadSanitizer, with the
+    /// current `Class.method:line` as the access site
+    fn build_tsan_access(&mut self, field_ptr: IrId, is_write: bool) {
+        if !self.sanitize_thread {
+            return;
+        }
+        let site = self.position_string(self.builder.source_location().line);
+        let hook = self.get_or_register_extern_function(
+            if is_write {
+                "rayzor_tsan_write"
+            } else {
+                "rayzor_tsan_read"
+            },
+            vec![
+                IrType::Ptr(Box::new(IrType::U8)),
+                IrType::Ptr(Box::new(IrType::String)),
+            ],
+            IrType::Void,
+        );
+        if let Some(site) = self.builder.build_string(site) {
+            self.builder
+                .build_call_direct(hook, vec![field_ptr, site], IrType::Void);
+        }
     }
 
     fn build_function_signature(&self, func: &HirFunction) -> super::IrFunctionSignature {
@@ -14828,11 +16456,11 @@ impl<'a> HirToMirContext<'a> {
 
     fn ensure_terminator(&mut self) {
         let is_term = self.is_terminated();
-        // eprintln!(
-        //     "DEBUG ensure_terminator: is_terminated={}, current_func={:?}",
-        //     is_term,
-        //     self.builder.current_function().map(|f| &f.name)
-        // );
+        trace!(
+            "ensure_terminator: is_terminated={}, current_func={:?}",
+            is_term,
+            self.builder.current_function().map(|f| &f.name)
+        );
         if !is_term {
             // debug!("ensure_terminator: Adding implicit return(None)");
             self.builder.build_return(None);
@@ -14850,25 +16478,25 @@ impl<'a> HirToMirContext<'a> {
         }
     }
 
-    /// If `type_id` resolves to a Class with a `toString()` in the current module,
-    /// call `obj.toString()` and return the resulting `*HaxeString` register.
+    /// If `type_id` resolves to a class or interface, convert `obj` to a string
+    /// via its `toString()` and return the resulting `*HaxeString` register.
+    /// Classes without a `toString()` use the runtime's `ClassName@address` form.
     /// Returns `Some(string_reg)` on success, `None` if not a class or toString not found.
     fn try_call_tostring(&mut self, obj_reg: IrId, type_id: TypeId) -> Option<Option<IrId>> {
         // Get the class symbol_id from the type_table
         let class_symbol = {
             let type_table = self.type_table.borrow();
-            type_table.get(type_id).and_then(|ti| {
-                if let TypeKind::Class { symbol_id, .. } = &ti.kind {
-                    Some(*symbol_id)
-                } else {
-                    None
-                }
-            })
+            match type_table.get(type_id).map(|ti| &ti.kind) {
+                Some(TypeKind::Class { symbol_id, .. }) => Some(*symbol_id),
+                Some(TypeKind::Interface { .. }) => None,
+                _ => return Some(None), // Not a class type
+            }
         };
 
-        let class_symbol = match class_symbol {
-            Some(s) => s,
-            None => return Some(None), // Not a class type
+        // Interface values: format the object behind the fat pointer
+        let Some(class_symbol) = class_symbol else {
+            let obj = self.build_interface_object(obj_reg)?;
+            return Some(Some(self.build_object_to_string(obj)?));
         };
 
         // Find the toString method symbol for THIS specific class by scanning HIR type declarations
@@ -14891,6 +16519,10 @@ impl<'a> HirToMirContext<'a> {
         }
 
         if let Some(tostring_symbol) = tostring_symbol {
+            // Overridden toString(): dispatch on the object's runtime class
+            if self.virtual_dispatch_info.contains_key(&tostring_symbol) {
+                return Some(Some(self.build_object_to_string(obj_reg)?));
+            }
             // User class with toString() in HIR — look up compiled function
             if let Some(tostring_id) = self.function_map.get(&tostring_symbol).copied() {
                 let string_ptr_ty = IrType::Ptr(Box::new(IrType::String));
@@ -14936,9 +16568,51 @@ impl<'a> HirToMirContext<'a> {
             }
         }
 
+        // Other Haxe classes: inherited toString() or the default form, resolved
+        // from the object header at runtime. Extern classes have no header.
+        let is_extern = self
+            .symbol_table
+            .get_symbol(class_symbol)
+            .is_some_and(|s| s.flags.contains(crate::tast::symbols::SymbolFlags::EXTERN));
+        if !is_extern {
+            return Some(Some(self.build_object_to_string(obj_reg)?));
+        }
+
         Some(None) // No toString() found
     }
 
+    /// Runtime `toString()` of a class instance: the class's own or inherited
+    /// `toString()` if any, else `ClassName@address` ("null" for null).
+    fn build_object_to_string(&mut self, obj_reg: IrId) -> Option<IrId> {
+        let ptr_u8 = IrType::Ptr(Box::new(IrType::U8));
+        let string_ptr_ty = IrType::Ptr(Box::new(IrType::String));
+        let to_string_func = self.get_or_register_extern_function(
+            "haxe_object_to_string",
+            vec![ptr_u8],
+            string_ptr_ty.clone(),
+        );
+        self.builder
+            .build_call_direct(to_string_func, vec![obj_reg], string_ptr_ty)
+    }
+
+    /// Instance `toString()` methods declared by classes in the current module,
+    /// as `(class, method)` pairs.
+    fn class_tostring_methods(&self) -> Vec<(SymbolId, SymbolId)> {
+        let mut methods = Vec::new();
+        for (_tid, type_decl) in self.current_hir_types.iter() {
+            if let HirTypeDecl::Class(class) = type_decl {
+                let tostring = class.methods.iter().find(|method| {
+                    !method.is_static
+                        && self.string_interner.get(method.function.name) == Some("toString")
+                });
+                if let Some(method) = tostring {
+                    methods.push((class.symbol_id, method.function.symbol_id));
+                }
+            }
+        }
+        methods
+    }
+
     /// Convert a value to a string pointer
     /// Uses the appropriate *_to_string MIR wrapper based on the source type
     fn convert_to_string(&mut self, value: IrId, from_type: &IrType) -> Option<IrId> {
@@ -15699,6 +17373,104 @@ impl<'a> HirToMirContext<'a> {
             .unwrap_or(value)
     }
 
+    /// Whether binding the result of `expr` must copy it: true for @:struct
+    /// values read from a variable, field, array element or `this`, which
+    /// would otherwise alias their source. Fresh values (`new`, calls) are
+    /// moved.
+    fn needs_value_struct_copy(&self, expr: &HirExpr) -> bool {
+        matches!(
+            expr.kind,
+            HirExprKind::Variable { .. }
+                | HirExprKind::Field { .. }
+                | HirExprKind::Index { .. }
+                | HirExprKind::This
+        ) && self.is_value_struct_class(expr.ty)
+    }
+
+    /// Give each @:struct parameter its own copy of the argument, so the
+    /// callee can't modify the caller's value
+    fn copy_value_struct_params(&mut self, params: &[HirParam]) {
+        for param in params {
+            if !self.is_value_struct_class(param.ty) {
+                continue;
+            }
+            let Some(&arg) = self.symbol_map.get(&param.symbol_id) else {
+                continue;
+            };
+            let copy = self.copy_value_struct(arg, param.ty);
+            self.symbol_map.insert(param.symbol_id, copy);
+            if self.type_needs_drop(param.ty) {
+                self.register_owned_value(param.symbol_id, copy);
+            }
+        }
+    }
+
+    /// Copy a @:struct value into a fresh allocation of its flat layout.
+    /// Returns the original value for any other type.
+    fn copy_value_struct(&mut self, value: IrId, value_ty: TypeId) -> IrId {
+        if !self.is_value_struct_class(value_ty) {
+            return value;
+        }
+        let Some(layout) = self.get_or_compute_cstruct_layout(value_ty) else {
+            return value;
+        };
+        let Some(copy) = self.build_heap_alloc(layout.total_size as u64, layout.alignment as u64)
+        else {
+            return value;
+        };
+        let Some(size) = self
+            .builder
+            .build_const(IrValue::I64(layout.total_size as i64))
+        else {
+            return value;
+        };
+        self.builder.build_memcpy(copy, value, size);
+        copy
+    }
+
+    /// Whether `array_ty` is an `Array<T>` of a @:struct class, whose
+    /// elements are stored inline in the buffer
+    fn array_holds_value_structs(&self, array_ty: TypeId) -> bool {
+        let element_ty = {
+            let type_table = self.type_table.borrow();
+            match type_table.get(array_ty).map(|t| &t.kind) {
+                Some(TypeKind::Array { element_type }) => *element_type,
+                _ => return false,
+            }
+        };
+        self.is_value_struct_class(element_ty)
+    }
+
+    /// Byte size of one element slot of an `Array<T>` buffer
+    fn array_elem_size(&mut self, array_ty: TypeId) -> i64 {
+        let element_ty = {
+            let type_table = self.type_table.borrow();
+            match type_table.get(array_ty).map(|t| &t.kind) {
+                Some(TypeKind::Array { element_type }) => *element_type,
+                _ => return 8,
+            }
+        };
+        if !self.is_value_struct_class(element_ty) {
+            return 8;
+        }
+        self.get_or_compute_cstruct_layout(element_ty)
+            .map_or(8, |layout| layout.total_size.max(1) as i64)
+    }
+
+    /// Free a @:struct value produced by `expr` once its bytes were copied
+    /// somewhere else, if `expr` allocated it (`new`, calls)
+    fn free_fresh_value_struct(&mut self, expr: &HirExpr, value: IrId) {
+        if matches!(
+            &expr.kind,
+            HirExprKind::New { .. } | HirExprKind::Call { .. }
+        ) && self.is_value_struct_class(expr.ty)
+            && self.type_needs_drop(expr.ty)
+            && !self.is_terminated()
+        {
+            self.builder.build_free(value);
+        }
+    }
+
     /// Try to register a structural subtyping view for an anonymous-typed variable.
     /// If source is a class or wider anon and target is a narrower anonymous type,
     /// records an AnonBacking so field access can be redirected at compile time.
@@ -16999,7 +18771,21 @@ impl<'a> HirToMirContext<'a> {
         match lvalue {
             HirLValue::Variable(symbol) => {
                 // Look up the variable in our symbol map
-                self.symbol_map.get(symbol).copied()
+                if let Some(reg) = self.symbol_map.get(symbol).copied() {
+                    return Some(reg);
+                }
+                if let Some(&(key, ty)) = self.static_locals.get(symbol) {
+                    return self.build_static_local_load(key, ty);
+                }
+                let global_id = self.static_global(*symbol)?;
+                let global_type = self
+                    .builder
+                    .module
+                    .globals
+                    .get(&global_id)
+                    .map(|g| g.ty.clone())
+                    .unwrap_or(IrType::Any);
+                self.builder.build_load_global(global_id, global_type)
             }
             HirLValue::Field { object, field } => {
                 // Read object.field
@@ -17015,6 +18801,9 @@ impl<'a> HirToMirContext<'a> {
             }
             HirLValue::Index { object, index } => {
                 // Read object[index]
+                if let Some(layout) = self.fixed_array_field(object) {
+                    return self.lower_fixed_array_read(object, index, &layout);
+                }
                 if let Some(obj_reg) = self.lower_expression(object) {
                     if let Some(idx_reg) = self.lower_expression(index) {
                         let elem_ty = object.ty; // Use object's type for now
@@ -17032,8 +18821,13 @@ impl<'a> HirToMirContext<'a> {
     fn lower_lvalue_write(&mut self, lvalue: &HirLValue, value: IrId) {
         match lvalue {
             HirLValue::Variable(symbol) => {
+                if let Some(&(key, ty)) = self.static_locals.get(symbol) {
+                    self.build_static_local_store(key, ty, value);
+                    return;
+                }
+
                 // Check if this is a global variable first
-                if let Some(&global_id) = self.global_symbol_map.get(symbol) {
+                if let Some(global_id) = self.static_global(*symbol) {
                     debug!(
                         "[GLOBAL STORE] Storing to global {:?} -> {:?}",
                         symbol, global_id
@@ -17082,6 +18876,24 @@ impl<'a> HirToMirContext<'a> {
             HirLValue::Field { object, field } => {
                 // Write object.field = value
                 if let Some(obj_reg) = self.lower_expression(object) {
+                    // Raw-slot stdlib property setter (sys.thread.Tls.value = v)
+                    if let Some(setter) = self.stdlib_property_setter(*field, object.ty) {
+                        let value_ty = self.builder.get_register_type(value).unwrap_or(IrType::I64);
+                        if let Some(raw) = self.ir_value_to_raw_slot(value, value_ty) {
+                            let setter_id = self.get_or_register_extern_function(
+                                setter,
+                                vec![IrType::Ptr(Box::new(IrType::Void)), IrType::I64],
+                                IrType::Void,
+                            );
+                            self.builder.build_call_direct(
+                                setter_id,
+                                vec![obj_reg, raw],
+                                IrType::Void,
+                            );
+                        }
+                        return;
+                    }
+
                     // Check if this is a property with a custom setter
                     if let Some(property_info) = self.property_access_map.get(field) {
                         match &property_info.setter {
@@ -17322,9 +19134,9 @@ impl<'a> HirToMirContext<'a> {
                         });
 
                     if let Some(field_index) = field_index_opt {
-                        // @:cstruct: use byte-offset PtrAdd instead of GEP
+                        // @:cstruct / @:struct: use byte-offset PtrAdd instead of GEP
                         let obj_type_id = object.ty;
-                        if self.is_cstruct_class(obj_type_id) {
+                        if self.has_flat_layout(obj_type_id) {
                             if let Some(layout) = self.get_or_compute_cstruct_layout(obj_type_id) {
                                 let field_layout = layout
                                     .fields
@@ -17350,7 +19162,18 @@ impl<'a> HirToMirContext<'a> {
                                             IrType::Ptr(Box::new(IrType::U8)),
                                         );
                                         if let Some(field_ptr) = field_ptr {
-                                            self.builder.build_store(field_ptr, value);
+                                            // Embedded nested struct: copy its bytes in
+                                            if let Some(size) = fl.embedded_size {
+                                                if let Some(size_const) = self
+                                                    .builder
+                                                    .build_const(IrValue::I64(size as i64))
+                                                {
+                                                    self.builder
+                                                        .build_memcpy(field_ptr, value, size_const);
+                                                }
+                                            } else {
+                                                self.builder.build_store(field_ptr, value);
+                                            }
                                         }
                                     }
                                     return;
@@ -17464,6 +19287,7 @@ impl<'a> HirToMirContext<'a> {
                                 } else {
                                     value
                                 };
+                                self.build_tsan_access(field_ptr, true);
                                 self.builder.build_store(field_ptr, store_value);
                             }
                         }
@@ -17577,11 +19401,44 @@ impl<'a> HirToMirContext<'a> {
             }
             HirLValue::Index { object, index } => {
                 // Write object[index] = value
+                // @:fixed(N) arrays: store straight into the inline element
+                if let Some(layout) = self.fixed_array_field(object) {
+                    if let Some(elem_ptr) = self.lower_fixed_array_elem_ptr(object, index, &layout)
+                    {
+                        let value = match self.builder.get_register_type(value) {
+                            Some(ty) if ty != layout.ir_type => self
+                                .builder
+                                .build_cast(value, ty, layout.ir_type.clone())
+                                .unwrap_or(value),
+                            _ => value,
+                        };
+                        self.builder.build_store(elem_ptr, value);
+                    }
+                    return;
+                }
                 // Use typed array set functions to pass values directly,
                 // avoiding DynamicValue boxing via haxe_box_*_ptr which
                 // would corrupt array storage (DynamicValue.type_id stored instead of value)
                 if let Some(obj_reg) = self.lower_expression(object) {
                     if let Some(idx_reg) = self.lower_expression(index) {
+                        // @:struct elements: copy the value's bytes into the slot
+                        if self.array_holds_value_structs(object.ty) {
+                            let func_id = self.get_or_register_extern_function(
+                                "haxe_array_set",
+                                vec![
+                                    IrType::Ptr(Box::new(IrType::Void)),
+                                    IrType::I64,
+                                    IrType::Ptr(Box::new(IrType::U8)),
+                                ],
+                                IrType::Bool,
+                            );
+                            self.builder.build_call_direct(
+                                func_id,
+                                vec![obj_reg, idx_reg, value],
+                                IrType::Bool,
+                            );
+                            return;
+                        }
                         let value_ir_type = self.builder.get_register_type(value);
                         match &value_ir_type {
                             Some(IrType::F32) | Some(IrType::F64) => {
@@ -17794,6 +19651,21 @@ impl<'a> HirToMirContext<'a> {
                 runtime_func
             );
 
+            // Raw-slot properties (sys.thread.Tls.value): the runtime returns the
+            // value's 8-byte bits, reinterpreted as the field type
+            if runtime_call.returns_raw_value {
+                let getter = self.get_or_register_extern_function(
+                    runtime_func,
+                    vec![IrType::Ptr(Box::new(IrType::Void))],
+                    IrType::I64,
+                );
+                let raw = self
+                    .builder
+                    .build_call_direct(getter, vec![obj], IrType::I64)?;
+                let value_ty = self.convert_type(field_ty);
+                return self.raw_slot_to_value(raw, &value_ty);
+            }
+
             // Determine result type based on whether it returns a primitive or complex type
             // If needs_out_param is false and has_return is true, it returns a primitive (i32/i64/f64)
             // Otherwise it returns a complex type (Ptr) or void
@@ -17809,6 +19681,10 @@ impl<'a> HirToMirContext<'a> {
                     Some(crate::tast::TypeKind::Int) => IrType::I64,
                     Some(crate::tast::TypeKind::Float) => IrType::F64,
                     Some(crate::tast::TypeKind::Bool) => IrType::Bool,
+                    // Field type unresolved (e.g. read through a typedef): trust the mapping
+                    _ if runtime_call.return_type.is_some() => {
+                        runtime_call.return_type.as_ref().unwrap().to_ir_type()
+                    }
                     _ => {
                         warn!(
                             "Unexpected field kind {:?} for primitive-returning function {}",
@@ -18169,11 +20045,11 @@ impl<'a> HirToMirContext<'a> {
             field_ir_ty.clone()
         };
 
-        // @:cstruct: use byte-offset PtrAdd instead of index-based GEP
+        // @:cstruct / @:struct: use byte-offset PtrAdd instead of index-based GEP
         // Check both class_type_id (from field_index_map) and receiver_ty (expression type)
-        let cstruct_type = if self.is_cstruct_class(class_type_id) {
+        let cstruct_type = if self.has_flat_layout(class_type_id) {
             Some(class_type_id)
-        } else if self.is_cstruct_class(receiver_ty) {
+        } else if self.has_flat_layout(receiver_ty) {
             Some(receiver_ty)
         } else {
             None
@@ -18186,6 +20062,10 @@ impl<'a> HirToMirContext<'a> {
                         .build_const(IrValue::I64(field_layout.byte_offset as i64))?;
                     let byte_ptr_ty = IrType::Ptr(Box::new(IrType::U8));
                     let field_ptr = self.builder.build_ptr_add(obj, offset_const, byte_ptr_ty)?;
+                    // Embedded nested struct: its value is its address
+                    if field_layout.embedded_size.is_some() {
+                        return Some(field_ptr);
+                    }
                     let field_value = self.builder.build_load(field_ptr, field_ir_ty.clone())?;
                     self.builder.set_register_type(field_value, field_ir_ty);
                     return Some(field_value);
@@ -18201,6 +20081,9 @@ impl<'a> HirToMirContext<'a> {
                         let byte_ptr_ty = IrType::Ptr(Box::new(IrType::U8));
                         let field_ptr =
                             self.builder.build_ptr_add(obj, offset_const, byte_ptr_ty)?;
+                        if field_layout.embedded_size.is_some() {
+                            return Some(field_ptr);
+                        }
                         let field_value =
                             self.builder.build_load(field_ptr, field_ir_ty.clone())?;
                         self.builder.set_register_type(field_value, field_ir_ty);
@@ -18268,6 +20151,7 @@ impl<'a> HirToMirContext<'a> {
             .build_gep(obj, vec![index_const], gep_element_ty.clone())?;
 
         // Load the value from the field pointer
+        self.build_tsan_access(field_ptr, false);
         let field_value = self.builder.build_load(field_ptr, gep_element_ty.clone())?;
 
         // Type erasure coercion: if field was loaded as I64 (erased type param),
@@ -18721,6 +20605,7 @@ impl<'a> HirToMirContext<'a> {
             .build_gep(obj, vec![index_const], field_ir_ty.clone())?;
 
         // Load the value from the field pointer
+        self.build_tsan_access(field_ptr, false);
         let field_value = self.builder.build_load(field_ptr, field_ir_ty.clone())?;
 
         // Register the type of the loaded value
@@ -18760,6 +20645,11 @@ impl<'a> HirToMirContext<'a> {
             IrType::Ptr(Box::new(IrType::U8)),
         )?;
 
+        // @:struct elements are stored inline: the element is its address
+        if self.is_value_struct_class(ty) {
+            return Some(elem_ptr);
+        }
+
         // Determine the correct load type based on the element type.
         // This preserves type information so trace and other consumers
         // can dispatch correctly (e.g., String elements print as strings).
@@ -19000,17 +20890,17 @@ impl<'a> HirToMirContext<'a> {
 
         // Snapshot symbol_map before branches
         let symbol_map_before = self.symbol_map.clone();
-        // eprintln!(
-        //     "DEBUG lower_conditional: symbol_map has {} entries before condition",
-        //     symbol_map_before.len()
-        // );
+        trace!(
+            "lower_conditional: symbol_map has {} entries before condition",
+            symbol_map_before.len()
+        );
 
         // Evaluate condition
         let cond_val = self.lower_expression(cond)?;
-        // eprintln!(
-        //     "DEBUG lower_conditional: After evaluating condition, in block {:?}",
-        //     self.builder.current_block()
-        // );
+        trace!(
+            "lower_conditional: After evaluating condition, in block {:?}",
+            self.builder.current_block()
+        );
 
         // Branch based on condition
         self.builder
@@ -19095,11 +20985,11 @@ impl<'a> HirToMirContext<'a> {
             self.builder.switch_to_block(else_end_block);
             self.builder.build_branch(merge_block)?;
         }
-        // eprintln!(
-        //     "DEBUG lower_conditional: else_end_block = {:?}, symbol_map has {} entries",
-        //     else_end_block,
-        //     symbol_map_after_else.len()
-        // );
+        trace!(
+            "lower_conditional: else_end_block = {:?}, symbol_map has {} entries",
+            else_end_block,
+            symbol_map_after_else.len()
+        );
 
         // If both branches terminated, no merge block needed
         if then_terminated && else_terminated {
@@ -19114,47 +21004,44 @@ impl<'a> HirToMirContext<'a> {
         // Find variables that were modified in either branch
         let mut modified_symbols = std::collections::HashSet::new();
         // debug!("Checking for modified symbols");
-        // eprintln!("  symbol_map_before: {} entries", symbol_map_before.len());
-        // eprintln!(
-        //     "  symbol_map_after_then: {} entries",
-        //     symbol_map_after_then.len()
-        // );
-        // eprintln!(
-        //     "  symbol_map_after_else: {} entries",
-        //     symbol_map_after_else.len()
-        // );
+        trace!("  symbol_map_before: {} entries", symbol_map_before.len());
+        trace!(
+            "  symbol_map_after_then: {} entries",
+            symbol_map_after_then.len()
+        );
+        trace!(
+            "  symbol_map_after_else: {} entries",
+            symbol_map_after_else.len()
+        );
 
         for (sym, reg_after_then) in &symbol_map_after_then {
             if symbol_map_before.get(sym) != Some(reg_after_then) {
-                // eprintln!(
-                //     "  Modified in then branch: {:?} (before: {:?}, after: {:?})",
-                //     sym,
-                //     symbol_map_before.get(sym),
-                //     reg_after_then
-                // );
+                trace!(
+                    "  Modified in then branch: {:?} (before: {:?}, after: {:?})",
+                    sym,
+                    symbol_map_before.get(sym),
+                    reg_after_then
+                );
                 modified_symbols.insert(*sym);
             }
         }
         for (sym, reg_after_else) in &symbol_map_after_else {
             if symbol_map_before.get(sym) != Some(reg_after_else) {
-                // eprintln!(
-                //     "  Modified in else branch: {:?} (before: {:?}, after: {:?})",
-                //     sym,
-                //     symbol_map_before.get(sym),
-                //     reg_after_else
-                // );
+                trace!(
+                    "  Modified in else branch: {:?} (before: {:?}, after: {:?})",
+                    sym,
+                    symbol_map_before.get(sym),
+                    reg_after_else
+                );
                 modified_symbols.insert(*sym);
             }
         }
         // debug!("Found {} modified symbols", modified_symbols.len());
 
         // Create phi nodes for modified variables
-        // eprintln!(
-        //     "DEBUG: Creating phi nodes for {} symbols",
-        //     modified_symbols.len()
-        // );
+        trace!("Creating phi nodes for {} symbols", modified_symbols.len());
         for symbol_id in &modified_symbols {
-            // eprintln!("  Processing symbol {:?}", symbol_id);
+            trace!("  Processing symbol {:?}", symbol_id);
             let before_reg = symbol_map_before.get(symbol_id).copied();
             let then_reg = symbol_map_after_then.get(symbol_id).copied();
             let else_reg = symbol_map_after_else.get(symbol_id).copied();
@@ -19169,14 +21056,15 @@ impl<'a> HirToMirContext<'a> {
                     .map(|local| local.ty.clone())
             }) {
                 Some(t) => {
-                    // eprintln!("  Found type {:?} for symbol {:?}", t, symbol_id);
+                    trace!("  Found type {:?} for symbol {:?}", t, symbol_id);
                     t
                 }
                 None => {
-                    // eprintln!(
-                    //     "  No type found for symbol {:?} (tried {:?}), skipping",
-                    //     symbol_id, type_lookup_reg
-                    // );
+                    trace!(
+                        "  No type found for symbol {:?} (tried {:?}), skipping",
+                        symbol_id,
+                        type_lookup_reg
+                    );
                     continue;
                 }
             };
@@ -19188,41 +21076,46 @@ impl<'a> HirToMirContext<'a> {
 
             // Skip if we can't provide values from all active branches
             if (!then_terminated && !has_then_value) || (!else_terminated && !has_else_value) {
-                // eprintln!("  Skipping phi for {:?} - not in all branches", symbol_id);
+                trace!("  Skipping phi for {:?} - not in all branches", symbol_id);
                 continue;
             }
 
             let sample_reg = then_reg.or(else_reg).or(before_reg).unwrap();
 
             // Create phi node
-            // eprintln!(
-            //     "  Creating phi for {:?} with type {:?}",
-            //     symbol_id, var_type
-            // );
+            trace!(
+                "  Creating phi for {:?} with type {:?}",
+                symbol_id,
+                var_type
+            );
             let phi_reg = match self.builder.build_phi(merge_block, var_type.clone()) {
                 Some(r) => r,
                 None => {
-                    // eprintln!("  Failed to create phi node");
+                    trace!("  Failed to create phi node");
                     continue;
                 }
             };
-            // eprintln!("  Created phi node {:?}", phi_reg);
+            trace!("  Created phi node {:?}", phi_reg);
 
             // Add incoming edges for non-terminated branches
             // IMPORTANT: Only add phi incoming if the variable exists in that branch
             // Don't use variables from other branches (causes domination errors)
-            // eprintln!(
-            //     "  Adding phi incoming: then_terminated={}, else_terminated={}",
-            //     then_terminated, else_terminated
-            // );
+            trace!(
+                "  Adding phi incoming: then_terminated={}, else_terminated={}",
+                then_terminated,
+                else_terminated
+            );
             if !then_terminated {
                 // Use then_reg if it exists, otherwise before_reg
                 // Do NOT use else_reg here - it would violate SSA dominance
                 if let Some(val) = then_reg.or(before_reg) {
-                    // eprintln!(
-                    //     "  Calling add_phi_incoming(merge={:?}, phi={:?}, from={:?}, val={:?})",
-                    //     merge_block, phi_reg, then_end_block, val
-                    // );
+                    trace!(
+                        "  Calling add_phi_incoming(merge={:?}, phi={:?}, from={:?}, val={:?})",
+                        merge_block,
+                        phi_reg,
+                        then_end_block,
+                        val
+                    );
                     self.builder
                         .add_phi_incoming(merge_block, phi_reg, then_end_block, val);
                     // {
@@ -19238,10 +21131,13 @@ impl<'a> HirToMirContext<'a> {
                 // Use else_reg if it exists, otherwise before_reg
                 // Do NOT use then_reg here - it would violate SSA dominance
                 if let Some(val) = else_reg.or(before_reg) {
-                    // eprintln!(
-                    //     "  Calling add_phi_incoming(merge={:?}, phi={:?}, from={:?}, val={:?})",
-                    //     merge_block, phi_reg, else_end_block, val
-                    // );
+                    trace!(
+                        "  Calling add_phi_incoming(merge={:?}, phi={:?}, from={:?}, val={:?})",
+                        merge_block,
+                        phi_reg,
+                        else_end_block,
+                        val
+                    );
                     self.builder
                         .add_phi_incoming(merge_block, phi_reg, else_end_block, val);
                     // {
@@ -19276,11 +21172,11 @@ impl<'a> HirToMirContext<'a> {
 
         // Create phi for expression result if both branches returned values
         let mut result_phi = None;
-        // eprintln!(
-        //     "DEBUG: Checking if need result phi: then_val={:?}, else_val={:?}",
-        //     then_val.is_some(),
-        //     else_val.is_some()
-        // );
+        trace!(
+            "Checking if need result phi: then_val={:?}, else_val={:?}",
+            then_val.is_some(),
+            else_val.is_some()
+        );
         // Only create result phi if BOTH branches return values (for expression-style ifs)
         // If only one returns a value, that's a type error - skip result phi
         if then_val.is_some() && else_val.is_some() {
@@ -19297,17 +21193,19 @@ impl<'a> HirToMirContext<'a> {
                 }
             };
 
-            // eprintln!(
-            //     "DEBUG: Adding result phi incoming: then_term={}, else_term={}",
-            //     then_terminated, else_terminated
-            // );
+            trace!(
+                "Adding result phi incoming: then_term={}, else_term={}",
+                then_terminated,
+                else_terminated
+            );
             // Both branches returned values, so add phi incoming from both
             if !then_terminated {
                 let val = then_val.unwrap(); // Safe because we checked is_some() above
-                                             // eprintln!(
-                                             //     "DEBUG:   Adding from then: block={:?}, val={:?}",
-                                             //     then_end_block, val
-                                             // );
+                trace!(
+                    "Adding from then: block={:?}, val={:?}",
+                    then_end_block,
+                    val
+                );
                 self.builder
                     .add_phi_incoming(merge_block, result, then_end_block, val);
                 // {
@@ -19317,10 +21215,11 @@ impl<'a> HirToMirContext<'a> {
             }
             if !else_terminated {
                 let val = else_val.unwrap(); // Safe because we checked is_some() above
-                                             // eprintln!(
-                                             //     "DEBUG:   Adding from else: block={:?}, val={:?}",
-                                             //     else_end_block, val
-                                             // );
+                trace!(
+                    "Adding from else: block={:?}, val={:?}",
+                    else_end_block,
+                    val
+                );
                 self.builder
                     .add_phi_incoming(merge_block, result, else_end_block, val);
             }
@@ -19636,8 +21535,8 @@ impl<'a> HirToMirContext<'a> {
                             .unwrap_or_else(|| type_table.dynamic_type());
                         Some((type_table.string_type(), value_type))
                     }
-                    Some("ObjectMap") => {
-                        // ObjectMap<K, V> has two type args
+                    Some("ObjectMap") | Some("WeakMap") | Some("EnumValueMap") => {
+                        // ObjectMap, WeakMap and EnumValueMap have two type args (K, V)
                         let key_type = type_args
                             .first()
                             .copied()
@@ -19714,6 +21613,15 @@ impl<'a> HirToMirContext<'a> {
             return;
         }
 
+        // Native Lists iterate over a snapshot of their elements as a HaxeArray
+        if let Some(element_type) = self.list_element_type(iter_expr.ty) {
+            let Some(list_array) = self.lower_iterable_as_array(iter_expr) else {
+                return;
+            };
+            self.lower_for_in_over_array(pattern, list_array, element_type, body, label);
+            return;
+        }
+
         // For class/interface types with hasNext()/next() iterator protocol,
         // desugar to a while loop calling those methods directly.
         if let Some(ref kind) = iter_type_kind {
@@ -19825,6 +21733,11 @@ impl<'a> HirToMirContext<'a> {
 
         // Step 5: Build condition block - check if index < length
         self.builder.switch_to_block(loop_cond_block);
+        if self.debug_checks {
+            // Runs before every iteration and once more on exit, so a push/pop
+            // in the final iteration is caught too
+            self.build_array_iteration_check(collection, array_len, &iter_expr.source_location);
+        }
         let Some(current_index) = self.builder.build_load(index_ptr, IrType::I64) else {
             self.loop_stack.pop();
             return;
@@ -19904,6 +21817,88 @@ impl<'a> HirToMirContext<'a> {
         self.builder.switch_to_block(loop_exit_block);
     }
 
+    /// Debug-mode guard for Array for-in: abort with the loop's location if the
+    /// array length no longer matches the length captured when the loop started.
+    fn build_array_iteration_check(
+        &mut self,
+        collection: IrId,
+        expected_len: IrId,
+        location: &SourceLocation,
+    ) {
+        let ptr_void = IrType::Ptr(Box::new(IrType::Void));
+        let check_fn = self.get_or_register_extern_function(
+            "haxe_array_check_iteration",
+            vec![
+                ptr_void.clone(),
+                IrType::I64,
+                IrType::Ptr(Box::new(IrType::String)),
+                IrType::I32,
+                IrType::I32,
+            ],
+            IrType::Void,
+        );
+
+        let Some((file, line, column)) = self.build_check_location(location) else {
+            return;
+        };
+
+        self.builder.build_call_direct(
+            check_fn,
+            vec![collection, expected_len, file, line, column],
+            IrType::Void,
+        );
+    }
+
+    /// Debug-mode check after `arr.sort(f)`: the runtime re-runs the comparator
+    /// over the sorted result and aborts with this location if it is inconsistent.
+    fn build_sort_comparator_check(
+        &mut self,
+        collection: IrId,
+        closure: IrId,
+        kind: IrId,
+        location: &SourceLocation,
+    ) {
+        let ptr_void = IrType::Ptr(Box::new(IrType::Void));
+        let check_fn = self.get_or_register_extern_function(
+            "haxe_array_check_sorted",
+            vec![
+                ptr_void.clone(),
+                IrType::I64,
+                IrType::I64,
+                IrType::I32,
+                IrType::Ptr(Box::new(IrType::String)),
+                IrType::I32,
+                IrType::I32,
+            ],
+            IrType::Void,
+        );
+
+        let Some((fn_ptr, env_ptr)) = self.closure_parts(closure) else {
+            return;
+        };
+        let Some((file, line, column)) = self.build_check_location(location) else {
+            return;
+        };
+
+        self.builder.build_call_direct(
+            check_fn,
+            vec![collection, fn_ptr, env_ptr, kind, file, line, column],
+            IrType::Void,
+        );
+    }
+
+    /// `(file, line, column)` arguments for a debug-mode runtime check.
+    fn build_check_location(&mut self, location: &SourceLocation) -> Option<(IrId, IrId, IrId)> {
+        let file = self.builder.module.source_file.clone();
+        Some((
+            self.builder.build_string(file)?,
+            self.builder
+                .build_const(IrValue::I32(location.line as i32))?,
+            self.builder
+                .build_const(IrValue::I32(location.column as i32))?,
+        ))
+    }
+
     /// Lower a range-based for-in loop: `for (i in start...end) { body }`
     /// Desugars to: `var i = start; while (i < end) { body; i++; }`
     fn lower_for_in_range(
@@ -20640,7 +22635,30 @@ impl<'a> HirToMirContext<'a> {
         let Some(index_ptr) = self.builder.build_alloc(IrType::I64, None) else {
             return;
         };
-        self.builder.build_store(index_ptr, zero);
+        self.builder.build_store(index_ptr, zero);
+
+        // Outer variables assigned in the body live in stack slots across
+        // iterations (same scheme as the iterator-protocol loop)
+        let modified_vars = {
+            let mut modified = std::collections::HashSet::new();
+            for stmt in &body.statements {
+                self.find_modified_variables_in_statement(stmt, &mut modified);
+            }
+            modified
+        };
+        let mut var_slots: BTreeMap<SymbolId, (IrId, IrType)> = BTreeMap::new();
+        for sym in &modified_vars {
+            if let Some(&current_reg) = self.symbol_map.get(sym) {
+                let ty = self
+                    .builder
+                    .get_register_type(current_reg)
+                    .unwrap_or(IrType::I64);
+                if let Some(slot) = self.builder.build_alloc(ty.clone(), None) {
+                    self.builder.build_store(slot, current_reg);
+                    var_slots.insert(*sym, (slot, ty));
+                }
+            }
+        }
 
         // Create loop blocks
         let Some(loop_cond_block) = self.builder.create_block() else {
@@ -20683,6 +22701,11 @@ impl<'a> HirToMirContext<'a> {
 
         // Body block: get element, bind, execute body, increment
         self.builder.switch_to_block(loop_body_block);
+        for (sym, (slot, ty)) in &var_slots {
+            if let Some(loaded) = self.builder.build_load(*slot, ty.clone()) {
+                self.symbol_map.insert(*sym, loaded);
+            }
+        }
         let Some(idx_for_access) = self.builder.build_load(index_ptr, IrType::I64) else {
             self.loop_stack.pop();
             return;
@@ -20707,6 +22730,11 @@ impl<'a> HirToMirContext<'a> {
 
         // Increment index
         if !self.is_terminated() {
+            for (sym, (slot, _ty)) in &var_slots {
+                if let Some(&current_reg) = self.symbol_map.get(sym) {
+                    self.builder.build_store(*slot, current_reg);
+                }
+            }
             self.exit_drop_scope();
             let Some(idx_to_inc) = self.builder.build_load(index_ptr, IrType::I64) else {
                 self.loop_stack.pop();
@@ -20729,6 +22757,11 @@ impl<'a> HirToMirContext<'a> {
 
         self.loop_stack.pop();
         self.builder.switch_to_block(loop_exit_block);
+        for (sym, (slot, ty)) in &var_slots {
+            if let Some(loaded) = self.builder.build_load(*slot, ty.clone()) {
+                self.symbol_map.insert(*sym, loaded);
+            }
+        }
     }
 
     fn lower_switch_statement(&mut self, scrutinee: &HirExpr, cases: &[HirMatchCase]) {
@@ -20769,6 +22802,19 @@ impl<'a> HirToMirContext<'a> {
             None => return,
         };
 
+        // Switches over integer literals become a single Switch terminator,
+        // which backends lower to a jump table or a binary search
+        let is_int_scrutinee = self
+            .builder
+            .get_register_type(scrut_val)
+            .is_some_and(|ty| ty.is_integer());
+        if is_int_scrutinee {
+            if let Some(case_values) = Self::integer_switch_cases(cases) {
+                self.lower_integer_switch(scrut_val, cases, &case_values, continuation);
+                return;
+            }
+        }
+
         // Create blocks for each case
         let mut case_test_blocks = Vec::new();
         let mut case_body_blocks = Vec::new();
@@ -20888,6 +22934,81 @@ impl<'a> HirToMirContext<'a> {
         self.builder.switch_to_block(continuation);
     }
 
+    /// Case values of a switch whose cases all match integer literals, with
+    /// no guards and at most a trailing `default`/`_` case (which gets no
+    /// values). None if any case needs a general pattern test.
+    fn integer_switch_cases(cases: &[HirMatchCase]) -> Option<Vec<Vec<i64>>> {
+        let mut case_values = Vec::with_capacity(cases.len());
+        for (i, case) in cases.iter().enumerate() {
+            if case.guard.is_some() {
+                return None;
+            }
+            let is_default = match case.patterns.as_slice() {
+                [] | [HirPattern::Wildcard] => true,
+                _ => false,
+            };
+            if is_default {
+                if i + 1 != cases.len() {
+                    return None;
+                }
+                case_values.push(Vec::new());
+                continue;
+            }
+            let values = case
+                .patterns
+                .iter()
+                .map(|pattern| match pattern {
+                    HirPattern::Literal(HirLiteral::Int(value)) => Some(*value),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+            case_values.push(values);
+        }
+        Some(case_values)
+    }
+
+    /// Lower a switch over integer literals (see `integer_switch_cases`) to
+    /// a Switch terminator. A value listed by several cases goes to the first.
+    fn lower_integer_switch(
+        &mut self,
+        scrut_val: IrId,
+        cases: &[HirMatchCase],
+        case_values: &[Vec<i64>],
+        continuation: IrBlockId,
+    ) {
+        let mut body_blocks = Vec::with_capacity(cases.len());
+        for _ in cases {
+            match self.builder.create_block() {
+                Some(b) => body_blocks.push(b),
+                None => return,
+            }
+        }
+
+        let mut seen = HashSet::new();
+        let mut switch_cases = Vec::new();
+        let mut default_block = continuation;
+        for (values, &body) in case_values.iter().zip(&body_blocks) {
+            if values.is_empty() {
+                default_block = body;
+            }
+            for &value in values {
+                if seen.insert(value) {
+                    switch_cases.push((value, body));
+                }
+            }
+        }
+        self.builder
+            .build_switch(scrut_val, switch_cases, default_block);
+
+        for (case, &body) in cases.iter().zip(&body_blocks) {
+            self.builder.switch_to_block(body);
+            self.lower_block(&case.body);
+            self.builder.build_branch(continuation);
+        }
+
+        self.builder.switch_to_block(continuation);
+    }
+
     fn lower_pattern_test(&mut self, scrutinee: IrId, pattern: &HirPattern) -> Option<IrId> {
         // Test if scrutinee matches pattern
         // Returns a boolean IrId indicating match success
@@ -21419,15 +23540,7 @@ impl<'a> HirToMirContext<'a> {
             // Build chain: for each catch, test type match → body or next catch
             let mut next_test_block: Option<IrBlockId> = None;
 
-            for (i, catch_clause) in catches.iter().enumerate() {
-                let catch_type_kind = {
-                    let type_table = self.type_table.borrow();
-                    type_table
-                        .get(catch_clause.exception_type)
-                        .map(|t| t.kind.clone())
-                };
-                let is_dynamic = matches!(catch_type_kind, Some(crate::tast::TypeKind::Dynamic));
-
+            for catch_clause in catches {
                 let catch_body_block = match self.builder.create_block() {
                     Some(b) => b,
                     None => return,
@@ -21439,26 +23552,23 @@ impl<'a> HirToMirContext<'a> {
                 }
                 // else: we're still in the landing_pad block from above
 
-                if is_dynamic || i == catches.len() - 1 {
-                    // Dynamic catches everything; last catch is also unconditional fallback
-                    self.builder.build_branch(catch_body_block);
-                    next_test_block = None;
-                } else {
-                    // Test: exc_type_id == expected_type_id
-                    let expected_type_id = self.runtime_type_id(catch_clause.exception_type);
-                    let expected_const = self
-                        .builder
-                        .build_const(IrValue::I32(expected_type_id as i32))
-                        .expect("failed to create type_id const");
-                    let type_match = self
-                        .builder
-                        .build_cmp(CompareOp::Eq, exc_type_id, expected_const)
-                        .expect("failed to build type cmp");
-
-                    let next_block = self.builder.create_block().expect("failed to create block");
-                    self.builder
-                        .build_cond_branch(type_match, catch_body_block, next_block);
-                    next_test_block = Some(next_block);
+                match self.catch_test(catch_clause.exception_type, catch_clause.exception_var) {
+                    CatchTest::All => {
+                        // Dynamic and Any catch everything
+                        self.builder.build_branch(catch_body_block);
+                        next_test_block = None;
+                    }
+                    test => {
+                        // Test: the exception is the caught type or a subclass
+                        let type_match = self
+                            .build_catch_test(exc_type_id, &test)
+                            .expect("failed to build type cmp");
+                        let next_block =
+                            self.builder.create_block().expect("failed to create block");
+                        self.builder
+                            .build_cond_branch(type_match, catch_body_block, next_block);
+                        next_test_block = Some(next_block);
+                    }
                 }
 
                 // --- catch body ---
@@ -21469,19 +23579,20 @@ impl<'a> HirToMirContext<'a> {
                 self.builder.build_branch(after_catch_target);
             }
 
-            // If all typed catches failed and no Dynamic/final catch consumed it,
-            // branch to finally/continuation (exception goes unhandled at this level)
+            // No catch matched: run finally, then propagate to the enclosing handler
             if let Some(fallthrough_block) = next_test_block {
                 self.builder.switch_to_block(fallthrough_block);
-                self.builder.build_branch(after_catch_target);
+                if let Some(finally_body) = finally {
+                    self.lower_block(finally_body);
+                }
+                self.build_rethrow();
             }
         } else {
-            // No catch clauses — go directly to finally or continuation
-            if let Some(fb) = finally_block {
-                self.builder.build_branch(fb);
-            } else {
-                self.builder.build_branch(continuation_block);
+            // No catch clauses (try/finally) — run finally, then propagate
+            if let Some(finally_body) = finally {
+                self.lower_block(finally_body);
             }
+            self.build_rethrow();
         }
 
         // --- finally block (if present) ---
@@ -21597,6 +23708,140 @@ impl<'a> HirToMirContext<'a> {
         result
     }
 
+    /// Lower `obj.method` taken as a value to a closure over the receiver.
+    ///
+    /// The closure is passed through `rayzor_bind_method`, which returns the
+    /// same closure every time the same method is bound to the same live
+    /// receiver, so `obj.method == obj.method` and `Reflect.compareMethods`
+    /// hold for bound methods.
+    fn lower_bound_method(&mut self, obj_reg: IrId, trampoline: IrFunctionId) -> Option<IrId> {
+        let closure = self.builder.build_make_closure(trampoline, vec![obj_reg])?;
+        let ptr_u8 = IrType::Ptr(Box::new(IrType::U8));
+        let bind_func = self.get_or_register_extern_function(
+            "rayzor_bind_method",
+            vec![ptr_u8.clone()],
+            ptr_u8.clone(),
+        );
+        self.builder
+            .build_call_direct(bind_func, vec![closure], ptr_u8)
+    }
+
+    /// Trampoline behind bound-method closures: `(env, args...)` calls
+    /// `method(env[0], args...)`, going through the vtable for virtual methods
+    /// so overrides are honoured. Generated once per method.
+    ///
+    /// Returns `None` if `method` is not a (non-generic) instance method
+    /// compiled in this module.
+    fn bound_method_trampoline(&mut self, method: SymbolId) -> Option<IrFunctionId> {
+        if let Some(&trampoline) = self.bound_method_trampolines.get(&method) {
+            return Some(trampoline);
+        }
+        let method_func_id = *self.function_map.get(&method)?;
+        let method_func = self.builder.module.functions.get(&method_func_id)?;
+        let signature = &method_func.signature;
+        if signature.parameters.first().map(|p| p.name.as_str()) != Some("this")
+            || !signature.type_params.is_empty()
+            || signature.uses_sret
+        {
+            return None;
+        }
+        let this_ty = signature.parameters[0].ty.clone();
+        let arg_types: Vec<IrType> = signature.parameters[1..]
+            .iter()
+            .map(|p| p.ty.clone())
+            .collect();
+        let return_type = signature.return_type.clone();
+        let can_throw = signature.can_throw;
+        let trampoline_name = format!("<bound {}>", method_func.name);
+
+        let mut parameters = vec![IrParameter {
+            name: "env".to_string(),
+            ty: IrType::Ptr(Box::new(IrType::Void)),
+            reg: IrId::new(0),
+            by_ref: false,
+        }];
+        parameters.extend(signature.parameters[1..].iter().cloned());
+        let trampoline_signature = IrFunctionSignature {
+            parameters,
+            return_type: return_type.clone(),
+            calling_convention: CallingConvention::Haxe,
+            can_throw,
+            type_params: vec![],
+            uses_sret: false,
+        };
+        let func_id = self.builder.module.alloc_function_id();
+        let trampoline = IrFunction::new(
+            func_id,
+            SymbolId::from_raw(1000000 + func_id.0),
+            trampoline_name,
+            trampoline_signature,
+        );
+        let entry_block = trampoline.entry_block();
+        self.builder.module.add_function(trampoline);
+        self.bound_method_trampolines.insert(method, func_id);
+
+        let saved_state = self.save_state();
+        self.builder.current_function = Some(func_id);
+        self.builder.current_block = Some(entry_block);
+        let built = self.build_bound_method_trampoline_body(
+            method,
+            method_func_id,
+            this_ty,
+            arg_types,
+            return_type,
+        );
+        self.restore_state(saved_state);
+        built.map(|_| func_id)
+    }
+
+    fn build_bound_method_trampoline_body(
+        &mut self,
+        method: SymbolId,
+        method_func_id: IrFunctionId,
+        this_ty: IrType,
+        arg_types: Vec<IrType>,
+        return_type: IrType,
+    ) -> Option<()> {
+        // The receiver is the single captured value
+        let env = IrId::new(0);
+        let this_reg = self.builder.build_load(env, this_ty)?;
+        let mut call_args = vec![this_reg];
+        call_args.extend((1..=arg_types.len()).map(|i| IrId::new(i as u32)));
+
+        let result = if let Some(&(slot_index, _)) = self.virtual_dispatch_info.get(&method) {
+            let lookup_fn = self.get_or_register_extern_function(
+                "haxe_vtable_lookup",
+                vec![IrType::Ptr(Box::new(IrType::U8)), IrType::I32],
+                IrType::I64,
+            );
+            let slot_reg = self.builder.build_const(IrValue::I32(slot_index as i32))?;
+            let closure_ptr =
+                self.builder
+                    .build_call_direct(lookup_fn, vec![this_reg, slot_reg], IrType::I64)?;
+            let mut params = vec![IrType::Ptr(Box::new(IrType::Void))]; // self
+            params.extend(arg_types);
+            self.builder.build_call_indirect(
+                closure_ptr,
+                call_args,
+                IrType::Function {
+                    params,
+                    return_type: Box::new(return_type.clone()),
+                    varargs: false,
+                },
+            )
+        } else {
+            self.builder
+                .build_call_direct(method_func_id, call_args, return_type.clone())
+        };
+
+        let value = if matches!(return_type, IrType::Void) {
+            None
+        } else {
+            Some(result?)
+        };
+        self.builder.build_return(value)
+    }
+
     // ========================================================================
     // Two-Pass Lambda Generation (New Architecture) - Helper Methods
     // ========================================================================
@@ -21891,6 +24136,12 @@ impl<'a> HirToMirContext<'a> {
                     return local.ty.clone();
                 }
 
+                // Then the register types recorded by the builder (binops, calls, phis, ...)
+                if let Some(ty) = function.register_types.get(ret_reg) {
+                    debug!("Found type in register_types: {:?}", ty);
+                    return ty.clone();
+                }
+
                 // If not in locals table, scan ALL blocks for the instruction that defines this register
                 debug!("Register not in locals, scanning all blocks for defining instruction...");
                 for (search_block_id, search_block) in &function.cfg.blocks {
@@ -22034,7 +24285,7 @@ impl<'a> HirToMirContext<'a> {
         self.lower_lambda_body(context, params, body)
     }
 
-    fn lower_array_literal(&mut self, elements: &[HirExpr]) -> Option<IrId> {
+    fn lower_array_literal(&mut self, elements: &[HirExpr], array_ty: TypeId) -> Option<IrId> {
         // Array literal: [e1, e2, e3, ...]
         //
         // HaxeArray is a 32-byte struct (4 x 8-byte fields): ptr, len, cap, elem_size
@@ -22092,8 +24343,9 @@ impl<'a> HirToMirContext<'a> {
                     self.builder.build_store(cap_field, zero_i64);
                 }
             }
-            // Set elem_size field to 8 bytes (offset 24) - assume pointer size
-            if let Some(elem_size_val) = self.builder.build_const(IrValue::I64(8)) {
+            // Set elem_size field (offset 24): 8 bytes, or the struct size for @:struct elements
+            let elem_size = self.array_elem_size(array_ty);
+            if let Some(elem_size_val) = self.builder.build_const(IrValue::I64(elem_size)) {
                 if let Some(index_3) = self.builder.build_const(IrValue::I32(3)) {
                     if let Some(elem_size_field) =
                         self.builder
@@ -22105,6 +24357,28 @@ impl<'a> HirToMirContext<'a> {
             }
         }
 
+        // @:struct elements: copy each value's bytes into the buffer
+        if element_count > 0 && self.array_holds_value_structs(array_ty) {
+            let push_func_id = self.get_or_register_extern_function(
+                "haxe_array_push",
+                vec![
+                    IrType::Ptr(Box::new(IrType::Void)),
+                    IrType::Ptr(Box::new(IrType::U8)),
+                ],
+                IrType::Void,
+            );
+            for elem in elements.iter() {
+                let elem_val = self.lower_expression(elem)?;
+                self.builder.build_call_direct(
+                    push_func_id,
+                    vec![array_ptr, elem_val],
+                    IrType::Void,
+                );
+                self.free_fresh_value_struct(elem, elem_val);
+            }
+            return Some(array_ptr);
+        }
+
         // For non-empty arrays, push each element using haxe_array_push_i64
         // This is inefficient but works correctly with the HaxeArray runtime
         if element_count > 0 {
@@ -22174,6 +24448,7 @@ impl<'a> HirToMirContext<'a> {
         // - Int keys → IntMap (haxe_intmap_new/set)
         // - String keys → StringMap (haxe_stringmap_new/set)
         // - Object keys → ObjectMap (haxe_objectmap_new/set, pointer identity)
+        // - Enum keys → ObjectMap (haxe_enummap_new/set, structural)
 
         if entries.is_empty() {
             // Default to StringMap for empty map literals
@@ -22303,15 +24578,26 @@ impl<'a> HirToMirContext<'a> {
 
             Some(map_ptr)
         } else {
-            // ObjectMap (object/pointer keys, identity-based)
-            let new_fn = self.get_or_register_extern_function(
-                "haxe_objectmap_new",
-                vec![],
-                map_ptr_type.clone(),
-            );
-            let map_ptr = self
-                .builder
-                .build_call_direct(new_fn, vec![], map_ptr_type.clone())?;
+            // ObjectMap (object/pointer keys, identity-based), or its enum-keyed
+            // form whose keys are hashed and compared structurally
+            let map_ptr = if let Some(type_id) = self.enum_runtime_type_id(entries[0].0.ty) {
+                let new_fn = self.get_or_register_extern_function(
+                    "haxe_enummap_new",
+                    vec![IrType::I32],
+                    map_ptr_type.clone(),
+                );
+                let type_id = self.builder.build_const(IrValue::I32(type_id as i32))?;
+                self.builder
+                    .build_call_direct(new_fn, vec![type_id], map_ptr_type.clone())?
+            } else {
+                let new_fn = self.get_or_register_extern_function(
+                    "haxe_objectmap_new",
+                    vec![],
+                    map_ptr_type.clone(),
+                );
+                self.builder
+                    .build_call_direct(new_fn, vec![], map_ptr_type.clone())?
+            };
 
             let set_fn = self.get_or_register_extern_function(
                 "haxe_objectmap_set",
@@ -22455,6 +24741,123 @@ impl<'a> HirToMirContext<'a> {
         })
     }
 
+    /// Carry `@:cold`, `@:noThrow`, `@:targetFeature` and `@:inline` over as
+    /// function attributes: calls to cold functions mark the calling block
+    /// cold (see `branch_hints`), no-throw functions are emitted without
+    /// unwinding, each `@:targetFeature` entry becomes one specialized
+    /// version in AOT builds (see `multiversion`), and `@:inline(never|
+    /// always|hot)` overrides the inliner's cost model (see `inlining`).
+    /// `@:requires`/`@:ensures` contracts are listed for the contract check
+    /// pass (see `contracts`).
+    fn apply_function_hints(&mut self, func_id: IrFunctionId, hir_func: &HirFunction) {
+        let mut is_cold = false;
+        let mut is_no_throw = false;
+        let mut feature_sets = Vec::new();
+        let mut inline_hint = None;
+        let mut contracts = Vec::new();
+        for attr in &hir_func.metadata {
+            match self.string_interner.get(attr.name) {
+                Some("cold") => is_cold = true,
+                Some("noThrow") => is_no_throw = true,
+                Some(kind @ ("requires" | "ensures")) => {
+                    // (predicate, condition, line, column), from TAST→HIR
+                    if let [HirAttributeArg::Literal(HirLiteral::Int(predicate)), HirAttributeArg::Literal(HirLiteral::String(condition)), HirAttributeArg::Literal(HirLiteral::Int(line)), HirAttributeArg::Literal(HirLiteral::Int(column))] =
+                        attr.args.as_slice()
+                    {
+                        contracts.push(super::contracts::Contract {
+                            kind: if kind == "requires" {
+                                crate::tast::node::ContractKind::Requires
+                            } else {
+                                crate::tast::node::ContractKind::Ensures
+                            },
+                            predicate: SymbolId::from_raw(*predicate as u32),
+                            condition: self
+                                .string_interner
+                                .get(*condition)
+                                .unwrap_or_default()
+                                .to_string(),
+                            file: self.builder.module.source_file.clone(),
+                            line: *line as u32,
+                            column: *column as u32,
+                        });
+                    }
+                }
+                Some("inline") => {
+                    let keyword = match attr.args.first() {
+                        Some(HirAttributeArg::Literal(HirLiteral::String(s))) => {
+                            self.string_interner.get(*s)
+                        }
+                        _ => None,
+                    };
+                    match keyword {
+                        Some("never") => inline_hint = Some(super::InlineHint::Never),
+                        Some("always") => inline_hint = Some(super::InlineHint::Always),
+                        Some("hot") => inline_hint = Some(super::InlineHint::Hint),
+                        _ => warn!(
+                            "@:inline expects never, always or hot; ignored on {}",
+                            self.string_interner
+                                .get(hir_func.name)
+                                .unwrap_or("<unknown>")
+                        ),
+                    }
+                }
+                Some("targetFeature") => {
+                    let features: Vec<&str> = attr
+                        .args
+                        .iter()
+                        .filter_map(|arg| match arg {
+                            HirAttributeArg::Literal(HirLiteral::String(s)) => {
+                                self.string_interner.get(*s)
+                            }
+                            _ => None,
+                        })
+                        .collect();
+                    if !features.is_empty() {
+                        feature_sets.push(features.join(","));
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !is_cold
+            && !is_no_throw
+            && feature_sets.is_empty()
+            && inline_hint.is_none()
+            && contracts.is_empty()
+        {
+            return;
+        }
+        if let Some(func) = self.builder.module.functions.get_mut(&func_id) {
+            if let Some(hint) = inline_hint {
+                func.attributes.inline = hint;
+            }
+            if is_cold {
+                func.attributes.custom.insert(
+                    super::branch_hints::COLD_ATTRIBUTE.to_string(),
+                    String::new(),
+                );
+            }
+            if is_no_throw {
+                func.signature.can_throw = false;
+                func.attributes
+                    .custom
+                    .insert(super::NO_THROW_ATTRIBUTE.to_string(), String::new());
+            }
+            if !feature_sets.is_empty() {
+                func.attributes.custom.insert(
+                    super::multiversion::TARGET_FEATURES_ATTRIBUTE.to_string(),
+                    feature_sets.join(";"),
+                );
+            }
+            if !contracts.is_empty() {
+                func.attributes.custom.insert(
+                    super::contracts::CONTRACTS_ATTRIBUTE.to_string(),
+                    super::contracts::encode(&contracts),
+                );
+            }
+        }
+    }
+
     /// Record which function parameters are constrained type parameters.
     /// Used at call sites to wrap class arguments in fat pointers.
     fn record_constrained_params(
@@ -22902,6 +25305,41 @@ impl<'a> HirToMirContext<'a> {
         }
     }
 
+    /// Prepare the arguments of `Reflect.compareMethods`: Dynamic-typed
+    /// function values are boxed, so unbox them to the closure pointer that
+    /// `haxe_reflect_compare_methods` compares by identity.
+    fn unbox_compare_methods_args(
+        &mut self,
+        args: &[HirExpr],
+        arg_regs: &mut [IrId],
+        arg_types: &mut [IrType],
+    ) {
+        let ptr_u8 = IrType::Ptr(Box::new(IrType::U8));
+        for (i, arg) in args.iter().enumerate().take(arg_regs.len()) {
+            let is_dynamic = self
+                .type_table
+                .borrow()
+                .get(arg.ty)
+                .map(|t| matches!(t.kind, TypeKind::Dynamic))
+                .unwrap_or(false);
+            if !is_dynamic {
+                continue;
+            }
+            let unbox_func_id = self.get_or_register_extern_function(
+                "haxe_unbox_reference_ptr",
+                vec![ptr_u8.clone()],
+                ptr_u8.clone(),
+            );
+            if let Some(unboxed) =
+                self.builder
+                    .build_call_direct(unbox_func_id, vec![arg_regs[i]], ptr_u8.clone())
+            {
+                arg_regs[i] = unboxed;
+                arg_types[i] = ptr_u8.clone();
+            }
+        }
+    }
+
     /// Coerce a value to i64 for anonymous object field storage.
     /// Ints pass through, floats are bitcast, pointers are cast to i64.
     fn coerce_to_i64(&mut self, value: IrId, type_id: TypeId) -> Option<IrId> {
@@ -22966,10 +25404,114 @@ impl<'a> HirToMirContext<'a> {
                 drop(type_table);
                 self.runtime_type_id(target)
             }
-            _ => 0, // default to void/unknown
+            _ => 0, // default to void/unknown
+        }
+    }
+
+    /// How a catch clause for `catch_type` tests the current exception.
+    /// `exception_var` is the clause's variable, for diagnostics.
+    fn catch_test(&mut self, catch_type: TypeId, exception_var: SymbolId) -> CatchTest {
+        let catch_type = self.resolve_through_aliases(catch_type);
+        let kind = {
+            let type_table = self.type_table.borrow();
+            type_table.get(catch_type).map(|t| t.kind.clone())
+        };
+        match kind {
+            Some(TypeKind::Dynamic) => CatchTest::All,
+            Some(TypeKind::Abstract {
+                underlying: Some(underlying),
+                ..
+            }) if matches!(
+                self.type_table
+                    .borrow()
+                    .get(self.resolve_through_aliases(underlying))
+                    .map(|t| &t.kind),
+                Some(TypeKind::Dynamic)
+            ) =>
+            {
+                // `Any` is `abstract Any(Dynamic)`
+                CatchTest::All
+            }
+            Some(TypeKind::Abstract { symbol_id, .. })
+            | Some(TypeKind::Class { symbol_id, .. })
+                if self.symbol_name(symbol_id) == Some("Any") =>
+            {
+                CatchTest::All
+            }
+            // The thrown type_id is the static type at the throw site, so
+            // classes are matched through the object header at runtime
+            Some(TypeKind::Class { symbol_id, .. }) => CatchTest::Instance(symbol_id),
+            Some(TypeKind::Interface { .. }) => {
+                // Binding the variable needs the interface vtable of the
+                // thrown object's class, which isn't known statically
+                let location = self
+                    .symbol_table
+                    .get_symbol(exception_var)
+                    .map(|sym| sym.definition_location)
+                    .unwrap_or_else(SourceLocation::unknown);
+                self.add_error(
+                    "Catching an interface type is not supported; catch the implementing \
+                     class or Dynamic and test with `is`",
+                    location,
+                );
+                CatchTest::TypeIds(vec![])
+            }
+            _ => CatchTest::TypeIds(vec![self.runtime_type_id(catch_type)]),
+        }
+    }
+
+    /// Name of a symbol, if it has one
+    fn symbol_name(&self, symbol_id: SymbolId) -> Option<&str> {
+        self.symbol_table
+            .get_symbol(symbol_id)
+            .and_then(|sym| self.string_interner.get(sym.name))
+    }
+
+    /// Branch condition for a catch clause that doesn't catch everything:
+    /// whether the current exception, with runtime type `exc_type_id`, is
+    /// handled by the clause
+    fn build_catch_test(&mut self, exc_type_id: IrId, test: &CatchTest) -> Option<IrId> {
+        match test {
+            CatchTest::All => self.builder.build_const(IrValue::Bool(true)),
+            CatchTest::Instance(class_sym) => {
+                let class_id = self
+                    .builder
+                    .build_const(IrValue::I64(class_sym.as_raw() as i64))?;
+                let is_instance_fn = self.get_or_register_extern_function(
+                    "rayzor_exception_is_instance",
+                    vec![IrType::I64],
+                    IrType::Bool,
+                );
+                self.builder
+                    .build_call_direct(is_instance_fn, vec![class_id], IrType::Bool)
+            }
+            CatchTest::TypeIds(type_ids) => {
+                let mut matched = None;
+                for &type_id in type_ids {
+                    let expected = self.builder.build_const(IrValue::I32(type_id as i32))?;
+                    let is_match = self
+                        .builder
+                        .build_cmp(CompareOp::Eq, exc_type_id, expected)?;
+                    matched = Some(match matched {
+                        Some(prev) => self.builder.build_binop(BinaryOp::Or, prev, is_match)?,
+                        None => is_match,
+                    });
+                }
+                matched.or_else(|| self.builder.build_const(IrValue::Bool(false)))
+            }
         }
     }
 
+    /// Propagate the current exception to the enclosing handler. Ends the
+    /// current block.
+    fn build_rethrow(&mut self) {
+        let rethrow_fn =
+            self.get_or_register_extern_function("rayzor_rethrow", vec![], IrType::Void);
+        self.builder
+            .build_call_direct(rethrow_fn, vec![], IrType::Void);
+        self.builder.build_unreachable();
+    }
+
     /// Resolve a TypeId through TypeAlias chains to find the underlying type.
     /// Returns the resolved TypeId (following aliases), or the original if not an alias.
     fn resolve_through_aliases(&self, type_id: TypeId) -> TypeId {
@@ -23041,6 +25583,129 @@ impl<'a> HirToMirContext<'a> {
         false
     }
 
+    /// Whether a class (or one of its ancestors) implements an interface,
+    /// directly or through an interface it extends.
+    fn class_implements_interface(&self, class_sym: SymbolId, iface_sym: SymbolId) -> bool {
+        let mut current = Some(class_sym);
+        while let Some(sym) = current {
+            if self.interface_vtables.contains_key(&(sym, iface_sym)) {
+                return true;
+            }
+            current = self.class_parent_map.get(&sym).copied();
+        }
+        false
+    }
+
+    /// Object pointer behind an interface fat pointer (null for null).
+    fn build_interface_object(&mut self, fat_ptr: IrId) -> Option<IrId> {
+        let ptr_u8 = IrType::Ptr(Box::new(IrType::U8));
+        let unwrap_func = self.get_or_register_extern_function(
+            "haxe_interface_object",
+            vec![ptr_u8.clone()],
+            ptr_u8.clone(),
+        );
+        self.builder
+            .build_call_direct(unwrap_func, vec![fat_ptr], ptr_u8)
+    }
+
+    /// `obj is Type` via the object header: true if the object's class is,
+    /// extends or implements the class/interface `type_sym`.
+    fn build_instance_check(&mut self, obj_reg: IrId, type_sym: SymbolId) -> Option<IrId> {
+        // Use SymbolId-based TypeId to match TYPE_REGISTRY and object header
+        let type_id_const = self
+            .builder
+            .build_const(IrValue::I64(type_sym.as_raw() as i64))?;
+        let ptr_void = IrType::Ptr(Box::new(IrType::Void));
+        let is_func = self.get_or_register_extern_function(
+            "haxe_object_is_instance",
+            vec![ptr_void, IrType::I64],
+            IrType::I64,
+        );
+        let result_i64 =
+            self.builder
+                .build_call_direct(is_func, vec![obj_reg, type_id_const], IrType::I64)?;
+        // Convert i64 (0/1) to Bool
+        let zero = self.builder.build_const(IrValue::I64(0))?;
+        self.builder.build_cmp(CompareOp::Ne, result_i64, zero)
+    }
+
+    /// `cast(obj, Type)` for a class target: returns the object if it is an
+    /// instance of `class_sym`, otherwise throws a catchable cast error.
+    fn build_checked_class_cast(&mut self, obj_reg: IrId, class_sym: SymbolId) -> Option<IrId> {
+        let type_id_const = self
+            .builder
+            .build_const(IrValue::I64(class_sym.as_raw() as i64))?;
+        let ptr_u8 = IrType::Ptr(Box::new(IrType::U8));
+        let cast_func = self.get_or_register_extern_function(
+            "haxe_checked_cast_class",
+            vec![ptr_u8.clone(), IrType::I64],
+            ptr_u8.clone(),
+        );
+        self.builder
+            .build_call_direct(cast_func, vec![obj_reg, type_id_const], ptr_u8)
+    }
+
+    /// `s.charCodeAt(i)` as a bounds-checked byte load from the HaxeString
+    /// (`{ptr, len, cap}`) instead of a runtime call. Yields the byte as `ty`,
+    /// or -1 (null) when `s` is null or `i` is out of range, like
+    /// `haxe_string_char_code_at_ptr`.
+    fn build_string_char_code_at(
+        &mut self,
+        str_reg: IrId,
+        index_reg: IrId,
+        ty: IrType,
+    ) -> Option<IrId> {
+        let check_block = self.builder.create_block()?;
+        let load_block = self.builder.create_block()?;
+        let null_block = self.builder.create_block()?;
+        let merge_block = self.builder.create_block()?;
+        let ptr_u8 = IrType::Ptr(Box::new(IrType::U8));
+
+        let null = self.builder.build_null()?;
+        let is_null = self.builder.build_cmp(CompareOp::Eq, str_reg, null)?;
+        self.builder
+            .build_cond_branch(is_null, null_block, check_block)?;
+
+        // A negative index wraps to a huge unsigned value, so one unsigned
+        // compare against the length covers both bounds
+        self.builder.switch_to_block(check_block);
+        let index_ty = self
+            .builder
+            .get_register_type(index_reg)
+            .unwrap_or(IrType::I32);
+        let index = self.builder.build_cast(index_reg, index_ty, IrType::I64)?;
+        let len_offset = self.builder.build_const(IrValue::I64(8))?;
+        let len_ptr = self
+            .builder
+            .build_ptr_add(str_reg, len_offset, ptr_u8.clone())?;
+        let len = self.builder.build_load(len_ptr, IrType::I64)?;
+        let in_bounds = self.builder.build_cmp(CompareOp::ULt, index, len)?;
+        self.builder
+            .build_cond_branch(in_bounds, load_block, null_block)?;
+
+        self.builder.switch_to_block(load_block);
+        let data = self.builder.build_load(str_reg, ptr_u8.clone())?;
+        let byte_ptr = self.builder.build_ptr_add(data, index, ptr_u8)?;
+        let byte = self.builder.build_load(byte_ptr, IrType::U8)?;
+        let code = self.builder.build_cast(byte, IrType::U8, ty.clone())?;
+        self.builder.build_branch(merge_block)?;
+
+        self.builder.switch_to_block(null_block);
+        let minus_one = match ty {
+            IrType::I64 => self.builder.build_const(IrValue::I64(-1))?,
+            _ => self.builder.build_const(IrValue::I32(-1))?,
+        };
+        self.builder.build_branch(merge_block)?;
+
+        self.builder.switch_to_block(merge_block);
+        let result = self.builder.build_phi(merge_block, ty)?;
+        self.builder
+            .add_phi_incoming(merge_block, result, load_block, code)?;
+        self.builder
+            .add_phi_incoming(merge_block, result, null_block, minus_one)?;
+        Some(result)
+    }
+
     fn lower_string_interpolation(&mut self, parts: &[HirStringPart]) -> Option<IrId> {
         if parts.is_empty() {
             return self.builder.build_string(String::new());
@@ -23832,10 +26497,21 @@ impl<'a> HirToMirContext<'a> {
                 })
                 .collect();
 
+            let field_enum_types = variant
+                .fields
+                .iter()
+                .map(|field| {
+                    self.enum_symbol_of_type(field.ty)
+                        .and_then(|sym| self.symbol_table.get_symbol(sym))
+                        .map(|sym| sym.type_id)
+                })
+                .collect();
+
             variants.push(IrEnumVariant {
                 name: variant_name,
                 discriminant,
                 fields,
+                field_enum_types,
             });
         }
 
@@ -23849,11 +26525,85 @@ impl<'a> HirToMirContext<'a> {
             },
             source_location: IrSourceLocation::unknown(),
             super_type_id: None,
+            interface_type_ids: Vec::new(),
+            metadata: IrTypeMetadata {
+                type_meta: self.convert_attributes(&enum_decl.metadata),
+                fields: self.convert_member_attributes(
+                    enum_decl.variants.iter().map(|v| (v.name, &v.metadata)),
+                ),
+                statics: Vec::new(),
+            },
+            layout: None,
         };
 
         self.builder.module.add_type(typedef);
     }
 
+    /// Convert HIR attributes into MIR metadata entries for the runtime
+    /// `haxe.rtti.Meta` table
+    fn convert_attributes(&self, attrs: &[HirAttribute]) -> Vec<IrMetadata> {
+        attrs
+            .iter()
+            .map(|attr| IrMetadata {
+                name: self
+                    .string_interner
+                    .get(attr.name)
+                    .unwrap_or("<unknown>")
+                    .to_string(),
+                args: attr
+                    .args
+                    .iter()
+                    .map(|arg| self.convert_attribute_arg(arg))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Per-member metadata, skipping members without any
+    fn convert_member_attributes<'a>(
+        &self,
+        members: impl Iterator<Item = (InternedString, &'a Vec<HirAttribute>)>,
+    ) -> Vec<(String, Vec<IrMetadata>)> {
+        members
+            .filter(|(_, attrs)| !attrs.is_empty())
+            .map(|(name, attrs)| {
+                let name = self
+                    .string_interner
+                    .get(name)
+                    .unwrap_or("<unknown>")
+                    .to_string();
+                (name, self.convert_attributes(attrs))
+            })
+            .collect()
+    }
+
+    fn convert_attribute_arg(&self, arg: &HirAttributeArg) -> IrMetaValue {
+        let string =
+            |s: InternedString| self.string_interner.get(s).unwrap_or_default().to_string();
+        match arg {
+            HirAttributeArg::Literal(lit) | HirAttributeArg::Named(_, lit) => match lit {
+                HirLiteral::Int(i) => IrMetaValue::Int(*i),
+                HirLiteral::Float(f) => IrMetaValue::Float(*f),
+                HirLiteral::String(s) => IrMetaValue::String(string(*s)),
+                HirLiteral::Bool(b) => IrMetaValue::Bool(*b),
+                HirLiteral::Regex { pattern, .. } => IrMetaValue::String(string(*pattern)),
+            },
+            HirAttributeArg::Null => IrMetaValue::Null,
+            HirAttributeArg::Array(items) => IrMetaValue::Array(
+                items
+                    .iter()
+                    .map(|item| self.convert_attribute_arg(item))
+                    .collect(),
+            ),
+            HirAttributeArg::Object(fields) => IrMetaValue::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| (string(*name), self.convert_attribute_arg(value)))
+                    .collect(),
+            ),
+        }
+    }
+
     /// Recursively collect fields from parent classes
     fn collect_inherited_fields(
         &mut self,
@@ -23891,10 +26641,10 @@ impl<'a> HirToMirContext<'a> {
                     }
                 }
 
-                // eprintln!(
-                //     "WARNING: Could not find parent class for TypeId={:?}",
-                //     parent_type_id
-                // );
+                trace!(
+                    "Could not find parent class for TypeId={:?}",
+                    parent_type_id
+                );
             }
         }
     }
@@ -23962,6 +26712,10 @@ impl<'a> HirToMirContext<'a> {
 
                 // Get initializer value if it's a constant
                 let initializer = if let Some(ref init_expr) = field.init {
+                    // Globals are stored by __init__ (backends do not materialize
+                    // initializers), constant or not
+                    self.dynamic_globals
+                        .push((field.symbol_id, init_expr.clone()));
                     // Try to evaluate as constant
                     self.try_evaluate_constant_init(init_expr)
                 } else {
@@ -24012,34 +26766,9 @@ impl<'a> HirToMirContext<'a> {
             field_index += 1;
         }
 
-        let typedef = IrTypeDef {
-            id: typedef_id,
-            name: self
-                .string_interner
-                .get(class.name)
-                .unwrap_or("<unknown>")
-                .to_string(),
-            type_id,
-            definition: IrTypeDefinition::Struct {
-                fields,
-                packed: false,
-            },
-            source_location: IrSourceLocation::unknown(),
-            super_type_id: class.extends,
-        };
-
-        self.builder.module.add_type(typedef);
-
-        // Record allocation size: field_index is the next available index,
-        // so total slots = field_index (includes header at index 0).
-        let alloc_size = (field_index as u64 * 8).max(16);
-        self.class_alloc_sizes.insert(type_id, alloc_size);
-
-        // Build interface vtables for each implemented interface AND their
-        // transitive parent interfaces. For each interface method, find the
-        // matching class method by name.
-        //
-        // Collect all interface symbols (direct + transitive parents)
+        // Collect all interface symbols (direct + transitive parents). They
+        // make up the class's entry in the runtime subtype table and get an
+        // interface vtable each (built below).
         let mut all_iface_symbols: Vec<SymbolId> = Vec::new();
         for &iface_type_id in &class.implements {
             let iface_symbol = {
@@ -24072,8 +26801,80 @@ impl<'a> HirToMirContext<'a> {
             }
         }
 
-        // Build vtable for each interface (direct and inherited)
-        for iface_sym in all_iface_symbols {
+        let parent_symbol = class.extends.and_then(|extends_type_id| {
+            let type_table = self.type_table.borrow();
+            type_table.get(extends_type_id).and_then(|t| {
+                if let TypeKind::Class { symbol_id, .. } = &t.kind {
+                    Some(*symbol_id)
+                } else {
+                    None
+                }
+            })
+        });
+
+        let typedef = IrTypeDef {
+            id: typedef_id,
+            name: self
+                .string_interner
+                .get(class.name)
+                .unwrap_or("<unknown>")
+                .to_string(),
+            type_id,
+            definition: IrTypeDefinition::Struct {
+                fields,
+                packed: false,
+            },
+            source_location: IrSourceLocation::unknown(),
+            // Runtime class ids are symbol-based, matching the object header
+            super_type_id: parent_symbol.map(|sym| TypeId::from_raw(sym.as_raw())),
+            interface_type_ids: all_iface_symbols
+                .iter()
+                .map(|sym| TypeId::from_raw(sym.as_raw()))
+                .collect(),
+            metadata: IrTypeMetadata {
+                type_meta: self.convert_attributes(&class.metadata),
+                fields: self.convert_member_attributes(
+                    class
+                        .fields
+                        .iter()
+                        .filter(|f| !f.is_static)
+                        .map(|f| (f.name, &f.metadata))
+                        .chain(
+                            class
+                                .methods
+                                .iter()
+                                .filter(|m| !m.is_static)
+                                .map(|m| (m.function.name, &m.metadata)),
+                        ),
+                ),
+                statics: self.convert_member_attributes(
+                    class
+                        .fields
+                        .iter()
+                        .filter(|f| f.is_static)
+                        .map(|f| (f.name, &f.metadata))
+                        .chain(
+                            class
+                                .methods
+                                .iter()
+                                .filter(|m| m.is_static)
+                                .map(|m| (m.function.name, &m.metadata)),
+                        ),
+                ),
+            },
+            layout: None,
+        };
+
+        self.builder.module.add_type(typedef);
+
+        // Record allocation size: field_index is the next available index,
+        // so total slots = field_index (includes header at index 0).
+        let alloc_size = (field_index as u64 * 8).max(16);
+        self.class_alloc_sizes.insert(type_id, alloc_size);
+
+        // Build vtable for each interface (direct and inherited). For each
+        // interface method, find the matching class method by name.
+        for &iface_sym in &all_iface_symbols {
             if let Some(method_names) = self.interface_method_names.get(&iface_sym).cloned() {
                 let mut vtable_entries = Vec::new();
                 for iface_method_name in &method_names {
@@ -24114,20 +26915,8 @@ impl<'a> HirToMirContext<'a> {
         }
 
         // Record parent class relationship
-        if let Some(extends_type_id) = class.extends {
-            let parent_symbol = {
-                let type_table = self.type_table.borrow();
-                type_table.get(extends_type_id).and_then(|t| {
-                    if let TypeKind::Class { symbol_id, .. } = &t.kind {
-                        Some(*symbol_id)
-                    } else {
-                        None
-                    }
-                })
-            };
-            if let Some(parent_sym) = parent_symbol {
-                self.class_parent_map.insert(class.symbol_id, parent_sym);
-            }
+        if let Some(parent_sym) = parent_symbol {
+            self.class_parent_map.insert(class.symbol_id, parent_sym);
         }
     }
 
@@ -24205,6 +26994,9 @@ impl<'a> HirToMirContext<'a> {
             },
             source_location: IrSourceLocation::unknown(),
             super_type_id: None,
+            interface_type_ids: Vec::new(),
+            metadata: IrTypeMetadata::default(),
+            layout: None,
         };
 
         self.builder.module.add_type(typedef);
@@ -24231,6 +27023,9 @@ impl<'a> HirToMirContext<'a> {
             },
             source_location: IrSourceLocation::unknown(),
             super_type_id: None,
+            interface_type_ids: Vec::new(),
+            metadata: IrTypeMetadata::default(),
+            layout: None,
         };
 
         self.builder.module.add_type(typedef);
@@ -24336,6 +27131,9 @@ impl<'a> HirToMirContext<'a> {
                     },
                     source_location: IrSourceLocation::unknown(),
                     super_type_id: None,
+                    interface_type_ids: Vec::new(),
+                    metadata: IrTypeMetadata::default(),
+                    layout: None,
                 };
 
                 self.builder.module.add_type(typedef);
@@ -24361,13 +27159,17 @@ impl<'a> HirToMirContext<'a> {
             },
             source_location: IrSourceLocation::unknown(),
             super_type_id: None,
+            interface_type_ids: Vec::new(),
+            metadata: IrTypeMetadata::default(),
+            layout: None,
         };
 
         self.builder.module.add_type(typedef);
     }
 
     fn generate_vtable_init_function(&mut self) {
-        // Generate __vtable_init__ function that registers class vtables at startup.
+        // Generate __vtable_init__ function that registers class vtables and
+        // toString() methods at startup.
         // Called before main() by the backend (same pattern as __init__).
 
         let sig = FunctionSignatureBuilder::new()
@@ -24426,17 +27228,202 @@ impl<'a> HirToMirContext<'a> {
             }
         }
 
+        // Register toString() methods for runtime string conversion:
+        // haxe_class_set_tostring(type_id, closure_ptr)
+        let tostring_methods = self.class_tostring_methods();
+        if !tostring_methods.is_empty() {
+            let set_tostring_fn = self.get_or_register_extern_function(
+                "haxe_class_set_tostring",
+                vec![IrType::I32, IrType::I64],
+                IrType::Void,
+            );
+            for (class_sym, method_sym) in tostring_methods {
+                if let Some(&func_id) = self.function_map.get(&method_sym) {
+                    let closure_ptr = self.builder.build_function_ref(func_id);
+                    let type_id_reg = self
+                        .builder
+                        .build_const(IrValue::I32(class_sym.as_raw() as i32));
+                    if let (Some(cp), Some(tid)) = (closure_ptr, type_id_reg) {
+                        self.builder.build_call_direct(
+                            set_tostring_fn,
+                            vec![tid, cp],
+                            IrType::Void,
+                        );
+                    }
+                }
+            }
+        }
+
         self.builder.build_return(None);
         self.builder.finish_function();
         self.symbol_map = saved_symbol_map;
     }
 
+    /// Global backing a static field or module-level variable. Statics of
+    /// classes lowered with another module are declared `External` here and
+    /// bound to that module's global by symbol when the modules are merged.
+    fn static_global(&mut self, symbol: SymbolId) -> Option<IrGlobalId> {
+        if let Some(&global_id) = self.global_symbol_map.get(&symbol) {
+            return Some(global_id);
+        }
+        let sym = self.symbol_table.get_symbol(symbol)?;
+        if sym.kind != crate::tast::SymbolKind::Variable || !sym.is_static() {
+            return None;
+        }
+        let (name, type_id) = (sym.name, sym.type_id);
+
+        let global_id = self.builder.module.alloc_global_id();
+        let ir_global = IrGlobal {
+            id: global_id,
+            name: self
+                .string_interner
+                .get(name)
+                .unwrap_or("<unknown>")
+                .to_string(),
+            symbol_id: symbol,
+            ty: self.convert_type(type_id),
+            initializer: None,
+            mutable: true,
+            linkage: Linkage::External,
+            alignment: None,
+            source_location: IrSourceLocation::unknown(),
+        };
+        self.builder.module.add_global(ir_global);
+        self.global_symbol_map.insert(symbol, global_id);
+        Some(global_id)
+    }
+
+    fn is_static_variable(&self, symbol: SymbolId) -> bool {
+        self.symbol_table
+            .get_symbol(symbol)
+            .is_some_and(|s| s.kind == crate::tast::SymbolKind::Variable && s.is_static())
+    }
+
+    /// Lower `static var x = init` inside a function body.
+    ///
+    /// The value lives in a process-wide runtime slot rather than a register.
+    /// The initializer runs once, guarded by `rayzor_static_local_init_begin`
+    /// (which blocks other threads until `rayzor_static_local_init_end`).
+    fn lower_static_local(&mut self, symbol: SymbolId, init: Option<&HirExpr>) {
+        static NEXT_KEY: std::sync::atomic::AtomicI64 = std::sync::atomic::AtomicI64::new(1);
+        let key = NEXT_KEY.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let ty = self
+            .symbol_table
+            .get_symbol(symbol)
+            .map(|s| s.type_id)
+            .unwrap_or_else(TypeId::invalid);
+        self.static_locals.insert(symbol, (key, ty));
+
+        let begin_fn = self.get_or_register_extern_function(
+            "rayzor_static_local_init_begin",
+            vec![IrType::I64],
+            IrType::I64,
+        );
+        let end_fn = self.get_or_register_extern_function(
+            "rayzor_static_local_init_end",
+            vec![IrType::I64],
+            IrType::Void,
+        );
+        let (Some(init_block), Some(cont_block)) =
+            (self.builder.create_block(), self.builder.create_block())
+        else {
+            return;
+        };
+        let Some(key_reg) = self.builder.build_const(IrValue::I64(key)) else {
+            return;
+        };
+        let Some(begin) = self
+            .builder
+            .build_call_direct(begin_fn, vec![key_reg], IrType::I64)
+        else {
+            return;
+        };
+        let Some(zero) = self.builder.build_const(IrValue::I64(0)) else {
+            return;
+        };
+        let Some(needs_init) = self.builder.build_cmp(CompareOp::Ne, begin, zero) else {
+            return;
+        };
+        self.builder
+            .build_cond_branch(needs_init, init_block, cont_block);
+
+        // Slots start zeroed, so a missing initializer leaves the default value
+        self.builder.switch_to_block(init_block);
+        if let Some(value) = init.and_then(|expr| self.lower_expression(expr)) {
+            self.build_static_local_store(key, ty, value);
+        }
+        if !self.is_terminated() {
+            if let Some(key_reg) = self.builder.build_const(IrValue::I64(key)) {
+                self.builder
+                    .build_call_direct(end_fn, vec![key_reg], IrType::Void);
+            }
+            self.builder.build_branch(cont_block);
+        }
+
+        self.builder.switch_to_block(cont_block);
+    }
+
+    fn build_static_local_load(&mut self, key: i64, ty: TypeId) -> Option<IrId> {
+        let load_fn = self.get_or_register_extern_function(
+            "rayzor_static_local_load",
+            vec![IrType::I64],
+            IrType::I64,
+        );
+        let key_reg = self.builder.build_const(IrValue::I64(key))?;
+        let raw = self
+            .builder
+            .build_call_direct(load_fn, vec![key_reg], IrType::I64)?;
+        self.coerce_from_i64(raw, ty)
+    }
+
+    fn build_static_local_store(&mut self, key: i64, ty: TypeId, value: IrId) {
+        let store_fn = self.get_or_register_extern_function(
+            "rayzor_static_local_store",
+            vec![IrType::I64, IrType::I64],
+            IrType::Void,
+        );
+        let (Some(key_reg), Some(raw)) = (
+            self.builder.build_const(IrValue::I64(key)),
+            self.coerce_to_i64(value, ty),
+        ) else {
+            return;
+        };
+        self.builder
+            .build_call_direct(store_fn, vec![key_reg, raw], IrType::Void);
+    }
+
+    /// Haxe's `static function __init__()` magic: a class's `__init__` runs
+    /// before `main`, as part of its module's initializer. Renamed to
+    /// `<Class>.__init__` so it isn't taken for the module initializer.
+    fn register_class_initializer(&mut self, class: &HirClass, method: &HirMethod) {
+        let is_init =
+            self.string_interner.get(method.function.name) == Some(module_init::INIT_FUNCTION);
+        if !is_init
+            || !method.is_static
+            || class.is_extern
+            || method.function.body.is_none()
+            || !method.function.params.is_empty()
+        {
+            return;
+        }
+        let Some(&func_id) = self.function_map.get(&method.function.symbol_id) else {
+            return;
+        };
+        let class_name = self.string_interner.get(class.name).unwrap_or("<class>");
+        if let Some(func) = self.builder.module.functions.get_mut(&func_id) {
+            func.name = module_init::class_initializer_name(class_name);
+        }
+        self.class_initializers.push(func_id);
+    }
+
     fn generate_module_init_function(&mut self) {
         // Generate __init__ function that initializes dynamic globals
         // This function is called once at module load time
         //
         // Function signature: fn __init__() -> void
-        // Body: Initialize each dynamic global in order
+        // Body: Run class `__init__` methods in declaration order (before
+        // static initialization, as in Haxe), then initialize each dynamic
+        // global in order
 
         let init_sig = FunctionSignatureBuilder::new()
             .returns(IrType::Void)
@@ -24448,23 +27435,37 @@ impl<'a> HirToMirContext<'a> {
             self.builder
                 .start_function(init_symbol, "__init__".to_string(), init_sig);
 
+        for func_id in self.class_initializers.clone() {
+            self.builder
+                .build_call_direct(func_id, Vec::new(), IrType::Void);
+        }
+
         // Save current symbol map (should be empty, but just in case)
         let saved_symbol_map = self.symbol_map.clone();
         self.symbol_map.clear();
 
-        // Lower each dynamic global initialization
-        for (_symbol, init_expr) in &self.dynamic_globals.clone() {
-            // Evaluate the initialization expression
-            let Some(_init_value) = self.lower_expression(init_expr) else {
+        // Lower each dynamic global initialization, in declaration order
+        for (symbol, init_expr) in &self.dynamic_globals.clone() {
+            // An initializer we cannot lower yet (e.g. one calling into an
+            // unsupported extern) leaves its global unset rather than failing
+            // the whole module
+            let error_count = self.errors.len();
+            let init_value = self.lower_expression(init_expr);
+            if self.errors.len() > error_count {
+                warn!(
+                    "Skipping initializer of global {:?}: {}",
+                    symbol, self.errors[error_count].message
+                );
+                self.errors.truncate(error_count);
+                continue;
+            }
+            let Some(init_value) = init_value else {
                 continue;
             };
 
-            // TODO: Store the result to the global variable
-            // This requires accessing the global by symbol ID
-            // For now, we just evaluate the expression (side effects occur)
-            // In a full implementation, we'd:
-            // 1. Get the global's address
-            // 2. Store init_value to that address
+            if let Some(&global_id) = self.global_symbol_map.get(symbol) {
+                self.builder.build_store_global(global_id, init_value);
+            }
         }
 
         // Return void
@@ -24577,6 +27578,9 @@ pub fn lower_hir_to_mir_with_function_map(
     external_class_alloc_sizes: BTreeMap<TypeId, u64>,
     external_class_method_symbols: BTreeMap<(SymbolId, InternedString), SymbolId>,
     external_class_type_to_symbol: BTreeMap<TypeId, SymbolId>,
+    debug_checks: bool,
+    sanitize_thread: bool,
+    trace_positions: bool,
 ) -> Result<MirLoweringResult, Vec<LoweringError>> {
     let mut context = HirToMirContext::new(
         hir_module.name.clone(),
@@ -24608,6 +27612,10 @@ pub fn lower_hir_to_mir_with_function_map(
     // Seed class_type_to_symbol from previously compiled imports
     context.class_type_to_symbol = external_class_type_to_symbol;
 
+    context.debug_checks = debug_checks;
+    context.sanitize_thread = sanitize_thread;
+    context.trace_positions = trace_positions;
+
     let module = context.lower_module(hir_module)?;
 
     Ok(MirLoweringResult {
@@ -4,7 +4,14 @@
 //! - Call graph construction and analysis
 //! - Inlining cost model and heuristics
 //! - Function body cloning and integration
+//!
+//! On tiered recompiles the cost model also sees how often each call site
+//! ran, from the interpreter's branch counts (`CallProfile`): hot sites get
+//! a larger budget and sites that never ran aren't inlined at all.
+//! `@:inline(never)`, `@:inline(always)` and `@:inline(hot)` on a function
+//! override the heuristics.
 
+use super::branch_hints::{BranchCounts, MIN_PROFILED_BRANCHES};
 use super::loop_analysis::{DominatorTree, LoopNestInfo};
 use super::optimization::{InstructionExt, OptimizationPass, OptimizationResult};
 use super::{
@@ -158,6 +165,52 @@ impl CallGraph {
     }
 }
 
+/// Branch counts from the tiered backend's profiler, used to weigh call
+/// sites by how often they ran
+#[derive(Debug, Clone, Default)]
+pub struct CallProfile {
+    /// Taken/not-taken counts of profiled branches, per function
+    pub branch_counts: HashMap<IrFunctionId, BranchCounts>,
+    /// Executions at which a call site counts as hot
+    pub hot_threshold: u64,
+}
+
+impl CallProfile {
+    /// Profiled executions of the block containing `site`, if every edge
+    /// into it is a profiled branch and those branches ran at least
+    /// `MIN_PROFILED_BRANCHES` times together
+    pub fn site_count(&self, caller: &IrFunction, site: &CallSite) -> Option<u64> {
+        let counts = self.branch_counts.get(&caller.id)?;
+        if site.block == caller.cfg.entry_block {
+            return None;
+        }
+        let mut count = 0;
+        let mut branch_runs = 0;
+        for (pred_id, pred) in &caller.cfg.blocks {
+            if !pred.successors().contains(&site.block) {
+                continue;
+            }
+            let IrTerminator::CondBranch {
+                true_target,
+                false_target,
+                ..
+            } = pred.terminator
+            else {
+                return None;
+            };
+            let &(taken, not_taken) = counts.get(pred_id)?;
+            if true_target == site.block {
+                count += taken;
+            }
+            if false_target == site.block {
+                count += not_taken;
+            }
+            branch_runs += taken + not_taken;
+        }
+        (branch_runs >= MIN_PROFILED_BRANCHES).then_some(count)
+    }
+}
+
 /// Inlining cost model parameters.
 #[derive(Debug, Clone)]
 pub struct InliningCostModel {
@@ -171,6 +224,8 @@ pub struct InliningCostModel {
     pub small_function_bonus: usize,
     /// Maximum total growth allowed (as percentage of original size)
     pub max_growth_percent: usize,
+    /// Threshold multiplier for call sites the profile marks hot
+    pub hot_site_bonus: f64,
 }
 
 impl Default for InliningCostModel {
@@ -181,6 +236,7 @@ impl Default for InliningCostModel {
             block_count_penalty: 0.9, // Reduce threshold by 10% per extra block
             small_function_bonus: 20, // Extra budget for tiny functions
             max_growth_percent: 200,  // Allow up to 2x code growth
+            hot_site_bonus: 4.0,      // Quadruple threshold at hot call sites
         }
     }
 }
@@ -192,6 +248,20 @@ impl InliningCostModel {
         callee: &IrFunction,
         call_site: &CallSite,
         call_graph: &CallGraph,
+    ) -> bool {
+        self.should_inline_profiled(callee, call_site, call_graph, None)
+    }
+
+    /// `should_inline`, weighed by the profiled executions of the call site
+    /// and the profile's hot threshold, if any. A site that never ran isn't
+    /// inlined; a hot one gets `hot_site_bonus` times the budget, and no
+    /// size limit for `@:inline(hot)` callees.
+    pub fn should_inline_profiled(
+        &self,
+        callee: &IrFunction,
+        call_site: &CallSite,
+        call_graph: &CallGraph,
+        site_hotness: Option<(u64, u64)>,
     ) -> bool {
         // Never inline recursive functions (for now)
         if call_graph.is_recursive(call_site.callee) {
@@ -203,8 +273,20 @@ impl InliningCostModel {
             return false;
         }
 
-        // Always inline functions marked with InlineHint::Always (Haxe `inline` keyword)
-        if callee.attributes.inline == super::InlineHint::Always {
+        match callee.attributes.inline {
+            // `@:inline(never)`
+            super::InlineHint::Never => return false,
+            // Haxe `inline` keyword or `@:inline(always)`
+            super::InlineHint::Always => return true,
+            _ => {}
+        }
+
+        let is_hot = match site_hotness {
+            Some((0, _)) => return false,
+            Some((count, hot_threshold)) => count >= hot_threshold,
+            None => false,
+        };
+        if is_hot && callee.attributes.inline == super::InlineHint::Hint {
             return true;
         }
 
@@ -231,6 +313,10 @@ impl InliningCostModel {
             threshold += self.small_function_bonus as f64;
         }
 
+        if is_hot {
+            threshold *= self.hot_site_bonus;
+        }
+
         inst_count as f64 <= threshold
     }
 }
@@ -241,6 +327,8 @@ pub struct InliningPass {
     cost_model: InliningCostModel,
     /// Maximum iterations of inlining
     max_iterations: usize,
+    /// Call-site counts from a tiered recompile, if any
+    profile: Option<CallProfile>,
 }
 
 impl InliningPass {
@@ -248,6 +336,7 @@ impl InliningPass {
         Self {
             cost_model: InliningCostModel::default(),
             max_iterations: 5,
+            profile: None,
         }
     }
 
@@ -255,9 +344,16 @@ impl InliningPass {
         Self {
             cost_model,
             max_iterations: 5,
+            profile: None,
         }
     }
 
+    /// Weigh call sites by the executions recorded in `profile`
+    pub fn with_profile(mut self, profile: CallProfile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
     /// Inline a specific call site.
     fn inline_call_site(
         module: &mut IrModule,
@@ -580,7 +676,17 @@ impl OptimizationPass for InliningPass {
 
             for site in &call_graph.call_sites {
                 if let Some(callee) = module.functions.get(&site.callee) {
-                    if self.cost_model.should_inline(callee, site, &call_graph) {
+                    let site_hotness = self.profile.as_ref().and_then(|profile| {
+                        let caller = module.functions.get(&site.caller)?;
+                        let count = profile.site_count(caller, site)?;
+                        Some((count, profile.hot_threshold))
+                    });
+                    if self.cost_model.should_inline_profiled(
+                        callee,
+                        site,
+                        &call_graph,
+                        site_hotness,
+                    ) {
                         candidates.push(site.clone());
                     }
                 }
@@ -658,6 +764,144 @@ impl OptimizationPass for InliningPass {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ir::builder::*;
+    use crate::ir::InlineHint;
+    use crate::tast::SymbolId;
+
+    /// A helper returning `2 + 3 + ...` with `adds` additions, and a caller
+    /// branching on a constant with one call to it on each side
+    struct BranchingCaller {
+        module: IrModule,
+        helper: IrFunctionId,
+        caller: IrFunctionId,
+        entry: IrBlockId,
+        then_block: IrBlockId,
+        else_block: IrBlockId,
+    }
+
+    fn branching_caller(adds: usize) -> BranchingCaller {
+        let mut builder = IrBuilder::new("test".to_string(), "test.hx".to_string());
+
+        let sig = FunctionSignatureBuilder::new().returns(IrType::I32).build();
+        let helper = builder.start_function(SymbolId::from_raw(1), "helper".to_string(), sig);
+        let mut value = builder.build_int(2, IrType::I32).unwrap();
+        for _ in 0..adds {
+            let three = builder.build_int(3, IrType::I32).unwrap();
+            value = builder.build_add(value, three, false).unwrap();
+        }
+        builder.build_return(Some(value));
+        builder.finish_function();
+
+        let sig = FunctionSignatureBuilder::new().returns(IrType::I32).build();
+        let caller = builder.start_function(SymbolId::from_raw(2), "caller".to_string(), sig);
+        let entry = builder.current_block().unwrap();
+        let then_block = builder.create_block().unwrap();
+        let else_block = builder.create_block().unwrap();
+        let condition = builder.build_bool(true).unwrap();
+        builder.build_cond_branch(condition, then_block, else_block);
+        for block in [then_block, else_block] {
+            builder.switch_to_block(block);
+            let result = builder
+                .build_call_direct(helper, Vec::new(), IrType::I32)
+                .unwrap();
+            builder.build_return(Some(result));
+        }
+        builder.finish_function();
+
+        BranchingCaller {
+            module: builder.module,
+            helper,
+            caller,
+            entry,
+            then_block,
+            else_block,
+        }
+    }
+
+    #[test]
+    fn test_profile_skips_cold_call_sites() {
+        let BranchingCaller {
+            mut module,
+            caller,
+            entry,
+            then_block,
+            else_block,
+            ..
+        } = branching_caller(1);
+        let mut profile = CallProfile {
+            hot_threshold: 1000,
+            ..Default::default()
+        };
+        profile
+            .branch_counts
+            .insert(caller, BranchCounts::from([(entry, (500, 0))]));
+
+        let result = InliningPass::new()
+            .with_profile(profile)
+            .run_on_module(&mut module);
+
+        assert_eq!(result.stats.get("functions_inlined"), Some(&1));
+        let calls_in = |block: IrBlockId| {
+            module.functions[&caller].cfg.blocks[&block]
+                .instructions
+                .iter()
+                .any(|inst| matches!(inst, IrInstruction::CallDirect { .. }))
+        };
+        assert!(!calls_in(then_block));
+        assert!(calls_in(else_block));
+    }
+
+    #[test]
+    fn test_inline_metadata_overrides() {
+        let BranchingCaller {
+            mut module,
+            helper,
+            caller,
+            entry,
+            then_block,
+            ..
+        } = branching_caller(60);
+        let cost_model = InliningCostModel::default();
+        let call_graph = CallGraph::build(&module);
+        let site = call_graph
+            .call_sites
+            .iter()
+            .find(|site| site.block == then_block)
+            .unwrap()
+            .clone();
+        let mut profile = CallProfile {
+            hot_threshold: 1000,
+            ..Default::default()
+        };
+        profile
+            .branch_counts
+            .insert(caller, BranchCounts::from([(entry, (5000, 0))]));
+        let count = profile.site_count(&module.functions[&caller], &site);
+        assert_eq!(count, Some(5000));
+        let hot = count.map(|count| (count, profile.hot_threshold));
+
+        // Too large for the static budget, but within the hot one
+        let callee = &module.functions[&helper];
+        assert!(!cost_model.should_inline(callee, &site, &call_graph));
+        assert!(cost_model.should_inline_profiled(callee, &site, &call_graph, hot));
+
+        // `@:inline(never)` wins even at a hot site
+        let callee = module.functions.get_mut(&helper).unwrap();
+        callee.attributes.inline = InlineHint::Never;
+        let callee = &module.functions[&helper];
+        assert!(!cost_model.should_inline_profiled(callee, &site, &call_graph, hot));
+
+        // `@:inline(hot)` lifts the size limit at hot sites only
+        let small_budget = InliningCostModel {
+            max_inline_size: 1,
+            ..Default::default()
+        };
+        let callee = module.functions.get_mut(&helper).unwrap();
+        callee.attributes.inline = InlineHint::Hint;
+        let callee = &module.functions[&helper];
+        assert!(small_budget.should_inline_profiled(callee, &site, &call_graph, hot));
+        assert!(!small_budget.should_inline(callee, &site, &call_graph));
+    }
 
     #[test]
     fn test_cost_model_small_function() {
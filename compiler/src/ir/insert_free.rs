@@ -13,7 +13,8 @@
 //! 3. Check escape conditions:
 //!    - Pointer returned from function → escapes
 //!    - Pointer passed as argument to a function call → escapes
-//!      (except for known-safe anon object accessors like rayzor_anon_set_field_by_index)
+//!      (except for known-safe anon object accessors like rayzor_anon_set_field_by_index,
+//!      and parameters the callee's escape summary keeps; see `escape_summary`)
 //!    - Pointer stored as a value (not as a store target) → escapes
 //!    - Pointer placed into a struct (CreateStruct) → escapes
 //!    - Pointer stored to global or used in memcpy → escapes
@@ -22,11 +23,13 @@
 //!    (or rayzor_anon_drop for Arc objects) before each return instruction
 
 use super::blocks::{IrBlockId, IrTerminator};
+use super::escape_summary::EscapeSummaries;
 use super::functions::IrFunctionId;
 use super::instructions::{IrInstruction, OwnershipMode};
 use super::optimization::{OptimizationPass, OptimizationResult};
 use super::{IrFunction, IrId, IrModule, IrType};
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 /// Collected function IDs for allocation/deallocation patterns
 struct AllocFuncIds {
@@ -38,11 +41,22 @@ struct AllocFuncIds {
     anon_safe_ids: HashSet<IrFunctionId>,
 }
 
-pub struct InsertFreePass;
+pub struct InsertFreePass {
+    /// Escape summaries of the whole program; without them, only callees in
+    /// the module being optimized are summarized
+    summaries: Option<Arc<EscapeSummaries>>,
+}
 
 impl InsertFreePass {
     pub fn new() -> Self {
-        InsertFreePass
+        InsertFreePass { summaries: None }
+    }
+
+    /// Look callees up in `summaries` (`EscapeSummaries::for_modules`), so
+    /// allocations handed to functions in other modules can still be freed
+    pub fn with_summaries(mut self, summaries: Arc<EscapeSummaries>) -> Self {
+        self.summaries = Some(summaries);
+        self
     }
 }
 
@@ -100,10 +114,14 @@ impl OptimizationPass for InsertFreePass {
             ids.anon_safe_ids.insert(drop_id);
         }
 
+        let summaries = match &self.summaries {
+            Some(summaries) => summaries.clone(),
+            None => Arc::new(EscapeSummaries::for_module(module)),
+        };
         let func_ids: Vec<_> = module.functions.keys().cloned().collect();
         for func_id in func_ids {
             if let Some(function) = module.functions.get_mut(&func_id) {
-                total_inserted += insert_free_for_function(function, &ids);
+                total_inserted += insert_free_for_function(function, &ids, &summaries);
             }
         }
 
@@ -150,7 +168,11 @@ fn classify_func(fid: IrFunctionId, name: &str, ids: &mut AllocFuncIds) {
 
 /// Insert Free instructions for non-escaping allocations in a single function.
 /// Returns the number of Free instructions inserted.
-fn insert_free_for_function(function: &mut IrFunction, ids: &AllocFuncIds) -> usize {
+fn insert_free_for_function(
+    function: &mut IrFunction,
+    ids: &AllocFuncIds,
+    summaries: &EscapeSummaries,
+) -> usize {
     if function.cfg.blocks.is_empty() {
         return 0;
     }
@@ -217,7 +239,7 @@ fn insert_free_for_function(function: &mut IrFunction, ids: &AllocFuncIds) -> us
         // For anon allocs, use modified escape analysis that whitelists safe accessors
         let empty = HashSet::new();
         let safe_ids = if is_anon { &ids.anon_safe_ids } else { &empty };
-        if !pointer_escapes(alloc_id, &derived, function, safe_ids) {
+        if !pointer_escapes(alloc_id, &derived, function, safe_ids, summaries) {
             allocs_needing_free.push(alloc_id);
         }
     }
@@ -367,22 +389,33 @@ fn build_derived_set(alloc_id: IrId, function: &IrFunction) -> HashSet<IrId> {
 }
 
 /// Check if a pointer (or any of its derived pointers) escapes the function.
-/// `safe_call_ids` are function IDs that don't capture the pointer (e.g., anon object accessors).
+/// `safe_call_ids` are function IDs that don't capture the pointer (e.g., anon object accessors);
+/// `summaries` tells which parameters of other callees don't.
 fn pointer_escapes(
     alloc_id: IrId,
     derived: &HashSet<IrId>,
     function: &IrFunction,
     safe_call_ids: &HashSet<IrFunctionId>,
+    summaries: &EscapeSummaries,
 ) -> bool {
     for block in function.cfg.blocks.values() {
         for inst in &block.instructions {
             match inst {
                 // Pointer passed as function argument → escapes
-                // (unless the call target is known-safe, e.g. rayzor_anon_* accessors)
-                IrInstruction::CallDirect { args, func_id, .. } => {
+                // (unless the call target is known-safe, e.g. rayzor_anon_* accessors,
+                // or its summary says the parameter doesn't escape)
+                IrInstruction::CallDirect {
+                    args,
+                    func_id,
+                    is_tail_call,
+                    ..
+                } => {
                     if !safe_call_ids.contains(func_id) {
-                        for arg in args {
-                            if *arg == alloc_id || derived.contains(arg) {
+                        for (index, arg) in args.iter().enumerate() {
+                            if (*arg == alloc_id || derived.contains(arg))
+                                && (*is_tail_call
+                                    || summaries.arg_escapes(*func_id, args.len(), index))
+                            {
                                 return true;
                             }
                         }
@@ -175,14 +175,18 @@ pub enum IrInstruction {
         ty: IrType,
     },
 
-    /// Memory copy
+    /// Memory copy (`dest` is the destination address, not a result register)
     MemCopy { dest: IrId, src: IrId, size: IrId },
 
-    /// Memory set
+    /// Memory set (`dest` is the destination address, not a result register)
     MemSet { dest: IrId, value: IrId, size: IrId },
 
     // === Type Operations ===
     /// Type cast
+    ///
+    /// Float to integer truncates toward zero and never traps: NaN becomes 0
+    /// and out-of-range values (including infinities) saturate to the bounds
+    /// of `to_ty`. `Std.int` follows the same rule with Int32 bounds.
     Cast {
         dest: IrId,
         src: IrId,
@@ -402,6 +406,9 @@ pub enum BinaryOp {
     FSub,
     FMul,
     FDiv,
+    /// Float remainder with C `fmod` semantics (also `Rem` on floats): the
+    /// result has the sign of the dividend, `x % 0` and `inf % y` are NaN,
+    /// `x % inf` is `x`.
     FRem,
 }
 
@@ -493,8 +500,6 @@ impl IrInstruction {
             IrInstruction::Cmp { dest, .. } |
             IrInstruction::Alloc { dest, .. } |
             IrInstruction::GetElementPtr { dest, .. } |
-            IrInstruction::MemCopy { dest, .. } |
-            IrInstruction::MemSet { dest, .. } |
             IrInstruction::Cast { dest, .. } |
             IrInstruction::BitCast { dest, .. } |
             IrInstruction::LandingPad { dest, .. } |
@@ -541,8 +546,6 @@ impl IrInstruction {
             | IrInstruction::Clone { dest, .. }
             | IrInstruction::Load { dest, .. }
             | IrInstruction::LoadGlobal { dest, .. }
-            | IrInstruction::MemCopy { dest, .. }
-            | IrInstruction::MemSet { dest, .. }
             | IrInstruction::BinOp { dest, .. }
             | IrInstruction::UnOp { dest, .. }
             | IrInstruction::Cmp { dest, .. }
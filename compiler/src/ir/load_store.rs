@@ -0,0 +1,383 @@
+//! Load/Store Elimination — redundant loads and dead stores within a block.
+//!
+//! Lowered constructors initialize every field to its default and then
+//! assign the constructor arguments, and field reads right after a write
+//! reload what was just stored. With [`AliasAnalysis`] telling which
+//! accesses can overlap:
+//!
+//! - [`RedundantLoadEliminationPass`] replaces a load by a copy of the value
+//!   last stored to or loaded from the same bytes, as long as no store that
+//!   may alias came in between.
+//! - [`DeadStoreEliminationPass`] removes a store that is overwritten before
+//!   anything may read it, and stores to an object nothing else can reach
+//!   (see [`AliasAnalysis::is_local`]) that is freed or whose function
+//!   returns before they are read.
+//!
+//! Both work one block at a time. Calls may read and write any memory except
+//! objects local to the function; `memcpy`, `memset` and inline assembly
+//! end what is known.
+
+use super::alias_analysis::{allocator_ids, AliasAnalysis, AliasResult, MemoryAccess, PointerBase};
+use super::blocks::IrTerminator;
+use super::functions::IrFunctionId;
+use super::instructions::IrInstruction;
+use super::optimization::{OptimizationPass, OptimizationResult};
+use super::{IrFunction, IrId, IrModule};
+use std::collections::{HashMap, HashSet};
+
+/// What an instruction does to memory
+enum Effect {
+    Load(MemoryAccess),
+    Store(MemoryAccess),
+    Free(IrId),
+    /// May touch memory reachable from outside the function
+    Call,
+    /// May touch any memory
+    Clobber,
+    None,
+}
+
+fn effect(inst: &IrInstruction, function: &IrFunction) -> Effect {
+    match inst {
+        IrInstruction::Load { .. } | IrInstruction::VectorLoad { .. } => {
+            MemoryAccess::of(inst, function).map_or(Effect::Clobber, Effect::Load)
+        }
+        IrInstruction::Store { .. } | IrInstruction::VectorStore { .. } => {
+            MemoryAccess::of(inst, function).map_or(Effect::Clobber, Effect::Store)
+        }
+        IrInstruction::Free { ptr } => Effect::Free(*ptr),
+        IrInstruction::CallDirect { .. }
+        | IrInstruction::CallIndirect { .. }
+        | IrInstruction::StoreGlobal { .. } => Effect::Call,
+        IrInstruction::MemCopy { .. }
+        | IrInstruction::MemSet { .. }
+        | IrInstruction::InlineAsm { .. }
+        | IrInstruction::Throw { .. } => Effect::Clobber,
+        _ => Effect::None,
+    }
+}
+
+/// Per-function inputs shared by both passes
+fn analyze_functions<F>(module: &mut IrModule, mut optimize: F) -> usize
+where
+    F: FnMut(&mut IrFunction, &AliasAnalysis, bool) -> usize,
+{
+    let allocators = allocator_ids(module);
+    // A catch block reached through longjmp may read what a call left behind
+    let setjmp: HashSet<IrFunctionId> = module
+        .functions
+        .iter()
+        .map(|(&id, f)| (id, f.name.as_str()))
+        .chain(
+            module
+                .extern_functions
+                .iter()
+                .map(|(&id, f)| (id, f.name.as_str())),
+        )
+        .filter(|(_, name)| matches!(*name, "_setjmp" | "setjmp"))
+        .map(|(id, _)| id)
+        .collect();
+
+    let mut total = 0;
+    for function in module.functions.values_mut() {
+        if function.cfg.blocks.is_empty() {
+            continue;
+        }
+        let aliases = AliasAnalysis::new(function, &allocators);
+        let catches = function.cfg.blocks.values().any(|block| {
+            block.instructions.iter().any(|inst| match inst {
+                IrInstruction::CallDirect { func_id, .. } => setjmp.contains(func_id),
+                _ => false,
+            })
+        });
+        total += optimize(function, &aliases, catches);
+    }
+    total
+}
+
+fn result(count: usize, stat: &str, eliminated: bool) -> OptimizationResult {
+    if count == 0 {
+        return OptimizationResult::unchanged();
+    }
+    let mut stats = HashMap::new();
+    stats.insert(stat.to_string(), count);
+    OptimizationResult {
+        modified: true,
+        instructions_eliminated: if eliminated { count } else { 0 },
+        blocks_eliminated: 0,
+        stats,
+    }
+}
+
+/// Replaces loads of values already in a register by copies
+pub struct RedundantLoadEliminationPass;
+
+impl RedundantLoadEliminationPass {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for RedundantLoadEliminationPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OptimizationPass for RedundantLoadEliminationPass {
+    fn name(&self) -> &'static str {
+        "redundant-load-elimination"
+    }
+
+    fn run_on_module(&mut self, module: &mut IrModule) -> OptimizationResult {
+        let forwarded = analyze_functions(module, |function, aliases, _| {
+            forward_loads(function, aliases)
+        });
+        result(forwarded, "loads_forwarded", false)
+    }
+}
+
+fn forward_loads(function: &mut IrFunction, aliases: &AliasAnalysis) -> usize {
+    let mut forwarded = 0;
+    let block_ids: Vec<_> = function.cfg.blocks.keys().copied().collect();
+    for block_id in block_ids {
+        // Memory contents known to be in a register
+        let mut available: Vec<(MemoryAccess, IrId)> = Vec::new();
+        let count = function.cfg.blocks[&block_id].instructions.len();
+        for index in 0..count {
+            let inst = &function.cfg.blocks[&block_id].instructions[index];
+            match effect(inst, function) {
+                Effect::Load(access) => {
+                    let dest = inst.dest().expect("loads define a register");
+                    let known = available.iter().find(|(known, _)| {
+                        known.ty == access.ty
+                            && aliases.alias(known, &access) == AliasResult::MustAlias
+                    });
+                    if let Some(&(_, src)) = known {
+                        let block = function.cfg.blocks.get_mut(&block_id).unwrap();
+                        block.instructions[index] = IrInstruction::Copy { dest, src };
+                        forwarded += 1;
+                    } else {
+                        available.push((access, dest));
+                    }
+                }
+                Effect::Store(access) => {
+                    available
+                        .retain(|(known, _)| aliases.alias(known, &access) == AliasResult::NoAlias);
+                    if let (Some(_), IrInstruction::Store { value, .. }) = (&access.ty, inst) {
+                        available.push((access, *value));
+                    }
+                }
+                Effect::Call => {
+                    available.retain(|(known, _)| aliases.is_local(aliases.pointer(known.ptr).base))
+                }
+                Effect::Free(_) | Effect::Clobber => available.clear(),
+                Effect::None => {}
+            }
+        }
+    }
+    forwarded
+}
+
+/// Removes stores nothing reads
+pub struct DeadStoreEliminationPass;
+
+impl DeadStoreEliminationPass {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for DeadStoreEliminationPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OptimizationPass for DeadStoreEliminationPass {
+    fn name(&self) -> &'static str {
+        "dead-store-elimination"
+    }
+
+    fn run_on_module(&mut self, module: &mut IrModule) -> OptimizationResult {
+        let removed = analyze_functions(module, remove_dead_stores);
+        result(removed, "stores_eliminated", true)
+    }
+}
+
+fn remove_dead_stores(function: &mut IrFunction, aliases: &AliasAnalysis, catches: bool) -> usize {
+    let mut removed = 0;
+    let block_ids: Vec<_> = function.cfg.blocks.keys().copied().collect();
+    for block_id in block_ids {
+        let block = &function.cfg.blocks[&block_id];
+        let base = |access: &MemoryAccess| aliases.pointer(access.ptr).base;
+
+        // Walking backwards: stores later in the block that nothing reads
+        // first, local objects that are dead from here on, and the loads
+        // from them that still come
+        let mut overwritten: Vec<MemoryAccess> = Vec::new();
+        let mut dead_objects: HashSet<PointerBase> = HashSet::new();
+        let mut reads: Vec<MemoryAccess> = Vec::new();
+        if matches!(block.terminator, IrTerminator::Return { .. }) {
+            dead_objects = block
+                .instructions
+                .iter()
+                .filter_map(|inst| match effect(inst, function) {
+                    Effect::Store(access) => Some(base(&access)),
+                    _ => None,
+                })
+                .filter(|object| aliases.is_local(*object))
+                .collect();
+        }
+
+        let mut dead: HashSet<usize> = HashSet::new();
+        for (index, inst) in block.instructions.iter().enumerate().rev() {
+            match effect(inst, function) {
+                Effect::Store(access) => {
+                    let unread = dead_objects.contains(&base(&access))
+                        && reads
+                            .iter()
+                            .all(|read| aliases.alias(read, &access) == AliasResult::NoAlias);
+                    let overwritten_later = overwritten
+                        .iter()
+                        .any(|later| aliases.alias(later, &access) == AliasResult::MustAlias);
+                    if unread || overwritten_later {
+                        dead.insert(index);
+                    } else {
+                        overwritten.push(access);
+                    }
+                }
+                Effect::Load(access) => {
+                    overwritten
+                        .retain(|later| aliases.alias(later, &access) == AliasResult::NoAlias);
+                    if dead_objects.contains(&base(&access)) {
+                        reads.push(access);
+                    }
+                }
+                Effect::Free(ptr) => {
+                    let object = aliases.pointer(ptr).base;
+                    if aliases.is_local(object) {
+                        dead_objects.insert(object);
+                        reads.retain(|read| base(read) != object);
+                    }
+                }
+                Effect::Call if !catches => {
+                    overwritten.retain(|later| aliases.is_local(base(later)));
+                }
+                Effect::Call | Effect::Clobber => {
+                    overwritten.clear();
+                    dead_objects.clear();
+                    reads.clear();
+                }
+                Effect::None => {}
+            }
+        }
+
+        if !dead.is_empty() {
+            removed += dead.len();
+            let block = function.cfg.blocks.get_mut(&block_id).unwrap();
+            let mut index = 0;
+            block.instructions.retain(|_| {
+                let keep = !dead.contains(&index);
+                index += 1;
+                keep
+            });
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::mir_builder::MirBuilder;
+    use crate::ir::IrType;
+
+    /// `init(v, p)`: a fresh object `a` whose field 0 is set to 0 and then
+    /// to `v`, field 1 set to 0, `a`'s field 0 and `*p` stored and reloaded
+    /// around a call
+    fn constructor_module() -> (IrModule, IrFunctionId, IrId) {
+        let mut builder = MirBuilder::new("test");
+        let byte_ptr = builder.ptr_type(IrType::U8);
+        let ptr = builder.ptr_type(IrType::I64);
+        let malloc = builder
+            .begin_function("malloc")
+            .param("size", IrType::I64)
+            .returns(byte_ptr)
+            .build();
+        builder.mark_as_extern(malloc);
+        let effect = builder.begin_function("effect").build();
+        builder.mark_as_extern(effect);
+
+        let init = builder
+            .begin_function("init")
+            .param("v", IrType::I64)
+            .param("p", ptr.clone())
+            .returns(IrType::I64)
+            .build();
+        builder.set_current_function(init);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let (v, p) = (builder.get_param(0), builder.get_param(1));
+        let size = builder.const_i64(16);
+        let raw = builder.call(malloc, vec![size]).unwrap();
+        let zero = builder.const_i64(0);
+        let a = builder.ptr_add(raw, zero, ptr.clone());
+        let one = builder.const_i64(1);
+        let field = builder.ptr_add(raw, one, ptr);
+        builder.store(a, zero);
+        builder.store(field, zero);
+        builder.store(a, v);
+        builder.store(p, v);
+        builder.call(effect, vec![]);
+        let x = builder.load(a, IrType::I64);
+        let y = builder.load(p, IrType::I64);
+        let sum = builder.add(x, y, IrType::I64);
+        builder.ret(Some(sum));
+
+        (builder.finish(), init, v)
+    }
+
+    fn instructions(module: &IrModule, function: IrFunctionId) -> &[IrInstruction] {
+        let function = &module.functions[&function];
+        &function.cfg.blocks[&function.cfg.entry_block].instructions
+    }
+
+    #[test]
+    fn test_forwards_stored_value_of_local_object() {
+        let (mut module, init, v) = constructor_module();
+        let result = RedundantLoadEliminationPass::new().run_on_module(&mut module);
+        assert_eq!(result.stats.get("loads_forwarded"), Some(&1));
+
+        let instructions = instructions(&module, init);
+        // `a` survives the call; `*p` may have been changed by it
+        assert!(instructions
+            .iter()
+            .any(|inst| matches!(inst, IrInstruction::Copy { src, .. } if *src == v)));
+        assert_eq!(
+            instructions
+                .iter()
+                .filter(|inst| matches!(inst, IrInstruction::Load { .. }))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_removes_overwritten_and_unread_stores() {
+        let (mut module, init, v) = constructor_module();
+        let result = DeadStoreEliminationPass::new().run_on_module(&mut module);
+        assert_eq!(result.instructions_eliminated, 2);
+
+        // The first store to field 0 is overwritten, and nothing reads
+        // field 1 before the return
+        let stored: Vec<IrId> = instructions(&module, init)
+            .iter()
+            .filter_map(|inst| match inst {
+                IrInstruction::Store { value, .. } => Some(*value),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(stored, vec![v, v]);
+    }
+}
@@ -0,0 +1,1120 @@
+//! Loop Unrolling and Peeling (O3)
+//!
+//! Works on innermost loops in the shape `while` loops and `for` loops over
+//! ranges lower to: a preheader, a header holding the loop phis and the only
+//! exit test, and a single latch branching back to the header.
+//!
+//! - A loop with a constant trip count (a header phi with a constant start
+//!   and step compared against a constant bound) is unrolled fully when all
+//!   its iterations fit in [`LoopUnrollPass::full_unroll_size`] instructions.
+//!   Otherwise its body is repeated [`LoopUnrollPass::factor`] times within
+//!   [`LoopUnrollPass::partial_unroll_size`], after peeling the iterations
+//!   that don't divide evenly, and only the first copy tests for the exit.
+//! - A loop with a header phi that starts with one value and then keeps a
+//!   loop-invariant one that a branch depends on has its first iteration
+//!   peeled, which leaves the phi invariant:
+//!
+//! ```haxe
+//! var first = true;
+//! while (i < n) {
+//!     if (!first) buf.add(", ");
+//!     first = false;
+//!     ...
+//! }
+//! ```
+//!
+//! `@:unroll(N)` on a loop repeats its body N times, testing for the exit
+//! after every copy when the trip count isn't known; `@:unroll` unrolls it
+//! fully if the trip count is known, and `@:unroll(1)` leaves it alone.
+//! Requested unrolls are limited by a larger, fixed size budget.
+//!
+//! Constant folding and copy propagation clean up the copies afterwards.
+
+use super::blocks::{IrBasicBlock, IrPhiNode, IrTerminator, OptimizationHint};
+use super::functions::IrFunctionId;
+use super::instructions::{BinaryOp, CompareOp, IrInstruction};
+use super::loop_analysis::{DominatorTree, LoopNestInfo, NaturalLoop};
+use super::optimization::{
+    replace_terminator_uses, terminator_uses, InstructionExt, OptimizationPass, OptimizationResult,
+};
+use super::{IrBlockId, IrFunction, IrId, IrModule, IrType, IrValue};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Iterations simulated to find a constant trip count
+const MAX_TRIP_COUNT: u64 = 4096;
+
+/// Largest unrolled loop `@:unroll` may ask for, in instructions
+const MAX_REQUESTED_SIZE: usize = 4096;
+
+/// Unrolls and peels innermost loops
+pub struct LoopUnrollPass {
+    /// Most instructions a fully unrolled loop may have
+    pub full_unroll_size: usize,
+    /// Most instructions of the repeated body of a partially unrolled loop
+    pub partial_unroll_size: usize,
+    /// Copies of the body in a partially unrolled loop
+    pub factor: u32,
+    /// Largest loop whose first iteration is peeled
+    pub peel_size: usize,
+}
+
+impl LoopUnrollPass {
+    pub fn new() -> Self {
+        Self {
+            full_unroll_size: 256,
+            partial_unroll_size: 128,
+            factor: 4,
+            peel_size: 64,
+        }
+    }
+}
+
+impl Default for LoopUnrollPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OptimizationPass for LoopUnrollPass {
+    fn name(&self) -> &'static str {
+        "loop-unroll"
+    }
+
+    fn run_on_module(&mut self, module: &mut IrModule) -> OptimizationResult {
+        // Copies of a setjmp call would return to the wrong iteration
+        let setjmp: HashSet<IrFunctionId> = module
+            .functions
+            .iter()
+            .map(|(&id, f)| (id, f.name.as_str()))
+            .chain(
+                module
+                    .extern_functions
+                    .iter()
+                    .map(|(&id, f)| (id, f.name.as_str())),
+            )
+            .filter(|(_, name)| matches!(*name, "_setjmp" | "setjmp"))
+            .map(|(id, _)| id)
+            .collect();
+
+        let mut stats: HashMap<String, usize> = HashMap::new();
+        for function in module.functions.values_mut() {
+            let calls_setjmp = function.cfg.blocks.values().any(|block| {
+                block.instructions.iter().any(|inst| match inst {
+                    IrInstruction::CallDirect { func_id, .. } => setjmp.contains(func_id),
+                    _ => false,
+                })
+            });
+            if function.cfg.blocks.is_empty() || calls_setjmp {
+                continue;
+            }
+            self.run_on_loops(function, &mut stats);
+        }
+
+        if stats.is_empty() {
+            return OptimizationResult::unchanged();
+        }
+        OptimizationResult {
+            modified: true,
+            instructions_eliminated: 0,
+            blocks_eliminated: 0,
+            stats,
+        }
+    }
+}
+
+impl LoopUnrollPass {
+    /// Transform the loops of `function` one at a time, innermost first,
+    /// recomputing the loop nest after each change
+    fn run_on_loops(&self, function: &mut IrFunction, stats: &mut HashMap<String, usize>) {
+        let mut visited: HashSet<IrBlockId> = HashSet::new();
+        'analyze: loop {
+            let domtree = DominatorTree::compute(function);
+            let loops = LoopNestInfo::analyze(function, &domtree);
+            let mut headers: Vec<IrBlockId> = loops
+                .loops
+                .keys()
+                .filter(|header| !visited.contains(header))
+                .copied()
+                .collect();
+            headers.sort();
+            for header in headers {
+                let natural = &loops.loops[&header];
+                if !natural.children.is_empty() {
+                    continue;
+                }
+                visited.insert(header);
+                let Some(mut lp) = SimpleLoop::find(function, natural) else {
+                    continue;
+                };
+                if let Some(stat) = self.transform(function, &mut lp) {
+                    *stats.entry(stat.to_string()).or_insert(0) += 1;
+                    continue 'analyze;
+                }
+            }
+            break;
+        }
+    }
+
+    /// Unroll or peel `lp` if worthwhile; returns the statistic to count
+    fn transform(&self, function: &mut IrFunction, lp: &mut SimpleLoop) -> Option<&'static str> {
+        let requested = match lp.hint {
+            Some(Some(0 | 1)) => return None,
+            hint => hint,
+        };
+        let trips = trip_count(function, lp);
+
+        if let Some(trips) = trips {
+            let (fully, limit) = match requested {
+                None => (true, self.full_unroll_size),
+                Some(None) => (true, MAX_REQUESTED_SIZE),
+                Some(Some(factor)) => (u64::from(factor) >= trips, MAX_REQUESTED_SIZE),
+            };
+            if fully && trips.saturating_mul(lp.size as u64) <= limit as u64 {
+                unroll_fully(function, lp, trips);
+                return Some("loops_unrolled_fully");
+            }
+        }
+
+        let factor = match requested {
+            Some(Some(factor)) => factor,
+            _ => {
+                let fits = self.partial_unroll_size / lp.size.max(1);
+                self.factor.min(u32::try_from(fits).unwrap_or(u32::MAX))
+            }
+        };
+        if factor >= 2 && (factor as usize).saturating_mul(lp.size) <= MAX_REQUESTED_SIZE {
+            match trips {
+                Some(trips) if trips >= u64::from(factor) => {
+                    let originals = lp.snapshot(function);
+                    for _ in 0..trips % u64::from(factor) {
+                        peel(function, lp, &originals, false);
+                    }
+                    unroll(function, lp, &originals, factor, false);
+                    return Some("loops_unrolled");
+                }
+                None if matches!(requested, Some(Some(_))) => {
+                    if let Some(live_out) = live_out(function, lp) {
+                        let originals = lp.snapshot(function);
+                        let copies = unroll(function, lp, &originals, factor, true);
+                        repair_exit(function, lp, &copies, &live_out);
+                        return Some("loops_unrolled");
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if requested.is_none() && lp.size <= self.peel_size && has_invariant_phi(function, lp) {
+            if let Some(live_out) = live_out(function, lp) {
+                let originals = lp.snapshot(function);
+                let copy = peel(function, lp, &originals, true);
+                repair_exit(function, lp, std::slice::from_ref(&copy), &live_out);
+                simplify_header_phis(function, lp.header);
+                return Some("loops_peeled");
+            }
+        }
+        None
+    }
+}
+
+/// An innermost loop in the shape the transformations handle
+struct SimpleLoop {
+    header: IrBlockId,
+    latch: IrBlockId,
+    /// Only block outside the loop branching to the header
+    preheader: IrBlockId,
+    /// Target of the header's exit test
+    exit: IrBlockId,
+    /// Target of the header's test staying in the loop
+    body: IrBlockId,
+    /// Loop blocks, header first
+    blocks: Vec<IrBlockId>,
+    /// Blocks outside the loop when it was found
+    outside: Vec<IrBlockId>,
+    /// Header phis as (dest, value from the preheader, value from the latch)
+    phis: Vec<(IrId, IrId, IrId)>,
+    /// Instructions (phis included) of one iteration
+    size: usize,
+    /// `@:unroll` on the header
+    hint: Option<Option<u32>>,
+}
+
+impl SimpleLoop {
+    fn find(function: &IrFunction, natural: &NaturalLoop) -> Option<Self> {
+        let cfg = &function.cfg;
+        let header = natural.header;
+        let preheader = natural.preheader?;
+        let header_block = cfg.blocks.get(&header)?;
+        let latch = natural.back_edge_source;
+        if latch == header
+            || header_block.predecessors.len() != 2
+            || !header_block.predecessors.contains(&latch)
+            || !header_block.predecessors.contains(&preheader)
+            || !matches!(cfg.blocks.get(&latch)?.terminator,
+                IrTerminator::Branch { target } if target == header)
+        {
+            return None;
+        }
+
+        let IrTerminator::CondBranch {
+            true_target,
+            false_target,
+            ..
+        } = header_block.terminator
+        else {
+            return None;
+        };
+        let (body, exit) = match (
+            natural.blocks.contains(&true_target),
+            natural.blocks.contains(&false_target),
+        ) {
+            (true, false) => (true_target, false_target),
+            (false, true) => (false_target, true_target),
+            _ => return None,
+        };
+        if cfg.blocks.get(&exit)?.predecessors != [header] {
+            return None;
+        }
+
+        let mut blocks: Vec<IrBlockId> = natural.blocks.iter().copied().collect();
+        blocks.sort();
+        blocks.retain(|&block| block != header);
+        blocks.insert(0, header);
+
+        let mut size = 0;
+        for id in &blocks {
+            let block = cfg.blocks.get(id)?;
+            let leaves = block
+                .successors()
+                .iter()
+                .any(|succ| !natural.blocks.contains(succ));
+            let unclonable = block.instructions.iter().any(|inst| {
+                matches!(
+                    inst,
+                    IrInstruction::InlineAsm { .. }
+                        | IrInstruction::LandingPad { .. }
+                        | IrInstruction::Phi { .. }
+                )
+            });
+            if (leaves && *id != header) || unclonable || block.metadata.in_exception_handler {
+                return None;
+            }
+            size += block.phi_nodes.len() + block.instructions.len();
+        }
+
+        let mut phis = Vec::new();
+        for phi in &header_block.phi_nodes {
+            let incoming = |from: IrBlockId| {
+                let mut values = phi.incoming.iter().filter(|(pred, _)| *pred == from);
+                match (values.next(), values.next()) {
+                    (Some(&(_, value)), None) => Some(value),
+                    _ => None,
+                }
+            };
+            if phi.incoming.len() != 2 {
+                return None;
+            }
+            phis.push((phi.dest, incoming(preheader)?, incoming(latch)?));
+        }
+
+        let hint = header_block
+            .metadata
+            .optimization_hints
+            .iter()
+            .find_map(|hint| match hint {
+                OptimizationHint::Unroll { factor } => Some(*factor),
+                _ => None,
+            });
+
+        Some(Self {
+            header,
+            latch,
+            preheader,
+            exit,
+            body,
+            outside: cfg
+                .blocks
+                .keys()
+                .filter(|block| !natural.blocks.contains(block))
+                .copied()
+                .collect(),
+            blocks,
+            phis,
+            size,
+            hint,
+        })
+    }
+
+    /// The loop blocks as they are now, to copy from
+    fn snapshot(&self, function: &IrFunction) -> Vec<IrBasicBlock> {
+        self.blocks
+            .iter()
+            .map(|id| function.cfg.blocks[id].clone())
+            .collect()
+    }
+
+    /// Registers defined in the loop
+    fn defines(&self, function: &IrFunction) -> HashSet<IrId> {
+        self.blocks
+            .iter()
+            .flat_map(|id| {
+                let block = &function.cfg.blocks[id];
+                block
+                    .phi_nodes
+                    .iter()
+                    .map(|phi| phi.dest)
+                    .chain(block.instructions.iter().filter_map(|inst| inst.dest()))
+            })
+            .collect()
+    }
+}
+
+fn const_int(value: &IrValue) -> Option<i64> {
+    match value {
+        IrValue::I8(v) => Some(i64::from(*v)),
+        IrValue::I16(v) => Some(i64::from(*v)),
+        IrValue::I32(v) => Some(i64::from(*v)),
+        IrValue::I64(v) => Some(*v),
+        IrValue::U8(v) => Some(i64::from(*v)),
+        IrValue::U16(v) => Some(i64::from(*v)),
+        IrValue::U32(v) => Some(i64::from(*v)),
+        IrValue::U64(v) => i64::try_from(*v).ok(),
+        _ => None,
+    }
+}
+
+/// Times the body runs, if the header's test compares a header phi with a
+/// constant start and step against a constant
+fn trip_count(function: &IrFunction, lp: &SimpleLoop) -> Option<u64> {
+    let consts: HashMap<IrId, i64> = function
+        .cfg
+        .blocks
+        .values()
+        .flat_map(|block| &block.instructions)
+        .filter_map(|inst| match inst {
+            IrInstruction::Const { dest, value } => Some((*dest, const_int(value)?)),
+            _ => None,
+        })
+        .collect();
+
+    let header = &function.cfg.blocks[&lp.header];
+    let IrTerminator::CondBranch {
+        condition,
+        true_target,
+        ..
+    } = header.terminator
+    else {
+        return None;
+    };
+    let (op, left, right) = header.instructions.iter().find_map(|inst| match inst {
+        IrInstruction::Cmp {
+            dest,
+            op,
+            left,
+            right,
+        } if *dest == condition => Some((*op, *left, *right)),
+        _ => None,
+    })?;
+    let (phi, start, next, phi_on_left) = lp.phis.iter().find_map(|&(phi, start, next)| {
+        if phi == left {
+            Some((phi, start, next, true))
+        } else if phi == right {
+            Some((phi, start, next, false))
+        } else {
+            None
+        }
+    })?;
+    let bound = *consts.get(if phi_on_left { &right } else { &left })?;
+    let start = *consts.get(&start)?;
+    let step = lp
+        .blocks
+        .iter()
+        .flat_map(|id| &function.cfg.blocks[id].instructions)
+        .find_map(|inst| match inst {
+            IrInstruction::BinOp {
+                dest,
+                op: BinaryOp::Add,
+                left,
+                right,
+            } if *dest == next => match (*left == phi, *right == phi) {
+                (true, false) => consts.get(right).copied(),
+                (false, true) => consts.get(left).copied(),
+                _ => None,
+            },
+            IrInstruction::BinOp {
+                dest,
+                op: BinaryOp::Sub,
+                left,
+                right,
+            } if *dest == next && *left == phi && *right != phi => consts.get(right)?.checked_neg(),
+            _ => None,
+        })?;
+
+    let ty = &header.phi_nodes.iter().find(|p| p.dest == phi)?.ty;
+    let range = match ty {
+        IrType::I8 => i64::from(i8::MIN)..=i64::from(i8::MAX),
+        IrType::I16 => i64::from(i16::MIN)..=i64::from(i16::MAX),
+        IrType::I32 => i64::from(i32::MIN)..=i64::from(i32::MAX),
+        IrType::I64 => i64::MIN..=i64::MAX,
+        IrType::U8 => 0..=i64::from(u8::MAX),
+        IrType::U16 => 0..=i64::from(u16::MAX),
+        IrType::U32 => 0..=i64::from(u32::MAX),
+        _ => return None,
+    };
+
+    let stays_on_true = true_target == lp.body;
+    let mut value = start;
+    let mut trips = 0;
+    loop {
+        let (a, b) = if phi_on_left {
+            (value, bound)
+        } else {
+            (bound, value)
+        };
+        let taken = match op {
+            CompareOp::Eq => a == b,
+            CompareOp::Ne => a != b,
+            CompareOp::Lt => a < b,
+            CompareOp::Le => a <= b,
+            CompareOp::Gt => a > b,
+            CompareOp::Ge => a >= b,
+            CompareOp::ULt => (a as u64) < (b as u64),
+            CompareOp::ULe => (a as u64) <= (b as u64),
+            CompareOp::UGt => (a as u64) > (b as u64),
+            CompareOp::UGe => (a as u64) >= (b as u64),
+            _ => return None,
+        };
+        if taken != stays_on_true {
+            return Some(trips);
+        }
+        trips += 1;
+        if trips > MAX_TRIP_COUNT {
+            return None;
+        }
+        // Stop at overflow rather than model wrapping
+        value = value
+            .checked_add(step)
+            .filter(|value| range.contains(value))?;
+    }
+}
+
+/// Whether a header phi takes a loop-invariant value after the first
+/// iteration and a branch in the loop depends on it
+fn has_invariant_phi(function: &IrFunction, lp: &SimpleLoop) -> bool {
+    let defined = lp.defines(function);
+    let mut conditions: Vec<IrId> = Vec::new();
+    for id in &lp.blocks {
+        let block = &function.cfg.blocks[id];
+        let IrTerminator::CondBranch { condition, .. } = block.terminator else {
+            continue;
+        };
+        conditions.push(condition);
+        let operands = lp
+            .blocks
+            .iter()
+            .flat_map(|id| &function.cfg.blocks[id].instructions)
+            .find(|inst| inst.dest() == Some(condition))
+            .map(|inst| inst.uses())
+            .unwrap_or_default();
+        conditions.extend(operands);
+    }
+    lp.phis.iter().any(|&(phi, start, next)| {
+        next != start && !defined.contains(&next) && conditions.contains(&phi)
+    })
+}
+
+/// Registers of the header used after the loop, with their types; `None`
+/// if one has no known type
+fn live_out(function: &IrFunction, lp: &SimpleLoop) -> Option<Vec<(IrId, IrType)>> {
+    let header = &function.cfg.blocks[&lp.header];
+    let mut used: HashSet<IrId> = HashSet::new();
+    for id in &lp.outside {
+        let block = &function.cfg.blocks[id];
+        for inst in &block.instructions {
+            used.extend(inst.uses());
+        }
+        used.extend(terminator_uses(&block.terminator));
+        // The exit block's own phis get incoming values instead
+        if *id != lp.exit {
+            for phi in &block.phi_nodes {
+                used.extend(phi.incoming.iter().map(|&(_, value)| value));
+            }
+        }
+    }
+
+    let phis = header.phi_nodes.iter().map(|phi| (phi.dest, Some(&phi.ty)));
+    let instructions = header
+        .instructions
+        .iter()
+        .filter_map(|inst| inst.dest())
+        .map(|dest| (dest, function.register_types.get(&dest)));
+    phis.chain(instructions)
+        .filter(|(dest, _)| used.contains(dest))
+        .map(|(dest, ty)| Some((dest, ty?.clone())))
+        .collect()
+}
+
+/// Registers and blocks of one copy of the loop body
+struct Iteration {
+    blocks: BTreeMap<IrBlockId, IrBlockId>,
+    regs: BTreeMap<IrId, IrId>,
+}
+
+impl Iteration {
+    fn block(&self, block: IrBlockId) -> IrBlockId {
+        self.blocks[&block]
+    }
+
+    /// The copy of `reg`, or `reg` itself if defined outside the loop
+    fn value(&self, reg: IrId) -> IrId {
+        self.regs.get(&reg).copied().unwrap_or(reg)
+    }
+}
+
+/// Add a copy of the loop body. Its header sets its phis' registers to
+/// `inputs` and tests for the exit only if `keep_exit`; its latch branches
+/// to the original header, and nothing branches to it yet.
+fn clone_iteration(
+    function: &mut IrFunction,
+    lp: &SimpleLoop,
+    originals: &[IrBasicBlock],
+    inputs: &[IrId],
+    keep_exit: bool,
+    suffix: &str,
+) -> Iteration {
+    let mut regs = BTreeMap::new();
+    for block in originals {
+        let phis = block.phi_nodes.iter().map(|phi| (phi.dest, Some(&phi.ty)));
+        let instructions = block
+            .instructions
+            .iter()
+            .filter_map(|inst| inst.dest())
+            .map(|dest| (dest, None));
+        for (dest, phi_ty) in phis.chain(instructions) {
+            let reg = function.alloc_reg();
+            if let Some(ty) = function.register_types.get(&dest).or(phi_ty).cloned() {
+                function.register_types.insert(reg, ty);
+            }
+            regs.insert(dest, reg);
+        }
+    }
+    let blocks: BTreeMap<IrBlockId, IrBlockId> = originals
+        .iter()
+        .map(|block| (block.id, function.cfg.create_block()))
+        .collect();
+    let target = |block: IrBlockId| match blocks.get(&block) {
+        Some(&copy) if block != lp.header => copy,
+        _ => block,
+    };
+
+    for original in originals {
+        let mut block = original.clone();
+        block.id = blocks[&original.id];
+        block.label = original
+            .label
+            .as_ref()
+            .map(|label| format!("{}.{}", label, suffix));
+        block.metadata.is_loop_header = false;
+        block
+            .metadata
+            .optimization_hints
+            .retain(|hint| !matches!(hint, OptimizationHint::Unroll { .. }));
+
+        for inst in &mut block.instructions {
+            inst.replace_uses(&regs);
+            if let Some(dest) = inst.dest() {
+                inst.replace_dest(regs[&dest]);
+            }
+        }
+        replace_terminator_uses(&mut block.terminator, &regs);
+        retarget(&mut block.terminator, target);
+
+        if original.id == lp.header {
+            let copies: Vec<IrInstruction> = block
+                .phi_nodes
+                .drain(..)
+                .zip(inputs)
+                .map(|(phi, &src)| IrInstruction::Copy {
+                    dest: regs[&phi.dest],
+                    src,
+                })
+                .collect();
+            block.instructions.splice(0..0, copies);
+            block.predecessors.clear();
+            if !keep_exit {
+                block.terminator = IrTerminator::Branch {
+                    target: blocks[&lp.body],
+                };
+            }
+        } else {
+            for phi in &mut block.phi_nodes {
+                phi.dest = regs[&phi.dest];
+                for (pred, value) in &mut phi.incoming {
+                    *pred = blocks[pred];
+                    *value = regs.get(value).copied().unwrap_or(*value);
+                }
+            }
+            for pred in &mut block.predecessors {
+                *pred = blocks[pred];
+            }
+        }
+        function.cfg.blocks.insert(block.id, block);
+    }
+
+    Iteration { blocks, regs }
+}
+
+/// Point the edges of `terminator` at `map` of their targets
+fn retarget(terminator: &mut IrTerminator, map: impl Fn(IrBlockId) -> IrBlockId) {
+    match terminator {
+        IrTerminator::Branch { target } => *target = map(*target),
+        IrTerminator::CondBranch {
+            true_target,
+            false_target,
+            ..
+        } => {
+            *true_target = map(*true_target);
+            *false_target = map(*false_target);
+        }
+        IrTerminator::Switch { cases, default, .. } => {
+            for (_, target) in cases {
+                *target = map(*target);
+            }
+            *default = map(*default);
+        }
+        _ => {}
+    }
+}
+
+/// Run the first iteration before the loop; the copy's latch becomes the
+/// loop's preheader
+fn peel(
+    function: &mut IrFunction,
+    lp: &mut SimpleLoop,
+    originals: &[IrBasicBlock],
+    keep_exit: bool,
+) -> Iteration {
+    let inputs: Vec<IrId> = lp.phis.iter().map(|&(_, start, _)| start).collect();
+    let copy = clone_iteration(function, lp, originals, &inputs, keep_exit, "peel");
+    let (header, latch) = (copy.block(lp.header), copy.block(lp.latch));
+
+    let blocks = &mut function.cfg.blocks;
+    let preheader = blocks.get_mut(&lp.preheader).unwrap();
+    retarget(&mut preheader.terminator, |target| {
+        if target == lp.header {
+            header
+        } else {
+            target
+        }
+    });
+    blocks
+        .get_mut(&header)
+        .unwrap()
+        .predecessors
+        .push(lp.preheader);
+
+    let original = blocks.get_mut(&lp.header).unwrap();
+    for pred in &mut original.predecessors {
+        if *pred == lp.preheader {
+            *pred = latch;
+        }
+    }
+    for (phi, (_, start, next)) in original.phi_nodes.iter_mut().zip(&mut lp.phis) {
+        *start = copy.value(*next);
+        for (pred, value) in &mut phi.incoming {
+            if *pred == lp.preheader {
+                *pred = latch;
+                *value = *start;
+            }
+        }
+    }
+    lp.preheader = latch;
+    copy
+}
+
+/// Repeat the body `factor` times per trip around the loop; returns the
+/// added copies, in order
+fn unroll(
+    function: &mut IrFunction,
+    lp: &SimpleLoop,
+    originals: &[IrBasicBlock],
+    factor: u32,
+    keep_exits: bool,
+) -> Vec<Iteration> {
+    let mut copies: Vec<Iteration> = Vec::new();
+    let mut latch = lp.latch;
+    for _ in 1..factor {
+        let inputs: Vec<IrId> = lp
+            .phis
+            .iter()
+            .map(|&(_, _, next)| copies.last().map_or(next, |copy| copy.value(next)))
+            .collect();
+        let copy = clone_iteration(function, lp, originals, &inputs, keep_exits, "unroll");
+        let header = copy.block(lp.header);
+        let previous = function.cfg.blocks.get_mut(&latch).unwrap();
+        retarget(&mut previous.terminator, |target| {
+            if target == lp.header {
+                header
+            } else {
+                target
+            }
+        });
+        function
+            .cfg
+            .blocks
+            .get_mut(&header)
+            .unwrap()
+            .predecessors
+            .push(latch);
+        latch = copy.block(lp.latch);
+        copies.push(copy);
+    }
+
+    let Some(last) = copies.last() else {
+        return copies;
+    };
+    let original = function.cfg.blocks.get_mut(&lp.header).unwrap();
+    for pred in &mut original.predecessors {
+        if *pred == lp.latch {
+            *pred = latch;
+        }
+    }
+    for (phi, &(_, _, next)) in original.phi_nodes.iter_mut().zip(&lp.phis) {
+        for (pred, value) in &mut phi.incoming {
+            if *pred == lp.latch {
+                *pred = latch;
+                *value = last.value(next);
+            }
+        }
+    }
+    copies
+}
+
+/// Replace the loop by `trips` copies of its body
+fn unroll_fully(function: &mut IrFunction, lp: &mut SimpleLoop, trips: u64) {
+    let originals = lp.snapshot(function);
+    for _ in 0..trips {
+        peel(function, lp, &originals, false);
+    }
+
+    // What is left of the loop is its final exit test
+    let header = function.cfg.blocks.get_mut(&lp.header).unwrap();
+    header.terminator = IrTerminator::Branch { target: lp.exit };
+    header.predecessors = vec![lp.preheader];
+    header.metadata.is_loop_header = false;
+    let copies: Vec<IrInstruction> = header
+        .phi_nodes
+        .drain(..)
+        .zip(&lp.phis)
+        .map(|(phi, &(_, start, _))| IrInstruction::Copy {
+            dest: phi.dest,
+            src: start,
+        })
+        .collect();
+    header.instructions.splice(0..0, copies);
+    for block in &lp.blocks[1..] {
+        function.cfg.blocks.remove(block);
+    }
+}
+
+/// Let the headers of `copies` leave the loop too: the exit block's phis
+/// get their values, and the `live_out` registers are merged in new phis
+/// that replace them after the loop
+fn repair_exit(
+    function: &mut IrFunction,
+    lp: &SimpleLoop,
+    copies: &[Iteration],
+    live_out: &[(IrId, IrType)],
+) {
+    let exit = function.cfg.blocks.get_mut(&lp.exit).unwrap();
+    for phi in &mut exit.phi_nodes {
+        let from_header = phi
+            .incoming
+            .iter()
+            .find(|(pred, _)| *pred == lp.header)
+            .map(|&(_, value)| value);
+        if let Some(value) = from_header {
+            for copy in copies {
+                phi.incoming
+                    .push((copy.block(lp.header), copy.value(value)));
+            }
+        }
+    }
+    exit.predecessors
+        .extend(copies.iter().map(|copy| copy.block(lp.header)));
+
+    let mut replacements = BTreeMap::new();
+    let mut phis = Vec::new();
+    for (value, ty) in live_out {
+        let dest = function.alloc_reg();
+        function.register_types.insert(dest, ty.clone());
+        let mut incoming = vec![(lp.header, *value)];
+        incoming.extend(
+            copies
+                .iter()
+                .map(|copy| (copy.block(lp.header), copy.value(*value))),
+        );
+        phis.push(IrPhiNode {
+            dest,
+            incoming,
+            ty: ty.clone(),
+        });
+        replacements.insert(*value, dest);
+    }
+    for id in &lp.outside {
+        let block = function.cfg.blocks.get_mut(id).unwrap();
+        for inst in &mut block.instructions {
+            inst.replace_uses(&replacements);
+        }
+        replace_terminator_uses(&mut block.terminator, &replacements);
+        if *id != lp.exit {
+            for phi in &mut block.phi_nodes {
+                for (_, value) in &mut phi.incoming {
+                    if let Some(&replacement) = replacements.get(value) {
+                        *value = replacement;
+                    }
+                }
+            }
+        }
+    }
+    function
+        .cfg
+        .blocks
+        .get_mut(&lp.exit)
+        .unwrap()
+        .phi_nodes
+        .extend(phis);
+}
+
+/// Turn phis of `header` with the same value on every edge into copies
+fn simplify_header_phis(function: &mut IrFunction, header: IrBlockId) {
+    let block = function.cfg.blocks.get_mut(&header).unwrap();
+    let mut copies = Vec::new();
+    block.phi_nodes.retain(|phi| {
+        let Some(&(_, src)) = phi.incoming.first() else {
+            return true;
+        };
+        let same = src != phi.dest && phi.incoming.iter().all(|&(_, value)| value == src);
+        if same {
+            copies.push(IrInstruction::Copy {
+                dest: phi.dest,
+                src,
+            });
+        }
+        !same
+    });
+    block.instructions.splice(0..0, copies);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::builder::*;
+    use crate::tast::SymbolId;
+
+    /// `sum(n)`: adds `i` to a sum for `i` from 0 while `i < bound`, where
+    /// `bound` is the constant `trips` or the parameter `n`
+    fn counting_loop(trips: Option<i64>) -> (IrModule, IrBlockId) {
+        let mut builder = IrBuilder::new("test".to_string(), "test.hx".to_string());
+        let sig = FunctionSignatureBuilder::new()
+            .param("n".to_string(), IrType::I32)
+            .returns(IrType::I32)
+            .build();
+        builder.start_function(SymbolId::from_raw(1), "sum".to_string(), sig);
+        let entry = builder.current_block().unwrap();
+        let zero = builder.build_int(0, IrType::I32).unwrap();
+        let one = builder.build_int(1, IrType::I32).unwrap();
+        let bound = match trips {
+            Some(trips) => builder.build_int(trips, IrType::I32).unwrap(),
+            None => builder.current_function().unwrap().signature.parameters[0].reg,
+        };
+        let header = builder.create_block().unwrap();
+        let body = builder.create_block().unwrap();
+        let exit = builder.create_block().unwrap();
+        builder.build_branch(header);
+
+        builder.switch_to_block(header);
+        let i = builder.build_phi(header, IrType::I32).unwrap();
+        let sum = builder.build_phi(header, IrType::I32).unwrap();
+        let cond = builder.build_cmp(CompareOp::Lt, i, bound).unwrap();
+        builder.build_cond_branch(cond, body, exit);
+
+        builder.switch_to_block(body);
+        let next_sum = builder.build_binop(BinaryOp::Add, sum, i).unwrap();
+        let next_i = builder.build_binop(BinaryOp::Add, i, one).unwrap();
+        builder.build_branch(header);
+
+        builder.add_phi_incoming(header, i, entry, zero);
+        builder.add_phi_incoming(header, i, body, next_i);
+        builder.add_phi_incoming(header, sum, entry, zero);
+        builder.add_phi_incoming(header, sum, body, next_sum);
+
+        builder.switch_to_block(exit);
+        builder.build_return(Some(sum));
+        builder.finish_function();
+        (builder.module, header)
+    }
+
+    fn function(module: &IrModule) -> &IrFunction {
+        module.functions.values().next().unwrap()
+    }
+
+    /// `@:unroll(factor)` on the loop at `header`
+    fn request_unroll(module: &mut IrModule, header: IrBlockId, factor: Option<u32>) {
+        let function = module.functions.values_mut().next().unwrap();
+        let header = function.cfg.blocks.get_mut(&header).unwrap();
+        header
+            .metadata
+            .optimization_hints
+            .push(OptimizationHint::Unroll { factor });
+    }
+
+    fn loop_count(function: &IrFunction) -> usize {
+        let domtree = DominatorTree::compute(function);
+        LoopNestInfo::analyze(function, &domtree).loops.len()
+    }
+
+    fn adds(function: &IrFunction) -> usize {
+        function
+            .cfg
+            .blocks
+            .values()
+            .flat_map(|block| &block.instructions)
+            .filter(|inst| {
+                matches!(
+                    inst,
+                    IrInstruction::BinOp {
+                        op: BinaryOp::Add,
+                        ..
+                    }
+                )
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_unrolls_constant_trip_count_fully() {
+        let (mut module, _) = counting_loop(Some(3));
+        let result = LoopUnrollPass::new().run_on_module(&mut module);
+        assert_eq!(result.stats.get("loops_unrolled_fully"), Some(&1));
+
+        let function = function(&module);
+        assert!(function.cfg.verify().is_ok());
+        assert_eq!(loop_count(function), 0);
+        // Three copies of the two additions; the original body is gone
+        assert_eq!(adds(function), 6);
+    }
+
+    #[test]
+    fn test_unrolls_partially_beyond_budget() {
+        let (mut module, header) = counting_loop(Some(10));
+        let mut pass = LoopUnrollPass::new();
+        pass.full_unroll_size = 16;
+        let result = pass.run_on_module(&mut module);
+        assert_eq!(result.stats.get("loops_unrolled"), Some(&1));
+
+        let function = function(&module);
+        assert!(function.cfg.verify().is_ok());
+        assert_eq!(loop_count(function), 1);
+        // 10 % 4 peeled iterations, then four bodies per trip
+        assert_eq!(adds(function), (2 + 4) * 2);
+        let header = &function.cfg.blocks[&header];
+        assert_eq!(header.predecessors.len(), 2);
+        assert!(header.phi_nodes.iter().all(|phi| phi.incoming.len() == 2));
+    }
+
+    #[test]
+    fn test_unknown_trip_count_needs_request() {
+        let (mut module, _) = counting_loop(None);
+        let result = LoopUnrollPass::new().run_on_module(&mut module);
+        assert!(!result.modified);
+
+        let (mut module, header) = counting_loop(None);
+        request_unroll(&mut module, header, Some(2));
+        let result = LoopUnrollPass::new().run_on_module(&mut module);
+        assert_eq!(result.stats.get("loops_unrolled"), Some(&1));
+
+        // Both headers test for the exit, which merges the sums
+        let function = function(&module);
+        assert!(function.cfg.verify().is_ok());
+        let exit = function
+            .cfg
+            .blocks
+            .values()
+            .find(|block| matches!(block.terminator, IrTerminator::Return { .. }))
+            .unwrap();
+        assert_eq!(exit.predecessors.len(), 2);
+        assert_eq!(exit.phi_nodes.len(), 1);
+        assert!(matches!(
+            exit.terminator,
+            IrTerminator::Return { value: Some(value) } if value == exit.phi_nodes[0].dest
+        ));
+    }
+
+    #[test]
+    fn test_peels_first_iteration() {
+        // `first` is true until the end of the first iteration
+        let mut builder = IrBuilder::new("test".to_string(), "test.hx".to_string());
+        let sig = FunctionSignatureBuilder::new()
+            .param("n".to_string(), IrType::I32)
+            .returns(IrType::Void)
+            .build();
+        builder.start_function(SymbolId::from_raw(1), "join".to_string(), sig);
+        let entry = builder.current_block().unwrap();
+        let n = builder.current_function().unwrap().signature.parameters[0].reg;
+        let zero = builder.build_int(0, IrType::I32).unwrap();
+        let one = builder.build_int(1, IrType::I32).unwrap();
+        let yes = builder.build_bool(true).unwrap();
+        let no = builder.build_bool(false).unwrap();
+        let header = builder.create_block().unwrap();
+        let body = builder.create_block().unwrap();
+        let separator = builder.create_block().unwrap();
+        let latch = builder.create_block().unwrap();
+        let exit = builder.create_block().unwrap();
+        builder.build_branch(header);
+
+        builder.switch_to_block(header);
+        let i = builder.build_phi(header, IrType::I32).unwrap();
+        let first = builder.build_phi(header, IrType::Bool).unwrap();
+        let cond = builder.build_cmp(CompareOp::Lt, i, n).unwrap();
+        builder.build_cond_branch(cond, body, exit);
+        builder.switch_to_block(body);
+        builder.build_cond_branch(first, latch, separator);
+        builder.switch_to_block(separator);
+        builder.build_branch(latch);
+        builder.switch_to_block(latch);
+        let next = builder.build_binop(BinaryOp::Add, i, one).unwrap();
+        builder.build_branch(header);
+        builder.add_phi_incoming(header, i, entry, zero);
+        builder.add_phi_incoming(header, i, latch, next);
+        builder.add_phi_incoming(header, first, entry, yes);
+        builder.add_phi_incoming(header, first, latch, no);
+        builder.switch_to_block(exit);
+        builder.build_return(None);
+        builder.finish_function();
+        let mut module = builder.module;
+
+        let result = LoopUnrollPass::new().run_on_module(&mut module);
+        assert_eq!(result.stats.get("loops_peeled"), Some(&1));
+
+        let function = function(&module);
+        assert!(function.cfg.verify().is_ok());
+        assert_eq!(loop_count(function), 1);
+        // In the loop `first` is always false now
+        let header = &function.cfg.blocks[&header];
+        assert_eq!(header.phi_nodes.len(), 1);
+        assert!(header.instructions.iter().any(|inst| matches!(
+            inst,
+            IrInstruction::Copy { dest, src } if *dest == first && *src == no
+        )));
+        assert_eq!(function.cfg.blocks[&exit].predecessors.len(), 2);
+    }
+
+    #[test]
+    fn test_unroll_one_disables() {
+        let (mut module, header) = counting_loop(Some(3));
+        request_unroll(&mut module, header, Some(1));
+        let result = LoopUnrollPass::new().run_on_module(&mut module);
+        assert!(!result.modified);
+    }
+}
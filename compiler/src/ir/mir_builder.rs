@@ -503,6 +503,16 @@ impl MirBuilder {
         dest
     }
 
+    /// Copy `size` bytes from `src` to `dest`
+    pub fn memcpy(&mut self, dest: IrId, src: IrId, size: IrId) {
+        self.insert_inst(IrInstruction::MemCopy { dest, src, size });
+    }
+
+    /// Fill `size` bytes at `dest` with the byte `value`
+    pub fn memset(&mut self, dest: IrId, value: IrId, size: IrId) {
+        self.insert_inst(IrInstruction::MemSet { dest, value, size });
+    }
+
     // === Comparison Operations ===
 
     /// Integer comparison (returns bool)
@@ -756,6 +766,15 @@ impl MirBuilder {
         });
     }
 
+    /// Multi-way branch on an integer value
+    pub fn switch(&mut self, value: IrId, cases: Vec<(i64, IrBlockId)>, default: IrBlockId) {
+        self.set_terminator(IrTerminator::Switch {
+            value,
+            cases,
+            default,
+        });
+    }
+
     /// Mark a function as extern by clearing its CFG blocks, setting External linkage,
     /// and setting FunctionKind to ExternC.
     /// This is used for runtime intrinsics like malloc/realloc/free and extern C functions
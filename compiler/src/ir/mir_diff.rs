@@ -0,0 +1,250 @@
+//! MIR diffs between pass pipelines (`rayzor dump --diff O0,O2`)
+//!
+//! Both sides are dumped with [`dump_function`] and compared line by line.
+//! Functions are matched by ID, since both modules are copies of the same
+//! lowered module; a function one pipeline removed (e.g. inlined and then
+//! dropped) is reported as removed.
+
+use super::dump::dump_function;
+use super::IrModule;
+use std::fmt::Write;
+
+/// Above this many cells the LCS table is skipped and the differing middle
+/// of the two texts is shown as one replaced block
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// One line of a line diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Line diff of two texts (longest common subsequence)
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut result: Vec<DiffLine> = old[..prefix].iter().map(|l| DiffLine::Same(l)).collect();
+    if old_mid.len() * new_mid.len() > MAX_LCS_CELLS {
+        result.extend(old_mid.iter().map(|l| DiffLine::Removed(l)));
+        result.extend(new_mid.iter().map(|l| DiffLine::Added(l)));
+    } else {
+        // lcs[i][j] = LCS length of old_mid[i..] and new_mid[j..]
+        let width = new_mid.len() + 1;
+        let mut lcs = vec![0u32; (old_mid.len() + 1) * width];
+        for i in (0..old_mid.len()).rev() {
+            for j in (0..new_mid.len()).rev() {
+                lcs[i * width + j] = if old_mid[i] == new_mid[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < old_mid.len() && j < new_mid.len() {
+            if old_mid[i] == new_mid[j] {
+                result.push(DiffLine::Same(old_mid[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+                result.push(DiffLine::Removed(old_mid[i]));
+                i += 1;
+            } else {
+                result.push(DiffLine::Added(new_mid[j]));
+                j += 1;
+            }
+        }
+        result.extend(old_mid[i..].iter().map(|l| DiffLine::Removed(l)));
+        result.extend(new_mid[j..].iter().map(|l| DiffLine::Added(l)));
+    }
+    result.extend(old[old.len() - suffix..].iter().map(|l| DiffLine::Same(l)));
+    result
+}
+
+/// Unified diff with `context` lines around each change, or None if the
+/// texts are equal
+pub fn unified_diff(
+    old_label: &str,
+    new_label: &str,
+    old: &str,
+    new: &str,
+    context: usize,
+) -> Option<String> {
+    let lines = diff_lines(old, new);
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !matches!(l, DiffLine::Same(_)))
+        .map(|(i, _)| i)
+        .collect();
+    if changed.is_empty() {
+        return None;
+    }
+
+    // Group changes whose context overlaps into hunks
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &i in &changed {
+        let start = i.saturating_sub(context);
+        let end = (i + context + 1).min(lines.len());
+        match hunks.last_mut() {
+            Some(hunk) if start <= hunk.1 => hunk.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(out, "--- {}", old_label);
+    let _ = writeln!(out, "+++ {}", new_label);
+    for (start, end) in hunks {
+        // 1-based line numbers of the hunk start on each side
+        let old_before = lines[..start]
+            .iter()
+            .filter(|l| !matches!(l, DiffLine::Added(_)))
+            .count();
+        let new_before = lines[..start]
+            .iter()
+            .filter(|l| !matches!(l, DiffLine::Removed(_)))
+            .count();
+        let hunk = &lines[start..end];
+        let old_len = hunk
+            .iter()
+            .filter(|l| !matches!(l, DiffLine::Added(_)))
+            .count();
+        let new_len = hunk
+            .iter()
+            .filter(|l| !matches!(l, DiffLine::Removed(_)))
+            .count();
+        let _ = writeln!(
+            out,
+            "@@ -{},{} +{},{} @@",
+            old_before + 1,
+            old_len,
+            new_before + 1,
+            new_len
+        );
+        for line in hunk {
+            let _ = match line {
+                DiffLine::Same(l) => writeln!(out, " {}", l),
+                DiffLine::Removed(l) => writeln!(out, "-{}", l),
+                DiffLine::Added(l) => writeln!(out, "+{}", l),
+            };
+        }
+    }
+    Some(out)
+}
+
+/// Per-function unified diffs of two pipelines' output for the same module.
+/// `filter` keeps functions whose name contains it.
+pub fn diff_modules(
+    old: &IrModule,
+    new: &IrModule,
+    old_label: &str,
+    new_label: &str,
+    filter: Option<&str>,
+) -> String {
+    let matches = |name: &str| filter.is_none_or(|f| name.contains(f));
+    let mut out = String::new();
+    let mut unchanged = 0;
+
+    for (id, old_func) in &old.functions {
+        if !matches(&old_func.name) {
+            continue;
+        }
+        let old_text = dump_function(old_func);
+        let Some(new_func) = new.functions.get(id) else {
+            let _ = writeln!(
+                out,
+                "; @{}: removed by {} ({} lines)\n",
+                old_func.name,
+                new_label,
+                old_text.lines().count()
+            );
+            continue;
+        };
+        let new_text = dump_function(new_func);
+        match unified_diff(
+            &format!("{} @{}", old_label, old_func.name),
+            &format!("{} @{}", new_label, new_func.name),
+            &old_text,
+            &new_text,
+            3,
+        ) {
+            Some(diff) => {
+                let _ = writeln!(
+                    out,
+                    "; @{}: {} -> {} lines",
+                    old_func.name,
+                    old_text.lines().count(),
+                    new_text.lines().count()
+                );
+                let _ = writeln!(out, "{}", diff);
+            }
+            None => unchanged += 1,
+        }
+    }
+
+    for (id, new_func) in &new.functions {
+        if matches(&new_func.name) && !old.functions.contains_key(id) {
+            let _ = writeln!(out, "; @{}: added by {}", new_func.name, new_label);
+            let _ = writeln!(out, "{}", dump_function(new_func));
+        }
+    }
+
+    let _ = writeln!(out, "; {} function(s) unchanged", unchanged);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let old = "a\nb\nc\nd\n";
+        let new = "a\nc\nx\nd\n";
+        assert_eq!(
+            diff_lines(old, new),
+            vec![
+                DiffLine::Same("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Same("c"),
+                DiffLine::Added("x"),
+                DiffLine::Same("d"),
+            ]
+        );
+        assert!(diff_lines("", "")
+            .iter()
+            .all(|l| matches!(l, DiffLine::Same(_))));
+    }
+
+    #[test]
+    fn test_unified_diff_hunks() {
+        let old: String = (1..=20).map(|i| format!("line {}\n", i)).collect();
+        let new = old
+            .replace("line 2\n", "line two\n")
+            .replace("line 18\n", "");
+        let diff = unified_diff("O0", "O2", &old, &new, 2).unwrap();
+        let lines: Vec<&str> = diff.lines().collect();
+        assert_eq!(lines[0], "--- O0");
+        assert_eq!(lines[1], "+++ O2");
+        assert_eq!(lines[2], "@@ -1,4 +1,4 @@");
+        assert_eq!(lines[4], "-line 2");
+        assert_eq!(lines[5], "+line two");
+        assert!(diff.contains("@@ -16,5 +16,4 @@\n line 16\n line 17\n-line 18\n line 19"));
+
+        assert!(unified_diff("a", "b", &old, &old, 3).is_none());
+    }
+}
@@ -14,25 +14,40 @@ pub mod hir_to_mir; // HIR to MIR lowering
 pub mod tast_to_hir; // TAST to HIR lowering // Drop point analysis for automatic memory deallocation
 
 // MIR modules (the existing IR serves as MIR)
+pub mod alias_analysis; // Alias analysis for load/store optimizations
+pub mod alloc_null_check; // Null checks on allocation results (--safety checked)
 pub mod blade; // BLADE format - Blazing Language Artifact Deployment Environment (.blade files)
+pub mod blade_cache; // Remote BLADE cache backends ([cache.remote])
 pub mod blocks;
+pub mod branch_hints; // Branch weights and cold blocks for block layout
 pub mod bounds_check_elimination; // Bounds Check Elimination for array loops
+pub mod bundle_crypt; // Payload encryption for .rzb bundles (--encrypt)
 pub mod builder;
+pub mod contracts; // Runtime checks for @:requires/@:ensures (debug, --safety checked)
 pub mod dump; // MIR pretty-printer for debugging
 pub mod environment_layout; // Closure environment layout abstraction
 pub mod escape_analysis; // Intra-loop escape analysis for Alloc hoisting
+pub mod escape_summary; // Interprocedural parameter escape summaries
 pub mod functions;
 pub mod inlining; // Function inlining and call graph analysis
 pub mod insert_free; // Insert Free instructions for non-escaping allocations
 pub mod instructions;
+pub mod load_store; // Redundant load and dead store elimination
 pub mod loop_analysis; // Loop analysis: dominators, natural loops, nesting
+pub mod loop_unroll; // Loop unrolling and peeling (O3, @:unroll)
 pub mod lowering; // Legacy TAST to MIR (being phased out)
 pub mod mir_builder; // Programmatic MIR construction API
+pub mod mir_diff; // MIR diffs between pass pipelines (rayzor dump --diff)
+pub mod module_init; // Initializer ordering and lazy module init
 pub mod modules;
 pub mod monomorphize; // Monomorphization pass for generics
+pub mod multiversion; // Per-CPU-feature function versions (@:targetFeature)
+pub mod obfuscate; // Identifier stripping for .rzb bundles (--obfuscate)
 pub mod optimizable; // Generic optimization trait for different IR levels
 pub mod optimization;
+pub mod return_slot; // Struct returns built in the caller's slot (RVO)
 pub mod scalar_replacement; // Scalar Replacement of Aggregates (SRA)
+pub mod specialization; // Constant argument specialization (function cloning)
 pub mod tree_shake; // Dead-code elimination for .rzb bundles
 pub mod types;
 pub mod validation;
@@ -0,0 +1,525 @@
+//! Module initializer ordering and lazy initialization.
+//!
+//! Every module with dynamic statics gets an `__init__` function. When
+//! imported modules are merged into the user module their initializers are
+//! renamed `__init__<Module>` and the user module's `__init__` calls them
+//! before running its own. This pass decides that order: an initializer
+//! runs after every initializer that stores a global it (or anything it
+//! calls) reads. Independent initializers keep their load order, so the
+//! result is deterministic. Initializers that depend on each other form a
+//! cycle, which is reported and resolved by load order.
+//!
+//! A class's `static function __init__()` (Haxe's `__init__` magic) is
+//! renamed `<Class>.__init__` and called from its module's `__init__`, in
+//! declaration order and before the module's statics are initialized.
+//!
+//! With lazy initialization (`-D lazy-init`) imported initializers are not
+//! run up front; each one is guarded by a flag and called on entry to every
+//! function that touches a global it initializes. Initializers that run a
+//! class `__init__` are exempt: those may have side effects beyond their
+//! module's globals, so they still run before `main`.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+
+use super::blocks::{IrBasicBlock, IrBlockId, IrTerminator};
+use super::instructions::IrInstruction;
+use super::modules::{IrGlobal, IrModule};
+use super::{IrFunctionId, IrGlobalId, IrSourceLocation, IrType, IrValue, Linkage};
+use crate::tast::SymbolId;
+
+/// Name of a module's initializer
+pub const INIT_FUNCTION: &str = "__init__";
+/// Name of the vtable/RTTI registration function
+pub const VTABLE_INIT_FUNCTION: &str = "__vtable_init__";
+/// Define that turns on lazy initialization of imported modules
+pub const LAZY_INIT_DEFINE: &str = "lazy-init";
+
+/// Name a class's static `__init__` is lowered under
+pub fn class_initializer_name(class: &str) -> String {
+    format!("{}.{}", class, INIT_FUNCTION)
+}
+
+/// Whether `name` is a lowered class `__init__`
+pub fn is_class_initializer(name: &str) -> bool {
+    name.strip_suffix(INIT_FUNCTION)
+        .is_some_and(|class| class.len() > 1 && class.ends_with('.'))
+}
+
+/// Whether the initializer `init` calls a class `__init__`
+pub fn runs_class_initializers(module: &IrModule, init: IrFunctionId) -> bool {
+    let Some(func) = module.functions.get(&init) else {
+        return false;
+    };
+    func.cfg
+        .blocks
+        .values()
+        .flat_map(|block| &block.instructions)
+        .any(|inst| match inst {
+            IrInstruction::CallDirect { func_id, .. } => module
+                .functions
+                .get(func_id)
+                .is_some_and(|callee| is_class_initializer(&callee.name)),
+            _ => false,
+        })
+}
+
+/// Initializers that (transitively) read each other's globals
+#[derive(Debug, Clone, PartialEq)]
+pub struct InitCycle {
+    /// Initializer names along the cycle; the first one is repeated at the end
+    pub path: Vec<String>,
+}
+
+impl fmt::Display for InitCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modules: Vec<&str> = self.path.iter().map(|name| module_of(name)).collect();
+        write!(
+            f,
+            "module initializers depend on each other: {}",
+            modules.join(" -> ")
+        )
+    }
+}
+
+/// `__init__Foo` -> `Foo`
+fn module_of(init_name: &str) -> &str {
+    match init_name.strip_prefix(INIT_FUNCTION) {
+        Some(module) if !module.is_empty() => module,
+        _ => init_name,
+    }
+}
+
+/// Order `inits` so that each runs after the initializers of the globals it
+/// reads. Ties keep the given order. On a cycle, returns the cycle; callers
+/// fall back to the given order.
+pub fn order_initializers(
+    module: &IrModule,
+    inits: &[IrFunctionId],
+) -> Result<Vec<IrFunctionId>, InitCycle> {
+    let stores: Vec<HashSet<IrGlobalId>> = inits
+        .iter()
+        .map(|&init| direct_accesses(module, init).1)
+        .collect();
+    let reads: Vec<HashSet<IrGlobalId>> = inits
+        .iter()
+        .map(|&init| transitive_loads(module, init))
+        .collect();
+
+    // deps[i]: initializers that must run before inits[i]
+    let deps: Vec<BTreeSet<usize>> = (0..inits.len())
+        .map(|i| {
+            (0..inits.len())
+                .filter(|&j| j != i && !stores[j].is_disjoint(&reads[i]))
+                .collect()
+        })
+        .collect();
+
+    let mut order = Vec::with_capacity(inits.len());
+    let mut done = vec![false; inits.len()];
+    while order.len() < inits.len() {
+        let next = (0..inits.len()).find(|&i| !done[i] && deps[i].iter().all(|&d| done[d]));
+        let Some(next) = next else {
+            return Err(find_cycle(module, inits, &deps, &done));
+        };
+        done[next] = true;
+        order.push(inits[next]);
+    }
+    Ok(order)
+}
+
+fn find_cycle(
+    module: &IrModule,
+    inits: &[IrFunctionId],
+    deps: &[BTreeSet<usize>],
+    done: &[bool],
+) -> InitCycle {
+    let name = |i: usize| {
+        module
+            .functions
+            .get(&inits[i])
+            .map(|f| f.name.clone())
+            .unwrap_or_else(|| format!("{:?}", inits[i]))
+    };
+    // Every pending initializer has a pending dependency, so following them
+    // must revisit one
+    let mut current = (0..inits.len()).find(|&i| !done[i]).unwrap_or(0);
+    let mut visited = vec![current];
+    loop {
+        let Some(&dep) = deps[current].iter().find(|&&d| !done[d]) else {
+            return InitCycle {
+                path: visited.into_iter().map(name).collect(),
+            };
+        };
+        if let Some(start) = visited.iter().position(|&v| v == dep) {
+            let mut path: Vec<String> = visited[start..].iter().map(|&i| name(i)).collect();
+            path.push(name(dep));
+            return InitCycle { path };
+        }
+        visited.push(dep);
+        current = dep;
+    }
+}
+
+/// Globals a function loads and stores directly
+fn direct_accesses(
+    module: &IrModule,
+    func_id: IrFunctionId,
+) -> (HashSet<IrGlobalId>, HashSet<IrGlobalId>) {
+    let mut loads = HashSet::new();
+    let mut stores = HashSet::new();
+    if let Some(func) = module.functions.get(&func_id) {
+        for block in func.cfg.blocks.values() {
+            for inst in &block.instructions {
+                match inst {
+                    IrInstruction::LoadGlobal { global_id, .. } => {
+                        loads.insert(*global_id);
+                    }
+                    IrInstruction::StoreGlobal { global_id, .. } => {
+                        stores.insert(*global_id);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    (loads, stores)
+}
+
+/// Globals loaded by a function or anything reachable from it
+fn transitive_loads(module: &IrModule, root: IrFunctionId) -> HashSet<IrGlobalId> {
+    let mut loads = HashSet::new();
+    let mut visited = HashSet::new();
+    let mut worklist = vec![root];
+    while let Some(func_id) = worklist.pop() {
+        if !visited.insert(func_id) {
+            continue;
+        }
+        let Some(func) = module.functions.get(&func_id) else {
+            continue;
+        };
+        for block in func.cfg.blocks.values() {
+            for inst in &block.instructions {
+                match inst {
+                    IrInstruction::LoadGlobal { global_id, .. } => {
+                        loads.insert(*global_id);
+                    }
+                    IrInstruction::CallDirect { func_id, .. }
+                    | IrInstruction::FunctionRef { func_id, .. }
+                    | IrInstruction::MakeClosure { func_id, .. } => {
+                        worklist.push(*func_id);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    loads
+}
+
+fn call(func_id: IrFunctionId) -> IrInstruction {
+    IrInstruction::CallDirect {
+        dest: None,
+        func_id,
+        args: Vec::new(),
+        arg_ownership: Vec::new(),
+        type_args: Vec::new(),
+        is_tail_call: false,
+    }
+}
+
+/// Insert calls to `callees` at the start of `func_id`'s entry block
+pub fn prepend_calls(module: &mut IrModule, func_id: IrFunctionId, callees: &[IrFunctionId]) {
+    if let Some(func) = module.functions.get_mut(&func_id) {
+        let entry = func.cfg.entry_block;
+        if let Some(block) = func.cfg.blocks.get_mut(&entry) {
+            block
+                .instructions
+                .splice(0..0, callees.iter().map(|&callee| call(callee)));
+        }
+    }
+}
+
+/// Make `entry` run the module's `__vtable_init__` and `__init__` first.
+/// For AOT executables, where no host calls them before `main`.
+pub fn call_initializers_from(module: &mut IrModule, entry: IrFunctionId) {
+    let inits: Vec<IrFunctionId> = [VTABLE_INIT_FUNCTION, INIT_FUNCTION]
+        .iter()
+        .filter_map(|name| {
+            module
+                .functions
+                .values()
+                .find(|f| f.name == *name && !f.cfg.blocks.is_empty())
+                .map(|f| f.id)
+        })
+        .filter(|&id| id != entry)
+        .collect();
+    prepend_calls(module, entry, &inits);
+}
+
+/// Run each of `inits` at most once, on entry to the first function that
+/// touches one of the globals it stores, instead of up front.
+pub fn make_lazy(module: &mut IrModule, inits: &[IrFunctionId]) {
+    let owned: Vec<(IrFunctionId, HashSet<IrGlobalId>)> = inits
+        .iter()
+        .map(|&init| (init, direct_accesses(module, init).1))
+        .collect();
+
+    // Triggers first, so the guard's own flag accesses are not counted
+    let func_ids: Vec<IrFunctionId> = module.functions.keys().copied().collect();
+    for func_id in func_ids {
+        let (loads, stores) = direct_accesses(module, func_id);
+        let triggers: Vec<IrFunctionId> = owned
+            .iter()
+            .filter(|(init, globals)| {
+                *init != func_id && !(globals.is_disjoint(&loads) && globals.is_disjoint(&stores))
+            })
+            .map(|(init, _)| *init)
+            .collect();
+        prepend_calls(module, func_id, &triggers);
+    }
+
+    for &init in inits {
+        add_once_guard(module, init);
+    }
+}
+
+/// Wrap a function's body in `if (!done) { done = true; body }`
+fn add_once_guard(module: &mut IrModule, func_id: IrFunctionId) {
+    let Some(name) = module.functions.get(&func_id).map(|f| f.name.clone()) else {
+        return;
+    };
+    let flag = module.alloc_global_id();
+    module.globals.insert(
+        flag,
+        IrGlobal {
+            id: flag,
+            name: format!("{}.done", name),
+            symbol_id: SymbolId::from_raw(u32::MAX - 3),
+            ty: IrType::Bool,
+            initializer: Some(IrValue::Bool(false)),
+            mutable: true,
+            linkage: Linkage::Internal,
+            alignment: None,
+            source_location: IrSourceLocation::unknown(),
+        },
+    );
+
+    let Some(func) = module.functions.get_mut(&func_id) else {
+        return;
+    };
+    let entry = func.cfg.entry_block;
+    let Some(entry_block) = func.cfg.blocks.get_mut(&entry) else {
+        return;
+    };
+    // The original entry block becomes the body; the entry id stays the same
+    // so backends that assume block 0 is the entry keep working
+    let mut body = std::mem::replace(entry_block, IrBasicBlock::new(entry));
+    let body_id = func.cfg.create_block();
+    body.id = body_id;
+    body.predecessors = vec![entry];
+    let done_id = func.cfg.create_block();
+
+    let is_done = func.alloc_reg();
+    let done_value = func.alloc_reg();
+    func.register_types.insert(is_done, IrType::Bool);
+    func.register_types.insert(done_value, IrType::Bool);
+    body.instructions.splice(
+        0..0,
+        [
+            IrInstruction::Const {
+                dest: done_value,
+                value: IrValue::Bool(true),
+            },
+            IrInstruction::StoreGlobal {
+                global_id: flag,
+                value: done_value,
+            },
+        ],
+    );
+
+    // Edges that pointed at the old entry now lead to the body
+    for block in func.cfg.blocks.values_mut() {
+        retarget(&mut block.terminator, entry, body_id);
+        for phi in &mut block.phi_nodes {
+            for (pred, _) in &mut phi.incoming {
+                if *pred == entry {
+                    *pred = body_id;
+                }
+            }
+        }
+        for pred in &mut block.predecessors {
+            if *pred == entry {
+                *pred = body_id;
+            }
+        }
+    }
+    retarget(&mut body.terminator, entry, body_id);
+    func.cfg.blocks.insert(body_id, body);
+
+    if let Some(done) = func.cfg.blocks.get_mut(&done_id) {
+        done.predecessors = vec![entry];
+        done.terminator = IrTerminator::Return { value: None };
+    }
+    if let Some(guard) = func.cfg.blocks.get_mut(&entry) {
+        guard.instructions.push(IrInstruction::LoadGlobal {
+            dest: is_done,
+            global_id: flag,
+            ty: IrType::Bool,
+        });
+        guard.terminator = IrTerminator::CondBranch {
+            condition: is_done,
+            true_target: done_id,
+            false_target: body_id,
+        };
+    }
+}
+
+fn retarget(terminator: &mut IrTerminator, from: IrBlockId, to: IrBlockId) {
+    let fix = |target: &mut IrBlockId| {
+        if *target == from {
+            *target = to;
+        }
+    };
+    match terminator {
+        IrTerminator::Branch { target } => fix(target),
+        IrTerminator::CondBranch {
+            true_target,
+            false_target,
+            ..
+        } => {
+            fix(true_target);
+            fix(false_target);
+        }
+        IrTerminator::Switch { cases, default, .. } => {
+            cases.iter_mut().for_each(|(_, target)| fix(target));
+            fix(default);
+        }
+        IrTerminator::Return { .. } | IrTerminator::Unreachable | IrTerminator::NoReturn { .. } => {
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::mir_builder::MirBuilder;
+
+    /// Module with one function per `(name, stores, reads)` entry
+    fn module_with_inits(specs: &[(&str, u32, Option<u32>)]) -> (IrModule, Vec<IrFunctionId>) {
+        let mut builder = MirBuilder::new("Main");
+        let mut ids = Vec::new();
+        for (name, _, _) in specs {
+            let id = builder.begin_function(*name).build();
+            builder.set_current_function(id);
+            let entry = builder.create_block("entry");
+            builder.set_insert_point(entry);
+            builder.ret(None);
+            ids.push(id);
+        }
+        let mut module = builder.finish();
+
+        for (id, (_, store, read)) in ids.iter().zip(specs) {
+            let func = module.functions.get_mut(id).unwrap();
+            let value = func.alloc_reg();
+            let mut insts = vec![
+                IrInstruction::Const {
+                    dest: value,
+                    value: IrValue::I64(1),
+                },
+                IrInstruction::StoreGlobal {
+                    global_id: IrGlobalId(*store),
+                    value,
+                },
+            ];
+            if let Some(read) = read {
+                insts.push(IrInstruction::LoadGlobal {
+                    dest: func.alloc_reg(),
+                    global_id: IrGlobalId(*read),
+                    ty: IrType::I64,
+                });
+            }
+            let entry = func.cfg.entry_block;
+            func.cfg.blocks.get_mut(&entry).unwrap().instructions = insts;
+        }
+        (module, ids)
+    }
+
+    #[test]
+    fn test_independent_inits_keep_load_order() {
+        let (module, inits) = module_with_inits(&[("__init__A", 1, None), ("__init__B", 2, None)]);
+        assert_eq!(order_initializers(&module, &inits).unwrap(), inits);
+    }
+
+    #[test]
+    fn test_dependency_runs_first() {
+        // A reads the global B initializes
+        let (module, inits) =
+            module_with_inits(&[("__init__A", 1, Some(2)), ("__init__B", 2, None)]);
+        assert_eq!(
+            order_initializers(&module, &inits).unwrap(),
+            vec![inits[1], inits[0]]
+        );
+    }
+
+    #[test]
+    fn test_cycle_is_reported() {
+        let (module, inits) = module_with_inits(&[
+            ("__init__A", 1, Some(2)),
+            ("__init__B", 2, Some(1)),
+            ("__init__C", 3, None),
+        ]);
+        let cycle = order_initializers(&module, &inits).unwrap_err();
+        assert_eq!(cycle.path, vec!["__init__A", "__init__B", "__init__A"]);
+        assert_eq!(
+            cycle.to_string(),
+            "module initializers depend on each other: A -> B -> A"
+        );
+    }
+
+    #[test]
+    fn test_lazy_init_guards_and_triggers() {
+        let (mut module, inits) =
+            module_with_inits(&[("__init__A", 1, None), ("reader", 9, Some(1))]);
+        let (init, reader) = (inits[0], inits[1]);
+        make_lazy(&mut module, &[init]);
+
+        let entry = &module.functions[&reader].cfg.blocks[&IrBlockId::entry()];
+        assert!(
+            matches!(entry.instructions[0], IrInstruction::CallDirect { func_id, .. } if func_id == init)
+        );
+
+        let init_fn = &module.functions[&init];
+        assert_eq!(init_fn.cfg.blocks.len(), 3);
+        let guard = &init_fn.cfg.blocks[&init_fn.cfg.entry_block];
+        assert!(matches!(guard.terminator, IrTerminator::CondBranch { .. }));
+        assert!(crate::ir::validation::validate_module(&module).is_ok());
+    }
+
+    #[test]
+    fn test_call_initializers_from_entry() {
+        let (mut module, ids) = module_with_inits(&[("__init__", 1, None), ("main", 2, None)]);
+        call_initializers_from(&mut module, ids[1]);
+        let entry = &module.functions[&ids[1]].cfg.blocks[&IrBlockId::entry()];
+        assert!(
+            matches!(entry.instructions[0], IrInstruction::CallDirect { func_id, .. } if func_id == ids[0])
+        );
+    }
+
+    #[test]
+    fn test_class_initializers() {
+        assert_eq!(class_initializer_name("Foo"), "Foo.__init__");
+        assert!(is_class_initializer("Foo.__init__"));
+        assert!(!is_class_initializer("__init__"));
+        assert!(!is_class_initializer("__init__Foo"));
+
+        let (mut module, ids) = module_with_inits(&[
+            ("__init__Foo", 1, None),
+            ("Foo.__init__", 2, None),
+            ("__init__Bar", 3, None),
+        ]);
+        prepend_calls(&mut module, ids[0], &[ids[1]]);
+        assert!(runs_class_initializers(&module, ids[0]));
+        assert!(!runs_class_initializers(&module, ids[2]));
+    }
+}
@@ -120,6 +120,68 @@ pub struct IrTypeDef {
     /// Super class type id (for class types with inheritance)
     #[serde(default)]
     pub super_type_id: Option<TypeId>,
+
+    /// Interfaces implemented by a class type, including the interfaces they
+    /// extend (interfaces inherited from the super class are not repeated)
+    #[serde(default)]
+    pub interface_type_ids: Vec<TypeId>,
+
+    /// User metadata for haxe.rtti.Meta
+    #[serde(default)]
+    pub metadata: IrTypeMetadata,
+
+    /// Byte layout of a class without an object header (@:cstruct,
+    /// @:struct); field offsets are in the `Struct` fields
+    #[serde(default)]
+    pub layout: Option<IrFlatLayout>,
+}
+
+/// Size of a flat class, as placed and as declared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IrFlatLayout {
+    pub size: u32,
+    pub align: u32,
+    /// Size with the fields in declaration order
+    pub declared_size: u32,
+}
+
+/// User metadata of a class or enum, registered with the runtime for
+/// `haxe.rtti.Meta` queries
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IrTypeMetadata {
+    /// Metadata on the type itself
+    pub type_meta: Vec<IrMetadata>,
+
+    /// Instance fields and methods (enum constructors for enums)
+    pub fields: Vec<(String, Vec<IrMetadata>)>,
+
+    /// Static fields and methods
+    pub statics: Vec<(String, Vec<IrMetadata>)>,
+}
+
+impl IrTypeMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.type_meta.is_empty() && self.fields.is_empty() && self.statics.is_empty()
+    }
+}
+
+/// One metadata entry: `@name(args)`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IrMetadata {
+    pub name: String,
+    pub args: Vec<IrMetaValue>,
+}
+
+/// Constant metadata argument
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum IrMetaValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<IrMetaValue>),
+    Object(Vec<(String, IrMetaValue)>),
 }
 
 /// Type definition variants
@@ -165,6 +227,11 @@ pub struct IrEnumVariant {
 
     /// Associated data (if any)
     pub fields: Vec<IrField>,
+
+    /// Enum type of each field that holds an enum value (None for other
+    /// fields), so the runtime can compare nested enum values by structure
+    #[serde(default)]
+    pub field_enum_types: Vec<Option<TypeId>>,
 }
 
 /// External function declaration
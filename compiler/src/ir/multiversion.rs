@@ -0,0 +1,295 @@
+//! Function multi-versioning for CPU features (`@:targetFeature`)
+//!
+//! AOT builds for a generic CPU can't use newer ISA extensions in numeric
+//! kernels. A function annotated with
+//!
+//! ```haxe
+//! @:targetFeature("avx2", "fma")
+//! @:targetFeature("neon")
+//! static function dot(a:Vector<Float>, b:Vector<Float>):Float { ... }
+//! ```
+//!
+//! is compiled once per feature set plus once for the baseline target. Each
+//! `@:targetFeature` entry is one version; entries for another architecture
+//! are skipped. The LLVM AOT backend then routes calls through a function
+//! pointer that a startup resolver sets to the first version, in
+//! declaration order, whose features the running CPU supports (see
+//! `llvm_aot_backend::emit_multiversion_dispatch`).
+//!
+//! hir_to_mir records the feature sets in the function's custom attributes
+//! as `"avx2,fma;neon"`. The JIT ignores them: it already compiles for the
+//! host CPU.
+
+use super::functions::IrFunctionId;
+use super::{IrFunction, IrModule};
+
+/// Function attribute (`attributes.custom`) holding the feature sets:
+/// versions separated by `;`, features within a version by `,`
+pub const TARGET_FEATURES_ATTRIBUTE: &str = "target_features";
+
+/// Architecture family a feature name belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetArch {
+    X86,
+    AArch64,
+    Other,
+}
+
+impl TargetArch {
+    /// Architecture of an LLVM target triple
+    pub fn from_triple(triple: &str) -> Self {
+        let arch = triple.split('-').next().unwrap_or("");
+        match arch {
+            "x86_64" | "i386" | "i486" | "i586" | "i686" => TargetArch::X86,
+            "aarch64" | "arm64" | "arm64e" => TargetArch::AArch64,
+            _ => TargetArch::Other,
+        }
+    }
+}
+
+/// Feature names accepted by `@:targetFeature` and their LLVM spelling
+const FEATURES: &[(TargetArch, &str, &str)] = &[
+    (TargetArch::X86, "sse3", "sse3"),
+    (TargetArch::X86, "ssse3", "ssse3"),
+    (TargetArch::X86, "sse4.1", "sse4.1"),
+    (TargetArch::X86, "sse4.2", "sse4.2"),
+    (TargetArch::X86, "popcnt", "popcnt"),
+    (TargetArch::X86, "avx", "avx"),
+    (TargetArch::X86, "avx2", "avx2"),
+    (TargetArch::X86, "fma", "fma"),
+    (TargetArch::X86, "f16c", "f16c"),
+    (TargetArch::X86, "bmi1", "bmi"),
+    (TargetArch::X86, "bmi2", "bmi2"),
+    (TargetArch::X86, "lzcnt", "lzcnt"),
+    (TargetArch::X86, "avx512f", "avx512f"),
+    (TargetArch::X86, "avx512bw", "avx512bw"),
+    (TargetArch::X86, "avx512dq", "avx512dq"),
+    (TargetArch::X86, "avx512vl", "avx512vl"),
+    (TargetArch::AArch64, "neon", "neon"),
+    (TargetArch::AArch64, "fp16", "fullfp16"),
+    (TargetArch::AArch64, "dotprod", "dotprod"),
+    (TargetArch::AArch64, "crc", "crc"),
+    (TargetArch::AArch64, "aes", "aes"),
+    (TargetArch::AArch64, "sha2", "sha2"),
+    (TargetArch::AArch64, "sve", "sve"),
+    (TargetArch::AArch64, "sve2", "sve2"),
+];
+
+/// One specialized copy of a function
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionVersion {
+    /// Feature names as written, e.g. `["avx2", "fma"]`
+    pub features: Vec<String>,
+    pub func_id: IrFunctionId,
+}
+
+/// A function with its specialized versions; `default` keeps the original
+/// body and ID, so existing callers reach it through the dispatcher
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiVersionedFunction {
+    pub default: IrFunctionId,
+    pub versions: Vec<FunctionVersion>,
+}
+
+/// Feature sets requested for a function, in declaration order
+pub fn feature_sets(function: &IrFunction) -> Vec<Vec<String>> {
+    let Some(spec) = function.attributes.custom.get(TARGET_FEATURES_ATTRIBUTE) else {
+        return Vec::new();
+    };
+    spec.split(';')
+        .map(|set| {
+            set.split(',')
+                .map(|f| f.trim().to_string())
+                .filter(|f| !f.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|set| !set.is_empty())
+        .collect()
+}
+
+/// Architecture of a feature name, or None if it is not a known feature
+pub fn feature_arch(feature: &str) -> Option<TargetArch> {
+    FEATURES
+        .iter()
+        .find(|(_, name, _)| *name == feature)
+        .map(|(arch, _, _)| *arch)
+}
+
+/// LLVM `target-features` value for a feature set, e.g. `+avx2,+fma`
+pub fn llvm_target_features(features: &[String]) -> String {
+    features
+        .iter()
+        .map(|feature| {
+            let llvm_name = FEATURES
+                .iter()
+                .find(|(_, name, _)| name == feature)
+                .map_or(feature.as_str(), |(_, _, llvm)| llvm);
+            format!("+{}", llvm_name)
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Clone every `@:targetFeature` function once per feature set that applies
+/// to `arch`. Clones get fresh IDs (unique across `modules`) and names
+/// ending in the feature list. Fails on unknown feature names.
+pub fn create_versions(
+    modules: &mut [IrModule],
+    arch: TargetArch,
+) -> Result<Vec<MultiVersionedFunction>, String> {
+    let mut next_id = modules
+        .iter()
+        .flat_map(|m| m.functions.keys().chain(m.extern_functions.keys()))
+        .map(|id| id.0 + 1)
+        .max()
+        .unwrap_or(0);
+
+    let mut result = Vec::new();
+    for module in modules.iter_mut() {
+        let mut clones = Vec::new();
+        for (&func_id, function) in &module.functions {
+            let sets = feature_sets(function);
+            if sets.is_empty() || function.cfg.blocks.is_empty() {
+                continue;
+            }
+
+            let mut versions = Vec::new();
+            for features in sets {
+                let mut applies = true;
+                for feature in &features {
+                    match feature_arch(feature) {
+                        Some(a) => applies &= a == arch,
+                        None => {
+                            return Err(format!(
+                                "Unknown CPU feature '{}' in @:targetFeature on {}",
+                                feature, function.name
+                            ))
+                        }
+                    }
+                }
+                if !applies {
+                    continue;
+                }
+
+                let id = IrFunctionId(next_id);
+                next_id += 1;
+                let mut clone = function.clone();
+                clone.id = id;
+                clone.name = format!(
+                    "{}__{}",
+                    function.name,
+                    features.join("_").replace('.', "_")
+                );
+                clone.attributes.custom.remove(TARGET_FEATURES_ATTRIBUTE);
+                clones.push(clone);
+                versions.push(FunctionVersion {
+                    features,
+                    func_id: id,
+                });
+            }
+
+            if !versions.is_empty() {
+                result.push(MultiVersionedFunction {
+                    default: func_id,
+                    versions,
+                });
+            }
+        }
+        for clone in clones {
+            module.add_function(clone);
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::mir_builder::MirBuilder;
+    use crate::ir::IrType;
+
+    fn kernel_module(spec: &str) -> (IrModule, IrFunctionId) {
+        let mut builder = MirBuilder::new("test");
+        let func = builder
+            .begin_function("dot")
+            .param("x", IrType::I32)
+            .returns(IrType::I32)
+            .build();
+        builder.set_current_function(func);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let x = builder.get_param(0);
+        builder.ret(Some(x));
+        let mut module = builder.finish();
+        module
+            .functions
+            .get_mut(&func)
+            .unwrap()
+            .attributes
+            .custom
+            .insert(TARGET_FEATURES_ATTRIBUTE.to_string(), spec.to_string());
+        (module, func)
+    }
+
+    #[test]
+    fn test_target_arch() {
+        assert_eq!(
+            TargetArch::from_triple("x86_64-unknown-linux-gnu"),
+            TargetArch::X86
+        );
+        assert_eq!(
+            TargetArch::from_triple("aarch64-apple-darwin"),
+            TargetArch::AArch64
+        );
+        assert_eq!(
+            TargetArch::from_triple("wasm32-unknown-unknown"),
+            TargetArch::Other
+        );
+    }
+
+    #[test]
+    fn test_llvm_target_features() {
+        let features = vec!["avx2".to_string(), "bmi1".to_string()];
+        assert_eq!(llvm_target_features(&features), "+avx2,+bmi");
+        assert_eq!(llvm_target_features(&["fp16".to_string()]), "+fullfp16");
+    }
+
+    #[test]
+    fn test_create_versions_for_arch() {
+        let (module, func) = kernel_module("avx2, fma;neon;avx512f");
+        let mut modules = vec![module];
+        let versioned = create_versions(&mut modules, TargetArch::X86).unwrap();
+
+        assert_eq!(versioned.len(), 1);
+        assert_eq!(versioned[0].default, func);
+        let features: Vec<_> = versioned[0]
+            .versions
+            .iter()
+            .map(|v| v.features.join(","))
+            .collect();
+        assert_eq!(features, vec!["avx2,fma", "avx512f"]);
+
+        let module = &modules[0];
+        assert_eq!(module.functions.len(), 3);
+        let clone = &module.functions[&versioned[0].versions[0].func_id];
+        assert_eq!(clone.name, "dot__avx2_fma");
+        assert!(feature_sets(clone).is_empty());
+        assert!(versioned[0].versions[0].func_id.0 > func.0);
+    }
+
+    #[test]
+    fn test_no_versions_for_other_arch() {
+        let (module, _) = kernel_module("avx2");
+        let mut modules = vec![module];
+        let versioned = create_versions(&mut modules, TargetArch::AArch64).unwrap();
+        assert!(versioned.is_empty());
+        assert_eq!(modules[0].functions.len(), 1);
+    }
+
+    #[test]
+    fn test_unknown_feature() {
+        let (module, _) = kernel_module("avx9000");
+        let err = create_versions(&mut [module], TargetArch::X86).unwrap_err();
+        assert!(err.contains("Unknown CPU feature 'avx9000'"));
+    }
+}
@@ -0,0 +1,218 @@
+//! Identifier stripping for .rzb bundles (`rayzor bundle --obfuscate`).
+//!
+//! Replaces the names of functions and classes compiled from the program's
+//! own sources with salted hashes, and drops local variable names and
+//! source paths, so a shipped bundle doesn't spell out the game's code
+//! structure. Entry points, module initializers and anything the code
+//! generator or runtime looks up by name keep their names.
+//!
+//! This is obfuscation, not DRM: control flow, constants and strings are
+//! untouched. Reflection that goes through class names
+//! (`Type.getClassName`, `Type.resolveClass`) sees the hashed names.
+
+use super::functions::FunctionKind;
+use super::modules::IrModule;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Statistics from identifier stripping.
+#[derive(Debug, Default)]
+pub struct ObfuscateStats {
+    pub functions_renamed: usize,
+    pub types_renamed: usize,
+    pub modules_obfuscated: usize,
+}
+
+/// Strip identifiers from the modules compiled from `user_sources`.
+///
+/// Renaming is consistent across all modules, so cross-module references
+/// (extern declarations and forward references by qualified name) still
+/// resolve. `salt` keys the hashes; a fresh salt per bundle keeps names
+/// from being matched against a dictionary of hashed identifiers.
+pub fn obfuscate_bundle(
+    modules: &mut [IrModule],
+    user_sources: &[String],
+    entry_functions: &[String],
+    salt: u64,
+) -> ObfuscateStats {
+    let mut stats = ObfuscateStats::default();
+    let is_user_module = |module: &IrModule| {
+        let file = Path::new(&module.source_file);
+        !module.source_file.is_empty()
+            && user_sources
+                .iter()
+                .any(|src| file == Path::new(src) || file.ends_with(src))
+    };
+
+    // Phase 1: names defined by the user modules
+    let stdlib_mapping = crate::stdlib::runtime_mapping::StdlibMapping::new();
+    let mut renames: HashMap<String, String> = HashMap::new();
+    for module in modules.iter().filter(|m| is_user_module(m)) {
+        for function in module.functions.values() {
+            if function.cfg.blocks.is_empty()
+                || function.kind != FunctionKind::UserDefined
+                || is_reserved(&function.name, entry_functions)
+                || stdlib_mapping
+                    .find_by_runtime_name(&function.name)
+                    .is_some()
+            {
+                continue;
+            }
+            renames
+                .entry(function.name.clone())
+                .or_insert_with(|| hashed_name("f", &function.name, salt));
+            if let Some(qualified) = &function.qualified_name {
+                renames
+                    .entry(qualified.clone())
+                    .or_insert_with(|| hashed_name("f", qualified, salt));
+            }
+        }
+    }
+
+    // Phase 2: rename the definitions, and references from every module
+    for module in modules.iter_mut() {
+        for extern_func in module.extern_functions.values_mut() {
+            if let Some(new_name) = renames.get(&extern_func.name) {
+                extern_func.name = new_name.clone();
+            }
+        }
+        if !is_user_module(module) {
+            continue;
+        }
+
+        for function in module.functions.values_mut() {
+            if let Some(new_name) = renames.get(&function.name) {
+                function.name = new_name.clone();
+                stats.functions_renamed += 1;
+            }
+            if let Some(new_name) = function
+                .qualified_name
+                .as_ref()
+                .and_then(|qualified| renames.get(qualified))
+            {
+                function.qualified_name = Some(new_name.clone());
+            }
+            for local in function.locals.values_mut() {
+                local.name.clear();
+            }
+        }
+        for typedef in module.types.values_mut() {
+            typedef.name = hashed_name("T", &typedef.name, salt);
+            stats.types_renamed += 1;
+        }
+        module.source_file = hashed_name("src", &module.source_file, salt);
+        stats.modules_obfuscated += 1;
+    }
+
+    stats
+}
+
+/// Names the code generator, runtime or bundle loader find functions by
+fn is_reserved(name: &str, entry_functions: &[String]) -> bool {
+    entry_functions.iter().any(|entry| entry == name)
+        || name == "main"
+        || name.ends_with("_main")
+        || name.starts_with("__")
+        || name.starts_with("<lambda")
+        || name.starts_with("haxe_")
+        || name.starts_with("rayzor_")
+        || matches!(name, "malloc" | "realloc" | "free")
+}
+
+/// `<prefix>_<16 hex digits>`: salted FNV-1a of `name`
+fn hashed_name(prefix: &str, name: &str, salt: u64) -> String {
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    for byte in salt.to_le_bytes().iter().chain(name.as_bytes()) {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{}_{:016x}", prefix, hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{
+        CallingConvention, IrExternFunction, IrFunction, IrFunctionId, IrFunctionSignature, IrType,
+    };
+    use crate::tast::SymbolId;
+
+    fn function(id: u32, name: &str, qualified: Option<&str>) -> IrFunction {
+        let signature = IrFunctionSignature {
+            parameters: vec![],
+            return_type: IrType::Void,
+            calling_convention: CallingConvention::Haxe,
+            can_throw: false,
+            type_params: vec![],
+            uses_sret: false,
+        };
+        let mut function = IrFunction::new(
+            IrFunctionId(id),
+            SymbolId::from_raw(id),
+            name.to_string(),
+            signature,
+        );
+        function.qualified_name = qualified.map(str::to_string);
+        function
+    }
+
+    #[test]
+    fn test_obfuscate_renames_consistently() {
+        let mut game = IrModule::new("Game".to_string(), "src/Game.hx".to_string());
+        game.functions
+            .insert(IrFunctionId(0), function(0, "update", Some("Game.update")));
+        game.functions
+            .insert(IrFunctionId(1), function(1, "Main_main", Some("Main.main")));
+
+        let mut other = IrModule::new("Other".to_string(), "std/Other.hx".to_string());
+        other.extern_functions.insert(
+            IrFunctionId(0),
+            IrExternFunction {
+                id: IrFunctionId(0),
+                name: "Game.update".to_string(),
+                symbol_id: SymbolId::from_raw(9),
+                signature: game.functions[&IrFunctionId(0)].signature.clone(),
+                source: "Game".to_string(),
+            },
+        );
+        other
+            .functions
+            .insert(IrFunctionId(1), function(1, "helper", Some("Other.helper")));
+
+        let mut modules = vec![game, other];
+        let stats = obfuscate_bundle(
+            &mut modules,
+            &["src/Game.hx".to_string()],
+            &["Main_main".to_string()],
+            42,
+        );
+        assert_eq!(stats.functions_renamed, 1);
+        assert_eq!(stats.modules_obfuscated, 1);
+
+        let update = &modules[0].functions[&IrFunctionId(0)];
+        assert!(update.name.starts_with("f_"));
+        let qualified = update.qualified_name.clone().unwrap();
+        assert_eq!(
+            modules[1].extern_functions[&IrFunctionId(0)].name,
+            qualified
+        );
+        assert!(modules[0].source_file.starts_with("src_"));
+
+        // Entry points and other modules keep their names
+        assert_eq!(modules[0].functions[&IrFunctionId(1)].name, "Main_main");
+        assert_eq!(modules[1].functions[&IrFunctionId(1)].name, "helper");
+        assert_eq!(
+            modules[1].functions[&IrFunctionId(1)]
+                .qualified_name
+                .as_deref(),
+            Some("Other.helper")
+        );
+    }
+
+    #[test]
+    fn test_hashed_name_is_salted() {
+        assert_eq!(hashed_name("f", "update", 1), hashed_name("f", "update", 1));
+        assert_ne!(hashed_name("f", "update", 1), hashed_name("f", "update", 2));
+        assert_eq!(hashed_name("f", "update", 1).len(), 18);
+    }
+}
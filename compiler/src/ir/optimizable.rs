@@ -252,7 +252,7 @@ impl<'a> HirOptimizationPass for HirDeadCodeElimination<'a> {
         if removed > 0 {
             result.modified = true;
             result.instructions_eliminated = removed;
-            eprintln!("HIR DCE: Removed {} unreachable functions", removed);
+            log::debug!("HIR DCE: Removed {} unreachable functions", removed);
         }
 
         result
@@ -8,6 +8,7 @@ use super::{
     BinaryOp, CompareOp, IrBasicBlock, IrBlockId, IrFunction, IrFunctionId, IrGlobalId, IrId,
     IrInstruction, IrModule, IrTerminator, IrType, IrValue,
 };
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 /// Optimization pass trait
@@ -75,15 +76,143 @@ impl OptimizationResult {
     }
 }
 
+/// Passes that can be limited by function size. Correctness passes
+/// (`InsertFree`) and the cheap `branch-hints` always run.
+pub const SIZE_LIMITED_PASSES: &[&str] = &[
+    "inlining",
+    "dead-code-elimination",
+    "constant-folding",
+    "copy-propagation",
+    "unreachable-block-elimination",
+    "control-flow-simplification",
+    "scalar_replacement",
+    "global_load_cache",
+    "BoundsCheckElimination",
+    "cse",
+    "licm",
+    "gvn",
+    "LoopVectorization",
+    "loop-unroll",
+    "tail-call-optimization",
+    "redundant-load-elimination",
+    "dead-store-elimination",
+];
+
+/// Per-pass instruction-count limits: a pass skips functions larger than
+/// its limit, so huge generated functions (e.g. macro-built switch tables)
+/// don't blow up compile times in passes whose cost grows faster than
+/// linearly.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PassSizeLimits {
+    limits: HashMap<String, usize>,
+}
+
+impl PassSizeLimits {
+    /// No limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits for the passes that are costliest on large functions
+    pub fn defaults() -> Self {
+        let mut limits = Self::new();
+        limits.set("gvn", 10_000);
+        limits.set("cse", 10_000);
+        limits.set("licm", 10_000);
+        limits.set("BoundsCheckElimination", 10_000);
+        limits.set("scalar_replacement", 20_000);
+        limits.set("LoopVectorization", 5_000);
+        limits.set("loop-unroll", 5_000);
+        limits
+    }
+
+    /// Skip `pass` on functions with more than `max_instructions`
+    pub fn set(&mut self, pass: &str, max_instructions: usize) {
+        self.limits.insert(pass.to_string(), max_instructions);
+    }
+
+    /// The limit for `pass`, if it has one
+    pub fn get(&self, pass: &str) -> Option<usize> {
+        self.limits.get(pass).copied()
+    }
+}
+
+/// A function that some passes skipped because of its size
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DemotedFunction {
+    pub function: String,
+    pub instructions: usize,
+    /// Passes that skipped it, in pipeline order
+    pub passes: Vec<&'static str>,
+}
+
+/// Number of instructions (including phis) in `function`
+pub fn function_size(function: &IrFunction) -> usize {
+    function
+        .cfg
+        .blocks
+        .values()
+        .map(|block| block.phi_nodes.len() + block.instructions.len())
+        .sum()
+}
+
 /// Optimization pass manager
 pub struct PassManager {
     passes: Vec<Box<dyn OptimizationPass>>,
+    size_limits: PassSizeLimits,
+    demoted: BTreeMap<String, DemotedFunction>,
 }
 
 impl PassManager {
     /// Create a new pass manager
     pub fn new() -> Self {
-        Self { passes: Vec::new() }
+        Self {
+            passes: Vec::new(),
+            size_limits: PassSizeLimits::new(),
+            demoted: BTreeMap::new(),
+        }
+    }
+
+    /// Replace the per-pass function size limits
+    pub fn with_size_limits(mut self, size_limits: PassSizeLimits) -> Self {
+        self.size_limits = size_limits;
+        self
+    }
+
+    /// Functions that passes skipped because of their size, by name
+    pub fn demoted_functions(&self) -> impl Iterator<Item = &DemotedFunction> {
+        self.demoted.values()
+    }
+
+    /// Print a note listing the functions passes skipped because of their
+    /// size. Each function is listed once per process, however many modules
+    /// or tier promotions optimize it.
+    pub fn report_demotions(&self) {
+        static REPORTED: std::sync::Mutex<BTreeSet<String>> =
+            std::sync::Mutex::new(BTreeSet::new());
+
+        let mut reported = REPORTED.lock().unwrap();
+        let new: Vec<&DemotedFunction> = self
+            .demoted_functions()
+            .filter(|demoted| reported.insert(demoted.function.clone()))
+            .collect();
+        if new.is_empty() {
+            return;
+        }
+        let mut note = format!(
+            "note: {} function(s) optimized with fewer passes because of their size\n",
+            new.len()
+        );
+        for demoted in new {
+            note.push_str(&format!(
+                "  {} ({} instructions) skipped {}\n",
+                demoted.function,
+                demoted.instructions,
+                demoted.passes.join(", ")
+            ));
+        }
+        note.push_str("  = help: raise the limits in [optimization.size-limits] of rayzor.toml\n");
+        eprint!("{}", note);
     }
 
     /// Add a pass to the manager
@@ -123,7 +252,13 @@ impl PassManager {
             let mut transformative_change = false;
 
             for pass in &mut self.passes {
+                // Oversized functions sit out the pass
+                let skipped = match self.size_limits.get(pass.name()) {
+                    Some(limit) => take_oversized(module, pass.name(), limit, &mut self.demoted),
+                    None => Vec::new(),
+                };
                 let result = pass.run_on_module(module);
+                module.functions.extend(skipped);
                 if result.modified {
                     // Only re-iterate if a transformative pass (not just cleanup) changed things
                     let is_cleanup = matches!(
@@ -131,6 +266,7 @@ impl PassManager {
                         "dead-code-elimination"
                             | "unreachable-block-elimination"
                             | "copy-propagation"
+                            | "branch-hints"
                     );
                     if !is_cleanup {
                         transformative_change = true;
@@ -148,6 +284,43 @@ impl PassManager {
     }
 }
 
+/// Remove the functions larger than `limit` from `module`, recording that
+/// `pass` skipped them
+fn take_oversized(
+    module: &mut IrModule,
+    pass: &'static str,
+    limit: usize,
+    demoted: &mut BTreeMap<String, DemotedFunction>,
+) -> Vec<(IrFunctionId, IrFunction)> {
+    let oversized: Vec<(IrFunctionId, usize)> = module
+        .functions
+        .iter()
+        .map(|(id, function)| (*id, function_size(function)))
+        .filter(|(_, size)| *size > limit)
+        .collect();
+
+    let mut skipped = Vec::with_capacity(oversized.len());
+    for (id, size) in oversized {
+        let function = module.functions.remove(&id).unwrap();
+        let name = function
+            .qualified_name
+            .clone()
+            .unwrap_or_else(|| function.name.clone());
+        let entry = demoted
+            .entry(name.clone())
+            .or_insert_with(|| DemotedFunction {
+                function: name,
+                instructions: size,
+                passes: Vec::new(),
+            });
+        if !entry.passes.contains(&pass) {
+            entry.passes.push(pass);
+        }
+        skipped.push((id, function));
+    }
+    skipped
+}
+
 /// Dead code elimination pass
 pub struct DeadCodeEliminationPass {
     // Configuration options can go here
@@ -571,7 +744,7 @@ impl OptimizationPass for ControlFlowSimplificationPass {
 // Helper functions
 
 /// Get registers used by a terminator
-fn terminator_uses(term: &IrTerminator) -> Vec<IrId> {
+pub(super) fn terminator_uses(term: &IrTerminator) -> Vec<IrId> {
     match term {
         IrTerminator::CondBranch { condition, .. } => vec![*condition],
         IrTerminator::Switch { value, .. } => vec![*value],
@@ -582,7 +755,10 @@ fn terminator_uses(term: &IrTerminator) -> Vec<IrId> {
 }
 
 /// Replace register uses in a terminator
-fn replace_terminator_uses(term: &mut IrTerminator, replacements: &BTreeMap<IrId, IrId>) {
+pub(super) fn replace_terminator_uses(
+    term: &mut IrTerminator,
+    replacements: &BTreeMap<IrId, IrId>,
+) {
     match term {
         IrTerminator::CondBranch { condition, .. } => {
             if let Some(&new_reg) = replacements.get(condition) {
@@ -1667,7 +1843,7 @@ impl OptimizationPass for TailCallOptimizationPass {
 }
 
 /// Optimization level for tiered compilation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OptimizationLevel {
     /// No optimization (fastest compilation)
     O0,
@@ -1682,13 +1858,36 @@ pub enum OptimizationLevel {
 impl PassManager {
     /// Create optimization pipeline for a specific level.
     pub fn for_level(level: OptimizationLevel) -> Self {
-        let mut manager = Self::new();
+        Self::for_program(level, None, None)
+    }
+
+    /// Pipeline for `level` optimizing one module of a program. Inlining
+    /// above O0 is weighed by the call site counts in `profile` (tiered
+    /// recompiles), and InsertFreePass looks callees in other modules up in
+    /// `escapes`.
+    pub fn for_program(
+        level: OptimizationLevel,
+        profile: Option<super::inlining::CallProfile>,
+        escapes: Option<std::sync::Arc<super::escape_summary::EscapeSummaries>>,
+    ) -> Self {
+        let mut manager = Self::new().with_size_limits(PassSizeLimits::defaults());
+        let inlining = || {
+            let pass = super::inlining::InliningPass::new();
+            match profile.clone() {
+                Some(profile) => pass.with_profile(profile),
+                None => pass,
+            }
+        };
 
         // InsertFreePass runs at ALL optimization levels — it's a correctness pass
         // that inserts Free instructions for non-escaping heap allocations.
         // The HIR-level drop analysis only handles direct `new` expressions; this
         // pass catches factory functions like createComplex() that return heap pointers.
-        manager.add_pass(super::insert_free::InsertFreePass::new());
+        let insert_free = super::insert_free::InsertFreePass::new();
+        manager.add_pass(match escapes {
+            Some(escapes) => insert_free.with_summaries(escapes),
+            None => insert_free,
+        });
 
         match level {
             OptimizationLevel::O0 => {
@@ -1711,7 +1910,7 @@ impl PassManager {
             OptimizationLevel::O1 => {
                 // Fast, low-overhead optimizations
                 // Always inline Haxe `inline`-marked functions + cost-model inlining
-                manager.add_pass(super::inlining::InliningPass::new());
+                manager.add_pass(inlining());
                 // manager.add_pass(GlobalLoadCachingPass::new()); // BUG: causes invalid IR
                 manager.add_pass(DeadCodeEliminationPass::new());
                 manager.add_pass(ConstantFoldingPass::new());
@@ -1720,11 +1919,14 @@ impl PassManager {
             }
             OptimizationLevel::O2 => {
                 // Standard optimizations
-                manager.add_pass(super::inlining::InliningPass::new());
+                manager.add_pass(inlining());
                 manager.add_pass(DeadCodeEliminationPass::new());
                 // SRA enabled - regular SRA doesn't modify phi nodes, phi-aware SRA remains disabled
                 manager.add_pass(super::scalar_replacement::ScalarReplacementPass::new());
                 manager.add_pass(ConstantFoldingPass::new());
+                // Field stores and reloads left over from inlined constructors
+                manager.add_pass(super::load_store::RedundantLoadEliminationPass::new());
+                manager.add_pass(super::load_store::DeadStoreEliminationPass::new());
                 manager.add_pass(CopyPropagationPass::new());
                 // GlobalLoadCachingPass: caches repeated global loads within functions
                 // Provides ~1.67x speedup on nbody by eliminating redundant HashMap lookups
@@ -1742,11 +1944,14 @@ impl PassManager {
             OptimizationLevel::O3 => {
                 // Aggressive optimizations
                 // Inlining first to expose more optimization opportunities
-                manager.add_pass(super::inlining::InliningPass::new());
+                manager.add_pass(inlining());
                 manager.add_pass(GlobalLoadCachingPass::new());
                 manager.add_pass(DeadCodeEliminationPass::new());
                 manager.add_pass(super::scalar_replacement::ScalarReplacementPass::new());
                 manager.add_pass(ConstantFoldingPass::new());
+                // Field stores and reloads left over from inlined constructors
+                manager.add_pass(super::load_store::RedundantLoadEliminationPass::new());
+                manager.add_pass(super::load_store::DeadStoreEliminationPass::new());
                 manager.add_pass(CopyPropagationPass::new());
                 // BCE: eliminate redundant bounds checks in for-in loops
                 manager
@@ -1756,6 +1961,10 @@ impl PassManager {
                 manager.add_pass(LICMPass::new());
                 // Loop vectorization after LICM (LICM prepares loops for vectorization)
                 manager.add_pass(super::vectorization::LoopVectorizationPass::new());
+                // Unroll and peel the loops left, then fold what the copies expose
+                manager.add_pass(super::loop_unroll::LoopUnrollPass::new());
+                manager.add_pass(CopyPropagationPass::new());
+                manager.add_pass(ConstantFoldingPass::new());
                 manager.add_pass(TailCallOptimizationPass::new());
                 manager.add_pass(ControlFlowSimplificationPass::new());
                 manager.add_pass(UnreachableBlockEliminationPass::new());
@@ -1763,6 +1972,11 @@ impl PassManager {
             }
         }
 
+        // Runs last so the weights describe the final CFG
+        manager.add_pass(super::branch_hints::BranchHintPass::new());
+        // After everything that renames registers, since it records one
+        manager.add_pass(super::return_slot::ReturnSlotPass::new());
+
         manager
     }
 }
@@ -1795,6 +2009,40 @@ mod tests {
         assert!(opt_result.modified);
     }
 
+    #[test]
+    fn test_size_limit_skips_large_functions() {
+        let mut builder = IrBuilder::new("test".to_string(), "test.hx".to_string());
+
+        let sig = FunctionSignatureBuilder::new().returns(IrType::I32).build();
+        builder.start_function(SymbolId::from_raw(1), "test".to_string(), sig);
+        let two = builder.build_int(2, IrType::I32).unwrap();
+        let three = builder.build_int(3, IrType::I32).unwrap();
+        let result = builder.build_add(two, three, false).unwrap();
+        builder.build_return(Some(result));
+        builder.finish_function();
+
+        let mut limits = PassSizeLimits::new();
+        limits.set("constant-folding", 2);
+        let mut manager = PassManager::new().with_size_limits(limits);
+        manager.add_pass(ConstantFoldingPass::new());
+        let opt_result = manager.run(&mut builder.module);
+
+        assert!(!opt_result.modified);
+        assert_eq!(builder.module.functions.len(), 1);
+        let demoted: Vec<_> = manager.demoted_functions().collect();
+        assert_eq!(demoted.len(), 1);
+        assert_eq!(demoted[0].function, "test");
+        assert_eq!(demoted[0].passes, vec!["constant-folding"]);
+
+        // Within the limit, the pass runs
+        let mut limits = PassSizeLimits::new();
+        limits.set("constant-folding", 100);
+        let mut manager = PassManager::new().with_size_limits(limits);
+        manager.add_pass(ConstantFoldingPass::new());
+        assert!(manager.run(&mut builder.module).modified);
+        assert_eq!(manager.demoted_functions().count(), 0);
+    }
+
     #[test]
     fn test_dead_code_elimination() {
         let mut builder = IrBuilder::new("test".to_string(), "test.hx".to_string());
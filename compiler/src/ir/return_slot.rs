@@ -0,0 +1,298 @@
+//! Return Slot — build struct return values in the caller's slot.
+//!
+//! A function returning a struct (`signature.uses_sret`) receives a hidden
+//! pointer to a slot in its caller. Without this pass the backend builds the
+//! value in a stack temporary and copies it field by field into the slot at
+//! every `return`, and a function ending in `return make()` gives `make` a
+//! fresh temporary of its own, so a chain of struct-returning helpers copies
+//! the value once per level.
+//!
+//! When every `return` of a function hands back the same register, and that
+//! register comes from a `CreateStruct` or a call to another struct-returning
+//! function, the pass records it in the `return_slot` function attribute.
+//! Cranelift then stores the fields straight into the caller's slot, or hands
+//! the slot on to the callee, and leaves out the copy at the return.
+//!
+//! `Copy`/`Move` temporaries between the construction and the `return` are
+//! looked through and the return is rewritten to the original register. The
+//! value must not reach a phi: built inside a loop, it would overwrite a copy
+//! from an earlier iteration that is still live.
+
+use super::blocks::IrTerminator;
+use super::functions::IrFunctionId;
+use super::instructions::IrInstruction;
+use super::optimization::{OptimizationPass, OptimizationResult};
+use super::{IrFunction, IrId, IrModule};
+use std::collections::{HashMap, HashSet};
+
+/// Function attribute (`attributes.custom`) naming the register built in
+/// the caller's return slot
+pub const RETURN_SLOT_ATTRIBUTE: &str = "return_slot";
+
+/// The register `function` builds in its caller's return slot, if any
+pub fn return_slot(function: &IrFunction) -> Option<IrId> {
+    let reg = function.attributes.custom.get(RETURN_SLOT_ATTRIBUTE)?;
+    reg.parse().ok().map(IrId::new)
+}
+
+/// Functions of `module` called with a hidden return slot (struct returns
+/// with a body; externs follow the C ABI instead)
+fn slot_functions(module: &IrModule) -> HashSet<IrFunctionId> {
+    module
+        .functions
+        .iter()
+        .filter(|(_, f)| f.signature.uses_sret && !f.cfg.blocks.is_empty())
+        .map(|(&id, _)| id)
+        .collect()
+}
+
+/// Pick the return slot register of one function; returns whether the
+/// attribute or a return value changed
+pub fn mark_function(function: &mut IrFunction, slot_callees: &HashSet<IrFunctionId>) -> bool {
+    let old = function.attributes.custom.remove(RETURN_SLOT_ATTRIBUTE);
+    let root = if function.signature.uses_sret {
+        slot_register(function, slot_callees)
+    } else {
+        None
+    };
+    let Some(root) = root else {
+        return old.is_some();
+    };
+
+    let mut changed = false;
+    for block in function.cfg.blocks.values_mut() {
+        if let IrTerminator::Return { value: Some(value) } = &mut block.terminator {
+            if *value != root {
+                *value = root;
+                changed = true;
+            }
+        }
+    }
+    let new = root.as_u32().to_string();
+    changed |= old.as_deref() != Some(new.as_str());
+    function
+        .attributes
+        .custom
+        .insert(RETURN_SLOT_ATTRIBUTE.to_string(), new);
+    changed
+}
+
+/// The register every return of `function` resolves to, if it can be built
+/// in the return slot
+fn slot_register(function: &IrFunction, slot_callees: &HashSet<IrFunctionId>) -> Option<IrId> {
+    let mut copies: HashMap<IrId, IrId> = HashMap::new();
+    let mut builds: HashSet<IrId> = HashSet::new();
+    for block in function.cfg.blocks.values() {
+        for inst in &block.instructions {
+            match inst {
+                IrInstruction::Copy { dest, src } | IrInstruction::Move { dest, src } => {
+                    copies.insert(*dest, *src);
+                }
+                IrInstruction::CreateStruct { dest, .. } => {
+                    builds.insert(*dest);
+                }
+                IrInstruction::CallDirect {
+                    dest: Some(dest),
+                    func_id,
+                    is_tail_call: false,
+                    ..
+                } if slot_callees.contains(func_id) => {
+                    builds.insert(*dest);
+                }
+                _ => {}
+            }
+        }
+    }
+    let resolve = |mut reg: IrId| {
+        // Bounded in case of a malformed copy cycle
+        for _ in 0..copies.len() {
+            match copies.get(&reg) {
+                Some(&src) => reg = src,
+                None => break,
+            }
+        }
+        reg
+    };
+
+    let mut root = None;
+    for block in function.cfg.blocks.values() {
+        match &block.terminator {
+            IrTerminator::Return { value: Some(value) } => {
+                let reg = resolve(*value);
+                if root.is_some_and(|root| root != reg) {
+                    return None;
+                }
+                root = Some(reg);
+            }
+            IrTerminator::Return { value: None } => return None,
+            _ => {}
+        }
+    }
+    let root = root.filter(|root| builds.contains(root))?;
+
+    let reaches_phi = function.cfg.blocks.values().any(|block| {
+        block.phi_nodes.iter().any(|phi| {
+            phi.incoming
+                .iter()
+                .any(|(_, value)| resolve(*value) == root)
+        })
+    });
+    (!reaches_phi).then_some(root)
+}
+
+/// Marks the register each struct-returning function builds in its
+/// caller's return slot
+pub struct ReturnSlotPass;
+
+impl ReturnSlotPass {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ReturnSlotPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OptimizationPass for ReturnSlotPass {
+    fn name(&self) -> &'static str {
+        "return-slot"
+    }
+
+    fn run_on_module(&mut self, module: &mut IrModule) -> OptimizationResult {
+        let slot_callees = slot_functions(module);
+        let mut marked = 0;
+        for function in module.functions.values_mut() {
+            if mark_function(function, &slot_callees) {
+                marked += 1;
+            }
+        }
+
+        if marked == 0 {
+            return OptimizationResult::unchanged();
+        }
+        let mut stats = HashMap::new();
+        stats.insert("return_slot_functions".to_string(), marked);
+        OptimizationResult {
+            modified: true,
+            instructions_eliminated: 0,
+            blocks_eliminated: 0,
+            stats,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::blocks::IrPhiNode;
+    use crate::ir::mir_builder::MirBuilder;
+    use crate::ir::IrType;
+
+    fn pair_type(builder: &MirBuilder) -> IrType {
+        builder.struct_type(Some("pair"), vec![IrType::I64, IrType::I64])
+    }
+
+    /// `make_pair(a, b)` building a struct and `wrap(a)` returning
+    /// `make_pair(a, a)` through a copy
+    fn pair_module() -> (IrModule, IrFunctionId, IrFunctionId) {
+        let mut builder = MirBuilder::new("test");
+        let pair_ty = pair_type(&builder);
+        let make_pair = builder
+            .begin_function("make_pair")
+            .param("a", IrType::I64)
+            .param("b", IrType::I64)
+            .returns(pair_ty.clone())
+            .build();
+        builder.set_current_function(make_pair);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let a = builder.get_param(0);
+        let b = builder.get_param(1);
+        let pair = builder.create_struct(pair_ty.clone(), vec![a, b]);
+        builder.ret(Some(pair));
+
+        let wrap = builder
+            .begin_function("wrap")
+            .param("a", IrType::I64)
+            .returns(pair_ty)
+            .build();
+        builder.set_current_function(wrap);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let a = builder.get_param(0);
+        builder.call(make_pair, vec![a, a]).unwrap();
+
+        (builder.finish(), make_pair, wrap)
+    }
+
+    /// Append `copy = result` and `return copy` to `wrap`'s entry block
+    fn return_through_copy(module: &mut IrModule, wrap: IrFunctionId) -> (IrId, IrId) {
+        let function = module.functions.get_mut(&wrap).unwrap();
+        let entry = function.cfg.entry_block;
+        let block = function.cfg.blocks.get_mut(&entry).unwrap();
+        let result = block.instructions.last().unwrap().dest().unwrap();
+        let copy = IrId::new(result.as_u32() + 1);
+        block.instructions.push(IrInstruction::Copy {
+            dest: copy,
+            src: result,
+        });
+        block.terminator = IrTerminator::Return { value: Some(copy) };
+        (result, copy)
+    }
+
+    #[test]
+    fn test_marks_struct_and_forwarded_call() {
+        let (mut module, make_pair, wrap) = pair_module();
+        let (result, _) = return_through_copy(&mut module, wrap);
+
+        let mut pass = ReturnSlotPass::new();
+        assert!(pass.run_on_module(&mut module).modified);
+
+        let pair = &module.functions[&make_pair];
+        let entry = &pair.cfg.blocks[&pair.cfg.entry_block];
+        assert_eq!(return_slot(pair), entry.instructions.last().unwrap().dest());
+
+        // The copy is looked through and the return uses the call result
+        let wrap = &module.functions[&wrap];
+        assert_eq!(return_slot(wrap), Some(result));
+        let entry = &wrap.cfg.blocks[&wrap.cfg.entry_block];
+        assert!(matches!(
+            entry.terminator,
+            IrTerminator::Return { value: Some(v) } if v == result
+        ));
+
+        // Nothing left to change on a second run
+        assert!(!pass.run_on_module(&mut module).modified);
+    }
+
+    #[test]
+    fn test_skips_value_reaching_phi() {
+        let (mut module, _, wrap) = pair_module();
+        let (result, copy) = return_through_copy(&mut module, wrap);
+
+        // A phi taking the copy keeps the value out of the return slot
+        let function = module.functions.get_mut(&wrap).unwrap();
+        let entry = function.cfg.entry_block;
+        function
+            .cfg
+            .blocks
+            .get_mut(&entry)
+            .unwrap()
+            .phi_nodes
+            .push(IrPhiNode {
+                dest: IrId::new(copy.as_u32() + 1),
+                incoming: vec![(entry, copy)],
+                ty: IrType::I64,
+            });
+        function.attributes.custom.insert(
+            RETURN_SLOT_ATTRIBUTE.to_string(),
+            result.as_u32().to_string(),
+        );
+
+        ReturnSlotPass::new().run_on_module(&mut module);
+        assert_eq!(return_slot(&module.functions[&wrap]), None);
+    }
+}
@@ -0,0 +1,749 @@
+//! Constant argument specialization (function cloning)
+//!
+//! A function that is nearly always called with the same value for some
+//! parameter, e.g. a config flag, gets a clone in which that parameter is
+//! replaced by the constant. Constant folding and CFG simplification then
+//! fold away the branches on it.
+//!
+//! The values come from the interpreter (`ProfileData::arg_values`), so
+//! clones are only made on tiered recompiles (Tier 2+). Calls in the same
+//! module are redirected: directly where the caller passes the same
+//! constants, otherwise behind a guard that compares the arguments and
+//! falls back to the original function.
+//!
+//! Clones are keyed by (function, constant arguments). `SpecializationLimits`
+//! caps the size of functions that are cloned, the number of clones and
+//! the instructions they add in total.
+
+use super::blocks::{IrBlockId, IrPhiNode, IrTerminator};
+use super::functions::IrFunctionId;
+use super::instructions::{BinaryOp, CompareOp, IrInstruction};
+use super::optimization::{function_size, replace_terminator_uses, InstructionExt};
+use super::{IrFunction, IrId, IrModule, IrType, IrValue};
+use std::collections::{BTreeMap, HashMap};
+
+/// Function attribute (`attributes.custom`) naming the function a clone
+/// was specialized from
+pub const SPECIALIZED_FROM_ATTRIBUTE: &str = "specialized_from";
+
+/// Profile of the values one parameter received: the leading value and its
+/// lead, i.e. calls with it minus calls with other values since it took the
+/// lead (a majority vote, so a value passed in most calls always leads)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArgValueCount {
+    pub value: i64,
+    pub lead: u64,
+    pub total: u64,
+}
+
+impl ArgValueCount {
+    /// Count one call that passed `value`
+    pub fn record(&mut self, value: i64) {
+        self.total += 1;
+        if self.lead == 0 {
+            self.value = value;
+            self.lead = 1;
+        } else if self.value == value {
+            self.lead += 1;
+        } else {
+            self.lead -= 1;
+        }
+    }
+}
+
+/// Integer and Bool argument values of profiled calls, by (function,
+/// parameter index)
+pub type ArgValues = HashMap<(IrFunctionId, usize), ArgValueCount>;
+
+/// When a parameter counts as constant, and how much cloning is allowed
+#[derive(Debug, Clone)]
+pub struct SpecializationLimits {
+    /// Profiled calls a function needs before it is considered
+    pub min_calls: u64,
+    /// Lead of the value as a percentage of calls (80 means at least 90%
+    /// of calls passed it)
+    pub min_lead_percent: u64,
+    /// Largest function (instructions and phis) that is cloned
+    pub max_function_size: usize,
+    /// Most clones per compile
+    pub max_clones: usize,
+    /// Most instructions all clones may add together
+    pub max_total_size: usize,
+}
+
+impl Default for SpecializationLimits {
+    fn default() -> Self {
+        Self {
+            min_calls: 100,
+            min_lead_percent: 80,
+            max_function_size: 400,
+            max_clones: 16,
+            max_total_size: 4000,
+        }
+    }
+}
+
+/// One clone: `function` with `constants` (parameter index, value)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Specialization {
+    pub function: IrFunctionId,
+    pub clone: IrFunctionId,
+    pub constants: Vec<(usize, i64)>,
+    /// Call sites calling the clone directly
+    pub direct_calls: usize,
+    /// Call sites calling it behind a guard
+    pub guarded_calls: usize,
+}
+
+/// Clone functions for the argument values that dominate `arg_values` and
+/// redirect their callers. Clones get fresh IDs, unique across `modules`.
+pub fn specialize_const_args(
+    modules: &mut [IrModule],
+    arg_values: &ArgValues,
+    limits: &SpecializationLimits,
+) -> Vec<Specialization> {
+    let mut next_id = modules
+        .iter()
+        .flat_map(|m| m.functions.keys().chain(m.extern_functions.keys()))
+        .map(|id| id.0 + 1)
+        .max()
+        .unwrap_or(0);
+
+    // Most called functions first, so the caps keep the valuable clones
+    let mut candidates: Vec<(u64, usize, IrFunctionId, Vec<(usize, i64)>)> = Vec::new();
+    for (module_index, module) in modules.iter().enumerate() {
+        for (&func_id, function) in &module.functions {
+            if let Some((calls, constants)) = constant_params(function, arg_values, limits) {
+                candidates.push((calls, module_index, func_id, constants));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.cmp(&a.0).then(a.2.cmp(&b.2)));
+
+    let mut result = Vec::new();
+    let mut total_size = 0;
+    for (_, module_index, func_id, constants) in candidates {
+        if result.len() >= limits.max_clones {
+            break;
+        }
+        let module = &mut modules[module_index];
+        let size = function_size(&module.functions[&func_id]);
+        if total_size + size > limits.max_total_size {
+            continue;
+        }
+
+        let sites = call_sites(module, func_id);
+        if sites.is_empty() {
+            continue;
+        }
+
+        let function = &module.functions[&func_id];
+        let typed: Vec<(usize, IrValue)> = constants
+            .iter()
+            .map(|&(index, value)| {
+                let ty = &function.signature.parameters[index].ty;
+                (index, const_value(ty, value).unwrap_or(IrValue::I64(value)))
+            })
+            .collect();
+        let clone_id = IrFunctionId(next_id);
+        next_id += 1;
+        let clone = specialize(function, clone_id, &constants, &typed);
+        module.add_function(clone);
+        total_size += size;
+
+        let mut direct_calls = 0;
+        let mut guarded_calls = 0;
+        for (caller_id, block, index) in sites {
+            let Some(caller) = module.functions.get_mut(&caller_id) else {
+                continue;
+            };
+            match redirect_call(caller, block, index, clone_id, &typed) {
+                Some(true) => direct_calls += 1,
+                Some(false) => guarded_calls += 1,
+                None => {}
+            }
+        }
+        result.push(Specialization {
+            function: func_id,
+            clone: clone_id,
+            constants,
+            direct_calls,
+            guarded_calls,
+        });
+    }
+    result
+}
+
+/// Parameters of `function` that the profile shows as constant, with the
+/// number of profiled calls
+fn constant_params(
+    function: &IrFunction,
+    arg_values: &ArgValues,
+    limits: &SpecializationLimits,
+) -> Option<(u64, Vec<(usize, i64)>)> {
+    if function.cfg.blocks.is_empty()
+        || function
+            .attributes
+            .custom
+            .contains_key(super::branch_hints::COLD_ATTRIBUTE)
+        || function
+            .attributes
+            .custom
+            .contains_key(SPECIALIZED_FROM_ATTRIBUTE)
+        || function_size(function) > limits.max_function_size
+    {
+        return None;
+    }
+
+    let mut calls = 0;
+    let mut constants = Vec::new();
+    for (index, param) in function.signature.parameters.iter().enumerate() {
+        if const_value(&param.ty, 0).is_none() {
+            continue;
+        }
+        let Some(count) = arg_values.get(&(function.id, index)) else {
+            continue;
+        };
+        calls = calls.max(count.total);
+        if count.total >= limits.min_calls
+            && count.lead * 100 >= count.total * limits.min_lead_percent
+        {
+            constants.push((index, count.value));
+        }
+    }
+    (!constants.is_empty()).then_some((calls, constants))
+}
+
+/// `value` as a constant of integer or Bool type `ty`
+fn const_value(ty: &IrType, value: i64) -> Option<IrValue> {
+    Some(match ty {
+        IrType::Bool => IrValue::Bool(value != 0),
+        IrType::I8 => IrValue::I8(value as i8),
+        IrType::I16 => IrValue::I16(value as i16),
+        IrType::I32 => IrValue::I32(value as i32),
+        IrType::I64 => IrValue::I64(value),
+        IrType::U8 => IrValue::U8(value as u8),
+        IrType::U16 => IrValue::U16(value as u16),
+        IrType::U32 => IrValue::U32(value as u32),
+        IrType::U64 => IrValue::U64(value as u64),
+        _ => return None,
+    })
+}
+
+/// The value of an integer or Bool constant
+fn const_int(value: &IrValue) -> Option<i64> {
+    Some(match value {
+        IrValue::Bool(b) => *b as i64,
+        IrValue::I8(v) => *v as i64,
+        IrValue::I16(v) => *v as i64,
+        IrValue::I32(v) => *v as i64,
+        IrValue::I64(v) => *v,
+        IrValue::U8(v) => *v as i64,
+        IrValue::U16(v) => *v as i64,
+        IrValue::U32(v) => *v as i64,
+        IrValue::U64(v) => *v as i64,
+        _ => return None,
+    })
+}
+
+/// Type of an integer or Bool constant
+fn value_type(value: &IrValue) -> IrType {
+    match value {
+        IrValue::Bool(_) => IrType::Bool,
+        IrValue::I8(_) => IrType::I8,
+        IrValue::I16(_) => IrType::I16,
+        IrValue::I32(_) => IrType::I32,
+        IrValue::U8(_) => IrType::U8,
+        IrValue::U16(_) => IrType::U16,
+        IrValue::U32(_) => IrType::U32,
+        IrValue::U64(_) => IrType::U64,
+        _ => IrType::I64,
+    }
+}
+
+/// (caller, block, instruction index) of every direct call to `callee` in
+/// `module`, last first within each block so redirecting one keeps the
+/// others' positions
+fn call_sites(module: &IrModule, callee: IrFunctionId) -> Vec<(IrFunctionId, IrBlockId, usize)> {
+    let mut sites = Vec::new();
+    for (&caller_id, caller) in &module.functions {
+        for (&block_id, block) in &caller.cfg.blocks {
+            for (index, inst) in block.instructions.iter().enumerate().rev() {
+                if let IrInstruction::CallDirect { func_id, .. } = inst {
+                    if *func_id == callee {
+                        sites.push((caller_id, block_id, index));
+                    }
+                }
+            }
+        }
+    }
+    sites
+}
+
+/// First register number not used in `function`
+fn next_free_reg(function: &IrFunction) -> u32 {
+    let mut next = function.next_reg_id;
+    for param in &function.signature.parameters {
+        next = next.max(param.reg.as_u32() + 1);
+    }
+    for block in function.cfg.blocks.values() {
+        for phi in &block.phi_nodes {
+            next = next.max(phi.dest.as_u32() + 1);
+        }
+        for inst in &block.instructions {
+            if let Some(dest) = inst.dest() {
+                next = next.max(dest.as_u32() + 1);
+            }
+        }
+    }
+    next
+}
+
+/// Copy of `function` whose parameters in `constants` are replaced by the
+/// constants (`typed`, as values of the parameter types), defined at the
+/// top of the entry block
+fn specialize(
+    function: &IrFunction,
+    id: IrFunctionId,
+    constants: &[(usize, i64)],
+    typed: &[(usize, IrValue)],
+) -> IrFunction {
+    let mut clone = function.clone();
+    clone.id = id;
+    clone.name = format!(
+        "{}__spec_{}",
+        function.name,
+        constants
+            .iter()
+            .map(|(index, value)| format!("{}_{}", index, value).replace('-', "m"))
+            .collect::<Vec<_>>()
+            .join("_")
+    );
+    clone.attributes.custom.insert(
+        SPECIALIZED_FROM_ATTRIBUTE.to_string(),
+        function.name.clone(),
+    );
+
+    let mut next_reg = next_free_reg(&clone);
+    let mut replacements = BTreeMap::new();
+    let mut defs = Vec::new();
+    for (index, value) in typed {
+        let param = &clone.signature.parameters[*index];
+        let reg = IrId::new(next_reg);
+        next_reg += 1;
+        clone.register_types.insert(reg, param.ty.clone());
+        replacements.insert(param.reg, reg);
+        defs.push(IrInstruction::Const {
+            dest: reg,
+            value: value.clone(),
+        });
+    }
+    clone.next_reg_id = next_reg;
+
+    for block in clone.cfg.blocks.values_mut() {
+        for phi in &mut block.phi_nodes {
+            for (_, value) in &mut phi.incoming {
+                if let Some(&reg) = replacements.get(value) {
+                    *value = reg;
+                }
+            }
+        }
+        for inst in &mut block.instructions {
+            inst.replace_uses(&replacements);
+        }
+        replace_terminator_uses(&mut block.terminator, &replacements);
+    }
+    let entry = clone.cfg.entry_block;
+    if let Some(entry_block) = clone.cfg.get_block_mut(entry) {
+        entry_block.instructions.splice(0..0, defs);
+    }
+    clone
+}
+
+/// Point the call at `block[index]` to `clone`. Returns Some(true) if the
+/// caller passes the constants, Some(false) if the call was guarded, and
+/// None if it passes other constants and keeps calling the original.
+fn redirect_call(
+    caller: &mut IrFunction,
+    block: IrBlockId,
+    index: usize,
+    clone: IrFunctionId,
+    constants: &[(usize, IrValue)],
+) -> Option<bool> {
+    // Constants defined in the caller
+    let mut known: HashMap<IrId, i64> = HashMap::new();
+    for b in caller.cfg.blocks.values() {
+        for inst in &b.instructions {
+            if let IrInstruction::Const { dest, value } = inst {
+                if let Some(v) = const_int(value) {
+                    known.insert(*dest, v);
+                }
+            }
+        }
+    }
+
+    let IrInstruction::CallDirect { args, .. } = &caller.cfg.blocks[&block].instructions[index]
+    else {
+        return None;
+    };
+    let mut unknown = Vec::new();
+    for (param, value) in constants {
+        let arg = *args.get(*param)?;
+        match known.get(&arg) {
+            Some(&v) if Some(v) == const_int(value) => {}
+            Some(_) => return None,
+            None => unknown.push((arg, value.clone())),
+        }
+    }
+
+    if unknown.is_empty() {
+        if let Some(IrInstruction::CallDirect { func_id, .. }) = caller
+            .cfg
+            .get_block_mut(block)
+            .and_then(|b| b.instructions.get_mut(index))
+        {
+            *func_id = clone;
+        }
+        return Some(true);
+    }
+
+    guard_call(caller, block, index, clone, &unknown);
+    Some(false)
+}
+
+/// Split `block` at the call at `index`: the arguments in `checks` are
+/// compared against their values, and the call goes to `clone` if all
+/// match and to the original function otherwise
+fn guard_call(
+    caller: &mut IrFunction,
+    block: IrBlockId,
+    index: usize,
+    clone: IrFunctionId,
+    checks: &[(IrId, IrValue)],
+) {
+    let mut next_reg = next_free_reg(caller);
+    let mut new_reg = |caller: &mut IrFunction, ty: IrType| {
+        let reg = IrId::new(next_reg);
+        next_reg += 1;
+        caller.register_types.insert(reg, ty);
+        reg
+    };
+
+    let spec_block = caller.cfg.create_block();
+    let generic_block = caller.cfg.create_block();
+    let cont_block = caller.cfg.create_block();
+
+    let (call, tail, terminator) = {
+        let b = caller.cfg.get_block_mut(block).unwrap();
+        let tail: Vec<IrInstruction> = b.instructions.drain(index + 1..).collect();
+        let call = b.instructions.remove(index);
+        let terminator = std::mem::replace(
+            &mut b.terminator,
+            IrTerminator::Branch {
+                target: generic_block,
+            },
+        );
+        (call, tail, terminator)
+    };
+
+    // Guard: every checked argument equals its constant
+    let mut guard_insts = Vec::new();
+    let mut condition = None;
+    for (arg, value) in checks {
+        let ty = value_type(value);
+        let constant_reg = new_reg(caller, ty);
+        let eq = new_reg(caller, IrType::Bool);
+        guard_insts.push(IrInstruction::Const {
+            dest: constant_reg,
+            value: value.clone(),
+        });
+        guard_insts.push(IrInstruction::Cmp {
+            dest: eq,
+            op: CompareOp::Eq,
+            left: *arg,
+            right: constant_reg,
+        });
+        condition = Some(match condition {
+            None => eq,
+            Some(previous) => {
+                let both = new_reg(caller, IrType::Bool);
+                guard_insts.push(IrInstruction::BinOp {
+                    dest: both,
+                    op: BinaryOp::And,
+                    left: previous,
+                    right: eq,
+                });
+                both
+            }
+        });
+    }
+
+    // Each side calls into its own result register, merged by a phi
+    let dest = match &call {
+        IrInstruction::CallDirect { dest, .. } => *dest,
+        _ => None,
+    };
+    let mut spec_call = call.clone();
+    let mut generic_call = call;
+    if let Some(dest) = dest {
+        let ty = caller
+            .register_types
+            .get(&dest)
+            .cloned()
+            .unwrap_or(IrType::Any);
+        let spec_dest = new_reg(caller, ty.clone());
+        let generic_dest = new_reg(caller, ty.clone());
+        spec_call.replace_dest(spec_dest);
+        generic_call.replace_dest(generic_dest);
+        let incoming = vec![(spec_block, spec_dest), (generic_block, generic_dest)];
+        caller
+            .cfg
+            .get_block_mut(cont_block)
+            .unwrap()
+            .phi_nodes
+            .push(IrPhiNode { dest, incoming, ty });
+    }
+    if let IrInstruction::CallDirect { func_id, .. } = &mut spec_call {
+        *func_id = clone;
+    }
+    caller.next_reg_id = next_reg;
+
+    // Successors now branch from the continuation block
+    for succ in terminator_successors(&terminator) {
+        if let Some(succ_block) = caller.cfg.get_block_mut(succ) {
+            for phi in &mut succ_block.phi_nodes {
+                for (pred, _) in &mut phi.incoming {
+                    if *pred == block {
+                        *pred = cont_block;
+                    }
+                }
+            }
+            for pred in &mut succ_block.predecessors {
+                if *pred == block {
+                    *pred = cont_block;
+                }
+            }
+        }
+    }
+    {
+        let b = caller.cfg.get_block_mut(block).unwrap();
+        b.instructions.extend(guard_insts);
+        b.terminator = match condition {
+            Some(condition) => IrTerminator::CondBranch {
+                condition,
+                true_target: spec_block,
+                false_target: generic_block,
+            },
+            None => IrTerminator::Branch {
+                target: generic_block,
+            },
+        };
+    }
+    for (side, call) in [(spec_block, spec_call), (generic_block, generic_call)] {
+        let b = caller.cfg.get_block_mut(side).unwrap();
+        b.instructions.push(call);
+        b.terminator = IrTerminator::Branch { target: cont_block };
+        caller.cfg.connect_blocks(block, side);
+        caller.cfg.connect_blocks(side, cont_block);
+    }
+    let cont = caller.cfg.get_block_mut(cont_block).unwrap();
+    cont.instructions = tail;
+    cont.terminator = terminator;
+}
+
+fn terminator_successors(terminator: &IrTerminator) -> Vec<IrBlockId> {
+    match terminator {
+        IrTerminator::Branch { target } => vec![*target],
+        IrTerminator::CondBranch {
+            true_target,
+            false_target,
+            ..
+        } => vec![*true_target, *false_target],
+        IrTerminator::Switch { cases, default, .. } => {
+            let mut targets: Vec<IrBlockId> = cases.iter().map(|(_, t)| *t).collect();
+            targets.push(*default);
+            targets
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::mir_builder::MirBuilder;
+
+    /// `scale(x, flag)` branching on `flag`, `guarded(x, flag)` passing its
+    /// own flag, and `direct(x)` calling it with `true` and with `false`
+    fn flag_module() -> (IrModule, IrFunctionId, IrFunctionId, IrFunctionId) {
+        let mut builder = MirBuilder::new("test");
+        let scale = builder
+            .begin_function("scale")
+            .param("x", IrType::I32)
+            .param("flag", IrType::Bool)
+            .returns(IrType::I32)
+            .build();
+        builder.set_current_function(scale);
+        let entry = builder.create_block("entry");
+        let double = builder.create_block("double");
+        let keep = builder.create_block("keep");
+        builder.set_insert_point(entry);
+        let x = builder.get_param(0);
+        let flag = builder.get_param(1);
+        builder.cond_br(flag, double, keep);
+        builder.set_insert_point(double);
+        let doubled = builder.add(x, x, IrType::I32);
+        builder.ret(Some(doubled));
+        builder.set_insert_point(keep);
+        builder.ret(Some(x));
+
+        let guarded = builder
+            .begin_function("guarded")
+            .param("x", IrType::I32)
+            .param("flag", IrType::Bool)
+            .returns(IrType::I32)
+            .build();
+        builder.set_current_function(guarded);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let x = builder.get_param(0);
+        let flag = builder.get_param(1);
+        let result = builder.call(scale, vec![x, flag]).unwrap();
+        let one = builder.const_i32(1);
+        let sum = builder.add(result, one, IrType::I32);
+        builder.ret(Some(sum));
+
+        let direct = builder
+            .begin_function("direct")
+            .param("x", IrType::I32)
+            .returns(IrType::I32)
+            .build();
+        builder.set_current_function(direct);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        let x = builder.get_param(0);
+        let yes = builder.const_bool(true);
+        let no = builder.const_bool(false);
+        let a = builder.call(scale, vec![x, yes]).unwrap();
+        let b = builder.call(scale, vec![a, no]).unwrap();
+        builder.ret(Some(b));
+
+        (builder.finish(), scale, guarded, direct)
+    }
+
+    fn called(function: &IrFunction) -> Vec<IrFunctionId> {
+        function
+            .cfg
+            .blocks
+            .values()
+            .flat_map(|block| &block.instructions)
+            .filter_map(|inst| match inst {
+                IrInstruction::CallDirect { func_id, .. } => Some(*func_id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_arg_value_majority() {
+        let mut count = ArgValueCount {
+            value: 0,
+            lead: 0,
+            total: 0,
+        };
+        for value in [1, 1, 2, 1, 3, 1] {
+            count.record(value);
+        }
+        assert_eq!(
+            count,
+            ArgValueCount {
+                value: 1,
+                lead: 2,
+                total: 6
+            }
+        );
+    }
+
+    #[test]
+    fn test_specialize_constant_flag() {
+        let (module, scale, guarded, direct) = flag_module();
+        let mut arg_values = ArgValues::new();
+        arg_values.insert(
+            (scale, 1),
+            ArgValueCount {
+                value: 1,
+                lead: 190,
+                total: 200,
+            },
+        );
+        // Varies too much to specialize
+        arg_values.insert(
+            (scale, 0),
+            ArgValueCount {
+                value: 7,
+                lead: 20,
+                total: 200,
+            },
+        );
+
+        let mut modules = vec![module];
+        let specialized =
+            specialize_const_args(&mut modules, &arg_values, &SpecializationLimits::default());
+        assert_eq!(specialized.len(), 1);
+        let spec = &specialized[0];
+        assert_eq!(spec.function, scale);
+        assert_eq!(spec.constants, vec![(1, 1)]);
+        assert_eq!((spec.direct_calls, spec.guarded_calls), (1, 1));
+
+        let module = &modules[0];
+        let clone = &module.functions[&spec.clone];
+        assert_eq!(clone.name, "scale__spec_1_1");
+        let entry = &clone.cfg.blocks[&clone.cfg.entry_block];
+        let IrInstruction::Const {
+            dest,
+            value: IrValue::Bool(true),
+        } = entry.instructions[0]
+        else {
+            panic!("flag not replaced: {:?}", entry.instructions);
+        };
+        assert!(matches!(
+            entry.terminator,
+            IrTerminator::CondBranch { condition, .. } if condition == dest
+        ));
+
+        // `true` goes straight to the clone, `false` keeps the original
+        assert_eq!(called(&module.functions[&direct]), vec![spec.clone, scale]);
+
+        // A runtime flag picks between both
+        let guarded = &module.functions[&guarded];
+        let mut targets = called(guarded);
+        targets.sort();
+        assert_eq!(targets, vec![scale, spec.clone]);
+        assert!(guarded
+            .cfg
+            .blocks
+            .values()
+            .any(|block| block.phi_nodes.len() == 1));
+    }
+
+    #[test]
+    fn test_size_cap() {
+        let (module, scale, _, _) = flag_module();
+        let mut arg_values = ArgValues::new();
+        arg_values.insert(
+            (scale, 1),
+            ArgValueCount {
+                value: 1,
+                lead: 200,
+                total: 200,
+            },
+        );
+        let limits = SpecializationLimits {
+            max_function_size: 0,
+            ..Default::default()
+        };
+        let mut modules = vec![module];
+        assert!(specialize_const_args(&mut modules, &arg_values, &limits).is_empty());
+        assert_eq!(modules[0].functions.len(), 3);
+    }
+}
@@ -180,11 +180,13 @@ impl<'a> TastToHirContext<'a> {
     /// This handles cases like: static inline var SOLAR_MASS = 4.0 * PI * PI;
     /// where PI is also a static inline var
     fn evaluate_inline_static_vars(&mut self, file: &TypedFile) {
-        // Collect all static fields that need evaluation (from classes and abstracts)
+        // Collect all static fields that need evaluation (immutable class statics
+        // and abstract statics); mutable statics are globals read at runtime
         let static_fields: Vec<_> = file
             .classes
             .iter()
             .flat_map(|class| class.fields.iter())
+            .filter(|field| field.mutability == crate::tast::Mutability::Immutable)
             .chain(file.abstracts.iter().flat_map(|abs| abs.fields.iter()))
             .filter(|field| field.is_static && field.initializer.is_some())
             .map(|field| (field.symbol_id, field.initializer.as_ref().unwrap().clone()))
@@ -407,6 +409,7 @@ impl<'a> TastToHirContext<'a> {
                 is_static: method.is_static,
                 is_override: method.metadata.is_override,
                 is_abstract: false, // Abstract methods would have no body
+                metadata: self.lower_metadata(&method.metadata.user_metadata),
             });
         }
 
@@ -421,6 +424,7 @@ impl<'a> TastToHirContext<'a> {
                 is_static: field.is_static,
                 is_final: !matches!(field.mutability, crate::tast::Mutability::Mutable),
                 property_access: field.property_access.clone(), // Preserve property accessor info
+                metadata: self.lower_metadata(&field.metadata),
             });
         }
 
@@ -436,7 +440,7 @@ impl<'a> TastToHirContext<'a> {
             fields: hir_fields,
             methods: hir_methods,
             constructor: hir_constructor,
-            metadata: Vec::new(), // Convert from TypedMetadata when available
+            metadata: self.lower_metadata(&class.metadata),
             // Look up class hierarchy info from SymbolTable
             is_final: self.is_class_final(class.symbol_id),
             is_abstract: self.is_class_abstract(class.symbol_id),
@@ -509,6 +513,7 @@ impl<'a> TastToHirContext<'a> {
                 name: variant.name.clone(),
                 fields: hir_fields,
                 discriminant: Some(i as i32),
+                metadata: self.lower_metadata(&variant.metadata),
             });
         }
 
@@ -520,7 +525,7 @@ impl<'a> TastToHirContext<'a> {
             name: enum_decl.name.clone(),
             type_params: self.lower_type_params(&enum_decl.type_parameters),
             variants: hir_variants,
-            metadata: Vec::new(),
+            metadata: self.lower_metadata(&enum_decl.metadata),
         };
 
         self.module
@@ -638,6 +643,7 @@ impl<'a> TastToHirContext<'a> {
                 is_static: method.is_static,
                 is_override: false,
                 is_abstract: false,
+                metadata: Vec::new(),
             })
             .collect();
 
@@ -702,6 +708,7 @@ impl<'a> TastToHirContext<'a> {
 
         // Extract optimization hints from SemanticGraphs/DFG if available
         let mut metadata = self.extract_function_metadata(&function.metadata);
+        metadata.extend(self.lower_function_hints(&function.metadata.user_metadata));
         if let Some(semantic_graphs) = self.semantic_graphs {
             metadata.extend(self.extract_ssa_optimization_hints(function, semantic_graphs));
         }
@@ -1260,7 +1267,11 @@ impl<'a> TastToHirContext<'a> {
                     for class in &file.classes {
                         for field in &class.fields {
                             if field.symbol_id == *symbol_id && field.is_static {
-                                // Found a static field - try to inline its constant value
+                                // Found a static field - try to inline its constant value.
+                                // Mutable statics are read from their global instead.
+                                if field.mutability != crate::tast::Mutability::Immutable {
+                                    break;
+                                }
                                 if let Some(ref init_expr) = field.initializer {
                                     if let TypedExpressionKind::Literal { value } = &init_expr.kind
                                     {
@@ -1427,8 +1438,13 @@ impl<'a> TastToHirContext<'a> {
                         )
                         .collect();
 
+                    let is_class = file.classes.iter().any(|c| c.symbol_id == *class_symbol);
                     for field in field_iter {
                         if field.symbol_id == *field_symbol && field.is_static {
+                            // Mutable class statics are read from their global
+                            if is_class && field.mutability != crate::tast::Mutability::Immutable {
+                                break;
+                            }
                             if let Some(ref init_expr) = field.initializer {
                                 if let TypedExpressionKind::Literal { value } = &init_expr.kind {
                                     inlined_value =
@@ -2491,8 +2507,50 @@ impl<'a> TastToHirContext<'a> {
                 metadata,
                 expression,
             } => {
-                // Metadata-annotated expression — just lower the inner expression
-                return self.lower_expression(expression);
+                // `@:likely cond` / `@:unlikely cond` become branch hints keyed
+                // by the condition's location; other metadata is dropped
+                let likely = metadata.iter().find_map(|meta| {
+                    let name = self.string_interner.get(meta.name)?;
+                    match name.strip_prefix(':').unwrap_or(name) {
+                        "likely" => Some(true),
+                        "unlikely" => Some(false),
+                        _ => None,
+                    }
+                });
+                // `@:unroll(N)` on a loop is keyed by its condition's location
+                let unroll = metadata.iter().find_map(|meta| {
+                    let name = self.string_interner.get(meta.name)?;
+                    (name.strip_prefix(':').unwrap_or(name) == "unroll").then(|| {
+                        meta.params.first().and_then(|param| match &param.kind {
+                            TypedExpressionKind::Literal {
+                                value: LiteralValue::Int(factor),
+                            } => Some(u32::try_from(*factor).unwrap_or(0)),
+                            _ => None,
+                        })
+                    })
+                });
+                let mut inner = self.lower_expression(expression);
+                if let Some(likely) = likely {
+                    if inner.source_location.is_valid() {
+                        self.module.metadata.optimization_hints.push(
+                            HirOptimizationHint::BranchHint(inner.source_location, likely),
+                        );
+                    }
+                }
+                if let Some(factor) = unroll {
+                    if let Some(condition) = loop_condition(&mut inner) {
+                        // Desugared `for` loops have no condition location
+                        if !condition.source_location.is_valid() {
+                            condition.source_location = expr.source_location;
+                        }
+                        if condition.source_location.is_valid() {
+                            self.module.metadata.optimization_hints.push(
+                                HirOptimizationHint::LoopUnroll(condition.source_location, factor),
+                            );
+                        }
+                    }
+                }
+                return inner;
             }
             TypedExpressionKind::MacroExpression {
                 macro_symbol,
@@ -2638,6 +2696,10 @@ impl<'a> TastToHirContext<'a> {
     fn lower_lvalue(&mut self, expr: &TypedExpression) -> HirLValue {
         match &expr.kind {
             TypedExpressionKind::Variable { symbol_id } => HirLValue::Variable(*symbol_id),
+            // Static fields are globals, assigned by symbol
+            TypedExpressionKind::StaticFieldAccess { field_symbol, .. } => {
+                HirLValue::Variable(*field_symbol)
+            }
             TypedExpressionKind::FieldAccess {
                 object,
                 field_symbol,
@@ -2796,9 +2858,55 @@ impl<'a> TastToHirContext<'a> {
         }
     }
 
-    fn lower_metadata(&mut self, _metadata: &[TypedMetadata]) -> Vec<HirAttribute> {
-        // TODO: Implement metadata lowering
-        Vec::new()
+    /// Lower user metadata into attributes. Entries with an argument that
+    /// isn't a constant are skipped (AST lowering already filters most).
+    fn lower_metadata(&mut self, metadata: &[TypedMetadata]) -> Vec<HirAttribute> {
+        metadata
+            .iter()
+            .filter_map(|meta| {
+                let args = meta
+                    .params
+                    .iter()
+                    .map(|param| self.lower_metadata_arg(param))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(HirAttribute {
+                    name: meta.name,
+                    args,
+                })
+            })
+            .collect()
+    }
+
+    fn lower_metadata_arg(&mut self, expr: &TypedExpression) -> Option<HirAttributeArg> {
+        match &expr.kind {
+            TypedExpressionKind::Literal { value } => {
+                Some(HirAttributeArg::Literal(self.lower_literal(value)))
+            }
+            TypedExpressionKind::Null => Some(HirAttributeArg::Null),
+            TypedExpressionKind::UnaryOp {
+                operator: UnaryOperator::Neg,
+                operand,
+            } => match self.lower_metadata_arg(operand)? {
+                HirAttributeArg::Literal(HirLiteral::Int(i)) => {
+                    Some(HirAttributeArg::Literal(HirLiteral::Int(-i)))
+                }
+                HirAttributeArg::Literal(HirLiteral::Float(f)) => {
+                    Some(HirAttributeArg::Literal(HirLiteral::Float(-f)))
+                }
+                _ => None,
+            },
+            TypedExpressionKind::ArrayLiteral { elements } => elements
+                .iter()
+                .map(|e| self.lower_metadata_arg(e))
+                .collect::<Option<Vec<_>>>()
+                .map(HirAttributeArg::Array),
+            TypedExpressionKind::ObjectLiteral { fields } => fields
+                .iter()
+                .map(|f| Some((f.name, self.lower_metadata_arg(&f.value)?)))
+                .collect::<Option<Vec<_>>>()
+                .map(HirAttributeArg::Object),
+            _ => None,
+        }
     }
 
     /// Desugar array comprehension into a loop that builds an array
@@ -3461,6 +3569,48 @@ impl<'a> TastToHirContext<'a> {
             });
         }
 
+        // Contracts: `requires`/`ensures`(predicate, condition, line, column)
+        for contract in &metadata.contracts {
+            attrs.push(HirAttribute {
+                name: self.string_interner.intern(contract.kind.name()),
+                args: vec![
+                    HirAttributeArg::Literal(HirLiteral::Int(contract.predicate.as_raw() as i64)),
+                    HirAttributeArg::Literal(HirLiteral::String(
+                        self.string_interner.intern(&contract.condition),
+                    )),
+                    HirAttributeArg::Literal(HirLiteral::Int(contract.source_location.line as i64)),
+                    HirAttributeArg::Literal(HirLiteral::Int(
+                        contract.source_location.column as i64,
+                    )),
+                ],
+            });
+        }
+
+        attrs
+    }
+
+    /// Compiler hints written as user metadata: `@:cold`, `@:noThrow` and
+    /// `@:targetFeature("avx2", ...)`, named without the leading colon
+    fn lower_function_hints(&mut self, metadata: &[TypedMetadata]) -> Vec<HirAttribute> {
+        let mut attrs = Vec::new();
+        for meta in metadata {
+            let Some(name) = self.string_interner.get(meta.name) else {
+                continue;
+            };
+            let name = name.trim_start_matches(':').to_string();
+            if name != "cold" && name != "noThrow" && name != "targetFeature" {
+                continue;
+            }
+            let args = meta
+                .params
+                .iter()
+                .filter_map(|param| self.lower_metadata_arg(param))
+                .collect();
+            attrs.push(HirAttribute {
+                name: self.string_interner.intern(&name),
+                args,
+            });
+        }
         attrs
     }
 
@@ -4381,10 +4531,18 @@ impl<'a> TastToHirContext<'a> {
             self.collect_local_defs_stmt(stmt, &mut locally_defined);
         }
 
-        // Free variables are those referenced but not locally defined
+        // Free variables are those referenced but not locally defined. Statics
+        // (including function-local `static var`s) live in shared storage and
+        // are never copied into the environment.
         let captures: Vec<_> = referenced_vars
             .into_iter()
             .filter(|(sym, _)| !locally_defined.contains(sym))
+            .filter(|(sym, _)| {
+                !self
+                    .symbol_table
+                    .get_symbol(*sym)
+                    .is_some_and(|s| s.kind == crate::tast::SymbolKind::Variable && s.is_static())
+            })
             .map(|(symbol, ty)| HirCapture {
                 symbol,
                 mode: HirCaptureMode::ByValue, // Default to by-value capture
@@ -4646,6 +4804,21 @@ impl<'a> TastToHirContext<'a> {
     }
 }
 
+/// Condition of the first `while` loop in a lowered loop expression
+fn loop_condition(expr: &mut HirExpr) -> Option<&mut HirExpr> {
+    let HirExprKind::Block(block) = &mut expr.kind else {
+        return None;
+    };
+    block
+        .statements
+        .iter_mut()
+        .find_map(|statement| match statement {
+            HirStatement::While { condition, .. } => Some(condition),
+            HirStatement::Expr(expr) => loop_condition(expr),
+            _ => None,
+        })
+}
+
 /// Public entry point for TAST to HIR lowering
 pub fn lower_tast_to_hir(
     file: &TypedFile,
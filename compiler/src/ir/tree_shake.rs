@@ -30,15 +30,31 @@ pub fn tree_shake_bundle(
     modules: &mut Vec<IrModule>,
     entry_module: &str,
     entry_function: &str,
+) -> TreeShakeStats {
+    tree_shake_bundle_roots(
+        modules,
+        &[(entry_module.to_string(), entry_function.to_string())],
+    )
+}
+
+/// Tree-shake a set of modules, keeping what's reachable from any of
+/// `roots` (`(module, function)` pairs), e.g. every entry point of a
+/// multi-entry bundle.
+pub fn tree_shake_bundle_roots(
+    modules: &mut Vec<IrModule>,
+    roots: &[(String, String)],
 ) -> TreeShakeStats {
     let mut stats = TreeShakeStats::default();
 
-    // Phase 1: Find entry function
-    let entry = find_entry(modules, entry_module, entry_function);
-    let Some((entry_mod_idx, entry_func_id)) = entry else {
+    // Phase 1: Find entry functions
+    let entries: Vec<(usize, IrFunctionId)> = roots
+        .iter()
+        .filter_map(|(module, function)| find_entry(modules, module, function))
+        .collect();
+    if entries.is_empty() {
         // Can't find entry — don't strip anything
         return stats;
-    };
+    }
 
     // Phase 2: Build reachable sets per module
     // Each module has its own function ID space, so we track (module_index, func_id)
@@ -49,8 +65,8 @@ pub fn tree_shake_bundle(
     // Worklist: (module_index, func_id) pairs to process
     let mut worklist: Vec<(usize, IrFunctionId)> = Vec::new();
 
-    // Seed with entry function
-    worklist.push((entry_mod_idx, entry_func_id));
+    // Seed with entry functions
+    worklist.extend(entries);
 
     // Phase 3: Walk call graph
     while let Some((mod_idx, func_id)) = worklist.pop() {
@@ -128,6 +128,9 @@ pub enum ValidationErrorKind {
     /// Phi node inconsistency
     InvalidPhiNode { block: IrBlockId, reason: String },
 
+    /// Call to a function that is neither defined nor declared in the module
+    UnknownFunction { function: IrFunctionId },
+
     /// Function signature mismatch
     SignatureMismatch {
         function: IrFunctionId,
@@ -247,8 +250,11 @@ pub fn validate_module(module: &IrModule) -> Result<(), Vec<ValidationError>> {
     let mut ctx = ValidationContext::new();
     ctx.module = module as *const IrModule;
 
-    // Validate all functions
+    // Validate all functions (extern declarations have no body to check)
     for (&func_id, function) in &module.functions {
+        if function.cfg.blocks.is_empty() {
+            continue;
+        }
         ctx.current_function = Some(func_id);
         validate_function(&mut ctx, function);
     }
@@ -541,6 +547,12 @@ fn validate_instruction(ctx: &mut ValidationContext, inst: &IrInstruction) {
             // For now, just validate that arguments are valid registers
             // TODO: Validate ownership modes match function signature
             // TODO: Validate type_args match function's type parameters
+            let module = ctx.module();
+            if !module.functions.contains_key(func_id)
+                && !module.extern_functions.contains_key(func_id)
+            {
+                ctx.add_error(ValidationErrorKind::UnknownFunction { function: *func_id });
+            }
             for &arg in args {
                 ctx.use_register(arg);
             }
@@ -1032,4 +1044,29 @@ mod tests {
             // Should have type mismatch error
         }
     }
+
+    #[test]
+    fn test_validate_call_targets() {
+        use crate::ir::mir_builder::MirBuilder;
+
+        let mut builder = MirBuilder::new("test");
+        let callee = builder.begin_function("callee").build();
+        builder.mark_as_extern(callee);
+        let main = builder.begin_function("main").build();
+        builder.set_current_function(main);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+        builder.call(callee, vec![]);
+        builder.ret(None);
+        let mut module = builder.finish();
+
+        // Extern declarations have no body and are not validated themselves
+        assert!(validate_module(&module).is_ok());
+
+        module.functions.remove(&callee);
+        let errors = validate_module(&module).unwrap_err();
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e.kind, ValidationErrorKind::UnknownFunction { function } if function == callee)));
+    }
 }
@@ -98,6 +98,7 @@ pub mod compilation;
 pub mod compiler_plugin; // Compiler-level plugin system for stdlib method mappings
 pub mod dependency_graph;
 pub mod error_codes;
+pub mod frontend; // MIR entry point for non-Haxe frontends
 pub mod hxml;
 pub mod ir;
 pub mod logging;
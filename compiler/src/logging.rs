@@ -45,6 +45,17 @@
 //! RUST_LOG=compiler::ir::hir_to_mir=debug ./rayzor compile main.hx
 //! RUST_LOG=compiler::codegen=trace ./rayzor compile main.hx
 //! ```
+//!
+//! The `rayzor` CLI reads `RAYZOR_LOG` (falling back to `RUST_LOG`), which
+//! also accepts short module names for the main compiler stages:
+//!
+//! ```bash
+//! RAYZOR_LOG=hir_to_mir=debug rayzor run main.hx
+//! RAYZOR_LOG=info,cranelift=trace rayzor run main.hx
+//! ```
+//!
+//! `tracing` events emitted by the codegen backends are forwarded to the same
+//! logger, so a single filter controls both.
 
 use env_logger::Builder;
 use log::LevelFilter;
@@ -92,6 +103,101 @@ pub fn init_from_env() {
     });
 }
 
+/// Environment variable read by the `rayzor` CLI for log filtering.
+pub const RAYZOR_LOG_ENV: &str = "RAYZOR_LOG";
+
+/// Short module names accepted in `RAYZOR_LOG` directives, mapped to their
+/// full module paths.
+pub const MODULE_ALIASES: &[(&str, &str)] = &[
+    ("compilation", "compiler::compilation"),
+    ("pipeline", "compiler::pipeline"),
+    ("ast_lowering", "compiler::tast::ast_lowering"),
+    ("type_checking", "compiler::tast::type_checking_pipeline"),
+    ("stdlib_loader", "compiler::tast::stdlib_loader"),
+    ("tast_to_hir", "compiler::ir::tast_to_hir"),
+    ("hir_to_mir", "compiler::ir::hir_to_mir"),
+    ("optimization", "compiler::ir::optimization"),
+    (
+        "ownership",
+        "compiler::semantic_graph::analysis::ownership_analyzer",
+    ),
+    ("cranelift", "compiler::codegen::cranelift_backend"),
+    ("llvm", "compiler::codegen::llvm_jit_backend"),
+    ("llvm_aot", "compiler::codegen::llvm_aot_backend"),
+    ("interpreter", "compiler::codegen::mir_interpreter"),
+    ("tiered", "compiler::codegen::tiered_backend"),
+    ("codegen", "compiler::codegen"),
+    ("ir", "compiler::ir"),
+    ("tast", "compiler::tast"),
+    ("runtime", "rayzor_runtime"),
+];
+
+/// Expand short module names in a filter spec (`hir_to_mir=debug,warn`)
+/// into full module paths (`compiler::ir::hir_to_mir=debug,warn`).
+///
+/// Bare levels, full module paths and unknown names are passed through unchanged.
+pub fn expand_filter_directives(spec: &str) -> String {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .map(|directive| {
+            let (name, level) = match directive.split_once('=') {
+                Some((name, level)) => (name, Some(level)),
+                None => (directive, None),
+            };
+            let name = MODULE_ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == name)
+                .map(|(_, path)| *path)
+                .unwrap_or(name);
+            match level {
+                Some(level) => format!("{}={}", name, level),
+                None => name.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Initialize logging for the `rayzor` CLI.
+///
+/// Filters come from `RAYZOR_LOG` (or `RUST_LOG`), with short module names
+/// expanded via [`expand_filter_directives`]. With `verbose`, the CLI's own
+/// progress messages (`info!` from the `rayzor` target) are shown as plain
+/// lines; everything else keeps the `[LEVEL] target - message` format.
+///
+/// This only initializes once; subsequent calls are no-ops.
+pub fn init_cli(verbose: bool) {
+    INIT.call_once(|| {
+        let spec = std::env::var(RAYZOR_LOG_ENV)
+            .or_else(|_| std::env::var("RUST_LOG"))
+            .unwrap_or_default();
+
+        let mut builder = Builder::new();
+        builder.filter_level(LevelFilter::Warn);
+        if verbose {
+            builder.filter_module("rayzor", LevelFilter::Info);
+        }
+        builder.parse_filters(&expand_filter_directives(&spec));
+
+        builder
+            .format(|buf, record| {
+                if record.level() == log::Level::Info && record.target() == "rayzor" {
+                    writeln!(buf, "{}", record.args())
+                } else {
+                    writeln!(
+                        buf,
+                        "[{:5}] {} - {}",
+                        record.level(),
+                        record.target(),
+                        record.args()
+                    )
+                }
+            })
+            .init();
+    });
+}
+
 /// Initialize logging for tests.
 ///
 /// Uses test-friendly output format and suppresses most output unless
@@ -135,6 +241,23 @@ mod tests {
         init_test();
     }
 
+    #[test]
+    fn test_expand_filter_directives() {
+        assert_eq!(
+            expand_filter_directives("hir_to_mir=debug"),
+            "compiler::ir::hir_to_mir=debug"
+        );
+        assert_eq!(
+            expand_filter_directives("info, cranelift=trace"),
+            "info,compiler::codegen::cranelift_backend=trace"
+        );
+        assert_eq!(
+            expand_filter_directives("compiler::ir::inlining=debug,unknown_mod=warn"),
+            "compiler::ir::inlining=debug,unknown_mod=warn"
+        );
+        assert_eq!(expand_filter_directives(""), "");
+    }
+
     #[test]
     fn test_log_levels() {
         init_test();
@@ -348,6 +348,7 @@ impl MacroDiagnostic {
             location: self.location,
             category: WarningCategory::Correctness,
             suppressible: true,
+            suggestions: Vec::new(),
         }
     }
 
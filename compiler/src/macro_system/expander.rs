@@ -377,6 +377,7 @@ impl MacroExpander {
             | ExprKind::Throw(_)
             | ExprKind::Var { .. }
             | ExprKind::Final { .. }
+            | ExprKind::StaticVar(_)
             | ExprKind::Assign { .. }
             | ExprKind::Field { .. }
             | ExprKind::Index { .. }
@@ -493,6 +494,12 @@ impl MacroExpander {
                 ExprKind::Paren(Box::new(expanded))
             }
 
+            ExprKind::StaticVar(decl) => {
+                let (expanded, c) = self.walk_expr(*decl)?;
+                changed |= c;
+                ExprKind::StaticVar(Box::new(expanded))
+            }
+
             ExprKind::Tuple(elements) => {
                 let mut new_elements = Vec::new();
                 for elem in elements {
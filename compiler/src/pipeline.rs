@@ -29,6 +29,8 @@ use crate::tast::{
     string_intern::{InternedString, StringInterner},
     SourceLocation, SymbolId, SymbolTable, TypeId, TypeTable,
 };
+use crate::tools::ownership_report::OwnershipReport;
+use crate::tools::symbol_index::SymbolIndex;
 
 // Use the parser's public interface
 use parser::{haxe_ast::HaxeFile, parse_haxe_file_with_diagnostics, ParseResult};
@@ -112,6 +114,16 @@ pub struct PipelineConfig {
 
     /// Enable macro expansion between parsing and TAST lowering
     pub enable_macro_expansion: bool,
+
+    /// Export the inferred ownership of each file (`rayzor check --ownership-report`)
+    pub emit_ownership_report: bool,
+
+    /// Index the declarations and references of each file (`rayzor lsp`)
+    pub emit_symbol_index: bool,
+
+    /// Warning categories left out of the result, e.g. `Deprecated`; only
+    /// suppressible warnings are dropped
+    pub allowed_warnings: Vec<WarningCategory>,
 }
 
 /// Target execution modes for the hybrid VM/compiler system
@@ -241,6 +253,12 @@ pub struct CompilationResult {
     /// Semantic analysis results
     pub semantic_graphs: Vec<Arc<SemanticGraphs>>,
 
+    /// Ownership reports, when `emit_ownership_report` is set
+    pub ownership_reports: Vec<OwnershipReport>,
+
+    /// Symbol indexes, when `emit_symbol_index` is set
+    pub symbol_indexes: Vec<SymbolIndex>,
+
     /// Compilation errors encountered
     pub errors: Vec<CompilationError>,
 
@@ -357,6 +375,9 @@ pub struct CompilationWarning {
 
     /// Whether this warning can be suppressed
     pub suppressible: bool,
+
+    /// Fixes to offer, e.g. renaming a deprecated name to its replacement
+    pub suggestions: Vec<diagnostics::Suggestion>,
 }
 
 impl CompilationWarning {
@@ -389,7 +410,7 @@ impl CompilationWarning {
             message: self.message.clone(),
             span: span.clone(),
             labels: vec![Label::primary(span, self.message.clone())],
-            suggestions: vec![],
+            suggestions: self.suggestions.clone(),
             notes: vec![],
             help: vec![],
         }
@@ -432,6 +453,9 @@ pub enum ErrorCategory {
     /// Macro expansion error
     MacroExpansionError,
 
+    /// Compiler plugin transform hook failure
+    PluginError,
+
     /// Internal compiler error
     InternalError,
 }
@@ -448,6 +472,7 @@ impl ErrorCategory {
     /// - E0500-E0599: HIR errors
     /// - E0600-E0699: Semantic analysis errors
     /// - E0700-E0799: Macro expansion errors
+    /// - E0800-E0899: Compiler plugin errors
     /// - E9999: Internal compiler errors
     pub fn error_code(&self) -> &'static str {
         match self {
@@ -478,6 +503,9 @@ impl ErrorCategory {
             // Macro expansion errors: E0700-E0799
             ErrorCategory::MacroExpansionError => "E0700",
 
+            // Compiler plugin errors: E0800-E0899
+            ErrorCategory::PluginError => "E0800",
+
             // Internal compiler errors
             ErrorCategory::InternalError => "E9999",
         }
@@ -526,6 +554,9 @@ impl Default for PipelineConfig {
             enable_enhanced_flow_analysis: true,
             enable_memory_safety_analysis: true,
             enable_macro_expansion: true,
+            emit_ownership_report: false,
+            emit_symbol_index: false,
+            allowed_warnings: Vec::new(),
         }
     }
 }
@@ -554,6 +585,9 @@ impl PipelineConfig {
             enable_enhanced_flow_analysis: false,
             enable_memory_safety_analysis: true,
             enable_macro_expansion: true,
+            emit_ownership_report: false,
+            emit_symbol_index: false,
+            allowed_warnings: Vec::new(),
         }
     }
 
@@ -580,6 +614,9 @@ impl PipelineConfig {
             enable_enhanced_flow_analysis: true,
             enable_memory_safety_analysis: true,
             enable_macro_expansion: true,
+            emit_ownership_report: false,
+            emit_symbol_index: false,
+            allowed_warnings: Vec::new(),
         }
     }
 
@@ -606,6 +643,9 @@ impl PipelineConfig {
             enable_enhanced_flow_analysis: true,
             enable_memory_safety_analysis: true,
             enable_macro_expansion: true,
+            emit_ownership_report: false,
+            emit_symbol_index: false,
+            allowed_warnings: Vec::new(),
         }
     }
 
@@ -645,6 +685,8 @@ impl HaxeCompilationPipeline {
             hir_modules: Vec::new(),
             mir_modules: Vec::new(),
             semantic_graphs: Vec::new(),
+            ownership_reports: Vec::new(),
+            symbol_indexes: Vec::new(),
             errors: Vec::new(),
             warnings: Vec::new(),
             stats: PipelineStats::default(),
@@ -683,11 +725,27 @@ impl HaxeCompilationPipeline {
                 // Stage 2: Lower AST to TAST
                 let lowering_start = std::time::Instant::now();
                 match self.lower_ast_to_tast(ast_file, file_path.as_ref(), source, source_map) {
-                    Ok((mut typed_file, lowering_errors, symbol_table, type_table, scope_tree)) => {
+                    Ok((
+                        mut typed_file,
+                        lowering_errors,
+                        type_warnings,
+                        symbol_table,
+                        type_table,
+                        scope_tree,
+                    )) => {
                         self.stats.lowering_time_us += lowering_start.elapsed().as_micros() as u64;
 
-                        // Add any type errors from lowering/type checking
+                        // Add any type errors and warnings from lowering/type checking
                         result.errors.extend(lowering_errors);
+                        result.warnings.extend(type_warnings);
+                        self.record_symbol_index(
+                            &mut result,
+                            &file_path.as_ref().to_string_lossy(),
+                            source,
+                            &typed_file,
+                            &symbol_table,
+                            &type_table.borrow(),
+                        );
 
                         // Stage 2b: Detect program-level safety mode (check Main class for @:safety)
                         let program_safety_mode = typed_file.detect_program_safety_mode();
@@ -734,6 +792,13 @@ impl HaxeCompilationPipeline {
                                 Ok(graphs) => {
                                     self.stats.semantic_analysis_time_us +=
                                         semantic_start.elapsed().as_micros() as u64;
+                                    self.record_ownership_report(
+                                        &mut result,
+                                        &file_path.as_ref().to_string_lossy(),
+                                        &typed_file,
+                                        &graphs,
+                                        &symbol_table,
+                                    );
 
                                     // Stage 4b: Enhanced flow analysis with CFG/DFG integration (if enabled)
                                     if self.config.enable_enhanced_flow_analysis {
@@ -811,6 +876,7 @@ impl HaxeCompilationPipeline {
                                                     location: err.location,
                                                     category: WarningCategory::Correctness,
                                                     suppressible: false, // Safety warnings in non-strict mode are important
+                                                    suggestions: Vec::new(),
                                                 });
                                             }
                                         } else {
@@ -939,6 +1005,7 @@ impl HaxeCompilationPipeline {
         self.stats.total_loc += source.lines().count();
         self.stats.total_time_us += start_time.elapsed().as_micros() as u64;
         self.stats.error_count += result.errors.len();
+        self.suppress_allowed_warnings(&mut result);
         self.stats.warning_count += result.warnings.len();
 
         result.stats = self.stats.clone();
@@ -955,6 +1022,8 @@ impl HaxeCompilationPipeline {
             hir_modules: Vec::new(),
             mir_modules: Vec::new(),
             semantic_graphs: Vec::new(),
+            ownership_reports: Vec::new(),
+            symbol_indexes: Vec::new(),
             errors: Vec::new(),
             warnings: Vec::new(),
             stats: PipelineStats::default(),
@@ -1026,6 +1095,7 @@ impl HaxeCompilationPipeline {
                                     location: err.location,
                                     category: WarningCategory::Correctness,
                                     suppressible: true,
+                                    suggestions: Vec::new(),
                                 });
                             }
                         }
@@ -1040,6 +1110,7 @@ impl HaxeCompilationPipeline {
                                 location: err.location,
                                 category: WarningCategory::Correctness,
                                 suppressible: true,
+                                suggestions: Vec::new(),
                             });
                         }
                         None
@@ -1067,6 +1138,7 @@ impl HaxeCompilationPipeline {
                                         location: err.location,
                                         category: WarningCategory::Correctness,
                                         suppressible: true,
+                                        suggestions: Vec::new(),
                                     });
                                 }
                             }
@@ -1107,6 +1179,7 @@ impl HaxeCompilationPipeline {
                                             location: err.location,
                                             category: WarningCategory::Correctness,
                                             suppressible: true,
+                                            suggestions: Vec::new(),
                                         });
                                     }
                                 }
@@ -1120,6 +1193,7 @@ impl HaxeCompilationPipeline {
                                 location: err.location,
                                 category: WarningCategory::Correctness,
                                 suppressible: true,
+                                suggestions: Vec::new(),
                             });
                         }
                     }
@@ -1134,11 +1208,20 @@ impl HaxeCompilationPipeline {
         self.stats.total_loc += files.iter().map(|(_, s)| s.lines().count()).sum::<usize>();
         self.stats.total_time_us += start_time.elapsed().as_micros() as u64;
         self.stats.error_count += result.errors.len();
+        self.suppress_allowed_warnings(&mut result);
         self.stats.warning_count += result.warnings.len();
         result.stats = self.stats.clone();
         result
     }
 
+    /// Drop the suppressible warnings of categories in `allowed_warnings`
+    fn suppress_allowed_warnings(&self, result: &mut CompilationResult) {
+        let allowed = &self.config.allowed_warnings;
+        result
+            .warnings
+            .retain(|w| !(w.suppressible && allowed.contains(&w.category)));
+    }
+
     /// Parse source code to AST and return both AST and SourceMap
     fn parse_source(
         &mut self,
@@ -1201,6 +1284,7 @@ impl HaxeCompilationPipeline {
         (
             TypedFile,
             Vec<CompilationError>,
+            Vec<CompilationWarning>,
             SymbolTable,
             Rc<RefCell<TypeTable>>,
             crate::tast::ScopeTree,
@@ -1358,6 +1442,26 @@ impl HaxeCompilationPipeline {
             }
         }
 
+        // Warnings (e.g. uses of @:deprecated symbols) keep their suggestions
+        let type_warnings: Vec<CompilationWarning> = diagnostics
+            .warnings()
+            .map(|diagnostic| CompilationWarning {
+                message: diagnostic.message.clone(),
+                location: SourceLocation {
+                    file_id: diagnostic.span.file_id.as_usize() as u32,
+                    line: diagnostic.span.start.line as u32,
+                    column: diagnostic.span.start.column as u32,
+                    byte_offset: diagnostic.span.start.byte_offset as u32,
+                },
+                category: match diagnostic.code.as_deref() {
+                    Some("W0004") => WarningCategory::Deprecated,
+                    _ => WarningCategory::Correctness,
+                },
+                suppressible: true,
+                suggestions: diagnostic.suggestions.clone(),
+            })
+            .collect();
+
         // Stage 2b: Basic flow-sensitive analysis (if enabled)
         if self.config.enable_flow_sensitive_analysis {
             let flow_start = std::time::Instant::now();
@@ -1370,6 +1474,7 @@ impl HaxeCompilationPipeline {
         Ok((
             typed_file,
             type_errors,
+            type_warnings,
             symbol_table,
             type_table,
             scope_tree,
@@ -1960,6 +2065,51 @@ impl HaxeCompilationPipeline {
         }
     }
 
+    /// Add the ownership report of `typed_file` (read from `file`) to
+    /// `result`, if enabled
+    fn record_ownership_report(
+        &self,
+        result: &mut CompilationResult,
+        file: &str,
+        typed_file: &TypedFile,
+        graphs: &SemanticGraphs,
+        symbol_table: &SymbolTable,
+    ) {
+        if !self.config.emit_ownership_report {
+            return;
+        }
+        result.ownership_reports.push(OwnershipReport::build(
+            file,
+            &graphs.ownership_graph,
+            symbol_table,
+            |name| typed_file.get_string(name),
+        ));
+    }
+
+    /// Add the symbol index of `typed_file` (read from `file`) to `result`,
+    /// if enabled
+    fn record_symbol_index(
+        &self,
+        result: &mut CompilationResult,
+        file: &str,
+        source: &str,
+        typed_file: &TypedFile,
+        symbol_table: &SymbolTable,
+        type_table: &TypeTable,
+    ) {
+        if !self.config.emit_symbol_index {
+            return;
+        }
+        result.symbol_indexes.push(SymbolIndex::build(
+            file,
+            source,
+            typed_file,
+            symbol_table,
+            type_table,
+            |name| typed_file.get_string(name),
+        ));
+    }
+
     /// Walk statements and populate ownership edges
     fn populate_ownership_from_statements(
         &self,
@@ -3163,6 +3313,58 @@ mod tests {
         assert!(result.stats.files_processed > 0);
     }
 
+    #[test]
+    fn test_deprecated_use_warning() {
+        let source = r#"
+            class Main {
+                @:deprecated("use add instead", "2.0")
+                static function plus(a:Int, b:Int):Int {
+                    return a + b;
+                }
+
+                static function add(a:Int, b:Int):Int {
+                    return a + b;
+                }
+
+                static function main() {
+                    var sum = Main.plus(1, 2);
+                }
+            }
+        "#;
+        let config = PipelineConfig {
+            enable_hir_lowering: false,
+            ..Default::default()
+        };
+
+        let result =
+            HaxeCompilationPipeline::with_config(config.clone()).compile_file("test.hx", source);
+        let warning = result
+            .warnings
+            .iter()
+            .find(|w| w.category == WarningCategory::Deprecated)
+            .expect("use of Main.plus should warn");
+        assert_eq!(
+            warning.message,
+            "`plus` is deprecated since 2.0: use add instead"
+        );
+        let suggestion = &warning.suggestions[0];
+        assert_eq!(suggestion.replacement, "add");
+        assert_eq!(
+            &source[suggestion.span.start.byte_offset..suggestion.span.end.byte_offset],
+            "plus"
+        );
+
+        let result = HaxeCompilationPipeline::with_config(PipelineConfig {
+            allowed_warnings: vec![WarningCategory::Deprecated],
+            ..config
+        })
+        .compile_file("test.hx", source);
+        assert!(result
+            .warnings
+            .iter()
+            .all(|w| w.category != WarningCategory::Deprecated));
+    }
+
     // #[test]
     // fn test_config_customization() {
     //     let config = PipelineConfig {
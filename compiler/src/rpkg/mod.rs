@@ -18,6 +18,7 @@
 
 pub mod install;
 pub mod pack;
+pub mod symbols;
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
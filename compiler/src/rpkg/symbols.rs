@@ -0,0 +1,282 @@
+//! Symbol information for rpkg method tables
+//!
+//! `rayzor preblade --rpkg` indexes packages into `.bsym` manifests so the
+//! `check` command and IDE tooling can resolve their classes without loading
+//! the native library. Bundled `.hx` externs are parsed like the stdlib; this
+//! module covers the method table: classes and methods that only exist as
+//! [`MethodDescEntry`] descriptors get signatures derived from their type
+//! tags, and descriptor methods missing from an extern are added to it.
+
+use super::MethodDescEntry;
+use crate::ir::blade::{BladeClassInfo, BladeMethodInfo, BladeModuleSymbols, BladeParamInfo};
+use rayzor_plugin::native_type;
+
+/// Haxe type shown for a native type tag
+fn tag_to_haxe(tag: u8) -> &'static str {
+    match tag {
+        native_type::VOID => "Void",
+        native_type::I64 => "Int",
+        native_type::F64 => "Float",
+        native_type::BOOL => "Bool",
+        _ => "Dynamic",
+    }
+}
+
+/// Symbol-level name of a class, as used in descriptors: the lowered
+/// `@:native` name (`rayzor::gpu::GPUCompute` -> `rayzor_gpu_GPUCompute`),
+/// or the qualified name with `.` replaced by `_`
+pub fn lowered_class_name(class: &BladeClassInfo) -> String {
+    match &class.native_name {
+        Some(native) => native.replace("::", "_"),
+        None => class
+            .package
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(class.name.as_str()))
+            .collect::<Vec<_>>()
+            .join("_"),
+    }
+}
+
+/// Split a descriptor class name into package and class name: leading
+/// lowercase segments are the package (`rayzor_gpu_GpuBuffer` ->
+/// `["rayzor", "gpu"]`, `GpuBuffer`)
+fn split_class_name(class_name: &str) -> (Vec<String>, String) {
+    let segments: Vec<&str> = class_name.split('_').collect();
+    let package_len = segments
+        .iter()
+        .take(segments.len().saturating_sub(1))
+        .take_while(|s| s.starts_with(|c: char| c.is_ascii_lowercase()))
+        .count();
+    let package = segments[..package_len]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    (package, segments[package_len..].join("_"))
+}
+
+fn descriptor_method(entry: &MethodDescEntry, package_name: &str) -> BladeMethodInfo {
+    // Instance methods list `self` first
+    let skip = usize::from(!entry.is_static);
+    let params = entry
+        .param_types
+        .iter()
+        .skip(skip)
+        .enumerate()
+        .map(|(i, &tag)| BladeParamInfo {
+            name: format!("arg{}", i),
+            param_type: tag_to_haxe(tag).to_string(),
+            has_default: false,
+            is_optional: false,
+        })
+        .collect();
+    BladeMethodInfo {
+        name: entry.method_name.clone(),
+        params,
+        return_type: tag_to_haxe(entry.return_type).to_string(),
+        is_public: true,
+        is_static: entry.is_static,
+        is_inline: false,
+        type_params: Vec::new(),
+        doc: Some(format!(
+            "Native method `{}` from package `{}`.",
+            entry.symbol_name, package_name
+        )),
+    }
+}
+
+/// Extern classes described by a method table, in first-seen order
+pub fn descriptor_classes(methods: &[MethodDescEntry], package_name: &str) -> Vec<BladeClassInfo> {
+    let mut classes: Vec<BladeClassInfo> = Vec::new();
+    for entry in methods {
+        let index = match classes
+            .iter()
+            .position(|c| c.native_name.as_deref() == Some(entry.class_name.as_str()))
+        {
+            Some(index) => index,
+            None => {
+                let (package, name) = split_class_name(&entry.class_name);
+                classes.push(BladeClassInfo {
+                    name,
+                    package,
+                    extends: None,
+                    implements: Vec::new(),
+                    type_params: Vec::new(),
+                    is_extern: true,
+                    is_abstract: false,
+                    is_final: false,
+                    fields: Vec::new(),
+                    methods: Vec::new(),
+                    static_fields: Vec::new(),
+                    static_methods: Vec::new(),
+                    constructor: None,
+                    native_name: Some(entry.class_name.clone()),
+                    doc: Some(format!("Native class from package `{}`.", package_name)),
+                });
+                classes.len() - 1
+            }
+        };
+        let method = descriptor_method(entry, package_name);
+        let class = &mut classes[index];
+        if method.is_static {
+            class.static_methods.push(method);
+        } else {
+            class.methods.push(method);
+        }
+    }
+    classes
+}
+
+/// Add the methods of a descriptor class to the class in `modules` with the
+/// same symbol-level name, keeping methods the class already declares.
+/// Returns the descriptor class back when no module declares it.
+pub fn merge_descriptor_class(
+    modules: &mut [BladeModuleSymbols],
+    class: BladeClassInfo,
+) -> Option<BladeClassInfo> {
+    let lowered = lowered_class_name(&class);
+    let Some(target) = modules
+        .iter_mut()
+        .flat_map(|m| m.types.classes.iter_mut())
+        .find(|c| lowered_class_name(c) == lowered)
+    else {
+        return Some(class);
+    };
+
+    for method in class.methods.into_iter().chain(class.static_methods) {
+        let declared = target
+            .methods
+            .iter()
+            .chain(&target.static_methods)
+            .any(|m| m.name == method.name);
+        if declared {
+            continue;
+        }
+        if method.is_static {
+            target.static_methods.push(method);
+        } else {
+            target.methods.push(method);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::blade::BladeTypeInfo;
+
+    fn entry(
+        class: &str,
+        method: &str,
+        is_static: bool,
+        params: &[u8],
+        ret: u8,
+    ) -> MethodDescEntry {
+        MethodDescEntry {
+            symbol_name: format!("{}_{}", class, method),
+            class_name: class.to_string(),
+            method_name: method.to_string(),
+            is_static,
+            param_count: params.len() as u8,
+            return_type: ret,
+            param_types: params.to_vec(),
+        }
+    }
+
+    fn gpu_table() -> Vec<MethodDescEntry> {
+        use native_type::*;
+        vec![
+            entry("rayzor_gpu_GPUCompute", "create", true, &[], PTR),
+            entry("rayzor_gpu_GPUCompute", "sum", true, &[PTR, PTR], F64),
+            entry(
+                "rayzor_gpu_GPUCompute",
+                "matmul",
+                false,
+                &[PTR, PTR, PTR, I64],
+                PTR,
+            ),
+            entry("rayzor_gpu_GpuBuffer", "numel", false, &[PTR], I64),
+        ]
+    }
+
+    #[test]
+    fn test_descriptor_classes() {
+        let classes = descriptor_classes(&gpu_table(), "rayzor-gpu");
+        assert_eq!(classes.len(), 2);
+
+        let gpu = &classes[0];
+        assert_eq!(gpu.name, "GPUCompute");
+        assert_eq!(gpu.package, vec!["rayzor", "gpu"]);
+        assert_eq!(gpu.native_name.as_deref(), Some("rayzor_gpu_GPUCompute"));
+        assert_eq!(gpu.static_methods.len(), 2);
+
+        // `self` is not a parameter of an instance method
+        let matmul = &gpu.methods[0];
+        let params: Vec<_> = matmul
+            .params
+            .iter()
+            .map(|p| p.param_type.as_str())
+            .collect();
+        assert_eq!(params, vec!["Dynamic", "Dynamic", "Int"]);
+        assert_eq!(matmul.return_type, "Dynamic");
+        assert!(matmul
+            .doc
+            .as_deref()
+            .unwrap()
+            .contains("rayzor_gpu_GPUCompute_matmul"));
+
+        assert_eq!(classes[1].name, "GpuBuffer");
+        assert_eq!(classes[1].methods[0].return_type, "Int");
+    }
+
+    #[test]
+    fn test_split_class_name() {
+        assert_eq!(split_class_name("MyClass"), (vec![], "MyClass".to_string()));
+        assert_eq!(split_class_name("lower"), (vec![], "lower".to_string()));
+        assert_eq!(
+            split_class_name("pkg_Outer_Inner"),
+            (vec!["pkg".to_string()], "Outer_Inner".to_string())
+        );
+    }
+
+    #[test]
+    fn test_merge_into_extern() {
+        let mut classes = descriptor_classes(&gpu_table(), "rayzor-gpu");
+        let mut declared = classes.remove(0);
+        declared.native_name = Some("rayzor::gpu::GPUCompute".to_string());
+        declared.static_methods.truncate(1);
+        declared.methods.clear();
+        declared.static_methods[0].doc = Some("Create a GPU context.".to_string());
+
+        let mut modules = vec![BladeModuleSymbols {
+            name: "rayzor.gpu.GPUCompute".to_string(),
+            source_path: "GPUCompute.hx".to_string(),
+            source_hash: 0,
+            types: BladeTypeInfo {
+                classes: vec![declared],
+                ..Default::default()
+            },
+            dependencies: Vec::new(),
+        }];
+
+        let mut descriptors = descriptor_classes(&gpu_table(), "rayzor-gpu").into_iter();
+        assert!(merge_descriptor_class(&mut modules, descriptors.next().unwrap()).is_none());
+        let merged = &modules[0].types.classes[0];
+        let names: Vec<_> = merged
+            .static_methods
+            .iter()
+            .chain(&merged.methods)
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["create", "sum", "matmul"]);
+        // The extern's own declaration wins
+        assert_eq!(
+            merged.static_methods[0].doc.as_deref(),
+            Some("Create a GPU context.")
+        );
+
+        let buffer = descriptors.next().unwrap();
+        assert!(merge_descriptor_class(&mut modules, buffer).is_some());
+    }
+}
@@ -6,7 +6,7 @@
 /// convention where the runtime function writes to a provided HaxeArray struct.
 /// The MIR wrappers handle allocation and forwarding.
 use crate::ir::mir_builder::MirBuilder;
-use crate::ir::{CallingConvention, IrType};
+use crate::ir::{CallingConvention, IrId, IrType};
 
 /// HaxeArray runtime structure size in bytes
 /// struct HaxeArray { ptr: *mut u8, len: usize, cap: usize, elem_size: usize }
@@ -23,6 +23,7 @@ pub fn build_array_type(builder: &mut MirBuilder) {
     build_array_pop(builder);
     build_array_length(builder);
     build_array_slice(builder);
+    build_array_slice_from(builder);
     build_array_join(builder);
     build_array_index_of(builder);
     build_array_last_index_of(builder);
@@ -72,7 +73,7 @@ fn declare_array_externs(builder: &mut MirBuilder) {
         .build();
     builder.mark_as_extern(func_id);
 
-    // haxe_array_slice(out: *mut HaxeArray, arr: *const HaxeArray, start: usize, end: usize)
+    // haxe_array_slice(out: *mut HaxeArray, arr: *const HaxeArray, start: i64, end: i64)
     let func_id = builder
         .begin_function("haxe_array_slice")
         .param("out", ptr_void.clone())
@@ -248,36 +249,39 @@ fn declare_array_externs(builder: &mut MirBuilder) {
         .build();
     builder.mark_as_extern(func_id);
 
-    // haxe_array_map(out: *mut HaxeArray, arr: *const HaxeArray, fn_ptr: usize, env_ptr: *mut u8)
+    // haxe_array_map(out: *mut HaxeArray, arr: *const HaxeArray, fn_ptr: usize, env_ptr: *mut u8, kind: i32)
     let func_id = builder
         .begin_function("haxe_array_map")
         .param("out", ptr_void.clone())
         .param("arr", ptr_void.clone())
         .param("fn_ptr", i64_ty.clone())
         .param("env_ptr", ptr_void.clone())
+        .param("kind", IrType::I32)
         .returns(void_ty.clone())
         .calling_convention(CallingConvention::C)
         .build();
     builder.mark_as_extern(func_id);
 
-    // haxe_array_filter(out: *mut HaxeArray, arr: *const HaxeArray, fn_ptr: usize, env_ptr: *mut u8)
+    // haxe_array_filter(out: *mut HaxeArray, arr: *const HaxeArray, fn_ptr: usize, env_ptr: *mut u8, kind: i32)
     let func_id = builder
         .begin_function("haxe_array_filter")
         .param("out", ptr_void.clone())
         .param("arr", ptr_void.clone())
         .param("fn_ptr", i64_ty.clone())
         .param("env_ptr", ptr_void.clone())
+        .param("kind", IrType::I32)
         .returns(void_ty.clone())
         .calling_convention(CallingConvention::C)
         .build();
     builder.mark_as_extern(func_id);
 
-    // haxe_array_sort(arr: *mut HaxeArray, fn_ptr: usize, env_ptr: *mut u8)
+    // haxe_array_sort(arr: *mut HaxeArray, fn_ptr: usize, env_ptr: *mut u8, kind: i32)
     let func_id = builder
         .begin_function("haxe_array_sort")
         .param("arr", ptr_void.clone())
         .param("fn_ptr", i64_ty.clone())
         .param("env_ptr", ptr_void.clone())
+        .param("kind", IrType::I32)
         .returns(void_ty.clone())
         .calling_convention(CallingConvention::C)
         .build();
@@ -441,6 +445,43 @@ fn build_array_slice(builder: &mut MirBuilder) {
     builder.ret(Some(out_ptr));
 }
 
+/// Build: fn array_slice_from(arr: Ptr(Void), start: i64) -> Ptr(Void)
+/// Wrapper for haxe_array_slice that defaults end to i64::MAX (slice to the end)
+fn build_array_slice_from(builder: &mut MirBuilder) {
+    let ptr_void = IrType::Ptr(Box::new(IrType::Void));
+
+    let func_id = builder
+        .begin_function("array_slice_from")
+        .param("arr", ptr_void.clone())
+        .param("start", IrType::I64)
+        .returns(ptr_void.clone())
+        .calling_convention(CallingConvention::C)
+        .build();
+
+    builder.set_current_function(func_id);
+    let entry = builder.create_block("entry");
+    builder.set_insert_point(entry);
+
+    let arr = builder.get_param(0);
+    let start = builder.get_param(1);
+    let end = builder.const_i64(i64::MAX);
+
+    let malloc_func = builder
+        .get_function_by_name("malloc")
+        .expect("malloc extern not found");
+    let size = builder.const_i64(HAXE_ARRAY_STRUCT_SIZE as i64);
+    let out_ptr = builder
+        .call(malloc_func, vec![size])
+        .expect("malloc should return a pointer");
+
+    let slice_func = builder
+        .get_function_by_name("haxe_array_slice")
+        .expect("haxe_array_slice extern not found");
+    builder.call(slice_func, vec![out_ptr, arr, start, end]);
+
+    builder.ret(Some(out_ptr));
+}
+
 /// Build: fn array_join(arr: Ptr(Void), sep: Ptr(Void)) -> Ptr(Void)
 /// Wrapper for haxe_array_join that joins array elements with separator
 fn build_array_join(builder: &mut MirBuilder) {
@@ -748,29 +789,25 @@ fn build_array_to_string(builder: &mut MirBuilder) {
 }
 
 /// Build: fn array_map(arr: Any, closure: Any) -> Ptr(Void)
+/// and fn array_map_typed(arr: Any, closure: Any, kind: i32) -> Ptr(Void)
 /// Applies callback to each element, returns new array.
-/// Closure struct layout: { fn_ptr: i64, env_ptr: i64 }
 fn build_array_map(builder: &mut MirBuilder) {
-    let ptr_void = IrType::Ptr(Box::new(IrType::Void));
-    let ptr_u8 = IrType::Ptr(Box::new(IrType::U8));
-
-    let func_id = builder
-        .begin_function("array_map")
-        .param("arr", IrType::Any)
-        .param("closure", IrType::Any)
-        .returns(ptr_void.clone())
-        .calling_convention(CallingConvention::C)
-        .build();
-
-    builder.set_current_function(func_id);
-    let entry = builder.create_block("entry");
-    builder.set_insert_point(entry);
+    build_array_callback_transform(builder, "array_map", "haxe_array_map", false);
+    build_array_callback_transform(builder, "array_map_typed", "haxe_array_map", true);
+}
 
-    let arr = builder.get_param(0);
-    let closure = builder.get_param(1);
+/// Build: fn array_filter(arr: Any, closure: Any) -> Ptr(Void)
+/// and fn array_filter_typed(arr: Any, closure: Any, kind: i32) -> Ptr(Void)
+/// Keeps elements where callback returns true, returns new array.
+fn build_array_filter(builder: &mut MirBuilder) {
+    build_array_callback_transform(builder, "array_filter", "haxe_array_filter", false);
+    build_array_callback_transform(builder, "array_filter_typed", "haxe_array_filter", true);
+}
 
-    // Cast arr from Any to Ptr for extern call
-    let arr_ptr = builder.cast(arr, IrType::Any, ptr_void.clone());
+/// Load (fn_ptr, env_ptr) from a closure struct { fn_ptr: i64, env_ptr: i64 }
+fn load_closure_parts(builder: &mut MirBuilder, closure: IrId) -> (IrId, IrId) {
+    let ptr_void = IrType::Ptr(Box::new(IrType::Void));
+    let ptr_u8 = IrType::Ptr(Box::new(IrType::U8));
 
     // Cast closure from Any to Ptr to load fields
     let closure_ptr = builder.cast(closure, IrType::Any, ptr_u8.clone());
@@ -780,38 +817,34 @@ fn build_array_map(builder: &mut MirBuilder) {
 
     // Load env_ptr from closure[8]
     let offset_8 = builder.const_i64(8);
-    let env_slot = builder.ptr_add(closure_ptr, offset_8, ptr_u8.clone());
+    let env_slot = builder.ptr_add(closure_ptr, offset_8, ptr_u8);
     let env_ptr = builder.load(env_slot, IrType::I64);
-    let env_ptr_cast = builder.cast(env_ptr, IrType::I64, ptr_void.clone());
+    let env_ptr_cast = builder.cast(env_ptr, IrType::I64, ptr_void);
 
-    // Allocate out array struct (32 bytes)
-    let malloc_func = builder
-        .get_function_by_name("malloc")
-        .expect("malloc extern not found");
-    let size = builder.const_i64(HAXE_ARRAY_STRUCT_SIZE as i64);
-    let out_ptr = builder
-        .call(malloc_func, vec![size])
-        .expect("malloc should return a pointer");
-
-    // Call haxe_array_map(out, arr, fn_ptr, env_ptr)
-    let map_func = builder
-        .get_function_by_name("haxe_array_map")
-        .expect("haxe_array_map extern not found");
-    builder.call(map_func, vec![out_ptr, arr_ptr, fn_ptr, env_ptr_cast]);
-
-    builder.ret(Some(out_ptr));
+    (fn_ptr, env_ptr_cast)
 }
 
-/// Build: fn array_filter(arr: Any, closure: Any) -> Ptr(Void)
-/// Keeps elements where callback returns true, returns new array.
-fn build_array_filter(builder: &mut MirBuilder) {
+/// Shared body of the map/filter wrappers: allocate the out array and call
+/// `runtime(out, arr, fn_ptr, env_ptr, kind)`.
+///
+/// The untyped wrapper passes kind 0 (raw i64 callback); the `_typed` wrapper
+/// takes the callback ABI flags computed by the compiler as a third parameter.
+fn build_array_callback_transform(
+    builder: &mut MirBuilder,
+    name: &str,
+    runtime: &str,
+    typed: bool,
+) {
     let ptr_void = IrType::Ptr(Box::new(IrType::Void));
-    let ptr_u8 = IrType::Ptr(Box::new(IrType::U8));
 
-    let func_id = builder
-        .begin_function("array_filter")
+    let mut func = builder
+        .begin_function(name)
         .param("arr", IrType::Any)
-        .param("closure", IrType::Any)
+        .param("closure", IrType::Any);
+    if typed {
+        func = func.param("kind", IrType::I32);
+    }
+    let func_id = func
         .returns(ptr_void.clone())
         .calling_convention(CallingConvention::C)
         .build();
@@ -822,17 +855,17 @@ fn build_array_filter(builder: &mut MirBuilder) {
 
     let arr = builder.get_param(0);
     let closure = builder.get_param(1);
+    let kind = if typed {
+        builder.get_param(2)
+    } else {
+        builder.const_i32(0)
+    };
 
+    // Cast arr from Any to Ptr for extern call
     let arr_ptr = builder.cast(arr, IrType::Any, ptr_void.clone());
-    let closure_ptr = builder.cast(closure, IrType::Any, ptr_u8.clone());
-
-    let fn_ptr = builder.load(closure_ptr, IrType::I64);
-
-    let offset_8 = builder.const_i64(8);
-    let env_slot = builder.ptr_add(closure_ptr, offset_8, ptr_u8.clone());
-    let env_ptr = builder.load(env_slot, IrType::I64);
-    let env_ptr_cast = builder.cast(env_ptr, IrType::I64, ptr_void.clone());
+    let (fn_ptr, env_ptr) = load_closure_parts(builder, closure);
 
+    // Allocate out array struct (32 bytes)
     let malloc_func = builder
         .get_function_by_name("malloc")
         .expect("malloc extern not found");
@@ -841,49 +874,54 @@ fn build_array_filter(builder: &mut MirBuilder) {
         .call(malloc_func, vec![size])
         .expect("malloc should return a pointer");
 
-    let filter_func = builder
-        .get_function_by_name("haxe_array_filter")
-        .expect("haxe_array_filter extern not found");
-    builder.call(filter_func, vec![out_ptr, arr_ptr, fn_ptr, env_ptr_cast]);
+    // Call runtime(out, arr, fn_ptr, env_ptr, kind)
+    let runtime_func = builder
+        .get_function_by_name(runtime)
+        .unwrap_or_else(|| panic!("{} extern not found", runtime));
+    builder.call(runtime_func, vec![out_ptr, arr_ptr, fn_ptr, env_ptr, kind]);
 
     builder.ret(Some(out_ptr));
 }
 
 /// Build: fn array_sort(arr: Any, closure: Any) -> Void
+/// and fn array_sort_typed(arr: Any, closure: Any, kind: i32) -> Void
 /// Sorts array in-place using comparator callback.
 fn build_array_sort(builder: &mut MirBuilder) {
-    let ptr_void = IrType::Ptr(Box::new(IrType::Void));
-    let ptr_u8 = IrType::Ptr(Box::new(IrType::U8));
-
-    let func_id = builder
-        .begin_function("array_sort")
-        .param("arr", IrType::Any)
-        .param("closure", IrType::Any)
-        .returns(IrType::Void)
-        .calling_convention(CallingConvention::C)
-        .build();
-
-    builder.set_current_function(func_id);
-    let entry = builder.create_block("entry");
-    builder.set_insert_point(entry);
-
-    let arr = builder.get_param(0);
-    let closure = builder.get_param(1);
-
-    let arr_ptr = builder.cast(arr, IrType::Any, ptr_void.clone());
-    let closure_ptr = builder.cast(closure, IrType::Any, ptr_u8.clone());
-
-    let fn_ptr = builder.load(closure_ptr, IrType::I64);
-
-    let offset_8 = builder.const_i64(8);
-    let env_slot = builder.ptr_add(closure_ptr, offset_8, ptr_u8.clone());
-    let env_ptr = builder.load(env_slot, IrType::I64);
-    let env_ptr_cast = builder.cast(env_ptr, IrType::I64, ptr_void.clone());
-
-    let sort_func = builder
-        .get_function_by_name("haxe_array_sort")
-        .expect("haxe_array_sort extern not found");
-    builder.call(sort_func, vec![arr_ptr, fn_ptr, env_ptr_cast]);
-
-    builder.ret(None);
+    for (name, typed) in [("array_sort", false), ("array_sort_typed", true)] {
+        let ptr_void = IrType::Ptr(Box::new(IrType::Void));
+
+        let mut func = builder
+            .begin_function(name)
+            .param("arr", IrType::Any)
+            .param("closure", IrType::Any);
+        if typed {
+            func = func.param("kind", IrType::I32);
+        }
+        let func_id = func
+            .returns(IrType::Void)
+            .calling_convention(CallingConvention::C)
+            .build();
+
+        builder.set_current_function(func_id);
+        let entry = builder.create_block("entry");
+        builder.set_insert_point(entry);
+
+        let arr = builder.get_param(0);
+        let closure = builder.get_param(1);
+        let kind = if typed {
+            builder.get_param(2)
+        } else {
+            builder.const_i32(0)
+        };
+
+        let arr_ptr = builder.cast(arr, IrType::Any, ptr_void);
+        let (fn_ptr, env_ptr) = load_closure_parts(builder, closure);
+
+        let sort_func = builder
+            .get_function_by_name("haxe_array_sort")
+            .expect("haxe_array_sort extern not found");
+        builder.call(sort_func, vec![arr_ptr, fn_ptr, env_ptr, kind]);
+
+        builder.ret(None);
+    }
 }
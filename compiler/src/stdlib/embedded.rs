@@ -0,0 +1,142 @@
+//! Precompiled stdlib shipped inside the compiler binary
+//!
+//! `rayzor preblade` writes `stdlib.rzb` next to the `.bsym` manifest: a
+//! bundle holding the stdlib symbol manifest and the prebuilt `haxe` MIR
+//! module. `build.rs` embeds that file, so a fresh install registers stdlib
+//! symbols without parsing haxe-std sources and skips rebuilding the stdlib
+//! MIR on every compile.
+//!
+//! The bundle is only used when it was produced from the same stdlib
+//! sources as this compiler (`STDLIB_VERSION`); otherwise, or when built
+//! without one, everything falls back to source.
+//! `-D stdlib-src` forces the source path as well.
+
+use std::sync::{Arc, OnceLock};
+
+use log::debug;
+
+use crate::ir::blade::{load_bundle_from_bytes, BladeSymbolManifest, RayzorBundle};
+use crate::ir::IrModule;
+
+/// Version an embedded bundle must carry: crate version plus a fingerprint
+/// of the sources the stdlib is built from (see build.rs)
+pub const STDLIB_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    "+",
+    env!("RAYZOR_STDLIB_FINGERPRINT")
+);
+
+/// Define that disables the embedded stdlib (`-D stdlib-src`)
+pub const STDLIB_SRC_DEFINE: &str = "stdlib-src";
+
+static EMBEDDED_BUNDLE: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/stdlib.rzb"));
+
+/// Embedded stdlib, decoded on first use
+pub struct EmbeddedStdlib {
+    symbols: BladeSymbolManifest,
+    mir: Arc<IrModule>,
+}
+
+impl EmbeddedStdlib {
+    /// Stdlib symbol manifest (same content as `stdlib.bsym`)
+    pub fn symbols(&self) -> &BladeSymbolManifest {
+        &self.symbols
+    }
+
+    /// Prebuilt `haxe` MIR module (`build_stdlib()` output)
+    pub fn mir(&self) -> &Arc<IrModule> {
+        &self.mir
+    }
+}
+
+/// The embedded stdlib, or None if the binary was built without one or it
+/// does not match this compiler version
+pub fn embedded_stdlib() -> Option<&'static EmbeddedStdlib> {
+    static STDLIB: OnceLock<Option<EmbeddedStdlib>> = OnceLock::new();
+    STDLIB.get_or_init(|| decode(EMBEDDED_BUNDLE)).as_ref()
+}
+
+fn decode(bytes: &[u8]) -> Option<EmbeddedStdlib> {
+    if bytes.is_empty() {
+        return None;
+    }
+    let bundle = match load_bundle_from_bytes(bytes) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            debug!("[BLADE] Ignoring embedded stdlib bundle: {}", e);
+            return None;
+        }
+    };
+    into_stdlib(bundle)
+}
+
+fn into_stdlib(bundle: RayzorBundle) -> Option<EmbeddedStdlib> {
+    if bundle.build_info().compiler_version != STDLIB_VERSION {
+        debug!(
+            "[BLADE] Embedded stdlib built by {}, compiler is {}; using sources",
+            bundle.build_info().compiler_version,
+            STDLIB_VERSION
+        );
+        return None;
+    }
+    let symbols = bundle.symbols()?.clone();
+    let mir = bundle.get_module("haxe")?.clone();
+    Some(EmbeddedStdlib {
+        symbols,
+        mir: Arc::new(mir),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::blade::{load_symbol_manifest, save_bundle, save_symbol_manifest};
+    use crate::stdlib::build_stdlib;
+
+    #[test]
+    fn test_empty_bundle_is_ignored() {
+        assert!(decode(&[]).is_none());
+        assert!(decode(b"not a bundle").is_none());
+    }
+
+    fn bundle_bytes(bundle: &RayzorBundle, name: &str) -> Vec<u8> {
+        let path = std::env::temp_dir().join(name);
+        save_bundle(&path, bundle).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        bytes
+    }
+
+    fn stdlib_symbols() -> BladeSymbolManifest {
+        let path = std::env::temp_dir().join("rayzor_stdlib_test.bsym");
+        save_symbol_manifest(&path, Vec::new()).unwrap();
+        let manifest = load_symbol_manifest(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        manifest
+    }
+
+    #[test]
+    fn test_bundle_without_symbols_is_ignored() {
+        let bundle = RayzorBundle::new(vec![build_stdlib()], "haxe", "", None)
+            .with_compiler_version(STDLIB_VERSION);
+        assert!(decode(&bundle_bytes(&bundle, "rayzor_stdlib_nosym.rzb")).is_none());
+    }
+
+    #[test]
+    fn test_stale_bundle_is_ignored() {
+        let symbols = stdlib_symbols();
+        let bundle = RayzorBundle::new(vec![build_stdlib()], "haxe", "", Some(symbols))
+            .with_compiler_version("0.0.0+stale");
+        assert!(decode(&bundle_bytes(&bundle, "rayzor_stdlib_stale.rzb")).is_none());
+    }
+
+    #[test]
+    fn test_matching_bundle_is_loaded() {
+        let symbols = stdlib_symbols();
+        let bundle = RayzorBundle::new(vec![build_stdlib()], "haxe", "", Some(symbols))
+            .with_compiler_version(STDLIB_VERSION);
+        let stdlib = decode(&bundle_bytes(&bundle, "rayzor_stdlib_ok.rzb")).unwrap();
+        assert_eq!(stdlib.mir().name, "haxe");
+        assert!(!stdlib.mir().functions.is_empty());
+    }
+}
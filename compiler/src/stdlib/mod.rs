@@ -28,6 +28,7 @@
 //! - I/O operations (print, trace)
 
 pub mod array;
+pub mod embedded;
 pub mod ereg;
 pub mod memory;
 pub mod runtime_mapping;
@@ -213,6 +213,7 @@ impl StdlibMapping {
 
         mapping.register_string_methods();
         mapping.register_stringtools_methods();
+        mapping.register_collator_methods();
         mapping.register_array_methods();
         mapping.register_math_methods();
         mapping.register_sys_methods();
@@ -229,6 +230,9 @@ impl StdlibMapping {
         mapping.register_stringmap_methods();
         mapping.register_intmap_methods();
         mapping.register_objectmap_methods();
+        mapping.register_list_methods();
+        mapping.register_vector_methods();
+        mapping.register_lambda_methods();
         mapping.register_date_methods();
         mapping.register_bytes_methods();
         // sys.thread.* mappings (standard Haxe threading API)
@@ -237,14 +241,17 @@ impl StdlibMapping {
         mapping.register_sys_lock_methods();
         mapping.register_sys_semaphore_methods();
         mapping.register_sys_deque_methods();
+        mapping.register_sys_tls_methods();
         mapping.register_sys_condition_methods();
+        mapping.register_sys_coroutine_methods();
         // Boxing/unboxing and other internal extern functions
         mapping.register_internal_extern_functions();
         // TinyCC runtime API (rayzor.runtime.CC)
         mapping.register_cc_methods();
-        // Systems-level types (rayzor.Box, rayzor.Ptr, rayzor.Ref, rayzor.Usize)
+        // Systems-level types (rayzor.Box, rayzor.Ptr, rayzor.Unsafe, rayzor.Ref, rayzor.Usize)
         mapping.register_box_methods();
         mapping.register_ptr_methods();
+        mapping.register_unsafe_methods();
         mapping.register_ref_methods();
         mapping.register_usize_methods();
         mapping.register_cstring_methods();
@@ -253,6 +260,7 @@ impl StdlibMapping {
         // Reflect + Type API
         mapping.register_reflect_methods();
         mapping.register_type_methods();
+        mapping.register_meta_methods();
         // EReg (regular expressions)
         mapping.register_ereg_methods();
         // Enum built-in methods (getIndex, getName, getParameters)
@@ -462,10 +470,15 @@ impl StdlibMapping {
     /// Get all monomorphized variants of a generic class (e.g., Vec -> VecI32, VecI64, etc.)
     /// This is used for looking up methods on generic classes without type info
     pub fn get_monomorphized_variants(&self, base_class: &str) -> Vec<&'static str> {
+        // Variant suffixes start uppercase, so "Vec" does not match "VectorI32"
         let mut variants: Vec<&'static str> = self
             .mappings
             .keys()
-            .filter(|sig| sig.class.starts_with(base_class) && sig.class != base_class)
+            .filter(|sig| {
+                sig.class
+                    .strip_prefix(base_class)
+                    .is_some_and(|suffix| suffix.starts_with(|c: char| c.is_ascii_uppercase()))
+            })
             .map(|sig| sig.class)
             .collect();
         variants.sort_unstable();
@@ -492,6 +505,14 @@ impl StdlibMapping {
             .find(|call| call.runtime_name == runtime_name)
     }
 
+    /// Find the Haxe method a runtime function implements
+    pub fn find_method_by_runtime_name(&self, runtime_name: &str) -> Option<&MethodSignature> {
+        self.mappings
+            .iter()
+            .find(|(_, call)| call.runtime_name == runtime_name)
+            .map(|(sig, _)| sig)
+    }
+
     /// Get the function signature (param types, return type) for a runtime function.
     /// Returns Some((params, return_type)) if the function has explicit type info,
     /// None if the function uses legacy inference.
@@ -740,6 +761,35 @@ macro_rules! map_method {
         )
     };
 
+    // Instance method taking and returning raw values
+    // Used for Vector<T>.set(index, value) which returns the stored value
+    (instance $class:expr, $method:expr => $runtime:expr, params: $params:expr, returns: raw_value, raw_value_params: $raw_mask:expr) => {
+        (
+            MethodSignature {
+                class: $class,
+                method: $method,
+                is_static: false,
+                is_constructor: false,
+                param_count: $params,
+            },
+            RuntimeFunctionCall {
+                runtime_name: $runtime,
+                needs_out_param: false,
+                has_self_param: true,
+                param_count: $params,
+                has_return: true,
+                params_need_ptr_conversion: 0,
+                raw_value_params: $raw_mask,
+                returns_raw_value: true,
+                extend_to_i64_params: 0,
+                param_types: None,
+                return_type: None,
+                is_mir_wrapper: false,
+                source: FunctionSource::Builtin,
+            },
+        )
+    };
+
     // Instance method returning primitive with pointer conversion metadata (DEPRECATED - use raw_value_params)
     (instance $class:expr, $method:expr => $runtime:expr, params: $params:expr, returns: primitive, ptr_params: $ptr_mask:expr) => {
         (
@@ -1409,6 +1459,35 @@ impl StdlibMapping {
             // StringTools.rpad(s: String, c: String, l: Int) -> String
             map_method!(static "StringTools", "rpad" => "haxe_string_rpad", params: 3, returns: primitive,
                 types: &[PtrString, PtrString, I32] => PtrString),
+            // StringTools.hex(n: Int, ?digits: Int) -> String
+            map_method!(static "StringTools", "hex" => "haxe_string_hex", params: 1, returns: primitive,
+                types: &[I32] => PtrString),
+            map_method!(static "StringTools", "hex" => "haxe_string_hex_digits", params: 2, returns: primitive,
+                types: &[I32, I32] => PtrString),
+        ];
+
+        self.register_from_tuples(mappings);
+    }
+
+    // ============================================================================
+    // Collator Methods (rayzor.text.Collator)
+    // ============================================================================
+    //
+    // Locale-aware comparison and case folding, as static functions over String.
+
+    fn register_collator_methods(&mut self) {
+        use IrTypeDescriptor::*;
+
+        let mappings = vec![
+            // Collator.compare(a: String, b: String) -> Int
+            map_method!(static "rayzor_text_Collator", "compare" => "haxe_collator_compare", params: 2, returns: primitive,
+                types: &[PtrString, PtrString] => I32),
+            // Collator.compareIgnoreCase(a: String, b: String) -> Int
+            map_method!(static "rayzor_text_Collator", "compareIgnoreCase" => "haxe_collator_compare_ignore_case", params: 2, returns: primitive,
+                types: &[PtrString, PtrString] => I32),
+            // Collator.foldCase(s: String) -> String
+            map_method!(static "rayzor_text_Collator", "foldCase" => "haxe_collator_fold_case", params: 1, returns: primitive,
+                types: &[PtrString] => PtrString),
         ];
 
         self.register_from_tuples(mappings);
@@ -1437,6 +1516,7 @@ impl StdlibMapping {
             map_method!(instance "Array", "remove" => "haxe_array_remove", params: 1, returns: primitive, ptr_params: 0b10),
             // Extraction methods
             // Array.slice uses MIR wrapper that handles out-param allocation
+            map_method!(instance "Array", "slice" => "array_slice_from", params: 1, returns: primitive),
             map_method!(instance "Array", "slice" => "array_slice", params: 2, returns: primitive),
             map_method!(instance "Array", "copy" => "haxe_array_copy", params: 0, returns: complex),
             // Search methods — MIR wrappers default optional fromIndex
@@ -1451,6 +1531,9 @@ impl StdlibMapping {
                 types: &[PtrU8] => PtrU8),
             map_method!(instance "Array", "unshift" => "array_unshift", params: 1, returns: void),
             map_method!(instance "Array", "resize" => "array_resize", params: 1, returns: void),
+            // resize(len, fill): arg[2]=fill value (needs ptr conversion), copied into each new slot
+            map_method!(instance "Array", "resize" => "haxe_array_resize_fill", params: 2, returns: void, ptr_params: 0b100),
+            map_method!(instance "Array", "shrinkToFit" => "haxe_array_shrink_to_fit", params: 0, returns: void),
             // concat and splice use MIR wrappers that handle out-param allocation
             map_method!(instance "Array", "concat" => "array_concat", params: 1, returns: primitive),
             map_method!(instance "Array", "splice" => "array_splice", params: 2, returns: primitive),
@@ -1458,10 +1541,15 @@ impl StdlibMapping {
             map_method!(instance "Array", "toString" => "array_to_string", params: 0, returns: primitive),
             // Higher-order methods
             // map/filter take a closure, sort takes a comparator closure
-            // All use MIR wrappers that extract fn_ptr + env_ptr from closure struct
+            // All use MIR wrappers that extract fn_ptr + env_ptr from closure struct.
+            // The compiler routes them (and join/indexOf/contains) through the
+            // `_typed` wrappers/runtime functions with callback ABI flags and
+            // element type tags (see lower_typed_array_method)
             map_method!(instance "Array", "map" => "array_map", params: 1, returns: primitive),
             map_method!(instance "Array", "filter" => "array_filter", params: 1, returns: primitive),
             map_method!(instance "Array", "sort" => "array_sort", params: 1, returns: void),
+            // haxe.ds.ArraySort.sort(a, cmp) is the same stable runtime sort
+            map_method!(static "haxe_ds_ArraySort", "sort" => "array_sort", params: 2, returns: void),
         ];
 
         self.register_from_tuples(mappings);
@@ -1546,6 +1634,23 @@ impl StdlibMapping {
                 types: &[] => F64),
             map_method!(static "Sys", "cpuTime" => "haxe_sys_cpu_time", params: 0, returns: primitive,
                 types: &[] => F64),
+            // haxe.Timer.stamp(): monotonic, unlike the wall-clock Sys.time()
+            map_method!(static "Timer", "stamp" => "haxe_timer_stamp", params: 0, returns: primitive,
+                types: &[] => F64),
+            // rayzor.runtime.Clock
+            map_method!(static "rayzor_runtime_Clock", "stamp" => "haxe_timer_stamp", params: 0, returns: primitive,
+                types: &[] => F64),
+            map_method!(static "rayzor_runtime_Clock", "monotonicNs" => "rayzor_time_monotonic_ns", params: 0, returns: primitive,
+                types: &[] => I64),
+            // rayzor.Runtime: loading bundles at runtime
+            map_method!(static "rayzor_Runtime", "loadModule" => "rayzor_runtime_load_module", params: 1, returns: primitive,
+                types: &[PtrVoid] => Bool),
+            map_method!(static "rayzor_Runtime", "loadModuleEntry" => "rayzor_runtime_load_module_entry", params: 2, returns: primitive,
+                types: &[PtrVoid, PtrVoid] => Bool),
+            map_method!(static "rayzor_Runtime", "loadError" => "rayzor_runtime_load_error", params: 0, returns: complex,
+                types: &[] => PtrVoid),
+            map_method!(static "Sys", "args" => "haxe_sys_args", params: 0, returns: complex,
+                types: &[] => PtrVoid),
             // Environment
             map_method!(static "Sys", "getEnv" => "haxe_sys_get_env", params: 1, returns: complex,
                 types: &[PtrVoid] => PtrVoid),
@@ -1776,6 +1881,10 @@ impl StdlibMapping {
             // MIR wrapper: takes closure (*u8), returns thread handle (*u8)
             map_method!(static "rayzor_concurrent_Thread", "spawn" => "Thread_spawn", params: 1, mir_wrapper,
                 types: &[PtrU8] => PtrU8),
+            // Thread::spawnWithStackSize<T>(f: Void -> T, stackSize: Int) -> Thread<T>
+            // MIR wrapper: takes closure (*u8) and stack size in bytes (i32)
+            map_method!(static "rayzor_concurrent_Thread", "spawnWithStackSize" => "Thread_spawnWithStackSize", params: 2, mir_wrapper,
+                types: &[PtrU8, I32] => PtrU8),
             // Thread<T>::join() -> T
             // MIR wrapper: takes thread handle (*u8), returns result (*u8 for Dynamic)
             map_method!(instance "rayzor_concurrent_Thread", "join" => "Thread_join", params: 0, mir_wrapper,
@@ -2204,6 +2313,186 @@ impl StdlibMapping {
         ];
 
         self.register_from_tuples(mappings);
+
+        // WeakMap<K,V> shares the ObjectMap runtime representation; only the
+        // constructor differs (entries of released keys are dropped)
+        let weakmap_mappings = vec![
+            map_method!(constructor "WeakMap", "new" => "haxe_weakmap_new", params: 0, returns: primitive),
+            map_method!(instance "WeakMap", "set" => "haxe_objectmap_set", params: 2, returns: void, raw_value_params: 0b100),
+            map_method!(instance "WeakMap", "get" => "haxe_objectmap_get", params: 1, returns: raw_value),
+            map_method!(instance "WeakMap", "exists" => "haxe_objectmap_exists", params: 1, returns: primitive),
+            map_method!(instance "WeakMap", "remove" => "haxe_objectmap_remove", params: 1, returns: primitive),
+            map_method!(instance "WeakMap", "clear" => "haxe_objectmap_clear", params: 0, returns: void),
+            map_method!(instance "WeakMap", "toString" => "haxe_objectmap_to_string", params: 0, returns: primitive),
+            map_method!(instance "WeakMap", "copy" => "haxe_objectmap_copy", params: 0, returns: primitive),
+        ];
+
+        self.register_from_tuples(weakmap_mappings);
+
+        // EnumValueMap<K,V> is an ObjectMap whose keys are hashed and compared
+        // structurally; the constructor takes the key enum's runtime type id,
+        // which is injected by the compiler
+        let enumvaluemap_mappings = vec![
+            map_method!(constructor "EnumValueMap", "new" => "haxe_enummap_new", params: 0, returns: primitive),
+            map_method!(instance "EnumValueMap", "set" => "haxe_objectmap_set", params: 2, returns: void, raw_value_params: 0b100),
+            map_method!(instance "EnumValueMap", "get" => "haxe_objectmap_get", params: 1, returns: raw_value),
+            map_method!(instance "EnumValueMap", "exists" => "haxe_objectmap_exists", params: 1, returns: primitive),
+            map_method!(instance "EnumValueMap", "remove" => "haxe_objectmap_remove", params: 1, returns: primitive),
+            map_method!(instance "EnumValueMap", "clear" => "haxe_objectmap_clear", params: 0, returns: void),
+            map_method!(instance "EnumValueMap", "toString" => "haxe_objectmap_to_string", params: 0, returns: primitive),
+            map_method!(instance "EnumValueMap", "copy" => "haxe_objectmap_copy", params: 0, returns: primitive),
+        ];
+
+        self.register_from_tuples(enumvaluemap_mappings);
+    }
+
+    // ============================================================================
+    // List<T> Methods (haxe.ds.List)
+    // ============================================================================
+    //
+    // List is an extern class backed by a native deque of raw u64 element slots.
+    // remove/join/toString/filter/map depend on the element or callback types and
+    // are lowered by `lower_typed_list_method` in hir_to_mir.
+
+    fn register_list_methods(&mut self) {
+        use IrTypeDescriptor::*;
+
+        let mappings = vec![
+            // Constructor: new List<T>() -> List<T>
+            map_method!(constructor "List", "new" => "haxe_list_new", params: 0, returns: primitive),
+            // List<T>::add(item: T) / push(item: T) -> Void
+            // Item is passed as raw u64 bits, like StringMap values
+            map_method!(instance "List", "add" => "haxe_list_add", params: 1, returns: void, raw_value_params: 0b10),
+            map_method!(instance "List", "push" => "haxe_list_push", params: 1, returns: void, raw_value_params: 0b10),
+            // List<T>::pop()/first()/last() -> Null<T> (as u64)
+            map_method!(instance "List", "pop" => "haxe_list_pop", params: 0, returns: raw_value),
+            map_method!(instance "List", "first" => "haxe_list_first", params: 0, returns: raw_value),
+            map_method!(instance "List", "last" => "haxe_list_last", params: 0, returns: raw_value),
+            // List<T>.length -> Int
+            map_method!(instance "List", "length" => "haxe_list_length", params: 0, returns: primitive,
+                types: &[PtrVoid] => I32),
+            // List<T>::isEmpty() -> Bool
+            map_method!(instance "List", "isEmpty" => "haxe_list_is_empty", params: 0, returns: primitive,
+                types: &[PtrVoid] => Bool),
+            // List<T>::clear() -> Void
+            map_method!(instance "List", "clear" => "haxe_list_clear", params: 0, returns: void,
+                types: &[PtrVoid]),
+            // Element/callback-typed methods (see lower_typed_list_method)
+            map_method!(instance "List", "remove" => "haxe_list_remove_typed", params: 1, returns: primitive),
+            map_method!(instance "List", "join" => "haxe_list_join", params: 1, returns: primitive),
+            map_method!(instance "List", "toString" => "haxe_list_to_string", params: 0, returns: primitive),
+            map_method!(instance "List", "filter" => "haxe_list_filter", params: 1, returns: primitive),
+            map_method!(instance "List", "map" => "haxe_list_map", params: 1, returns: primitive),
+        ];
+
+        self.register_from_tuples(mappings);
+    }
+
+    // ============================================================================
+    // Vector<T> Methods (haxe.ds.Vector - fixed-length, monomorphized)
+    // ============================================================================
+    //
+    // Vector is an extern class monomorphized like Vec<T>:
+    // - "VectorI32" for Vector<Int> (unboxed i32 storage)
+    // - "VectorF64" for Vector<Float> (unboxed f64 storage)
+    // - "VectorPtr" for any other T (raw u64 slots, like List)
+    //
+    // `v[i]` and `v[i] = x` are lowered to `get`/`set` calls in the TAST.
+
+    fn register_vector_methods(&mut self) {
+        use IrTypeDescriptor::*;
+
+        let vector_i32_mappings = vec![
+            map_method!(constructor "VectorI32", "new" => "haxe_vector_i32_new", params: 1, returns: primitive,
+                types: &[I32] => PtrVoid),
+            map_method!(instance "VectorI32", "get" => "haxe_vector_i32_get", params: 1, returns: primitive,
+                types: &[PtrVoid, I32] => I32),
+            map_method!(instance "VectorI32", "set" => "haxe_vector_i32_set", params: 2, returns: primitive,
+                types: &[PtrVoid, I32, I32] => I32),
+            map_method!(instance "VectorI32", "fill" => "haxe_vector_i32_fill", params: 1, returns: void,
+                types: &[PtrVoid, I32]),
+        ];
+        self.register_from_tuples(vector_i32_mappings);
+
+        let vector_f64_mappings = vec![
+            map_method!(constructor "VectorF64", "new" => "haxe_vector_f64_new", params: 1, returns: primitive,
+                types: &[I32] => PtrVoid),
+            map_method!(instance "VectorF64", "get" => "haxe_vector_f64_get", params: 1, returns: primitive,
+                types: &[PtrVoid, I32] => F64),
+            map_method!(instance "VectorF64", "set" => "haxe_vector_f64_set", params: 2, returns: primitive,
+                types: &[PtrVoid, I32, F64] => F64),
+            map_method!(instance "VectorF64", "fill" => "haxe_vector_f64_fill", params: 1, returns: void,
+                types: &[PtrVoid, F64]),
+        ];
+        self.register_from_tuples(vector_f64_mappings);
+
+        // Elements are passed as raw u64 bits, like List<T> items
+        let vector_ptr_mappings = vec![
+            map_method!(constructor "VectorPtr", "new" => "haxe_vector_new", params: 1, returns: primitive,
+                types: &[I32] => PtrVoid),
+            map_method!(instance "VectorPtr", "get" => "haxe_vector_get", params: 1, returns: raw_value),
+            map_method!(instance "VectorPtr", "set" => "haxe_vector_set", params: 2, returns: raw_value,
+                raw_value_params: 0b100),
+            map_method!(instance "VectorPtr", "fill" => "haxe_vector_fill", params: 1, returns: void,
+                raw_value_params: 0b10),
+        ];
+        self.register_from_tuples(vector_ptr_mappings);
+
+        // Operations that do not depend on the element type
+        for class in ["VectorI32", "VectorF64", "VectorPtr"] {
+            self.register_from_tuples(vec![
+                map_method!(instance class, "length" => "haxe_vector_length", params: 0, returns: primitive,
+                    types: &[PtrVoid] => I32),
+                map_method!(instance class, "copy" => "haxe_vector_copy", params: 0, returns: primitive,
+                    types: &[PtrVoid] => PtrVoid),
+                map_method!(instance class, "toArray" => "haxe_vector_to_array", params: 0, returns: primitive,
+                    types: &[PtrVoid] => PtrVoid),
+            ]);
+        }
+
+        // Vector.blit(src, srcPos, dest, destPos, len)
+        // Vector.fromArrayCopy(array) picks its variant from the array's
+        // element type (see lower_vector_from_array)
+        let vector_static_mappings = vec![
+            map_method!(static "haxe_ds_Vector", "blit" => "haxe_vector_blit", params: 5, returns: void),
+            map_method!(static "haxe_ds_Vector", "fromArrayCopy" => "haxe_vector_from_array", params: 1, returns: primitive),
+        ];
+        self.register_from_tuples(vector_static_mappings);
+    }
+
+    // ============================================================================
+    // Lambda Methods
+    // ============================================================================
+    //
+    // Lambda is an extern class. Every call is lowered by `lower_lambda_call` in
+    // hir_to_mir, which converts the iterable to a HaxeArray and passes closures
+    // with their ABI flags; these entries name the runtime function each method
+    // lowers to.
+
+    fn register_lambda_methods(&mut self) {
+        let mappings = vec![
+            map_method!(static "Lambda", "array" => "haxe_lambda_array", params: 1, returns: primitive),
+            map_method!(static "Lambda", "list" => "haxe_list_from_array", params: 1, returns: primitive),
+            map_method!(static "Lambda", "map" => "haxe_array_map", params: 2, returns: primitive),
+            map_method!(static "Lambda", "mapi" => "haxe_lambda_mapi", params: 2, returns: primitive),
+            map_method!(static "Lambda", "flatten" => "haxe_lambda_flatten", params: 1, returns: primitive),
+            map_method!(static "Lambda", "flatMap" => "haxe_lambda_flatten", params: 2, returns: primitive),
+            map_method!(static "Lambda", "has" => "haxe_array_contains_typed", params: 2, returns: primitive),
+            map_method!(static "Lambda", "exists" => "haxe_lambda_exists", params: 2, returns: primitive),
+            map_method!(static "Lambda", "foreach" => "haxe_lambda_foreach", params: 2, returns: primitive),
+            map_method!(static "Lambda", "iter" => "haxe_lambda_iter", params: 2, returns: void),
+            map_method!(static "Lambda", "filter" => "haxe_array_filter", params: 2, returns: primitive),
+            map_method!(static "Lambda", "fold" => "haxe_lambda_fold", params: 3, returns: primitive),
+            map_method!(static "Lambda", "foldi" => "haxe_lambda_foldi", params: 3, returns: primitive),
+            map_method!(static "Lambda", "count" => "haxe_lambda_count", params: 2, returns: primitive),
+            map_method!(static "Lambda", "empty" => "haxe_lambda_empty", params: 1, returns: primitive),
+            map_method!(static "Lambda", "indexOf" => "haxe_array_index_of_typed", params: 2, returns: primitive),
+            map_method!(static "Lambda", "find" => "haxe_lambda_find", params: 2, returns: primitive),
+            map_method!(static "Lambda", "findIndex" => "haxe_lambda_find_index", params: 2, returns: primitive),
+            map_method!(static "Lambda", "concat" => "haxe_lambda_concat", params: 2, returns: primitive),
+        ];
+
+        self.register_from_tuples(mappings);
     }
 
     // ============================================================================
@@ -2596,6 +2885,22 @@ impl StdlibMapping {
         self.register_from_tuples(mappings);
     }
 
+    fn register_sys_tls_methods(&mut self) {
+        use IrTypeDescriptor::*;
+
+        let mappings = vec![
+            // Constructor: new Tls<T>() -> Tls<T>
+            map_method!(constructor "sys_thread_Tls", "new" => "rayzor_tls_alloc", params: 0, returns: primitive,
+                types: &[] => PtrU8),
+            // tls.value -> T (raw 8-byte slot, see lower_field_access)
+            map_method!(instance "sys_thread_Tls", "value" => "rayzor_tls_get", params: 0, returns: raw_value),
+            // tls.value = v (raw 8-byte slot, see lower_lvalue_write)
+            map_method!(instance "sys_thread_Tls", "set_value" => "rayzor_tls_set", params: 1, returns: void, raw_value_params: 0b10),
+        ];
+
+        self.register_from_tuples(mappings);
+    }
+
     fn register_sys_condition_methods(&mut self) {
         use IrTypeDescriptor::*;
 
@@ -2626,6 +2931,43 @@ impl StdlibMapping {
         self.register_from_tuples(mappings);
     }
 
+    // ============================================================================
+    // sys.thread.Coroutine Methods (stackful coroutines)
+    // ============================================================================
+    //
+    // Yielded values are boxed like Deque items, so resume() returns Null<T>.
+
+    fn register_sys_coroutine_methods(&mut self) {
+        use IrTypeDescriptor::*;
+
+        let mappings = vec![
+            // Coroutine.create(fn: Void->Void) -> Coroutine<T>
+            // Uses Coroutine_create wrapper which extracts fn_ptr and env_ptr from closure object
+            map_method!(static "sys_thread_Coroutine", "create" => "Coroutine_create", params: 1, mir_wrapper,
+                types: &[PtrU8] => PtrU8),
+            // coroutine.resume() -> Null<T> (boxed DynamicValue*, null once dead)
+            map_method!(instance "sys_thread_Coroutine", "resume" => "rayzor_coroutine_resume", params: 0, returns: primitive,
+                types: &[PtrU8] => PtrU8),
+            // Coroutine.yield(value: Dynamic) -> Void (value is boxed)
+            map_method!(static "sys_thread_Coroutine", "yield" => "rayzor_coroutine_yield", params: 1, returns: void,
+                types: &[PtrU8]),
+            // coroutine.status() -> Int (0 suspended, 1 running, 2 dead)
+            map_method!(instance "sys_thread_Coroutine", "status" => "rayzor_coroutine_status", params: 0, returns: primitive,
+                types: &[PtrU8] => I32),
+            // coroutine.isDead() -> Bool
+            map_method!(instance "sys_thread_Coroutine", "isDead" => "rayzor_coroutine_is_dead", params: 0, returns: primitive,
+                types: &[PtrU8] => Bool),
+            // coroutine.dispose() -> Bool (false while running)
+            map_method!(instance "sys_thread_Coroutine", "dispose" => "rayzor_coroutine_destroy", params: 0, returns: primitive,
+                types: &[PtrU8] => Bool),
+            // Coroutine.isInside() -> Bool
+            map_method!(static "sys_thread_Coroutine", "isInside" => "rayzor_coroutine_is_inside", params: 0, returns: primitive,
+                types: &[] => Bool),
+        ];
+
+        self.register_from_tuples(mappings);
+    }
+
     // ============================================================================
     // Internal Extern Functions (not Haxe-method-mapped)
     // ============================================================================
@@ -2745,6 +3087,37 @@ impl StdlibMapping {
             // ptr.isNull(): Bool  (instance, compare to 0)
             map_method!(instance "rayzor_Ptr", "isNull" => "Ptr_isNull", params: 0, mir_wrapper,
                 types: &[I64] => Bool),
+            // Ptr.alloc<T>(n: Int): Ptr<T>  (static, malloc of n elements)
+            map_method!(static "rayzor_Ptr", "alloc" => "Ptr_alloc", params: 1, mir_wrapper,
+                types: &[I64] => I64),
+            // ptr.get(index: Int): T  (instance, load from ptr + index)
+            map_method!(instance "rayzor_Ptr", "get" => "Ptr_get", params: 1, mir_wrapper,
+                types: &[I64, I64] => I64),
+            // ptr.set(index: Int, value: T): Void  (instance, store to ptr + index)
+            map_method!(instance "rayzor_Ptr", "set" => "Ptr_set", params: 2, mir_wrapper,
+                types: &[I64, I64, I64]),
+            // ptr.free(): Void  (instance, free memory from alloc)
+            map_method!(instance "rayzor_Ptr", "free" => "Ptr_free", params: 0, mir_wrapper,
+                types: &[I64]),
+        ];
+
+        self.register_from_tuples(mappings);
+    }
+
+    // ============================================================================
+    // Unsafe Methods (rayzor.Unsafe — raw memory operations)
+    // ============================================================================
+
+    fn register_unsafe_methods(&mut self) {
+        use IrTypeDescriptor::*;
+
+        let mappings = vec![
+            // Unsafe.memcpy(dest: Ptr<T>, src: Ptr<T>, bytes: Int): Void  (static, MemCopy)
+            map_method!(static "rayzor_Unsafe", "memcpy" => "Unsafe_memcpy", params: 3, mir_wrapper,
+                types: &[I64, I64, I64]),
+            // Unsafe.memset(dest: Ptr<T>, value: Int, bytes: Int): Void  (static, MemSet)
+            map_method!(static "rayzor_Unsafe", "memset" => "Unsafe_memset", params: 3, mir_wrapper,
+                types: &[I64, I64, I64]),
         ];
 
         self.register_from_tuples(mappings);
@@ -2888,6 +3261,9 @@ impl StdlibMapping {
             // simd.set(lane, value): SIMD4f  (instance, @:arrayAccess write)
             map_method!(instance "rayzor_SIMD4f", "set" => "SIMD4f_insert", params: 2, mir_wrapper,
                 types: &[VecF32x4, I32, F32] => VecF32x4),
+            // simd.shuffle(x, y, z, w): SIMD4f  (instance, lane i = self[index i])
+            map_method!(instance "rayzor_SIMD4f", "shuffle" => "SIMD4f_shuffle", params: 4, mir_wrapper,
+                types: &[VecF32x4, I32, I32, I32, I32] => VecF32x4),
             // simd.sum(): Float  (instance, horizontal sum — returns f64 to match Haxe Float)
             map_method!(instance "rayzor_SIMD4f", "sum" => "SIMD4f_sum", params: 0, mir_wrapper,
                 types: &[VecF32x4] => F64),
@@ -2944,6 +3320,22 @@ impl StdlibMapping {
         ];
 
         self.register_from_tuples(mappings);
+
+        // rayzor.simd.Float32x4 is the portable name for the same f32x4 type.
+        // Instance calls on any f32x4 receiver already resolve through
+        // "rayzor_SIMD4f", so only the static constructors need registering.
+        let float32x4_mappings = vec![
+            map_method!(static "rayzor_simd_Float32x4", "splat" => "SIMD4f_splat", params: 1, mir_wrapper,
+                types: &[F32] => VecF32x4),
+            map_method!(static "rayzor_simd_Float32x4", "make" => "SIMD4f_make", params: 4, mir_wrapper,
+                types: &[F32, F32, F32, F32] => VecF32x4),
+            map_method!(static "rayzor_simd_Float32x4", "load" => "SIMD4f_load", params: 1, mir_wrapper,
+                types: &[I64] => VecF32x4),
+            map_method!(static "rayzor_simd_Float32x4", "fromArray" => "SIMD4f_fromArray", params: 1, mir_wrapper,
+                types: &[PtrVoid] => VecF32x4),
+        ];
+
+        self.register_from_tuples(float32x4_mappings);
     }
 
     // ============================================================================
@@ -3132,6 +3524,9 @@ impl StdlibMapping {
             // Reflect.compare(a:T, b:T):Int
             map_method!(static "Reflect", "compare" => "haxe_reflect_compare", params: 2, returns: primitive,
                 types: &[PtrU8, PtrU8] => I64),
+            // Reflect.compareMethods(f1:Dynamic, f2:Dynamic):Bool — closure identity
+            map_method!(static "Reflect", "compareMethods" => "haxe_reflect_compare_methods", params: 2, returns: primitive,
+                types: &[PtrU8, PtrU8] => Bool),
             // Reflect.isEnumValue(v:Dynamic):Bool
             map_method!(static "Reflect", "isEnumValue" => "haxe_reflect_is_enum_value", params: 1, returns: primitive,
                 types: &[PtrU8] => Bool),
@@ -3146,6 +3541,24 @@ impl StdlibMapping {
         self.register_from_tuples(mappings);
     }
 
+    fn register_meta_methods(&mut self) {
+        use IrTypeDescriptor::*;
+
+        let mappings = vec![
+            // Meta.getType(t:Dynamic):Dynamic<Array<Dynamic>> (class or enum id)
+            map_method!(static "Meta", "getType" => "haxe_meta_get_type", params: 1, returns: primitive,
+                types: &[I64] => PtrU8),
+            // Meta.getFields(t:Dynamic):Dynamic<Dynamic<Array<Dynamic>>>
+            map_method!(static "Meta", "getFields" => "haxe_meta_get_fields", params: 1, returns: primitive,
+                types: &[I64] => PtrU8),
+            // Meta.getStatics(t:Dynamic):Dynamic<Dynamic<Array<Dynamic>>>
+            map_method!(static "Meta", "getStatics" => "haxe_meta_get_statics", params: 1, returns: primitive,
+                types: &[I64] => PtrU8),
+        ];
+
+        self.register_from_tuples(mappings);
+    }
+
     fn register_type_methods(&mut self) {
         use IrTypeDescriptor::*;
 
@@ -3192,6 +3605,9 @@ impl StdlibMapping {
             // Type.enumParameters(e:EnumValue):Array<Dynamic> — takes (value, type_id), type_id injected by compiler
             map_method!(static "Type", "enumParameters" => "haxe_type_enum_parameters", params: 1, returns: complex,
                 types: &[I64, I32] => PtrVoid),
+            // Type.enumEq(a:T, b:T):Bool — structural; takes (a, b, type_id), type_id injected by compiler
+            map_method!(static "Type", "enumEq" => "haxe_type_enum_eq", params: 2, returns: primitive,
+                types: &[I64, I64, I32] => Bool),
         ];
 
         self.register_from_tuples(mappings);
@@ -1,12 +1,14 @@
-/// Systems-level type MIR wrappers (Box, Ptr, Ref, Usize)
+/// Systems-level type MIR wrappers (Box, Ptr, Ref, Usize, Unsafe)
 ///
 /// These are zero-cost abstracts over Int (i64) at MIR level.
 /// Box operations delegate to runtime functions (alloc/free).
 /// Ptr/Ref operations are direct load/store/arithmetic MIR instructions.
+/// Unsafe.memcpy/memset are direct MemCopy/MemSet MIR instructions.
 /// Usize operations are native i64 arithmetic.
 use crate::ir::mir_builder::MirBuilder;
 use crate::ir::{
-    BinaryOp, CallingConvention, CompareOp, IrType, IrValue, VectorMinMaxKind, VectorUnaryOpKind,
+    BinaryOp, CallingConvention, CompareOp, IrId, IrType, IrValue, VectorMinMaxKind,
+    VectorUnaryOpKind,
 };
 
 /// Build all systems-level type functions
@@ -20,13 +22,21 @@ pub fn build_systems_types(builder: &mut MirBuilder) {
     build_box_raw(builder);
     build_box_free(builder);
 
-    // Build Ptr MIR wrappers (no externs needed — direct MIR ops)
+    // Build Ptr MIR wrappers (direct MIR ops; alloc/free call libc malloc/free)
     build_ptr_from_raw(builder);
     build_ptr_raw(builder);
     build_ptr_deref(builder);
     build_ptr_write(builder);
     build_ptr_offset(builder);
     build_ptr_is_null(builder);
+    build_ptr_alloc(builder);
+    build_ptr_get(builder);
+    build_ptr_set(builder);
+    build_ptr_free(builder);
+
+    // Build Unsafe MIR wrappers (direct MIR memory ops)
+    build_unsafe_memcpy(builder);
+    build_unsafe_memset(builder);
 
     // Build Ref MIR wrappers (no externs needed — direct MIR ops)
     build_ref_from_raw(builder);
@@ -56,6 +66,7 @@ pub fn build_systems_types(builder: &mut MirBuilder) {
     build_simd4f_store(builder);
     build_simd4f_extract(builder);
     build_simd4f_insert(builder);
+    build_simd4f_shuffle(builder);
     build_simd4f_sum(builder);
     build_simd4f_dot(builder);
     build_simd4f_from_array(builder);
@@ -353,6 +364,179 @@ fn build_ptr_is_null(builder: &mut MirBuilder) {
     builder.ret(Some(is_null));
 }
 
+/// Ptr_alloc(n: i64) -> i64  — malloc(n * 8), null if n <= 0 or out of memory
+fn build_ptr_alloc(builder: &mut MirBuilder) {
+    let i64_ty = IrType::I64;
+    let ptr_u8_ty = IrType::Ptr(Box::new(IrType::U8));
+
+    let func_id = builder
+        .begin_function("Ptr_alloc")
+        .param("n", i64_ty.clone())
+        .returns(i64_ty.clone())
+        .calling_convention(CallingConvention::C)
+        .build();
+
+    builder.set_current_function(func_id);
+    let entry = builder.create_block("entry");
+    let alloc_block = builder.create_block("alloc");
+    let empty_block = builder.create_block("empty");
+    builder.set_insert_point(entry);
+
+    let n = builder.get_param(0);
+    let zero = builder.const_i64(0);
+    let is_positive = builder.icmp(CompareOp::Gt, n, zero, IrType::Bool);
+    builder.cond_br(is_positive, alloc_block, empty_block);
+
+    builder.set_insert_point(alloc_block);
+    let eight = builder.const_i64(8);
+    let size = builder.mul(n, eight, i64_ty.clone());
+    let malloc_id = builder
+        .get_function_by_name("malloc")
+        .expect("malloc not found");
+    let ptr = builder.call(malloc_id, vec![size]).unwrap();
+    let address = builder.cast(ptr, ptr_u8_ty, i64_ty);
+    builder.ret(Some(address));
+
+    builder.set_insert_point(empty_block);
+    let null = builder.const_i64(0);
+    builder.ret(Some(null));
+}
+
+/// Ptr_get(ptr: i64, index: i64) -> i64  — load i64 from ptr + index * 8
+fn build_ptr_get(builder: &mut MirBuilder) {
+    let i64_ty = IrType::I64;
+
+    let func_id = builder
+        .begin_function("Ptr_get")
+        .param("ptr", i64_ty.clone())
+        .param("index", i64_ty.clone())
+        .returns(i64_ty.clone())
+        .calling_convention(CallingConvention::C)
+        .build();
+
+    builder.set_current_function(func_id);
+    let entry = builder.create_block("entry");
+    builder.set_insert_point(entry);
+
+    let ptr = builder.get_param(0);
+    let index = builder.get_param(1);
+    let eight = builder.const_i64(8);
+    let byte_offset = builder.mul(index, eight, i64_ty.clone());
+    let address = builder.add(ptr, byte_offset, i64_ty.clone());
+    let value = builder.load(address, i64_ty);
+    builder.ret(Some(value));
+}
+
+/// Ptr_set(ptr: i64, index: i64, value: i64) -> void  — store i64 to ptr + index * 8
+fn build_ptr_set(builder: &mut MirBuilder) {
+    let i64_ty = IrType::I64;
+
+    let func_id = builder
+        .begin_function("Ptr_set")
+        .param("ptr", i64_ty.clone())
+        .param("index", i64_ty.clone())
+        .param("value", i64_ty.clone())
+        .returns(IrType::Void)
+        .calling_convention(CallingConvention::C)
+        .build();
+
+    builder.set_current_function(func_id);
+    let entry = builder.create_block("entry");
+    builder.set_insert_point(entry);
+
+    let ptr = builder.get_param(0);
+    let index = builder.get_param(1);
+    let value = builder.get_param(2);
+    let eight = builder.const_i64(8);
+    let byte_offset = builder.mul(index, eight, i64_ty.clone());
+    let address = builder.add(ptr, byte_offset, i64_ty);
+    builder.store(address, value);
+    builder.ret(None);
+}
+
+/// Ptr_free(ptr: i64) -> void  — free memory from Ptr_alloc (null is a no-op)
+fn build_ptr_free(builder: &mut MirBuilder) {
+    let i64_ty = IrType::I64;
+    let ptr_u8_ty = IrType::Ptr(Box::new(IrType::U8));
+
+    let func_id = builder
+        .begin_function("Ptr_free")
+        .param("ptr", i64_ty.clone())
+        .returns(IrType::Void)
+        .calling_convention(CallingConvention::C)
+        .build();
+
+    builder.set_current_function(func_id);
+    let entry = builder.create_block("entry");
+    builder.set_insert_point(entry);
+
+    let ptr = builder.get_param(0);
+    let raw = builder.cast(ptr, i64_ty, ptr_u8_ty);
+    let free_id = builder
+        .get_function_by_name("free")
+        .expect("free not found");
+    builder.call(free_id, vec![raw]);
+    builder.ret(None);
+}
+
+// ============================================================================
+// Unsafe — MIR wrappers (MemCopy/MemSet instructions, no runtime calls)
+// ============================================================================
+
+/// Unsafe_memcpy(dest: i64, src: i64, bytes: i64) -> void
+fn build_unsafe_memcpy(builder: &mut MirBuilder) {
+    let i64_ty = IrType::I64;
+    let ptr_u8_ty = IrType::Ptr(Box::new(IrType::U8));
+
+    let func_id = builder
+        .begin_function("Unsafe_memcpy")
+        .param("dest", i64_ty.clone())
+        .param("src", i64_ty.clone())
+        .param("bytes", i64_ty.clone())
+        .returns(IrType::Void)
+        .calling_convention(CallingConvention::C)
+        .build();
+
+    builder.set_current_function(func_id);
+    let entry = builder.create_block("entry");
+    builder.set_insert_point(entry);
+
+    let dest = builder.get_param(0);
+    let src = builder.get_param(1);
+    let bytes = builder.get_param(2);
+    let dest_ptr = builder.cast(dest, i64_ty.clone(), ptr_u8_ty.clone());
+    let src_ptr = builder.cast(src, i64_ty, ptr_u8_ty);
+    builder.memcpy(dest_ptr, src_ptr, bytes);
+    builder.ret(None);
+}
+
+/// Unsafe_memset(dest: i64, value: i64, bytes: i64) -> void  — fills with the low byte of value
+fn build_unsafe_memset(builder: &mut MirBuilder) {
+    let i64_ty = IrType::I64;
+    let ptr_u8_ty = IrType::Ptr(Box::new(IrType::U8));
+
+    let func_id = builder
+        .begin_function("Unsafe_memset")
+        .param("dest", i64_ty.clone())
+        .param("value", i64_ty.clone())
+        .param("bytes", i64_ty.clone())
+        .returns(IrType::Void)
+        .calling_convention(CallingConvention::C)
+        .build();
+
+    builder.set_current_function(func_id);
+    let entry = builder.create_block("entry");
+    builder.set_insert_point(entry);
+
+    let dest = builder.get_param(0);
+    let value = builder.get_param(1);
+    let bytes = builder.get_param(2);
+    let dest_ptr = builder.cast(dest, i64_ty.clone(), ptr_u8_ty);
+    let byte = builder.cast(value, i64_ty, IrType::U8);
+    builder.memset(dest_ptr, byte, bytes);
+    builder.ret(None);
+}
+
 // ============================================================================
 // Ref<T> — MIR wrappers (same as Ptr but read-only, no write)
 // ============================================================================
@@ -809,7 +993,10 @@ fn build_simd4f_extract(builder: &mut MirBuilder) {
     builder.set_insert_point(entry);
 
     let self_val = builder.get_param(0);
-    let f32_result = builder.vector_extract(self_val, 0, f32_ty.clone());
+    let lane = builder.get_param(1);
+    let slot = spill_simd4f(builder, self_val);
+    let lane_ptr = simd4f_lane_ptr(builder, slot, lane);
+    let f32_result = builder.load(lane_ptr, f32_ty.clone());
     let result = builder.cast(f32_result, f32_ty, f64_ty);
     builder.ret(Some(result));
 }
@@ -834,12 +1021,72 @@ fn build_simd4f_insert(builder: &mut MirBuilder) {
     builder.set_insert_point(entry);
 
     let self_val = builder.get_param(0);
+    let lane = builder.get_param(1);
     let value = builder.get_param(2);
-    // Static lane 0 for now (same limitation as extract)
-    let result = builder.vector_insert(self_val, value, 0, vec_ty);
+    let slot = spill_simd4f(builder, self_val);
+    let lane_ptr = simd4f_lane_ptr(builder, slot, lane);
+    builder.store(lane_ptr, value);
+    let result = builder.vector_load(slot, vec_ty);
+    builder.ret(Some(result));
+}
+
+/// SIMD4f_shuffle(self: vec<f32; 4>, x: i32, y: i32, z: i32, w: i32) -> vec<f32; 4>
+///
+/// Result lane i is `self[index_i]`; indices are taken modulo 4.
+fn build_simd4f_shuffle(builder: &mut MirBuilder) {
+    let vec_ty = IrType::vector(IrType::F32, 4);
+    let f32_ty = IrType::F32;
+    let i32_ty = IrType::I32;
+
+    let func_id = builder
+        .begin_function("SIMD4f_shuffle")
+        .param("self_val", vec_ty.clone())
+        .param("x", i32_ty.clone())
+        .param("y", i32_ty.clone())
+        .param("z", i32_ty.clone())
+        .param("w", i32_ty)
+        .returns(vec_ty.clone())
+        .calling_convention(CallingConvention::C)
+        .build();
+
+    builder.set_current_function(func_id);
+    let entry = builder.create_block("entry");
+    builder.set_insert_point(entry);
+
+    let self_val = builder.get_param(0);
+    let slot = spill_simd4f(builder, self_val);
+    let mut lanes = Vec::with_capacity(4);
+    for i in 1..=4 {
+        let index = builder.get_param(i);
+        let lane_ptr = simd4f_lane_ptr(builder, slot, index);
+        lanes.push(builder.load(lane_ptr, f32_ty.clone()));
+    }
+
+    // Same construction as SIMD4f_make: splat lane 0, insert lanes 1..3
+    let mut result = builder.vector_splat(lanes[0], vec_ty.clone());
+    for (i, &lane) in lanes.iter().enumerate().skip(1) {
+        result = builder.vector_insert(result, lane, i as u8, vec_ty.clone());
+    }
     builder.ret(Some(result));
 }
 
+/// Store a SIMD4f into a 4×f32 stack slot so lanes can be addressed by a
+/// runtime index (vector extract/insert only take constant lanes)
+fn spill_simd4f(builder: &mut MirBuilder, vector: IrId) -> IrId {
+    let count = builder.const_i64(4);
+    let slot = builder.alloc(IrType::F32, Some(count));
+    builder.vector_store(slot, vector, IrType::vector(IrType::F32, 4));
+    slot
+}
+
+/// Address of lane `lane & 3` in a slot produced by `spill_simd4f`
+fn simd4f_lane_ptr(builder: &mut MirBuilder, slot: IrId, lane: IrId) -> IrId {
+    let lane_i64 = builder.cast(lane, IrType::I32, IrType::I64);
+    let mask = builder.const_i64(3);
+    let index = builder.bin_op(BinaryOp::And, lane_i64, mask);
+    builder.ptr_add(slot, index, IrType::Ptr(Box::new(IrType::F32)))
+}
+
 /// SIMD4f_sum(self: vec<f32; 4>) -> f32  — horizontal add
 fn build_simd4f_sum(builder: &mut MirBuilder) {
     let vec_ty = IrType::vector(IrType::F32, 4);
@@ -21,11 +21,13 @@ pub fn build_thread_type(builder: &mut MirBuilder) {
 
     // Build wrapper functions
     build_thread_spawn(builder);
+    build_thread_spawn_with_stack_size(builder);
     build_thread_join(builder);
     build_thread_is_finished(builder);
     build_thread_yield_now(builder);
     build_thread_sleep(builder);
     build_thread_current_id(builder);
+    build_coroutine_create(builder);
 
     // Lock wrappers (Lock is backed by semaphore with initial count 0)
     build_lock_init(builder);
@@ -55,6 +57,28 @@ fn declare_thread_externs(builder: &mut MirBuilder) {
         .build();
     builder.mark_as_extern(func_id);
 
+    // extern fn rayzor_thread_spawn_with_stack_size(closure: *u8, closure_env: *u8, stack_size: i64) -> *u8
+    let func_id = builder
+        .begin_function("rayzor_thread_spawn_with_stack_size")
+        .param("closure", ptr_u8.clone())
+        .param("closure_env", ptr_u8.clone())
+        .param("stack_size", IrType::I64)
+        .returns(ptr_u8.clone())
+        .calling_convention(CallingConvention::C)
+        .build();
+    builder.mark_as_extern(func_id);
+
+    // extern fn rayzor_coroutine_create(closure: *u8, closure_env: *u8, stack_size: i64) -> *u8
+    let func_id = builder
+        .begin_function("rayzor_coroutine_create")
+        .param("closure", ptr_u8.clone())
+        .param("closure_env", ptr_u8.clone())
+        .param("stack_size", IrType::I64)
+        .returns(ptr_u8.clone())
+        .calling_convention(CallingConvention::C)
+        .build();
+    builder.mark_as_extern(func_id);
+
     // extern fn rayzor_thread_join(handle: *u8) -> *u8
     let func_id = builder
         .begin_function("rayzor_thread_join")
@@ -174,6 +198,80 @@ fn build_thread_spawn(builder: &mut MirBuilder) {
     builder.ret(Some(handle));
 }
 
+/// Build: fn Thread_spawnWithStackSize(closure_obj: *u8, stack_size: i32) -> *Thread
+/// Same as Thread_spawn, with the thread's stack size in bytes
+fn build_thread_spawn_with_stack_size(builder: &mut MirBuilder) {
+    let ptr_u8 = builder.ptr_type(builder.u8_type());
+    let i32_ty = builder.i32_type();
+
+    let func_id = builder
+        .begin_function("Thread_spawnWithStackSize")
+        .param("closure_obj", ptr_u8.clone())
+        .param("stack_size", i32_ty)
+        .returns(ptr_u8.clone())
+        .calling_convention(CallingConvention::C)
+        .build();
+
+    builder.set_current_function(func_id);
+
+    let entry = builder.create_block("entry");
+    builder.set_insert_point(entry);
+
+    let closure_obj = builder.get_param(0);
+    let stack_size = builder.get_param(1);
+
+    // Closure object is { fn_ptr, env_ptr }, as in Thread_spawn
+    let fn_ptr = builder.load(closure_obj, ptr_u8.clone());
+    let offset_8 = builder.const_i64(8);
+    let env_ptr_addr = builder.ptr_add(closure_obj, offset_8, ptr_u8.clone());
+    let env_ptr = builder.load(env_ptr_addr, ptr_u8.clone());
+    let stack_size = builder.cast(stack_size, IrType::I32, IrType::I64);
+
+    let spawn_id = builder
+        .get_function_by_name("rayzor_thread_spawn_with_stack_size")
+        .expect("rayzor_thread_spawn_with_stack_size not found");
+    let handle = builder
+        .call(spawn_id, vec![fn_ptr, env_ptr, stack_size])
+        .unwrap();
+
+    builder.ret(Some(handle));
+}
+
+/// Build: fn Coroutine_create(closure_obj: *u8) -> *Coroutine
+/// Closure object is { fn_ptr, env_ptr }, as in Thread_spawn; the coroutine
+/// gets the default stack size
+fn build_coroutine_create(builder: &mut MirBuilder) {
+    let ptr_u8 = builder.ptr_type(builder.u8_type());
+
+    let func_id = builder
+        .begin_function("Coroutine_create")
+        .param("closure_obj", ptr_u8.clone())
+        .returns(ptr_u8.clone())
+        .calling_convention(CallingConvention::C)
+        .build();
+
+    builder.set_current_function(func_id);
+
+    let entry = builder.create_block("entry");
+    builder.set_insert_point(entry);
+
+    let closure_obj = builder.get_param(0);
+    let fn_ptr = builder.load(closure_obj, ptr_u8.clone());
+    let offset_8 = builder.const_i64(8);
+    let env_ptr_addr = builder.ptr_add(closure_obj, offset_8, ptr_u8.clone());
+    let env_ptr = builder.load(env_ptr_addr, ptr_u8.clone());
+    let default_stack_size = builder.const_i64(0);
+
+    let create_id = builder
+        .get_function_by_name("rayzor_coroutine_create")
+        .expect("rayzor_coroutine_create not found");
+    let handle = builder
+        .call(create_id, vec![fn_ptr, env_ptr, default_stack_size])
+        .unwrap();
+
+    builder.ret(Some(handle));
+}
+
 /// Build: fn Thread_join(handle: *Thread) -> *u8 (i64)
 /// TODO: This should be generic Thread<T>.join() -> T
 /// For now it returns i64 and relies on caller to cast to correct type
@@ -25,11 +25,11 @@
 //! for failed expressions to maintain type safety.
 
 use crate::tast::node::HasSourceLocation;
-use crate::tast::{core::*, node::MemoryEffects, node::*, type_resolution, *};
+use crate::tast::{core::*, generators, node::MemoryEffects, node::*, type_resolution, *};
 use parser::{
     AbstractDecl, BinaryOp, ClassDecl, ClassField, ClassFieldKind, EnumConstructor, EnumDecl, Expr,
     ExprKind, Function, FunctionParam, HaxeFile, Import, InterfaceDecl, Metadata, Modifier,
-    ModuleField, Package, Type, TypeDeclaration, TypeParam, TypedefDecl, UnaryOp, Using,
+    ModuleFieldKind, Package, Type, TypeDeclaration, TypeParam, TypedefDecl, UnaryOp, Using,
 };
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -539,6 +539,11 @@ impl<'a> LoweringContext<'a> {
         }
     }
 
+    /// Source text of a span, if the span converter has the file
+    pub fn span_text(&self, span: &parser::Span) -> Option<&str> {
+        self.span_converter.as_ref()?.source_text(*span)
+    }
+
     /// Create a source location (fallback when span not available)
     pub fn create_location(&self) -> SourceLocation {
         SourceLocation::unknown()
@@ -606,10 +611,132 @@ pub struct AstLowering<'a> {
     pub pending_usings: Vec<String>,
     /// Whether we're currently lowering a static method body (no `this` available)
     in_static_method: bool,
+    /// Predicate methods synthesized for the @:requires/@:ensures contracts
+    /// of the methods lowered so far, added to the enclosing type's methods
+    contract_predicates: Vec<TypedFunction>,
     /// Ordered type parameter TypeIds for each generic class (class_symbol → [TypeParam TypeIds])
     class_type_params: HashMap<SymbolId, Vec<TypeId>>,
     /// Constructor symbol for each class (class_symbol → constructor SymbolId)
     class_constructor_symbols: HashMap<SymbolId, SymbolId>,
+    /// Contextual parameter types for the next untyped function literal
+    /// (e.g. `Int` for `x` in `ints.map(function(x) return x * 2)`)
+    lambda_param_hints: Option<Vec<TypeId>>,
+    /// Module-fields classes whose statics resolve unqualified in this file:
+    /// the file's own module first, then modules imported whole
+    module_field_classes: Vec<SymbolId>,
+    /// Module-level fields imported by name (`import pack.Module.field`),
+    /// mapped to (module-fields class, field)
+    module_field_imports: HashMap<InternedString, (SymbolId, SymbolId)>,
+}
+
+/// Metadata marking the synthetic class that holds a module's module-level fields
+const MODULE_FIELDS_META: &str = ":moduleFields";
+
+/// Name of the class holding the module-level fields of `file`: the module
+/// name, or `<Module>_Fields_` when the module also declares a type of that name.
+fn module_fields_class_name(file: &HaxeFile) -> String {
+    let module_name: String = std::path::Path::new(&file.filename)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("Module")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let declares_module_type = file.declarations.iter().any(|decl| {
+        let name = match decl {
+            TypeDeclaration::Class(c) => &c.name,
+            TypeDeclaration::Interface(i) => &i.name,
+            TypeDeclaration::Enum(e) => &e.name,
+            TypeDeclaration::Typedef(t) => &t.name,
+            TypeDeclaration::Abstract(a) => &a.name,
+            TypeDeclaration::Conditional(_) => return false,
+        };
+        *name == module_name
+    });
+    if declares_module_type {
+        format!("{}_Fields_", module_name)
+    } else {
+        module_name
+    }
+}
+
+/// Rewrite the module-level fields of `file` (Haxe 4 `var`, `final` and
+/// `function` outside any type) into public static fields of a synthetic
+/// class, declared first so its statics are lowered, registered as globals and
+/// initialized before the module's other types.
+///
+/// Returns None when the file has no module-level fields.
+fn desugar_module_fields(file: &HaxeFile) -> Option<HaxeFile> {
+    if file.module_fields.is_empty() {
+        return None;
+    }
+    let fields = file
+        .module_fields
+        .iter()
+        .map(|field| {
+            let mut modifiers = field.modifiers.clone();
+            if !modifiers.contains(&Modifier::Static) {
+                modifiers.push(Modifier::Static);
+            }
+            let kind = match &field.kind {
+                ModuleFieldKind::Var {
+                    name,
+                    type_hint,
+                    expr,
+                } => ClassFieldKind::Var {
+                    name: name.clone(),
+                    type_hint: type_hint.clone(),
+                    expr: expr.clone(),
+                },
+                ModuleFieldKind::Final {
+                    name,
+                    type_hint,
+                    expr,
+                } => ClassFieldKind::Final {
+                    name: name.clone(),
+                    type_hint: type_hint.clone(),
+                    expr: expr.clone(),
+                },
+                ModuleFieldKind::Function(func) => ClassFieldKind::Function(func.clone()),
+            };
+            ClassField {
+                meta: field.meta.clone(),
+                access: Some(field.access.unwrap_or(parser::Access::Public)),
+                modifiers,
+                kind,
+                span: field.span,
+            }
+        })
+        .collect();
+    let fields_class = ClassDecl {
+        meta: vec![Metadata {
+            name: MODULE_FIELDS_META.to_string(),
+            params: Vec::new(),
+            span: file.span,
+        }],
+        access: Some(parser::Access::Public),
+        modifiers: Vec::new(),
+        name: module_fields_class_name(file),
+        type_params: Vec::new(),
+        extends: None,
+        implements: Vec::new(),
+        fields,
+        span: file.span,
+    };
+
+    let mut desugared = file.clone();
+    desugared.module_fields.clear();
+    desugared
+        .declarations
+        .insert(0, TypeDeclaration::Class(fields_class));
+    Some(desugared)
+}
+
+fn is_module_fields_class(class_decl: &ClassDecl) -> bool {
+    class_decl
+        .meta
+        .iter()
+        .any(|meta| meta.name == MODULE_FIELDS_META)
 }
 
 /// Result of type parameter substitution for generic method return types
@@ -666,7 +793,14 @@ impl<'a> AstLowering<'a> {
                 "gpuStruct" => {
                     flags = flags.union(SymbolFlags::GPU_STRUCT);
                 }
+                "struct" => flags = flags.union(SymbolFlags::STRUCT),
+                "keepLayout" => flags = flags.union(SymbolFlags::KEEP_LAYOUT),
+                "moduleFields" => flags = flags.union(SymbolFlags::MODULE_FIELDS),
                 "no_mangle" => flags = flags.union(SymbolFlags::NO_MANGLE),
+                "deprecated" => {
+                    flags = flags.union(SymbolFlags::DEPRECATED);
+                    self.record_deprecation(meta, symbol_id);
+                }
                 "frameworks" | "cInclude" | "cSource" | "clib" => {
                     // @:frameworks(["Accelerate"]), @:cInclude(["vendor/stb"]), @:cSource(["lib.c"])
                     if let Some(first_param) = meta.params.first() {
@@ -699,6 +833,25 @@ impl<'a> AstLowering<'a> {
         flags
     }
 
+    /// Mark `symbol_id` deprecated from `@:deprecated`, `@:deprecated("use Y
+    /// instead")` or `@:deprecated("use Y instead", "2.0")`
+    fn record_deprecation(&mut self, meta: &parser::haxe_ast::Metadata, symbol_id: SymbolId) {
+        let mut strings = meta.params.iter().map(|param| match &param.kind {
+            parser::haxe_ast::ExprKind::String(s) => Some(self.context.string_interner.intern(s)),
+            _ => None,
+        });
+        let deprecation = crate::tast::symbols::Deprecation {
+            message: strings.next().flatten(),
+            since: strings.next().flatten(),
+        };
+        self.context
+            .symbol_table
+            .add_symbol_flags(symbol_id, crate::tast::symbols::SymbolFlags::DEPRECATED);
+        if let Some(sym) = self.context.symbol_table.get_symbol_mut(symbol_id) {
+            sym.deprecation = Some(deprecation);
+        }
+    }
+
     /// Resolve a TypeId through TypeAlias chains to find the underlying type.
     fn resolve_alias_chain(type_table: &TypeTable, type_id: TypeId) -> TypeId {
         let mut current = type_id;
@@ -1071,6 +1224,17 @@ impl<'a> AstLowering<'a> {
             }
         }
 
+        // Module-level fields: imported by name, then this module's own and
+        // those of whole-module imports
+        if let Some(&(_, field_symbol)) = self.module_field_imports.get(&name) {
+            return Some(field_symbol);
+        }
+        for &fields_class in &self.module_field_classes {
+            if let Some(member) = self.static_member_of(fields_class, name) {
+                return Some(member);
+            }
+        }
+
         // Fallback: explicitly check the global root scope (ScopeId::first())
         // This is needed for symbols like enum variants that are registered globally
         // but may not be reachable through the current scope's parent chain
@@ -1085,6 +1249,26 @@ impl<'a> AstLowering<'a> {
         None
     }
 
+    /// Find a static field or method named `name` on `class_symbol`, whether
+    /// the class was lowered by this lowering pass or an earlier module's.
+    fn static_member_of(&self, class_symbol: SymbolId, name: InternedString) -> Option<SymbolId> {
+        let tracked_fields = self.class_fields.get(&class_symbol).into_iter().flatten();
+        let tracked_methods = self.class_methods.get(&class_symbol).into_iter().flatten();
+        if let Some((_, member, _)) = tracked_fields
+            .chain(tracked_methods)
+            .find(|(member_name, _, is_static)| *member_name == name && *is_static)
+        {
+            return Some(*member);
+        }
+
+        let class_scope = self.context.symbol_table.get_symbol(class_symbol)?.scope_id;
+        self.context
+            .symbol_table
+            .lookup_symbol(class_scope, name)
+            .filter(|member| member.is_static())
+            .map(|member| member.id)
+    }
+
     pub fn new(
         string_interner: &'a mut StringInterner,
         string_interner_rc: Rc<RefCell<StringInterner>>,
@@ -1117,8 +1301,12 @@ impl<'a> AstLowering<'a> {
             using_modules: Vec::new(),
             pending_usings: Vec::new(),
             in_static_method: false,
+            contract_predicates: Vec::new(),
             class_type_params: HashMap::new(),
             class_constructor_symbols: HashMap::new(),
+            lambda_param_hints: None,
+            module_field_classes: Vec::new(),
+            module_field_imports: HashMap::new(),
         }
     }
 
@@ -1186,6 +1374,27 @@ impl<'a> AstLowering<'a> {
     pub fn lower_file(&mut self, file: &HaxeFile) -> LoweringResult<TypedFile> {
         // Optimizer barrier
 
+        // Module-level fields are lowered as statics of a synthetic class
+        let desugared;
+        let file = match desugar_module_fields(file) {
+            Some(with_class) => {
+                desugared = with_class;
+                &desugared
+            }
+            None => file,
+        };
+        // Generator methods return a coroutine-backed rayzor.Generator
+        let with_generators;
+        let file = match generators::desugar_generators(file) {
+            Some(rewritten) => {
+                with_generators = rewritten;
+                &with_generators
+            }
+            None => file,
+        };
+        self.module_field_classes.clear();
+        self.module_field_imports.clear();
+
         // Create TypedFile with the shared interner from the pipeline
         let mut typed_file = TypedFile::new(Rc::clone(&self.context.string_interner_rc));
 
@@ -1259,14 +1468,6 @@ impl<'a> AstLowering<'a> {
             }
         }
 
-        // Process module-level fields - collect errors but continue
-        for module_field in &file.module_fields {
-            match self.lower_module_field(module_field) {
-                Ok(typed_field) => typed_file.module_fields.push(typed_field),
-                Err(e) => self.collected_errors.push(e),
-            }
-        }
-
         // First pass: Pre-register all type declarations in the symbol table
         // Skip this if CompilationUnit has already pre-registered all files
         if !self.skip_pre_registration {
@@ -1289,6 +1490,20 @@ impl<'a> AstLowering<'a> {
             }
         }
 
+        // This module's own module-level fields resolve unqualified everywhere in it
+        if let Some(TypeDeclaration::Class(fields_class)) = file.declarations.first() {
+            if is_module_fields_class(fields_class) {
+                let name = self.context.intern_string(&fields_class.name);
+                if let Some(class_symbol) = self
+                    .context
+                    .symbol_table
+                    .lookup_symbol(ScopeId::first(), name)
+                {
+                    self.module_field_classes.insert(0, class_symbol.id);
+                }
+            }
+        }
+
         // Second pass: Process declarations with full type resolution
         for (i, declaration) in file.declarations.iter().enumerate() {
             match self.lower_declaration(declaration) {
@@ -1331,6 +1546,15 @@ impl<'a> AstLowering<'a> {
     /// without lowering their bodies. Used for multi-file compilation where all
     /// type names need to be available before any file is fully compiled.
     pub fn pre_register_file(&mut self, file: &HaxeFile) -> LoweringResult<()> {
+        let desugared;
+        let file = match desugar_module_fields(file) {
+            Some(with_class) => {
+                desugared = with_class;
+                &desugared
+            }
+            None => file,
+        };
+
         // Process package declaration to set up the namespace context
         if let Some(package) = &file.package {
             // Create or get package in namespace resolver
@@ -1709,6 +1933,7 @@ impl<'a> AstLowering<'a> {
                 c_includes: None,
                 c_sources: None,
                 c_libs: None,
+                deprecation: None,
             };
 
             // Add symbol to symbol table
@@ -1886,6 +2111,7 @@ impl<'a> AstLowering<'a> {
                 c_includes: None,
                 c_sources: None,
                 c_libs: None,
+                deprecation: None,
             };
 
             self.context.symbol_table.add_symbol(func_symbol);
@@ -2278,6 +2504,37 @@ impl<'a> AstLowering<'a> {
             _ => None,
         };
 
+        // Module-level fields: `import pack.Module;` brings all of them into
+        // scope, `import pack.Module.field;` just the one
+        match &import.mode {
+            parser::ImportMode::Normal => {
+                if let Some(fields_class) = self.module_fields_class_for(&import.path) {
+                    if !self.module_field_classes.contains(&fields_class) {
+                        self.module_field_classes.push(fields_class);
+                    }
+                }
+            }
+            parser::ImportMode::Field(field) => {
+                let field_name = self.context.intern_string(field);
+                let member = self
+                    .module_fields_class_for(&import.path)
+                    .and_then(|fields_class| {
+                        let member = self.static_member_of(fields_class, field_name)?;
+                        Some((fields_class, member))
+                    });
+                if let Some(member) = member {
+                    self.module_field_imports.insert(field_name, member);
+                    return Ok(TypedImport {
+                        module_path: self.context.intern_string(&import.path.join(".")),
+                        imported_symbols,
+                        alias,
+                        source_location: self.context.create_location_from_span(import.span),
+                    });
+                }
+            }
+            _ => {}
+        }
+
         // Create import entry for the import resolver
         let package_path: Vec<_> = import
             .path
@@ -2441,6 +2698,45 @@ impl<'a> AstLowering<'a> {
         })
     }
 
+    /// The module-fields class declaring `function_symbol`, if it is a
+    /// module-level function in scope in this file.
+    fn module_function_owner(&self, function_symbol: SymbolId) -> Option<SymbolId> {
+        let symbol = self.context.symbol_table.get_symbol(function_symbol)?;
+        if symbol.kind != crate::tast::symbols::SymbolKind::Function {
+            return None;
+        }
+        self.module_field_classes
+            .iter()
+            .copied()
+            .chain(self.module_field_imports.values().map(|&(class, _)| class))
+            .find(|&fields_class| {
+                self.static_member_of(fields_class, symbol.name) == Some(function_symbol)
+            })
+    }
+
+    /// Find the module-fields class of the module at `path` (`pack.Module`),
+    /// if that module declares module-level fields.
+    fn module_fields_class_for(&mut self, path: &[String]) -> Option<SymbolId> {
+        let (module_name, package) = path.split_last()?;
+        let package: Vec<_> = package
+            .iter()
+            .map(|s| self.context.string_interner.intern(s))
+            .collect();
+        [module_name.clone(), format!("{}_Fields_", module_name)]
+            .iter()
+            .filter_map(|class_name| {
+                let class_name = self.context.string_interner.intern(class_name);
+                let class_path = super::namespace::QualifiedPath::new(package.clone(), class_name);
+                self.context.namespace_resolver.lookup_symbol(&class_path)
+            })
+            .find(|&class_symbol| {
+                self.context
+                    .symbol_table
+                    .get_symbol(class_symbol)
+                    .is_some_and(|sym| sym.flags.contains(SymbolFlags::MODULE_FIELDS))
+            })
+    }
+
     /// Lower a using declaration
     fn lower_using(&mut self, using: &Using) -> LoweringResult<TypedUsing> {
         let module_path_str = using.path.join(".");
@@ -2535,80 +2831,6 @@ impl<'a> AstLowering<'a> {
         })
     }
 
-    /// Lower a module field
-    fn lower_module_field(
-        &mut self,
-        module_field: &ModuleField,
-    ) -> LoweringResult<TypedModuleField> {
-        let field_name = match &module_field.kind {
-            parser::ModuleFieldKind::Var { name, .. } => name.clone(),
-            parser::ModuleFieldKind::Final { name, .. } => name.clone(),
-            parser::ModuleFieldKind::Function(func) => func.name.clone(),
-        };
-
-        let interned_name = self.context.intern_string(&field_name);
-        let field_symbol = self.context.symbol_table.create_variable(interned_name);
-
-        let kind = match &module_field.kind {
-            parser::ModuleFieldKind::Var {
-                name: _,
-                type_hint,
-                expr,
-            } => {
-                let field_type = if let Some(type_hint) = type_hint {
-                    self.lower_type(type_hint)?
-                } else {
-                    self.context.type_table.borrow().dynamic_type()
-                };
-
-                let initializer = if let Some(expr) = expr {
-                    Some(self.lower_expression(expr)?)
-                } else {
-                    None
-                };
-
-                TypedModuleFieldKind::Var {
-                    field_type,
-                    initializer,
-                    mutability: crate::tast::Mutability::Mutable,
-                }
-            }
-            parser::ModuleFieldKind::Final {
-                name: _,
-                type_hint,
-                expr,
-            } => {
-                let field_type = if let Some(type_hint) = type_hint {
-                    self.lower_type(type_hint)?
-                } else {
-                    self.context.type_table.borrow().dynamic_type()
-                };
-
-                let initializer = if let Some(expr) = expr {
-                    Some(self.lower_expression(expr)?)
-                } else {
-                    None
-                };
-
-                TypedModuleFieldKind::Final {
-                    field_type,
-                    initializer,
-                }
-            }
-            parser::ModuleFieldKind::Function(func) => {
-                TypedModuleFieldKind::Function(self.lower_function_object(func)?)
-            }
-        };
-
-        Ok(TypedModuleField {
-            symbol_id: field_symbol,
-            name: interned_name,
-            kind,
-            visibility: self.lower_access(&module_field.access),
-            source_location: self.context.create_location_from_span(module_field.span),
-        })
-    }
-
     /// Lower a declaration
     fn lower_declaration(
         &mut self,
@@ -2753,6 +2975,26 @@ impl<'a> AstLowering<'a> {
             None
         };
 
+        // @:struct value types are copied by their flat layout, so they can
+        // neither extend a class nor be extended
+        let extends_value_struct = extends.is_some_and(|parent| {
+            let parent_symbol = self
+                .context
+                .type_table
+                .borrow()
+                .get(parent)
+                .and_then(|t| t.symbol_id());
+            parent_symbol
+                .and_then(|sym| self.context.symbol_table.get_symbol(sym))
+                .is_some_and(|sym| sym.flags.is_value_struct())
+        });
+        if extends.is_some() && (symbol_flags.is_value_struct() || extends_value_struct) {
+            return Err(LoweringError::InvalidModifiers {
+                modifiers: vec!["@:struct".to_string(), "extends".to_string()],
+                location: self.context.create_location_from_span(class_decl.span),
+            });
+        }
+
         // Copy parent FIELDS and METHODS before processing child's members
         // This ensures:
         // 1. Field inheritance works (constructor can access parent fields)
@@ -2901,13 +3143,23 @@ impl<'a> AstLowering<'a> {
                 _ => {
                     // Handle regular fields (var, final, property)
                     match self.lower_field(field) {
-                        Ok(typed_field) => fields.push(typed_field),
+                        Ok(typed_field) => {
+                            if let Err(e) =
+                                self.check_fixed_array_field(field, &typed_field, symbol_flags)
+                            {
+                                self.context.add_error(e);
+                            }
+                            fields.push(typed_field)
+                        }
                         Err(e) => self.context.add_error(e),
                     }
                 }
             }
         }
 
+        // Contract predicates of the methods above
+        methods.append(&mut self.contract_predicates);
+
         // Note: Parent fields and methods were already copied before processing members
         // This ensures:
         // 1. Field/method inheritance works (child can access parent members)
@@ -2923,152 +3175,30 @@ impl<'a> AstLowering<'a> {
 
         self.context.exit_scope();
 
-        // Auto-inject synthetic cdef() static method for @:cstruct classes
+        // Auto-inject synthetic static methods, intercepted at MIR level:
+        // cdef() for @:cstruct, gpuDef()/gpuSize()/gpuAlignment() for @:gpuStruct,
+        // and layout() (shared layout descriptor) for every laid-out class
+        let string_type = self.context.type_table.borrow().string_type();
+        let int_type = self.context.type_table.borrow().int_type();
+        let mut synthetic_methods = Vec::new();
         if symbol_flags.is_cstruct() {
-            let cdef_name = self.context.intern_string("cdef");
-            // Create a symbol for the synthetic method
-            let cdef_symbol = self
-                .context
-                .symbol_table
-                .create_function_in_scope(cdef_name, class_scope);
-            self.context
-                .symbol_table
-                .add_symbol_flags(cdef_symbol, crate::tast::symbols::SymbolFlags::STATIC);
-            if let Some(scope) = self.context.scope_tree.get_scope_mut(class_scope) {
-                scope.add_symbol(cdef_symbol, cdef_name);
-            }
-            // Set return type to String
-            let string_type = self.context.type_table.borrow().string_type();
-            let fn_type = self
-                .context
-                .type_table
-                .borrow_mut()
-                .create_function_type(vec![], string_type);
-            self.context
-                .symbol_table
-                .update_symbol_type(cdef_symbol, fn_type);
-
-            // Register in class_methods
-            if let Some(methods_list) = self.class_methods.get_mut(&class_symbol) {
-                methods_list.push((cdef_name, cdef_symbol, true));
-            }
-
-            // Add synthetic TypedFunction (empty body — intercepted at MIR level)
-            methods.push(crate::tast::node::TypedFunction {
-                symbol_id: cdef_symbol,
-                name: cdef_name,
-                parameters: vec![],
-                return_type: string_type,
-                body: vec![],
-                visibility: crate::tast::symbols::Visibility::Public,
-                effects: crate::tast::node::FunctionEffects {
-                    can_throw: false,
-                    async_kind: crate::tast::node::AsyncKind::Sync,
-                    is_pure: true,
-                    is_inline: true,
-                    exception_types: vec![],
-                    memory_effects: crate::tast::node::MemoryEffects::default(),
-                    resource_effects: crate::tast::node::ResourceEffects::default(),
-                },
-                type_parameters: vec![],
-                is_static: true,
-                source_location: crate::tast::symbols::SourceLocation {
-                    file_id: 0,
-                    line: 0,
-                    column: 0,
-                    byte_offset: 0,
-                },
-                metadata: crate::tast::node::FunctionMetadata {
-                    complexity_score: 0,
-                    statement_count: 0,
-                    is_recursive: false,
-                    call_count: 0,
-                    is_override: false,
-                    overload_signatures: vec![],
-                    operator_metadata: vec![],
-                    is_array_access: false,
-                    is_from_conversion: false,
-                    is_to_conversion: false,
-                    memory_annotations: vec![],
-                },
-            });
+            synthetic_methods.push(("cdef", string_type));
         }
-
-        // Auto-inject synthetic gpuDef(), gpuSize(), gpuAlignment() for @:gpuStruct classes
         if symbol_flags.is_gpu_struct() {
-            let string_type = self.context.type_table.borrow().string_type();
-            let int_type = self.context.type_table.borrow().int_type();
-
-            // Helper to create a synthetic static method with empty body
-            let synthetic_names = [
-                ("gpuDef", string_type),
-                ("gpuSize", int_type),
-                ("gpuAlignment", int_type),
-            ];
-            for (name_str, ret_type) in &synthetic_names {
-                let method_name = self.context.intern_string(name_str);
-                let method_symbol = self
-                    .context
-                    .symbol_table
-                    .create_function_in_scope(method_name, class_scope);
-                self.context
-                    .symbol_table
-                    .add_symbol_flags(method_symbol, crate::tast::symbols::SymbolFlags::STATIC);
-                if let Some(scope) = self.context.scope_tree.get_scope_mut(class_scope) {
-                    scope.add_symbol(method_symbol, method_name);
-                }
-                let fn_type = self
-                    .context
-                    .type_table
-                    .borrow_mut()
-                    .create_function_type(vec![], *ret_type);
-                self.context
-                    .symbol_table
-                    .update_symbol_type(method_symbol, fn_type);
-
-                if let Some(methods_list) = self.class_methods.get_mut(&class_symbol) {
-                    methods_list.push((method_name, method_symbol, true));
-                }
-
-                methods.push(crate::tast::node::TypedFunction {
-                    symbol_id: method_symbol,
-                    name: method_name,
-                    parameters: vec![],
-                    return_type: *ret_type,
-                    body: vec![],
-                    visibility: crate::tast::symbols::Visibility::Public,
-                    effects: crate::tast::node::FunctionEffects {
-                        can_throw: false,
-                        async_kind: crate::tast::node::AsyncKind::Sync,
-                        is_pure: true,
-                        is_inline: true,
-                        exception_types: vec![],
-                        memory_effects: crate::tast::node::MemoryEffects::default(),
-                        resource_effects: crate::tast::node::ResourceEffects::default(),
-                    },
-                    type_parameters: vec![],
-                    is_static: true,
-                    source_location: crate::tast::symbols::SourceLocation {
-                        file_id: 0,
-                        line: 0,
-                        column: 0,
-                        byte_offset: 0,
-                    },
-                    metadata: crate::tast::node::FunctionMetadata {
-                        complexity_score: 0,
-                        statement_count: 0,
-                        is_recursive: false,
-                        call_count: 0,
-                        is_override: false,
-                        overload_signatures: vec![],
-                        operator_metadata: vec![],
-                        is_array_access: false,
-                        is_from_conversion: false,
-                        is_to_conversion: false,
-                        memory_annotations: vec![],
-                    },
-                });
-            }
+            synthetic_methods.push(("gpuDef", string_type));
+            synthetic_methods.push(("gpuSize", int_type));
+            synthetic_methods.push(("gpuAlignment", int_type));
+        }
+        if symbol_flags.is_cstruct()
+            || symbol_flags.is_value_struct()
+            || symbol_flags.is_gpu_struct()
+        {
+            synthetic_methods.push(("layout", string_type));
+        }
+        for (name, ret_type) in synthetic_methods {
+            let method =
+                self.create_synthetic_static_method(class_symbol, class_scope, name, ret_type);
+            methods.push(method);
         }
 
         // Extract memory safety annotations from metadata
@@ -3077,6 +3207,17 @@ impl<'a> AstLowering<'a> {
         // Extract derived traits from @:derive metadata
         let mut derived_traits = self.extract_derived_traits(class_decl);
 
+        // Externs have no runtime type to hang metadata on
+        let is_extern = class_decl
+            .modifiers
+            .iter()
+            .any(|m| matches!(m, parser::haxe_ast::Modifier::Extern));
+        let metadata = if is_extern {
+            Vec::new()
+        } else {
+            self.lower_user_metadata(&class_decl.meta)
+        };
+
         // Create typed class first (needed for validation)
         let typed_class = TypedClass {
             symbol_id: class_symbol,
@@ -3091,6 +3232,7 @@ impl<'a> AstLowering<'a> {
             source_location: self.context.create_location_from_span(class_decl.span),
             memory_annotations,
             derived_traits: derived_traits.clone(),
+            metadata,
         };
 
         // Validate derived traits against field types
@@ -3297,6 +3439,8 @@ impl<'a> AstLowering<'a> {
             variants.push(self.lower_enum_variant(variant, enum_type_id, enum_symbol)?);
         }
 
+        let metadata = self.lower_user_metadata(&enum_decl.meta);
+
         self.context.pop_type_parameters();
         self.context.exit_scope();
 
@@ -3307,6 +3451,7 @@ impl<'a> AstLowering<'a> {
             type_parameters: type_params,
             visibility: self.lower_access(&enum_decl.access),
             source_location: self.context.create_location_from_span(enum_decl.span),
+            metadata,
         };
 
         Ok(TypedDeclaration::Enum(typed_enum))
@@ -3613,6 +3758,8 @@ impl<'a> AstLowering<'a> {
             }
         }
 
+        methods.append(&mut self.contract_predicates);
+
         // Pop abstract from class context stack
         self.context.class_context_stack.pop();
 
@@ -3820,6 +3967,138 @@ impl<'a> AstLowering<'a> {
         }
     }
 
+    /// Declare a body-less static method `name():ret_type` in a class scope.
+    /// Calls to it are replaced with a constant during MIR lowering.
+    fn create_synthetic_static_method(
+        &mut self,
+        class_symbol: SymbolId,
+        class_scope: ScopeId,
+        name: &str,
+        ret_type: TypeId,
+    ) -> TypedFunction {
+        let method_name = self.context.intern_string(name);
+        let method_symbol = self
+            .context
+            .symbol_table
+            .create_function_in_scope(method_name, class_scope);
+        self.context
+            .symbol_table
+            .add_symbol_flags(method_symbol, crate::tast::symbols::SymbolFlags::STATIC);
+        if let Some(scope) = self.context.scope_tree.get_scope_mut(class_scope) {
+            scope.add_symbol(method_symbol, method_name);
+        }
+        let fn_type = self
+            .context
+            .type_table
+            .borrow_mut()
+            .create_function_type(vec![], ret_type);
+        self.context
+            .symbol_table
+            .update_symbol_type(method_symbol, fn_type);
+
+        if let Some(methods_list) = self.class_methods.get_mut(&class_symbol) {
+            methods_list.push((method_name, method_symbol, true));
+        }
+
+        TypedFunction {
+            symbol_id: method_symbol,
+            name: method_name,
+            parameters: vec![],
+            return_type: ret_type,
+            body: vec![],
+            visibility: crate::tast::symbols::Visibility::Public,
+            effects: crate::tast::node::FunctionEffects {
+                can_throw: false,
+                async_kind: crate::tast::node::AsyncKind::Sync,
+                is_pure: true,
+                is_inline: true,
+                exception_types: vec![],
+                memory_effects: crate::tast::node::MemoryEffects::default(),
+                resource_effects: crate::tast::node::ResourceEffects::default(),
+            },
+            type_parameters: vec![],
+            is_static: true,
+            source_location: crate::tast::symbols::SourceLocation {
+                file_id: 0,
+                line: 0,
+                column: 0,
+                byte_offset: 0,
+            },
+            metadata: crate::tast::node::FunctionMetadata {
+                complexity_score: 0,
+                statement_count: 0,
+                is_recursive: false,
+                call_count: 0,
+                is_override: false,
+                overload_signatures: vec![],
+                operator_metadata: vec![],
+                is_array_access: false,
+                is_from_conversion: false,
+                is_to_conversion: false,
+                is_no_throw: false,
+                memory_annotations: vec![],
+                user_metadata: vec![],
+                contracts: vec![],
+            },
+        }
+    }
+
+    /// Validate `@:fixed(N)` on a field: an inline array of N elements is only
+    /// laid out by @:cstruct and @:struct classes, and holds Int or Float
+    fn check_fixed_array_field(
+        &mut self,
+        field: &ClassField,
+        typed_field: &TypedField,
+        class_flags: SymbolFlags,
+    ) -> LoweringResult<()> {
+        let Some(meta) = field.meta.iter().find(|m| m.name == "fixed") else {
+            return Ok(());
+        };
+        let location = self.context.create_location_from_span(field.span);
+
+        let placement = if typed_field.is_static {
+            Some("static")
+        } else if typed_field.initializer.is_some() {
+            Some("initializer")
+        } else if !(class_flags.is_cstruct() || class_flags.is_value_struct()) {
+            Some("class without @:cstruct or @:struct")
+        } else {
+            None
+        };
+        if let Some(placement) = placement {
+            return Err(LoweringError::InvalidModifiers {
+                modifiers: vec!["@:fixed".to_string(), placement.to_string()],
+                location,
+            });
+        }
+
+        let count_ok = match meta.params.as_slice() {
+            [param] => {
+                matches!(param.kind, parser::ExprKind::Int(n) if n > 0 && n <= u32::MAX as i64)
+            }
+            _ => false,
+        };
+        let element_ok = {
+            let type_table = self.context.type_table.borrow();
+            match type_table.get(typed_field.field_type).map(|t| &t.kind) {
+                Some(TypeKind::Array { element_type }) => matches!(
+                    type_table.get(*element_type).map(|t| &t.kind),
+                    Some(TypeKind::Int | TypeKind::Float)
+                ),
+                _ => false,
+            }
+        };
+        if !count_ok || !element_ok {
+            return Err(LoweringError::GenericParameterError {
+                message: "@:fixed(N) needs a positive Int literal N on an Array<Int> or \
+                          Array<Float> field"
+                    .to_string(),
+                location,
+            });
+        }
+        Ok(())
+    }
+
     /// Lower a field
     fn lower_field(&mut self, field: &ClassField) -> LoweringResult<TypedField> {
         let (field_name, field_type, initializer, mutability, is_static, property_access) =
@@ -3849,12 +4128,21 @@ impl<'a> AstLowering<'a> {
                         .modifiers
                         .iter()
                         .any(|m| matches!(m, parser::Modifier::Static));
+                    // `inline var` and `final var` cannot be reassigned
+                    let mutability =
+                        if field.modifiers.iter().any(|m| {
+                            matches!(m, parser::Modifier::Inline | parser::Modifier::Final)
+                        }) {
+                            crate::tast::Mutability::Immutable
+                        } else {
+                            crate::tast::Mutability::Mutable
+                        };
 
                     (
                         name.clone(),
                         field_type,
                         initializer,
-                        crate::tast::Mutability::Mutable,
+                        mutability,
                         is_static,
                         None, // No property access for regular var fields
                     )
@@ -3958,10 +4246,22 @@ impl<'a> AstLowering<'a> {
             };
 
         let interned_field_name = self.context.intern_string(&field_name);
-        let field_symbol = self
-            .context
-            .symbol_table
-            .create_variable(interned_field_name);
+        let field_symbol = if is_static {
+            // Statics live in the class scope so they resolve through the class
+            // symbol from other modules (e.g. imported module-level fields)
+            let symbol = self
+                .context
+                .symbol_table
+                .create_variable_in_scope(interned_field_name, self.context.current_scope);
+            self.context
+                .symbol_table
+                .add_symbol_flags(symbol, crate::tast::symbols::SymbolFlags::STATIC);
+            symbol
+        } else {
+            self.context
+                .symbol_table
+                .create_variable(interned_field_name)
+        };
 
         // Update the field symbol with its type
         self.context
@@ -3984,9 +4284,16 @@ impl<'a> AstLowering<'a> {
             }
         }
 
+        for meta in &field.meta {
+            if meta.name.strip_prefix(':').unwrap_or(&meta.name) == "deprecated" {
+                self.record_deprecation(meta, field_symbol);
+            }
+        }
+
         // Process modifiers and access separately
         let modifier_info = self.lower_modifiers(&field.modifiers)?;
         let visibility = self.lower_access(&field.access);
+        let metadata = self.lower_user_metadata(&field.meta);
 
         Ok(TypedField {
             symbol_id: field_symbol,
@@ -3998,6 +4305,7 @@ impl<'a> AstLowering<'a> {
             is_static: modifier_info.is_static,
             property_access,
             source_location: self.context.create_location_from_span(field.span),
+            metadata,
         })
     }
 
@@ -4056,6 +4364,7 @@ impl<'a> AstLowering<'a> {
             name: variant_name,
             parameters,
             source_location: self.context.create_location(),
+            metadata: self.lower_user_metadata(&variant.meta),
         })
     }
 
@@ -4263,6 +4572,8 @@ impl<'a> AstLowering<'a> {
                         }
                     }
                 }
+            } else if name == "deprecated" {
+                self.record_deprecation(meta, function_symbol);
             } else if matches!(name, "frameworks" | "cInclude" | "cSource" | "clib") {
                 if let Some(first_param) = meta.params.first() {
                     if let parser::haxe_ast::ExprKind::Array(elements) = &first_param.kind {
@@ -4402,6 +4713,21 @@ impl<'a> AstLowering<'a> {
         let is_from_conversion = field.meta.iter().any(|m| m.name == "from");
         let is_to_conversion = field.meta.iter().any(|m| m.name == "to");
 
+        // Check for @:noThrow metadata (verified by effect analysis)
+        let is_no_throw = field.meta.iter().any(|m| m.name == "noThrow");
+
+        let user_metadata = self.lower_user_metadata(&field.meta);
+
+        let contracts = self.lower_contracts(
+            field,
+            func,
+            class_scope,
+            &parameters,
+            &type_params,
+            return_type,
+            is_static_method,
+        );
+
         self.context.pop_type_parameters();
         self.context.exit_scope();
 
@@ -4427,21 +4753,220 @@ impl<'a> AstLowering<'a> {
             is_static: modifier_info.is_static,
             source_location: self.context.create_location_from_span(field.span),
             metadata: FunctionMetadata {
-                complexity_score: self.calculate_complexity(&func.body),
-                statement_count: body_len,
-                is_recursive: false, // Recursion detection requires call graph analysis
-                call_count: 0,
-                is_override: modifier_info.is_override,
-                overload_signatures,
-                operator_metadata,
-                is_array_access,
-                is_from_conversion,
-                is_to_conversion,
-                memory_annotations: self.extract_memory_annotations(&field.meta),
+                complexity_score: self.calculate_complexity(&func.body),
+                statement_count: body_len,
+                is_recursive: false, // Recursion detection requires call graph analysis
+                call_count: 0,
+                is_override: modifier_info.is_override,
+                overload_signatures,
+                operator_metadata,
+                is_array_access,
+                is_from_conversion,
+                is_to_conversion,
+                is_no_throw,
+                memory_annotations: self.extract_memory_annotations(&field.meta),
+                user_metadata,
+                contracts,
+            },
+        })
+    }
+
+    /// Lower the `@:requires(cond)` / `@:ensures(cond)` metadata of a method,
+    /// synthesizing one predicate method per contract into
+    /// `contract_predicates`. Malformed contracts are reported and skipped.
+    #[allow(clippy::too_many_arguments)]
+    fn lower_contracts(
+        &mut self,
+        field: &ClassField,
+        func: &Function,
+        class_scope: ScopeId,
+        parameters: &[TypedParameter],
+        type_params: &[TypedTypeParameter],
+        return_type: TypeId,
+        is_static: bool,
+    ) -> Vec<FunctionContract> {
+        let mut contracts = Vec::new();
+        for meta in &field.meta {
+            let kind = match meta.name.strip_prefix(':').unwrap_or(&meta.name) {
+                "requires" => ContractKind::Requires,
+                "ensures" => ContractKind::Ensures,
+                _ => continue,
+            };
+            let location = self.context.create_location_from_span(meta.span);
+            let condition = match meta.params.as_slice() {
+                [condition] if func.name != "new" => condition,
+                _ => {
+                    let problem = if func.name == "new" {
+                        "is not supported on constructors"
+                    } else {
+                        "takes exactly one Bool condition"
+                    };
+                    self.context
+                        .add_error(LoweringError::GenericParameterError {
+                            message: format!("@:{} {}", kind.name(), problem),
+                            location,
+                        });
+                    continue;
+                }
+            };
+            let predicate = self.lower_contract_predicate(
+                kind,
+                &format!("__{}_{}_{}", kind.name(), func.name, contracts.len()),
+                class_scope,
+                parameters,
+                type_params,
+                return_type,
+                is_static,
+                condition,
+                location,
+            );
+            match predicate {
+                Ok(predicate) => {
+                    // One line, however the condition was written
+                    let text = self
+                        .context
+                        .span_text(&condition.span)
+                        .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+                        .unwrap_or_else(|| "<condition>".to_string());
+                    contracts.push(FunctionContract {
+                        kind,
+                        predicate: predicate.symbol_id,
+                        condition: text,
+                        source_location: location,
+                    });
+                    self.contract_predicates.push(predicate);
+                }
+                Err(e) => self.context.add_error(e),
+            }
+        }
+        contracts
+    }
+
+    /// Synthesize `function <name>(<params>[, result]):Bool return <condition>;`
+    /// beside the method, with its own copies of the parameters. It has the
+    /// method's static-ness, so conditions can read fields through `this`;
+    /// `result` is the returned value, for postconditions of non-Void methods.
+    #[allow(clippy::too_many_arguments)]
+    fn lower_contract_predicate(
+        &mut self,
+        kind: ContractKind,
+        name: &str,
+        class_scope: ScopeId,
+        parameters: &[TypedParameter],
+        type_params: &[TypedTypeParameter],
+        return_type: TypeId,
+        is_static: bool,
+        condition: &parser::Expr,
+        location: SourceLocation,
+    ) -> LoweringResult<TypedFunction> {
+        let name = self.context.intern_string(name);
+        let symbol = self
+            .context
+            .symbol_table
+            .create_function_in_scope(name, class_scope);
+        if is_static {
+            self.context
+                .symbol_table
+                .add_symbol_flags(symbol, crate::tast::symbols::SymbolFlags::STATIC);
+        }
+        if let Some(scope) = self.context.scope_tree.get_scope_mut(class_scope) {
+            scope.add_symbol(symbol, name);
+        }
+        self.context.update_symbol_qualified_name(symbol);
+        if let Some(class_symbol) = self.context.class_context_stack.last().copied() {
+            if let Some(methods_list) = self.class_methods.get_mut(&class_symbol) {
+                methods_list.push((name, symbol, is_static));
+            }
+        }
+
+        // The copies shadow the method's own parameters
+        self.context.enter_scope(ScopeKind::Function);
+        let mut predicate_params = Vec::with_capacity(parameters.len() + 1);
+        for param in parameters {
+            predicate_params.push(self.declare_contract_parameter(
+                param.name,
+                param.param_type,
+                param.source_location,
+            ));
+        }
+        let (void_type, bool_type) = {
+            let type_table = self.context.type_table.borrow();
+            (type_table.void_type(), type_table.bool_type())
+        };
+        if kind == ContractKind::Ensures && return_type != void_type {
+            let result = self.context.intern_string("result");
+            predicate_params.push(self.declare_contract_parameter(result, return_type, location));
+        }
+        let prev_static = self.in_static_method;
+        self.in_static_method = is_static;
+        let condition = self.lower_expression(condition);
+        self.in_static_method = prev_static;
+        self.context.exit_scope();
+        let condition = condition?;
+
+        let param_types = predicate_params.iter().map(|p| p.param_type).collect();
+        let function_type = self
+            .context
+            .type_table
+            .borrow_mut()
+            .create_function_type(param_types, bool_type);
+        self.context
+            .symbol_table
+            .update_symbol_type(symbol, function_type);
+
+        Ok(TypedFunction {
+            symbol_id: symbol,
+            name,
+            parameters: predicate_params,
+            return_type: bool_type,
+            body: vec![TypedStatement::Return {
+                value: Some(condition),
+                source_location: location,
+            }],
+            visibility: crate::tast::symbols::Visibility::Private,
+            effects: FunctionEffects::default(),
+            type_parameters: type_params.to_vec(),
+            is_static,
+            source_location: location,
+            metadata: FunctionMetadata {
+                statement_count: 1,
+                ..Default::default()
             },
         })
     }
 
+    /// Declare a parameter of a contract predicate in the current scope
+    fn declare_contract_parameter(
+        &mut self,
+        name: InternedString,
+        param_type: TypeId,
+        source_location: SourceLocation,
+    ) -> TypedParameter {
+        let symbol = self
+            .context
+            .symbol_table
+            .create_variable_in_scope(name, self.context.current_scope);
+        if let Some(scope) = self
+            .context
+            .scope_tree
+            .get_scope_mut(self.context.current_scope)
+        {
+            scope.add_symbol(symbol, name);
+        }
+        self.context
+            .symbol_table
+            .update_symbol_type(symbol, param_type);
+        TypedParameter {
+            symbol_id: symbol,
+            name,
+            param_type,
+            is_optional: false,
+            default_value: None,
+            mutability: crate::tast::Mutability::Immutable,
+            source_location,
+        }
+    }
+
     /// Lower a function signature for interfaces (no body, just signature)
     fn lower_function_signature(
         &mut self,
@@ -5068,6 +5593,70 @@ impl<'a> AstLowering<'a> {
             .collect()
     }
 
+    /// Lower declaration metadata kept for runtime reflection (haxe.rtti.Meta).
+    /// Only entries whose arguments are all constants survive; compiler
+    /// metadata like `@:op(A + B)` or `@:derive([Clone])` is dropped here.
+    /// The keyword argument of `@:inline(never)` is kept as a string.
+    fn lower_user_metadata(&mut self, metadata: &[parser::Metadata]) -> Vec<TypedMetadata> {
+        fn is_constant(expr: &parser::Expr) -> bool {
+            match &expr.kind {
+                parser::ExprKind::Int(_)
+                | parser::ExprKind::Float(_)
+                | parser::ExprKind::String(_)
+                | parser::ExprKind::Bool(_)
+                | parser::ExprKind::Null => true,
+                parser::ExprKind::Unary {
+                    op: parser::UnaryOp::Neg,
+                    expr,
+                } => matches!(
+                    expr.kind,
+                    parser::ExprKind::Int(_) | parser::ExprKind::Float(_)
+                ),
+                parser::ExprKind::Paren(inner) => is_constant(inner),
+                parser::ExprKind::Array(items) => items.iter().all(is_constant),
+                parser::ExprKind::Object(fields) => fields.iter().all(|f| is_constant(&f.expr)),
+                _ => false,
+            }
+        }
+
+        let mut typed = Vec::new();
+        for meta in metadata {
+            let keyword_params;
+            let meta_params = if meta.name == "inline" {
+                keyword_params = meta
+                    .params
+                    .iter()
+                    .map(|param| match &param.kind {
+                        parser::ExprKind::Ident(name) => parser::Expr {
+                            kind: parser::ExprKind::String(name.clone()),
+                            span: param.span,
+                        },
+                        _ => param.clone(),
+                    })
+                    .collect::<Vec<_>>();
+                &keyword_params
+            } else {
+                &meta.params
+            };
+            if !meta_params.iter().all(is_constant) {
+                continue;
+            }
+            let Ok(params) = meta_params
+                .iter()
+                .map(|param| self.lower_expression(param))
+                .collect::<Result<Vec<_>, _>>()
+            else {
+                continue;
+            };
+            typed.push(TypedMetadata {
+                name: self.context.intern_string(&meta.name),
+                params,
+                source_location: self.context.span_to_location(&meta.span),
+            });
+        }
+        typed
+    }
+
     /// Extract derived traits from @:derive metadata
     /// Example: @:derive([Clone, Copy]) or @:derive(Clone)
     fn extract_derived_traits(
@@ -5785,22 +6374,46 @@ impl<'a> AstLowering<'a> {
                 let array_expr = self.lower_expression(expr)?;
                 let index_expr = self.lower_expression(index)?;
 
-                TypedExpressionKind::ArrayAccess {
-                    array: Box::new(array_expr),
-                    index: Box::new(index_expr),
+                if self.is_native_vector(array_expr.expr_type) {
+                    self.native_vector_call(array_expr, "get", vec![index_expr])
+                } else {
+                    TypedExpressionKind::ArrayAccess {
+                        array: Box::new(array_expr),
+                        index: Box::new(index_expr),
+                    }
                 }
             }
             ExprKind::Assign { left, op, right } => {
                 let target_expr = self.lower_expression(left)?;
                 let value_expr = self.lower_expression(right)?;
 
+                // `v[i] = x` on a Vector stores through `set`; `target_expr` is
+                // the matching `get` call, which compound assignments still read
+                let vector_slot = match (&left.kind, &target_expr.kind) {
+                    (
+                        ExprKind::Index { .. },
+                        TypedExpressionKind::MethodCall {
+                            receiver,
+                            arguments,
+                            ..
+                        },
+                    ) if self.is_native_vector(receiver.expr_type) => {
+                        Some(((**receiver).clone(), arguments[0].clone()))
+                    }
+                    _ => None,
+                };
+
                 match op {
                     parser::AssignOp::Assign => {
                         // Simple assignment: target = value
-                        TypedExpressionKind::BinaryOp {
-                            left: Box::new(target_expr),
-                            operator: BinaryOperator::Assign,
-                            right: Box::new(value_expr),
+                        if let Some((receiver, index_expr)) = vector_slot {
+                            self.native_vector_call(receiver, "set", vec![index_expr, value_expr])
+                        } else {
+                            TypedExpressionKind::BinaryOp {
+                                left: Box::new(target_expr),
+                                operator: BinaryOperator::Assign,
+                                right: Box::new(value_expr),
+                            }
                         }
                     }
                     _ => {
@@ -5839,10 +6452,14 @@ impl<'a> AstLowering<'a> {
                         };
 
                         // Now assign the result back to target: target = (target op value)
-                        TypedExpressionKind::BinaryOp {
-                            left: Box::new(target_expr),
-                            operator: BinaryOperator::Assign,
-                            right: Box::new(binary_expr),
+                        if let Some((receiver, index_expr)) = vector_slot {
+                            self.native_vector_call(receiver, "set", vec![index_expr, binary_expr])
+                        } else {
+                            TypedExpressionKind::BinaryOp {
+                                left: Box::new(target_expr),
+                                operator: BinaryOperator::Assign,
+                                right: Box::new(binary_expr),
+                            }
                         }
                     }
                 }
@@ -5853,7 +6470,25 @@ impl<'a> AstLowering<'a> {
                 args,
             } => {
                 // Resolve the base class type from type_path
-                let base_class_type_id = self.resolve_type_path(type_path)?;
+                let mut base_class_type_id = self.resolve_type_path(type_path)?;
+                let is_typedef = matches!(
+                    self.context
+                        .type_table
+                        .borrow()
+                        .get(base_class_type_id)
+                        .map(|t| &t.kind),
+                    Some(crate::tast::core::TypeKind::TypeAlias { .. })
+                );
+                if !base_class_type_id.is_valid() || is_typedef {
+                    // Typedefs (e.g. top-level `List<T>`) have no registered type id
+                    // yet, or only their generic declaration; resolve them the same
+                    // way a type annotation would
+                    base_class_type_id = self.lower_type(&Type::Path {
+                        path: type_path.clone(),
+                        params: params.clone(),
+                        span: expression.span,
+                    })?;
+                }
 
                 // Lower constructor arguments
                 let arg_exprs = args
@@ -5880,6 +6515,16 @@ impl<'a> AstLowering<'a> {
                                 crate::tast::core::TypeKind::Array { .. } => {
                                     Some((SymbolId::invalid(), true)) // Mark as array type
                                 }
+                                // `new List<Int>()` through `typedef List<T> = haxe.ds.List<T>`
+                                crate::tast::core::TypeKind::TypeAlias { target_type, .. } => {
+                                    match type_table.get(*target_type).map(|t| &t.kind) {
+                                        Some(crate::tast::core::TypeKind::Class {
+                                            symbol_id,
+                                            ..
+                                        }) => Some((*symbol_id, false)),
+                                        _ => None,
+                                    }
+                                }
                                 _ => None,
                             }
                         } else {
@@ -5948,7 +6593,9 @@ impl<'a> AstLowering<'a> {
                         parser::BlockElement::Expr(expr) => {
                             // Check if this is a variable declaration expression
                             match &expr.kind {
-                                parser::ExprKind::Var { .. } | parser::ExprKind::Final { .. } => {
+                                parser::ExprKind::Var { .. }
+                                | parser::ExprKind::Final { .. }
+                                | parser::ExprKind::StaticVar(_) => {
                                     // Variable declaration - lower as expression and convert to statement
                                     match self.lower_expression(expr) {
                                         Ok(typed_expr) => {
@@ -6388,11 +7035,21 @@ impl<'a> AstLowering<'a> {
             ExprKind::Function(func) => {
                 // Function expression/lambda - create a new scope for the function body
                 let function_scope = self.context.enter_scope(ScopeKind::Function);
+                let param_hints = self.lambda_param_hints.take();
 
                 // Lower parameters - they will be automatically registered in the function scope
                 let mut parameters = Vec::new();
-                for param in &func.params {
-                    let param_result = self.lower_function_param(param)?;
+                for (i, param) in func.params.iter().enumerate() {
+                    let mut param_result = self.lower_function_param(param)?;
+                    // Untyped parameters take their type from the call context
+                    if param.type_hint.is_none() {
+                        if let Some(&hint) = param_hints.as_ref().and_then(|h| h.get(i)) {
+                            param_result.param_type = hint;
+                            self.context
+                                .symbol_table
+                                .update_symbol_type(param_result.symbol_id, hint);
+                        }
+                    }
                     parameters.push(param_result);
                 }
 
@@ -6406,6 +7063,9 @@ impl<'a> AstLowering<'a> {
                 // Determine return type
                 let return_type = if let Some(ret_type) = &func.return_type {
                     self.lower_type(ret_type)?
+                } else if param_hints.is_some() {
+                    // Contextually typed callback: the body's return type is meaningful
+                    self.infer_return_type_from_body(&body)
                 } else {
                     self.context.type_table.borrow().dynamic_type()
                 };
@@ -6422,14 +7082,17 @@ impl<'a> AstLowering<'a> {
             ExprKind::Arrow { params, expr } => {
                 // Arrow function: x -> x * 2 or (x:Int) -> x * 2
                 let function_scope = self.context.enter_scope(ScopeKind::Function);
+                let param_hints = self.lambda_param_hints.take();
 
                 let mut typed_params = Vec::new();
-                for param in params {
+                for (i, param) in params.iter().enumerate() {
                     let param_interned = self.context.string_interner.intern(&param.name);
 
-                    // Use type annotation if present, otherwise fall back to dynamic
+                    // Use type annotation if present, then the call context, otherwise dynamic
                     let param_type = if let Some(ref type_hint) = param.type_hint {
                         self.lower_type(type_hint)?
+                    } else if let Some(&hint) = param_hints.as_ref().and_then(|h| h.get(i)) {
+                        hint
                     } else {
                         self.context.type_table.borrow().dynamic_type()
                     };
@@ -6703,7 +7366,26 @@ impl<'a> AstLowering<'a> {
                     initializer: Box::new(initializer),
                 }
             }
+            ExprKind::StaticVar(decl) => {
+                // Local static: an ordinary declaration whose symbol is flagged
+                // STATIC, so HIR->MIR backs it with a lazily initialized slot
+                // that persists across calls instead of a stack local.
+                let declaration = self.lower_expression(decl)?;
+                if let TypedExpressionKind::VarDeclarationExpr { symbol_id, .. }
+                | TypedExpressionKind::FinalDeclarationExpr { symbol_id, .. } = &declaration.kind
+                {
+                    self.context
+                        .symbol_table
+                        .add_symbol_flags(*symbol_id, crate::tast::symbols::SymbolFlags::STATIC);
+                }
+                return Ok(declaration);
+            }
             ExprKind::Meta { meta, expr } => {
+                // @:yield value suspends the running coroutine (see generators)
+                if let Some(value) = generators::yielded_value(meta, expr) {
+                    return self.lower_yield(expression, value);
+                }
+
                 // Metadata annotation: @:meta expr
                 let inner_expr = self.lower_expression(expr)?;
 
@@ -6851,9 +7533,17 @@ impl<'a> AstLowering<'a> {
         expr: &Expr,
         args: &[Expr],
     ) -> LoweringResult<TypedExpression> {
+        let callback_hints = self.callback_param_hints(expr, args);
         let arg_exprs = args
             .iter()
-            .map(|arg| self.lower_expression(arg))
+            .map(|arg| {
+                if matches!(arg.kind, ExprKind::Function(_) | ExprKind::Arrow { .. }) {
+                    self.lambda_param_hints = callback_hints.clone();
+                }
+                let lowered = self.lower_expression(arg);
+                self.lambda_param_hints = None;
+                lowered
+            })
             .collect::<Result<Vec<_>, _>>()?;
 
         // Check if this is a method call (field access being called)
@@ -7009,16 +7699,30 @@ impl<'a> AstLowering<'a> {
                                                     inferred
                                                 } else if let Some(ret_info) = type_table.get(ret) {
                                                     // Check if return type is a GenericInstance with TypeParameter args
-                                                    // e.g., Thread<T> from spawn<T>(fn: Void -> T): Thread<T>
-                                                    if let crate::tast::core::TypeKind::GenericInstance {
-                                                        base_type,
-                                                        type_args: ret_type_args,
-                                                        ..
-                                                    } = &ret_info.kind
+                                                    // e.g., Thread<T> from spawn<T>(fn: Void -> T): Thread<T>,
+                                                    // or an instantiated class, e.g. Vector<T> from
+                                                    // fromArrayCopy<T>(array: Array<T>): Vector<T>
+                                                    let generic_return = match &ret_info.kind {
+                                                        crate::tast::core::TypeKind::GenericInstance {
+                                                            base_type,
+                                                            type_args,
+                                                            ..
+                                                        } => Some((Ok(*base_type), type_args)),
+                                                        crate::tast::core::TypeKind::Class {
+                                                            symbol_id,
+                                                            type_args,
+                                                        } => Some((Err(*symbol_id), type_args)),
+                                                        _ => None,
+                                                    };
+                                                    if let Some((instance_of, ret_type_args)) =
+                                                        generic_return
                                                     {
-                                                        let mut subs: Vec<(TypeId, TypeId)> = Vec::new();
+                                                        let mut subs: Vec<(TypeId, TypeId)> =
+                                                            Vec::new();
                                                         for ret_ta in ret_type_args.iter() {
-                                                            if let Some(ta_info) = type_table.get(*ret_ta) {
+                                                            if let Some(ta_info) =
+                                                                type_table.get(*ret_ta)
+                                                            {
                                                                 if let crate::tast::core::TypeKind::TypeParameter {
                                                                     symbol_id: tp_sym,
                                                                     ..
@@ -7045,24 +7749,34 @@ impl<'a> AstLowering<'a> {
                                                         }
 
                                                         if !subs.is_empty() {
-                                                            let base_type_val = *base_type;
-                                                            let new_args: Vec<TypeId> = ret_type_args
-                                                                .iter()
-                                                                .map(|ta| {
-                                                                    subs.iter()
-                                                                        .find(|(old, _)| old == ta)
-                                                                        .map(|(_, new)| *new)
-                                                                        .unwrap_or(*ta)
-                                                                })
-                                                                .collect();
+                                                            let new_args: Vec<TypeId> =
+                                                                ret_type_args
+                                                                    .iter()
+                                                                    .map(|ta| {
+                                                                        subs.iter()
+                                                                            .find(|(old, _)| {
+                                                                                old == ta
+                                                                            })
+                                                                            .map(|(_, new)| *new)
+                                                                            .unwrap_or(*ta)
+                                                                    })
+                                                                    .collect();
                                                             drop(type_table);
-                                                            self.context
+                                                            let mut type_table = self
+                                                                .context
                                                                 .type_table
-                                                                .borrow_mut()
-                                                                .create_generic_instance(
-                                                                    base_type_val,
-                                                                    new_args,
-                                                                )
+                                                                .borrow_mut();
+                                                            match instance_of {
+                                                                Ok(base_type) => type_table
+                                                                    .create_generic_instance(
+                                                                        base_type, new_args,
+                                                                    ),
+                                                                Err(class_symbol) => type_table
+                                                                    .create_class_type(
+                                                                        class_symbol,
+                                                                        new_args,
+                                                                    ),
+                                                            }
                                                         } else {
                                                             ret
                                                         }
@@ -7082,6 +7796,10 @@ impl<'a> AstLowering<'a> {
                                     self.context.type_table.borrow().dynamic_type()
                                 };
 
+                                let expr_type = self
+                                    .lambda_return_type(class_symbol, field, &arg_exprs)
+                                    .unwrap_or(expr_type);
+
                                 let kind = TypedExpressionKind::StaticMethodCall {
                                     class_symbol,
                                     method_symbol,
@@ -7249,18 +7967,23 @@ impl<'a> AstLowering<'a> {
                 // In Haxe, `calculate(10, 20)` inside a class method is `this.calculate(10, 20)`,
                 // and `staticMethod()` is `ClassName.staticMethod()`.
                 if let TypedExpressionKind::Variable { symbol_id } = &func_expr.kind {
-                    let method_info =
-                        self.context
-                            .class_context_stack
-                            .last()
-                            .and_then(|class_sym| {
-                                self.class_methods.get(class_sym).and_then(|methods| {
-                                    methods
-                                        .iter()
-                                        .find(|(_, sym, _)| *sym == *symbol_id)
-                                        .map(|(_, _, is_static)| (*class_sym, *is_static))
-                                })
-                            });
+                    let method_info = self
+                        .context
+                        .class_context_stack
+                        .last()
+                        .and_then(|class_sym| {
+                            self.class_methods.get(class_sym).and_then(|methods| {
+                                methods
+                                    .iter()
+                                    .find(|(_, sym, _)| *sym == *symbol_id)
+                                    .map(|(_, _, is_static)| (*class_sym, *is_static))
+                            })
+                        })
+                        .or_else(|| {
+                            // Module-level function called unqualified
+                            self.module_function_owner(*symbol_id)
+                                .map(|fields_class| (fields_class, true))
+                        });
 
                     if let Some((class_symbol, is_static)) = method_info {
                         let method_symbol = *symbol_id;
@@ -7367,6 +8090,7 @@ impl<'a> AstLowering<'a> {
 
         // Build the TypedExpression for the non-early-return paths
         let expr_type = self.infer_expression_type(&kind)?;
+        let expr_type = self.refine_array_map_type(&kind, expr_type);
         let usage = self.determine_variable_usage(&kind);
         let lifetime_id = self.assign_lifetime(&kind, &expr_type);
         let metadata = self.analyze_expression_metadata(&kind);
@@ -7733,7 +8457,18 @@ impl<'a> AstLowering<'a> {
                             .find(|(name, _, _)| *name == field_name)
                             .map(|(_, symbol, is_static)| (*symbol, *is_static))
                     } else {
-                        None
+                        // Class lowered with an earlier module: find its static
+                        // variables through the class scope
+                        self.static_member_of(class_symbol, field_name)
+                            .filter(|&member| {
+                                self.context
+                                    .symbol_table
+                                    .get_symbol(member)
+                                    .is_some_and(|sym| {
+                                        sym.kind == crate::tast::symbols::SymbolKind::Variable
+                                    })
+                            })
+                            .map(|member| (member, true))
                     };
 
                     if let Some((field_symbol, _is_static)) = field_info {
@@ -7744,7 +8479,12 @@ impl<'a> AstLowering<'a> {
                         {
                             field.1 // field type
                         } else {
-                            self.context.type_table.borrow().dynamic_type()
+                            self.context
+                                .symbol_table
+                                .get_symbol(field_symbol)
+                                .map(|sym| sym.type_id)
+                                .filter(|type_id| type_id.is_valid())
+                                .unwrap_or_else(|| self.context.type_table.borrow().dynamic_type())
                         };
 
                         let kind = TypedExpressionKind::StaticFieldAccess {
@@ -8324,8 +9064,8 @@ impl<'a> AstLowering<'a> {
                                     .unwrap_or_else(|| type_table.dynamic_type());
                                 Some((type_table.string_type(), value_type))
                             }
-                            Some("ObjectMap") => {
-                                // ObjectMap<K, V> has two type args
+                            Some("ObjectMap") | Some("WeakMap") | Some("EnumValueMap") => {
+                                // ObjectMap, WeakMap and EnumValueMap have two type args (K, V)
                                 let key_type = type_args
                                     .first()
                                     .copied()
@@ -9128,6 +9868,11 @@ impl<'a> AstLowering<'a> {
 
     /// Infer the element type from an iterable expression (array, map, etc.)
     fn infer_element_type_from_iterable(&self, iterable: &TypedExpression) -> TypeId {
+        // Arrays and native Lists (including the top-level `List<T>` typedef)
+        if let Some(element_type) = self.collection_element_type(iterable.expr_type) {
+            return element_type;
+        }
+
         let type_table = self.context.type_table.borrow();
 
         // Get the actual type of the iterable
@@ -9464,12 +10209,24 @@ impl<'a> AstLowering<'a> {
                 self.infer_method_call_return_type(*method_symbol, receiver.expr_type)
             }
             TypedExpressionKind::StaticMethodCall {
+                class_symbol,
                 method_symbol,
                 arguments,
                 ..
             } => {
                 // Extract return type from static method signature
                 if let Some(symbol) = self.context.symbol_table.get_symbol(*method_symbol) {
+                    let method_name = self
+                        .context
+                        .string_interner
+                        .get(symbol.name)
+                        .unwrap_or("")
+                        .to_string();
+                    if let Some(return_type) =
+                        self.lambda_return_type(*class_symbol, &method_name, arguments)
+                    {
+                        return Ok(return_type);
+                    }
                     let type_table = self.context.type_table.borrow();
                     if let Some(method_type) = type_table.get(symbol.type_id) {
                         match &method_type.kind {
@@ -9716,6 +10473,24 @@ impl<'a> AstLowering<'a> {
         method_symbol: SymbolId,
         receiver_type: TypeId,
     ) -> LoweringResult<TypeId> {
+        // Native List/Vector methods: `T` in the extern can't be substituted from
+        // the receiver (or the List typedef target is still unresolved)
+        let method_name = self
+            .context
+            .symbol_table
+            .get_symbol(method_symbol)
+            .and_then(|symbol| self.context.string_interner.get(symbol.name))
+            .map(str::to_string);
+        if let Some(member_type) =
+            method_name.and_then(|name| self.native_collection_member_type(receiver_type, &name))
+        {
+            let type_table = self.context.type_table.borrow();
+            return Ok(match type_table.get(member_type).map(|t| &t.kind) {
+                Some(crate::tast::core::TypeKind::Function { return_type, .. }) => *return_type,
+                _ => member_type,
+            });
+        }
+
         // Phase 1: Collect all necessary information with immutable borrow
         let substitution_result = {
             let type_table = self.context.type_table.borrow();
@@ -10121,6 +10896,19 @@ impl<'a> AstLowering<'a> {
                 }
                 None
             }
+            crate::tast::core::TypeKind::Array { element_type } => {
+                match &type_table.get(arg_ty)?.kind {
+                    crate::tast::core::TypeKind::Array {
+                        element_type: arg_element,
+                    } => Self::match_type_param_in_types(
+                        target_sym,
+                        *element_type,
+                        *arg_element,
+                        type_table,
+                    ),
+                    _ => None,
+                }
+            }
             _ => None,
         }
     }
@@ -11468,6 +12256,205 @@ impl<'a> AstLowering<'a> {
         })
     }
 
+    /// Contextual parameter types for a function literal passed to an Array/List
+    /// higher-order method (`map`, `filter`, `sort`, ...) or a `Lambda` function
+    /// (`Lambda.fold(xs, f, 0)`, or `xs.fold(f, 0)` with `using Lambda`) on a
+    /// local variable.
+    ///
+    /// Lets `ints.sort(function(a, b) return a - b)` type `a` and `b` as `Int`
+    /// instead of `Dynamic`, so the callback gets the element's native ABI.
+    fn callback_param_hints(&self, callee: &Expr, args: &[Expr]) -> Option<Vec<TypeId>> {
+        let ExprKind::Field {
+            expr: obj_expr,
+            field,
+            ..
+        } = &callee.kind
+        else {
+            return None;
+        };
+        // The collection is the receiver, or the first argument of a Lambda call
+        let (collection, rest) = match &obj_expr.kind {
+            ExprKind::Ident(name) if name == "Lambda" => (args.first()?, &args[1..]),
+            _ => (obj_expr.as_ref(), args),
+        };
+        let ExprKind::Ident(name) = &collection.kind else {
+            return None;
+        };
+        let element_type = self
+            .local_type(name)
+            .and_then(|ty| self.collection_element_type(ty))?;
+
+        let accumulator = |first: Option<&Expr>| -> TypeId {
+            let type_table = self.context.type_table.borrow();
+            match first.map(|first| &first.kind) {
+                Some(ExprKind::Int(_)) => type_table.int_type(),
+                Some(ExprKind::Float(_)) => type_table.float_type(),
+                Some(ExprKind::String(_)) => type_table.string_type(),
+                Some(ExprKind::Bool(_)) => type_table.bool_type(),
+                Some(ExprKind::Ident(name)) => {
+                    let dynamic = type_table.dynamic_type();
+                    drop(type_table);
+                    self.local_type(name).unwrap_or(dynamic)
+                }
+                _ => type_table.dynamic_type(),
+            }
+        };
+        let int_type = self.context.type_table.borrow().int_type();
+        match field.as_str() {
+            "map" | "filter" | "flatMap" | "exists" | "foreach" | "iter" | "find" | "findIndex"
+            | "count" => Some(vec![element_type]),
+            "sort" => Some(vec![element_type; 2]),
+            "mapi" => Some(vec![int_type, element_type]),
+            // `fold(f, first)`: the accumulator has the type of `first`
+            "fold" | "foldi" => Some(vec![element_type, accumulator(rest.get(1)), int_type]),
+            _ => None,
+        }
+    }
+
+    /// Declared type of a variable visible from the current scope.
+    fn local_type(&self, name: &str) -> Option<TypeId> {
+        let symbol_id =
+            self.resolve_symbol_in_scope_hierarchy(self.context.string_interner.intern(name))?;
+        Some(self.context.symbol_table.get_symbol(symbol_id)?.type_id)
+    }
+
+    /// Whether `type_id` is the extern `haxe.ds.Vector<T>`, which the runtime
+    /// implements natively, so its element accesses are `get`/`set` calls
+    /// rather than HaxeArray indexing.
+    fn is_native_vector(&self, type_id: TypeId) -> bool {
+        let type_table = self.context.type_table.borrow();
+        let Some(TypeKind::Class { symbol_id, .. }) = type_table.get(type_id).map(|t| &t.kind)
+        else {
+            return false;
+        };
+        self.context
+            .symbol_table
+            .get_symbol(*symbol_id)
+            .is_some_and(|sym| {
+                sym.flags
+                    .contains(crate::tast::symbols::SymbolFlags::EXTERN)
+                    && self.context.string_interner.get(sym.name) == Some("Vector")
+            })
+    }
+
+    /// Call `method` on a native Vector receiver, for lowering `v[i]` and
+    /// `v[i] = x` to `get`/`set`.
+    fn native_vector_call(
+        &mut self,
+        receiver: TypedExpression,
+        method: &str,
+        arguments: Vec<TypedExpression>,
+    ) -> TypedExpressionKind {
+        let method_name = self.context.intern_string(method);
+        let method_symbol = self.resolve_method_symbol(&receiver, method_name);
+        TypedExpressionKind::MethodCall {
+            receiver: Box::new(receiver),
+            method_symbol,
+            arguments,
+            type_arguments: Vec::new(),
+            is_optional: false,
+        }
+    }
+
+    /// Element type of an `Array<T>` or a (possibly aliased) `List<T>`.
+    fn collection_element_type(&self, collection_type: TypeId) -> Option<TypeId> {
+        let type_table = self.context.type_table.borrow();
+        let mut current = collection_type;
+        let mut alias_arg = None;
+        for _ in 0..10 {
+            match &type_table.get(current)?.kind {
+                TypeKind::Array { element_type } => return Some(*element_type),
+                TypeKind::TypeAlias {
+                    target_type,
+                    type_args,
+                    ..
+                } => {
+                    alias_arg = alias_arg.or(type_args.first().copied());
+                    current = *target_type;
+                }
+                TypeKind::Class {
+                    symbol_id,
+                    type_args,
+                } => {
+                    let name = self
+                        .context
+                        .symbol_table
+                        .get_symbol(*symbol_id)
+                        .and_then(|sym| self.context.string_interner.get(sym.name));
+                    if name != Some("List") {
+                        return None;
+                    }
+                    // The alias target still has the typedef's own type parameter
+                    let class_arg = type_args.first().copied().filter(|arg| {
+                        !matches!(
+                            type_table.get(*arg).map(|t| &t.kind),
+                            Some(TypeKind::TypeParameter { .. })
+                        )
+                    });
+                    return class_arg.or(alias_arg);
+                }
+                // Typedef target not resolved yet (e.g. when `Lambda` pulls in List first)
+                TypeKind::Placeholder { name } => {
+                    let name = self.context.string_interner.get(*name);
+                    return matches!(name, Some("List") | Some("haxe.ds.List"))
+                        .then_some(alias_arg)
+                        .flatten();
+                }
+                _ => return None,
+            }
+        }
+        None
+    }
+
+    /// `Array<T>.map(f)` returns `Array<S>` where `S` is the callback's return type.
+    ///
+    /// The builtin method type only knows `T`; when the callback literal has a
+    /// concrete return type, use it for the result element type.
+    fn refine_array_map_type(&self, kind: &TypedExpressionKind, expr_type: TypeId) -> TypeId {
+        let TypedExpressionKind::MethodCall {
+            receiver,
+            method_symbol,
+            arguments,
+            ..
+        } = kind
+        else {
+            return expr_type;
+        };
+        let is_map = self
+            .context
+            .symbol_table
+            .get_symbol(*method_symbol)
+            .and_then(|sym| self.context.string_interner.get(sym.name))
+            == Some("map");
+        let callback_return = match arguments.first().map(|arg| &arg.kind) {
+            Some(TypedExpressionKind::FunctionLiteral { return_type, .. }) if is_map => {
+                *return_type
+            }
+            _ => return expr_type,
+        };
+
+        {
+            let type_table = self.context.type_table.borrow();
+            let receiver_is_array = matches!(
+                type_table.get(receiver.expr_type).map(|info| &info.kind),
+                Some(crate::tast::core::TypeKind::Array { .. })
+            );
+            let concrete = !matches!(
+                type_table.get(callback_return).map(|info| &info.kind),
+                None | Some(crate::tast::core::TypeKind::Dynamic)
+                    | Some(crate::tast::core::TypeKind::Void)
+                    | Some(crate::tast::core::TypeKind::Unknown)
+            );
+            if !receiver_is_array || !concrete {
+                return expr_type;
+            }
+        }
+        self.context
+            .type_table
+            .borrow_mut()
+            .create_array_type(callback_return)
+    }
+
     /// Infer the type of built-in methods like Array.push, String.charAt, etc.
     fn infer_builtin_method_type(
         &mut self,
@@ -11485,6 +12472,10 @@ impl<'a> AstLowering<'a> {
             return Ok(self.context.type_table.borrow().dynamic_type());
         };
 
+        if let Some(member_type) = self.native_collection_member_type(receiver_type, &field_name) {
+            return Ok(member_type);
+        }
+
         // Check the object type to see if it's a built-in type with known methods
         let type_table = self.context.type_table.borrow();
         if let Some(object_type_info) = type_table.get(receiver_type) {
@@ -11682,6 +12673,143 @@ impl<'a> AstLowering<'a> {
         }
     }
 
+    /// Result type of a `Lambda` call, mirroring the signatures in `Lambda.hx`.
+    ///
+    /// The declared signatures are generic over `Iterable<A>`; derive concrete
+    /// element and callback result types from the arguments instead.
+    fn lambda_return_type(
+        &self,
+        class_symbol: SymbolId,
+        method: &str,
+        args: &[TypedExpression],
+    ) -> Option<TypeId> {
+        let class_name = self
+            .context
+            .symbol_table
+            .get_symbol(class_symbol)
+            .and_then(|sym| self.context.string_interner.get(sym.name));
+        if class_name != Some("Lambda") {
+            return None;
+        }
+        let element_type = args
+            .first()
+            .and_then(|it| self.collection_element_type(it.expr_type));
+        let callback_return = || {
+            let type_table = self.context.type_table.borrow();
+            match args
+                .get(1)
+                .and_then(|f| type_table.get(f.expr_type))
+                .map(|t| &t.kind)
+            {
+                Some(TypeKind::Function { return_type, .. }) => Some(*return_type),
+                _ => None,
+            }
+        };
+
+        let mut type_table = self.context.type_table.borrow_mut();
+        let dynamic = type_table.dynamic_type();
+        Some(match method {
+            "exists" | "foreach" | "has" | "empty" => type_table.bool_type(),
+            "count" | "indexOf" | "findIndex" => type_table.int_type(),
+            "iter" => type_table.void_type(),
+            "find" => element_type.unwrap_or(dynamic),
+            "fold" | "foldi" => args.get(2).map_or(dynamic, |first| first.expr_type),
+            "array" | "filter" | "concat" => {
+                type_table.create_array_type(element_type.unwrap_or(dynamic))
+            }
+            "map" | "mapi" => {
+                drop(type_table);
+                let mapped = callback_return().unwrap_or(dynamic);
+                self.context
+                    .type_table
+                    .borrow_mut()
+                    .create_array_type(mapped)
+            }
+            "flatMap" => {
+                drop(type_table);
+                let mapped = callback_return()
+                    .and_then(|ret| self.collection_element_type(ret))
+                    .unwrap_or(dynamic);
+                self.context
+                    .type_table
+                    .borrow_mut()
+                    .create_array_type(mapped)
+            }
+            "flatten" => {
+                drop(type_table);
+                let inner = element_type
+                    .and_then(|inner| self.collection_element_type(inner))
+                    .unwrap_or(dynamic);
+                self.context
+                    .type_table
+                    .borrow_mut()
+                    .create_array_type(inner)
+            }
+            _ => return None,
+        })
+    }
+
+    /// Type of a member of the native `List<T>` or `Vector<T>` (haxe.ds), or
+    /// `None` if the receiver is neither. Their extern classes declare members
+    /// over `T`, which is not substituted from the receiver's type arguments.
+    fn native_collection_member_type(&self, receiver_type: TypeId, name: &str) -> Option<TypeId> {
+        if self.is_native_vector(receiver_type) {
+            return self.vector_method_type(receiver_type, name);
+        }
+        let is_array = matches!(
+            self.context
+                .type_table
+                .borrow()
+                .get(receiver_type)
+                .map(|t| &t.kind),
+            Some(TypeKind::Array { .. })
+        );
+        if is_array {
+            return None;
+        }
+        let element_type = self.collection_element_type(receiver_type)?;
+        Some(self.list_method_type(receiver_type, element_type, name))
+    }
+
+    /// Member types of the native `List<T>`, mirroring `haxe/ds/List.hx`.
+    fn list_method_type(&self, list_type: TypeId, element_type: TypeId, name: &str) -> TypeId {
+        let mut type_table = self.context.type_table.borrow_mut();
+        let (params, return_type) = match name {
+            "length" => return type_table.int_type(),
+            "add" | "push" => (vec![element_type], type_table.void_type()),
+            "first" | "last" | "pop" => (vec![], element_type),
+            "isEmpty" => (vec![], type_table.bool_type()),
+            "clear" => (vec![], type_table.void_type()),
+            "remove" => (vec![element_type], type_table.bool_type()),
+            "join" => (vec![type_table.string_type()], type_table.string_type()),
+            "toString" => (vec![], type_table.string_type()),
+            "filter" => (vec![type_table.dynamic_type()], list_type),
+            _ => return type_table.dynamic_type(),
+        };
+        type_table.create_function_type(params, return_type)
+    }
+
+    /// Member types of the native `Vector<T>`, mirroring `haxe/ds/Vector.hx`.
+    fn vector_method_type(&self, vector_type: TypeId, name: &str) -> Option<TypeId> {
+        let mut type_table = self.context.type_table.borrow_mut();
+        let element_type = match type_table.get(vector_type).map(|t| &t.kind) {
+            Some(TypeKind::Class { type_args, .. }) => type_args.first().copied(),
+            _ => None,
+        }
+        .unwrap_or_else(|| type_table.dynamic_type());
+        let int_type = type_table.int_type();
+        let (params, return_type) = match name {
+            "length" => return Some(int_type),
+            "get" => (vec![int_type], element_type),
+            "set" => (vec![int_type, element_type], element_type),
+            "fill" => (vec![element_type], type_table.void_type()),
+            "copy" => (vec![], vector_type),
+            "toArray" => (vec![], type_table.create_array_type(element_type)),
+            _ => return None,
+        };
+        Some(type_table.create_function_type(params, return_type))
+    }
+
     /// Lower a function body
     fn lower_function_body(
         &mut self,
@@ -11696,7 +12824,9 @@ impl<'a> AstLowering<'a> {
                         parser::BlockElement::Expr(expr) => {
                             // Check if this is a variable declaration expression
                             match &expr.kind {
-                                parser::ExprKind::Var { .. } | parser::ExprKind::Final { .. } => {
+                                parser::ExprKind::Var { .. }
+                                | parser::ExprKind::Final { .. }
+                                | parser::ExprKind::StaticVar(_) => {
                                     // Variable declaration - lower as expression and convert to statement
                                     match self.lower_expression(expr) {
                                         Ok(typed_expr) => {
@@ -11891,6 +13021,34 @@ impl<'a> AstLowering<'a> {
         )
     }
 
+    /// Lower `@:yield value` as `sys.thread.Coroutine.yield(value)`
+    fn lower_yield(&mut self, expression: &Expr, value: &Expr) -> LoweringResult<TypedExpression> {
+        let span = expression.span;
+        let (root, fields) = generators::COROUTINE_YIELD_PATH.split_first().unwrap();
+        let callee = fields.iter().fold(
+            Expr {
+                kind: ExprKind::Ident(root.to_string()),
+                span,
+            },
+            |object, field| Expr {
+                kind: ExprKind::Field {
+                    expr: Box::new(object),
+                    field: field.to_string(),
+                    is_optional: false,
+                },
+                span,
+            },
+        );
+        let call = Expr {
+            kind: ExprKind::Call {
+                expr: Box::new(callee),
+                args: vec![value.clone()],
+            },
+            span,
+        };
+        self.lower_expression(&call)
+    }
+
     /// Try to desugar a tuple literal to a static method call (e.g., SIMD4f.make()).
     /// Returns Ok(Some(expr)) if desugared, Ok(None) if the target type doesn't support tuple construction.
     fn try_desugar_tuple_to_make(
@@ -12119,6 +13277,14 @@ impl<'a> AstLowering<'a> {
                     Some(self.context.type_table.borrow().void_type())
                 }
             }
+            // `return e` used as an expression (e.g. `function(x) return x * 2`)
+            TypedStatement::Expression { expression, .. } => match &expression.kind {
+                TypedExpressionKind::Return { value } => Some(match value {
+                    Some(expr) => expr.expr_type,
+                    None => self.context.type_table.borrow().void_type(),
+                }),
+                _ => None,
+            },
             TypedStatement::Block { statements, .. } => {
                 for s in statements {
                     if let Some(ret_type) = self.find_return_type_in_statement(s) {
@@ -16,6 +16,7 @@ pub struct CoreTypePaths {
     pub channel: &'static str,
     pub mutex: &'static str,
     pub arc: &'static str,
+    pub tls: &'static str,
 
     // Memory types
     pub rc: &'static str,
@@ -35,6 +36,7 @@ impl CoreTypePaths {
             channel: "rayzor.concurrent.Channel",
             mutex: "rayzor.concurrent.Mutex",
             arc: "rayzor.concurrent.Arc",
+            tls: "sys.thread.Tls",
 
             // Memory
             rc: "rayzor.memory.Rc",
@@ -84,6 +86,11 @@ impl<'a> CoreTypeChecker<'a> {
         self.is_core_type(type_id, self.paths.mutex)
     }
 
+    /// Check if a type is sys.thread.Tls<T>
+    pub fn is_tls(&self, type_id: TypeId) -> bool {
+        self.is_core_type(type_id, self.paths.tls)
+    }
+
     /// Check if a type is rayzor.memory.Rc<T>
     pub fn is_rc(&self, type_id: TypeId) -> bool {
         self.is_core_type(type_id, self.paths.rc)
@@ -197,13 +204,13 @@ impl<'a> CoreTypeChecker<'a> {
                 return None;
             }
 
-            // Check if the method is "spawn"
+            // Check if the method is "spawn" or "spawnWithStackSize"
             let method_name = self.symbol_table.get_symbol(*method_symbol)?;
             let method_name_str = {
                 let type_table = self.type_table.borrow();
                 type_table.get_string(method_name.name)?.to_string()
             };
-            if method_name_str != "spawn" {
+            if method_name_str != "spawn" && method_name_str != "spawnWithStackSize" {
                 return None;
             }
 
@@ -248,6 +255,7 @@ mod tests {
         assert_eq!(paths.thread, "rayzor.concurrent.Thread");
         assert_eq!(paths.channel, "rayzor.concurrent.Channel");
         assert_eq!(paths.arc, "rayzor.concurrent.Arc");
+        assert_eq!(paths.tls, "sys.thread.Tls");
     }
 
     // TODO: Add integration tests with actual TypeTable and SymbolTable
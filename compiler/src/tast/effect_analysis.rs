@@ -4,6 +4,11 @@
 //! - Can throw: Whether the function can throw exceptions
 //! - Is async: Whether the function is asynchronous
 //! - Is pure: Whether the function has no side effects
+//!
+//! Throws come from `throw`, from calls to functions found to throw, from
+//! `new` of a class whose constructor throws, and from macros. Calls to
+//! functions without an analyzed body (externs, function values) are assumed
+//! not to throw. [`check_no_throw`] uses this to verify `@:noThrow` functions.
 
 use crate::tast::{
     node::{
@@ -11,10 +16,42 @@ use crate::tast::{
         TypedCatchClause, TypedExpression, TypedExpressionKind, TypedFunction, TypedStatement,
         UnaryOperator,
     },
-    SymbolId, SymbolTable, TypeId, TypeTable,
+    SourceLocation, SymbolId, SymbolTable, TypeId, TypeTable,
 };
 use std::cell::RefCell;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+
+/// A place where a function body can throw
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThrowSite {
+    pub location: SourceLocation,
+    pub kind: ThrowSiteKind,
+}
+
+/// What throws at a [`ThrowSite`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThrowSiteKind {
+    /// `throw` expression or statement
+    Throw,
+    /// Call to a function or method that can throw
+    Call(SymbolId),
+    /// `new` of a class (by symbol) whose constructor can throw
+    Constructor(SymbolId),
+    /// Macro expression with unknown effects
+    Macro,
+}
+
+/// A `@:noThrow` function whose body can throw
+#[derive(Debug, Clone)]
+pub struct NoThrowViolation {
+    /// The `@:noThrow` function
+    pub function: SymbolId,
+    /// Where the function is declared
+    pub location: SourceLocation,
+    /// Throw sites from the function body down to the code that throws: each
+    /// call is followed by the site where the callee throws
+    pub path: Vec<ThrowSite>,
+}
 
 /// Analyzes a function to determine its effects
 pub struct EffectAnalyzer<'a> {
@@ -22,6 +59,10 @@ pub struct EffectAnalyzer<'a> {
     type_table: &'a RefCell<TypeTable>,
     /// Set of functions known to throw
     throwing_functions: HashSet<SymbolId>,
+    /// First place each throwing function was found to throw
+    throw_sites: HashMap<SymbolId, ThrowSite>,
+    /// Classes with a throwing constructor, to that constructor's symbol
+    throwing_constructors: HashMap<SymbolId, SymbolId>,
     /// Set of functions known to be async
     async_functions: HashSet<SymbolId>,
     /// Set of functions known to be pure
@@ -35,6 +76,8 @@ impl<'a> EffectAnalyzer<'a> {
             symbol_table,
             type_table,
             throwing_functions: HashSet::new(),
+            throw_sites: HashMap::new(),
+            throwing_constructors: HashMap::new(),
             async_functions: HashSet::new(),
             pure_functions: HashSet::new(),
         }
@@ -71,6 +114,9 @@ impl<'a> EffectAnalyzer<'a> {
         if effects.can_throw {
             self.throwing_functions.insert(function.symbol_id);
         }
+        if let Some(site) = body_effects.throw_site {
+            self.throw_sites.entry(function.symbol_id).or_insert(site);
+        }
         if effects.async_kind != AsyncKind::Sync {
             self.async_functions.insert(function.symbol_id);
         }
@@ -81,6 +127,50 @@ impl<'a> EffectAnalyzer<'a> {
         effects
     }
 
+    /// Analyze a constructor of `class`, so `new` of that class throws when
+    /// the constructor does
+    pub fn analyze_constructor(
+        &mut self,
+        class: SymbolId,
+        constructor: &TypedFunction,
+    ) -> FunctionEffects {
+        let effects = self.analyze_function(constructor);
+        if effects.can_throw {
+            self.throwing_constructors
+                .entry(class)
+                .or_insert(constructor.symbol_id);
+        }
+        effects
+    }
+
+    /// Whether `function` was found to throw
+    pub fn can_throw(&self, function: SymbolId) -> bool {
+        self.throwing_functions.contains(&function)
+    }
+
+    /// Where `function` throws, followed through callees down to the code
+    /// that throws
+    pub fn throw_path(&self, function: SymbolId) -> Vec<ThrowSite> {
+        let mut path = Vec::new();
+        let mut visited = HashSet::new();
+        let mut current = function;
+        while visited.insert(current) {
+            let Some(&site) = self.throw_sites.get(&current) else {
+                break;
+            };
+            path.push(site);
+            current = match site.kind {
+                ThrowSiteKind::Call(callee) => callee,
+                ThrowSiteKind::Constructor(class) => match self.throwing_constructors.get(&class) {
+                    Some(&constructor) => constructor,
+                    None => break,
+                },
+                ThrowSiteKind::Throw | ThrowSiteKind::Macro => break,
+            };
+        }
+        path
+    }
+
     /// Check function metadata for effect annotations
     fn check_function_metadata(&self, function: &TypedFunction) -> Option<FunctionEffects> {
         // In Haxe, functions can be marked with metadata like @:throws, @:async, @:pure
@@ -180,9 +270,12 @@ impl<'a> EffectAnalyzer<'a> {
                     BodyEffects::default()
                 }
             }
-            TypedStatement::Throw { exception, .. } => {
+            TypedStatement::Throw {
+                exception,
+                source_location,
+            } => {
                 let mut effects = self.analyze_expression(exception);
-                effects.can_throw = true; // Explicit throw
+                effects.throws(*source_location, ThrowSiteKind::Throw); // Explicit throw
                 effects
             }
             TypedStatement::Try {
@@ -195,6 +288,7 @@ impl<'a> EffectAnalyzer<'a> {
 
                 // Try blocks can catch exceptions, so they don't propagate throws
                 let body_can_throw = effects.can_throw;
+                let body_throw_site = effects.throw_site.take();
                 effects.can_throw = false;
 
                 // Analyze catch clauses
@@ -210,6 +304,7 @@ impl<'a> EffectAnalyzer<'a> {
                 // If all catch clauses rethrow, the try can still throw
                 if catch_clauses.is_empty() && body_can_throw {
                     effects.can_throw = true;
+                    effects.throw_site = effects.throw_site.or(body_throw_site);
                 }
 
                 effects
@@ -267,11 +362,10 @@ impl<'a> EffectAnalyzer<'a> {
     /// Analyze an expression for effects
     fn analyze_expression(&self, expression: &TypedExpression) -> BodyEffects {
         let mut effects = BodyEffects::default();
+        let location = expression.source_location;
 
-        // Check expression metadata first
-        if expression.metadata.can_throw {
-            effects.can_throw = true;
-        }
+        // Check expression metadata first. Its `can_throw` marks every call
+        // and division, so throws are derived from the expression kinds below
         if expression.metadata.has_side_effects {
             effects.has_side_effects = true;
         }
@@ -313,7 +407,7 @@ impl<'a> EffectAnalyzer<'a> {
                 // Check if the function is known to throw or be async
                 if let TypedExpressionKind::Variable { symbol_id } = &function.kind {
                     if self.throwing_functions.contains(symbol_id) {
-                        effects.can_throw = true;
+                        effects.throws(location, ThrowSiteKind::Call(*symbol_id));
                     }
                     if self.async_functions.contains(symbol_id) {
                         effects.is_async = true;
@@ -338,7 +432,7 @@ impl<'a> EffectAnalyzer<'a> {
 
                 // Check if the method is known to throw or be async
                 if self.throwing_functions.contains(method_symbol) {
-                    effects.can_throw = true;
+                    effects.throws(location, ThrowSiteKind::Call(*method_symbol));
                 }
                 if self.async_functions.contains(method_symbol) {
                     effects.is_async = true;
@@ -359,7 +453,7 @@ impl<'a> EffectAnalyzer<'a> {
 
                 // Check if the method is known to throw or be async
                 if self.throwing_functions.contains(method_symbol) {
-                    effects.can_throw = true;
+                    effects.throws(location, ThrowSiteKind::Call(*method_symbol));
                 }
                 if self.async_functions.contains(method_symbol) {
                     effects.is_async = true;
@@ -462,13 +556,21 @@ impl<'a> EffectAnalyzer<'a> {
                 effects.merge(self.analyze_expression(expression));
             }
 
-            TypedExpressionKind::New { arguments, .. } => {
+            TypedExpressionKind::New {
+                class_type,
+                arguments,
+                ..
+            } => {
                 for arg in arguments {
                     effects.merge(self.analyze_expression(arg));
                 }
-                // Constructors can have side effects and might throw
+                // Constructors have side effects and throw if their body does
                 effects.has_side_effects = true;
-                effects.can_throw = true;
+                if let Some(class) = self.symbol_table.get_symbol_from_type(*class_type) {
+                    if self.throwing_constructors.contains_key(&class) {
+                        effects.throws(location, ThrowSiteKind::Constructor(class));
+                    }
+                }
             }
 
             TypedExpressionKind::Return { value } => {
@@ -479,7 +581,7 @@ impl<'a> EffectAnalyzer<'a> {
 
             TypedExpressionKind::Throw { expression } => {
                 effects.merge(self.analyze_expression(expression));
-                effects.can_throw = true;
+                effects.throws(location, ThrowSiteKind::Throw);
             }
 
             TypedExpressionKind::VarDeclarationExpr { initializer, .. }
@@ -498,7 +600,7 @@ impl<'a> EffectAnalyzer<'a> {
             TypedExpressionKind::MacroExpression { .. } => {
                 // Macros can have arbitrary effects
                 effects.has_side_effects = true;
-                effects.can_throw = true;
+                effects.throws(location, ThrowSiteKind::Macro);
             }
 
             TypedExpressionKind::Block { statements, .. } => {
@@ -546,6 +648,7 @@ impl<'a> EffectAnalyzer<'a> {
 
                 // Try expressions can catch exceptions
                 try_effects.can_throw = false;
+                try_effects.throw_site = None;
 
                 for catch in catch_clauses {
                     effects.merge(self.analyze_catch_clause(catch));
@@ -602,6 +705,8 @@ impl<'a> EffectAnalyzer<'a> {
 struct BodyEffects {
     /// Whether the body can throw exceptions
     can_throw: bool,
+    /// First place the body can throw
+    throw_site: Option<ThrowSite>,
     /// Whether the body contains async operations
     is_async: bool,
     /// Whether the body has side effects
@@ -612,9 +717,16 @@ impl BodyEffects {
     /// Merge effects from another analysis
     fn merge(&mut self, other: BodyEffects) {
         self.can_throw = self.can_throw || other.can_throw;
+        self.throw_site = self.throw_site.or(other.throw_site);
         self.is_async = self.is_async || other.is_async;
         self.has_side_effects = self.has_side_effects || other.has_side_effects;
     }
+
+    /// Record that the body throws at `location`
+    fn throws(&mut self, location: SourceLocation, kind: ThrowSiteKind) {
+        self.can_throw = true;
+        self.throw_site.get_or_insert(ThrowSite { location, kind });
+    }
 }
 
 /// Analyze all functions in a compilation unit for effects
@@ -625,39 +737,94 @@ pub fn analyze_file_effects(
 ) {
     let mut analyzer = EffectAnalyzer::new(symbol_table, type_table);
 
-    // Analyze all functions
-    for function in &file.functions {
-        let effects = analyzer.analyze_function(function);
-        // The effects are already stored in the function, but we could
-        // update them here if needed
+    // The effects are already stored in the functions, but we could update
+    // them here if needed
+    for (class, function) in file_functions(file) {
+        match class {
+            Some(class) => analyzer.analyze_constructor(class, function),
+            None => analyzer.analyze_function(function),
+        };
     }
+}
 
-    // Analyze functions in classes
-    for class in &file.classes {
-        for method in &class.methods {
-            let effects = analyzer.analyze_function(method);
+/// Verify that no `@:noThrow` function in a compilation unit can throw.
+///
+/// Functions are re-analyzed until no more are found to throw, so a call to a
+/// throwing function declared further down is still seen.
+pub fn check_no_throw(
+    file: &crate::tast::node::TypedFile,
+    symbol_table: &SymbolTable,
+    type_table: &RefCell<TypeTable>,
+) -> Vec<NoThrowViolation> {
+    let functions = file_functions(file);
+    if !functions
+        .iter()
+        .any(|(_, function)| function.metadata.is_no_throw)
+    {
+        return Vec::new();
+    }
+
+    let mut analyzer = EffectAnalyzer::new(symbol_table, type_table);
+    loop {
+        let known = analyzer.throwing_functions.len();
+        for &(class, function) in &functions {
+            match class {
+                Some(class) => analyzer.analyze_constructor(class, function),
+                None => analyzer.analyze_function(function),
+            };
         }
-        for constructor in &class.constructors {
-            let effects = analyzer.analyze_function(constructor);
+        if analyzer.throwing_functions.len() == known {
+            break;
         }
     }
 
-    // Analyze functions in abstracts
+    functions
+        .iter()
+        .filter(|(_, function)| {
+            function.metadata.is_no_throw && analyzer.can_throw(function.symbol_id)
+        })
+        .map(|(_, function)| NoThrowViolation {
+            function: function.symbol_id,
+            location: function.source_location,
+            path: analyzer.throw_path(function.symbol_id),
+        })
+        .collect()
+}
+
+/// Every function with a body in `file`; constructors come with their class
+fn file_functions(file: &crate::tast::node::TypedFile) -> Vec<(Option<SymbolId>, &TypedFunction)> {
+    let mut functions: Vec<(Option<SymbolId>, &TypedFunction)> =
+        file.functions.iter().map(|f| (None, f)).collect();
+
+    // Functions in classes
+    for class in &file.classes {
+        functions.extend(class.methods.iter().map(|m| (None, m)));
+        functions.extend(
+            class
+                .constructors
+                .iter()
+                .map(|c| (Some(class.symbol_id), c)),
+        );
+    }
+
+    // Functions in abstracts
     for abstract_type in &file.abstracts {
-        for method in &abstract_type.methods {
-            let effects = analyzer.analyze_function(method);
-        }
-        for constructor in &abstract_type.constructors {
-            let effects = analyzer.analyze_function(constructor);
-        }
+        functions.extend(abstract_type.methods.iter().map(|m| (None, m)));
+        functions.extend(
+            abstract_type
+                .constructors
+                .iter()
+                .map(|c| (Some(abstract_type.symbol_id), c)),
+        );
     }
 
-    // Analyze module-level functions
+    // Module-level functions
     for field in &file.module_fields {
         if let crate::tast::node::TypedModuleFieldKind::Function(function) = &field.kind {
-            let effects = analyzer.analyze_function(function);
+            functions.push((None, function));
         }
     }
+    functions
 }
 
 #[cfg(test)]
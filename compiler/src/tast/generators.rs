@@ -0,0 +1,363 @@
+//! Generator functions (`@:yield`)
+//!
+//! A method whose body contains `@:yield value` is a generator: calling it
+//! returns a `rayzor.Generator<T>`, an iterator that runs the body lazily on a
+//! `sys.thread.Coroutine`, up to the next yield per `next()`:
+//!
+//! ```haxe
+//! function evens(limit:Int):Iterator<Int> {
+//!     var i = 0;
+//!     while (i < limit) {
+//!         @:yield i;
+//!         i += 2;
+//!     }
+//! }
+//! ```
+//!
+//! is rewritten before lowering into
+//!
+//! ```haxe
+//! function evens(limit:Int):rayzor.Generator<Int> {
+//!     return new rayzor.Generator<Int>(function():Void { ... });
+//! }
+//! ```
+//!
+//! and each `@:yield value` is lowered as `sys.thread.Coroutine.yield(value)`.
+//! A declared `Iterator<T>` or `Iterable<T>` return type becomes
+//! `rayzor.Generator<T>`, which satisfies both and can be iterated with
+//! `for`. `@yield return value`, the syntax of the `yield` haxelib, works
+//! too. A bare `return` ends the generator.
+//!
+//! Yields inside nested function literals belong to those functions, which
+//! are not rewritten.
+
+use parser::{
+    BlockElement, ClassField, ClassFieldKind, Expr, ExprKind, Function, HaxeFile, Metadata, Span,
+    Type, TypeDeclaration, TypePath,
+};
+
+/// Metadata name of `@:yield` (and `@yield`)
+pub const YIELD_META: &str = "yield";
+
+/// Fully qualified path of the coroutine `yield` a `@:yield` lowers to
+pub const COROUTINE_YIELD_PATH: [&str; 4] = ["sys", "thread", "Coroutine", "yield"];
+
+/// Stdlib types a file with generators needs loaded
+pub const GENERATOR_DEPENDENCIES: [&str; 2] = ["rayzor.Generator", "sys.thread.Coroutine"];
+
+/// The value yielded by `@meta expr`, if it is a `@:yield`
+pub fn yielded_value<'a>(meta: &Metadata, expr: &'a Expr) -> Option<&'a Expr> {
+    if meta.name != YIELD_META {
+        return None;
+    }
+    match &expr.kind {
+        ExprKind::Return(Some(value)) => Some(value),
+        _ => Some(expr),
+    }
+}
+
+/// Whether `func` is a generator
+pub fn is_generator(func: &Function) -> bool {
+    func.body.as_deref().is_some_and(contains_yield)
+}
+
+/// Whether `expr` yields, not counting nested function literals
+fn contains_yield(expr: &Expr) -> bool {
+    let any = |exprs: &[Expr]| exprs.iter().any(contains_yield);
+    let opt = |expr: &Option<Box<Expr>>| expr.as_deref().is_some_and(contains_yield);
+    match &expr.kind {
+        ExprKind::Meta { meta, expr } => meta.name == YIELD_META || contains_yield(expr),
+        ExprKind::Function(_) | ExprKind::Arrow { .. } => false,
+        ExprKind::Field { expr, .. }
+        | ExprKind::Unary { expr, .. }
+        | ExprKind::Cast { expr, .. }
+        | ExprKind::TypeCheck { expr, .. }
+        | ExprKind::Paren(expr)
+        | ExprKind::Throw(expr)
+        | ExprKind::Untyped(expr)
+        | ExprKind::Inline(expr)
+        | ExprKind::StaticVar(expr) => contains_yield(expr),
+        ExprKind::Return(value) | ExprKind::Var { expr: value, .. } => opt(value),
+        ExprKind::Final { expr: value, .. } => opt(value),
+        ExprKind::Index { expr, index } => contains_yield(expr) || contains_yield(index),
+        ExprKind::Call { expr, args } => contains_yield(expr) || any(args),
+        ExprKind::New { args, .. } => any(args),
+        ExprKind::Binary { left, right, .. } | ExprKind::Assign { left, right, .. } => {
+            contains_yield(left) || contains_yield(right)
+        }
+        ExprKind::Ternary {
+            cond,
+            then_expr,
+            else_expr,
+        } => contains_yield(cond) || contains_yield(then_expr) || contains_yield(else_expr),
+        ExprKind::Array(items) | ExprKind::Tuple(items) => any(items),
+        ExprKind::Map(entries) => entries
+            .iter()
+            .any(|(key, value)| contains_yield(key) || contains_yield(value)),
+        ExprKind::Object(fields) => fields.iter().any(|field| contains_yield(&field.expr)),
+        ExprKind::Block(elements) => elements
+            .iter()
+            .any(|element| matches!(element, BlockElement::Expr(e) if contains_yield(e))),
+        ExprKind::If {
+            cond,
+            then_branch,
+            else_branch,
+        } => contains_yield(cond) || contains_yield(then_branch) || opt(else_branch),
+        ExprKind::Switch {
+            expr,
+            cases,
+            default,
+        } => {
+            contains_yield(expr)
+                || cases.iter().any(|case| {
+                    case.guard.as_ref().is_some_and(contains_yield) || contains_yield(&case.body)
+                })
+                || opt(default)
+        }
+        ExprKind::For { iter, body, .. } => contains_yield(iter) || contains_yield(body),
+        ExprKind::While { cond, body } | ExprKind::DoWhile { body, cond } => {
+            contains_yield(cond) || contains_yield(body)
+        }
+        ExprKind::Try {
+            expr,
+            catches,
+            finally_block,
+        } => {
+            contains_yield(expr)
+                || catches.iter().any(|catch| contains_yield(&catch.body))
+                || opt(finally_block)
+        }
+        _ => false,
+    }
+}
+
+fn path_type(package: &[&str], name: &str, params: Vec<Type>, span: Span) -> Type {
+    Type::Path {
+        path: TypePath {
+            package: package.iter().map(|p| p.to_string()).collect(),
+            name: name.to_string(),
+            sub: None,
+        },
+        params,
+        span,
+    }
+}
+
+/// Element type of a declared `Iterator<T>`, `Iterable<T>` or `Generator<T>`
+fn iterated_type(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path { path, params, .. }
+            if params.len() == 1
+                && matches!(path.name.as_str(), "Iterator" | "Iterable" | "Generator") =>
+        {
+            params.first()
+        }
+        _ => None,
+    }
+}
+
+/// Rewrite the body of generator `func` to return a `rayzor.Generator<T>`
+/// running it
+fn desugar_generator(func: &mut Function) {
+    let Some(body) = func.body.take() else {
+        return;
+    };
+    let span = body.span;
+    let iterated = func.return_type.as_ref().and_then(iterated_type).cloned();
+    // Any other declared type is kept, for the type checker to reject
+    let rewrite_return_type = func.return_type.is_none() || iterated.is_some();
+    let item = iterated.unwrap_or_else(|| path_type(&[], "Dynamic", Vec::new(), span));
+    if rewrite_return_type {
+        func.return_type = Some(path_type(
+            &["rayzor"],
+            "Generator",
+            vec![item.clone()],
+            span,
+        ));
+    }
+
+    let closure = Expr {
+        kind: ExprKind::Function(Function {
+            name: String::new(),
+            type_params: Vec::new(),
+            params: Vec::new(),
+            return_type: Some(path_type(&[], "Void", Vec::new(), span)),
+            body: Some(body),
+            span,
+        }),
+        span,
+    };
+    let generator = Expr {
+        kind: ExprKind::New {
+            type_path: TypePath {
+                package: vec!["rayzor".to_string()],
+                name: "Generator".to_string(),
+                sub: None,
+            },
+            params: vec![item],
+            args: vec![closure],
+        },
+        span,
+    };
+    let ret = Expr {
+        kind: ExprKind::Return(Some(Box::new(generator))),
+        span,
+    };
+    func.body = Some(Box::new(Expr {
+        kind: ExprKind::Block(vec![BlockElement::Expr(ret)]),
+        span,
+    }));
+}
+
+fn desugar_fields(fields: &mut [ClassField]) {
+    for field in fields {
+        if let ClassFieldKind::Function(func) = &mut field.kind {
+            if is_generator(func) {
+                desugar_generator(func);
+            }
+        }
+    }
+}
+
+/// Rewrite the generator methods of `file` as described in the module docs.
+///
+/// Returns None when the file has no generators.
+pub fn desugar_generators(file: &HaxeFile) -> Option<HaxeFile> {
+    let has_generators = file.declarations.iter().any(|decl| {
+        let fields = match decl {
+            TypeDeclaration::Class(class) => &class.fields,
+            TypeDeclaration::Abstract(abs) => &abs.fields,
+            _ => return false,
+        };
+        fields
+            .iter()
+            .any(|field| matches!(&field.kind, ClassFieldKind::Function(f) if is_generator(f)))
+    });
+    if !has_generators {
+        return None;
+    }
+
+    let mut desugared = file.clone();
+    for decl in &mut desugared.declarations {
+        match decl {
+            TypeDeclaration::Class(class) => desugar_fields(&mut class.fields),
+            TypeDeclaration::Abstract(abs) => desugar_fields(&mut abs.fields),
+            _ => {}
+        }
+    }
+    Some(desugared)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn method<'a>(file: &'a HaxeFile, name: &str) -> &'a Function {
+        let TypeDeclaration::Class(class) = &file.declarations[0] else {
+            panic!("expected a class");
+        };
+        class
+            .fields
+            .iter()
+            .find_map(|field| match &field.kind {
+                ClassFieldKind::Function(f) if f.name == name => Some(f),
+                _ => None,
+            })
+            .expect("method")
+    }
+
+    #[test]
+    fn test_desugar_generator() {
+        let source = r#"
+            class Main {
+                static function evens(limit:Int):Iterator<Int> {
+                    var i = 0;
+                    while (i < limit) {
+                        @:yield i;
+                        i += 2;
+                    }
+                }
+                static function legacy() {
+                    @yield return "a";
+                }
+                static function callbacks():Void {
+                    var f = function() { @:yield 1; };
+                }
+            }
+        "#;
+        let file = parser::parse_haxe_file("Main.hx", source, false).unwrap();
+        assert!(is_generator(method(&file, "evens")));
+        assert!(is_generator(method(&file, "legacy")));
+        // The yield belongs to the function literal
+        assert!(!is_generator(method(&file, "callbacks")));
+
+        let desugared = desugar_generators(&file).expect("has generators");
+        let evens = method(&desugared, "evens");
+        let Some(Type::Path { path, params, .. }) = &evens.return_type else {
+            panic!("expected a path return type");
+        };
+        assert_eq!(path.package, vec!["rayzor"]);
+        assert_eq!(path.name, "Generator");
+        assert!(matches!(&params[0], Type::Path { path, .. } if path.name == "Int"));
+
+        let body = evens.body.as_deref().unwrap();
+        let ExprKind::Block(elements) = &body.kind else {
+            panic!("expected a block");
+        };
+        let BlockElement::Expr(ret) = &elements[0] else {
+            panic!("expected an expression");
+        };
+        let ExprKind::Return(Some(generator)) = &ret.kind else {
+            panic!("expected a return");
+        };
+        let ExprKind::New {
+            type_path, args, ..
+        } = &generator.kind
+        else {
+            panic!("expected new rayzor.Generator");
+        };
+        assert_eq!(type_path.name, "Generator");
+        assert!(
+            matches!(&args[0].kind, ExprKind::Function(f) if contains_yield(f.body.as_deref().unwrap()))
+        );
+
+        // No declared type: a generator of Dynamic
+        let Some(Type::Path { params, .. }) = &method(&desugared, "legacy").return_type else {
+            panic!("expected a path return type");
+        };
+        assert!(matches!(&params[0], Type::Path { path, .. } if path.name == "Dynamic"));
+        assert_eq!(method(&desugared, "callbacks"), method(&file, "callbacks"));
+    }
+
+    #[test]
+    fn test_yielded_value() {
+        let source = r#"
+            class Main {
+                static function f() {
+                    @:yield 1;
+                    @yield return 2;
+                    @:keep 3;
+                }
+            }
+        "#;
+        let file = parser::parse_haxe_file("Main.hx", source, false).unwrap();
+        let body = method(&file, "f").body.as_deref().unwrap();
+        let ExprKind::Block(elements) = &body.kind else {
+            panic!("expected a block");
+        };
+        let values: Vec<Option<i64>> = elements
+            .iter()
+            .map(|element| match element {
+                BlockElement::Expr(Expr {
+                    kind: ExprKind::Meta { meta, expr },
+                    ..
+                }) => yielded_value(meta, expr).and_then(|value| match value.kind {
+                    ExprKind::Int(n) => Some(n),
+                    _ => None,
+                }),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(values, vec![Some(1), Some(2), None]);
+    }
+}
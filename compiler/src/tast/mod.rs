@@ -6,6 +6,7 @@ pub mod control_flow_analysis;
 pub mod core;
 pub mod core_types;
 pub mod effect_analysis;
+pub mod generators;
 pub mod generic_instantiation;
 pub mod generics;
 pub mod id_types;
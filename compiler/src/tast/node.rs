@@ -298,6 +298,42 @@ pub struct MethodOverload {
     pub source_location: SourceLocation,
 }
 
+/// Which side of a call a contract constrains
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContractKind {
+    /// @:requires(cond) - checked on entry, a caller bug when it fails
+    Requires,
+    /// @:ensures(cond) - checked before each return, with the returned
+    /// value bound to `result`
+    Ensures,
+}
+
+impl ContractKind {
+    /// Metadata name, without the leading colon
+    pub fn name(self) -> &'static str {
+        match self {
+            ContractKind::Requires => "requires",
+            ContractKind::Ensures => "ensures",
+        }
+    }
+}
+
+/// A @:requires/@:ensures contract on a method
+///
+/// The condition is lowered into a synthetic Bool method of the same class
+/// taking the method's parameters (and `result` for postconditions), which
+/// the contract check MIR pass calls in checked builds.
+#[derive(Debug, Clone)]
+pub struct FunctionContract {
+    pub kind: ContractKind,
+    /// The synthetic predicate method
+    pub predicate: SymbolId,
+    /// Condition source text, for the violation report
+    pub condition: String,
+    /// Location of the contract metadata
+    pub source_location: SourceLocation,
+}
+
 /// Safety mode for @:safety annotation
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SafetyMode {
@@ -597,8 +633,17 @@ pub struct FunctionMetadata {
     /// Whether this function is marked with @:to (abstract implicit conversion)
     pub is_to_conversion: bool,
 
+    /// Whether this function is marked with @:noThrow (verified by effect analysis)
+    pub is_no_throw: bool,
+
     /// Memory safety annotations
     pub memory_annotations: Vec<MemoryAnnotation>,
+
+    /// User metadata with constant arguments (for haxe.rtti.Meta)
+    pub user_metadata: Vec<TypedMetadata>,
+
+    /// @:requires/@:ensures contracts, in declaration order
+    pub contracts: Vec<FunctionContract>,
 }
 
 /// Generic type parameter with variance support
@@ -1504,6 +1549,9 @@ pub struct TypedClass {
 
     /// Derived traits from @:derive([Clone, Copy, ...])
     pub derived_traits: Vec<DerivedTrait>,
+
+    /// User metadata with constant arguments (for haxe.rtti.Meta)
+    pub metadata: Vec<TypedMetadata>,
 }
 
 impl TypedClass {
@@ -1617,6 +1665,9 @@ pub struct TypedEnum {
 
     /// Source location
     pub source_location: SourceLocation,
+
+    /// User metadata with constant arguments (for haxe.rtti.Meta)
+    pub metadata: Vec<TypedMetadata>,
 }
 
 /// Enum variant
@@ -1630,6 +1681,9 @@ pub struct TypedEnumVariant {
 
     /// Source location
     pub source_location: SourceLocation,
+
+    /// User metadata with constant arguments (for haxe.rtti.Meta)
+    pub metadata: Vec<TypedMetadata>,
 }
 
 /// Property accessor information for Haxe properties
@@ -1693,6 +1747,9 @@ pub struct TypedField {
 
     /// Source location
     pub source_location: SourceLocation,
+
+    /// User metadata with constant arguments (for haxe.rtti.Meta)
+    pub metadata: Vec<TypedMetadata>,
 }
 
 /// Type alias definition
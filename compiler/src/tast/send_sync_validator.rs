@@ -10,6 +10,8 @@
 //! 2. **Channel<T>** - T must be Send
 //! 3. **Arc<T>** - T must be Send + Sync
 //! 4. **Mutex<T>** - T can be any type (Mutex provides interior mutability)
+//! 5. **Tls<T>** - always Send + Sync; its contents are thread-confined, so
+//!    T can be any type
 //!
 //! ## Example
 //!
@@ -61,6 +61,14 @@ impl SpanConverter {
         }
     }
 
+    /// Source text of a span in the current file
+    pub fn source_text(&self, span: Span) -> Option<&str> {
+        self.source_map
+            .get_file(self.current_file_id)?
+            .content
+            .get(span.start..span.end)
+    }
+
     /// Create a source location for unknown/synthetic nodes
     pub fn unknown_location(&self) -> SourceLocation {
         SourceLocation::unknown()
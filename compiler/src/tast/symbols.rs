@@ -290,6 +290,18 @@ pub struct Symbol {
     /// System libraries from @:clib(["sqlite3"]) metadata
     /// Discovered via pkg-config and loaded into TCC context
     pub c_libs: Option<Vec<InternedString>>,
+    /// Message and version from @:deprecated("use Y instead", "2.0");
+    /// set together with `SymbolFlags::DEPRECATED`
+    pub deprecation: Option<Deprecation>,
+}
+
+/// Why and since when a symbol is deprecated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deprecation {
+    /// Explanation shown at each use, e.g. "use Y instead"
+    pub message: Option<InternedString>,
+    /// Version the symbol was deprecated in
+    pub since: Option<InternedString>,
 }
 
 /// Bitflags for various symbol properties
@@ -321,6 +333,12 @@ impl SymbolFlags {
     pub const NO_MANGLE: Self = Self(1 << 15);
     /// @:gpuStruct - GPU-compatible flat struct layout (4-byte floats, no object header)
     pub const GPU_STRUCT: Self = Self(1 << 16);
+    /// Synthetic class holding a module's module-level fields (Haxe 4)
+    pub const MODULE_FIELDS: Self = Self(1 << 17);
+    /// @:struct - value type: flat layout (no object header), copied on assignment
+    pub const STRUCT: Self = Self(1 << 18);
+    /// @:keepLayout - @:struct fields stay in declaration order (FFI-visible)
+    pub const KEEP_LAYOUT: Self = Self(1 << 19);
 
     pub const fn empty() -> Self {
         Self::NONE
@@ -379,6 +397,16 @@ impl SymbolFlags {
     pub const fn is_gpu_struct(self) -> bool {
         self.contains(Self::GPU_STRUCT)
     }
+
+    /// Check if this symbol has @:struct metadata (value type)
+    pub const fn is_value_struct(self) -> bool {
+        self.contains(Self::STRUCT)
+    }
+
+    /// Check if this symbol has @:keepLayout metadata
+    pub const fn is_keep_layout(self) -> bool {
+        self.contains(Self::KEEP_LAYOUT)
+    }
 }
 
 impl Default for SymbolFlags {
@@ -418,6 +446,7 @@ impl Symbol {
             c_includes: None,
             c_sources: None,
             c_libs: None,
+            deprecation: None,
         }
     }
 
@@ -868,6 +897,7 @@ impl SymbolTable {
             c_includes: None,
             c_sources: None,
             c_libs: None,
+            deprecation: None,
         };
 
         // Add the symbol to the table
@@ -907,6 +937,7 @@ impl SymbolTable {
             c_includes: None,
             c_sources: None,
             c_libs: None,
+            deprecation: None,
         };
 
         // Add the symbol to the table
@@ -950,6 +981,7 @@ impl SymbolTable {
             c_includes: None,
             c_sources: None,
             c_libs: None,
+            deprecation: None,
         };
 
         // Add the symbol to the table
@@ -992,6 +1024,7 @@ impl SymbolTable {
             c_includes: None,
             c_sources: None,
             c_libs: None,
+            deprecation: None,
         };
 
         self.add_symbol(symbol);
@@ -1032,6 +1065,7 @@ impl SymbolTable {
             c_includes: None,
             c_sources: None,
             c_libs: None,
+            deprecation: None,
         };
 
         self.add_symbol(symbol);
@@ -1082,6 +1116,7 @@ impl SymbolTable {
             c_includes: None,
             c_sources: None,
             c_libs: None,
+            deprecation: None,
         };
 
         self.add_symbol(symbol);
@@ -1113,6 +1148,7 @@ impl SymbolTable {
             c_includes: None,
             c_sources: None,
             c_libs: None,
+            deprecation: None,
         };
 
         self.add_symbol(symbol);
@@ -1148,6 +1184,7 @@ impl SymbolTable {
             c_includes: None,
             c_sources: None,
             c_libs: None,
+            deprecation: None,
         };
 
         self.add_symbol(symbol);
@@ -1183,6 +1220,7 @@ impl SymbolTable {
             c_includes: None,
             c_sources: None,
             c_libs: None,
+            deprecation: None,
         };
 
         self.add_symbol(symbol);
@@ -1219,6 +1257,7 @@ impl SymbolTable {
             c_includes: None,
             c_sources: None,
             c_libs: None,
+            deprecation: None,
         };
 
         self.add_symbol(symbol);
@@ -6,6 +6,7 @@
 
 use crate::tast::{
     core::{Mutability, TypeKind},
+    core_types::CoreTypeChecker,
     node::{DerivedTrait, TypedClass},
     SymbolId, SymbolTable, TypeId, TypeTable,
 };
@@ -68,6 +69,13 @@ impl<'a> TraitChecker<'a> {
 
     /// Generic trait checking
     pub fn implements_trait(&self, type_id: TypeId, trait_: DerivedTrait) -> bool {
+        // Tls<T> is Send + Sync whatever T is: each thread only sees its own value
+        if matches!(trait_, DerivedTrait::Send | DerivedTrait::Sync)
+            && CoreTypeChecker::new(self.type_table, self.symbol_table).is_tls(type_id)
+        {
+            return true;
+        }
+
         // Extract the kind from the type table, then drop the borrow
         let type_kind = {
             let type_table = self.type_table.borrow();
@@ -4,12 +4,14 @@
 //! providing a complete type checking phase with diagnostic reporting.
 
 use super::{
+    effect_analysis::{check_no_throw, NoThrowViolation, ThrowSite, ThrowSiteKind},
     node::{
         BinaryOperator, CastKind, StringInterpolationPart, TypedClass, TypedEnum, TypedExpression,
         TypedExpressionKind, TypedField, TypedFile, TypedFunction, TypedInterface, TypedMapEntry,
         TypedMethodSignature, TypedStatement, TypedSwitchCase,
     },
     send_sync_validator::{SendSyncError, SendSyncValidator},
+    symbols::Deprecation,
     type_checker::TypeCompatibility,
     type_diagnostics::{TypeDiagnosticEmitter, TypeErrorContext},
     AccessLevel, FlowSafetyError, FlowSafetyResults, InternedString, NamespaceResolver,
@@ -32,6 +34,8 @@ pub struct TypeCheckingPhase<'a> {
     type_table: &'a Rc<RefCell<TypeTable>>,
     /// Symbol table (stored separately for SendSyncValidator)
     symbol_table: &'a SymbolTable,
+    /// Source text, for the exact spans of deprecation suggestions
+    source_map: &'a SourceMap,
     /// Stack of expected return types for nested function contexts
     expected_return_types: Vec<TypeId>,
     /// Temporary reference to the typed file for constraint validation
@@ -70,6 +74,7 @@ impl<'a> TypeCheckingPhase<'a> {
             string_interner,
             type_table,
             symbol_table,
+            source_map,
             expected_return_types: Vec::new(),
             current_typed_file: None,
             current_method_context: None,
@@ -142,6 +147,9 @@ impl<'a> TypeCheckingPhase<'a> {
         // Phase 7: Send/Sync validation for thread safety
         self.run_send_sync_validation(typed_file)?;
 
+        // Phase 8: @:noThrow verification
+        self.run_no_throw_verification(typed_file)?;
+
         // Return error if we collected any error diagnostics
         if self.diagnostics.has_errors() {
             Err(format!(
@@ -264,29 +272,16 @@ impl<'a> TypeCheckingPhase<'a> {
             // Convert flow safety errors to diagnostics
             self.emit_flow_safety_diagnostics(&results);
 
-            // Log performance metrics if in debug mode
-            #[cfg(debug_assertions)]
-            {
-                eprintln!("Flow analysis metrics:");
-                eprintln!(
-                    "  Functions analyzed: {}",
-                    results.metrics.functions_analyzed
-                );
-                eprintln!("  Blocks processed: {}", results.metrics.blocks_processed);
-                eprintln!(
-                    "  CFG construction time: {} μs",
-                    results.metrics.cfg_construction_time_us
-                );
-                eprintln!(
-                    "  Variable analysis time: {} μs",
-                    results.metrics.variable_analysis_time_us
-                );
-                eprintln!(
-                    "  Null safety time: {} μs",
-                    results.metrics.null_safety_time_us
-                );
-                eprintln!("  Dead code time: {} μs", results.metrics.dead_code_time_us);
-            }
+            // Log performance metrics
+            log::debug!(
+                "Flow analysis metrics: {} functions, {} blocks, cfg {} μs, variables {} μs, null safety {} μs, dead code {} μs",
+                results.metrics.functions_analyzed,
+                results.metrics.blocks_processed,
+                results.metrics.cfg_construction_time_us,
+                results.metrics.variable_analysis_time_us,
+                results.metrics.null_safety_time_us,
+                results.metrics.dead_code_time_us
+            );
         }
 
         Ok(())
@@ -336,6 +331,183 @@ impl<'a> TypeCheckingPhase<'a> {
         });
     }
 
+    /// Verify that `@:noThrow` functions can't throw
+    fn run_no_throw_verification(&mut self, typed_file: &TypedFile) -> Result<(), String> {
+        for violation in check_no_throw(typed_file, self.symbol_table, self.type_table) {
+            self.emit_no_throw_violation(&violation);
+        }
+        Ok(())
+    }
+
+    /// Report a `@:noThrow` function that can throw, with the path from its
+    /// body down to the code that throws
+    fn emit_no_throw_violation(&mut self, violation: &NoThrowViolation) {
+        let name = self.symbol_name(violation.function);
+        let Some((first, rest)) = violation.path.split_first() else {
+            return;
+        };
+        let span = location_to_span(first.location);
+        let mut builder = diagnostics::DiagnosticBuilder::error(
+            format!("`{}` is marked @:noThrow but can throw", name),
+            span.clone(),
+        )
+        .code(crate::error_codes::format_error_code(6401))
+        .label(span, self.describe_throw_site(first))
+        .secondary_label(
+            location_to_span(violation.location),
+            "marked @:noThrow here",
+        );
+        for site in rest {
+            builder = builder.note(format!(
+                "at {}:{}: {}",
+                site.location.line,
+                site.location.column,
+                self.describe_throw_site(site)
+            ));
+        }
+        let diagnostic = builder
+            .help(format!(
+                "catch the exception inside `{}` or remove @:noThrow",
+                name
+            ))
+            .build();
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn describe_throw_site(&self, site: &ThrowSite) -> String {
+        match site.kind {
+            ThrowSiteKind::Throw => "exception thrown here".to_string(),
+            ThrowSiteKind::Call(callee) => {
+                format!("calls `{}`, which can throw", self.symbol_name(callee))
+            }
+            ThrowSiteKind::Constructor(class) => {
+                format!(
+                    "constructs `{}`, whose constructor can throw",
+                    self.symbol_name(class)
+                )
+            }
+            ThrowSiteKind::Macro => "macro expansion may throw".to_string(),
+        }
+    }
+
+    fn symbol_name(&self, symbol_id: SymbolId) -> String {
+        self.symbol_table
+            .get_symbol(symbol_id)
+            .map(|symbol| self.get_string(symbol.name).to_string())
+            .unwrap_or_else(|| "<unknown>".to_string())
+    }
+
+    /// Warn when `expr` uses a `@:deprecated` class, field or method
+    fn check_deprecated_use(&mut self, expr: &TypedExpression) {
+        // (symbol, whether it is written after a `.`)
+        let used: Vec<(SymbolId, bool)> = match &expr.kind {
+            TypedExpressionKind::Variable { symbol_id } => vec![(*symbol_id, false)],
+            TypedExpressionKind::FieldAccess { field_symbol, .. } => vec![(*field_symbol, true)],
+            TypedExpressionKind::MethodCall { method_symbol, .. } => vec![(*method_symbol, true)],
+            TypedExpressionKind::StaticFieldAccess {
+                class_symbol,
+                field_symbol,
+            } => vec![(*class_symbol, false), (*field_symbol, true)],
+            TypedExpressionKind::StaticMethodCall {
+                class_symbol,
+                method_symbol,
+                ..
+            } => vec![(*class_symbol, false), (*method_symbol, true)],
+            TypedExpressionKind::New { class_type, .. } => {
+                match self.type_table.borrow().get(*class_type).map(|t| &t.kind) {
+                    Some(TypeKind::Class { symbol_id, .. }) => vec![(*symbol_id, false)],
+                    _ => return,
+                }
+            }
+            _ => return,
+        };
+
+        for (symbol_id, is_member) in used {
+            let Some(deprecation) = self
+                .symbol_table
+                .get_symbol(symbol_id)
+                .and_then(|symbol| symbol.deprecation)
+            else {
+                continue;
+            };
+            self.emit_deprecation_warning(symbol_id, deprecation, expr.source_location, is_member);
+        }
+    }
+
+    /// Report a use of a deprecated symbol. When the message names a
+    /// replacement ("use Y instead"), the warning carries a machine-applicable
+    /// suggestion that renames the use to it.
+    fn emit_deprecation_warning(
+        &mut self,
+        symbol_id: SymbolId,
+        deprecation: Deprecation,
+        location: SourceLocation,
+        is_member: bool,
+    ) {
+        let name = self.symbol_name(symbol_id);
+        let message = deprecation.message.map(|m| self.get_string(m).to_string());
+        let since = deprecation.since.map(|v| self.get_string(v).to_string());
+
+        let mut text = format!("`{}` is deprecated", name);
+        if let Some(since) = &since {
+            text.push_str(&format!(" since {}", since));
+        }
+        if let Some(message) = &message {
+            text.push_str(&format!(": {}", message));
+        }
+
+        let use_span = self.use_site_span(location, &name, is_member);
+        let span = use_span
+            .clone()
+            .unwrap_or_else(|| location_to_span(location));
+        let mut builder = diagnostics::DiagnosticBuilder::warning(text, span.clone())
+            .code("W0004")
+            .label(span, "deprecated");
+        if let (Some(use_span), Some(replacement)) = (
+            use_span,
+            message.as_deref().and_then(replacement_identifier),
+        ) {
+            builder = builder.suggestion_with_applicability(
+                format!("use `{}`", replacement),
+                use_span,
+                replacement,
+                diagnostics::Applicability::MachineApplicable,
+            );
+        }
+        self.diagnostics.push(builder.build());
+    }
+
+    /// Span of `name` where an expression at `location` uses it: the
+    /// expression itself for a plain identifier, the part after the first
+    /// `.name` for a member access
+    fn use_site_span(
+        &self,
+        location: SourceLocation,
+        name: &str,
+        is_member: bool,
+    ) -> Option<SourceSpan> {
+        let file_id = source_map::FileId::new(location.file_id as usize);
+        let file = self.source_map.get_file(file_id)?;
+        let rest = file.content.get(location.byte_offset as usize..)?;
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+        let offset = rest.match_indices(name).map(|(i, _)| i).find(|&i| {
+            let before = rest[..i].chars().next_back();
+            let after = rest[i + name.len()..].chars().next();
+            let starts_word = if is_member {
+                before == Some('.')
+            } else {
+                i == 0 || !before.is_some_and(is_ident)
+            };
+            starts_word && !after.is_some_and(is_ident)
+        })?;
+        let start = location.byte_offset as usize + offset;
+        Some(SourceSpan::new(
+            file.offset_to_position(start),
+            file.offset_to_position(start + name.len()),
+            file_id,
+        ))
+    }
+
     /// Convert flow safety results to diagnostics
     fn emit_flow_safety_diagnostics(&mut self, results: &FlowSafetyResults) {
         // Emit errors
@@ -531,7 +703,7 @@ impl<'a> TypeCheckingPhase<'a> {
         for stmt in &method.body {
             if let Err(e) = self.check_statement(stmt) {
                 // Continue checking even if there's an error
-                eprintln!("Type checking error: {}", e);
+                log::debug!("Type checking error: {}", e);
             }
         }
 
@@ -934,6 +1106,8 @@ impl<'a> TypeCheckingPhase<'a> {
 
     /// Check an expression and return its type
     pub fn check_expression(&mut self, expr: &TypedExpression) -> Result<TypeId, String> {
+        self.check_deprecated_use(expr);
+
         match &expr.kind {
             TypedExpressionKind::BinaryOp {
                 left,
@@ -2387,7 +2561,7 @@ impl<'a> TypeCheckingPhase<'a> {
             } => {
                 if let Err(e) = self.check_expression(expression) {
                     // Continue checking even if expression has errors
-                    eprintln!("Expression error: {}", e);
+                    log::debug!("Expression error: {}", e);
                 }
             }
             TypedStatement::Return {
@@ -2398,7 +2572,7 @@ impl<'a> TypeCheckingPhase<'a> {
                     let expr_type = match self.check_expression(return_expr) {
                         Ok(t) => t,
                         Err(e) => {
-                            eprintln!("Return expression error: {}", e);
+                            log::debug!("Return expression error: {}", e);
                             return Ok(());
                         }
                     };
@@ -4009,6 +4183,43 @@ impl<'a> TypeCheckingPhase<'a> {
     }
 }
 
+/// Single-position span at `location`
+fn location_to_span(location: SourceLocation) -> SourceSpan {
+    SourceSpan::single_position(
+        SourcePosition::new(
+            location.line as usize,
+            location.column as usize,
+            location.byte_offset as usize,
+        ),
+        source_map::FileId::new(location.file_id as usize),
+    )
+}
+
+/// The identifier a deprecation message tells users to switch to, as in
+/// "use Y instead" or "Use `Y`.". Qualified names and calls are not taken:
+/// only a bare identifier can replace the deprecated one in place.
+fn replacement_identifier(message: &str) -> Option<&str> {
+    let lower = message.to_ascii_lowercase();
+    let (at, _) = lower.match_indices("use ").find(|&(i, _)| {
+        i == 0
+            || !message[..i]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_')
+    })?;
+    let word = message[at + 4..].split_whitespace().next()?;
+    let word = word
+        .trim_start_matches(['`', '\'', '"'])
+        .trim_end_matches(['`', '\'', '"', '.', ',', ';', ':', '!']);
+    let mut chars = word.chars();
+    let first = chars.next()?;
+    if (first.is_alphabetic() || first == '_') && chars.all(|c| c.is_alphanumeric() || c == '_') {
+        Some(word)
+    } else {
+        None
+    }
+}
+
 /// Run type checking on a typed file with full diagnostic support
 pub fn type_check_with_diagnostics(
     typed_file: &mut TypedFile,
@@ -4046,6 +4257,15 @@ mod tests {
     use diagnostics::ErrorFormatter;
     use parser::parse_haxe_file;
 
+    #[test]
+    fn test_replacement_identifier() {
+        assert_eq!(replacement_identifier("use add instead"), Some("add"));
+        assert_eq!(replacement_identifier("Use `sum`."), Some("sum"));
+        assert_eq!(replacement_identifier("slow because of boxing"), None);
+        assert_eq!(replacement_identifier("use Math.max instead"), None);
+        assert_eq!(replacement_identifier("will be removed"), None);
+    }
+
     #[test]
     fn test_type_checking_pipeline() {
         let haxe_code = r#"
@@ -4544,6 +4764,53 @@ class CustomException {
         );
     }
 
+    #[test]
+    fn test_no_throw_verification() {
+        let haxe_code = r#"
+class NoThrowTest {
+    static function check(x:Int):Int {
+        if (x < 0) {
+            throw "negative";
+        }
+        return x;
+    }
+
+    @:noThrow
+    static function caught(x:Int):Int {
+        try {
+            return check(x);
+        } catch (e:String) {
+            return 0;
+        }
+    }
+
+    @:noThrow
+    static function hot(x:Int):Int {
+        return check(x) * 2;
+    }
+
+    public static function main() {
+        trace(caught(1) + hot(2));
+    }
+}
+        "#;
+
+        let result = crate::pipeline::compile_haxe_source(haxe_code);
+
+        // Only `hot` lets the exception escape; the error points at the call
+        // and follows it to the throw in `check`
+        let errors: Vec<_> = result
+            .errors
+            .iter()
+            .filter(|e| e.message.contains("@:noThrow"))
+            .collect();
+        assert_eq!(errors.len(), 1, "{:?}", result.errors);
+        assert!(errors[0]
+            .message
+            .contains("[E6401] `hot` is marked @:noThrow"));
+        assert!(errors[0].message.contains("exception thrown here"));
+    }
+
     #[test]
     fn test_object_literal_validation() {
         let haxe_code = r#"
@@ -2,9 +2,12 @@
 //!
 //! Wraps `AotCompiler` with a config struct for CLI integration.
 
+use crate::codegen::aot_compiler::AotCompiler;
 #[cfg(feature = "llvm-backend")]
-use crate::codegen::aot_compiler::{AotCompiler, OutputFormat};
+use crate::codegen::aot_compiler::OutputFormat;
 use crate::ir::optimization::OptimizationLevel;
+#[cfg(feature = "llvm-backend")]
+use crate::ir::optimization::PassSizeLimits;
 use std::path::PathBuf;
 
 /// Configuration for AOT compilation via the unified CLI.
@@ -24,6 +27,18 @@ pub struct AotConfig {
     pub strip: bool,
     /// Strip debug symbols from binary
     pub strip_symbols: bool,
+    /// Let the linker drop unreferenced functions and data
+    pub gc_sections: bool,
+    /// Link a position-independent executable
+    pub pie: bool,
+    /// Full RELRO (ELF targets)
+    pub relro: bool,
+    /// Stack canaries (`-fstack-protector-strong`)
+    pub stack_protector: bool,
+    /// Non-executable stack
+    pub nx: bool,
+    /// Fully static executable against musl
+    pub static_link: bool,
     /// Verbose output
     pub verbose: bool,
     /// Custom linker path
@@ -36,6 +51,8 @@ pub struct AotConfig {
     pub enable_cache: bool,
     /// Custom BLADE cache directory
     pub cache_dir: Option<PathBuf>,
+    /// Function size limits of the MIR optimization passes
+    pub pass_size_limits: PassSizeLimits,
 }
 
 /// Run AOT compilation with the given config.
@@ -53,10 +70,22 @@ pub fn run_aot(config: AotConfig) -> Result<(), String> {
     compiler.opt_level = config.opt_level;
     compiler.strip = !config.strip; // AotCompiler.strip means "don't tree-shake" when false
     compiler.strip_symbols = config.strip_symbols;
+    compiler.gc_sections = config.gc_sections;
+    compiler.pie = config.pie;
+    compiler.relro = config.relro;
+    compiler.stack_protector = config.stack_protector;
+    compiler.nx = config.nx;
+    compiler.static_link = config.static_link;
+    // Static builds target the host architecture's musl flavour unless a
+    // target is given
+    if config.static_link && compiler.target_triple.is_none() && cfg!(target_os = "linux") {
+        compiler.target_triple = Some(format!("{}-unknown-linux-musl", std::env::consts::ARCH));
+    }
     compiler.verbose = config.verbose;
     compiler.linker = config.linker;
     compiler.runtime_dir = config.runtime_dir;
     compiler.sysroot = config.sysroot;
+    compiler.pass_size_limits = config.pass_size_limits;
 
     // Default output path
     let output = config.output.unwrap_or_else(|| {
@@ -66,8 +95,14 @@ pub fn run_aot(config: AotConfig) -> Result<(), String> {
             .to_string_lossy()
             .to_string();
         match compiler.output_format {
-            OutputFormat::Executable => {
-                if cfg!(target_os = "windows")
+            OutputFormat::Executable | OutputFormat::SizeReport => {
+                if compiler
+                    .target_triple
+                    .as_deref()
+                    .is_some_and(crate::codegen::wasi_runtime::is_wasm_triple)
+                {
+                    PathBuf::from(format!("{}.wasm", base))
+                } else if cfg!(target_os = "windows")
                     || compiler
                         .target_triple
                         .as_deref()
@@ -115,6 +150,10 @@ pub fn run_aot(config: AotConfig) -> Result<(), String> {
                 result.code_size,
                 result.target_triple
             );
+            if let Some(report) = &result.size_report {
+                println!();
+                print!("{}", report);
+            }
             println!("✓ Build succeeded");
             Ok(())
         }
@@ -122,6 +161,37 @@ pub fn run_aot(config: AotConfig) -> Result<(), String> {
     }
 }
 
+/// Check that librayzor_runtime.a provides every extern the program in
+/// `source_files` references, without compiling or linking
+/// (`rayzor aot --check-symbols`). Doesn't need the LLVM backend.
+pub fn check_aot_symbols(
+    source_files: &[String],
+    target_triple: Option<String>,
+    opt_level: OptimizationLevel,
+    strip: bool,
+    runtime_dir: Option<PathBuf>,
+    verbose: bool,
+) -> Result<(), String> {
+    if source_files.is_empty() {
+        return Err("No source files specified".to_string());
+    }
+
+    let compiler = AotCompiler {
+        target_triple,
+        opt_level,
+        strip: !strip, // as in run_aot
+        runtime_dir,
+        verbose,
+        ..AotCompiler::default()
+    };
+    let check = compiler.check_symbols(source_files)?;
+    if !check.is_ok() {
+        return Err(format!("Symbol check failed: {}", check));
+    }
+    println!("✓ {}", check);
+    Ok(())
+}
+
 /// Parse an optimization level string.
 pub fn parse_opt_level(s: &str) -> OptimizationLevel {
     match s {
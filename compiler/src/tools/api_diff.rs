@@ -0,0 +1,832 @@
+//! API stability report (`rayzor api-diff old/ new/`)
+//!
+//! Type checks two versions of a library and compares their public API, so
+//! a package author can tell which version number a release needs:
+//!
+//! - `removed`: a public type or member is gone
+//! - `renamed`: a removed member has a new public counterpart with the same
+//!   signature in the same type
+//! - `signature_changed`: parameter or return types, optionality, `static`,
+//!   a field's type or an abstract's underlying type changed
+//! - `visibility_reduced`: a public type or member is no longer public
+//! - `added`: new public API, which only needs a minor version
+//!
+//! Types and members are matched by qualified path (`geom.Point.distance`).
+//! Callers don't see parameter names, so renaming a parameter is not a
+//! change. When a whole type is removed or made private, only the type is
+//! reported, not each of its members.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::symbol_index::TypeNames;
+use crate::compilation::{CompilationConfig, CompilationUnit};
+use crate::tast::node::{
+    TypedField, TypedFunction, TypedMethodSignature, TypedModuleFieldKind, TypedParameter,
+    TypedTypeParameter,
+};
+use crate::tast::{
+    InternedString, Mutability, SymbolTable, TypeId, TypeTable, TypedFile, Visibility,
+};
+
+/// One type or member of a library
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiItem {
+    /// Qualified path, e.g. `geom.Point.distance`
+    pub path: String,
+    /// `class`, `interface`, `enum`, `typedef`, `abstract`, `field`,
+    /// `method`, `constructor`, `variant`, `function` or `variable`
+    pub kind: &'static str,
+    /// Haxe-style declaration, e.g. `function distance(p:Point):Float`
+    pub signature: String,
+    /// What callers depend on: the signature without parameter names
+    shape: String,
+    /// Public, and declared in a public type
+    pub public: bool,
+    /// Path of the enclosing type, for members
+    container: Option<String>,
+}
+
+impl ApiItem {
+    fn is_type(&self) -> bool {
+        self.container.is_none()
+            && matches!(
+                self.kind,
+                "class" | "interface" | "enum" | "typedef" | "abstract"
+            )
+    }
+}
+
+/// The types and members of one version of a library
+#[derive(Debug, Clone, Default)]
+pub struct ApiSurface {
+    pub items: BTreeMap<String, ApiItem>,
+}
+
+/// Kind of an API change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Removed,
+    Renamed,
+    SignatureChanged,
+    VisibilityReduced,
+    Added,
+}
+
+impl ChangeKind {
+    pub fn is_breaking(self) -> bool {
+        self != ChangeKind::Added
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ChangeKind::Removed => "removed",
+            ChangeKind::Renamed => "renamed",
+            ChangeKind::SignatureChanged => "signature changed",
+            ChangeKind::VisibilityReduced => "no longer public",
+            ChangeKind::Added => "added",
+        }
+    }
+}
+
+/// One difference between the two versions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiChange {
+    pub kind: ChangeKind,
+    pub path: String,
+    /// The replacement's path, for `renamed`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub renamed_to: Option<String>,
+    /// Declaration in the old version; `null` for `added`
+    pub old: Option<String>,
+    /// Declaration in the new version; `null` for `removed`
+    pub new: Option<String>,
+    pub breaking: bool,
+}
+
+/// Changes between two versions of a library
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ApiDiff {
+    /// Breaking changes first, each group sorted by path
+    pub changes: Vec<ApiChange>,
+}
+
+impl ApiSurface {
+    /// Type check the `.hx` files under `dir` and collect their API
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let mut paths = Vec::new();
+        collect_haxe_files(dir, &mut paths)?;
+        if paths.is_empty() {
+            return Err(format!("no .hx files in {}", dir.display()));
+        }
+        paths.sort();
+
+        let mut sources = Vec::new();
+        for path in paths {
+            let source = std::fs::read_to_string(&path)
+                .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+            sources.push((path.to_string_lossy().to_string(), source));
+        }
+        Self::from_sources(&sources)
+            .map_err(|e| format!("{} doesn't type check: {}", dir.display(), e))
+    }
+
+    /// Type check `(file name, source)` pairs together and collect their API
+    pub fn from_sources(sources: &[(String, String)]) -> Result<Self, String> {
+        let mut unit = CompilationUnit::new(CompilationConfig::default());
+        unit.load_stdlib()
+            .map_err(|e| format!("failed to load stdlib: {}", e))?;
+        for (file, source) in sources {
+            unit.add_file(source, file)?;
+        }
+
+        // The typed files include the stdlib modules the library uses; keep
+        // the declarations of its own sources
+        let declared = declared_names(&unit);
+        let typed_files = unit
+            .lower_to_tast()
+            .map_err(|errors| format!("{} errors", errors.len()))?;
+
+        let type_table = unit.type_table.borrow();
+        let mut surface = ApiSurface::default();
+        for typed_file in &typed_files {
+            let resolve = |name: InternedString| typed_file.get_string(name);
+            let mut collector = Collector {
+                symbol_table: &unit.symbol_table,
+                type_table: &type_table,
+                resolve: &resolve,
+                declared: &declared,
+                package: typed_file.metadata.package_name.clone().unwrap_or_default(),
+                surface: &mut surface,
+            };
+            collector.collect_file(typed_file);
+        }
+        Ok(surface)
+    }
+
+    /// Public items
+    pub fn public_items(&self) -> impl Iterator<Item = &ApiItem> {
+        self.items.values().filter(|item| item.public)
+    }
+}
+
+impl ApiDiff {
+    /// Compare the public API of `old` with that of `new`
+    pub fn between(old: &ApiSurface, new: &ApiSurface) -> Self {
+        let in_public_api = |surface: &ApiSurface, path: &str| {
+            surface.items.get(path).is_some_and(|item| item.public)
+        };
+
+        // Types that left the public API, and types that joined it: their
+        // members are covered by the type's own change
+        let gone_types: HashSet<&str> = old
+            .public_items()
+            .filter(|item| item.is_type() && !in_public_api(new, &item.path))
+            .map(|item| item.path.as_str())
+            .collect();
+        let new_types: HashSet<&str> = new
+            .public_items()
+            .filter(|item| item.is_type() && !in_public_api(old, &item.path))
+            .map(|item| item.path.as_str())
+            .collect();
+        let covered = |item: &ApiItem, types: &HashSet<&str>| {
+            item.container
+                .as_deref()
+                .is_some_and(|container| types.contains(container))
+        };
+
+        let mut changes = Vec::new();
+        let mut rename_targets = HashSet::new();
+        for item in old.public_items() {
+            if covered(item, &gone_types) {
+                continue;
+            }
+            let change = |kind: ChangeKind, new_item: Option<&ApiItem>| ApiChange {
+                kind,
+                path: item.path.clone(),
+                renamed_to: None,
+                old: Some(item.signature.clone()),
+                new: new_item.map(|n| n.signature.clone()),
+                breaking: kind.is_breaking(),
+            };
+            match new.items.get(&item.path) {
+                None => match rename_of(item, old, new, &rename_targets) {
+                    Some(renamed) => {
+                        rename_targets.insert(renamed.path.clone());
+                        changes.push(ApiChange {
+                            renamed_to: Some(renamed.path.clone()),
+                            ..change(ChangeKind::Renamed, Some(renamed))
+                        });
+                    }
+                    None => changes.push(change(ChangeKind::Removed, None)),
+                },
+                Some(new_item) if !new_item.public => {
+                    changes.push(change(ChangeKind::VisibilityReduced, Some(new_item)))
+                }
+                Some(new_item) if new_item.shape != item.shape || new_item.kind != item.kind => {
+                    changes.push(change(ChangeKind::SignatureChanged, Some(new_item)))
+                }
+                Some(_) => {}
+            }
+        }
+
+        for item in new.public_items() {
+            if in_public_api(old, &item.path)
+                || rename_targets.contains(&item.path)
+                || covered(item, &new_types)
+            {
+                continue;
+            }
+            changes.push(ApiChange {
+                kind: ChangeKind::Added,
+                path: item.path.clone(),
+                renamed_to: None,
+                old: None,
+                new: Some(item.signature.clone()),
+                breaking: false,
+            });
+        }
+
+        changes.sort_by(|a, b| (!a.breaking, &a.path).cmp(&(!b.breaking, &b.path)));
+        ApiDiff { changes }
+    }
+
+    pub fn breaking_count(&self) -> usize {
+        self.changes.iter().filter(|c| c.breaking).count()
+    }
+
+    /// Semantic version component the new version has to bump
+    pub fn required_bump(&self) -> &'static str {
+        if self.breaking_count() > 0 {
+            "major"
+        } else if !self.changes.is_empty() {
+            "minor"
+        } else {
+            "patch"
+        }
+    }
+
+    /// The report as pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        #[derive(Serialize)]
+        struct Report<'a> {
+            required_bump: &'static str,
+            breaking: usize,
+            changes: &'a [ApiChange],
+        }
+        serde_json::to_string_pretty(&Report {
+            required_bump: self.required_bump(),
+            breaking: self.breaking_count(),
+            changes: &self.changes,
+        })
+        .unwrap_or_else(|_| "{}".to_string())
+    }
+
+    /// The report for people, one change per line
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        if self.changes.is_empty() {
+            out.push_str("No API changes\n");
+        }
+        for change in &self.changes {
+            out.push_str(&format!(
+                "{} {}: {}\n",
+                if change.breaking {
+                    "BREAKING"
+                } else {
+                    "added   "
+                },
+                change.path,
+                change.kind.label()
+            ));
+            match change.kind {
+                ChangeKind::Renamed => {
+                    if let Some(to) = &change.renamed_to {
+                        out.push_str(&format!("    now {}\n", to));
+                    }
+                }
+                ChangeKind::Removed => {}
+                _ => {
+                    if let Some(old) = change.old.as_ref().filter(|_| change.breaking) {
+                        out.push_str(&format!("    - {}\n", old));
+                    }
+                    if let Some(new) = &change.new {
+                        out.push_str(&format!("    + {}\n", new));
+                    }
+                }
+            }
+        }
+        out.push_str(&format!(
+            "\n{} breaking, {} added: needs a {} version bump\n",
+            self.breaking_count(),
+            self.changes.len() - self.breaking_count(),
+            self.required_bump()
+        ));
+        out
+    }
+}
+
+/// The unmatched public member of `new` that `item`, gone from `old`, was
+/// most likely renamed to: same enclosing type, kind and shape
+fn rename_of<'a>(
+    item: &ApiItem,
+    old: &ApiSurface,
+    new: &'a ApiSurface,
+    taken: &HashSet<String>,
+) -> Option<&'a ApiItem> {
+    item.container.as_ref()?;
+    new.public_items().find(|candidate| {
+        candidate.container == item.container
+            && candidate.kind == item.kind
+            && candidate.shape == item.shape
+            && !old.items.contains_key(&candidate.path)
+            && !taken.contains(&candidate.path)
+    })
+}
+
+pub(crate) fn collect_haxe_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("failed to read {}: {}", dir.display(), e))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("failed to read {}: {}", dir.display(), e))?
+            .path();
+        if path.is_dir() {
+            collect_haxe_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "hx") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// `package.Name` of every type and module field the unit's own sources
+/// declare
+fn declared_names(unit: &CompilationUnit) -> HashSet<String> {
+    unit.user_files
+        .iter()
+        .flat_map(file_declared_names)
+        .collect()
+}
+
+/// `package.Name` of every type and module field `file` declares
+pub(crate) fn file_declared_names(file: &parser::HaxeFile) -> Vec<String> {
+    use parser::haxe_ast::{ModuleFieldKind, TypeDeclaration};
+
+    let package = file
+        .package
+        .as_ref()
+        .map(|p| p.path.join("."))
+        .unwrap_or_default();
+    let declarations = file.declarations.iter().filter_map(|decl| match decl {
+        TypeDeclaration::Class(c) => Some(&c.name),
+        TypeDeclaration::Interface(i) => Some(&i.name),
+        TypeDeclaration::Enum(e) => Some(&e.name),
+        TypeDeclaration::Typedef(t) => Some(&t.name),
+        TypeDeclaration::Abstract(a) => Some(&a.name),
+        TypeDeclaration::Conditional(_) => None,
+    });
+    let fields = file.module_fields.iter().map(|field| match &field.kind {
+        ModuleFieldKind::Var { name, .. } | ModuleFieldKind::Final { name, .. } => name,
+        ModuleFieldKind::Function(function) => &function.name,
+    });
+    declarations
+        .chain(fields)
+        .map(|name| qualify(&package, name))
+        .collect()
+}
+
+pub(crate) fn qualify(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+struct Collector<'a> {
+    symbol_table: &'a SymbolTable,
+    type_table: &'a TypeTable,
+    resolve: &'a dyn Fn(InternedString) -> Option<String>,
+    declared: &'a HashSet<String>,
+    package: String,
+    surface: &'a mut ApiSurface,
+}
+
+impl Collector<'_> {
+    fn collect_file(&mut self, file: &TypedFile) {
+        for class in &file.classes {
+            if let Some(path) = self.add_type(
+                class.name,
+                "class",
+                &class.type_parameters,
+                None,
+                class.visibility,
+            ) {
+                let public = is_public_type(class.visibility);
+                self.add_fields(&class.fields, &path, public);
+                for constructor in &class.constructors {
+                    self.add_function(constructor, "constructor", Some(&path), public);
+                }
+                for method in &class.methods {
+                    self.add_function(method, "method", Some(&path), public);
+                }
+            }
+        }
+
+        for interface in &file.interfaces {
+            if let Some(path) = self.add_type(
+                interface.name,
+                "interface",
+                &interface.type_parameters,
+                None,
+                interface.visibility,
+            ) {
+                let public = is_public_type(interface.visibility);
+                for method in &interface.methods {
+                    self.add_signature(method, &path, public);
+                }
+            }
+        }
+
+        for enum_decl in &file.enums {
+            if let Some(path) = self.add_type(
+                enum_decl.name,
+                "enum",
+                &enum_decl.type_parameters,
+                None,
+                enum_decl.visibility,
+            ) {
+                for variant in &enum_decl.variants {
+                    let name = self.string(variant.name);
+                    let (signature, shape) = if variant.parameters.is_empty() {
+                        (name.clone(), String::new())
+                    } else {
+                        (
+                            format!("{}({})", name, self.parameter_list(&variant.parameters)),
+                            format!("({})", self.parameter_shapes(&variant.parameters)),
+                        )
+                    };
+                    self.add(ApiItem {
+                        path: qualify(&path, &name),
+                        kind: "variant",
+                        signature,
+                        shape,
+                        public: is_public_type(enum_decl.visibility),
+                        container: Some(path.clone()),
+                    });
+                }
+            }
+        }
+
+        for alias in &file.type_aliases {
+            let target = self.type_name(alias.target_type);
+            self.add_type(
+                alias.name,
+                "typedef",
+                &alias.type_parameters,
+                Some(format!(" = {}", target)),
+                alias.visibility,
+            );
+        }
+
+        for abstract_decl in &file.abstracts {
+            let underlying = abstract_decl
+                .underlying_type
+                .map(|t| format!("({})", self.type_name(t)));
+            if let Some(path) = self.add_type(
+                abstract_decl.name,
+                "abstract",
+                &abstract_decl.type_parameters,
+                underlying,
+                abstract_decl.visibility,
+            ) {
+                let public = is_public_type(abstract_decl.visibility);
+                self.add_fields(&abstract_decl.fields, &path, public);
+                for constructor in &abstract_decl.constructors {
+                    self.add_function(constructor, "constructor", Some(&path), public);
+                }
+                for method in &abstract_decl.methods {
+                    self.add_function(method, "method", Some(&path), public);
+                }
+            }
+        }
+
+        for field in &file.module_fields {
+            let name = self.string(field.name);
+            let path = qualify(&self.package, &name);
+            if !self.declared.contains(&path) {
+                continue;
+            }
+            let public = is_public_type(field.visibility);
+            let (keyword, field_type) = match &field.kind {
+                TypedModuleFieldKind::Var {
+                    field_type,
+                    mutability,
+                    ..
+                } => (var_keyword(*mutability), *field_type),
+                TypedModuleFieldKind::Final { field_type, .. } => ("final", *field_type),
+                TypedModuleFieldKind::Function(function) => {
+                    self.add_function(function, "function", None, public);
+                    continue;
+                }
+            };
+            let field_type = self.type_name(field_type);
+            self.add(ApiItem {
+                path,
+                kind: "variable",
+                signature: format!("{} {}:{}", keyword, name, field_type),
+                shape: format!("{} :{}", keyword, field_type),
+                public,
+                container: None,
+            });
+        }
+    }
+
+    /// Add a type declared by the library's sources, returning its path
+    fn add_type(
+        &mut self,
+        name: InternedString,
+        kind: &'static str,
+        type_parameters: &[TypedTypeParameter],
+        suffix: Option<String>,
+        visibility: Visibility,
+    ) -> Option<String> {
+        let name = self.string(name);
+        let path = qualify(&self.package, &name);
+        if !self.declared.contains(&path) {
+            return None;
+        }
+        let params = if type_parameters.is_empty() {
+            String::new()
+        } else {
+            let names: Vec<String> = type_parameters
+                .iter()
+                .map(|p| self.string(p.name))
+                .collect();
+            format!("<{}>", names.join(", "))
+        };
+        let suffix = suffix.unwrap_or_default();
+        self.add(ApiItem {
+            path: path.clone(),
+            kind,
+            signature: format!("{} {}{}{}", kind, name, params, suffix),
+            shape: format!("<{}>{}", type_parameters.len(), suffix),
+            public: is_public_type(visibility),
+            container: None,
+        });
+        Some(path)
+    }
+
+    fn add_fields(&mut self, fields: &[TypedField], container: &str, container_public: bool) {
+        for field in fields {
+            let name = self.string(field.name);
+            let modifier = if field.is_static { "static " } else { "" };
+            let keyword = var_keyword(field.mutability);
+            let field_type = self.type_name(field.field_type);
+            self.add(ApiItem {
+                path: qualify(container, &name),
+                kind: "field",
+                signature: format!("{}{} {}:{}", modifier, keyword, name, field_type),
+                shape: format!("{}{} :{}", modifier, keyword, field_type),
+                public: container_public && is_public(field.visibility),
+                container: Some(container.to_string()),
+            });
+        }
+    }
+
+    fn add_function(
+        &mut self,
+        function: &TypedFunction,
+        kind: &'static str,
+        container: Option<&str>,
+        container_public: bool,
+    ) {
+        let name = self.string(function.name);
+        let path = match container {
+            Some(container) => qualify(container, &name),
+            None => qualify(&self.package, &name),
+        };
+        if container.is_none() && !self.declared.contains(&path) {
+            return;
+        }
+        let modifier = if function.is_static { "static " } else { "" };
+        let type_params = if function.type_parameters.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", function.type_parameters.len())
+        };
+        let mut signature = format!(
+            "{}function {}({})",
+            modifier,
+            name,
+            self.parameter_list(&function.parameters)
+        );
+        let mut shape = format!(
+            "{}{}({})",
+            modifier,
+            type_params,
+            self.parameter_shapes(&function.parameters)
+        );
+        if kind != "constructor" {
+            let return_type = self.type_name(function.return_type);
+            signature = format!("{}:{}", signature, return_type);
+            shape = format!("{}:{}", shape, return_type);
+        }
+        self.add(ApiItem {
+            path,
+            kind,
+            signature,
+            shape,
+            public: match container {
+                Some(_) => container_public && is_public(function.visibility),
+                None => is_public_type(function.visibility),
+            },
+            container: container.map(str::to_string),
+        });
+    }
+
+    /// Interface methods are always public
+    fn add_signature(&mut self, method: &TypedMethodSignature, container: &str, public: bool) {
+        let name = self.string(method.name);
+        let return_type = self.type_name(method.return_type);
+        self.add(ApiItem {
+            path: qualify(container, &name),
+            kind: "method",
+            signature: format!(
+                "function {}({}):{}",
+                name,
+                self.parameter_list(&method.parameters),
+                return_type
+            ),
+            shape: format!(
+                "({}):{}",
+                self.parameter_shapes(&method.parameters),
+                return_type
+            ),
+            public,
+            container: Some(container.to_string()),
+        });
+    }
+
+    /// Record `item`; the first of several declarations with one path
+    /// (overloads) wins
+    fn add(&mut self, item: ApiItem) {
+        self.surface.items.entry(item.path.clone()).or_insert(item);
+    }
+
+    fn parameter_list(&self, parameters: &[TypedParameter]) -> String {
+        let parameters: Vec<String> = parameters
+            .iter()
+            .map(|p| {
+                format!(
+                    "{}{}:{}",
+                    if p.is_optional { "?" } else { "" },
+                    self.string(p.name),
+                    self.type_name(p.param_type)
+                )
+            })
+            .collect();
+        parameters.join(", ")
+    }
+
+    /// Parameter types and optionality, without names
+    fn parameter_shapes(&self, parameters: &[TypedParameter]) -> String {
+        let parameters: Vec<String> = parameters
+            .iter()
+            .map(|p| {
+                format!(
+                    "{}{}",
+                    if p.is_optional { "?" } else { "" },
+                    self.type_name(p.param_type)
+                )
+            })
+            .collect();
+        parameters.join(", ")
+    }
+
+    fn string(&self, name: InternedString) -> String {
+        (self.resolve)(name).unwrap_or_default()
+    }
+
+    fn type_name(&self, type_id: TypeId) -> String {
+        TypeNames {
+            symbol_table: self.symbol_table,
+            type_table: self.type_table,
+            resolve: self.resolve,
+        }
+        .name(type_id)
+    }
+}
+
+/// Members are private unless marked `public`
+fn is_public(visibility: Visibility) -> bool {
+    visibility == Visibility::Public
+}
+
+/// Types and module-level fields are public unless marked `private`
+fn is_public_type(visibility: Visibility) -> bool {
+    visibility != Visibility::Private
+}
+
+fn var_keyword(mutability: Mutability) -> &'static str {
+    if mutability == Mutability::Immutable {
+        "final"
+    } else {
+        "var"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn surface(source: &str) -> ApiSurface {
+        ApiSurface::from_sources(&[("geom/Point.hx".to_string(), source.to_string())])
+            .expect("type checks")
+    }
+
+    #[test]
+    fn test_api_diff() {
+        let old = surface(
+            r#"
+            package geom;
+            class Point {
+                public var x:Float;
+                public var y:Float;
+                public function new(x:Float, y:Float) { this.x = x; this.y = y; }
+                public function length():Float { return Math.sqrt(x * x + y * y); }
+                public function scale(f:Float):Void { x *= f; y *= f; }
+                public function dot(p:Point):Float { return x * p.x + y * p.y; }
+                function helper():Void {}
+            }
+        "#,
+        );
+        let new = surface(
+            r#"
+            package geom;
+            class Point {
+                public var x:Float;
+                var y:Float;
+                public function new(x:Float, y:Float) { this.x = x; this.y = y; }
+                public function magnitude():Float { return Math.sqrt(x * x + y * y); }
+                public function scale(factor:Int):Void { x *= factor; y *= factor; }
+                public function dot(other:Point):Float { return x * other.x + y * other.y; }
+                public function translate(dx:Float, dy:Float):Void { x += dx; y += dy; }
+            }
+        "#,
+        );
+
+        let diff = ApiDiff::between(&old, &new);
+        let kind_of = |path: &str| diff.changes.iter().find(|c| c.path == path).map(|c| c.kind);
+        assert_eq!(kind_of("geom.Point.length"), Some(ChangeKind::Renamed));
+        assert_eq!(
+            kind_of("geom.Point.scale"),
+            Some(ChangeKind::SignatureChanged)
+        );
+        assert_eq!(kind_of("geom.Point.y"), Some(ChangeKind::VisibilityReduced));
+        assert_eq!(kind_of("geom.Point.translate"), Some(ChangeKind::Added));
+        // Parameter names and private members are not part of the API
+        assert_eq!(kind_of("geom.Point.dot"), None);
+        assert_eq!(kind_of("geom.Point.helper"), None);
+        assert_eq!(kind_of("geom.Point.magnitude"), None);
+
+        let renamed = diff
+            .changes
+            .iter()
+            .find(|c| c.kind == ChangeKind::Renamed)
+            .unwrap();
+        assert_eq!(renamed.renamed_to.as_deref(), Some("geom.Point.magnitude"));
+        assert_eq!(diff.breaking_count(), 3);
+        assert_eq!(diff.required_bump(), "major");
+        assert!(diff.changes[..3].iter().all(|c| c.breaking));
+
+        let json: serde_json::Value = serde_json::from_str(&diff.to_json()).unwrap();
+        assert_eq!(json["required_bump"], "major");
+        assert_eq!(json["changes"][0]["kind"], "renamed");
+    }
+
+    #[test]
+    fn test_removed_type_is_reported_once() {
+        let old =
+            surface("package geom; class Point { public function new() {} public var x:Int = 0; }");
+        let new = surface("package geom; class Vec { public function new() {} }");
+
+        let diff = ApiDiff::between(&old, &new);
+        let paths: Vec<(&str, ChangeKind)> = diff
+            .changes
+            .iter()
+            .map(|c| (c.path.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("geom.Point", ChangeKind::Removed),
+                ("geom.Vec", ChangeKind::Added)
+            ]
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Golden snapshot testing for diagnostics output.
+//!
+//! Compiles every `.hx` fixture in a directory and compares the formatted
+//! diagnostics (with ANSI colors stripped) against a `.stderr` snapshot stored
+//! next to the fixture. In bless mode, snapshots are (re)written instead of
+//! compared, so intended formatter changes can be accepted in one step.
+//!
+//! Used by `compiler/tests/test_diagnostic_snapshots.rs` and the
+//! `diagnostic_snapshots` binary (`--bless` to update).
+
+use std::path::{Path, PathBuf};
+
+use crate::compilation::{CompilationConfig, CompilationUnit};
+
+/// File extension used for snapshot files.
+pub const SNAPSHOT_EXTENSION: &str = "stderr";
+
+/// Environment variable that switches the snapshot test into bless mode.
+pub const BLESS_ENV_VAR: &str = "RAYZOR_BLESS";
+
+/// Configuration for a snapshot run.
+pub struct SnapshotConfig {
+    /// Directory containing `.hx` fixtures
+    pub fixtures_dir: PathBuf,
+    /// Rewrite snapshots instead of comparing against them
+    pub bless: bool,
+    /// Load the standard library before compiling each fixture
+    pub load_stdlib: bool,
+}
+
+impl SnapshotConfig {
+    pub fn new(fixtures_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            fixtures_dir: fixtures_dir.into(),
+            bless: false,
+            load_stdlib: true,
+        }
+    }
+
+    /// Enable bless mode if `RAYZOR_BLESS` is set to a non-empty value other than `0`.
+    pub fn bless_from_env(mut self) -> Self {
+        self.bless = std::env::var(BLESS_ENV_VAR)
+            .map(|v| !v.is_empty() && v != "0")
+            .unwrap_or(false);
+        self
+    }
+}
+
+/// Outcome for a single fixture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotOutcome {
+    /// Output matches the stored snapshot
+    Matched,
+    /// Snapshot was written (bless mode)
+    Blessed,
+    /// No snapshot exists for this fixture
+    Missing { actual: String },
+    /// Output differs from the stored snapshot
+    Mismatched { expected: String, actual: String },
+}
+
+/// Result of a snapshot run.
+#[derive(Debug, Default)]
+pub struct SnapshotReport {
+    pub results: Vec<(PathBuf, SnapshotOutcome)>,
+}
+
+impl SnapshotReport {
+    /// Fixtures whose output is missing or differs from the snapshot.
+    pub fn failures(&self) -> impl Iterator<Item = &(PathBuf, SnapshotOutcome)> {
+        self.results.iter().filter(|(_, outcome)| {
+            matches!(
+                outcome,
+                SnapshotOutcome::Missing { .. } | SnapshotOutcome::Mismatched { .. }
+            )
+        })
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Human-readable summary including a line diff for every failure.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        for (fixture, outcome) in &self.results {
+            let name = fixture.display();
+            match outcome {
+                SnapshotOutcome::Matched => out.push_str(&format!("ok       {}\n", name)),
+                SnapshotOutcome::Blessed => out.push_str(&format!("blessed  {}\n", name)),
+                SnapshotOutcome::Missing { actual } => {
+                    out.push_str(&format!(
+                        "MISSING  {} (no .{} snapshot)\n",
+                        name, SNAPSHOT_EXTENSION
+                    ));
+                    out.push_str(&render_diff("", actual));
+                }
+                SnapshotOutcome::Mismatched { expected, actual } => {
+                    out.push_str(&format!("CHANGED  {}\n", name));
+                    out.push_str(&render_diff(expected, actual));
+                }
+            }
+        }
+
+        let failed = self.failures().count();
+        out.push_str(&format!(
+            "\n{} fixture(s), {} failed",
+            self.results.len(),
+            failed
+        ));
+        if failed > 0 {
+            out.push_str(&format!(
+                "\nrerun with {}=1 (or `diagnostic_snapshots --bless`) to accept the new output",
+                BLESS_ENV_VAR
+            ));
+        }
+        out.push('\n');
+        out
+    }
+}
+
+/// Compile every fixture in `config.fixtures_dir` and check or bless its snapshot.
+pub fn run_snapshots(config: &SnapshotConfig) -> Result<SnapshotReport, String> {
+    let mut fixtures: Vec<PathBuf> = std::fs::read_dir(&config.fixtures_dir)
+        .map_err(|e| {
+            format!(
+                "Failed to read fixtures directory {}: {}",
+                config.fixtures_dir.display(),
+                e
+            )
+        })?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("hx"))
+        .collect();
+    fixtures.sort();
+
+    let mut report = SnapshotReport::default();
+
+    for fixture in fixtures {
+        let actual = render_fixture_diagnostics(&fixture, config.load_stdlib)?;
+        let snapshot_path = fixture.with_extension(SNAPSHOT_EXTENSION);
+
+        let outcome = if config.bless {
+            std::fs::write(&snapshot_path, &actual).map_err(|e| {
+                format!(
+                    "Failed to write snapshot {}: {}",
+                    snapshot_path.display(),
+                    e
+                )
+            })?;
+            SnapshotOutcome::Blessed
+        } else {
+            match std::fs::read_to_string(&snapshot_path) {
+                Ok(expected) => {
+                    let expected = normalize_newlines(&expected);
+                    if expected == actual {
+                        SnapshotOutcome::Matched
+                    } else {
+                        SnapshotOutcome::Mismatched { expected, actual }
+                    }
+                }
+                Err(_) => SnapshotOutcome::Missing { actual },
+            }
+        };
+
+        report.results.push((fixture, outcome));
+    }
+
+    Ok(report)
+}
+
+/// Compile a single fixture and return its diagnostics as plain text.
+///
+/// The fixture is registered under its bare file name so snapshots do not
+/// depend on where the repository is checked out.
+pub fn render_fixture_diagnostics(fixture: &Path, load_stdlib: bool) -> Result<String, String> {
+    let source = std::fs::read_to_string(fixture)
+        .map_err(|e| format!("Failed to read fixture {}: {}", fixture.display(), e))?;
+    let file_name = fixture
+        .file_name()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| format!("Invalid fixture path: {}", fixture.display()))?;
+
+    let config = CompilationConfig {
+        load_stdlib,
+        enable_cache: false,
+        ..Default::default()
+    };
+    let mut unit = CompilationUnit::new(config);
+
+    if load_stdlib {
+        unit.load_stdlib()
+            .map_err(|e| format!("Failed to load stdlib: {}", e))?;
+    }
+
+    let output = match unit.add_file(&source, file_name) {
+        Err(parse_error) => format!("{}\n", parse_error),
+        Ok(()) => match unit.lower_to_tast() {
+            Ok(_) => String::new(),
+            Err(errors) => unit.format_compilation_errors(&errors, false),
+        },
+    };
+
+    Ok(normalize_newlines(&diagnostics::strip_ansi_codes(&output)))
+}
+
+fn normalize_newlines(text: &str) -> String {
+    text.replace("\r\n", "\n")
+}
+
+/// Minimal line diff: common prefix/suffix are elided, the differing middle
+/// is shown as `-expected` / `+actual` lines.
+fn render_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    let prefix = expected
+        .iter()
+        .zip(&actual)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = expected[prefix..]
+        .iter()
+        .rev()
+        .zip(actual[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut out = String::new();
+    if prefix > 0 {
+        out.push_str(&format!("    @@ {} unchanged line(s) @@\n", prefix));
+    }
+    for line in &expected[prefix..expected.len() - suffix] {
+        out.push_str(&format!("    -{}\n", line));
+    }
+    for line in &actual[prefix..actual.len() - suffix] {
+        out.push_str(&format!("    +{}\n", line));
+    }
+    if suffix > 0 {
+        out.push_str(&format!("    @@ {} unchanged line(s) @@\n", suffix));
+    }
+    out
+}
@@ -0,0 +1,359 @@
+//! Call graph and module dependency graph export (`rayzor graph`)
+//!
+//! Writes graphs of a type-checked project ([`ProjectIndex`]) as Graphviz
+//! dot or JSON:
+//!
+//! - calls: a node per function, method and constructor, and an edge from
+//!   caller to callee counting the call sites between them
+//! - modules: a node per source file (`package.File`), and an edge to each
+//!   file whose declarations it uses, counting the references
+//!
+//! Only the project's own declarations are nodes. [`GraphOptions`] limits
+//! the nodes to some packages and their subpackages, and can merge the
+//! methods of each type into one node so only calls between types remain.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::api_diff::qualify;
+use super::query::{definition_path, IndexedFile, ProjectIndex};
+use crate::tast::SymbolId;
+
+/// Which graph to export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphKind {
+    Calls,
+    Modules,
+}
+
+/// Filters applied to the nodes of a graph
+#[derive(Debug, Clone, Default)]
+pub struct GraphOptions {
+    /// Keep nodes in these packages or their subpackages; all if empty.
+    /// `""` is the root package.
+    pub packages: Vec<String>,
+    /// Call graph: one node per type instead of per method
+    pub collapse_classes: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphNode {
+    /// Qualified path, e.g. `geom.Point.add`
+    pub id: String,
+    /// `method`, `class`, `module`, ...
+    pub kind: &'static str,
+    pub package: String,
+    pub file: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    /// Call sites or references the edge stands for
+    pub count: usize,
+}
+
+/// Nodes and edges of an exported graph
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Graph {
+    pub kind: GraphKind,
+    /// Sorted by id
+    pub nodes: Vec<GraphNode>,
+    /// Sorted by `from`, then `to`
+    pub edges: Vec<GraphEdge>,
+}
+
+impl Graph {
+    /// The project's call graph
+    pub fn calls(index: &ProjectIndex, options: &GraphOptions) -> Self {
+        let node_of = |symbol: SymbolId| {
+            let (file, mut definition) = index.declaration(symbol)?;
+            let file = &index.files[file];
+            if !file.index.definitions[definition].kind.is_callable() {
+                return None;
+            }
+            if options.collapse_classes {
+                definition = file.index.definitions[definition]
+                    .container
+                    .unwrap_or(definition);
+            }
+            Some(GraphNode {
+                id: definition_path(file, definition),
+                kind: file.index.definitions[definition].kind.label(),
+                package: file.package.clone(),
+                file: file.path.clone(),
+            })
+        };
+
+        let mut nodes = BTreeMap::new();
+        for &function in &index.call_graph.functions {
+            if let Some(node) = node_of(function) {
+                nodes.insert(node.id.clone(), node);
+            }
+        }
+        let mut edges = BTreeMap::new();
+        for site in index.call_graph.call_sites.values() {
+            let callee = site.get_direct_callee().and_then(node_of);
+            let (Some(from), Some(to)) = (node_of(site.caller), callee) else {
+                continue;
+            };
+            // Calls within a type disappear with the type's methods
+            if options.collapse_classes && from.id == to.id {
+                continue;
+            }
+            *edges.entry((from.id, to.id)).or_insert(0) += 1;
+        }
+        Self::finish(GraphKind::Calls, nodes, edges, options)
+    }
+
+    /// The dependencies between the project's source files
+    pub fn modules(index: &ProjectIndex, options: &GraphOptions) -> Self {
+        let mut nodes = BTreeMap::new();
+        let mut edges = BTreeMap::new();
+        for (file_index, file) in index.files.iter().enumerate() {
+            let from = module_name(file);
+            nodes.insert(
+                from.clone(),
+                GraphNode {
+                    id: from.clone(),
+                    kind: "module",
+                    package: file.package.clone(),
+                    file: file.path.clone(),
+                },
+            );
+            for reference in &file.index.references {
+                match index.declaration(reference.symbol) {
+                    Some((declared_in, _)) if declared_in != file_index => {
+                        let to = module_name(&index.files[declared_in]);
+                        *edges.entry((from.clone(), to)).or_insert(0) += 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Self::finish(GraphKind::Modules, nodes, edges, options)
+    }
+
+    /// Apply the package filter and drop edges to removed nodes
+    fn finish(
+        kind: GraphKind,
+        nodes: BTreeMap<String, GraphNode>,
+        edges: BTreeMap<(String, String), usize>,
+        options: &GraphOptions,
+    ) -> Self {
+        let nodes: BTreeMap<String, GraphNode> = nodes
+            .into_iter()
+            .filter(|(_, node)| in_packages(&node.package, &options.packages))
+            .collect();
+        let edges = edges
+            .into_iter()
+            .filter(|((from, to), _)| nodes.contains_key(from) && nodes.contains_key(to))
+            .map(|((from, to), count)| GraphEdge { from, to, count })
+            .collect();
+        Graph {
+            kind,
+            nodes: nodes.into_values().collect(),
+            edges,
+        }
+    }
+
+    /// Graphviz dot, with a cluster per package
+    pub fn to_dot(&self) -> String {
+        let name = match self.kind {
+            GraphKind::Calls => "calls",
+            GraphKind::Modules => "modules",
+        };
+        let mut out = format!("digraph {} {{\n", name);
+        out.push_str("    rankdir=LR;\n");
+        out.push_str("    node [shape=box, fontname=\"Helvetica\"];\n");
+
+        let mut packages: BTreeMap<&str, Vec<&GraphNode>> = BTreeMap::new();
+        for node in &self.nodes {
+            packages.entry(&node.package).or_default().push(node);
+        }
+        for (package, nodes) in packages {
+            let indent = if package.is_empty() {
+                "    "
+            } else {
+                out.push_str(&format!(
+                    "    subgraph {} {{\n",
+                    quote(&format!("cluster_{}", package))
+                ));
+                out.push_str(&format!("        label={};\n", quote(package)));
+                "        "
+            };
+            for node in nodes {
+                let label = node
+                    .id
+                    .strip_prefix(package)
+                    .and_then(|rest| rest.strip_prefix('.'))
+                    .unwrap_or(&node.id);
+                out.push_str(&format!(
+                    "{}{} [label={}];\n",
+                    indent,
+                    quote(&node.id),
+                    quote(label)
+                ));
+            }
+            if !package.is_empty() {
+                out.push_str("    }\n");
+            }
+        }
+
+        for edge in &self.edges {
+            out.push_str(&format!("    {} -> {}", quote(&edge.from), quote(&edge.to)));
+            if edge.count > 1 {
+                out.push_str(&format!(" [label=\"{}\"]", edge.count));
+            }
+            out.push_str(";\n");
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+/// `package.File` of a source file
+fn module_name(file: &IndexedFile) -> String {
+    let stem = Path::new(&file.path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    qualify(&file.package, &stem)
+}
+
+/// Whether `package` is one of `packages` or inside one; true if `packages`
+/// is empty
+fn in_packages(package: &str, packages: &[String]) -> bool {
+    packages.is_empty()
+        || packages.iter().any(|p| {
+            package == p
+                || (!p.is_empty()
+                    && package
+                        .strip_prefix(p.as_str())
+                        .is_some_and(|rest| rest.starts_with('.')))
+        })
+}
+
+/// A dot ID in double quotes
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> ProjectIndex {
+        let sources = [
+            (
+                "geom/Point.hx",
+                r#"package geom;
+class Point {
+    public var x:Float;
+    public function new(x:Float) { this.x = x; }
+    public function scaled(f:Float):Point { return new Point(x * f); }
+    public function double():Point { return scaled(2.0); }
+}
+"#,
+            ),
+            (
+                "Main.hx",
+                r#"import geom.Point;
+class Main {
+    static function main() {
+        var p = new Point(1.0);
+        trace(p.double().x);
+        trace(p.scaled(3.0).x);
+    }
+}
+"#,
+            ),
+        ];
+        let sources: Vec<(String, String)> = sources
+            .iter()
+            .map(|(name, source)| (name.to_string(), source.to_string()))
+            .collect();
+        ProjectIndex::from_sources(&sources).expect("type checks")
+    }
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str, usize)> {
+        graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.count))
+            .collect()
+    }
+
+    #[test]
+    fn test_graphs() {
+        let index = project();
+
+        let calls = Graph::calls(&index, &GraphOptions::default());
+        let found = edges(&calls);
+        assert!(found.contains(&("Main.main", "geom.Point.new", 1)));
+        assert!(found.contains(&("Main.main", "geom.Point.double", 1)));
+        assert!(found.contains(&("geom.Point.double", "geom.Point.scaled", 1)));
+        assert!(found.contains(&("geom.Point.scaled", "geom.Point.new", 1)));
+
+        let collapsed = Graph::calls(
+            &index,
+            &GraphOptions {
+                collapse_classes: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(edges(&collapsed), vec![("Main", "geom.Point", 3)]);
+
+        let only_geom = Graph::calls(
+            &index,
+            &GraphOptions {
+                packages: vec!["geom".to_string()],
+                ..Default::default()
+            },
+        );
+        assert!(only_geom.nodes.iter().all(|n| n.package == "geom"));
+        assert!(only_geom.edges.iter().all(|e| e.from.starts_with("geom.")));
+
+        let modules = Graph::modules(&index, &GraphOptions::default());
+        let found = edges(&modules);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].0, found[0].1), ("Main", "geom.Point"));
+    }
+
+    #[test]
+    fn test_dot() {
+        let node = |id: &str, package: &str| GraphNode {
+            id: id.to_string(),
+            kind: "method",
+            package: package.to_string(),
+            file: String::new(),
+        };
+        let graph = Graph {
+            kind: GraphKind::Calls,
+            nodes: vec![node("Main.main", ""), node("geom.Point.add", "geom")],
+            edges: vec![GraphEdge {
+                from: "Main.main".to_string(),
+                to: "geom.Point.add".to_string(),
+                count: 2,
+            }],
+        };
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph calls {\n"));
+        assert!(dot.contains("    \"Main.main\" [label=\"Main.main\"];\n"));
+        assert!(dot.contains("    subgraph \"cluster_geom\" {\n        label=\"geom\";\n"));
+        assert!(dot.contains("        \"geom.Point.add\" [label=\"Point.add\"];\n"));
+        assert!(dot.contains("    \"Main.main\" -> \"geom.Point.add\" [label=\"2\"];\n"));
+
+        assert!(in_packages("geom.shapes", &["geom".to_string()]));
+        assert!(!in_packages("geometry", &["geom".to_string()]));
+        assert!(in_packages("", &["".to_string()]));
+    }
+}
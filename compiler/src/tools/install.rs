@@ -0,0 +1,444 @@
+//! `rayzor install`: copy a built program and the native libraries it needs
+//! into an install prefix.
+//!
+//! Layout under the prefix:
+//!
+//! ```text
+//! bin/<name>          the executable, or a launcher script for a bundle
+//! lib/<name>/         GPU plugin and rpkg native libraries; for a bundle
+//!                     also <name>.rzb and the .rpkg packages it runs with
+//! ```
+//!
+//! Executables get an rpath to `lib/<name>` and libraries an rpath-relative
+//! install name, so the installed tree keeps working when moved as a whole.
+//! The fixups use `patchelf` on Linux and `install_name_tool` on macOS; if
+//! the tool is missing they are skipped with a warning.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Environment variable `rayzor run` searches first for the GPU plugin;
+/// bundle launchers point it at the installed `lib/<name>`
+pub const PLUGIN_DIR_ENV_VAR: &str = "RAYZOR_PLUGIN_DIR";
+
+/// What to install
+#[derive(Debug, Clone)]
+pub struct InstallOptions {
+    /// Executable built by `rayzor aot`, or a `.rzb` bundle
+    pub artifact: PathBuf,
+    /// Install prefix
+    pub prefix: PathBuf,
+    /// Installed program name (default: the artifact's file stem)
+    pub name: Option<String>,
+    /// Packages whose native libraries the program needs
+    pub rpkg_files: Vec<PathBuf>,
+    /// GPU plugin library to install alongside
+    pub gpu_plugin: Option<PathBuf>,
+}
+
+/// Kind of program being installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactKind {
+    /// Native executable
+    Executable,
+    /// `.rzb` bundle, run through a launcher script
+    Bundle,
+}
+
+/// Where an installed file's contents come from
+#[derive(Debug, Clone)]
+pub enum InstallSource {
+    Copy(PathBuf),
+    /// Generated contents (launcher scripts, libraries extracted from rpkgs)
+    Write(Vec<u8>),
+}
+
+/// Post-install fixup of a file's library search paths
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fixup {
+    None,
+    /// Search `lib/<name>` for libraries
+    Executable,
+    /// Identify by `@rpath` name and search its own directory
+    Library,
+}
+
+/// One file to install
+#[derive(Debug, Clone)]
+pub struct InstallStep {
+    pub dest: PathBuf,
+    pub source: InstallSource,
+    pub executable: bool,
+    pub fixup: Fixup,
+}
+
+/// The files `rayzor install` writes, computed before touching the prefix
+#[derive(Debug, Clone)]
+pub struct InstallPlan {
+    pub kind: ArtifactKind,
+    pub name: String,
+    pub prefix: PathBuf,
+    pub steps: Vec<InstallStep>,
+}
+
+impl InstallPlan {
+    pub fn new(options: &InstallOptions) -> Result<Self, String> {
+        let artifact = &options.artifact;
+        if !artifact.is_file() {
+            return Err(format!(
+                "{} not found; build it with `rayzor aot` or `rayzor bundle` first",
+                artifact.display()
+            ));
+        }
+        let kind = if artifact.extension().is_some_and(|ext| ext == "rzb") {
+            ArtifactKind::Bundle
+        } else {
+            ArtifactKind::Executable
+        };
+        let name = match &options.name {
+            Some(name) => name.clone(),
+            None => artifact
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .ok_or_else(|| format!("can't name {}", artifact.display()))?,
+        };
+
+        let bin_dir = options.prefix.join("bin");
+        let lib_dir = options.prefix.join("lib").join(&name);
+        let mut steps = Vec::new();
+        let mut libraries = Vec::new();
+
+        if let Some(plugin) = &options.gpu_plugin {
+            let file_name = plugin
+                .file_name()
+                .ok_or_else(|| format!("bad GPU plugin path {}", plugin.display()))?;
+            libraries.push(InstallStep {
+                dest: lib_dir.join(file_name),
+                source: InstallSource::Copy(plugin.clone()),
+                executable: false,
+                fixup: Fixup::Library,
+            });
+        }
+
+        let mut launcher_rpkgs = Vec::new();
+        for rpkg in &options.rpkg_files {
+            match kind {
+                // `rayzor run` loads the package itself: it needs the
+                // method table as well as the native library
+                ArtifactKind::Bundle => {
+                    let file_name = rpkg
+                        .file_name()
+                        .ok_or_else(|| format!("bad rpkg path {}", rpkg.display()))?;
+                    launcher_rpkgs.push(file_name.to_string_lossy().to_string());
+                    steps.push(InstallStep {
+                        dest: lib_dir.join(file_name),
+                        source: InstallSource::Copy(rpkg.clone()),
+                        executable: false,
+                        fixup: Fixup::None,
+                    });
+                }
+                ArtifactKind::Executable => {
+                    let loaded = crate::rpkg::load_rpkg(rpkg)
+                        .map_err(|e| format!("failed to load {}: {}", rpkg.display(), e))?;
+                    // Pure-Haxe packages are compiled into the executable
+                    if let Some(bytes) = loaded.native_lib_bytes {
+                        libraries.push(InstallStep {
+                            dest: lib_dir.join(native_lib_name(&loaded.package_name)),
+                            source: InstallSource::Write(bytes),
+                            executable: false,
+                            fixup: Fixup::Library,
+                        });
+                    }
+                }
+            }
+        }
+
+        match kind {
+            ArtifactKind::Executable => {
+                let file_name = if cfg!(target_os = "windows") {
+                    format!("{}.exe", name)
+                } else {
+                    name.clone()
+                };
+                steps.insert(
+                    0,
+                    InstallStep {
+                        dest: bin_dir.join(file_name),
+                        source: InstallSource::Copy(artifact.clone()),
+                        executable: true,
+                        // Only worth it when there are libraries to find
+                        fixup: if libraries.is_empty() {
+                            Fixup::None
+                        } else {
+                            Fixup::Executable
+                        },
+                    },
+                );
+            }
+            ArtifactKind::Bundle => {
+                let bundle_file = format!("{}.rzb", name);
+                let (launcher_name, launcher) = launcher(
+                    &name,
+                    &bundle_file,
+                    &launcher_rpkgs,
+                    options.gpu_plugin.is_some(),
+                );
+                steps.insert(
+                    0,
+                    InstallStep {
+                        dest: bin_dir.join(launcher_name),
+                        source: InstallSource::Write(launcher.into_bytes()),
+                        executable: true,
+                        fixup: Fixup::None,
+                    },
+                );
+                steps.insert(
+                    1,
+                    InstallStep {
+                        dest: lib_dir.join(bundle_file),
+                        source: InstallSource::Copy(artifact.clone()),
+                        executable: false,
+                        fixup: Fixup::None,
+                    },
+                );
+            }
+        }
+        steps.extend(libraries);
+
+        Ok(Self {
+            kind,
+            name,
+            prefix: options.prefix.clone(),
+            steps,
+        })
+    }
+
+    /// Write the files and fix up their library paths. Returns warnings
+    /// about fixups that couldn't be applied.
+    pub fn run(&self) -> Result<Vec<String>, String> {
+        let mut warnings = Vec::new();
+        for step in &self.steps {
+            if let Some(dir) = step.dest.parent() {
+                std::fs::create_dir_all(dir)
+                    .map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+            }
+            // Replace rather than overwrite, so a running copy keeps its file
+            let _ = std::fs::remove_file(&step.dest);
+            match &step.source {
+                InstallSource::Copy(source) => std::fs::copy(source, &step.dest).map(|_| ()),
+                InstallSource::Write(bytes) => std::fs::write(&step.dest, bytes),
+            }
+            .map_err(|e| format!("failed to install {}: {}", step.dest.display(), e))?;
+            if step.executable {
+                set_executable(&step.dest)?;
+            }
+            if let Err(e) = self.fix_up(step) {
+                warnings.push(format!("{}: {}", step.dest.display(), e));
+            }
+        }
+        Ok(warnings)
+    }
+
+    /// Library directory relative to `bin/`
+    fn lib_dir_from_bin(&self) -> String {
+        format!("../lib/{}", self.name)
+    }
+
+    fn fix_up(&self, step: &InstallStep) -> Result<(), String> {
+        let path = &step.dest;
+        if cfg!(target_os = "macos") {
+            match step.fixup {
+                Fixup::None => return Ok(()),
+                Fixup::Executable => {
+                    let rpath = format!("@executable_path/{}", self.lib_dir_from_bin());
+                    run_tool("install_name_tool", &["-add_rpath", &rpath], path)?;
+                }
+                Fixup::Library => {
+                    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                    let id = format!("@rpath/{}", file_name);
+                    run_tool("install_name_tool", &["-id", &id], path)?;
+                }
+            }
+            // Editing load commands invalidates the signature, which arm64
+            // refuses to run: re-sign ad hoc
+            run_tool("codesign", &["--force", "--sign", "-"], path)
+        } else if cfg!(target_os = "linux") {
+            let rpath = match step.fixup {
+                Fixup::None => return Ok(()),
+                Fixup::Executable => format!("$ORIGIN/{}", self.lib_dir_from_bin()),
+                Fixup::Library => "$ORIGIN".to_string(),
+            };
+            run_tool("patchelf", &["--set-rpath", &rpath], path)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl fmt::Display for InstallPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            ArtifactKind::Executable => "executable",
+            ArtifactKind::Bundle => "bundle",
+        };
+        writeln!(
+            f,
+            "Install {} ({}) into {}",
+            self.name,
+            kind,
+            self.prefix.display()
+        )?;
+        for step in &self.steps {
+            let dest = step
+                .dest
+                .strip_prefix(&self.prefix)
+                .unwrap_or(&step.dest)
+                .display();
+            match &step.source {
+                InstallSource::Copy(source) => {
+                    writeln!(f, "  {:<32} from {}", dest, source.display())?
+                }
+                InstallSource::Write(bytes) => {
+                    writeln!(f, "  {:<32} ({} bytes, generated)", dest, bytes.len())?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// File name an rpkg's native library is installed under
+fn native_lib_name(package: &str) -> String {
+    if cfg!(target_os = "macos") {
+        format!("lib{}.dylib", package)
+    } else if cfg!(target_os = "windows") {
+        format!("{}.dll", package)
+    } else {
+        format!("lib{}.so", package)
+    }
+}
+
+/// Launcher script for a bundle: `rayzor run` with the installed bundle,
+/// packages and plugin directory. Returns (file name, contents).
+fn launcher(name: &str, bundle_file: &str, rpkgs: &[String], gpu: bool) -> (String, String) {
+    if cfg!(target_os = "windows") {
+        let lib = format!("%~dp0..\\lib\\{}", name);
+        let mut args = format!("\"{}\\{}\"", lib, bundle_file);
+        if gpu {
+            args.push_str(" --compute");
+        }
+        for rpkg in rpkgs {
+            args.push_str(&format!(" --rpkg \"{}\\{}\"", lib, rpkg));
+        }
+        let script = format!(
+            "@echo off\r\nset {}={}\r\nrayzor run {} -- %*\r\n",
+            PLUGIN_DIR_ENV_VAR, lib, args
+        );
+        return (format!("{}.cmd", name), script);
+    }
+
+    let mut args = format!("\"$lib/{}\"", bundle_file);
+    if gpu {
+        args.push_str(" --compute");
+    }
+    for rpkg in rpkgs {
+        args.push_str(&format!(" --rpkg \"$lib/{}\"", rpkg));
+    }
+    let script = format!(
+        "#!/bin/sh\n\
+         # Installed by `rayzor install`; runs {name} with the rayzor on PATH\n\
+         # (or $RAYZOR)\n\
+         lib=\"$(cd \"$(dirname \"$0\")/../lib/{name}\" && pwd)\"\n\
+         {env}=\"$lib\" exec \"${{RAYZOR:-rayzor}}\" run {args} -- \"$@\"\n",
+        name = name,
+        env = PLUGIN_DIR_ENV_VAR,
+        args = args
+    );
+    (name.to_string(), script)
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+        .map_err(|e| format!("failed to make {} executable: {}", path.display(), e))
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+fn run_tool(tool: &str, args: &[&str], path: &Path) -> Result<(), String> {
+    let output = Command::new(tool)
+        .args(args)
+        .arg(path)
+        .output()
+        .map_err(|e| format!("{} not available ({}); library paths not fixed up", tool, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            tool,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("rayzor_install_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_install_bundle_with_launcher() {
+        let dir = temp_dir("bundle");
+        let bundle = dir.join("app.rzb");
+        std::fs::write(&bundle, b"bundle").unwrap();
+        let rpkg = dir.join("math.rpkg");
+        std::fs::write(&rpkg, b"rpkg").unwrap();
+        let prefix = dir.join("prefix");
+
+        let plan = InstallPlan::new(&InstallOptions {
+            artifact: bundle,
+            prefix: prefix.clone(),
+            name: Some("tool".to_string()),
+            rpkg_files: vec![rpkg],
+            gpu_plugin: None,
+        })
+        .unwrap();
+        assert_eq!(plan.kind, ArtifactKind::Bundle);
+        assert!(plan.run().unwrap().is_empty());
+
+        let lib = prefix.join("lib").join("tool");
+        assert_eq!(std::fs::read(lib.join("tool.rzb")).unwrap(), b"bundle");
+        assert!(lib.join("math.rpkg").is_file());
+        if cfg!(unix) {
+            let script = std::fs::read_to_string(prefix.join("bin").join("tool")).unwrap();
+            assert!(script.starts_with("#!/bin/sh"));
+            assert!(script.contains("run \"$lib/tool.rzb\" --rpkg \"$lib/math.rpkg\" -- \"$@\""));
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_missing_artifact() {
+        let err = InstallPlan::new(&InstallOptions {
+            artifact: PathBuf::from("/nonexistent/app"),
+            prefix: PathBuf::from("/tmp"),
+            name: None,
+            rpkg_files: Vec::new(),
+            gpu_plugin: None,
+        })
+        .unwrap_err();
+        assert!(err.contains("rayzor aot"));
+    }
+}
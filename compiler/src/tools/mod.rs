@@ -5,4 +5,14 @@
 //! be called from the unified `rayzor` CLI or programmatically.
 
 pub mod aot_build;
+pub mod api_diff;
+pub mod diagnostic_snapshots;
+pub mod graph;
+pub mod install;
+pub mod ownership_report;
 pub mod preblade;
+pub mod query;
+pub mod run_cache;
+pub mod script;
+pub mod symbol_index;
+pub mod usage_stats;
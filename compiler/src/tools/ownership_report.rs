@@ -0,0 +1,244 @@
+//! Ownership report (`rayzor check --ownership-report`)
+//!
+//! Exports what the ownership graph inferred for a file, so the analysis
+//! can be audited without reading compiler logs:
+//!
+//! - each variable with its final ownership state (`owned`, `borrowed`,
+//!   `borrowed_mut`, `shared`, `moved`, `unknown`) and declaration span
+//! - each move, from which variable to which, and where
+//! - each borrow, of which variable by which, and where
+//!
+//! Entries are sorted by source position, so two reports of the same file
+//! can be diffed. Spans are 1-based `line`/`column`; a location the analysis
+//! did not record is `null`.
+
+use serde::Serialize;
+
+use crate::semantic_graph::{BorrowType, MoveType, OwnershipGraph, OwnershipKind};
+use crate::tast::{InternedString, SourceLocation, SymbolId, SymbolTable};
+
+/// A source position in the reported file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Span {
+    pub line: u32,
+    pub column: u32,
+}
+
+impl Span {
+    fn from_location(location: &SourceLocation) -> Option<Span> {
+        (location.line > 0).then_some(Span {
+            line: location.line,
+            column: location.column,
+        })
+    }
+}
+
+/// Inferred ownership of one variable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VariableEntry {
+    pub id: u32,
+    pub name: String,
+    pub state: &'static str,
+    pub declared_at: Option<Span>,
+    /// Where the variable was last moved out, if it was
+    pub moved_at: Option<Span>,
+    /// Names of the variables borrowing this one
+    pub borrowed_by: Vec<String>,
+}
+
+/// One move of a value out of a variable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MoveEntry {
+    pub source: String,
+    /// Receiving variable; `null` when moved into a call or closure
+    pub destination: Option<String>,
+    pub kind: &'static str,
+    pub span: Option<Span>,
+    pub invalidates_source: bool,
+}
+
+/// One borrow of a variable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BorrowEntry {
+    pub borrower: String,
+    pub borrowed: String,
+    pub kind: &'static str,
+    pub span: Option<Span>,
+}
+
+/// Ownership report of one file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct OwnershipReport {
+    pub file: String,
+    pub variables: Vec<VariableEntry>,
+    pub moves: Vec<MoveEntry>,
+    pub borrows: Vec<BorrowEntry>,
+}
+
+fn state_name(kind: OwnershipKind) -> &'static str {
+    match kind {
+        OwnershipKind::Owned => "owned",
+        OwnershipKind::Borrowed => "borrowed",
+        OwnershipKind::BorrowedMut => "borrowed_mut",
+        OwnershipKind::Shared => "shared",
+        OwnershipKind::Unknown => "unknown",
+        OwnershipKind::Moved => "moved",
+    }
+}
+
+fn move_kind_name(kind: MoveType) -> &'static str {
+    match kind {
+        MoveType::Explicit => "explicit",
+        MoveType::Implicit => "implicit",
+        MoveType::FunctionCall => "function_call",
+        MoveType::Destruction => "destruction",
+    }
+}
+
+fn borrow_kind_name(kind: BorrowType) -> &'static str {
+    match kind {
+        BorrowType::Immutable => "immutable",
+        BorrowType::Mutable => "mutable",
+        BorrowType::Weak => "weak",
+    }
+}
+
+impl OwnershipReport {
+    /// Build the report of `file` from its ownership graph; `resolve` looks
+    /// up the interned variable names of `symbol_table`
+    pub fn build(
+        file: &str,
+        graph: &OwnershipGraph,
+        symbol_table: &SymbolTable,
+        resolve: impl Fn(InternedString) -> Option<String>,
+    ) -> Self {
+        let name = |id: SymbolId| {
+            symbol_table
+                .get_symbol(id)
+                .and_then(|symbol| resolve(symbol.name))
+                .unwrap_or_else(|| format!("<symbol {}>", id.as_raw()))
+        };
+
+        let mut variables: Vec<VariableEntry> = graph
+            .variables
+            .values()
+            .map(|node| {
+                let declared_at = symbol_table
+                    .get_symbol(node.variable)
+                    .and_then(|symbol| Span::from_location(&symbol.definition_location));
+                let moved_at = node
+                    .move_site
+                    .and_then(|id| graph.move_edges.get(&id))
+                    .and_then(|edge| Span::from_location(&edge.move_location));
+                let borrowed_by = node
+                    .borrowed_by
+                    .iter()
+                    .filter_map(|id| graph.borrow_edges.get(id))
+                    .map(|edge| name(edge.borrower))
+                    .collect();
+                VariableEntry {
+                    id: node.variable.as_raw(),
+                    name: name(node.variable),
+                    state: state_name(node.ownership_kind),
+                    declared_at,
+                    moved_at,
+                    borrowed_by,
+                }
+            })
+            .collect();
+        variables.sort_by_key(|v| (v.declared_at.is_none(), v.declared_at, v.id));
+
+        let mut moves: Vec<_> = graph.move_edges.values().collect();
+        moves.sort_by_key(|edge| edge.id.as_raw());
+        let mut moves: Vec<MoveEntry> = moves
+            .into_iter()
+            .map(|edge| MoveEntry {
+                source: name(edge.source),
+                destination: edge.destination.map(&name),
+                kind: move_kind_name(edge.move_type),
+                span: Span::from_location(&edge.move_location),
+                invalidates_source: edge.invalidates_source,
+            })
+            .collect();
+        moves.sort_by_key(|m| (m.span.is_none(), m.span));
+
+        let mut borrows: Vec<_> = graph.borrow_edges.values().collect();
+        borrows.sort_by_key(|edge| edge.id.as_raw());
+        let mut borrows: Vec<BorrowEntry> = borrows
+            .into_iter()
+            .map(|edge| BorrowEntry {
+                borrower: name(edge.borrower),
+                borrowed: name(edge.borrowed),
+                kind: borrow_kind_name(edge.borrow_type),
+                span: Span::from_location(&edge.borrow_location),
+            })
+            .collect();
+        borrows.sort_by_key(|b| (b.span.is_none(), b.span));
+
+        OwnershipReport {
+            file: file.to_string(),
+            variables,
+            moves,
+            borrows,
+        }
+    }
+
+    /// Reports of several files as a pretty-printed JSON array
+    pub fn to_json(reports: &[OwnershipReport]) -> String {
+        serde_json::to_string_pretty(reports).unwrap_or_else(|_| "[]".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::{HaxeCompilationPipeline, PipelineConfig};
+
+    #[test]
+    fn test_report_moves() {
+        let source = r#"
+            class Resource {
+                public function new() {}
+            }
+            class Main {
+                static function main() {
+                    var a = new Resource();
+                    var b = a;
+                }
+            }
+        "#;
+        let mut pipeline = HaxeCompilationPipeline::with_config(PipelineConfig {
+            emit_ownership_report: true,
+            ..Default::default()
+        });
+        let result = pipeline.compile_file("Main.hx", source);
+        assert_eq!(result.ownership_reports.len(), 1);
+        let report = &result.ownership_reports[0];
+        assert_eq!(report.file, "Main.hx");
+
+        let state = |name: &str| {
+            report
+                .variables
+                .iter()
+                .find(|v| v.name == name)
+                .map(|v| v.state)
+        };
+        assert_eq!(state("a"), Some("moved"));
+        assert_eq!(state("b"), Some("owned"));
+        let a = report.variables.iter().find(|v| v.name == "a").unwrap();
+        assert_eq!(a.declared_at.map(|s| s.line), Some(7));
+
+        let moved = report
+            .moves
+            .iter()
+            .find(|m| m.source == "a")
+            .expect("move of a");
+        assert_eq!(moved.destination.as_deref(), Some("b"));
+        assert_eq!(moved.kind, "explicit");
+
+        let json = OwnershipReport::to_json(&result.ownership_reports);
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0]["file"], "Main.hx");
+        assert!(parsed[0]["variables"].is_array());
+    }
+}
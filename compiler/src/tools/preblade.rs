@@ -3,17 +3,23 @@
 //! Extracted from the `preblade` binary for use as library functions.
 
 use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
+use std::hash::{BuildHasher, Hash, Hasher};
 use std::path::{Path, PathBuf};
 
 use crate::compilation::{CompilationConfig, CompilationUnit};
 use crate::ir::blade::{
-    save_bundle, save_symbol_manifest, BladeAbstractInfo, BladeClassInfo, BladeEnumInfo,
-    BladeEnumVariantInfo, BladeFieldInfo, BladeMethodInfo, BladeModuleSymbols, BladeParamInfo,
-    BladeTypeAliasInfo, BladeTypeInfo, RayzorBundle,
+    load_symbol_manifest, save_bundle, save_bundle_encrypted, save_symbol_manifest,
+    BladeAbstractInfo, BladeClassInfo, BladeEnumInfo, BladeEnumVariantInfo, BladeFieldInfo,
+    BladeMethodInfo, BladeModuleSymbols, BladeParamInfo, BladeSymbolManifest, BladeTypeAliasInfo,
+    BladeTypeInfo, BundledSource, RayzorBundle,
 };
+use crate::ir::bundle_crypt::BundleKey;
 use crate::ir::optimization::{OptimizationLevel, PassManager};
-use crate::ir::tree_shake;
+use crate::ir::IrModule;
+use crate::ir::{obfuscate, tree_shake};
+use crate::rpkg::{load_rpkg, symbols};
+use crate::stdlib::build_stdlib;
+use crate::stdlib::embedded::STDLIB_VERSION;
 
 /// Configuration for bundle creation.
 pub struct BundleConfig {
@@ -33,6 +39,16 @@ pub struct BundleConfig {
     pub enable_cache: bool,
     /// Custom BLADE cache directory
     pub cache_dir: Option<PathBuf>,
+    /// Embed the Haxe sources, so the bundle can be recompiled when it runs
+    /// on a compiler with a different stdlib
+    pub embed_sources: bool,
+    /// Replace the program's function and class names with hashes
+    pub obfuscate: bool,
+    /// Encrypt the bundle payload with this key
+    pub encryption_key: Option<BundleKey>,
+    /// Named entry points, as `name=Class.method` (or `Class.method`, named
+    /// after the method). A bundle with entry points needs no main.
+    pub entry_points: Vec<String>,
 }
 
 /// Configuration for symbol extraction.
@@ -45,6 +61,8 @@ pub struct PrebladeConfig {
     pub verbose: bool,
     /// Custom BLADE cache directory
     pub cache_dir: Option<PathBuf>,
+    /// Packages to index next to the stdlib, one `<package>.bsym` each
+    pub rpkg_files: Vec<PathBuf>,
 }
 
 /// Create a .rzb bundle from source files.
@@ -53,89 +71,128 @@ pub struct PrebladeConfig {
 pub fn create_bundle(config: &BundleConfig) -> Result<usize, String> {
     use std::time::Instant;
 
+    if config.obfuscate && config.embed_sources {
+        return Err(
+            "--obfuscate can't be combined with --embed-sources, which ships the Haxe sources"
+                .to_string(),
+        );
+    }
+
     println!("Creating Rayzor Bundle: {}", config.output.display());
 
     let t0 = Instant::now();
 
-    // Create compilation unit
     let mut comp_config = CompilationConfig::default();
     comp_config.enable_cache = config.enable_cache;
     comp_config.cache_dir = config.cache_dir.clone();
 
-    let mut unit = CompilationUnit::new(comp_config);
-
-    // Load stdlib
-    if config.verbose {
-        println!("  stdlib   loading");
-    }
-    unit.load_stdlib()
-        .map_err(|e| format!("Failed to load stdlib: {}", e))?;
-
-    // Add source files and type-check (results cached as BLADE artifacts)
+    let mut sources = Vec::new();
     for source_file in &config.source_files {
-        if config.verbose {
-            println!("  check    {}", source_file);
-        }
-        let source = std::fs::read_to_string(source_file)
+        let content = std::fs::read_to_string(source_file)
             .map_err(|e| format!("Failed to read {}: {}", source_file, e))?;
-        unit.add_file(&source, source_file)
-            .map_err(|e| format!("Failed to add {}: {}", source_file, e))?;
+        sources.push(BundledSource {
+            path: source_file.clone(),
+            content,
+        });
     }
 
-    // Type-check pass — caches successful checks as BLADE artifacts.
-    // If check fails, errors are reported via diagnostics formatter and we do NOT build.
-    if let Err(errors) = unit.lower_to_tast() {
-        unit.print_compilation_errors(&errors);
-        return Err(format!("Check failed with {} error(s)", errors.len()));
-    }
+    let mut modules = compile_bundle_sources(&sources, comp_config, config.verbose)?;
+    let module_count = modules.len();
+    let entry_points = resolve_entry_points(&modules, &config.entry_points)?;
+    let (entry_module, entry_function) = default_entry(&modules, &entry_points)?;
 
     if config.verbose {
-        println!("  check    passed");
+        println!("  entry    {}::{}", entry_module, entry_function);
+        for (name, module, function) in &entry_points {
+            println!("  entry    {} = {}::{}", name, module, function);
+        }
     }
 
-    // Get MIR modules (uses cached BLADE files when available)
-    let mir_modules = unit.get_mir_modules();
+    build_bundle_modules(
+        &mut modules,
+        (&entry_module, &entry_function),
+        &entry_points,
+        config.opt_level,
+        config.strip,
+        config.verbose,
+    );
 
-    if mir_modules.is_empty() {
-        return Err("No MIR modules generated".to_string());
+    // Strip identifiers last, so optimization sees the original names
+    if config.obfuscate {
+        let salt = std::collections::hash_map::RandomState::new()
+            .build_hasher()
+            .finish();
+        let mut keep = vec![entry_function.clone()];
+        keep.extend(entry_points.iter().map(|(_, _, function)| function.clone()));
+        let stats = obfuscate::obfuscate_bundle(&mut modules, &config.source_files, &keep, salt);
+        if config.verbose {
+            println!(
+                "  obfusc   {} fn, {} types renamed in {} modules",
+                stats.functions_renamed, stats.types_renamed, stats.modules_obfuscated
+            );
+        }
     }
 
-    // Convert Arc<IrModule> to IrModule for the bundle
-    let mut modules: Vec<_> = mir_modules.iter().map(|m| (**m).clone()).collect();
-
-    let module_count = modules.len();
+    // Create and save bundle
+    let mut bundle = RayzorBundle::new(modules, &entry_module, &entry_function, None)
+        .with_pipeline(config.opt_level, config.strip);
+    if config.compress {
+        bundle.flags.compressed = true;
+    }
+    if config.embed_sources {
+        bundle = bundle.with_sources(sources);
+    }
+    for (name, module, function) in &entry_points {
+        bundle = bundle.with_entry_point(name, module, function);
+    }
 
-    // Find entry module and function
-    let entry_module = modules
-        .iter()
-        .rev()
-        .find(|m| {
-            m.functions
-                .values()
-                .any(|f| f.name == "main" || f.name == "Main_main" || f.name.ends_with("_main"))
-        })
-        .map(|m| m.name.clone())
-        .ok_or("No entry point found (no main function)")?;
+    match &config.encryption_key {
+        Some(key) => save_bundle_encrypted(&config.output, &bundle, key),
+        None => save_bundle(&config.output, &bundle),
+    }
+    .map_err(|e| format!("Failed to save bundle: {}", e))?;
+    if config.verbose && config.encryption_key.is_some() {
+        println!("  encrypt  payload encrypted (ChaCha20)");
+    }
 
-    let entry_function = modules
-        .iter()
-        .find(|m| m.name == entry_module)
-        .and_then(|m| {
-            m.functions
-                .values()
-                .find(|f| f.name == "main" || f.name == "Main_main" || f.name.ends_with("_main"))
-                .map(|f| f.name.clone())
-        })
-        .ok_or("Entry function not found")?;
+    let elapsed = t0.elapsed();
+    println!("  bundle   {} modules in {:?}", module_count, elapsed);
 
-    if config.verbose {
-        println!("  entry    {}::{}", entry_module, entry_function);
+    // Show bundle size
+    if let Ok(meta) = std::fs::metadata(&config.output) {
+        let size = meta.len();
+        if size > 1024 * 1024 {
+            println!("  Bundle size: {:.2} MB", size as f64 / (1024.0 * 1024.0));
+        } else if size > 1024 {
+            println!("  Bundle size: {:.2} KB", size as f64 / 1024.0);
+        } else {
+            println!("  Bundle size: {} bytes", size);
+        }
     }
 
-    // Tree-shake BEFORE optimization
-    if config.strip {
-        let stats = tree_shake::tree_shake_bundle(&mut modules, &entry_module, &entry_function);
-        if config.verbose {
+    Ok(module_count)
+}
+
+/// Tree-shake (when `strip` is set) and optimize freshly compiled bundle
+/// modules, keeping everything the entry points reach
+fn build_bundle_modules(
+    modules: &mut Vec<IrModule>,
+    (entry_module, entry_function): (&str, &str),
+    entry_points: &[(String, String, String)],
+    opt_level: Option<OptimizationLevel>,
+    strip: bool,
+    verbose: bool,
+) {
+    // Tree-shake BEFORE optimization, keeping everything any entry point reaches
+    if strip {
+        let mut roots = vec![(entry_module.to_string(), entry_function.to_string())];
+        roots.extend(
+            entry_points
+                .iter()
+                .map(|(_, module, function)| (module.clone(), function.clone())),
+        );
+        let stats = tree_shake::tree_shake_bundle_roots(modules, &roots);
+        if verbose {
             println!(
                 "  shake    -{} fn, -{} ext, -{} glob, -{} mod | kept {} fn, {} ext",
                 stats.functions_removed,
@@ -149,42 +206,162 @@ pub fn create_bundle(config: &BundleConfig) -> Result<usize, String> {
     }
 
     // Apply MIR optimizations after tree-shaking
-    if let Some(level) = config.opt_level {
+    if let Some(level) = opt_level {
         if level != OptimizationLevel::O0 {
-            if config.verbose {
+            if verbose {
                 println!("  opt      {:?} ({} modules)", level, modules.len());
             }
             let mut pass_manager = PassManager::for_level(level);
-            for module in &mut modules {
+            for module in modules {
                 let _ = pass_manager.run(module);
             }
         }
     }
+}
 
-    // Create and save bundle
-    let mut bundle = RayzorBundle::new(modules, &entry_module, &entry_function, None);
-    if config.compress {
-        bundle.flags.compressed = true;
+/// Type-check and lower bundle sources against the stdlib, returning all
+/// MIR modules (stdlib and user)
+fn compile_bundle_sources(
+    sources: &[BundledSource],
+    comp_config: CompilationConfig,
+    verbose: bool,
+) -> Result<Vec<IrModule>, String> {
+    let mut unit = CompilationUnit::new(comp_config);
+
+    // Load stdlib
+    if verbose {
+        println!("  stdlib   loading");
     }
+    unit.load_stdlib()
+        .map_err(|e| format!("Failed to load stdlib: {}", e))?;
 
-    save_bundle(&config.output, &bundle).map_err(|e| format!("Failed to save bundle: {}", e))?;
+    // Add source files and type-check (results cached as BLADE artifacts)
+    for source in sources {
+        if verbose {
+            println!("  check    {}", source.path);
+        }
+        unit.add_file(&source.content, &source.path)
+            .map_err(|e| format!("Failed to add {}: {}", source.path, e))?;
+    }
 
-    let elapsed = t0.elapsed();
-    println!("  bundle   {} modules in {:?}", module_count, elapsed);
+    // Type-check pass — caches successful checks as BLADE artifacts.
+    // If check fails, errors are reported via diagnostics formatter and we do NOT build.
+    if let Err(errors) = unit.lower_to_tast() {
+        unit.print_compilation_errors(&errors);
+        return Err(format!("Check failed with {} error(s)", errors.len()));
+    }
 
-    // Show bundle size
-    if let Ok(meta) = std::fs::metadata(&config.output) {
-        let size = meta.len();
-        if size > 1024 * 1024 {
-            println!("  Bundle size: {:.2} MB", size as f64 / (1024.0 * 1024.0));
-        } else if size > 1024 {
-            println!("  Bundle size: {:.2} KB", size as f64 / 1024.0);
-        } else {
-            println!("  Bundle size: {} bytes", size);
-        }
+    if verbose {
+        println!("  check    passed");
     }
 
-    Ok(module_count)
+    // Get MIR modules (uses cached BLADE files when available)
+    let mir_modules = unit.get_mir_modules();
+
+    if mir_modules.is_empty() {
+        return Err("No MIR modules generated".to_string());
+    }
+
+    // Convert Arc<IrModule> to IrModule for the bundle
+    Ok(mir_modules.iter().map(|m| (**m).clone()).collect())
+}
+
+/// Entry module and function of a program: the last module with a main
+fn find_entry(modules: &[IrModule]) -> Result<(String, String), String> {
+    let is_main = |name: &str| name == "main" || name == "Main_main" || name.ends_with("_main");
+    modules
+        .iter()
+        .rev()
+        .find_map(|m| {
+            m.functions
+                .values()
+                .find(|f| is_main(&f.name))
+                .map(|f| (m.name.clone(), f.name.clone()))
+        })
+        .ok_or_else(|| "No entry point found (no main function)".to_string())
+}
+
+/// The default entry point: the program's main, or else the first named
+/// entry point (plugin bundles have no main)
+fn default_entry(
+    modules: &[IrModule],
+    entry_points: &[(String, String, String)],
+) -> Result<(String, String), String> {
+    match (find_entry(modules), entry_points.first()) {
+        (Ok(entry), _) => Ok(entry),
+        (Err(_), Some((_, module, function))) => Ok((module.clone(), function.clone())),
+        (Err(e), None) => Err(e),
+    }
+}
+
+/// Resolve `name=Class.method` entry point specs to (name, module, function)
+fn resolve_entry_points(
+    modules: &[IrModule],
+    specs: &[String],
+) -> Result<Vec<(String, String, String)>, String> {
+    specs
+        .iter()
+        .map(|spec| {
+            let (name, target) = match spec.split_once('=') {
+                Some((name, target)) => (name.trim(), target.trim()),
+                None => {
+                    let target = spec.trim();
+                    (target.rsplit('.').next().unwrap_or(target), target)
+                }
+            };
+            if name.is_empty() || target.is_empty() {
+                return Err(format!(
+                    "Invalid entry point '{}': expected name=Class.method",
+                    spec
+                ));
+            }
+            modules
+                .iter()
+                .find_map(|m| {
+                    m.functions
+                        .values()
+                        .find(|f| {
+                            !f.cfg.blocks.is_empty()
+                                && (f.qualified_name.as_deref() == Some(target) || f.name == target)
+                        })
+                        .map(|f| (name.to_string(), m.name.clone(), f.name.clone()))
+                })
+                .ok_or_else(|| format!("Entry point '{}': no function {} found", name, target))
+        })
+        .collect()
+}
+
+/// Recompile a bundle from its embedded sources against this compiler's
+/// stdlib (used when only the stdlib differs from the one it was built
+/// with), tree-shaking and optimizing as the original build did
+pub fn recompile_bundle(bundle: &RayzorBundle, verbose: bool) -> Result<RayzorBundle, String> {
+    if bundle.sources().is_empty() {
+        return Err("Bundle has no embedded sources".to_string());
+    }
+    let build_info = bundle.build_info();
+    let mut modules =
+        compile_bundle_sources(bundle.sources(), CompilationConfig::default(), verbose)?;
+    let entry_points: Vec<(String, String, String)> = bundle
+        .entry_points()
+        .iter()
+        .map(|e| (e.name.clone(), e.module.clone(), e.function.clone()))
+        .collect();
+    let (entry_module, entry_function) = default_entry(&modules, &entry_points)?;
+    build_bundle_modules(
+        &mut modules,
+        (&entry_module, &entry_function),
+        &entry_points,
+        build_info.opt_level,
+        build_info.tree_shaken,
+        verbose,
+    );
+    let mut recompiled = RayzorBundle::new(modules, &entry_module, &entry_function, None)
+        .with_pipeline(build_info.opt_level, build_info.tree_shaken)
+        .with_sources(bundle.sources().to_vec());
+    for (name, module, function) in &entry_points {
+        recompiled = recompiled.with_entry_point(name, module, function);
+    }
+    Ok(recompiled)
 }
 
 /// Extract symbols from stdlib.
@@ -242,7 +419,7 @@ pub fn extract_stdlib_symbols(config: &PrebladeConfig) -> Result<(usize, usize,
             }
         };
 
-        let type_info = extract_type_info_from_ast(&haxe_file);
+        let type_info = extract_type_info_from_ast(&haxe_file, &source);
 
         let class_count = type_info.classes.len();
         let enum_count = type_info.enums.len();
@@ -279,6 +456,29 @@ pub fn extract_stdlib_symbols(config: &PrebladeConfig) -> Result<(usize, usize,
         }
     }
 
+    // Packages: their externs, plus methods known only from the method
+    // table. Those of stdlib externs (e.g. rayzor.gpu.GPUCompute) are merged
+    // into the stdlib manifest.
+    let mut package_manifests = Vec::new();
+    for rpkg_path in &config.rpkg_files {
+        let (package_name, modules) =
+            extract_rpkg_symbols(rpkg_path, &mut all_module_symbols, config.verbose)?;
+        let class_count: usize = modules.iter().map(|m| m.types.classes.len()).sum();
+        println!(
+            "  Package {}: {} modules, {} classes",
+            package_name,
+            modules.len(),
+            class_count
+        );
+        total_classes += class_count;
+        total_enums += modules.iter().map(|m| m.types.enums.len()).sum::<usize>();
+        total_aliases += modules
+            .iter()
+            .map(|m| m.types.type_aliases.len())
+            .sum::<usize>();
+        package_manifests.push((package_name, modules));
+    }
+
     // Save symbol manifest
     let manifest_path = config.out_path.join("stdlib.bsym");
     println!();
@@ -292,11 +492,130 @@ pub fn extract_stdlib_symbols(config: &PrebladeConfig) -> Result<(usize, usize,
         eprintln!("Failed to save symbol manifest: {}", e);
     } else {
         println!("  Symbol manifest saved successfully");
+        create_stdlib_bundle(&manifest_path, &config.out_path.join("stdlib.rzb"))?;
+    }
+
+    for (package_name, modules) in package_manifests {
+        let path = config.out_path.join(format!("{}.bsym", package_name));
+        save_symbol_manifest(&path, modules)
+            .map_err(|e| format!("Failed to save {}: {}", path.display(), e))?;
+        println!("  Package symbols saved to {}", path.display());
     }
 
     Ok((total_classes, total_enums, total_aliases))
 }
 
+/// Symbols of an `.rpkg` package without loading its native library.
+///
+/// Parses the bundled `.hx` sources and turns the method table into extern
+/// classes; table methods of classes declared in `stdlib` are added to those
+/// instead. Returns the package name and its modules.
+fn extract_rpkg_symbols(
+    rpkg_path: &Path,
+    stdlib: &mut [BladeModuleSymbols],
+    verbose: bool,
+) -> Result<(String, Vec<BladeModuleSymbols>), String> {
+    let loaded = load_rpkg(rpkg_path)
+        .map_err(|e| format!("Failed to load {}: {}", rpkg_path.display(), e))?;
+    let origin = rpkg_path.to_string_lossy().to_string();
+
+    let mut sources: Vec<_> = loaded.haxe_sources.iter().collect();
+    sources.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut modules: Vec<BladeModuleSymbols> = Vec::new();
+    for (module_path, source) in sources {
+        let module_name = module_path
+            .trim_end_matches(".hx")
+            .replace(['/', '\\'], ".");
+        // A package may ship its own copy of a stdlib extern
+        if stdlib.iter().any(|m| m.name == module_name) {
+            continue;
+        }
+        let filename = format!("{}/{}", origin, module_path);
+        let haxe_file = match parser::parse_haxe_file(&filename, source, true) {
+            Ok(f) => f,
+            Err(e) => {
+                if verbose {
+                    println!("    Warning: Parse error in {}: {}", filename, e);
+                }
+                continue;
+            }
+        };
+        modules.push(BladeModuleSymbols {
+            name: module_name,
+            source_hash: hash_string(&filename),
+            source_path: filename,
+            types: extract_type_info_from_ast(&haxe_file, source),
+            dependencies: Vec::new(),
+        });
+    }
+
+    for class in symbols::descriptor_classes(&loaded.methods, &loaded.package_name) {
+        let Some(class) = symbols::merge_descriptor_class(stdlib, class) else {
+            continue;
+        };
+        let Some(class) = symbols::merge_descriptor_class(&mut modules, class) else {
+            continue;
+        };
+        let name = class
+            .package
+            .iter()
+            .chain(std::iter::once(&class.name))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(".");
+        modules.push(BladeModuleSymbols {
+            name,
+            source_path: origin.clone(),
+            source_hash: hash_string(&origin),
+            types: BladeTypeInfo {
+                classes: vec![class],
+                ..Default::default()
+            },
+            dependencies: Vec::new(),
+        });
+    }
+
+    Ok((loaded.package_name, modules))
+}
+
+/// Load the package manifests written by `extract_stdlib_symbols` from
+/// `dir`: every `.bsym` except `stdlib.bsym`. Unreadable files are skipped.
+pub fn load_package_manifests(dir: &Path) -> Vec<BladeSymbolManifest> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.extension().is_some_and(|e| e == "bsym")
+                && p.file_name().is_some_and(|n| n != "stdlib.bsym")
+        })
+        .collect();
+    paths.sort();
+    paths
+        .iter()
+        .filter_map(|p| load_symbol_manifest(p).ok())
+        .collect()
+}
+
+/// Write the stdlib bundle embedded into the compiler binary (see
+/// `stdlib::embedded`): the symbol manifest plus the prebuilt `haxe` MIR,
+/// stamped with the stdlib version of this compiler build.
+fn create_stdlib_bundle(manifest_path: &Path, output: &Path) -> Result<(), String> {
+    let manifest = load_symbol_manifest(manifest_path)
+        .map_err(|e| format!("Failed to reload symbol manifest: {}", e))?;
+    let mut bundle = RayzorBundle::new(vec![build_stdlib()], "haxe", "", Some(manifest))
+        .with_compiler_version(STDLIB_VERSION);
+    bundle.flags.compressed = true;
+
+    save_bundle(output, &bundle).map_err(|e| format!("Failed to save stdlib bundle: {}", e))?;
+    println!("  Stdlib bundle saved to {}", output.display());
+    println!("  (rebuild rayzor to embed it, or set RAYZOR_STDLIB_BUNDLE)");
+    Ok(())
+}
+
 /// Parse an optimization level string.
 pub fn parse_opt_level(s: &str) -> OptimizationLevel {
     match s {
@@ -366,7 +685,10 @@ fn discover_files_recursive(
     }
 }
 
-fn extract_type_info_from_ast(haxe_file: &parser::HaxeFile) -> BladeTypeInfo {
+pub(crate) fn extract_type_info_from_ast(
+    haxe_file: &parser::HaxeFile,
+    source: &str,
+) -> BladeTypeInfo {
     let mut type_info = BladeTypeInfo::default();
 
     let package: Vec<String> = haxe_file
@@ -462,7 +784,9 @@ fn extract_type_info_from_ast(haxe_file: &parser::HaxeFile) -> BladeTypeInfo {
                             }
                         }
                         parser::ClassFieldKind::Function(func) => {
-                            let method_info = extract_method_from_ast(func, is_public, is_static);
+                            let mut method_info =
+                                extract_method_from_ast(func, is_public, is_static);
+                            method_info.doc = doc_comment_before(source, field.span.start);
                             if func.name == "new" {
                                 constructor = Some(method_info);
                             } else if is_static {
@@ -489,6 +813,7 @@ fn extract_type_info_from_ast(haxe_file: &parser::HaxeFile) -> BladeTypeInfo {
                     static_methods,
                     constructor,
                     native_name,
+                    doc: doc_comment_before(source, class.span.start),
                 });
             }
             parser::TypeDeclaration::Enum(enum_decl) => {
@@ -576,7 +901,8 @@ fn extract_type_info_from_ast(haxe_file: &parser::HaxeFile) -> BladeTypeInfo {
                     if let parser::ClassFieldKind::Function(func) = &field.kind {
                         let is_static = field.modifiers.contains(&parser::Modifier::Static);
                         let is_public = matches!(field.access, Some(parser::Access::Public));
-                        let method_info = extract_method_from_ast(func, is_public, is_static);
+                        let mut method_info = extract_method_from_ast(func, is_public, is_static);
+                        method_info.doc = doc_comment_before(source, field.span.start);
                         if is_static {
                             static_methods.push(method_info);
                         } else {
@@ -614,7 +940,8 @@ fn extract_type_info_from_ast(haxe_file: &parser::HaxeFile) -> BladeTypeInfo {
                     if let parser::ClassFieldKind::Function(func) = &field.kind {
                         let is_public = true;
                         let is_static = field.modifiers.contains(&parser::Modifier::Static);
-                        let method_info = extract_method_from_ast(func, is_public, is_static);
+                        let mut method_info = extract_method_from_ast(func, is_public, is_static);
+                        method_info.doc = doc_comment_before(source, field.span.start);
                         methods.push(method_info);
                     }
                 }
@@ -634,6 +961,7 @@ fn extract_type_info_from_ast(haxe_file: &parser::HaxeFile) -> BladeTypeInfo {
                     static_methods: vec![],
                     constructor: None,
                     native_name: None,
+                    doc: doc_comment_before(source, iface.span.start),
                 });
             }
             parser::TypeDeclaration::Conditional(_) => {}
@@ -691,7 +1019,40 @@ fn extract_method_from_ast(
         is_static,
         is_inline: false,
         type_params,
+        doc: None,
+    }
+}
+
+/// The `/** ... */` comment directly above a declaration starting at
+/// `offset`, skipping metadata between the two. Leading `*` columns are
+/// stripped.
+fn doc_comment_before(source: &str, offset: usize) -> Option<String> {
+    let mut before = source.get(..offset)?.trim_end();
+    // Metadata on its own lines (or before the span on the same line)
+    loop {
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line = before[line_start..].trim();
+        if !line.starts_with('@') {
+            break;
+        }
+        before = before[..line_start].trim_end();
     }
+    let body = before.strip_suffix("*/")?;
+    let start = body.rfind("/**")?;
+    if body[start..].contains("*/") {
+        // A plain `/* */` comment closes here, not a doc comment
+        return None;
+    }
+    let text = body[start + 3..]
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            line.strip_prefix('*').map_or(line, str::trim_start)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
 }
 
 fn type_to_string(ty: &parser::Type) -> String {
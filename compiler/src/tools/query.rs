@@ -0,0 +1,567 @@
+//! Symbol search (`rayzor query`)
+//!
+//! Type checks a project's sources together and answers questions a text
+//! search gets wrong:
+//!
+//! - `--symbol NAME`: declarations named `NAME`, or `Type.member` for a
+//!   member of a type; `*` matches any characters
+//! - `--implementations-of TYPE`: classes implementing an interface or
+//!   extending a class, directly or through their supertypes
+//! - `--callers-of Type.method`: call sites of a function, method or
+//!   constructor (`Type.new`), from the project's call graph
+//!
+//! Names match the trailing components of qualified paths
+//! (`geom.Point.add`), so `add`, `Point.add` and `geom.Point.add` all find
+//! the method. Declarations come from the project's own sources; types and
+//! functions of the stdlib can still be the target of the last two queries.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::api_diff::{collect_haxe_files, file_declared_names, qualify};
+use super::symbol_index::{DefinitionKind, SymbolIndex};
+use crate::compilation::{CompilationConfig, CompilationUnit};
+use crate::semantic_graph::{CallGraph, CallSite, CallTarget, CallType};
+use crate::tast::{
+    BlockId, CallSiteId, InternedString, SourceLocation, SymbolId, SymbolKind, TypedFile,
+};
+
+/// A declaration or call site found by a query
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueryMatch {
+    pub file: String,
+    /// 1-based
+    pub line: usize,
+    /// 1-based, in characters
+    pub column: usize,
+    /// Kind of declaration (`class`, `method`, ...), or `call`
+    pub kind: &'static str,
+    /// Qualified path of the declaration, or of the function making the call
+    pub path: String,
+    /// Haxe-style signature of that declaration
+    pub signature: String,
+}
+
+impl QueryMatch {
+    /// One line: location, kind, path and signature
+    pub fn render(&self) -> String {
+        let what = if self.kind == "call" {
+            format!("call in {}", self.path)
+        } else {
+            format!("{} {}", self.kind, self.path)
+        };
+        format!(
+            "{}:{}:{}  {}  {}",
+            self.file, self.line, self.column, what, self.signature
+        )
+    }
+}
+
+/// One type-checked source file
+#[derive(Debug, Clone)]
+pub struct IndexedFile {
+    pub path: String,
+    pub source: String,
+    pub index: SymbolIndex,
+    /// Package the file declares, empty for the root package
+    pub package: String,
+}
+
+/// Symbol indexes of a project's sources, with their call graph
+#[derive(Debug, Clone)]
+pub struct ProjectIndex {
+    pub files: Vec<IndexedFile>,
+    /// Calls made by the project's functions; the `file_id` of a call
+    /// site's location is its index in `files`
+    pub call_graph: CallGraph,
+    /// Qualified path of each symbol declared in the project, and of the
+    /// stdlib types and functions it extends or calls
+    paths: HashMap<SymbolId, String>,
+    /// `(file, definition)` of each symbol declared in the project
+    declarations: HashMap<SymbolId, (usize, usize)>,
+    /// Direct supertypes of each type declared in the project
+    supertypes: HashMap<SymbolId, Vec<SymbolId>>,
+}
+
+impl ProjectIndex {
+    /// Type check the `.hx` files in `paths` (files or directories) together
+    pub fn load(paths: &[PathBuf]) -> Result<Self, String> {
+        let mut files = Vec::new();
+        for path in paths {
+            if path.is_dir() {
+                collect_haxe_files(path, &mut files)?;
+            } else {
+                files.push(path.clone());
+            }
+        }
+        if files.is_empty() {
+            return Err("no .hx files to search".to_string());
+        }
+        files.sort();
+        files.dedup();
+
+        let mut sources = Vec::new();
+        for path in files {
+            let source = std::fs::read_to_string(&path)
+                .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+            sources.push((path.to_string_lossy().to_string(), source));
+        }
+        Self::from_sources(&sources)
+    }
+
+    /// Type check `(file name, source)` pairs together and index them
+    pub fn from_sources(sources: &[(String, String)]) -> Result<Self, String> {
+        let mut unit = CompilationUnit::new(CompilationConfig::default());
+        unit.load_stdlib()
+            .map_err(|e| format!("failed to load stdlib: {}", e))?;
+        for (file, source) in sources {
+            unit.add_file(source, file)?;
+        }
+
+        // Typed files don't record the file they came from; match them to
+        // their source by the names they declare
+        let mut owners = HashMap::new();
+        for (file, haxe_file) in unit.user_files.iter().enumerate() {
+            for name in file_declared_names(haxe_file) {
+                owners.insert(name, file);
+            }
+        }
+        let typed_files = unit
+            .lower_to_tast()
+            .map_err(|errors| format!("type checking failed with {} errors", errors.len()))?;
+
+        let type_table = unit.type_table.borrow();
+        let mut files = Vec::new();
+        let mut indexed = HashSet::new();
+        for typed_file in &typed_files {
+            let package = typed_file.metadata.package_name.clone().unwrap_or_default();
+            let Some(&owner) = typed_declared_names(typed_file)
+                .iter()
+                .find_map(|name| owners.get(&qualify(&package, name)))
+            else {
+                continue;
+            };
+            if !indexed.insert(owner) {
+                continue;
+            }
+            let (path, source) = &sources[owner];
+            let index = SymbolIndex::build(
+                path,
+                source,
+                typed_file,
+                &unit.symbol_table,
+                &type_table,
+                |name| typed_file.get_string(name),
+            );
+            files.push(IndexedFile {
+                path: path.clone(),
+                source: source.clone(),
+                index,
+                package,
+            });
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        // The typed files share the unit's interner
+        let resolve = |name: InternedString| typed_files.first()?.get_string(name);
+        Ok(Self::link(files, |symbol| {
+            let declared = unit.symbol_table.get_symbol(symbol)?;
+            let path = declared.qualified_name.unwrap_or(declared.name);
+            Some((resolve(path)?, declared.kind))
+        }))
+    }
+
+    /// Build the cross-file tables; `lookup` gives the qualified path and
+    /// kind of a symbol declared outside the project
+    fn link(
+        files: Vec<IndexedFile>,
+        lookup: impl Fn(SymbolId) -> Option<(String, SymbolKind)>,
+    ) -> Self {
+        let mut paths = HashMap::new();
+        let mut declarations = HashMap::new();
+        let mut supertypes: HashMap<SymbolId, Vec<SymbolId>> = HashMap::new();
+        let mut constructors = HashMap::new();
+        for (file_index, file) in files.iter().enumerate() {
+            for (definition_index, definition) in file.index.definitions.iter().enumerate() {
+                let Some(symbol) = definition.symbol else {
+                    continue;
+                };
+                if definition.local || definition.name_range.is_none() {
+                    continue;
+                }
+                paths.insert(symbol, definition_path(file, definition_index));
+                declarations.insert(symbol, (file_index, definition_index));
+                if definition.kind == DefinitionKind::Constructor {
+                    let class = definition
+                        .container
+                        .and_then(|c| file.index.definitions[c].symbol);
+                    if let Some(class) = class {
+                        constructors.insert(class, symbol);
+                    }
+                }
+            }
+            for &(symbol, supertype) in &file.index.supertypes {
+                supertypes.entry(symbol).or_default().push(supertype);
+            }
+        }
+
+        let mut call_graph = CallGraph::new();
+        for (&symbol, &(file, definition)) in &declarations {
+            if files[file].index.definitions[definition].kind.is_callable() {
+                call_graph.add_function(symbol);
+            }
+        }
+        for (file_index, file) in files.iter().enumerate() {
+            for call in &file.index.calls {
+                // `new` calls the class's constructor
+                let (callee, call_type) = match constructors.get(&call.callee) {
+                    Some(&constructor) => (constructor, CallType::Constructor),
+                    None => (call.callee, CallType::Direct),
+                };
+                let callable = match declarations.get(&callee) {
+                    Some(&(file, definition)) => {
+                        files[file].index.definitions[definition].kind.is_callable()
+                    }
+                    None => match lookup(callee) {
+                        Some((path, SymbolKind::Function)) => {
+                            paths.insert(callee, path);
+                            true
+                        }
+                        _ => false,
+                    },
+                };
+                if !callable {
+                    continue;
+                }
+                let (line, column) = line_column(&file.source, call.range.start);
+                let id = CallSiteId::from_raw(call_graph.call_sites.len() as u32);
+                call_graph.add_call_site(CallSite::new(
+                    id,
+                    call.caller,
+                    CallTarget::Direct { function: callee },
+                    call_type,
+                    BlockId::invalid(),
+                    SourceLocation::new(
+                        file_index as u32,
+                        line as u32,
+                        column as u32,
+                        call.range.start as u32,
+                    ),
+                ));
+            }
+        }
+
+        for supertype in supertypes.values().flatten() {
+            if !paths.contains_key(supertype) {
+                if let Some((path, _)) = lookup(*supertype) {
+                    paths.insert(*supertype, path);
+                }
+            }
+        }
+
+        ProjectIndex {
+            files,
+            call_graph,
+            paths,
+            declarations,
+            supertypes,
+        }
+    }
+
+    /// Indexes in `files` and in the file's definitions of a symbol
+    /// declared in the project
+    pub fn declaration(&self, symbol: SymbolId) -> Option<(usize, usize)> {
+        self.declarations.get(&symbol).copied()
+    }
+
+    /// Qualified path of a symbol declared in the project, or of a stdlib
+    /// function or type it calls or extends
+    pub fn path_of(&self, symbol: SymbolId) -> Option<&str> {
+        self.paths.get(&symbol).map(String::as_str)
+    }
+
+    /// Declarations matching `pattern`, in file and source order
+    pub fn symbols(&self, pattern: &str) -> Vec<QueryMatch> {
+        let mut matches = Vec::new();
+        for (file_index, file) in self.files.iter().enumerate() {
+            for (definition_index, definition) in file.index.definitions.iter().enumerate() {
+                if definition.local || definition.name_range.is_none() {
+                    continue;
+                }
+                let path = definition_path(file, definition_index);
+                if path_matches(pattern, &path) {
+                    matches.push(self.found(file_index, definition_index));
+                }
+            }
+        }
+        matches
+    }
+
+    /// Classes declared in the project that implement or extend a type
+    /// matching `pattern`
+    pub fn implementations_of(&self, pattern: &str) -> Result<Vec<QueryMatch>, String> {
+        let targets = self.targets(pattern, |kind| {
+            matches!(kind, DefinitionKind::Class | DefinitionKind::Interface)
+        });
+        if targets.is_empty() {
+            return Err(format!("no class or interface matches `{}`", pattern));
+        }
+
+        let mut matches: Vec<(usize, usize)> = self
+            .declarations
+            .iter()
+            .filter(|&(&symbol, &(file, definition))| {
+                let kind = self.files[file].index.definitions[definition].kind;
+                kind == DefinitionKind::Class
+                    && self.ancestors(symbol).iter().any(|t| targets.contains(t))
+            })
+            .map(|(_, &location)| location)
+            .collect();
+        matches.sort();
+        Ok(matches
+            .into_iter()
+            .map(|(file, definition)| self.found(file, definition))
+            .collect())
+    }
+
+    /// Calls to functions, methods or constructors matching `pattern`
+    pub fn callers_of(&self, pattern: &str) -> Result<Vec<QueryMatch>, String> {
+        let targets = self.targets(pattern, DefinitionKind::is_callable);
+        if targets.is_empty() {
+            return Err(format!("no function or method matches `{}`", pattern));
+        }
+
+        let mut sites: Vec<&CallSite> = targets
+            .iter()
+            .flat_map(|&target| self.call_graph.get_calls_to(target))
+            .filter_map(|&id| self.call_graph.get_call_site(id))
+            .collect();
+        sites.sort_by_key(|site| {
+            (
+                site.source_location.file_id,
+                site.source_location.byte_offset,
+            )
+        });
+
+        Ok(sites
+            .into_iter()
+            .filter_map(|site| {
+                let file = &self.files[site.source_location.file_id as usize];
+                let &(_, caller) = self.declarations.get(&site.caller)?;
+                Some(QueryMatch {
+                    file: file.path.clone(),
+                    line: site.source_location.line as usize,
+                    column: site.source_location.column as usize,
+                    kind: "call",
+                    path: definition_path(file, caller),
+                    signature: file.index.definitions[caller].detail.clone(),
+                })
+            })
+            .collect())
+    }
+
+    /// Symbols whose path matches `pattern`: declarations of a kind `accept`
+    /// allows, and stdlib symbols the project calls or extends
+    fn targets(&self, pattern: &str, accept: impl Fn(DefinitionKind) -> bool) -> HashSet<SymbolId> {
+        self.paths
+            .iter()
+            .filter(|&(symbol, path)| {
+                path_matches(pattern, path)
+                    && self
+                        .declarations
+                        .get(symbol)
+                        .is_none_or(|&(file, definition)| {
+                            accept(self.files[file].index.definitions[definition].kind)
+                        })
+            })
+            .map(|(&symbol, _)| symbol)
+            .collect()
+    }
+
+    /// All supertypes of `symbol`, through superclasses and interfaces
+    fn ancestors(&self, symbol: SymbolId) -> HashSet<SymbolId> {
+        let mut seen = HashSet::new();
+        let mut pending = vec![symbol];
+        while let Some(current) = pending.pop() {
+            for &supertype in self.supertypes.get(&current).into_iter().flatten() {
+                if seen.insert(supertype) {
+                    pending.push(supertype);
+                }
+            }
+        }
+        seen
+    }
+
+    fn found(&self, file_index: usize, definition_index: usize) -> QueryMatch {
+        let file = &self.files[file_index];
+        let definition = &file.index.definitions[definition_index];
+        let start = definition
+            .name_range
+            .as_ref()
+            .map_or(0, |range| range.start);
+        let (line, column) = line_column(&file.source, start);
+        QueryMatch {
+            file: file.path.clone(),
+            line,
+            column,
+            kind: definition.kind.label(),
+            path: definition_path(file, definition_index),
+            signature: definition.detail.clone(),
+        }
+    }
+}
+
+/// `package.Type.member` of a definition
+pub(crate) fn definition_path(file: &IndexedFile, definition: usize) -> String {
+    let definition = &file.index.definitions[definition];
+    match definition.container {
+        Some(container) => format!("{}.{}", definition_path(file, container), definition.name),
+        None => qualify(&file.package, &definition.name),
+    }
+}
+
+/// Names of the types and module fields `file` declares
+fn typed_declared_names(file: &TypedFile) -> Vec<String> {
+    let names = file
+        .classes
+        .iter()
+        .map(|c| c.name)
+        .chain(file.interfaces.iter().map(|i| i.name))
+        .chain(file.enums.iter().map(|e| e.name))
+        .chain(file.abstracts.iter().map(|a| a.name))
+        .chain(file.type_aliases.iter().map(|t| t.name))
+        .chain(file.module_fields.iter().map(|f| f.name));
+    names.filter_map(|name| file.get_string(name)).collect()
+}
+
+/// Whether the trailing components of `path` match `pattern`, where `*`
+/// matches any characters
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let wanted = pattern.split('.').count();
+    let components: Vec<&str> = path.split('.').collect();
+    if wanted > components.len() {
+        return false;
+    }
+    let suffix = components[components.len() - wanted..].join(".");
+    wildcard_matches(pattern.as_bytes(), suffix.as_bytes())
+}
+
+fn wildcard_matches(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| wildcard_matches(rest, &text[skip..])),
+        Some((&c, rest)) => text.first() == Some(&c) && wildcard_matches(rest, &text[1..]),
+    }
+}
+
+/// 1-based line and column of byte `offset` in `source`
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+/// Matches as pretty-printed JSON
+pub fn to_json(matches: &[QueryMatch]) -> String {
+    serde_json::to_string_pretty(matches).unwrap_or_else(|_| "[]".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(files: &[(&str, &str)]) -> ProjectIndex {
+        let sources: Vec<(String, String)> = files
+            .iter()
+            .map(|(name, source)| (name.to_string(), source.to_string()))
+            .collect();
+        ProjectIndex::from_sources(&sources).expect("type checks")
+    }
+
+    #[test]
+    fn test_query() {
+        let index = project(&[
+            (
+                "shapes/Shape.hx",
+                "package shapes;\ninterface Shape {\n    function area():Float;\n}\n",
+            ),
+            (
+                "shapes/Square.hx",
+                r#"package shapes;
+class Square implements Shape {
+    public var side:Float;
+    public function new(side:Float) { this.side = side; }
+    public function area():Float { return side * side; }
+}
+class Cube extends Square {
+    public function volume():Float { return area() * side; }
+}
+"#,
+            ),
+            (
+                "Main.hx",
+                r#"import shapes.Square;
+class Main {
+    static function main() {
+        var s = new Square(2.0);
+        trace(s.area());
+    }
+}
+"#,
+            ),
+        ]);
+
+        let area = index.symbols("Square.area");
+        assert_eq!(area.len(), 1);
+        assert_eq!(area[0].path, "shapes.Square.area");
+        assert_eq!(area[0].kind, "method");
+        assert_eq!(area[0].signature, "function area():Float");
+        assert_eq!(
+            (area[0].file.as_str(), area[0].line),
+            ("shapes/Square.hx", 5)
+        );
+        let all: Vec<String> = index.symbols("a*").into_iter().map(|m| m.path).collect();
+        assert!(all.contains(&"shapes.Shape.area".to_string()));
+
+        let implementations: Vec<String> = index
+            .implementations_of("Shape")
+            .unwrap()
+            .into_iter()
+            .map(|m| m.path)
+            .collect();
+        assert_eq!(implementations, vec!["shapes.Cube", "shapes.Square"]);
+
+        let callers = index.callers_of("Square.area").unwrap();
+        let callers: Vec<(&str, usize, &str)> = callers
+            .iter()
+            .map(|m| (m.file.as_str(), m.line, m.path.as_str()))
+            .collect();
+        assert_eq!(
+            callers,
+            vec![
+                ("Main.hx", 5, "Main.main"),
+                ("shapes/Square.hx", 8, "shapes.Cube.volume")
+            ]
+        );
+        let constructed = index.callers_of("Square.new").unwrap();
+        assert_eq!((constructed[0].line, constructed[0].column), (4, 21));
+
+        assert!(index.callers_of("Square.missing").is_err());
+    }
+
+    #[test]
+    fn test_path_matches() {
+        assert!(path_matches("add", "geom.Point.add"));
+        assert!(path_matches("Point.add", "geom.Point.add"));
+        assert!(path_matches("Point.*", "geom.Point.add"));
+        assert!(path_matches("to*Case", "String.toUpperCase"));
+        assert!(!path_matches("Vec.add", "geom.Point.add"));
+        assert!(!path_matches("ad", "geom.Point.add"));
+        assert_eq!(line_column("a\nbcd", 3), (2, 2));
+    }
+}
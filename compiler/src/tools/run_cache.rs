@@ -0,0 +1,171 @@
+//! No-op detection for `rayzor run --cache`
+//!
+//! A cached run saves the program's final MIR together with a fingerprint of
+//! everything it was compiled from: the rayzor version and stdlib, the flags
+//! that change code generation, and the contents of each user source file
+//! the compile read. The next run of the same entry file rehashes just those
+//! files; if the fingerprint matches, it executes the saved module without
+//! parsing or type checking anything.
+//!
+//! Files are compared by content, so touching a file or checking it out
+//! again keeps the entry valid. A new file that would shadow an import (e.g.
+//! one created on a class path) is not noticed; `rayzor cache clear` drops
+//! the entry.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::ir::blade::{function_dependencies, load_blade, save_blade, BladeMetadata};
+use crate::ir::IrModule;
+
+/// Subdirectory of the BLADE cache that holds run entries
+pub const RUN_CACHE_DIR: &str = "run";
+
+/// The cached compile of one entry file
+pub struct RunCache {
+    path: PathBuf,
+    entry: PathBuf,
+    /// Flags that change what the entry file compiles to
+    flags: String,
+}
+
+impl RunCache {
+    /// Entry for running `entry` with `flags`, stored under `cache_dir`.
+    /// `flags` may be any string that differs whenever the compile would.
+    pub fn new(cache_dir: &Path, entry: &Path, flags: String) -> Self {
+        let entry = entry.canonicalize().unwrap_or_else(|_| entry.to_path_buf());
+        let stem = entry
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut hasher = DefaultHasher::new();
+        entry.hash(&mut hasher);
+        let path =
+            cache_dir
+                .join(RUN_CACHE_DIR)
+                .join(format!("{}-{:016x}.blade", stem, hasher.finish()));
+        Self { path, entry, flags }
+    }
+
+    /// Where the entry is stored
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The saved module, if nothing it was compiled from has changed
+    pub fn load(&self) -> Option<IrModule> {
+        let (module, metadata) = load_blade(&self.path).ok()?;
+        if metadata.compiler_version != env!("CARGO_PKG_VERSION") {
+            return None;
+        }
+        let sources: Vec<PathBuf> = metadata.dependencies.iter().map(PathBuf::from).collect();
+        (fingerprint(&self.flags, &sources)? == metadata.source_hash).then_some(module)
+    }
+
+    /// Save `module`, compiled from `sources`. The entry file is always one
+    /// of them.
+    pub fn save(&self, module: &IrModule, sources: &[PathBuf]) -> Result<(), String> {
+        let mut sources: Vec<PathBuf> = sources
+            .iter()
+            .map(|path| path.canonicalize().unwrap_or_else(|_| path.clone()))
+            .collect();
+        sources.push(self.entry.clone());
+        sources.sort();
+        sources.dedup();
+
+        let source_hash = fingerprint(&self.flags, &sources)
+            .ok_or_else(|| format!("a source of {} can't be read", self.entry.display()))?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let metadata = BladeMetadata {
+            name: module.name.clone(),
+            source_path: self.entry.to_string_lossy().to_string(),
+            source_hash,
+            source_timestamp: now,
+            compile_timestamp: now,
+            dependencies: sources
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect(),
+            compiler_version: env!("CARGO_PKG_VERSION").to_string(),
+            functions: function_dependencies(module),
+        };
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+        }
+        save_blade(&self.path, module, metadata)
+            .map_err(|e| format!("Failed to save run cache: {}", e))
+    }
+}
+
+/// Hash of the compiler, `flags` and the contents of `sources`, or None if
+/// a source can't be read (e.g. it was deleted)
+fn fingerprint(flags: &str, sources: &[PathBuf]) -> Option<u64> {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    env!("RAYZOR_STDLIB_FINGERPRINT").hash(&mut hasher);
+    flags.hash(&mut hasher);
+    for source in sources {
+        source.hash(&mut hasher);
+        std::fs::read(source).ok()?.hash(&mut hasher);
+    }
+    Some(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("rayzor_run_cache_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_hit_until_a_source_changes() {
+        let dir = temp_dir("sources");
+        let main = dir.join("Main.hx");
+        let util = dir.join("Util.hx");
+        std::fs::write(&main, "class Main {}").unwrap();
+        std::fs::write(&util, "class Util {}").unwrap();
+
+        let module = IrModule::new("Main".to_string(), main.to_string_lossy().to_string());
+        let cache = RunCache::new(&dir.join("cache"), &main, "debug".to_string());
+        assert!(cache.load().is_none());
+        cache.save(&module, std::slice::from_ref(&util)).unwrap();
+        assert_eq!(cache.load().unwrap().name, "Main");
+
+        // Other flags don't match the saved fingerprint
+        let release = RunCache::new(&dir.join("cache"), &main, "release".to_string());
+        assert!(release.load().is_none());
+
+        std::fs::write(&util, "class Util { static var x = 1; }").unwrap();
+        assert!(cache.load().is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_deleted_source_misses() {
+        let dir = temp_dir("deleted");
+        let main = dir.join("Main.hx");
+        let util = dir.join("Util.hx");
+        std::fs::write(&main, "class Main {}").unwrap();
+        std::fs::write(&util, "class Util {}").unwrap();
+
+        let module = IrModule::new("Main".to_string(), main.to_string_lossy().to_string());
+        let cache = RunCache::new(&dir.join("cache"), &main, String::new());
+        cache.save(&module, std::slice::from_ref(&util)).unwrap();
+        std::fs::remove_file(&util).unwrap();
+        assert!(cache.load().is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,127 @@
+//! Single-file scripts (`#!/usr/bin/env rayzor`)
+//!
+//! A script may start with a shebang line, which the parser ignores, and a
+//! comment block declaring its dependencies:
+//!
+//! ```haxe
+//! #!/usr/bin/env rayzor
+//! //! rpkg: ./mylib.rpkg
+//! //! class-path: ../shared
+//! ```
+//!
+//! `rayzor run` loads the declared dependencies before compiling, resolving
+//! relative paths against the script's directory. The block ends at the
+//! first line that is neither blank nor a `//` comment.
+
+use std::path::{Path, PathBuf};
+
+/// Dependencies declared in a script's header
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptHeader {
+    /// Whether the file starts with a `#!` line
+    pub shebang: bool,
+    /// `.rpkg` packages to load (`//! rpkg: <path>`)
+    pub rpkgs: Vec<PathBuf>,
+    /// Extra source directories (`//! class-path: <dir>`)
+    pub class_paths: Vec<PathBuf>,
+}
+
+/// Whether `source` starts with a shebang line
+pub fn has_shebang(source: &str) -> bool {
+    source.starts_with("#!")
+}
+
+impl ScriptHeader {
+    /// Read the header of `source`; relative paths resolve against `dir`
+    pub fn parse(source: &str, dir: &Path) -> Result<Self, String> {
+        let mut header = ScriptHeader {
+            shebang: has_shebang(source),
+            ..Default::default()
+        };
+        let mut lines = source.lines().enumerate();
+        if header.shebang {
+            lines.next();
+        }
+
+        for (index, line) in lines {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some(comment) = line.strip_prefix("//") else {
+                break;
+            };
+            let Some(directive) = comment.strip_prefix('!') else {
+                continue;
+            };
+            let directive = directive.trim();
+            if directive.is_empty() {
+                continue;
+            }
+            let (key, value) = directive
+                .split_once(':')
+                .map(|(k, v)| (k.trim(), v.trim()))
+                .ok_or_else(|| {
+                    format!(
+                        "line {}: expected `//! <key>: <value>`, found `{}`",
+                        index + 1,
+                        line
+                    )
+                })?;
+            if value.is_empty() {
+                return Err(format!("line {}: `{}` needs a path", index + 1, key));
+            }
+            let path = dir.join(value);
+            match key {
+                "rpkg" => header.rpkgs.push(path),
+                "class-path" | "cp" => header.class_paths.push(path),
+                _ => {
+                    return Err(format!(
+                        "line {}: unknown script directive `{}` (expected rpkg or class-path)",
+                        index + 1,
+                        key
+                    ))
+                }
+            }
+        }
+        Ok(header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header() {
+        let source = "#!/usr/bin/env rayzor\n\
+                      //! rpkg: ./mylib.rpkg\n\
+                      // plain comment\n\
+                      \n\
+                      //! class-path: ../shared\n\
+                      class Main {\n\
+                      //! rpkg: ignored.rpkg\n\
+                      }\n";
+        let header = ScriptHeader::parse(source, Path::new("/scripts")).unwrap();
+        assert!(header.shebang);
+        assert_eq!(header.rpkgs, vec![PathBuf::from("/scripts/./mylib.rpkg")]);
+        assert_eq!(
+            header.class_paths,
+            vec![PathBuf::from("/scripts/../shared")]
+        );
+    }
+
+    #[test]
+    fn test_parse_header_errors() {
+        let dir = Path::new(".");
+        assert_eq!(
+            ScriptHeader::parse("class Main {}", dir).unwrap(),
+            ScriptHeader::default()
+        );
+        assert!(ScriptHeader::parse("//! rpkg ./a.rpkg\n", dir).is_err());
+        assert!(ScriptHeader::parse("//! rpkg:\n", dir).is_err());
+        assert!(ScriptHeader::parse("//! haxelib: format\n", dir)
+            .unwrap_err()
+            .contains("unknown script directive `haxelib`"));
+    }
+}
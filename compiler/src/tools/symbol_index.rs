@@ -0,0 +1,1368 @@
+//! Symbol index (`rayzor lsp`)
+//!
+//! Records where the symbols of a type-checked file are declared and used,
+//! as byte ranges of its source, so an editor can answer requests without
+//! walking the TAST again:
+//!
+//! - each declaration (types, members, functions, locals, parameters) with
+//!   its kind, a Haxe-style signature (`function add(a:Int, b:Int):Int`) and
+//!   the range of its name
+//! - each reference to a symbol by name (variables, fields, calls, `new`)
+//! - each call to a known function or method, with the function making it,
+//!   and the superclass and interfaces of each type (`rayzor query`)
+//!
+//! The TAST keeps one location per node, usually at its start, so name
+//! ranges are found by searching the source from there for the name as a
+//! whole word. Symbols declared in other files get a definition without a
+//! range, built from the symbol table, so hovering them still shows a type.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::tast::node::{
+    StringInterpolationPart, TypedCatchClause, TypedComprehensionFor, TypedField, TypedFunction,
+    TypedMethodSignature, TypedModuleFieldKind, TypedParameter, TypedPattern, TypedSwitchCase,
+};
+use crate::tast::{
+    InternedString, Mutability, SourceLocation, SymbolId, SymbolKind, SymbolTable, TypeId,
+    TypeKind, TypeTable, TypedExpression, TypedExpressionKind, TypedFile, TypedStatement,
+};
+
+/// How far past a node's location its name is searched for
+const NAME_SEARCH_WINDOW: usize = 512;
+
+/// Nesting depth after which types print as `...`
+const MAX_TYPE_DEPTH: usize = 8;
+
+/// What a definition declares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefinitionKind {
+    Class,
+    Interface,
+    Enum,
+    EnumVariant,
+    Abstract,
+    Typedef,
+    Field,
+    Method,
+    Constructor,
+    Function,
+    Variable,
+    Parameter,
+}
+
+impl DefinitionKind {
+    /// Lowercase name, e.g. `method`
+    pub fn label(self) -> &'static str {
+        match self {
+            DefinitionKind::Class => "class",
+            DefinitionKind::Interface => "interface",
+            DefinitionKind::Enum => "enum",
+            DefinitionKind::EnumVariant => "variant",
+            DefinitionKind::Abstract => "abstract",
+            DefinitionKind::Typedef => "typedef",
+            DefinitionKind::Field => "field",
+            DefinitionKind::Method => "method",
+            DefinitionKind::Constructor => "constructor",
+            DefinitionKind::Function => "function",
+            DefinitionKind::Variable => "variable",
+            DefinitionKind::Parameter => "parameter",
+        }
+    }
+
+    /// A function, method or constructor
+    pub fn is_callable(self) -> bool {
+        matches!(
+            self,
+            DefinitionKind::Method | DefinitionKind::Constructor | DefinitionKind::Function
+        )
+    }
+}
+
+/// One declared symbol
+#[derive(Debug, Clone)]
+pub struct Definition {
+    /// `None` for declarations without a symbol (enum variants, interface
+    /// methods)
+    pub symbol: Option<SymbolId>,
+    pub name: String,
+    pub kind: DefinitionKind,
+    /// Haxe-style signature, e.g. `var x:Int` or `class Point`
+    pub detail: String,
+    /// Byte range of the name; `None` if declared in another file or the
+    /// name was not found
+    pub name_range: Option<Range<usize>>,
+    /// Index of the enclosing type's definition
+    pub container: Option<usize>,
+    /// Local variable or parameter, not part of the file's outline
+    pub local: bool,
+}
+
+/// One use of a symbol by name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub symbol: SymbolId,
+    pub range: Range<usize>,
+}
+
+/// One call from a function or method to another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    pub caller: SymbolId,
+    /// The called function; for `new`, the class
+    pub callee: SymbolId,
+    /// Byte range of the callee's name at the call
+    pub range: Range<usize>,
+}
+
+/// Definitions and references of one file
+#[derive(Debug, Clone, Default)]
+pub struct SymbolIndex {
+    pub file: String,
+    /// In source order within each declaration, containers before members
+    pub definitions: Vec<Definition>,
+    /// Sorted by start offset
+    pub references: Vec<Reference>,
+    /// Calls made by the file's functions, in source order within each
+    /// function
+    pub calls: Vec<Call>,
+    /// `(type, supertype)` for the superclass and interfaces of each class
+    /// and the interfaces each interface extends
+    pub supertypes: Vec<(SymbolId, SymbolId)>,
+    by_symbol: HashMap<SymbolId, usize>,
+}
+
+impl SymbolIndex {
+    /// Index `typed_file`, type checked from `source`; `resolve` looks up
+    /// the interned names of `symbol_table`
+    pub fn build(
+        file: &str,
+        source: &str,
+        typed_file: &TypedFile,
+        symbol_table: &SymbolTable,
+        type_table: &TypeTable,
+        resolve: impl Fn(InternedString) -> Option<String>,
+    ) -> Self {
+        let mut builder = IndexBuilder {
+            source,
+            symbol_table,
+            type_table,
+            resolve: &resolve,
+            function: None,
+            index: SymbolIndex {
+                file: file.to_string(),
+                ..Default::default()
+            },
+        };
+        builder.index_file(typed_file);
+
+        let mut index = builder.index;
+        index
+            .references
+            .sort_by_key(|r| (r.range.start, r.range.end));
+        index.references.dedup();
+        index
+    }
+
+    /// The definition of the symbol declared or referenced at `offset`; a
+    /// name's range includes the offset just past its end
+    pub fn definition_at(&self, offset: usize) -> Option<&Definition> {
+        let contains = |range: &Range<usize>| range.start <= offset && offset <= range.end;
+        if let Some(reference) = self.references.iter().find(|r| contains(&r.range)) {
+            return self.definition_of(reference.symbol);
+        }
+        self.definitions
+            .iter()
+            .find(|d| d.name_range.as_ref().is_some_and(contains))
+    }
+
+    pub fn definition_of(&self, symbol: SymbolId) -> Option<&Definition> {
+        self.by_symbol
+            .get(&symbol)
+            .map(|&index| &self.definitions[index])
+    }
+}
+
+struct IndexBuilder<'a> {
+    source: &'a str,
+    symbol_table: &'a SymbolTable,
+    type_table: &'a TypeTable,
+    resolve: &'a dyn Fn(InternedString) -> Option<String>,
+    /// Function whose body is being indexed
+    function: Option<SymbolId>,
+    index: SymbolIndex,
+}
+
+impl IndexBuilder<'_> {
+    fn index_file(&mut self, file: &TypedFile) {
+        for class in &file.classes {
+            let name = self.string(class.name);
+            let container = self.define(
+                Some(class.symbol_id),
+                DefinitionKind::Class,
+                format!("class {}", name),
+                name,
+                &class.source_location,
+                None,
+            );
+            let supertypes = class.super_class.iter().chain(&class.interfaces);
+            self.supertypes(class.symbol_id, supertypes);
+            self.index_fields(&class.fields, container);
+            for constructor in &class.constructors {
+                self.index_function(constructor, DefinitionKind::Constructor, Some(container));
+            }
+            for method in &class.methods {
+                self.index_function(method, DefinitionKind::Method, Some(container));
+            }
+        }
+
+        for interface in &file.interfaces {
+            let name = self.string(interface.name);
+            let container = self.define(
+                Some(interface.symbol_id),
+                DefinitionKind::Interface,
+                format!("interface {}", name),
+                name,
+                &interface.source_location,
+                None,
+            );
+            self.supertypes(interface.symbol_id, &interface.extends);
+            for method in &interface.methods {
+                self.index_signature(method, container);
+            }
+        }
+
+        for enum_decl in &file.enums {
+            let name = self.string(enum_decl.name);
+            let container = self.define(
+                Some(enum_decl.symbol_id),
+                DefinitionKind::Enum,
+                format!("enum {}", name),
+                name,
+                &enum_decl.source_location,
+                None,
+            );
+            for variant in &enum_decl.variants {
+                let name = self.string(variant.name);
+                let detail = if variant.parameters.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}({})", name, self.parameter_list(&variant.parameters))
+                };
+                self.define(
+                    None,
+                    DefinitionKind::EnumVariant,
+                    detail,
+                    name,
+                    &variant.source_location,
+                    Some(container),
+                );
+            }
+        }
+
+        for alias in &file.type_aliases {
+            let name = self.string(alias.name);
+            let detail = format!("typedef {} = {}", name, self.type_name(alias.target_type));
+            self.define(
+                Some(alias.symbol_id),
+                DefinitionKind::Typedef,
+                detail,
+                name,
+                &alias.source_location,
+                None,
+            );
+        }
+
+        for abstract_decl in &file.abstracts {
+            let name = self.string(abstract_decl.name);
+            let detail = match abstract_decl.underlying_type {
+                Some(underlying) => format!("abstract {}({})", name, self.type_name(underlying)),
+                None => format!("abstract {}", name),
+            };
+            let container = self.define(
+                Some(abstract_decl.symbol_id),
+                DefinitionKind::Abstract,
+                detail,
+                name,
+                &abstract_decl.source_location,
+                None,
+            );
+            self.index_fields(&abstract_decl.fields, container);
+            for constructor in &abstract_decl.constructors {
+                self.index_function(constructor, DefinitionKind::Constructor, Some(container));
+            }
+            for method in &abstract_decl.methods {
+                self.index_function(method, DefinitionKind::Method, Some(container));
+            }
+        }
+
+        for field in &file.module_fields {
+            let name = self.string(field.name);
+            match &field.kind {
+                TypedModuleFieldKind::Var {
+                    field_type,
+                    initializer,
+                    mutability,
+                } => {
+                    let keyword = if *mutability == Mutability::Immutable {
+                        "final"
+                    } else {
+                        "var"
+                    };
+                    let detail = format!("{} {}:{}", keyword, name, self.type_name(*field_type));
+                    self.define(
+                        Some(field.symbol_id),
+                        DefinitionKind::Variable,
+                        detail,
+                        name,
+                        &field.source_location,
+                        None,
+                    );
+                    if let Some(initializer) = initializer {
+                        self.index_expression(initializer);
+                    }
+                }
+                TypedModuleFieldKind::Final {
+                    field_type,
+                    initializer,
+                } => {
+                    let detail = format!("final {}:{}", name, self.type_name(*field_type));
+                    self.define(
+                        Some(field.symbol_id),
+                        DefinitionKind::Variable,
+                        detail,
+                        name,
+                        &field.source_location,
+                        None,
+                    );
+                    if let Some(initializer) = initializer {
+                        self.index_expression(initializer);
+                    }
+                }
+                TypedModuleFieldKind::Function(function) => {
+                    self.index_function(function, DefinitionKind::Function, None);
+                }
+            }
+        }
+
+        for function in &file.functions {
+            self.index_function(function, DefinitionKind::Function, None);
+        }
+    }
+
+    fn index_fields(&mut self, fields: &[TypedField], container: usize) {
+        for field in fields {
+            let name = self.string(field.name);
+            let keyword = if field.mutability == Mutability::Immutable {
+                "final"
+            } else {
+                "var"
+            };
+            let detail = format!(
+                "{}{} {}:{}",
+                if field.is_static { "static " } else { "" },
+                keyword,
+                name,
+                self.type_name(field.field_type)
+            );
+            self.define(
+                Some(field.symbol_id),
+                DefinitionKind::Field,
+                detail,
+                name,
+                &field.source_location,
+                Some(container),
+            );
+            if let Some(initializer) = &field.initializer {
+                self.index_expression(initializer);
+            }
+        }
+    }
+
+    fn index_function(
+        &mut self,
+        function: &TypedFunction,
+        kind: DefinitionKind,
+        container: Option<usize>,
+    ) {
+        let name = self.string(function.name);
+        let mut detail = format!(
+            "{}function {}({})",
+            if function.is_static { "static " } else { "" },
+            name,
+            self.parameter_list(&function.parameters)
+        );
+        if kind != DefinitionKind::Constructor {
+            detail = format!("{}:{}", detail, self.type_name(function.return_type));
+        }
+        self.define(
+            Some(function.symbol_id),
+            kind,
+            detail,
+            name,
+            &function.source_location,
+            container,
+        );
+        self.index_parameters(&function.parameters);
+        let enclosing = self.function.replace(function.symbol_id);
+        self.index_statements(&function.body);
+        self.function = enclosing;
+    }
+
+    fn index_signature(&mut self, method: &TypedMethodSignature, container: usize) {
+        let name = self.string(method.name);
+        let detail = format!(
+            "function {}({}):{}",
+            name,
+            self.parameter_list(&method.parameters),
+            self.type_name(method.return_type)
+        );
+        self.define(
+            None,
+            DefinitionKind::Method,
+            detail,
+            name,
+            &method.source_location,
+            Some(container),
+        );
+    }
+
+    fn index_parameters(&mut self, parameters: &[TypedParameter]) {
+        for parameter in parameters {
+            let name = self.string(parameter.name);
+            let index = self.define(
+                Some(parameter.symbol_id),
+                DefinitionKind::Parameter,
+                self.parameter(parameter),
+                name,
+                &parameter.source_location,
+                None,
+            );
+            self.index.definitions[index].local = true;
+            if let Some(default) = &parameter.default_value {
+                self.index_expression(default);
+            }
+        }
+    }
+
+    fn index_statements(&mut self, statements: &[TypedStatement]) {
+        for statement in statements {
+            self.index_statement(statement);
+        }
+    }
+
+    fn index_statement(&mut self, statement: &TypedStatement) {
+        match statement {
+            TypedStatement::Expression { expression, .. } => self.index_expression(expression),
+            TypedStatement::VarDeclaration {
+                symbol_id,
+                var_type,
+                initializer,
+                mutability,
+                source_location,
+            } => {
+                self.define_local(*symbol_id, *var_type, *mutability, source_location);
+                if let Some(initializer) = initializer {
+                    self.index_expression(initializer);
+                }
+            }
+            TypedStatement::Assignment { target, value, .. } => {
+                self.index_expression(target);
+                self.index_expression(value);
+            }
+            TypedStatement::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.index_expression(condition);
+                self.index_statement(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.index_statement(else_branch);
+                }
+            }
+            TypedStatement::While {
+                condition, body, ..
+            } => {
+                self.index_expression(condition);
+                self.index_statement(body);
+            }
+            TypedStatement::For {
+                init,
+                condition,
+                update,
+                body,
+                ..
+            } => {
+                if let Some(init) = init {
+                    self.index_statement(init);
+                }
+                if let Some(condition) = condition {
+                    self.index_expression(condition);
+                }
+                if let Some(update) = update {
+                    self.index_expression(update);
+                }
+                self.index_statement(body);
+            }
+            TypedStatement::ForIn {
+                value_var,
+                key_var,
+                iterable,
+                body,
+                source_location,
+            } => {
+                self.define_loop_variables(*key_var, *value_var, source_location);
+                self.index_expression(iterable);
+                self.index_statement(body);
+            }
+            TypedStatement::Return { value, .. } => {
+                if let Some(value) = value {
+                    self.index_expression(value);
+                }
+            }
+            TypedStatement::Throw { exception, .. } => self.index_expression(exception),
+            TypedStatement::Try {
+                body,
+                catch_clauses,
+                finally_block,
+                ..
+            } => {
+                self.index_statement(body);
+                self.index_catch_clauses(catch_clauses);
+                if let Some(finally_block) = finally_block {
+                    self.index_statement(finally_block);
+                }
+            }
+            TypedStatement::Switch {
+                discriminant,
+                cases,
+                default_case,
+                ..
+            } => {
+                self.index_expression(discriminant);
+                self.index_switch_cases(cases);
+                if let Some(default_case) = default_case {
+                    self.index_statement(default_case);
+                }
+            }
+            TypedStatement::Block { statements, .. } => self.index_statements(statements),
+            TypedStatement::PatternMatch {
+                value, patterns, ..
+            } => {
+                self.index_expression(value);
+                for case in patterns {
+                    self.index_pattern(&case.pattern);
+                    if let Some(guard) = &case.guard {
+                        self.index_expression(guard);
+                    }
+                    self.index_statement(&case.body);
+                }
+            }
+            TypedStatement::MacroExpansion {
+                expanded_statements,
+                ..
+            } => self.index_statements(expanded_statements),
+            TypedStatement::Break { .. } | TypedStatement::Continue { .. } => {}
+        }
+    }
+
+    fn index_catch_clauses(&mut self, catch_clauses: &[TypedCatchClause]) {
+        for clause in catch_clauses {
+            let symbol = self.symbol_table.get_symbol(clause.exception_variable);
+            let mutability = symbol.map_or(Mutability::Mutable, |s| s.mutability);
+            self.define_local(
+                clause.exception_variable,
+                clause.exception_type,
+                mutability,
+                &clause.source_location,
+            );
+            if let Some(filter) = &clause.filter {
+                self.index_expression(filter);
+            }
+            self.index_statement(&clause.body);
+        }
+    }
+
+    fn index_switch_cases(&mut self, cases: &[TypedSwitchCase]) {
+        for case in cases {
+            self.index_expression(&case.case_value);
+            if let Some(guard) = &case.guard {
+                self.index_expression(guard);
+            }
+            self.index_statement(&case.body);
+        }
+    }
+
+    fn index_pattern(&mut self, pattern: &TypedPattern) {
+        match pattern {
+            TypedPattern::Variable {
+                symbol_id,
+                pattern_type,
+                source_location,
+            } => self.define_local(
+                *symbol_id,
+                *pattern_type,
+                Mutability::Immutable,
+                source_location,
+            ),
+            TypedPattern::Literal { value, .. } => self.index_expression(value),
+            TypedPattern::Constructor { args, .. } => {
+                for arg in args {
+                    self.index_pattern(arg);
+                }
+            }
+            TypedPattern::Array { elements, rest, .. } => {
+                for element in elements {
+                    self.index_pattern(element);
+                }
+                if let Some(rest) = rest {
+                    self.index_pattern(rest);
+                }
+            }
+            TypedPattern::Object { fields, .. } => {
+                for field in fields {
+                    self.index_pattern(&field.pattern);
+                }
+            }
+            TypedPattern::Guard { pattern, guard } => {
+                self.index_pattern(pattern);
+                self.index_expression(guard);
+            }
+            TypedPattern::Extractor {
+                extractor_expr,
+                value_expr,
+                ..
+            } => {
+                self.index_expression(extractor_expr);
+                self.index_expression(value_expr);
+            }
+            TypedPattern::Wildcard { .. } => {}
+        }
+    }
+
+    fn index_comprehension(&mut self, for_parts: &[TypedComprehensionFor], at: &SourceLocation) {
+        for part in for_parts {
+            self.define_loop_variables(part.key_var_symbol, part.var_symbol, at);
+            self.index_expression(&part.iterator);
+        }
+    }
+
+    fn index_expression(&mut self, expression: &TypedExpression) {
+        let at = &expression.source_location;
+        match &expression.kind {
+            TypedExpressionKind::Variable { symbol_id } => self.reference(*symbol_id, at, false),
+            TypedExpressionKind::FieldAccess {
+                object,
+                field_symbol,
+                ..
+            } => {
+                self.index_expression(object);
+                let member = !self.is_implicit_this(object);
+                self.reference(*field_symbol, at, member);
+            }
+            TypedExpressionKind::StaticFieldAccess {
+                class_symbol,
+                field_symbol,
+            } => {
+                self.reference(*class_symbol, at, false);
+                self.reference(*field_symbol, at, true);
+            }
+            TypedExpressionKind::ArrayAccess { array, index } => {
+                self.index_expression(array);
+                self.index_expression(index);
+            }
+            TypedExpressionKind::FunctionCall {
+                function,
+                arguments,
+                ..
+            } => {
+                self.index_expression(function);
+                if let Some(callee) = self.called_function(function) {
+                    let range = self.find_called_name(callee, &function.source_location);
+                    self.call(callee, range);
+                }
+                self.index_expressions(arguments);
+            }
+            TypedExpressionKind::MethodCall {
+                receiver,
+                method_symbol,
+                arguments,
+                ..
+            } => {
+                self.index_expression(receiver);
+                let member = !self.is_implicit_this(receiver);
+                let range = self.reference(*method_symbol, at, member);
+                self.call(*method_symbol, range);
+                self.index_expressions(arguments);
+            }
+            TypedExpressionKind::StaticMethodCall {
+                class_symbol,
+                method_symbol,
+                arguments,
+                ..
+            } => {
+                self.reference(*class_symbol, at, false);
+                let range = self.reference(*method_symbol, at, true);
+                self.call(*method_symbol, range);
+                self.index_expressions(arguments);
+            }
+            TypedExpressionKind::BinaryOp { left, right, .. } => {
+                self.index_expression(left);
+                self.index_expression(right);
+            }
+            TypedExpressionKind::UnaryOp { operand, .. } => self.index_expression(operand),
+            TypedExpressionKind::Conditional {
+                condition,
+                then_expr,
+                else_expr,
+            } => {
+                self.index_expression(condition);
+                self.index_expression(then_expr);
+                if let Some(else_expr) = else_expr {
+                    self.index_expression(else_expr);
+                }
+            }
+            TypedExpressionKind::While {
+                condition,
+                then_expr,
+            } => {
+                self.index_expression(condition);
+                self.index_expression(then_expr);
+            }
+            TypedExpressionKind::For {
+                variable,
+                iterable,
+                body,
+            } => {
+                self.define_loop_variables(None, *variable, at);
+                self.index_expression(iterable);
+                self.index_expression(body);
+            }
+            TypedExpressionKind::ForIn {
+                value_var,
+                key_var,
+                iterable,
+                body,
+            } => {
+                self.define_loop_variables(*key_var, *value_var, at);
+                self.index_expression(iterable);
+                self.index_expression(body);
+            }
+            TypedExpressionKind::ArrayLiteral { elements } => self.index_expressions(elements),
+            TypedExpressionKind::MapLiteral { entries } => {
+                for entry in entries {
+                    self.index_expression(&entry.key);
+                    self.index_expression(&entry.value);
+                }
+            }
+            TypedExpressionKind::ObjectLiteral { fields } => {
+                for field in fields {
+                    self.index_expression(&field.value);
+                }
+            }
+            TypedExpressionKind::FunctionLiteral {
+                parameters, body, ..
+            } => {
+                self.index_parameters(parameters);
+                self.index_statements(body);
+            }
+            TypedExpressionKind::Cast { expression, .. }
+            | TypedExpressionKind::Is { expression, .. }
+            | TypedExpressionKind::Throw { expression }
+            | TypedExpressionKind::Await { expression, .. } => self.index_expression(expression),
+            TypedExpressionKind::New {
+                class_type,
+                arguments,
+                ..
+            } => {
+                if let Some(class) = self.type_symbol(*class_type) {
+                    let range = self.reference(class, at, false);
+                    self.call(class, range);
+                }
+                self.index_expressions(arguments);
+            }
+            TypedExpressionKind::Return { value } => {
+                if let Some(value) = value {
+                    self.index_expression(value);
+                }
+            }
+            TypedExpressionKind::VarDeclarationExpr {
+                symbol_id,
+                var_type,
+                initializer,
+            } => {
+                self.define_local(*symbol_id, *var_type, Mutability::Mutable, at);
+                self.index_expression(initializer);
+            }
+            TypedExpressionKind::FinalDeclarationExpr {
+                symbol_id,
+                var_type,
+                initializer,
+            } => {
+                self.define_local(*symbol_id, *var_type, Mutability::Immutable, at);
+                self.index_expression(initializer);
+            }
+            TypedExpressionKind::StringInterpolation { parts } => {
+                for part in parts {
+                    if let StringInterpolationPart::Expression(expression) = part {
+                        self.index_expression(expression);
+                    }
+                }
+            }
+            TypedExpressionKind::MacroExpression { arguments, .. } => {
+                self.index_expressions(arguments)
+            }
+            TypedExpressionKind::Block { statements, .. } => self.index_statements(statements),
+            TypedExpressionKind::Meta { expression, .. } => self.index_expression(expression),
+            TypedExpressionKind::DollarIdent { arg, .. } => {
+                if let Some(arg) = arg {
+                    self.index_expression(arg);
+                }
+            }
+            TypedExpressionKind::CompilerSpecific { code, args, .. } => {
+                self.index_expression(code);
+                self.index_expressions(args);
+            }
+            TypedExpressionKind::Switch {
+                discriminant,
+                cases,
+                default_case,
+            } => {
+                self.index_expression(discriminant);
+                self.index_switch_cases(cases);
+                if let Some(default_case) = default_case {
+                    self.index_expression(default_case);
+                }
+            }
+            TypedExpressionKind::Try {
+                try_expr,
+                catch_clauses,
+                finally_block,
+            } => {
+                self.index_expression(try_expr);
+                self.index_catch_clauses(catch_clauses);
+                if let Some(finally_block) = finally_block {
+                    self.index_expression(finally_block);
+                }
+            }
+            TypedExpressionKind::ArrayComprehension {
+                for_parts,
+                expression,
+                ..
+            } => {
+                self.index_comprehension(for_parts, at);
+                self.index_expression(expression);
+            }
+            TypedExpressionKind::MapComprehension {
+                for_parts,
+                key_expr,
+                value_expr,
+                ..
+            } => {
+                self.index_comprehension(for_parts, at);
+                self.index_expression(key_expr);
+                self.index_expression(value_expr);
+            }
+            TypedExpressionKind::Literal { .. }
+            | TypedExpressionKind::This { .. }
+            | TypedExpressionKind::Super { .. }
+            | TypedExpressionKind::Null
+            | TypedExpressionKind::Break
+            | TypedExpressionKind::Continue
+            | TypedExpressionKind::PatternPlaceholder { .. } => {}
+        }
+    }
+
+    fn index_expressions(&mut self, expressions: &[TypedExpression]) {
+        for expression in expressions {
+            self.index_expression(expression);
+        }
+    }
+
+    /// `this` inserted for an unqualified member access, with nothing
+    /// written in the source
+    fn is_implicit_this(&self, object: &TypedExpression) -> bool {
+        matches!(object.kind, TypedExpressionKind::This { .. })
+            && !self
+                .source
+                .get(object.source_location.byte_offset as usize..)
+                .is_some_and(|rest| rest.starts_with("this"))
+    }
+
+    /// Add a definition and return its index
+    fn define(
+        &mut self,
+        symbol: Option<SymbolId>,
+        kind: DefinitionKind,
+        detail: String,
+        name: String,
+        at: &SourceLocation,
+        container: Option<usize>,
+    ) -> usize {
+        let name_range = self.find_name(at, &name, false);
+        let index = self.index.definitions.len();
+        if let Some(symbol) = symbol {
+            self.index.by_symbol.entry(symbol).or_insert(index);
+        }
+        self.index.definitions.push(Definition {
+            symbol,
+            name,
+            kind,
+            detail,
+            name_range,
+            container,
+            local: false,
+        });
+        index
+    }
+
+    fn define_local(
+        &mut self,
+        symbol: SymbolId,
+        var_type: TypeId,
+        mutability: Mutability,
+        at: &SourceLocation,
+    ) {
+        let name = self.symbol_name(symbol);
+        let keyword = if mutability == Mutability::Immutable {
+            "final"
+        } else {
+            "var"
+        };
+        let detail = format!("{} {}:{}", keyword, name, self.type_name(var_type));
+        let index = self.define(
+            Some(symbol),
+            DefinitionKind::Variable,
+            detail,
+            name,
+            at,
+            None,
+        );
+        self.index.definitions[index].local = true;
+    }
+
+    /// Loop variables, declared left to right after the node's location
+    fn define_loop_variables(
+        &mut self,
+        key: Option<SymbolId>,
+        value: SymbolId,
+        at: &SourceLocation,
+    ) {
+        for symbol in key.into_iter().chain(Some(value)) {
+            let var_type = self
+                .symbol_table
+                .get_symbol(symbol)
+                .map_or(TypeId::invalid(), |s| s.type_id);
+            self.define_local(symbol, var_type, Mutability::Mutable, at);
+        }
+    }
+
+    /// Record a use of `symbol` named after `at`; `member` looks for the
+    /// name after a `.`
+    /// Record a reference to `symbol` and return the range of its name
+    fn reference(
+        &mut self,
+        symbol: SymbolId,
+        at: &SourceLocation,
+        member: bool,
+    ) -> Option<Range<usize>> {
+        let declared = self.symbol_table.get_symbol(symbol)?;
+        let name = self.string(declared.name);
+        let range = self.find_name(at, &name, member)?;
+        if !self.index.by_symbol.contains_key(&symbol) {
+            self.define_external(symbol, name);
+        }
+        self.index.references.push(Reference {
+            symbol,
+            range: range.clone(),
+        });
+        Some(range)
+    }
+
+    /// Record a call to `callee` from the function being indexed
+    fn call(&mut self, callee: SymbolId, range: Option<Range<usize>>) {
+        if let (Some(caller), Some(range)) = (self.function, range) {
+            self.index.calls.push(Call {
+                caller,
+                callee,
+                range,
+            });
+        }
+    }
+
+    /// The function `function` names, if it is called by name
+    fn called_function(&self, function: &TypedExpression) -> Option<SymbolId> {
+        let symbol = match &function.kind {
+            TypedExpressionKind::Variable { symbol_id } => *symbol_id,
+            TypedExpressionKind::FieldAccess { field_symbol, .. }
+            | TypedExpressionKind::StaticFieldAccess { field_symbol, .. } => *field_symbol,
+            _ => return None,
+        };
+        let declared = self.symbol_table.get_symbol(symbol)?;
+        (declared.kind == SymbolKind::Function).then_some(symbol)
+    }
+
+    /// Range of the name of `callee`, called from an expression at `at`
+    fn find_called_name(&self, callee: SymbolId, at: &SourceLocation) -> Option<Range<usize>> {
+        let declared = self.symbol_table.get_symbol(callee)?;
+        let name = self.string(declared.name);
+        self.find_name(at, &name, false)
+            .or_else(|| self.find_name(at, &name, true))
+    }
+
+    /// Record the supertypes of the type `symbol`
+    fn supertypes<'t>(&mut self, symbol: SymbolId, types: impl IntoIterator<Item = &'t TypeId>) {
+        for &type_id in types {
+            if let Some(supertype) = self.type_symbol(type_id) {
+                self.index.supertypes.push((symbol, supertype));
+            }
+        }
+    }
+
+    /// Definition of a symbol declared outside the indexed declarations
+    fn define_external(&mut self, symbol: SymbolId, name: String) {
+        let Some(declared) = self.symbol_table.get_symbol(symbol) else {
+            return;
+        };
+        let (kind, detail) = match declared.kind {
+            SymbolKind::Class => (DefinitionKind::Class, format!("class {}", name)),
+            SymbolKind::Interface => (DefinitionKind::Interface, format!("interface {}", name)),
+            SymbolKind::Enum => (DefinitionKind::Enum, format!("enum {}", name)),
+            SymbolKind::Abstract => (DefinitionKind::Abstract, format!("abstract {}", name)),
+            SymbolKind::TypeAlias => (DefinitionKind::Typedef, format!("typedef {}", name)),
+            SymbolKind::EnumVariant => (DefinitionKind::EnumVariant, name.clone()),
+            SymbolKind::Function => {
+                let detail = match self.type_table.get(declared.type_id).map(|t| &t.kind) {
+                    Some(TypeKind::Function {
+                        params,
+                        return_type,
+                        ..
+                    }) => {
+                        let params: Vec<String> =
+                            params.iter().map(|&p| self.type_name(p)).collect();
+                        format!(
+                            "function {}({}):{}",
+                            name,
+                            params.join(", "),
+                            self.type_name(*return_type)
+                        )
+                    }
+                    _ => format!("function {}", name),
+                };
+                (DefinitionKind::Function, detail)
+            }
+            SymbolKind::Parameter => (
+                DefinitionKind::Parameter,
+                format!("{}:{}", name, self.type_name(declared.type_id)),
+            ),
+            SymbolKind::Field | SymbolKind::Property => (
+                DefinitionKind::Field,
+                format!("var {}:{}", name, self.type_name(declared.type_id)),
+            ),
+            _ => (
+                DefinitionKind::Variable,
+                format!("var {}:{}", name, self.type_name(declared.type_id)),
+            ),
+        };
+        let index = self.index.definitions.len();
+        self.index.by_symbol.insert(symbol, index);
+        self.index.definitions.push(Definition {
+            symbol: Some(symbol),
+            name,
+            kind,
+            detail,
+            name_range: None,
+            container: None,
+            local: false,
+        });
+    }
+
+    /// Byte range of `name` as a whole word within the window after `at`,
+    /// not crossing a statement or block boundary. With `member`, the first
+    /// occurrence after a `.`, else one right at `at`.
+    fn find_name(&self, at: &SourceLocation, name: &str, member: bool) -> Option<Range<usize>> {
+        if at.line == 0 || name.is_empty() {
+            return None;
+        }
+        let start = at.byte_offset as usize;
+        let source = self.source.as_bytes();
+        let mut end = (start + NAME_SEARCH_WINDOW).min(source.len());
+        if let Some(stop) = source
+            .get(start..end)?
+            .iter()
+            .position(|b| matches!(b, b';' | b'{' | b'}'))
+        {
+            end = start + stop;
+        }
+        while !self.source.is_char_boundary(end) {
+            end -= 1;
+        }
+        let window = self.source.get(start..end)?;
+
+        let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+        let mut fallback = None;
+        for (offset, _) in window.match_indices(name) {
+            let from = start + offset;
+            let to = from + name.len();
+            if (from > 0 && is_ident(source[from - 1]))
+                || source.get(to).copied().is_some_and(is_ident)
+            {
+                continue;
+            }
+            if !member {
+                return Some(from..to);
+            }
+            let before = source[..from]
+                .iter()
+                .rev()
+                .find(|b| !b.is_ascii_whitespace());
+            if before == Some(&b'.') {
+                return Some(from..to);
+            }
+            if from == start {
+                fallback = Some(from..to);
+            }
+        }
+        fallback
+    }
+
+    fn type_symbol(&self, type_id: TypeId) -> Option<SymbolId> {
+        match self.type_table.get(type_id).map(|t| &t.kind) {
+            Some(TypeKind::Class { symbol_id, .. } | TypeKind::Interface { symbol_id, .. }) => {
+                Some(*symbol_id)
+            }
+            Some(TypeKind::GenericInstance { base_type, .. }) => self.type_symbol(*base_type),
+            _ => self.symbol_table.get_symbol_from_type(type_id),
+        }
+    }
+
+    fn parameter(&self, parameter: &TypedParameter) -> String {
+        format!(
+            "{}{}:{}",
+            if parameter.is_optional { "?" } else { "" },
+            self.string(parameter.name),
+            self.type_name(parameter.param_type)
+        )
+    }
+
+    fn parameter_list(&self, parameters: &[TypedParameter]) -> String {
+        let parameters: Vec<String> = parameters.iter().map(|p| self.parameter(p)).collect();
+        parameters.join(", ")
+    }
+
+    fn string(&self, name: InternedString) -> String {
+        (self.resolve)(name).unwrap_or_default()
+    }
+
+    fn symbol_name(&self, symbol: SymbolId) -> String {
+        self.symbol_table
+            .get_symbol(symbol)
+            .map(|s| self.string(s.name))
+            .unwrap_or_default()
+    }
+
+    /// `type_id` as written in Haxe
+    fn type_name(&self, type_id: TypeId) -> String {
+        TypeNames {
+            symbol_table: self.symbol_table,
+            type_table: self.type_table,
+            resolve: self.resolve,
+        }
+        .name(type_id)
+    }
+}
+
+/// Prints types as written in Haxe, e.g. `Array<Int>` or `(Int) -> String`
+pub(crate) struct TypeNames<'a> {
+    pub symbol_table: &'a SymbolTable,
+    pub type_table: &'a TypeTable,
+    pub resolve: &'a dyn Fn(InternedString) -> Option<String>,
+}
+
+impl TypeNames<'_> {
+    pub fn name(&self, type_id: TypeId) -> String {
+        self.name_at(type_id, 0)
+    }
+
+    fn string(&self, name: InternedString) -> String {
+        (self.resolve)(name).unwrap_or_default()
+    }
+
+    fn symbol_name(&self, symbol: SymbolId) -> String {
+        self.symbol_table
+            .get_symbol(symbol)
+            .map(|s| self.string(s.name))
+            .unwrap_or_default()
+    }
+
+    fn name_at(&self, type_id: TypeId, depth: usize) -> String {
+        if depth > MAX_TYPE_DEPTH {
+            return "...".to_string();
+        }
+        let name = |id: TypeId| self.name_at(id, depth + 1);
+        let names = |ids: &[TypeId]| ids.iter().map(|&id| name(id)).collect::<Vec<_>>();
+        let generic = |base: String, args: &[TypeId]| {
+            if args.is_empty() {
+                base
+            } else {
+                format!("{}<{}>", base, names(args).join(", "))
+            }
+        };
+
+        let Some(ty) = self.type_table.get(type_id) else {
+            return "Unknown".to_string();
+        };
+        match &ty.kind {
+            TypeKind::Void => "Void".to_string(),
+            TypeKind::Bool => "Bool".to_string(),
+            TypeKind::Int => "Int".to_string(),
+            TypeKind::Float => "Float".to_string(),
+            TypeKind::String => "String".to_string(),
+            TypeKind::Char => "Char".to_string(),
+            TypeKind::Dynamic => "Dynamic".to_string(),
+            TypeKind::Unknown | TypeKind::Error => "Unknown".to_string(),
+            TypeKind::Placeholder { name } => self.string(*name),
+            TypeKind::Class {
+                symbol_id,
+                type_args,
+            }
+            | TypeKind::Interface {
+                symbol_id,
+                type_args,
+            }
+            | TypeKind::Enum {
+                symbol_id,
+                type_args,
+            }
+            | TypeKind::Abstract {
+                symbol_id,
+                type_args,
+                ..
+            }
+            | TypeKind::TypeAlias {
+                symbol_id,
+                type_args,
+                ..
+            } => generic(self.symbol_name(*symbol_id), type_args),
+            TypeKind::TypeParameter { symbol_id, .. } => self.symbol_name(*symbol_id),
+            TypeKind::GenericInstance {
+                base_type,
+                type_args,
+                ..
+            } => {
+                let base = name(*base_type);
+                let base = base.split('<').next().unwrap_or_default().to_string();
+                generic(base, type_args)
+            }
+            TypeKind::Function {
+                params,
+                return_type,
+                ..
+            } => {
+                let params = if params.is_empty() {
+                    "()".to_string()
+                } else {
+                    format!("({})", names(params).join(", "))
+                };
+                format!("{} -> {}", params, name(*return_type))
+            }
+            TypeKind::Array { element_type } => format!("Array<{}>", name(*element_type)),
+            TypeKind::Map {
+                key_type,
+                value_type,
+            } => format!("Map<{}, {}>", name(*key_type), name(*value_type)),
+            TypeKind::Optional { inner_type } => format!("Null<{}>", name(*inner_type)),
+            TypeKind::Anonymous { fields } => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|field| {
+                        format!(
+                            "{}{}:{}",
+                            if field.optional { "?" } else { "" },
+                            self.string(field.name),
+                            name(field.type_id)
+                        )
+                    })
+                    .collect();
+                format!("{{ {} }}", fields.join(", "))
+            }
+            TypeKind::Union { types } => names(types).join(" | "),
+            TypeKind::Intersection { types } => names(types).join(" & "),
+            TypeKind::Reference { target_type, .. } => name(*target_type),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::{HaxeCompilationPipeline, PipelineConfig};
+
+    #[test]
+    fn test_index_definitions_and_references() {
+        let source = r#"
+class Point {
+    public var x:Int;
+    public function new(x:Int) {
+        this.x = x;
+    }
+    public function add(other:Point):Int {
+        return x + other.x;
+    }
+}
+class Main {
+    static function main() {
+        var p = new Point(1);
+        var sum = p.add(p);
+        trace(sum);
+    }
+}
+"#;
+        let mut pipeline = HaxeCompilationPipeline::with_config(PipelineConfig {
+            emit_symbol_index: true,
+            ..Default::default()
+        });
+        let result = pipeline.compile_file("Main.hx", source);
+        assert_eq!(result.symbol_indexes.len(), 1);
+        let index = &result.symbol_indexes[0];
+        assert_eq!(index.file, "Main.hx");
+
+        let offset = |needle: &str| source.find(needle).unwrap();
+        let point = index
+            .definitions
+            .iter()
+            .find(|d| d.kind == DefinitionKind::Class && d.name == "Point")
+            .expect("class Point");
+        let class_name = offset("Point {");
+        assert_eq!(point.name_range, Some(class_name..class_name + 5));
+
+        let add = index
+            .definitions
+            .iter()
+            .find(|d| d.name == "add")
+            .expect("method add");
+        assert_eq!(add.kind, DefinitionKind::Method);
+        assert_eq!(add.detail, "function add(other:Point):Int");
+        assert_eq!(
+            add.container.map(|c| index.definitions[c].name.as_str()),
+            Some("Point")
+        );
+
+        // `p` in `p.add(p)` resolves to the local declared in `main`
+        let p = index
+            .definition_at(offset("p.add") + 1)
+            .expect("definition of p");
+        assert!(p.local);
+        assert_eq!(p.detail, "var p:Point");
+        let declared = offset("var p") + 4;
+        assert_eq!(p.name_range, Some(declared..declared + 1));
+
+        // The call resolves to the method, `new Point` to the class
+        let called = index.definition_at(offset(".add(p)") + 2).expect("add");
+        assert_eq!(called.symbol, add.symbol);
+        let constructed = index.definition_at(offset("Point(1)") + 2).expect("Point");
+        assert_eq!(constructed.symbol, point.symbol);
+    }
+}
@@ -0,0 +1,330 @@
+//! Local usage statistics (`rayzor stats show`)
+//!
+//! An opt-in log of how a project compiles over time: compile and JIT times,
+//! BLADE cache hits and misses, and how many functions ended each run in
+//! each tier. Nothing is ever uploaded; records are appended as JSON lines to
+//! `.rayzor/stats.jsonl` under the project root (or the file named in
+//! `[stats]`), so the log can be inspected, trimmed or deleted by hand.
+//!
+//! Recording is off unless `[stats] enabled = true` is set in rayzor.toml or
+//! `RAYZOR_STATS=1` is set in the environment; `RAYZOR_STATS=0` turns it off
+//! again for one invocation.
+
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::workspace::{self, RayzorManifest};
+
+/// Environment variable that overrides `[stats] enabled`.
+pub const STATS_ENV_VAR: &str = "RAYZOR_STATS";
+
+/// Default stats file, relative to the project root.
+pub const DEFAULT_STATS_FILE: &str = ".rayzor/stats.jsonl";
+
+/// Tier names, in the order of [`StatsRecord::tiers`].
+pub const TIER_NAMES: [&str; 5] = ["interpreted", "baseline", "standard", "optimized", "llvm"];
+
+/// One compilation or run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatsRecord {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    /// CLI command that produced the record (`run`, `compile`)
+    pub command: String,
+    /// Source file, as given on the command line
+    pub file: String,
+    /// Haxe to MIR compile time
+    pub compile_ms: u64,
+    /// MIR to machine code time, for runs that JIT
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jit_ms: Option<u64>,
+    #[serde(default)]
+    pub cache_hits: u64,
+    #[serde(default)]
+    pub cache_misses: u64,
+    /// Functions per tier when the run finished (see [`TIER_NAMES`])
+    #[serde(default)]
+    pub tiers: [u64; 5],
+}
+
+impl StatsRecord {
+    pub fn new(command: &str, file: &Path) -> Self {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            timestamp,
+            command: command.to_string(),
+            file: file.display().to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Stats file of the project enclosing the current directory, and whether
+/// recording is on for it.
+pub fn stats_file() -> Option<(PathBuf, bool)> {
+    let cwd = std::env::current_dir().ok()?;
+    let root = workspace::find_project_root(&cwd);
+    let config = root
+        .as_deref()
+        .and_then(|root| workspace::load_manifest(root).ok())
+        .and_then(|manifest| match manifest {
+            RayzorManifest::SingleProject(project) => project.stats,
+            RayzorManifest::Workspace(_) => None,
+        })
+        .unwrap_or_default();
+
+    let enabled = match std::env::var(STATS_ENV_VAR).ok().as_deref() {
+        Some("0") | Some("false") => false,
+        Some(v) if !v.is_empty() => true,
+        _ => config.enabled.unwrap_or(false),
+    };
+    let root = root.unwrap_or(cwd);
+    let path = root.join(config.file.as_deref().unwrap_or(DEFAULT_STATS_FILE));
+    Some((path, enabled))
+}
+
+/// Append `record` to the current project's stats file if recording is on.
+/// Statistics are best effort: a failed write is logged, never an error.
+pub fn record(record: &StatsRecord) {
+    if let Some((path, true)) = stats_file() {
+        if let Err(e) = append(&path, record) {
+            log::warn!("usage statistics not recorded: {}", e);
+        }
+    }
+}
+
+/// Append `record` to the stats file at `path`, creating it if needed.
+pub fn append(path: &Path, record: &StatsRecord) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let line = serde_json::to_string(record).map_err(|e| e.to_string())?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Read every record in the stats file at `path`, skipping lines that do
+/// not parse (e.g. a write cut short by a crash).
+pub fn load(path: &Path) -> Result<Vec<StatsRecord>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Render trends over `records`, oldest first: per-day compile times and
+/// cache hit rates, and the average tier histogram of the first and second
+/// half of the runs.
+pub fn render(records: &[StatsRecord]) -> String {
+    let mut out = String::new();
+    if records.is_empty() {
+        out.push_str("No statistics recorded yet.\n");
+        return out;
+    }
+
+    let compile: Vec<u64> = records.iter().map(|r| r.compile_ms).collect();
+    let _ = writeln!(out, "{} runs", records.len());
+    let _ = writeln!(
+        out,
+        "compile time  min {} ms, median {} ms, max {} ms",
+        compile.iter().min().unwrap_or(&0),
+        median(&compile),
+        compile.iter().max().unwrap_or(&0)
+    );
+    let _ = writeln!(out, "              {}", sparkline(&compile));
+    let (hits, misses) = cache_totals(records);
+    if hits + misses > 0 {
+        let _ = writeln!(
+            out,
+            "cache         {:.0}% hit rate ({} hits, {} misses)",
+            hit_rate(hits, misses),
+            hits,
+            misses
+        );
+    }
+
+    let _ = writeln!(out, "\nBy day:");
+    let _ = writeln!(
+        out,
+        "  {:<10}  {:>5}  {:>12}  {:>9}",
+        "date", "runs", "median ms", "cache hit"
+    );
+    let mut start = 0;
+    while start < records.len() {
+        let day = records[start].timestamp / 86_400;
+        let end = records[start..]
+            .iter()
+            .position(|r| r.timestamp / 86_400 != day)
+            .map_or(records.len(), |n| start + n);
+        let group = &records[start..end];
+        let times: Vec<u64> = group.iter().map(|r| r.compile_ms).collect();
+        let (hits, misses) = cache_totals(group);
+        let cache = if hits + misses > 0 {
+            format!("{:.0}%", hit_rate(hits, misses))
+        } else {
+            "-".to_string()
+        };
+        let _ = writeln!(
+            out,
+            "  {:<10}  {:>5}  {:>12}  {:>9}",
+            date(day),
+            group.len(),
+            median(&times),
+            cache
+        );
+        start = end;
+    }
+
+    let runs: Vec<&StatsRecord> = records
+        .iter()
+        .filter(|r| r.tiers.iter().any(|&n| n > 0))
+        .collect();
+    if !runs.is_empty() {
+        let (earlier, recent) = runs.split_at(runs.len() / 2);
+        let _ = writeln!(out, "\nTier histogram (average functions per run):");
+        let _ = writeln!(out, "  {:<12}  {:>8}  {:>8}", "tier", "earlier", "recent");
+        for (tier, name) in TIER_NAMES.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "  {:<12}  {:>8}  {:>8}",
+                name,
+                average_tier(earlier, tier),
+                average_tier(recent, tier)
+            );
+        }
+    }
+    out
+}
+
+fn cache_totals(records: &[StatsRecord]) -> (u64, u64) {
+    records.iter().fold((0, 0), |(hits, misses), r| {
+        (hits + r.cache_hits, misses + r.cache_misses)
+    })
+}
+
+fn hit_rate(hits: u64, misses: u64) -> f64 {
+    hits as f64 * 100.0 / (hits + misses) as f64
+}
+
+fn median(values: &[u64]) -> u64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    sorted.get(sorted.len() / 2).copied().unwrap_or(0)
+}
+
+fn average_tier(runs: &[&StatsRecord], tier: usize) -> String {
+    if runs.is_empty() {
+        return "-".to_string();
+    }
+    let total: u64 = runs.iter().map(|r| r.tiers[tier]).sum();
+    format!("{:.1}", total as f64 / runs.len() as f64)
+}
+
+/// One bar per value (the most recent 60), scaled between min and max.
+fn sparkline(values: &[u64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let values = &values[values.len().saturating_sub(60)..];
+    let min = values.iter().copied().min().unwrap_or(0);
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&v| {
+            let scaled = (v - min) * 7 / (max - min).max(1);
+            BARS[scaled as usize]
+        })
+        .collect()
+}
+
+/// `YYYY-MM-DD` of a day number since the Unix epoch (UTC).
+fn date(day: u64) -> String {
+    // Civil-from-days, Howard Hinnant's algorithm
+    let z = day as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(timestamp: u64, compile_ms: u64, hits: u64, tiers: [u64; 5]) -> StatsRecord {
+        StatsRecord {
+            timestamp,
+            command: "run".to_string(),
+            file: "src/Main.hx".to_string(),
+            compile_ms,
+            jit_ms: None,
+            cache_hits: hits,
+            cache_misses: 1 - hits,
+            tiers,
+        }
+    }
+
+    #[test]
+    fn test_append_and_load() {
+        let path = std::env::temp_dir()
+            .join(format!("rayzor-stats-{}", std::process::id()))
+            .join("stats.jsonl");
+        let _ = std::fs::remove_file(&path);
+
+        let first = record(1_700_000_000, 120, 0, [0, 3, 0, 0, 0]);
+        let second = record(1_700_000_100, 40, 1, [0, 1, 2, 0, 0]);
+        append(&path, &first).unwrap();
+        append(&path, &second).unwrap();
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"truncated\n")
+            .unwrap();
+
+        assert_eq!(load(&path).unwrap(), vec![first, second]);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_render_trends() {
+        let records = vec![
+            record(1_700_000_000, 120, 0, [2, 4, 0, 0, 0]),
+            record(1_700_000_100, 100, 1, [0, 4, 2, 0, 0]),
+            record(1_700_086_400, 40, 1, [0, 2, 2, 2, 0]),
+        ];
+        let report = render(&records);
+        assert!(report.contains("3 runs"));
+        assert!(report.contains("min 40 ms, median 100 ms, max 120 ms"));
+        assert!(report.contains("67% hit rate (2 hits, 1 misses)"));
+        assert!(report.contains("2023-11-14"));
+        assert!(report.contains("2023-11-15"));
+        assert!(report.contains("interpreted        2.0       0.0"));
+        assert!(report.contains("optimized          0.0       1.0"));
+
+        assert_eq!(render(&[]), "No statistics recorded yet.\n");
+    }
+
+    #[test]
+    fn test_date() {
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(date(19_675), "2023-11-14");
+        assert_eq!(date(11_016), "2000-02-29");
+    }
+}
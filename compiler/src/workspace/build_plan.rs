@@ -0,0 +1,440 @@
+//! Build plan export (`rayzor build --emit-plan plan.json`)
+//!
+//! A build plan is the resolved build graph of one or more targets (a
+//! project, every member of a workspace, or an HXML build): entry files,
+//! class paths, defines, outputs, and every source file reachable from the
+//! entry through `import`/`using`, with each import resolved to a file.
+//! External build systems (Bazel/Buck rules, Ninja generators) read it to
+//! declare inputs and outputs and wrap Rayzor hermetically.
+//!
+//! Imports are resolved against the target's class paths, then the stdlib
+//! paths. An import that resolves to neither is kept with kind `unresolved`;
+//! with the embedded stdlib most stdlib imports have no file on disk and are
+//! reported as `stdlib` without a path. Types used from the same package
+//! without an import are not followed.
+//!
+//! Each source carries the cache key the BLADE cache validates its module
+//! against, so a wrapper can tell which modules a change invalidates.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+/// Version of the plan format; bumped on incompatible changes
+pub const PLAN_FORMAT_VERSION: u32 = 1;
+
+/// Resolved build graph of all targets of one `rayzor build`
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildPlan {
+    pub format_version: u32,
+    pub compiler_version: String,
+    pub targets: Vec<TargetPlan>,
+}
+
+impl Default for BuildPlan {
+    fn default() -> Self {
+        Self {
+            format_version: PLAN_FORMAT_VERSION,
+            compiler_version: env!("CARGO_PKG_VERSION").to_string(),
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl BuildPlan {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("build plan serializes")
+    }
+}
+
+/// One project (or HXML build) in a plan
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TargetPlan {
+    /// Project name, if the manifest sets one
+    pub name: Option<String>,
+    /// Project directory (the HXML file's directory for HXML builds)
+    pub root: PathBuf,
+    pub entry: PathBuf,
+    /// Build target from the manifest (`native`, `jit`, `bundle`)
+    pub target: Option<String>,
+    pub opt_level: Option<u8>,
+    pub class_paths: Vec<PathBuf>,
+    /// `-D` defines; flags without a value map to `"1"`
+    pub defines: BTreeMap<String, String>,
+    /// BLADE cache directory, if caching is enabled
+    pub cache_dir: Option<PathBuf>,
+    pub outputs: Vec<PathBuf>,
+    /// Reachable sources, entry first, in discovery order
+    pub sources: Vec<SourcePlan>,
+}
+
+/// A source file of a target
+#[derive(Debug, Clone, Serialize)]
+pub struct SourcePlan {
+    pub path: PathBuf,
+    /// Module path from the package declaration and file name, e.g. `game.Player`
+    pub module: String,
+    /// BLADE cache key (hash of the source text), as 16 hex digits
+    pub cache_key: String,
+    pub imports: Vec<ImportPlan>,
+}
+
+/// An `import` or `using` of a source file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportPlan {
+    /// Dotted path as written, e.g. `game.entities.*`
+    pub path: String,
+    pub kind: ImportKind,
+    /// Files the import resolved to (several for wildcard imports)
+    pub files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportKind {
+    /// A file in the target's class paths
+    Source,
+    /// A standard library module
+    Stdlib,
+    Unresolved,
+}
+
+/// Where a dotted import path resolves to: the files and which search path
+/// family they came from
+fn resolve_import(
+    segments: &[String],
+    wildcard: bool,
+    class_paths: &[PathBuf],
+    stdlib_paths: &[PathBuf],
+) -> (ImportKind, Vec<PathBuf>) {
+    for (kind, paths) in [
+        (ImportKind::Source, class_paths),
+        (ImportKind::Stdlib, stdlib_paths),
+    ] {
+        let files = if wildcard {
+            package_files(segments, paths)
+        } else {
+            module_file(segments, paths).into_iter().collect()
+        };
+        if !files.is_empty() {
+            return (kind, files);
+        }
+    }
+
+    // Not on disk: the stdlib is usually embedded in the compiler
+    if is_stdlib_path(segments) {
+        (ImportKind::Stdlib, Vec::new())
+    } else {
+        (ImportKind::Unresolved, Vec::new())
+    }
+}
+
+/// File of a module path. The path may name a sub-type or a static field
+/// after the module (`pack.Module.SubType`), so shorter prefixes ending in
+/// a type name are tried too.
+fn module_file(segments: &[String], search_paths: &[PathBuf]) -> Option<PathBuf> {
+    (1..=segments.len())
+        .rev()
+        .filter(|&len| segments[len - 1].starts_with(|c: char| c.is_ascii_uppercase()))
+        .find_map(|len| {
+            let relative = format!("{}.hx", segments[..len].join("/"));
+            search_paths
+                .iter()
+                .map(|dir| dir.join(&relative))
+                .find(|path| path.is_file())
+        })
+}
+
+/// `.hx` files of a package (`import pack.*`), from the first search path
+/// that has the package directory
+fn package_files(segments: &[String], search_paths: &[PathBuf]) -> Vec<PathBuf> {
+    for dir in search_paths {
+        let package_dir = segments.iter().fold(dir.clone(), |p, s| p.join(s));
+        let Ok(entries) = std::fs::read_dir(&package_dir) else {
+            continue;
+        };
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "hx") && p.is_file())
+            .collect();
+        files.sort();
+        return files;
+    }
+    Vec::new()
+}
+
+/// Top-level packages and modules of the Haxe and Rayzor standard library
+fn is_stdlib_path(segments: &[String]) -> bool {
+    const ROOTS: &[&str] = &[
+        "haxe",
+        "sys",
+        "rayzor",
+        "Std",
+        "StdTypes",
+        "Math",
+        "String",
+        "StringTools",
+        "Array",
+        "Lambda",
+        "Date",
+        "DateTools",
+        "EReg",
+        "Reflect",
+        "Type",
+        "Sys",
+        "List",
+        "Map",
+        "IntIterator",
+        "StringBuf",
+        "Xml",
+        "UInt",
+        "Int32",
+        "Int64",
+    ];
+    segments
+        .first()
+        .is_some_and(|root| ROOTS.contains(&root.as_str()))
+}
+
+/// Cache key of a source text, matching the BLADE cache's source hash
+fn cache_key(source: &str) -> String {
+    format!(
+        "{:016x}",
+        crate::compilation::CompilationUnit::hash_source(source)
+    )
+}
+
+impl TargetPlan {
+    /// Target of a `rayzor.toml` project, without sources. None if the
+    /// manifest has no entry.
+    pub fn from_project(project: &super::Project) -> Option<Self> {
+        let build = project.manifest.build.as_ref();
+        Some(Self {
+            name: project.manifest.name.clone(),
+            root: project.root.clone(),
+            entry: project.entry_path()?,
+            target: build.and_then(|b| b.target.clone()),
+            opt_level: build.and_then(|b| b.opt_level),
+            class_paths: project.resolved_class_paths(),
+            defines: manifest_defines(build.and_then(|b| b.defines.as_ref())),
+            cache_dir: project.cache_enabled().then(|| project.cache_dir()),
+            outputs: project.output_path().into_iter().collect(),
+            sources: Vec::new(),
+        })
+    }
+
+    /// Walk `import`/`using` from the entry and fill in [`Self::sources`].
+    /// With no class paths, the entry's directory is the class path.
+    pub fn resolve_sources(&mut self, stdlib_paths: &[PathBuf]) -> Result<(), String> {
+        if self.class_paths.is_empty() {
+            if let Some(dir) = self.entry.parent() {
+                self.class_paths.push(dir.to_path_buf());
+            }
+        }
+
+        let mut seen = BTreeSet::new();
+        let mut queue = VecDeque::from([self.entry.clone()]);
+        seen.insert(normalize(&self.entry));
+        self.sources.clear();
+
+        while let Some(path) = queue.pop_front() {
+            let source = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let file = parser::parse_haxe_file(&path.to_string_lossy(), &source, false)
+                .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+
+            let written = file
+                .imports
+                .iter()
+                .map(|import| {
+                    let wildcard = matches!(
+                        import.mode,
+                        parser::ImportMode::Wildcard
+                            | parser::ImportMode::WildcardWithExclusions(_)
+                    );
+                    (import.path.clone(), wildcard)
+                })
+                .chain(file.using.iter().map(|using| (using.path.clone(), false)));
+
+            let mut imports = Vec::new();
+            for (segments, wildcard) in written {
+                let (kind, files) =
+                    resolve_import(&segments, wildcard, &self.class_paths, stdlib_paths);
+                if kind == ImportKind::Source {
+                    for file in &files {
+                        if seen.insert(normalize(file)) {
+                            queue.push_back(file.clone());
+                        }
+                    }
+                }
+                let mut dotted = segments.join(".");
+                if wildcard {
+                    dotted.push_str(".*");
+                }
+                imports.push(ImportPlan {
+                    path: dotted,
+                    kind,
+                    files,
+                });
+            }
+
+            let module_name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let module = match &file.package {
+                Some(package) if !package.path.is_empty() => {
+                    format!("{}.{}", package.path.join("."), module_name)
+                }
+                _ => module_name,
+            };
+            self.sources.push(SourcePlan {
+                path,
+                module,
+                cache_key: cache_key(&source),
+                imports,
+            });
+        }
+        Ok(())
+    }
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Defines from `[build] defines` as strings
+pub fn manifest_defines(
+    defines: Option<&std::collections::HashMap<String, toml::Value>>,
+) -> BTreeMap<String, String> {
+    defines
+        .into_iter()
+        .flatten()
+        .map(|(name, value)| {
+            let value = match value {
+                toml::Value::String(s) => s.clone(),
+                toml::Value::Boolean(true) => "1".to_string(),
+                other => other.to_string(),
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, relative: &str, source: &str) {
+        let path = root.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_sources() {
+        let root = std::env::temp_dir().join(format!("rayzor-plan-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        write(
+            &root,
+            "src/Main.hx",
+            "import game.Player;\nimport game.items.*;\nimport haxe.ds.StringMap;\nimport nowhere.Thing;\nusing StringTools;\n\nclass Main {\n    static function main() {}\n}\n",
+        );
+        write(
+            &root,
+            "src/game/Player.hx",
+            "package game;\n\nimport game.items.Sword;\n\nclass Player {}\n",
+        );
+        write(
+            &root,
+            "src/game/items/Sword.hx",
+            "package game.items;\n\nclass Sword {}\n",
+        );
+        write(
+            &root,
+            "src/game/items/Shield.hx",
+            "package game.items;\n\nclass Shield {}\n",
+        );
+
+        let mut target = TargetPlan {
+            root: root.clone(),
+            entry: root.join("src/Main.hx"),
+            class_paths: vec![root.join("src")],
+            ..Default::default()
+        };
+        target.resolve_sources(&[]).unwrap();
+
+        let modules: Vec<&str> = target.sources.iter().map(|s| s.module.as_str()).collect();
+        assert_eq!(
+            modules,
+            vec![
+                "Main",
+                "game.Player",
+                "game.items.Shield",
+                "game.items.Sword"
+            ]
+        );
+
+        let main = &target.sources[0];
+        let kinds: Vec<(&str, ImportKind)> = main
+            .imports
+            .iter()
+            .map(|i| (i.path.as_str(), i.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("game.Player", ImportKind::Source),
+                ("game.items.*", ImportKind::Source),
+                ("haxe.ds.StringMap", ImportKind::Stdlib),
+                ("nowhere.Thing", ImportKind::Unresolved),
+                ("StringTools", ImportKind::Stdlib),
+            ]
+        );
+        assert_eq!(main.imports[1].files.len(), 2);
+        assert_eq!(main.cache_key.len(), 16);
+
+        let json = BuildPlan {
+            targets: vec![target],
+            ..Default::default()
+        }
+        .to_json();
+        assert!(json.contains("\"format_version\": 1"));
+        assert!(json.contains("\"kind\": \"unresolved\""));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_module_file_with_subtype() {
+        let root = std::env::temp_dir().join(format!("rayzor-plan-sub-{}", std::process::id()));
+        write(
+            &root,
+            "pack/Shapes.hx",
+            "package pack;\n\nclass Shapes {}\n",
+        );
+        let segments: Vec<String> = ["pack", "Shapes", "Circle"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            module_file(&segments, std::slice::from_ref(&root)),
+            Some(root.join("pack/Shapes.hx"))
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_manifest_defines() {
+        let mut defines = std::collections::HashMap::new();
+        defines.insert("debug".to_string(), toml::Value::Boolean(true));
+        defines.insert("level".to_string(), toml::Value::Integer(3));
+        defines.insert("name".to_string(), toml::Value::String("demo".to_string()));
+        let defines = manifest_defines(Some(&defines));
+        assert_eq!(defines["debug"], "1");
+        assert_eq!(defines["level"], "3");
+        assert_eq!(defines["name"], "demo");
+    }
+}
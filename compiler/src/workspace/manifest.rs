@@ -1,5 +1,7 @@
 //! TOML manifest parsing for `rayzor.toml`.
 
+use crate::codegen::tiered_backend::{BailoutStrategy, OptimizationTier, TierPreset, TieredConfig};
+use crate::ir::optimization::{PassSizeLimits, SIZE_LIMITED_PASSES};
 use serde::Deserialize;
 use std::collections::HashMap;
 
@@ -18,6 +20,10 @@ struct RawManifest {
     build: Option<BuildConfig>,
     cache: Option<CacheConfig>,
     bundle: Option<BundleConfig>,
+    jit: Option<JitConfig>,
+    optimization: Option<OptimizationConfig>,
+    stats: Option<StatsConfig>,
+    install: Option<InstallConfig>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -46,6 +52,18 @@ pub struct ProjectManifest {
     /// Bundle configuration
     #[serde(skip)]
     pub bundle: Option<BundleConfig>,
+    /// Tiered JIT configuration
+    #[serde(skip)]
+    pub jit: Option<JitConfig>,
+    /// MIR optimization configuration
+    #[serde(skip)]
+    pub optimization: Option<OptimizationConfig>,
+    /// Local usage statistics configuration
+    #[serde(skip)]
+    pub stats: Option<StatsConfig>,
+    /// `rayzor install` configuration
+    #[serde(skip)]
+    pub install: Option<InstallConfig>,
 }
 
 /// Workspace manifest fields.
@@ -83,6 +101,30 @@ pub struct CacheConfig {
     pub dir: Option<String>,
     /// Whether caching is enabled
     pub enabled: Option<bool>,
+    /// Shared cache behind the local one
+    pub remote: Option<RemoteCacheConfig>,
+}
+
+/// `[cache.remote]` section: a shared BLADE cache, read through on local
+/// misses (see `ir::blade_cache`).
+///
+/// ```toml
+/// [cache.remote]
+/// url = "http://cache.internal:9090/rayzor"
+/// token-env = "RAYZOR_CACHE_TOKEN"
+/// upload = true
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RemoteCacheConfig {
+    /// `http://host[:port]/prefix`, `file:///path`, or a directory path
+    pub url: String,
+    /// Environment variable holding a bearer token for the HTTP backend
+    pub token_env: Option<String>,
+    /// Upload freshly compiled modules (default: false, read-only)
+    pub upload: Option<bool>,
+    /// Timeout of one remote request in seconds (default: 10)
+    pub timeout_secs: Option<u64>,
 }
 
 /// `[bundle]` section.
@@ -94,6 +136,207 @@ pub struct BundleConfig {
     pub strip: Option<bool>,
 }
 
+/// `[stats]` section: opt-in local usage statistics, shown by
+/// `rayzor stats show` (see `tools::usage_stats`). Never uploaded.
+///
+/// ```toml
+/// [stats]
+/// enabled = true
+/// file = ".rayzor/stats.jsonl"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct StatsConfig {
+    /// Record compile times, cache hits and tier histograms (default: false)
+    pub enabled: Option<bool>,
+    /// Stats file (relative to project root)
+    pub file: Option<String>,
+}
+
+/// `[install]` section: what `rayzor install` installs and where (see
+/// `tools::install`). Paths are relative to the project root.
+///
+/// ```toml
+/// [install]
+/// prefix = "dist"
+/// artifact = "build/app"
+/// gpu = true
+/// rpkgs = ["vendor/math.rpkg"]
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct InstallConfig {
+    /// Install prefix (default: ~/.local)
+    pub prefix: Option<String>,
+    /// Executable or .rzb bundle to install (default: `[build] output`)
+    pub artifact: Option<String>,
+    /// Installed program name (default: the artifact's file stem)
+    pub name: Option<String>,
+    /// Install the GPU plugin alongside (default: false)
+    pub gpu: Option<bool>,
+    /// Packages whose native libraries the program needs
+    #[serde(default)]
+    pub rpkgs: Vec<String>,
+}
+
+/// `[jit]` section: a tiered JIT configuration built on a preset.
+///
+/// ```toml
+/// [jit]
+/// preset = "server"
+/// start-tier = "interpreted"
+/// hot-threshold = 100
+/// background-threads = 4
+/// llvm = false
+///
+/// [jit.functions]
+/// "Main.render" = "optimized"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct JitConfig {
+    /// Preset the other settings modify (default: the `--preset` option)
+    pub preset: Option<String>,
+    /// "interpreted" for instant startup, or "baseline" to JIT up front
+    pub start_tier: Option<String>,
+    /// Calls before leaving the interpreter
+    pub interpreter_threshold: Option<u64>,
+    /// Calls before promotion to the Standard tier
+    pub warm_threshold: Option<u64>,
+    /// Calls before promotion to the Optimized tier
+    pub hot_threshold: Option<u64>,
+    /// Calls before promotion to the Maximum (LLVM) tier
+    pub blazing_threshold: Option<u64>,
+    /// Profile every Nth call
+    pub sample_rate: Option<u64>,
+    /// Background optimization threads; 0 optimizes synchronously
+    pub background_threads: Option<usize>,
+    /// How often the background worker looks for hot functions
+    pub check_interval_ms: Option<u64>,
+    /// Whether hot functions may be promoted to the LLVM tier
+    pub llvm: Option<bool>,
+    /// Tier promotion budget (0 disables promotion)
+    pub max_tier_promotions: Option<u64>,
+    /// Interpreter loop iterations before bailing out to the JIT
+    pub bailout_iterations: Option<u64>,
+    /// Per-function tier overrides (`"Class.method" = "optimized"`)
+    #[serde(default)]
+    pub functions: HashMap<String, String>,
+}
+
+impl JitConfig {
+    /// The preset named in this section, if any
+    pub fn preset(&self) -> Result<Option<TierPreset>, String> {
+        self.preset
+            .as_deref()
+            .map(|name| {
+                TierPreset::from_name(name)
+                    .ok_or_else(|| format!("Unknown JIT preset '{}' in rayzor.toml", name))
+            })
+            .transpose()
+    }
+
+    /// Override the settings given in this section on top of `config`
+    pub fn apply(&self, config: &mut TieredConfig) -> Result<(), String> {
+        if let Some(start) = &self.start_tier {
+            config.start_interpreted = match start.as_str() {
+                "interpreted" => true,
+                "baseline" => false,
+                other => {
+                    return Err(format!(
+                        "Invalid [jit] start-tier '{}': expected \"interpreted\" or \"baseline\"",
+                        other
+                    ))
+                }
+            };
+        }
+
+        let profile = &mut config.profile_config;
+        if let Some(n) = self.interpreter_threshold {
+            profile.interpreter_threshold = n;
+        }
+        if let Some(n) = self.warm_threshold {
+            profile.warm_threshold = n;
+        }
+        if let Some(n) = self.hot_threshold {
+            profile.hot_threshold = n;
+        }
+        if let Some(n) = self.blazing_threshold {
+            profile.blazing_threshold = n;
+        }
+        if let Some(n) = self.sample_rate {
+            if n == 0 {
+                return Err("Invalid [jit] sample-rate 0: must be at least 1".to_string());
+            }
+            profile.sample_rate = n;
+        }
+        if self.llvm == Some(false) {
+            profile.blazing_threshold = u64::MAX;
+        }
+
+        if let Some(threads) = self.background_threads {
+            config.enable_background_optimization = threads > 0;
+            config.max_parallel_optimizations = threads;
+        }
+        if let Some(ms) = self.check_interval_ms {
+            config.optimization_check_interval_ms = ms;
+        }
+        if let Some(n) = self.max_tier_promotions {
+            config.max_tier_promotions = n;
+        }
+        if let Some(n) = self.bailout_iterations {
+            config.bailout_strategy = BailoutStrategy::Custom(n);
+        }
+
+        for (function, tier) in &self.functions {
+            let tier = OptimizationTier::from_name(tier).ok_or_else(|| {
+                format!(
+                    "Invalid tier '{}' for {} in [jit.functions]: expected interpreted, \
+                     baseline, standard, optimized or maximum",
+                    tier, function
+                )
+            })?;
+            config.function_tiers.insert(function.clone(), tier);
+        }
+        Ok(())
+    }
+}
+
+/// `[optimization]` section: MIR optimization settings shared by the JIT
+/// tiers and AOT builds.
+///
+/// ```toml
+/// [optimization.size-limits]
+/// gvn = 50000
+/// inlining = 20000
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct OptimizationConfig {
+    /// Per-pass instruction counts above which the pass skips a function.
+    /// Passes not listed keep their default limit.
+    #[serde(default)]
+    pub size_limits: HashMap<String, usize>,
+}
+
+impl OptimizationConfig {
+    /// The default size limits with this section's applied on top
+    pub fn size_limits(&self) -> Result<PassSizeLimits, String> {
+        let mut limits = PassSizeLimits::defaults();
+        for (pass, &max_instructions) in &self.size_limits {
+            if !SIZE_LIMITED_PASSES.contains(&pass.as_str()) {
+                return Err(format!(
+                    "Unknown pass '{}' in [optimization.size-limits]: expected one of {}",
+                    pass,
+                    SIZE_LIMITED_PASSES.join(", ")
+                ));
+            }
+            limits.set(pass, max_instructions);
+        }
+        Ok(limits)
+    }
+}
+
 /// `[workspace.cache]` section.
 #[derive(Debug, Clone, Deserialize)]
 pub struct WorkspaceCacheConfig {
@@ -119,6 +362,10 @@ pub fn parse_manifest(content: &str) -> Result<RayzorManifest, String> {
         project.build = raw.build;
         project.cache = raw.cache;
         project.bundle = raw.bundle;
+        project.jit = raw.jit;
+        project.optimization = raw.optimization;
+        project.stats = raw.stats;
+        project.install = raw.install;
         return Ok(RayzorManifest::SingleProject(project));
     }
 
@@ -192,4 +439,161 @@ hxml = "build.hxml"
             _ => panic!("Expected SingleProject"),
         }
     }
+
+    #[test]
+    fn test_parse_stats_section() {
+        let toml = r#"
+[project]
+name = "api"
+
+[stats]
+enabled = true
+"#;
+        match parse_manifest(toml).unwrap() {
+            RayzorManifest::SingleProject(p) => {
+                let stats = p.stats.unwrap();
+                assert_eq!(stats.enabled, Some(true));
+                assert_eq!(stats.file, None);
+            }
+            _ => panic!("Expected SingleProject"),
+        }
+
+        let err =
+            parse_manifest("[project]\nname = \"api\"\n[stats]\nupload = true\n").unwrap_err();
+        assert!(err.contains("upload"));
+    }
+
+    #[test]
+    fn test_parse_install_section() {
+        let toml = r#"
+[project]
+name = "tool"
+
+[install]
+prefix = "dist"
+gpu = true
+rpkgs = ["vendor/math.rpkg"]
+"#;
+        let RayzorManifest::SingleProject(p) = parse_manifest(toml).unwrap() else {
+            panic!("Expected SingleProject");
+        };
+        let install = p.install.unwrap();
+        assert_eq!(install.prefix.as_deref(), Some("dist"));
+        assert_eq!(install.artifact, None);
+        assert_eq!(install.gpu, Some(true));
+        assert_eq!(install.rpkgs, vec!["vendor/math.rpkg"]);
+
+        assert!(parse_manifest("[project]\nname = \"x\"\n[install]\ndest = \"d\"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_jit_section() {
+        let toml = r#"
+[project]
+name = "api"
+
+[jit]
+preset = "server"
+start-tier = "interpreted"
+hot-threshold = 100
+background-threads = 0
+llvm = false
+bailout-iterations = 500
+
+[jit.functions]
+"Main.render" = "optimized"
+"#;
+        let manifest = parse_manifest(toml).unwrap();
+        let RayzorManifest::SingleProject(p) = manifest else {
+            panic!("Expected SingleProject");
+        };
+        let jit = p.jit.unwrap();
+        assert_eq!(jit.preset().unwrap(), Some(TierPreset::Server));
+
+        let mut config = TieredConfig::from_preset(TierPreset::Server);
+        config.start_interpreted = false;
+        jit.apply(&mut config).unwrap();
+        assert!(config.start_interpreted);
+        assert_eq!(config.profile_config.hot_threshold, 100);
+        // Untouched settings keep the preset's values
+        assert_eq!(config.profile_config.warm_threshold, 50);
+        assert_eq!(config.profile_config.blazing_threshold, u64::MAX);
+        assert!(!config.enable_background_optimization);
+        assert_eq!(config.bailout_strategy, BailoutStrategy::Custom(500));
+        assert_eq!(
+            config.function_tiers.get("Main.render"),
+            Some(&OptimizationTier::Optimized)
+        );
+    }
+
+    #[test]
+    fn test_parse_optimization_section() {
+        let toml = r#"
+[project]
+name = "gen"
+
+[optimization.size-limits]
+gvn = 50000
+inlining = 2000
+"#;
+        let RayzorManifest::SingleProject(p) = parse_manifest(toml).unwrap() else {
+            panic!("Expected SingleProject");
+        };
+        let limits = p.optimization.unwrap().size_limits().unwrap();
+        assert_eq!(limits.get("gvn"), Some(50000));
+        assert_eq!(limits.get("inlining"), Some(2000));
+        // Untouched passes keep their default limit
+        assert_eq!(limits.get("licm"), PassSizeLimits::defaults().get("licm"));
+
+        let typo = parse_manifest("[project]\nname = \"x\"\n[optimization.size-limits]\ngnv = 1\n");
+        let RayzorManifest::SingleProject(p) = typo.unwrap() else {
+            panic!("Expected SingleProject");
+        };
+        assert!(p.optimization.unwrap().size_limits().is_err());
+    }
+
+    #[test]
+    fn test_parse_remote_cache() {
+        let toml = r#"
+[project]
+name = "hello"
+
+[cache]
+dir = ".rayzor/cache"
+
+[cache.remote]
+url = "http://cache.internal:9090/rayzor"
+token-env = "RAYZOR_CACHE_TOKEN"
+upload = true
+"#;
+        let RayzorManifest::SingleProject(p) = parse_manifest(toml).unwrap() else {
+            panic!("Expected SingleProject");
+        };
+        let remote = p.cache.unwrap().remote.unwrap();
+        assert_eq!(remote.url, "http://cache.internal:9090/rayzor");
+        assert_eq!(remote.token_env.as_deref(), Some("RAYZOR_CACHE_TOKEN"));
+        assert_eq!(remote.upload, Some(true));
+        assert_eq!(remote.timeout_secs, None);
+    }
+
+    #[test]
+    fn test_invalid_jit_settings() {
+        let jit = JitConfig {
+            preset: Some("turbo".to_string()),
+            ..Default::default()
+        };
+        assert!(jit.preset().unwrap_err().contains("turbo"));
+
+        let mut functions = HashMap::new();
+        functions.insert("Main.main".to_string(), "ludicrous".to_string());
+        let jit = JitConfig {
+            functions,
+            ..Default::default()
+        };
+        let mut config = TieredConfig::default();
+        assert!(jit.apply(&mut config).unwrap_err().contains("ludicrous"));
+
+        // Unknown keys are rejected rather than silently ignored
+        assert!(parse_manifest("[project]\nname = \"x\"\n[jit]\nhot = 1\n").is_err());
+    }
 }
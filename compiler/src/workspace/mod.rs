@@ -4,14 +4,16 @@
 //! multi-project workspaces, shared BLADE caches, and backwards
 //! compatibility with `.hxml` build files.
 
+pub mod build_plan;
 pub mod init;
 pub mod manifest;
 
 use std::path::{Path, PathBuf};
 
 pub use manifest::{
-    BuildConfig, BundleConfig as ManifestBundleConfig, CacheConfig, ProjectManifest,
-    RayzorManifest, WorkspaceCacheConfig, WorkspaceManifest,
+    BuildConfig, BundleConfig as ManifestBundleConfig, CacheConfig, InstallConfig,
+    OptimizationConfig, ProjectManifest, RayzorManifest, RemoteCacheConfig, StatsConfig,
+    WorkspaceCacheConfig, WorkspaceManifest,
 };
 
 /// A resolved workspace (may contain multiple projects).
@@ -157,10 +159,12 @@ pub fn load_workspace(dir: &Path) -> Result<Workspace, String> {
                 .map(|wc| CacheConfig {
                     dir: wc.dir.clone(),
                     enabled: Some(true),
+                    remote: None,
                 })
                 .unwrap_or_else(|| CacheConfig {
                     dir: Some(".rayzor/cache".to_string()),
                     enabled: Some(true),
+                    remote: None,
                 });
 
             Ok(Workspace {
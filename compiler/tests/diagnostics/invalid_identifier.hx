@@ -0,0 +1,5 @@
+class Main {
+    static function main() {
+        var 1st = 1;
+    }
+}
@@ -0,0 +1,6 @@
+class Main {
+    static function main() {
+        var x = 1;
+        trace(y + x);
+    }
+}
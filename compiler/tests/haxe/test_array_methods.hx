@@ -19,5 +19,42 @@ class Main {
         trace(unsorted[0]); // 1
         trace(unsorted[1]); // 2
         trace(unsorted[4]); // 5
+
+        // Float callbacks: parameters are typed from the element type
+        var floats = [2.5, -1.0, 9.75];
+        var halves = floats.map(function(x) return x / 2);
+        trace(halves[0]); // 1.25
+        var positive = floats.filter(x -> x > 0);
+        trace(positive.length); // 2
+        floats.sort(function(a, b) return a < b ? -1 : (a > b ? 1 : 0));
+        trace(floats[0]); // -1
+
+        // map can change the element type
+        var ratios = arr.map(function(i) return i / 4);
+        trace(ratios[1]); // 0.5
+
+        // join/indexOf/contains respect the element type
+        trace(arr.join(",")); // 1,2,3,4,5
+        trace(floats.join(" ")); // -1 2.5 9.75
+        trace(floats.indexOf(9.75)); // 2
+        var words = ["a", "b", "c"];
+        trace(words.contains("b" + "")); // true
+        trace(words.indexOf("z")); // -1
+
+        // slice/concat/reverse
+        trace(arr.slice(-2).join(",")); // 4,5
+        var both = arr.concat([6, 7]);
+        both.reverse();
+        trace(both.join("")); // 7654321
+
+        // sort is stable: equal tens digits keep their original order
+        var tens = [31, 12, 22, 11, 32, 21];
+        tens.sort(function(a, b) return (a - a % 10) - (b - b % 10));
+        trace(tens.join(",")); // 12,11,22,21,31,32
+        var scattered = [];
+        for (i in 0...100) scattered.push((i * 37) % 100);
+        haxe.ds.ArraySort.sort(scattered, function(a, b) return a - b);
+        trace(scattered[0]); // 0
+        trace(scattered[99]); // 99
     }
 }
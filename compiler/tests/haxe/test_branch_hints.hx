@@ -0,0 +1,45 @@
+class Main {
+    @:cold
+    static function fail(code:Int):Int {
+        trace("error " + code); // error -3
+        return -1;
+    }
+
+    // Negative inputs take the cold path
+    static function check(x:Int):Int {
+        if (x < 0) {
+            return fail(x);
+        }
+        return x * 2;
+    }
+
+    static function clamp(x:Int, max:Int):Int {
+        if (@:unlikely (x > max)) {
+            return max;
+        }
+        return x;
+    }
+
+    static function parity(x:Int):String {
+        if (@:likely (x % 2 == 0)) {
+            return "even";
+        } else {
+            return "odd";
+        }
+    }
+
+    static function main() {
+        var sum = 0;
+        for (i in 0...1000) {
+            sum += check(i);
+        }
+        trace(sum); // 999000
+        trace(check(-3)); // -1
+
+        trace(clamp(5, 10)); // 5
+        trace(clamp(50, 10)); // 10
+
+        trace(parity(4)); // even
+        trace(parity(7)); // odd
+    }
+}
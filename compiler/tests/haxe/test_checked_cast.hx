@@ -0,0 +1,63 @@
+interface Named {
+    function name():String;
+}
+
+class Animal {
+    public function new() {}
+}
+
+class Dog extends Animal implements Named {
+    public function new() {
+        super();
+    }
+
+    public function name():String {
+        return "dog";
+    }
+}
+
+class Puppy extends Dog {
+    public function new() {
+        super();
+    }
+}
+
+class Cat extends Animal {
+    public function new() {
+        super();
+    }
+}
+
+class Main {
+    static function main() {
+        var a:Animal = new Puppy();
+
+        // Downcasts to the class and to an intermediate ancestor
+        var p:Puppy = cast(a, Puppy);
+        trace(p != null); // true
+        var d:Dog = cast(a, Dog);
+        trace(d.name()); // dog
+
+        // Instance checks walk the class hierarchy and implemented interfaces
+        trace(Std.isOfType(a, Dog)); // true
+        trace(Std.isOfType(a, Cat)); // false
+        trace(Std.isOfType(a, Named)); // true
+        trace(Std.isOfType(new Cat(), Named)); // false
+
+        // Checks through an interface look at the underlying object
+        var n:Named = new Puppy();
+        trace(Std.isOfType(n, Puppy)); // true
+        trace(Std.isOfType(n, Cat)); // false
+
+        // Std.downcast returns null on mismatch
+        trace(Std.downcast(a, Cat) == null); // true
+
+        // A failed checked cast throws a catchable error
+        try {
+            var c:Cat = cast(a, Cat);
+            trace(c != null);
+        } catch (e:String) {
+            trace(e); // Cannot cast Puppy to Cat
+        }
+    }
+}
@@ -0,0 +1,75 @@
+class Counter {
+    public var count:Int;
+
+    public function new() {
+        count = 0;
+    }
+
+    public function inc():Void {
+        count++;
+    }
+
+    public function add(n:Int):Int {
+        count += n;
+        return count;
+    }
+}
+
+class DoubleCounter extends Counter {
+    public function new() {
+        super();
+    }
+
+    override public function inc():Void {
+        count += 2;
+    }
+}
+
+class Main {
+    static function helper():Int {
+        return 1;
+    }
+
+    static function main() {
+        var c = new Counter();
+
+        // Taking the same bound method twice yields the same function
+        var f = c.inc;
+        var g = c.inc;
+        trace(f == g); // true
+        trace(Reflect.compareMethods(c.inc, c.inc)); // true
+
+        // Different receiver or different method: not the same function
+        var other = new Counter();
+        trace(Reflect.compareMethods(c.inc, other.inc)); // false
+        trace(Reflect.compareMethods(c.inc, c.add)); // false
+
+        // Bound methods call through to their receiver
+        f();
+        g();
+        trace(c.count); // 2
+        var add = c.add;
+        trace(add(5)); // 7
+
+        // Overrides are honoured when bound through a base-class reference
+        var base:Counter = new DoubleCounter();
+        var h = base.inc;
+        h();
+        trace(base.count); // 2
+
+        // Static functions compare by identity
+        var s1 = helper;
+        var s2 = helper;
+        trace(s1 == s2); // true
+
+        // A lambda equals itself, but not another evaluation of the same expression
+        var l1 = function() {};
+        var l2 = l1;
+        trace(l1 == l2); // true
+        trace(Reflect.compareMethods(l1, function() {})); // false
+
+        // Dynamic-typed function values compare the underlying closure
+        var d:Dynamic = c.inc;
+        trace(Reflect.compareMethods(d, c.inc)); // true
+    }
+}
@@ -0,0 +1,22 @@
+import rayzor.text.Collator;
+
+class Main {
+    static function main() {
+        // Unicode case conversion
+        trace("straße".toUpperCase()); // STRASSE
+        trace("ÉCOLE Ω".toLowerCase()); // école ω
+
+        // Collation: accents and case are secondary to the base letters
+        trace(Collator.compare("apple", "Zebra") < 0); // true
+        trace(Collator.compare("école", "zoo") < 0); // true
+        trace(Collator.compare("ecole", "école") < 0); // true
+        trace(Collator.compare("a", "A") < 0); // true
+        trace(Collator.compare("same", "same")); // 0
+
+        trace(Collator.compareIgnoreCase("STRASSE", "Straße")); // 0
+        trace(Collator.compareIgnoreCase("abc", "ABD") < 0); // true
+
+        trace(Collator.foldCase("Straße")); // strasse
+        trace(Collator.foldCase("HeLLo") == Collator.foldCase("hello")); // true
+    }
+}
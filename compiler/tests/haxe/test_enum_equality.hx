@@ -0,0 +1,52 @@
+import haxe.ds.EnumValueMap;
+
+enum Color {
+    Red;
+    Green;
+    Rgb(r:Int, g:Int, b:Int);
+}
+
+enum Shape {
+    Circle(radius:Float, fill:Color);
+    Label(text:String);
+    Empty;
+}
+
+class Main {
+    static function main() {
+        // Same constructor and parameters: equal, even when built separately
+        trace(Rgb(1, 2, 3) == Rgb(1, 2, 3)); // true
+        trace(Rgb(1, 2, 3) == Rgb(1, 2, 4)); // false
+        trace(Rgb(1, 2, 3) != Rgb(3, 2, 1)); // true
+        trace(Red == Red); // true
+        trace(Red == Green); // false
+
+        // Enum parameters compare recursively, strings by content
+        var a = Circle(1.5, Rgb(0, 0, 255));
+        var b = Circle(1.5, Rgb(0, 0, 255));
+        trace(a == b); // true
+        trace(a == Circle(1.5, Red)); // false
+        var name = "hel" + "lo";
+        trace(Label("hello") == Label(name)); // true
+        trace(Empty == Empty); // true
+        trace(a == Empty); // false
+
+        // Type.enumEq
+        trace(Type.enumEq(Rgb(4, 5, 6), Rgb(4, 5, 6))); // true
+        trace(Type.enumEq(Label("x"), Label("y"))); // false
+
+        // Structurally equal keys address the same map entry
+        var counts = new EnumValueMap<Shape, Int>();
+        counts.set(Circle(2.0, Green), 1);
+        counts.set(Label("hi"), 2);
+        counts.set(Circle(2.0, Green), 3);
+        trace(counts.get(Circle(2.0, Green))); // 3
+        trace(counts.exists(Label("h" + "i"))); // true
+        trace(counts.exists(Circle(2.0, Red))); // false
+        trace(counts.remove(Label("hi"))); // true
+        trace(counts.exists(Label("hi"))); // false
+
+        var names = [Rgb(255, 0, 0) => "red", Rgb(0, 255, 0) => "green"];
+        trace(names.get(Rgb(0, 255, 0))); // green
+    }
+}
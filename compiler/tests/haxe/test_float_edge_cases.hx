@@ -0,0 +1,32 @@
+class Main {
+    static function main() {
+        var zero = 0.0;
+        var nan = zero / zero;
+        var inf = 1.0 / zero;
+
+        // Std.int truncates toward zero, NaN is 0, out-of-range saturates
+        trace(Std.int(2.7)); // 2
+        trace(Std.int(-2.7)); // -2
+        trace(Std.int(-0.0)); // 0
+        trace(Std.int(nan)); // 0
+        trace(Std.int(inf)); // 2147483647
+        trace(Std.int(-inf)); // -2147483648
+        trace(Std.int(3e9)); // 2147483647
+        trace(Std.int(-3e9)); // -2147483648
+
+        // Math.round rounds ties up
+        trace(Math.round(2.5)); // 3
+        trace(Math.round(-2.5)); // -2
+        trace(Math.round(-0.5)); // 0
+        trace(Math.round(-1.6)); // -2
+
+        // % on floats keeps the sign of the dividend
+        trace(5.5 % 2); // 1.5
+        trace(-5.5 % 2); // -1.5
+        trace(5.5 % -2); // 1.5
+        trace(1 % inf); // 1
+        trace(Math.isNaN(1 % zero)); // true
+        trace(Math.isNaN(inf % 1)); // true
+        trace(1e20 % 3); // 1
+    }
+}
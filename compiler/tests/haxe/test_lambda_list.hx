@@ -0,0 +1,46 @@
+using Lambda;
+
+class Main {
+    static function main() {
+        // Lambda static calls over arrays
+        var arr = [1, 2, 3, 4];
+        trace(Lambda.count(arr)); // 4
+        trace(Lambda.exists(arr, x -> x > 3)); // true
+        trace(Lambda.fold(arr, function(x, acc) return x + acc, 0)); // 10
+        trace(Lambda.indexOf(arr, 3)); // 2
+        trace(Lambda.find(arr, x -> x % 2 == 0)); // 2
+        trace(Lambda.filter(arr, x -> x > 2).length); // 2
+
+        // Static extension form
+        trace(arr.has(2)); // true
+        trace(arr.foreach(x -> x > 0)); // true
+        trace(arr.count(x -> x % 2 == 1)); // 2
+
+        // Native List
+        var list = new List<Int>();
+        list.add(1);
+        list.add(2);
+        list.push(0);
+        trace(list.length); // 3
+        trace(list.first()); // 0
+        trace(list.last()); // 2
+        trace(list.join("-")); // 0-1-2
+
+        var total = 0;
+        for (x in list) total += x;
+        trace(total); // 3
+
+        trace(list.remove(1)); // true
+        trace(list.toString()); // {0, 2}
+        trace(list.pop()); // 0
+        trace(list.isEmpty()); // false
+        list.clear();
+        trace(list.isEmpty()); // true
+
+        // Lists of strings keep their element type
+        var names = new List<String>();
+        names.add("x");
+        names.add("y");
+        trace(names.join(", ")); // x, y
+    }
+}
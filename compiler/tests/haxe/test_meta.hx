@@ -0,0 +1,58 @@
+// haxe.rtti.Meta: user metadata with constant arguments is kept through to
+// the runtime and queried per class/enum
+import haxe.rtti.Meta;
+
+@author("Ada") @version(2, 1.5) @:serialize
+class Route {
+    @path("/users") @methods(["GET", "POST"])
+    public var users:Int = 0;
+
+    @:skip
+    public var cache:Int = 0;
+
+    public var plain:Int = 0;
+
+    @handler({name: "index", weight: -1})
+    public function index():Void {}
+
+    @deprecated("use create")
+    public static function make():Route {
+        return new Route();
+    }
+
+    public function new() {}
+}
+
+enum Color {
+    @hex("ff0000") Red;
+    Green;
+}
+
+class Plain {
+    public function new() {}
+}
+
+class Main {
+    static function main() {
+        var type = Meta.getType(Route);
+        trace(Reflect.fields(type)); // [author,serialize,version]
+        trace(Reflect.field(type, "author")); // [Ada]
+        trace(Reflect.field(type, "version")); // [2,1.5]
+        trace(Reflect.field(type, "serialize") == null); // true
+
+        var fields = Meta.getFields(Route);
+        trace(Reflect.fields(fields)); // [cache,index,users]
+        trace(Reflect.field(Reflect.field(fields, "users"), "path")); // [/users]
+        trace(Reflect.field(Reflect.field(fields, "users"), "methods")); // [[GET,POST]]
+        var handler:Array<Dynamic> = Reflect.field(Reflect.field(fields, "index"), "handler");
+        trace(Reflect.field(handler[0], "weight")); // -1
+
+        var statics = Meta.getStatics(Route);
+        trace(Reflect.fields(statics)); // [make]
+        trace(Reflect.field(Reflect.field(statics, "make"), "deprecated")); // [use create]
+
+        trace(Reflect.field(Reflect.field(Meta.getFields(Color), "Red"), "hex")); // [ff0000]
+
+        trace(Reflect.fields(Meta.getType(Plain)).length); // 0
+    }
+}
@@ -0,0 +1,45 @@
+// Haxe 4 module-level fields: declared outside any type, visible to every
+// type in the module (and to other modules via `import pack.Module`)
+
+final GREETING = "hello";
+final LIMIT:Int = 3;
+var counter = 10;
+var names = ["a", "b"];
+
+function square(x:Int):Int {
+    return x * x;
+}
+
+function bump():Int {
+    counter += 1;
+    return counter;
+}
+
+class Main {
+    static var total = 100;
+
+    static function main() {
+        trace(GREETING); // hello
+        trace(LIMIT); // 3
+        trace(square(7)); // 49
+
+        // Module-level vars are mutable globals shared by every caller
+        trace(counter); // 10
+        trace(bump()); // 11
+        counter = 20;
+        trace(bump()); // 21
+        counter++;
+        trace(counter); // 22
+
+        // Non-constant initializers run in __init__ before main
+        trace(names.length); // 2
+        names.push("c");
+        trace(names.length); // 3
+
+        // Mutable class statics keep their assigned values too
+        total += 5;
+        trace(total); // 105
+        Main.total = 1;
+        trace(Main.total); // 1
+    }
+}
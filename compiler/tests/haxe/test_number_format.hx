@@ -0,0 +1,33 @@
+using StringTools;
+
+class Main {
+    static function main() {
+        // Shortest round-trip Float printing
+        trace(0.1 + 0.2); // 0.30000000000000004
+        trace(1.0); // 1
+        trace(1 / 3); // 0.3333333333333333
+        trace("x=" + 2.5); // x=2.5
+
+        // Scientific notation outside [1e-7, 1e21)
+        trace(1e21); // 1e+21
+        trace(1.5e-7); // 1.5e-7
+        trace(0.000001); // 0.000001
+        trace(Std.string(123456789.0 * 1e15)); // 1.23456789e+23
+
+        // Special values
+        trace(Math.NaN); // NaN
+        trace(Math.POSITIVE_INFINITY); // Infinity
+        trace(Math.NEGATIVE_INFINITY); // -Infinity
+
+        // StringTools.hex
+        trace(StringTools.hex(255)); // FF
+        trace(StringTools.hex(255, 4)); // 00FF
+        trace(StringTools.hex(-1)); // FFFFFFFF
+
+        // StringTools.lpad / rpad
+        trace("5".lpad("0", 3)); // 005
+        trace("ab".rpad(".", 5)); // ab...
+        trace("abc".lpad("0", 2)); // abc
+        trace("x".lpad("ab", 4)); // ababx
+    }
+}
@@ -0,0 +1,47 @@
+// Run with and without --trace-pos: the PosInfos checks print the same
+// either way, and with it every trace line starts with its own call site,
+// including the one nested in another trace's argument.
+
+class Logger {
+    public function new() {}
+
+    public function where(?pos:haxe.PosInfos):String {
+        return pos.className + "." + pos.methodName + ":" + pos.lineNumber;
+    }
+}
+
+class Main {
+    static function check(expectedLine:Int, ?pos:haxe.PosInfos):Bool {
+        return StringTools.endsWith(pos.fileName, "test_pos_infos.hx")
+            && pos.lineNumber == expectedLine
+            && pos.className == "Main"
+            && pos.methodName == "main";
+    }
+
+    static function line(?pos:haxe.PosInfos):Int {
+        return pos.lineNumber;
+    }
+
+    static function describe():String {
+        trace("inner");  // inner (Main.describe:26 with --trace-pos)
+        return "outer";
+    }
+
+    static function main() {
+        // Test 1: the call site fills in every field
+        trace(check(32));  // true
+
+        // Test 2: each call gets its own line
+        trace(line());  // 35
+        trace(line());  // 36
+
+        // Test 3: an explicit argument is passed through
+        trace(check(0, {fileName: "test_pos_infos.hx", lineNumber: 0, className: "Main", methodName: "main"}));  // true
+
+        // Test 4: instance methods get the caller's class and method
+        trace(new Logger().where());  // Main.main:42
+
+        // Test 5: a trace nested in the argument keeps its own position
+        trace(describe());  // outer (Main.main:45 with --trace-pos)
+    }
+}
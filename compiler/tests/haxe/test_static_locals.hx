@@ -0,0 +1,49 @@
+// Haxe 4.3 local static variables: `static var` inside a function body is
+// initialized once and keeps its value across calls
+
+class Main {
+    static function next():Int {
+        static var count = 0;
+        count++;
+        return count;
+    }
+
+    static function scaled():Float {
+        static var factor:Float = 1.5;
+        factor *= 2;
+        return factor;
+    }
+
+    static function once():String {
+        static var calls = 0;
+        static final label = "init-" + Std.string(calls);
+        calls += 1;
+        return label + "/" + calls;
+    }
+
+    static function hot(n:Int):Int {
+        static var total = 0;
+        total += n;
+        return total;
+    }
+
+    static function main() {
+        trace(next()); // 1
+        trace(next()); // 2
+        trace(next()); // 3
+
+        trace(scaled()); // 3
+        trace(scaled()); // 6
+
+        trace(once()); // init-0/1
+        trace(once()); // init-0/2
+
+        // Enough calls to trigger a tier upgrade; the running total must
+        // survive re-compilation of `hot`
+        var last = 0;
+        for (i in 0...2000) {
+            last = hot(1);
+        }
+        trace(last); // 2000
+    }
+}
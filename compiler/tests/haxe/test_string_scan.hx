@@ -0,0 +1,54 @@
+class Main {
+    static function main() {
+        // charCodeAt is lowered to an inline bounds-checked byte load
+        var s = "{\"a\":1}";
+        trace(s.charCodeAt(0)); // 123
+        trace(s.charCodeAt(1)); // 34
+        trace(s.charCodeAt(s.length - 1)); // 125
+
+        var sum = 0;
+        var i = 0;
+        while (i < s.length) {
+            sum += s.charCodeAt(i);
+            i++;
+        }
+        trace(sum); // 520
+
+        // Scanning past the end stops the loop instead of reading out of bounds
+        var digits = "12345";
+        var pos = 0;
+        while (true) {
+            var c = digits.charCodeAt(pos);
+            if (c >= 48 && c <= 57) {
+                pos++;
+            } else {
+                break;
+            }
+        }
+        trace(pos); // 5
+
+        // indexOf / lastIndexOf with single-byte and longer needles
+        var text = "key: \"value\", other: \"more\"";
+        trace(text.indexOf("\"")); // 5
+        trace(text.indexOf("\"", 6)); // 11
+        trace(text.indexOf("other")); // 14
+        trace(text.indexOf("missing")); // -1
+        trace(text.lastIndexOf("\"")); // 26
+        trace(text.lastIndexOf("\"", 20)); // 11
+
+        // Needle far into a long haystack
+        var parts = new Array<String>();
+        for (n in 0...200) {
+            parts.push("xxxxx");
+        }
+        var long = parts.join("") + "needle";
+        trace(long.indexOf("needle")); // 1000
+        trace(long.indexOf("e")); // 1001
+
+        // split on single-byte and multi-byte delimiters
+        trace("a,b,,c".split(",").join("|")); // a|b||c
+        trace("one::two::three".split("::").join("|")); // one|two|three
+        trace("no delimiter".split(";").join("|")); // no delimiter
+        trace("line1\nline2\n".split("\n").length); // 3
+    }
+}
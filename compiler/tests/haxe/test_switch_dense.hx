@@ -0,0 +1,56 @@
+class Main {
+    // Dense opcodes: lowered to a jump table
+    static function step(op:Int, acc:Int):Int {
+        switch (op) {
+            case 0:
+                return acc;
+            case 1:
+                return acc + 1;
+            case 2:
+                return acc - 1;
+            case 3:
+                return acc * 2;
+            case 4 | 5:
+                return acc * acc;
+            case 7:
+                return -acc;
+            default:
+                return 0;
+        }
+    }
+
+    // Sparse values: lowered to a binary search
+    static function name(code:Int):String {
+        switch (code) {
+            case -1:
+                return "eof";
+            case 10:
+                return "newline";
+            case 1000:
+                return "big";
+            case 65536:
+                return "huge";
+            default:
+                return "other";
+        }
+    }
+
+    static function main() {
+        var acc = step(1, 0);
+        trace(acc); // 1
+        acc = step(3, step(1, acc));
+        trace(acc); // 4
+        acc = step(4, acc);
+        trace(acc); // 16
+        acc = step(7, step(2, acc));
+        trace(acc); // -15
+        trace(step(0, acc)); // -15
+        trace(step(6, acc)); // 0
+        trace(step(-3, acc)); // 0
+
+        trace(name(-1)); // eof
+        trace(name(10)); // newline
+        trace(name(65536)); // huge
+        trace(name(11)); // other
+    }
+}
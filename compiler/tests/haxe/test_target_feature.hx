@@ -0,0 +1,34 @@
+class Main {
+    // Baseline plus AVX2+FMA on x86-64 and NEON on AArch64; every version
+    // must compute the same result
+    @:targetFeature("avx2", "fma")
+    @:targetFeature("neon")
+    static function dot(a:Array<Float>, b:Array<Float>):Float {
+        var sum = 0.0;
+        for (i in 0...a.length) {
+            sum += a[i] * b[i];
+        }
+        return sum;
+    }
+
+    @:targetFeature("popcnt")
+    static function popcount(x:Int):Int {
+        var count = 0;
+        while (x != 0) {
+            x &= x - 1;
+            count++;
+        }
+        return count;
+    }
+
+    static function main() {
+        var a = [1.0, 2.0, 3.0, 4.0];
+        var b = [0.5, 0.25, 2.0, 1.0];
+        trace(dot(a, b)); // 11.5
+        trace(dot([], [])); // 0
+
+        trace(popcount(0)); // 0
+        trace(popcount(255)); // 8
+        trace(popcount(-1)); // 32
+    }
+}
@@ -0,0 +1,85 @@
+interface Shape {
+    function area():Float;
+}
+
+class Point {
+    public var x:Int;
+    public var y:Int;
+
+    public function new(x:Int, y:Int) {
+        this.x = x;
+        this.y = y;
+    }
+
+    public function toString():String {
+        return "(" + x + ", " + y + ")";
+    }
+}
+
+class Point3 extends Point {
+    public var z:Int;
+
+    public function new(x:Int, y:Int, z:Int) {
+        super(x, y);
+        this.z = z;
+    }
+
+    override public function toString():String {
+        return "(" + x + ", " + y + ", " + z + ")";
+    }
+}
+
+class Labeled extends Point {
+    public function new(x:Int, y:Int) {
+        super(x, y);
+    }
+}
+
+class Square implements Shape {
+    var side:Float;
+
+    public function new(side:Float) {
+        this.side = side;
+    }
+
+    public function area():Float {
+        return side * side;
+    }
+
+    public function toString():String {
+        return "Square(" + side + ")";
+    }
+}
+
+class Plain {
+    public function new() {}
+}
+
+class Main {
+    static function main() {
+        var p = new Point(1, 2);
+        trace(p); // (1, 2)
+        trace("p=" + p); // p=(1, 2)
+
+        // Overrides dispatch on the runtime class
+        var q:Point = new Point3(1, 2, 3);
+        trace(q); // (1, 2, 3)
+        trace("q=" + q); // q=(1, 2, 3)
+
+        // Inherited toString()
+        trace(new Labeled(4, 5)); // (4, 5)
+
+        // Through an interface
+        var s:Shape = new Square(2.5);
+        trace(s); // Square(2.5)
+        trace("s=" + s); // s=Square(2.5)
+
+        // Through Dynamic
+        var d:Dynamic = p;
+        trace(Std.string(d)); // (1, 2)
+
+        // No toString(): class name and address
+        var plain = "" + new Plain();
+        trace(StringTools.startsWith(plain, "Plain@")); // true
+    }
+}
@@ -1,3 +1,25 @@
+interface Coded {
+    function code():Int;
+}
+
+class Failure implements Coded {
+    var status:Int;
+
+    public function new(status:Int) {
+        this.status = status;
+    }
+
+    public function code():Int {
+        return status;
+    }
+}
+
+class NotFound extends Failure {
+    public function new() {
+        super(404);
+    }
+}
+
 class Main {
     static function main() {
         // Test 1: Basic throw and catch
@@ -17,5 +39,61 @@ class Main {
             trace("should not print");
         }
         trace("ok");  // ok
+
+        // Test 3: A subclass is caught by its parent's clause
+        try {
+            throw new NotFound();
+        } catch (e:String) {
+            trace("should not print");
+        } catch (e:Failure) {
+            trace(e.code());  // 404
+        }
+
+        // Test 4: The object's class decides, not the static type thrown
+        var failure:Failure = new NotFound();
+        try {
+            throw failure;
+        } catch (e:NotFound) {
+            trace("not found");  // not found
+        }
+
+        // Test 5: An interface-typed value is caught by its class
+        var coded:Coded = new Failure(418);
+        try {
+            throw coded;
+        } catch (e:Failure) {
+            trace(e.code());  // 418
+        }
+
+        // Test 6: An unmatched catch propagates to the outer try
+        try {
+            try {
+                throw "oops";
+            } catch (e:Int) {
+                trace("should not print");
+            }
+        } catch (e:String) {
+            trace(e);  // oops
+        }
+
+        // Test 7: Any catches everything
+        try {
+            throw new Failure(500);
+        } catch (e:Any) {
+            trace("any");  // any
+        }
+
+        // Test 8: finally runs before the exception propagates
+        try {
+            try {
+                throw new Failure(503);
+            } catch (e:String) {
+                trace("should not print");
+            } finally {
+                trace("finally");  // finally
+            }
+        } catch (e:Failure) {
+            trace(e.code());  // 503
+        }
     }
 }
@@ -0,0 +1,64 @@
+import haxe.ds.Vector;
+
+class Point {
+    public var x:Int;
+
+    public function new(x:Int) {
+        this.x = x;
+    }
+}
+
+class Main {
+    static function main() {
+        // Vector<Int>: unboxed, fixed length, zero-filled
+        var v = new Vector<Int>(4);
+        trace(v.length); // 4
+        trace(v[0]); // 0
+        v[1] = 7;
+        v[2] += 5;
+        trace(v[1] + v[2]); // 12
+        v[9] = 1; // out of range: ignored, never grows
+        trace(v.length); // 4
+        trace(v[9]); // 0
+
+        v.fill(3);
+        var total = 0;
+        for (i in 0...v.length) total += v[i];
+        trace(total); // 12
+
+        var c = v.copy();
+        c[0] = 100;
+        trace(v[0]); // 3
+        Vector.blit(c, 0, v, 1, 2);
+        trace(v.toArray()); // [3, 100, 3, 3]
+
+        // Vector<Float>
+        var f = new Vector<Float>(3);
+        f[0] = 1.5;
+        f.set(2, 2.25);
+        trace(f[0] + f[1] + f[2]); // 3.75
+
+        // Other element types use type-erased slots
+        var s = new Vector<String>(2);
+        s[0] = "a";
+        s[1] = "b";
+        trace(s[0] + s[1]); // ab
+
+        var flags = new Vector<Bool>(2);
+        flags[1] = true;
+        trace(flags[0]); // false
+        trace(flags[1]); // true
+
+        var points = new Vector<Point>(2);
+        points[0] = new Point(4);
+        trace(points[0].x); // 4
+        trace(points[1] == null); // true
+
+        // fromArrayCopy keeps the array's element type
+        var w = Vector.fromArrayCopy([5, 6, 7]);
+        trace(w.length); // 3
+        trace(w[2]); // 7
+        var fw = Vector.fromArrayCopy([0.5, 1.5]);
+        trace(fw[1]); // 1.5
+    }
+}
@@ -0,0 +1,34 @@
+class Point {
+    public var x:Int;
+
+    public function new(x:Int) {
+        this.x = x;
+    }
+}
+
+class Main {
+    static function main() {
+        var p1 = new Point(1);
+        var p2 = new Point(2);
+
+        var weak = new haxe.ds.WeakMap<Point, Int>();
+        weak.set(p1, 10);
+        weak.set(p2, 20);
+        trace(weak.get(p1)); // 10
+        trace(weak.exists(p2)); // true
+
+        // Keys are compared by identity
+        trace(weak.exists(new Point(1))); // false
+
+        trace(weak.remove(p2)); // true
+        trace(weak.exists(p2)); // false
+
+        var sum = 0;
+        for (key => value in weak) {
+            sum += key.x + value;
+        }
+        trace(sum); // 11
+
+        trace("done");
+    }
+}
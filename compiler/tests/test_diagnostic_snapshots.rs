@@ -0,0 +1,21 @@
+//! Golden snapshot tests for formatted diagnostics.
+//!
+//! Each `tests/diagnostics/*.hx` fixture is compiled and its diagnostics are
+//! compared against the neighbouring `.stderr` file. After an intended
+//! formatter change, update the snapshots with:
+//!
+//!   RAYZOR_BLESS=1 cargo test -p compiler --test test_diagnostic_snapshots
+
+use std::path::PathBuf;
+
+use compiler::tools::diagnostic_snapshots::{run_snapshots, SnapshotConfig};
+
+#[test]
+fn test_diagnostic_snapshots() {
+    let fixtures_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/diagnostics");
+    let config = SnapshotConfig::new(fixtures_dir).bless_from_env();
+
+    let report = run_snapshots(&config).expect("snapshot run failed");
+    assert!(!report.results.is_empty(), "no diagnostic fixtures found");
+    assert!(report.is_success(), "\n{}", report.render());
+}
@@ -0,0 +1,277 @@
+//! Machine-readable diagnostics
+//!
+//! [`JsonFormatter`] writes each diagnostic as one JSON object on its own
+//! line, for editors and CI tools (`--format json`):
+//!
+//! ```json
+//! {"severity":"error","code":"E0002","message":"missing ';' at the end of statement",
+//!  "spans":[{"file":"Main.hx","line_start":3,"column_start":14,"line_end":3,
+//!  "column_end":15,"byte_start":42,"byte_end":43,"is_primary":true,
+//!  "label":"expected ';' here"}],
+//!  "suggestions":[{"message":"add semicolon","replacement":";",
+//!  "applicability":"machine-applicable","span":{...}}],
+//!  "notes":[],"help":["statement must end with a semicolon"],"rendered":"..."}
+//! ```
+//!
+//! Lines and columns are 1-based, byte offsets 0-based and end-exclusive.
+//! `file` is null for spans outside the source map. `rendered` is the plain
+//! text [`ErrorFormatter`] would print.
+
+use crate::{
+    Applicability, Diagnostic, DiagnosticSeverity, Diagnostics, ErrorFormatter, LabelStyle,
+    SourceMap, SourceSpan,
+};
+
+/// Formatter writing diagnostics as JSON Lines
+#[derive(Debug, Default)]
+pub struct JsonFormatter;
+
+impl JsonFormatter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// One JSON object per diagnostic, each followed by a newline
+    pub fn format_diagnostics(&self, diagnostics: &Diagnostics, source_map: &SourceMap) -> String {
+        diagnostics
+            .diagnostics
+            .iter()
+            .map(|diagnostic| self.format_diagnostic(diagnostic, source_map) + "\n")
+            .collect()
+    }
+
+    /// `diagnostic` as a single-line JSON object
+    pub fn format_diagnostic(&self, diagnostic: &Diagnostic, source_map: &SourceMap) -> String {
+        let mut out = String::from("{");
+        field(&mut out, "severity", &diagnostic.severity.to_string());
+        out.push(',');
+        key(&mut out, "code");
+        match &diagnostic.code {
+            Some(code) => string(&mut out, code),
+            None => out.push_str("null"),
+        }
+        out.push(',');
+        field(&mut out, "message", &diagnostic.message);
+
+        // Labelled spans in order; the diagnostic's own span stands in for a
+        // missing primary label
+        out.push_str(",\"spans\":[");
+        let has_primary = diagnostic
+            .labels
+            .iter()
+            .any(|label| label.style == LabelStyle::Primary);
+        if !has_primary {
+            span(&mut out, &diagnostic.span, source_map, true, None);
+        }
+        for (i, label) in diagnostic.labels.iter().enumerate() {
+            if i > 0 || !has_primary {
+                out.push(',');
+            }
+            let is_primary = label.style == LabelStyle::Primary;
+            span(
+                &mut out,
+                &label.span,
+                source_map,
+                is_primary,
+                Some(&label.message),
+            );
+        }
+
+        out.push_str("],\"suggestions\":[");
+        for (i, suggestion) in diagnostic.suggestions.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push('{');
+            field(&mut out, "message", &suggestion.message);
+            out.push(',');
+            field(&mut out, "replacement", &suggestion.replacement);
+            out.push(',');
+            field(
+                &mut out,
+                "applicability",
+                applicability(suggestion.applicability),
+            );
+            out.push(',');
+            key(&mut out, "span");
+            span(&mut out, &suggestion.span, source_map, false, None);
+            out.push('}');
+        }
+
+        out.push_str("],");
+        strings(&mut out, "notes", &diagnostic.notes);
+        out.push(',');
+        strings(&mut out, "help", &diagnostic.help);
+        out.push(',');
+        let rendered = ErrorFormatter::new().format_diagnostic(diagnostic, source_map);
+        field(&mut out, "rendered", rendered.trim_start_matches('\n'));
+        out.push('}');
+        out
+    }
+
+    /// An error without a source location (e.g. a missing input file), in
+    /// the same shape as [`format_diagnostic`](Self::format_diagnostic)
+    pub fn format_message(&self, severity: DiagnosticSeverity, message: &str) -> String {
+        let mut out = String::from("{");
+        field(&mut out, "severity", &severity.to_string());
+        out.push_str(",\"code\":null,");
+        field(&mut out, "message", message);
+        out.push_str(",\"spans\":[],\"suggestions\":[],\"notes\":[],\"help\":[],");
+        field(
+            &mut out,
+            "rendered",
+            &format!("{}: {}\n", severity, message),
+        );
+        out.push('}');
+        out
+    }
+}
+
+fn applicability(applicability: Applicability) -> &'static str {
+    match applicability {
+        Applicability::MachineApplicable => "machine-applicable",
+        Applicability::HasPlaceholders => "has-placeholders",
+        Applicability::MaybeIncorrect => "maybe-incorrect",
+        Applicability::Unspecified => "unspecified",
+    }
+}
+
+fn span(
+    out: &mut String,
+    span: &SourceSpan,
+    source_map: &SourceMap,
+    is_primary: bool,
+    label: Option<&str>,
+) {
+    out.push('{');
+    key(out, "file");
+    match source_map.get_file(span.file_id) {
+        Some(file) => string(out, &file.name),
+        None => out.push_str("null"),
+    }
+    out.push_str(&format!(
+        ",\"line_start\":{},\"column_start\":{},\"line_end\":{},\"column_end\":{}",
+        span.start.line, span.start.column, span.end.line, span.end.column
+    ));
+    out.push_str(&format!(
+        ",\"byte_start\":{},\"byte_end\":{},\"is_primary\":{},",
+        span.start.byte_offset, span.end.byte_offset, is_primary
+    ));
+    key(out, "label");
+    match label {
+        Some(label) => string(out, label),
+        None => out.push_str("null"),
+    }
+    out.push('}');
+}
+
+fn strings(out: &mut String, name: &str, values: &[String]) {
+    key(out, name);
+    out.push('[');
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        string(out, value);
+    }
+    out.push(']');
+}
+
+fn field(out: &mut String, name: &str, value: &str) {
+    key(out, name);
+    string(out, value);
+}
+
+fn key(out: &mut String, name: &str) {
+    string(out, name);
+    out.push(':');
+}
+
+fn string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 || c == '\x7f' => {
+                out.push_str(&format!("\\u{:04x}", c as u32));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::haxe::HaxeDiagnostics;
+    use crate::{DiagnosticBuilder, FileId, SourcePosition};
+
+    #[test]
+    fn test_diagnostic_json() {
+        let mut source_map = SourceMap::new();
+        let file_id = source_map.add_file("Main.hx".to_string(), "var x = 1\n".to_string());
+        let span = SourceSpan::new(
+            SourcePosition::new(1, 10, 9),
+            SourcePosition::new(1, 11, 10),
+            file_id,
+        );
+        let diagnostic = HaxeDiagnostics::missing_semicolon(span, "statement");
+
+        let json = JsonFormatter::new().format_diagnostic(&diagnostic, &source_map);
+        assert!(!json.contains('\n'));
+        assert!(json.starts_with(
+            "{\"severity\":\"error\",\"code\":\"E0002\",\
+             \"message\":\"missing ';' at the end of statement\",\
+             \"spans\":[{\"file\":\"Main.hx\",\"line_start\":1,\"column_start\":10,\
+             \"line_end\":1,\"column_end\":11,\"byte_start\":9,\"byte_end\":10,\
+             \"is_primary\":true,\"label\":\"expected ';' here\"}],\
+             \"suggestions\":[{\"message\":\"add semicolon\",\"replacement\":\";\",\
+             \"applicability\":\"machine-applicable\",\"span\":{\"file\":\"Main.hx\","
+        ));
+        assert!(json.contains(
+            "\"notes\":[],\"help\":[\"statement must end with a semicolon\"],\
+             \"rendered\":\"error[E0002]: missing ';' at the end of statement\\n  --> Main.hx:1:10\\n"
+        ));
+    }
+
+    #[test]
+    fn test_unlabelled_span_and_escaping() {
+        let source_map = SourceMap::new();
+        let span = SourceSpan::new(
+            SourcePosition::new(2, 1, 5),
+            SourcePosition::new(2, 3, 7),
+            FileId::new(7),
+        );
+        let diagnostic = DiagnosticBuilder::warning("unused \"x\"\tin\\scope", span)
+            .note("line\u{1}")
+            .build();
+
+        let json = JsonFormatter::new().format_diagnostic(&diagnostic, &source_map);
+        assert!(json.starts_with(
+            "{\"severity\":\"warning\",\"code\":null,\
+             \"message\":\"unused \\\"x\\\"\\tin\\\\scope\",\
+             \"spans\":[{\"file\":null,\"line_start\":2,\"column_start\":1,\
+             \"line_end\":2,\"column_end\":3,\"byte_start\":5,\"byte_end\":7,\
+             \"is_primary\":true,\"label\":null}],\"suggestions\":[],\
+             \"notes\":[\"line\\u0001\"],"
+        ));
+
+        let mut diagnostics = Diagnostics::new();
+        diagnostics.push(diagnostic.clone());
+        diagnostics.push(diagnostic);
+        let lines = JsonFormatter::new().format_diagnostics(&diagnostics, &source_map);
+        assert_eq!(lines.lines().count(), 2);
+
+        assert_eq!(
+            JsonFormatter::new().format_message(DiagnosticSeverity::Error, "File not found"),
+            "{\"severity\":\"error\",\"code\":null,\"message\":\"File not found\",\
+             \"spans\":[],\"suggestions\":[],\"notes\":[],\"help\":[],\
+             \"rendered\":\"error: File not found\\n\"}"
+        );
+    }
+}
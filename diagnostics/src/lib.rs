@@ -6,6 +6,7 @@
 //! - Suggestions with applicability levels
 //! - Multi-file source map support
 //! - Colored terminal output
+//! - JSON output for tools ([`json::JsonFormatter`])
 
 use std::fmt;
 
@@ -289,6 +290,16 @@ impl DiagnosticBuilder {
     }
 }
 
+/// How diagnostics are written for the user
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiagnosticFormat {
+    /// Source snippets via [`ErrorFormatter`]
+    #[default]
+    Human,
+    /// One JSON object per line via [`json::JsonFormatter`]
+    Json,
+}
+
 /// Formatter for displaying diagnostics
 pub struct ErrorFormatter {
     use_colors: bool,
@@ -530,12 +541,38 @@ impl Default for ErrorFormatter {
     }
 }
 
+/// Remove ANSI escape sequences (as emitted by `ErrorFormatter::with_colors`)
+/// from formatted output, leaving only the plain text.
+pub fn strip_ansi_codes(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' && chars.peek() == Some(&'[') {
+            chars.next();
+            // CSI sequence: parameter/intermediate bytes until a final byte in '@'..='~'
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        } else {
+            output.push(c);
+        }
+    }
+
+    output
+}
+
 /// Result type that includes diagnostics
 pub type DiagnosticResult<T> = Result<T, Diagnostics>;
 
 // Haxe-specific diagnostics
 pub mod haxe;
 
+// Machine-readable output
+pub mod json;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -574,4 +611,28 @@ mod tests {
         assert_eq!(diagnostic.help.len(), 1);
         assert_eq!(diagnostic.notes.len(), 1);
     }
+
+    #[test]
+    fn test_strip_ansi_codes() {
+        let span = SourceSpan::new(
+            SourcePosition::new(1, 1, 0),
+            SourcePosition::new(1, 5, 4),
+            FileId::new(0),
+        );
+        let mut source_map = SourceMap::new();
+        source_map.add_file("test.hx".to_string(), "var x = 1;".to_string());
+
+        let diagnostic = DiagnosticBuilder::error("test error", span.clone())
+            .code("E0001")
+            .label(span, "here")
+            .help("try this")
+            .build();
+
+        let colored = ErrorFormatter::with_colors().format_diagnostic(&diagnostic, &source_map);
+        let plain = ErrorFormatter::new().format_diagnostic(&diagnostic, &source_map);
+
+        assert_ne!(colored, plain);
+        assert_eq!(strip_ansi_codes(&colored), plain);
+        assert_eq!(strip_ansi_codes(&plain), plain);
+    }
 }
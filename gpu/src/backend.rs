@@ -14,8 +14,32 @@ use crate::metal::{buffer_ops::MetalBuffer, compile::CompiledKernel, device_init
 #[cfg(feature = "webgpu-backend")]
 use crate::wgpu_backend::{
     buffer_ops::WgpuBuffer, compile::WgpuCompiledKernel, device_init::WgpuContext,
+    transfer::WgpuTransfer,
 };
 
+// ---------------------------------------------------------------------------
+// DeviceInfo
+// ---------------------------------------------------------------------------
+
+/// Device type tags matching rayzor.gpu.GpuDeviceType
+pub const DEVICE_OTHER: i64 = 0;
+pub const DEVICE_INTEGRATED: i64 = 1;
+pub const DEVICE_DISCRETE: i64 = 2;
+pub const DEVICE_VIRTUAL: i64 = 3;
+pub const DEVICE_CPU: i64 = 4;
+
+/// Description of a GPU a context can be created on.
+#[derive(Debug, Clone)]
+pub struct DeviceInfo {
+    pub name: String,
+    /// One of the `DEVICE_*` tags.
+    pub device_type: i64,
+    /// Recommended working-set size in bytes, or 0 if the backend can't tell.
+    pub memory: u64,
+    /// Backend that drives the device ("metal", "wgpu/Vulkan", ...).
+    pub backend: String,
+}
+
 // ---------------------------------------------------------------------------
 // NativeContext
 // ---------------------------------------------------------------------------
@@ -49,6 +73,51 @@ impl NativeContext {
         None
     }
 
+    /// List every device of every enabled backend, Metal first.
+    ///
+    /// Indices into this list are what `new_on_device` takes.
+    pub fn enumerate_devices() -> Vec<DeviceInfo> {
+        #[allow(unused_mut)]
+        let mut devices = Vec::new();
+        #[cfg(feature = "metal-backend")]
+        devices.extend(MetalContext::enumerate());
+        #[cfg(feature = "webgpu-backend")]
+        devices.extend(WgpuContext::enumerate());
+        devices
+    }
+
+    /// Create a GPU context on the device at `index` in `enumerate_devices()`.
+    pub fn new_on_device(index: usize) -> Option<Self> {
+        #[allow(unused_mut)]
+        let mut index = index;
+        #[cfg(feature = "metal-backend")]
+        {
+            let count = MetalContext::enumerate().len();
+            if index < count {
+                return MetalContext::new_on_device(index).map(NativeContext::Metal);
+            }
+            index -= count;
+        }
+        #[cfg(feature = "webgpu-backend")]
+        {
+            if let Some(ctx) = WgpuContext::new_on_device(index) {
+                return Some(NativeContext::Wgpu(ctx));
+            }
+        }
+        None
+    }
+
+    /// Describe the device this context runs on.
+    pub fn device_info(&self) -> Option<DeviceInfo> {
+        match self {
+            #[cfg(feature = "metal-backend")]
+            NativeContext::Metal(ctx) => Some(ctx.info()),
+            #[cfg(feature = "webgpu-backend")]
+            NativeContext::Wgpu(ctx) => Some(ctx.info()),
+            NativeContext::Unavailable => None,
+        }
+    }
+
     /// Check if any GPU backend is available.
     pub fn is_available() -> bool {
         #[cfg(feature = "metal-backend")]
@@ -159,6 +228,107 @@ impl NativeBuffer {
         }
     }
 
+    /// Overwrite `data.len()` bytes at `offset`. Returns false if out of range.
+    pub fn write_bytes(&self, offset: usize, data: &[u8]) -> bool {
+        if offset + data.len() > self.byte_size() {
+            return false;
+        }
+        match self {
+            #[cfg(feature = "metal-backend")]
+            NativeBuffer::Metal(buf) => {
+                let ptr = buf.contents();
+                if ptr.is_null() {
+                    return false;
+                }
+                unsafe {
+                    std::ptr::copy_nonoverlapping(data.as_ptr(), ptr.add(offset), data.len());
+                }
+                true
+            }
+            #[cfg(feature = "webgpu-backend")]
+            NativeBuffer::Wgpu(buf) => {
+                buf.write(offset, data);
+                true
+            }
+            NativeBuffer::Unavailable => false,
+        }
+    }
+
+    /// Start copying `len` bytes from host memory into the buffer at `offset`.
+    ///
+    /// Metal buffers are host-visible (shared storage), so the copy finishes
+    /// before this returns. Returns None if the range is out of bounds.
+    ///
+    /// # Safety
+    /// `src` must point to at least `len` readable bytes.
+    pub unsafe fn upload_async(
+        &self,
+        offset: usize,
+        src: *const u8,
+        len: usize,
+    ) -> Option<NativeEvent> {
+        if offset + len > self.byte_size() {
+            return None;
+        }
+        match self {
+            #[cfg(feature = "metal-backend")]
+            NativeBuffer::Metal(buf) => {
+                let ptr = buf.contents();
+                if ptr.is_null() {
+                    return None;
+                }
+                std::ptr::copy_nonoverlapping(src, ptr.add(offset), len);
+                Some(NativeEvent::Complete)
+            }
+            #[cfg(feature = "webgpu-backend")]
+            NativeBuffer::Wgpu(buf) => Some(NativeEvent::Wgpu(buf.upload_async(offset, src, len))),
+            NativeBuffer::Unavailable => None,
+        }
+    }
+
+    /// Start copying the first `len` bytes of the buffer into host memory.
+    ///
+    /// # Safety
+    /// `dst` must stay valid for `len` writable bytes until the returned
+    /// event completes.
+    pub unsafe fn download_async(&self, dst: *mut u8, len: usize) -> Option<NativeEvent> {
+        if len > self.byte_size() {
+            return None;
+        }
+        match self {
+            #[cfg(feature = "metal-backend")]
+            NativeBuffer::Metal(buf) => {
+                let ptr = buf.contents();
+                if ptr.is_null() {
+                    return None;
+                }
+                std::ptr::copy_nonoverlapping(ptr, dst, len);
+                Some(NativeEvent::Complete)
+            }
+            #[cfg(feature = "webgpu-backend")]
+            NativeBuffer::Wgpu(buf) => Some(NativeEvent::Wgpu(buf.download_async(dst, len))),
+            NativeBuffer::Unavailable => None,
+        }
+    }
+
+    /// Whether this buffer was allocated on the device `ctx` dispatches to.
+    pub fn belongs_to(&self, ctx: &NativeContext) -> bool {
+        match (self, ctx) {
+            #[cfg(feature = "metal-backend")]
+            (NativeBuffer::Metal(buf), NativeContext::Metal(ctx)) => {
+                use objc2_metal::{MTLDevice, MTLResource};
+                buf.mtl_buffer.device().registryID() == ctx.device.registryID()
+            }
+            // wgpu resources are tied to the `wgpu::Device` that created
+            // them, not just the adapter, so compare device identity.
+            #[cfg(feature = "webgpu-backend")]
+            (NativeBuffer::Wgpu(buf), NativeContext::Wgpu(ctx)) => {
+                std::ptr::eq(buf.device, &ctx.device)
+            }
+            _ => false,
+        }
+    }
+
     /// Get the byte size of the buffer.
     pub fn byte_size(&self) -> usize {
         match self {
@@ -171,6 +341,38 @@ impl NativeBuffer {
     }
 }
 
+// ---------------------------------------------------------------------------
+// NativeEvent
+// ---------------------------------------------------------------------------
+
+/// Completion handle for an async host <-> device transfer.
+pub enum NativeEvent {
+    /// The transfer finished before the call that started it returned.
+    Complete,
+    #[cfg(feature = "webgpu-backend")]
+    Wgpu(WgpuTransfer),
+}
+
+impl NativeEvent {
+    /// Check for completion without blocking.
+    pub fn is_complete(&mut self) -> bool {
+        match self {
+            NativeEvent::Complete => true,
+            #[cfg(feature = "webgpu-backend")]
+            NativeEvent::Wgpu(transfer) => transfer.is_complete(),
+        }
+    }
+
+    /// Block until the transfer completes. Returns false if it failed.
+    pub fn wait(&mut self) -> bool {
+        match self {
+            NativeEvent::Complete => true,
+            #[cfg(feature = "webgpu-backend")]
+            NativeEvent::Wgpu(transfer) => transfer.wait(),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // NativeCompiledKernel
 // ---------------------------------------------------------------------------
@@ -8,6 +8,9 @@
 
 use std::rc::Rc;
 
+use rayzor_plugin::layout::{FieldKind, StructLayout};
+use rayzor_runtime::haxe_string::HaxeString;
+
 use crate::backend::{NativeBuffer, NativeCompiledKernel, NativeContext};
 use crate::device::GpuContext;
 use crate::lazy::LazyNode;
@@ -77,13 +80,14 @@ impl GpuBuffer {
 
     /// Materialize a lazy buffer by compiling and dispatching its fused kernel.
     ///
-    /// No-op if already materialized.
+    /// No-op if already materialized. Fails if the buffer (or any input of a
+    /// lazy chain) was allocated on a different device than `gpu_ctx`.
     pub(crate) fn ensure_materialized(&mut self, gpu_ctx: &mut GpuContext) -> Result<(), String> {
         if let GpuBufferKind::Lazy(ref lazy_node) = self.kind {
             let native_buf = materialize_lazy(gpu_ctx, lazy_node)?;
             self.kind = GpuBufferKind::Materialized(Rc::new(native_buf));
         }
-        Ok(())
+        gpu_ctx.check_affinity(self.native_buffer())
     }
 }
 
@@ -99,6 +103,10 @@ fn materialize_lazy(
     // Collect all input buffers from the lazy tree
     let (input_bufs, ptr_to_idx) = crate::lazy::collect_inputs(op);
 
+    for input in &input_bufs {
+        gpu_ctx.check_affinity(input)?;
+    }
+
     // Check fused kernel cache (keyed by structural hash + dtype)
     let struct_hash = crate::lazy::structural_hash(op);
     let cache_key = (struct_hash, dtype);
@@ -492,3 +500,105 @@ pub unsafe extern "C" fn rayzor_gpu_compute_read_struct_int(
     }
     0
 }
+
+// ---------------------------------------------------------------------------
+// Layout-descriptor variants (`Class.layout()` instead of manual sizes/offsets)
+// ---------------------------------------------------------------------------
+
+/// Decode the layout descriptor string passed from Haxe.
+unsafe fn struct_layout_arg(layout_ptr: i64) -> Option<StructLayout> {
+    let s = (layout_ptr as *const HaxeString).as_ref()?;
+    if s.ptr.is_null() {
+        return None;
+    }
+    let text = std::str::from_utf8(std::slice::from_raw_parts(s.ptr, s.len)).ok()?;
+    StructLayout::parse(text).ok()
+}
+
+/// Create a GPU buffer from an array of structs described by `layout`.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_create_layout_buffer(
+    ctx: i64,
+    array_ptr: i64,
+    count: i64,
+    layout_ptr: i64,
+) -> i64 {
+    match struct_layout_arg(layout_ptr) {
+        Some(layout) => {
+            rayzor_gpu_compute_create_struct_buffer(ctx, array_ptr, count, layout.stride() as i64)
+        }
+        None => 0,
+    }
+}
+
+/// Allocate an empty GPU buffer for `count` structs described by `layout`.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_alloc_layout_buffer(
+    ctx: i64,
+    count: i64,
+    layout_ptr: i64,
+) -> i64 {
+    match struct_layout_arg(layout_ptr) {
+        Some(layout) => rayzor_gpu_compute_alloc_struct_buffer(ctx, count, layout.stride() as i64),
+        None => 0,
+    }
+}
+
+/// Read the named scalar field of struct `index`, converted to f64.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_read_struct_field(
+    _ctx: i64,
+    buffer_ptr: i64,
+    index: i64,
+    layout_ptr: i64,
+    field_ptr: i64,
+) -> f64 {
+    if buffer_ptr == 0 || index < 0 {
+        return 0.0;
+    }
+    let Some(layout) = struct_layout_arg(layout_ptr) else {
+        return 0.0;
+    };
+    let Some(field_name) = (field_ptr as *const HaxeString)
+        .as_ref()
+        .filter(|s| !s.ptr.is_null())
+        .and_then(|s| std::str::from_utf8(std::slice::from_raw_parts(s.ptr, s.len)).ok())
+    else {
+        return 0.0;
+    };
+    let Some(field) = layout.field(field_name) else {
+        return 0.0;
+    };
+
+    let width = match field.kind {
+        FieldKind::F64 | FieldKind::I64 | FieldKind::Ptr => 8,
+        FieldKind::F32 | FieldKind::I32 | FieldKind::U32 => 4,
+        FieldKind::Struct(_) => return 0.0,
+    };
+    let byte_offset = (index as usize) * (layout.stride() as usize) + field.offset as usize;
+
+    let buf = &*(buffer_ptr as *const GpuBuffer);
+    let native_buf = buf.native_buffer();
+    let mut bytes = [0u8; 8];
+    let ptr = native_buf.contents_ptr();
+    if !ptr.is_null() {
+        std::ptr::copy_nonoverlapping(ptr.add(byte_offset), bytes.as_mut_ptr(), width);
+    } else {
+        // Fallback for wgpu: read via staging buffer
+        let total = byte_offset + width;
+        match native_buf.read_bytes(total) {
+            Some(data) if data.len() >= total => {
+                bytes[..width].copy_from_slice(&data[byte_offset..total]);
+            }
+            _ => return 0.0,
+        }
+    }
+
+    match field.kind {
+        FieldKind::F32 => f32::from_ne_bytes(bytes[..4].try_into().unwrap()) as f64,
+        FieldKind::I32 => i32::from_ne_bytes(bytes[..4].try_into().unwrap()) as f64,
+        FieldKind::U32 => u32::from_ne_bytes(bytes[..4].try_into().unwrap()) as f64,
+        FieldKind::F64 => f64::from_ne_bytes(bytes),
+        _ => i64::from_ne_bytes(bytes) as f64,
+    }
+}
@@ -8,8 +8,14 @@ pub mod msl;
 #[cfg(feature = "metal-backend")]
 pub mod msl_fused;
 #[cfg(feature = "metal-backend")]
+pub mod msl_image;
+#[cfg(feature = "metal-backend")]
 pub mod msl_matmul;
 #[cfg(feature = "metal-backend")]
+pub mod msl_primitives;
+#[cfg(feature = "metal-backend")]
+pub mod msl_random;
+#[cfg(feature = "metal-backend")]
 pub mod msl_reduction;
 
 #[cfg(feature = "webgpu-backend")]
@@ -17,6 +23,12 @@ pub mod wgsl;
 #[cfg(feature = "webgpu-backend")]
 pub mod wgsl_fused;
 #[cfg(feature = "webgpu-backend")]
+pub mod wgsl_image;
+#[cfg(feature = "webgpu-backend")]
 pub mod wgsl_matmul;
 #[cfg(feature = "webgpu-backend")]
+pub mod wgsl_primitives;
+#[cfg(feature = "webgpu-backend")]
+pub mod wgsl_random;
+#[cfg(feature = "webgpu-backend")]
 pub mod wgsl_reduction;
@@ -105,6 +105,15 @@ pub fn emit_kernel(op: KernelOp, dtype: u8) -> String {
     if op == KernelOp::Matmul {
         return super::msl_matmul::emit_matmul(dtype);
     }
+    if op.is_image() {
+        return super::msl_image::emit_image_kernel(op);
+    }
+    if op.is_primitive() {
+        return super::msl_primitives::emit_primitive(op, dtype);
+    }
+    if op.is_random() {
+        return super::msl_random::emit_random_kernel(op);
+    }
     match op.input_count() {
         2 => emit_binary_elementwise(op, dtype),
         1 => emit_unary_elementwise(op, dtype),
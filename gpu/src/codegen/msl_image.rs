@@ -0,0 +1,130 @@
+//! MSL code generation for image ops over texture buffers.
+//!
+//! Textures are row-major f32 texel buffers with interleaved channels.
+//! Every kernel shares `read_texel` (clamp-to-edge), `sample_bilinear` and
+//! `write_texel` helpers and is dispatched as a 2D grid of 16x16
+//! threadgroups, one thread per output texel. Sizes are passed in a
+//! `constant uint4*` buffer so kernels are cached by op only.
+
+use crate::kernel_ir::KernelOp;
+
+/// Texel helpers shared by all image kernels.
+///
+/// `d` is (width, height, channels, 0) of the image being accessed.
+const TEXEL_HELPERS: &str = r#"#include <metal_stdlib>
+using namespace metal;
+
+static float read_texel(device const float* img, uint4 d, int x, int y, uint c) {
+    uint cx = uint(clamp(x, 0, int(d.x) - 1));
+    uint cy = uint(clamp(y, 0, int(d.y) - 1));
+    return img[(cy * d.x + cx) * d.z + c];
+}
+
+static float sample_bilinear(device const float* img, uint4 d, float u, float v, uint c) {
+    float x0 = floor(u);
+    float y0 = floor(v);
+    float fx = u - x0;
+    float fy = v - y0;
+    int ix = int(x0);
+    int iy = int(y0);
+    float top = mix(read_texel(img, d, ix, iy, c), read_texel(img, d, ix + 1, iy, c), fx);
+    float bottom = mix(read_texel(img, d, ix, iy + 1, c), read_texel(img, d, ix + 1, iy + 1, c), fx);
+    return mix(top, bottom, fy);
+}
+
+static void write_texel(device float* img, uint4 d, uint x, uint y, uint c, float value) {
+    img[(y * d.x + x) * d.z + c] = value;
+}
+"#;
+
+/// Generate MSL source for an image op.
+pub fn emit_image_kernel(op: KernelOp) -> String {
+    match op {
+        KernelOp::Conv2d => emit_conv2d(),
+        KernelOp::Resize => emit_resize(),
+        _ => unreachable!("not an image op"),
+    }
+}
+
+/// Convolution with a KW×KH f32 weight matrix centred on each texel.
+///
+/// Buffers: src, weights, dst, dims (uint4[2]: (W, H, C, 0), (KW, KH, 0, 0))
+fn emit_conv2d() -> String {
+    format!(
+        r#"{TEXEL_HELPERS}
+kernel void rayzor_conv2d_float(
+    device const float* src [[buffer(0)]],
+    device const float* weights [[buffer(1)]],
+    device float* dst [[buffer(2)]],
+    constant uint4* dims [[buffer(3)]],
+    uint2 gid [[thread_position_in_grid]]
+) {{
+    uint4 d = dims[0];
+    uint kw = dims[1].x;
+    uint kh = dims[1].y;
+    if (gid.x >= d.x || gid.y >= d.y) return;
+
+    int ox = int(gid.x) - int(kw / 2);
+    int oy = int(gid.y) - int(kh / 2);
+    for (uint c = 0; c < d.z; c++) {{
+        float acc = 0.0;
+        for (uint ky = 0; ky < kh; ky++) {{
+            for (uint kx = 0; kx < kw; kx++) {{
+                acc = fma(weights[ky * kw + kx], read_texel(src, d, ox + int(kx), oy + int(ky), c), acc);
+            }}
+        }}
+        write_texel(dst, d, gid.x, gid.y, c, acc);
+    }}
+}}
+"#
+    )
+}
+
+/// Bilinear resize, sampling at texel centres.
+///
+/// Buffers: src, dst, dims (uint4[2]: (SW, SH, C, 0), (DW, DH, C, 0))
+fn emit_resize() -> String {
+    format!(
+        r#"{TEXEL_HELPERS}
+kernel void rayzor_resize_float(
+    device const float* src [[buffer(0)]],
+    device float* dst [[buffer(1)]],
+    constant uint4* dims [[buffer(2)]],
+    uint2 gid [[thread_position_in_grid]]
+) {{
+    uint4 s = dims[0];
+    uint4 d = dims[1];
+    if (gid.x >= d.x || gid.y >= d.y) return;
+
+    float u = (float(gid.x) + 0.5) * float(s.x) / float(d.x) - 0.5;
+    float v = (float(gid.y) + 0.5) * float(s.y) / float(d.y) - 0.5;
+    for (uint c = 0; c < d.z; c++) {{
+        write_texel(dst, d, gid.x, gid.y, c, sample_bilinear(src, s, u, v, c));
+    }}
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conv2d() {
+        let src = emit_image_kernel(KernelOp::Conv2d);
+        assert!(src.contains("kernel void rayzor_conv2d_float"));
+        assert!(src.contains("device const float* weights [[buffer(1)]]"));
+        assert!(src.contains("constant uint4* dims [[buffer(3)]]"));
+        assert!(src.contains("static float read_texel"));
+        assert!(src.contains("write_texel(dst"));
+    }
+
+    #[test]
+    fn test_resize() {
+        let src = emit_image_kernel(KernelOp::Resize);
+        assert!(src.contains("kernel void rayzor_resize_float"));
+        assert!(src.contains("constant uint4* dims [[buffer(2)]]"));
+        assert!(src.contains("sample_bilinear(src, s, u, v, c)"));
+    }
+}
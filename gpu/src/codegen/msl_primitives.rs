@@ -0,0 +1,303 @@
+//! MSL code generation for reduction and scan primitives.
+//!
+//! Mirrors `wgsl_primitives`: two-pass argmax/argmin, a block scan plus
+//! finalize pass, an atomic histogram and one-threadgroup-per-segment
+//! reductions. Every kernel runs 256 threads per threadgroup and takes its
+//! sizes in a `constant uint4&` buffer.
+
+use crate::buffer;
+use crate::kernel_ir::KernelOp;
+
+use super::msl::dtype_to_msl;
+
+/// Threadgroup size (and scan block length) for all primitives.
+pub const PRIMITIVE_THREADGROUP_SIZE: u32 = 256;
+
+/// Smallest and largest value of a dtype, as MSL literals.
+fn limits(dtype: u8) -> (&'static str, &'static str) {
+    match dtype {
+        buffer::DTYPE_I32 => ("-2147483647", "2147483647"),
+        buffer::DTYPE_I64 => ("-9223372036854775807", "9223372036854775807"),
+        _ => ("-INFINITY", "INFINITY"),
+    }
+}
+
+/// Generate MSL source for a primitive op.
+pub fn emit_primitive(op: KernelOp, dtype: u8) -> String {
+    match op {
+        KernelOp::ArgMax | KernelOp::ArgMin => emit_arg_reduce(op, dtype),
+        KernelOp::ScanBlock => emit_scan_block(dtype),
+        KernelOp::ScanFinalize => emit_scan_finalize(dtype),
+        KernelOp::Histogram => emit_histogram(dtype),
+        KernelOp::SegmentSum | KernelOp::SegmentMax | KernelOp::SegmentMin => {
+            emit_segment_reduce(op, dtype)
+        }
+        _ => unreachable!("not a primitive op"),
+    }
+}
+
+/// Buffers: input, in_idx, out_val, out_idx, params (numel, use_in_idx, 0, 0)
+fn emit_arg_reduce(op: KernelOp, dtype: u8) -> String {
+    let t = dtype_to_msl(dtype);
+    let fn_name = format!("rayzor_{}_{}", op.name(), t);
+    let cmp = if op == KernelOp::ArgMax { ">" } else { "<" };
+    let tg = PRIMITIVE_THREADGROUP_SIZE;
+
+    format!(
+        r#"#include <metal_stdlib>
+using namespace metal;
+
+constant uint NONE = 0xffffffffu;
+
+// Whether (a, ai) should replace (b, bi); ties keep the lower index
+static bool better({t} a, uint ai, {t} b, uint bi) {{
+    if (ai == NONE) return false;
+    return bi == NONE || a {cmp} b || (a == b && ai < bi);
+}}
+
+kernel void {fn_name}(
+    device const {t}* input [[buffer(0)]],
+    device const uint* in_idx [[buffer(1)]],
+    device {t}* out_val [[buffer(2)]],
+    device uint* out_idx [[buffer(3)]],
+    constant uint4& params [[buffer(4)]],
+    uint gid [[thread_position_in_grid]],
+    uint tid [[thread_index_in_threadgroup]],
+    uint tgid [[threadgroup_position_in_grid]],
+    uint num_tgs [[threadgroups_per_grid]]
+) {{
+    threadgroup {t} shared_val[{tg}];
+    threadgroup uint shared_idx[{tg}];
+
+    uint numel = params.x;
+    uint stride = {tg} * num_tgs;
+    {t} best = {t}(0);
+    uint best_i = NONE;
+    for (uint i = gid; i < numel; i += stride) {{
+        uint vi = params.y != 0 ? in_idx[i] : i;
+        if (better(input[i], vi, best, best_i)) {{
+            best = input[i];
+            best_i = vi;
+        }}
+    }}
+    shared_val[tid] = best;
+    shared_idx[tid] = best_i;
+    threadgroup_barrier(mem_flags::mem_threadgroup);
+
+    for (uint s = {tg} / 2; s > 0; s >>= 1) {{
+        if (tid < s && better(shared_val[tid + s], shared_idx[tid + s], shared_val[tid], shared_idx[tid])) {{
+            shared_val[tid] = shared_val[tid + s];
+            shared_idx[tid] = shared_idx[tid + s];
+        }}
+        threadgroup_barrier(mem_flags::mem_threadgroup);
+    }}
+
+    if (tid == 0) {{
+        out_val[tgid] = shared_val[0];
+        out_idx[tgid] = shared_idx[0];
+    }}
+}}
+"#
+    )
+}
+
+/// Buffers: input, incl, block_sums, params (n, 0, 0, 0)
+fn emit_scan_block(dtype: u8) -> String {
+    let t = dtype_to_msl(dtype);
+    let fn_name = format!("rayzor_scan_block_{t}");
+    let tg = PRIMITIVE_THREADGROUP_SIZE;
+
+    format!(
+        r#"#include <metal_stdlib>
+using namespace metal;
+
+kernel void {fn_name}(
+    device const {t}* input [[buffer(0)]],
+    device {t}* incl [[buffer(1)]],
+    device {t}* block_sums [[buffer(2)]],
+    constant uint4& params [[buffer(3)]],
+    uint gid [[thread_position_in_grid]],
+    uint tid [[thread_index_in_threadgroup]],
+    uint tgid [[threadgroup_position_in_grid]]
+) {{
+    threadgroup {t} temp[{tg}];
+
+    uint n = params.x;
+    temp[tid] = gid < n ? input[gid] : {t}(0);
+    threadgroup_barrier(mem_flags::mem_threadgroup);
+
+    for (uint offset = 1; offset < {tg}; offset <<= 1) {{
+        {t} add = tid >= offset ? temp[tid - offset] : {t}(0);
+        threadgroup_barrier(mem_flags::mem_threadgroup);
+        temp[tid] += add;
+        threadgroup_barrier(mem_flags::mem_threadgroup);
+    }}
+
+    if (gid < n) {{
+        incl[gid] = temp[tid];
+    }}
+    if (tid == {tg} - 1) {{
+        block_sums[tgid] = temp[tid];
+    }}
+}}
+"#
+    )
+}
+
+/// Buffers: incl, offsets (inclusive scan of block sums), result,
+/// params (n, exclusive, has_offsets, 0)
+fn emit_scan_finalize(dtype: u8) -> String {
+    let t = dtype_to_msl(dtype);
+    let fn_name = format!("rayzor_scan_finalize_{t}");
+    let tg = PRIMITIVE_THREADGROUP_SIZE;
+
+    format!(
+        r#"#include <metal_stdlib>
+using namespace metal;
+
+static {t} scanned(device const {t}* incl, device const {t}* offsets, uint4 params, uint j) {{
+    uint block = j / {tg};
+    {t} v = incl[j];
+    if (params.z != 0 && block > 0) {{
+        v += offsets[block - 1];
+    }}
+    return v;
+}}
+
+kernel void {fn_name}(
+    device const {t}* incl [[buffer(0)]],
+    device const {t}* offsets [[buffer(1)]],
+    device {t}* result [[buffer(2)]],
+    constant uint4& params [[buffer(3)]],
+    uint gid [[thread_position_in_grid]]
+) {{
+    if (gid >= params.x) return;
+    if (params.y == 0) {{
+        result[gid] = scanned(incl, offsets, params, gid);
+    }} else if (gid == 0) {{
+        result[gid] = {t}(0);
+    }} else {{
+        result[gid] = scanned(incl, offsets, params, gid - 1);
+    }}
+}}
+"#
+    )
+}
+
+/// Buffers: input, bins (atomic uint), params (n, num_bins, bits(min), bits(max))
+fn emit_histogram(dtype: u8) -> String {
+    let t = dtype_to_msl(dtype);
+    let fn_name = format!("rayzor_histogram_{t}");
+
+    format!(
+        r#"#include <metal_stdlib>
+using namespace metal;
+
+kernel void {fn_name}(
+    device const {t}* input [[buffer(0)]],
+    device atomic_uint* bins [[buffer(1)]],
+    constant uint4& params [[buffer(2)]],
+    uint gid [[thread_position_in_grid]]
+) {{
+    if (gid >= params.x) return;
+    float lo = as_type<float>(params.z);
+    float hi = as_type<float>(params.w);
+    float v = float(input[gid]);
+    // Values outside [lo, hi] (and NaN) are not counted
+    if (!(v >= lo && v <= hi)) return;
+    uint num_bins = params.y;
+    uint b = min(uint((v - lo) / (hi - lo) * float(num_bins)), num_bins - 1);
+    atomic_fetch_add_explicit(&bins[b], 1u, memory_order_relaxed);
+}}
+"#
+    )
+}
+
+/// Buffers: input, offsets (int, num_segments + 1), output,
+/// params (num_segments, 0, 0, 0)
+fn emit_segment_reduce(op: KernelOp, dtype: u8) -> String {
+    let t = dtype_to_msl(dtype);
+    let fn_name = format!("rayzor_{}_{}", op.name(), t);
+    let tg = PRIMITIVE_THREADGROUP_SIZE;
+    let (lowest, highest) = limits(dtype);
+    let (identity, combine) = match op {
+        KernelOp::SegmentSum => (format!("{t}(0)"), "a + b"),
+        KernelOp::SegmentMax => (format!("{t}({lowest})"), "max(a, b)"),
+        KernelOp::SegmentMin => (format!("{t}({highest})"), "min(a, b)"),
+        _ => unreachable!("not a segmented reduction"),
+    };
+
+    format!(
+        r#"#include <metal_stdlib>
+using namespace metal;
+
+static {t} combine({t} a, {t} b) {{
+    return {combine};
+}}
+
+kernel void {fn_name}(
+    device const {t}* input [[buffer(0)]],
+    device const int* offsets [[buffer(1)]],
+    device {t}* output [[buffer(2)]],
+    constant uint4& params [[buffer(3)]],
+    uint tid [[thread_index_in_threadgroup]],
+    uint tgid [[threadgroup_position_in_grid]],
+    uint num_tgs [[threadgroups_per_grid]]
+) {{
+    threadgroup {t} shared_data[{tg}];
+
+    for (uint seg = tgid; seg < params.x; seg += num_tgs) {{
+        uint start = uint(offsets[seg]);
+        uint end = uint(offsets[seg + 1]);
+        {t} acc = {identity};
+        for (uint i = start + tid; i < end; i += {tg}) {{
+            acc = combine(acc, input[i]);
+        }}
+        shared_data[tid] = acc;
+        threadgroup_barrier(mem_flags::mem_threadgroup);
+
+        for (uint s = {tg} / 2; s > 0; s >>= 1) {{
+            if (tid < s) {{
+                shared_data[tid] = combine(shared_data[tid], shared_data[tid + s]);
+            }}
+            threadgroup_barrier(mem_flags::mem_threadgroup);
+        }}
+
+        if (tid == 0) {{
+            output[seg] = shared_data[0];
+        }}
+        threadgroup_barrier(mem_flags::mem_threadgroup);
+    }}
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_argmin_i32() {
+        let src = emit_primitive(KernelOp::ArgMin, buffer::DTYPE_I32);
+        assert!(src.contains("kernel void rayzor_argmin_int"));
+        assert!(src.contains("a < b || (a == b && ai < bi)"));
+        assert!(src.contains("threadgroup uint shared_idx[256]"));
+    }
+
+    #[test]
+    fn test_scan_and_histogram() {
+        let block = emit_primitive(KernelOp::ScanBlock, buffer::DTYPE_F32);
+        assert!(block.contains("kernel void rayzor_scan_block_float"));
+        let hist = emit_primitive(KernelOp::Histogram, buffer::DTYPE_F32);
+        assert!(hist.contains("device atomic_uint* bins"));
+        assert!(hist.contains("atomic_fetch_add_explicit(&bins[b], 1u, memory_order_relaxed)"));
+    }
+
+    #[test]
+    fn test_segment_max_f32() {
+        let src = emit_primitive(KernelOp::SegmentMax, buffer::DTYPE_F32);
+        assert!(src.contains("kernel void rayzor_segment_max_float"));
+        assert!(src.contains("float acc = float(-INFINITY)"));
+    }
+}
@@ -0,0 +1,82 @@
+//! MSL code generation for counter-based random fills.
+//!
+//! Philox4x32-10 keyed by the 64-bit seed, with counter `(i, 0, 0, 0)` for
+//! output element `i`. Must stay bit-for-bit in sync with
+//! `random::philox4x32` and `wgsl_random`.
+
+use crate::kernel_ir::KernelOp;
+
+/// Philox rounds, multipliers and key schedule shared by both ops.
+const PHILOX: &str = r#"#include <metal_stdlib>
+using namespace metal;
+
+constant uint PHILOX_M0 = 0xD2511F53u;
+constant uint PHILOX_M1 = 0xCD9E8D57u;
+constant uint PHILOX_W0 = 0x9E3779B9u;
+constant uint PHILOX_W1 = 0xBB67AE85u;
+
+static uint4 philox4x32(uint4 c, uint2 k) {
+    for (uint round = 0; round < 10; round++) {
+        uint hi0 = mulhi(PHILOX_M0, c.x);
+        uint lo0 = PHILOX_M0 * c.x;
+        uint hi1 = mulhi(PHILOX_M1, c.z);
+        uint lo1 = PHILOX_M1 * c.z;
+        c = uint4(hi1 ^ c.y ^ k.x, lo1, hi0 ^ c.w ^ k.y, lo0);
+        k += uint2(PHILOX_W0, PHILOX_W1);
+    }
+    return c;
+}
+
+// Top 24 bits as a float in [0, 1)
+static float to_unit(uint x) {
+    return float(x >> 8) * 5.9604645e-8f;
+}
+"#;
+
+/// Generate MSL source for a random fill op.
+///
+/// Buffers: output (float), params (numel, seed_lo, seed_hi, 0)
+pub fn emit_random_kernel(op: KernelOp) -> String {
+    let body = match op {
+        KernelOp::RandomUniform => "output[i] = to_unit(r.x);",
+        KernelOp::RandomNormal => {
+            // Box–Muller; u1 is shifted into (0, 1] so log(u1) is finite
+            r#"float u1 = to_unit(r.x) + 5.9604645e-8f;
+        float u2 = to_unit(r.y);
+        output[i] = sqrt(-2.0f * log(u1)) * cos(6.2831855f * u2);"#
+        }
+        _ => unreachable!("not a random op"),
+    };
+
+    format!(
+        r#"{PHILOX}
+kernel void rayzor_{name}_float(
+    device float* output [[buffer(0)]],
+    constant uint4& params [[buffer(1)]],
+    uint gid [[thread_position_in_grid]],
+    uint num_tgs [[threadgroups_per_grid]]
+) {{
+    uint stride = 256 * num_tgs;
+    for (uint i = gid; i < params.x; i += stride) {{
+        uint4 r = philox4x32(uint4(i, 0, 0, 0), params.yz);
+        {body}
+    }}
+}}
+"#,
+        name = op.name()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_kernels() {
+        let uniform = emit_random_kernel(KernelOp::RandomUniform);
+        assert!(uniform.contains("kernel void rayzor_random_uniform_float"));
+        assert!(uniform.contains("uint hi0 = mulhi(PHILOX_M0, c.x);"));
+        let normal = emit_random_kernel(KernelOp::RandomNormal);
+        assert!(normal.contains("kernel void rayzor_random_normal_float"));
+    }
+}
@@ -35,6 +35,16 @@ pub fn kernel_num_buffers(op: KernelOp) -> usize {
         3 // input, output, numel uniform
     } else if op == KernelOp::Matmul {
         4 // A, B, C, dims uniform
+    } else if op.is_image() {
+        op.input_count() + 2 // inputs + result + dims uniform
+    } else if op.is_primitive() {
+        match op {
+            KernelOp::ArgMax | KernelOp::ArgMin => 5, // input, indices, values, indices, params
+            KernelOp::Histogram => 3,                 // input, bins, params
+            _ => 4,                                   // two inputs/outputs + result + params
+        }
+    } else if op.is_random() {
+        2 // output, params uniform
     } else {
         op.input_count() + 1 // inputs + result
     }
@@ -107,6 +117,15 @@ pub fn emit_kernel(op: KernelOp, dtype: u8) -> String {
     if op == KernelOp::Matmul {
         return super::wgsl_matmul::emit_matmul(dtype);
     }
+    if op.is_image() {
+        return super::wgsl_image::emit_image_kernel(op);
+    }
+    if op.is_primitive() {
+        return super::wgsl_primitives::emit_primitive(op, dtype);
+    }
+    if op.is_random() {
+        return super::wgsl_random::emit_random_kernel(op);
+    }
     match op.input_count() {
         2 => emit_binary_elementwise(op, dtype),
         1 => emit_unary_elementwise(op, dtype),
@@ -0,0 +1,130 @@
+//! WGSL code generation for image ops over texture buffers.
+//!
+//! Textures are row-major f32 texel buffers with interleaved channels.
+//! Kernels read `src` through `read_texel` (clamp-to-edge) and
+//! `sample_bilinear`, write `dst` through `write_texel`, and run as a 2D
+//! grid of 16x16 workgroups, one invocation per output texel. Sizes are
+//! passed in a uniform `array<vec4<u32>, 2>`.
+
+use crate::kernel_ir::KernelOp;
+
+/// Texel helpers over the module-scope `src`/`dst` bindings.
+///
+/// `d` is (width, height, channels, 0) of the image being accessed.
+const TEXEL_HELPERS: &str = r#"
+fn read_texel(d: vec4<u32>, x: i32, y: i32, c: u32) -> f32 {
+    let cx = u32(clamp(x, 0, i32(d.x) - 1));
+    let cy = u32(clamp(y, 0, i32(d.y) - 1));
+    return src[(cy * d.x + cx) * d.z + c];
+}
+
+fn sample_bilinear(d: vec4<u32>, u: f32, v: f32, c: u32) -> f32 {
+    let x0 = floor(u);
+    let y0 = floor(v);
+    let fx = u - x0;
+    let fy = v - y0;
+    let ix = i32(x0);
+    let iy = i32(y0);
+    let top = mix(read_texel(d, ix, iy, c), read_texel(d, ix + 1, iy, c), fx);
+    let bottom = mix(read_texel(d, ix, iy + 1, c), read_texel(d, ix + 1, iy + 1, c), fx);
+    return mix(top, bottom, fy);
+}
+
+fn write_texel(d: vec4<u32>, x: u32, y: u32, c: u32, value: f32) {
+    dst[(y * d.x + x) * d.z + c] = value;
+}
+"#;
+
+/// Generate WGSL source for an image op.
+pub fn emit_image_kernel(op: KernelOp) -> String {
+    match op {
+        KernelOp::Conv2d => emit_conv2d(),
+        KernelOp::Resize => emit_resize(),
+        _ => unreachable!("not an image op"),
+    }
+}
+
+/// Convolution with a KW×KH f32 weight matrix centred on each texel.
+///
+/// Buffers: src, weights, dst, dims ((W, H, C, 0), (KW, KH, 0, 0))
+fn emit_conv2d() -> String {
+    format!(
+        r#"@group(0) @binding(0) var<storage, read> src: array<f32>;
+@group(0) @binding(1) var<storage, read> weights: array<f32>;
+@group(0) @binding(2) var<storage, read_write> dst: array<f32>;
+@group(0) @binding(3) var<uniform> dims: array<vec4<u32>, 2>;
+{TEXEL_HELPERS}
+@compute @workgroup_size(16, 16)
+fn rayzor_conv2d_f32(@builtin(global_invocation_id) gid: vec3<u32>) {{
+    let d = dims[0];
+    let kw = dims[1].x;
+    let kh = dims[1].y;
+    if (gid.x >= d.x || gid.y >= d.y) {{
+        return;
+    }}
+
+    let ox = i32(gid.x) - i32(kw / 2u);
+    let oy = i32(gid.y) - i32(kh / 2u);
+    for (var c = 0u; c < d.z; c = c + 1u) {{
+        var acc = 0.0;
+        for (var ky = 0u; ky < kh; ky = ky + 1u) {{
+            for (var kx = 0u; kx < kw; kx = kx + 1u) {{
+                acc = fma(weights[ky * kw + kx], read_texel(d, ox + i32(kx), oy + i32(ky), c), acc);
+            }}
+        }}
+        write_texel(d, gid.x, gid.y, c, acc);
+    }}
+}}
+"#
+    )
+}
+
+/// Bilinear resize, sampling at texel centres.
+///
+/// Buffers: src, dst, dims ((SW, SH, C, 0), (DW, DH, C, 0))
+fn emit_resize() -> String {
+    format!(
+        r#"@group(0) @binding(0) var<storage, read> src: array<f32>;
+@group(0) @binding(1) var<storage, read_write> dst: array<f32>;
+@group(0) @binding(2) var<uniform> dims: array<vec4<u32>, 2>;
+{TEXEL_HELPERS}
+@compute @workgroup_size(16, 16)
+fn rayzor_resize_f32(@builtin(global_invocation_id) gid: vec3<u32>) {{
+    let s = dims[0];
+    let d = dims[1];
+    if (gid.x >= d.x || gid.y >= d.y) {{
+        return;
+    }}
+
+    let u = (f32(gid.x) + 0.5) * f32(s.x) / f32(d.x) - 0.5;
+    let v = (f32(gid.y) + 0.5) * f32(s.y) / f32(d.y) - 0.5;
+    for (var c = 0u; c < d.z; c = c + 1u) {{
+        write_texel(d, gid.x, gid.y, c, sample_bilinear(s, u, v, c));
+    }}
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conv2d() {
+        let src = emit_image_kernel(KernelOp::Conv2d);
+        assert!(src.contains("fn rayzor_conv2d_f32"));
+        assert!(src.contains("var<storage, read> weights: array<f32>"));
+        assert!(src.contains("var<uniform> dims: array<vec4<u32>, 2>"));
+        assert!(src.contains("@workgroup_size(16, 16)"));
+        assert!(src.contains("fn read_texel"));
+    }
+
+    #[test]
+    fn test_resize() {
+        let src = emit_image_kernel(KernelOp::Resize);
+        assert!(src.contains("fn rayzor_resize_f32"));
+        assert!(src.contains("var<uniform> dims: array<vec4<u32>, 2>"));
+        assert!(src.contains("sample_bilinear(s, u, v, c)"));
+    }
+}
@@ -0,0 +1,329 @@
+//! WGSL code generation for reduction and scan primitives.
+//!
+//! - `argmax`/`argmin`: two-pass reduction carrying (value, index) pairs;
+//!   pass 2 reads the indices written by pass 1 (`params.y != 0`).
+//! - `scan_block` + `scan_finalize`: block-wise Hillis–Steele scan writing
+//!   per-block totals, then a pass adding the scanned totals of preceding
+//!   blocks (and shifting by one for exclusive scans).
+//! - `histogram`: one invocation per element, atomic bin counters.
+//! - `segment_*`: one workgroup per segment of an offsets buffer.
+//!
+//! Every kernel runs 256 invocations per workgroup and takes its sizes in a
+//! `vec4<u32>` uniform.
+
+use crate::buffer;
+use crate::kernel_ir::KernelOp;
+
+use super::wgsl::dtype_to_wgsl;
+
+/// Workgroup size (and scan block length) for all primitives.
+pub const PRIMITIVE_WORKGROUP_SIZE: u32 = 256;
+
+/// Most negative and most positive finite value of a dtype, as WGSL literals.
+fn limits(dtype: u8) -> (&'static str, &'static str) {
+    match dtype {
+        buffer::DTYPE_I32 | buffer::DTYPE_I64 => ("-2147483647", "2147483647"),
+        _ => ("-3.402823e+38", "3.402823e+38"),
+    }
+}
+
+/// Generate WGSL source for a primitive op.
+pub fn emit_primitive(op: KernelOp, dtype: u8) -> String {
+    match op {
+        KernelOp::ArgMax | KernelOp::ArgMin => emit_arg_reduce(op, dtype),
+        KernelOp::ScanBlock => emit_scan_block(dtype),
+        KernelOp::ScanFinalize => emit_scan_finalize(dtype),
+        KernelOp::Histogram => emit_histogram(dtype),
+        KernelOp::SegmentSum | KernelOp::SegmentMax | KernelOp::SegmentMin => {
+            emit_segment_reduce(op, dtype)
+        }
+        _ => unreachable!("not a primitive op"),
+    }
+}
+
+/// Buffers: input, in_idx, out_val, out_idx, params (numel, use_in_idx, 0, 0)
+fn emit_arg_reduce(op: KernelOp, dtype: u8) -> String {
+    let t = dtype_to_wgsl(dtype);
+    let fn_name = format!("rayzor_{}_{}", op.name(), t);
+    let cmp = if op == KernelOp::ArgMax { ">" } else { "<" };
+    let wg = PRIMITIVE_WORKGROUP_SIZE;
+
+    format!(
+        r#"@group(0) @binding(0) var<storage, read> input: array<{t}>;
+@group(0) @binding(1) var<storage, read> in_idx: array<u32>;
+@group(0) @binding(2) var<storage, read_write> out_val: array<{t}>;
+@group(0) @binding(3) var<storage, read_write> out_idx: array<u32>;
+@group(0) @binding(4) var<uniform> params: vec4<u32>;
+
+const NONE: u32 = 0xffffffffu;
+
+var<workgroup> shared_val: array<{t}, {wg}>;
+var<workgroup> shared_idx: array<u32, {wg}>;
+
+// Whether (a, ai) should replace (b, bi); ties keep the lower index
+fn better(a: {t}, ai: u32, b: {t}, bi: u32) -> bool {{
+    if (ai == NONE) {{
+        return false;
+    }}
+    return bi == NONE || a {cmp} b || (a == b && ai < bi);
+}}
+
+@compute @workgroup_size({wg})
+fn {fn_name}(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) wg_id: vec3<u32>,
+    @builtin(num_workgroups) num_wgs: vec3<u32>
+) {{
+    let numel = params.x;
+    let tid = local_id.x;
+    let stride = {wg}u * num_wgs.x;
+
+    var best = {t}(0);
+    var best_i = NONE;
+    for (var i = global_id.x; i < numel; i = i + stride) {{
+        var vi = i;
+        if (params.y != 0u) {{
+            vi = in_idx[i];
+        }}
+        if (better(input[i], vi, best, best_i)) {{
+            best = input[i];
+            best_i = vi;
+        }}
+    }}
+    shared_val[tid] = best;
+    shared_idx[tid] = best_i;
+    workgroupBarrier();
+
+    for (var s = {wg}u / 2u; s > 0u; s = s >> 1u) {{
+        if (tid < s && better(shared_val[tid + s], shared_idx[tid + s], shared_val[tid], shared_idx[tid])) {{
+            shared_val[tid] = shared_val[tid + s];
+            shared_idx[tid] = shared_idx[tid + s];
+        }}
+        workgroupBarrier();
+    }}
+
+    if (tid == 0u) {{
+        out_val[wg_id.x] = shared_val[0];
+        out_idx[wg_id.x] = shared_idx[0];
+    }}
+}}
+"#
+    )
+}
+
+/// Buffers: input, incl, block_sums, params (n, 0, 0, 0)
+fn emit_scan_block(dtype: u8) -> String {
+    let t = dtype_to_wgsl(dtype);
+    let fn_name = format!("rayzor_scan_block_{t}");
+    let wg = PRIMITIVE_WORKGROUP_SIZE;
+
+    format!(
+        r#"@group(0) @binding(0) var<storage, read> input: array<{t}>;
+@group(0) @binding(1) var<storage, read_write> incl: array<{t}>;
+@group(0) @binding(2) var<storage, read_write> block_sums: array<{t}>;
+@group(0) @binding(3) var<uniform> params: vec4<u32>;
+
+var<workgroup> temp: array<{t}, {wg}>;
+
+@compute @workgroup_size({wg})
+fn {fn_name}(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) wg_id: vec3<u32>
+) {{
+    let n = params.x;
+    let i = global_id.x;
+    let tid = local_id.x;
+
+    var v = {t}(0);
+    if (i < n) {{
+        v = input[i];
+    }}
+    temp[tid] = v;
+    workgroupBarrier();
+
+    for (var offset = 1u; offset < {wg}u; offset = offset << 1u) {{
+        var add = {t}(0);
+        if (tid >= offset) {{
+            add = temp[tid - offset];
+        }}
+        workgroupBarrier();
+        temp[tid] = temp[tid] + add;
+        workgroupBarrier();
+    }}
+
+    if (i < n) {{
+        incl[i] = temp[tid];
+    }}
+    if (tid == {wg}u - 1u) {{
+        block_sums[wg_id.x] = temp[tid];
+    }}
+}}
+"#
+    )
+}
+
+/// Buffers: incl, offsets (inclusive scan of block sums), result,
+/// params (n, exclusive, has_offsets, 0)
+fn emit_scan_finalize(dtype: u8) -> String {
+    let t = dtype_to_wgsl(dtype);
+    let fn_name = format!("rayzor_scan_finalize_{t}");
+    let wg = PRIMITIVE_WORKGROUP_SIZE;
+
+    format!(
+        r#"@group(0) @binding(0) var<storage, read> incl: array<{t}>;
+@group(0) @binding(1) var<storage, read> offsets: array<{t}>;
+@group(0) @binding(2) var<storage, read_write> result: array<{t}>;
+@group(0) @binding(3) var<uniform> params: vec4<u32>;
+
+fn scanned(j: u32) -> {t} {{
+    let block = j / {wg}u;
+    var v = incl[j];
+    if (params.z != 0u && block > 0u) {{
+        v = v + offsets[block - 1u];
+    }}
+    return v;
+}}
+
+@compute @workgroup_size({wg})
+fn {fn_name}(@builtin(global_invocation_id) global_id: vec3<u32>) {{
+    let i = global_id.x;
+    if (i >= params.x) {{
+        return;
+    }}
+    if (params.y == 0u) {{
+        result[i] = scanned(i);
+    }} else if (i == 0u) {{
+        result[i] = {t}(0);
+    }} else {{
+        result[i] = scanned(i - 1u);
+    }}
+}}
+"#
+    )
+}
+
+/// Buffers: input, bins (atomic u32), params (n, num_bins, bits(min), bits(max))
+fn emit_histogram(dtype: u8) -> String {
+    let t = dtype_to_wgsl(dtype);
+    let fn_name = format!("rayzor_histogram_{t}");
+    let wg = PRIMITIVE_WORKGROUP_SIZE;
+
+    format!(
+        r#"@group(0) @binding(0) var<storage, read> input: array<{t}>;
+@group(0) @binding(1) var<storage, read_write> bins: array<atomic<u32>>;
+@group(0) @binding(2) var<uniform> params: vec4<u32>;
+
+@compute @workgroup_size({wg})
+fn {fn_name}(@builtin(global_invocation_id) global_id: vec3<u32>) {{
+    let i = global_id.x;
+    if (i >= params.x) {{
+        return;
+    }}
+    let lo = bitcast<f32>(params.z);
+    let hi = bitcast<f32>(params.w);
+    let v = f32(input[i]);
+    // Values outside [lo, hi] (and NaN) are not counted
+    if (!(v >= lo && v <= hi)) {{
+        return;
+    }}
+    let num_bins = params.y;
+    let b = min(u32((v - lo) / (hi - lo) * f32(num_bins)), num_bins - 1u);
+    atomicAdd(&bins[b], 1u);
+}}
+"#
+    )
+}
+
+/// Buffers: input, offsets (i32, num_segments + 1), output,
+/// params (num_segments, 0, 0, 0)
+fn emit_segment_reduce(op: KernelOp, dtype: u8) -> String {
+    let t = dtype_to_wgsl(dtype);
+    let fn_name = format!("rayzor_{}_{}", op.name(), t);
+    let wg = PRIMITIVE_WORKGROUP_SIZE;
+    let (lowest, highest) = limits(dtype);
+    let (identity, combine) = match op {
+        KernelOp::SegmentSum => (format!("{t}(0)"), "a + b"),
+        KernelOp::SegmentMax => (format!("{t}({lowest})"), "max(a, b)"),
+        KernelOp::SegmentMin => (format!("{t}({highest})"), "min(a, b)"),
+        _ => unreachable!("not a segmented reduction"),
+    };
+
+    format!(
+        r#"@group(0) @binding(0) var<storage, read> input: array<{t}>;
+@group(0) @binding(1) var<storage, read> offsets: array<i32>;
+@group(0) @binding(2) var<storage, read_write> output: array<{t}>;
+@group(0) @binding(3) var<uniform> params: vec4<u32>;
+
+var<workgroup> shared_data: array<{t}, {wg}>;
+
+fn combine(a: {t}, b: {t}) -> {t} {{
+    return {combine};
+}}
+
+@compute @workgroup_size({wg})
+fn {fn_name}(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) wg_id: vec3<u32>,
+    @builtin(num_workgroups) num_wgs: vec3<u32>
+) {{
+    let tid = local_id.x;
+    for (var seg = wg_id.x; seg < params.x; seg = seg + num_wgs.x) {{
+        let start = u32(offsets[seg]);
+        let end = u32(offsets[seg + 1u]);
+        var acc = {identity};
+        for (var i = start + tid; i < end; i = i + {wg}u) {{
+            acc = combine(acc, input[i]);
+        }}
+        shared_data[tid] = acc;
+        workgroupBarrier();
+
+        for (var s = {wg}u / 2u; s > 0u; s = s >> 1u) {{
+            if (tid < s) {{
+                shared_data[tid] = combine(shared_data[tid], shared_data[tid + s]);
+            }}
+            workgroupBarrier();
+        }}
+
+        if (tid == 0u) {{
+            output[seg] = shared_data[0];
+        }}
+        workgroupBarrier();
+    }}
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_argmax_f32() {
+        let src = emit_primitive(KernelOp::ArgMax, buffer::DTYPE_F32);
+        assert!(src.contains("fn rayzor_argmax_f32"));
+        assert!(src.contains("a > b || (a == b && ai < bi)"));
+        assert!(src.contains("var<storage, read_write> out_idx: array<u32>"));
+    }
+
+    #[test]
+    fn test_scan_passes() {
+        let block = emit_primitive(KernelOp::ScanBlock, buffer::DTYPE_I32);
+        assert!(block.contains("fn rayzor_scan_block_i32"));
+        assert!(block.contains("block_sums[wg_id.x] = temp[tid]"));
+        let fin = emit_primitive(KernelOp::ScanFinalize, buffer::DTYPE_F32);
+        assert!(fin.contains("result[i] = scanned(i - 1u)"));
+    }
+
+    #[test]
+    fn test_histogram_and_segments() {
+        let hist = emit_primitive(KernelOp::Histogram, buffer::DTYPE_F32);
+        assert!(hist.contains("array<atomic<u32>>"));
+        assert!(hist.contains("atomicAdd(&bins[b], 1u)"));
+        let seg = emit_primitive(KernelOp::SegmentMin, buffer::DTYPE_I32);
+        assert!(seg.contains("fn rayzor_segment_min_i32"));
+        assert!(seg.contains("var acc = i32(2147483647)"));
+    }
+}
@@ -0,0 +1,101 @@
+//! WGSL code generation for counter-based random fills.
+//!
+//! Philox4x32-10 keyed by the 64-bit seed, with counter `(i, 0, 0, 0)` for
+//! output element `i`, so every element is independent of the dispatch
+//! shape. WGSL has no 64-bit integers; the 32×32 high product is built from
+//! 16-bit halves. Must stay bit-for-bit in sync with `random::philox4x32`.
+
+use crate::kernel_ir::KernelOp;
+
+/// Philox rounds, multipliers and key schedule shared by both ops.
+const PHILOX: &str = r#"const PHILOX_M0: u32 = 0xD2511F53u;
+const PHILOX_M1: u32 = 0xCD9E8D57u;
+const PHILOX_W0: u32 = 0x9E3779B9u;
+const PHILOX_W1: u32 = 0xBB67AE85u;
+
+fn mulhi(a: u32, b: u32) -> u32 {
+    let a_lo = a & 0xffffu;
+    let a_hi = a >> 16u;
+    let b_lo = b & 0xffffu;
+    let b_hi = b >> 16u;
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let cross = (lo_lo >> 16u) + (hi_lo & 0xffffu) + a_lo * b_hi;
+    return a_hi * b_hi + (hi_lo >> 16u) + (cross >> 16u);
+}
+
+fn philox4x32(counter: vec4<u32>, seed: vec2<u32>) -> vec4<u32> {
+    var c = counter;
+    var k = seed;
+    for (var round = 0u; round < 10u; round = round + 1u) {
+        let hi0 = mulhi(PHILOX_M0, c.x);
+        let lo0 = PHILOX_M0 * c.x;
+        let hi1 = mulhi(PHILOX_M1, c.z);
+        let lo1 = PHILOX_M1 * c.z;
+        c = vec4<u32>(hi1 ^ c.y ^ k.x, lo1, hi0 ^ c.w ^ k.y, lo0);
+        k = k + vec2<u32>(PHILOX_W0, PHILOX_W1);
+    }
+    return c;
+}
+
+// Top 24 bits as a float in [0, 1)
+fn to_unit(x: u32) -> f32 {
+    return f32(x >> 8u) * 5.9604645e-8;
+}
+"#;
+
+/// Generate WGSL source for a random fill op.
+///
+/// Buffers: output (f32), params (numel, seed_lo, seed_hi, 0)
+pub fn emit_random_kernel(op: KernelOp) -> String {
+    let body = match op {
+        KernelOp::RandomUniform => "output[i] = to_unit(r.x);",
+        KernelOp::RandomNormal => {
+            // Box–Muller; u1 is shifted into (0, 1] so log(u1) is finite
+            r#"let u1 = to_unit(r.x) + 5.9604645e-8;
+        let u2 = to_unit(r.y);
+        output[i] = sqrt(-2.0 * log(u1)) * cos(6.2831855 * u2);"#
+        }
+        _ => unreachable!("not a random op"),
+    };
+
+    format!(
+        r#"@group(0) @binding(0) var<storage, read_write> output: array<f32>;
+@group(0) @binding(1) var<uniform> params: vec4<u32>;
+
+{PHILOX}
+@compute @workgroup_size(256)
+fn rayzor_{name}_f32(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) num_wgs: vec3<u32>
+) {{
+    let stride = 256u * num_wgs.x;
+    for (var i = global_id.x; i < params.x; i = i + stride) {{
+        let r = philox4x32(vec4<u32>(i, 0u, 0u, 0u), params.yz);
+        {body}
+    }}
+}}
+"#,
+        name = op.name()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_uniform() {
+        let src = emit_random_kernel(KernelOp::RandomUniform);
+        assert!(src.contains("fn rayzor_random_uniform_f32"));
+        assert!(src.contains("fn philox4x32(counter: vec4<u32>, seed: vec2<u32>)"));
+        assert!(src.contains("output[i] = to_unit(r.x);"));
+    }
+
+    #[test]
+    fn test_random_normal() {
+        let src = emit_random_kernel(KernelOp::RandomNormal);
+        assert!(src.contains("fn rayzor_random_normal_f32"));
+        assert!(src.contains("sqrt(-2.0 * log(u1)) * cos(6.2831855 * u2)"));
+    }
+}
@@ -3,7 +3,10 @@
 use std::collections::HashMap;
 use std::rc::Rc;
 
-use crate::backend::{NativeCompiledKernel, NativeContext};
+use rayzor_runtime::haxe_array::{haxe_array_new, haxe_array_push_i64, HaxeArray};
+use rayzor_runtime::haxe_sys::haxe_string_from_string;
+
+use crate::backend::{DeviceInfo, NativeBuffer, NativeCompiledKernel, NativeContext};
 use crate::kernel_cache::KernelCache;
 
 /// Opaque GPU context handle passed as i64 through the JIT ABI.
@@ -14,6 +17,44 @@ pub struct GpuContext {
     pub(crate) kernel_cache: KernelCache,
     /// Cache for fused kernels, keyed by (structural_hash, dtype).
     pub(crate) fused_cache: HashMap<(u64, u8), Rc<NativeCompiledKernel>>,
+    /// Most recent error, surfaced to Haxe via `GPUCompute.lastError()`.
+    pub(crate) last_error: Option<String>,
+}
+
+impl GpuContext {
+    pub(crate) fn new(inner: NativeContext) -> Self {
+        GpuContext {
+            inner,
+            kernel_cache: KernelCache::new(),
+            fused_cache: HashMap::new(),
+            last_error: None,
+        }
+    }
+
+    /// Fail if `buf` lives on a different device than this context.
+    ///
+    /// Buffers are bound to the device that allocated them; dispatching one
+    /// from another context is an error on every backend.
+    pub(crate) fn check_affinity(&mut self, buf: &NativeBuffer) -> Result<(), String> {
+        if buf.belongs_to(&self.inner) {
+            return Ok(());
+        }
+        let device = self
+            .inner
+            .device_info()
+            .map_or_else(|| "unknown".to_string(), |info| info.name);
+        let msg = format!(
+            "GPU buffer belongs to a different device than this context ({}); \
+             buffers cannot be shared between GPUCompute instances",
+            device
+        );
+        self.last_error = Some(msg.clone());
+        Err(msg)
+    }
+}
+
+fn into_handle(ctx: NativeContext) -> i64 {
+    Box::into_raw(Box::new(GpuContext::new(ctx))) as i64
 }
 
 // ---------------------------------------------------------------------------
@@ -25,15 +66,61 @@ pub struct GpuContext {
 #[no_mangle]
 pub extern "C" fn rayzor_gpu_compute_create() -> i64 {
     match NativeContext::new() {
-        Some(ctx) => {
-            let gpu_ctx = GpuContext {
-                inner: ctx,
-                kernel_cache: KernelCache::new(),
-                fused_cache: HashMap::new(),
-            };
-            let boxed = Box::new(gpu_ctx);
-            Box::into_raw(boxed) as i64
-        }
+        Some(ctx) => into_handle(ctx),
+        None => 0,
+    }
+}
+
+/// Create a GPU compute context on the device at `index` in
+/// `enumerateDevices()`. Returns 0 if there is no such device.
+#[no_mangle]
+pub extern "C" fn rayzor_gpu_compute_create_on_device(index: i64) -> i64 {
+    if index < 0 {
+        return 0;
+    }
+    match NativeContext::new_on_device(index as usize) {
+        Some(ctx) => into_handle(ctx),
+        None => 0,
+    }
+}
+
+/// List the available devices as an `Array<GpuDeviceInfo>`.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_enumerate_devices() -> i64 {
+    let arr_layout = std::alloc::Layout::new::<HaxeArray>();
+    let arr = std::alloc::alloc(arr_layout) as *mut HaxeArray;
+    if arr.is_null() {
+        return 0;
+    }
+    haxe_array_new(arr, std::mem::size_of::<i64>());
+    for info in NativeContext::enumerate_devices() {
+        haxe_array_push_i64(arr, Box::into_raw(Box::new(info)) as i64);
+    }
+    arr as i64
+}
+
+/// Describe the device this context runs on.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_device(ctx: i64) -> i64 {
+    if ctx == 0 {
+        return 0;
+    }
+    let gpu_ctx = &*(ctx as *const GpuContext);
+    match gpu_ctx.inner.device_info() {
+        Some(info) => Box::into_raw(Box::new(info)) as i64,
+        None => 0,
+    }
+}
+
+/// Most recent error message for this context, or null if none.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_last_error(ctx: i64) -> i64 {
+    if ctx == 0 {
+        return 0;
+    }
+    let gpu_ctx = &*(ctx as *const GpuContext);
+    match &gpu_ctx.last_error {
+        Some(msg) => haxe_string_from_string(msg.as_ptr(), msg.len()) as i64,
         None => 0,
     }
 }
@@ -57,3 +144,84 @@ pub extern "C" fn rayzor_gpu_compute_is_available() -> i8 {
         0
     }
 }
+
+// ---------------------------------------------------------------------------
+// Extern C API — GpuDeviceInfo instance methods
+// ---------------------------------------------------------------------------
+
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_device_name(info: i64) -> i64 {
+    if info == 0 {
+        return 0;
+    }
+    let name = &(*(info as *const DeviceInfo)).name;
+    haxe_string_from_string(name.as_ptr(), name.len()) as i64
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_device_type(info: i64) -> i64 {
+    if info == 0 {
+        return 0;
+    }
+    (*(info as *const DeviceInfo)).device_type
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_device_memory(info: i64) -> i64 {
+    if info == 0 {
+        return 0;
+    }
+    (*(info as *const DeviceInfo)).memory as i64
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_device_backend(info: i64) -> i64 {
+    if info == 0 {
+        return 0;
+    }
+    let backend = &(*(info as *const DeviceInfo)).backend;
+    haxe_string_from_string(backend.as_ptr(), backend.len()) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enumerate_and_create_on_device() {
+        let devices = NativeContext::enumerate_devices();
+        if devices.is_empty() {
+            assert_eq!(rayzor_gpu_compute_create_on_device(0), 0);
+            return;
+        }
+        let ctx = rayzor_gpu_compute_create_on_device(0);
+        assert_ne!(ctx, 0);
+        assert_eq!(rayzor_gpu_compute_create_on_device(devices.len() as i64), 0);
+        unsafe { rayzor_gpu_compute_destroy(ctx) };
+    }
+
+    #[test]
+    fn test_buffer_affinity_across_contexts() {
+        if NativeContext::enumerate_devices().is_empty() {
+            return;
+        }
+        let a = rayzor_gpu_compute_create_on_device(0);
+        let b = rayzor_gpu_compute_create_on_device(0);
+        unsafe {
+            let ctx_a = &mut *(a as *mut GpuContext);
+            let ctx_b = &mut *(b as *mut GpuContext);
+            let buf = ctx_a.inner.allocate_buffer(16).unwrap();
+            assert!(ctx_a.check_affinity(&buf).is_ok());
+            let shared = buf.belongs_to(&ctx_b.inner);
+            assert_eq!(ctx_b.check_affinity(&buf).is_ok(), shared);
+            assert_eq!(ctx_b.last_error.is_some(), !shared);
+            // wgpu resources never cross `wgpu::Device`s, even on one adapter
+            #[cfg(feature = "webgpu-backend")]
+            if matches!(ctx_b.inner, NativeContext::Wgpu(_)) {
+                assert!(!shared);
+            }
+            rayzor_gpu_compute_destroy(a);
+            rayzor_gpu_compute_destroy(b);
+        }
+    }
+}
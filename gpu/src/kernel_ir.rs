@@ -27,6 +27,25 @@ pub enum KernelOp {
 
     // Linear algebra
     Matmul,
+
+    // Image ops over f32 texel buffers (see texture.rs)
+    Conv2d,
+    Resize,
+
+    // Primitives (see ops.rs): index-carrying reductions, scan passes,
+    // histogram and per-segment reductions over an offsets buffer
+    ArgMax,
+    ArgMin,
+    ScanBlock,
+    ScanFinalize,
+    Histogram,
+    SegmentSum,
+    SegmentMax,
+    SegmentMin,
+
+    // Counter-based random fills (see random.rs)
+    RandomUniform,
+    RandomNormal,
 }
 
 impl KernelOp {
@@ -37,6 +56,14 @@ impl KernelOp {
             Self::Neg | Self::Abs | Self::Sqrt | Self::Exp | Self::Log | Self::Relu => 1,
             Self::ReduceSum | Self::ReduceMax | Self::ReduceMin => 1,
             Self::Matmul => 2,
+            Self::Conv2d => 2,
+            Self::Resize => 1,
+            Self::ArgMax | Self::ArgMin => 2,
+            Self::ScanBlock => 1,
+            Self::ScanFinalize => 2,
+            Self::Histogram => 1,
+            Self::SegmentSum | Self::SegmentMax | Self::SegmentMin => 2,
+            Self::RandomUniform | Self::RandomNormal => 0,
         }
     }
 
@@ -57,6 +84,18 @@ impl KernelOp {
             Self::ReduceMax => "reduce_max",
             Self::ReduceMin => "reduce_min",
             Self::Matmul => "matmul",
+            Self::Conv2d => "conv2d",
+            Self::Resize => "resize",
+            Self::ArgMax => "argmax",
+            Self::ArgMin => "argmin",
+            Self::ScanBlock => "scan_block",
+            Self::ScanFinalize => "scan_finalize",
+            Self::Histogram => "histogram",
+            Self::SegmentSum => "segment_sum",
+            Self::SegmentMax => "segment_max",
+            Self::SegmentMin => "segment_min",
+            Self::RandomUniform => "random_uniform",
+            Self::RandomNormal => "random_normal",
         }
     }
 
@@ -64,4 +103,29 @@ impl KernelOp {
     pub fn is_reduction(self) -> bool {
         matches!(self, Self::ReduceSum | Self::ReduceMax | Self::ReduceMin)
     }
+
+    /// Whether this op runs over a 2D texture grid (one thread per texel).
+    pub fn is_image(self) -> bool {
+        matches!(self, Self::Conv2d | Self::Resize)
+    }
+
+    /// Whether this op is a reduction/scan primitive with its own buffer layout.
+    pub fn is_primitive(self) -> bool {
+        matches!(
+            self,
+            Self::ArgMax
+                | Self::ArgMin
+                | Self::ScanBlock
+                | Self::ScanFinalize
+                | Self::Histogram
+                | Self::SegmentSum
+                | Self::SegmentMax
+                | Self::SegmentMin
+        )
+    }
+
+    /// Whether this op fills its output from a counter-based RNG.
+    pub fn is_random(self) -> bool {
+        matches!(self, Self::RandomUniform | Self::RandomNormal)
+    }
 }
@@ -20,6 +20,9 @@ pub mod kernel_cache;
 pub mod kernel_ir;
 pub mod lazy;
 pub mod ops;
+pub mod random;
+pub mod texture;
+pub mod transfer;
 
 pub mod backend;
 
@@ -29,7 +32,7 @@ pub mod metal;
 #[cfg(feature = "webgpu-backend")]
 pub mod wgpu_backend;
 
-use rayzor_plugin::{declare_native_methods, NativeMethodDesc};
+use rayzor_plugin::{declare_native_methods, NativeMethodDesc, NativePluginInfo};
 use std::ffi::c_void;
 
 // ============================================================================
@@ -41,8 +44,12 @@ declare_native_methods! {
     // GPUCompute lifecycle (static)
     "rayzor_gpu_GPUCompute", "create",       static,   "rayzor_gpu_compute_create",        []              => Ptr;
     "rayzor_gpu_GPUCompute", "isAvailable",  static,   "rayzor_gpu_compute_is_available",  []              => Bool;
+    "rayzor_gpu_GPUCompute", "enumerateDevices", static, "rayzor_gpu_compute_enumerate_devices", []          => Ptr;
+    "rayzor_gpu_GPUCompute", "createOnDevice", static, "rayzor_gpu_compute_create_on_device", [I64]         => Ptr;
     // GPUCompute instance methods (self = Ptr is first param)
     "rayzor_gpu_GPUCompute", "destroy",      instance, "rayzor_gpu_compute_destroy",       [Ptr]           => Void;
+    "rayzor_gpu_GPUCompute", "device",       instance, "rayzor_gpu_compute_device",        [Ptr]           => Ptr;
+    "rayzor_gpu_GPUCompute", "lastError",    instance, "rayzor_gpu_compute_last_error",    [Ptr]           => Ptr;
     "rayzor_gpu_GPUCompute", "createBuffer", instance, "rayzor_gpu_compute_create_buffer", [Ptr, Ptr]      => Ptr;
     "rayzor_gpu_GPUCompute", "allocBuffer",  instance, "rayzor_gpu_compute_alloc_buffer",  [Ptr, I64, I64] => Ptr;
     "rayzor_gpu_GPUCompute", "toTensor",     instance, "rayzor_gpu_compute_to_tensor",     [Ptr, Ptr]      => Ptr;
@@ -68,14 +75,68 @@ declare_native_methods! {
     "rayzor_gpu_GPUCompute", "dot",          instance, "rayzor_gpu_compute_dot",           [Ptr, Ptr, Ptr] => F64;
     // Matmul: (self, a, b, m, k, n) -> GpuBuffer
     "rayzor_gpu_GPUCompute", "matmul",       instance, "rayzor_gpu_compute_matmul",        [Ptr, Ptr, Ptr, I64, I64, I64] => Ptr;
+    // Primitives: index reductions, scans, histogram, segmented reductions
+    "rayzor_gpu_GPUCompute", "argmax",        instance, "rayzor_gpu_compute_argmax",         [Ptr, Ptr]      => I64;
+    "rayzor_gpu_GPUCompute", "argmin",        instance, "rayzor_gpu_compute_argmin",         [Ptr, Ptr]      => I64;
+    "rayzor_gpu_GPUCompute", "inclusiveScan", instance, "rayzor_gpu_compute_inclusive_scan", [Ptr, Ptr]      => Ptr;
+    "rayzor_gpu_GPUCompute", "exclusiveScan", instance, "rayzor_gpu_compute_exclusive_scan", [Ptr, Ptr]      => Ptr;
+    "rayzor_gpu_GPUCompute", "histogram",     instance, "rayzor_gpu_compute_histogram",      [Ptr, Ptr, I64, F64, F64] => Ptr;
+    "rayzor_gpu_GPUCompute", "segmentedSum",  instance, "rayzor_gpu_compute_segment_sum",    [Ptr, Ptr, Ptr] => Ptr;
+    "rayzor_gpu_GPUCompute", "segmentedMax",  instance, "rayzor_gpu_compute_segment_max",    [Ptr, Ptr, Ptr] => Ptr;
+    "rayzor_gpu_GPUCompute", "segmentedMin",  instance, "rayzor_gpu_compute_segment_min",    [Ptr, Ptr, Ptr] => Ptr;
+    // Random fills: (self, buf, seed) — Philox4x32, deterministic per seed
+    "rayzor_gpu_GPUCompute", "randomUniform", instance, "rayzor_gpu_compute_random_uniform", [Ptr, Ptr, I64] => Void;
+    "rayzor_gpu_GPUCompute", "randomNormal",  instance, "rayzor_gpu_compute_random_normal",  [Ptr, Ptr, I64] => Void;
     // Structured buffer ops: (self, ...) -> result
     "rayzor_gpu_GPUCompute", "createStructBuffer", instance, "rayzor_gpu_compute_create_struct_buffer", [Ptr, Ptr, I64, I64] => Ptr;
     "rayzor_gpu_GPUCompute", "allocStructBuffer",  instance, "rayzor_gpu_compute_alloc_struct_buffer",  [Ptr, I64, I64]      => Ptr;
     "rayzor_gpu_GPUCompute", "readStructFloat",    instance, "rayzor_gpu_compute_read_struct_float",    [Ptr, Ptr, I64, I64, I64] => F64;
     "rayzor_gpu_GPUCompute", "readStructInt",      instance, "rayzor_gpu_compute_read_struct_int",      [Ptr, Ptr, I64, I64, I64] => I64;
+    // Layout-descriptor variants: the layout argument is `Class.layout()`
+    "rayzor_gpu_GPUCompute", "createLayoutBuffer", instance, "rayzor_gpu_compute_create_layout_buffer", [Ptr, Ptr, I64, Ptr] => Ptr;
+    "rayzor_gpu_GPUCompute", "allocLayoutBuffer",  instance, "rayzor_gpu_compute_alloc_layout_buffer",  [Ptr, I64, Ptr]      => Ptr;
+    "rayzor_gpu_GPUCompute", "readStructField",    instance, "rayzor_gpu_compute_read_struct_field",    [Ptr, Ptr, I64, Ptr, Ptr] => F64;
+    // Textures: row-major f32 texels; format 0 = RGBA8, 1 = F32
+    "rayzor_gpu_GPUCompute", "createTexture",      instance, "rayzor_gpu_compute_create_texture",       [Ptr, Ptr, I64, I64, I64] => Ptr;
+    "rayzor_gpu_GPUCompute", "allocTexture",       instance, "rayzor_gpu_compute_alloc_texture",        [Ptr, I64, I64, I64]      => Ptr;
+    "rayzor_gpu_GPUCompute", "readTexture",        instance, "rayzor_gpu_compute_read_texture",         [Ptr, Ptr]                => Ptr;
+    "rayzor_gpu_GPUCompute", "getPixel",           instance, "rayzor_gpu_compute_texture_get",          [Ptr, Ptr, I64, I64, I64] => F64;
+    "rayzor_gpu_GPUCompute", "setPixel",           instance, "rayzor_gpu_compute_texture_set",          [Ptr, Ptr, I64, I64, I64, F64] => Void;
+    "rayzor_gpu_GPUCompute", "convolve",           instance, "rayzor_gpu_compute_convolve",             [Ptr, Ptr, Ptr, I64, I64] => Ptr;
+    "rayzor_gpu_GPUCompute", "resize",             instance, "rayzor_gpu_compute_resize",               [Ptr, Ptr, I64, I64]      => Ptr;
+    "rayzor_gpu_GPUCompute", "freeTexture",        instance, "rayzor_gpu_compute_free_texture",         [Ptr, Ptr]                => Void;
     // GpuBuffer instance methods
     "rayzor_gpu_GpuBuffer",  "numel",        instance, "rayzor_gpu_compute_buffer_numel",  [Ptr]           => I64;
     "rayzor_gpu_GpuBuffer",  "dtype",        instance, "rayzor_gpu_compute_buffer_dtype",  [Ptr]           => I64;
+    // Pinned host memory, async transfers and double buffering
+    "rayzor_gpu_GPUCompute", "allocPinned",        instance, "rayzor_gpu_compute_alloc_pinned",         [Ptr, I64]                => Ptr;
+    "rayzor_gpu_GPUCompute", "freePinned",         instance, "rayzor_gpu_compute_free_pinned",          [Ptr, Ptr]                => Void;
+    "rayzor_gpu_GPUCompute", "uploadAsync",        instance, "rayzor_gpu_compute_upload_async",         [Ptr, Ptr, Ptr]           => Ptr;
+    "rayzor_gpu_GPUCompute", "downloadAsync",      instance, "rayzor_gpu_compute_download_async",       [Ptr, Ptr, Ptr]           => Ptr;
+    "rayzor_gpu_GPUCompute", "freeEvent",          instance, "rayzor_gpu_compute_free_event",           [Ptr, Ptr]                => Void;
+    "rayzor_gpu_GPUCompute", "createDoubleBuffer", instance, "rayzor_gpu_compute_create_double_buffer", [Ptr, I64, I64]           => Ptr;
+    "rayzor_gpu_GPUCompute", "present",            instance, "rayzor_gpu_compute_present",              [Ptr, Ptr]                => Bool;
+    "rayzor_gpu_GPUCompute", "freeDoubleBuffer",   instance, "rayzor_gpu_compute_free_double_buffer",   [Ptr, Ptr]                => Void;
+    // GpuDeviceInfo instance methods
+    "rayzor_gpu_GpuDeviceInfo", "name",       instance, "rayzor_gpu_device_name",         [Ptr]           => Ptr;
+    "rayzor_gpu_GpuDeviceInfo", "deviceType", instance, "rayzor_gpu_device_type",         [Ptr]           => I64;
+    "rayzor_gpu_GpuDeviceInfo", "memory",     instance, "rayzor_gpu_device_memory",       [Ptr]           => I64;
+    "rayzor_gpu_GpuDeviceInfo", "backend",    instance, "rayzor_gpu_device_backend",      [Ptr]           => Ptr;
+    // PinnedBuffer / GpuEvent / DoubleBuffer instance methods
+    "rayzor_gpu_PinnedBuffer", "length",      instance, "rayzor_gpu_pinned_length",       [Ptr]           => I64;
+    "rayzor_gpu_PinnedBuffer", "isPageLocked", instance, "rayzor_gpu_pinned_is_locked",   [Ptr]           => Bool;
+    "rayzor_gpu_PinnedBuffer", "getFloat",    instance, "rayzor_gpu_pinned_get_float",    [Ptr, I64]      => F64;
+    "rayzor_gpu_PinnedBuffer", "setFloat",    instance, "rayzor_gpu_pinned_set_float",    [Ptr, I64, F64] => Void;
+    "rayzor_gpu_PinnedBuffer", "getInt",      instance, "rayzor_gpu_pinned_get_int",      [Ptr, I64]      => I64;
+    "rayzor_gpu_PinnedBuffer", "setInt",      instance, "rayzor_gpu_pinned_set_int",      [Ptr, I64, I64] => Void;
+    "rayzor_gpu_GpuEvent",     "isComplete",  instance, "rayzor_gpu_event_is_complete",   [Ptr]           => Bool;
+    "rayzor_gpu_GpuEvent",     "wait",        instance, "rayzor_gpu_event_wait",          [Ptr]           => Bool;
+    "rayzor_gpu_DoubleBuffer", "back",        instance, "rayzor_gpu_double_buffer_back",  [Ptr]           => Ptr;
+    "rayzor_gpu_DoubleBuffer", "front",       instance, "rayzor_gpu_double_buffer_front", [Ptr]           => Ptr;
+    // GpuTexture instance methods
+    "rayzor_gpu_GpuTexture", "width",        instance, "rayzor_gpu_texture_width",         [Ptr]           => I64;
+    "rayzor_gpu_GpuTexture", "height",       instance, "rayzor_gpu_texture_height",        [Ptr]           => I64;
+    "rayzor_gpu_GpuTexture", "format",       instance, "rayzor_gpu_texture_format",        [Ptr]           => I64;
 }
 
 // ============================================================================
@@ -121,6 +182,26 @@ pub unsafe extern "C" fn rayzor_gpu_plugin_describe(
     GPU_METHODS.as_ptr()
 }
 
+/// Compute backends compiled into this build.
+const BACKENDS: &str = if cfg!(all(feature = "metal-backend", feature = "webgpu-backend")) {
+    "metal,webgpu"
+} else if cfg!(feature = "metal-backend") {
+    "metal"
+} else if cfg!(feature = "webgpu-backend") {
+    "webgpu"
+} else {
+    ""
+};
+
+static PLUGIN_INFO: NativePluginInfo = NativePluginInfo::new(env!("CARGO_PKG_VERSION"), BACKENDS);
+
+/// Plugin build information (ABI version, crate version, compiled-in
+/// backends), reported by `rayzor doctor`.
+#[no_mangle]
+pub extern "C" fn rayzor_gpu_plugin_info() -> *const NativePluginInfo {
+    &PLUGIN_INFO
+}
+
 /// Rust-callable API returning runtime symbols.
 pub fn get_runtime_symbols() -> Vec<(&'static str, *const u8)> {
     vec![
@@ -137,6 +218,39 @@ pub fn get_runtime_symbols() -> Vec<(&'static str, *const u8)> {
             "rayzor_gpu_compute_is_available",
             device::rayzor_gpu_compute_is_available as *const u8,
         ),
+        // Device selection
+        (
+            "rayzor_gpu_compute_create_on_device",
+            device::rayzor_gpu_compute_create_on_device as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_enumerate_devices",
+            device::rayzor_gpu_compute_enumerate_devices as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_device",
+            device::rayzor_gpu_compute_device as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_last_error",
+            device::rayzor_gpu_compute_last_error as *const u8,
+        ),
+        (
+            "rayzor_gpu_device_name",
+            device::rayzor_gpu_device_name as *const u8,
+        ),
+        (
+            "rayzor_gpu_device_type",
+            device::rayzor_gpu_device_type as *const u8,
+        ),
+        (
+            "rayzor_gpu_device_memory",
+            device::rayzor_gpu_device_memory as *const u8,
+        ),
+        (
+            "rayzor_gpu_device_backend",
+            device::rayzor_gpu_device_backend as *const u8,
+        ),
         // Buffer management
         (
             "rayzor_gpu_compute_create_buffer",
@@ -231,6 +345,48 @@ pub fn get_runtime_symbols() -> Vec<(&'static str, *const u8)> {
             "rayzor_gpu_compute_matmul",
             ops::rayzor_gpu_compute_matmul as *const u8,
         ),
+        // Primitives
+        (
+            "rayzor_gpu_compute_argmax",
+            ops::rayzor_gpu_compute_argmax as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_argmin",
+            ops::rayzor_gpu_compute_argmin as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_inclusive_scan",
+            ops::rayzor_gpu_compute_inclusive_scan as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_exclusive_scan",
+            ops::rayzor_gpu_compute_exclusive_scan as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_histogram",
+            ops::rayzor_gpu_compute_histogram as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_segment_sum",
+            ops::rayzor_gpu_compute_segment_sum as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_segment_max",
+            ops::rayzor_gpu_compute_segment_max as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_segment_min",
+            ops::rayzor_gpu_compute_segment_min as *const u8,
+        ),
+        // Random fills
+        (
+            "rayzor_gpu_compute_random_uniform",
+            random::rayzor_gpu_compute_random_uniform as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_random_normal",
+            random::rayzor_gpu_compute_random_normal as *const u8,
+        ),
         // Structured buffer ops
         (
             "rayzor_gpu_compute_create_struct_buffer",
@@ -248,6 +404,136 @@ pub fn get_runtime_symbols() -> Vec<(&'static str, *const u8)> {
             "rayzor_gpu_compute_read_struct_int",
             buffer::rayzor_gpu_compute_read_struct_int as *const u8,
         ),
+        (
+            "rayzor_gpu_compute_create_layout_buffer",
+            buffer::rayzor_gpu_compute_create_layout_buffer as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_alloc_layout_buffer",
+            buffer::rayzor_gpu_compute_alloc_layout_buffer as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_read_struct_field",
+            buffer::rayzor_gpu_compute_read_struct_field as *const u8,
+        ),
+        // Textures
+        (
+            "rayzor_gpu_compute_create_texture",
+            texture::rayzor_gpu_compute_create_texture as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_alloc_texture",
+            texture::rayzor_gpu_compute_alloc_texture as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_read_texture",
+            texture::rayzor_gpu_compute_read_texture as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_texture_get",
+            texture::rayzor_gpu_compute_texture_get as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_texture_set",
+            texture::rayzor_gpu_compute_texture_set as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_convolve",
+            texture::rayzor_gpu_compute_convolve as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_resize",
+            texture::rayzor_gpu_compute_resize as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_free_texture",
+            texture::rayzor_gpu_compute_free_texture as *const u8,
+        ),
+        (
+            "rayzor_gpu_texture_width",
+            texture::rayzor_gpu_texture_width as *const u8,
+        ),
+        (
+            "rayzor_gpu_texture_height",
+            texture::rayzor_gpu_texture_height as *const u8,
+        ),
+        (
+            "rayzor_gpu_texture_format",
+            texture::rayzor_gpu_texture_format as *const u8,
+        ),
+        // Pinned memory and async transfers
+        (
+            "rayzor_gpu_compute_alloc_pinned",
+            transfer::rayzor_gpu_compute_alloc_pinned as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_free_pinned",
+            transfer::rayzor_gpu_compute_free_pinned as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_upload_async",
+            transfer::rayzor_gpu_compute_upload_async as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_download_async",
+            transfer::rayzor_gpu_compute_download_async as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_free_event",
+            transfer::rayzor_gpu_compute_free_event as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_create_double_buffer",
+            transfer::rayzor_gpu_compute_create_double_buffer as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_present",
+            transfer::rayzor_gpu_compute_present as *const u8,
+        ),
+        (
+            "rayzor_gpu_compute_free_double_buffer",
+            transfer::rayzor_gpu_compute_free_double_buffer as *const u8,
+        ),
+        (
+            "rayzor_gpu_pinned_length",
+            transfer::rayzor_gpu_pinned_length as *const u8,
+        ),
+        (
+            "rayzor_gpu_pinned_is_locked",
+            transfer::rayzor_gpu_pinned_is_locked as *const u8,
+        ),
+        (
+            "rayzor_gpu_pinned_get_float",
+            transfer::rayzor_gpu_pinned_get_float as *const u8,
+        ),
+        (
+            "rayzor_gpu_pinned_set_float",
+            transfer::rayzor_gpu_pinned_set_float as *const u8,
+        ),
+        (
+            "rayzor_gpu_pinned_get_int",
+            transfer::rayzor_gpu_pinned_get_int as *const u8,
+        ),
+        (
+            "rayzor_gpu_pinned_set_int",
+            transfer::rayzor_gpu_pinned_set_int as *const u8,
+        ),
+        (
+            "rayzor_gpu_event_is_complete",
+            transfer::rayzor_gpu_event_is_complete as *const u8,
+        ),
+        (
+            "rayzor_gpu_event_wait",
+            transfer::rayzor_gpu_event_wait as *const u8,
+        ),
+        (
+            "rayzor_gpu_double_buffer_back",
+            transfer::rayzor_gpu_double_buffer_back as *const u8,
+        ),
+        (
+            "rayzor_gpu_double_buffer_front",
+            transfer::rayzor_gpu_double_buffer_front as *const u8,
+        ),
     ]
 }
 
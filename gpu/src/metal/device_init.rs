@@ -2,7 +2,9 @@
 
 use objc2::rc::Retained;
 use objc2::runtime::ProtocolObject;
-use objc2_metal::{MTLCommandQueue, MTLCreateSystemDefaultDevice, MTLDevice};
+use objc2_metal::{MTLCommandQueue, MTLCopyAllDevices, MTLCreateSystemDefaultDevice, MTLDevice};
+
+use crate::backend::{DeviceInfo, DEVICE_DISCRETE, DEVICE_INTEGRATED};
 
 // MTLCreateSystemDefaultDevice requires CoreGraphics to be linked
 #[link(name = "CoreGraphics", kind = "framework")]
@@ -25,12 +27,51 @@ impl MetalContext {
         })
     }
 
+    /// Create a Metal context on the `index`-th device of `MTLCopyAllDevices()`.
+    pub fn new_on_device(index: usize) -> Option<Self> {
+        let device = MTLCopyAllDevices().to_vec().into_iter().nth(index)?;
+        let command_queue = device.newCommandQueue()?;
+        Some(MetalContext {
+            device,
+            command_queue,
+        })
+    }
+
+    /// Describe every Metal device on this system.
+    pub fn enumerate() -> Vec<DeviceInfo> {
+        MTLCopyAllDevices()
+            .to_vec()
+            .iter()
+            .map(|device| device_info(device))
+            .collect()
+    }
+
+    /// Describe the device this context runs on.
+    pub fn info(&self) -> DeviceInfo {
+        device_info(&self.device)
+    }
+
     /// Check if Metal is available on this system.
     pub fn is_available() -> bool {
         MTLCreateSystemDefaultDevice().is_some()
     }
 }
 
+fn device_info(device: &ProtocolObject<dyn MTLDevice>) -> DeviceInfo {
+    // Apple silicon and Intel iGPUs share system memory; eGPUs are removable
+    let device_type = if device.hasUnifiedMemory() && !device.isRemovable() {
+        DEVICE_INTEGRATED
+    } else {
+        DEVICE_DISCRETE
+    };
+    DeviceInfo {
+        name: device.name().to_string(),
+        device_type,
+        memory: device.recommendedMaxWorkingSetSize(),
+        backend: "metal".to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -549,6 +549,411 @@ pub unsafe extern "C" fn rayzor_gpu_compute_matmul(
     matmul_impl(ctx, a, b, m as usize, k as usize, n as usize)
 }
 
+// ---------------------------------------------------------------------------
+// Internal helpers — Primitives (argmax/argmin, scan, histogram, segments)
+// ---------------------------------------------------------------------------
+
+/// Workgroup size of the primitive kernels (see `codegen::*_primitives`).
+const PRIMITIVE_WG_SIZE: usize = 256;
+
+/// Largest 1D grid every backend accepts.
+pub(crate) const MAX_GROUPS: usize = 65535;
+
+/// Dispatch `groups` 1D workgroups of `PRIMITIVE_WG_SIZE` threads, binding
+/// `buffers` in order.
+#[allow(unused_variables)]
+fn dispatch_grid(
+    ctx: &NativeContext,
+    compiled: &NativeCompiledKernel,
+    buffers: &[&NativeBuffer],
+    groups: usize,
+) -> Result<(), String> {
+    if groups > MAX_GROUPS {
+        return Err(format!(
+            "input too large: needs {} workgroups (max {})",
+            groups, MAX_GROUPS
+        ));
+    }
+    match (ctx, compiled) {
+        #[cfg(feature = "metal-backend")]
+        (NativeContext::Metal(metal_ctx), NativeCompiledKernel::Metal(kernel)) => {
+            use crate::metal::dispatch;
+            use objc2_metal::MTLSize;
+
+            let mut bufs = Vec::with_capacity(buffers.len());
+            for buf in buffers {
+                match buf {
+                    NativeBuffer::Metal(mb) => bufs.push(mb),
+                    _ => return Err("buffer not Metal".into()),
+                }
+            }
+            dispatch::dispatch_threadgroups(
+                metal_ctx,
+                kernel,
+                &bufs,
+                MTLSize {
+                    width: groups,
+                    height: 1,
+                    depth: 1,
+                },
+                MTLSize {
+                    width: PRIMITIVE_WG_SIZE,
+                    height: 1,
+                    depth: 1,
+                },
+            )
+        }
+        #[cfg(feature = "webgpu-backend")]
+        (NativeContext::Wgpu(wgpu_ctx), NativeCompiledKernel::Wgpu(kernel)) => {
+            use crate::wgpu_backend::dispatch;
+
+            let mut bufs = Vec::with_capacity(buffers.len());
+            for buf in buffers {
+                match buf {
+                    NativeBuffer::Wgpu(wb) => bufs.push(wb),
+                    _ => return Err("buffer not wgpu".into()),
+                }
+            }
+            dispatch::dispatch_workgroups(wgpu_ctx, kernel, &bufs, (groups, 1, 1))
+        }
+        _ => Err("backend mismatch".into()),
+    }
+}
+
+/// Compile (or fetch) a primitive kernel and dispatch it.
+pub(crate) fn run_primitive(
+    gpu_ctx: &mut GpuContext,
+    op: KernelOp,
+    dtype: u8,
+    buffers: &[&NativeBuffer],
+    groups: usize,
+) -> Result<(), String> {
+    let cached = gpu_ctx
+        .kernel_cache
+        .get_or_compile(&gpu_ctx.inner, op, dtype)?;
+    dispatch_grid(&gpu_ctx.inner, &cached.compiled, buffers, groups)
+}
+
+fn alloc(gpu_ctx: &GpuContext, byte_size: usize) -> Result<NativeBuffer, String> {
+    gpu_ctx
+        .inner
+        .allocate_buffer(byte_size.max(4))
+        .ok_or_else(|| "failed to allocate GPU buffer".to_string())
+}
+
+pub(crate) fn params(gpu_ctx: &GpuContext, values: [u32; 4]) -> Result<NativeBuffer, String> {
+    gpu_ctx
+        .inner
+        .buffer_from_value(&values)
+        .ok_or_else(|| "failed to allocate params buffer".to_string())
+}
+
+/// Record `err` on the context and return the null handle.
+fn fail(gpu_ctx: &mut GpuContext, err: String) -> i64 {
+    gpu_ctx.last_error = Some(err);
+    0
+}
+
+/// Materialize a primitive's input; only Float32 and Int32 are supported.
+unsafe fn primitive_input<'a>(
+    gpu_ctx: &mut GpuContext,
+    buf: i64,
+    what: &str,
+) -> Result<&'a GpuBuffer, String> {
+    if buf == 0 {
+        return Err(format!("{}: null buffer", what));
+    }
+    let a_buf = &mut *(buf as *mut GpuBuffer);
+    a_buf.ensure_materialized(gpu_ctx)?;
+    if !matches!(a_buf.dtype, buffer::DTYPE_F32 | buffer::DTYPE_I32) {
+        return Err(format!(
+            "{}: only Float32 and Int32 buffers are supported",
+            what
+        ));
+    }
+    if a_buf.numel == 0 {
+        return Err(format!("{}: empty buffer", what));
+    }
+    Ok(a_buf)
+}
+
+/// Two-pass argmax/argmin; returns the lowest index of the extreme value.
+fn arg_reduce(
+    gpu_ctx: &mut GpuContext,
+    input: &NativeBuffer,
+    numel: usize,
+    dtype: u8,
+    op: KernelOp,
+) -> Result<i64, String> {
+    let elem_size = buffer::dtype_byte_size(dtype);
+    let groups = numel.div_ceil(PRIMITIVE_WG_SIZE).min(PRIMITIVE_WG_SIZE);
+
+    let no_idx = alloc(gpu_ctx, 4)?;
+    let vals = alloc(gpu_ctx, groups * elem_size)?;
+    let idx = alloc(gpu_ctx, groups * 4)?;
+    let p1 = params(gpu_ctx, [numel as u32, 0, 0, 0])?;
+    run_primitive(
+        gpu_ctx,
+        op,
+        dtype,
+        &[input, &no_idx, &vals, &idx, &p1],
+        groups,
+    )?;
+
+    let idx = if groups > 1 {
+        let final_val = alloc(gpu_ctx, elem_size)?;
+        let final_idx = alloc(gpu_ctx, 4)?;
+        let p2 = params(gpu_ctx, [groups as u32, 1, 0, 0])?;
+        run_primitive(
+            gpu_ctx,
+            op,
+            dtype,
+            &[&vals, &idx, &final_val, &final_idx, &p2],
+            1,
+        )?;
+        final_idx
+    } else {
+        idx
+    };
+
+    let data = idx.read_bytes(4).ok_or("failed to read back index")?;
+    let i = u32::from_ne_bytes([data[0], data[1], data[2], data[3]]);
+    Ok(if i == u32::MAX { -1 } else { i as i64 })
+}
+
+/// Scan `numel` elements of `input`; block totals are scanned recursively.
+fn scan(
+    gpu_ctx: &mut GpuContext,
+    input: &NativeBuffer,
+    numel: usize,
+    dtype: u8,
+    exclusive: bool,
+) -> Result<NativeBuffer, String> {
+    let elem_size = buffer::dtype_byte_size(dtype);
+    let blocks = numel.div_ceil(PRIMITIVE_WG_SIZE);
+
+    let incl = alloc(gpu_ctx, numel * elem_size)?;
+    let block_sums = alloc(gpu_ctx, blocks * elem_size)?;
+    let p1 = params(gpu_ctx, [numel as u32, 0, 0, 0])?;
+    run_primitive(
+        gpu_ctx,
+        KernelOp::ScanBlock,
+        dtype,
+        &[input, &incl, &block_sums, &p1],
+        blocks,
+    )?;
+
+    let offsets = if blocks > 1 {
+        Some(scan(gpu_ctx, &block_sums, blocks, dtype, false)?)
+    } else {
+        None
+    };
+
+    let result = alloc(gpu_ctx, numel * elem_size)?;
+    let p2 = params(
+        gpu_ctx,
+        [numel as u32, exclusive as u32, offsets.is_some() as u32, 0],
+    )?;
+    run_primitive(
+        gpu_ctx,
+        KernelOp::ScanFinalize,
+        dtype,
+        &[&incl, offsets.as_ref().unwrap_or(&block_sums), &result, &p2],
+        blocks,
+    )?;
+    Ok(result)
+}
+
+/// Check an offsets buffer on the host: starts at 0, non-decreasing, ends at
+/// most at `numel`. Returns the number of segments.
+fn check_offsets(offsets: &GpuBuffer, numel: usize) -> Result<usize, String> {
+    if offsets.dtype != buffer::DTYPE_I32 || offsets.numel < 2 {
+        return Err("segment offsets must be an Int32 buffer of numSegments + 1 entries".into());
+    }
+    let data = offsets
+        .native_buffer()
+        .read_bytes(offsets.numel * 4)
+        .ok_or("failed to read segment offsets")?;
+    let mut prev = 0i32;
+    for (i, chunk) in data.chunks_exact(4).enumerate() {
+        let off = i32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        if (i == 0 && off != 0) || off < prev || off as usize > numel {
+            return Err(format!("invalid segment offset {} at index {}", off, i));
+        }
+        prev = off;
+    }
+    Ok(offsets.numel - 1)
+}
+
+unsafe fn arg_reduce_impl(ctx: i64, buf: i64, op: KernelOp) -> i64 {
+    if ctx == 0 {
+        return -1;
+    }
+    let gpu_ctx = &mut *(ctx as *mut GpuContext);
+    let result = primitive_input(gpu_ctx, buf, op.name())
+        .and_then(|a_buf| arg_reduce(gpu_ctx, a_buf.native_buffer(), a_buf.numel, a_buf.dtype, op));
+    result.unwrap_or_else(|err| {
+        gpu_ctx.last_error = Some(err);
+        -1
+    })
+}
+
+unsafe fn scan_impl(ctx: i64, buf: i64, exclusive: bool) -> i64 {
+    if ctx == 0 {
+        return 0;
+    }
+    let gpu_ctx = &mut *(ctx as *mut GpuContext);
+    let result = primitive_input(gpu_ctx, buf, "scan").and_then(|a_buf| {
+        let out = scan(
+            gpu_ctx,
+            a_buf.native_buffer(),
+            a_buf.numel,
+            a_buf.dtype,
+            exclusive,
+        )?;
+        Ok(GpuBuffer::materialized(out, a_buf.numel, a_buf.dtype))
+    });
+    match result {
+        Ok(out) => Box::into_raw(Box::new(out)) as i64,
+        Err(err) => fail(gpu_ctx, err),
+    }
+}
+
+unsafe fn histogram_impl(ctx: i64, buf: i64, bins: i64, min: f64, max: f64) -> i64 {
+    if ctx == 0 {
+        return 0;
+    }
+    let gpu_ctx = &mut *(ctx as *mut GpuContext);
+    if bins <= 0 || max.partial_cmp(&min) != Some(std::cmp::Ordering::Greater) {
+        return fail(gpu_ctx, "histogram: need bins > 0 and max > min".into());
+    }
+    let bins = bins as usize;
+    let result = primitive_input(gpu_ctx, buf, "histogram").and_then(|a_buf| {
+        let zeros = vec![0u8; bins * 4];
+        let counts = gpu_ctx
+            .inner
+            .buffer_from_data(zeros.as_ptr(), zeros.len())
+            .ok_or("failed to allocate histogram bins")?;
+        let p = params(
+            gpu_ctx,
+            [
+                a_buf.numel as u32,
+                bins as u32,
+                (min as f32).to_bits(),
+                (max as f32).to_bits(),
+            ],
+        )?;
+        run_primitive(
+            gpu_ctx,
+            KernelOp::Histogram,
+            a_buf.dtype,
+            &[a_buf.native_buffer(), &counts, &p],
+            a_buf.numel.div_ceil(PRIMITIVE_WG_SIZE),
+        )?;
+        Ok(GpuBuffer::materialized(counts, bins, buffer::DTYPE_I32))
+    });
+    match result {
+        Ok(out) => Box::into_raw(Box::new(out)) as i64,
+        Err(err) => fail(gpu_ctx, err),
+    }
+}
+
+unsafe fn segment_impl(ctx: i64, values: i64, offsets: i64, op: KernelOp) -> i64 {
+    if ctx == 0 {
+        return 0;
+    }
+    let gpu_ctx = &mut *(ctx as *mut GpuContext);
+    let result = primitive_input(gpu_ctx, values, op.name()).and_then(|v_buf| {
+        let o_buf = primitive_input(gpu_ctx, offsets, op.name())?;
+        let segments = check_offsets(o_buf, v_buf.numel)?;
+        let out = alloc(gpu_ctx, segments * buffer::dtype_byte_size(v_buf.dtype))?;
+        let p = params(gpu_ctx, [segments as u32, 0, 0, 0])?;
+        run_primitive(
+            gpu_ctx,
+            op,
+            v_buf.dtype,
+            &[v_buf.native_buffer(), o_buf.native_buffer(), &out, &p],
+            segments.min(MAX_GROUPS),
+        )?;
+        Ok(GpuBuffer::materialized(out, segments, v_buf.dtype))
+    });
+    match result {
+        Ok(out) => Box::into_raw(Box::new(out)) as i64,
+        Err(err) => fail(gpu_ctx, err),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Extern C API — Primitives
+// ---------------------------------------------------------------------------
+
+/// Index of the largest element (lowest index on ties), or -1 on error.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_argmax(ctx: i64, buf: i64) -> i64 {
+    arg_reduce_impl(ctx, buf, KernelOp::ArgMax)
+}
+
+/// Index of the smallest element (lowest index on ties), or -1 on error.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_argmin(ctx: i64, buf: i64) -> i64 {
+    arg_reduce_impl(ctx, buf, KernelOp::ArgMin)
+}
+
+/// Inclusive prefix sum: `out[i] = buf[0] + ... + buf[i]`.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_inclusive_scan(ctx: i64, buf: i64) -> i64 {
+    scan_impl(ctx, buf, false)
+}
+
+/// Exclusive prefix sum: `out[0] = 0`, `out[i] = buf[0] + ... + buf[i - 1]`.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_exclusive_scan(ctx: i64, buf: i64) -> i64 {
+    scan_impl(ctx, buf, true)
+}
+
+/// Count values into `bins` equal-width bins over `[min, max]` (Int32 result).
+/// Values outside the range are ignored; `max` falls into the last bin.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_histogram(
+    ctx: i64,
+    buf: i64,
+    bins: i64,
+    min: f64,
+    max: f64,
+) -> i64 {
+    histogram_impl(ctx, buf, bins, min, max)
+}
+
+/// Per-segment sum; segment `s` covers `values[offsets[s]..offsets[s + 1]]`.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_segment_sum(
+    ctx: i64,
+    values: i64,
+    offsets: i64,
+) -> i64 {
+    segment_impl(ctx, values, offsets, KernelOp::SegmentSum)
+}
+
+/// Per-segment maximum (the dtype's lowest value for empty segments).
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_segment_max(
+    ctx: i64,
+    values: i64,
+    offsets: i64,
+) -> i64 {
+    segment_impl(ctx, values, offsets, KernelOp::SegmentMax)
+}
+
+/// Per-segment minimum (the dtype's highest value for empty segments).
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_segment_min(
+    ctx: i64,
+    values: i64,
+    offsets: i64,
+) -> i64 {
+    segment_impl(ctx, values, offsets, KernelOp::SegmentMin)
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -556,19 +961,13 @@ pub unsafe extern "C" fn rayzor_gpu_compute_matmul(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::kernel_cache::KernelCache;
-    use std::collections::HashMap;
 
     fn make_ctx() -> i64 {
         if !NativeContext::is_available() {
             return 0;
         }
         let native_ctx = NativeContext::new().unwrap();
-        let gpu_ctx = GpuContext {
-            inner: native_ctx,
-            kernel_cache: KernelCache::new(),
-            fused_cache: HashMap::new(),
-        };
+        let gpu_ctx = GpuContext::new(native_ctx);
         Box::into_raw(Box::new(gpu_ctx)) as i64
     }
 
@@ -793,4 +1192,106 @@ mod tests {
             let _ = Box::from_raw(ctx as *mut GpuContext);
         }
     }
+
+    unsafe fn create_test_buffer_i32(ctx: i64, data: &[i32]) -> i64 {
+        let gpu_ctx = &*(ctx as *const GpuContext);
+        let inner = gpu_ctx
+            .inner
+            .buffer_from_data(data.as_ptr() as *const u8, std::mem::size_of_val(data))
+            .expect("failed to create test buffer");
+        let buf = GpuBuffer::materialized(inner, data.len(), buffer::DTYPE_I32);
+        Box::into_raw(Box::new(buf)) as i64
+    }
+
+    fn read_f32(handle: i64) -> Vec<f32> {
+        let buf = unsafe { &*(handle as *const GpuBuffer) };
+        let data = buf.native_buffer().read_bytes(buf.numel * 4).unwrap();
+        data.chunks_exact(4)
+            .map(|c| f32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+            .collect()
+    }
+
+    #[test]
+    fn test_gpu_argmax_argmin() {
+        let ctx = make_ctx();
+        if ctx == 0 {
+            return;
+        }
+        let mut data: Vec<f32> = (0..5000).map(|i| ((i * 37) % 101) as f32).collect();
+        data[777] = 500.0;
+        data[4321] = 500.0;
+        data[3] = -1.0;
+        let buf = unsafe { create_test_buffer(ctx, &data) };
+        unsafe {
+            assert_eq!(rayzor_gpu_compute_argmax(ctx, buf), 777);
+            assert_eq!(rayzor_gpu_compute_argmin(ctx, buf), 3);
+            let _ = Box::from_raw(buf as *mut GpuBuffer);
+            let _ = Box::from_raw(ctx as *mut GpuContext);
+        }
+    }
+
+    #[test]
+    fn test_gpu_scan_multi_level() {
+        let ctx = make_ctx();
+        if ctx == 0 {
+            return;
+        }
+        // 70000 elements → 274 blocks → 2 blocks: two levels of block sums
+        let data: Vec<f32> = (0..70000).map(|i| (i % 7) as f32).collect();
+        let buf = unsafe { create_test_buffer(ctx, &data) };
+        let incl = unsafe { rayzor_gpu_compute_inclusive_scan(ctx, buf) };
+        let excl = unsafe { rayzor_gpu_compute_exclusive_scan(ctx, buf) };
+        assert!(incl != 0 && excl != 0);
+
+        let (incl_out, excl_out) = (read_f32(incl), read_f32(excl));
+        let mut running = 0.0f32;
+        for (i, &v) in data.iter().enumerate() {
+            assert_eq!(excl_out[i], running, "exclusive scan mismatch at {}", i);
+            running += v;
+            assert_eq!(incl_out[i], running, "inclusive scan mismatch at {}", i);
+        }
+
+        unsafe {
+            let _ = Box::from_raw(incl as *mut GpuBuffer);
+            let _ = Box::from_raw(excl as *mut GpuBuffer);
+            let _ = Box::from_raw(buf as *mut GpuBuffer);
+            let _ = Box::from_raw(ctx as *mut GpuContext);
+        }
+    }
+
+    #[test]
+    fn test_gpu_histogram_and_segments() {
+        let ctx = make_ctx();
+        if ctx == 0 {
+            return;
+        }
+        let data: Vec<f32> = (0..10).map(|i| i as f32).collect();
+        let values = unsafe { create_test_buffer(ctx, &data) };
+        let offsets = unsafe { create_test_buffer_i32(ctx, &[0, 3, 3, 10]) };
+        unsafe {
+            // [0, 10) in 4 bins of 2.5; 9.0 lands in the last bin
+            let hist = rayzor_gpu_compute_histogram(ctx, values, 4, 0.0, 10.0);
+            assert_ne!(hist, 0);
+            let hist_buf = &*(hist as *const GpuBuffer);
+            assert_eq!(hist_buf.dtype, buffer::DTYPE_I32);
+            let counts = hist_buf.native_buffer().read_bytes(16).unwrap();
+            let counts = std::slice::from_raw_parts(counts.as_ptr() as *const i32, 4);
+            assert_eq!(counts, &[3, 2, 3, 2]);
+            assert_eq!(rayzor_gpu_compute_histogram(ctx, values, 4, 1.0, 1.0), 0);
+
+            let sums = rayzor_gpu_compute_segment_sum(ctx, values, offsets);
+            assert_eq!(read_f32(sums), vec![3.0, 0.0, 42.0]);
+            let mins = rayzor_gpu_compute_segment_min(ctx, values, offsets);
+            assert_eq!(read_f32(mins)[2], 3.0);
+
+            let bad = create_test_buffer_i32(ctx, &[0, 11]);
+            assert_eq!(rayzor_gpu_compute_segment_sum(ctx, values, bad), 0);
+            assert!((*(ctx as *const GpuContext)).last_error.is_some());
+
+            for h in [hist, sums, mins, bad, offsets, values] {
+                let _ = Box::from_raw(h as *mut GpuBuffer);
+            }
+            let _ = Box::from_raw(ctx as *mut GpuContext);
+        }
+    }
 }
@@ -0,0 +1,183 @@
+//! Counter-based random number generation (Philox4x32-10).
+//!
+//! `randomUniform` / `randomNormal` overwrite a Float32 buffer in place.
+//! Element `i` is derived only from `(seed, i)`, so a seed produces the same
+//! values on every backend and for every dispatch shape. The CPU functions
+//! below are the reference implementation the kernels are tested against.
+
+use crate::buffer::{self, GpuBuffer};
+use crate::device::GpuContext;
+use crate::kernel_ir::KernelOp;
+use crate::ops;
+
+const PHILOX_M0: u32 = 0xD251_1F53;
+const PHILOX_M1: u32 = 0xCD9E_8D57;
+const PHILOX_W0: u32 = 0x9E37_79B9;
+const PHILOX_W1: u32 = 0xBB67_AE85;
+
+/// Philox4x32 with 10 rounds.
+pub fn philox4x32(counter: [u32; 4], key: [u32; 2]) -> [u32; 4] {
+    let mut c = counter;
+    let mut k = key;
+    for _ in 0..10 {
+        let p0 = PHILOX_M0 as u64 * c[0] as u64;
+        let p1 = PHILOX_M1 as u64 * c[2] as u64;
+        c = [
+            (p1 >> 32) as u32 ^ c[1] ^ k[0],
+            p1 as u32,
+            (p0 >> 32) as u32 ^ c[3] ^ k[1],
+            p0 as u32,
+        ];
+        k = [k[0].wrapping_add(PHILOX_W0), k[1].wrapping_add(PHILOX_W1)];
+    }
+    c
+}
+
+fn seed_key(seed: i64) -> [u32; 2] {
+    [seed as u32, (seed >> 32) as u32]
+}
+
+/// Top 24 bits as a float in [0, 1).
+fn to_unit(x: u32) -> f32 {
+    (x >> 8) as f32 * (1.0 / 16_777_216.0)
+}
+
+/// CPU reference for `randomUniform`: values in [0, 1).
+pub fn uniform_reference(numel: usize, seed: i64) -> Vec<f32> {
+    let key = seed_key(seed);
+    (0..numel as u32)
+        .map(|i| to_unit(philox4x32([i, 0, 0, 0], key)[0]))
+        .collect()
+}
+
+/// CPU reference for `randomNormal`: standard normal via Box–Muller.
+pub fn normal_reference(numel: usize, seed: i64) -> Vec<f32> {
+    let key = seed_key(seed);
+    (0..numel as u32)
+        .map(|i| {
+            let r = philox4x32([i, 0, 0, 0], key);
+            let u1 = to_unit(r[0]) + 1.0 / 16_777_216.0;
+            let u2 = to_unit(r[1]);
+            (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+        })
+        .collect()
+}
+
+fn fill(
+    gpu_ctx: &mut GpuContext,
+    out: &mut GpuBuffer,
+    seed: i64,
+    op: KernelOp,
+) -> Result<(), String> {
+    out.ensure_materialized(gpu_ctx)?;
+    if out.dtype != buffer::DTYPE_F32 {
+        return Err(format!("{}: only Float32 buffers are supported", op.name()));
+    }
+    if out.numel == 0 {
+        return Ok(());
+    }
+    let key = seed_key(seed);
+    let params = ops::params(gpu_ctx, [out.numel as u32, key[0], key[1], 0])?;
+    // The kernels grid-stride, so large buffers just reuse workgroups
+    let groups = out.numel.div_ceil(256).min(ops::MAX_GROUPS);
+    ops::run_primitive(
+        gpu_ctx,
+        op,
+        buffer::DTYPE_F32,
+        &[out.native_buffer(), &params],
+        groups,
+    )
+}
+
+unsafe fn fill_impl(ctx: i64, buf: i64, seed: i64, op: KernelOp) {
+    if ctx == 0 || buf == 0 {
+        return;
+    }
+    let gpu_ctx = &mut *(ctx as *mut GpuContext);
+    let out = &mut *(buf as *mut GpuBuffer);
+    if let Err(err) = fill(gpu_ctx, out, seed, op) {
+        gpu_ctx.last_error = Some(err);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Extern C API
+// ---------------------------------------------------------------------------
+
+/// Fill a Float32 buffer with uniform values in [0, 1) derived from `seed`.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_random_uniform(ctx: i64, buf: i64, seed: i64) {
+    fill_impl(ctx, buf, seed, KernelOp::RandomUniform);
+}
+
+/// Fill a Float32 buffer with standard normal values derived from `seed`.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_random_normal(ctx: i64, buf: i64, seed: i64) {
+    fill_impl(ctx, buf, seed, KernelOp::RandomNormal);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::NativeContext;
+
+    #[test]
+    fn test_philox_known_answers() {
+        // Random123 kat_vectors for philox4x32-10
+        assert_eq!(
+            philox4x32([0; 4], [0; 2]),
+            [0x6627_e8d5, 0xe169_c58d, 0xbc57_ac4c, 0x9b00_dbd8]
+        );
+        assert_eq!(
+            philox4x32([u32::MAX; 4], [u32::MAX; 2]),
+            [0x408f_276d, 0x41c8_3b0e, 0xa20b_c7c6, 0x6d54_51fd]
+        );
+        let uniform = uniform_reference(10_000, 42);
+        assert!(uniform.iter().all(|&v| (0.0..1.0).contains(&v)));
+        let mean = uniform.iter().sum::<f32>() / uniform.len() as f32;
+        assert!((mean - 0.5).abs() < 0.02, "uniform mean {}", mean);
+    }
+
+    #[test]
+    fn test_gpu_random_matches_reference() {
+        if !NativeContext::is_available() {
+            return;
+        }
+        let native_ctx = NativeContext::new().unwrap();
+        let ctx = Box::into_raw(Box::new(GpuContext::new(native_ctx))) as i64;
+        let n = 3000;
+        unsafe {
+            let gpu_ctx = &*(ctx as *const GpuContext);
+            let inner = gpu_ctx.inner.allocate_buffer(n * 4).unwrap();
+            let buf = Box::into_raw(Box::new(GpuBuffer::materialized(
+                inner,
+                n,
+                buffer::DTYPE_F32,
+            ))) as i64;
+            let read = || {
+                let data = (*(buf as *const GpuBuffer))
+                    .native_buffer()
+                    .read_bytes(n * 4)
+                    .unwrap();
+                std::slice::from_raw_parts(data.as_ptr() as *const f32, n).to_vec()
+            };
+
+            rayzor_gpu_compute_random_uniform(ctx, buf, 0x1234_5678_9abc);
+            assert_eq!(read(), uniform_reference(n, 0x1234_5678_9abc));
+
+            rayzor_gpu_compute_random_normal(ctx, buf, 7);
+            for (i, (g, c)) in read().iter().zip(normal_reference(n, 7)).enumerate() {
+                assert!(
+                    (g - c).abs() <= 1e-4 * (1.0 + c.abs()),
+                    "normal[{}]: gpu {} vs cpu {}",
+                    i,
+                    g,
+                    c
+                );
+            }
+
+            let _ = Box::from_raw(buf as *mut GpuBuffer);
+            let _ = Box::from_raw(ctx as *mut GpuContext);
+        }
+    }
+}
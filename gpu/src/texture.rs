@@ -0,0 +1,528 @@
+//! GPU textures — 2D images for image-processing kernels
+//!
+//! A texture is a row-major buffer of `width * height * channels` f32 texels
+//! with interleaved channels. `RGBA8` bytes are normalized to 0..1 on upload
+//! and quantized back on readback; `F32` textures hold a single float
+//! channel. Image ops (convolution, resize) are 2D kernels over these
+//! buffers that sample with clamp-to-edge addressing.
+
+use std::rc::Rc;
+
+use rayzor_runtime::haxe_sys::{haxe_bytes_alloc, HaxeBytes};
+
+use crate::backend::{NativeBuffer, NativeCompiledKernel, NativeContext};
+use crate::buffer::{self, GpuBuffer};
+use crate::device::GpuContext;
+use crate::kernel_ir::KernelOp;
+
+/// Texture format tags matching rayzor.gpu.TextureFormat
+pub const FORMAT_RGBA8: i64 = 0;
+pub const FORMAT_F32: i64 = 1;
+
+/// Opaque GPU texture handle.
+pub struct GpuTexture {
+    pub(crate) buffer: Rc<NativeBuffer>,
+    pub width: usize,
+    pub height: usize,
+    pub format: i64,
+}
+
+impl GpuTexture {
+    /// f32 channels stored per texel.
+    pub fn channels(&self) -> usize {
+        format_channels(self.format).unwrap_or(1)
+    }
+
+    /// Total f32 values in the texel buffer.
+    fn value_count(&self) -> usize {
+        self.width * self.height * self.channels()
+    }
+
+    /// Kernel dims entry: (width, height, channels, 0).
+    fn dims(&self) -> [u32; 4] {
+        [
+            self.width as u32,
+            self.height as u32,
+            self.channels() as u32,
+            0,
+        ]
+    }
+
+    /// Byte offset of channel `c` of texel (x, y), if in range.
+    fn value_offset(&self, x: i64, y: i64, c: i64) -> Option<usize> {
+        let channels = self.channels() as i64;
+        if x < 0 || y < 0 || c < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return None;
+        }
+        if c >= channels {
+            return None;
+        }
+        let index = (y as usize * self.width + x as usize) * channels as usize + c as usize;
+        Some(index * 4)
+    }
+}
+
+/// f32 channels per texel for a format tag.
+pub fn format_channels(format: i64) -> Option<usize> {
+    match format {
+        FORMAT_RGBA8 => Some(4),
+        FORMAT_F32 => Some(1),
+        _ => None,
+    }
+}
+
+/// Convert CPU pixel bytes in `format` to f32 texel values.
+pub fn decode_texels(format: i64, bytes: &[u8]) -> Vec<f32> {
+    match format {
+        FORMAT_RGBA8 => bytes.iter().map(|&b| b as f32 / 255.0).collect(),
+        _ => bytes
+            .chunks_exact(4)
+            .map(|c| f32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+            .collect(),
+    }
+}
+
+/// Convert f32 texel values back to CPU pixel bytes in `format`.
+pub fn encode_texels(format: i64, texels: &[f32]) -> Vec<u8> {
+    match format {
+        FORMAT_RGBA8 => texels
+            .iter()
+            .map(|&v| (v.clamp(0.0, 1.0) * 255.0).round() as u8)
+            .collect(),
+        _ => texels.iter().flat_map(|v| v.to_ne_bytes()).collect(),
+    }
+}
+
+/// Read the whole texel buffer back as f32 values.
+fn read_texels(tex: &GpuTexture) -> Option<Vec<f32>> {
+    let byte_size = tex.value_count() * 4;
+    let data = tex.buffer.read_bytes(byte_size)?;
+    if data.len() < byte_size {
+        return None;
+    }
+    Some(decode_texels(FORMAT_F32, &data))
+}
+
+fn into_handle(tex: GpuTexture) -> i64 {
+    Box::into_raw(Box::new(tex)) as i64
+}
+
+// ---------------------------------------------------------------------------
+// Extern C API — creation, readback, texel access
+// ---------------------------------------------------------------------------
+
+/// Create a texture from `width * height` pixels of `format` in a Bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_create_texture(
+    ctx: i64,
+    bytes_ptr: i64,
+    width: i64,
+    height: i64,
+    format: i64,
+) -> i64 {
+    if ctx == 0 || bytes_ptr == 0 || width <= 0 || height <= 0 {
+        return 0;
+    }
+    if format_channels(format).is_none() {
+        return 0;
+    }
+    let (width, height) = (width as usize, height as usize);
+    // RGBA8 pixels and F32 texels are both 4 bytes
+    let needed = width * height * 4;
+    let bytes = &*(bytes_ptr as *const HaxeBytes);
+    if bytes.ptr.is_null() || bytes.len < needed {
+        return 0;
+    }
+
+    let data = std::slice::from_raw_parts(bytes.ptr, needed);
+    let texels = decode_texels(format, data);
+    let gpu_ctx = &*(ctx as *const GpuContext);
+    match gpu_ctx
+        .inner
+        .buffer_from_data(texels.as_ptr() as *const u8, texels.len() * 4)
+    {
+        Some(inner) => into_handle(GpuTexture {
+            buffer: Rc::new(inner),
+            width,
+            height,
+            format,
+        }),
+        None => 0,
+    }
+}
+
+/// Allocate a zero-filled texture.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_alloc_texture(
+    ctx: i64,
+    width: i64,
+    height: i64,
+    format: i64,
+) -> i64 {
+    if ctx == 0 || width <= 0 || height <= 0 {
+        return 0;
+    }
+    let Some(channels) = format_channels(format) else {
+        return 0;
+    };
+    let (width, height) = (width as usize, height as usize);
+    let gpu_ctx = &*(ctx as *const GpuContext);
+    match gpu_ctx.inner.allocate_buffer(width * height * channels * 4) {
+        Some(inner) => into_handle(GpuTexture {
+            buffer: Rc::new(inner),
+            width,
+            height,
+            format,
+        }),
+        None => 0,
+    }
+}
+
+/// Copy a texture back into a new Bytes in the texture's format.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_read_texture(_ctx: i64, tex_ptr: i64) -> i64 {
+    if tex_ptr == 0 {
+        return 0;
+    }
+    let tex = &*(tex_ptr as *const GpuTexture);
+    let Some(texels) = read_texels(tex) else {
+        return 0;
+    };
+    let data = encode_texels(tex.format, &texels);
+    let out = haxe_bytes_alloc(data.len() as i32);
+    if out.is_null() {
+        return 0;
+    }
+    std::ptr::copy_nonoverlapping(data.as_ptr(), (*out).ptr, data.len());
+    out as i64
+}
+
+/// Read channel `c` of texel (x, y). RGBA8 channels read as 0..1.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_texture_get(
+    _ctx: i64,
+    tex_ptr: i64,
+    x: i64,
+    y: i64,
+    c: i64,
+) -> f64 {
+    if tex_ptr == 0 {
+        return 0.0;
+    }
+    let tex = &*(tex_ptr as *const GpuTexture);
+    let Some(offset) = tex.value_offset(x, y, c) else {
+        return 0.0;
+    };
+    let ptr = tex.buffer.contents_ptr();
+    if !ptr.is_null() {
+        return *(ptr.add(offset) as *const f32) as f64;
+    }
+    // Fallback for wgpu: read via staging buffer
+    match tex.buffer.read_bytes(offset + 4) {
+        Some(data) if data.len() >= offset + 4 => {
+            f32::from_ne_bytes(data[offset..offset + 4].try_into().unwrap()) as f64
+        }
+        _ => 0.0,
+    }
+}
+
+/// Write channel `c` of texel (x, y). RGBA8 channels take 0..1.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_texture_set(
+    _ctx: i64,
+    tex_ptr: i64,
+    x: i64,
+    y: i64,
+    c: i64,
+    value: f64,
+) {
+    if tex_ptr == 0 {
+        return;
+    }
+    let tex = &*(tex_ptr as *const GpuTexture);
+    if let Some(offset) = tex.value_offset(x, y, c) {
+        tex.buffer
+            .write_bytes(offset, &(value as f32).to_ne_bytes());
+    }
+}
+
+/// Free a texture.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_free_texture(_ctx: i64, tex_ptr: i64) {
+    if tex_ptr != 0 {
+        let _ = Box::from_raw(tex_ptr as *mut GpuTexture);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_texture_width(tex_ptr: i64) -> i64 {
+    if tex_ptr == 0 {
+        return 0;
+    }
+    (*(tex_ptr as *const GpuTexture)).width as i64
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_texture_height(tex_ptr: i64) -> i64 {
+    if tex_ptr == 0 {
+        return 0;
+    }
+    (*(tex_ptr as *const GpuTexture)).height as i64
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_texture_format(tex_ptr: i64) -> i64 {
+    if tex_ptr == 0 {
+        return 0;
+    }
+    (*(tex_ptr as *const GpuTexture)).format
+}
+
+// ---------------------------------------------------------------------------
+// Extern C API — image ops
+// ---------------------------------------------------------------------------
+
+/// Convolve every channel with a `kw * kh` F32 weight buffer (row-major).
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_convolve(
+    ctx: i64,
+    tex_ptr: i64,
+    weights_ptr: i64,
+    kw: i64,
+    kh: i64,
+) -> i64 {
+    if ctx == 0 || tex_ptr == 0 || weights_ptr == 0 || kw <= 0 || kh <= 0 {
+        return 0;
+    }
+    let gpu_ctx = &mut *(ctx as *mut GpuContext);
+    let tex = &*(tex_ptr as *const GpuTexture);
+    let weights = &mut *(weights_ptr as *mut GpuBuffer);
+    if gpu_ctx.check_affinity(&tex.buffer).is_err()
+        || weights.ensure_materialized(gpu_ctx).is_err()
+        || weights.dtype != buffer::DTYPE_F32
+        || weights.numel != (kw * kh) as usize
+    {
+        return 0;
+    }
+
+    let d = tex.dims();
+    let dims: [u32; 8] = [d[0], d[1], d[2], d[3], kw as u32, kh as u32, 0, 0];
+    let inputs = [&tex.buffer, weights.native_buffer()];
+    match image_op(
+        gpu_ctx,
+        KernelOp::Conv2d,
+        &inputs,
+        &dims,
+        tex.width,
+        tex.height,
+    ) {
+        Some(inner) => into_handle(GpuTexture {
+            buffer: Rc::new(inner),
+            width: tex.width,
+            height: tex.height,
+            format: tex.format,
+        }),
+        None => 0,
+    }
+}
+
+/// Resize to `width * height` with bilinear filtering.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_resize(
+    ctx: i64,
+    tex_ptr: i64,
+    width: i64,
+    height: i64,
+) -> i64 {
+    if ctx == 0 || tex_ptr == 0 || width <= 0 || height <= 0 {
+        return 0;
+    }
+    let gpu_ctx = &mut *(ctx as *mut GpuContext);
+    let tex = &*(tex_ptr as *const GpuTexture);
+    if gpu_ctx.check_affinity(&tex.buffer).is_err() {
+        return 0;
+    }
+    let (width, height) = (width as usize, height as usize);
+
+    let s = tex.dims();
+    let dims: [u32; 8] = [s[0], s[1], s[2], s[3], width as u32, height as u32, s[2], 0];
+    match image_op(
+        gpu_ctx,
+        KernelOp::Resize,
+        &[&tex.buffer],
+        &dims,
+        width,
+        height,
+    ) {
+        Some(inner) => into_handle(GpuTexture {
+            buffer: Rc::new(inner),
+            width,
+            height,
+            format: tex.format,
+        }),
+        None => 0,
+    }
+}
+
+/// Compile (cached) and run an image kernel over a `width * height` output
+/// texture with the same channel count as `dims[2]`.
+fn image_op(
+    gpu_ctx: &mut GpuContext,
+    op: KernelOp,
+    inputs: &[&Rc<NativeBuffer>],
+    dims: &[u32; 8],
+    width: usize,
+    height: usize,
+) -> Option<NativeBuffer> {
+    let cached = gpu_ctx
+        .kernel_cache
+        .get_or_compile(&gpu_ctx.inner, op, buffer::DTYPE_F32)
+        .ok()?;
+    let out_bytes = width * height * dims[2] as usize * 4;
+    image_dispatch(
+        &gpu_ctx.inner,
+        &cached.compiled,
+        inputs,
+        dims,
+        out_bytes,
+        width,
+        height,
+    )
+    .ok()
+}
+
+/// Backend-dispatch for image kernels: buffers are inputs, result, dims.
+#[allow(unused_variables)]
+fn image_dispatch(
+    ctx: &NativeContext,
+    compiled: &NativeCompiledKernel,
+    inputs: &[&Rc<NativeBuffer>],
+    dims: &[u32; 8],
+    out_bytes: usize,
+    width: usize,
+    height: usize,
+) -> Result<NativeBuffer, String> {
+    match (ctx, compiled) {
+        #[cfg(feature = "metal-backend")]
+        (NativeContext::Metal(metal_ctx), NativeCompiledKernel::Metal(kernel)) => {
+            use crate::metal::{buffer_ops::MetalBuffer, dispatch};
+            use objc2_metal::MTLSize;
+
+            let mut bufs = Vec::with_capacity(inputs.len() + 2);
+            for input in inputs {
+                match input.as_ref() {
+                    NativeBuffer::Metal(mb) => bufs.push(mb),
+                    _ => return Err("input not Metal".into()),
+                }
+            }
+            let result_inner =
+                MetalBuffer::allocate(metal_ctx, out_bytes).ok_or("failed to alloc result")?;
+            let dims_buf =
+                MetalBuffer::from_value(metal_ctx, dims).ok_or("failed to alloc dims")?;
+            bufs.push(&result_inner);
+            bufs.push(&dims_buf);
+
+            let threads_per_tg = 16usize;
+            dispatch::dispatch_threadgroups(
+                metal_ctx,
+                kernel,
+                &bufs,
+                MTLSize {
+                    width: width.div_ceil(threads_per_tg),
+                    height: height.div_ceil(threads_per_tg),
+                    depth: 1,
+                },
+                MTLSize {
+                    width: threads_per_tg,
+                    height: threads_per_tg,
+                    depth: 1,
+                },
+            )?;
+
+            Ok(NativeBuffer::Metal(result_inner))
+        }
+        #[cfg(feature = "webgpu-backend")]
+        (NativeContext::Wgpu(wgpu_ctx), NativeCompiledKernel::Wgpu(kernel)) => {
+            use crate::wgpu_backend::{buffer_ops::WgpuBuffer, dispatch};
+
+            let mut bufs = Vec::with_capacity(inputs.len() + 2);
+            for input in inputs {
+                match input.as_ref() {
+                    NativeBuffer::Wgpu(wb) => bufs.push(wb),
+                    _ => return Err("input not wgpu".into()),
+                }
+            }
+            let result_inner =
+                WgpuBuffer::allocate(wgpu_ctx, out_bytes).ok_or("failed to alloc result")?;
+            let dims_buf =
+                unsafe { WgpuBuffer::from_data(wgpu_ctx, dims.as_ptr() as *const u8, 32) }
+                    .ok_or("failed to alloc dims")?;
+            bufs.push(&result_inner);
+            bufs.push(&dims_buf);
+
+            let threads_per_wg = 16usize;
+            dispatch::dispatch_workgroups(
+                wgpu_ctx,
+                kernel,
+                &bufs,
+                (
+                    width.div_ceil(threads_per_wg),
+                    height.div_ceil(threads_per_wg),
+                    1,
+                ),
+            )?;
+
+            Ok(NativeBuffer::Wgpu(result_inner))
+        }
+        _ => Err("backend mismatch".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rgba8_round_trip() {
+        let pixels = [0u8, 64, 128, 255];
+        let texels = decode_texels(FORMAT_RGBA8, &pixels);
+        assert!((texels[3] - 1.0).abs() < 1e-6);
+        assert_eq!(encode_texels(FORMAT_RGBA8, &texels), pixels);
+        assert_eq!(encode_texels(FORMAT_RGBA8, &[-0.5, 2.0]), [0, 255]);
+    }
+
+    #[test]
+    fn test_f32_round_trip() {
+        let values = [0.25f32, -3.5];
+        let bytes = encode_texels(FORMAT_F32, &values);
+        assert_eq!(bytes.len(), 8);
+        assert_eq!(decode_texels(FORMAT_F32, &bytes), values);
+    }
+
+    #[test]
+    fn test_gpu_resize_f32() {
+        if !NativeContext::is_available() {
+            return;
+        }
+        let mut gpu_ctx = GpuContext::new(NativeContext::new().unwrap());
+
+        // 2x1 gradient upscaled to 4x1: clamp-to-edge bilinear
+        let src: [f32; 2] = [0.0, 1.0];
+        let inner = unsafe { gpu_ctx.inner.buffer_from_data(src.as_ptr() as *const u8, 8) };
+        let tex = GpuTexture {
+            buffer: Rc::new(inner.unwrap()),
+            width: 2,
+            height: 1,
+            format: FORMAT_F32,
+        };
+        let dims: [u32; 8] = [2, 1, 1, 0, 4, 1, 1, 0];
+        let out = image_op(&mut gpu_ctx, KernelOp::Resize, &[&tex.buffer], &dims, 4, 1)
+            .expect("resize dispatch failed");
+        let data = out.read_bytes(16).unwrap();
+        let values = decode_texels(FORMAT_F32, &data);
+        let expected = [0.0, 0.25, 0.75, 1.0];
+        for (got, want) in values.iter().zip(expected) {
+            assert!((got - want).abs() < 1e-5, "got {:?}", values);
+        }
+    }
+}
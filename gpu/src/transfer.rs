@@ -0,0 +1,442 @@
+//! Pinned host memory, async transfers and double-buffered streaming
+//!
+//! `PinnedBuffer` is page-aligned host memory, page-locked with `mlock` where
+//! the OS allows, used as the source or destination of async copies.
+//! `uploadAsync`/`downloadAsync` return a `GpuEvent` instead of blocking, and
+//! `DoubleBuffer` pairs two pinned staging buffers with two device buffers so
+//! a frame can be filled and uploaded while the previous one is computed on.
+//!
+//! On Metal every buffer is host-visible shared memory and transfers finish
+//! immediately; on wgpu they complete in the background and are observed by
+//! polling the device.
+
+use std::alloc::{alloc_zeroed, dealloc, Layout};
+
+use crate::backend::NativeEvent;
+use crate::buffer::{dtype_byte_size, GpuBuffer};
+use crate::device::GpuContext;
+
+/// Page-aligned, best-effort page-locked host allocation.
+pub struct PinnedBuffer {
+    ptr: *mut u8,
+    len: usize,
+    locked: bool,
+}
+
+impl PinnedBuffer {
+    /// Allocate `len` zeroed bytes. Returns None if `len` is 0 or allocation fails.
+    pub fn new(len: usize) -> Option<Self> {
+        if len == 0 {
+            return None;
+        }
+        let layout = Layout::from_size_align(len, page_size()).ok()?;
+        let ptr = unsafe { alloc_zeroed(layout) };
+        if ptr.is_null() {
+            return None;
+        }
+        #[cfg(unix)]
+        let locked = unsafe { libc::mlock(ptr as *const libc::c_void, len) == 0 };
+        #[cfg(not(unix))]
+        let locked = false;
+        Some(PinnedBuffer { ptr, len, locked })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether the OS agreed to page-lock the allocation.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.ptr
+    }
+
+    /// Pointer to the 4-byte element at `index`, if in range.
+    fn element_ptr(&self, index: i64) -> Option<*mut u8> {
+        if index < 0 || (index as usize + 1) * 4 > self.len {
+            return None;
+        }
+        Some(unsafe { self.ptr.add(index as usize * 4) })
+    }
+}
+
+impl Drop for PinnedBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            #[cfg(unix)]
+            if self.locked {
+                libc::munlock(self.ptr as *const libc::c_void, self.len);
+            }
+            dealloc(
+                self.ptr,
+                Layout::from_size_align_unchecked(self.len, page_size()),
+            );
+        }
+    }
+}
+
+fn page_size() -> usize {
+    #[cfg(unix)]
+    {
+        let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        if size > 0 {
+            return size as usize;
+        }
+    }
+    4096
+}
+
+/// Completion handle returned by async transfers.
+pub struct GpuEvent {
+    inner: NativeEvent,
+}
+
+/// Two pinned staging buffers feeding two device buffers.
+///
+/// The host fills `back()`; `present()` starts its upload and swaps, so the
+/// just-uploaded buffer becomes `front()` for compute while the other slot is
+/// refilled. Backend queues order the upload before any later dispatch.
+pub struct DoubleBuffer {
+    staging: [PinnedBuffer; 2],
+    buffers: [Box<GpuBuffer>; 2],
+    uploads: [Option<NativeEvent>; 2],
+    back: usize,
+}
+
+// ---------------------------------------------------------------------------
+// Extern C API — pinned memory
+// ---------------------------------------------------------------------------
+
+/// Allocate `bytes` of pinned host memory.
+#[no_mangle]
+pub extern "C" fn rayzor_gpu_compute_alloc_pinned(_ctx: i64, bytes: i64) -> i64 {
+    if bytes <= 0 {
+        return 0;
+    }
+    match PinnedBuffer::new(bytes as usize) {
+        Some(pinned) => Box::into_raw(Box::new(pinned)) as i64,
+        None => 0,
+    }
+}
+
+/// Free pinned host memory. Any transfer using it must have completed.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_free_pinned(_ctx: i64, pinned: i64) {
+    if pinned != 0 {
+        let _ = Box::from_raw(pinned as *mut PinnedBuffer);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_pinned_length(pinned: i64) -> i64 {
+    if pinned == 0 {
+        return 0;
+    }
+    (*(pinned as *const PinnedBuffer)).len() as i64
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_pinned_is_locked(pinned: i64) -> i8 {
+    if pinned == 0 {
+        return 0;
+    }
+    (*(pinned as *const PinnedBuffer)).is_locked() as i8
+}
+
+/// Read f32 element `index`, promoted to f64.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_pinned_get_float(pinned: i64, index: i64) -> f64 {
+    if pinned == 0 {
+        return 0.0;
+    }
+    match (*(pinned as *const PinnedBuffer)).element_ptr(index) {
+        Some(ptr) => *(ptr as *const f32) as f64,
+        None => 0.0,
+    }
+}
+
+/// Write f32 element `index`.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_pinned_set_float(pinned: i64, index: i64, value: f64) {
+    if pinned == 0 {
+        return;
+    }
+    if let Some(ptr) = (*(pinned as *const PinnedBuffer)).element_ptr(index) {
+        *(ptr as *mut f32) = value as f32;
+    }
+}
+
+/// Read i32 element `index`, extended to i64.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_pinned_get_int(pinned: i64, index: i64) -> i64 {
+    if pinned == 0 {
+        return 0;
+    }
+    match (*(pinned as *const PinnedBuffer)).element_ptr(index) {
+        Some(ptr) => *(ptr as *const i32) as i64,
+        None => 0,
+    }
+}
+
+/// Write i32 element `index`.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_pinned_set_int(pinned: i64, index: i64, value: i64) {
+    if pinned == 0 {
+        return;
+    }
+    if let Some(ptr) = (*(pinned as *const PinnedBuffer)).element_ptr(index) {
+        *(ptr as *mut i32) = value as i32;
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Extern C API — async transfers and events
+// ---------------------------------------------------------------------------
+
+/// Start copying a pinned buffer into a GPU buffer (up to the smaller size).
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_upload_async(ctx: i64, pinned: i64, buf: i64) -> i64 {
+    if ctx == 0 || pinned == 0 || buf == 0 {
+        return 0;
+    }
+    let gpu_ctx = &mut *(ctx as *mut GpuContext);
+    let src = &*(pinned as *const PinnedBuffer);
+    let dst = &mut *(buf as *mut GpuBuffer);
+    if dst.ensure_materialized(gpu_ctx).is_err() {
+        return 0;
+    }
+    let native = dst.native_buffer();
+    let len = transfer_len(src.len(), native.byte_size());
+    match native.upload_async(0, src.as_ptr(), len) {
+        Some(inner) => Box::into_raw(Box::new(GpuEvent { inner })) as i64,
+        None => 0,
+    }
+}
+
+/// Start copying a GPU buffer into a pinned buffer (up to the smaller size).
+///
+/// The pinned buffer is written once the event completes.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_download_async(ctx: i64, buf: i64, pinned: i64) -> i64 {
+    if ctx == 0 || pinned == 0 || buf == 0 {
+        return 0;
+    }
+    let gpu_ctx = &mut *(ctx as *mut GpuContext);
+    let src = &mut *(buf as *mut GpuBuffer);
+    let dst = &mut *(pinned as *mut PinnedBuffer);
+    if src.ensure_materialized(gpu_ctx).is_err() {
+        return 0;
+    }
+    let native = src.native_buffer();
+    let len = transfer_len(dst.len(), native.byte_size());
+    match native.download_async(dst.as_mut_ptr(), len) {
+        Some(inner) => Box::into_raw(Box::new(GpuEvent { inner })) as i64,
+        None => 0,
+    }
+}
+
+/// Copies move whole 4-byte words (the wgpu copy alignment).
+fn transfer_len(host: usize, device: usize) -> usize {
+    host.min(device) & !3
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_event_is_complete(event: i64) -> i8 {
+    if event == 0 {
+        return 1;
+    }
+    (*(event as *mut GpuEvent)).inner.is_complete() as i8
+}
+
+/// Block until the transfer completes. Returns false if it failed.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_event_wait(event: i64) -> i8 {
+    if event == 0 {
+        return 0;
+    }
+    (*(event as *mut GpuEvent)).inner.wait() as i8
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_free_event(_ctx: i64, event: i64) {
+    if event != 0 {
+        let _ = Box::from_raw(event as *mut GpuEvent);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Extern C API — double buffering
+// ---------------------------------------------------------------------------
+
+/// Create a double buffer of two `numel`-element device buffers.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_create_double_buffer(
+    ctx: i64,
+    numel: i64,
+    dtype: i64,
+) -> i64 {
+    if ctx == 0 || numel <= 0 {
+        return 0;
+    }
+    let gpu_ctx = &*(ctx as *const GpuContext);
+    let (numel, dtype) = (numel as usize, dtype as u8);
+    let byte_size = numel * dtype_byte_size(dtype);
+
+    let slot = || -> Option<(PinnedBuffer, Box<GpuBuffer>)> {
+        let inner = gpu_ctx.inner.allocate_buffer(byte_size)?;
+        let pinned = PinnedBuffer::new(byte_size)?;
+        Some((
+            pinned,
+            Box::new(GpuBuffer::materialized(inner, numel, dtype)),
+        ))
+    };
+    let (Some((staging0, buf0)), Some((staging1, buf1))) = (slot(), slot()) else {
+        return 0;
+    };
+    let db = DoubleBuffer {
+        staging: [staging0, staging1],
+        buffers: [buf0, buf1],
+        uploads: [None, None],
+        back: 0,
+    };
+    Box::into_raw(Box::new(db)) as i64
+}
+
+/// Pinned buffer to fill with the next frame. Owned by the double buffer.
+///
+/// Waits for any upload still reading from it.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_double_buffer_back(db: i64) -> i64 {
+    if db == 0 {
+        return 0;
+    }
+    let db = &mut *(db as *mut DoubleBuffer);
+    if let Some(mut upload) = db.uploads[db.back].take() {
+        upload.wait();
+    }
+    &mut db.staging[db.back] as *mut PinnedBuffer as i64
+}
+
+/// Device buffer holding the most recently presented frame. Owned by the
+/// double buffer — do not pass it to `freeBuffer`.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_double_buffer_front(db: i64) -> i64 {
+    if db == 0 {
+        return 0;
+    }
+    let db = &mut *(db as *mut DoubleBuffer);
+    let front = 1 - db.back;
+    &mut *db.buffers[front] as *mut GpuBuffer as i64
+}
+
+/// Start uploading `back()` and swap it to the front.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_present(ctx: i64, db: i64) -> i8 {
+    if ctx == 0 || db == 0 {
+        return 0;
+    }
+    let gpu_ctx = &mut *(ctx as *mut GpuContext);
+    let db = &mut *(db as *mut DoubleBuffer);
+    let slot = db.back;
+    let native = db.buffers[slot].native_buffer();
+    if gpu_ctx.check_affinity(native).is_err() {
+        return 0;
+    }
+    let staging = &db.staging[slot];
+    let len = transfer_len(staging.len(), native.byte_size());
+    match native.upload_async(0, staging.as_ptr(), len) {
+        Some(upload) => {
+            db.uploads[slot] = Some(upload);
+            db.back = 1 - slot;
+            1
+        }
+        None => 0,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gpu_compute_free_double_buffer(_ctx: i64, db: i64) {
+    if db == 0 {
+        return;
+    }
+    let mut db = Box::from_raw(db as *mut DoubleBuffer);
+    for upload in db.uploads.iter_mut().flatten() {
+        upload.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::NativeContext;
+    use crate::buffer::DTYPE_F32;
+
+    #[test]
+    fn test_pinned_alloc_and_access() {
+        let pinned = rayzor_gpu_compute_alloc_pinned(0, 10);
+        assert_ne!(pinned, 0);
+        unsafe {
+            let p = &*(pinned as *const PinnedBuffer);
+            assert_eq!(p.as_ptr() as usize % page_size(), 0);
+            assert_eq!(rayzor_gpu_pinned_length(pinned), 10);
+            rayzor_gpu_pinned_set_float(pinned, 1, 2.5);
+            assert_eq!(rayzor_gpu_pinned_get_float(pinned, 1), 2.5);
+            // Element 2 would end at byte 12 > 10
+            rayzor_gpu_pinned_set_int(pinned, 2, 7);
+            assert_eq!(rayzor_gpu_pinned_get_int(pinned, 2), 0);
+            rayzor_gpu_compute_free_pinned(0, pinned);
+        }
+        assert_eq!(rayzor_gpu_compute_alloc_pinned(0, 0), 0);
+    }
+
+    #[test]
+    fn test_async_round_trip_and_double_buffer() {
+        if !NativeContext::is_available() {
+            return;
+        }
+        let ctx = Box::into_raw(Box::new(GpuContext::new(NativeContext::new().unwrap()))) as i64;
+        unsafe {
+            let up = rayzor_gpu_compute_alloc_pinned(ctx, 16);
+            let down = rayzor_gpu_compute_alloc_pinned(ctx, 16);
+            for i in 0..4 {
+                rayzor_gpu_pinned_set_float(up, i, i as f64 + 0.5);
+            }
+            let buf = crate::buffer::rayzor_gpu_compute_alloc_buffer(ctx, 4, DTYPE_F32 as i64);
+
+            let ev = rayzor_gpu_compute_upload_async(ctx, up, buf);
+            assert_eq!(rayzor_gpu_event_wait(ev), 1);
+            let ev2 = rayzor_gpu_compute_download_async(ctx, buf, down);
+            assert_eq!(rayzor_gpu_event_wait(ev2), 1);
+            assert_eq!(rayzor_gpu_event_is_complete(ev2), 1);
+            assert_eq!(rayzor_gpu_pinned_get_float(down, 3), 3.5);
+
+            let db = rayzor_gpu_compute_create_double_buffer(ctx, 4, DTYPE_F32 as i64);
+            rayzor_gpu_pinned_set_float(rayzor_gpu_double_buffer_back(db), 0, 9.0);
+            assert_eq!(rayzor_gpu_compute_present(ctx, db), 1);
+            let front = rayzor_gpu_double_buffer_front(db);
+            let ev3 = rayzor_gpu_compute_download_async(ctx, front, down);
+            assert_eq!(rayzor_gpu_event_wait(ev3), 1);
+            assert_eq!(rayzor_gpu_pinned_get_float(down, 0), 9.0);
+
+            for ev in [ev, ev2, ev3] {
+                rayzor_gpu_compute_free_event(ctx, ev);
+            }
+            rayzor_gpu_compute_free_double_buffer(ctx, db);
+            crate::buffer::rayzor_gpu_compute_free_buffer(ctx, buf);
+            rayzor_gpu_compute_free_pinned(ctx, up);
+            rayzor_gpu_compute_free_pinned(ctx, down);
+            crate::device::rayzor_gpu_compute_destroy(ctx);
+        }
+    }
+}
@@ -111,6 +111,12 @@ impl WgpuBuffer {
         }
     }
 
+    /// Write `data` into the buffer at `offset` (both multiples of 4 bytes).
+    pub fn write(&self, offset: usize, data: &[u8]) {
+        let queue = unsafe { &*self.queue };
+        queue.write_buffer(&self.buffer, offset as u64, data);
+    }
+
     /// Get the byte size of the buffer.
     pub fn byte_size(&self) -> usize {
         self.byte_size
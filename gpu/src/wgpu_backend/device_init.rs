@@ -2,26 +2,46 @@
 
 use wgpu;
 
+use crate::backend::{
+    DeviceInfo, DEVICE_CPU, DEVICE_DISCRETE, DEVICE_INTEGRATED, DEVICE_OTHER, DEVICE_VIRTUAL,
+};
+
 /// WebGPU-specific GPU context wrapping device + queue.
 pub struct WgpuContext {
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
+    pub adapter_info: wgpu::AdapterInfo,
+}
+
+fn instance() -> wgpu::Instance {
+    wgpu::Instance::new(&wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::all(),
+        ..Default::default()
+    })
 }
 
 impl WgpuContext {
     /// Create a new wgpu context using the best available adapter.
     pub fn new() -> Option<Self> {
-        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
-            ..Default::default()
-        });
+        let adapter =
+            pollster::block_on(instance().request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            }))?;
+        Self::from_adapter(adapter)
+    }
 
-        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::HighPerformance,
-            compatible_surface: None,
-            force_fallback_adapter: false,
-        }))?;
+    /// Create a wgpu context on the `index`-th adapter of `enumerate()`.
+    pub fn new_on_device(index: usize) -> Option<Self> {
+        let adapter = instance()
+            .enumerate_adapters(wgpu::Backends::all())
+            .into_iter()
+            .nth(index)?;
+        Self::from_adapter(adapter)
+    }
 
+    fn from_adapter(adapter: wgpu::Adapter) -> Option<Self> {
         let (device, queue) = pollster::block_on(adapter.request_device(
             &wgpu::DeviceDescriptor {
                 label: Some("rayzor_gpu"),
@@ -33,17 +53,30 @@ impl WgpuContext {
         ))
         .ok()?;
 
-        Some(WgpuContext { device, queue })
+        Some(WgpuContext {
+            device,
+            queue,
+            adapter_info: adapter.get_info(),
+        })
+    }
+
+    /// Describe every adapter wgpu can see, across all native backends.
+    pub fn enumerate() -> Vec<DeviceInfo> {
+        instance()
+            .enumerate_adapters(wgpu::Backends::all())
+            .iter()
+            .map(|adapter| device_info(&adapter.get_info()))
+            .collect()
+    }
+
+    /// Describe the adapter this context runs on.
+    pub fn info(&self) -> DeviceInfo {
+        device_info(&self.adapter_info)
     }
 
     /// Check if wgpu is available on this system.
     pub fn is_available() -> bool {
-        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
-            ..Default::default()
-        });
-
-        pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        pollster::block_on(instance().request_adapter(&wgpu::RequestAdapterOptions {
             power_preference: wgpu::PowerPreference::HighPerformance,
             compatible_surface: None,
             force_fallback_adapter: false,
@@ -51,3 +84,20 @@ impl WgpuContext {
         .is_some()
     }
 }
+
+fn device_info(info: &wgpu::AdapterInfo) -> DeviceInfo {
+    let device_type = match info.device_type {
+        wgpu::DeviceType::IntegratedGpu => DEVICE_INTEGRATED,
+        wgpu::DeviceType::DiscreteGpu => DEVICE_DISCRETE,
+        wgpu::DeviceType::VirtualGpu => DEVICE_VIRTUAL,
+        wgpu::DeviceType::Cpu => DEVICE_CPU,
+        wgpu::DeviceType::Other => DEVICE_OTHER,
+    };
+    DeviceInfo {
+        name: info.name.clone(),
+        device_type,
+        // wgpu does not expose adapter memory size
+        memory: 0,
+        backend: format!("wgpu/{:?}", info.backend),
+    }
+}
@@ -4,3 +4,4 @@ pub mod buffer_ops;
 pub mod compile;
 pub mod device_init;
 pub mod dispatch;
+pub mod transfer;
@@ -0,0 +1,130 @@
+//! WebGPU async transfers — uploads and readbacks that complete in the background
+//!
+//! Uploads stage data with `Queue::write_buffer` and submit immediately;
+//! readbacks copy into a MAP_READ staging buffer and map it asynchronously.
+//! Completion is observed by polling the device, so the caller can keep
+//! recording compute work while the copy is in flight.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+use wgpu;
+
+use super::buffer_ops::WgpuBuffer;
+
+const PENDING: u8 = 0;
+const DONE: u8 = 1;
+const FAILED: u8 = 2;
+
+/// Readback whose mapped staging data still has to be copied to the host.
+struct PendingReadback {
+    staging: wgpu::Buffer,
+    dst: *mut u8,
+    len: usize,
+}
+
+/// In-flight wgpu transfer.
+pub struct WgpuTransfer {
+    device: *const wgpu::Device,
+    state: Arc<AtomicU8>,
+    readback: Option<PendingReadback>,
+}
+
+impl WgpuTransfer {
+    /// Poll the device without blocking; true once the transfer has landed.
+    pub fn is_complete(&mut self) -> bool {
+        let device = unsafe { &*self.device };
+        device.poll(wgpu::Maintain::Poll);
+        self.finish()
+    }
+
+    /// Block until the transfer lands. Returns false if it failed.
+    pub fn wait(&mut self) -> bool {
+        let device = unsafe { &*self.device };
+        while self.state.load(Ordering::Acquire) == PENDING {
+            device.poll(wgpu::Maintain::Wait);
+        }
+        self.finish() && self.state.load(Ordering::Acquire) == DONE
+    }
+
+    /// Copy a mapped readback into host memory once, after the map completes.
+    fn finish(&mut self) -> bool {
+        match self.state.load(Ordering::Acquire) {
+            PENDING => false,
+            DONE => {
+                if let Some(rb) = self.readback.take() {
+                    let data = rb.staging.slice(..).get_mapped_range();
+                    unsafe { std::ptr::copy_nonoverlapping(data.as_ptr(), rb.dst, rb.len) };
+                    drop(data);
+                    rb.staging.unmap();
+                }
+                true
+            }
+            _ => true,
+        }
+    }
+}
+
+impl WgpuBuffer {
+    /// Start copying `len` bytes from `src` into the buffer at `offset`.
+    ///
+    /// `src` is consumed before this returns (wgpu stages the write), so the
+    /// host memory may be reused immediately.
+    ///
+    /// # Safety
+    /// `src` must point to at least `len` readable bytes.
+    pub unsafe fn upload_async(&self, offset: usize, src: *const u8, len: usize) -> WgpuTransfer {
+        let queue = &*self.queue;
+        let data = std::slice::from_raw_parts(src, len);
+        queue.write_buffer(&self.buffer, offset as u64, data);
+        queue.submit(std::iter::empty());
+
+        let state = Arc::new(AtomicU8::new(PENDING));
+        let done = state.clone();
+        queue.on_submitted_work_done(move || done.store(DONE, Ordering::Release));
+
+        WgpuTransfer {
+            device: self.device,
+            state,
+            readback: None,
+        }
+    }
+
+    /// Start copying the first `len` bytes of the buffer to `dst`.
+    ///
+    /// `dst` is written when the transfer is observed complete.
+    ///
+    /// # Safety
+    /// `dst` must stay valid for `len` writable bytes until then.
+    pub unsafe fn download_async(&self, dst: *mut u8, len: usize) -> WgpuTransfer {
+        let device = &*self.device;
+        let queue = &*self.queue;
+
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("rayzor_async_staging"),
+            size: len as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("rayzor_async_readback"),
+        });
+        encoder.copy_buffer_to_buffer(&self.buffer, 0, &staging, 0, len as u64);
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let state = Arc::new(AtomicU8::new(PENDING));
+        let done = state.clone();
+        staging
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let s = if result.is_ok() { DONE } else { FAILED };
+                done.store(s, Ordering::Release);
+            });
+
+        WgpuTransfer {
+            device: self.device,
+            state,
+            readback: Some(PendingReadback { staging, dst, len }),
+        }
+    }
+}
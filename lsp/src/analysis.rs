@@ -0,0 +1,124 @@
+//! Checking a document with the compiler
+//!
+//! The text is first parsed with the incremental parser, which recovers
+//! after a syntax error and reports all of them with exact spans. Only a
+//! file that parses is type checked, through the compilation pipeline with
+//! lowering past the TAST turned off; that also builds the symbol index
+//! behind hover, go-to-definition and document symbols.
+//!
+//! A compiler panic is caught and reported as an error at the start of the
+//! file, so one bad input doesn't take the server down.
+
+use std::panic::{self, AssertUnwindSafe};
+
+use compiler::pipeline::{HaxeCompilationPipeline, PipelineConfig};
+use compiler::tools::symbol_index::SymbolIndex;
+use diagnostics::{
+    Diagnostic, DiagnosticBuilder, DiagnosticSeverity, FileId, SourceMap, SourcePosition,
+    SourceSpan,
+};
+
+/// Diagnostics and symbols of one version of a document
+#[derive(Debug, Default)]
+pub struct Analysis {
+    /// Spans are byte offsets into the analyzed text
+    pub diagnostics: Vec<Diagnostic>,
+    /// `None` if the text did not parse
+    pub index: Option<SymbolIndex>,
+}
+
+impl Analysis {
+    /// Check `text`, the contents of `file`
+    pub fn run(file: &str, text: &str) -> Self {
+        guarded(|| Self::check(file, text))
+    }
+
+    fn check(file: &str, text: &str) -> Self {
+        let parsed = parser::parse_incrementally_enhanced(file, text);
+        if parsed.has_errors() {
+            return Analysis {
+                diagnostics: parsed
+                    .diagnostics
+                    .diagnostics
+                    .into_iter()
+                    .filter(|d| d.severity == DiagnosticSeverity::Error)
+                    .collect(),
+                index: None,
+            };
+        }
+
+        let mut pipeline = HaxeCompilationPipeline::with_config(PipelineConfig {
+            enable_hir_lowering: false,
+            enable_hir_optimization: false,
+            enable_hir_validation: false,
+            enable_mir_lowering: false,
+            enable_mir_optimization: false,
+            enable_colored_errors: false,
+            emit_symbol_index: true,
+            ..Default::default()
+        });
+        let result = pipeline.compile_file(file, text);
+
+        // The pipeline reports file 0 of a single-file source map
+        let mut source_map = SourceMap::new();
+        source_map.add_file(file.to_string(), text.to_string());
+        let diagnostics = result
+            .errors
+            .iter()
+            .map(|error| error.to_diagnostic(&source_map))
+            .chain(
+                result
+                    .warnings
+                    .iter()
+                    .map(|warning| warning.to_diagnostic(&source_map)),
+            )
+            .collect();
+
+        Analysis {
+            diagnostics,
+            index: result.symbol_indexes.into_iter().next(),
+        }
+    }
+}
+
+/// Run `check`, turning a panic into a diagnostic
+fn guarded(check: impl FnOnce() -> Analysis) -> Analysis {
+    match panic::catch_unwind(AssertUnwindSafe(check)) {
+        Ok(analysis) => analysis,
+        Err(payload) => {
+            let reason = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            let start = SourcePosition::new(1, 1, 0);
+            Analysis {
+                diagnostics: vec![DiagnosticBuilder::error(
+                    format!(
+                        "internal compiler error while checking this file: {}",
+                        reason
+                    ),
+                    SourceSpan::single_position(start, FileId::new(0)),
+                )
+                .build()],
+                index: None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panic_becomes_diagnostic() {
+        let analysis = guarded(|| panic!("lowering blew up"));
+        assert!(analysis.index.is_none());
+        assert_eq!(analysis.diagnostics.len(), 1);
+        let diagnostic = &analysis.diagnostics[0];
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::Error);
+        assert!(diagnostic.message.contains("lowering blew up"));
+        assert_eq!(diagnostic.span.start.byte_offset, 0);
+    }
+}
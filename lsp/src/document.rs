@@ -0,0 +1,102 @@
+//! Open documents and LSP position conversion
+//!
+//! LSP positions are 0-based lines and UTF-16 code unit columns; the
+//! compiler works in byte offsets. [`LineIndex`] converts between the two.
+
+use lsp_types::{Position, Range};
+
+use crate::analysis::Analysis;
+
+/// Line starts of a text, for offset/position conversion
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        Self { line_starts }
+    }
+
+    /// Position of byte `offset` in `text`, clamped to its end
+    pub fn position(&self, text: &str, offset: usize) -> Position {
+        let offset = offset.min(text.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let start = self.line_starts[line];
+        let character = text.get(start..offset).map_or(offset - start, |prefix| {
+            prefix.chars().map(char::len_utf16).sum()
+        });
+        Position::new(line as u32, character as u32)
+    }
+
+    pub fn range(&self, text: &str, range: std::ops::Range<usize>) -> Range {
+        Range::new(
+            self.position(text, range.start),
+            self.position(text, range.end),
+        )
+    }
+
+    /// Byte offset of `position` in `text`; past the end of a line is its
+    /// end, past the last line the end of the text
+    pub fn offset(&self, text: &str, position: Position) -> usize {
+        let Some(&start) = self.line_starts.get(position.line as usize) else {
+            return text.len();
+        };
+        let line = &text[start..];
+        let line = &line[..line.find('\n').unwrap_or(line.len())];
+        let mut units = 0;
+        for (i, c) in line.char_indices() {
+            if units >= position.character as usize {
+                return start + i;
+            }
+            units += c.len_utf16();
+        }
+        start + line.len()
+    }
+}
+
+/// An open document and the analysis of its current text
+pub struct Document {
+    pub text: String,
+    pub version: i32,
+    pub line_index: LineIndex,
+    pub analysis: Analysis,
+}
+
+impl Document {
+    pub fn new(file: &str, text: String, version: i32) -> Self {
+        let analysis = Analysis::run(file, &text);
+        Self {
+            line_index: LineIndex::new(&text),
+            text,
+            version,
+            analysis,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_round_trip() {
+        // 'é' is 2 bytes and 1 UTF-16 unit, '𝄞' 4 bytes and 2 units
+        let text = "var a = 1;\nvar é𝄞 = \"x\";\n";
+        let index = LineIndex::new(text);
+
+        let name = text.find('é').unwrap();
+        assert_eq!(index.position(text, name), Position::new(1, 4));
+        let after = name + 'é'.len_utf8() + '𝄞'.len_utf8();
+        assert_eq!(index.position(text, after), Position::new(1, 7));
+        assert_eq!(index.offset(text, Position::new(1, 7)), after);
+        assert_eq!(index.offset(text, Position::new(1, 4)), name);
+
+        // Past the end of a line or the text
+        assert_eq!(index.offset(text, Position::new(0, 99)), 10);
+        assert_eq!(index.offset(text, Position::new(9, 0)), text.len());
+        assert_eq!(index.position(text, text.len()), Position::new(2, 0));
+    }
+}
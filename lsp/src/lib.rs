@@ -0,0 +1,344 @@
+//! Rayzor language server (`rayzor lsp`)
+//!
+//! Speaks the Language Server Protocol over stdio and answers from the
+//! compiler's own front end:
+//!
+//! - live diagnostics: syntax errors from the incremental parser, then type
+//!   errors and warnings from the type checker, published on every change
+//! - hover: the signature of the symbol under the cursor (`var x:Int`)
+//! - go to definition, for symbols declared in the same file
+//! - document symbols: the file's types and their members
+//!
+//! Documents are synchronized in full and re-checked on each change. Each
+//! file is checked on its own, so imports from other project files don't
+//! resolve yet.
+
+mod analysis;
+mod document;
+
+use std::collections::HashMap;
+
+use lsp_server::{Connection, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+    PublishDiagnostics,
+};
+use lsp_types::request::{DocumentSymbolRequest, GotoDefinition, HoverRequest, Request as _};
+use lsp_types::{
+    DiagnosticRelatedInformation, DocumentSymbol, DocumentSymbolResponse, GotoDefinitionResponse,
+    Hover, HoverContents, HoverProviderCapability, Location, MarkupContent, MarkupKind,
+    NumberOrString, OneOf, PublishDiagnosticsParams, ServerCapabilities, ServerInfo,
+    TextDocumentPositionParams, TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+
+use compiler::tools::symbol_index::{Definition, DefinitionKind, SymbolIndex};
+use diagnostics::{DiagnosticSeverity, LabelStyle};
+
+use document::Document;
+
+/// Serve one client on stdin/stdout until it asks to exit
+pub fn run_stdio() -> Result<(), String> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = serde_json::to_value(capabilities()).map_err(|e| e.to_string())?;
+    let initialize = serde_json::json!({
+        "capabilities": capabilities,
+        "serverInfo": ServerInfo {
+            name: "rayzor".to_string(),
+            version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        },
+    });
+    let (id, _params) = connection
+        .initialize_start()
+        .map_err(|e| format!("LSP initialization failed: {}", e))?;
+    connection
+        .initialize_finish(id, initialize)
+        .map_err(|e| format!("LSP initialization failed: {}", e))?;
+    log::info!("rayzor lsp: initialized");
+
+    Server::default().serve(&connection)?;
+
+    drop(connection);
+    io_threads
+        .join()
+        .map_err(|e| format!("LSP connection failed: {}", e))
+}
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    }
+}
+
+/// Open documents by URI
+#[derive(Default)]
+struct Server {
+    documents: HashMap<Url, Document>,
+}
+
+impl Server {
+    fn serve(&mut self, connection: &Connection) -> Result<(), String> {
+        for message in &connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if connection
+                        .handle_shutdown(&request)
+                        .map_err(|e| e.to_string())?
+                    {
+                        return Ok(());
+                    }
+                    let response = self.handle_request(request);
+                    send(connection, Message::Response(response))?;
+                }
+                Message::Notification(notification) => {
+                    if let Some(uri) = self.handle_notification(notification) {
+                        send(connection, self.publish_diagnostics(&uri))?;
+                    }
+                }
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        let result = match request.method.as_str() {
+            HoverRequest::METHOD => params::<HoverRequest>(request)
+                .map(|p| serde_json::to_value(self.hover(&p.text_document_position_params))),
+            GotoDefinition::METHOD => params::<GotoDefinition>(request)
+                .map(|p| serde_json::to_value(self.definition(&p.text_document_position_params))),
+            DocumentSymbolRequest::METHOD => params::<DocumentSymbolRequest>(request)
+                .map(|p| serde_json::to_value(self.document_symbols(&p.text_document.uri))),
+            method => {
+                return Response::new_err(
+                    id,
+                    lsp_server::ErrorCode::MethodNotFound as i32,
+                    format!("unsupported request {}", method),
+                )
+            }
+        };
+        match result {
+            Ok(Ok(value)) => Response::new_ok(id, value),
+            Ok(Err(e)) => Response::new_err(
+                id,
+                lsp_server::ErrorCode::InternalError as i32,
+                e.to_string(),
+            ),
+            Err(e) => Response::new_err(id, lsp_server::ErrorCode::InvalidParams as i32, e),
+        }
+    }
+
+    /// Apply a document notification; returns the URI whose diagnostics
+    /// changed
+    fn handle_notification(&mut self, notification: Notification) -> Option<Url> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: lsp_types::DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params).ok()?;
+                let document = params.text_document;
+                self.open(document.uri.clone(), document.text, document.version);
+                Some(document.uri)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: lsp_types::DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params).ok()?;
+                // Full sync: the last change holds the whole text
+                let text = params.content_changes.into_iter().last()?.text;
+                let uri = params.text_document.uri;
+                self.open(uri.clone(), text, params.text_document.version);
+                Some(uri)
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: lsp_types::DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params).ok()?;
+                self.documents.remove(&params.text_document.uri);
+                Some(params.text_document.uri)
+            }
+            _ => None,
+        }
+    }
+
+    fn open(&mut self, uri: Url, text: String, version: i32) {
+        let file = match uri.to_file_path() {
+            Ok(path) => path.to_string_lossy().into_owned(),
+            Err(()) => uri.to_string(),
+        };
+        self.documents
+            .insert(uri, Document::new(&file, text, version));
+    }
+
+    /// Diagnostics of `uri`, empty once it is closed
+    fn publish_diagnostics(&self, uri: &Url) -> Message {
+        let (diagnostics, version) = match self.documents.get(uri) {
+            Some(document) => (
+                document
+                    .analysis
+                    .diagnostics
+                    .iter()
+                    .map(|d| to_lsp_diagnostic(uri, document, d))
+                    .collect(),
+                Some(document.version),
+            ),
+            None => (Vec::new(), None),
+        };
+        Message::Notification(Notification::new(
+            PublishDiagnostics::METHOD.to_string(),
+            PublishDiagnosticsParams {
+                uri: uri.clone(),
+                diagnostics,
+                version,
+            },
+        ))
+    }
+
+    /// The document at `position` and the definition there
+    fn definition_at(
+        &self,
+        position: &TextDocumentPositionParams,
+    ) -> Option<(&Document, &Definition)> {
+        let document = self.documents.get(&position.text_document.uri)?;
+        let index = document.analysis.index.as_ref()?;
+        let offset = document
+            .line_index
+            .offset(&document.text, position.position);
+        Some((document, index.definition_at(offset)?))
+    }
+
+    fn hover(&self, position: &TextDocumentPositionParams) -> Option<Hover> {
+        let (_, definition) = self.definition_at(position)?;
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!("```haxe\n{}\n```", definition.detail),
+            }),
+            range: None,
+        })
+    }
+
+    fn definition(&self, position: &TextDocumentPositionParams) -> Option<GotoDefinitionResponse> {
+        let (document, definition) = self.definition_at(position)?;
+        let range = definition.name_range.clone()?;
+        Some(GotoDefinitionResponse::Scalar(Location::new(
+            position.text_document.uri.clone(),
+            document.line_index.range(&document.text, range),
+        )))
+    }
+
+    fn document_symbols(&self, uri: &Url) -> Option<DocumentSymbolResponse> {
+        let document = self.documents.get(uri)?;
+        let index = document.analysis.index.as_ref()?;
+        Some(DocumentSymbolResponse::Nested(outline(
+            document, index, None,
+        )))
+    }
+}
+
+/// Declarations of `index` directly inside `container`, with their members
+fn outline(
+    document: &Document,
+    index: &SymbolIndex,
+    container: Option<usize>,
+) -> Vec<DocumentSymbol> {
+    index
+        .definitions
+        .iter()
+        .enumerate()
+        .filter(|(_, d)| d.container == container && !d.local)
+        .filter_map(|(i, definition)| {
+            let range = document
+                .line_index
+                .range(&document.text, definition.name_range.clone()?);
+            let children = outline(document, index, Some(i));
+            #[allow(deprecated)]
+            Some(DocumentSymbol {
+                name: definition.name.clone(),
+                detail: Some(definition.detail.clone()),
+                kind: symbol_kind(definition.kind),
+                tags: None,
+                deprecated: None,
+                range,
+                selection_range: range,
+                children: (!children.is_empty()).then_some(children),
+            })
+        })
+        .collect()
+}
+
+fn symbol_kind(kind: DefinitionKind) -> lsp_types::SymbolKind {
+    use lsp_types::SymbolKind;
+    match kind {
+        DefinitionKind::Class => SymbolKind::CLASS,
+        DefinitionKind::Interface => SymbolKind::INTERFACE,
+        DefinitionKind::Enum => SymbolKind::ENUM,
+        DefinitionKind::EnumVariant => SymbolKind::ENUM_MEMBER,
+        DefinitionKind::Abstract => SymbolKind::STRUCT,
+        DefinitionKind::Typedef => SymbolKind::TYPE_PARAMETER,
+        DefinitionKind::Field => SymbolKind::FIELD,
+        DefinitionKind::Method => SymbolKind::METHOD,
+        DefinitionKind::Constructor => SymbolKind::CONSTRUCTOR,
+        DefinitionKind::Function => SymbolKind::FUNCTION,
+        DefinitionKind::Variable => SymbolKind::VARIABLE,
+        DefinitionKind::Parameter => SymbolKind::VARIABLE,
+    }
+}
+
+/// `diagnostic` of `document`, with notes and help appended to the message
+/// and secondary labels as related information
+fn to_lsp_diagnostic(
+    uri: &Url,
+    document: &Document,
+    diagnostic: &diagnostics::Diagnostic,
+) -> lsp_types::Diagnostic {
+    let range = |span: &diagnostics::SourceSpan| {
+        document
+            .line_index
+            .range(&document.text, span.start.byte_offset..span.end.byte_offset)
+    };
+
+    let mut message = diagnostic.message.clone();
+    for note in &diagnostic.notes {
+        message.push_str(&format!("\nnote: {}", note));
+    }
+    for help in &diagnostic.help {
+        message.push_str(&format!("\nhelp: {}", help));
+    }
+    let related: Vec<DiagnosticRelatedInformation> = diagnostic
+        .labels
+        .iter()
+        .filter(|label| label.style == LabelStyle::Secondary)
+        .map(|label| DiagnosticRelatedInformation {
+            location: Location::new(uri.clone(), range(&label.span)),
+            message: label.message.clone(),
+        })
+        .collect();
+
+    lsp_types::Diagnostic {
+        range: range(&diagnostic.span),
+        severity: Some(match diagnostic.severity {
+            DiagnosticSeverity::Error => lsp_types::DiagnosticSeverity::ERROR,
+            DiagnosticSeverity::Warning => lsp_types::DiagnosticSeverity::WARNING,
+            DiagnosticSeverity::Info => lsp_types::DiagnosticSeverity::INFORMATION,
+            DiagnosticSeverity::Hint => lsp_types::DiagnosticSeverity::HINT,
+        }),
+        code: diagnostic.code.clone().map(NumberOrString::String),
+        source: Some("rayzor".to_string()),
+        message,
+        related_information: (!related.is_empty()).then_some(related),
+        ..Default::default()
+    }
+}
+
+fn params<R: lsp_types::request::Request>(request: Request) -> Result<R::Params, String> {
+    serde_json::from_value(request.params).map_err(|e| format!("invalid {}: {}", R::METHOD, e))
+}
+
+fn send(connection: &Connection, message: Message) -> Result<(), String> {
+    connection
+        .sender
+        .send(message)
+        .map_err(|e| format!("LSP connection closed: {}", e))
+}
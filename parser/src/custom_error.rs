@@ -20,29 +20,43 @@ pub struct ContextualError<I> {
     pub byte_offset: Option<usize>,
 }
 
-// Thread-local storage for the full input to calculate byte offsets
+// Thread-local address range of the full input, to calculate byte offsets.
+// Parser inputs are slices of it, so an offset is a pointer difference; no
+// copy of the input is kept and no search is done while backtracking.
 thread_local! {
-    static FULL_INPUT: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
+    static FULL_INPUT: std::cell::Cell<Option<(usize, usize)>> = const { std::cell::Cell::new(None) };
     static DEEPEST_ERROR: std::cell::RefCell<Option<(Vec<ContextWithLocation>, usize)>> = const { std::cell::RefCell::new(None) };
 }
 
 /// Set the full input for automatic byte offset calculation
 pub fn set_full_input(input: &str) {
-    FULL_INPUT.with(|i| {
-        *i.borrow_mut() = Some(input.to_string());
-    });
+    FULL_INPUT.with(|i| i.set(Some((input.as_ptr() as usize, input.len()))));
 }
 
 /// Clear the full input
 pub fn clear_full_input() {
-    FULL_INPUT.with(|i| {
-        *i.borrow_mut() = None;
-    });
+    FULL_INPUT.with(|i| i.set(None));
     DEEPEST_ERROR.with(|e| {
         *e.borrow_mut() = None;
     });
 }
 
+/// Byte offset of `input` within the full input, or 0 if it isn't a slice
+/// of it
+fn full_input_offset(input: &str) -> usize {
+    FULL_INPUT.with(|full| match full.get() {
+        Some((start, len)) => {
+            let addr = input.as_ptr() as usize;
+            if addr >= start && addr + input.len() <= start + len {
+                addr - start
+            } else {
+                0
+            }
+        }
+        None => 0,
+    })
+}
+
 /// Get the deepest error if any
 pub fn get_deepest_error() -> Option<(Vec<ContextWithLocation>, usize)> {
     DEEPEST_ERROR.with(|e| e.borrow().clone())
@@ -144,13 +158,7 @@ where
         // }
 
         // Calculate byte offset for the new input position
-        let new_byte_offset = FULL_INPUT.with(|full| {
-            if let Some(full_str) = full.borrow().as_ref() {
-                full_str.find(input.as_ref()).unwrap_or(0)
-            } else {
-                0
-            }
-        });
+        let new_byte_offset = full_input_offset(input.as_ref());
 
         // println!("        new byte_offset would be: {}", new_byte_offset);
 
@@ -204,14 +212,7 @@ where
     fn add_context(_input: I, ctx: &'static str, mut other: Self) -> Self {
         // This is called when a parser with context() fails
         // Calculate the byte offset for this context based on the error input position
-        let byte_offset = FULL_INPUT.with(|full| {
-            if let Some(full_str) = full.borrow().as_ref() {
-                let error_input = other.input.as_ref();
-                full_str.find(error_input).unwrap_or(0)
-            } else {
-                0
-            }
-        });
+        let byte_offset = full_input_offset(other.input.as_ref());
 
         // println!("add_context: '{}' at offset {}", ctx, byte_offset);
 
@@ -321,4 +322,16 @@ mod tests {
             _ => panic!("Expected error with context"),
         }
     }
+
+    #[test]
+    fn test_context_offset_in_full_input() {
+        let input = "hello hello world";
+        set_full_input(input);
+        let result = test_parser(&input[12..]);
+        clear_full_input();
+        match result {
+            Err(nom::Err::Error(e)) => assert_eq!(e.contexts[0].byte_offset, 12),
+            _ => panic!("Expected error with context"),
+        }
+    }
 }
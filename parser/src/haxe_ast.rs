@@ -652,6 +652,10 @@ pub enum ExprKind {
         expr: Option<Box<Expr>>,
     },
 
+    /// Local static variable: `static var x = 0` inside a function body.
+    /// Wraps the `Var` or `Final` declaration; its value persists across calls.
+    StaticVar(Box<Expr>),
+
     /// Function expression: `function(x) return x * 2`
     Function(Function),
 
@@ -62,12 +62,23 @@ pub fn parse_haxe_file_with_debug(
     input: &str,
     recovery: bool,
     debug: bool,
+) -> Result<HaxeFile, String> {
+    let preprocessor_config = crate::preprocessor::PreprocessorConfig::default();
+    parse_haxe_file_with_config(file_name, input, recovery, debug, &preprocessor_config)
+}
+
+/// Parse a complete Haxe file with the given conditional compilation defines
+pub fn parse_haxe_file_with_config(
+    file_name: &str,
+    input: &str,
+    recovery: bool,
+    debug: bool,
+    preprocessor_config: &crate::preprocessor::PreprocessorConfig,
 ) -> Result<HaxeFile, String> {
     let is_import_file = is_import_hx_file(file_name);
 
     // Preprocess to handle conditional compilation directives
-    let preprocessor_config = crate::preprocessor::PreprocessorConfig::default();
-    let preprocessed = crate::preprocessor::preprocess(input, &preprocessor_config);
+    let preprocessed = crate::preprocessor::preprocess(input, preprocessor_config);
 
     if recovery {
         parse_haxe_file_with_enhanced_errors(&preprocessed, file_name, is_import_file).map_err(
@@ -78,14 +89,15 @@ pub fn parse_haxe_file_with_debug(
         )
     } else {
         // Use enhanced incremental parser with diagnostics for better error reporting
-        let incremental_result = crate::incremental_parser_enhanced::parse_incrementally_enhanced(
-            file_name,
-            &preprocessed,
-        );
+        let mut incremental_result =
+            crate::incremental_parser_enhanced::parse_incrementally_enhanced(
+                file_name,
+                &preprocessed,
+            );
 
         // Convert enhanced result to HaxeFile
         convert_enhanced_incremental_to_haxe_file(
-            incremental_result,
+            &mut incremental_result,
             file_name,
             &preprocessed,
             is_import_file,
@@ -95,8 +107,11 @@ pub fn parse_haxe_file_with_debug(
 }
 
 /// Convert enhanced incremental parse result to HaxeFile
+///
+/// Only the parsed elements are taken out of `result`, so callers can keep
+/// its diagnostics and source map without copying them.
 fn convert_enhanced_incremental_to_haxe_file(
-    result: crate::incremental_parser_enhanced::IncrementalParseResult,
+    result: &mut crate::incremental_parser_enhanced::IncrementalParseResult,
     file_name: &str,
     input: &str,
     is_import_file: bool,
@@ -118,7 +133,7 @@ fn convert_enhanced_incremental_to_haxe_file(
     let mut module_fields = Vec::new();
     let mut declarations = Vec::new();
 
-    for element in result.parsed_elements {
+    for element in std::mem::take(&mut result.parsed_elements) {
         match element {
             ParsedElement::Package(pkg) => package = Some(pkg),
             ParsedElement::Import(imp) => imports.push(imp),
@@ -268,16 +283,12 @@ pub fn parse_haxe_file_with_enhanced_errors(
     is_import_file: bool,
 ) -> Result<HaxeFile, (diagnostics::Diagnostics, diagnostics::SourceMap)> {
     // Use enhanced incremental parser for better error recovery with diagnostics
-    let incremental_result =
+    let mut incremental_result =
         crate::incremental_parser_enhanced::parse_incrementally_enhanced(file_name, input);
 
-    // Extract the diagnostics and source map from the result
-    let diagnostics = incremental_result.diagnostics.clone();
-    let source_map = incremental_result.source_map.clone();
-
     // If parsing succeeded, return the file (preserve source with debug=true)
     if let Ok(file) = convert_enhanced_incremental_to_haxe_file(
-        incremental_result,
+        &mut incremental_result,
         file_name,
         input,
         is_import_file,
@@ -287,7 +298,10 @@ pub fn parse_haxe_file_with_enhanced_errors(
     }
 
     // Otherwise, return the diagnostics and source map
-    Err((diagnostics, source_map))
+    Err((
+        incremental_result.diagnostics,
+        incremental_result.source_map,
+    ))
 }
 
 /// Parse a Haxe file and always return diagnostics along with the result
@@ -295,28 +309,34 @@ pub fn parse_haxe_file_with_diagnostics(
     file_name: &str,
     input: &str,
 ) -> Result<ParseResult, String> {
-    // Preprocess to handle conditional compilation directives
     let preprocessor_config = crate::preprocessor::PreprocessorConfig::default();
-    let preprocessed_source = crate::preprocessor::preprocess(input, &preprocessor_config);
+    parse_haxe_file_with_diagnostics_and_config(file_name, input, &preprocessor_config)
+}
+
+/// [`parse_haxe_file_with_diagnostics`] with the given conditional
+/// compilation defines
+pub fn parse_haxe_file_with_diagnostics_and_config(
+    file_name: &str,
+    input: &str,
+    preprocessor_config: &crate::preprocessor::PreprocessorConfig,
+) -> Result<ParseResult, String> {
+    // Preprocess to handle conditional compilation directives
+    let preprocessed_source = crate::preprocessor::preprocess(input, preprocessor_config);
 
     // Check if this is an import.hx file
     let is_import_file = is_import_hx_file(file_name);
 
     // Use enhanced incremental parser for better error recovery with diagnostics
     // Parse the preprocessed source instead of the original
-    let incremental_result = crate::incremental_parser_enhanced::parse_incrementally_enhanced(
+    let mut incremental_result = crate::incremental_parser_enhanced::parse_incrementally_enhanced(
         file_name,
         &preprocessed_source,
     );
 
-    // Extract the diagnostics and source map
-    let diagnostics = incremental_result.diagnostics.clone();
-    let source_map = incremental_result.source_map.clone();
-
     // Try to convert to HaxeFile
     // Use preprocessed source so the file has the correct content
     match convert_enhanced_incremental_to_haxe_file(
-        incremental_result,
+        &mut incremental_result,
         file_name,
         &preprocessed_source,
         is_import_file,
@@ -324,8 +344,8 @@ pub fn parse_haxe_file_with_diagnostics(
     ) {
         Ok(file) => Ok(ParseResult {
             file,
-            diagnostics,
-            source_map,
+            diagnostics: incremental_result.diagnostics,
+            source_map: incremental_result.source_map,
         }),
         Err(e) => {
             // If we have diagnostics, format them nicely
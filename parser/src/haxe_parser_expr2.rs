@@ -832,6 +832,9 @@ fn block_element<'a>(full: &'a str, input: &'a str) -> PResult<'a, BlockElement>
                     let error_msg = match &expr.kind {
                         ExprKind::Var { .. } => "expected ';' after variable declaration",
                         ExprKind::Final { .. } => "expected ';' after final variable declaration",
+                        ExprKind::StaticVar(_) => {
+                            "expected ';' after static variable declaration"
+                        }
                         ExprKind::Assign { .. } => "expected ';' after assignment",
                         ExprKind::Call { .. } => "expected ';' after function call",
                         ExprKind::Return { .. } => "expected ';' after return statement",
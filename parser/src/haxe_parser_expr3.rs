@@ -252,6 +252,7 @@ pub fn var_expr<'a>(full: &'a str, input: &'a str) -> PResult<'a, Expr> {
     use nom::error::context;
     let start = position(full, input);
 
+    let (input, is_static) = opt(keyword("static")).parse(input)?;
     let (input, is_final) = alt((
         value(
             true,
@@ -296,6 +297,16 @@ pub fn var_expr<'a>(full: &'a str, input: &'a str) -> PResult<'a, Expr> {
         }
     };
 
+    let kind = if is_static.is_some() {
+        let decl = Expr {
+            kind,
+            span: Span::new(start, end),
+        };
+        ExprKind::StaticVar(Box::new(decl))
+    } else {
+        kind
+    };
+
     Ok((
         input,
         Expr {
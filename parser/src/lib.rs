@@ -38,7 +38,8 @@ pub use diagnostics::haxe::HaxeDiagnostics;
 // Export new Haxe parser
 pub use haxe_ast::*;
 pub use haxe_parser::{
-    parse_haxe_file, parse_haxe_file_with_debug, parse_haxe_file_with_diagnostics, ParseResult,
+    parse_haxe_file, parse_haxe_file_with_config, parse_haxe_file_with_debug,
+    parse_haxe_file_with_diagnostics, parse_haxe_file_with_diagnostics_and_config, ParseResult,
 };
 pub use incremental_parser_enhanced::{
     parse_incrementally_enhanced, IncrementalParseResult as EnhancedParseResult,
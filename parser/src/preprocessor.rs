@@ -9,37 +9,117 @@
 //! - Custom defines
 //!
 //! Since Rayzor is a new target, we need to:
-//! 1. Define which platform defines are active (rayzor, and maybe sys for system access)
+//! 1. Define which platform defines are active (`rayzor`, `sys`, and the
+//!    version defines `haxe`, `haxe_ver`, `rayzor_ver`)
 //! 2. Strip out platform-specific code for other targets
 //! 3. Keep only code that applies to Rayzor
+//!
+//! Defines with a value can be compared: `#if (haxe_ver >= 4.3)`,
+//! `#if (rayzor_ver >= "0.1.0")`. As in Haxe, `-` in a define's name is read
+//! as `_`, so `-D lazy-init` is tested with `#if lazy_init`.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 
-use std::collections::HashSet;
+/// Haxe version Rayzor is source-compatible with (the `haxe` define)
+pub const HAXE_VERSION: &str = "4.3.0";
+
+/// Rayzor version (the `rayzor_ver` define)
+pub const RAYZOR_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Configuration for conditional compilation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PreprocessorConfig {
     /// Active compiler defines (e.g., "rayzor", "sys", "debug")
     pub defines: HashSet<String>,
+    /// Values of the defines that have one (e.g., `haxe_ver` -> "4.300"),
+    /// used by comparisons. A define without one compares as "1".
+    pub values: HashMap<String, String>,
 }
 
 impl Default for PreprocessorConfig {
     fn default() -> Self {
-        let mut defines = HashSet::new();
+        // `debug` in debug builds of the compiler itself
+        Self::for_target(cfg!(debug_assertions))
+    }
+}
 
-        // Rayzor is our target
-        defines.insert("rayzor".to_string());
+impl PreprocessorConfig {
+    /// Defines of the Rayzor target; `debug` builds also define `debug`
+    pub fn for_target(debug: bool) -> Self {
+        let mut config = Self {
+            defines: HashSet::new(),
+            values: HashMap::new(),
+        };
+
+        // Rayzor is our target, with system access. `target.threaded` stays
+        // undefined: the stdlib's `haxe.Timer`, `EntryPoint` and `MainLoop`
+        // are built from their non-threaded code paths.
+        config.define("rayzor", None);
+        config.define("sys", None);
+        config.define("target.sys", None);
+        config.define("target.name", Some("rayzor"));
+        config.define("rayzor_ver", Some(RAYZOR_VERSION));
+
+        // Haxe 4 source compatibility
+        config.define("haxe", Some(HAXE_VERSION));
+        config.define("haxe3", None);
+        config.define("haxe4", None);
+        config.define("haxe_ver", Some(&haxe_ver(HAXE_VERSION)));
+
+        if debug {
+            config.define("debug", None);
+        }
+        config
+    }
 
-        // We support system access
-        defines.insert("sys".to_string());
+    /// Define `name` (`-D name` or `-D name=value`)
+    pub fn define(&mut self, name: &str, value: Option<&str>) {
+        let name = name.replace('-', "_");
+        match value {
+            Some(value) => {
+                self.values.insert(name.clone(), value.to_string());
+            }
+            None => {
+                self.values.remove(&name);
+            }
+        }
+        self.defines.insert(name);
+    }
 
-        // Add debug in debug builds
-        #[cfg(debug_assertions)]
-        defines.insert("debug".to_string());
+    /// Define each `NAME` or `NAME=VALUE` of a `-D` list
+    pub fn define_all<'a>(&mut self, defines: impl IntoIterator<Item = &'a str>) {
+        for define in defines {
+            match define.split_once('=') {
+                Some((name, value)) => self.define(name, Some(value)),
+                None => self.define(define, None),
+            }
+        }
+    }
+
+    /// Whether `name` is defined (`lazy-init` and `lazy_init` alike)
+    pub fn is_defined(&self, name: &str) -> bool {
+        self.defines.contains(&name.replace('-', "_"))
+    }
 
-        Self { defines }
+    /// Value a define compares as; None if it isn't defined
+    fn value(&self, name: &str) -> Option<&str> {
+        match self.values.get(name) {
+            Some(value) => Some(value),
+            None => self.defines.contains(name).then_some("1"),
+        }
     }
 }
 
+/// Haxe's `haxe_ver` for a version: "4.3.0" -> "4.300"
+fn haxe_ver(version: &str) -> String {
+    let mut parts = version.split('.').map(|p| p.parse::<u32>().unwrap_or(0));
+    let major = parts.next().unwrap_or(0);
+    let minor = parts.next().unwrap_or(0);
+    let patch = parts.next().unwrap_or(0);
+    format!("{}.{:03}", major, minor * 100 + patch)
+}
+
 /// Preprocess Haxe source code to handle conditional compilation
 ///
 /// This strips out platform-specific code that doesn't apply to Rayzor.
@@ -67,8 +147,12 @@ pub fn preprocess(source: &str, config: &PreprocessorConfig) -> String {
         let line = lines[i];
         let trimmed = line.trim_start();
 
-        // Check if this line contains inline conditionals (e.g., return #if flash ... #else ... #end;)
-        if line.contains("#if ") && line.contains("#end") {
+        // A script's shebang line (`#!/usr/bin/env rayzor`); kept as a blank
+        // line so line numbers don't shift
+        if i == 0 && line.starts_with("#!") {
+            result.push('\n');
+            i += 1;
+        } else if line.contains("#if ") && line.contains("#end") {
             // Process inline conditional
             let processed = process_inline_conditionals(line, config);
             result.push_str(&processed);
@@ -145,20 +229,29 @@ fn process_inline_conditionals(line: &str, config: &PreprocessorConfig) -> Strin
             let cond_start = absolute_if_start + 4; // Skip "#if "
 
             // Condition can include: identifiers, !, ||, &&, ()
-            // Find where the condition ends (first whitespace that ends the condition)
+            // Find where the condition ends (first whitespace that ends the condition,
+            // or the closing parenthesis of a parenthesized one)
             let mut cond_end = cond_start;
-            for (i, ch) in line[cond_start..].char_indices() {
-                // Condition can contain: alphanumeric, _, !, |, &, (, ), and spaces within operators
-                if ch.is_whitespace() {
-                    // Check if this whitespace is followed by more condition tokens
-                    let remaining = &line[cond_start + i..];
-                    let trimmed = remaining.trim_start();
-                    if !trimmed.starts_with("||")
-                        && !trimmed.starts_with("&&")
-                        && !trimmed.starts_with("!")
-                    {
-                        cond_end = cond_start + i;
-                        break;
+            let paren_end = line[cond_start..]
+                .starts_with('(')
+                .then(|| closing_paren(line, cond_start))
+                .flatten();
+            if let Some(end) = paren_end {
+                cond_end = end;
+            } else {
+                for (i, ch) in line[cond_start..].char_indices() {
+                    // Condition can contain: alphanumeric, _, !, |, &, (, ), and spaces within operators
+                    if ch.is_whitespace() {
+                        // Check if this whitespace is followed by more condition tokens
+                        let remaining = &line[cond_start + i..];
+                        let trimmed = remaining.trim_start();
+                        if !trimmed.starts_with("||")
+                            && !trimmed.starts_with("&&")
+                            && !trimmed.starts_with("!")
+                        {
+                            cond_end = cond_start + i;
+                            break;
+                        }
                     }
                 }
             }
@@ -237,6 +330,24 @@ fn process_inline_conditionals(line: &str, config: &PreprocessorConfig) -> Strin
     result
 }
 
+/// Index just past the `)` closing the `(` at `open`
+fn closing_paren(line: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, ch) in line[open..].char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
 /// Process a conditional block and return the lines from the active branch
 fn process_conditional_block<'a>(
     block_lines: &[&'a str],
@@ -345,6 +456,7 @@ fn extract_conditional_block<'a>(lines: &[&'a str], start_idx: usize) -> (Vec<&'
 /// - Boolean AND: `sys && debug`
 /// - Parentheses: `(java || cs) && sys`
 /// - Negation: `!jvm`
+/// - Comparisons of define values: `haxe_ver >= 4.3`, `target.name == "rayzor"`
 fn evaluate_condition(condition: &str, config: &PreprocessorConfig) -> bool {
     // For MVP, we'll implement a simple recursive descent evaluator
     let tokens = tokenize_condition(condition);
@@ -354,6 +466,9 @@ fn evaluate_condition(condition: &str, config: &PreprocessorConfig) -> bool {
 #[derive(Debug, Clone, PartialEq)]
 enum CondToken {
     Ident(String),
+    /// Number or string literal
+    Literal(String),
+    Compare(Ordering, bool),
     Or,
     And,
     Not,
@@ -370,24 +485,65 @@ fn tokenize_condition(condition: &str) -> Vec<CondToken> {
             ' ' | '\t' => continue,
             '(' => tokens.push(CondToken::LParen),
             ')' => tokens.push(CondToken::RParen),
+            '!' if chars.peek() == Some(&'=') => {
+                chars.next();
+                tokens.push(CondToken::Compare(Ordering::Equal, false));
+            }
             '!' => tokens.push(CondToken::Not),
-            '|' => {
-                if chars.peek() == Some(&'|') {
+            '=' if chars.peek() == Some(&'=') => {
+                chars.next();
+                tokens.push(CondToken::Compare(Ordering::Equal, true));
+            }
+            '<' | '>' => {
+                let ordering = if ch == '<' {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                };
+                // `>=` matches everything but `<`, `>` only `>`
+                if chars.peek() == Some(&'=') {
                     chars.next();
-                    tokens.push(CondToken::Or);
+                    tokens.push(CondToken::Compare(ordering.reverse(), false));
+                } else {
+                    tokens.push(CondToken::Compare(ordering, true));
                 }
             }
-            '&' => {
-                if chars.peek() == Some(&'&') {
-                    chars.next();
-                    tokens.push(CondToken::And);
+            '"' | '\'' => {
+                let mut literal = String::new();
+                for next_ch in chars.by_ref() {
+                    if next_ch == ch {
+                        break;
+                    }
+                    literal.push(next_ch);
+                }
+                tokens.push(CondToken::Literal(literal));
+            }
+            _ if ch.is_ascii_digit() => {
+                let mut literal = String::new();
+                literal.push(ch);
+                while let Some(&next_ch) = chars.peek() {
+                    if next_ch.is_ascii_digit() || next_ch == '.' {
+                        literal.push(chars.next().unwrap());
+                    } else {
+                        break;
+                    }
                 }
+                tokens.push(CondToken::Literal(literal));
+            }
+            '|' if chars.peek() == Some(&'|') => {
+                chars.next();
+                tokens.push(CondToken::Or);
+            }
+            '&' if chars.peek() == Some(&'&') => {
+                chars.next();
+                tokens.push(CondToken::And);
             }
             _ if ch.is_alphabetic() || ch == '_' => {
                 let mut ident = String::new();
                 ident.push(ch);
                 while let Some(&next_ch) = chars.peek() {
-                    if next_ch.is_alphanumeric() || next_ch == '_' {
+                    // Dotted names such as `target.sys`
+                    if next_ch.is_alphanumeric() || next_ch == '_' || next_ch == '.' {
                         ident.push(chars.next().unwrap());
                     } else {
                         break;
@@ -403,19 +559,26 @@ fn tokenize_condition(condition: &str) -> Vec<CondToken> {
 }
 
 fn evaluate_tokens(tokens: &[CondToken], config: &PreprocessorConfig) -> bool {
-    // Simple evaluation: check each identifier, combine with operators
-    // For MVP, we'll use a basic approach
-
     if tokens.is_empty() {
         return false;
     }
 
-    // Handle single identifier case
-    if tokens.len() == 1 {
-        if let CondToken::Ident(ref name) = tokens[0] {
-            return config.defines.contains(name);
-        }
-        return false;
+    // Handle parentheses around the whole condition
+    if tokens[0] == CondToken::LParen && closing_token(tokens) == Some(tokens.len() - 1) {
+        return evaluate_tokens(&tokens[1..tokens.len() - 1], config);
+    }
+
+    // Handle OR - if any part is true, return true. Operators inside
+    // parentheses are left to the parts.
+    let parts = split_by_token(tokens, &CondToken::Or);
+    if parts.len() > 1 {
+        return parts.iter().any(|part| evaluate_tokens(part, config));
+    }
+
+    // Handle AND - all parts must be true
+    let parts = split_by_token(tokens, &CondToken::And);
+    if parts.len() > 1 {
+        return parts.iter().all(|part| evaluate_tokens(part, config));
     }
 
     // Handle negation
@@ -423,48 +586,75 @@ fn evaluate_tokens(tokens: &[CondToken], config: &PreprocessorConfig) -> bool {
         return !evaluate_tokens(&tokens[1..], config);
     }
 
-    // Handle OR - if any part is true, return true
-    if tokens.contains(&CondToken::Or) {
-        // Split by OR and evaluate each part
-        let parts = split_by_token(tokens, &CondToken::Or);
-        for part in parts {
-            if evaluate_tokens(&part, config) {
-                return true;
-            }
-        }
-        return false;
+    // Handle comparison of a define's value: `haxe_ver >= 4.3`
+    if let [left, CondToken::Compare(ordering, matches), right] = tokens {
+        let (Some(left), Some(right)) = (operand(left, config), operand(right, config)) else {
+            return false;
+        };
+        return (compare_values(left, right) == *ordering) == *matches;
     }
 
-    // Handle AND - all parts must be true
-    if tokens.contains(&CondToken::And) {
-        let parts = split_by_token(tokens, &CondToken::And);
-        for part in parts {
-            if !evaluate_tokens(&part, config) {
-                return false;
+    // Single identifier
+    if let [CondToken::Ident(name)] = tokens {
+        return config.defines.contains(name);
+    }
+
+    false
+}
+
+/// Index of the `)` closing the `(` that starts `tokens`
+fn closing_token(tokens: &[CondToken]) -> Option<usize> {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            CondToken::LParen => depth += 1,
+            CondToken::RParen => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
             }
+            _ => {}
         }
-        return true;
     }
+    None
+}
 
-    // Handle parentheses (simplified - just remove them for now)
-    if tokens[0] == CondToken::LParen && tokens[tokens.len() - 1] == CondToken::RParen {
-        return evaluate_tokens(&tokens[1..tokens.len() - 1], config);
+/// Value of a comparison operand; None for an undefined define
+fn operand<'a>(token: &'a CondToken, config: &'a PreprocessorConfig) -> Option<&'a str> {
+    match token {
+        CondToken::Ident(name) => config.value(name),
+        CondToken::Literal(literal) => Some(literal),
+        _ => None,
     }
+}
 
-    // Single identifier
-    if let CondToken::Ident(ref name) = tokens[0] {
-        return config.defines.contains(name);
+/// Compare define values as numbers (`4.300` vs `4.3`), then as dotted
+/// versions (`0.1.0` vs `0.10.2`), and otherwise as strings
+fn compare_values(left: &str, right: &str) -> Ordering {
+    if let (Ok(l), Ok(r)) = (left.parse::<f64>(), right.parse::<f64>()) {
+        return l.partial_cmp(&r).unwrap_or(Ordering::Equal);
+    }
+    let version = |v: &str| -> Option<Vec<u64>> { v.split('.').map(|p| p.parse().ok()).collect() };
+    match (version(left), version(right)) {
+        (Some(l), Some(r)) => l.cmp(&r),
+        _ => left.cmp(right),
     }
-
-    false
 }
 
+/// Split `tokens` at each `separator` outside parentheses
 fn split_by_token(tokens: &[CondToken], separator: &CondToken) -> Vec<Vec<CondToken>> {
     let mut parts = Vec::new();
     let mut current = Vec::new();
+    let mut depth = 0;
 
     for token in tokens {
-        if token == separator {
+        match token {
+            CondToken::LParen => depth += 1,
+            CondToken::RParen => depth -= 1,
+            _ => {}
+        }
+        if token == separator && depth == 0 {
             if !current.is_empty() {
                 parts.push(current);
                 current = Vec::new();
@@ -519,6 +709,16 @@ mod tests {
         assert!(!evaluate_condition("rayzor && jvm", &config));
     }
 
+    #[test]
+    fn test_preprocess_shebang() {
+        let config = PreprocessorConfig::default();
+        let source = "#!/usr/bin/env rayzor\nclass Main {}\n";
+        assert_eq!(preprocess(source, &config), "\nclass Main {}\n");
+        // Only a first-line shebang is stripped
+        let source = "class Main {}\n#!x\n";
+        assert_eq!(preprocess(source, &config), source);
+    }
+
     #[test]
     fn test_preprocess_simple() {
         let source = r#"
@@ -731,4 +931,56 @@ abstract UInt(Int) {}
         assert!(result.contains("s:String"), "Result was: '{}'", result);
         assert!(!result.contains("#if"), "Result was: '{}'", result);
     }
+
+    #[test]
+    fn test_target_defines() {
+        let release = PreprocessorConfig::for_target(false);
+        assert!(evaluate_condition("rayzor && sys && haxe4", &release));
+        assert!(evaluate_condition("target.sys", &release));
+        assert!(!evaluate_condition("debug", &release));
+        assert!(evaluate_condition(
+            "debug",
+            &PreprocessorConfig::for_target(true)
+        ));
+        assert_eq!(release.values["haxe_ver"], "4.300");
+        assert_eq!(haxe_ver("4.1.5"), "4.105");
+    }
+
+    #[test]
+    fn test_version_comparisons() {
+        let config = PreprocessorConfig::for_target(false);
+        assert!(evaluate_condition("(haxe_ver >= 4.0)", &config));
+        assert!(evaluate_condition("haxe_ver > 4.2", &config));
+        assert!(!evaluate_condition("haxe_ver < 4", &config));
+        assert!(evaluate_condition("haxe_ver <= 4.300", &config));
+        assert!(evaluate_condition("haxe >= \"4.2.10\"", &config));
+        assert!(evaluate_condition("rayzor_ver >= \"0.1\"", &config));
+        assert!(evaluate_condition("target.name == \"rayzor\"", &config));
+        assert!(evaluate_condition("target.name != 'js'", &config));
+        assert!(evaluate_condition("sys && haxe_ver >= 4", &config));
+        // Undefined defines never compare
+        assert!(!evaluate_condition("js_es >= 6", &config));
+        assert!(!evaluate_condition("js_es < 6", &config));
+    }
+
+    #[test]
+    fn test_user_defines() {
+        let mut config = PreprocessorConfig::for_target(false);
+        config.define_all(["lazy-init", "level=3"]);
+        assert!(evaluate_condition("lazy_init", &config));
+        assert!(config.is_defined("lazy-init"));
+        assert!(evaluate_condition("level == 3 && level > 2", &config));
+        assert!(evaluate_condition("lazy_init == 1", &config));
+    }
+
+    #[test]
+    fn test_inline_parenthesized_comparison() {
+        let config = PreprocessorConfig::for_target(false);
+        let source = "var v = #if (haxe_ver >= 4.0) \"new\" #else \"old\" #end;";
+        assert_eq!(preprocess(source, &config), "var v = \"new\";");
+
+        let source =
+            "#if (haxe_ver < 4.0)\nold();\n#elseif (rayzor && haxe_ver >= 4)\nnew();\n#end\n";
+        assert_eq!(preprocess(source, &config), "new();\n");
+    }
 }
@@ -0,0 +1,68 @@
+//! Local static variable tests (Haxe 4.3 `static var` inside functions)
+
+use parser::haxe_ast::{BlockElement, Expr, ExprKind, TypeDeclaration};
+use parser::{parse_haxe_file, ClassFieldKind};
+
+fn parse_body(body: &str) -> Vec<Expr> {
+    let input = format!("class Test {{ function test() {{ {} }} }}", body);
+    let file = parse_haxe_file("test.hx", &input, false)
+        .unwrap_or_else(|e| panic!("Failed to parse '{}': {}", body, e));
+    let TypeDeclaration::Class(class) = &file.declarations[0] else {
+        panic!("Expected a class");
+    };
+    let ClassFieldKind::Function(func) = &class.fields[0].kind else {
+        panic!("Expected a method");
+    };
+    let Some(ExprKind::Block(elements)) = func.body.as_ref().map(|b| &b.kind) else {
+        panic!("Expected a block body");
+    };
+    elements
+        .iter()
+        .filter_map(|element| match element {
+            BlockElement::Expr(expr) => Some(expr.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_static_var() {
+    let exprs = parse_body("static var count = 0; count++;");
+    assert_eq!(exprs.len(), 2);
+    let ExprKind::StaticVar(decl) = &exprs[0].kind else {
+        panic!("Expected StaticVar, got {:?}", exprs[0].kind);
+    };
+    match &decl.kind {
+        ExprKind::Var {
+            name,
+            expr: Some(init),
+            ..
+        } => {
+            assert_eq!(name, "count");
+            assert!(matches!(init.kind, ExprKind::Int(0)));
+        }
+        other => panic!("Expected Var, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_static_final_with_type() {
+    let exprs = parse_body("static final cache:Array<Int> = [];");
+    let ExprKind::StaticVar(decl) = &exprs[0].kind else {
+        panic!("Expected StaticVar, got {:?}", exprs[0].kind);
+    };
+    match &decl.kind {
+        ExprKind::Final {
+            name,
+            type_hint: Some(_),
+            expr: Some(_),
+        } => assert_eq!(name, "cache"),
+        other => panic!("Expected Final, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_plain_var_is_not_static() {
+    let exprs = parse_body("var x = 1;");
+    assert!(matches!(exprs[0].kind, ExprKind::Var { .. }));
+}
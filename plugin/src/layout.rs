@@ -0,0 +1,303 @@
+//! Struct layout descriptors shared by the compiler, GPU plugin and FFI code
+//!
+//! The compiler places the fields of `@:cstruct`, `@:struct` and `@:gpuStruct`
+//! classes with [`LayoutBuilder`] and embeds the resulting [`StructLayout`] in
+//! the program as an encoded string (`Class.layout()`). Native packages decode
+//! it with [`StructLayout::parse`] instead of taking hand-computed sizes and
+//! offsets, so every consumer agrees on one layout.
+//!
+//! `@:struct` fields are placed in [`packed_order`] rather than declaration
+//! order unless the class is marked `@:keepLayout`.
+//!
+//! # Encoding
+//!
+//! ```text
+//! Particle,32,8;pos,Vec2,0,16,1;life,i64,16,8,1;data,f32,24,4,2
+//! ^name    ^size ^align  ^field,kind,offset,size,count
+//! ```
+//!
+//! `size` of a field covers all `count` elements of a fixed-size array.
+
+use std::fmt;
+
+/// Placement rules of the target the layout is computed for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutRules {
+    /// Natural alignment, as a C compiler lays out a struct (FFI, `cdef()`)
+    C,
+    /// Metal/WGSL storage buffers: every member is at least 4-byte aligned
+    Gpu,
+}
+
+impl LayoutRules {
+    /// Smallest alignment a field or struct may have under these rules
+    pub fn min_align(self) -> u32 {
+        match self {
+            LayoutRules::C => 1,
+            LayoutRules::Gpu => 4,
+        }
+    }
+}
+
+/// Element type of a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldKind {
+    I32,
+    U32,
+    I64,
+    F32,
+    F64,
+    Ptr,
+    /// Nested struct stored inline, by type name
+    Struct(String),
+}
+
+impl FieldKind {
+    /// Kind of a C or MSL field type name (`double`, `int`, `void*`, `Vec2`, ...)
+    pub fn from_c_type(c_type: &str) -> Self {
+        match c_type {
+            "int" => FieldKind::I32,
+            "uint" | "unsigned int" => FieldKind::U32,
+            "long" | "size_t" | "int64_t" => FieldKind::I64,
+            "float" => FieldKind::F32,
+            "double" => FieldKind::F64,
+            t if t.ends_with('*') => FieldKind::Ptr,
+            t => FieldKind::Struct(t.to_string()),
+        }
+    }
+
+    /// Short tag used in the encoded descriptor
+    pub fn tag(&self) -> &str {
+        match self {
+            FieldKind::I32 => "i32",
+            FieldKind::U32 => "u32",
+            FieldKind::I64 => "i64",
+            FieldKind::F32 => "f32",
+            FieldKind::F64 => "f64",
+            FieldKind::Ptr => "ptr",
+            FieldKind::Struct(name) => name,
+        }
+    }
+
+    fn from_tag(tag: &str) -> Self {
+        match tag {
+            "i32" => FieldKind::I32,
+            "u32" => FieldKind::U32,
+            "i64" => FieldKind::I64,
+            "f32" => FieldKind::F32,
+            "f64" => FieldKind::F64,
+            "ptr" => FieldKind::Ptr,
+            name => FieldKind::Struct(name.to_string()),
+        }
+    }
+}
+
+/// Placement of one field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldLayout {
+    pub name: String,
+    pub kind: FieldKind,
+    /// Byte offset from the start of the struct
+    pub offset: u32,
+    /// Bytes occupied, including every element of a fixed-size array
+    pub size: u32,
+    /// Element count; 1 unless the field is a fixed-size array
+    pub count: u32,
+}
+
+/// Size, alignment and field offsets of a struct
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructLayout {
+    pub name: String,
+    /// Total size, padded to `align`; also the array stride
+    pub size: u32,
+    pub align: u32,
+    pub fields: Vec<FieldLayout>,
+}
+
+impl StructLayout {
+    /// Look up a field by name
+    pub fn field(&self, name: &str) -> Option<&FieldLayout> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// Distance in bytes between consecutive elements of an array of this struct
+    pub fn stride(&self) -> u32 {
+        self.size
+    }
+
+    /// Encode as the compact string described in the module docs
+    pub fn encode(&self) -> String {
+        self.to_string()
+    }
+
+    /// Decode a string produced by [`StructLayout::encode`]
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut parts = s.split(';');
+        let header: Vec<&str> = parts.next().unwrap_or("").split(',').collect();
+        let [name, size, align] = header.as_slice() else {
+            return Err(format!("invalid layout header '{}'", s));
+        };
+        let mut layout = StructLayout {
+            name: name.to_string(),
+            size: parse_u32(size)?,
+            align: parse_u32(align)?,
+            fields: Vec::new(),
+        };
+        for part in parts.filter(|p| !p.is_empty()) {
+            let field: Vec<&str> = part.split(',').collect();
+            let [name, kind, offset, size, count] = field.as_slice() else {
+                return Err(format!("invalid layout field '{}'", part));
+            };
+            layout.fields.push(FieldLayout {
+                name: name.to_string(),
+                kind: FieldKind::from_tag(kind),
+                offset: parse_u32(offset)?,
+                size: parse_u32(size)?,
+                count: parse_u32(count)?,
+            });
+        }
+        Ok(layout)
+    }
+}
+
+impl fmt::Display for StructLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{}", self.name, self.size, self.align)?;
+        for field in &self.fields {
+            write!(
+                f,
+                ";{},{},{},{},{}",
+                field.name,
+                field.kind.tag(),
+                field.offset,
+                field.size,
+                field.count
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_u32(s: &str) -> Result<u32, String> {
+    s.parse()
+        .map_err(|_| format!("invalid layout number '{}'", s))
+}
+
+/// Places fields one after another, padding each to its alignment
+pub struct LayoutBuilder {
+    rules: LayoutRules,
+    layout: StructLayout,
+}
+
+impl LayoutBuilder {
+    pub fn new(name: impl Into<String>, rules: LayoutRules) -> Self {
+        Self {
+            rules,
+            layout: StructLayout {
+                name: name.into(),
+                size: 0,
+                align: rules.min_align(),
+                fields: Vec::new(),
+            },
+        }
+    }
+
+    /// Append a field of `count` elements; `size` covers all of them.
+    /// Returns the field's byte offset.
+    pub fn push(
+        &mut self,
+        name: impl Into<String>,
+        kind: FieldKind,
+        size: u32,
+        align: u32,
+        count: u32,
+    ) -> u32 {
+        let align = align.max(self.rules.min_align());
+        let offset = align_up(self.layout.size, align);
+        self.layout.fields.push(FieldLayout {
+            name: name.into(),
+            kind,
+            offset,
+            size,
+            count,
+        });
+        self.layout.size = offset + size;
+        self.layout.align = self.layout.align.max(align);
+        offset
+    }
+
+    /// Pad the total size to the struct alignment
+    pub fn finish(mut self) -> StructLayout {
+        self.layout.size = align_up(self.layout.size, self.layout.align);
+        self.layout
+    }
+}
+
+/// Placement order (indices into `aligns`) that leaves no padding between
+/// fields whose sizes are multiples of their alignment: strictest alignment
+/// first, declaration order among equals
+pub fn packed_order(aligns: &[u32]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..aligns.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(aligns[i]));
+    order
+}
+
+fn align_up(offset: u32, align: u32) -> u32 {
+    (offset + align - 1) & !(align - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn c_rules_pad_fields_and_total_size() {
+        let mut b = LayoutBuilder::new("Mixed", LayoutRules::C);
+        assert_eq!(b.push("flag", FieldKind::I32, 4, 4, 1), 0);
+        assert_eq!(b.push("value", FieldKind::F64, 8, 8, 1), 8);
+        assert_eq!(b.push("tag", FieldKind::I32, 4, 4, 1), 16);
+        let layout = b.finish();
+        assert_eq!((layout.size, layout.align), (24, 8));
+    }
+
+    #[test]
+    fn packed_order_removes_padding() {
+        // 24 bytes in declaration order (see above)
+        let fields = [
+            ("flag", FieldKind::I32, 4),
+            ("value", FieldKind::F64, 8),
+            ("tag", FieldKind::I32, 4),
+        ];
+        let aligns: Vec<u32> = fields.iter().map(|f| f.2).collect();
+        let order = packed_order(&aligns);
+        assert_eq!(order, vec![1, 0, 2]);
+
+        let mut b = LayoutBuilder::new("Mixed", LayoutRules::C);
+        for i in order {
+            let (name, kind, size) = fields[i].clone();
+            b.push(name, kind, size, size, 1);
+        }
+        assert_eq!(b.finish().size, 16);
+    }
+
+    #[test]
+    fn gpu_rules_raise_alignment_to_four() {
+        let mut b = LayoutBuilder::new("Tiny", LayoutRules::Gpu);
+        b.push("a", FieldKind::U32, 1, 1, 1);
+        assert_eq!(b.push("b", FieldKind::U32, 1, 1, 1), 4);
+        assert_eq!(b.finish().size, 8);
+    }
+
+    #[test]
+    fn encode_round_trips() {
+        let mut b = LayoutBuilder::new("Particle", LayoutRules::C);
+        b.push("pos", FieldKind::Struct("Vec2".into()), 16, 8, 1);
+        b.push("data", FieldKind::F32, 8, 4, 2);
+        let layout = b.finish();
+        let encoded = layout.encode();
+        assert_eq!(encoded, "Particle,24,8;pos,Vec2,0,16,1;data,f32,16,8,2");
+        assert_eq!(StructLayout::parse(&encoded).unwrap(), layout);
+        assert!(StructLayout::parse("Particle,24").is_err());
+    }
+}
@@ -22,6 +22,9 @@
 //! }
 //! ```
 
+pub mod layout;
+pub mod symbols;
+
 /// Trait for runtime plugins
 ///
 /// Implement this trait to provide
@@ -69,6 +72,16 @@ impl PluginRegistry {
             return Err(format!("Plugin '{}' is already registered", name));
         }
 
+        // Symbol names must be unique across plugins
+        let mut table = symbols::SymbolTable::new();
+        for p in &self.plugins {
+            table.register_global(p.name(), p.runtime_symbols());
+        }
+        let report = table.register_global(name, plugin.runtime_symbols());
+        if let Some(conflict) = report.conflicts.first() {
+            return Err(conflict.to_string());
+        }
+
         // Call the plugin's load hook
         plugin.on_load()?;
 
@@ -77,6 +90,9 @@ impl PluginRegistry {
     }
 
     /// Get all runtime symbols from all registered plugins
+    ///
+    /// Names are unique: `register` rejects plugins exporting a symbol that
+    /// another plugin already provides.
     pub fn collect_symbols(&self) -> Vec<(&'static str, *const u8)> {
         let mut symbols = Vec::new();
         for plugin in &self.plugins {
@@ -146,6 +162,62 @@ pub struct NativeMethodDesc {
 unsafe impl Send for NativeMethodDesc {}
 unsafe impl Sync for NativeMethodDesc {}
 
+/// Version of the native plugin interface: the `<name>_plugin_init` symbol
+/// table, [`NativeMethodDesc`] and [`NativePluginInfo`]. Bumped whenever
+/// their layout changes.
+pub const NATIVE_PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Build information a native plugin exports through `<name>_plugin_info`.
+///
+/// `#[repr(C)]` like [`NativeMethodDesc`]; strings point to static data in
+/// the plugin's binary.
+#[repr(C)]
+pub struct NativePluginInfo {
+    /// [`NATIVE_PLUGIN_ABI_VERSION`] the plugin was built against
+    pub abi_version: u32,
+    pub version: *const u8,
+    pub version_len: usize,
+    /// Comma-separated features compiled into the plugin (e.g. backends)
+    pub features: *const u8,
+    pub features_len: usize,
+}
+
+impl NativePluginInfo {
+    /// Info for a plugin built against the current ABI.
+    pub const fn new(version: &'static str, features: &'static str) -> Self {
+        Self {
+            abi_version: NATIVE_PLUGIN_ABI_VERSION,
+            version: version.as_ptr(),
+            version_len: version.len(),
+            features: features.as_ptr(),
+            features_len: features.len(),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `version` must point to `version_len` bytes of valid UTF-8.
+    pub unsafe fn version(&self) -> &str {
+        std::str::from_utf8_unchecked(std::slice::from_raw_parts(self.version, self.version_len))
+    }
+
+    /// Compiled-in features, without empty entries.
+    ///
+    /// # Safety
+    ///
+    /// `features` must point to `features_len` bytes of valid UTF-8.
+    pub unsafe fn features(&self) -> Vec<&str> {
+        std::str::from_utf8_unchecked(std::slice::from_raw_parts(self.features, self.features_len))
+            .split(',')
+            .filter(|f| !f.is_empty())
+            .collect()
+    }
+}
+
+// SAFETY: only points to static string data.
+unsafe impl Send for NativePluginInfo {}
+unsafe impl Sync for NativePluginInfo {}
+
 /// Declare a static table of native method descriptors for plugin registration.
 ///
 /// Generates a `static` array of [`NativeMethodDesc`] that the compiler reads
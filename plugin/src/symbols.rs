@@ -0,0 +1,245 @@
+//! Runtime symbol table with per-plugin namespacing
+//!
+//! Symbols from every plugin end up in one flat list handed to the JIT, so
+//! two plugins exporting the same name would otherwise shadow each other
+//! depending on load order. [`SymbolTable`] records which plugin owns each
+//! symbol, keeps the first registration, and reports later duplicates as
+//! [`SymbolConflict`]s instead of silently replacing them.
+//!
+//! Plugins other than the runtime itself are expected to prefix their symbols
+//! with their namespace (`<name>_` or `rayzor_<name>_`, see
+//! [`namespace_prefix`]); symbols outside it are accepted but reported.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// Namespace prefix for a plugin name: lowercase, with `-`, `.` and `:`
+/// mapped to `_` (`rayzor-gpu` -> `rayzor_gpu`).
+pub fn namespace_prefix(plugin: &str) -> String {
+    plugin
+        .chars()
+        .map(|c| match c {
+            '-' | '.' | ':' => '_',
+            c => c.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+/// Whether `symbol` lives in `plugin`'s namespace.
+pub fn is_namespaced(plugin: &str, symbol: &str) -> bool {
+    let prefix = namespace_prefix(plugin);
+    let in_ns = |s: &str| {
+        s.strip_prefix(prefix.as_str())
+            .is_some_and(|rest| rest.starts_with('_'))
+    };
+    in_ns(symbol) || symbol.strip_prefix("rayzor_").is_some_and(in_ns)
+}
+
+/// A symbol exported by two different plugins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolConflict {
+    pub symbol: String,
+    /// Plugin that registered the symbol first (and keeps it)
+    pub owner: String,
+    /// Plugin whose export was rejected
+    pub rejected: String,
+}
+
+impl fmt::Display for SymbolConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "symbol '{}' from plugin '{}' conflicts with plugin '{}' (keeping '{}')",
+            self.symbol, self.rejected, self.owner, self.owner
+        )
+    }
+}
+
+/// Outcome of registering one plugin's symbols.
+#[derive(Debug, Default)]
+pub struct RegisterReport {
+    /// Symbols already owned by another plugin; not registered
+    pub conflicts: Vec<SymbolConflict>,
+    /// Registered symbols outside the plugin's namespace
+    pub unnamespaced: Vec<String>,
+}
+
+impl RegisterReport {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty() && self.unnamespaced.is_empty()
+    }
+}
+
+struct PluginSymbols {
+    name: String,
+    symbols: Vec<(String, *const u8)>,
+}
+
+/// Symbols of all loaded plugins, in registration order.
+#[derive(Default)]
+pub struct SymbolTable {
+    plugins: Vec<PluginSymbols>,
+    /// symbol -> index into `plugins`
+    owners: HashMap<String, usize>,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the runtime's own symbols. They define the global namespace,
+    /// so no prefix is checked.
+    pub fn register_global<S: AsRef<str>>(
+        &mut self,
+        plugin: &str,
+        symbols: impl IntoIterator<Item = (S, *const u8)>,
+    ) -> RegisterReport {
+        self.insert(plugin, symbols, false)
+    }
+
+    /// Register a plugin's symbols, checking them against its namespace.
+    pub fn register<S: AsRef<str>>(
+        &mut self,
+        plugin: &str,
+        symbols: impl IntoIterator<Item = (S, *const u8)>,
+    ) -> RegisterReport {
+        self.insert(plugin, symbols, true)
+    }
+
+    fn insert<S: AsRef<str>>(
+        &mut self,
+        plugin: &str,
+        symbols: impl IntoIterator<Item = (S, *const u8)>,
+        check_namespace: bool,
+    ) -> RegisterReport {
+        let index = match self.plugins.iter().position(|p| p.name == plugin) {
+            Some(index) => index,
+            None => {
+                self.plugins.push(PluginSymbols {
+                    name: plugin.to_string(),
+                    symbols: Vec::new(),
+                });
+                self.plugins.len() - 1
+            }
+        };
+
+        let mut report = RegisterReport::default();
+        for (symbol, ptr) in symbols {
+            let symbol = symbol.as_ref();
+            if let Some(&owner) = self.owners.get(symbol) {
+                // Re-exporting the exact same function is harmless
+                let same = self.plugins[owner]
+                    .symbols
+                    .iter()
+                    .any(|(s, p)| s == symbol && *p == ptr);
+                if owner != index && !same {
+                    report.conflicts.push(SymbolConflict {
+                        symbol: symbol.to_string(),
+                        owner: self.plugins[owner].name.clone(),
+                        rejected: plugin.to_string(),
+                    });
+                }
+                continue;
+            }
+            if check_namespace && !is_namespaced(plugin, symbol) {
+                report.unnamespaced.push(symbol.to_string());
+            }
+            self.owners.insert(symbol.to_string(), index);
+            self.plugins[index].symbols.push((symbol.to_string(), ptr));
+        }
+        report
+    }
+
+    /// Plugin that owns `symbol`, if any.
+    pub fn owner(&self, symbol: &str) -> Option<&str> {
+        self.owners
+            .get(symbol)
+            .map(|&i| self.plugins[i].name.as_str())
+    }
+
+    /// Registered plugin names with their symbol names, in registration order.
+    pub fn plugins(&self) -> impl Iterator<Item = (&str, Vec<&str>)> {
+        self.plugins.iter().map(|p| {
+            (
+                p.name.as_str(),
+                p.symbols.iter().map(|(s, _)| s.as_str()).collect(),
+            )
+        })
+    }
+
+    /// Flat symbol list for JIT linking; every name appears once.
+    pub fn flat(&self) -> Vec<(&str, *const u8)> {
+        self.plugins
+            .iter()
+            .flat_map(|p| p.symbols.iter().map(|(s, ptr)| (s.as_str(), *ptr)))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.owners.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.owners.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ptr(n: usize) -> *const u8 {
+        n as *const u8
+    }
+
+    #[test]
+    fn test_namespace_prefix() {
+        assert_eq!(namespace_prefix("rayzor-gpu"), "rayzor_gpu");
+        assert!(is_namespaced("rayzor-gpu", "rayzor_gpu_compute_create"));
+        assert!(is_namespaced("sqlite", "rayzor_sqlite_open"));
+        assert!(is_namespaced("sqlite", "sqlite_open"));
+        assert!(!is_namespaced("sqlite", "sqlite3_open"));
+        assert!(!is_namespaced("sqlite", "open"));
+    }
+
+    #[test]
+    fn test_conflicts_keep_first_registration() {
+        let mut table = SymbolTable::new();
+        assert!(table
+            .register_global("runtime", [("haxe_trace", ptr(1)), ("malloc", ptr(2))])
+            .is_clean());
+
+        let report = table.register(
+            "math",
+            [
+                ("math_sin", ptr(3)),
+                ("haxe_trace", ptr(4)),
+                ("malloc", ptr(2)),
+                ("cosine", ptr(5)),
+            ],
+        );
+        assert_eq!(
+            report.conflicts,
+            vec![SymbolConflict {
+                symbol: "haxe_trace".into(),
+                owner: "runtime".into(),
+                rejected: "math".into(),
+            }]
+        );
+        assert_eq!(report.unnamespaced, vec!["cosine".to_string()]);
+        assert_eq!(
+            report.conflicts[0].to_string(),
+            "symbol 'haxe_trace' from plugin 'math' conflicts with plugin 'runtime' (keeping 'runtime')"
+        );
+
+        // The runtime's pointer wins; names stay unique
+        assert_eq!(table.owner("haxe_trace"), Some("runtime"));
+        let flat = table.flat();
+        assert_eq!(flat.len(), 4);
+        assert!(flat.contains(&("haxe_trace", ptr(1))));
+
+        let listing: Vec<_> = table.plugins().collect();
+        assert_eq!(listing[1], ("math", vec!["math_sin", "cosine"]));
+    }
+}
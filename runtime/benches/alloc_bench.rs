@@ -0,0 +1,92 @@
+//! Microbenchmarks for the size-class pooled allocator
+//!
+//! Compares small-object churn through the pools (`pool_alloc`) against the
+//! global allocator, which is what `rayzor_malloc` uses with
+//! `RAYZOR_ALLOCATOR=system`.
+//!
+//! Run with `cargo bench -p rayzor-runtime --bench alloc_bench`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rayzor_runtime::pool_alloc::{pool_alloc, pool_free, size_class, SIZE_CLASSES};
+use std::alloc::{alloc, dealloc, Layout};
+
+/// Live objects kept around while churning, so frees and allocations interleave
+/// the way they do in an object-heavy program.
+const LIVE_OBJECTS: usize = 1024;
+
+fn system_churn(size: usize, rounds: usize) {
+    let layout = Layout::from_size_align(size, 16).unwrap();
+    let mut live: Vec<*mut u8> = Vec::with_capacity(LIVE_OBJECTS);
+    unsafe {
+        for i in 0..rounds {
+            if live.len() == LIVE_OBJECTS {
+                dealloc(live.swap_remove(i % LIVE_OBJECTS), layout);
+            }
+            live.push(black_box(alloc(layout)));
+        }
+        for ptr in live {
+            dealloc(ptr, layout);
+        }
+    }
+}
+
+fn pooled_churn(size: usize, rounds: usize) {
+    let class = size_class(size).unwrap();
+    let mut live: Vec<*mut u8> = Vec::with_capacity(LIVE_OBJECTS);
+    unsafe {
+        for i in 0..rounds {
+            if live.len() == LIVE_OBJECTS {
+                pool_free(live.swap_remove(i % LIVE_OBJECTS), class);
+            }
+            live.push(black_box(pool_alloc(class)));
+        }
+        for ptr in live {
+            pool_free(ptr, class);
+        }
+    }
+}
+
+fn benchmark_small_object_churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("small_object_churn");
+    let rounds = 100_000;
+
+    for size in SIZE_CLASSES {
+        group.bench_with_input(BenchmarkId::new("system", size), &size, |b, &size| {
+            b.iter(|| system_churn(size, rounds));
+        });
+        group.bench_with_input(BenchmarkId::new("pooled", size), &size, |b, &size| {
+            b.iter(|| pooled_churn(size, rounds));
+        });
+    }
+
+    group.finish();
+}
+
+fn benchmark_alloc_free_pair(c: &mut Criterion) {
+    let mut group = c.benchmark_group("alloc_free_pair");
+    let size = 32;
+    let layout = Layout::from_size_align(size, 16).unwrap();
+    let class = size_class(size).unwrap();
+
+    group.bench_function("system", |b| {
+        b.iter(|| unsafe {
+            let ptr = alloc(layout);
+            dealloc(black_box(ptr), layout);
+        });
+    });
+    group.bench_function("pooled", |b| {
+        b.iter(|| unsafe {
+            let ptr = pool_alloc(class);
+            pool_free(black_box(ptr), class);
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    benchmark_small_object_churn,
+    benchmark_alloc_free_pair
+);
+criterion_main!(benches);
@@ -1,8 +1,67 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
 fn main() {
+    write_symbol_manifest();
+
     #[cfg(feature = "tcc-runtime")]
     build_tcc();
 }
 
+/// Check that every symbol registered for JIT linking in plugin_impl.rs is
+/// also exported under the same name, so the static library AOT binaries
+/// link against provides it, and write the list to
+/// `$OUT_DIR/symbol_manifest.rs` (see `symbol_manifest`).
+fn write_symbol_manifest() {
+    println!("cargo:rerun-if-changed=src");
+
+    let mut exported = HashSet::new();
+    for file in rust_sources(Path::new("src")) {
+        let source = fs::read_to_string(&file)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {}", file.display(), e));
+        exported.extend(no_mangle_functions(&source));
+    }
+
+    let registry = fs::read_to_string("src/plugin_impl.rs").expect("Failed to read plugin_impl.rs");
+    let mut registered = Vec::new();
+    let mut system = Vec::new();
+    let mut missing = Vec::new();
+    for (name, path) in registrations(&registry) {
+        if path.starts_with("libc::") {
+            system.push(name);
+        } else if exported.contains(&name) {
+            registered.push(name);
+        } else {
+            missing.push(format!("  {} (registered as {})", name, path));
+        }
+    }
+    if !missing.is_empty() {
+        panic!(
+            "{} runtime symbols are registered for JIT linking but not exported as \
+             `#[no_mangle] extern \"C\"` functions of the same name, so AOT binaries \
+             can't link them:\n{}",
+            missing.len(),
+            missing.join("\n")
+        );
+    }
+    registered.sort();
+    registered.dedup();
+    system.sort();
+    system.dedup();
+
+    let out = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("symbol_manifest.rs");
+    let manifest = format!(
+        "/// Runtime symbols, exported by the static and dynamic runtime libraries\n\
+         pub const RUNTIME_SYMBOLS: &[&str] = &{:?};\n\
+         /// Registered symbols the system C library provides\n\
+         pub const SYSTEM_SYMBOLS: &[&str] = &{:?};\n",
+        registered, system
+    );
+    fs::write(&out, manifest)
+        .unwrap_or_else(|e| panic!("Failed to write {}: {}", out.display(), e));
+}
+
 #[cfg(feature = "tcc-runtime")]
 fn build_tcc() {
     // TCC source lives in the compiler crate's vendor directory
@@ -47,3 +106,93 @@ fn build_tcc() {
     println!("cargo:rerun-if-changed=../compiler/vendor/tinycc/libtcc.c");
     println!("cargo:rerun-if-changed=../compiler/vendor/tinycc/libtcc.h");
 }
+
+fn rust_sources(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).unwrap_or_else(|e| panic!("{}: {}", dir.display(), e)) {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(rust_sources(&path));
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+    files
+}
+
+/// Names of the `#[no_mangle]` functions in `source`
+fn no_mangle_functions(source: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut lines = source.lines().map(str::trim);
+    while let Some(line) = lines.next() {
+        if line != "#[no_mangle]" {
+            continue;
+        }
+        // Skip further attributes and doc comments up to the signature
+        let Some(signature) = lines
+            .by_ref()
+            .find(|l| !l.starts_with("#[") && !l.starts_with("//"))
+        else {
+            break;
+        };
+        if let Some((_, rest)) = signature.split_once("fn ") {
+            let name: String = rest
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_')
+                .collect();
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// `(name, function path)` of each `register_symbol!` in `source` that is
+/// compiled in with the enabled features
+fn registrations(source: &str) -> Vec<(String, String)> {
+    let mut kept = String::new();
+    let mut skipping = false;
+    let mut disabled = false;
+    for line in source.lines() {
+        let line = line.trim();
+        if line.starts_with("//") {
+            continue;
+        }
+        if let Some(feature) = line
+            .strip_prefix("#[cfg(feature = \"")
+            .and_then(|f| f.strip_suffix("\")]"))
+        {
+            let var = format!("CARGO_FEATURE_{}", feature.replace('-', "_").to_uppercase());
+            disabled = std::env::var_os(var).is_none();
+            continue;
+        }
+        if disabled && line.starts_with("register_symbol!(") {
+            skipping = true;
+        }
+        disabled = false;
+        if !skipping {
+            kept.push_str(line);
+            kept.push('\n');
+        }
+        if skipping && line.ends_with(");") {
+            skipping = false;
+        }
+    }
+
+    let mut found = Vec::new();
+    for (i, _) in kept.match_indices("register_symbol!(") {
+        let args = &kept[i + "register_symbol!(".len()..];
+        let Some(end) = args.find(')') else { continue };
+        let Some((name, path)) = args[..end].split_once(',') else {
+            continue;
+        };
+        let name = name.trim();
+        // Skips the macro definition, whose arguments are `$name:expr`
+        if let Some(name) = name.strip_prefix('"').and_then(|n| n.strip_suffix('"')) {
+            found.push((
+                name.to_string(),
+                path.trim().trim_end_matches(',').to_string(),
+            ));
+        }
+    }
+    found
+}
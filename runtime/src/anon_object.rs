@@ -195,11 +195,14 @@ unsafe fn borrow_arc_mut(ptr: *mut u8) -> &'static mut Arc<AnonObject> {
 /// Create a new anonymous object with the given shape
 #[no_mangle]
 pub extern "C" fn rayzor_anon_new(shape_id: u32, field_count: u32) -> *mut u8 {
-    let data = if shape_id == DYNAMIC_SHAPE {
-        AnonData::Map(HashMap::new())
-    } else {
-        AnonData::Inline(vec![0u64; field_count as usize])
-    };
+    // Fields may be GC pointers, so their storage is scanned under `--gc`
+    let data = crate::gc::tracked(|| {
+        if shape_id == DYNAMIC_SHAPE {
+            AnonData::Map(HashMap::new())
+        } else {
+            AnonData::Inline(vec![0u64; field_count as usize])
+        }
+    });
 
     let obj = AnonObject { shape_id, data };
     let arc = Arc::new(obj);
@@ -227,6 +230,7 @@ pub extern "C" fn rayzor_anon_drop(ptr: *mut u8) {
     if ptr.is_null() {
         return;
     }
+    crate::object_id::release(ptr as usize);
     unsafe {
         let _boxed: Box<Arc<AnonObject>> = Box::from_raw(ptr as *mut Arc<AnonObject>);
         // Box dropped here → Arc dropped → refcount decremented → object freed if zero
@@ -254,7 +258,7 @@ pub extern "C" fn rayzor_anon_set_field_by_index(ptr: *mut u8, index: u32, value
     if ptr.is_null() {
         return;
     }
-    unsafe {
+    crate::gc::tracked(|| unsafe {
         let arc = borrow_arc_mut(ptr);
         let obj = Arc::make_mut(arc);
         if let AnonData::Inline(fields) = &mut obj.data {
@@ -262,7 +266,7 @@ pub extern "C" fn rayzor_anon_set_field_by_index(ptr: *mut u8, index: u32, value
                 fields[index as usize] = value;
             }
         }
-    }
+    })
 }
 
 /// Check if field exists by name
@@ -360,7 +364,7 @@ pub extern "C" fn rayzor_anon_set_field(
         }
     };
 
-    unsafe {
+    crate::gc::tracked(|| unsafe {
         let arc = borrow_arc_mut(ptr);
         let obj = Arc::make_mut(arc);
 
@@ -388,7 +392,7 @@ pub extern "C" fn rayzor_anon_set_field(
                 map.insert(name, (type_id, raw_value));
             }
         }
-    }
+    })
 }
 
 /// Delete field by name with COW (returns true if field existed)
@@ -405,7 +409,7 @@ pub extern "C" fn rayzor_anon_delete_field(
         String::from_utf8_lossy(std::slice::from_raw_parts(name_ptr, name_len as usize)).to_string()
     };
 
-    unsafe {
+    crate::gc::tracked(|| unsafe {
         let arc = borrow_arc_mut(ptr);
         let obj = Arc::make_mut(arc);
 
@@ -431,7 +435,7 @@ pub extern "C" fn rayzor_anon_delete_field(
             }
             AnonData::Map(map) => map.remove(&name).is_some(),
         }
-    }
+    })
 }
 
 /// Get all field names as a HaxeArray of HaxeString pointers
@@ -496,7 +500,7 @@ pub extern "C" fn rayzor_anon_copy(ptr: *mut u8) -> *mut u8 {
     }
     unsafe {
         let arc_ref = borrow_arc(ptr);
-        let cloned_obj = (**arc_ref).clone();
+        let cloned_obj = crate::gc::tracked(|| (**arc_ref).clone());
         let arc = Arc::new(cloned_obj);
         let boxed = Box::new(arc);
         Box::into_raw(boxed) as *mut u8
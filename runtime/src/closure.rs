@@ -0,0 +1,145 @@
+//! Function values: bound-method caching and identity
+//!
+//! A function value is a pointer to a 16-byte closure object `{fn_ptr, env}`.
+//! Equality on function values (`==`, `!=`, `Reflect.compareMethods`) is
+//! identity of that pointer, which gives these rules:
+//!
+//! - a static function referenced twice is the same function value, so
+//!   `Foo.bar == Foo.bar`
+//! - a lambda is a fresh closure every time its expression is evaluated; the
+//!   same closure stored in two variables compares equal, two evaluations of
+//!   the same `function() {}` expression do not
+//! - a bound method `o.method` is cached per (receiver, method) by
+//!   `rayzor_bind_method`, so taking `o.method` twice yields the same closure
+//!   and compares equal, while `a.method` and `b.method` on different
+//!   receivers do not
+//!
+//! Bound-method entries are keyed by receiver address and guarded by the
+//! receiver's identity id (`object_id`), so a new object allocated at a freed
+//! receiver's address never picks up the old closure.
+
+use crate::object_id;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+/// A cached bound-method closure and the identity id of its receiver.
+struct BoundMethod {
+    receiver_id: u64,
+    closure: usize,
+}
+
+/// Bound-method closures by (receiver address, trampoline code pointer).
+static BOUND_METHODS: Mutex<Option<HashMap<(usize, usize), BoundMethod>>> = Mutex::new(None);
+
+/// Canonicalize a freshly built bound-method closure.
+///
+/// `closure` is a `{trampoline, env}` closure whose env holds the receiver in
+/// its first slot, as built by the compiler for `o.method`. If a live closure
+/// for the same receiver and method already exists, the new closure and its
+/// env (both `malloc`ed by the closure lowering) are freed and the cached one
+/// is returned; otherwise `closure` becomes the cached closure.
+///
+/// # Safety
+/// `closure` must be null or a closure built by `MakeClosure` with at least
+/// one captured value.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_bind_method(closure: *mut u8) -> *mut u8 {
+    if closure.is_null() {
+        return closure;
+    }
+    let fn_ptr = *(closure as *const usize);
+    let env = *(closure.add(8) as *const *const usize);
+    if env.is_null() || *env == 0 {
+        return closure;
+    }
+    let receiver = *env;
+
+    let mut cache = BOUND_METHODS.lock();
+    let cache = cache.get_or_insert_with(HashMap::new);
+    if let Some(bound) = cache.get(&(receiver, fn_ptr)) {
+        if object_id::is_live(receiver, bound.receiver_id) {
+            crate::debug_alloc::free(env as *mut u8);
+            crate::debug_alloc::free(closure);
+            return bound.closure as *mut u8;
+        }
+    }
+    cache.insert(
+        (receiver, fn_ptr),
+        BoundMethod {
+            receiver_id: object_id::id_of(receiver),
+            closure: closure as usize,
+        },
+    );
+    closure
+}
+
+/// `Reflect.compareMethods(f1, f2)`: whether two function values are the same
+/// function, by the identity rules above.
+#[no_mangle]
+pub extern "C" fn haxe_reflect_compare_methods(a: *mut u8, b: *mut u8) -> bool {
+    a == b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a closure the way `MakeClosure` does: malloc'd env and object.
+    unsafe fn make_closure(fn_ptr: usize, receiver: usize) -> *mut u8 {
+        let env = libc::malloc(8) as *mut usize;
+        *env = receiver;
+        let closure = libc::malloc(16) as *mut usize;
+        *closure = fn_ptr;
+        *closure.add(1) = env as usize;
+        closure as *mut u8
+    }
+
+    #[test]
+    fn test_bind_method_is_cached_per_receiver_and_method() {
+        unsafe {
+            let a = Box::into_raw(Box::new(0u64)) as usize;
+            let b = Box::into_raw(Box::new(0u64)) as usize;
+
+            let first = rayzor_bind_method(make_closure(0x1000, a));
+            let again = rayzor_bind_method(make_closure(0x1000, a));
+            assert_eq!(first, again);
+            assert!(haxe_reflect_compare_methods(first, again));
+
+            let other_receiver = rayzor_bind_method(make_closure(0x1000, b));
+            assert_ne!(first, other_receiver);
+            let other_method = rayzor_bind_method(make_closure(0x2000, a));
+            assert_ne!(first, other_method);
+            assert!(!haxe_reflect_compare_methods(first, other_method));
+
+            drop(Box::from_raw(a as *mut u64));
+            drop(Box::from_raw(b as *mut u64));
+        }
+    }
+
+    #[test]
+    fn test_bind_method_after_receiver_released() {
+        unsafe {
+            let a = Box::into_raw(Box::new(0u64)) as usize;
+            let first = rayzor_bind_method(make_closure(0x3000, a));
+
+            // A new object at the same address must not reuse the closure
+            object_id::release(a);
+            let fresh = make_closure(0x3000, a);
+            assert_eq!(rayzor_bind_method(fresh), fresh);
+            assert_ne!(first, fresh);
+
+            drop(Box::from_raw(a as *mut u64));
+        }
+    }
+
+    #[test]
+    fn test_bind_method_null() {
+        unsafe {
+            assert!(rayzor_bind_method(std::ptr::null_mut()).is_null());
+        }
+        assert!(haxe_reflect_compare_methods(
+            std::ptr::null_mut(),
+            std::ptr::null_mut()
+        ));
+    }
+}
@@ -0,0 +1,243 @@
+//! Locale-aware string collation (`rayzor.text.Collator`)
+//!
+//! `String` comparison operators compare code units, which sorts `"Zebra"`
+//! before `"apple"` and `"école"` after `"zoo"`. The collator instead orders
+//! strings the way the root (language-neutral) locale does, comparing level
+//! by level and only falling through to the next level on a tie:
+//!
+//! 1. primary: base characters, with case and accents removed; spaces and
+//!    punctuation sort before digits, digits before letters
+//! 2. secondary: accents (`e` < `é` < `è`)
+//! 3. tertiary: case, lowercase first (`a` < `A`)
+//!
+//! Remaining ties are broken by code point so the order is total.
+//!
+//! Accents are decomposed for the Latin-1 Supplement and Latin Extended-A
+//! blocks, which cover Western and Central European languages; other
+//! characters compare by their case-folded code point at the primary level.
+//! Ligatures `ß`, `æ` and `œ` expand to `ss`, `ae` and `oe`.
+
+use crate::haxe_string::HaxeString;
+use std::cmp::Ordering;
+
+/// Accented Latin letters by base letter; the position in the string is the
+/// accent's secondary weight.
+const LATIN_ACCENTS: &[(char, &str)] = &[
+    ('a', "àáâãäåāăą"),
+    ('c', "çćĉċč"),
+    ('d', "ďđ"),
+    ('e', "èéêëēĕėęě"),
+    ('g', "ĝğġģ"),
+    ('h', "ĥħ"),
+    ('i', "ìíîïĩīĭįı"),
+    ('j', "ĵ"),
+    ('k', "ķ"),
+    ('l', "ĺļľŀł"),
+    ('n', "ñńņňŉ"),
+    ('o', "òóôõöōŏőø"),
+    ('r', "ŕŗř"),
+    ('s', "śŝşš"),
+    ('t', "ţťŧ"),
+    ('u', "ùúûüũūŭůűų"),
+    ('w', "ŵ"),
+    ('y', "ýÿŷ"),
+    ('z', "źżž"),
+];
+
+/// Primary character classes, in sort order.
+const CLASS_SEPARATOR: u8 = 0;
+const CLASS_DIGIT: u8 = 1;
+const CLASS_LETTER: u8 = 2;
+const CLASS_OTHER: u8 = 3;
+
+/// Collation weights of one character (ligatures produce several).
+#[derive(Clone, Copy)]
+struct Element {
+    primary: (u8, char),
+    secondary: u8,
+    tertiary: u8,
+}
+
+/// Base letter and accent weight of a lowercase character.
+fn decompose(c: char) -> (char, u8) {
+    if c.is_ascii() {
+        return (c, 0);
+    }
+    for &(base, variants) in LATIN_ACCENTS {
+        if let Some(pos) = variants.chars().position(|v| v == c) {
+            return (base, pos as u8 + 1);
+        }
+    }
+    (c, 0)
+}
+
+fn primary_class(c: char) -> u8 {
+    if c.is_whitespace() || c.is_ascii_punctuation() {
+        CLASS_SEPARATOR
+    } else if c.is_numeric() {
+        CLASS_DIGIT
+    } else if c.is_alphabetic() {
+        CLASS_LETTER
+    } else {
+        CLASS_OTHER
+    }
+}
+
+/// Collation elements of a string.
+fn elements(s: &str) -> Vec<Element> {
+    let mut out = Vec::with_capacity(s.len());
+    for c in s.chars() {
+        let tertiary = c.is_uppercase() as u8;
+        for lower in c.to_lowercase() {
+            let expansion: &[char] = match lower {
+                'ß' => &['s', 's'],
+                'æ' => &['a', 'e'],
+                'œ' => &['o', 'e'],
+                _ => &[],
+            };
+            if !expansion.is_empty() {
+                for &base in expansion {
+                    out.push(Element {
+                        primary: (CLASS_LETTER, base),
+                        secondary: 0,
+                        tertiary,
+                    });
+                }
+                continue;
+            }
+            let (base, secondary) = decompose(lower);
+            out.push(Element {
+                primary: (primary_class(base), base),
+                secondary,
+                tertiary,
+            });
+        }
+    }
+    out
+}
+
+/// Compare two strings with the collation levels above. With `ignore_case`
+/// the tertiary level is skipped, so strings differing only in case compare
+/// equal.
+pub fn collate(a: &str, b: &str, ignore_case: bool) -> Ordering {
+    let ea = elements(a);
+    let eb = elements(b);
+    let level = |key: fn(&Element) -> u32| ea.iter().map(key).cmp(eb.iter().map(key));
+    let primary = ea
+        .iter()
+        .map(|e| e.primary)
+        .cmp(eb.iter().map(|e| e.primary));
+    let ordering = primary
+        .then_with(|| level(|e| e.secondary as u32))
+        .then_with(|| {
+            if ignore_case {
+                Ordering::Equal
+            } else {
+                level(|e| e.tertiary as u32)
+            }
+        });
+    if ordering != Ordering::Equal || ignore_case {
+        return ordering;
+    }
+    a.cmp(b)
+}
+
+/// Locale-independent case folding: maps case variants to one form so that
+/// folded strings compare equal regardless of case (`"Straße"` and
+/// `"STRASSE"` both fold to `"strasse"`).
+pub fn fold_case(s: &str) -> String {
+    s.to_uppercase().to_lowercase()
+}
+
+/// View a HaxeString as `&str` (empty for null).
+unsafe fn as_str<'a>(s: *const HaxeString) -> &'a str {
+    if s.is_null() || (*s).ptr.is_null() || (*s).len == 0 {
+        return "";
+    }
+    std::str::from_utf8_unchecked(std::slice::from_raw_parts((*s).ptr, (*s).len))
+}
+
+fn ordering_to_int(ordering: Ordering) -> i32 {
+    match ordering {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
+}
+
+/// Collator.compare(a, b): -1, 0 or 1 by locale-aware collation
+#[no_mangle]
+pub extern "C" fn haxe_collator_compare(a: *const HaxeString, b: *const HaxeString) -> i32 {
+    unsafe { ordering_to_int(collate(as_str(a), as_str(b), false)) }
+}
+
+/// Collator.compareIgnoreCase(a, b): like `compare`, but case differences
+/// compare equal
+#[no_mangle]
+pub extern "C" fn haxe_collator_compare_ignore_case(
+    a: *const HaxeString,
+    b: *const HaxeString,
+) -> i32 {
+    unsafe { ordering_to_int(collate(as_str(a), as_str(b), true)) }
+}
+
+/// Collator.foldCase(s): locale-independent case-folded copy of `s`
+#[no_mangle]
+pub extern "C" fn haxe_collator_fold_case(s: *const HaxeString) -> *mut HaxeString {
+    let folded = fold_case(unsafe { as_str(s) });
+    let out = Box::into_raw(Box::new(HaxeString {
+        ptr: std::ptr::null_mut(),
+        len: 0,
+        cap: 0,
+    }));
+    crate::haxe_string::haxe_string_from_bytes(out, folded.as_ptr(), folded.len());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(words: &[&str]) -> Vec<String> {
+        let mut words: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+        words.sort_by(|a, b| collate(a, b, false));
+        words
+    }
+
+    #[test]
+    fn test_case_and_accents_are_secondary() {
+        assert_eq!(
+            sorted(&["zoo", "École", "apple", "Zebra", "ecole", "école"]),
+            vec!["apple", "ecole", "école", "École", "Zebra", "zoo"]
+        );
+        assert_eq!(sorted(&["B", "a", "b", "A"]), vec!["a", "A", "b", "B"]);
+    }
+
+    #[test]
+    fn test_separators_and_digits_sort_first() {
+        assert_eq!(
+            sorted(&["b", "2", "a b", "ab", "1"]),
+            vec!["1", "2", "a b", "ab", "b"]
+        );
+    }
+
+    #[test]
+    fn test_ligatures_expand() {
+        assert_eq!(collate("Straße", "strasse", true), Ordering::Equal);
+        assert_eq!(collate("Æther", "aether", true), Ordering::Equal);
+        assert_ne!(collate("Straße", "strasse", false), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_ignore_case() {
+        assert_eq!(collate("HELLO", "hello", true), Ordering::Equal);
+        assert_eq!(collate("hello", "héllo", true), Ordering::Less);
+        assert_eq!(collate("a", "B", true), Ordering::Less);
+    }
+
+    #[test]
+    fn test_fold_case() {
+        assert_eq!(fold_case("Straße"), "strasse");
+        assert_eq!(fold_case("ΣΊΣΥΦΟΣ"), fold_case("σίσυφος"));
+    }
+}
@@ -229,6 +229,19 @@ fn arm64_jit_barrier() {
 pub unsafe extern "C" fn rayzor_thread_spawn(
     closure: *const u8,
     closure_env: *const u8,
+) -> *mut u8 {
+    spawn_thread(closure, closure_env, None)
+}
+
+/// Spawn a thread running `closure(closure_env)`, with a `stack_size`-byte
+/// stack if given. Returns null if the thread couldn't be created.
+///
+/// # Safety
+/// Same as [`rayzor_thread_spawn`]
+pub(crate) unsafe fn spawn_thread(
+    closure: *const u8,
+    closure_env: *const u8,
+    stack_size: Option<usize>,
 ) -> *mut u8 {
     // Simple null check
     if closure.is_null() {
@@ -245,19 +258,34 @@ pub unsafe extern "C" fn rayzor_thread_spawn(
     // Execute barrier on main thread before spawning to ensure JIT code is visible
     arm64_jit_barrier();
 
+    let mut builder = thread::Builder::new();
+    if let Some(size) = stack_size {
+        builder = builder.stack_size(size);
+    }
+
     // Spawn thread
-    let handle = thread::spawn(move || {
+    let spawned = builder.spawn(move || {
         // Execute barrier before calling JIT code
         arm64_jit_barrier();
 
+        let _tracked = crate::deadlock::ThreadGuard::enter();
+        let _stack = crate::stack_guard::ThreadStack::enter();
+
         type ClosureFn = extern "C" fn(*const u8) -> i32;
         let env_ptr = env_addr as *const u8;
         let func: ClosureFn = unsafe { std::mem::transmute(func_addr) };
         func(env_ptr)
     });
 
-    // Return simple handle
-    Box::into_raw(Box::new(handle)) as *mut u8
+    match spawned {
+        // Return simple handle
+        Ok(handle) => Box::into_raw(Box::new(handle)) as *mut u8,
+        Err(e) => {
+            ACTIVE_THREAD_COUNT.fetch_sub(1, Ordering::SeqCst);
+            eprintln!("Thread.spawn: failed to create thread: {}", e);
+            ptr::null_mut()
+        }
+    }
 }
 
 /// Join a thread and wait for it to complete
@@ -274,6 +302,14 @@ pub unsafe extern "C" fn rayzor_thread_join(handle: *mut u8) -> *mut u8 {
 
     // Simple implementation using std::thread
     let boxed_handle: Box<JoinHandle<i32>> = Box::from_raw(handle as *mut JoinHandle<i32>);
+    if crate::deadlock::is_enabled() && !boxed_handle.is_finished() {
+        crate::deadlock::block(crate::deadlock::Wait::Join(boxed_handle.thread().id()));
+        while !boxed_handle.is_finished() {
+            thread::sleep(crate::deadlock::POLL_INTERVAL);
+            crate::deadlock::check_stalled();
+        }
+        crate::deadlock::unblock();
+    }
     let result = boxed_handle.join().unwrap_or(-1);
     ACTIVE_THREAD_COUNT.fetch_sub(1, Ordering::SeqCst);
     result as usize as *mut u8
@@ -465,6 +501,48 @@ struct MutexHandle {
     value: *mut u8,
 }
 
+impl MutexHandle {
+    /// Lock (blocking), recorded for deadlock detection when it is enabled
+    fn lock(&self) {
+        if !crate::deadlock::is_enabled() {
+            self.raw_mutex.lock();
+            crate::sanitizer::acquire(self.addr());
+            return;
+        }
+        if !self.raw_mutex.try_lock() {
+            crate::deadlock::block(crate::deadlock::Wait::Mutex(self.addr()));
+            self.raw_mutex.lock();
+        }
+        crate::deadlock::acquired(self.addr());
+        crate::sanitizer::acquire(self.addr());
+    }
+
+    fn try_lock(&self) -> bool {
+        let locked = self.raw_mutex.try_lock();
+        if locked {
+            if crate::deadlock::is_enabled() {
+                crate::deadlock::acquired(self.addr());
+            }
+            crate::sanitizer::acquire(self.addr());
+        }
+        locked
+    }
+
+    /// # Safety
+    /// The mutex must be locked
+    unsafe fn unlock(&self) {
+        if crate::deadlock::is_enabled() {
+            crate::deadlock::released(self.addr());
+        }
+        crate::sanitizer::release(self.addr());
+        self.raw_mutex.unlock();
+    }
+
+    fn addr(&self) -> usize {
+        self as *const Self as usize
+    }
+}
+
 /// Mutex guard handle - stores reference back to mutex for unlocking
 struct MutexGuardHandle {
     /// Pointer to the mutex handle for unlocking
@@ -496,7 +574,7 @@ pub unsafe extern "C" fn rayzor_mutex_lock(mutex: *mut u8) -> *mut u8 {
     let mutex_handle = &*(mutex as *const MutexHandle);
 
     // Lock the raw mutex (blocks until acquired)
-    mutex_handle.raw_mutex.lock();
+    mutex_handle.lock();
 
     let guard_handle = Box::new(MutexGuardHandle {
         mutex: mutex_handle as *const MutexHandle,
@@ -514,7 +592,7 @@ pub unsafe extern "C" fn rayzor_mutex_try_lock(mutex: *mut u8) -> *mut u8 {
 
     let mutex_handle = &*(mutex as *const MutexHandle);
 
-    if mutex_handle.raw_mutex.try_lock() {
+    if mutex_handle.try_lock() {
         let guard_handle = Box::new(MutexGuardHandle {
             mutex: mutex_handle as *const MutexHandle,
         });
@@ -553,7 +631,7 @@ pub unsafe extern "C" fn rayzor_mutex_unlock(guard: *mut u8) {
         // Reconstruct Box and get the mutex reference
         let guard_handle = Box::from_raw(guard as *mut MutexGuardHandle);
         // Unlock the raw mutex
-        (*guard_handle.mutex).raw_mutex.unlock();
+        (*guard_handle.mutex).unlock();
         // Box will be dropped here
     }
 }
@@ -616,7 +694,20 @@ pub unsafe extern "C" fn rayzor_channel_send(channel: *mut u8, value: *mut u8) {
     debug!("[rayzor_channel_send] Lock acquired");
 
     // For bounded channels, wait while full
-    while state.capacity > 0 && state.buffer.len() >= state.capacity && !state.closed {
+    let is_full = |s: &ChannelState| s.capacity > 0 && s.buffer.len() >= s.capacity && !s.closed;
+    if crate::deadlock::is_enabled() && is_full(&state) {
+        crate::deadlock::block(crate::deadlock::Wait::ChannelSend(channel as usize));
+        while is_full(&state) {
+            state = channel_handle
+                .not_full
+                .wait_timeout(state, crate::deadlock::POLL_INTERVAL)
+                .unwrap()
+                .0;
+            crate::deadlock::check_stalled();
+        }
+        crate::deadlock::unblock();
+    }
+    while is_full(&state) {
         state = channel_handle.not_full.wait(state).unwrap();
     }
 
@@ -624,6 +715,7 @@ pub unsafe extern "C" fn rayzor_channel_send(channel: *mut u8, value: *mut u8) {
         return;
     }
 
+    crate::sanitizer::release(channel as usize);
     state.buffer.push_back(value);
     drop(state);
 
@@ -650,6 +742,7 @@ pub unsafe extern "C" fn rayzor_channel_try_send(channel: *mut u8, value: *mut u
         return false;
     }
 
+    crate::sanitizer::release(channel as usize);
     state.buffer.push_back(value);
     drop(state);
     channel_handle.not_empty.notify_one();
@@ -667,12 +760,26 @@ pub unsafe extern "C" fn rayzor_channel_receive(channel: *mut u8) -> *mut u8 {
     let mut state = channel_handle.state.lock().unwrap();
 
     // Wait while buffer is empty and channel is not closed
-    while state.buffer.is_empty() && !state.closed {
+    let is_empty = |s: &ChannelState| s.buffer.is_empty() && !s.closed;
+    if crate::deadlock::is_enabled() && is_empty(&state) {
+        crate::deadlock::block(crate::deadlock::Wait::ChannelReceive(channel as usize));
+        while is_empty(&state) {
+            state = channel_handle
+                .not_empty
+                .wait_timeout(state, crate::deadlock::POLL_INTERVAL)
+                .unwrap()
+                .0;
+            crate::deadlock::check_stalled();
+        }
+        crate::deadlock::unblock();
+    }
+    while is_empty(&state) {
         state = channel_handle.not_empty.wait(state).unwrap();
     }
 
     if let Some(value) = state.buffer.pop_front() {
         drop(state);
+        crate::sanitizer::acquire(channel as usize);
         channel_handle.not_full.notify_one();
         value
     } else {
@@ -692,6 +799,7 @@ pub unsafe extern "C" fn rayzor_channel_try_receive(channel: *mut u8) -> *mut u8
 
     if let Some(value) = state.buffer.pop_front() {
         drop(state);
+        crate::sanitizer::acquire(channel as usize);
         channel_handle.not_full.notify_one();
         value
     } else {
@@ -994,7 +1102,7 @@ pub unsafe extern "C" fn sys_mutex_acquire(mutex: *mut u8) {
         // Cast directly to MutexHandle (same struct as used by rayzor_mutex_*)
         let mutex_handle = &*(mutex as *const MutexHandle);
         // Lock the raw mutex (blocking)
-        mutex_handle.raw_mutex.lock();
+        mutex_handle.lock();
     }
 }
 
@@ -1007,7 +1115,7 @@ pub unsafe extern "C" fn sys_mutex_try_acquire(mutex: *mut u8) -> *mut u8 {
     }
 
     let mutex_handle = &*(mutex as *const MutexHandle);
-    let result = mutex_handle.raw_mutex.try_lock();
+    let result = mutex_handle.try_lock();
 
     crate::type_system::haxe_box_bool_ptr(result)
 }
@@ -1020,7 +1128,7 @@ pub unsafe extern "C" fn sys_mutex_release(mutex: *mut u8) {
         let mutex_handle = &*(mutex as *const MutexHandle);
         // Unlock the raw mutex
         // SAFETY: Caller is responsible for only calling unlock when they hold the lock
-        mutex_handle.raw_mutex.unlock();
+        mutex_handle.unlock();
     }
 }
 
@@ -1229,6 +1337,56 @@ pub unsafe extern "C" fn sys_condition_broadcast(condition: *mut u8) {
     handle.condvar.notify_all();
 }
 
+// ============================================================================
+// sys.thread.Tls - Thread-Local Storage
+// ============================================================================
+
+/// Next TLS key; keys are never reused, so a stale slot can't alias a new one
+static NEXT_TLS_KEY: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// This thread's TLS values, indexed by key (0 = unset/null)
+    static TLS_SLOTS: std::cell::RefCell<Vec<u64>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// Handle for sys.thread.Tls<T>
+struct TlsHandle {
+    key: usize,
+}
+
+/// Allocate a TLS key; every thread starts with a null value for it
+#[no_mangle]
+pub extern "C" fn rayzor_tls_alloc() -> *mut u8 {
+    let key = NEXT_TLS_KEY.fetch_add(1, Ordering::Relaxed) as usize;
+    Box::into_raw(Box::new(TlsHandle { key })) as *mut u8
+}
+
+/// Read the calling thread's value (raw 8-byte slot)
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_tls_get(tls: *mut u8) -> u64 {
+    if tls.is_null() {
+        return 0;
+    }
+    let key = (*(tls as *const TlsHandle)).key;
+    TLS_SLOTS.with(|slots| slots.borrow().get(key).copied().unwrap_or(0))
+}
+
+/// Set the calling thread's value (raw 8-byte slot)
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_tls_set(tls: *mut u8, value: u64) {
+    if tls.is_null() {
+        return;
+    }
+    let key = (*(tls as *const TlsHandle)).key;
+    TLS_SLOTS.with(|slots| {
+        let mut slots = slots.borrow_mut();
+        if slots.len() <= key {
+            slots.resize(key + 1, 0);
+        }
+        slots[key] = value;
+    });
+}
+
 // ============================================================================
 // JIT Lifecycle Management
 // ============================================================================
@@ -1285,6 +1443,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_tls_is_per_thread() {
+        unsafe {
+            let tls = rayzor_tls_alloc();
+            let other = rayzor_tls_alloc();
+            assert_eq!(rayzor_tls_get(tls), 0);
+            rayzor_tls_set(tls, 7);
+            rayzor_tls_set(other, 9);
+
+            let addr = tls as usize;
+            let seen = thread::spawn(move || {
+                let tls = addr as *mut u8;
+                let before = rayzor_tls_get(tls);
+                rayzor_tls_set(tls, 42);
+                (before, rayzor_tls_get(tls))
+            })
+            .join()
+            .unwrap();
+
+            assert_eq!(seen, (0, 42));
+            assert_eq!(rayzor_tls_get(tls), 7);
+            assert_eq!(rayzor_tls_get(other), 9);
+        }
+    }
+
     #[test]
     fn test_channel_send_receive() {
         unsafe {
@@ -0,0 +1,94 @@
+//! Contract violation reports
+//!
+//! Debug and `--safety checked` builds check `@:requires` / `@:ensures`
+//! contracts at method entry and exit, and call `rayzor_contract_failed`
+//! when one does not hold:
+//!
+//! ```text
+//! Main.hx:3:5: runtime error: precondition of Main.divide failed: b != 0
+//! note: callers of Main.divide must make sure `b != 0` holds
+//! ```
+
+use crate::haxe_string::HaxeString;
+
+/// Report the failed contract at `file:line:column` and abort.
+///
+/// `kind` is 0 for a precondition and 1 for a postcondition; `function` is
+/// the qualified name of the method and `condition` the contract's source.
+#[no_mangle]
+pub extern "C" fn rayzor_contract_failed(
+    kind: i32,
+    function: *const HaxeString,
+    condition: *const HaxeString,
+    file: *const HaxeString,
+    line: i32,
+    column: i32,
+) {
+    let (error, note) = violation_message(
+        kind,
+        &string_or(function, "<unknown>"),
+        &string_or(condition, "<condition>"),
+    );
+    eprintln!(
+        "{}:{}:{}: runtime error: {}",
+        string_or(file, "<unknown>"),
+        line,
+        column,
+        error
+    );
+    eprintln!("note: {}", note);
+    std::process::abort();
+}
+
+/// The error and note lines for a failed contract
+fn violation_message(kind: i32, function: &str, condition: &str) -> (String, String) {
+    if kind == 0 {
+        (
+            format!("precondition of {} failed: {}", function, condition),
+            format!(
+                "callers of {} must make sure `{}` holds",
+                function, condition
+            ),
+        )
+    } else {
+        (
+            format!("postcondition of {} failed: {}", function, condition),
+            format!("{} returned without establishing `{}`", function, condition),
+        )
+    }
+}
+
+fn string_or(s: *const HaxeString, fallback: &str) -> String {
+    if s.is_null() {
+        return fallback.to_string();
+    }
+    unsafe {
+        let s = &*s;
+        if s.ptr.is_null() || s.len == 0 {
+            fallback.to_string()
+        } else {
+            String::from_utf8_lossy(std::slice::from_raw_parts(s.ptr, s.len)).into_owned()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_violation_message() {
+        let (error, note) = violation_message(0, "Main.divide", "b != 0");
+        assert_eq!(error, "precondition of Main.divide failed: b != 0");
+        assert_eq!(note, "callers of Main.divide must make sure `b != 0` holds");
+
+        let (error, note) = violation_message(1, "Main.abs", "result >= 0");
+        assert_eq!(error, "postcondition of Main.abs failed: result >= 0");
+        assert_eq!(note, "Main.abs returned without establishing `result >= 0`");
+    }
+
+    #[test]
+    fn test_string_or_null() {
+        assert_eq!(string_or(std::ptr::null(), "<unknown>"), "<unknown>");
+    }
+}
@@ -0,0 +1,602 @@
+//! Stackful coroutines (sys.thread.Coroutine)
+//!
+//! A coroutine runs a `Void->Void` closure on its own stack. `resume` runs
+//! it until it calls `Coroutine.yield(value)` or returns, and gives back the
+//! yielded value (null once the body returned). Coroutines are far cheaper
+//! than threads: creating one maps a stack, and switching is a handful of
+//! instructions that save the callee-saved registers of one stack and load
+//! those of the other.
+//!
+//! ```text
+//! status: Suspended --resume--> Running --yield--> Suspended
+//!                                       --return-> Dead
+//! ```
+//!
+//! A dead coroutine has already released its stack, but the coroutine
+//! itself stays allocated until `rayzor_coroutine_destroy`, which also
+//! frees a suspended coroutine's stack without finishing its body.
+//!
+//! Stacks are mmap'd with a guard page below them, so an overflow faults
+//! instead of corrupting memory, and is reported by [`crate::stack_guard`]
+//! like a thread stack overflow. A coroutine can be resumed from any thread,
+//! but only by one at a time; `yield` always suspends the innermost coroutine
+//! running on the calling thread.
+//!
+//! Stack switching is implemented for x86_64 (System V) and aarch64 on Unix.
+//! Elsewhere, creating a coroutine returns null.
+//!
+//! Exceptions must not escape a coroutine body: the handler they would land
+//! in lives on the stack of whoever resumed it.
+
+use parking_lot::Mutex;
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::ptr;
+use std::sync::atomic::{AtomicI32, Ordering};
+
+/// Stack size of a coroutine created without an explicit size
+pub const DEFAULT_STACK_SIZE: usize = 256 * 1024;
+
+/// Not started yet, or suspended in `yield`
+pub const STATUS_SUSPENDED: i32 = 0;
+/// Between `resume` and the next `yield`
+pub const STATUS_RUNNING: i32 = 1;
+/// The body returned
+pub const STATUS_DEAD: i32 = 2;
+
+type ClosureFn = extern "C" fn(*const u8) -> i32;
+
+pub struct Coroutine {
+    status: AtomicI32,
+    body: ClosureFn,
+    env: *const u8,
+    /// Freed once the body returned
+    stack: Option<Stack>,
+    /// Saved stack pointer of the coroutine while suspended
+    sp: usize,
+    /// Saved stack pointer of the resumer while running
+    caller_sp: usize,
+    /// High end of the resumer's stack while running, 0 if not recorded
+    caller_high: usize,
+    /// Value passed from `yield` to `resume`
+    transfer: *mut u8,
+    /// Coroutine that was running on this thread when this one was resumed
+    parent: *mut Coroutine,
+}
+
+thread_local! {
+    /// Innermost coroutine running on this thread
+    static CURRENT: Cell<*mut Coroutine> = const { Cell::new(ptr::null_mut()) };
+}
+
+/// Coroutines that still have a stack, for the collector to scan
+static LIVE: Mutex<BTreeSet<usize>> = Mutex::new(BTreeSet::new());
+
+/// An mmap'd stack with a guard page at its low end
+struct Stack {
+    base: *mut u8,
+    len: usize,
+    page_size: usize,
+}
+
+impl Stack {
+    #[cfg(unix)]
+    fn new(size: usize) -> Option<Stack> {
+        unsafe {
+            let page_size = match libc::sysconf(libc::_SC_PAGESIZE) {
+                n if n > 0 => n as usize,
+                _ => 4096,
+            };
+            let len = size
+                .div_ceil(page_size)
+                .checked_add(1)?
+                .checked_mul(page_size)?;
+            let base = libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANON,
+                -1,
+                0,
+            );
+            if base == libc::MAP_FAILED {
+                return None;
+            }
+            if libc::mprotect(base, page_size, libc::PROT_NONE) != 0 {
+                libc::munmap(base, len);
+                return None;
+            }
+            Some(Stack {
+                base: base.cast(),
+                len,
+                page_size,
+            })
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn new(_size: usize) -> Option<Stack> {
+        None
+    }
+
+    /// Usable range `[low, high)`
+    fn bounds(&self) -> (usize, usize) {
+        let base = self.base as usize;
+        (base + self.page_size, base + self.len)
+    }
+}
+
+impl Drop for Stack {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        unsafe {
+            libc::munmap(self.base.cast(), self.len);
+        }
+    }
+}
+
+#[cfg(all(unix, target_arch = "x86_64"))]
+mod arch {
+    /// Push the callee-saved registers, store the stack pointer to
+    /// `*save_sp`, switch to `new_sp` and pop the registers saved there
+    #[unsafe(naked)]
+    pub unsafe extern "C" fn switch(save_sp: *mut usize, new_sp: usize) {
+        core::arch::naked_asm!(
+            "push rbp",
+            "push rbx",
+            "push r12",
+            "push r13",
+            "push r14",
+            "push r15",
+            "sub rsp, 8",
+            "stmxcsr [rsp]",
+            "fnstcw [rsp + 4]",
+            "mov [rdi], rsp",
+            "mov rsp, rsi",
+            "ldmxcsr [rsp]",
+            "fldcw [rsp + 4]",
+            "add rsp, 8",
+            "pop r15",
+            "pop r14",
+            "pop r13",
+            "pop r12",
+            "pop rbx",
+            "pop rbp",
+            "ret",
+        )
+    }
+
+    /// First code run on a new stack: `entry(arg)`, which never returns
+    #[unsafe(naked)]
+    unsafe extern "C" fn start() {
+        core::arch::naked_asm!("mov rdi, r12", "call r13", "ud2")
+    }
+
+    /// Lay out a frame at the top of `[low, high)` that `switch` pops into
+    /// `entry(arg)`, and return its stack pointer
+    pub unsafe fn init(high: usize, entry: usize, arg: usize) -> usize {
+        let sp = (high & !15) - 64;
+        let frame = sp as *mut usize;
+        // Default MXCSR and x87 control word
+        frame.write((0x037F << 32) | 0x1F80);
+        frame.add(1).write(0); // r15
+        frame.add(2).write(0); // r14
+        frame.add(3).write(entry); // r13
+        frame.add(4).write(arg); // r12
+        frame.add(5).write(0); // rbx
+        frame.add(6).write(0); // rbp
+        frame.add(7).write(start as *const () as usize);
+        sp
+    }
+}
+
+#[cfg(all(unix, target_arch = "aarch64"))]
+mod arch {
+    /// Store the callee-saved registers, store the stack pointer to
+    /// `*save_sp`, switch to `new_sp` and load the registers saved there
+    #[unsafe(naked)]
+    pub unsafe extern "C" fn switch(save_sp: *mut usize, new_sp: usize) {
+        core::arch::naked_asm!(
+            "sub sp, sp, #160",
+            "stp x19, x20, [sp, #0]",
+            "stp x21, x22, [sp, #16]",
+            "stp x23, x24, [sp, #32]",
+            "stp x25, x26, [sp, #48]",
+            "stp x27, x28, [sp, #64]",
+            "stp x29, x30, [sp, #80]",
+            "stp d8, d9, [sp, #96]",
+            "stp d10, d11, [sp, #112]",
+            "stp d12, d13, [sp, #128]",
+            "stp d14, d15, [sp, #144]",
+            "mov x2, sp",
+            "str x2, [x0]",
+            "mov sp, x1",
+            "ldp x19, x20, [sp, #0]",
+            "ldp x21, x22, [sp, #16]",
+            "ldp x23, x24, [sp, #32]",
+            "ldp x25, x26, [sp, #48]",
+            "ldp x27, x28, [sp, #64]",
+            "ldp x29, x30, [sp, #80]",
+            "ldp d8, d9, [sp, #96]",
+            "ldp d10, d11, [sp, #112]",
+            "ldp d12, d13, [sp, #128]",
+            "ldp d14, d15, [sp, #144]",
+            "add sp, sp, #160",
+            "ret",
+        )
+    }
+
+    /// First code run on a new stack: `entry(arg)`, which never returns
+    #[unsafe(naked)]
+    unsafe extern "C" fn start() {
+        core::arch::naked_asm!("mov x0, x19", "blr x20", "brk #1")
+    }
+
+    /// Lay out a frame at the top of `[low, high)` that `switch` loads into
+    /// `entry(arg)`, and return its stack pointer
+    pub unsafe fn init(high: usize, entry: usize, arg: usize) -> usize {
+        let sp = (high & !15) - 160;
+        let frame = sp as *mut usize;
+        for i in 0..20 {
+            frame.add(i).write(0);
+        }
+        frame.write(arg); // x19
+        frame.add(1).write(entry); // x20
+        frame.add(11).write(start as *const () as usize); // x30
+        sp
+    }
+}
+
+#[cfg(not(all(unix, any(target_arch = "x86_64", target_arch = "aarch64"))))]
+mod arch {
+    pub unsafe fn switch(_save_sp: *mut usize, _new_sp: usize) {
+        unreachable!("coroutines are not supported on this platform")
+    }
+
+    pub unsafe fn init(_high: usize, _entry: usize, _arg: usize) -> usize {
+        unreachable!("coroutines are not supported on this platform")
+    }
+}
+
+/// Whether coroutines can be created on this platform
+pub fn is_supported() -> bool {
+    cfg!(all(
+        unix,
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))
+}
+
+/// Runs on the coroutine's stack: the body, then back to the resumer for good
+extern "C" fn coroutine_main(co: *mut Coroutine) {
+    unsafe {
+        ((*co).body)((*co).env);
+        (*co).transfer = ptr::null_mut();
+        (*co).status.store(STATUS_DEAD, Ordering::Release);
+        arch::switch(&mut (*co).sp, (*co).caller_sp);
+    }
+    unreachable!("dead coroutine resumed");
+}
+
+/// Create a suspended coroutine running `closure(closure_env)` on a
+/// `stack_size`-byte stack ([`DEFAULT_STACK_SIZE`] if zero or negative).
+/// Returns null if the platform has no coroutine support or the stack can't
+/// be mapped.
+///
+/// # Safety
+/// `closure` must be a valid closure function pointer
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_coroutine_create(
+    closure: *const u8,
+    closure_env: *const u8,
+    stack_size: i64,
+) -> *mut u8 {
+    if closure.is_null() || !is_supported() {
+        return ptr::null_mut();
+    }
+    let size = usize::try_from(stack_size)
+        .ok()
+        .filter(|&size| size > 0)
+        .unwrap_or(DEFAULT_STACK_SIZE);
+    let Some(stack) = Stack::new(size) else {
+        eprintln!("Coroutine.create: failed to map a {} byte stack", size);
+        return ptr::null_mut();
+    };
+    let (_, high) = stack.bounds();
+    let co = Box::into_raw(Box::new(Coroutine {
+        status: AtomicI32::new(STATUS_SUSPENDED),
+        body: std::mem::transmute::<*const u8, ClosureFn>(closure),
+        env: closure_env,
+        stack: Some(stack),
+        sp: 0,
+        caller_sp: 0,
+        caller_high: 0,
+        transfer: ptr::null_mut(),
+        parent: ptr::null_mut(),
+    }));
+    (*co).sp = arch::init(high, coroutine_main as *const () as usize, co as usize);
+    LIVE.lock().insert(co as usize);
+    co as *mut u8
+}
+
+/// Run `co` until it yields or returns. Returns the yielded value, or null
+/// once the body returned. Resuming a running or dead coroutine returns null
+/// without doing anything.
+///
+/// # Safety
+/// `co` must be null or a coroutine from [`rayzor_coroutine_create`]
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_coroutine_resume(co: *mut u8) -> *mut u8 {
+    let co = co as *mut Coroutine;
+    if co.is_null()
+        || (*co)
+            .status
+            .compare_exchange(
+                STATUS_SUSPENDED,
+                STATUS_RUNNING,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            .is_err()
+    {
+        return ptr::null_mut();
+    }
+
+    (*co).parent = CURRENT.with(|c| c.replace(co));
+    let bounds = (*co).stack.as_ref().map_or((0, 0), Stack::bounds);
+    let caller_bounds = crate::stack_guard::swap_bounds(bounds);
+    (*co).caller_high = caller_bounds.1;
+
+    arch::switch(&mut (*co).caller_sp, (*co).sp);
+
+    crate::stack_guard::swap_bounds(caller_bounds);
+    CURRENT.with(|c| c.set((*co).parent));
+    (*co).parent = ptr::null_mut();
+
+    let value = std::mem::replace(&mut (*co).transfer, ptr::null_mut());
+    if (*co).status.load(Ordering::Acquire) == STATUS_DEAD {
+        // Off its stack for good
+        LIVE.lock().remove(&(co as usize));
+        (*co).stack = None;
+    }
+    value
+}
+
+/// Suspend the innermost coroutine running on this thread, handing `value`
+/// to its `resume`. Does nothing outside a coroutine.
+///
+/// # Safety
+/// Must not be called while a borrow into the coroutine's stack is held by
+/// the resumer
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_coroutine_yield(value: *mut u8) {
+    let co = CURRENT.with(|c| c.get());
+    if co.is_null() {
+        return;
+    }
+    (*co).transfer = value;
+    (*co).status.store(STATUS_SUSPENDED, Ordering::Release);
+    arch::switch(&mut (*co).sp, (*co).caller_sp);
+}
+
+/// Status of `co`: 0 suspended, 1 running, 2 dead (also for null)
+///
+/// # Safety
+/// `co` must be null or a coroutine from [`rayzor_coroutine_create`]
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_coroutine_status(co: *mut u8) -> i32 {
+    if co.is_null() {
+        return STATUS_DEAD;
+    }
+    (*(co as *const Coroutine)).status.load(Ordering::Acquire)
+}
+
+/// Whether the body of `co` returned
+///
+/// # Safety
+/// `co` must be null or a coroutine from [`rayzor_coroutine_create`]
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_coroutine_is_dead(co: *mut u8) -> bool {
+    rayzor_coroutine_status(co) == STATUS_DEAD
+}
+
+/// Free `co` and its stack. A suspended coroutine is dropped without
+/// finishing its body. Returns false, leaving `co` untouched, if it is
+/// running; null is a no-op that returns true.
+///
+/// # Safety
+/// `co` must be null or a coroutine from [`rayzor_coroutine_create`], and
+/// must not be used after this returns true
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_coroutine_destroy(co: *mut u8) -> bool {
+    let co = co as *mut Coroutine;
+    if co.is_null() {
+        return true;
+    }
+    // Claim a suspended coroutine, so no other thread resumes it meanwhile
+    let claimed = (*co).status.compare_exchange(
+        STATUS_SUSPENDED,
+        STATUS_DEAD,
+        Ordering::Acquire,
+        Ordering::Acquire,
+    );
+    if claimed == Err(STATUS_RUNNING) {
+        return false;
+    }
+    LIVE.lock().remove(&(co as usize));
+    // Unmaps the stack
+    drop(Box::from_raw(co));
+    true
+}
+
+/// Add the stack ranges the collector must scan besides the current one:
+/// from each resumer's saved stack pointer up, for the coroutines this
+/// thread is nested in, and the suspended part of every suspended
+/// coroutine's stack. Returns false if a resumer's stack can't be found.
+pub(crate) fn stack_ranges(ranges: &mut Vec<(usize, usize)>) -> bool {
+    let mut co = CURRENT.with(|c| c.get());
+    while !co.is_null() {
+        // SAFETY: coroutines in the chain are running, so alive
+        unsafe {
+            // The outermost resumer may be a thread that never recorded
+            // its stack
+            let high = match (*co).caller_high {
+                0 => match crate::stack_guard::current_bounds() {
+                    Some((_, high)) => high,
+                    None => return false,
+                },
+                high => high,
+            };
+            ranges.push(((*co).caller_sp, high));
+            co = (*co).parent;
+        }
+    }
+    for &co in LIVE.lock().iter() {
+        let co = co as *const Coroutine;
+        // SAFETY: coroutines leave LIVE before their stack is dropped
+        unsafe {
+            if (*co).status.load(Ordering::Acquire) != STATUS_SUSPENDED {
+                continue;
+            }
+            if let Some(stack) = &(*co).stack {
+                ranges.push(((*co).sp, stack.bounds().1));
+            }
+        }
+    }
+    true
+}
+
+/// Whether the calling code runs inside a coroutine
+#[no_mangle]
+pub extern "C" fn rayzor_coroutine_is_inside() -> bool {
+    !CURRENT.with(|c| c.get()).is_null()
+}
+
+#[cfg(all(test, unix, any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod tests {
+    use super::*;
+
+    /// Yields 1, 2, 3 and returns
+    extern "C" fn count_to_three(_env: *const u8) -> i32 {
+        for i in 1..=3usize {
+            unsafe { rayzor_coroutine_yield(i as *mut u8) };
+        }
+        0
+    }
+
+    #[test]
+    fn test_resume_yield() {
+        unsafe {
+            let co = rayzor_coroutine_create(count_to_three as *const u8, ptr::null(), 0);
+            assert!(!co.is_null());
+            assert_eq!(rayzor_coroutine_status(co), STATUS_SUSPENDED);
+            for i in 1..=3usize {
+                assert_eq!(rayzor_coroutine_resume(co) as usize, i);
+                assert_eq!(rayzor_coroutine_status(co), STATUS_SUSPENDED);
+            }
+            assert!(rayzor_coroutine_resume(co).is_null());
+            assert!(rayzor_coroutine_is_dead(co));
+            assert!((*(co as *mut Coroutine)).stack.is_none());
+            // Resuming a dead coroutine is a no-op
+            assert!(rayzor_coroutine_resume(co).is_null());
+            assert!(!rayzor_coroutine_is_inside());
+        }
+    }
+
+    /// Resumes the coroutine in `env` twice from inside another coroutine,
+    /// yielding the sum of its values
+    extern "C" fn sum_inner(env: *const u8) -> i32 {
+        unsafe {
+            let inner = env as *mut u8;
+            let a = rayzor_coroutine_resume(inner) as usize;
+            assert!(rayzor_coroutine_is_inside());
+            let b = rayzor_coroutine_resume(inner) as usize;
+            rayzor_coroutine_yield((a + b) as *mut u8);
+        }
+        0
+    }
+
+    #[test]
+    fn test_nested() {
+        unsafe {
+            let inner = rayzor_coroutine_create(count_to_three as *const u8, ptr::null(), 0);
+            let outer = rayzor_coroutine_create(sum_inner as *const u8, inner, 64 * 1024);
+            assert_eq!(rayzor_coroutine_resume(outer) as usize, 3);
+            assert_eq!(rayzor_coroutine_resume(inner) as usize, 3);
+            assert!(rayzor_coroutine_resume(outer).is_null());
+            assert!(rayzor_coroutine_is_dead(outer));
+            assert!(!rayzor_coroutine_is_dead(inner));
+        }
+    }
+
+    /// Keeps floating-point and callee-saved state live across yields
+    extern "C" fn float_sum(_env: *const u8) -> i32 {
+        let mut total = 0.5f64;
+        for i in 0..4 {
+            total = total * 2.0 + i as f64;
+            unsafe { rayzor_coroutine_yield(total as usize as *mut u8) };
+        }
+        0
+    }
+
+    #[test]
+    fn test_preserves_registers() {
+        unsafe {
+            let co = rayzor_coroutine_create(float_sum as *const u8, ptr::null(), 0);
+            let mut expected = 0.5f64;
+            let mut acc = 1.25f64;
+            for i in 0..4 {
+                expected = expected * 2.0 + i as f64;
+                acc *= 1.5;
+                assert_eq!(rayzor_coroutine_resume(co) as usize, expected as usize);
+            }
+            assert_eq!(acc, 1.25 * 1.5f64.powi(4));
+            assert!(rayzor_coroutine_resume(co).is_null());
+        }
+    }
+
+    /// Tries to destroy itself, then yields
+    extern "C" fn destroy_self(_env: *const u8) -> i32 {
+        unsafe {
+            let co = CURRENT.with(|c| c.get());
+            let destroyed = rayzor_coroutine_destroy(co as *mut u8);
+            rayzor_coroutine_yield(destroyed as usize as *mut u8);
+        }
+        0
+    }
+
+    #[test]
+    fn test_destroy() {
+        unsafe {
+            // Suspended halfway through its body
+            let co = rayzor_coroutine_create(count_to_three as *const u8, ptr::null(), 0);
+            assert_eq!(rayzor_coroutine_resume(co) as usize, 1);
+            assert!(LIVE.lock().contains(&(co as usize)));
+            assert!(rayzor_coroutine_destroy(co));
+            assert!(!LIVE.lock().contains(&(co as usize)));
+
+            // Dead
+            let co = rayzor_coroutine_create(count_to_three as *const u8, ptr::null(), 0);
+            while !rayzor_coroutine_is_dead(co) {
+                rayzor_coroutine_resume(co);
+            }
+            assert!(rayzor_coroutine_destroy(co));
+
+            // Running: rejected
+            let co = rayzor_coroutine_create(destroy_self as *const u8, ptr::null(), 0);
+            assert_eq!(rayzor_coroutine_resume(co) as usize, 0);
+            assert_eq!(rayzor_coroutine_status(co), STATUS_SUSPENDED);
+            assert!(rayzor_coroutine_destroy(co));
+
+            assert!(rayzor_coroutine_destroy(ptr::null_mut()));
+        }
+    }
+
+    #[test]
+    fn test_yield_outside_coroutine() {
+        unsafe {
+            rayzor_coroutine_yield(7 as *mut u8);
+            assert!(rayzor_coroutine_resume(ptr::null_mut()).is_null());
+            assert_eq!(rayzor_coroutine_status(ptr::null_mut()), STATUS_DEAD);
+        }
+    }
+}
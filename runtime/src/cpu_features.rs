@@ -0,0 +1,124 @@
+//! CPU feature detection for multi-versioned functions
+//!
+//! AOT builds compile `@:targetFeature("avx2")` functions once per feature
+//! set plus a baseline version. A startup resolver asks this module which
+//! versions the running CPU can execute and points each function at the
+//! first one that fits.
+//!
+//! ```c
+//! // Non-zero if every feature of the comma-separated list is available.
+//! int32_t rayzor_cpu_supports(const char* features);
+//! ```
+//!
+//! Feature names follow the Rust/LLVM spelling (`avx2`, `sse4.2`, `neon`,
+//! `dotprod`). Unknown names, and names for another architecture, are
+//! reported as unsupported.
+
+use std::ffi::{c_char, CStr};
+
+/// Whether the running CPU supports one feature
+pub fn cpu_has_feature(feature: &str) -> bool {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        match feature {
+            "sse3" => std::arch::is_x86_feature_detected!("sse3"),
+            "ssse3" => std::arch::is_x86_feature_detected!("ssse3"),
+            "sse4.1" => std::arch::is_x86_feature_detected!("sse4.1"),
+            "sse4.2" => std::arch::is_x86_feature_detected!("sse4.2"),
+            "popcnt" => std::arch::is_x86_feature_detected!("popcnt"),
+            "avx" => std::arch::is_x86_feature_detected!("avx"),
+            "avx2" => std::arch::is_x86_feature_detected!("avx2"),
+            "fma" => std::arch::is_x86_feature_detected!("fma"),
+            "f16c" => std::arch::is_x86_feature_detected!("f16c"),
+            "bmi1" => std::arch::is_x86_feature_detected!("bmi1"),
+            "bmi2" => std::arch::is_x86_feature_detected!("bmi2"),
+            "lzcnt" => std::arch::is_x86_feature_detected!("lzcnt"),
+            "avx512f" => std::arch::is_x86_feature_detected!("avx512f"),
+            "avx512bw" => std::arch::is_x86_feature_detected!("avx512bw"),
+            "avx512dq" => std::arch::is_x86_feature_detected!("avx512dq"),
+            "avx512vl" => std::arch::is_x86_feature_detected!("avx512vl"),
+            _ => false,
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        match feature {
+            "neon" => std::arch::is_aarch64_feature_detected!("neon"),
+            "fp16" => std::arch::is_aarch64_feature_detected!("fp16"),
+            "dotprod" => std::arch::is_aarch64_feature_detected!("dotprod"),
+            "crc" => std::arch::is_aarch64_feature_detected!("crc"),
+            "aes" => std::arch::is_aarch64_feature_detected!("aes"),
+            "sha2" => std::arch::is_aarch64_feature_detected!("sha2"),
+            "sve" => std::arch::is_aarch64_feature_detected!("sve"),
+            "sve2" => std::arch::is_aarch64_feature_detected!("sve2"),
+            _ => false,
+        }
+    }
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        let _ = feature;
+        false
+    }
+}
+
+/// Whether the running CPU supports every feature of a comma-separated list
+pub fn cpu_supports(features: &str) -> bool {
+    features
+        .split(',')
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .all(cpu_has_feature)
+}
+
+/// C entry point used by the multi-versioning resolver
+///
+/// # Safety
+/// `features` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_cpu_supports(features: *const c_char) -> i32 {
+    if features.is_null() {
+        return 0;
+    }
+    let features = CStr::from_ptr(features).to_string_lossy();
+    cpu_supports(&features) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_features_are_unsupported() {
+        assert!(!cpu_has_feature("warp-drive"));
+        assert!(!cpu_supports("avx2,warp-drive"));
+        assert_eq!(unsafe { rayzor_cpu_supports(c"warp-drive".as_ptr()) }, 0);
+        assert_eq!(unsafe { rayzor_cpu_supports(std::ptr::null()) }, 0);
+    }
+
+    #[test]
+    fn test_empty_list_is_supported() {
+        assert!(cpu_supports(""));
+        assert_eq!(unsafe { rayzor_cpu_supports(c"".as_ptr()) }, 1);
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn test_x86_64_baseline() {
+        assert_eq!(
+            cpu_has_feature("avx2"),
+            std::arch::is_x86_feature_detected!("avx2")
+        );
+        if cpu_supports("avx2, fma") {
+            assert!(cpu_has_feature("avx2") && cpu_has_feature("fma"));
+        }
+        assert!(!cpu_has_feature("neon"));
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    #[test]
+    fn test_aarch64_baseline() {
+        // NEON is mandatory on AArch64
+        assert!(cpu_has_feature("neon"));
+        assert!(!cpu_has_feature("avx2"));
+    }
+}
@@ -0,0 +1,830 @@
+//! Crash reports for faults in JIT-compiled code
+//!
+//! Crash reporting is opt-in (`rayzor run --crash-report` or
+//! `RAYZOR_CRASH_REPORT=1`). Once [`install`] is called, SIGSEGV, SIGBUS,
+//! SIGILL, SIGFPE and SIGABRT write a plain-text report to `.rayzor-crash/`
+//! before the process dies with the original signal:
+//!
+//! - signal, fault address and program counter
+//! - the faulting Haxe function, its source location and the offset into it
+//! - register state
+//! - the MIR and Cranelift disassembly of the faulting function
+//! - the machine code around the program counter
+//! - the last [`TRACE_HISTORY`] lines of `trace()` output
+//! - compiler and runtime versions
+//!
+//! The JIT describes each compiled function with [`register_function`]. The
+//! signal handler never allocates: it formats straight into the report file
+//! and skips the function table or trace history if another thread holds
+//! their lock at the time of the crash.
+//!
+//! `rayzor crash-report inspect` parses reports with [`CrashReport`].
+
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use parking_lot::{Mutex, RwLock};
+
+/// Directory reports are written to, relative to the working directory
+pub const CRASH_DIR: &str = ".rayzor-crash";
+
+/// Number of recent trace lines kept for the report
+pub const TRACE_HISTORY: usize = 64;
+
+/// First line of every report
+const REPORT_HEADER: &str = "rayzor crash report";
+
+/// Bytes of machine code dumped on each side of the program counter
+const CODE_WINDOW: usize = 64;
+
+/// A JIT-compiled function, as shown in crash reports
+#[derive(Debug, Clone)]
+pub struct CrashFunction {
+    /// Qualified Haxe name, e.g. `Main.divide`
+    pub name: String,
+    /// Source location, e.g. `Main.hx:12`
+    pub location: String,
+    /// Address of the first instruction
+    pub start: usize,
+    /// Size of the machine code in bytes
+    pub size: usize,
+    /// MIR dump of the function
+    pub mir: String,
+    /// Backend disassembly (empty if unavailable)
+    pub disassembly: String,
+}
+
+impl CrashFunction {
+    fn contains(&self, pc: usize) -> bool {
+        pc >= self.start && pc < self.start.saturating_add(self.size)
+    }
+}
+
+struct CrashState {
+    /// Report directory, NUL-terminated
+    dir: Vec<u8>,
+    version: String,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static FUNCTION_TABLE: AtomicBool = AtomicBool::new(false);
+static IN_HANDLER: AtomicBool = AtomicBool::new(false);
+static STATE: OnceLock<CrashState> = OnceLock::new();
+static FUNCTIONS: RwLock<Vec<CrashFunction>> = parking_lot::const_rwlock(Vec::new());
+static TRACES: Mutex<VecDeque<String>> = parking_lot::const_mutex(VecDeque::new());
+
+/// Whether crash reporting has been installed. The JIT only collects MIR
+/// and disassembly for [`register_function`] when this is true.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Acquire)
+}
+
+/// Have the JIT register its functions even without crash reports, so that
+/// other diagnostics (stack overflows) can name Haxe functions
+pub fn enable_function_table() {
+    FUNCTION_TABLE.store(true, Ordering::Release);
+}
+
+/// Whether the JIT should call [`register_function`]
+pub fn has_function_table() -> bool {
+    is_enabled() || FUNCTION_TABLE.load(Ordering::Acquire)
+}
+
+/// Describe a compiled function for crash reports. Functions recompiled at a
+/// higher tier are registered again; the newest registration covering the
+/// program counter wins.
+pub fn register_function(function: CrashFunction) {
+    if function.size == 0 {
+        return;
+    }
+    FUNCTIONS.write().push(function);
+}
+
+/// Call `f` with the registered function containing `pc`, or None if there
+/// is none or another thread holds the function table. Doesn't allocate, so
+/// it can be used in a signal handler.
+pub fn with_function_at<R>(pc: usize, f: impl FnOnce(Option<&CrashFunction>) -> R) -> R {
+    match FUNCTIONS.try_read() {
+        Some(functions) => f(functions.iter().rev().find(|func| func.contains(pc))),
+        None => f(None),
+    }
+}
+
+/// `Class.method (File.hx:line)` of the registered function containing `pc`
+pub fn describe_pc(pc: usize) -> Option<String> {
+    let functions = FUNCTIONS.read();
+    functions
+        .iter()
+        .rev()
+        .find(|f| f.contains(pc))
+        .map(|f| format!("{} ({})", f.name, f.location))
+}
+
+/// Remember a line of `trace()` output for the report
+pub fn record_trace(line: &str) {
+    if !is_enabled() {
+        return;
+    }
+    let mut traces = TRACES.lock();
+    if traces.len() == TRACE_HISTORY {
+        traces.pop_front();
+    }
+    traces.push_back(line.to_string());
+}
+
+/// Install the crash handler, writing reports to `dir` (created on the
+/// first crash). `version` identifies the compiler in reports. Only the
+/// first call takes effect.
+///
+/// The alternate signal stack used to report stack overflows is installed
+/// for the calling thread only.
+#[cfg(unix)]
+pub fn install(dir: impl AsRef<Path>, version: &str) -> std::io::Result<()> {
+    let dir = std::path::absolute(dir.as_ref())?;
+    let mut dir_bytes = dir.as_os_str().as_encoded_bytes().to_vec();
+    dir_bytes.push(0);
+    if STATE
+        .set(CrashState {
+            dir: dir_bytes,
+            version: version.to_string(),
+        })
+        .is_err()
+    {
+        return Ok(());
+    }
+
+    unsafe { handler::install()? };
+    ENABLED.store(true, Ordering::Release);
+    Ok(())
+}
+
+/// Crash reports need POSIX signals
+#[cfg(not(unix))]
+pub fn install(_dir: impl AsRef<Path>, _version: &str) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "crash reports are only supported on Unix",
+    ))
+}
+
+/// Name of a fatal signal number
+pub fn signal_name(signal: i32) -> &'static str {
+    match signal {
+        libc::SIGSEGV => "SIGSEGV",
+        libc::SIGBUS => "SIGBUS",
+        libc::SIGILL => "SIGILL",
+        libc::SIGFPE => "SIGFPE",
+        libc::SIGABRT => "SIGABRT",
+        _ => "signal",
+    }
+}
+
+/// Machine state at the fault
+struct Fault<'a> {
+    signal: i32,
+    address: usize,
+    pc: usize,
+    registers: &'a [(&'static str, u64)],
+}
+
+/// Format a report. `functions` and `traces` are None when their lock could
+/// not be taken.
+fn write_report<W: fmt::Write>(
+    out: &mut W,
+    fault: &Fault,
+    version: &str,
+    pid: u32,
+    time: i64,
+    functions: Option<&[CrashFunction]>,
+    traces: Option<&VecDeque<String>>,
+) -> fmt::Result {
+    writeln!(out, "{}", REPORT_HEADER)?;
+    writeln!(
+        out,
+        "version = {} (runtime {}, {}-{})",
+        version,
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )?;
+    writeln!(
+        out,
+        "signal = {} ({})",
+        signal_name(fault.signal),
+        fault.signal
+    )?;
+    writeln!(out, "fault-address = {:#018x}", fault.address)?;
+    writeln!(out, "pc = {:#018x}", fault.pc)?;
+    writeln!(out, "pid = {}", pid)?;
+    writeln!(out, "time = {}", time)?;
+
+    let function = functions.and_then(|fs| fs.iter().rev().find(|f| f.contains(fault.pc)));
+    match function {
+        Some(f) => {
+            writeln!(out, "function = {}", f.name)?;
+            writeln!(out, "location = {}", f.location)?;
+            writeln!(out, "offset = {:#x}", fault.pc - f.start)?;
+        }
+        None if functions.is_none() => writeln!(out, "function = <function table locked>")?,
+        None => writeln!(out, "function = <not JIT code>")?,
+    }
+
+    writeln!(out, "\n--- registers ---")?;
+    for (name, value) in fault.registers {
+        writeln!(out, "{:<6} {:#018x}", name, value)?;
+    }
+
+    if let Some(f) = function {
+        writeln!(out, "\n--- mir ---")?;
+        writeln!(out, "{}", f.mir.trim_end())?;
+        if !f.disassembly.is_empty() {
+            writeln!(out, "\n--- disassembly ---")?;
+            writeln!(out, "{}", f.disassembly.trim_end())?;
+        }
+        writeln!(out, "\n--- code ---")?;
+        write_code(out, f, fault.pc)?;
+    }
+
+    writeln!(out, "\n--- trace ---")?;
+    match traces {
+        Some(traces) => {
+            for line in traces {
+                writeln!(out, "{}", line)?;
+            }
+        }
+        None => writeln!(out, "<trace history locked>")?,
+    }
+    Ok(())
+}
+
+/// Hex dump of the code around `pc`, 16 bytes per line, marking the line
+/// holding the faulting instruction
+fn write_code<W: fmt::Write>(out: &mut W, function: &CrashFunction, pc: usize) -> fmt::Result {
+    let end = function.start + function.size;
+    let from = pc.saturating_sub(CODE_WINDOW) & !0xf;
+    let from = from.max(function.start);
+    let to = pc.saturating_add(CODE_WINDOW).min(end);
+    let mut line = from;
+    while line < to {
+        let line_end = (line + 16).min(to);
+        let marker = if (line..line_end).contains(&pc) {
+            "=>"
+        } else {
+            "  "
+        };
+        write!(out, "{} {:#018x} ", marker, line)?;
+        for addr in line..line_end {
+            // SAFETY: [start, start + size) is registered compiled code
+            let byte = unsafe { *(addr as *const u8) };
+            write!(out, " {:02x}", byte)?;
+        }
+        writeln!(out)?;
+        line = line_end;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+pub(crate) use handler::{context_pc, FdWriter};
+
+#[cfg(unix)]
+mod handler {
+    use super::*;
+
+    const SIGNALS: [libc::c_int; 5] = [
+        libc::SIGSEGV,
+        libc::SIGBUS,
+        libc::SIGILL,
+        libc::SIGFPE,
+        libc::SIGABRT,
+    ];
+
+    /// Alternate stack size; large enough to format a report after a stack
+    /// overflow
+    const ALT_STACK_SIZE: usize = 256 * 1024;
+
+    pub(super) unsafe fn install() -> std::io::Result<()> {
+        let stack = Box::leak(vec![0u8; ALT_STACK_SIZE].into_boxed_slice());
+        let alt = libc::stack_t {
+            ss_sp: stack.as_mut_ptr().cast(),
+            ss_flags: 0,
+            ss_size: ALT_STACK_SIZE,
+        };
+        if libc::sigaltstack(&alt, std::ptr::null_mut()) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = on_fault as *const () as usize;
+        action.sa_flags = libc::SA_SIGINFO | libc::SA_ONSTACK;
+        libc::sigemptyset(&mut action.sa_mask);
+        for signal in SIGNALS {
+            if libc::sigaction(signal, &action, std::ptr::null_mut()) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    extern "C" fn on_fault(
+        signal: libc::c_int,
+        info: *mut libc::siginfo_t,
+        context: *mut libc::c_void,
+    ) {
+        unsafe {
+            // A fault while reporting: die with the original signal
+            if !IN_HANDLER.swap(true, Ordering::SeqCst) {
+                report(signal, info, context);
+            }
+            libc::signal(signal, libc::SIG_DFL);
+            libc::raise(signal);
+        }
+    }
+
+    unsafe fn report(signal: libc::c_int, info: *mut libc::siginfo_t, context: *mut libc::c_void) {
+        let Some(state) = STATE.get() else {
+            return;
+        };
+
+        let mut registers = [("", 0u64); 40];
+        let (count, pc) = read_registers(context, &mut registers);
+        let fault = Fault {
+            signal,
+            address: if info.is_null() {
+                0
+            } else {
+                (*info).si_addr() as usize
+            },
+            pc,
+            registers: &registers[..count],
+        };
+
+        let pid = libc::getpid() as u32;
+        let time = libc::time(std::ptr::null_mut()) as i64;
+        libc::mkdir(state.dir.as_ptr().cast(), 0o755);
+        let mut path = ReportPath::new();
+        if write!(
+            path,
+            "{}/crash-{}-{}.txt\0",
+            std::str::from_utf8_unchecked(&state.dir[..state.dir.len() - 1]),
+            time,
+            pid
+        )
+        .is_err()
+        {
+            return;
+        }
+        let fd = libc::open(
+            path.as_ptr().cast(),
+            libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC,
+            0o644,
+        );
+        if fd < 0 {
+            return;
+        }
+
+        let functions = FUNCTIONS.try_read();
+        let traces = TRACES.try_lock();
+        let _ = write_report(
+            &mut FdWriter(fd),
+            &fault,
+            &state.version,
+            pid,
+            time,
+            functions.as_deref().map(Vec::as_slice),
+            traces.as_deref(),
+        );
+        libc::close(fd);
+
+        let _ = writeln!(
+            FdWriter(libc::STDERR_FILENO),
+            "rayzor: fatal {}, crash report written to {}",
+            signal_name(signal),
+            path.as_str()
+        );
+    }
+
+    /// Fixed-size, NUL-terminated path buffer (no allocation in the handler)
+    struct ReportPath {
+        bytes: [u8; 4096],
+        len: usize,
+    }
+
+    impl ReportPath {
+        fn new() -> Self {
+            Self {
+                bytes: [0; 4096],
+                len: 0,
+            }
+        }
+
+        fn as_ptr(&self) -> *const u8 {
+            self.bytes.as_ptr()
+        }
+
+        fn as_str(&self) -> &str {
+            // Without the trailing NUL
+            std::str::from_utf8(&self.bytes[..self.len.saturating_sub(1)]).unwrap_or("?")
+        }
+    }
+
+    impl fmt::Write for ReportPath {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            if end > self.bytes.len() {
+                return Err(fmt::Error);
+            }
+            self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    /// Writes straight to a file descriptor
+    pub(crate) struct FdWriter(pub(crate) libc::c_int);
+
+    impl fmt::Write for FdWriter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let mut bytes = s.as_bytes();
+            while !bytes.is_empty() {
+                let n = unsafe { libc::write(self.0, bytes.as_ptr().cast(), bytes.len()) };
+                if n <= 0 {
+                    return Err(fmt::Error);
+                }
+                bytes = &bytes[n as usize..];
+            }
+            Ok(())
+        }
+    }
+
+    /// Program counter of the signal context
+    pub(crate) unsafe fn context_pc(context: *mut libc::c_void) -> usize {
+        if context.is_null() {
+            return 0;
+        }
+        machine_registers(context as *const libc::ucontext_t, &mut |_, _| {})
+    }
+
+    /// Copy the general-purpose registers out of the signal context.
+    /// Returns the number of registers written and the program counter.
+    unsafe fn read_registers(
+        context: *mut libc::c_void,
+        out: &mut [(&'static str, u64); 40],
+    ) -> (usize, usize) {
+        if context.is_null() {
+            return (0, 0);
+        }
+        let mut count = 0;
+        let pc = machine_registers(context as *const libc::ucontext_t, &mut |name, value| {
+            out[count] = (name, value);
+            count += 1;
+        });
+        (count, pc)
+    }
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    unsafe fn machine_registers(
+        uc: *const libc::ucontext_t,
+        push: &mut dyn FnMut(&'static str, u64),
+    ) -> usize {
+        const NAMES: [(&str, libc::c_int); 18] = [
+            ("rax", libc::REG_RAX),
+            ("rbx", libc::REG_RBX),
+            ("rcx", libc::REG_RCX),
+            ("rdx", libc::REG_RDX),
+            ("rsi", libc::REG_RSI),
+            ("rdi", libc::REG_RDI),
+            ("rbp", libc::REG_RBP),
+            ("rsp", libc::REG_RSP),
+            ("r8", libc::REG_R8),
+            ("r9", libc::REG_R9),
+            ("r10", libc::REG_R10),
+            ("r11", libc::REG_R11),
+            ("r12", libc::REG_R12),
+            ("r13", libc::REG_R13),
+            ("r14", libc::REG_R14),
+            ("r15", libc::REG_R15),
+            ("rip", libc::REG_RIP),
+            ("rflags", libc::REG_EFL),
+        ];
+        let gregs = &(*uc).uc_mcontext.gregs;
+        for (name, index) in NAMES {
+            push(name, gregs[index as usize] as u64);
+        }
+        gregs[libc::REG_RIP as usize] as usize
+    }
+
+    #[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+    unsafe fn machine_registers(
+        uc: *const libc::ucontext_t,
+        push: &mut dyn FnMut(&'static str, u64),
+    ) -> usize {
+        let mcontext = &(*uc).uc_mcontext;
+        for (name, value) in X_NAMES.iter().zip(mcontext.regs.iter()) {
+            push(name, *value);
+        }
+        push("sp", mcontext.sp);
+        push("pc", mcontext.pc);
+        push("pstate", mcontext.pstate);
+        mcontext.pc as usize
+    }
+
+    #[cfg(all(target_os = "macos", target_arch = "x86_64"))]
+    unsafe fn machine_registers(
+        uc: *const libc::ucontext_t,
+        push: &mut dyn FnMut(&'static str, u64),
+    ) -> usize {
+        let ss = &(*(*uc).uc_mcontext).__ss;
+        for (name, value) in [
+            ("rax", ss.__rax),
+            ("rbx", ss.__rbx),
+            ("rcx", ss.__rcx),
+            ("rdx", ss.__rdx),
+            ("rsi", ss.__rsi),
+            ("rdi", ss.__rdi),
+            ("rbp", ss.__rbp),
+            ("rsp", ss.__rsp),
+            ("r8", ss.__r8),
+            ("r9", ss.__r9),
+            ("r10", ss.__r10),
+            ("r11", ss.__r11),
+            ("r12", ss.__r12),
+            ("r13", ss.__r13),
+            ("r14", ss.__r14),
+            ("r15", ss.__r15),
+            ("rip", ss.__rip),
+            ("rflags", ss.__rflags),
+        ] {
+            push(name, value);
+        }
+        ss.__rip as usize
+    }
+
+    #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+    unsafe fn machine_registers(
+        uc: *const libc::ucontext_t,
+        push: &mut dyn FnMut(&'static str, u64),
+    ) -> usize {
+        let ss = &(*(*uc).uc_mcontext).__ss;
+        for (name, value) in X_NAMES.iter().zip(ss.__x.iter()) {
+            push(name, *value);
+        }
+        push("fp", ss.__fp);
+        push("lr", ss.__lr);
+        push("sp", ss.__sp);
+        push("pc", ss.__pc);
+        push("cpsr", ss.__cpsr as u64);
+        ss.__pc as usize
+    }
+
+    /// Register layout unknown: the report only has the fault address
+    #[cfg(not(any(
+        all(
+            target_os = "linux",
+            any(target_arch = "x86_64", target_arch = "aarch64")
+        ),
+        all(
+            target_os = "macos",
+            any(target_arch = "x86_64", target_arch = "aarch64")
+        ),
+    )))]
+    unsafe fn machine_registers(
+        _uc: *const libc::ucontext_t,
+        _push: &mut dyn FnMut(&'static str, u64),
+    ) -> usize {
+        0
+    }
+
+    #[cfg(all(any(target_os = "linux", target_os = "macos"), target_arch = "aarch64"))]
+    const X_NAMES: [&str; 31] = [
+        "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12", "x13",
+        "x14", "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24", "x25", "x26",
+        "x27", "x28", "x29", "x30",
+    ];
+}
+
+/// A parsed crash report
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrashReport {
+    /// `key = value` header fields, in order
+    pub fields: Vec<(String, String)>,
+    /// `--- name ---` sections and their text, in order
+    pub sections: Vec<(String, String)>,
+}
+
+impl CrashReport {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines();
+        if lines.next() != Some(REPORT_HEADER) {
+            return Err("not a rayzor crash report".to_string());
+        }
+
+        let mut fields = Vec::new();
+        let mut sections: Vec<(String, String)> = Vec::new();
+        for line in lines {
+            let section_name = line
+                .strip_prefix("--- ")
+                .and_then(|rest| rest.strip_suffix(" ---"));
+            if let Some(name) = section_name {
+                sections.push((name.to_string(), String::new()));
+            } else if let Some((_, body)) = sections.last_mut() {
+                body.push_str(line);
+                body.push('\n');
+            } else if let Some((key, value)) = line.split_once(" = ") {
+                fields.push((key.to_string(), value.to_string()));
+            }
+        }
+        for (_, body) in &mut sections {
+            let trimmed = body.trim_end().len();
+            body.truncate(trimmed);
+        }
+        Ok(Self { fields, sections })
+    }
+
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn section(&self, name: &str) -> Option<&str> {
+        self.sections
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Human-readable summary followed by the report sections
+    pub fn pretty(&self) -> String {
+        let get = |key| self.field(key).unwrap_or("?");
+        let mut out = String::new();
+        let _ = writeln!(out, "{} at pc {}", get("signal"), get("pc"));
+        match self.field("location") {
+            Some(location) => {
+                let _ = writeln!(
+                    out,
+                    "  in {} ({}) at offset {}",
+                    get("function"),
+                    location,
+                    get("offset")
+                );
+            }
+            None => {
+                let _ = writeln!(out, "  in {}", get("function"));
+            }
+        }
+        let _ = writeln!(out, "  fault address {}", get("fault-address"));
+        let _ = writeln!(out, "  process {} at unix time {}", get("pid"), get("time"));
+        let _ = writeln!(out, "  {}", get("version"));
+
+        for (name, body) in &self.sections {
+            let _ = writeln!(out, "\n{}:", capitalize(name));
+            if body.is_empty() {
+                let _ = writeln!(out, "    (none)");
+                continue;
+            }
+            if name == "registers" {
+                // Three registers per line
+                let registers: Vec<&str> = body.lines().collect();
+                for row in registers.chunks(3) {
+                    let _ = writeln!(out, "    {}", row.join("   "));
+                }
+                continue;
+            }
+            for line in body.lines() {
+                let _ = writeln!(out, "    {}", line);
+            }
+        }
+        out
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Most recent report in `dir`, if any
+pub fn latest_report(dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("crash-") && n.ends_with(".txt"))
+        })
+        .max_by_key(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static CODE: [u8; 48] = [0x90; 48];
+
+    fn function() -> CrashFunction {
+        CrashFunction {
+            name: "Main.divide".to_string(),
+            location: "Main.hx:12".to_string(),
+            start: CODE.as_ptr() as usize,
+            size: CODE.len(),
+            mir: "fn divide(a: i32, b: i32) -> i32 {\n  bb0:\n    ret\n}\n".to_string(),
+            disassembly: "block0:\n  udf\n".to_string(),
+        }
+    }
+
+    fn report(functions: Option<&[CrashFunction]>, pc: usize) -> String {
+        let registers = [("rax", 1u64), ("rbx", 2), ("rip", pc as u64), ("rsp", 4)];
+        let fault = Fault {
+            signal: libc::SIGSEGV,
+            address: 0,
+            pc,
+            registers: &registers,
+        };
+        let traces: VecDeque<String> = ["first".to_string(), "second".to_string()].into();
+        let mut out = String::new();
+        write_report(
+            &mut out,
+            &fault,
+            "rayzor 0.1.0",
+            42,
+            1_700_000_000,
+            functions,
+            Some(&traces),
+        )
+        .unwrap();
+        out
+    }
+
+    #[test]
+    fn test_report_for_jit_function() {
+        let functions = [function()];
+        let pc = CODE.as_ptr() as usize + 20;
+        let text = report(Some(&functions), pc);
+
+        let parsed = CrashReport::parse(&text).unwrap();
+        assert_eq!(parsed.field("signal"), Some("SIGSEGV (11)"));
+        assert_eq!(parsed.field("function"), Some("Main.divide"));
+        assert_eq!(parsed.field("location"), Some("Main.hx:12"));
+        assert_eq!(parsed.field("offset"), Some("0x14"));
+        assert_eq!(parsed.field("pid"), Some("42"));
+        assert!(parsed.field("version").unwrap().starts_with("rayzor 0.1.0"));
+
+        assert!(parsed.section("mir").unwrap().contains("fn divide"));
+        assert_eq!(parsed.section("disassembly"), Some("block0:\n  udf"));
+        assert_eq!(parsed.section("trace"), Some("first\nsecond"));
+
+        // The code dump covers the whole function and marks the faulting line
+        let code = parsed.section("code").unwrap();
+        let marked: Vec<_> = code.lines().filter(|l| l.starts_with("=>")).collect();
+        assert_eq!(marked.len(), 1);
+        let bytes: usize = code.lines().map(|l| l.matches(" 90").count()).sum();
+        assert_eq!(bytes, CODE.len());
+    }
+
+    #[test]
+    fn test_report_outside_jit_code() {
+        let functions = [function()];
+        let text = report(Some(&functions), 0x1000);
+        let parsed = CrashReport::parse(&text).unwrap();
+        assert_eq!(parsed.field("function"), Some("<not JIT code>"));
+        assert!(parsed.section("mir").is_none());
+        assert!(parsed.section("registers").unwrap().contains("rbx"));
+
+        let text = report(None, 0x1000);
+        assert!(text.contains("function = <function table locked>"));
+    }
+
+    #[test]
+    fn test_pretty() {
+        let functions = [function()];
+        let text = report(Some(&functions), CODE.as_ptr() as usize);
+        let pretty = CrashReport::parse(&text).unwrap().pretty();
+        assert!(pretty.starts_with("SIGSEGV (11) at pc 0x"));
+        assert!(pretty.contains("in Main.divide (Main.hx:12) at offset 0x0"));
+        assert!(pretty.contains("Registers:\n    rax "));
+        assert!(pretty.contains("Trace:\n    first\n    second"));
+
+        assert!(CrashReport::parse("hello").is_err());
+    }
+
+    #[test]
+    fn test_latest_report() {
+        let dir = std::env::temp_dir().join(format!("rayzor-crash-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(latest_report(&dir).is_none());
+        std::fs::write(dir.join("notes.txt"), "").unwrap();
+        std::fs::write(dir.join("crash-1-2.txt"), REPORT_HEADER).unwrap();
+        assert_eq!(latest_report(&dir), Some(dir.join("crash-1-2.txt")));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,375 @@
+//! Deadlock detection for Mutex, Channel and Thread.join
+//!
+//! Detection is opt-in: `rayzor run --preset development` turns it on, as
+//! does `RAYZOR_DEADLOCK_DETECT=1` or [`rayzor_set_deadlock_detection`].
+//! While enabled, the concurrency runtime records which thread holds each
+//! mutex and what every blocked thread waits for. Two situations are
+//! reported as a deadlock:
+//!
+//! - a cycle of threads, each waiting for a mutex the next one holds. This
+//!   is detected as soon as the last thread of the cycle blocks.
+//! - every tracked thread blocked, with no lock, channel or thread making
+//!   progress for [`STALL_TIMEOUT`]. Channel and join waits are caught this
+//!   way, since whoever would wake them is not known in advance.
+//!
+//! Instead of hanging, the runtime then prints each involved thread with the
+//! mutexes it holds, what it waits for and its stack at the point it
+//! blocked, and aborts. Blocking calls outside this module's view (sleep,
+//! semaphores, I/O) count as running, so they can hide a deadlock but never
+//! cause a false report.
+
+use std::backtrace::Backtrace;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+/// How long all threads must be blocked without progress to be reported
+pub const STALL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often channel and join waits wake up to check for a stall
+pub const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What a blocked thread waits for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wait {
+    /// Acquiring a mutex (by handle address)
+    Mutex(usize),
+    /// Receiving from an empty channel
+    ChannelReceive(usize),
+    /// Sending to a full channel
+    ChannelSend(usize),
+    /// Joining another thread
+    Join(ThreadId),
+}
+
+struct Blocked {
+    wait: Wait,
+    stack: Backtrace,
+}
+
+struct ThreadState {
+    name: String,
+    /// Mutexes held, in acquisition order
+    holds: Vec<usize>,
+    blocked: Option<Blocked>,
+}
+
+/// Wait-for graph of the tracked threads
+pub struct Graph {
+    threads: HashMap<ThreadId, ThreadState>,
+    owners: HashMap<usize, ThreadId>,
+    /// Last time a thread acquired, released, woke up or exited
+    last_progress: Instant,
+}
+
+impl Default for Graph {
+    fn default() -> Self {
+        Graph {
+            threads: HashMap::new(),
+            owners: HashMap::new(),
+            last_progress: Instant::now(),
+        }
+    }
+}
+
+impl Graph {
+    fn thread(&mut self, id: ThreadId) -> &mut ThreadState {
+        self.threads.entry(id).or_insert_with(|| {
+            let current = thread::current();
+            let name = match current.name() {
+                Some(name) if current.id() == id => name.to_string(),
+                _ => format!("{:?}", id),
+            };
+            ThreadState {
+                name,
+                holds: Vec::new(),
+                blocked: None,
+            }
+        })
+    }
+
+    fn progress(&mut self) {
+        self.last_progress = Instant::now();
+    }
+
+    /// Record that `id` is about to block on `wait`
+    pub fn block(&mut self, id: ThreadId, wait: Wait, stack: Backtrace) {
+        self.thread(id).blocked = Some(Blocked { wait, stack });
+    }
+
+    /// Record that `id` is running again
+    pub fn unblock(&mut self, id: ThreadId) {
+        self.thread(id).blocked = None;
+        self.progress();
+    }
+
+    /// Record that `id` acquired `mutex`
+    pub fn acquired(&mut self, id: ThreadId, mutex: usize) {
+        let state = self.thread(id);
+        state.blocked = None;
+        state.holds.push(mutex);
+        self.owners.insert(mutex, id);
+        self.progress();
+    }
+
+    /// Record that `mutex` was released
+    pub fn released(&mut self, mutex: usize) {
+        if let Some(owner) = self.owners.remove(&mutex) {
+            if let Some(state) = self.threads.get_mut(&owner) {
+                state.holds.retain(|&m| m != mutex);
+            }
+        }
+        self.progress();
+    }
+
+    /// Record that `id` finished
+    pub fn exited(&mut self, id: ThreadId) {
+        if let Some(state) = self.threads.remove(&id) {
+            for mutex in state.holds {
+                self.owners.remove(&mutex);
+            }
+        }
+        self.progress();
+    }
+
+    /// The threads of a mutex wait cycle through `start`, if any
+    pub fn mutex_cycle(&self, start: ThreadId) -> Option<Vec<ThreadId>> {
+        let mut cycle = vec![start];
+        let mut current = start;
+        loop {
+            let Some(Wait::Mutex(mutex)) =
+                self.threads.get(&current)?.blocked.as_ref().map(|b| b.wait)
+            else {
+                return None;
+            };
+            let owner = *self.owners.get(&mutex)?;
+            if owner == start {
+                return Some(cycle);
+            }
+            if cycle.contains(&owner) {
+                // A cycle that doesn't involve `start`; its last thread reports it
+                return None;
+            }
+            cycle.push(owner);
+            current = owner;
+        }
+    }
+
+    /// All tracked threads, if every one of them is blocked and none has made
+    /// progress for `timeout`
+    pub fn stalled(&self, timeout: Duration) -> Option<Vec<ThreadId>> {
+        if self.threads.is_empty()
+            || self.last_progress.elapsed() < timeout
+            || self.threads.values().any(|t| t.blocked.is_none())
+        {
+            return None;
+        }
+        Some(self.threads.keys().copied().collect())
+    }
+
+    fn describe_wait(&self, wait: Wait) -> String {
+        match wait {
+            Wait::Mutex(mutex) => match self.owners.get(&mutex).and_then(|o| self.threads.get(o)) {
+                Some(owner) => format!("mutex {:#x}, held by '{}'", mutex, owner.name),
+                None => format!("mutex {:#x}", mutex),
+            },
+            Wait::ChannelReceive(channel) => format!("receive on channel {:#x}", channel),
+            Wait::ChannelSend(channel) => format!("send on full channel {:#x}", channel),
+            Wait::Join(target) => match self.threads.get(&target) {
+                Some(thread) => format!("join of '{}'", thread.name),
+                None => format!("join of {:?}", target),
+            },
+        }
+    }
+
+    /// Human-readable report of a deadlock between `threads`
+    pub fn report(&self, threads: &[ThreadId]) -> String {
+        let mut out = format!(
+            "fatal runtime error: deadlock detected between {} thread(s)\n",
+            threads.len()
+        );
+        for id in threads {
+            let Some(state) = self.threads.get(id) else {
+                continue;
+            };
+            let _ = writeln!(out, "\nthread '{}'", state.name);
+            if !state.holds.is_empty() {
+                let held: Vec<String> = state.holds.iter().map(|m| format!("{:#x}", m)).collect();
+                let _ = writeln!(out, "  holds mutex {}", held.join(", "));
+            }
+            if let Some(blocked) = &state.blocked {
+                let _ = writeln!(out, "  waits for {}", self.describe_wait(blocked.wait));
+                let stack = blocked.stack.to_string();
+                if !stack.trim().is_empty() {
+                    let _ = writeln!(out, "  blocked at:");
+                    for line in stack.lines() {
+                        let _ = writeln!(out, "    {}", line);
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+static GRAPH: OnceLock<Mutex<Graph>> = OnceLock::new();
+
+fn graph() -> parking_lot::MutexGuard<'static, Graph> {
+    GRAPH.get_or_init(|| Mutex::new(Graph::default())).lock()
+}
+
+fn enabled_flag() -> &'static AtomicBool {
+    static ENABLED: OnceLock<AtomicBool> = OnceLock::new();
+    ENABLED.get_or_init(|| {
+        let from_env = std::env::var("RAYZOR_DEADLOCK_DETECT").is_ok_and(|v| v != "0");
+        AtomicBool::new(from_env)
+    })
+}
+
+/// Whether deadlock detection is on
+pub fn is_enabled() -> bool {
+    enabled_flag().load(Ordering::Relaxed)
+}
+
+/// Turn deadlock detection on or off. The calling thread is tracked from
+/// now on, so that a deadlock involving it is reported.
+pub fn set_enabled(enabled: bool) {
+    enabled_flag().store(enabled, Ordering::SeqCst);
+    if enabled {
+        let id = thread::current().id();
+        graph().thread(id);
+    }
+}
+
+/// Turn deadlock detection on (non-zero) or off (zero)
+#[no_mangle]
+pub extern "C" fn rayzor_set_deadlock_detection(enabled: i32) {
+    set_enabled(enabled != 0);
+}
+
+fn fail(graph: &Graph, threads: &[ThreadId]) -> ! {
+    eprint!("{}", graph.report(threads));
+    std::process::abort()
+}
+
+/// Record that the current thread is about to block on `wait`. Reports and
+/// aborts if that closes a mutex cycle.
+pub fn block(wait: Wait) {
+    let id = thread::current().id();
+    let mut graph = graph();
+    graph.block(id, wait, Backtrace::force_capture());
+    if let Some(cycle) = graph.mutex_cycle(id) {
+        fail(&graph, &cycle);
+    }
+}
+
+/// Record that the current thread stopped waiting without acquiring a mutex
+pub fn unblock() {
+    graph().unblock(thread::current().id());
+}
+
+/// Record that the current thread acquired `mutex`
+pub fn acquired(mutex: usize) {
+    graph().acquired(thread::current().id(), mutex);
+}
+
+/// Record that `mutex` was released
+pub fn released(mutex: usize) {
+    graph().released(mutex);
+}
+
+/// Called by a waiting thread each [`POLL_INTERVAL`]; reports and aborts if
+/// all threads have been stalled for [`STALL_TIMEOUT`]
+pub fn check_stalled() {
+    let graph = graph();
+    if let Some(threads) = graph.stalled(STALL_TIMEOUT) {
+        fail(&graph, &threads);
+    }
+}
+
+/// Tracks a spawned thread for its lifetime
+pub struct ThreadGuard {
+    id: Option<ThreadId>,
+}
+
+impl ThreadGuard {
+    /// Start tracking the current thread (no-op while detection is off)
+    pub fn enter() -> Self {
+        if !is_enabled() {
+            return ThreadGuard { id: None };
+        }
+        let id = thread::current().id();
+        let mut graph = graph();
+        graph.thread(id);
+        graph.progress();
+        ThreadGuard { id: Some(id) }
+    }
+}
+
+impl Drop for ThreadGuard {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            graph().exited(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn_ids(n: usize) -> Vec<ThreadId> {
+        (0..n)
+            .map(|_| thread::spawn(|| thread::current().id()).join().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_mutex_cycle() {
+        let ids = spawn_ids(3);
+        let (a, b, c) = (ids[0], ids[1], ids[2]);
+        let mut graph = Graph::default();
+
+        graph.acquired(a, 1);
+        graph.acquired(b, 2);
+        graph.block(a, Wait::Mutex(2), Backtrace::disabled());
+        assert_eq!(graph.mutex_cycle(a), None);
+
+        // c waits on a lock nobody holds; not part of any cycle
+        graph.block(c, Wait::Mutex(3), Backtrace::disabled());
+        assert_eq!(graph.mutex_cycle(c), None);
+
+        graph.block(b, Wait::Mutex(1), Backtrace::disabled());
+        assert_eq!(graph.mutex_cycle(b), Some(vec![b, a]));
+        let report = graph.report(&[b, a]);
+        assert!(report.contains("deadlock detected between 2 thread(s)"));
+        assert!(report.contains("waits for mutex 0x1"));
+
+        // Releasing breaks the cycle
+        graph.released(1);
+        assert_eq!(graph.mutex_cycle(b), None);
+    }
+
+    #[test]
+    fn test_stall() {
+        let ids = spawn_ids(2);
+        let (main, worker) = (ids[0], ids[1]);
+        let mut graph = Graph::default();
+
+        graph.block(main, Wait::Join(worker), Backtrace::disabled());
+        graph.block(worker, Wait::ChannelReceive(0x10), Backtrace::disabled());
+        assert_eq!(graph.stalled(Duration::from_secs(60)), None);
+        assert_eq!(graph.stalled(Duration::ZERO).map(|t| t.len()), Some(2));
+
+        // Any running thread means no stall
+        graph.unblock(worker);
+        assert_eq!(graph.stalled(Duration::ZERO), None);
+        graph.exited(worker);
+        assert_eq!(graph.stalled(Duration::ZERO), Some(vec![main]));
+    }
+}
@@ -0,0 +1,505 @@
+//! Debug allocator for `rayzor run --sanitize address`
+//!
+//! Generated code allocates with `malloc`, `realloc` and `free`. Under
+//! `--sanitize address` the JIT links those names to [`rayzor_debug_malloc`],
+//! [`rayzor_debug_realloc`] and [`rayzor_debug_free`] (see [`symbols`]), and
+//! `rayzor_malloc` and the tracked allocator route here too. Every block
+//! then gets:
+//!
+//! - [`GUARD_SIZE`] guard bytes on each side, checked when the block is
+//!   freed or reallocated, so writes past either end are caught
+//! - poisoning on free: the freed bytes are overwritten with `0xFD` and the
+//!   block is quarantined until [`QUARANTINE_BYTES`] of newer frees push it
+//!   out. A write through a stale pointer is caught when the block leaves
+//!   quarantine; reads see the poison pattern instead of live data.
+//! - double free detection while the block is quarantined
+//!
+//! [`check_heap`] checks all live and quarantined blocks at once; `rayzor
+//! run` calls it when the program ends. A detected error prints the kind of
+//! error, the block, and the Haxe stacks that allocated and freed it, then
+//! aborts. Stacks name JIT-compiled functions through the crash reporter's
+//! function table, which `--sanitize address` turns on.
+//!
+//! Pointers this allocator did not hand out (allocated before it was
+//! enabled, or by C code) are passed on to libc unchecked.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use parking_lot::Mutex;
+
+/// Guard bytes on each side of a block
+pub const GUARD_SIZE: usize = 32;
+
+/// Freed bytes kept in quarantine before being returned to libc
+pub const QUARANTINE_BYTES: usize = 64 << 20;
+
+/// Pattern of the guard bytes
+const GUARD_BYTE: u8 = 0xFA;
+
+/// Pattern written over freed blocks
+const FREED_BYTE: u8 = 0xFD;
+
+/// Blocks are 16-byte aligned, like `malloc`
+const ALIGNMENT: usize = 16;
+
+/// Return addresses kept per allocation or free
+const STACK_DEPTH: usize = 32;
+
+/// Return addresses of a call stack, innermost first
+pub type Stack = Vec<usize>;
+
+/// Kind of memory error found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// A write past the end of a block
+    Overflow,
+    /// A write before the start of a block
+    Underflow,
+    /// A write to a block after it was freed
+    UseAfterFree,
+    /// A block freed twice
+    DoubleFree,
+}
+
+impl ErrorKind {
+    fn name(self) -> &'static str {
+        match self {
+            ErrorKind::Overflow => "heap-buffer-overflow",
+            ErrorKind::Underflow => "heap-buffer-underflow",
+            ErrorKind::UseAfterFree => "heap-use-after-free",
+            ErrorKind::DoubleFree => "double-free",
+        }
+    }
+}
+
+struct Block {
+    /// Start of the underlying libc allocation
+    base: *mut u8,
+    size: usize,
+    alloc_stack: Stack,
+    free_stack: Stack,
+}
+
+unsafe impl Send for Block {}
+
+impl Block {
+    fn total_size(size: usize) -> usize {
+        GUARD_SIZE + size.next_multiple_of(ALIGNMENT) + GUARD_SIZE
+    }
+
+    fn user(&self) -> *mut u8 {
+        unsafe { self.base.add(GUARD_SIZE) }
+    }
+
+    /// Offset (from the block start) of the first damaged guard byte
+    fn damaged_guard(&self) -> Option<(ErrorKind, isize)> {
+        let total = Block::total_size(self.size);
+        let bytes = unsafe { std::slice::from_raw_parts(self.base, total) };
+        if let Some(i) = bytes[..GUARD_SIZE].iter().position(|&b| b != GUARD_BYTE) {
+            return Some((ErrorKind::Underflow, i as isize - GUARD_SIZE as isize));
+        }
+        let after = GUARD_SIZE + self.size;
+        bytes[after..]
+            .iter()
+            .position(|&b| b != GUARD_BYTE)
+            .map(|i| (ErrorKind::Overflow, (self.size + i) as isize))
+    }
+
+    /// Offset of the first byte written after the block was freed
+    fn damaged_poison(&self) -> Option<isize> {
+        let bytes = unsafe { std::slice::from_raw_parts(self.user(), self.size) };
+        bytes
+            .iter()
+            .position(|&b| b != FREED_BYTE)
+            .map(|i| i as isize)
+    }
+}
+
+/// A memory error with the stacks needed to report it
+#[derive(Debug)]
+pub struct Error {
+    pub kind: ErrorKind,
+    /// Address of the block
+    pub addr: usize,
+    /// Size of the block in bytes
+    pub size: usize,
+    /// Offset of the bad access from the block start, if known
+    pub offset: Option<isize>,
+    pub alloc_stack: Stack,
+    pub free_stack: Stack,
+    /// Where the error was detected (empty when found by [`check_heap`])
+    pub detected_stack: Stack,
+}
+
+impl Error {
+    /// Human-readable report
+    pub fn report(&self) -> String {
+        let mut out = format!(
+            "==rayzor== ERROR: {} on {:#x} ({}-byte block)\n",
+            self.kind.name(),
+            self.addr,
+            self.size
+        );
+        if let Some(offset) = self.offset {
+            let _ = writeln!(
+                out,
+                "first bad byte at offset {} of the block ({:#x})",
+                offset,
+                self.addr.wrapping_add_signed(offset)
+            );
+        }
+        if !self.detected_stack.is_empty() {
+            write_stack(&mut out, "detected at", &self.detected_stack);
+        }
+        if !self.free_stack.is_empty() {
+            write_stack(&mut out, "block freed at", &self.free_stack);
+        }
+        write_stack(&mut out, "block allocated at", &self.alloc_stack);
+        out
+    }
+}
+
+fn write_stack(out: &mut String, title: &str, stack: &Stack) {
+    let _ = writeln!(out, "\n{}:", title);
+    let mut frames = 0;
+    for &pc in stack {
+        // Return addresses point after the call
+        if let Some(function) = crate::crash::describe_pc(pc.saturating_sub(1)) {
+            let _ = writeln!(out, "    #{} {}", frames, function);
+            frames += 1;
+        }
+    }
+    if frames == 0 {
+        let _ = writeln!(out, "    <no JIT-compiled frames>");
+    }
+}
+
+/// Live and quarantined blocks of the debug allocator
+pub struct Heap {
+    live: HashMap<usize, Block>,
+    freed: HashMap<usize, Block>,
+    /// Quarantined blocks, oldest first
+    quarantine: VecDeque<usize>,
+    quarantined_bytes: usize,
+    quarantine_limit: usize,
+}
+
+impl Heap {
+    /// A heap keeping up to `quarantine_limit` freed bytes in quarantine
+    pub fn new(quarantine_limit: usize) -> Self {
+        Heap {
+            live: HashMap::new(),
+            freed: HashMap::new(),
+            quarantine: VecDeque::new(),
+            quarantined_bytes: 0,
+            quarantine_limit,
+        }
+    }
+
+    /// Size of the live block at `ptr`, if it belongs to this heap
+    pub fn live_size(&self, ptr: *mut u8) -> Option<usize> {
+        self.live.get(&(ptr as usize)).map(|b| b.size)
+    }
+
+    /// Whether `ptr` was handed out by this heap (live or quarantined)
+    pub fn owns(&self, ptr: *mut u8) -> bool {
+        let addr = ptr as usize;
+        self.live.contains_key(&addr) || self.freed.contains_key(&addr)
+    }
+
+    /// Allocate `size` bytes surrounded by guard zones
+    pub fn alloc(&mut self, size: usize, stack: Stack) -> *mut u8 {
+        let total = Block::total_size(size);
+        let base = unsafe { libc::malloc(total) } as *mut u8;
+        if base.is_null() {
+            return ptr::null_mut();
+        }
+        unsafe { ptr::write_bytes(base, GUARD_BYTE, total) };
+        let block = Block {
+            base,
+            size,
+            alloc_stack: stack,
+            free_stack: Vec::new(),
+        };
+        let user = block.user();
+        self.live.insert(user as usize, block);
+        user
+    }
+
+    /// Free the block at `ptr`, which must be owned by this heap
+    pub fn free(&mut self, ptr: *mut u8, stack: Stack) -> Result<(), Error> {
+        let addr = ptr as usize;
+        let Some(mut block) = self.live.remove(&addr) else {
+            let block = self.freed.get(&addr);
+            return Err(Error {
+                kind: ErrorKind::DoubleFree,
+                addr,
+                size: block.map_or(0, |b| b.size),
+                offset: None,
+                alloc_stack: block.map(|b| b.alloc_stack.clone()).unwrap_or_default(),
+                free_stack: block.map(|b| b.free_stack.clone()).unwrap_or_default(),
+                detected_stack: stack,
+            });
+        };
+        block.free_stack = stack;
+        if let Some((kind, offset)) = block.damaged_guard() {
+            let error = Error {
+                kind,
+                addr,
+                size: block.size,
+                offset: Some(offset),
+                alloc_stack: block.alloc_stack.clone(),
+                free_stack: Vec::new(),
+                detected_stack: block.free_stack.clone(),
+            };
+            self.live.insert(addr, block);
+            return Err(error);
+        }
+
+        unsafe { ptr::write_bytes(block.user(), FREED_BYTE, block.size) };
+        self.quarantined_bytes += block.size;
+        self.quarantine.push_back(addr);
+        self.freed.insert(addr, block);
+        self.evict()
+    }
+
+    /// Release quarantined blocks beyond the limit, checking their poison
+    fn evict(&mut self) -> Result<(), Error> {
+        while self.quarantined_bytes > self.quarantine_limit {
+            let Some(addr) = self.quarantine.pop_front() else {
+                break;
+            };
+            let Some(block) = self.freed.remove(&addr) else {
+                continue;
+            };
+            self.quarantined_bytes -= block.size;
+            if let Some(error) = Self::check_freed(addr, &block) {
+                return Err(error);
+            }
+            unsafe { libc::free(block.base as *mut libc::c_void) };
+        }
+        Ok(())
+    }
+
+    fn check_freed(addr: usize, block: &Block) -> Option<Error> {
+        let (kind, offset) = match block.damaged_poison() {
+            Some(offset) => (ErrorKind::UseAfterFree, offset),
+            None => block.damaged_guard()?,
+        };
+        Some(Error {
+            kind,
+            addr,
+            size: block.size,
+            offset: Some(offset),
+            alloc_stack: block.alloc_stack.clone(),
+            free_stack: block.free_stack.clone(),
+            detected_stack: Vec::new(),
+        })
+    }
+
+    /// Check the guards of every live block and the poison of every
+    /// quarantined one
+    pub fn check(&self) -> Result<(), Error> {
+        for (&addr, block) in &self.live {
+            if let Some((kind, offset)) = block.damaged_guard() {
+                return Err(Error {
+                    kind,
+                    addr,
+                    size: block.size,
+                    offset: Some(offset),
+                    alloc_stack: block.alloc_stack.clone(),
+                    free_stack: Vec::new(),
+                    detected_stack: Vec::new(),
+                });
+            }
+        }
+        for (&addr, block) in &self.freed {
+            if let Some(error) = Self::check_freed(addr, block) {
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static HEAP: Mutex<Option<Heap>> = parking_lot::const_mutex(None);
+
+/// Whether the debug allocator is on
+#[inline]
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Turn the debug allocator on. Must be called before the program
+/// allocates: blocks from the regular allocators can't be tracked.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// Symbols that route generated code's `malloc`/`realloc`/`free` through
+/// the debug allocator; they override the runtime's defaults when linking
+pub fn symbols() -> Vec<(&'static str, *const u8)> {
+    vec![
+        ("malloc", rayzor_debug_malloc as *const u8),
+        ("realloc", rayzor_debug_realloc as *const u8),
+        ("free", rayzor_debug_free as *const u8),
+    ]
+}
+
+fn capture_stack() -> Stack {
+    #[cfg(any(all(target_os = "linux", target_env = "gnu"), target_os = "macos"))]
+    unsafe {
+        let mut frames = [ptr::null_mut(); STACK_DEPTH];
+        let count = libc::backtrace(frames.as_mut_ptr(), STACK_DEPTH as libc::c_int);
+        frames[..count.max(0) as usize]
+            .iter()
+            .map(|&pc| pc as usize)
+            .collect()
+    }
+    #[cfg(not(any(all(target_os = "linux", target_env = "gnu"), target_os = "macos")))]
+    Vec::new()
+}
+
+fn fail(error: Error) -> ! {
+    eprint!("{}", error.report());
+    std::process::abort()
+}
+
+fn with_heap<R>(f: impl FnOnce(&mut Heap) -> R) -> R {
+    let mut heap = HEAP.lock();
+    f(heap.get_or_insert_with(|| Heap::new(QUARANTINE_BYTES)))
+}
+
+/// `malloc` replacement: a guarded block of `size` bytes
+#[no_mangle]
+pub extern "C" fn rayzor_debug_malloc(size: usize) -> *mut u8 {
+    let stack = capture_stack();
+    with_heap(|heap| heap.alloc(size, stack))
+}
+
+/// `free` replacement: check the block's guards and quarantine it
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_debug_free(ptr: *mut u8) {
+    if ptr.is_null() {
+        return;
+    }
+    let stack = capture_stack();
+    let owned = with_heap(|heap| {
+        if !heap.owns(ptr) {
+            return Ok(false);
+        }
+        heap.free(ptr, stack).map(|()| true)
+    });
+    match owned {
+        Ok(true) => {}
+        Ok(false) => libc::free(ptr as *mut libc::c_void),
+        Err(mut error) => {
+            if error.detected_stack.is_empty() {
+                error.detected_stack = capture_stack();
+            }
+            fail(error)
+        }
+    }
+}
+
+/// `realloc` replacement: move the data into a new guarded block
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_debug_realloc(ptr: *mut u8, size: usize) -> *mut u8 {
+    if ptr.is_null() {
+        return rayzor_debug_malloc(size);
+    }
+    let owned = with_heap(|heap| heap.owns(ptr));
+    if !owned {
+        return libc::realloc(ptr as *mut libc::c_void, size) as *mut u8;
+    }
+    let old_size = with_heap(|heap| heap.live_size(ptr));
+    let new_ptr = rayzor_debug_malloc(size);
+    if new_ptr.is_null() {
+        return ptr::null_mut();
+    }
+    if let Some(old_size) = old_size {
+        ptr::copy_nonoverlapping(ptr, new_ptr, old_size.min(size));
+        crate::object_id::relocate(ptr as usize, new_ptr as usize);
+    }
+    // Reports a double free if `ptr` was already freed
+    rayzor_debug_free(ptr);
+    new_ptr
+}
+
+/// Free memory that generated code allocated with `malloc`
+///
+/// # Safety
+/// `ptr` must come from `malloc`, or from the debug allocator or the GC
+/// heap while they are on
+pub unsafe fn free(ptr: *mut u8) {
+    if is_enabled() {
+        rayzor_debug_free(ptr);
+    } else if crate::gc::is_enabled() {
+        crate::gc::rayzor_gc_free(ptr);
+    } else {
+        libc::free(ptr as *mut libc::c_void);
+    }
+}
+
+/// Check every live and quarantined block; reports and aborts on damage
+pub fn check_heap() {
+    if !is_enabled() {
+        return;
+    }
+    if let Err(error) = with_heap(|heap| heap.check()) {
+        fail(error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overflow_and_underflow() {
+        let mut heap = Heap::new(1024);
+        let p = heap.alloc(10, Vec::new());
+        assert_eq!(p as usize % ALIGNMENT, 0);
+        unsafe { ptr::write_bytes(p, 1, 10) };
+        assert!(heap.check().is_ok());
+
+        unsafe { *p.add(12) = 0 };
+        let error = heap.free(p, Vec::new()).err().unwrap();
+        assert_eq!(error.kind, ErrorKind::Overflow);
+        assert_eq!(error.offset, Some(12));
+        assert!(error.report().contains("heap-buffer-overflow"));
+
+        let mut heap = Heap::new(1024);
+        let q = heap.alloc(8, Vec::new());
+        unsafe { *q.sub(1) = 0 };
+        let error = heap.check().err().unwrap();
+        assert_eq!(error.kind, ErrorKind::Underflow);
+        assert_eq!(error.offset, Some(-1));
+    }
+
+    #[test]
+    fn test_use_after_free_and_double_free() {
+        let mut heap = Heap::new(64);
+        let p = heap.alloc(16, vec![1]);
+        heap.free(p, vec![2]).unwrap();
+        assert_eq!(unsafe { *p }, FREED_BYTE);
+
+        let error = heap.free(p, vec![3]).err().unwrap();
+        assert_eq!(error.kind, ErrorKind::DoubleFree);
+        assert_eq!(error.alloc_stack, vec![1]);
+        assert_eq!(error.free_stack, vec![2]);
+
+        // A stale write is found when the block leaves quarantine
+        unsafe { *p.add(4) = 7 };
+        let big = heap.alloc(64, Vec::new());
+        let error = heap.free(big, Vec::new()).err().unwrap();
+        assert_eq!(error.kind, ErrorKind::UseAfterFree);
+        assert_eq!(error.addr, p as usize);
+        assert_eq!(error.offset, Some(4));
+    }
+}
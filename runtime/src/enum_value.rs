@@ -0,0 +1,238 @@
+//! Structural equality and hashing of enum values
+//!
+//! An enum value is a raw 8-byte word: the constructor index for enums without
+//! parameterized constructors, otherwise a pointer to
+//! `[tag:i32][pad:i32][field0:i64][field1:i64]...` (null for a null value).
+//!
+//! Two values of the same enum are equal when they have the same constructor
+//! and pairwise equal parameters, walking the variant metadata registered in
+//! the type registry:
+//!
+//! - Int and Bool parameters compare as integers, Float by value, String by
+//!   content
+//! - enum-typed parameters compare recursively by the same rules
+//! - other parameters (class instances, type parameters) compare by identity
+//!
+//! Hashes agree with this equality, so enum values can be used as map keys
+//! (`haxe_enummap_new`). Values of enums that were never registered fall back
+//! to identity.
+
+use crate::haxe_string::HaxeString;
+use crate::type_system::{EnumInfo, EnumVariantInfo, ParamType, TypeId, TYPE_REGISTRY};
+use std::hash::{Hash, Hasher};
+
+fn enum_info(type_id: u32) -> Option<&'static EnumInfo> {
+    let registry = TYPE_REGISTRY.read().unwrap();
+    registry.as_ref()?.get(&TypeId(type_id))?.enum_info
+}
+
+/// Whether values of this enum are heap-allocated (some constructor has
+/// parameters), mirroring the compiler's layout choice.
+fn is_boxed(info: &EnumInfo) -> bool {
+    info.variants.iter().any(|v| v.param_count > 0)
+}
+
+/// Constructor index and parameter words of a boxed enum value.
+///
+/// # Safety
+/// `value` must be null or point to a boxed value of an enum described by `info`.
+unsafe fn decode(
+    info: &'static EnumInfo,
+    value: u64,
+) -> Option<(&'static EnumVariantInfo, &'static [u64])> {
+    if value == 0 {
+        return None;
+    }
+    let ptr = value as *const u8;
+    let tag = *(ptr as *const i32);
+    let variant = info.variants.get(usize::try_from(tag).ok()?)?;
+    let params = std::slice::from_raw_parts(ptr.add(8) as *const u64, variant.param_count);
+    Some((variant, params))
+}
+
+/// Whether two values of the enum `type_id` are structurally equal.
+///
+/// # Safety
+/// `a` and `b` must be values of the enum registered as `type_id`.
+pub unsafe fn equals(type_id: u32, a: u64, b: u64) -> bool {
+    if a == b {
+        return true;
+    }
+    let Some(info) = enum_info(type_id) else {
+        return false;
+    };
+    if !is_boxed(info) {
+        return false;
+    }
+    match (decode(info, a), decode(info, b)) {
+        (Some((va, pa)), Some((vb, pb))) => {
+            std::ptr::eq(va, vb) && (0..pa.len()).all(|i| param_equals(va, i, pa[i], pb[i]))
+        }
+        _ => false,
+    }
+}
+
+unsafe fn param_equals(variant: &EnumVariantInfo, i: usize, a: u64, b: u64) -> bool {
+    if let Some(&nested) = variant.param_enum_types.get(i).filter(|&&t| t != 0) {
+        return equals(nested, a, b);
+    }
+    match variant.param_types.get(i) {
+        Some(ParamType::Float) => f64::from_bits(a) == f64::from_bits(b),
+        Some(ParamType::Bool) => (a != 0) == (b != 0),
+        Some(ParamType::String) => a == b || string_bytes(a) == string_bytes(b),
+        _ => a == b,
+    }
+}
+
+/// Hash of a value of the enum `type_id`, consistent with [`equals`].
+///
+/// # Safety
+/// `value` must be a value of the enum registered as `type_id`.
+pub unsafe fn hash(type_id: u32, value: u64) -> u64 {
+    let mut state = std::collections::hash_map::DefaultHasher::new();
+    hash_into(type_id, value, &mut state);
+    state.finish()
+}
+
+unsafe fn hash_into(type_id: u32, value: u64, state: &mut impl Hasher) {
+    let info = match enum_info(type_id) {
+        Some(info) if is_boxed(info) => info,
+        _ => return value.hash(state),
+    };
+    let Some((variant, params)) = decode(info, value) else {
+        return value.hash(state);
+    };
+    variant.name.hash(state);
+    for (i, &param) in params.iter().enumerate() {
+        if let Some(&nested) = variant.param_enum_types.get(i).filter(|&&t| t != 0) {
+            hash_into(nested, param, state);
+            continue;
+        }
+        match variant.param_types.get(i) {
+            // 0.0 == -0.0, so both hash as zero
+            Some(ParamType::Float) if f64::from_bits(param) == 0.0 => 0u64.hash(state),
+            Some(ParamType::Bool) => (param != 0).hash(state),
+            Some(ParamType::String) => string_bytes(param).hash(state),
+            _ => param.hash(state),
+        }
+    }
+}
+
+/// Bytes of a HaxeString pointer, or None for null.
+unsafe fn string_bytes<'a>(ptr: u64) -> Option<&'a [u8]> {
+    let s = (ptr as *const HaxeString).as_ref()?;
+    if s.ptr.is_null() {
+        return Some(&[]);
+    }
+    Some(std::slice::from_raw_parts(s.ptr, s.len))
+}
+
+/// `Type.enumEq(a, b)` and `==` on enum values: structural equality.
+/// `type_id` is the enum's runtime type id, injected by the compiler.
+#[no_mangle]
+pub extern "C" fn haxe_type_enum_eq(a: i64, b: i64, type_id: i32) -> bool {
+    unsafe { equals(type_id as u32, a as u64, b as u64) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_system::register_enum_from_mir;
+
+    // Type ids well above anything the compiler assigns in tests
+    const COLOR: u32 = 910_001;
+    const SHAPE: u32 = 910_002;
+
+    fn register() {
+        register_enum_from_mir(
+            COLOR,
+            "Color",
+            &[
+                ("Red".into(), 0, vec![], vec![]),
+                ("Rgb".into(), 3, vec![ParamType::Int; 3], vec![]),
+            ],
+        );
+        register_enum_from_mir(
+            SHAPE,
+            "Shape",
+            &[
+                ("Empty".into(), 0, vec![], vec![]),
+                (
+                    "Circle".into(),
+                    3,
+                    vec![ParamType::Float, ParamType::String, ParamType::Int],
+                    vec![0, 0, COLOR],
+                ),
+            ],
+        );
+    }
+
+    /// A boxed value `[tag][fields...]`; the Vec must outlive its address.
+    fn boxed(tag: i32, fields: &[u64]) -> Vec<u64> {
+        let mut words = vec![tag as u32 as u64];
+        words.extend_from_slice(fields);
+        words
+    }
+
+    fn addr(words: &[u64]) -> u64 {
+        words.as_ptr() as u64
+    }
+
+    fn haxe_string(s: &'static str) -> HaxeString {
+        HaxeString {
+            ptr: s.as_ptr() as *mut u8,
+            len: s.len(),
+            cap: 0,
+        }
+    }
+
+    #[test]
+    fn test_equality_by_structure() {
+        register();
+        let a = boxed(1, &[1, 2, 3]);
+        let b = boxed(1, &[1, 2, 3]);
+        let c = boxed(1, &[1, 2, 4]);
+        let red = boxed(0, &[]);
+        let red2 = boxed(0, &[]);
+        let eq = |x: u64, y: u64| haxe_type_enum_eq(x as i64, y as i64, COLOR as i32);
+        assert!(eq(addr(&a), addr(&b)));
+        assert!(!eq(addr(&a), addr(&c)));
+        assert!(eq(addr(&red), addr(&red2)));
+        assert!(!eq(addr(&red), addr(&a)));
+        assert!(!eq(addr(&red), 0));
+        unsafe {
+            assert_eq!(hash(COLOR, addr(&a)), hash(COLOR, addr(&b)));
+            assert_eq!(hash(COLOR, addr(&red)), hash(COLOR, addr(&red2)));
+        }
+    }
+
+    #[test]
+    fn test_nested_enum_and_string_params() {
+        register();
+        let (s1, s2, s3) = (haxe_string("big"), haxe_string("big"), haxe_string("small"));
+        let (rgb1, rgb2) = (boxed(1, &[9, 9, 9]), boxed(1, &[9, 9, 9]));
+        let circle = |r: f64, s: &HaxeString, c: &Vec<u64>| {
+            boxed(1, &[r.to_bits(), s as *const _ as u64, addr(c)])
+        };
+        let a = circle(1.5, &s1, &rgb1);
+        let b = circle(1.5, &s2, &rgb2);
+        let c = circle(1.5, &s3, &rgb2);
+        let d = circle(-0.0, &s1, &rgb1);
+        let e = circle(0.0, &s2, &rgb2);
+        unsafe {
+            assert!(equals(SHAPE, addr(&a), addr(&b)));
+            assert_eq!(hash(SHAPE, addr(&a)), hash(SHAPE, addr(&b)));
+            assert!(!equals(SHAPE, addr(&a), addr(&c)));
+            assert!(equals(SHAPE, addr(&d), addr(&e)));
+            assert_eq!(hash(SHAPE, addr(&d)), hash(SHAPE, addr(&e)));
+        }
+    }
+
+    #[test]
+    fn test_unregistered_enum_is_identity() {
+        unsafe {
+            assert!(equals(910_999, 3, 3));
+            assert!(!equals(910_999, 3, 4));
+        }
+    }
+}
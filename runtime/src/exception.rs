@@ -11,6 +11,10 @@ const JMP_BUF_SIZE: usize = 256;
 /// Dynamic type_id used when no specific type is known
 const TYPE_DYNAMIC: u32 = 5;
 
+/// Offset of class and enum type_ids thrown by compiled code from their
+/// type registry ids
+const TYPE_ID_REGISTRY_OFFSET: u32 = 1000;
+
 extern "C" {
     fn _setjmp(buf: *mut u8) -> i32;
     fn _longjmp(buf: *mut u8, val: i32) -> !;
@@ -88,6 +92,56 @@ pub extern "C" fn rayzor_throw_typed(exception_value: i64, type_id: u32) {
     });
 }
 
+/// Throw a class instance, typed by the class in its object header rather
+/// than the static type at the throw site. Used when that static type is an
+/// interface.
+#[no_mangle]
+pub extern "C" fn rayzor_throw_object(object: *const u8) {
+    let type_id = if object.is_null() {
+        TYPE_DYNAMIC
+    } else {
+        (unsafe { *(object as *const i64) }) as u32 + TYPE_ID_REGISTRY_OFFSET
+    };
+    rayzor_throw_typed(object as i64, type_id);
+}
+
+/// Rethrow the current exception to the next enclosing handler, keeping
+/// its type_id. Used when no catch clause matches, after `finally` has run
+/// (the landing pad has already popped its own handler).
+#[no_mangle]
+pub extern "C" fn rayzor_rethrow() {
+    let (exception_value, type_id) = STATE.with(|state| {
+        let state = state.borrow();
+        (state.current_exception, state.current_exception_type_id)
+    });
+    rayzor_throw_typed(exception_value, type_id);
+}
+
+/// Whether the current exception is an instance of the class
+/// `class_type_id` (its type registry id) or of one of its subclasses.
+/// Used by catch clauses for class types.
+///
+/// The thrown type_id is the static type at the throw site, so a class
+/// exception is checked through its object header: a subclass declared in
+/// another module matches as well.
+#[no_mangle]
+pub extern "C" fn rayzor_exception_is_instance(class_type_id: i64) -> bool {
+    let (exception_value, type_id) = STATE.with(|state| {
+        let state = state.borrow();
+        (state.current_exception, state.current_exception_type_id)
+    });
+    let Some(thrown) = type_id.checked_sub(TYPE_ID_REGISTRY_OFFSET) else {
+        return false;
+    };
+    let thrown_is_class = crate::type_system::get_type_info(crate::type_system::TypeId(thrown))
+        .is_some_and(|info| info.class_info.is_some());
+    if !thrown_is_class {
+        // An enum, or a class without RTTI: only the exact type matches
+        return thrown as i64 == class_type_id;
+    }
+    crate::type_system::haxe_object_is_instance(exception_value as *const u8, class_type_id) != 0
+}
+
 /// Get the current exception value (called after landing in catch block).
 #[no_mangle]
 pub extern "C" fn rayzor_get_exception() -> i64 {
@@ -100,3 +154,34 @@ pub extern "C" fn rayzor_get_exception() -> i64 {
 pub extern "C" fn rayzor_get_exception_type_id() -> u32 {
     STATE.with(|state| state.borrow().current_exception_type_id)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_system::register_class_from_mir;
+
+    #[test]
+    fn test_exception_is_instance_reads_object_header() {
+        const BASE: u32 = 91_001;
+        const SUB: u32 = 91_002;
+        const OTHER: u32 = 91_003;
+        register_class_from_mir(BASE, "Base", None, &[], &[], &[]);
+        register_class_from_mir(SUB, "Sub", Some(BASE), &[], &[], &[]);
+        register_class_from_mir(OTHER, "Other", None, &[], &[], &[]);
+
+        // A Sub thrown through a variable of static type Base
+        let object: [i64; 2] = [SUB as i64, 0];
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            state.current_exception = object.as_ptr() as i64;
+            state.current_exception_type_id = BASE + TYPE_ID_REGISTRY_OFFSET;
+        });
+        assert!(rayzor_exception_is_instance(BASE as i64));
+        assert!(rayzor_exception_is_instance(SUB as i64));
+        assert!(!rayzor_exception_is_instance(OTHER as i64));
+
+        // Primitive exceptions never match a class
+        STATE.with(|state| state.borrow_mut().current_exception_type_id = TYPE_DYNAMIC);
+        assert!(!rayzor_exception_is_instance(BASE as i64));
+    }
+}
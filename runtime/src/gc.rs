@@ -0,0 +1,833 @@
+//! Tracing garbage collector
+//!
+//! A stop-the-world mark-and-sweep collector for objects allocated with
+//! `rayzor_gc_alloc`. Object contents are scanned conservatively: every
+//! aligned word holding an address inside a GC object keeps that object
+//! alive, so no per-type layout maps are needed.
+//!
+//! ```c
+//! void *node = rayzor_gc_alloc(32);
+//! rayzor_gc_add_root(&node);            // alive while the slot is rooted
+//! ((void **)node)[0] = child;
+//! rayzor_gc_write_barrier(node, child); // after each pointer store
+//! rayzor_gc_collect();
+//! rayzor_gc_remove_root(&node);
+//! ```
+//!
+//! Under `rayzor run --gc` the JIT links generated code's `malloc`,
+//! `realloc` and `free` to [`rayzor_gc_malloc`], [`rayzor_gc_realloc`] and
+//! [`rayzor_gc_free`] (see [`symbols`]), and `rayzor_malloc` and the tracked
+//! allocator route here too, so class instances, closures, enum boxes and
+//! the rest of what compiled code allocates live in this heap. Explicit
+//! frees from the compiler's drop analysis still release objects early; the
+//! collector reclaims whatever the analysis leaks.
+//!
+//! Marking starts from:
+//!
+//! - slots registered with `rayzor_gc_add_root`
+//! - the calling thread's native stack, from the collector's frame up, and
+//!   its callee-saved registers
+//! - the stacks of the coroutines this thread is nested in, and of every
+//!   suspended coroutine
+//! - the global variable store and the exception in flight
+//! - the buffers of the runtime containers that hold GC pointers (arrays,
+//!   maps, lists, vectors, anonymous objects), allocated inside [`tracked`]
+//!   and recorded by [`TrackingAllocator`]
+//!
+//! The library doesn't install the allocator: a binary that runs programs
+//! with the collector declares it, as `rayzor` does:
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static ALLOCATOR: rayzor_runtime::gc::TrackingAllocator =
+//!     rayzor_runtime::gc::TrackingAllocator;
+//! ```
+//!
+//! Without it the collector still runs, but can't see GC pointers that are
+//! only held by a container.
+//!
+//! Other threads' stacks can't be scanned without stopping them, so no
+//! collection runs while a Haxe thread spawned with `Thread.create` is
+//! still unjoined; allocation simply continues until it is.
+//!
+//! Collections run on `rayzor_gc_collect`, and automatically every
+//! `RAYZOR_GC_THRESHOLD` allocated bytes ([`DEFAULT_THRESHOLD`] under
+//! `--gc`, otherwise only when the variable is set), as read by
+//! `rayzor_runtime_init`. `rayzor_runtime_shutdown` frees every remaining
+//! object.
+//!
+//! The write barrier is called after storing a GC pointer into a GC object.
+//! Marking never overlaps with other code in this collector, so the barrier
+//! only checks and counts the store; it is part of the API so compiled code
+//! keeps working once marking becomes incremental.
+
+use parking_lot::Mutex;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Environment variable with the bytes allocated between automatic
+/// collections (0: collect only on `rayzor_gc_collect`)
+pub const GC_THRESHOLD_ENV_VAR: &str = "RAYZOR_GC_THRESHOLD";
+
+/// Bytes allocated between automatic collections under `--gc` when
+/// `RAYZOR_GC_THRESHOLD` is unset
+pub const DEFAULT_THRESHOLD: usize = 8 << 20;
+
+/// Alignment of every GC object, as for `rayzor_malloc`
+const OBJECT_ALIGNMENT: usize = crate::DEFAULT_ALIGNMENT;
+
+const WORD: usize = std::mem::size_of::<usize>();
+
+/// Counters of one heap
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GcStats {
+    pub objects: usize,
+    pub live_bytes: usize,
+    pub collections: usize,
+    pub freed_bytes: usize,
+    pub barriers: usize,
+}
+
+/// Receives `[start, end)` memory ranges to mark from
+pub type Visit<'a> = dyn FnMut(&[(usize, usize)]) + 'a;
+
+/// Finds memory to mark from besides the root slots: calls `visit` with
+/// ranges that stay readable until it returns. Returns false if a
+/// collection isn't safe right now.
+pub type Scanner = fn(visit: &mut Visit) -> bool;
+
+fn no_ranges(visit: &mut Visit) -> bool {
+    visit(&[]);
+    true
+}
+
+#[derive(Debug)]
+struct Object {
+    size: usize,
+    marked: bool,
+}
+
+/// Objects, roots and collection policy of one heap
+#[derive(Debug)]
+pub struct Heap {
+    /// Every object by start address
+    objects: BTreeMap<usize, Object>,
+    /// Root slots and how many times each was added
+    roots: BTreeMap<usize, usize>,
+    scanner: Scanner,
+    /// Bytes between automatic collections, 0 for none
+    threshold: usize,
+    allocated_since_collection: usize,
+    stats: GcStats,
+}
+
+impl Heap {
+    pub const fn new() -> Self {
+        Self {
+            objects: BTreeMap::new(),
+            roots: BTreeMap::new(),
+            scanner: no_ranges,
+            threshold: 0,
+            allocated_since_collection: 0,
+            stats: GcStats {
+                objects: 0,
+                live_bytes: 0,
+                collections: 0,
+                freed_bytes: 0,
+                barriers: 0,
+            },
+        }
+    }
+
+    /// Collect automatically every `bytes` allocated bytes (0: never)
+    pub fn set_threshold(&mut self, bytes: usize) {
+        self.threshold = bytes;
+    }
+
+    /// Mark from `scanner`'s ranges as well as the root slots
+    pub fn set_scanner(&mut self, scanner: Scanner) {
+        self.scanner = scanner;
+    }
+
+    pub fn stats(&self) -> GcStats {
+        self.stats
+    }
+
+    /// Allocate a zeroed object of `size` bytes, or null
+    pub fn alloc(&mut self, size: usize) -> *mut u8 {
+        if size == 0 {
+            return ptr::null_mut();
+        }
+        let Ok(layout) = Layout::from_size_align(size, OBJECT_ALIGNMENT) else {
+            return ptr::null_mut();
+        };
+        if self.threshold > 0 && self.allocated_since_collection >= self.threshold {
+            self.collect();
+        }
+
+        // SAFETY: `layout` has a non-zero size
+        let mut ptr = unsafe { System.alloc_zeroed(layout) };
+        if ptr.is_null() && self.threshold > 0 {
+            self.collect();
+            ptr = unsafe { System.alloc_zeroed(layout) };
+        }
+        if ptr.is_null() {
+            ptr = crate::oom::retry_allocation(size as u64, || unsafe {
+                System.alloc_zeroed(layout)
+            });
+            if ptr.is_null() {
+                return ptr;
+            }
+        }
+
+        self.objects.insert(
+            ptr as usize,
+            Object {
+                size,
+                marked: false,
+            },
+        );
+        self.allocated_since_collection += size;
+        self.stats.objects += 1;
+        self.stats.live_bytes += size;
+        ptr
+    }
+
+    /// Size of the object starting at `ptr`, if it is one
+    pub fn size_of(&self, ptr: *mut u8) -> Option<usize> {
+        self.objects.get(&(ptr as usize)).map(|object| object.size)
+    }
+
+    /// Free the object starting at `ptr` now; returns false if it isn't one
+    pub fn free(&mut self, ptr: *mut u8) -> bool {
+        let Some(object) = self.objects.remove(&(ptr as usize)) else {
+            return false;
+        };
+        self.release(ptr as usize, object.size);
+        self.stats.objects -= 1;
+        self.stats.live_bytes -= object.size;
+        self.stats.freed_bytes += object.size;
+        true
+    }
+
+    fn release(&mut self, start: usize, size: usize) {
+        crate::object_id::release(start);
+        // SAFETY: allocated in `alloc` with this layout
+        unsafe {
+            System.dealloc(
+                start as *mut u8,
+                Layout::from_size_align_unchecked(size, OBJECT_ALIGNMENT),
+            )
+        };
+    }
+
+    /// Keep whatever `slot` points to alive until the slot is removed.
+    /// Slots may be added more than once and must be removed as often.
+    pub fn add_root(&mut self, slot: *mut *mut u8) {
+        if !slot.is_null() {
+            *self.roots.entry(slot as usize).or_insert(0) += 1;
+        }
+    }
+
+    pub fn remove_root(&mut self, slot: *mut *mut u8) {
+        let slot = slot as usize;
+        if let Some(count) = self.roots.get_mut(&slot) {
+            *count -= 1;
+            if *count == 0 {
+                self.roots.remove(&slot);
+            }
+        }
+    }
+
+    /// Record that `value` was stored into `object`; returns whether both
+    /// are GC objects
+    pub fn write_barrier(&mut self, object: *mut u8, value: *mut u8) -> bool {
+        self.stats.barriers += 1;
+        self.object_at(object as usize).is_some() && self.object_at(value as usize).is_some()
+    }
+
+    /// Start address of the object containing `addr`
+    fn object_at(&self, addr: usize) -> Option<usize> {
+        let (&start, object) = self.objects.range(..=addr).next_back()?;
+        (addr < start + object.size).then_some(start)
+    }
+
+    /// Free every object not reachable from a root; returns the bytes freed,
+    /// 0 if the scanner refused to collect
+    pub fn collect(&mut self) -> usize {
+        let scanner = self.scanner;
+        if !scanner(&mut |ranges| self.mark(ranges)) {
+            return 0;
+        }
+        self.sweep()
+    }
+
+    fn mark(&mut self, ranges: &[(usize, usize)]) {
+        let mut pending: Vec<usize> = self
+            .roots
+            .keys()
+            // SAFETY: roots are live slots until removed
+            .map(|&slot| unsafe { *(slot as *const usize) })
+            .collect();
+        for &(start, end) in ranges {
+            // SAFETY: the scanner keeps its ranges readable while marking
+            unsafe { push_words(&mut pending, start, end) };
+        }
+        while let Some(addr) = pending.pop() {
+            let Some(start) = self.object_at(addr) else {
+                continue;
+            };
+            let object = self.objects.get_mut(&start).unwrap();
+            if object.marked {
+                continue;
+            }
+            object.marked = true;
+            let size = object.size;
+            // SAFETY: the object is live and 16-byte aligned, so every word
+            // in it is readable
+            unsafe { push_words(&mut pending, start, start + size) };
+        }
+    }
+
+    fn sweep(&mut self) -> usize {
+        let garbage: Vec<(usize, usize)> = self
+            .objects
+            .iter()
+            .filter(|(_, object)| !object.marked)
+            .map(|(&start, object)| (start, object.size))
+            .collect();
+        let mut freed = 0;
+        for (start, size) in garbage {
+            self.objects.remove(&start);
+            self.release(start, size);
+            freed += size;
+        }
+        for object in self.objects.values_mut() {
+            object.marked = false;
+        }
+
+        self.allocated_since_collection = 0;
+        self.stats.collections += 1;
+        self.stats.objects = self.objects.len();
+        self.stats.live_bytes -= freed;
+        self.stats.freed_bytes += freed;
+        freed
+    }
+
+    /// Drop all roots and free every object
+    pub fn release_all(&mut self) -> usize {
+        self.roots.clear();
+        self.sweep()
+    }
+}
+
+impl Default for Heap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Heap {
+    fn drop(&mut self) {
+        self.release_all();
+    }
+}
+
+/// Push every aligned word of `[start, end)`
+///
+/// # Safety
+/// The range must be readable
+unsafe fn push_words(pending: &mut Vec<usize>, start: usize, end: usize) {
+    let mut addr = start.next_multiple_of(WORD);
+    while addr + WORD <= end {
+        // Volatile: the words may be written by code the compiler can't see
+        pending.push(ptr::read_volatile(addr as *const usize));
+        addr += WORD;
+    }
+}
+
+// ============================================================================
+// Tracked Rust heap
+// ============================================================================
+
+/// Whether generated code allocates from the GC heap (`rayzor run --gc`)
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Blocks allocated inside [`tracked`] while enabled, by start address. Held
+/// while marking, so none of them is freed mid-scan.
+static BLOCKS: Mutex<BTreeMap<usize, usize>> = Mutex::new(BTreeMap::new());
+
+thread_local! {
+    /// Set while this thread holds `BLOCKS`, whose own nodes are not tracked
+    static IN_BLOCKS: Cell<bool> = const { Cell::new(false) };
+    /// Set inside [`tracked`], cleared inside [`untracked`]
+    static TRACKING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Global allocator that records the blocks allocated inside [`tracked`]
+/// while the collector is enabled, so they are scanned. A single atomic load
+/// otherwise. Binaries opt in with `#[global_allocator]`; see the module
+/// docs.
+pub struct TrackingAllocator;
+
+fn flag(key: &'static std::thread::LocalKey<Cell<bool>>) -> bool {
+    // During thread teardown the flags may be gone: treat as set
+    key.try_with(Cell::get).unwrap_or(true)
+}
+
+fn with_blocks<R>(f: impl FnOnce(&mut BTreeMap<usize, usize>) -> R) -> Option<R> {
+    if flag(&IN_BLOCKS) {
+        return None;
+    }
+    let _ = IN_BLOCKS.try_with(|b| b.set(true));
+    let result = f(&mut BLOCKS.lock());
+    let _ = IN_BLOCKS.try_with(|b| b.set(false));
+    Some(result)
+}
+
+fn track(ptr: *mut u8, size: usize) {
+    // During thread teardown the flag may be gone: don't track
+    if !ptr.is_null() && is_enabled() && TRACKING.try_with(Cell::get).unwrap_or(false) {
+        with_blocks(|blocks| blocks.insert(ptr as usize, size));
+    }
+}
+
+/// Stop tracking `ptr`; returns whether it was tracked
+fn untrack(ptr: *mut u8) -> bool {
+    // Always, even outside `tracked`: a stale entry would be scanned after
+    // the block is gone
+    is_enabled()
+        && matches!(
+            with_blocks(|blocks| blocks.remove(&(ptr as usize))),
+            Some(Some(_))
+        )
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        track(ptr, layout.size());
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        track(ptr, layout.size());
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        untrack(ptr);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // A tracked block stays tracked wherever it is resized
+        let was_tracked = untrack(ptr);
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        let (block, size) = if new_ptr.is_null() {
+            (ptr, layout.size())
+        } else {
+            (new_ptr, new_size)
+        };
+        if was_tracked {
+            tracked(|| track(block, size));
+        } else {
+            track(block, size);
+        }
+        new_ptr
+    }
+}
+
+fn with_tracking<R>(tracking: bool, f: impl FnOnce() -> R) -> R {
+    let outer = TRACKING.try_with(|t| t.replace(tracking)).unwrap_or(false);
+    let result = f();
+    let _ = TRACKING.try_with(|t| t.set(outer));
+    result
+}
+
+/// Run `f` tracking the blocks it allocates: for runtime containers whose
+/// buffers hold GC pointers. Only blocks allocated on this thread are
+/// tracked, and only while the collector is enabled and the binary installed
+/// [`TrackingAllocator`].
+pub fn tracked<R>(f: impl FnOnce() -> R) -> R {
+    with_tracking(true, f)
+}
+
+/// Run `f` without tracking the blocks it allocates, even inside
+/// [`tracked`]: for runtime tables that hold object addresses without
+/// keeping the objects alive
+pub fn untracked<R>(f: impl FnOnce() -> R) -> R {
+    with_tracking(false, f)
+}
+
+// ============================================================================
+// Native roots
+// ============================================================================
+
+/// Callee-saved registers of the caller, which may hold the only copy of a
+/// pointer
+#[inline(always)]
+fn spill_registers() -> Option<[usize; 12]> {
+    let mut regs = [0usize; 12];
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        std::arch::asm!(
+            "mov [{0}], rbx",
+            "mov [{0} + 8], rbp",
+            "mov [{0} + 16], r12",
+            "mov [{0} + 24], r13",
+            "mov [{0} + 32], r14",
+            "mov [{0} + 40], r15",
+            in(reg) regs.as_mut_ptr(),
+            options(nostack, preserves_flags),
+        );
+        Some(regs)
+    }
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        std::arch::asm!(
+            "stp x19, x20, [{0}]",
+            "stp x21, x22, [{0}, #16]",
+            "stp x23, x24, [{0}, #32]",
+            "stp x25, x26, [{0}, #48]",
+            "stp x27, x28, [{0}, #64]",
+            "str x29, [{0}, #80]",
+            in(reg) regs.as_mut_ptr(),
+            options(nostack, preserves_flags),
+        );
+        Some(regs)
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        let _ = &mut regs;
+        None
+    }
+}
+
+/// Scanner of the global heap: see the module docs for what it covers
+#[inline(never)]
+fn scan_native_roots(visit: &mut Visit) -> bool {
+    if crate::concurrency::rayzor_active_thread_count() > 0 {
+        return false;
+    }
+    let Some(regs) = spill_registers() else {
+        return false;
+    };
+    let regs = std::hint::black_box(regs);
+    // Everything above this frame: the mutator's frames and spilled values
+    let low = &regs as *const _ as usize;
+    let high = match crate::stack_guard::recorded_bounds() {
+        (0, _) => crate::stack_guard::current_bounds().map_or(0, |(_, high)| high),
+        (_, high) => high,
+    };
+    if low >= high {
+        return false;
+    }
+
+    let exception = [crate::exception::rayzor_get_exception() as usize];
+    let mut ranges = vec![
+        (low, high),
+        (
+            exception.as_ptr() as usize,
+            exception.as_ptr() as usize + WORD,
+        ),
+    ];
+    if !crate::coroutine::stack_ranges(&mut ranges) {
+        return false;
+    }
+    crate::with_global_store(|store| {
+        let start = store.as_ptr() as usize;
+        ranges.push((start, start + std::mem::size_of_val(store)));
+    });
+
+    if is_enabled() {
+        let done = with_blocks(|blocks| {
+            ranges.extend(blocks.iter().map(|(&start, &size)| (start, start + size)));
+            visit(&ranges);
+        });
+        if done.is_none() {
+            return false;
+        }
+    } else {
+        visit(&ranges);
+    }
+    std::hint::black_box(&regs);
+    true
+}
+
+// ============================================================================
+// Global heap
+// ============================================================================
+
+/// The heap behind the `rayzor_gc_*` functions
+static HEAP: Mutex<Heap> = Mutex::new(Heap::new());
+
+fn with_heap<R>(f: impl FnOnce(&mut Heap) -> R) -> R {
+    untracked(|| {
+        let mut heap = HEAP.lock();
+        heap.set_scanner(scan_native_roots);
+        f(&mut heap)
+    })
+}
+
+/// Whether generated code allocates from the GC heap
+#[inline]
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Allocate generated code's memory from the GC heap. Must be called before
+/// the program allocates: blocks from the regular allocators are neither
+/// collected nor scanned.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// Symbols that route generated code's `malloc`/`realloc`/`free` to the GC
+/// heap; they override the runtime's defaults when linking
+pub fn symbols() -> Vec<(&'static str, *const u8)> {
+    vec![
+        ("malloc", rayzor_gc_malloc as *const u8),
+        ("realloc", rayzor_gc_realloc as *const u8),
+        ("free", rayzor_gc_free as *const u8),
+    ]
+}
+
+/// Read the collection policy from the environment (`rayzor_runtime_init`)
+pub fn init() {
+    let default = if is_enabled() { DEFAULT_THRESHOLD } else { 0 };
+    let threshold = std::env::var(GC_THRESHOLD_ENV_VAR)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(default);
+    with_heap(|heap| heap.set_threshold(threshold));
+}
+
+/// Free every GC object (`rayzor_runtime_shutdown`)
+pub fn shutdown() {
+    with_heap(|heap| heap.release_all());
+}
+
+/// Counters of the global heap
+pub fn stats() -> GcStats {
+    with_heap(|heap| heap.stats())
+}
+
+/// Free `ptr` if it is a GC object; returns false otherwise
+///
+/// # Safety
+/// A GC object freed here must not be used again
+pub unsafe fn free(ptr: *mut u8) -> bool {
+    !ptr.is_null() && with_heap(|heap| heap.free(ptr))
+}
+
+/// Whether `ptr` is the start of a GC object
+pub fn owns(ptr: *mut u8) -> bool {
+    with_heap(|heap| heap.size_of(ptr).is_some())
+}
+
+/// Allocate a zeroed, 16-byte aligned GC object; null if `size` is 0 or
+/// memory is exhausted
+#[no_mangle]
+pub extern "C" fn rayzor_gc_alloc(size: u64) -> *mut u8 {
+    with_heap(|heap| heap.alloc(size as usize))
+}
+
+/// `malloc` replacement under `--gc`: a GC object of at least one byte
+#[no_mangle]
+pub extern "C" fn rayzor_gc_malloc(size: u64) -> *mut u8 {
+    rayzor_gc_alloc(size.max(1))
+}
+
+/// `free` replacement under `--gc`: frees a GC object now, and passes
+/// other pointers on to libc
+///
+/// # Safety
+/// `ptr` must be null, a GC object, or a block from libc `malloc`
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gc_free(ptr: *mut u8) {
+    if !ptr.is_null() && !free(ptr) {
+        libc::free(ptr as *mut libc::c_void);
+    }
+}
+
+/// `realloc` replacement under `--gc`: moves a GC object into a new one,
+/// and passes other pointers on to libc
+///
+/// # Safety
+/// Same as [`rayzor_gc_free`]
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_gc_realloc(ptr: *mut u8, size: u64) -> *mut u8 {
+    if ptr.is_null() {
+        return rayzor_gc_malloc(size);
+    }
+    let Some(old_size) = with_heap(|heap| heap.size_of(ptr)) else {
+        return libc::realloc(ptr as *mut libc::c_void, size as usize) as *mut u8;
+    };
+    let new_ptr = rayzor_gc_malloc(size);
+    if new_ptr.is_null() {
+        return ptr::null_mut();
+    }
+    ptr::copy_nonoverlapping(ptr, new_ptr, old_size.min(size as usize));
+    crate::object_id::relocate(ptr as usize, new_ptr as usize);
+    free(ptr);
+    new_ptr
+}
+
+/// Register `slot` as a root
+#[no_mangle]
+pub extern "C" fn rayzor_gc_add_root(slot: *mut *mut u8) {
+    with_heap(|heap| heap.add_root(slot));
+}
+
+/// Unregister a root added with `rayzor_gc_add_root`
+#[no_mangle]
+pub extern "C" fn rayzor_gc_remove_root(slot: *mut *mut u8) {
+    with_heap(|heap| heap.remove_root(slot));
+}
+
+/// Called after storing `value` into the GC object `object`
+#[no_mangle]
+pub extern "C" fn rayzor_gc_write_barrier(object: *mut u8, value: *mut u8) {
+    with_heap(|heap| heap.write_barrier(object, value));
+}
+
+/// Collect now; returns the number of bytes freed (0 while other Haxe
+/// threads are running)
+#[no_mangle]
+pub extern "C" fn rayzor_gc_collect() -> u64 {
+    with_heap(|heap| heap.collect()) as u64
+}
+
+/// Bytes held by live GC objects
+#[no_mangle]
+pub extern "C" fn rayzor_gc_live_bytes() -> u64 {
+    with_heap(|heap| heap.stats().live_bytes) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe fn set_field(object: *mut u8, index: usize, value: *mut u8) {
+        *(object as *mut *mut u8).add(index) = value;
+    }
+
+    #[test]
+    fn test_collects_unreachable_objects() {
+        let mut heap = Heap::new();
+        let mut root = heap.alloc(32);
+        let child = heap.alloc(16);
+        let grandchild = heap.alloc(64);
+        heap.alloc(48);
+        unsafe {
+            set_field(root, 1, child);
+            // Interior pointers keep their object alive too
+            set_field(child, 0, grandchild.add(24));
+        }
+        assert!(heap.write_barrier(root, child));
+        heap.add_root(&mut root);
+
+        assert_eq!(heap.collect(), 48);
+        assert_eq!(heap.stats().objects, 3);
+
+        // Unlinking the child frees both
+        unsafe { set_field(root, 1, std::ptr::null_mut()) };
+        assert_eq!(heap.collect(), 80);
+        assert_eq!(heap.stats().live_bytes, 32);
+
+        heap.remove_root(&mut root);
+        assert_eq!(heap.collect(), 32);
+        assert_eq!(heap.stats().freed_bytes, 160);
+    }
+
+    #[test]
+    fn test_cycles_and_repeated_roots() {
+        let mut heap = Heap::new();
+        let mut a = heap.alloc(16);
+        let b = heap.alloc(16);
+        unsafe {
+            set_field(a, 0, b);
+            set_field(b, 0, a);
+        }
+        heap.add_root(&mut a);
+        heap.add_root(&mut a);
+        heap.remove_root(&mut a);
+        assert_eq!(heap.collect(), 0);
+
+        // An unreachable cycle is collected
+        heap.remove_root(&mut a);
+        assert_eq!(heap.collect(), 32);
+        assert!(!heap.write_barrier(a, b));
+    }
+
+    #[test]
+    fn test_threshold_collects_on_allocation() {
+        let mut heap = Heap::new();
+        heap.set_threshold(64);
+        for _ in 0..4 {
+            heap.alloc(32);
+        }
+        // The third allocation found 64 bytes allocated and collected first
+        assert_eq!(heap.stats().collections, 1);
+        assert_eq!(heap.stats().objects, 2);
+        assert_eq!(heap.release_all(), 64);
+    }
+
+    static RANGE: Mutex<(usize, usize)> = Mutex::new((0, 0));
+
+    fn scan_range(visit: &mut Visit) -> bool {
+        let range = *RANGE.lock();
+        if range.0 == 0 {
+            return false;
+        }
+        visit(&[range]);
+        true
+    }
+
+    #[test]
+    fn test_scanner_ranges_and_explicit_free() {
+        let mut heap = Heap::new();
+        heap.set_scanner(scan_range);
+        let a = heap.alloc(16);
+        let b = heap.alloc(16);
+        let c = heap.alloc(16);
+
+        // The scanner refuses: nothing is collected
+        assert_eq!(heap.collect(), 0);
+        assert_eq!(heap.stats().collections, 0);
+
+        // A word in a scanned range keeps `a`, and through it `b`, alive
+        let frame = [a as usize, 0x10];
+        unsafe { set_field(a, 0, b) };
+        *RANGE.lock() = (frame.as_ptr() as usize, frame.as_ptr() as usize + 16);
+        assert_eq!(heap.collect(), 16);
+        assert_eq!(heap.size_of(c), None);
+        assert_eq!(heap.size_of(b), Some(16));
+
+        // Freed explicitly, even though still referenced
+        assert!(heap.free(b));
+        assert!(!heap.free(b));
+        assert_eq!(heap.stats().live_bytes, 16);
+        std::hint::black_box(&frame);
+        *RANGE.lock() = (0, 0);
+        assert_eq!(heap.release_all(), 16);
+    }
+
+    #[test]
+    fn test_stack_words_keep_objects_alive() {
+        // The global heap scans this thread's stack: an object referenced
+        // only from a local survives a collection
+        let kept = std::hint::black_box(rayzor_gc_alloc(64));
+        unsafe { *(kept as *mut u64) = 42 };
+        rayzor_gc_collect();
+        assert!(owns(kept));
+        assert_eq!(unsafe { *(kept as *const u64) }, 42);
+        unsafe { rayzor_gc_free(kept) };
+        assert!(!owns(std::hint::black_box(kept)));
+    }
+}
@@ -20,6 +20,51 @@ pub struct HaxeArray {
 
 const INITIAL_CAPACITY: usize = 8;
 
+/// Allocate an element buffer. Elements may be GC pointers, so the buffer is
+/// scanned under `--gc`; it stays tracked when `realloc`ed.
+unsafe fn alloc_elements(layout: Layout) -> *mut u8 {
+    crate::gc::tracked(|| alloc(layout))
+}
+
+/// Capacity multiplier applied when an array runs out of room.
+///
+/// Growing geometrically keeps `push` amortized O(1): an array that reaches
+/// `n` elements has copied fewer than `n` elements in total across all of its
+/// reallocations. Use `shrinkToFit` to give back the slack afterwards.
+pub const GROWTH_FACTOR: usize = 2;
+
+/// Grow `arr` so it can hold at least `min_cap` elements.
+///
+/// Capacity starts at `INITIAL_CAPACITY` and is multiplied by `GROWTH_FACTOR`
+/// until it fits. Returns false if the allocation failed (the array is left
+/// untouched).
+unsafe fn reserve(arr: &mut HaxeArray, min_cap: usize) -> bool {
+    if min_cap <= arr.cap {
+        return true;
+    }
+
+    let mut new_cap = arr.cap.max(INITIAL_CAPACITY);
+    while new_cap < min_cap {
+        new_cap *= GROWTH_FACTOR;
+    }
+
+    let new_size = new_cap * arr.elem_size;
+    let new_ptr = if arr.ptr.is_null() || arr.cap == 0 {
+        alloc_elements(Layout::from_size_align_unchecked(new_size, 8))
+    } else {
+        let old_layout = Layout::from_size_align_unchecked(arr.cap * arr.elem_size, 8);
+        realloc(arr.ptr, old_layout, new_size)
+    };
+
+    if new_ptr.is_null() {
+        return false;
+    }
+
+    arr.ptr = new_ptr;
+    arr.cap = new_cap;
+    true
+}
+
 // ============================================================================
 // Array Creation
 // ============================================================================
@@ -30,7 +75,7 @@ pub extern "C" fn haxe_array_new(out: *mut HaxeArray, elem_size: usize) {
     unsafe {
         let total_size = INITIAL_CAPACITY * elem_size;
         let layout = Layout::from_size_align_unchecked(total_size, 8);
-        let ptr = alloc(layout);
+        let ptr = alloc_elements(layout);
 
         if ptr.is_null() {
             panic!("Failed to allocate memory for Array");
@@ -60,7 +105,7 @@ pub extern "C" fn haxe_array_from_elements(
         let cap = count.max(INITIAL_CAPACITY);
         let total_size = cap * elem_size;
         let layout = Layout::from_size_align_unchecked(total_size, 8);
-        let ptr = alloc(layout);
+        let ptr = alloc_elements(layout);
 
         if ptr.is_null() {
             panic!("Failed to allocate memory for Array");
@@ -160,33 +205,9 @@ pub extern "C" fn haxe_array_set(arr: *mut HaxeArray, index: usize, data: *const
         let new_len = index + 1;
         if new_len > arr_ref.len {
             // Ensure we have enough capacity
-            if new_len > arr_ref.cap {
-                let mut new_cap = if arr_ref.cap == 0 {
-                    INITIAL_CAPACITY
-                } else {
-                    arr_ref.cap
-                };
-                while new_cap < new_len {
-                    new_cap *= 2;
-                }
-
-                let new_size = new_cap * arr_ref.elem_size;
-                let new_ptr = if arr_ref.ptr.is_null() || arr_ref.cap == 0 {
-                    let layout = Layout::from_size_align_unchecked(new_size, 8);
-                    alloc(layout)
-                } else {
-                    let old_size = arr_ref.cap * arr_ref.elem_size;
-                    let old_layout = Layout::from_size_align_unchecked(old_size, 8);
-                    realloc(arr_ref.ptr, old_layout, new_size)
-                };
-
-                if new_ptr.is_null() {
-                    debug!("[haxe_array_set] Failed to allocate memory");
-                    return false;
-                }
-
-                arr_ref.ptr = new_ptr;
-                arr_ref.cap = new_cap;
+            if !reserve(arr_ref, new_len) {
+                debug!("[haxe_array_set] Failed to allocate memory");
+                return false;
             }
 
             // Zero-initialize the new elements between old len and index
@@ -236,29 +257,8 @@ pub extern "C" fn haxe_array_set_i64(arr: *mut HaxeArray, index: usize, value: i
         // Auto-expand if needed
         let new_len = index + 1;
         if new_len > arr_ref.len {
-            if new_len > arr_ref.cap {
-                let mut new_cap = if arr_ref.cap == 0 {
-                    INITIAL_CAPACITY
-                } else {
-                    arr_ref.cap
-                };
-                while new_cap < new_len {
-                    new_cap *= 2;
-                }
-                let new_size = new_cap * arr_ref.elem_size;
-                let new_ptr = if arr_ref.ptr.is_null() || arr_ref.cap == 0 {
-                    let layout = Layout::from_size_align_unchecked(new_size, 8);
-                    alloc(layout)
-                } else {
-                    let old_size = arr_ref.cap * arr_ref.elem_size;
-                    let old_layout = Layout::from_size_align_unchecked(old_size, 8);
-                    realloc(arr_ref.ptr, old_layout, new_size)
-                };
-                if new_ptr.is_null() {
-                    return false;
-                }
-                arr_ref.ptr = new_ptr;
-                arr_ref.cap = new_cap;
+            if !reserve(arr_ref, new_len) {
+                return false;
             }
             // Zero-fill gap
             if arr_ref.len < new_len {
@@ -288,29 +288,8 @@ pub extern "C" fn haxe_array_set_f64(arr: *mut HaxeArray, index: usize, value: f
         // Auto-expand if needed
         let new_len = index + 1;
         if new_len > arr_ref.len {
-            if new_len > arr_ref.cap {
-                let mut new_cap = if arr_ref.cap == 0 {
-                    INITIAL_CAPACITY
-                } else {
-                    arr_ref.cap
-                };
-                while new_cap < new_len {
-                    new_cap *= 2;
-                }
-                let new_size = new_cap * arr_ref.elem_size;
-                let new_ptr = if arr_ref.ptr.is_null() || arr_ref.cap == 0 {
-                    let layout = Layout::from_size_align_unchecked(new_size, 8);
-                    alloc(layout)
-                } else {
-                    let old_size = arr_ref.cap * arr_ref.elem_size;
-                    let old_layout = Layout::from_size_align_unchecked(old_size, 8);
-                    realloc(arr_ref.ptr, old_layout, new_size)
-                };
-                if new_ptr.is_null() {
-                    return false;
-                }
-                arr_ref.ptr = new_ptr;
-                arr_ref.cap = new_cap;
+            if !reserve(arr_ref, new_len) {
+                return false;
             }
             if arr_ref.len < new_len {
                 let start = arr_ref.len * arr_ref.elem_size;
@@ -339,29 +318,8 @@ pub extern "C" fn haxe_array_set_null(arr: *mut HaxeArray, index: usize) -> bool
         // Auto-expand if needed
         let new_len = index + 1;
         if new_len > arr_ref.len {
-            if new_len > arr_ref.cap {
-                let mut new_cap = if arr_ref.cap == 0 {
-                    INITIAL_CAPACITY
-                } else {
-                    arr_ref.cap
-                };
-                while new_cap < new_len {
-                    new_cap *= 2;
-                }
-                let new_size = new_cap * arr_ref.elem_size;
-                let new_ptr = if arr_ref.ptr.is_null() || arr_ref.cap == 0 {
-                    let layout = Layout::from_size_align_unchecked(new_size, 8);
-                    alloc(layout)
-                } else {
-                    let old_size = arr_ref.cap * arr_ref.elem_size;
-                    let old_layout = Layout::from_size_align_unchecked(old_size, 8);
-                    realloc(arr_ref.ptr, old_layout, new_size)
-                };
-                if new_ptr.is_null() {
-                    return false;
-                }
-                arr_ref.ptr = new_ptr;
-                arr_ref.cap = new_cap;
+            if !reserve(arr_ref, new_len) {
+                return false;
             }
             if arr_ref.len < new_len {
                 let start = arr_ref.len * arr_ref.elem_size;
@@ -433,33 +391,9 @@ pub extern "C" fn haxe_array_push(arr: *mut HaxeArray, data: *const u8) {
             arr_ref.len, arr_ref.cap, arr_ref.elem_size
         );
 
-        // Check if we need to grow
-        if arr_ref.len >= arr_ref.cap {
-            let new_cap = if arr_ref.cap == 0 {
-                INITIAL_CAPACITY
-            } else {
-                arr_ref.cap * 2
-            };
-
-            let new_size = new_cap * arr_ref.elem_size;
-
-            let new_ptr = if arr_ref.ptr.is_null() || arr_ref.cap == 0 {
-                // First allocation - use alloc instead of realloc
-                let layout = Layout::from_size_align_unchecked(new_size, 8);
-                alloc(layout)
-            } else {
-                // Grow existing allocation
-                let old_size = arr_ref.cap * arr_ref.elem_size;
-                let old_layout = Layout::from_size_align_unchecked(old_size, 8);
-                realloc(arr_ref.ptr, old_layout, new_size)
-            };
-
-            if new_ptr.is_null() {
-                panic!("Failed to allocate/reallocate memory for Array");
-            }
-
-            arr_ref.ptr = new_ptr;
-            arr_ref.cap = new_cap;
+        // Ensure capacity
+        if !reserve(arr_ref, arr_ref.len + 1) {
+            panic!("Failed to allocate/reallocate memory for Array");
         }
 
         // Add element
@@ -575,20 +509,8 @@ pub extern "C" fn haxe_array_insert(arr: *mut HaxeArray, index: i32, data: *cons
         let insert_pos = (index.max(0) as usize).min(arr_ref.len);
 
         // Ensure capacity
-        if arr_ref.len >= arr_ref.cap {
-            let new_cap = arr_ref.cap * 2;
-            let old_size = arr_ref.cap * arr_ref.elem_size;
-            let new_size = new_cap * arr_ref.elem_size;
-
-            let old_layout = Layout::from_size_align_unchecked(old_size, 8);
-            let new_ptr = realloc(arr_ref.ptr, old_layout, new_size);
-
-            if new_ptr.is_null() {
-                panic!("Failed to reallocate memory for Array");
-            }
-
-            arr_ref.ptr = new_ptr;
-            arr_ref.cap = new_cap;
+        if !reserve(arr_ref, arr_ref.len + 1) {
+            panic!("Failed to reallocate memory for Array");
         }
 
         // Shift elements to the right
@@ -683,13 +605,13 @@ pub extern "C" fn haxe_array_copy(out: *mut HaxeArray, arr: *const HaxeArray) {
     }
 }
 
-/// Slice array
+/// Slice array: copy elements in [start, end). Negative positions count from the end.
 #[no_mangle]
 pub extern "C" fn haxe_array_slice(
     out: *mut HaxeArray,
     arr: *const HaxeArray,
-    start: usize,
-    end: usize,
+    start: i64,
+    end: i64,
 ) {
     debug!(
         "[haxe_array_slice] Called with out={:?}, arr={:?}, start={}, end={}",
@@ -706,8 +628,17 @@ pub extern "C" fn haxe_array_slice(
             "[haxe_array_slice] arr.len={}, arr.cap={}, arr.elem_size={}",
             arr_ref.len, arr_ref.cap, arr_ref.elem_size
         );
-        let actual_start = start.min(arr_ref.len);
-        let actual_end = end.min(arr_ref.len);
+        // Negative positions count from the end of the array
+        let len = arr_ref.len as i64;
+        let resolve = |pos: i64| {
+            if pos < 0 {
+                (len + pos).max(0) as usize
+            } else {
+                pos.min(len) as usize
+            }
+        };
+        let actual_start = resolve(start);
+        let actual_end = resolve(end);
         debug!(
             "[haxe_array_slice] actual_start={}, actual_end={}",
             actual_start, actual_end
@@ -913,15 +844,84 @@ pub extern "C" fn haxe_array_join(
 // ============================================================================
 // Higher-Order Array Methods
 // ============================================================================
+//
+// Callbacks are compiled Haxe closures: `fn(env_ptr, args...) -> result`.
+// Elements live in 8-byte slots (Int sign-extended, Float as f64 bits,
+// references as raw pointers), so the slot itself does not say how it must be
+// passed. The compiler describes the closure's ABI with `CALLBACK_*` flags.
+
+/// Callback parameters are Float: slots are passed as f64 (XMM) arguments.
+///
+/// For two-argument callbacks this describes the first parameter only (see
+/// `CALLBACK_F64_ARG2`), except for `sort`, whose comparator takes two elements.
+pub const CALLBACK_F64_ARGS: i32 = 1;
+/// Callback returns Float: the result is stored as f64 bits.
+pub const CALLBACK_F64_RESULT: i32 = 2;
+/// Callback returns Int (i32): the result is sign-extended into the slot.
+pub const CALLBACK_I32_RESULT: i32 = 4;
+/// Callback returns Bool: only the low byte of the result is meaningful.
+pub const CALLBACK_BOOL_RESULT: i32 = 8;
+/// Second callback parameter is Float (e.g. the accumulator of `Lambda.fold`).
+pub const CALLBACK_F64_ARG2: i32 = 16;
+
+/// Element type tags (shared with `haxe_reflect_compare_typed`)
+pub(crate) const TAG_INT: i32 = 1;
+pub(crate) const TAG_BOOL: i32 = 2;
+pub(crate) const TAG_INT_LEGACY: i32 = 3;
+pub(crate) const TAG_FLOAT: i32 = 4;
+pub(crate) const TAG_STRING: i32 = 5;
+
+/// Read slot `i` as a raw 64-bit value.
+pub(crate) unsafe fn read_slot(arr: &HaxeArray, i: usize) -> i64 {
+    match arr.elem_size {
+        8 => *(arr.ptr.add(i * 8) as *const i64),
+        4 => *(arr.ptr.add(i * 4) as *const i32) as i64,
+        _ => 0,
+    }
+}
+
+/// Call a one-argument callback on a raw slot value, honouring the ABI flags.
+pub(crate) unsafe fn call_unary(fn_ptr: usize, env_ptr: *mut u8, elem: i64, kind: i32) -> i64 {
+    let f64_args = kind & CALLBACK_F64_ARGS != 0;
+    let result = if kind & CALLBACK_F64_RESULT != 0 {
+        let value = if f64_args {
+            let f: extern "C" fn(*mut u8, f64) -> f64 = std::mem::transmute(fn_ptr);
+            f(env_ptr, f64::from_bits(elem as u64))
+        } else {
+            let f: extern "C" fn(*mut u8, i64) -> f64 = std::mem::transmute(fn_ptr);
+            f(env_ptr, elem)
+        };
+        return value.to_bits() as i64;
+    } else if f64_args {
+        let f: extern "C" fn(*mut u8, f64) -> i64 = std::mem::transmute(fn_ptr);
+        f(env_ptr, f64::from_bits(elem as u64))
+    } else {
+        let f: extern "C" fn(*mut u8, i64) -> i64 = std::mem::transmute(fn_ptr);
+        f(env_ptr, elem)
+    };
+    narrow_result(result, kind)
+}
+
+/// Normalise an integer callback result into its slot form per the ABI flags.
+pub(crate) fn narrow_result(result: i64, kind: i32) -> i64 {
+    if kind & CALLBACK_BOOL_RESULT != 0 {
+        (result & 0xff != 0) as i64
+    } else if kind & CALLBACK_I32_RESULT != 0 {
+        result as i32 as i64
+    } else {
+        result
+    }
+}
 
 /// Map: apply callback to each element, collect results into a new array.
-/// Callback signature: fn(env_ptr: *mut u8, element: i64) -> i64
+/// Callback signature: fn(env_ptr: *mut u8, element) -> result, described by `kind`
 #[no_mangle]
 pub extern "C" fn haxe_array_map(
     out: *mut HaxeArray,
     arr: *const HaxeArray,
     fn_ptr: usize,
     env_ptr: *mut u8,
+    kind: i32,
 ) {
     if arr.is_null() || out.is_null() || fn_ptr == 0 {
         // Initialize empty output array
@@ -934,34 +934,18 @@ pub extern "C" fn haxe_array_map(
     unsafe {
         let arr_ref = &*arr;
         let len = arr_ref.len;
-        let elem_size = arr_ref.elem_size;
 
         // Initialize output array with same length capacity
         let out_cap = len.max(INITIAL_CAPACITY);
         let out_total = out_cap * 8; // result elements are always i64 (8 bytes)
         let layout = Layout::from_size_align_unchecked(out_total, 8);
-        let out_ptr = alloc(layout);
+        let out_ptr = alloc_elements(layout);
         if out_ptr.is_null() {
             panic!("Failed to allocate memory for Array.map result");
         }
 
-        // Cast fn_ptr to callable function pointer
-        let callback: extern "C" fn(*mut u8, i64) -> i64 = std::mem::transmute(fn_ptr);
-
         for i in 0..len {
-            // Read element as i64
-            let elem = if elem_size == 8 {
-                *(arr_ref.ptr.add(i * elem_size) as *const i64)
-            } else if elem_size == 4 {
-                *(arr_ref.ptr.add(i * elem_size) as *const i32) as i64
-            } else {
-                0i64
-            };
-
-            // Call the callback
-            let result = callback(env_ptr, elem);
-
-            // Store result
+            let result = call_unary(fn_ptr, env_ptr, read_slot(arr_ref, i), kind);
             *(out_ptr.add(i * 8) as *mut i64) = result;
         }
 
@@ -972,14 +956,15 @@ pub extern "C" fn haxe_array_map(
     }
 }
 
-/// Filter: keep elements where callback returns non-zero.
-/// Callback signature: fn(env_ptr: *mut u8, element: i64) -> i64 (0 = false, non-zero = true)
+/// Filter: keep elements where callback returns true.
+/// Callback signature: fn(env_ptr: *mut u8, element) -> Bool, described by `kind`
 #[no_mangle]
 pub extern "C" fn haxe_array_filter(
     out: *mut HaxeArray,
     arr: *const HaxeArray,
     fn_ptr: usize,
     env_ptr: *mut u8,
+    kind: i32,
 ) {
     if arr.is_null() || out.is_null() || fn_ptr == 0 {
         if !out.is_null() {
@@ -991,31 +976,23 @@ pub extern "C" fn haxe_array_filter(
     unsafe {
         let arr_ref = &*arr;
         let len = arr_ref.len;
-        let elem_size = arr_ref.elem_size;
 
         // Allocate output with same capacity as input (worst case: all pass)
         let out_cap = len.max(INITIAL_CAPACITY);
         let out_total = out_cap * 8;
         let layout = Layout::from_size_align_unchecked(out_total, 8);
-        let out_ptr = alloc(layout);
+        let out_ptr = alloc_elements(layout);
         if out_ptr.is_null() {
             panic!("Failed to allocate memory for Array.filter result");
         }
 
-        let callback: extern "C" fn(*mut u8, i64) -> i64 = std::mem::transmute(fn_ptr);
+        // The predicate's result is a Bool whatever the caller says
+        let kind = (kind & CALLBACK_F64_ARGS) | CALLBACK_BOOL_RESULT;
 
         let mut out_len = 0usize;
         for i in 0..len {
-            let elem = if elem_size == 8 {
-                *(arr_ref.ptr.add(i * elem_size) as *const i64)
-            } else if elem_size == 4 {
-                *(arr_ref.ptr.add(i * elem_size) as *const i32) as i64
-            } else {
-                0i64
-            };
-
-            let keep = callback(env_ptr, elem);
-            if keep != 0 {
+            let elem = read_slot(arr_ref, i);
+            if call_unary(fn_ptr, env_ptr, elem, kind) != 0 {
                 *(out_ptr.add(out_len * 8) as *mut i64) = elem;
                 out_len += 1;
             }
@@ -1028,6 +1005,93 @@ pub extern "C" fn haxe_array_filter(
     }
 }
 
+/// Compare two raw slot values for equality according to the element type tag.
+///
+/// Floats compare numerically (so `0.0 == -0.0` and NaN never matches), strings
+/// by content; everything else (Int, Bool, references) by raw value.
+pub(crate) unsafe fn slot_equals(a: i64, b: i64, tag: i32) -> bool {
+    match tag {
+        TAG_FLOAT => f64::from_bits(a as u64) == f64::from_bits(b as u64),
+        TAG_STRING => {
+            if a == b {
+                return true;
+            }
+            if a == 0 || b == 0 {
+                return false;
+            }
+            let sa = &*(a as *const HaxeString);
+            let sb = &*(b as *const HaxeString);
+            sa.len == sb.len
+                && (sa.len == 0
+                    || std::slice::from_raw_parts(sa.ptr, sa.len)
+                        == std::slice::from_raw_parts(sb.ptr, sb.len))
+        }
+        _ => a == b,
+    }
+}
+
+/// Format a raw slot value for `join` according to the element type tag.
+pub(crate) unsafe fn format_slot(value: i64, tag: i32, out: &mut String) {
+    match tag {
+        TAG_INT | TAG_INT_LEGACY => out.push_str(&(value as i32).to_string()),
+        TAG_BOOL => out.push_str(if value & 0xff != 0 { "true" } else { "false" }),
+        TAG_FLOAT => out.push_str(&crate::number_format::format_float(f64::from_bits(
+            value as u64,
+        ))),
+        _ => {
+            if value == 0 {
+                out.push_str("null");
+            } else {
+                let s = &*(value as *const HaxeString);
+                if s.len > 0 && !s.ptr.is_null() {
+                    out.push_str(&String::from_utf8_lossy(std::slice::from_raw_parts(
+                        s.ptr, s.len,
+                    )));
+                }
+            }
+        }
+    }
+}
+
+/// Join array elements with a separator, formatting each by its element type tag.
+/// Tags: 1 = Int, 2 = Bool, 4 = Float; anything else is treated as String.
+#[no_mangle]
+pub extern "C" fn haxe_array_join_typed(
+    arr: *const HaxeArray,
+    sep: *const HaxeString,
+    tag: i32,
+) -> *mut HaxeString {
+    if !matches!(tag, TAG_INT | TAG_BOOL | TAG_INT_LEGACY | TAG_FLOAT) {
+        return haxe_array_join(arr, sep);
+    }
+
+    unsafe {
+        let result_ptr = alloc(Layout::new::<HaxeString>()) as *mut HaxeString;
+        if result_ptr.is_null() {
+            panic!("Failed to allocate HaxeString for join result");
+        }
+
+        let mut joined = String::new();
+        if !arr.is_null() {
+            let arr_ref = &*arr;
+            let sep_str = if sep.is_null() || (*sep).len == 0 {
+                std::borrow::Cow::Borrowed("")
+            } else {
+                String::from_utf8_lossy(std::slice::from_raw_parts((*sep).ptr, (*sep).len))
+            };
+            for i in 0..arr_ref.len {
+                if i > 0 {
+                    joined.push_str(&sep_str);
+                }
+                format_slot(read_slot(arr_ref, i), tag, &mut joined);
+            }
+        }
+
+        crate::haxe_string::haxe_string_from_bytes(result_ptr, joined.as_ptr(), joined.len());
+        result_ptr
+    }
+}
+
 // ============================================================================
 // Search & Query Methods
 // ============================================================================
@@ -1036,6 +1100,18 @@ pub extern "C" fn haxe_array_filter(
 /// Returns index or -1 if not found. Compares raw i64 values.
 #[no_mangle]
 pub extern "C" fn haxe_array_index_of(arr: *const HaxeArray, value: i64, from_index: i64) -> i64 {
+    haxe_array_index_of_typed(arr, value, from_index, 0)
+}
+
+/// indexOf with element-aware equality (see `slot_equals`).
+/// `value` is the raw slot representation (f64 bits for Float).
+#[no_mangle]
+pub extern "C" fn haxe_array_index_of_typed(
+    arr: *const HaxeArray,
+    value: i64,
+    from_index: i64,
+    tag: i32,
+) -> i64 {
     if arr.is_null() {
         return -1;
     }
@@ -1057,7 +1133,7 @@ pub extern "C" fn haxe_array_index_of(arr: *const HaxeArray, value: i64, from_in
 
         let data = arr_ref.ptr as *const i64;
         for i in start..arr_ref.len {
-            if *data.add(i) == value {
+            if slot_equals(*data.add(i), value, tag) {
                 return i as i64;
             }
         }
@@ -1113,7 +1189,13 @@ pub extern "C" fn haxe_array_last_index_of(
 /// contains: check if array contains value. Returns 1 (true) or 0 (false).
 #[no_mangle]
 pub extern "C" fn haxe_array_contains(arr: *const HaxeArray, value: i64) -> i64 {
-    if haxe_array_index_of(arr, value, 0) >= 0 {
+    haxe_array_contains_typed(arr, value, 0)
+}
+
+/// contains with element-aware equality (see `slot_equals`).
+#[no_mangle]
+pub extern "C" fn haxe_array_contains_typed(arr: *const HaxeArray, value: i64, tag: i32) -> i64 {
+    if haxe_array_index_of_typed(arr, value, 0, tag) >= 0 {
         1
     } else {
         0
@@ -1206,26 +1288,8 @@ pub extern "C" fn haxe_array_unshift(arr: *mut HaxeArray, value: i64) {
         let arr_ref = &mut *arr;
 
         // Ensure capacity
-        if arr_ref.len >= arr_ref.cap {
-            let new_cap = if arr_ref.cap == 0 {
-                INITIAL_CAPACITY
-            } else {
-                arr_ref.cap * 2
-            };
-            let new_size = new_cap * arr_ref.elem_size;
-            let new_ptr = if arr_ref.ptr.is_null() || arr_ref.cap == 0 {
-                let layout = Layout::from_size_align_unchecked(new_size, 8);
-                alloc(layout)
-            } else {
-                let old_size = arr_ref.cap * arr_ref.elem_size;
-                let old_layout = Layout::from_size_align_unchecked(old_size, 8);
-                realloc(arr_ref.ptr, old_layout, new_size)
-            };
-            if new_ptr.is_null() {
-                panic!("Failed to reallocate memory for Array.unshift");
-            }
-            arr_ref.ptr = new_ptr;
-            arr_ref.cap = new_cap;
+        if !reserve(arr_ref, arr_ref.len + 1) {
+            panic!("Failed to reallocate memory for Array.unshift");
         }
 
         // Shift all elements right by one
@@ -1278,7 +1342,7 @@ pub extern "C" fn haxe_array_concat(
         let cap = total_len.max(INITIAL_CAPACITY);
         let total_size = cap * es;
         let layout = Layout::from_size_align_unchecked(total_size, 8);
-        let new_ptr = alloc(layout);
+        let new_ptr = alloc_elements(layout);
         if new_ptr.is_null() {
             panic!("Failed to allocate memory for Array.concat");
         }
@@ -1366,29 +1430,8 @@ pub extern "C" fn haxe_array_resize(arr: *mut HaxeArray, new_len: i64) {
         }
 
         // Extend - ensure capacity
-        if new_len > arr_ref.cap {
-            let mut new_cap = if arr_ref.cap == 0 {
-                INITIAL_CAPACITY
-            } else {
-                arr_ref.cap
-            };
-            while new_cap < new_len {
-                new_cap *= 2;
-            }
-            let new_size = new_cap * arr_ref.elem_size;
-            let new_ptr = if arr_ref.ptr.is_null() || arr_ref.cap == 0 {
-                let layout = Layout::from_size_align_unchecked(new_size, 8);
-                alloc(layout)
-            } else {
-                let old_size = arr_ref.cap * arr_ref.elem_size;
-                let old_layout = Layout::from_size_align_unchecked(old_size, 8);
-                realloc(arr_ref.ptr, old_layout, new_size)
-            };
-            if new_ptr.is_null() {
-                panic!("Failed to allocate memory for Array.resize");
-            }
-            arr_ref.ptr = new_ptr;
-            arr_ref.cap = new_cap;
+        if !reserve(arr_ref, new_len) {
+            panic!("Failed to allocate memory for Array.resize");
         }
 
         // Zero-fill new elements
@@ -1399,6 +1442,127 @@ pub extern "C" fn haxe_array_resize(arr: *mut HaxeArray, new_len: i64) {
     }
 }
 
+/// resize with fill: set array length, initializing new slots with `*fill`.
+///
+/// `fill` points to one element (`elem_size` bytes). A null `fill` behaves
+/// like `haxe_array_resize` and zero-fills.
+#[no_mangle]
+pub extern "C" fn haxe_array_resize_fill(arr: *mut HaxeArray, new_len: i64, fill: *const u8) {
+    if fill.is_null() {
+        haxe_array_resize(arr, new_len);
+        return;
+    }
+    if arr.is_null() || new_len < 0 {
+        return;
+    }
+
+    let new_len = new_len as usize;
+
+    unsafe {
+        let arr_ref = &mut *arr;
+
+        if new_len <= arr_ref.len {
+            arr_ref.len = new_len;
+            return;
+        }
+
+        if !reserve(arr_ref, new_len) {
+            panic!("Failed to allocate memory for Array.resize");
+        }
+
+        let es = arr_ref.elem_size;
+        for i in arr_ref.len..new_len {
+            ptr::copy_nonoverlapping(fill, arr_ref.ptr.add(i * es), es);
+        }
+        arr_ref.len = new_len;
+    }
+}
+
+/// shrinkToFit: release unused capacity so that `cap == len`.
+///
+/// An empty array releases its buffer entirely; the next push allocates
+/// `INITIAL_CAPACITY` elements again.
+#[no_mangle]
+pub extern "C" fn haxe_array_shrink_to_fit(arr: *mut HaxeArray) {
+    if arr.is_null() {
+        return;
+    }
+
+    unsafe {
+        let arr_ref = &mut *arr;
+        if arr_ref.ptr.is_null() || arr_ref.len >= arr_ref.cap {
+            return;
+        }
+
+        let old_layout = Layout::from_size_align_unchecked(arr_ref.cap * arr_ref.elem_size, 8);
+
+        if arr_ref.len == 0 {
+            dealloc(arr_ref.ptr, old_layout);
+            arr_ref.ptr = ptr::null_mut();
+            arr_ref.cap = 0;
+            return;
+        }
+
+        let new_ptr = realloc(arr_ref.ptr, old_layout, arr_ref.len * arr_ref.elem_size);
+        if new_ptr.is_null() {
+            // Keeping the larger buffer is always valid
+            return;
+        }
+
+        arr_ref.ptr = new_ptr;
+        arr_ref.cap = arr_ref.len;
+    }
+}
+
+/// Debug-mode iterator invalidation check for `for (x in arr)`.
+///
+/// The compiler records the array length when the loop starts and calls this
+/// at the top of every iteration when debug checks are enabled. If the array
+/// was resized by the loop body, reports the loop's source location and aborts.
+#[no_mangle]
+pub extern "C" fn haxe_array_check_iteration(
+    arr: *const HaxeArray,
+    expected_len: i64,
+    file: *const HaxeString,
+    line: i32,
+    column: i32,
+) {
+    if arr.is_null() {
+        return;
+    }
+
+    let actual_len = unsafe { (*arr).len } as i64;
+    if actual_len == expected_len {
+        return;
+    }
+
+    let file = source_file_name(file);
+
+    eprintln!(
+        "{}:{}:{}: runtime error: Array modified during iteration (length changed from {} to {})",
+        file, line, column, expected_len, actual_len
+    );
+    eprintln!(
+        "note: iterate over a copy (`for (x in arr.copy())`) to modify the array inside the loop"
+    );
+    std::process::abort();
+}
+
+/// File name passed to the debug-mode checks, or `<unknown>`.
+fn source_file_name(file: *const HaxeString) -> String {
+    if file.is_null() {
+        return "<unknown>".to_string();
+    }
+    unsafe {
+        let s = &*file;
+        if s.ptr.is_null() || s.len == 0 {
+            "<unknown>".to_string()
+        } else {
+            String::from_utf8_lossy(std::slice::from_raw_parts(s.ptr, s.len)).into_owned()
+        }
+    }
+}
+
 /// toString: create string representation "[elem0, elem1, ...]"
 /// Elements are printed as integers (i64). For proper type-aware printing,
 /// the compiler should use trace() which has type info.
@@ -1444,11 +1608,96 @@ pub extern "C" fn haxe_array_to_string(arr: *const HaxeArray) -> *mut HaxeString
 // Higher-Order Array Methods
 // ============================================================================
 
-/// Sort: in-place sort using comparator callback.
-/// Callback signature: fn(env_ptr: *mut u8, a: i64, b: i64) -> i32
+/// Runs shorter than this are insertion-sorted before merging starts.
+const SORT_MIN_RUN: usize = 32;
+
+/// Build the comparator for `haxe_array_sort` from a closure's parts.
+/// Callback signature: fn(env_ptr: *mut u8, a, b) -> i32, with f64 arguments
+/// when `kind` has `CALLBACK_F64_ARGS`.
+unsafe fn sort_comparator(fn_ptr: usize, env_ptr: *mut u8, kind: i32) -> impl Fn(i64, i64) -> i32 {
+    move |a: i64, b: i64| -> i32 {
+        if kind & CALLBACK_F64_ARGS != 0 {
+            let f: extern "C" fn(*mut u8, f64, f64) -> i32 = std::mem::transmute(fn_ptr);
+            f(env_ptr, f64::from_bits(a as u64), f64::from_bits(b as u64))
+        } else {
+            let f: extern "C" fn(*mut u8, i64, i64) -> i32 = std::mem::transmute(fn_ptr);
+            f(env_ptr, a, b)
+        }
+    }
+}
+
+/// Stable merge sort of raw element slots.
+///
+/// Short runs are insertion-sorted, then merged bottom-up between `slots`
+/// and a scratch buffer; a merge is skipped when its two runs are already in
+/// order, so sorted input costs a single pass. Only the sign of `compare`
+/// matters, and an element moves ahead of an earlier one only when `compare`
+/// is positive, which keeps equal elements in their original order. Every
+/// index stays in bounds whatever the comparator returns: an inconsistent
+/// comparator produces an unspecified permutation, never lost elements.
+fn stable_sort_slots(slots: &mut [i64], compare: &impl Fn(i64, i64) -> i32) {
+    let len = slots.len();
+
+    for run in slots.chunks_mut(SORT_MIN_RUN) {
+        for i in 1..run.len() {
+            let key = run[i];
+            let mut j = i;
+            while j > 0 && compare(run[j - 1], key) > 0 {
+                run[j] = run[j - 1];
+                j -= 1;
+            }
+            run[j] = key;
+        }
+    }
+    if len <= SORT_MIN_RUN {
+        return;
+    }
+
+    let mut scratch = vec![0i64; len];
+    let mut in_scratch = false;
+    let mut width = SORT_MIN_RUN;
+    while width < len {
+        let (src, dst): (&[i64], &mut [i64]) = if in_scratch {
+            (&scratch, &mut *slots)
+        } else {
+            (&*slots, &mut scratch)
+        };
+        for lo in (0..len).step_by(2 * width) {
+            let mid = (lo + width).min(len);
+            let hi = (lo + 2 * width).min(len);
+            if mid == hi || compare(src[mid - 1], src[mid]) <= 0 {
+                dst[lo..hi].copy_from_slice(&src[lo..hi]);
+                continue;
+            }
+            let (mut i, mut j) = (lo, mid);
+            for slot in &mut dst[lo..hi] {
+                // Take from the right run only when strictly smaller (stability)
+                if i < mid && (j >= hi || compare(src[i], src[j]) <= 0) {
+                    *slot = src[i];
+                    i += 1;
+                } else {
+                    *slot = src[j];
+                    j += 1;
+                }
+            }
+        }
+        in_scratch = !in_scratch;
+        width *= 2;
+    }
+    if in_scratch {
+        slots.copy_from_slice(&scratch);
+    }
+}
+
+/// Sort: stable in-place sort using comparator callback.
+/// Callback signature: fn(env_ptr: *mut u8, a, b) -> i32, with f64 arguments
+/// when `kind` has `CALLBACK_F64_ARGS`.
 /// Returns negative if a < b, 0 if equal, positive if a > b.
+///
+/// Sorting happens on a copy that is written back once complete, so a
+/// comparator that throws leaves the array unchanged.
 #[no_mangle]
-pub extern "C" fn haxe_array_sort(arr: *mut HaxeArray, fn_ptr: usize, env_ptr: *mut u8) {
+pub extern "C" fn haxe_array_sort(arr: *mut HaxeArray, fn_ptr: usize, env_ptr: *mut u8, kind: i32) {
     if arr.is_null() || fn_ptr == 0 {
         return;
     }
@@ -1460,24 +1709,321 @@ pub extern "C" fn haxe_array_sort(arr: *mut HaxeArray, fn_ptr: usize, env_ptr: *
             return;
         }
 
-        let callback: extern "C" fn(*mut u8, i64, i64) -> i32 = std::mem::transmute(fn_ptr);
+        // Elements are i64 slots (8 bytes each)
+        let data = std::slice::from_raw_parts_mut(arr_ref.ptr as *mut i64, len);
+        let mut sorted = data.to_vec();
+        stable_sort_slots(&mut sorted, &sort_comparator(fn_ptr, env_ptr, kind));
+        data.copy_from_slice(&sorted);
+    }
+}
 
-        // Simple insertion sort for now (stable, in-place)
-        // Elements are i64 (8 bytes each)
-        let data = arr_ref.ptr as *mut i64;
-        for i in 1..len {
-            let key = *data.add(i);
-            let mut j = i as isize - 1;
-            while j >= 0 {
-                let cmp = callback(env_ptr, *data.add(j as usize), key);
-                if cmp > 0 {
-                    *data.add((j + 1) as usize) = *data.add(j as usize);
-                    j -= 1;
-                } else {
-                    break;
-                }
+/// First place where `compare` contradicts the order of sorted `slots`, as
+/// `(i, j)` slot indices with `i < j`, checking adjacent and next-but-one
+/// pairs: `compare(a, b)` must not be positive for `a` before `b`, and
+/// `compare(b, a)` must then not be negative. A consistent comparator never
+/// fails this; a non-transitive one (`a < b < c < a`) fails it on any
+/// three-element cycle.
+fn find_comparator_violation(
+    slots: &[i64],
+    compare: &impl Fn(i64, i64) -> i32,
+) -> Option<(usize, usize)> {
+    for i in 0..slots.len() {
+        for j in (i + 1)..slots.len().min(i + 3) {
+            if compare(slots[i], slots[j]) > 0 || compare(slots[j], slots[i]) < 0 {
+                return Some((i, j));
+            }
+        }
+    }
+    None
+}
+
+/// Debug-mode comparator check, called after `arr.sort(f)` when debug checks
+/// are enabled. If `f` is inconsistent with the order it just produced (not
+/// antisymmetric or not transitive), reports the sort's source location and
+/// aborts.
+#[no_mangle]
+pub extern "C" fn haxe_array_check_sorted(
+    arr: *const HaxeArray,
+    fn_ptr: usize,
+    env_ptr: *mut u8,
+    kind: i32,
+    file: *const HaxeString,
+    line: i32,
+    column: i32,
+) {
+    if arr.is_null() || fn_ptr == 0 {
+        return;
+    }
+
+    let violation = unsafe {
+        let arr_ref = &*arr;
+        if arr_ref.len <= 1 {
+            return;
+        }
+        let data = std::slice::from_raw_parts(arr_ref.ptr as *const i64, arr_ref.len);
+        find_comparator_violation(data, &sort_comparator(fn_ptr, env_ptr, kind))
+    };
+    let Some((i, j)) = violation else {
+        return;
+    };
+
+    eprintln!(
+        "{}:{}:{}: runtime error: Array.sort comparator is inconsistent (elements {} and {} of the result compare out of order)",
+        source_file_name(file),
+        line,
+        column,
+        i,
+        j
+    );
+    eprintln!(
+        "note: the comparator must be transitive and return opposite signs for swapped arguments"
+    );
+    std::process::abort();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_i64_array() -> HaxeArray {
+        let mut arr = HaxeArray {
+            ptr: ptr::null_mut(),
+            len: 0,
+            cap: 0,
+            elem_size: 0,
+        };
+        haxe_array_new(&mut arr, 8);
+        arr
+    }
+
+    #[test]
+    fn test_growth_is_geometric() {
+        let mut arr = new_i64_array();
+        let mut reallocations = 0;
+        let mut last_cap = arr.cap;
+        for i in 0..1000 {
+            haxe_array_push_i64(&mut arr, i);
+            if arr.cap != last_cap {
+                assert_eq!(arr.cap, last_cap * GROWTH_FACTOR);
+                last_cap = arr.cap;
+                reallocations += 1;
+            }
+        }
+        assert_eq!(arr.len, 1000);
+        assert!(reallocations <= 7, "{} reallocations", reallocations);
+        assert_eq!(haxe_array_get_i64(&arr, 999), 999);
+        haxe_array_free(&mut arr);
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let mut arr = new_i64_array();
+        for i in 0..20 {
+            haxe_array_push_i64(&mut arr, i);
+        }
+        assert!(arr.cap > 20);
+        haxe_array_shrink_to_fit(&mut arr);
+        assert_eq!(arr.cap, 20);
+        assert_eq!(haxe_array_get_i64(&arr, 19), 19);
+
+        // Empty arrays drop their buffer and can grow again
+        haxe_array_resize(&mut arr, 0);
+        haxe_array_shrink_to_fit(&mut arr);
+        assert!(arr.ptr.is_null());
+        assert_eq!(arr.cap, 0);
+        haxe_array_insert(&mut arr, 0, &7i64 as *const i64 as *const u8);
+        assert_eq!(haxe_array_get_i64(&arr, 0), 7);
+        haxe_array_free(&mut arr);
+    }
+
+    #[test]
+    fn test_resize_fill() {
+        let mut arr = new_i64_array();
+        haxe_array_push_i64(&mut arr, 1);
+        let fill: i64 = -5;
+        haxe_array_resize_fill(&mut arr, 12, &fill as *const i64 as *const u8);
+        assert_eq!(arr.len, 12);
+        assert_eq!(haxe_array_get_i64(&arr, 0), 1);
+        assert!((1..12).all(|i| haxe_array_get_i64(&arr, i) == -5));
+
+        // Null fill zero-extends; shrinking truncates
+        haxe_array_resize_fill(&mut arr, 14, ptr::null());
+        assert_eq!(haxe_array_get_i64(&arr, 13), 0);
+        haxe_array_resize_fill(&mut arr, 3, &fill as *const i64 as *const u8);
+        assert_eq!(arr.len, 3);
+        haxe_array_free(&mut arr);
+    }
+
+    #[test]
+    fn test_check_iteration_unchanged() {
+        let mut arr = new_i64_array();
+        haxe_array_push_i64(&mut arr, 1);
+        // Matching length returns normally
+        haxe_array_check_iteration(&arr, 1, ptr::null(), 1, 1);
+        haxe_array_free(&mut arr);
+    }
+
+    #[test]
+    fn test_sort_is_stable() {
+        // Slots are key * 1000 + original index; compare keys only
+        let by_key = |a: i64, b: i64| ((a / 1000) - (b / 1000)) as i32;
+        let mut slots: Vec<i64> = (0..500).map(|i| (i * 7 % 13) * 1000 + i).collect();
+        stable_sort_slots(&mut slots, &by_key);
+        for pair in slots.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            assert!(a / 1000 < b / 1000 || (a / 1000 == b / 1000 && a % 1000 < b % 1000));
+        }
+
+        // Sorted and reversed input around the run boundary
+        for len in [31, 32, 33, 64, 65, 1000] {
+            let mut ascending: Vec<i64> = (0..len).collect();
+            let mut descending: Vec<i64> = (0..len).rev().collect();
+            let cmp = |a: i64, b: i64| (a - b).signum() as i32;
+            stable_sort_slots(&mut ascending, &cmp);
+            stable_sort_slots(&mut descending, &cmp);
+            assert_eq!(ascending, descending);
+            assert!(ascending.windows(2).all(|p| p[0] < p[1]));
+        }
+    }
+
+    #[test]
+    fn test_inconsistent_comparator_keeps_elements() {
+        // Rock-paper-scissors: 0 < 1 < 2 < 0
+        let cyclic = |a: i64, b: i64| {
+            let (a, b) = (a % 3, b % 3);
+            if a == b {
+                0
+            } else if (a + 1) % 3 == b {
+                -1
+            } else {
+                1
             }
-            *data.add((j + 1) as usize) = key;
+        };
+        let mut slots: Vec<i64> = (0..100).collect();
+        stable_sort_slots(&mut slots, &cyclic);
+        let mut seen = slots.clone();
+        seen.sort_unstable();
+        assert_eq!(seen, (0..100).collect::<Vec<_>>());
+        assert!(find_comparator_violation(&slots, &cyclic).is_some());
+
+        // Always-positive comparators are not antisymmetric
+        assert_eq!(find_comparator_violation(&[1, 2], &|_, _| 1), Some((0, 1)));
+
+        let cmp = |a: i64, b: i64| (a - b).signum() as i32;
+        assert_eq!(find_comparator_violation(&[1, 2, 2, 5], &cmp), None);
+    }
+
+    extern "C" fn halve(_env: *mut u8, x: f64) -> f64 {
+        x / 2.0
+    }
+
+    extern "C" fn is_positive(_env: *mut u8, x: f64) -> bool {
+        x > 0.0
+    }
+
+    extern "C" fn descending(_env: *mut u8, a: f64, b: f64) -> i32 {
+        if a > b {
+            -1
+        } else if a < b {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn f64_array(values: &[f64]) -> HaxeArray {
+        let mut arr = new_i64_array();
+        for v in values {
+            haxe_array_set_f64(&mut arr, arr.len, *v);
+        }
+        arr
+    }
+
+    #[test]
+    fn test_float_callbacks() {
+        let mut arr = f64_array(&[3.0, -1.5, 9.0]);
+        let env = ptr::null_mut();
+
+        let mut mapped = new_i64_array();
+        let kind = CALLBACK_F64_ARGS | CALLBACK_F64_RESULT;
+        haxe_array_map(&mut mapped, &arr, halve as *const () as usize, env, kind);
+        assert_eq!(haxe_array_get_f64(&mapped, 0), 1.5);
+        assert_eq!(haxe_array_get_f64(&mapped, 1), -0.75);
+
+        let mut kept = new_i64_array();
+        haxe_array_filter(
+            &mut kept,
+            &arr,
+            is_positive as *const () as usize,
+            env,
+            CALLBACK_F64_ARGS,
+        );
+        assert_eq!(kept.len, 2);
+        assert_eq!(haxe_array_get_f64(&kept, 1), 9.0);
+
+        haxe_array_sort(
+            &mut arr,
+            descending as *const () as usize,
+            env,
+            CALLBACK_F64_ARGS,
+        );
+        assert_eq!(haxe_array_get_f64(&arr, 0), 9.0);
+        assert_eq!(haxe_array_get_f64(&arr, 2), -1.5);
+
+        haxe_array_free(&mut mapped);
+        haxe_array_free(&mut kept);
+        haxe_array_free(&mut arr);
+    }
+
+    #[test]
+    fn test_typed_search_and_join() {
+        let sep = HaxeString {
+            ptr: b", ".as_ptr() as *mut u8,
+            len: 2,
+            cap: 0,
+        };
+
+        // Floats compare numerically: -0.0 finds 0.0
+        let mut arr = f64_array(&[0.5, 0.0, 2.0]);
+        assert_eq!(
+            haxe_array_index_of_typed(&arr, (-0.0f64).to_bits() as i64, 0, 4),
+            1
+        );
+        assert_eq!(
+            haxe_array_contains_typed(&arr, 2.0f64.to_bits() as i64, 4),
+            1
+        );
+        assert_eq!(
+            haxe_array_contains_typed(&arr, 3.0f64.to_bits() as i64, 4),
+            0
+        );
+
+        let joined = haxe_array_join_typed(&arr, &sep, 4);
+        let text = unsafe { std::slice::from_raw_parts((*joined).ptr, (*joined).len) };
+        assert_eq!(text, b"0.5, 0, 2");
+        haxe_array_free(&mut arr);
+
+        let mut ints = new_i64_array();
+        for v in [-3i64, 7] {
+            haxe_array_push_i64(&mut ints, v);
+        }
+        let joined = haxe_array_join_typed(&ints, &sep, 1);
+        let text = unsafe { std::slice::from_raw_parts((*joined).ptr, (*joined).len) };
+        assert_eq!(text, b"-3, 7");
+        haxe_array_free(&mut ints);
+    }
+
+    #[test]
+    fn test_slice_negative_positions() {
+        let mut arr = new_i64_array();
+        for i in 0..5 {
+            haxe_array_push_i64(&mut arr, i);
         }
+        let mut out = new_i64_array();
+        haxe_array_slice(&mut out, &arr, -2, i64::MAX);
+        assert_eq!(out.len, 2);
+        assert_eq!(haxe_array_get_i64(&out, 0), 3);
+        haxe_array_free(&mut out);
+        haxe_array_free(&mut arr);
     }
 }
@@ -0,0 +1,415 @@
+//! Native `Lambda` functions
+//!
+//! `Lambda` is an extern class: the compiler lowers each call to one of these
+//! functions, converting the iterable argument to a HaxeArray first (Lists go
+//! through `haxe_list_to_array`). Operations that Array already provides
+//! (`map`, `filter`, `has`, `indexOf`, `concat`, `array`) reuse the Array
+//! runtime directly.
+//!
+//! Callbacks are compiled Haxe closures described by the `CALLBACK_*` ABI flags
+//! from `haxe_array`; results and elements are raw 8-byte slot values.
+
+use crate::haxe_array::{
+    call_unary, haxe_array_new, haxe_array_push_i64, narrow_result, read_slot, HaxeArray,
+    CALLBACK_BOOL_RESULT, CALLBACK_F64_ARG2, CALLBACK_F64_ARGS, CALLBACK_F64_RESULT,
+};
+use std::alloc::{alloc, Layout};
+
+/// Slots of `arr` in order (empty for null).
+unsafe fn slots(arr: *const HaxeArray) -> impl Iterator<Item = i64> {
+    let arr = arr.as_ref();
+    let len = arr.map_or(0, |a| a.len);
+    (0..len).map(move |i| read_slot(arr.unwrap_unchecked(), i))
+}
+
+/// Call `f(env, a, b, extra...)` where `a`/`b` are raw slots passed as f64 or
+/// i64 according to `CALLBACK_F64_ARGS`/`CALLBACK_F64_ARG2`, followed by any
+/// trailing `type = value` arguments. Evaluates to the result as a raw slot.
+macro_rules! call_with_slots {
+    ($fn_ptr:expr, $env:expr, $a:expr, $b:expr, $kind:expr $(, $xt:ty = $x:expr)*) => {{
+        let (fn_ptr, env, a, b, kind): (usize, *mut u8, i64, i64, i32) =
+            ($fn_ptr, $env, $a, $b, $kind);
+        let (fa, fb) = (f64::from_bits(a as u64), f64::from_bits(b as u64));
+        let f64_a = kind & CALLBACK_F64_ARGS != 0;
+        let f64_b = kind & CALLBACK_F64_ARG2 != 0;
+        if kind & CALLBACK_F64_RESULT != 0 {
+            let result = match (f64_a, f64_b) {
+                (false, false) => {
+                    let f: extern "C" fn(*mut u8, i64, i64 $(, $xt)*) -> f64 =
+                        std::mem::transmute(fn_ptr);
+                    f(env, a, b $(, $x)*)
+                }
+                (true, false) => {
+                    let f: extern "C" fn(*mut u8, f64, i64 $(, $xt)*) -> f64 =
+                        std::mem::transmute(fn_ptr);
+                    f(env, fa, b $(, $x)*)
+                }
+                (false, true) => {
+                    let f: extern "C" fn(*mut u8, i64, f64 $(, $xt)*) -> f64 =
+                        std::mem::transmute(fn_ptr);
+                    f(env, a, fb $(, $x)*)
+                }
+                (true, true) => {
+                    let f: extern "C" fn(*mut u8, f64, f64 $(, $xt)*) -> f64 =
+                        std::mem::transmute(fn_ptr);
+                    f(env, fa, fb $(, $x)*)
+                }
+            };
+            result.to_bits() as i64
+        } else {
+            let result = match (f64_a, f64_b) {
+                (false, false) => {
+                    let f: extern "C" fn(*mut u8, i64, i64 $(, $xt)*) -> i64 =
+                        std::mem::transmute(fn_ptr);
+                    f(env, a, b $(, $x)*)
+                }
+                (true, false) => {
+                    let f: extern "C" fn(*mut u8, f64, i64 $(, $xt)*) -> i64 =
+                        std::mem::transmute(fn_ptr);
+                    f(env, fa, b $(, $x)*)
+                }
+                (false, true) => {
+                    let f: extern "C" fn(*mut u8, i64, f64 $(, $xt)*) -> i64 =
+                        std::mem::transmute(fn_ptr);
+                    f(env, a, fb $(, $x)*)
+                }
+                (true, true) => {
+                    let f: extern "C" fn(*mut u8, f64, f64 $(, $xt)*) -> i64 =
+                        std::mem::transmute(fn_ptr);
+                    f(env, fa, fb $(, $x)*)
+                }
+            };
+            narrow_result(result, kind)
+        }
+    }};
+}
+
+/// Predicate flags: element ABI from the caller, Bool result.
+fn predicate_kind(kind: i32) -> i32 {
+    (kind & CALLBACK_F64_ARGS) | CALLBACK_BOOL_RESULT
+}
+
+/// Index of the first element for which the predicate holds, if any.
+unsafe fn position(arr: *const HaxeArray, fn_ptr: usize, env_ptr: *mut u8, kind: i32) -> i64 {
+    if fn_ptr == 0 {
+        return -1;
+    }
+    let kind = predicate_kind(kind);
+    slots(arr)
+        .position(|item| call_unary(fn_ptr, env_ptr, item, kind) != 0)
+        .map_or(-1, |i| i as i64)
+}
+
+/// exists: true if the predicate holds for any element
+#[no_mangle]
+pub extern "C" fn haxe_lambda_exists(
+    arr: *const HaxeArray,
+    fn_ptr: usize,
+    env_ptr: *mut u8,
+    kind: i32,
+) -> bool {
+    unsafe { position(arr, fn_ptr, env_ptr, kind) >= 0 }
+}
+
+/// foreach: true if the predicate holds for every element (or there are none)
+#[no_mangle]
+pub extern "C" fn haxe_lambda_foreach(
+    arr: *const HaxeArray,
+    fn_ptr: usize,
+    env_ptr: *mut u8,
+    kind: i32,
+) -> bool {
+    if fn_ptr == 0 {
+        return true;
+    }
+    let kind = predicate_kind(kind);
+    unsafe { slots(arr).all(|item| call_unary(fn_ptr, env_ptr, item, kind) != 0) }
+}
+
+/// iter: call the callback on every element, in order
+#[no_mangle]
+pub extern "C" fn haxe_lambda_iter(
+    arr: *const HaxeArray,
+    fn_ptr: usize,
+    env_ptr: *mut u8,
+    kind: i32,
+) {
+    if fn_ptr == 0 {
+        return;
+    }
+    unsafe {
+        for item in slots(arr) {
+            call_unary(fn_ptr, env_ptr, item, kind & CALLBACK_F64_ARGS);
+        }
+    }
+}
+
+/// count: number of elements for which the predicate holds, or all elements
+/// when no predicate is given (`fn_ptr` = 0)
+#[no_mangle]
+pub extern "C" fn haxe_lambda_count(
+    arr: *const HaxeArray,
+    fn_ptr: usize,
+    env_ptr: *mut u8,
+    kind: i32,
+) -> i32 {
+    unsafe {
+        if fn_ptr == 0 {
+            return slots(arr).count() as i32;
+        }
+        let kind = predicate_kind(kind);
+        slots(arr)
+            .filter(|&item| call_unary(fn_ptr, env_ptr, item, kind) != 0)
+            .count() as i32
+    }
+}
+
+/// find: the first element for which the predicate holds, or 0 (null)
+#[no_mangle]
+pub extern "C" fn haxe_lambda_find(
+    arr: *const HaxeArray,
+    fn_ptr: usize,
+    env_ptr: *mut u8,
+    kind: i32,
+) -> u64 {
+    unsafe {
+        match position(arr, fn_ptr, env_ptr, kind) {
+            -1 => 0,
+            i => read_slot(&*arr, i as usize) as u64,
+        }
+    }
+}
+
+/// findIndex: index of the first element for which the predicate holds, or -1
+#[no_mangle]
+pub extern "C" fn haxe_lambda_find_index(
+    arr: *const HaxeArray,
+    fn_ptr: usize,
+    env_ptr: *mut u8,
+    kind: i32,
+) -> i32 {
+    unsafe { position(arr, fn_ptr, env_ptr, kind) as i32 }
+}
+
+/// fold: `first = f(item, first)` for every element; returns the final value.
+/// `kind` describes the item (`CALLBACK_F64_ARGS`), the accumulator
+/// (`CALLBACK_F64_ARG2`) and the result.
+#[no_mangle]
+pub extern "C" fn haxe_lambda_fold(
+    arr: *const HaxeArray,
+    fn_ptr: usize,
+    env_ptr: *mut u8,
+    first: u64,
+    kind: i32,
+) -> u64 {
+    if fn_ptr == 0 {
+        return first;
+    }
+    unsafe {
+        slots(arr).fold(first as i64, |acc, item| {
+            call_with_slots!(fn_ptr, env_ptr, item, acc, kind)
+        }) as u64
+    }
+}
+
+/// foldi: like fold, with the element index passed as a third (Int) argument
+#[no_mangle]
+pub extern "C" fn haxe_lambda_foldi(
+    arr: *const HaxeArray,
+    fn_ptr: usize,
+    env_ptr: *mut u8,
+    first: u64,
+    kind: i32,
+) -> u64 {
+    if fn_ptr == 0 {
+        return first;
+    }
+    unsafe {
+        slots(arr).enumerate().fold(first as i64, |acc, (i, item)| {
+            call_with_slots!(fn_ptr, env_ptr, item, acc, kind, i64 = i as i64)
+        }) as u64
+    }
+}
+
+/// Allocate a new empty HaxeArray with 8-byte slots.
+fn new_array() -> *mut HaxeArray {
+    unsafe {
+        let arr = alloc(Layout::new::<HaxeArray>()) as *mut HaxeArray;
+        haxe_array_new(arr, 8);
+        arr
+    }
+}
+
+/// mapi: new array of `f(index, item)` results.
+/// `kind` describes the item with `CALLBACK_F64_ARG2` (it is the second parameter).
+#[no_mangle]
+pub extern "C" fn haxe_lambda_mapi(
+    arr: *const HaxeArray,
+    fn_ptr: usize,
+    env_ptr: *mut u8,
+    kind: i32,
+) -> *mut HaxeArray {
+    let out = new_array();
+    if fn_ptr == 0 {
+        return out;
+    }
+    unsafe {
+        for (i, item) in slots(arr).enumerate() {
+            let result = call_with_slots!(fn_ptr, env_ptr, i as i64, item, kind);
+            haxe_array_push_i64(out, result);
+        }
+    }
+    out
+}
+
+/// flatten: concatenate an array of arrays into a new array
+#[no_mangle]
+pub extern "C" fn haxe_lambda_flatten(arr: *const HaxeArray) -> *mut HaxeArray {
+    let out = new_array();
+    unsafe {
+        for inner in slots(arr) {
+            for item in slots(inner as *const HaxeArray) {
+                haxe_array_push_i64(out, item);
+            }
+        }
+    }
+    out
+}
+
+/// array: copy the elements into a new array
+#[no_mangle]
+pub extern "C" fn haxe_lambda_array(arr: *const HaxeArray) -> *mut HaxeArray {
+    haxe_lambda_concat(arr, std::ptr::null())
+}
+
+/// concat: new array with the elements of `a` followed by those of `b`
+#[no_mangle]
+pub extern "C" fn haxe_lambda_concat(a: *const HaxeArray, b: *const HaxeArray) -> *mut HaxeArray {
+    let out = new_array();
+    unsafe {
+        for item in slots(a).chain(slots(b)) {
+            haxe_array_push_i64(out, item);
+        }
+    }
+    out
+}
+
+/// empty: true if the iterable has no elements
+#[no_mangle]
+pub extern "C" fn haxe_lambda_empty(arr: *const HaxeArray) -> bool {
+    unsafe { arr.as_ref() }.is_none_or(|arr| arr.len == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::haxe_array::CALLBACK_I32_RESULT;
+
+    extern "C" fn greater_than_two(_env: *mut u8, x: i64) -> i64 {
+        (x > 2) as i64
+    }
+
+    extern "C" fn add(_env: *mut u8, item: i64, acc: i64) -> i64 {
+        item + acc
+    }
+
+    extern "C" fn add_to_float(_env: *mut u8, item: i64, acc: f64) -> f64 {
+        item as f64 + acc
+    }
+
+    extern "C" fn weighted(_env: *mut u8, item: i64, acc: i64, index: i64) -> i64 {
+        acc + item * index
+    }
+
+    extern "C" fn index_plus_half(_env: *mut u8, index: i64, item: f64) -> f64 {
+        index as f64 + item
+    }
+
+    fn array_of(values: &[i64]) -> HaxeArray {
+        let mut arr = HaxeArray {
+            ptr: std::ptr::null_mut(),
+            len: 0,
+            cap: 0,
+            elem_size: 8,
+        };
+        haxe_array_new(&mut arr, 8);
+        for &v in values {
+            haxe_array_push_i64(&mut arr, v);
+        }
+        arr
+    }
+
+    fn ptr(f: *const ()) -> usize {
+        f as usize
+    }
+
+    fn slot_values(arr: *const HaxeArray) -> Vec<i64> {
+        unsafe { slots(arr).collect() }
+    }
+
+    #[test]
+    fn test_predicates() {
+        let arr = array_of(&[1, 2, 3, 4]);
+        let pred = ptr(greater_than_two as *const ());
+        let env = std::ptr::null_mut();
+
+        assert!(haxe_lambda_exists(&arr, pred, env, 0));
+        assert!(!haxe_lambda_foreach(&arr, pred, env, 0));
+        assert_eq!(haxe_lambda_count(&arr, pred, env, 0), 2);
+        assert_eq!(haxe_lambda_count(&arr, 0, env, 0), 4);
+        assert_eq!(haxe_lambda_find(&arr, pred, env, 0), 3);
+        assert_eq!(haxe_lambda_find_index(&arr, pred, env, 0), 2);
+        assert!(!haxe_lambda_empty(&arr));
+        assert!(haxe_lambda_empty(std::ptr::null()));
+
+        let small = array_of(&[1, 2]);
+        assert_eq!(haxe_lambda_find(&small, pred, env, 0), 0);
+        assert_eq!(haxe_lambda_find_index(&small, pred, env, 0), -1);
+    }
+
+    #[test]
+    fn test_folds() {
+        let arr = array_of(&[1, 2, 3, 4]);
+        let env = std::ptr::null_mut();
+
+        let sum = haxe_lambda_fold(&arr, ptr(add as *const ()), env, 10, CALLBACK_I32_RESULT);
+        assert_eq!(sum, 20);
+
+        let kind = CALLBACK_F64_ARG2 | CALLBACK_F64_RESULT;
+        let total = haxe_lambda_fold(
+            &arr,
+            ptr(add_to_float as *const ()),
+            env,
+            0.5f64.to_bits(),
+            kind,
+        );
+        assert_eq!(f64::from_bits(total), 10.5);
+
+        let weighted_sum = haxe_lambda_foldi(&arr, ptr(weighted as *const ()), env, 0, 0);
+        assert_eq!(weighted_sum, 2 + 6 + 12);
+    }
+
+    #[test]
+    fn test_array_results() {
+        let floats: Vec<i64> = [0.5f64, 0.25].iter().map(|f| f.to_bits() as i64).collect();
+        let arr = array_of(&floats);
+        let out = haxe_lambda_mapi(
+            &arr,
+            ptr(index_plus_half as *const ()),
+            std::ptr::null_mut(),
+            CALLBACK_F64_ARG2 | CALLBACK_F64_RESULT,
+        );
+        let mapped: Vec<f64> = slot_values(out)
+            .into_iter()
+            .map(|bits| f64::from_bits(bits as u64))
+            .collect();
+        assert_eq!(mapped, vec![0.5, 1.25]);
+
+        let a = array_of(&[1, 2]);
+        let b = array_of(&[3]);
+        let nested = array_of(&[&a as *const HaxeArray as i64, &b as *const HaxeArray as i64]);
+        assert_eq!(slot_values(haxe_lambda_flatten(&nested)), vec![1, 2, 3]);
+        assert_eq!(slot_values(haxe_lambda_concat(&a, &b)), vec![1, 2, 3]);
+        assert_eq!(slot_values(haxe_lambda_array(&a)), vec![1, 2]);
+    }
+}
@@ -0,0 +1,377 @@
+//! Native `haxe.ds.List` implementation
+//!
+//! `List<T>` is an extern class backed by a double-ended queue of 8-byte
+//! element slots. Slots use the same type-erased representation as Array
+//! elements (Int sign-extended, Float as f64 bits, references as raw pointers),
+//! and values cross the call boundary as raw u64 bits like StringMap values.
+//!
+//! Operations that depend on the element type (`remove`, `join`, `toString`)
+//! take the element type tag shared with `haxe_array_join_typed`; callbacks
+//! (`filter`, `map`) take the `CALLBACK_*` ABI flags from `haxe_array`.
+
+use crate::haxe_array::{
+    call_unary, format_slot, haxe_array_new, haxe_array_push_i64, read_slot, slot_equals,
+    HaxeArray, CALLBACK_BOOL_RESULT, CALLBACK_F64_ARGS, TAG_BOOL, TAG_FLOAT, TAG_INT,
+    TAG_INT_LEGACY, TAG_STRING,
+};
+use crate::haxe_string::HaxeString;
+use std::alloc::{alloc, Layout};
+use std::collections::VecDeque;
+
+/// Runtime representation of `haxe.ds.List<T>`
+#[derive(Default)]
+pub struct HaxeList {
+    items: VecDeque<i64>,
+}
+
+impl HaxeList {
+    fn from_slots(items: impl IntoIterator<Item = i64>) -> *mut HaxeList {
+        Box::into_raw(Box::new(HaxeList {
+            items: crate::gc::tracked(|| items.into_iter().collect()),
+        }))
+    }
+}
+
+/// Borrow a list, treating null as absent.
+unsafe fn list_ref<'a>(list: *const HaxeList) -> Option<&'a HaxeList> {
+    list.as_ref()
+}
+
+/// Borrow a list mutably, treating null as absent.
+unsafe fn list_mut<'a>(list: *mut HaxeList) -> Option<&'a mut HaxeList> {
+    list.as_mut()
+}
+
+/// Format elements separated by `sep`, using the element type tag.
+///
+/// Elements of unknown type (tag 0, objects) are printed as their raw value.
+unsafe fn format_items(items: &VecDeque<i64>, sep: &str, tag: i32) -> String {
+    let known = matches!(
+        tag,
+        TAG_INT | TAG_BOOL | TAG_INT_LEGACY | TAG_FLOAT | TAG_STRING
+    );
+    let mut out = String::new();
+    for (i, &value) in items.iter().enumerate() {
+        if i > 0 {
+            out.push_str(sep);
+        }
+        if known {
+            format_slot(value, tag, &mut out);
+        } else {
+            out.push_str(&value.to_string());
+        }
+    }
+    out
+}
+
+/// Allocate a HaxeString holding `s`.
+unsafe fn new_haxe_string(s: &str) -> *mut HaxeString {
+    let result = alloc(Layout::new::<HaxeString>()) as *mut HaxeString;
+    if result.is_null() {
+        panic!("Failed to allocate HaxeString for List result");
+    }
+    crate::haxe_string::haxe_string_from_bytes(result, s.as_ptr(), s.len());
+    result
+}
+
+// ============================================================================
+// Construction & Mutation
+// ============================================================================
+
+/// Create a new empty list
+#[no_mangle]
+pub extern "C" fn haxe_list_new() -> *mut HaxeList {
+    Box::into_raw(Box::default())
+}
+
+/// add: append a raw element value at the end
+#[no_mangle]
+pub extern "C" fn haxe_list_add(list: *mut HaxeList, value: u64) {
+    if let Some(list) = unsafe { list_mut(list) } {
+        // Elements may be GC pointers, so the queue is scanned under `--gc`
+        crate::gc::tracked(|| list.items.push_back(value as i64));
+    }
+}
+
+/// push: insert a raw element value at the beginning
+#[no_mangle]
+pub extern "C" fn haxe_list_push(list: *mut HaxeList, value: u64) {
+    if let Some(list) = unsafe { list_mut(list) } {
+        crate::gc::tracked(|| list.items.push_front(value as i64));
+    }
+}
+
+/// pop: remove and return the first element. Returns 0 (null) if empty.
+#[no_mangle]
+pub extern "C" fn haxe_list_pop(list: *mut HaxeList) -> u64 {
+    unsafe { list_mut(list) }
+        .and_then(|list| list.items.pop_front())
+        .unwrap_or(0) as u64
+}
+
+/// clear: remove all elements
+#[no_mangle]
+pub extern "C" fn haxe_list_clear(list: *mut HaxeList) {
+    if let Some(list) = unsafe { list_mut(list) } {
+        list.items.clear();
+    }
+}
+
+/// remove: remove the first element equal to `value` (raw comparison).
+#[no_mangle]
+pub extern "C" fn haxe_list_remove(list: *mut HaxeList, value: u64) -> bool {
+    haxe_list_remove_typed(list, value, 0)
+}
+
+/// remove with element-aware equality: floats compare numerically, strings by
+/// content (see `haxe_array_index_of_typed`).
+#[no_mangle]
+pub extern "C" fn haxe_list_remove_typed(list: *mut HaxeList, value: u64, tag: i32) -> bool {
+    let Some(list) = (unsafe { list_mut(list) }) else {
+        return false;
+    };
+    let position = list
+        .items
+        .iter()
+        .position(|&item| unsafe { slot_equals(item, value as i64, tag) });
+    match position {
+        Some(index) => {
+            list.items.remove(index);
+            true
+        }
+        None => false,
+    }
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// first: the first element without removing it. Returns 0 (null) if empty.
+#[no_mangle]
+pub extern "C" fn haxe_list_first(list: *const HaxeList) -> u64 {
+    unsafe { list_ref(list) }
+        .and_then(|list| list.items.front().copied())
+        .unwrap_or(0) as u64
+}
+
+/// last: the last element without removing it. Returns 0 (null) if empty.
+#[no_mangle]
+pub extern "C" fn haxe_list_last(list: *const HaxeList) -> u64 {
+    unsafe { list_ref(list) }
+        .and_then(|list| list.items.back().copied())
+        .unwrap_or(0) as u64
+}
+
+/// length: number of elements
+#[no_mangle]
+pub extern "C" fn haxe_list_length(list: *const HaxeList) -> i32 {
+    unsafe { list_ref(list) }.map_or(0, |list| list.items.len() as i32)
+}
+
+/// isEmpty: true if the list has no elements
+#[no_mangle]
+pub extern "C" fn haxe_list_is_empty(list: *const HaxeList) -> bool {
+    unsafe { list_ref(list) }.is_none_or(|list| list.items.is_empty())
+}
+
+/// join: elements formatted by type tag, separated by `sep`
+#[no_mangle]
+pub extern "C" fn haxe_list_join(
+    list: *const HaxeList,
+    sep: *const HaxeString,
+    tag: i32,
+) -> *mut HaxeString {
+    unsafe {
+        let sep = if sep.is_null() || (*sep).len == 0 {
+            std::borrow::Cow::Borrowed("")
+        } else {
+            String::from_utf8_lossy(std::slice::from_raw_parts((*sep).ptr, (*sep).len))
+        };
+        let joined =
+            list_ref(list).map_or_else(String::new, |list| format_items(&list.items, &sep, tag));
+        new_haxe_string(&joined)
+    }
+}
+
+/// toString: `{a, b, c}` with elements formatted by type tag
+#[no_mangle]
+pub extern "C" fn haxe_list_to_string(list: *const HaxeList, tag: i32) -> *mut HaxeString {
+    unsafe {
+        let inner =
+            list_ref(list).map_or_else(String::new, |list| format_items(&list.items, ", ", tag));
+        new_haxe_string(&format!("{{{}}}", inner))
+    }
+}
+
+// ============================================================================
+// Higher-Order Methods
+// ============================================================================
+
+/// filter: new list of the elements for which the predicate returns true.
+/// Callback signature: fn(env_ptr, element) -> Bool, described by `kind`
+#[no_mangle]
+pub extern "C" fn haxe_list_filter(
+    list: *const HaxeList,
+    fn_ptr: usize,
+    env_ptr: *mut u8,
+    kind: i32,
+) -> *mut HaxeList {
+    let Some(list) = (unsafe { list_ref(list) }).filter(|_| fn_ptr != 0) else {
+        return haxe_list_new();
+    };
+    // The predicate's result is a Bool whatever the caller says
+    let kind = (kind & CALLBACK_F64_ARGS) | CALLBACK_BOOL_RESULT;
+    HaxeList::from_slots(
+        list.items
+            .iter()
+            .copied()
+            .filter(|&item| unsafe { call_unary(fn_ptr, env_ptr, item, kind) } != 0),
+    )
+}
+
+/// map: new list of the callback results, in order.
+/// Callback signature: fn(env_ptr, element) -> result, described by `kind`
+#[no_mangle]
+pub extern "C" fn haxe_list_map(
+    list: *const HaxeList,
+    fn_ptr: usize,
+    env_ptr: *mut u8,
+    kind: i32,
+) -> *mut HaxeList {
+    let Some(list) = (unsafe { list_ref(list) }).filter(|_| fn_ptr != 0) else {
+        return haxe_list_new();
+    };
+    HaxeList::from_slots(
+        list.items
+            .iter()
+            .map(|&item| unsafe { call_unary(fn_ptr, env_ptr, item, kind) }),
+    )
+}
+
+// ============================================================================
+// Conversion
+// ============================================================================
+
+/// Copy the list's elements into a new HaxeArray (8-byte slots).
+///
+/// Used by for-in loops and `Lambda` functions, which iterate lists as arrays.
+#[no_mangle]
+pub extern "C" fn haxe_list_to_array(list: *const HaxeList) -> *mut HaxeArray {
+    unsafe {
+        let arr = alloc(Layout::new::<HaxeArray>()) as *mut HaxeArray;
+        haxe_array_new(arr, 8);
+        if let Some(list) = list_ref(list) {
+            for &item in &list.items {
+                haxe_array_push_i64(arr, item);
+            }
+        }
+        arr
+    }
+}
+
+/// Create a new list holding the elements of a HaxeArray.
+#[no_mangle]
+pub extern "C" fn haxe_list_from_array(arr: *const HaxeArray) -> *mut HaxeList {
+    let Some(arr) = (unsafe { arr.as_ref() }) else {
+        return haxe_list_new();
+    };
+    HaxeList::from_slots((0..arr.len).map(|i| unsafe { read_slot(arr, i) }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::haxe_array::{CALLBACK_F64_RESULT, CALLBACK_I32_RESULT};
+
+    extern "C" fn is_even(_env: *mut u8, x: i64) -> i64 {
+        (x % 2 == 0) as i64
+    }
+
+    extern "C" fn halve(_env: *mut u8, x: i64) -> f64 {
+        x as f64 / 2.0
+    }
+
+    fn items(list: *const HaxeList) -> Vec<i64> {
+        unsafe { (*list).items.iter().copied().collect() }
+    }
+
+    #[test]
+    fn test_list_queue_operations() {
+        let list = haxe_list_new();
+        assert!(haxe_list_is_empty(list));
+        assert_eq!(haxe_list_pop(list), 0);
+
+        haxe_list_add(list, 2);
+        haxe_list_add(list, 3);
+        haxe_list_push(list, 1);
+        assert_eq!(items(list), vec![1, 2, 3]);
+        assert_eq!(haxe_list_length(list), 3);
+        assert_eq!(haxe_list_first(list), 1);
+        assert_eq!(haxe_list_last(list), 3);
+
+        assert_eq!(haxe_list_pop(list), 1);
+        assert!(haxe_list_remove(list, 3));
+        assert!(!haxe_list_remove(list, 3));
+        assert_eq!(items(list), vec![2]);
+
+        haxe_list_clear(list);
+        assert!(haxe_list_is_empty(list));
+        assert_eq!(haxe_list_length(std::ptr::null()), 0);
+    }
+
+    #[test]
+    fn test_list_remove_float_numerically() {
+        let list = haxe_list_new();
+        haxe_list_add(list, (-0.0f64).to_bits());
+        assert!(!haxe_list_remove(list, 0.0f64.to_bits()));
+        assert!(haxe_list_remove_typed(list, 0.0f64.to_bits(), TAG_FLOAT));
+        assert!(haxe_list_is_empty(list));
+    }
+
+    #[test]
+    fn test_list_filter_map_and_arrays() {
+        let list = haxe_list_new();
+        for i in 1..=4 {
+            haxe_list_add(list, i);
+        }
+
+        let evens = haxe_list_filter(list, is_even as *const () as usize, std::ptr::null_mut(), 0);
+        assert_eq!(items(evens), vec![2, 4]);
+
+        let halves = haxe_list_map(
+            list,
+            halve as *const () as usize,
+            std::ptr::null_mut(),
+            CALLBACK_F64_RESULT,
+        );
+        let halves: Vec<f64> = items(halves)
+            .into_iter()
+            .map(|bits| f64::from_bits(bits as u64))
+            .collect();
+        assert_eq!(halves, vec![0.5, 1.0, 1.5, 2.0]);
+
+        let flags = haxe_list_map(
+            list,
+            is_even as *const () as usize,
+            std::ptr::null_mut(),
+            CALLBACK_I32_RESULT,
+        );
+        assert_eq!(items(flags), vec![0, 1, 0, 1]);
+
+        let arr = haxe_list_to_array(list);
+        assert_eq!(unsafe { (*arr).len }, 4);
+        let copy = haxe_list_from_array(arr);
+        assert_eq!(items(copy), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_list_formatting() {
+        let list = haxe_list_new();
+        haxe_list_add(list, 1);
+        haxe_list_add(list, (-2i64) as u64);
+        let s = haxe_list_to_string(list, TAG_INT);
+        let text = unsafe { std::slice::from_raw_parts((*s).ptr, (*s).len) };
+        assert_eq!(text, b"{1, -2}");
+    }
+}
@@ -0,0 +1,971 @@
+//! Native `StringMap`, `IntMap` and `ObjectMap` implementations
+//!
+//! `Map<K, V>` lowers to one of three maps chosen by the key type:
+//!
+//! - `haxe_stringmap_*`: String keys, compared by content
+//! - `haxe_intmap_*`: Int keys
+//! - `haxe_objectmap_*`: object keys by identity, enum keys by structure,
+//!   and `WeakMap`
+//!
+//! Values cross the call boundary as raw u64 bits: Int, Float and Bool
+//! values directly, references as pointers. The value type is known at
+//! compile time, so entries carry no type tag and values are never boxed.
+//!
+//! All three store their entries inline in an open-addressing table with
+//! linear probing ([`OpenTable`]). Iteration follows slot order, so keys and
+//! values come out in the same order on every run.
+
+use std::borrow::Borrow;
+use std::hash::{Hash, Hasher};
+
+use crate::haxe_array::{haxe_array_new, haxe_array_push_i64, HaxeArray};
+use crate::haxe_string::HaxeString;
+use crate::haxe_sys::rust_string_to_haxe;
+use std::alloc::{alloc, Layout};
+
+// ============================================================================
+// Open-addressing table
+// ============================================================================
+
+/// Smallest non-empty capacity
+const MIN_CAPACITY: usize = 8;
+
+/// Multiplier of [`KeyHasher`], from FxHash
+const HASH_SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+/// Fast non-cryptographic hasher for map keys. Slots are picked from the
+/// high bits of the hash, which the final multiply mixes best.
+#[derive(Default)]
+struct KeyHasher {
+    hash: u64,
+}
+
+impl KeyHasher {
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(HASH_SEED);
+    }
+}
+
+impl Hasher for KeyHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0u8; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add(i as u64);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add(i as u64);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.add(i as u64);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+fn hash_key<Q: Hash + ?Sized>(key: &Q) -> u64 {
+    let mut hasher = KeyHasher::default();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Hash table with linear probing and backward-shift deletion, so there
+/// are no tombstones and lookups stop at the first empty slot. The
+/// capacity is a power of two and at most 3/4 of it is used.
+#[derive(Clone)]
+pub struct OpenTable<K, V> {
+    slots: Vec<Option<(K, V)>>,
+    len: usize,
+}
+
+impl<K: Hash + Eq, V> Default for OpenTable<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq, V> OpenTable<K, V> {
+    pub fn new() -> Self {
+        OpenTable {
+            slots: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Home slot of `hash`
+    fn home(&self, hash: u64) -> usize {
+        let bits = self.slots.len().trailing_zeros();
+        (hash >> (64 - bits)) as usize
+    }
+
+    fn mask(&self) -> usize {
+        self.slots.len() - 1
+    }
+
+    /// Slot holding `key`, or the empty slot where it would go
+    fn find<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut index = self.home(hash_key(key));
+        loop {
+            match &self.slots[index] {
+                None => return Err(index),
+                Some((k, _)) if k.borrow() == key => return Ok(index),
+                Some(_) => index = (index + 1) & self.mask(),
+            }
+        }
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.len == 0 {
+            return None;
+        }
+        let index = self.find(key).ok()?;
+        self.slots[index].as_ref().map(|(_, v)| v)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Insert or replace; returns the previous value
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if (self.len + 1) * 4 > self.slots.len() * 3 {
+            self.grow();
+        }
+        match self.find(&key) {
+            Ok(index) => self.slots[index]
+                .as_mut()
+                .map(|(_, v)| std::mem::replace(v, value)),
+            Err(index) => {
+                self.slots[index] = Some((key, value));
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.len == 0 {
+            return None;
+        }
+        let index = self.find(key).ok()?;
+        Some(self.remove_at(index))
+    }
+
+    /// Take the entry at `index` and shift the rest of its probe run back
+    /// to close the gap
+    fn remove_at(&mut self, index: usize) -> V {
+        let (_, value) = self.slots[index].take().expect("occupied slot");
+        self.len -= 1;
+
+        let mut hole = index;
+        let mut next = (index + 1) & self.mask();
+        while let Some((key, _)) = &self.slots[next] {
+            // An entry in its home slot starts a new run
+            if self.home(hash_key(key)) == next {
+                break;
+            }
+            self.slots.swap(hole, next);
+            hole = next;
+            next = (next + 1) & self.mask();
+        }
+        value
+    }
+
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.len = 0;
+    }
+
+    /// Keep the entries `keep` returns true for
+    pub fn retain(&mut self, mut keep: impl FnMut(&K, &V) -> bool) {
+        if self.iter().all(|(k, v)| keep(k, v)) {
+            return;
+        }
+        let slots = std::mem::take(&mut self.slots);
+        self.len = 0;
+        for (key, value) in slots.into_iter().flatten() {
+            if keep(&key, &value) {
+                self.insert(key, value);
+            }
+        }
+    }
+
+    fn grow(&mut self) {
+        let capacity = (self.slots.len() * 2).max(MIN_CAPACITY);
+        // Values may be GC pointers, so the slots are scanned under `--gc`
+        let slots = std::mem::replace(
+            &mut self.slots,
+            crate::gc::tracked(|| std::iter::repeat_with(|| None).take(capacity).collect()),
+        );
+        for (key, value) in slots.into_iter().flatten() {
+            if let Err(index) = self.find(&key) {
+                self.slots[index] = Some((key, value));
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.slots.iter().flatten().map(|(k, v)| (k, v))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, v)| v)
+    }
+}
+
+/// Borrow the text of a key; None for null or invalid UTF-8
+unsafe fn key_str<'a>(key: *const HaxeString) -> Option<&'a str> {
+    if key.is_null() {
+        return None;
+    }
+    let key = &*key;
+    if key.ptr.is_null() || key.len == 0 {
+        return Some("");
+    }
+    std::str::from_utf8(std::slice::from_raw_parts(key.ptr, key.len)).ok()
+}
+
+/// New empty HaxeArray of 8-byte elements, filled from `items`
+unsafe fn array_of(items: impl Iterator<Item = i64>) -> *mut HaxeArray {
+    let arr = alloc(Layout::new::<HaxeArray>()) as *mut HaxeArray;
+    haxe_array_new(arr, 8);
+    for item in items {
+        haxe_array_push_i64(arr, item);
+    }
+    arr
+}
+
+// ============================================================================
+// StringMap<T> (haxe.ds.StringMap)
+// ============================================================================
+
+/// StringMap with inline 8-byte value storage
+#[repr(C)]
+pub struct HaxeStringMap {
+    map: OpenTable<String, u64>,
+}
+
+/// Create a new StringMap
+#[no_mangle]
+pub extern "C" fn haxe_stringmap_new() -> *mut HaxeStringMap {
+    Box::into_raw(Box::new(HaxeStringMap {
+        map: OpenTable::new(),
+    }))
+}
+
+/// Set a value in the StringMap
+/// Value is passed as raw u64 bits (compiler handles type conversion)
+#[no_mangle]
+pub extern "C" fn haxe_stringmap_set(
+    map_ptr: *mut HaxeStringMap,
+    key: *const HaxeString,
+    value: u64,
+) {
+    if map_ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let map = &mut *map_ptr;
+        if let Some(key) = key_str(key) {
+            map.map.insert(key.to_string(), value);
+        }
+    }
+}
+
+/// Get a value from the StringMap
+/// Returns raw u64 bits (compiler handles type conversion)
+/// Returns 0 if key doesn't exist (caller should use exists() to distinguish)
+#[no_mangle]
+pub extern "C" fn haxe_stringmap_get(map_ptr: *mut HaxeStringMap, key: *const HaxeString) -> u64 {
+    if map_ptr.is_null() {
+        return 0;
+    }
+    unsafe {
+        let map = &*map_ptr;
+        key_str(key)
+            .and_then(|key| map.map.get(key).copied())
+            .unwrap_or(0)
+    }
+}
+
+/// Check if a key exists in the StringMap
+#[no_mangle]
+pub extern "C" fn haxe_stringmap_exists(
+    map_ptr: *mut HaxeStringMap,
+    key: *const HaxeString,
+) -> bool {
+    if map_ptr.is_null() {
+        return false;
+    }
+    unsafe {
+        let map = &*map_ptr;
+        key_str(key).is_some_and(|key| map.map.contains_key(key))
+    }
+}
+
+/// Remove a key from the StringMap
+/// Returns true if the key existed and was removed
+#[no_mangle]
+pub extern "C" fn haxe_stringmap_remove(
+    map_ptr: *mut HaxeStringMap,
+    key: *const HaxeString,
+) -> bool {
+    if map_ptr.is_null() {
+        return false;
+    }
+    unsafe {
+        let map = &mut *map_ptr;
+        key_str(key).is_some_and(|key| map.map.remove(key).is_some())
+    }
+}
+
+/// Clear all entries from the StringMap
+#[no_mangle]
+pub extern "C" fn haxe_stringmap_clear(map_ptr: *mut HaxeStringMap) {
+    if map_ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let map = &mut *map_ptr;
+        map.map.clear();
+    }
+}
+
+/// Get the number of entries in the map
+#[no_mangle]
+pub extern "C" fn haxe_stringmap_count(map_ptr: *mut HaxeStringMap) -> i64 {
+    if map_ptr.is_null() {
+        return 0;
+    }
+    unsafe {
+        let map = &*map_ptr;
+        map.map.len() as i64
+    }
+}
+
+/// Get all keys as an array
+/// Returns pointer to array of HaxeString pointers, sets out_len to count
+#[no_mangle]
+pub extern "C" fn haxe_stringmap_keys(
+    map_ptr: *mut HaxeStringMap,
+    out_len: *mut i64,
+) -> *mut *mut HaxeString {
+    if map_ptr.is_null() || out_len.is_null() {
+        if !out_len.is_null() {
+            unsafe {
+                *out_len = 0;
+            }
+        }
+        return std::ptr::null_mut();
+    }
+    unsafe {
+        let map = &*map_ptr;
+        let keys: Vec<*mut HaxeString> = map
+            .map
+            .keys()
+            .map(|k| rust_string_to_haxe(k.clone()))
+            .collect();
+        *out_len = keys.len() as i64;
+        Box::into_raw(keys.into_boxed_slice()) as *mut *mut HaxeString
+    }
+}
+
+/// Convert StringMap to string representation
+#[no_mangle]
+pub extern "C" fn haxe_stringmap_to_string(map_ptr: *mut HaxeStringMap) -> *mut HaxeString {
+    if map_ptr.is_null() {
+        return rust_string_to_haxe("{}".to_string());
+    }
+    unsafe {
+        let map = &*map_ptr;
+        let entries: Vec<String> = map
+            .map
+            .iter()
+            .map(|(k, v)| format!("{} => {}", k, v))
+            .collect();
+        let result = format!("{{{}}}", entries.join(", "));
+        rust_string_to_haxe(result)
+    }
+}
+
+/// Get StringMap keys as a HaxeArray of HaxeString pointers.
+/// Returns a pointer to a heap-allocated HaxeArray with elem_size=8 (pointer-sized elements).
+#[no_mangle]
+pub extern "C" fn haxe_stringmap_keys_to_array(map_ptr: *mut HaxeStringMap) -> *mut HaxeArray {
+    unsafe {
+        if map_ptr.is_null() {
+            return array_of(std::iter::empty());
+        }
+        let map = &*map_ptr;
+        array_of(
+            map.map
+                .keys()
+                .map(|key| rust_string_to_haxe(key.clone()) as i64),
+        )
+    }
+}
+
+/// Get StringMap values as a HaxeArray of u64 raw values.
+/// Returns a pointer to a heap-allocated HaxeArray with elem_size=8.
+#[no_mangle]
+pub extern "C" fn haxe_stringmap_values_to_array(map_ptr: *mut HaxeStringMap) -> *mut HaxeArray {
+    unsafe {
+        if map_ptr.is_null() {
+            return array_of(std::iter::empty());
+        }
+        let map = &*map_ptr;
+        array_of(map.map.values().map(|&val| val as i64))
+    }
+}
+
+// ============================================================================
+// IntMap<T> (haxe.ds.IntMap)
+// ============================================================================
+//
+// Same design as StringMap - values stored as raw u64 bits.
+
+/// IntMap with inline 8-byte value storage
+#[repr(C)]
+pub struct HaxeIntMap {
+    map: OpenTable<i64, u64>,
+}
+
+/// Create a new IntMap
+#[no_mangle]
+pub extern "C" fn haxe_intmap_new() -> *mut HaxeIntMap {
+    Box::into_raw(Box::new(HaxeIntMap {
+        map: OpenTable::new(),
+    }))
+}
+
+/// Set a value in the IntMap
+/// Value is passed as raw u64 bits
+#[no_mangle]
+pub extern "C" fn haxe_intmap_set(map_ptr: *mut HaxeIntMap, key: i64, value: u64) {
+    if map_ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let map = &mut *map_ptr;
+        map.map.insert(key, value);
+    }
+}
+
+/// Get a value from the IntMap
+/// Returns raw u64 bits, 0 if key doesn't exist
+#[no_mangle]
+pub extern "C" fn haxe_intmap_get(map_ptr: *mut HaxeIntMap, key: i64) -> u64 {
+    if map_ptr.is_null() {
+        return 0;
+    }
+    unsafe {
+        let map = &*map_ptr;
+        map.map.get(&key).copied().unwrap_or(0)
+    }
+}
+
+/// Check if a key exists in the IntMap
+#[no_mangle]
+pub extern "C" fn haxe_intmap_exists(map_ptr: *mut HaxeIntMap, key: i64) -> bool {
+    if map_ptr.is_null() {
+        return false;
+    }
+    unsafe {
+        let map = &*map_ptr;
+        map.map.contains_key(&key)
+    }
+}
+
+/// Remove a key from the IntMap
+/// Returns true if the key existed and was removed
+#[no_mangle]
+pub extern "C" fn haxe_intmap_remove(map_ptr: *mut HaxeIntMap, key: i64) -> bool {
+    if map_ptr.is_null() {
+        return false;
+    }
+    unsafe {
+        let map = &mut *map_ptr;
+        map.map.remove(&key).is_some()
+    }
+}
+
+/// Clear all entries from the IntMap
+#[no_mangle]
+pub extern "C" fn haxe_intmap_clear(map_ptr: *mut HaxeIntMap) {
+    if map_ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let map = &mut *map_ptr;
+        map.map.clear();
+    }
+}
+
+/// Get the number of entries in the map
+#[no_mangle]
+pub extern "C" fn haxe_intmap_count(map_ptr: *mut HaxeIntMap) -> i64 {
+    if map_ptr.is_null() {
+        return 0;
+    }
+    unsafe {
+        let map = &*map_ptr;
+        map.map.len() as i64
+    }
+}
+
+/// Get all keys as an array
+/// Returns pointer to array of i64, sets out_len to count
+#[no_mangle]
+pub extern "C" fn haxe_intmap_keys(map_ptr: *mut HaxeIntMap, out_len: *mut i64) -> *mut i64 {
+    if map_ptr.is_null() || out_len.is_null() {
+        if !out_len.is_null() {
+            unsafe {
+                *out_len = 0;
+            }
+        }
+        return std::ptr::null_mut();
+    }
+    unsafe {
+        let map = &*map_ptr;
+        let keys: Vec<i64> = map.map.keys().copied().collect();
+        *out_len = keys.len() as i64;
+        Box::into_raw(keys.into_boxed_slice()) as *mut i64
+    }
+}
+
+/// Convert IntMap to string representation
+#[no_mangle]
+pub extern "C" fn haxe_intmap_to_string(map_ptr: *mut HaxeIntMap) -> *mut HaxeString {
+    if map_ptr.is_null() {
+        return rust_string_to_haxe("{}".to_string());
+    }
+    unsafe {
+        let map = &*map_ptr;
+        let entries: Vec<String> = map
+            .map
+            .iter()
+            .map(|(k, v)| format!("{} => {}", k, v))
+            .collect();
+        let result = format!("{{{}}}", entries.join(", "));
+        rust_string_to_haxe(result)
+    }
+}
+
+/// Get IntMap keys as a HaxeArray of i64 values.
+/// Returns a pointer to a heap-allocated HaxeArray with elem_size=8.
+#[no_mangle]
+pub extern "C" fn haxe_intmap_keys_to_array(map_ptr: *mut HaxeIntMap) -> *mut HaxeArray {
+    unsafe {
+        if map_ptr.is_null() {
+            return array_of(std::iter::empty());
+        }
+        let map = &*map_ptr;
+        array_of(map.map.keys().copied())
+    }
+}
+
+/// Get IntMap values as a HaxeArray of u64 raw values.
+/// Returns a pointer to a heap-allocated HaxeArray with elem_size=8.
+#[no_mangle]
+pub extern "C" fn haxe_intmap_values_to_array(map_ptr: *mut HaxeIntMap) -> *mut HaxeArray {
+    unsafe {
+        if map_ptr.is_null() {
+            return array_of(std::iter::empty());
+        }
+        let map = &*map_ptr;
+        array_of(map.map.values().map(|&val| val as i64))
+    }
+}
+
+// ============================================================================
+// ObjectMap<K:{}, V> / WeakMap<K:{}, V> — maps objects to values by identity
+// Keys are object pointers passed as raw u64. Entries are hashed by the key's
+// identity id (see `object_id`), so an object allocated at the address of a
+// freed key never aliases the old entry. ObjectMap keys are strong: the
+// caller keeps them alive. WeakMap shares the representation but drops the
+// entries of keys whose storage has been released.
+//
+// Maps with enum keys (`Map<SomeEnum, V>`, created by `haxe_enummap_new`) use
+// the same representation and functions, but compare and hash keys by
+// structure (see `enum_value`).
+// ============================================================================
+
+/// Hash key of an ObjectMap entry.
+#[derive(Clone, Copy)]
+enum ObjectKey {
+    /// Identity id of an object key
+    Identity(u64),
+    /// Enum value of the given enum type, compared by structure
+    Enum(u32, u64),
+}
+
+impl PartialEq for ObjectKey {
+    fn eq(&self, other: &Self) -> bool {
+        match (*self, *other) {
+            (ObjectKey::Identity(a), ObjectKey::Identity(b)) => a == b,
+            (ObjectKey::Enum(ta, a), ObjectKey::Enum(tb, b)) => {
+                ta == tb && unsafe { crate::enum_value::equals(ta, a, b) }
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ObjectKey {}
+
+impl Hash for ObjectKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            ObjectKey::Identity(id) => id.hash(state),
+            ObjectKey::Enum(type_id, value) => {
+                unsafe { crate::enum_value::hash(type_id, value) }.hash(state)
+            }
+        }
+    }
+}
+
+#[repr(C)]
+pub struct HaxeObjectMap {
+    /// Key -> (key bits, value)
+    map: OpenTable<ObjectKey, (u64, u64)>,
+    weak: bool,
+    /// Runtime type id of the key enum, for maps with enum keys
+    enum_type: Option<u32>,
+}
+
+impl HaxeObjectMap {
+    fn new_raw(weak: bool, enum_type: Option<u32>) -> *mut HaxeObjectMap {
+        Box::into_raw(Box::new(HaxeObjectMap {
+            map: OpenTable::new(),
+            weak,
+            enum_type,
+        }))
+    }
+
+    /// Drop the entries of released keys (weak maps only).
+    fn purge(&mut self) {
+        if self.weak {
+            self.map.retain(|key, &(bits, _)| match *key {
+                ObjectKey::Identity(id) => crate::object_id::is_live(bits as usize, id),
+                ObjectKey::Enum(..) => true,
+            });
+        }
+    }
+
+    /// Key for looking up `key`; None if it can't be in the map.
+    fn lookup_key(&self, key: u64) -> Option<ObjectKey> {
+        match self.enum_type {
+            Some(type_id) => Some(ObjectKey::Enum(type_id, key)),
+            None => crate::object_id::existing_id(key as usize).map(ObjectKey::Identity),
+        }
+    }
+
+    /// Key for inserting `key`, assigning an identity id if needed.
+    fn insert_key(&self, key: u64) -> ObjectKey {
+        match self.enum_type {
+            Some(type_id) => ObjectKey::Enum(type_id, key),
+            None => ObjectKey::Identity(crate::object_id::id_of(key as usize)),
+        }
+    }
+
+    fn entry(&self, key: u64) -> Option<&(u64, u64)> {
+        self.map.get(&self.lookup_key(key)?)
+    }
+}
+
+/// Create a new ObjectMap
+#[no_mangle]
+pub extern "C" fn haxe_objectmap_new() -> *mut HaxeObjectMap {
+    HaxeObjectMap::new_raw(false, None)
+}
+
+/// Create a new WeakMap
+///
+/// WeakMaps use the `haxe_objectmap_*` functions for every other operation.
+#[no_mangle]
+pub extern "C" fn haxe_weakmap_new() -> *mut HaxeObjectMap {
+    HaxeObjectMap::new_raw(true, None)
+}
+
+/// Create a new map keyed by values of the enum `type_id`
+///
+/// Keys are raw enum values compared by structure. Enum-keyed maps use the
+/// `haxe_objectmap_*` functions for every other operation.
+#[no_mangle]
+pub extern "C" fn haxe_enummap_new(type_id: u32) -> *mut HaxeObjectMap {
+    HaxeObjectMap::new_raw(false, Some(type_id))
+}
+
+/// Set a value in the ObjectMap
+/// Key is an object pointer cast to u64, value is raw u64 bits
+#[no_mangle]
+pub extern "C" fn haxe_objectmap_set(map_ptr: *mut HaxeObjectMap, key: u64, value: u64) {
+    if map_ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let map = &mut *map_ptr;
+        // Constructor index 0 is a valid enum key; 0 is only null for objects
+        if key == 0 && map.enum_type.is_none() {
+            return;
+        }
+        let map_key = map.insert_key(key);
+        map.map.insert(map_key, (key, value));
+    }
+}
+
+/// Get a value from the ObjectMap
+/// Returns raw u64 bits, 0 if key doesn't exist
+#[no_mangle]
+pub extern "C" fn haxe_objectmap_get(map_ptr: *mut HaxeObjectMap, key: u64) -> u64 {
+    if map_ptr.is_null() {
+        return 0;
+    }
+    unsafe {
+        let map = &*map_ptr;
+        map.entry(key).map_or(0, |&(_, value)| value)
+    }
+}
+
+/// Check if a key exists in the ObjectMap
+#[no_mangle]
+pub extern "C" fn haxe_objectmap_exists(map_ptr: *mut HaxeObjectMap, key: u64) -> bool {
+    if map_ptr.is_null() {
+        return false;
+    }
+    unsafe {
+        let map = &*map_ptr;
+        map.entry(key).is_some()
+    }
+}
+
+/// Remove a key from the ObjectMap
+/// Returns true if the key existed and was removed
+#[no_mangle]
+pub extern "C" fn haxe_objectmap_remove(map_ptr: *mut HaxeObjectMap, key: u64) -> bool {
+    if map_ptr.is_null() {
+        return false;
+    }
+    unsafe {
+        let map = &mut *map_ptr;
+        match map.lookup_key(key) {
+            Some(map_key) => map.map.remove(&map_key).is_some(),
+            None => false,
+        }
+    }
+}
+
+/// Clear all entries from the ObjectMap
+#[no_mangle]
+pub extern "C" fn haxe_objectmap_clear(map_ptr: *mut HaxeObjectMap) {
+    if map_ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let map = &mut *map_ptr;
+        map.map.clear();
+    }
+}
+
+/// Get the number of entries in the ObjectMap
+#[no_mangle]
+pub extern "C" fn haxe_objectmap_count(map_ptr: *mut HaxeObjectMap) -> i64 {
+    if map_ptr.is_null() {
+        return 0;
+    }
+    unsafe {
+        let map = &mut *map_ptr;
+        map.purge();
+        map.map.len() as i64
+    }
+}
+
+/// Get all keys as a HaxeArray of u64 pointer values
+#[no_mangle]
+pub extern "C" fn haxe_objectmap_keys_to_array(map_ptr: *mut HaxeObjectMap) -> *mut HaxeArray {
+    unsafe {
+        if map_ptr.is_null() {
+            return array_of(std::iter::empty());
+        }
+        let map = &mut *map_ptr;
+        map.purge();
+        array_of(map.map.values().map(|&(key, _)| key as i64))
+    }
+}
+
+/// Get all values as a HaxeArray of raw u64 values
+#[no_mangle]
+pub extern "C" fn haxe_objectmap_values_to_array(map_ptr: *mut HaxeObjectMap) -> *mut HaxeArray {
+    unsafe {
+        if map_ptr.is_null() {
+            return array_of(std::iter::empty());
+        }
+        let map = &mut *map_ptr;
+        map.purge();
+        array_of(map.map.values().map(|&(_, val)| val as i64))
+    }
+}
+
+/// Convert ObjectMap to string representation
+#[no_mangle]
+pub extern "C" fn haxe_objectmap_to_string(map_ptr: *mut HaxeObjectMap) -> *mut HaxeString {
+    if map_ptr.is_null() {
+        return rust_string_to_haxe("null".to_string());
+    }
+    unsafe {
+        let map = &mut *map_ptr;
+        map.purge();
+        let mut result = String::from("{");
+        for (i, (key, value)) in map.map.values().enumerate() {
+            if i > 0 {
+                result.push_str(", ");
+            }
+            result.push_str(&format!("0x{:x} => {}", key, value));
+        }
+        result.push('}');
+        rust_string_to_haxe(result)
+    }
+}
+
+/// Shallow copy of the ObjectMap (a WeakMap copy is also weak)
+#[no_mangle]
+pub extern "C" fn haxe_objectmap_copy(map_ptr: *mut HaxeObjectMap) -> *mut HaxeObjectMap {
+    if map_ptr.is_null() {
+        return haxe_objectmap_new();
+    }
+    unsafe {
+        let map = &mut *map_ptr;
+        map.purge();
+        Box::into_raw(Box::new(HaxeObjectMap {
+            map: crate::gc::tracked(|| map.map.clone()),
+            weak: map.weak,
+            enum_type: map.enum_type,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_table_insert_get_remove() {
+        let mut table = OpenTable::new();
+        for i in 0..1000i64 {
+            assert_eq!(table.insert(i * 8, i), None);
+        }
+        assert_eq!(table.len(), 1000);
+        assert_eq!(table.insert(16, -2), Some(2));
+        assert_eq!(table.get(&16), Some(&-2));
+
+        // Removing every other key keeps the rest reachable past the gaps
+        for i in (0..1000i64).step_by(2) {
+            assert!(table.remove(&(i * 8)).is_some());
+        }
+        assert_eq!(table.len(), 500);
+        for i in 0..1000i64 {
+            assert_eq!(table.contains_key(&(i * 8)), i % 2 == 1, "key {}", i * 8);
+        }
+        assert_eq!(table.remove(&0), None);
+
+        table.retain(|&k, _| k % 3 == 0);
+        assert!(table.keys().all(|k| k % 3 == 0));
+        assert_eq!(
+            table.len(),
+            (0..1000).filter(|i| i % 2 == 1 && (i * 8) % 3 == 0).count()
+        );
+
+        table.clear();
+        assert!(table.is_empty());
+        assert_eq!(table.get(&24), None);
+    }
+
+    #[test]
+    fn test_stringmap() {
+        let key = |s: &str| rust_string_to_haxe(s.to_string());
+        let map = haxe_stringmap_new();
+        haxe_stringmap_set(map, key("one"), 1);
+        haxe_stringmap_set(map, key("two"), 2);
+        haxe_stringmap_set(map, key(""), 3);
+        haxe_stringmap_set(map, key("one"), 11);
+
+        assert_eq!(haxe_stringmap_get(map, key("one")), 11);
+        assert_eq!(haxe_stringmap_get(map, key("")), 3);
+        assert_eq!(haxe_stringmap_get(map, key("three")), 0);
+        assert_eq!(haxe_stringmap_get(map, std::ptr::null()), 0);
+        assert_eq!(haxe_stringmap_count(map), 3);
+        assert!(haxe_stringmap_remove(map, key("two")));
+        assert!(!haxe_stringmap_exists(map, key("two")));
+        assert_eq!(haxe_stringmap_count(map), 2);
+
+        unsafe { drop(Box::from_raw(map)) };
+    }
+
+    #[test]
+    fn test_intmap_keys_in_stable_order() {
+        let first = haxe_intmap_new();
+        let second = haxe_intmap_new();
+        for key in [42, -7, 0, 1 << 40, 5] {
+            haxe_intmap_set(first, key, key as u64);
+        }
+        for key in [5, 1 << 40, 0, -7, 42] {
+            haxe_intmap_set(second, key, key as u64);
+        }
+        unsafe {
+            let keys = |map: *mut HaxeIntMap| (*map).map.keys().copied().collect::<Vec<_>>();
+            assert_eq!(keys(first), keys(second));
+            drop(Box::from_raw(first));
+            drop(Box::from_raw(second));
+        }
+    }
+}
@@ -42,22 +42,32 @@ pub extern "C" fn haxe_math_max(a: f64, b: f64) -> f64 {
     a.max(b)
 }
 
+// Math.floor/ceil/round are declared with a Float return in the runtime
+// mapping (backends inline them as float instructions); the caller converts
+// the result to Int like any other Float -> Int cast.
+
 /// Floor (round down)
 #[no_mangle]
-pub extern "C" fn haxe_math_floor(x: f64) -> i32 {
-    x.floor() as i32
+pub extern "C" fn haxe_math_floor(x: f64) -> f64 {
+    x.floor()
 }
 
 /// Ceiling (round up)
 #[no_mangle]
-pub extern "C" fn haxe_math_ceil(x: f64) -> i32 {
-    x.ceil() as i32
+pub extern "C" fn haxe_math_ceil(x: f64) -> f64 {
+    x.ceil()
+}
+
+/// Round to nearest integer, ties rounded up (`-0.5` becomes `0`)
+#[no_mangle]
+pub extern "C" fn haxe_math_round(x: f64) -> f64 {
+    (x + 0.5).floor()
 }
 
-/// Round to nearest integer
+/// Float version of `haxe_math_round` (Math.fround)
 #[no_mangle]
-pub extern "C" fn haxe_math_round(x: f64) -> i32 {
-    x.round() as i32
+pub extern "C" fn haxe_math_fround(x: f64) -> f64 {
+    haxe_math_round(x)
 }
 
 // ============================================================================
@@ -319,14 +319,7 @@ pub extern "C" fn haxe_string_index_of(
         let haystack = slice::from_raw_parts(s_ref.ptr, s_ref.len);
         let needle_bytes = slice::from_raw_parts(needle_ref.ptr, needle_ref.len);
 
-        // Simple substring search
-        for i in start..=(s_ref.len.saturating_sub(needle_ref.len)) {
-            if &haystack[i..i + needle_ref.len] == needle_bytes {
-                return i as i32;
-            }
-        }
-
-        -1
+        crate::string_search::find(haystack, needle_bytes, start).map_or(-1, |i| i as i32)
     }
 }
 
@@ -427,18 +420,17 @@ pub extern "C" fn haxe_string_split_array(
             s_ref.len, delim_ref.len
         );
 
-        // Count occurrences
-        let mut count = 1;
-        let mut pos = 0;
-        loop {
-            let idx = haxe_string_index_of(s, delimiter, pos);
-            debug!("[split] index_of from pos={} returned idx={}", pos, idx);
-            if idx < 0 {
-                break;
-            }
-            count += 1;
-            pos = (idx as usize) + delim_ref.len;
-        }
+        // Find all split points in one scan; an empty delimiter leaves the
+        // string whole
+        let split_points = if delim_ref.len == 0 || s_ref.len == 0 {
+            Vec::new()
+        } else {
+            crate::string_search::split_points(
+                slice::from_raw_parts(s_ref.ptr, s_ref.len),
+                slice::from_raw_parts(delim_ref.ptr, delim_ref.len),
+            )
+        };
+        let count = split_points.len() + 1;
         debug!("[split] Final count={}", count);
 
         // Create HaxeArray to hold string pointers as i64
@@ -451,38 +443,21 @@ pub extern "C" fn haxe_string_split_array(
             panic!("Failed to allocate memory for string split array");
         }
 
-        // Fill array with string pointers
-        let mut array_idx = 0;
-        let mut start = 0;
+        // Fill array with string pointers: the part before each split point,
+        // then the remainder
         let i64_ptr = data_ptr as *mut i64;
-
-        loop {
-            let idx = haxe_string_index_of(s, delimiter, start);
-            if idx < 0 {
-                // Last part - allocate and store substring
-                let substring = Box::new(HaxeString {
-                    ptr: ptr::null_mut(),
-                    len: 0,
-                    cap: 0,
-                });
-                let substr_ptr = Box::into_raw(substring);
-                haxe_string_substring(substr_ptr, s, start, s_ref.len);
-                *i64_ptr.add(array_idx) = substr_ptr as i64;
-                break;
-            }
-
-            // Allocate and store substring
+        let mut start = 0;
+        let part_ends = split_points.into_iter().chain(std::iter::once(s_ref.len));
+        for (array_idx, end) in part_ends.enumerate() {
             let substring = Box::new(HaxeString {
                 ptr: ptr::null_mut(),
                 len: 0,
                 cap: 0,
             });
             let substr_ptr = Box::into_raw(substring);
-            haxe_string_substring(substr_ptr, s, start, idx as usize);
+            haxe_string_substring(substr_ptr, s, start, end);
             *i64_ptr.add(array_idx) = substr_ptr as i64;
-
-            array_idx += 1;
-            start = (idx as usize) + delim_ref.len;
+            start = end + delim_ref.len;
         }
 
         // Create and return HaxeArray
@@ -536,8 +511,8 @@ pub extern "C" fn haxe_string_print(s: *const HaxeString) {
         let s_ref = &*s;
         if s_ref.len > 0 {
             let slice = slice::from_raw_parts(s_ref.ptr, s_ref.len);
-            if let Ok(rust_str) = str::from_utf8(slice) {
-                print!("{}", rust_str);
+            if str::from_utf8(slice).is_ok() {
+                crate::stdout::write_stdout(slice);
             }
         }
     }
@@ -546,8 +521,20 @@ pub extern "C" fn haxe_string_print(s: *const HaxeString) {
 /// Print string to stdout with newline
 #[no_mangle]
 pub extern "C" fn haxe_string_println(s: *const HaxeString) {
-    haxe_string_print(s);
-    println!();
+    let mut line = Vec::new();
+    if !s.is_null() {
+        unsafe {
+            let s_ref = &*s;
+            if s_ref.len > 0 {
+                let slice = slice::from_raw_parts(s_ref.ptr, s_ref.len);
+                if str::from_utf8(slice).is_ok() {
+                    line.extend_from_slice(slice);
+                }
+            }
+        }
+    }
+    line.push(b'\n');
+    crate::stdout::write_stdout(&line);
 }
 
 /// Replace all occurrences of `needle` in `haystack` with `replacement`.
@@ -603,6 +590,68 @@ pub extern "C" fn haxe_string_replace(
     }
 }
 
+/// StringTools.lpad: prepend `c` to `s` until it is at least `l` characters
+/// long. The last repetition of `c` is not truncated, so the result may be
+/// longer than `l`. Returns `s` unchanged if `c` is empty.
+#[no_mangle]
+pub extern "C" fn haxe_string_lpad(
+    s: *const HaxeString,
+    c: *const HaxeString,
+    l: i32,
+) -> *mut HaxeString {
+    pad(s, c, l, true)
+}
+
+/// StringTools.rpad: append `c` to `s` until it is at least `l` characters
+/// long. The last repetition of `c` is not truncated, so the result may be
+/// longer than `l`. Returns `s` unchanged if `c` is empty.
+#[no_mangle]
+pub extern "C" fn haxe_string_rpad(
+    s: *const HaxeString,
+    c: *const HaxeString,
+    l: i32,
+) -> *mut HaxeString {
+    pad(s, c, l, false)
+}
+
+/// Shared implementation of lpad/rpad; lengths are counted in characters.
+fn pad(s: *const HaxeString, c: *const HaxeString, l: i32, left: bool) -> *mut HaxeString {
+    let text = unsafe { as_str(s) };
+    let fill = unsafe { as_str(c) };
+
+    let mut padding = String::new();
+    let fill_len = fill.chars().count();
+    if fill_len > 0 {
+        let target = (l.max(0) as usize).saturating_sub(text.chars().count());
+        let mut padded = 0;
+        while padded < target {
+            padding.push_str(fill);
+            padded += fill_len;
+        }
+    }
+
+    let result = if left {
+        padding + text
+    } else {
+        text.to_string() + &padding
+    };
+    let out = Box::into_raw(Box::new(HaxeString {
+        ptr: ptr::null_mut(),
+        len: 0,
+        cap: 0,
+    }));
+    haxe_string_from_bytes(out, result.as_ptr(), result.len());
+    out
+}
+
+/// View a HaxeString as `&str` (empty for null).
+unsafe fn as_str<'a>(s: *const HaxeString) -> &'a str {
+    if s.is_null() || (*s).ptr.is_null() || (*s).len == 0 {
+        return "";
+    }
+    str::from_utf8_unchecked(slice::from_raw_parts((*s).ptr, (*s).len))
+}
+
 /// Get C string pointer (null-terminated)
 #[no_mangle]
 pub extern "C" fn haxe_string_to_cstr(s: *const HaxeString) -> *const u8 {
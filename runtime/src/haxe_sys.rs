@@ -4,7 +4,11 @@
 
 use log::debug;
 use std::cell::RefCell;
-use std::io::{self, Write};
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use crate::stdout::{write_line, write_stdout};
 
 // Use the canonical HaxeString definition from haxe_string module
 use crate::haxe_string::HaxeString;
@@ -34,13 +38,72 @@ pub fn set_trace_prefix(prefix: &str) {
     TRACE_PREFIX.with(|p| *p.borrow_mut() = prefix.to_string());
 }
 
-fn print_with_prefix(msg: &str) {
+// Position of the pending trace() call (`Class.method:line`), set by the
+// compiler-emitted haxe_trace_pos call just before it
+thread_local! {
+    static TRACE_POS: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Whether trace output starts with the call's position, like upstream Haxe.
+/// Off by default; RAYZOR_TRACE_POS=1 turns it on. The compiler only emits
+/// positions when this is on at compile time, so turning it on later shows
+/// nothing for code already compiled.
+fn trace_positions() -> &'static AtomicBool {
+    static ENABLED: OnceLock<AtomicBool> = OnceLock::new();
+    ENABLED.get_or_init(|| {
+        AtomicBool::new(std::env::var_os("RAYZOR_TRACE_POS").is_some_and(|v| v != "0"))
+    })
+}
+
+/// Show (non-zero) or hide (zero) trace positions
+#[no_mangle]
+pub extern "C" fn rayzor_set_trace_positions(enabled: i32) {
+    trace_positions().store(enabled != 0, Ordering::Relaxed);
+}
+
+/// Show or hide trace positions (convenience for Rust callers)
+pub fn set_trace_positions(enabled: bool) {
+    trace_positions().store(enabled, Ordering::Relaxed);
+}
+
+/// Whether trace positions are shown, for the compiler to decide whether to
+/// emit them
+pub fn trace_positions_enabled() -> bool {
+    trace_positions().load(Ordering::Relaxed)
+}
+
+/// Record the position of the next trace() call
+#[no_mangle]
+pub extern "C" fn haxe_trace_pos(pos: *const HaxeString) {
+    if !trace_positions().load(Ordering::Relaxed) {
+        return;
+    }
+    let pos = unsafe { haxe_string_to_rust(pos) };
+    TRACE_POS.with(|p| *p.borrow_mut() = pos);
+}
+
+/// Color trace positions when writing to a terminal (unless NO_COLOR is set)
+fn color_trace_positions() -> bool {
+    static TTY: OnceLock<bool> = OnceLock::new();
+    let tty = *TTY.get_or_init(|| {
+        use std::io::IsTerminal;
+        std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+    });
+    tty && !crate::stdout::is_redirected()
+}
+
+pub(crate) fn print_with_prefix(msg: &str) {
+    crate::crash::record_trace(msg);
+    let pos = TRACE_POS.with(|p| p.borrow_mut().take());
     TRACE_PREFIX.with(|p| {
         let prefix = p.borrow();
-        if prefix.is_empty() {
-            println!("{}", msg);
-        } else {
-            println!("{}{}", *prefix, msg);
+        match pos {
+            Some(pos) if color_trace_positions() => {
+                write_line(&format!("{}\x1b[36m{}\x1b[0m: {}", *prefix, pos, msg))
+            }
+            Some(pos) => write_line(&format!("{}{}: {}", *prefix, pos, msg)),
+            None if prefix.is_empty() => write_line(msg),
+            None => write_line(&format!("{}{}", *prefix, msg)),
         }
     });
 }
@@ -52,28 +115,25 @@ fn print_with_prefix(msg: &str) {
 /// Print integer to stdout
 #[no_mangle]
 pub extern "C" fn haxe_sys_print_int(value: i64) {
-    print!("{}", value);
-    let _ = io::stdout().flush();
+    write_stdout(value.to_string().as_bytes());
 }
 
 /// Print float to stdout
 #[no_mangle]
 pub extern "C" fn haxe_sys_print_float(value: f64) {
-    print!("{}", value);
-    let _ = io::stdout().flush();
+    write_stdout(value.to_string().as_bytes());
 }
 
 /// Print boolean to stdout
 #[no_mangle]
 pub extern "C" fn haxe_sys_print_bool(value: bool) {
-    print!("{}", value);
-    let _ = io::stdout().flush();
+    write_stdout(value.to_string().as_bytes());
 }
 
 /// Print newline
 #[no_mangle]
 pub extern "C" fn haxe_sys_println() {
-    println!();
+    write_stdout(b"\n");
 }
 
 // ============================================================================
@@ -89,7 +149,7 @@ pub extern "C" fn haxe_trace_int(value: i64) {
 /// Trace float value
 #[no_mangle]
 pub extern "C" fn haxe_trace_float(value: f64) {
-    print_with_prefix(&format!("{}", value));
+    print_with_prefix(&crate::number_format::format_float(value));
 }
 
 /// Trace boolean value
@@ -119,7 +179,7 @@ pub extern "C" fn haxe_trace_string(ptr: *const u8, len: usize) {
 #[no_mangle]
 pub extern "C" fn haxe_trace_string_struct(s_ptr: *const HaxeString) {
     if s_ptr.is_null() {
-        println!("null");
+        print_with_prefix("null");
         return;
     }
     unsafe {
@@ -233,7 +293,7 @@ pub extern "C" fn haxe_string_from_int(value: i64) -> *mut HaxeString {
 /// Convert Float to String - returns heap-allocated HaxeString pointer
 #[no_mangle]
 pub extern "C" fn haxe_string_from_float(value: f64) -> *mut HaxeString {
-    let s = value.to_string();
+    let s = crate::number_format::format_float(value);
     let bytes = s.into_bytes();
     let len = bytes.len();
     let cap = bytes.capacity();
@@ -474,13 +534,7 @@ pub extern "C" fn haxe_string_index_of_ptr(
         let haystack = std::slice::from_raw_parts(s_ref.ptr, s_ref.len);
         let needle_bytes = std::slice::from_raw_parts(needle_ref.ptr, needle_ref.len);
 
-        // Simple substring search
-        for i in start..=(s_ref.len.saturating_sub(needle_ref.len)) {
-            if &haystack[i..i + needle_ref.len] == needle_bytes {
-                return i as i32;
-            }
-        }
-        -1
+        crate::string_search::find(haystack, needle_bytes, start).map_or(-1, |i| i as i32)
     }
 }
 
@@ -528,13 +582,7 @@ pub extern "C" fn haxe_string_last_index_of_ptr(
             (start_index as usize).min(max_start)
         };
 
-        // Search backwards
-        for i in (0..=search_start).rev() {
-            if &haystack[i..i + needle_ref.len] == needle_bytes {
-                return i as i32;
-            }
-        }
-        -1
+        crate::string_search::rfind(haystack, needle_bytes, search_start).map_or(-1, |i| i as i32)
     }
 }
 
@@ -791,21 +839,14 @@ pub extern "C" fn haxe_string_split_ptr(
             Box::into_raw(parts.into_boxed_slice()) as *mut *mut HaxeString
         } else {
             let delim_bytes = std::slice::from_raw_parts(delim_ref.ptr, delim_ref.len);
+            let mut split_points =
+                crate::string_search::split_points(haystack, delim_bytes).into_iter();
 
             let mut parts: Vec<*mut HaxeString> = Vec::new();
             let mut start = 0;
 
             while start <= s_ref.len {
-                // Find next occurrence of delimiter
-                let mut found_at = None;
-                for i in start..=(s_ref.len.saturating_sub(delim_ref.len)) {
-                    if &haystack[i..i + delim_ref.len] == delim_bytes {
-                        found_at = Some(i);
-                        break;
-                    }
-                }
-
-                match found_at {
+                match split_points.next() {
                     Some(idx) => {
                         // Add substring before delimiter
                         let part_len = idx - start;
@@ -863,7 +904,10 @@ pub extern "C" fn haxe_sys_exit(code: i32) -> ! {
     std::process::exit(code)
 }
 
-/// Get current time in milliseconds
+/// Wall-clock time in seconds since the Unix epoch (`Sys.time()`).
+///
+/// Follows the system clock, which may jump; use [`haxe_timer_stamp`] to
+/// measure elapsed time.
 #[no_mangle]
 pub extern "C" fn haxe_sys_time() -> f64 {
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -874,12 +918,70 @@ pub extern "C" fn haxe_sys_time() -> f64 {
         .unwrap_or(0.0)
 }
 
+/// Reference point of the monotonic clock, fixed on first use
+fn monotonic_epoch() -> std::time::Instant {
+    static EPOCH: OnceLock<std::time::Instant> = OnceLock::new();
+    *EPOCH.get_or_init(std::time::Instant::now)
+}
+
+/// Monotonic high-resolution clock in nanoseconds.
+///
+/// Never goes backwards and is unaffected by system clock changes; only
+/// differences between two readings are meaningful.
+#[no_mangle]
+pub extern "C" fn rayzor_time_monotonic_ns() -> i64 {
+    monotonic_epoch().elapsed().as_nanos() as i64
+}
+
+/// Monotonic timestamp in seconds (`haxe.Timer.stamp()`)
+#[no_mangle]
+pub extern "C" fn haxe_timer_stamp() -> f64 {
+    monotonic_epoch().elapsed().as_secs_f64()
+}
+
 /// Get command line arguments count
 #[no_mangle]
 pub extern "C" fn haxe_sys_args_count() -> i32 {
     std::env::args().count() as i32
 }
 
+/// Program arguments set by the host (`rayzor run Main.hx -- a b`)
+static PROGRAM_ARGS: std::sync::RwLock<Option<Vec<String>>> = std::sync::RwLock::new(None);
+
+/// Set the arguments `Sys.args()` returns. A JIT host calls this, since the
+/// process arguments are its own; compiled executables use the process
+/// arguments after the program name.
+pub fn set_program_args(args: Vec<String>) {
+    *PROGRAM_ARGS.write().unwrap() = Some(args);
+}
+
+/// Arguments `Sys.args()` returns
+pub fn program_args() -> Vec<String> {
+    match &*PROGRAM_ARGS.read().unwrap() {
+        Some(args) => args.clone(),
+        None => std::env::args().skip(1).collect(),
+    }
+}
+
+/// Sys.args(): Array<String>
+#[no_mangle]
+pub extern "C" fn haxe_sys_args() -> *mut crate::haxe_array::HaxeArray {
+    use crate::haxe_array::{haxe_array_new, haxe_array_push, HaxeArray};
+
+    unsafe {
+        let arr = Box::into_raw(Box::new(std::mem::zeroed::<HaxeArray>()));
+        haxe_array_new(arr, 8);
+        for arg in program_args() {
+            let haxe_str = rust_string_to_haxe(arg);
+            if !haxe_str.is_null() {
+                let str_ptr = haxe_str as u64;
+                haxe_array_push(arr, &str_ptr as *const u64 as *const u8);
+            }
+        }
+        arr
+    }
+}
+
 // ============================================================================
 // Environment Variables
 // ============================================================================
@@ -1045,13 +1147,19 @@ pub extern "C" fn haxe_sys_system_name() -> *mut HaxeString {
 /// Get CPU time for current process (in seconds)
 #[no_mangle]
 pub extern "C" fn haxe_sys_cpu_time() -> f64 {
-    // This is a simplified implementation - full accuracy would require platform-specific code
-    // On Unix, we could use getrusage() for accurate CPU time
-    // On Windows, we could use GetProcessTimes()
-    // For portability, we use a static start time and return elapsed time
-    static START_TIME: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
-    let start = START_TIME.get_or_init(std::time::Instant::now);
-    start.elapsed().as_secs_f64()
+    #[cfg(unix)]
+    {
+        let mut ts = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        // SAFETY: clock_gettime only writes to the timespec we pass
+        if unsafe { libc::clock_gettime(libc::CLOCK_PROCESS_CPUTIME_ID, &mut ts) } == 0 {
+            return ts.tv_sec as f64 + ts.tv_nsec as f64 / 1e9;
+        }
+    }
+    // No process CPU clock: fall back to monotonic time since first use
+    haxe_timer_stamp()
 }
 
 /// Get path to current executable
@@ -1110,7 +1218,7 @@ pub extern "C" fn haxe_sys_get_char(echo: bool) -> i32 {
     match std::io::stdin().read_exact(&mut buffer) {
         Ok(_) => {
             if echo {
-                print!("{}", buffer[0] as char);
+                write_stdout(&buffer);
             }
             buffer[0] as i32
         }
@@ -1136,7 +1244,7 @@ unsafe fn haxe_string_to_rust(s: *const HaxeString) -> Option<String> {
 }
 
 /// Helper to create HaxeString from Rust String
-fn rust_string_to_haxe(s: String) -> *mut HaxeString {
+pub(crate) fn rust_string_to_haxe(s: String) -> *mut HaxeString {
     let bytes = s.into_bytes();
     let len = bytes.len();
     let cap = bytes.capacity();
@@ -2571,588 +2679,92 @@ pub extern "C" fn haxe_bytes_free(bytes: *mut HaxeBytes) {
     }
 }
 
-// ============================================================================
-// StringMap<T> (haxe.ds.StringMap)
-// ============================================================================
-//
-// High-performance StringMap with inline value storage.
-// Values are stored as raw 64-bit values (u64) - no boxing, no heap allocation per value.
-// Type is known at compile time; the runtime stores raw bits.
-//
-// For primitives (Int, Float, Bool): value is stored directly as bits
-// For pointers (String, objects): pointer value is stored as u64
-//
-// This gives us:
-// - No heap allocation per value (values inline in HashMap)
-// - No type tags (type known at compile time)
-// - Cache-friendly layout
-// - Zero-cost abstraction over HashMap<String, u64>
-
-use std::collections::HashMap;
-
-/// High-performance StringMap with inline 8-byte value storage
-/// Values are stored as raw u64 bits - no boxing overhead
-#[repr(C)]
-pub struct HaxeStringMap {
-    map: HashMap<String, u64>,
-}
-
-/// Create a new StringMap
-#[no_mangle]
-pub extern "C" fn haxe_stringmap_new() -> *mut HaxeStringMap {
-    Box::into_raw(Box::new(HaxeStringMap {
-        map: HashMap::new(),
-    }))
-}
-
-/// Set a value in the StringMap
-/// Value is passed as raw u64 bits (compiler handles type conversion)
-#[no_mangle]
-pub extern "C" fn haxe_stringmap_set(
-    map_ptr: *mut HaxeStringMap,
-    key: *const HaxeString,
-    value: u64,
-) {
-    if map_ptr.is_null() {
-        return;
-    }
-    unsafe {
-        let map = &mut *map_ptr;
-        if let Some(key_str) = haxe_string_to_rust(key) {
-            map.map.insert(key_str, value);
-        }
-    }
-}
-
-/// Get a value from the StringMap
-/// Returns raw u64 bits (compiler handles type conversion)
-/// Returns 0 if key doesn't exist (caller should use exists() to distinguish)
-#[no_mangle]
-pub extern "C" fn haxe_stringmap_get(map_ptr: *mut HaxeStringMap, key: *const HaxeString) -> u64 {
-    if map_ptr.is_null() {
-        return 0;
-    }
-    unsafe {
-        let map = &*map_ptr;
-        if let Some(key_str) = haxe_string_to_rust(key) {
-            map.map.get(&key_str).copied().unwrap_or(0)
-        } else {
-            0
-        }
-    }
-}
-
-/// Check if a key exists in the StringMap
-#[no_mangle]
-pub extern "C" fn haxe_stringmap_exists(
-    map_ptr: *mut HaxeStringMap,
-    key: *const HaxeString,
-) -> bool {
-    if map_ptr.is_null() {
-        return false;
-    }
-    unsafe {
-        let map = &*map_ptr;
-        if let Some(key_str) = haxe_string_to_rust(key) {
-            map.map.contains_key(&key_str)
-        } else {
-            false
-        }
-    }
-}
-
-/// Remove a key from the StringMap
-/// Returns true if the key existed and was removed
-#[no_mangle]
-pub extern "C" fn haxe_stringmap_remove(
-    map_ptr: *mut HaxeStringMap,
-    key: *const HaxeString,
-) -> bool {
-    if map_ptr.is_null() {
-        return false;
-    }
-    unsafe {
-        let map = &mut *map_ptr;
-        if let Some(key_str) = haxe_string_to_rust(key) {
-            map.map.remove(&key_str).is_some()
-        } else {
-            false
-        }
-    }
-}
-
-/// Clear all entries from the StringMap
-#[no_mangle]
-pub extern "C" fn haxe_stringmap_clear(map_ptr: *mut HaxeStringMap) {
-    if map_ptr.is_null() {
-        return;
-    }
-    unsafe {
-        let map = &mut *map_ptr;
-        map.map.clear();
-    }
-}
-
-/// Get the number of entries in the map
-#[no_mangle]
-pub extern "C" fn haxe_stringmap_count(map_ptr: *mut HaxeStringMap) -> i64 {
-    if map_ptr.is_null() {
-        return 0;
-    }
-    unsafe {
-        let map = &*map_ptr;
-        map.map.len() as i64
-    }
-}
-
-/// Get all keys as an array
-/// Returns pointer to array of HaxeString pointers, sets out_len to count
-#[no_mangle]
-pub extern "C" fn haxe_stringmap_keys(
-    map_ptr: *mut HaxeStringMap,
-    out_len: *mut i64,
-) -> *mut *mut HaxeString {
-    if map_ptr.is_null() || out_len.is_null() {
-        if !out_len.is_null() {
-            unsafe {
-                *out_len = 0;
-            }
-        }
-        return std::ptr::null_mut();
-    }
-    unsafe {
-        let map = &*map_ptr;
-        let keys: Vec<*mut HaxeString> = map
-            .map
-            .keys()
-            .map(|k| rust_string_to_haxe(k.clone()))
-            .collect();
-        *out_len = keys.len() as i64;
-        Box::into_raw(keys.into_boxed_slice()) as *mut *mut HaxeString
-    }
-}
-
-/// Convert StringMap to string representation
-#[no_mangle]
-pub extern "C" fn haxe_stringmap_to_string(map_ptr: *mut HaxeStringMap) -> *mut HaxeString {
-    if map_ptr.is_null() {
-        return rust_string_to_haxe("{}".to_string());
-    }
-    unsafe {
-        let map = &*map_ptr;
-        let entries: Vec<String> = map
-            .map
-            .iter()
-            .map(|(k, v)| format!("{} => {}", k, v))
-            .collect();
-        let result = format!("{{{}}}", entries.join(", "));
-        rust_string_to_haxe(result)
-    }
-}
-
-// ============================================================================
-// IntMap<T> (haxe.ds.IntMap)
-// ============================================================================
-//
-// High-performance IntMap with inline value storage.
-// Same design as StringMap - values stored as raw u64 bits.
-
-/// High-performance IntMap with inline 8-byte value storage
-#[repr(C)]
-pub struct HaxeIntMap {
-    map: HashMap<i64, u64>,
-}
-
-/// Create a new IntMap
-#[no_mangle]
-pub extern "C" fn haxe_intmap_new() -> *mut HaxeIntMap {
-    Box::into_raw(Box::new(HaxeIntMap {
-        map: HashMap::new(),
-    }))
-}
-
-/// Set a value in the IntMap
-/// Value is passed as raw u64 bits
-#[no_mangle]
-pub extern "C" fn haxe_intmap_set(map_ptr: *mut HaxeIntMap, key: i64, value: u64) {
-    if map_ptr.is_null() {
-        return;
-    }
-    unsafe {
-        let map = &mut *map_ptr;
-        map.map.insert(key, value);
-    }
-}
-
-/// Get a value from the IntMap
-/// Returns raw u64 bits, 0 if key doesn't exist
-#[no_mangle]
-pub extern "C" fn haxe_intmap_get(map_ptr: *mut HaxeIntMap, key: i64) -> u64 {
-    if map_ptr.is_null() {
-        return 0;
-    }
-    unsafe {
-        let map = &*map_ptr;
-        map.map.get(&key).copied().unwrap_or(0)
-    }
-}
-
-/// Check if a key exists in the IntMap
-#[no_mangle]
-pub extern "C" fn haxe_intmap_exists(map_ptr: *mut HaxeIntMap, key: i64) -> bool {
-    if map_ptr.is_null() {
-        return false;
-    }
-    unsafe {
-        let map = &*map_ptr;
-        map.map.contains_key(&key)
-    }
-}
-
-/// Remove a key from the IntMap
-/// Returns true if the key existed and was removed
-#[no_mangle]
-pub extern "C" fn haxe_intmap_remove(map_ptr: *mut HaxeIntMap, key: i64) -> bool {
-    if map_ptr.is_null() {
-        return false;
-    }
-    unsafe {
-        let map = &mut *map_ptr;
-        map.map.remove(&key).is_some()
-    }
-}
-
-/// Clear all entries from the IntMap
-#[no_mangle]
-pub extern "C" fn haxe_intmap_clear(map_ptr: *mut HaxeIntMap) {
-    if map_ptr.is_null() {
-        return;
-    }
-    unsafe {
-        let map = &mut *map_ptr;
-        map.map.clear();
-    }
-}
-
-/// Get the number of entries in the map
-#[no_mangle]
-pub extern "C" fn haxe_intmap_count(map_ptr: *mut HaxeIntMap) -> i64 {
-    if map_ptr.is_null() {
-        return 0;
-    }
-    unsafe {
-        let map = &*map_ptr;
-        map.map.len() as i64
-    }
-}
-
-/// Get all keys as an array
-/// Returns pointer to array of i64, sets out_len to count
-#[no_mangle]
-pub extern "C" fn haxe_intmap_keys(map_ptr: *mut HaxeIntMap, out_len: *mut i64) -> *mut i64 {
-    if map_ptr.is_null() || out_len.is_null() {
-        if !out_len.is_null() {
-            unsafe {
-                *out_len = 0;
-            }
-        }
-        return std::ptr::null_mut();
-    }
-    unsafe {
-        let map = &*map_ptr;
-        let keys: Vec<i64> = map.map.keys().copied().collect();
-        *out_len = keys.len() as i64;
-        Box::into_raw(keys.into_boxed_slice()) as *mut i64
-    }
-}
-
-/// Convert IntMap to string representation
-#[no_mangle]
-pub extern "C" fn haxe_intmap_to_string(map_ptr: *mut HaxeIntMap) -> *mut HaxeString {
-    if map_ptr.is_null() {
-        return rust_string_to_haxe("{}".to_string());
-    }
-    unsafe {
-        let map = &*map_ptr;
-        let entries: Vec<String> = map
-            .map
-            .iter()
-            .map(|(k, v)| format!("{} => {}", k, v))
-            .collect();
-        let result = format!("{{{}}}", entries.join(", "));
-        rust_string_to_haxe(result)
-    }
-}
-
-/// Get StringMap keys as a HaxeArray of HaxeString pointers.
-/// Returns a pointer to a heap-allocated HaxeArray with elem_size=8 (pointer-sized elements).
-#[no_mangle]
-pub extern "C" fn haxe_stringmap_keys_to_array(
-    map_ptr: *mut HaxeStringMap,
-) -> *mut crate::haxe_array::HaxeArray {
-    use crate::haxe_array::HaxeArray;
-    use std::alloc::{alloc, Layout};
-
-    unsafe {
-        let arr = alloc(Layout::new::<HaxeArray>()) as *mut HaxeArray;
-        crate::haxe_array::haxe_array_new(arr, 8); // 8 bytes per element (pointer-sized)
-
-        if !map_ptr.is_null() {
-            let map = &*map_ptr;
-            for key in map.map.keys() {
-                let hs = rust_string_to_haxe(key.clone());
-                let hs_as_i64 = hs as i64;
-                crate::haxe_array::haxe_array_push_i64(arr, hs_as_i64);
-            }
-        }
-
-        arr
-    }
-}
-
-/// Get IntMap keys as a HaxeArray of i64 values.
-/// Returns a pointer to a heap-allocated HaxeArray with elem_size=8.
-#[no_mangle]
-pub extern "C" fn haxe_intmap_keys_to_array(
-    map_ptr: *mut HaxeIntMap,
-) -> *mut crate::haxe_array::HaxeArray {
-    use crate::haxe_array::HaxeArray;
-    use std::alloc::{alloc, Layout};
-
-    unsafe {
-        let arr = alloc(Layout::new::<HaxeArray>()) as *mut HaxeArray;
-        crate::haxe_array::haxe_array_new(arr, 8);
-
-        if !map_ptr.is_null() {
-            let map = &*map_ptr;
-            for &key in map.map.keys() {
-                crate::haxe_array::haxe_array_push_i64(arr, key);
-            }
-        }
-
-        arr
-    }
-}
-
-/// Get StringMap values as a HaxeArray of u64 raw values.
-/// Returns a pointer to a heap-allocated HaxeArray with elem_size=8.
-#[no_mangle]
-pub extern "C" fn haxe_stringmap_values_to_array(
-    map_ptr: *mut HaxeStringMap,
-) -> *mut crate::haxe_array::HaxeArray {
-    use crate::haxe_array::HaxeArray;
-    use std::alloc::{alloc, Layout};
-
-    unsafe {
-        let arr = alloc(Layout::new::<HaxeArray>()) as *mut HaxeArray;
-        crate::haxe_array::haxe_array_new(arr, 8);
-
-        if !map_ptr.is_null() {
-            let map = &*map_ptr;
-            for &val in map.map.values() {
-                crate::haxe_array::haxe_array_push_i64(arr, val as i64);
-            }
-        }
-
-        arr
-    }
-}
-
-/// Get IntMap values as a HaxeArray of u64 raw values.
-/// Returns a pointer to a heap-allocated HaxeArray with elem_size=8.
-#[no_mangle]
-pub extern "C" fn haxe_intmap_values_to_array(
-    map_ptr: *mut HaxeIntMap,
-) -> *mut crate::haxe_array::HaxeArray {
-    use crate::haxe_array::HaxeArray;
-    use std::alloc::{alloc, Layout};
-
-    unsafe {
-        let arr = alloc(Layout::new::<HaxeArray>()) as *mut HaxeArray;
-        crate::haxe_array::haxe_array_new(arr, 8);
-
-        if !map_ptr.is_null() {
-            let map = &*map_ptr;
-            for &val in map.map.values() {
-                crate::haxe_array::haxe_array_push_i64(arr, val as i64);
-            }
-        }
-
-        arr
-    }
-}
-
-// ============================================================================
-// ObjectMap<K:{}, V> — maps object pointers to values by pointer identity
-// Keys are stored as raw u64 (pointer address). Class instances are safe
-// because escaped pointers aren't freed by InsertFreePass. For anonymous
-// objects (Arc-based), the caller must keep their own reference alive.
-// ============================================================================
-
-#[repr(C)]
-pub struct HaxeObjectMap {
-    map: HashMap<u64, u64>,
-}
-
-/// Create a new ObjectMap
-#[no_mangle]
-pub extern "C" fn haxe_objectmap_new() -> *mut HaxeObjectMap {
-    Box::into_raw(Box::new(HaxeObjectMap {
-        map: HashMap::new(),
-    }))
-}
-
-/// Set a value in the ObjectMap
-/// Key is an object pointer cast to u64, value is raw u64 bits
-#[no_mangle]
-pub extern "C" fn haxe_objectmap_set(map_ptr: *mut HaxeObjectMap, key: u64, value: u64) {
-    if map_ptr.is_null() {
-        return;
-    }
-    unsafe {
-        let map = &mut *map_ptr;
-        map.map.insert(key, value);
-    }
-}
-
-/// Get a value from the ObjectMap
-/// Returns raw u64 bits, 0 if key doesn't exist
-#[no_mangle]
-pub extern "C" fn haxe_objectmap_get(map_ptr: *mut HaxeObjectMap, key: u64) -> u64 {
-    if map_ptr.is_null() {
-        return 0;
-    }
-    unsafe {
-        let map = &*map_ptr;
-        map.map.get(&key).copied().unwrap_or(0)
-    }
-}
-
-/// Check if a key exists in the ObjectMap
-#[no_mangle]
-pub extern "C" fn haxe_objectmap_exists(map_ptr: *mut HaxeObjectMap, key: u64) -> bool {
-    if map_ptr.is_null() {
-        return false;
-    }
-    unsafe {
-        let map = &*map_ptr;
-        map.map.contains_key(&key)
-    }
-}
-
-/// Remove a key from the ObjectMap
-/// Returns true if the key existed and was removed
-#[no_mangle]
-pub extern "C" fn haxe_objectmap_remove(map_ptr: *mut HaxeObjectMap, key: u64) -> bool {
-    if map_ptr.is_null() {
-        return false;
-    }
-    unsafe {
-        let map = &mut *map_ptr;
-        map.map.remove(&key).is_some()
-    }
-}
-
-/// Clear all entries from the ObjectMap
-#[no_mangle]
-pub extern "C" fn haxe_objectmap_clear(map_ptr: *mut HaxeObjectMap) {
-    if map_ptr.is_null() {
-        return;
-    }
-    unsafe {
-        let map = &mut *map_ptr;
-        map.map.clear();
-    }
-}
-
-/// Get the number of entries in the ObjectMap
-#[no_mangle]
-pub extern "C" fn haxe_objectmap_count(map_ptr: *mut HaxeObjectMap) -> i64 {
-    if map_ptr.is_null() {
-        return 0;
-    }
-    unsafe {
-        let map = &*map_ptr;
-        map.map.len() as i64
-    }
-}
-
-/// Get all keys as a HaxeArray of u64 pointer values
-#[no_mangle]
-pub extern "C" fn haxe_objectmap_keys_to_array(
-    map_ptr: *mut HaxeObjectMap,
-) -> *mut crate::haxe_array::HaxeArray {
-    use crate::haxe_array::HaxeArray;
-    use std::alloc::{alloc, Layout};
-
-    unsafe {
-        let arr = alloc(Layout::new::<HaxeArray>()) as *mut HaxeArray;
-        crate::haxe_array::haxe_array_new(arr, 8);
-
-        if !map_ptr.is_null() {
-            let map = &*map_ptr;
-            for &key in map.map.keys() {
-                crate::haxe_array::haxe_array_push_i64(arr, key as i64);
-            }
-        }
-
-        arr
-    }
-}
-
-/// Get all values as a HaxeArray of raw u64 values
-#[no_mangle]
-pub extern "C" fn haxe_objectmap_values_to_array(
-    map_ptr: *mut HaxeObjectMap,
-) -> *mut crate::haxe_array::HaxeArray {
-    use crate::haxe_array::HaxeArray;
-    use std::alloc::{alloc, Layout};
-
-    unsafe {
-        let arr = alloc(Layout::new::<HaxeArray>()) as *mut HaxeArray;
-        crate::haxe_array::haxe_array_new(arr, 8);
-
-        if !map_ptr.is_null() {
-            let map = &*map_ptr;
-            for &val in map.map.values() {
-                crate::haxe_array::haxe_array_push_i64(arr, val as i64);
-            }
-        }
-
-        arr
-    }
-}
-
-/// Convert ObjectMap to string representation
-#[no_mangle]
-pub extern "C" fn haxe_objectmap_to_string(map_ptr: *mut HaxeObjectMap) -> *mut HaxeString {
-    if map_ptr.is_null() {
-        return rust_string_to_haxe("null".to_string());
-    }
-    unsafe {
-        let map = &*map_ptr;
-        let mut result = String::from("{");
-        for (i, (key, value)) in map.map.iter().enumerate() {
-            if i > 0 {
-                result.push_str(", ");
-            }
-            result.push_str(&format!("0x{:x} => {}", key, value));
-        }
-        result.push('}');
-        rust_string_to_haxe(result)
-    }
-}
-
-/// Shallow copy of the ObjectMap
-#[no_mangle]
-pub extern "C" fn haxe_objectmap_copy(map_ptr: *mut HaxeObjectMap) -> *mut HaxeObjectMap {
-    if map_ptr.is_null() {
-        return haxe_objectmap_new();
-    }
-    unsafe {
-        let map = &*map_ptr;
-        Box::into_raw(Box::new(HaxeObjectMap {
-            map: map.map.clone(),
-        }))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdout::StdoutCapture;
+    use std::time::{Duration, Instant};
+
+    fn trace_pos(pos: &str) {
+        haxe_trace_pos(haxe_string_from_string(pos.as_ptr(), pos.len()));
+    }
+
+    fn traced_lines(output: &str) -> Vec<&str> {
+        output
+            .lines()
+            .filter(|line| line.contains("trace-pos-test"))
+            .collect()
+    }
+
+    #[test]
+    fn test_trace_positions() {
+        let capture = StdoutCapture::start(false);
+
+        // Without --trace-pos a position sent by the caller is ignored
+        set_trace_positions(false);
+        trace_pos("Main.main:3");
+        print_with_prefix("trace-pos-test: plain");
+
+        // With it the position comes first, and only for the next trace
+        set_trace_positions(true);
+        trace_pos("Main.main:4");
+        print_with_prefix("trace-pos-test: positioned");
+        print_with_prefix("trace-pos-test: unpositioned");
+        set_trace_positions(false);
+
+        assert_eq!(
+            traced_lines(&capture.finish()),
+            [
+                "trace-pos-test: plain",
+                "Main.main:4: trace-pos-test: positioned",
+                "trace-pos-test: unpositioned",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_monotonic_clock() {
+        let mut last = rayzor_time_monotonic_ns();
+        for _ in 0..10_000 {
+            let now = rayzor_time_monotonic_ns();
+            assert!(now >= last, "went back from {} to {}", last, now);
+            last = now;
+        }
+
+        // Timer.stamp() reads the same clock, in seconds
+        let before = rayzor_time_monotonic_ns() as f64 / 1e9;
+        let stamp = haxe_timer_stamp();
+        let after = rayzor_time_monotonic_ns() as f64 / 1e9;
+        assert!(
+            before - 1e-9 <= stamp && stamp <= after + 1e-9,
+            "{} not in [{}, {}]",
+            stamp,
+            before,
+            after
+        );
+    }
+
+    #[test]
+    fn test_cpu_time() {
+        let start = haxe_sys_cpu_time();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut x = 0u64;
+        while haxe_sys_cpu_time() - start < 0.01 && Instant::now() < deadline {
+            x = std::hint::black_box(x.wrapping_mul(31).wrapping_add(1));
+        }
+        let end = haxe_sys_cpu_time();
+        assert!(
+            end - start >= 0.01,
+            "busy work took {}s of CPU",
+            end - start
+        );
+
+        // Process CPU time, not seconds since the epoch like Sys.time()
+        assert!(
+            haxe_sys_time() - end > 1e6,
+            "cpu {} vs wall {}",
+            end,
+            haxe_sys_time()
+        );
     }
 }
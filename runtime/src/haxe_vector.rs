@@ -0,0 +1,384 @@
+//! Native `haxe.ds.Vector` implementation
+//!
+//! `Vector<T>` is an extern class with fixed-length storage: its length is
+//! set on construction and never grows. The compiler monomorphizes it like
+//! `rayzor.Vec<T>`:
+//!
+//! - `Vector<Int>` -> `haxe_vector_i32_*` (unboxed i32 elements)
+//! - `Vector<Float>` -> `haxe_vector_f64_*` (unboxed f64 elements)
+//! - any other `T` -> `haxe_vector_*` (8-byte type-erased slots, passed as raw
+//!   u64 bits like List and StringMap values)
+//!
+//! New vectors hold neutral elements (0, 0.0, null/false). As with the other
+//! native collections, reads outside `0...length` return the neutral element
+//! and writes outside it are ignored.
+
+use crate::haxe_array::{haxe_array_new, haxe_array_push_i64, read_slot, HaxeArray};
+use std::alloc::{alloc, Layout};
+
+/// Element storage of a vector; the variant is fixed by the constructor
+enum Storage {
+    I32(Box<[i32]>),
+    F64(Box<[f64]>),
+    Slots(Box<[i64]>),
+}
+
+/// Runtime representation of `haxe.ds.Vector<T>`
+pub struct HaxeVector {
+    storage: Storage,
+}
+
+impl HaxeVector {
+    fn into_raw(storage: Storage) -> *mut HaxeVector {
+        Box::into_raw(Box::new(HaxeVector { storage }))
+    }
+
+    fn len(&self) -> usize {
+        match &self.storage {
+            Storage::I32(items) => items.len(),
+            Storage::F64(items) => items.len(),
+            Storage::Slots(items) => items.len(),
+        }
+    }
+}
+
+/// Number of elements for a Haxe `length` argument (negative lengths give an
+/// empty vector).
+fn element_count(length: i32) -> usize {
+    length.max(0) as usize
+}
+
+/// The in-bounds position for a Haxe index, if any.
+fn position(index: i32, len: usize) -> Option<usize> {
+    usize::try_from(index).ok().filter(|&i| i < len)
+}
+
+/// Borrow a vector, treating null as absent.
+unsafe fn vector_ref<'a>(vector: *const HaxeVector) -> Option<&'a HaxeVector> {
+    vector.as_ref()
+}
+
+/// Borrow a vector mutably, treating null as absent.
+unsafe fn vector_mut<'a>(vector: *mut HaxeVector) -> Option<&'a mut HaxeVector> {
+    vector.as_mut()
+}
+
+/// Read the element slots of a HaxeArray.
+unsafe fn array_slots(arr: *const HaxeArray) -> Vec<i64> {
+    match arr.as_ref() {
+        Some(arr) => (0..arr.len).map(|i| read_slot(arr, i)).collect(),
+        None => Vec::new(),
+    }
+}
+
+// ============================================================================
+// Vector<Int>
+// ============================================================================
+
+/// Create a `Vector<Int>` of `length` zeroes
+#[no_mangle]
+pub extern "C" fn haxe_vector_i32_new(length: i32) -> *mut HaxeVector {
+    HaxeVector::into_raw(Storage::I32(vec![0; element_count(length)].into()))
+}
+
+/// Get the element at `index`
+#[no_mangle]
+pub extern "C" fn haxe_vector_i32_get(vector: *const HaxeVector, index: i32) -> i32 {
+    match unsafe { vector_ref(vector) }.map(|v| &v.storage) {
+        Some(Storage::I32(items)) => position(index, items.len()).map_or(0, |i| items[i]),
+        _ => 0,
+    }
+}
+
+/// Set the element at `index`, returning `value`
+#[no_mangle]
+pub extern "C" fn haxe_vector_i32_set(vector: *mut HaxeVector, index: i32, value: i32) -> i32 {
+    if let Some(Storage::I32(items)) = unsafe { vector_mut(vector) }.map(|v| &mut v.storage) {
+        if let Some(i) = position(index, items.len()) {
+            items[i] = value;
+        }
+    }
+    value
+}
+
+/// Set every element to `value`
+#[no_mangle]
+pub extern "C" fn haxe_vector_i32_fill(vector: *mut HaxeVector, value: i32) {
+    if let Some(Storage::I32(items)) = unsafe { vector_mut(vector) }.map(|v| &mut v.storage) {
+        items.fill(value);
+    }
+}
+
+/// Create a `Vector<Int>` holding the elements of an `Array<Int>`
+#[no_mangle]
+pub extern "C" fn haxe_vector_i32_from_array(arr: *const HaxeArray) -> *mut HaxeVector {
+    let items = unsafe { array_slots(arr) };
+    HaxeVector::into_raw(Storage::I32(items.iter().map(|&x| x as i32).collect()))
+}
+
+// ============================================================================
+// Vector<Float>
+// ============================================================================
+
+/// Create a `Vector<Float>` of `length` zeroes
+#[no_mangle]
+pub extern "C" fn haxe_vector_f64_new(length: i32) -> *mut HaxeVector {
+    HaxeVector::into_raw(Storage::F64(vec![0.0; element_count(length)].into()))
+}
+
+/// Get the element at `index`
+#[no_mangle]
+pub extern "C" fn haxe_vector_f64_get(vector: *const HaxeVector, index: i32) -> f64 {
+    match unsafe { vector_ref(vector) }.map(|v| &v.storage) {
+        Some(Storage::F64(items)) => position(index, items.len()).map_or(0.0, |i| items[i]),
+        _ => 0.0,
+    }
+}
+
+/// Set the element at `index`, returning `value`
+#[no_mangle]
+pub extern "C" fn haxe_vector_f64_set(vector: *mut HaxeVector, index: i32, value: f64) -> f64 {
+    if let Some(Storage::F64(items)) = unsafe { vector_mut(vector) }.map(|v| &mut v.storage) {
+        if let Some(i) = position(index, items.len()) {
+            items[i] = value;
+        }
+    }
+    value
+}
+
+/// Set every element to `value`
+#[no_mangle]
+pub extern "C" fn haxe_vector_f64_fill(vector: *mut HaxeVector, value: f64) {
+    if let Some(Storage::F64(items)) = unsafe { vector_mut(vector) }.map(|v| &mut v.storage) {
+        items.fill(value);
+    }
+}
+
+/// Create a `Vector<Float>` holding the elements of an `Array<Float>`
+#[no_mangle]
+pub extern "C" fn haxe_vector_f64_from_array(arr: *const HaxeArray) -> *mut HaxeVector {
+    let items = unsafe { array_slots(arr) };
+    HaxeVector::into_raw(Storage::F64(
+        items.iter().map(|&x| f64::from_bits(x as u64)).collect(),
+    ))
+}
+
+// ============================================================================
+// Vector<T> (type-erased slots)
+// ============================================================================
+
+/// Create a `Vector<T>` of `length` null slots
+#[no_mangle]
+pub extern "C" fn haxe_vector_new(length: i32) -> *mut HaxeVector {
+    // Slots may be GC pointers, so they are scanned under `--gc`
+    let items = crate::gc::tracked(|| vec![0; element_count(length)].into());
+    HaxeVector::into_raw(Storage::Slots(items))
+}
+
+/// Get the slot at `index` as raw u64 bits
+#[no_mangle]
+pub extern "C" fn haxe_vector_get(vector: *const HaxeVector, index: i32) -> u64 {
+    match unsafe { vector_ref(vector) }.map(|v| &v.storage) {
+        Some(Storage::Slots(items)) => position(index, items.len()).map_or(0, |i| items[i] as u64),
+        _ => 0,
+    }
+}
+
+/// Set the slot at `index` from raw u64 bits, returning `value`
+#[no_mangle]
+pub extern "C" fn haxe_vector_set(vector: *mut HaxeVector, index: i32, value: u64) -> u64 {
+    if let Some(Storage::Slots(items)) = unsafe { vector_mut(vector) }.map(|v| &mut v.storage) {
+        if let Some(i) = position(index, items.len()) {
+            items[i] = value as i64;
+        }
+    }
+    value
+}
+
+/// Set every slot to `value`
+#[no_mangle]
+pub extern "C" fn haxe_vector_fill(vector: *mut HaxeVector, value: u64) {
+    if let Some(Storage::Slots(items)) = unsafe { vector_mut(vector) }.map(|v| &mut v.storage) {
+        items.fill(value as i64);
+    }
+}
+
+/// Create a `Vector<T>` holding the elements of an `Array<T>`
+#[no_mangle]
+pub extern "C" fn haxe_vector_from_array(arr: *const HaxeArray) -> *mut HaxeVector {
+    let items = crate::gc::tracked(|| unsafe { array_slots(arr) }.into());
+    HaxeVector::into_raw(Storage::Slots(items))
+}
+
+// ============================================================================
+// Shared operations
+// ============================================================================
+
+/// `vector.length`
+#[no_mangle]
+pub extern "C" fn haxe_vector_length(vector: *const HaxeVector) -> i32 {
+    unsafe { vector_ref(vector) }.map_or(0, |v| v.len() as i32)
+}
+
+/// Shallow copy with the same length and element type
+#[no_mangle]
+pub extern "C" fn haxe_vector_copy(vector: *const HaxeVector) -> *mut HaxeVector {
+    let Some(vector) = (unsafe { vector_ref(vector) }) else {
+        return haxe_vector_new(0);
+    };
+    HaxeVector::into_raw(match &vector.storage {
+        Storage::I32(items) => Storage::I32(items.clone()),
+        Storage::F64(items) => Storage::F64(items.clone()),
+        Storage::Slots(items) => Storage::Slots(crate::gc::tracked(|| items.clone())),
+    })
+}
+
+/// `Vector.blit(src, srcPos, dest, destPos, len)`
+///
+/// Copies `len` elements between vectors of the same element type; `src` and
+/// `dest` may be the same vector with overlapping ranges. Ranges outside
+/// either vector copy nothing.
+#[no_mangle]
+pub extern "C" fn haxe_vector_blit(
+    src: *const HaxeVector,
+    src_pos: i32,
+    dest: *mut HaxeVector,
+    dest_pos: i32,
+    len: i32,
+) {
+    fn copy<T: Copy>(src: Option<&[T]>, dest: &mut [T], src_pos: usize, dest_pos: usize, n: usize) {
+        let src_len = src.map_or(dest.len(), <[T]>::len);
+        if src_pos + n > src_len || dest_pos + n > dest.len() {
+            return;
+        }
+        match src {
+            Some(src) => dest[dest_pos..dest_pos + n].copy_from_slice(&src[src_pos..src_pos + n]),
+            None => dest.copy_within(src_pos..src_pos + n, dest_pos),
+        }
+    }
+
+    let (Ok(src_pos), Ok(dest_pos), Ok(n)) = (
+        usize::try_from(src_pos),
+        usize::try_from(dest_pos),
+        usize::try_from(len),
+    ) else {
+        return;
+    };
+    if src.is_null() || dest.is_null() {
+        return;
+    }
+    unsafe {
+        if std::ptr::eq(src, dest) {
+            match &mut (*dest).storage {
+                Storage::I32(items) => copy(None, items, src_pos, dest_pos, n),
+                Storage::F64(items) => copy(None, items, src_pos, dest_pos, n),
+                Storage::Slots(items) => copy(None, items, src_pos, dest_pos, n),
+            }
+            return;
+        }
+        match (&(*src).storage, &mut (*dest).storage) {
+            (Storage::I32(from), Storage::I32(to)) => {
+                copy(Some(&from[..]), to, src_pos, dest_pos, n)
+            }
+            (Storage::F64(from), Storage::F64(to)) => {
+                copy(Some(&from[..]), to, src_pos, dest_pos, n)
+            }
+            (Storage::Slots(from), Storage::Slots(to)) => {
+                copy(Some(&from[..]), to, src_pos, dest_pos, n)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Create an Array holding the elements of `vector`
+#[no_mangle]
+pub extern "C" fn haxe_vector_to_array(vector: *const HaxeVector) -> *mut HaxeArray {
+    unsafe {
+        let arr = alloc(Layout::new::<HaxeArray>()) as *mut HaxeArray;
+        haxe_array_new(arr, 8);
+        match vector_ref(vector).map(|v| &v.storage) {
+            Some(Storage::I32(items)) => {
+                for &x in items.iter() {
+                    haxe_array_push_i64(arr, x as i64);
+                }
+            }
+            Some(Storage::F64(items)) => {
+                for &x in items.iter() {
+                    haxe_array_push_i64(arr, x.to_bits() as i64);
+                }
+            }
+            Some(Storage::Slots(items)) => {
+                for &x in items.iter() {
+                    haxe_array_push_i64(arr, x);
+                }
+            }
+            None => {}
+        }
+        arr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_is_fixed_length() {
+        let v = haxe_vector_i32_new(3);
+        assert_eq!(haxe_vector_length(v), 3);
+        assert_eq!(haxe_vector_i32_set(v, 1, 7), 7);
+        assert_eq!(haxe_vector_i32_get(v, 1), 7);
+        // Out-of-range writes never grow the vector
+        haxe_vector_i32_set(v, 3, 9);
+        haxe_vector_i32_set(v, -1, 9);
+        assert_eq!(haxe_vector_length(v), 3);
+        assert_eq!(haxe_vector_i32_get(v, 3), 0);
+
+        let f = haxe_vector_f64_new(2);
+        haxe_vector_f64_fill(f, 1.5);
+        assert_eq!(haxe_vector_f64_get(f, 1), 1.5);
+
+        let s = haxe_vector_new(2);
+        haxe_vector_set(s, 0, u64::MAX);
+        assert_eq!(haxe_vector_get(s, 0), u64::MAX);
+        assert_eq!(haxe_vector_get(s, 1), 0);
+    }
+
+    #[test]
+    fn test_vector_blit_and_copy() {
+        let v = haxe_vector_i32_new(5);
+        for i in 0..5 {
+            haxe_vector_i32_set(v, i, i);
+        }
+        // Overlapping blit within one vector
+        haxe_vector_blit(v, 0, v, 1, 4);
+        let values: Vec<i32> = (0..5).map(|i| haxe_vector_i32_get(v, i)).collect();
+        assert_eq!(values, vec![0, 0, 1, 2, 3]);
+
+        let copy = haxe_vector_copy(v);
+        haxe_vector_i32_set(copy, 0, 42);
+        assert_eq!(haxe_vector_i32_get(v, 0), 0);
+        haxe_vector_blit(copy, 0, v, 4, 1);
+        assert_eq!(haxe_vector_i32_get(v, 4), 42);
+        // Ranges past the end copy nothing
+        haxe_vector_blit(copy, 3, v, 0, 3);
+        assert_eq!(haxe_vector_i32_get(v, 0), 0);
+    }
+
+    #[test]
+    fn test_vector_array_round_trip() {
+        let v = haxe_vector_f64_new(2);
+        haxe_vector_f64_set(v, 0, 0.5);
+        haxe_vector_f64_set(v, 1, -2.0);
+        let arr = haxe_vector_to_array(v);
+        assert_eq!(unsafe { (*arr).len }, 2);
+        let back = haxe_vector_f64_from_array(arr);
+        assert_eq!(haxe_vector_f64_get(back, 1), -2.0);
+
+        let ints = haxe_vector_i32_new(1);
+        haxe_vector_i32_set(ints, 0, -3);
+        let back = haxe_vector_i32_from_array(haxe_vector_to_array(ints));
+        assert_eq!(haxe_vector_i32_get(back, 0), -3);
+    }
+}
@@ -15,6 +15,9 @@
 //! - Memory-safe
 //! - Platform-independent
 //! - No external C dependencies
+//!
+//! Objects allocated with `rayzor_gc_alloc` are instead owned by the tracing
+//! collector in [`gc`].
 
 // Runtime FFI functions take raw pointers from JIT-compiled code.
 // The safety contract is between the compiler (which generates valid pointer arguments)
@@ -25,7 +28,10 @@
 
 use std::alloc::{alloc, dealloc, realloc, Layout};
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ptr;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::{OnceLock, RwLock};
 
 // Export Vec module (old API - keeping for backward compat)
 pub mod vec;
@@ -37,15 +43,41 @@ pub mod generic_vec;
 
 // Export Haxe core type runtime modules
 pub mod anon_object; // Anonymous object runtime (Arc-based, COW)
+pub mod closure; // Bound-method cache and function identity
+pub mod collation; // Locale-aware string comparison (rayzor.text.Collator)
 pub mod concurrency; // Concurrency primitives (Thread, Arc, Mutex, Channel)
+pub mod contracts; // @:requires/@:ensures failure reports
+pub mod coroutine; // Stackful coroutines (sys.thread.Coroutine)
+pub mod cpu_features; // CPU feature detection for multi-versioned functions
+pub mod crash; // Opt-in crash reports for faults in JIT code
+pub mod deadlock; // Opt-in deadlock detection for Mutex/Channel/join
+pub mod debug_alloc; // Guarded, quarantining allocator (--sanitize address)
+pub mod enum_value; // Structural equality/hashing of enum values
 pub mod ereg; // EReg regular expressions (regex crate)
 pub mod exception;
+pub mod gc; // Tracing garbage collector (rayzor_gc_*)
 pub mod haxe_array; // Dynamic Array API
+pub mod haxe_lambda; // Lambda functions over arrays and lists
+pub mod haxe_list; // Native haxe.ds.List
+pub mod haxe_map; // Native StringMap/IntMap/ObjectMap (open addressing)
 pub mod haxe_math; // Math functions
 pub mod haxe_string; // Comprehensive String API
 pub mod haxe_sys; // System/IO functions
+pub mod haxe_vector; // Native fixed-length haxe.ds.Vector
+pub mod meta; // User metadata tables for haxe.rtti.Meta
+pub mod missing_extern; // Diagnostic stubs for externs no plugin provides
+pub mod module_loader; // Loading bundles at runtime (rayzor.Runtime.loadModule)
+pub mod number_format; // Haxe-compatible Float/hex formatting
+pub mod object_id; // Identity ids for ObjectMap/WeakMap keys
+pub mod oom; // Allocation failure hooks (rayzor_set_oom_handler)
+pub mod pool_alloc; // Size-class pools for small rayzor_malloc requests
 pub mod reflect; // Reflect + Type API for anonymous objects
 pub mod safety; // Safety validation and error reporting
+pub mod sanitizer; // ThreadSanitizer hooks (rayzor run --sanitize thread)
+pub mod stack_guard; // Thread stack sizes and stack overflow reports
+pub mod stdout; // Output redirection (rayzor_set_stdout_handler)
+pub mod string_search; // Vectorized indexOf/lastIndexOf/split (memchr)
+pub mod symbol_manifest; // Runtime symbols shared by JIT and AOT linking
 pub mod type_system; // Runtime type information for Dynamic values
 pub mod vec_plugin; // Pointer-based Vec API // Exception handling (setjmp/longjmp)
 
@@ -87,8 +119,26 @@ pub const DEBUG_MODE: bool = true;
 #[cfg(not(debug_assertions))]
 pub const DEBUG_MODE: bool = false;
 
+/// Alignment of `rayzor_malloc`/`rayzor_realloc` blocks: large enough for any
+/// scalar (f64, i64, pointers) and 128-bit SIMD values, matching `max_align_t`.
+pub const DEFAULT_ALIGNMENT: usize = 16;
+
+/// Size class for a `rayzor_malloc` request, or `None` for the global allocator.
+#[inline]
+fn pooled_class(size: u64) -> Option<usize> {
+    if pool_alloc::pooling_enabled() {
+        pool_alloc::size_class(size as usize)
+    } else {
+        None
+    }
+}
+
 /// Allocate memory on the heap
 ///
+/// Returned memory is aligned to `DEFAULT_ALIGNMENT`. Requests of up to
+/// `pool_alloc::MAX_POOLED_SIZE` bytes are served from size-class pools
+/// (see `pool_alloc`).
+///
 /// # Safety
 /// The returned pointer must be freed with `rayzor_free` when no longer needed.
 ///
@@ -103,17 +153,33 @@ pub unsafe extern "C" fn rayzor_malloc(size: u64) -> *mut u8 {
         return ptr::null_mut();
     }
 
+    if debug_alloc::is_enabled() {
+        return debug_alloc::rayzor_debug_malloc(size as usize);
+    }
+    if gc::is_enabled() {
+        return gc::rayzor_gc_malloc(size);
+    }
+
+    // Small objects come from the size-class pools
+    if let Some(class) = pooled_class(size) {
+        let ptr = pool_alloc::pool_alloc(class);
+        if ptr.is_null() {
+            return oom::retry_allocation(size, || pool_alloc::pool_alloc(class));
+        }
+        return ptr;
+    }
+
     // Create layout for allocation
-    let layout = match Layout::from_size_align(size as usize, 1) {
+    let layout = match Layout::from_size_align(size as usize, DEFAULT_ALIGNMENT) {
         Ok(layout) => layout,
         Err(_) => return ptr::null_mut(),
     };
 
-    // Allocate memory
+    // Allocate memory, retrying through the OOM handler on failure
     let ptr = alloc(layout);
 
     if ptr.is_null() {
-        return ptr::null_mut();
+        return oom::retry_allocation(size, || alloc(layout));
     }
 
     ptr
@@ -143,8 +209,32 @@ pub unsafe extern "C" fn rayzor_realloc(ptr: *mut u8, old_size: u64, new_size: u
         return ptr::null_mut();
     }
 
+    if debug_alloc::is_enabled() {
+        return debug_alloc::rayzor_debug_realloc(ptr, new_size as usize);
+    }
+    if gc::is_enabled() && gc::owns(ptr) {
+        return gc::rayzor_gc_realloc(ptr, new_size);
+    }
+
+    // Moving into or out of a pool: copy into a fresh block
+    let old_class = pooled_class(old_size);
+    let new_class = pooled_class(new_size);
+    if old_class.is_some() || new_class.is_some() {
+        if old_class == new_class {
+            return ptr;
+        }
+        let new_ptr = rayzor_malloc(new_size);
+        if new_ptr.is_null() {
+            return ptr::null_mut();
+        }
+        ptr::copy_nonoverlapping(ptr, new_ptr, old_size.min(new_size) as usize);
+        object_id::relocate(ptr as usize, new_ptr as usize);
+        rayzor_free(ptr, old_size);
+        return new_ptr;
+    }
+
     // Create layouts
-    let old_layout = match Layout::from_size_align(old_size as usize, 1) {
+    let old_layout = match Layout::from_size_align(old_size as usize, DEFAULT_ALIGNMENT) {
         Ok(layout) => layout,
         Err(_) => return ptr::null_mut(),
     };
@@ -152,8 +242,15 @@ pub unsafe extern "C" fn rayzor_realloc(ptr: *mut u8, old_size: u64, new_size: u
     // Reallocate
     let new_ptr = realloc(ptr, old_layout, new_size as usize);
 
-    if new_ptr.is_null() {
-        return ptr::null_mut();
+    let new_ptr = if new_ptr.is_null() {
+        // The original block is still valid; retry through the OOM handler
+        oom::retry_allocation(new_size, || realloc(ptr, old_layout, new_size as usize))
+    } else {
+        new_ptr
+    };
+
+    if !new_ptr.is_null() {
+        object_id::relocate(ptr as usize, new_ptr as usize);
     }
 
     new_ptr
@@ -175,8 +272,24 @@ pub unsafe extern "C" fn rayzor_free(ptr: *mut u8, size: u64) {
         return;
     }
 
+    if debug_alloc::is_enabled() {
+        object_id::release(ptr as usize);
+        debug_alloc::rayzor_debug_free(ptr);
+        return;
+    }
+    if gc::is_enabled() && gc::free(ptr) {
+        return;
+    }
+
+    object_id::release(ptr as usize);
+
+    if let Some(class) = pooled_class(size) {
+        pool_alloc::pool_free(ptr, class);
+        return;
+    }
+
     // Create layout
-    let layout = match Layout::from_size_align(size as usize, 1) {
+    let layout = match Layout::from_size_align(size as usize, DEFAULT_ALIGNMENT) {
         Ok(layout) => layout,
         Err(_) => return, // Invalid layout, can't free
     };
@@ -185,6 +298,145 @@ pub unsafe extern "C" fn rayzor_free(ptr: *mut u8, size: u64) {
     dealloc(ptr, layout);
 }
 
+// ============================================================================
+// Aligned Heap Allocator
+// ============================================================================
+// Allocations with an explicit alignment (over-aligned structs, SIMD vectors,
+// GPU-shared buffers). Blocks come from the platform's aligned allocator, so
+// on Unix they may also be released with libc `free` like other allocations
+// made by compiled code.
+
+/// Allocate `size` bytes aligned to `align` using the platform allocator.
+#[cfg(unix)]
+unsafe fn sys_alloc_aligned(size: usize, align: usize) -> *mut u8 {
+    // posix_memalign requires a multiple of sizeof(void*)
+    let align = align.max(std::mem::size_of::<usize>());
+    let mut out: *mut libc::c_void = ptr::null_mut();
+    if libc::posix_memalign(&mut out, align, size) != 0 {
+        return ptr::null_mut();
+    }
+    out as *mut u8
+}
+
+#[cfg(windows)]
+unsafe fn sys_alloc_aligned(size: usize, align: usize) -> *mut u8 {
+    libc::aligned_malloc(size, align) as *mut u8
+}
+
+#[cfg(unix)]
+unsafe fn sys_free_aligned(ptr: *mut u8) {
+    libc::free(ptr as *mut libc::c_void);
+}
+
+#[cfg(windows)]
+unsafe fn sys_free_aligned(ptr: *mut u8) {
+    libc::aligned_free(ptr as *mut libc::c_void);
+}
+
+/// Allocate memory with an explicit alignment
+///
+/// # Safety
+/// The returned pointer must be freed with `rayzor_free_aligned`.
+///
+/// # Arguments
+/// * `size` - Number of bytes to allocate
+/// * `align` - Required alignment in bytes (must be a power of two)
+///
+/// # Returns
+/// Pointer aligned to at least `align`, or null on failure or invalid alignment
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_malloc_aligned(size: u64, align: u64) -> *mut u8 {
+    let align = align as usize;
+    if size == 0 || !align.is_power_of_two() {
+        return ptr::null_mut();
+    }
+
+    let ptr = sys_alloc_aligned(size as usize, align);
+    if ptr.is_null() {
+        return oom::retry_allocation(size, || sys_alloc_aligned(size as usize, align));
+    }
+
+    ptr
+}
+
+/// Reallocate aligned memory, preserving its alignment
+///
+/// # Safety
+/// - `ptr` must have been allocated by `rayzor_malloc_aligned` or
+///   `rayzor_realloc_aligned` with the same `align`
+/// - If reallocation fails, the original pointer remains valid
+///
+/// # Arguments
+/// * `ptr` - Pointer to existing allocation
+/// * `old_size` - Original size in bytes
+/// * `new_size` - New size in bytes
+/// * `align` - Alignment the allocation was made with
+///
+/// # Returns
+/// Pointer to reallocated memory aligned to `align`, or null on failure
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_realloc_aligned(
+    ptr: *mut u8,
+    old_size: u64,
+    new_size: u64,
+    align: u64,
+) -> *mut u8 {
+    if ptr.is_null() {
+        return rayzor_malloc_aligned(new_size, align);
+    }
+
+    if new_size == 0 {
+        rayzor_free_aligned(ptr);
+        return ptr::null_mut();
+    }
+
+    // Platform realloc does not guarantee alignment, so always move the data
+    // into a fresh aligned block
+    let new_ptr = rayzor_malloc_aligned(new_size, align);
+    if new_ptr.is_null() {
+        return ptr::null_mut();
+    }
+
+    ptr::copy_nonoverlapping(ptr, new_ptr, old_size.min(new_size) as usize);
+    object_id::relocate(ptr as usize, new_ptr as usize);
+    rayzor_free_aligned(ptr);
+
+    new_ptr
+}
+
+/// Free memory allocated by `rayzor_malloc_aligned`
+///
+/// # Safety
+/// - `ptr` must have been allocated by `rayzor_malloc_aligned` or `rayzor_realloc_aligned`
+/// - After calling this function, `ptr` is invalid and must not be used
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_free_aligned(ptr: *mut u8) {
+    if ptr.is_null() {
+        return;
+    }
+
+    object_id::release(ptr as usize);
+    sys_free_aligned(ptr);
+}
+
+// ============================================================================
+// Runtime Lifecycle
+// ============================================================================
+
+/// Set up runtime state that depends on the environment (currently the
+/// collector's `RAYZOR_GC_THRESHOLD`). Safe to call more than once.
+#[no_mangle]
+pub extern "C" fn rayzor_runtime_init() {
+    gc::init();
+}
+
+/// Release runtime-owned memory before exit: every object in the GC heap is
+/// freed, whether or not it is still rooted
+#[no_mangle]
+pub extern "C" fn rayzor_runtime_shutdown() {
+    gc::shutdown();
+}
+
 // ============================================================================
 // Tracked Heap Allocator
 // ============================================================================
@@ -221,6 +473,13 @@ pub unsafe extern "C" fn rayzor_tracked_alloc(size: u64) -> *mut u8 {
         return ptr::null_mut();
     }
 
+    if debug_alloc::is_enabled() {
+        return debug_alloc::rayzor_debug_malloc(size as usize);
+    }
+    if gc::is_enabled() {
+        return gc::rayzor_gc_malloc(size);
+    }
+
     // Round up to 16-byte alignment
     let aligned_size = ((size as usize) + (TRACKED_ALIGNMENT - 1)) & !(TRACKED_ALIGNMENT - 1);
     let total = aligned_size + TRACKED_HEADER_SIZE;
@@ -229,9 +488,12 @@ pub unsafe extern "C" fn rayzor_tracked_alloc(size: u64) -> *mut u8 {
         Err(_) => return ptr::null_mut(),
     };
 
-    let base = alloc(layout);
+    let mut base = alloc(layout);
     if base.is_null() {
-        return ptr::null_mut();
+        base = oom::retry_allocation(total as u64, || alloc(layout));
+        if base.is_null() {
+            return ptr::null_mut();
+        }
     }
 
     // Write size header at base (first 8 bytes of the 16-byte header)
@@ -251,6 +513,15 @@ pub unsafe extern "C" fn rayzor_tracked_free(ptr: *mut u8) {
         return;
     }
 
+    if debug_alloc::is_enabled() {
+        object_id::release(ptr as usize);
+        debug_alloc::rayzor_debug_free(ptr);
+        return;
+    }
+    if gc::is_enabled() && gc::free(ptr) {
+        return;
+    }
+
     // Read the size header (first 8 bytes of 16-byte header before the user pointer)
     let base = ptr.sub(TRACKED_HEADER_SIZE);
     let aligned_size = *(base as *const u64) as usize;
@@ -262,6 +533,8 @@ pub unsafe extern "C" fn rayzor_tracked_free(ptr: *mut u8) {
         return;
     }
 
+    object_id::release(ptr as usize);
+
     // Clear the size header to help catch double-frees
     // (subsequent free of this address will see size=0 and return early)
     *(base as *mut u64) = 0;
@@ -286,6 +559,13 @@ pub unsafe extern "C" fn rayzor_tracked_realloc(ptr: *mut u8, new_size: u64) ->
         return ptr::null_mut();
     }
 
+    if debug_alloc::is_enabled() {
+        return debug_alloc::rayzor_debug_realloc(ptr, new_size as usize);
+    }
+    if gc::is_enabled() && gc::owns(ptr) {
+        return gc::rayzor_gc_realloc(ptr, new_size);
+    }
+
     // Read old size from header
     let old_base = ptr.sub(TRACKED_HEADER_SIZE);
     let old_aligned_size = *(old_base as *const u64) as usize;
@@ -313,6 +593,7 @@ pub unsafe extern "C" fn rayzor_tracked_realloc(ptr: *mut u8, new_size: u64) ->
     // Copy old data (up to the smaller of old and new sizes)
     let copy_size = old_aligned_size.min(new_aligned_size);
     ptr::copy_nonoverlapping(ptr, new_ptr, copy_size);
+    object_id::relocate(ptr as usize, new_ptr as usize);
 
     // Free old block
     rayzor_tracked_free(ptr);
@@ -361,6 +642,11 @@ pub unsafe extern "C" fn rayzor_global_store(global_id: i64, value: i64) {
     });
 }
 
+/// Run `f` on this thread's global variable slots (for the collector)
+pub(crate) fn with_global_store(f: impl FnOnce(&[u64])) {
+    GLOBAL_STORE.with(|store| f(&store.borrow()));
+}
+
 /// Load a value from a global variable
 ///
 /// # Arguments
@@ -381,6 +667,99 @@ pub unsafe extern "C" fn rayzor_global_load(global_id: i64) -> i64 {
     })
 }
 
+// ============================================================================
+// Function-Local Static Storage
+// ============================================================================
+// `static var` declarations inside function bodies. Unlike the global store
+// above, these slots are process-wide so every thread observes the same lazily
+// initialized value. Slots are keyed by a compile-time id that is baked into
+// the MIR, so state survives re-compilation of the function at a higher tier.
+
+const STATIC_LOCAL_UNINIT: u8 = 0;
+const STATIC_LOCAL_RUNNING: u8 = 1;
+const STATIC_LOCAL_READY: u8 = 2;
+
+struct StaticLocal {
+    value: AtomicU64,
+    state: AtomicU8,
+    /// Thread running the initializer, so re-entrant calls don't spin forever
+    owner: AtomicU64,
+}
+
+static STATIC_LOCALS: OnceLock<RwLock<HashMap<i64, &'static StaticLocal>>> = OnceLock::new();
+static NEXT_THREAD_TOKEN: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static THREAD_TOKEN: u64 = NEXT_THREAD_TOKEN.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Look up (or lazily create) the slot for `key`. Slots are leaked so their
+/// addresses stay valid for the life of the process.
+fn static_local(key: i64) -> &'static StaticLocal {
+    let table = STATIC_LOCALS.get_or_init(|| RwLock::new(HashMap::new()));
+    if let Some(slot) = table.read().unwrap().get(&key) {
+        return slot;
+    }
+    table.write().unwrap().entry(key).or_insert_with(|| {
+        Box::leak(Box::new(StaticLocal {
+            value: AtomicU64::new(0),
+            state: AtomicU8::new(STATIC_LOCAL_UNINIT),
+            owner: AtomicU64::new(0),
+        }))
+    })
+}
+
+/// Begin lazy initialization of a local static.
+///
+/// # Returns
+/// 1 if the caller must run the initializer and then call
+/// `rayzor_static_local_init_end`, 0 if the value is ready. Blocks while
+/// another thread is running the initializer.
+#[no_mangle]
+pub extern "C" fn rayzor_static_local_init_begin(key: i64) -> i64 {
+    let slot = static_local(key);
+    let me = THREAD_TOKEN.with(|t| *t);
+    loop {
+        match slot.state.compare_exchange(
+            STATIC_LOCAL_UNINIT,
+            STATIC_LOCAL_RUNNING,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => {
+                slot.owner.store(me, Ordering::Relaxed);
+                return 1;
+            }
+            Err(STATIC_LOCAL_READY) => return 0,
+            // Recursive call from inside the initializer sees the default value
+            Err(_) if slot.owner.load(Ordering::Relaxed) == me => return 0,
+            Err(_) => std::thread::yield_now(),
+        }
+    }
+}
+
+/// Mark a local static as initialized, releasing any waiting threads
+#[no_mangle]
+pub extern "C" fn rayzor_static_local_init_end(key: i64) {
+    let slot = static_local(key);
+    slot.owner.store(0, Ordering::Relaxed);
+    slot.state.store(STATIC_LOCAL_READY, Ordering::Release);
+}
+
+/// Load the raw 8-byte value of a local static
+#[no_mangle]
+pub extern "C" fn rayzor_static_local_load(key: i64) -> i64 {
+    static_local(key).value.load(Ordering::Acquire) as i64
+}
+
+/// Store the raw 8-byte value of a local static
+#[no_mangle]
+pub extern "C" fn rayzor_static_local_store(key: i64, value: i64) {
+    static_local(key)
+        .value
+        .store(value as u64, Ordering::Release);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -441,4 +820,81 @@ mod tests {
             rayzor_free(ptr, 100);
         }
     }
+
+    #[test]
+    fn test_default_alignment() {
+        unsafe {
+            let ptr = rayzor_malloc(24);
+            assert_eq!(ptr as usize % DEFAULT_ALIGNMENT, 0);
+
+            let new_ptr = rayzor_realloc(ptr, 24, 4096);
+            assert_eq!(new_ptr as usize % DEFAULT_ALIGNMENT, 0);
+            rayzor_free(new_ptr, 4096);
+        }
+    }
+
+    #[test]
+    fn test_malloc_aligned() {
+        unsafe {
+            for align in [8u64, 16, 32, 64, 4096] {
+                let ptr = rayzor_malloc_aligned(100, align);
+                assert!(!ptr.is_null());
+                assert_eq!(ptr as u64 % align, 0);
+
+                for i in 0..100 {
+                    *ptr.add(i) = i as u8;
+                }
+
+                // Growing keeps both the data and the alignment
+                let new_ptr = rayzor_realloc_aligned(ptr, 100, 1000, align);
+                assert!(!new_ptr.is_null());
+                assert_eq!(new_ptr as u64 % align, 0);
+                for i in 0..100 {
+                    assert_eq!(*new_ptr.add(i), i as u8);
+                }
+
+                rayzor_free_aligned(new_ptr);
+            }
+
+            // Alignment must be a power of two
+            assert!(rayzor_malloc_aligned(16, 24).is_null());
+        }
+    }
+
+    #[test]
+    fn test_static_local_init_once() {
+        let key = 0x57a7_1c00;
+        assert_eq!(rayzor_static_local_init_begin(key), 1);
+        // Re-entrant begin on the initializing thread must not spin
+        assert_eq!(rayzor_static_local_init_begin(key), 0);
+        rayzor_static_local_store(key, 42);
+        rayzor_static_local_init_end(key);
+
+        assert_eq!(rayzor_static_local_init_begin(key), 0);
+        assert_eq!(rayzor_static_local_load(key), 42);
+    }
+
+    #[test]
+    fn test_static_local_shared_across_threads() {
+        let key = 0x57a7_1c01;
+        let inits: i64 = (0..8)
+            .map(|_| {
+                std::thread::spawn(move || {
+                    let won = rayzor_static_local_init_begin(key);
+                    if won == 1 {
+                        std::thread::sleep(std::time::Duration::from_millis(5));
+                        rayzor_static_local_store(key, 7);
+                        rayzor_static_local_init_end(key);
+                    }
+                    // Every thread sees the initialized value once begin returns
+                    assert_eq!(rayzor_static_local_load(key), 7);
+                    won
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .sum();
+        assert_eq!(inits, 1);
+    }
 }
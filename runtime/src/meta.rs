@@ -0,0 +1,299 @@
+//! Runtime metadata for `haxe.rtti.Meta`
+//!
+//! The compiler keeps user metadata (`@author("x") class Foo`) through MIR and
+//! registers it here per class/enum type id, next to the class RTTI in
+//! `type_system`. Queries build the Haxe-shaped values on demand:
+//!
+//! - `Meta.getType(Foo)`    -> `{ author: ["x"] }`
+//! - `Meta.getFields(Foo)`  -> `{ name: { meta: [args] } }` for instance fields
+//!   and methods (enum constructors for enums)
+//! - `Meta.getStatics(Foo)` -> the same for static fields and methods
+//!
+//! Metadata without arguments maps to `null`, as in Haxe. Objects are
+//! Map-backed anonymous objects, argument lists are `Array<Dynamic>` of boxed
+//! values, so the results work with `Reflect.field`/`Reflect.fields`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Once, RwLock};
+
+use crate::anon_object::{AnonData, AnonObject, DYNAMIC_SHAPE, TYPE_ANON_OBJECT};
+use crate::haxe_array::HaxeArray;
+use crate::type_system::{
+    register_type, DynamicValue, StringPtr, TypeId, TypeInfo, TYPE_BOOL, TYPE_FLOAT, TYPE_INT,
+    TYPE_NULL, TYPE_STRING,
+};
+
+/// Type id of the `Array<Dynamic>` argument lists inside metadata objects
+pub const TYPE_META_ARRAY: TypeId = TypeId(7);
+
+/// A constant metadata argument
+#[derive(Clone, Debug, PartialEq)]
+pub enum MetaValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<MetaValue>),
+    Object(Vec<(String, MetaValue)>),
+}
+
+/// Metadata entries of one declaration: `(name, arguments)` in source order
+pub type MetaEntries = Vec<(String, Vec<MetaValue>)>;
+
+/// All metadata of a class or enum
+#[derive(Clone, Debug, Default)]
+pub struct TypeMeta {
+    /// Metadata on the type itself
+    pub obj: MetaEntries,
+    /// Instance fields and methods, or enum constructors
+    pub fields: Vec<(String, MetaEntries)>,
+    /// Static fields and methods
+    pub statics: Vec<(String, MetaEntries)>,
+}
+
+impl TypeMeta {
+    pub fn is_empty(&self) -> bool {
+        self.obj.is_empty() && self.fields.is_empty() && self.statics.is_empty()
+    }
+}
+
+static META_REGISTRY: RwLock<Option<HashMap<u32, Arc<TypeMeta>>>> = RwLock::new(None);
+static META_ARRAY_TYPE: Once = Once::new();
+
+/// Register the metadata of a class or enum (called from compiler backends).
+/// Types without metadata are not recorded; queries on them return `{}`.
+pub fn register_meta_from_mir(type_id: u32, meta: TypeMeta) {
+    if meta.is_empty() {
+        return;
+    }
+    META_ARRAY_TYPE.call_once(|| {
+        register_type(
+            TYPE_META_ARRAY,
+            TypeInfo {
+                name: "Array",
+                size: std::mem::size_of::<HaxeArray>(),
+                align: std::mem::align_of::<HaxeArray>(),
+                to_string: meta_array_to_string,
+                enum_info: None,
+                class_info: None,
+            },
+        )
+    });
+    let mut guard = META_REGISTRY.write().unwrap();
+    guard
+        .get_or_insert_with(HashMap::new)
+        .insert(type_id, Arc::new(meta));
+}
+
+fn lookup(type_id: i64) -> Option<Arc<TypeMeta>> {
+    let guard = META_REGISTRY.read().unwrap();
+    guard.as_ref()?.get(&(type_id as u32)).cloned()
+}
+
+// ============================================================================
+// Meta API (called from JIT'd code)
+// ============================================================================
+
+/// Meta.getType(t) -> Dynamic<Array<Dynamic>>
+/// Takes a class or enum type id.
+#[no_mangle]
+pub extern "C" fn haxe_meta_get_type(type_id: i64) -> *mut u8 {
+    let meta = lookup(type_id);
+    entries_object(meta.as_ref().map_or(&[][..], |m| &m.obj))
+}
+
+/// Meta.getFields(t) -> Dynamic<Dynamic<Array<Dynamic>>>
+#[no_mangle]
+pub extern "C" fn haxe_meta_get_fields(type_id: i64) -> *mut u8 {
+    let meta = lookup(type_id);
+    members_object(meta.as_ref().map_or(&[][..], |m| &m.fields))
+}
+
+/// Meta.getStatics(t) -> Dynamic<Dynamic<Array<Dynamic>>>
+#[no_mangle]
+pub extern "C" fn haxe_meta_get_statics(type_id: i64) -> *mut u8 {
+    let meta = lookup(type_id);
+    members_object(meta.as_ref().map_or(&[][..], |m| &m.statics))
+}
+
+// ============================================================================
+// Value construction
+// ============================================================================
+
+fn new_anon(map: HashMap<String, (u32, u64)>) -> *mut u8 {
+    let obj = AnonObject {
+        shape_id: DYNAMIC_SHAPE,
+        data: AnonData::Map(map),
+    };
+    Box::into_raw(Box::new(Arc::new(obj))) as *mut u8
+}
+
+/// `{ name: [args] | null, ... }`
+fn entries_object(entries: &[(String, Vec<MetaValue>)]) -> *mut u8 {
+    let map = entries
+        .iter()
+        .map(|(name, args)| {
+            let value = if args.is_empty() {
+                (TYPE_NULL.0, 0)
+            } else {
+                (TYPE_META_ARRAY.0, new_array(args) as u64)
+            };
+            (name.clone(), value)
+        })
+        .collect();
+    new_anon(map)
+}
+
+/// `{ member: { name: [args] | null }, ... }`
+fn members_object(members: &[(String, MetaEntries)]) -> *mut u8 {
+    let map = members
+        .iter()
+        .map(|(member, entries)| {
+            let obj = entries_object(entries);
+            (member.clone(), (TYPE_ANON_OBJECT.0, obj as u64))
+        })
+        .collect();
+    new_anon(map)
+}
+
+/// Raw anonymous-object slot `(type_id, bits)` for a value
+fn raw_value(value: &MetaValue) -> (u32, u64) {
+    match value {
+        MetaValue::Null => (TYPE_NULL.0, 0),
+        MetaValue::Bool(b) => (TYPE_BOOL.0, *b as u64),
+        MetaValue::Int(i) => (TYPE_INT.0, *i as u64),
+        MetaValue::Float(f) => (TYPE_FLOAT.0, f.to_bits()),
+        MetaValue::String(s) => (TYPE_STRING.0, unsafe {
+            crate::type_system::alloc_haxe_string(s) as u64
+        }),
+        MetaValue::Array(items) => (TYPE_META_ARRAY.0, new_array(items) as u64),
+        MetaValue::Object(fields) => {
+            let map = fields
+                .iter()
+                .map(|(name, v)| (name.clone(), raw_value(v)))
+                .collect();
+            (TYPE_ANON_OBJECT.0, new_anon(map) as u64)
+        }
+    }
+}
+
+/// `Array<Dynamic>` holding boxed values
+fn new_array(items: &[MetaValue]) -> *mut u8 {
+    unsafe {
+        let arr = std::alloc::alloc(std::alloc::Layout::new::<HaxeArray>()) as *mut HaxeArray;
+        if arr.is_null() {
+            return std::ptr::null_mut();
+        }
+        crate::haxe_array::haxe_array_new(arr, std::mem::size_of::<*mut u8>());
+        for item in items {
+            let boxed = box_value(item);
+            crate::haxe_array::haxe_array_push(arr, &boxed as *const *mut u8 as *const u8);
+        }
+        arr as *mut u8
+    }
+}
+
+fn box_value(value: &MetaValue) -> *mut u8 {
+    use crate::type_system::{haxe_box_bool_ptr, haxe_box_float_ptr, haxe_box_int_ptr};
+    match value {
+        MetaValue::Bool(b) => haxe_box_bool_ptr(*b),
+        MetaValue::Int(i) => haxe_box_int_ptr(*i),
+        MetaValue::Float(f) => haxe_box_float_ptr(*f),
+        _ => {
+            let (type_id, raw) = raw_value(value);
+            let dv = DynamicValue {
+                type_id: TypeId(type_id),
+                value_ptr: raw as *mut u8,
+            };
+            Box::into_raw(Box::new(dv)) as *mut u8
+        }
+    }
+}
+
+/// `[a, b]` rendering for `trace`/`Std.string` of an argument list
+unsafe extern "C" fn meta_array_to_string(value_ptr: *const u8) -> StringPtr {
+    let arr = &*(value_ptr as *const HaxeArray);
+    let items: Vec<String> = (0..arr.len)
+        .map(|i| {
+            let elem = *(arr.ptr as *const *mut u8).add(i);
+            let hs = &*crate::type_system::haxe_std_string_ptr(elem);
+            String::from_utf8_lossy(std::slice::from_raw_parts(hs.ptr, hs.len)).into_owned()
+        })
+        .collect();
+    let s = Box::leak(format!("[{}]", items.join(",")).into_boxed_str());
+    StringPtr {
+        ptr: s.as_ptr(),
+        len: s.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::anon_object::rayzor_anon_fields;
+
+    fn field_names(obj: *mut u8) -> Vec<String> {
+        unsafe {
+            let arr = &*(rayzor_anon_fields(obj) as *const HaxeArray);
+            (0..arr.len)
+                .map(|i| {
+                    let hs = &**(arr.ptr as *const *const crate::haxe_string::HaxeString).add(i);
+                    String::from_utf8_lossy(std::slice::from_raw_parts(hs.ptr, hs.len)).into_owned()
+                })
+                .collect()
+        }
+    }
+
+    fn raw_field(obj: *mut u8, name: &str) -> (u32, u64) {
+        let arc = unsafe { &*(obj as *const Arc<AnonObject>) };
+        match &arc.data {
+            AnonData::Map(map) => map[name],
+            AnonData::Inline(_) => panic!("metadata objects are map-backed"),
+        }
+    }
+
+    #[test]
+    fn test_type_and_field_metadata() {
+        register_meta_from_mir(
+            0x4d45_5441,
+            TypeMeta {
+                obj: vec![
+                    ("author".into(), vec![MetaValue::String("x".into())]),
+                    ("keep".into(), vec![]),
+                ],
+                fields: vec![(
+                    "route".into(),
+                    vec![(
+                        "path".into(),
+                        vec![MetaValue::String("/a".into()), MetaValue::Int(2)],
+                    )],
+                )],
+                statics: vec![],
+            },
+        );
+
+        let obj = haxe_meta_get_type(0x4d45_5441);
+        assert_eq!(field_names(obj), ["author", "keep"]);
+        assert_eq!(raw_field(obj, "keep"), (TYPE_NULL.0, 0));
+        let (ty, arr) = raw_field(obj, "author");
+        assert_eq!(ty, TYPE_META_ARRAY.0);
+        let s = unsafe { meta_array_to_string(arr as *const u8) };
+        let s = unsafe { std::str::from_utf8(std::slice::from_raw_parts(s.ptr, s.len)).unwrap() };
+        assert_eq!(s, "[x]");
+
+        let fields = haxe_meta_get_fields(0x4d45_5441);
+        assert_eq!(field_names(fields), ["route"]);
+        let (ty, route) = raw_field(fields, "route");
+        assert_eq!(ty, TYPE_ANON_OBJECT.0);
+        assert_eq!(field_names(route as *mut u8), ["path"]);
+
+        assert!(field_names(haxe_meta_get_statics(0x4d45_5441)).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_type_is_empty_object() {
+        assert!(field_names(haxe_meta_get_type(0x4d45_5442)).is_empty());
+        assert!(field_names(haxe_meta_get_fields(0x4d45_5442)).is_empty());
+    }
+}
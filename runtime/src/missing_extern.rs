@@ -0,0 +1,157 @@
+//! Diagnostics for externs no loaded symbol table provides
+//!
+//! When compiled code references an extern that neither the runtime, the GPU
+//! plugin nor any loaded rpkg exports, the JIT binds the call to a stub
+//! instead of failing at link time (or jumping to a null address). The
+//! program runs normally until the extern is actually called; the stub then
+//! calls [`rayzor_missing_extern`] with the index returned by [`register`],
+//! which reports the missing symbol, the Haxe declaration that required it
+//! and where the symbol most likely comes from, and aborts.
+
+use std::fmt;
+
+use parking_lot::Mutex;
+
+/// An extern that was bound to a diagnostic stub.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingExtern {
+    /// Native symbol name, e.g. `rayzor_gpu_compute_create`
+    pub symbol: String,
+    /// Haxe declaration that required it, e.g. `GPUCompute.create`
+    pub declaration: Option<String>,
+}
+
+impl MissingExtern {
+    pub fn new(symbol: impl Into<String>, declaration: Option<String>) -> Self {
+        Self {
+            symbol: symbol.into(),
+            declaration,
+        }
+    }
+
+    /// Best guess at which plugin or package should export the symbol.
+    pub fn provider(&self) -> String {
+        likely_provider(&self.symbol)
+    }
+}
+
+impl fmt::Display for MissingExtern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "fatal runtime error: call to missing extern '{}'",
+            self.symbol
+        )?;
+        match &self.declaration {
+            Some(decl) => writeln!(f, "note: required by the Haxe extern '{}'", decl)?,
+            None => writeln!(f, "note: required by an extern declaration in the program")?,
+        }
+        write!(f, "note: probably provided by {}", self.provider())
+    }
+}
+
+/// Guess the provider of `symbol` from its namespace prefix.
+///
+/// Plugins prefix their exports with `<name>_` or `rayzor_<name>_` (see
+/// `rayzor_plugin::symbols`), so the first segment after an optional
+/// `rayzor_` names the package.
+pub fn likely_provider(symbol: &str) -> String {
+    if symbol.starts_with("rayzor_gpu_") {
+        return "the rayzor-gpu plugin; build it so librayzor_gpu sits next to the \
+                rayzor binary and check `rayzor info --plugins`"
+            .to_string();
+    }
+    if symbol.starts_with("haxe_") {
+        return "the rayzor runtime; the runtime in this build is older than the \
+                standard library that declares it"
+            .to_string();
+    }
+
+    let (rest, runtime_namespace) = match symbol.strip_prefix("rayzor_") {
+        Some(rest) => (rest, true),
+        None => (symbol, false),
+    };
+    match rest.split_once('_') {
+        Some((package, _)) if !package.is_empty() => {
+            let rpkg = format!(
+                "an rpkg named '{package}'; load it with `--rpkg {package}.rpkg` and \
+                 check `rayzor info --plugins --rpkg {package}.rpkg`"
+            );
+            if runtime_namespace {
+                format!("the rayzor runtime or {rpkg}")
+            } else {
+                rpkg
+            }
+        }
+        _ => "a native library; export it from an rpkg or plugin".to_string(),
+    }
+}
+
+/// Externs bound to stubs, indexed by the value passed to the stub.
+static MISSING: Mutex<Vec<MissingExtern>> = Mutex::new(Vec::new());
+
+/// Record a missing extern, returning the index its stub should pass to
+/// [`rayzor_missing_extern`]. Registering the same symbol twice returns the
+/// existing index.
+pub fn register(missing: MissingExtern) -> i64 {
+    let mut table = MISSING.lock();
+    if let Some(index) = table.iter().position(|m| m.symbol == missing.symbol) {
+        return index as i64;
+    }
+    table.push(missing);
+    table.len() as i64 - 1
+}
+
+/// Look up a registered missing extern.
+pub fn get(index: i64) -> Option<MissingExtern> {
+    let table = MISSING.lock();
+    usize::try_from(index)
+        .ok()
+        .and_then(|i| table.get(i).cloned())
+}
+
+/// Called by a missing-extern stub: print the diagnostic and abort.
+#[no_mangle]
+pub extern "C" fn rayzor_missing_extern(index: i64) -> ! {
+    match get(index) {
+        Some(missing) => eprintln!("{}", missing),
+        None => eprintln!("fatal runtime error: call to missing extern #{}", index),
+    }
+    std::process::abort()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_likely_provider() {
+        assert!(likely_provider("rayzor_gpu_compute_create").starts_with("the rayzor-gpu plugin"));
+        assert!(likely_provider("haxe_string_char_at").starts_with("the rayzor runtime;"));
+        assert!(likely_provider("sqlite_open").starts_with("an rpkg named 'sqlite'"));
+        assert!(likely_provider("rayzor_sqlite_open")
+            .starts_with("the rayzor runtime or an rpkg named 'sqlite'"));
+        assert!(likely_provider("frobnicate").starts_with("a native library"));
+    }
+
+    #[test]
+    fn test_register_and_format() {
+        let missing = MissingExtern::new("mathx_gamma", Some("mathx.Special.gamma".to_string()));
+        let index = register(missing.clone());
+        assert_eq!(register(missing.clone()), index);
+        assert_eq!(get(index), Some(missing.clone()));
+        assert_eq!(get(-1), None);
+
+        let text = missing.to_string();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(
+            lines[0],
+            "fatal runtime error: call to missing extern 'mathx_gamma'"
+        );
+        assert_eq!(
+            lines[1],
+            "note: required by the Haxe extern 'mathx.Special.gamma'"
+        );
+        assert!(lines[2].starts_with("note: probably provided by an rpkg named 'mathx'"));
+    }
+}
@@ -0,0 +1,131 @@
+//! Loading bundles into a running program (`rayzor.Runtime.loadModule`)
+//!
+//! The runtime doesn't know how to compile MIR; the host that runs the
+//! program (the `rayzor` CLI, or an embedding application) installs a
+//! loader with [`set_loader`], and `rayzor.Runtime.loadModule` calls it with
+//! the bundle path and the entry point to run. Without a loader every load
+//! fails with an error saying so.
+//!
+//! ```haxe
+//! if (!rayzor.Runtime.loadModuleEntry("plugins/foo.rzb", "init"))
+//!     trace(rayzor.Runtime.loadError());
+//! ```
+//!
+//! The loader is called without any lock held, so a module may load further
+//! modules while it initializes.
+
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use crate::haxe_string::HaxeString;
+
+/// Loads the bundle at `path` and runs `entry` (a named entry point of the
+/// bundle), or its default entry point when `entry` is None.
+pub type ModuleLoader = dyn Fn(&str, Option<&str>) -> Result<(), String> + Send + Sync;
+
+static LOADER: RwLock<Option<Arc<ModuleLoader>>> = RwLock::new(None);
+
+thread_local! {
+    /// Error of the last failed load on this thread
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Install the loader used by `rayzor.Runtime.loadModule`, replacing any
+/// previous one.
+pub fn set_loader(loader: Arc<ModuleLoader>) {
+    *LOADER.write() = Some(loader);
+}
+
+/// Remove the installed loader.
+pub fn clear_loader() {
+    *LOADER.write() = None;
+}
+
+/// Load a bundle through the installed loader. On failure the error is
+/// also kept for [`rayzor_runtime_load_error`].
+pub fn load(path: &str, entry: Option<&str>) -> Result<(), String> {
+    let loader = LOADER.read().clone();
+    let result = match loader {
+        Some(loader) => loader(path, entry),
+        None => Err(format!(
+            "cannot load '{}': this host does not support loading modules at runtime",
+            path
+        )),
+    };
+    LAST_ERROR.with(|last| *last.borrow_mut() = result.as_ref().err().cloned());
+    result
+}
+
+unsafe fn string_arg(s: *const HaxeString) -> Option<String> {
+    if s.is_null() || (*s).ptr.is_null() {
+        return None;
+    }
+    let bytes = std::slice::from_raw_parts((*s).ptr, (*s).len);
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// `rayzor.Runtime.loadModule(path)`: load a bundle and run its default
+/// entry point. Returns false on failure.
+#[no_mangle]
+pub extern "C" fn rayzor_runtime_load_module(path: *const HaxeString) -> bool {
+    rayzor_runtime_load_module_entry(path, std::ptr::null())
+}
+
+/// `rayzor.Runtime.loadModuleEntry(path, entry)`: load a bundle and run the
+/// named entry point. Returns false on failure.
+#[no_mangle]
+pub extern "C" fn rayzor_runtime_load_module_entry(
+    path: *const HaxeString,
+    entry: *const HaxeString,
+) -> bool {
+    let Some(path) = (unsafe { string_arg(path) }) else {
+        LAST_ERROR.with(|last| *last.borrow_mut() = Some("module path is null".to_string()));
+        return false;
+    };
+    let entry = unsafe { string_arg(entry) };
+    load(&path, entry.as_deref()).is_ok()
+}
+
+/// `rayzor.Runtime.loadError()`: why the last load on this thread failed,
+/// or null if it succeeded.
+#[no_mangle]
+pub extern "C" fn rayzor_runtime_load_error() -> *mut HaxeString {
+    match LAST_ERROR.with(|last| last.borrow().clone()) {
+        Some(error) => {
+            let mut bytes = error.into_bytes();
+            let (ptr, len, cap) = (bytes.as_mut_ptr(), bytes.len(), bytes.capacity());
+            std::mem::forget(bytes);
+            Box::into_raw(Box::new(HaxeString { ptr, len, cap }))
+        }
+        None => std::ptr::null_mut(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_through_loader() {
+        clear_loader();
+        let err = load("plugins/foo.rzb", None).unwrap_err();
+        assert!(err.contains("does not support loading modules"));
+        assert!(!rayzor_runtime_load_error().is_null());
+
+        set_loader(Arc::new(|path: &str, entry: Option<&str>| {
+            match (path, entry) {
+                ("plugins/foo.rzb", Some("init")) => Ok(()),
+                _ => Err(format!("no module {}", path)),
+            }
+        }));
+        assert_eq!(load("plugins/foo.rzb", Some("init")), Ok(()));
+        assert!(rayzor_runtime_load_error().is_null());
+        assert_eq!(
+            load("plugins/bar.rzb", None),
+            Err("no module plugins/bar.rzb".to_string())
+        );
+        clear_loader();
+    }
+}
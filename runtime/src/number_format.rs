@@ -0,0 +1,145 @@
+//! Haxe-compatible number formatting
+//!
+//! Float output follows the ECMAScript `Number.prototype.toString` rules that
+//! the JS and HashLink targets print with:
+//!
+//! - the shortest digit string that round-trips to the same `f64`
+//! - plain notation for magnitudes in `[1e-7, 1e21)`: `0.000001`, `1e20` as
+//!   `100000000000000000000`
+//! - scientific notation otherwise, with an explicit exponent sign: `1e+21`,
+//!   `1.5e-7`
+//! - `NaN`, `Infinity`, `-Infinity`, and `0` for negative zero
+//!
+//! Rust's `Display` for `f64` already produces shortest round-trip digits but
+//! never switches to scientific notation and prints `inf`/`-0`, so all float
+//! printing in the runtime goes through [`format_float`].
+
+use crate::haxe_string::HaxeString;
+
+/// Format a Float the way Haxe prints it (`Std.string`, `trace`, `"" + f`).
+pub fn format_float(value: f64) -> String {
+    if value.is_nan() {
+        return "NaN".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    if value == 0.0 {
+        return "0".to_string();
+    }
+
+    // `{:e}` yields the shortest round-trip digits as `d.ddde±x`
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("{:e} output always has an exponent");
+    let digits: String = mantissa.chars().filter(|&c| c != '.').collect();
+    let exponent: i32 = exponent.parse().expect("{:e} exponent is an integer");
+
+    // value = 0.digits × 10^point
+    let k = digits.len() as i32;
+    let point = exponent + 1;
+
+    let mut out = String::with_capacity(digits.len() + 8);
+    if value < 0.0 {
+        out.push('-');
+    }
+    if k <= point && point <= 21 {
+        // Integer: digits followed by zeros
+        out.push_str(&digits);
+        out.push_str(&"0".repeat((point - k) as usize));
+    } else if 0 < point && point <= 21 {
+        // Decimal point inside the digits
+        out.push_str(&digits[..point as usize]);
+        out.push('.');
+        out.push_str(&digits[point as usize..]);
+    } else if -6 < point && point <= 0 {
+        // Small magnitude: leading zeros after the point
+        out.push_str("0.");
+        out.push_str(&"0".repeat((-point) as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        out.push('e');
+        out.push(if exponent < 0 { '-' } else { '+' });
+        out.push_str(&exponent.unsigned_abs().to_string());
+    }
+    out
+}
+
+/// Uppercase hexadecimal form of an Int, zero-padded to at least `digits`
+/// characters. Negative values print as their 32-bit two's complement.
+pub fn format_hex(value: i32, digits: i32) -> String {
+    format!("{:0width$X}", value as u32, width = digits.max(0) as usize)
+}
+
+/// Allocate a HaxeString holding `s`.
+fn new_haxe_string(s: &str) -> *mut HaxeString {
+    let out = Box::into_raw(Box::new(HaxeString {
+        ptr: std::ptr::null_mut(),
+        len: 0,
+        cap: 0,
+    }));
+    crate::haxe_string::haxe_string_from_bytes(out, s.as_ptr(), s.len());
+    out
+}
+
+/// StringTools.hex(n): uppercase hexadecimal representation of `n`
+#[no_mangle]
+pub extern "C" fn haxe_string_hex(value: i32) -> *mut HaxeString {
+    new_haxe_string(&format_hex(value, 0))
+}
+
+/// StringTools.hex(n, digits): hexadecimal representation of `n`, left-padded
+/// with zeros to at least `digits` characters
+#[no_mangle]
+pub extern "C" fn haxe_string_hex_digits(value: i32, digits: i32) -> *mut HaxeString {
+    new_haxe_string(&format_hex(value, digits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_float_plain() {
+        assert_eq!(format_float(1.0), "1");
+        assert_eq!(format_float(-2.5), "-2.5");
+        assert_eq!(format_float(0.1 + 0.2), "0.30000000000000004");
+        assert_eq!(format_float(1.0 / 3.0), "0.3333333333333333");
+        assert_eq!(format_float(123456789.125), "123456789.125");
+        assert_eq!(format_float(0.000001), "0.000001");
+        assert_eq!(format_float(1e20), "100000000000000000000");
+    }
+
+    #[test]
+    fn test_format_float_scientific() {
+        assert_eq!(format_float(1e21), "1e+21");
+        assert_eq!(format_float(1.5e300), "1.5e+300");
+        assert_eq!(format_float(1e-7), "1e-7");
+        assert_eq!(format_float(-1.25e-10), "-1.25e-10");
+        assert_eq!(format_float(f64::MIN_POSITIVE), "2.2250738585072014e-308");
+    }
+
+    #[test]
+    fn test_format_float_special() {
+        assert_eq!(format_float(f64::NAN), "NaN");
+        assert_eq!(format_float(f64::INFINITY), "Infinity");
+        assert_eq!(format_float(f64::NEG_INFINITY), "-Infinity");
+        assert_eq!(format_float(-0.0), "0");
+        assert_eq!(format_float(0.0), "0");
+    }
+
+    #[test]
+    fn test_format_hex() {
+        assert_eq!(format_hex(255, 0), "FF");
+        assert_eq!(format_hex(255, 4), "00FF");
+        assert_eq!(format_hex(0, 0), "0");
+        assert_eq!(format_hex(0x12345, 2), "12345");
+        assert_eq!(format_hex(-1, 0), "FFFFFFFF");
+    }
+}
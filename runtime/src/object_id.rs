@@ -0,0 +1,177 @@
+//! Identity ids for heap objects
+//!
+//! `ObjectMap` and `WeakMap` hash their keys by identity. A raw address is not
+//! a stable identity on its own: once an object is freed, a new object can be
+//! allocated at the same address and would alias the old key. Instead, each
+//! object gets a process-wide id the first time it is used as a key:
+//!
+//! - ids are assigned from a counter and never reused
+//! - when the object's storage is released (`rayzor_free`,
+//!   `rayzor_tracked_free`, `rayzor_anon_drop`) its id is retired, so a later
+//!   object at the same address gets a fresh id
+//! - when storage moves (`rayzor_realloc`, `rayzor_tracked_realloc`) the id
+//!   follows the object to its new address
+//!
+//! Objects that were never used as keys cost nothing: the release and
+//! relocate hooks return after a single atomic load until the first id is
+//! assigned.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Live ids by object address, plus the next id to hand out.
+struct IdTable {
+    ids: HashMap<usize, u64>,
+    next_id: u64,
+}
+
+static TABLE: Mutex<Option<IdTable>> = Mutex::new(None);
+
+/// Set once the first id is assigned; lets the allocator hooks skip the lock.
+static TRACKING: AtomicBool = AtomicBool::new(false);
+
+/// Id of the object at `addr`, assigning a new one if it has none.
+///
+/// Returns 0 for null. Ids start at 1.
+pub fn id_of(addr: usize) -> u64 {
+    if addr == 0 {
+        return 0;
+    }
+    // The table holds addresses, not references: the collector must not
+    // scan it
+    crate::gc::untracked(|| {
+        let mut table = TABLE.lock();
+        let table = table.get_or_insert_with(|| {
+            TRACKING.store(true, Ordering::Release);
+            IdTable {
+                ids: HashMap::new(),
+                next_id: 1,
+            }
+        });
+        *table.ids.entry(addr).or_insert_with(|| {
+            let id = table.next_id;
+            table.next_id += 1;
+            id
+        })
+    })
+}
+
+/// Id of the object at `addr`, if one has been assigned.
+pub fn existing_id(addr: usize) -> Option<u64> {
+    if addr == 0 || !TRACKING.load(Ordering::Acquire) {
+        return None;
+    }
+    TABLE.lock().as_ref()?.ids.get(&addr).copied()
+}
+
+/// Whether `id` still names the object at `addr` (it has not been released).
+pub fn is_live(addr: usize, id: u64) -> bool {
+    existing_id(addr) == Some(id)
+}
+
+/// Retire the id of the object at `addr`; called when its storage is freed.
+pub fn release(addr: usize) {
+    if !TRACKING.load(Ordering::Acquire) {
+        return;
+    }
+    if let Some(table) = TABLE.lock().as_mut() {
+        table.ids.remove(&addr);
+    }
+}
+
+/// Move the id of an object whose storage moved from `old` to `new`.
+pub fn relocate(old: usize, new: usize) {
+    if old == new || !TRACKING.load(Ordering::Acquire) {
+        return;
+    }
+    if let Some(table) = TABLE.lock().as_mut() {
+        if let Some(id) = table.ids.remove(&old) {
+            crate::gc::untracked(|| table.ids.insert(new, id));
+        }
+    }
+}
+
+/// Identity id of an object (0 for null)
+///
+/// Stable for the object's lifetime, including across reallocation, and
+/// never reused for another object.
+#[no_mangle]
+pub extern "C" fn rayzor_object_id(ptr: *const u8) -> i64 {
+    id_of(ptr as usize) as i64
+}
+
+/// Retire the identity id of an object whose storage is about to be freed
+///
+/// For allocators outside the runtime; `rayzor_free` and friends already do
+/// this.
+#[no_mangle]
+pub extern "C" fn rayzor_object_id_release(ptr: *const u8) {
+    release(ptr as usize);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_are_stable_and_not_reused() {
+        let a = Box::into_raw(Box::new(0u64)) as usize;
+        let b = Box::into_raw(Box::new(0u64)) as usize;
+        let id_a = id_of(a);
+        assert_ne!(id_a, 0);
+        assert_eq!(id_of(a), id_a);
+        assert_ne!(id_of(b), id_a);
+        assert_eq!(id_of(0), 0);
+
+        // A released address gets a fresh id
+        release(a);
+        assert_eq!(existing_id(a), None);
+        assert!(!is_live(a, id_a));
+        assert_ne!(id_of(a), id_a);
+
+        unsafe {
+            drop(Box::from_raw(a as *mut u64));
+            drop(Box::from_raw(b as *mut u64));
+        }
+    }
+
+    #[test]
+    fn test_weakmap_drops_released_keys() {
+        use crate::haxe_map::*;
+
+        let strong = haxe_objectmap_new();
+        let weak = haxe_weakmap_new();
+        let key = Box::into_raw(Box::new(0u64)) as u64;
+        haxe_objectmap_set(strong, key, 1);
+        haxe_objectmap_set(weak, key, 2);
+        assert_eq!(haxe_objectmap_get(weak, key), 2);
+        assert_eq!(haxe_objectmap_count(weak), 1);
+
+        // The key's storage is released: the weak entry goes away, and the
+        // strong entry is no longer reachable through the reused address
+        release(key as usize);
+        assert_eq!(haxe_objectmap_count(weak), 0);
+        assert_eq!(haxe_objectmap_count(strong), 1);
+        assert!(!haxe_objectmap_exists(strong, key));
+
+        unsafe {
+            drop(Box::from_raw(key as *mut u64));
+        }
+    }
+
+    #[test]
+    fn test_id_follows_relocation() {
+        let old = Box::into_raw(Box::new(0u64)) as usize;
+        let new = Box::into_raw(Box::new(0u64)) as usize;
+        let id = id_of(old);
+        relocate(old, new);
+        assert_eq!(existing_id(new), Some(id));
+        assert_eq!(existing_id(old), None);
+
+        unsafe {
+            drop(Box::from_raw(old as *mut u64));
+            drop(Box::from_raw(new as *mut u64));
+        }
+    }
+}
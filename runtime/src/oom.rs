@@ -0,0 +1,184 @@
+//! Out-of-memory handling for runtime allocations
+//!
+//! When an allocation fails, the runtime consults an optional user-installed
+//! handler before giving up. The handler can release memory (trigger a GC,
+//! reset an arena, drop caches), log the event, or abort with its own report.
+//!
+//! ```c
+//! // Return non-zero to retry the allocation, zero to give up.
+//! int32_t on_oom(uint64_t size, uint32_t attempt);
+//! rayzor_set_oom_handler(on_oom);
+//! ```
+//!
+//! Runtime allocators (`rayzor_malloc`, `rayzor_realloc`, `rayzor_tracked_alloc`)
+//! retry through the handler and return null if it declines. Compiled code built
+//! with `--safety checked` calls `rayzor_alloc_failed` when `malloc` returns
+//! null and `rayzor_realloc_failed` when `realloc` does. Both retry through the
+//! handler and abort with a report if memory still cannot be obtained.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Allocation failure callback.
+///
+/// Receives the requested size and the 1-based retry attempt. Returns non-zero
+/// if the allocation should be retried, zero to give up.
+pub type OomHandler = extern "C" fn(size: u64, attempt: u32) -> i32;
+
+/// Maximum number of times a failed allocation is retried through the handler.
+pub const MAX_OOM_RETRIES: u32 = 3;
+
+/// Installed handler as a raw function address (0 = none).
+static OOM_HANDLER: AtomicUsize = AtomicUsize::new(0);
+
+/// Install an allocation-failure handler, returning the previous one.
+///
+/// Pass null to remove the handler.
+#[no_mangle]
+pub extern "C" fn rayzor_set_oom_handler(handler: Option<OomHandler>) -> Option<OomHandler> {
+    let new = handler.map_or(0, |h| h as usize);
+    let old = OOM_HANDLER.swap(new, Ordering::SeqCst);
+    handler_from_raw(old)
+}
+
+/// Get the currently installed allocation-failure handler.
+pub fn oom_handler() -> Option<OomHandler> {
+    handler_from_raw(OOM_HANDLER.load(Ordering::SeqCst))
+}
+
+fn handler_from_raw(raw: usize) -> Option<OomHandler> {
+    if raw == 0 {
+        None
+    } else {
+        // SAFETY: only ever stored from a valid `OomHandler` in `rayzor_set_oom_handler`
+        Some(unsafe { std::mem::transmute::<usize, OomHandler>(raw) })
+    }
+}
+
+/// Retry a failed allocation through the installed handler.
+///
+/// `alloc` is invoked again each time the handler asks for a retry. Returns the
+/// first non-null result, or null if there is no handler, the handler gives up,
+/// or `MAX_OOM_RETRIES` is exhausted.
+pub fn retry_allocation(size: u64, mut alloc: impl FnMut() -> *mut u8) -> *mut u8 {
+    let Some(handler) = oom_handler() else {
+        return std::ptr::null_mut();
+    };
+
+    for attempt in 1..=MAX_OOM_RETRIES {
+        if handler(size, attempt) == 0 {
+            break;
+        }
+        let ptr = alloc();
+        if !ptr.is_null() {
+            return ptr;
+        }
+    }
+
+    std::ptr::null_mut()
+}
+
+/// Print an out-of-memory report and abort the process.
+pub fn oom_abort(size: u64) -> ! {
+    eprintln!(
+        "fatal runtime error: out of memory (failed to allocate {} bytes)",
+        size
+    );
+    if oom_handler().is_some() {
+        eprintln!("note: the installed OOM handler could not recover enough memory");
+    } else {
+        eprintln!("note: install a handler with rayzor_set_oom_handler to recover from allocation failures");
+    }
+    std::process::abort()
+}
+
+/// Slow path for null `malloc` results in checked code.
+///
+/// Emitted by the compiler after `malloc` calls when allocation checks are
+/// enabled. Retries `malloc(size)` through the OOM handler and aborts with a
+/// report if memory cannot be obtained; never returns null.
+#[no_mangle]
+pub extern "C" fn rayzor_alloc_failed(size: u64) -> *mut u8 {
+    let ptr = retry_allocation(size, || unsafe { libc::malloc(size as usize) as *mut u8 });
+    if ptr.is_null() {
+        oom_abort(size);
+    }
+    ptr
+}
+
+/// Slow path for null `realloc` results in checked code.
+///
+/// A failed `realloc` leaves the old block untouched, so this retries
+/// `realloc(old, size)` through the OOM handler and the contents carry over.
+/// Aborts with a report if memory cannot be obtained; never returns null.
+#[no_mangle]
+pub extern "C" fn rayzor_realloc_failed(old: *mut u8, size: u64) -> *mut u8 {
+    let ptr = retry_allocation(size, || unsafe {
+        libc::realloc(old as *mut libc::c_void, size as usize) as *mut u8
+    });
+    if ptr.is_null() {
+        oom_abort(size);
+    }
+    ptr
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+    use std::sync::Mutex;
+
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+
+    /// The handler is process-wide; tests that install one take this lock
+    static HANDLER_LOCK: Mutex<()> = Mutex::new(());
+
+    extern "C" fn give_up(_size: u64, _attempt: u32) -> i32 {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+        0
+    }
+
+    #[test]
+    fn test_oom_handler_retry() {
+        let _guard = HANDLER_LOCK.lock().unwrap();
+
+        // No handler: nothing is retried
+        let prev = rayzor_set_oom_handler(None);
+        assert!(retry_allocation(8, || unreachable!()).is_null());
+
+        // Handler declines: allocation is not retried
+        rayzor_set_oom_handler(Some(give_up));
+        let ptr = retry_allocation(8, || unreachable!());
+        assert!(ptr.is_null());
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+
+        // Swapping returns the previous handler
+        let old = rayzor_set_oom_handler(prev);
+        assert_eq!(old.map(|h| h as usize), Some(give_up as *const () as usize));
+    }
+
+    extern "C" fn retry(_size: u64, _attempt: u32) -> i32 {
+        1
+    }
+
+    #[test]
+    fn test_realloc_failed_keeps_contents() {
+        let _guard = HANDLER_LOCK.lock().unwrap();
+        let prev = rayzor_set_oom_handler(Some(retry));
+
+        unsafe {
+            let old = libc::malloc(16) as *mut u8;
+            for i in 0..16 {
+                *old.add(i) = i as u8;
+            }
+            // As if realloc(old, 4096) had returned null in checked code
+            let grown = rayzor_realloc_failed(old, 4096);
+            assert!(!grown.is_null());
+            for i in 0..16 {
+                assert_eq!(*grown.add(i), i as u8);
+            }
+            libc::free(grown as *mut libc::c_void);
+        }
+
+        rayzor_set_oom_handler(prev);
+    }
+}
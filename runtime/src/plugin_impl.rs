@@ -103,6 +103,25 @@ register_symbol!(
     "haxe_string_replace",
     crate::haxe_string::haxe_string_replace
 );
+register_symbol!("haxe_string_lpad", crate::haxe_string::haxe_string_lpad);
+register_symbol!("haxe_string_rpad", crate::haxe_string::haxe_string_rpad);
+register_symbol!("haxe_string_hex", crate::number_format::haxe_string_hex);
+register_symbol!(
+    "haxe_string_hex_digits",
+    crate::number_format::haxe_string_hex_digits
+);
+register_symbol!(
+    "haxe_collator_compare",
+    crate::collation::haxe_collator_compare
+);
+register_symbol!(
+    "haxe_collator_compare_ignore_case",
+    crate::collation::haxe_collator_compare_ignore_case
+);
+register_symbol!(
+    "haxe_collator_fold_case",
+    crate::collation::haxe_collator_fold_case
+);
 register_symbol!(
     "haxe_string_starts_with",
     crate::string::haxe_string_starts_with
@@ -165,6 +184,10 @@ register_symbol!("haxe_array_reverse", crate::haxe_array::haxe_array_reverse);
 register_symbol!("haxe_array_copy", crate::haxe_array::haxe_array_copy);
 register_symbol!("haxe_array_slice", crate::haxe_array::haxe_array_slice);
 register_symbol!("haxe_array_join", crate::haxe_array::haxe_array_join);
+register_symbol!(
+    "haxe_array_join_typed",
+    crate::haxe_array::haxe_array_join_typed
+);
 register_symbol!("haxe_array_concat", crate::haxe_array::haxe_array_concat);
 register_symbol!("haxe_array_splice", crate::haxe_array::haxe_array_splice);
 
@@ -181,6 +204,14 @@ register_symbol!(
     "haxe_array_contains",
     crate::haxe_array::haxe_array_contains
 );
+register_symbol!(
+    "haxe_array_index_of_typed",
+    crate::haxe_array::haxe_array_index_of_typed
+);
+register_symbol!(
+    "haxe_array_contains_typed",
+    crate::haxe_array::haxe_array_contains_typed
+);
 
 // Mutation
 register_symbol!("haxe_array_shift", crate::haxe_array::haxe_array_shift);
@@ -190,6 +221,22 @@ register_symbol!(
 );
 register_symbol!("haxe_array_unshift", crate::haxe_array::haxe_array_unshift);
 register_symbol!("haxe_array_resize", crate::haxe_array::haxe_array_resize);
+register_symbol!(
+    "haxe_array_resize_fill",
+    crate::haxe_array::haxe_array_resize_fill
+);
+register_symbol!(
+    "haxe_array_shrink_to_fit",
+    crate::haxe_array::haxe_array_shrink_to_fit
+);
+register_symbol!(
+    "haxe_array_check_iteration",
+    crate::haxe_array::haxe_array_check_iteration
+);
+register_symbol!(
+    "haxe_array_check_sorted",
+    crate::haxe_array::haxe_array_check_sorted
+);
 
 // String representation
 register_symbol!(
@@ -237,6 +284,7 @@ register_symbol!("haxe_math_max", crate::haxe_math::haxe_math_max);
 register_symbol!("haxe_math_floor", crate::haxe_math::haxe_math_floor);
 register_symbol!("haxe_math_ceil", crate::haxe_math::haxe_math_ceil);
 register_symbol!("haxe_math_round", crate::haxe_math::haxe_math_round);
+register_symbol!("haxe_math_fround", crate::haxe_math::haxe_math_fround);
 
 // Trigonometric
 register_symbol!("haxe_math_sin", crate::haxe_math::haxe_math_sin);
@@ -283,6 +331,11 @@ register_symbol!(
 register_symbol!("haxe_trace_any", crate::haxe_sys::haxe_trace_any);
 register_symbol!("haxe_trace_typed", crate::haxe_sys::haxe_trace_typed);
 register_symbol!("haxe_trace_array", crate::haxe_sys::haxe_trace_array);
+register_symbol!("haxe_trace_pos", crate::haxe_sys::haxe_trace_pos);
+register_symbol!(
+    "rayzor_set_trace_positions",
+    crate::haxe_sys::rayzor_set_trace_positions
+);
 
 // Enum RTTI
 register_symbol!("haxe_register_enum", crate::type_system::haxe_register_enum);
@@ -330,6 +383,7 @@ register_symbol!(
     "haxe_type_enum_parameters",
     crate::type_system::haxe_type_enum_parameters
 );
+register_symbol!("haxe_type_enum_eq", crate::enum_value::haxe_type_enum_eq);
 // Type RTTI query functions
 register_symbol!(
     "haxe_type_get_class_name",
@@ -367,6 +421,10 @@ register_symbol!(
     "haxe_type_create_enum_index",
     crate::type_system::haxe_type_create_enum_index
 );
+// haxe.rtti.Meta
+register_symbol!("haxe_meta_get_type", crate::meta::haxe_meta_get_type);
+register_symbol!("haxe_meta_get_fields", crate::meta::haxe_meta_get_fields);
+register_symbol!("haxe_meta_get_statics", crate::meta::haxe_meta_get_statics);
 register_symbol!("haxe_trace_enum", crate::type_system::haxe_trace_enum);
 register_symbol!(
     "haxe_trace_enum_boxed",
@@ -390,6 +448,14 @@ register_symbol!(
     "haxe_safe_downcast_class",
     crate::type_system::haxe_safe_downcast_class
 );
+register_symbol!(
+    "haxe_checked_cast_class",
+    crate::type_system::haxe_checked_cast_class
+);
+register_symbol!(
+    "haxe_interface_object",
+    crate::type_system::haxe_interface_object
+);
 
 // Std.string() - Type-specific conversions
 register_symbol!(
@@ -456,7 +522,13 @@ register_symbol!(
 // Program control
 register_symbol!("haxe_sys_exit", crate::haxe_sys::haxe_sys_exit);
 register_symbol!("haxe_sys_time", crate::haxe_sys::haxe_sys_time);
+register_symbol!(
+    "rayzor_time_monotonic_ns",
+    crate::haxe_sys::rayzor_time_monotonic_ns
+);
+register_symbol!("haxe_timer_stamp", crate::haxe_sys::haxe_timer_stamp);
 register_symbol!("haxe_sys_args_count", crate::haxe_sys::haxe_sys_args_count);
+register_symbol!("haxe_sys_args", crate::haxe_sys::haxe_sys_args);
 
 // Environment
 register_symbol!("haxe_sys_get_env", crate::haxe_sys::haxe_sys_get_env);
@@ -726,98 +798,212 @@ register_symbol!("haxe_bytes_free", crate::haxe_sys::haxe_bytes_free);
 // ============================================================================
 // StringMap<T> (haxe.ds.StringMap)
 // ============================================================================
-register_symbol!("haxe_stringmap_new", crate::haxe_sys::haxe_stringmap_new);
-register_symbol!("haxe_stringmap_set", crate::haxe_sys::haxe_stringmap_set);
-register_symbol!("haxe_stringmap_get", crate::haxe_sys::haxe_stringmap_get);
+register_symbol!("haxe_stringmap_new", crate::haxe_map::haxe_stringmap_new);
+register_symbol!("haxe_stringmap_set", crate::haxe_map::haxe_stringmap_set);
+register_symbol!("haxe_stringmap_get", crate::haxe_map::haxe_stringmap_get);
 register_symbol!(
     "haxe_stringmap_exists",
-    crate::haxe_sys::haxe_stringmap_exists
+    crate::haxe_map::haxe_stringmap_exists
 );
 register_symbol!(
     "haxe_stringmap_remove",
-    crate::haxe_sys::haxe_stringmap_remove
+    crate::haxe_map::haxe_stringmap_remove
 );
 register_symbol!(
     "haxe_stringmap_clear",
-    crate::haxe_sys::haxe_stringmap_clear
+    crate::haxe_map::haxe_stringmap_clear
 );
 register_symbol!(
     "haxe_stringmap_count",
-    crate::haxe_sys::haxe_stringmap_count
+    crate::haxe_map::haxe_stringmap_count
 );
-register_symbol!("haxe_stringmap_keys", crate::haxe_sys::haxe_stringmap_keys);
+register_symbol!("haxe_stringmap_keys", crate::haxe_map::haxe_stringmap_keys);
 register_symbol!(
     "haxe_stringmap_to_string",
-    crate::haxe_sys::haxe_stringmap_to_string
+    crate::haxe_map::haxe_stringmap_to_string
 );
 
+// ============================================================================
+// List<T> (haxe.ds.List)
+// ============================================================================
+register_symbol!("haxe_list_new", crate::haxe_list::haxe_list_new);
+register_symbol!("haxe_list_add", crate::haxe_list::haxe_list_add);
+register_symbol!("haxe_list_push", crate::haxe_list::haxe_list_push);
+register_symbol!("haxe_list_pop", crate::haxe_list::haxe_list_pop);
+register_symbol!("haxe_list_first", crate::haxe_list::haxe_list_first);
+register_symbol!("haxe_list_last", crate::haxe_list::haxe_list_last);
+register_symbol!("haxe_list_length", crate::haxe_list::haxe_list_length);
+register_symbol!("haxe_list_is_empty", crate::haxe_list::haxe_list_is_empty);
+register_symbol!("haxe_list_clear", crate::haxe_list::haxe_list_clear);
+register_symbol!("haxe_list_remove", crate::haxe_list::haxe_list_remove);
+register_symbol!(
+    "haxe_list_remove_typed",
+    crate::haxe_list::haxe_list_remove_typed
+);
+register_symbol!("haxe_list_join", crate::haxe_list::haxe_list_join);
+register_symbol!("haxe_list_to_string", crate::haxe_list::haxe_list_to_string);
+register_symbol!("haxe_list_filter", crate::haxe_list::haxe_list_filter);
+register_symbol!("haxe_list_map", crate::haxe_list::haxe_list_map);
+register_symbol!("haxe_list_to_array", crate::haxe_list::haxe_list_to_array);
+register_symbol!(
+    "haxe_list_from_array",
+    crate::haxe_list::haxe_list_from_array
+);
+
+// ============================================================================
+// Vector<T> (haxe.ds.Vector)
+// ============================================================================
+register_symbol!(
+    "haxe_vector_i32_new",
+    crate::haxe_vector::haxe_vector_i32_new
+);
+register_symbol!(
+    "haxe_vector_i32_get",
+    crate::haxe_vector::haxe_vector_i32_get
+);
+register_symbol!(
+    "haxe_vector_i32_set",
+    crate::haxe_vector::haxe_vector_i32_set
+);
+register_symbol!(
+    "haxe_vector_i32_fill",
+    crate::haxe_vector::haxe_vector_i32_fill
+);
+register_symbol!(
+    "haxe_vector_i32_from_array",
+    crate::haxe_vector::haxe_vector_i32_from_array
+);
+register_symbol!(
+    "haxe_vector_f64_new",
+    crate::haxe_vector::haxe_vector_f64_new
+);
+register_symbol!(
+    "haxe_vector_f64_get",
+    crate::haxe_vector::haxe_vector_f64_get
+);
+register_symbol!(
+    "haxe_vector_f64_set",
+    crate::haxe_vector::haxe_vector_f64_set
+);
+register_symbol!(
+    "haxe_vector_f64_fill",
+    crate::haxe_vector::haxe_vector_f64_fill
+);
+register_symbol!(
+    "haxe_vector_f64_from_array",
+    crate::haxe_vector::haxe_vector_f64_from_array
+);
+register_symbol!("haxe_vector_new", crate::haxe_vector::haxe_vector_new);
+register_symbol!("haxe_vector_get", crate::haxe_vector::haxe_vector_get);
+register_symbol!("haxe_vector_set", crate::haxe_vector::haxe_vector_set);
+register_symbol!("haxe_vector_fill", crate::haxe_vector::haxe_vector_fill);
+register_symbol!(
+    "haxe_vector_from_array",
+    crate::haxe_vector::haxe_vector_from_array
+);
+register_symbol!("haxe_vector_length", crate::haxe_vector::haxe_vector_length);
+register_symbol!("haxe_vector_copy", crate::haxe_vector::haxe_vector_copy);
+register_symbol!("haxe_vector_blit", crate::haxe_vector::haxe_vector_blit);
+register_symbol!(
+    "haxe_vector_to_array",
+    crate::haxe_vector::haxe_vector_to_array
+);
+
+// ============================================================================
+// Lambda
+// ============================================================================
+register_symbol!("haxe_lambda_exists", crate::haxe_lambda::haxe_lambda_exists);
+register_symbol!(
+    "haxe_lambda_foreach",
+    crate::haxe_lambda::haxe_lambda_foreach
+);
+register_symbol!("haxe_lambda_iter", crate::haxe_lambda::haxe_lambda_iter);
+register_symbol!("haxe_lambda_count", crate::haxe_lambda::haxe_lambda_count);
+register_symbol!("haxe_lambda_find", crate::haxe_lambda::haxe_lambda_find);
+register_symbol!(
+    "haxe_lambda_find_index",
+    crate::haxe_lambda::haxe_lambda_find_index
+);
+register_symbol!("haxe_lambda_fold", crate::haxe_lambda::haxe_lambda_fold);
+register_symbol!("haxe_lambda_foldi", crate::haxe_lambda::haxe_lambda_foldi);
+register_symbol!("haxe_lambda_mapi", crate::haxe_lambda::haxe_lambda_mapi);
+register_symbol!(
+    "haxe_lambda_flatten",
+    crate::haxe_lambda::haxe_lambda_flatten
+);
+register_symbol!("haxe_lambda_empty", crate::haxe_lambda::haxe_lambda_empty);
+register_symbol!("haxe_lambda_array", crate::haxe_lambda::haxe_lambda_array);
+register_symbol!("haxe_lambda_concat", crate::haxe_lambda::haxe_lambda_concat);
+
 // ============================================================================
 // IntMap<T> (haxe.ds.IntMap)
 // ============================================================================
-register_symbol!("haxe_intmap_new", crate::haxe_sys::haxe_intmap_new);
-register_symbol!("haxe_intmap_set", crate::haxe_sys::haxe_intmap_set);
-register_symbol!("haxe_intmap_get", crate::haxe_sys::haxe_intmap_get);
-register_symbol!("haxe_intmap_exists", crate::haxe_sys::haxe_intmap_exists);
-register_symbol!("haxe_intmap_remove", crate::haxe_sys::haxe_intmap_remove);
-register_symbol!("haxe_intmap_clear", crate::haxe_sys::haxe_intmap_clear);
-register_symbol!("haxe_intmap_count", crate::haxe_sys::haxe_intmap_count);
-register_symbol!("haxe_intmap_keys", crate::haxe_sys::haxe_intmap_keys);
+register_symbol!("haxe_intmap_new", crate::haxe_map::haxe_intmap_new);
+register_symbol!("haxe_intmap_set", crate::haxe_map::haxe_intmap_set);
+register_symbol!("haxe_intmap_get", crate::haxe_map::haxe_intmap_get);
+register_symbol!("haxe_intmap_exists", crate::haxe_map::haxe_intmap_exists);
+register_symbol!("haxe_intmap_remove", crate::haxe_map::haxe_intmap_remove);
+register_symbol!("haxe_intmap_clear", crate::haxe_map::haxe_intmap_clear);
+register_symbol!("haxe_intmap_count", crate::haxe_map::haxe_intmap_count);
+register_symbol!("haxe_intmap_keys", crate::haxe_map::haxe_intmap_keys);
 register_symbol!(
     "haxe_intmap_to_string",
-    crate::haxe_sys::haxe_intmap_to_string
+    crate::haxe_map::haxe_intmap_to_string
 );
 register_symbol!(
     "haxe_stringmap_keys_to_array",
-    crate::haxe_sys::haxe_stringmap_keys_to_array
+    crate::haxe_map::haxe_stringmap_keys_to_array
 );
 register_symbol!(
     "haxe_intmap_keys_to_array",
-    crate::haxe_sys::haxe_intmap_keys_to_array
+    crate::haxe_map::haxe_intmap_keys_to_array
 );
 register_symbol!(
     "haxe_stringmap_values_to_array",
-    crate::haxe_sys::haxe_stringmap_values_to_array
+    crate::haxe_map::haxe_stringmap_values_to_array
 );
 register_symbol!(
     "haxe_intmap_values_to_array",
-    crate::haxe_sys::haxe_intmap_values_to_array
+    crate::haxe_map::haxe_intmap_values_to_array
 );
 
 // ============================================================================
-// ObjectMap<K,V> (haxe.ds.ObjectMap) — pointer identity keys
+// ObjectMap<K,V> / WeakMap<K,V> (haxe.ds) — identity keys
 // ============================================================================
-register_symbol!("haxe_objectmap_new", crate::haxe_sys::haxe_objectmap_new);
-register_symbol!("haxe_objectmap_set", crate::haxe_sys::haxe_objectmap_set);
-register_symbol!("haxe_objectmap_get", crate::haxe_sys::haxe_objectmap_get);
+register_symbol!("haxe_objectmap_new", crate::haxe_map::haxe_objectmap_new);
+register_symbol!("haxe_weakmap_new", crate::haxe_map::haxe_weakmap_new);
+register_symbol!("haxe_enummap_new", crate::haxe_map::haxe_enummap_new);
+register_symbol!("haxe_objectmap_set", crate::haxe_map::haxe_objectmap_set);
+register_symbol!("haxe_objectmap_get", crate::haxe_map::haxe_objectmap_get);
 register_symbol!(
     "haxe_objectmap_exists",
-    crate::haxe_sys::haxe_objectmap_exists
+    crate::haxe_map::haxe_objectmap_exists
 );
 register_symbol!(
     "haxe_objectmap_remove",
-    crate::haxe_sys::haxe_objectmap_remove
+    crate::haxe_map::haxe_objectmap_remove
 );
 register_symbol!(
     "haxe_objectmap_clear",
-    crate::haxe_sys::haxe_objectmap_clear
+    crate::haxe_map::haxe_objectmap_clear
 );
 register_symbol!(
     "haxe_objectmap_count",
-    crate::haxe_sys::haxe_objectmap_count
+    crate::haxe_map::haxe_objectmap_count
 );
 register_symbol!(
     "haxe_objectmap_to_string",
-    crate::haxe_sys::haxe_objectmap_to_string
+    crate::haxe_map::haxe_objectmap_to_string
 );
 register_symbol!(
     "haxe_objectmap_keys_to_array",
-    crate::haxe_sys::haxe_objectmap_keys_to_array
+    crate::haxe_map::haxe_objectmap_keys_to_array
 );
 register_symbol!(
     "haxe_objectmap_values_to_array",
-    crate::haxe_sys::haxe_objectmap_values_to_array
+    crate::haxe_map::haxe_objectmap_values_to_array
 );
-register_symbol!("haxe_objectmap_copy", crate::haxe_sys::haxe_objectmap_copy);
+register_symbol!("haxe_objectmap_copy", crate::haxe_map::haxe_objectmap_copy);
 
 // ============================================================================
 // Type System (Dynamic values and Std.string)
@@ -895,6 +1081,10 @@ register_symbol!(
     "rayzor_thread_spawn",
     crate::concurrency::rayzor_thread_spawn
 );
+register_symbol!(
+    "rayzor_thread_spawn_with_stack_size",
+    crate::stack_guard::rayzor_thread_spawn_with_stack_size
+);
 register_symbol!("rayzor_thread_join", crate::concurrency::rayzor_thread_join);
 register_symbol!(
     "rayzor_thread_is_finished",
@@ -921,6 +1111,14 @@ register_symbol!(
     "rayzor_active_thread_count",
     crate::concurrency::rayzor_active_thread_count
 );
+// Deadlock detection (Development preset)
+register_symbol!(
+    "rayzor_set_deadlock_detection",
+    crate::deadlock::rayzor_set_deadlock_detection
+);
+// ThreadSanitizer access hooks (--sanitize thread)
+register_symbol!("rayzor_tsan_read", crate::sanitizer::rayzor_tsan_read);
+register_symbol!("rayzor_tsan_write", crate::sanitizer::rayzor_tsan_write);
 
 // Arc functions
 register_symbol!("rayzor_arc_init", crate::concurrency::rayzor_arc_init);
@@ -1117,6 +1315,36 @@ register_symbol!("sys_thread_yield", crate::concurrency::sys_thread_yield);
 register_symbol!("sys_thread_sleep", crate::concurrency::sys_thread_sleep);
 register_symbol!("sys_thread_current", crate::concurrency::sys_thread_current);
 
+// sys.thread.Coroutine
+register_symbol!(
+    "rayzor_coroutine_create",
+    crate::coroutine::rayzor_coroutine_create
+);
+register_symbol!(
+    "rayzor_coroutine_resume",
+    crate::coroutine::rayzor_coroutine_resume
+);
+register_symbol!(
+    "rayzor_coroutine_yield",
+    crate::coroutine::rayzor_coroutine_yield
+);
+register_symbol!(
+    "rayzor_coroutine_status",
+    crate::coroutine::rayzor_coroutine_status
+);
+register_symbol!(
+    "rayzor_coroutine_is_dead",
+    crate::coroutine::rayzor_coroutine_is_dead
+);
+register_symbol!(
+    "rayzor_coroutine_destroy",
+    crate::coroutine::rayzor_coroutine_destroy
+);
+register_symbol!(
+    "rayzor_coroutine_is_inside",
+    crate::coroutine::rayzor_coroutine_is_inside
+);
+
 // sys.thread.Mutex wrapper functions
 register_symbol!("sys_mutex_alloc", crate::concurrency::sys_mutex_alloc);
 register_symbol!("sys_mutex_acquire", crate::concurrency::sys_mutex_acquire);
@@ -1132,6 +1360,11 @@ register_symbol!("sys_deque_add", crate::concurrency::sys_deque_add);
 register_symbol!("sys_deque_push", crate::concurrency::sys_deque_push);
 register_symbol!("sys_deque_pop", crate::concurrency::sys_deque_pop);
 
+// sys.thread.Tls<T>
+register_symbol!("rayzor_tls_alloc", crate::concurrency::rayzor_tls_alloc);
+register_symbol!("rayzor_tls_get", crate::concurrency::rayzor_tls_get);
+register_symbol!("rayzor_tls_set", crate::concurrency::rayzor_tls_set);
+
 // sys.thread.Condition
 register_symbol!(
     "sys_condition_alloc",
@@ -1425,6 +1658,11 @@ register_symbol!(
     "haxe_reflect_compare_typed",
     crate::reflect::haxe_reflect_compare_typed
 );
+register_symbol!(
+    "haxe_reflect_compare_methods",
+    crate::closure::haxe_reflect_compare_methods
+);
+register_symbol!("rayzor_bind_method", crate::closure::rayzor_bind_method);
 register_symbol!(
     "haxe_reflect_is_enum_value",
     crate::reflect::haxe_reflect_is_enum_value
@@ -1440,6 +1678,14 @@ register_symbol!(
     crate::type_system::haxe_vtable_set_slot
 );
 register_symbol!("haxe_vtable_lookup", crate::type_system::haxe_vtable_lookup);
+register_symbol!(
+    "haxe_class_set_tostring",
+    crate::type_system::haxe_class_set_tostring
+);
+register_symbol!(
+    "haxe_object_to_string",
+    crate::type_system::haxe_object_to_string
+);
 
 // ============================================================================
 // Memory Allocation (libc malloc/free for heap allocations)
@@ -1452,6 +1698,19 @@ register_symbol!("free", libc::free);
 // ============================================================================
 register_symbol!("rayzor_global_store", crate::rayzor_global_store);
 register_symbol!("rayzor_global_load", crate::rayzor_global_load);
+register_symbol!(
+    "rayzor_static_local_init_begin",
+    crate::rayzor_static_local_init_begin
+);
+register_symbol!(
+    "rayzor_static_local_init_end",
+    crate::rayzor_static_local_init_end
+);
+register_symbol!("rayzor_static_local_load", crate::rayzor_static_local_load);
+register_symbol!(
+    "rayzor_static_local_store",
+    crate::rayzor_static_local_store
+);
 
 // ============================================================================
 // Tracked Heap Allocator (Rust allocator with double-free protection)
@@ -1460,6 +1719,88 @@ register_symbol!("rayzor_tracked_alloc", crate::rayzor_tracked_alloc);
 register_symbol!("rayzor_tracked_realloc", crate::rayzor_tracked_realloc);
 register_symbol!("rayzor_tracked_free", crate::rayzor_tracked_free);
 
+// ============================================================================
+// Aligned Heap Allocator (explicit alignment for SIMD / GPU / over-aligned structs)
+// ============================================================================
+register_symbol!("rayzor_malloc_aligned", crate::rayzor_malloc_aligned);
+register_symbol!("rayzor_realloc_aligned", crate::rayzor_realloc_aligned);
+register_symbol!("rayzor_free_aligned", crate::rayzor_free_aligned);
+
+// ============================================================================
+// Out-of-Memory Handling (allocation failure hooks)
+// ============================================================================
+register_symbol!("rayzor_set_oom_handler", crate::oom::rayzor_set_oom_handler);
+register_symbol!("rayzor_alloc_failed", crate::oom::rayzor_alloc_failed);
+register_symbol!("rayzor_realloc_failed", crate::oom::rayzor_realloc_failed);
+
+// ============================================================================
+// Contracts (@:requires/@:ensures checks in debug and --safety checked builds)
+// ============================================================================
+register_symbol!(
+    "rayzor_contract_failed",
+    crate::contracts::rayzor_contract_failed
+);
+
+// ============================================================================
+// Garbage Collector (opt-in tracing heap) and Runtime Lifecycle
+// ============================================================================
+register_symbol!("rayzor_gc_alloc", crate::gc::rayzor_gc_alloc);
+register_symbol!("rayzor_gc_add_root", crate::gc::rayzor_gc_add_root);
+register_symbol!("rayzor_gc_remove_root", crate::gc::rayzor_gc_remove_root);
+register_symbol!(
+    "rayzor_gc_write_barrier",
+    crate::gc::rayzor_gc_write_barrier
+);
+register_symbol!("rayzor_gc_collect", crate::gc::rayzor_gc_collect);
+register_symbol!("rayzor_gc_live_bytes", crate::gc::rayzor_gc_live_bytes);
+register_symbol!("rayzor_runtime_init", crate::rayzor_runtime_init);
+register_symbol!("rayzor_runtime_shutdown", crate::rayzor_runtime_shutdown);
+
+// ============================================================================
+// Missing Externs (diagnostic stubs bound by the JIT)
+// ============================================================================
+register_symbol!(
+    "rayzor_missing_extern",
+    crate::missing_extern::rayzor_missing_extern
+);
+
+// ============================================================================
+// Module Loading (rayzor.Runtime.loadModule)
+// ============================================================================
+register_symbol!(
+    "rayzor_runtime_load_module",
+    crate::module_loader::rayzor_runtime_load_module
+);
+register_symbol!(
+    "rayzor_runtime_load_module_entry",
+    crate::module_loader::rayzor_runtime_load_module_entry
+);
+register_symbol!(
+    "rayzor_runtime_load_error",
+    crate::module_loader::rayzor_runtime_load_error
+);
+
+// ============================================================================
+// Output Redirection (stdout handler and capture)
+// ============================================================================
+register_symbol!(
+    "rayzor_set_stdout_handler",
+    crate::stdout::rayzor_set_stdout_handler
+);
+register_symbol!(
+    "rayzor_set_stdout_tee",
+    crate::stdout::rayzor_set_stdout_tee
+);
+
+// ============================================================================
+// Object Identity (stable ids for ObjectMap/WeakMap keys)
+// ============================================================================
+register_symbol!("rayzor_object_id", crate::object_id::rayzor_object_id);
+register_symbol!(
+    "rayzor_object_id_release",
+    crate::object_id::rayzor_object_id_release
+);
+
 // ============================================================================
 // CString Runtime (rayzor.CString — null-terminated C string interop)
 // ============================================================================
@@ -1555,6 +1896,12 @@ register_symbol!(
 );
 register_symbol!("rayzor_throw", crate::exception::rayzor_throw);
 register_symbol!("rayzor_throw_typed", crate::exception::rayzor_throw_typed);
+register_symbol!("rayzor_throw_object", crate::exception::rayzor_throw_object);
+register_symbol!("rayzor_rethrow", crate::exception::rayzor_rethrow);
+register_symbol!(
+    "rayzor_exception_is_instance",
+    crate::exception::rayzor_exception_is_instance
+);
 register_symbol!(
     "rayzor_get_exception",
     crate::exception::rayzor_get_exception
@@ -0,0 +1,218 @@
+//! Size-class pooled allocator for small objects
+//!
+//! `rayzor_malloc`/`rayzor_free` route requests of up to `MAX_POOLED_SIZE` bytes
+//! through per-size-class freelists instead of the global allocator. Object-churny
+//! programs allocate and release the same few sizes over and over; recycling
+//! blocks from a thread-local freelist avoids a trip through the global
+//! allocator for each of them.
+//!
+//! - Size classes: 16, 32, 64 and 128 bytes (every block is 16-byte aligned)
+//! - Each thread keeps its own freelists; no locking on the fast path
+//! - Freelists are refilled from 64 KiB chunks carved into equal blocks
+//! - Overflowing caches (and the caches of exiting threads) are handed to a
+//!   shared depot so other threads can reuse the blocks
+//! - Chunks are never returned to the system
+//!
+//! Set `RAYZOR_ALLOCATOR=system` to bypass the pools and use the global
+//! allocator for every request. The choice is made once per process, so
+//! `rayzor_malloc` and `rayzor_free` always agree on where a block came from.
+
+use parking_lot::Mutex;
+use std::alloc::{alloc, Layout};
+use std::cell::RefCell;
+use std::sync::OnceLock;
+
+/// Block sizes served by the pools, smallest first.
+pub const SIZE_CLASSES: [usize; 4] = [16, 32, 64, 128];
+
+/// Largest request served from a pool; bigger requests use the global allocator.
+pub const MAX_POOLED_SIZE: usize = 128;
+
+/// Environment variable selecting the allocator (`system` disables pooling).
+pub const ALLOCATOR_ENV_VAR: &str = "RAYZOR_ALLOCATOR";
+
+/// Bytes requested from the global allocator when a freelist runs dry.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Alignment of every pooled block (and of the chunks they are carved from).
+const BLOCK_ALIGNMENT: usize = 16;
+
+/// Free blocks a thread may cache per class before spilling half to the depot.
+const MAX_CACHED_BLOCKS: usize = 4096;
+
+/// Free blocks shared between threads, one list per size class.
+static DEPOT: [Mutex<Vec<usize>>; SIZE_CLASSES.len()] = [
+    Mutex::new(Vec::new()),
+    Mutex::new(Vec::new()),
+    Mutex::new(Vec::new()),
+    Mutex::new(Vec::new()),
+];
+
+thread_local! {
+    static CACHE: RefCell<ThreadCache> = RefCell::new(ThreadCache::default());
+}
+
+/// Per-thread freelists (block addresses), one per size class.
+#[derive(Default)]
+struct ThreadCache {
+    free: [Vec<usize>; SIZE_CLASSES.len()],
+}
+
+impl Drop for ThreadCache {
+    fn drop(&mut self) {
+        // Hand cached blocks to the depot so they outlive this thread
+        for (class, blocks) in self.free.iter_mut().enumerate() {
+            if !blocks.is_empty() {
+                DEPOT[class].lock().append(blocks);
+            }
+        }
+    }
+}
+
+/// Whether small allocations are served from the pools.
+pub fn pooling_enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        !matches!(
+            std::env::var(ALLOCATOR_ENV_VAR).as_deref(),
+            Ok("system") | Ok("SYSTEM")
+        )
+    })
+}
+
+/// Index into `SIZE_CLASSES` for a request of `size` bytes, if it is pooled.
+pub fn size_class(size: usize) -> Option<usize> {
+    if size == 0 || size > MAX_POOLED_SIZE {
+        return None;
+    }
+    SIZE_CLASSES
+        .iter()
+        .position(|&class_size| size <= class_size)
+}
+
+/// Take a block from the pool for `class`.
+///
+/// Returns null only if a fresh chunk could not be allocated.
+pub fn pool_alloc(class: usize) -> *mut u8 {
+    CACHE
+        .try_with(|cache| {
+            let mut cache = cache.borrow_mut();
+            let free = &mut cache.free[class];
+            if free.is_empty() {
+                refill(class, free);
+            }
+            free.pop()
+                .map_or(std::ptr::null_mut(), |addr| addr as *mut u8)
+        })
+        // Thread is shutting down: allocate straight from the depot/a new chunk
+        .unwrap_or_else(|_| {
+            let mut free = Vec::new();
+            refill(class, &mut free);
+            let ptr = free
+                .pop()
+                .map_or(std::ptr::null_mut(), |addr| addr as *mut u8);
+            if !free.is_empty() {
+                DEPOT[class].lock().append(&mut free);
+            }
+            ptr
+        })
+}
+
+/// Return a block previously obtained from `pool_alloc(class)`.
+///
+/// # Safety
+/// `ptr` must come from `pool_alloc` with the same `class` and must not be used
+/// after this call.
+pub unsafe fn pool_free(ptr: *mut u8, class: usize) {
+    let addr = ptr as usize;
+    let cached = CACHE.try_with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let free = &mut cache.free[class];
+        free.push(addr);
+        if free.len() > MAX_CACHED_BLOCKS {
+            let spill = free.split_off(MAX_CACHED_BLOCKS / 2);
+            DEPOT[class].lock().extend(spill);
+        }
+    });
+    if cached.is_err() {
+        DEPOT[class].lock().push(addr);
+    }
+}
+
+/// Refill an empty freelist from the depot, or carve a new chunk.
+fn refill(class: usize, free: &mut Vec<usize>) {
+    {
+        let mut depot = DEPOT[class].lock();
+        if !depot.is_empty() {
+            let take = depot.len().min(MAX_CACHED_BLOCKS / 2);
+            let start = depot.len() - take;
+            free.extend(depot.drain(start..));
+            return;
+        }
+    }
+
+    let block_size = SIZE_CLASSES[class];
+    // SAFETY: CHUNK_SIZE is non-zero and BLOCK_ALIGNMENT is a power of two
+    let chunk = unsafe {
+        alloc(Layout::from_size_align_unchecked(
+            CHUNK_SIZE,
+            BLOCK_ALIGNMENT,
+        ))
+    };
+    if chunk.is_null() {
+        return;
+    }
+
+    // Push in reverse so blocks are handed out in address order
+    let base = chunk as usize;
+    free.extend(
+        (0..CHUNK_SIZE / block_size)
+            .rev()
+            .map(|i| base + i * block_size),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_classes() {
+        assert_eq!(size_class(0), None);
+        assert_eq!(size_class(1), Some(0));
+        assert_eq!(size_class(16), Some(0));
+        assert_eq!(size_class(17), Some(1));
+        assert_eq!(size_class(64), Some(2));
+        assert_eq!(size_class(128), Some(3));
+        assert_eq!(size_class(129), None);
+    }
+
+    #[test]
+    fn test_pool_reuses_freed_blocks() {
+        let class = size_class(24).unwrap();
+        let a = pool_alloc(class);
+        assert!(!a.is_null());
+        assert_eq!(a as usize % BLOCK_ALIGNMENT, 0);
+
+        unsafe { pool_free(a, class) };
+        let b = pool_alloc(class);
+        assert_eq!(a, b, "freed block should be handed out again");
+        unsafe { pool_free(b, class) };
+    }
+
+    #[test]
+    fn test_blocks_do_not_overlap() {
+        let class = size_class(64).unwrap();
+        let mut blocks: Vec<*mut u8> = (0..2048).map(|_| pool_alloc(class)).collect();
+        for (i, &block) in blocks.iter().enumerate() {
+            unsafe { std::ptr::write_bytes(block, i as u8, 64) };
+        }
+        for (i, &block) in blocks.iter().enumerate() {
+            let bytes = unsafe { std::slice::from_raw_parts(block, 64) };
+            assert!(bytes.iter().all(|&b| b == i as u8));
+        }
+        for block in blocks.drain(..) {
+            unsafe { pool_free(block, class) };
+        }
+    }
+}
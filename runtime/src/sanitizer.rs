@@ -0,0 +1,171 @@
+//! ThreadSanitizer hooks for `rayzor run --sanitize thread`
+//!
+//! JIT-compiled code is not instrumented by rustc, so the compiler emits
+//! explicit calls to [`rayzor_tsan_read`] and [`rayzor_tsan_write`] around
+//! instance field accesses, and the concurrency runtime annotates the
+//! happens-before edges it creates:
+//!
+//! - `Mutex.release` → a later `Mutex.acquire` of the same mutex
+//! - `Channel.send` → the `Channel.receive` that takes the value
+//!
+//! Each access carries its source position (`Class.method:line`), registered
+//! as a TSan "external" object type, so a report names the Haxe lines
+//! involved: `WARNING: ThreadSanitizer: race on Main.worker:12`.
+//!
+//! The hooks only do something when the runtime is built with the `tsan`
+//! feature, as part of a binary instrumented with ThreadSanitizer:
+//!
+//! ```sh
+//! RUSTFLAGS="-Zsanitizer=thread" cargo +nightly build -Zbuild-std \
+//!     --target x86_64-unknown-linux-gnu --features tsan
+//! ```
+//!
+//! Otherwise they are no-ops, and `rayzor run --sanitize thread` refuses to
+//! run (see [`is_available`]).
+
+use std::collections::HashMap;
+use std::ffi::CString;
+
+use crate::haxe_string::HaxeString;
+
+#[cfg(feature = "tsan")]
+mod tsan {
+    use std::ffi::{c_char, c_void};
+
+    extern "C" {
+        pub fn __tsan_acquire(addr: *mut c_void);
+        pub fn __tsan_release(addr: *mut c_void);
+        pub fn __tsan_external_register_tag(object_type: *const c_char) -> *mut c_void;
+        pub fn __tsan_external_read(addr: *mut c_void, caller_pc: *mut c_void, tag: *mut c_void);
+        pub fn __tsan_external_write(addr: *mut c_void, caller_pc: *mut c_void, tag: *mut c_void);
+    }
+}
+
+/// Whether the runtime was built with ThreadSanitizer support
+pub fn is_available() -> bool {
+    cfg!(feature = "tsan")
+}
+
+/// Annotate the acquiring side of a happens-before edge on `addr`
+#[inline]
+pub fn acquire(addr: usize) {
+    #[cfg(feature = "tsan")]
+    unsafe {
+        tsan::__tsan_acquire(addr as *mut _)
+    };
+    #[cfg(not(feature = "tsan"))]
+    let _ = addr;
+}
+
+/// Annotate the releasing side of a happens-before edge on `addr`
+#[inline]
+pub fn release(addr: usize) {
+    #[cfg(feature = "tsan")]
+    unsafe {
+        tsan::__tsan_release(addr as *mut _)
+    };
+    #[cfg(not(feature = "tsan"))]
+    let _ = addr;
+}
+
+/// TSan object-type tags of access sites, by site string address.
+///
+/// Site strings are constants of the compiled program, so their address
+/// identifies the site; the name is only read on first use.
+#[cfg_attr(not(feature = "tsan"), allow(dead_code))]
+#[derive(Default)]
+struct Sites {
+    tags: HashMap<usize, usize>,
+    /// Keeps the registered names alive; TSan stores the pointer
+    names: Vec<CString>,
+}
+
+#[cfg_attr(not(feature = "tsan"), allow(dead_code))]
+impl Sites {
+    fn tag(
+        &mut self,
+        site: usize,
+        name: impl FnOnce() -> String,
+        register: impl FnOnce(&CString) -> usize,
+    ) -> usize {
+        if let Some(&tag) = self.tags.get(&site) {
+            return tag;
+        }
+        let name = CString::new(name().replace('\0', "")).unwrap_or_default();
+        let tag = register(&name);
+        self.names.push(name);
+        self.tags.insert(site, tag);
+        tag
+    }
+}
+
+#[cfg(feature = "tsan")]
+static SITES: std::sync::Mutex<Option<Sites>> = std::sync::Mutex::new(None);
+
+/// The tag of the access site `site` (a `Class.method:line` string)
+#[cfg(feature = "tsan")]
+fn site_tag(site: *const HaxeString) -> *mut std::ffi::c_void {
+    let mut sites = SITES.lock().unwrap_or_else(|e| e.into_inner());
+    let tag = sites.get_or_insert_with(Sites::default).tag(
+        site as usize,
+        || unsafe { site_name(site) },
+        |name| unsafe { tsan::__tsan_external_register_tag(name.as_ptr()) as usize },
+    );
+    tag as *mut _
+}
+
+#[cfg(feature = "tsan")]
+unsafe fn site_name(site: *const HaxeString) -> String {
+    if site.is_null() || (*site).ptr.is_null() {
+        return "<unknown>".to_string();
+    }
+    let bytes = std::slice::from_raw_parts((*site).ptr, (*site).len);
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// Report a read of the field at `addr` by the access site `site`
+#[no_mangle]
+pub extern "C" fn rayzor_tsan_read(addr: *mut u8, site: *const HaxeString) {
+    #[cfg(feature = "tsan")]
+    unsafe {
+        tsan::__tsan_external_read(addr as *mut _, std::ptr::null_mut(), site_tag(site))
+    };
+    #[cfg(not(feature = "tsan"))]
+    let _ = (addr, site);
+}
+
+/// Report a write of the field at `addr` by the access site `site`
+#[no_mangle]
+pub extern "C" fn rayzor_tsan_write(addr: *mut u8, site: *const HaxeString) {
+    #[cfg(feature = "tsan")]
+    unsafe {
+        tsan::__tsan_external_write(addr as *mut _, std::ptr::null_mut(), site_tag(site))
+    };
+    #[cfg(not(feature = "tsan"))]
+    let _ = (addr, site);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_site_tags_registered_once() {
+        let mut sites = Sites::default();
+        let mut registered = Vec::new();
+        let mut tag = |site: usize, name: &str| {
+            sites.tag(
+                site,
+                || name.to_string(),
+                |n| {
+                    registered.push(n.to_str().unwrap().to_string());
+                    registered.len()
+                },
+            )
+        };
+        assert_eq!(tag(0x10, "Main.worker:12"), 1);
+        assert_eq!(tag(0x20, "Main.main:30"), 2);
+        assert_eq!(tag(0x10, "ignored"), 1);
+        assert_eq!(registered, vec!["Main.worker:12", "Main.main:30"]);
+    }
+}
@@ -0,0 +1,377 @@
+//! Thread stack sizes and stack overflow detection
+//!
+//! Haxe threads run with the default thread stack size unless spawned with
+//! `Thread.spawnWithStackSize`; `rayzor run --main-stack-size` sets the size
+//! of the thread running `main`.
+//!
+//! Every thread stack ends in a guard page. Once [`install`] is called, a
+//! fault in the guard zone of a thread that entered [`ThreadStack::enter`]
+//! is reported as a stack overflow, naming the Haxe function at fault (from
+//! the crash reporter's function table) and the stack size, and the process
+//! aborts:
+//!
+//! ```text
+//! fatal runtime error: stack overflow in Main.recurse (Main.hx:4)
+//!   stack size: 8192 KiB (raise it with --main-stack-size or Thread.spawnWithStackSize)
+//! ```
+//!
+//! Other faults go to the handler installed before (the crash reporter, or
+//! Rust's own). When crash reports are on, overflows are passed on too, so
+//! that a report is written.
+
+use std::cell::Cell;
+
+/// Faults this far below the lowest usable stack address count as overflows,
+/// since a large frame can skip past the guard page
+const GUARD_WINDOW: usize = 64 * 1024;
+
+/// Size of the alternate signal stack the overflow is reported on
+#[cfg(unix)]
+const ALT_STACK_SIZE: usize = 64 * 1024;
+
+thread_local! {
+    /// Usable stack range `[low, high)` of the current thread, zero if unknown
+    static BOUNDS: Cell<(usize, usize)> = const { Cell::new((0, 0)) };
+}
+
+/// Parse a stack size: bytes, or a number with a `K`, `M` or `G` suffix
+/// (powers of 1024)
+pub fn parse_size(text: &str) -> Result<usize, String> {
+    let text = text.trim();
+    let (digits, unit) = match text.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => (&text[..i], c.to_ascii_uppercase()),
+        _ => (text, 'B'),
+    };
+    let shift = match unit {
+        'B' => 0,
+        'K' => 10,
+        'M' => 20,
+        'G' => 30,
+        _ => {
+            return Err(format!(
+                "invalid size unit in `{}` (expected K, M or G)",
+                text
+            ))
+        }
+    };
+    let value: usize = digits
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid stack size `{}`", text))?;
+    value
+        .checked_mul(1 << shift)
+        .filter(|&size| size > 0)
+        .ok_or_else(|| format!("invalid stack size `{}`", text))
+}
+
+/// Whether a fault at `addr` hit the guard zone of a stack whose usable range
+/// starts at `low`
+pub fn in_guard_zone(addr: usize, low: usize, page_size: usize) -> bool {
+    low != 0 && addr < low.saturating_add(page_size) && addr >= low.saturating_sub(GUARD_WINDOW)
+}
+
+/// Usable stack range of the calling thread
+#[cfg(target_os = "linux")]
+pub fn current_bounds() -> Option<(usize, usize)> {
+    unsafe {
+        let mut attr: libc::pthread_attr_t = std::mem::zeroed();
+        if libc::pthread_getattr_np(libc::pthread_self(), &mut attr) != 0 {
+            return None;
+        }
+        let mut addr = std::ptr::null_mut();
+        let mut size = 0;
+        let ok = libc::pthread_attr_getstack(&attr, &mut addr, &mut size) == 0;
+        libc::pthread_attr_destroy(&mut attr);
+        ok.then(|| (addr as usize, addr as usize + size))
+    }
+}
+
+/// Usable stack range of the calling thread
+#[cfg(target_os = "macos")]
+pub fn current_bounds() -> Option<(usize, usize)> {
+    unsafe {
+        let thread = libc::pthread_self();
+        let high = libc::pthread_get_stackaddr_np(thread) as usize;
+        let size = libc::pthread_get_stacksize_np(thread);
+        Some((high - size, high))
+    }
+}
+
+/// Usable stack range of the calling thread
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn current_bounds() -> Option<(usize, usize)> {
+    None
+}
+
+/// Stack overflow detection for the current thread, until dropped
+pub struct ThreadStack {
+    /// Alternate signal stack installed by [`ThreadStack::enter`], if any
+    #[cfg(unix)]
+    alt_stack: Option<Box<[u8]>>,
+}
+
+impl ThreadStack {
+    /// Record the current thread's stack range, and give it an alternate
+    /// signal stack to report an overflow on if it has none
+    pub fn enter() -> Self {
+        let bounds = current_bounds().unwrap_or((0, 0));
+        BOUNDS.with(|b| b.set(bounds));
+        ThreadStack {
+            #[cfg(unix)]
+            alt_stack: unsafe { handler::ensure_alt_stack() },
+        }
+    }
+
+    /// Size of the current thread's stack in bytes, if known
+    pub fn size() -> Option<usize> {
+        let (low, high) = BOUNDS.with(|b| b.get());
+        (low != 0).then_some(high - low)
+    }
+}
+
+/// Recorded stack range of the current thread (the running coroutine's
+/// while inside one), zero if [`ThreadStack::enter`] wasn't called
+pub(crate) fn recorded_bounds() -> (usize, usize) {
+    BOUNDS.with(|b| b.get())
+}
+
+/// Replace the current thread's recorded stack range, returning the old one.
+/// Coroutines swap in their own stack while they run.
+pub(crate) fn swap_bounds(bounds: (usize, usize)) -> (usize, usize) {
+    BOUNDS.with(|b| b.replace(bounds))
+}
+
+impl Drop for ThreadStack {
+    fn drop(&mut self) {
+        BOUNDS.with(|b| b.set((0, 0)));
+        #[cfg(unix)]
+        if self.alt_stack.is_some() {
+            unsafe { handler::disable_alt_stack() };
+        }
+    }
+}
+
+/// Install the stack overflow handler. Only the first call takes effect.
+#[cfg(unix)]
+pub fn install() -> std::io::Result<()> {
+    unsafe { handler::install() }
+}
+
+/// Stack overflows are only detected with POSIX signals
+#[cfg(not(unix))]
+pub fn install() -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+mod handler {
+    use super::*;
+    use std::fmt::Write as _;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::OnceLock;
+
+    use crate::crash::FdWriter;
+
+    const SIGNALS: [libc::c_int; 2] = [libc::SIGSEGV, libc::SIGBUS];
+
+    static PREVIOUS: OnceLock<[libc::sigaction; 2]> = OnceLock::new();
+    static PAGE_SIZE: AtomicUsize = AtomicUsize::new(4096);
+    static REPORTED: AtomicBool = AtomicBool::new(false);
+
+    pub(super) unsafe fn install() -> std::io::Result<()> {
+        if PREVIOUS.get().is_some() {
+            return Ok(());
+        }
+        let page_size = libc::sysconf(libc::_SC_PAGESIZE);
+        if page_size > 0 {
+            PAGE_SIZE.store(page_size as usize, Ordering::Relaxed);
+        }
+
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = on_fault as *const () as usize;
+        action.sa_flags = libc::SA_SIGINFO | libc::SA_ONSTACK;
+        libc::sigemptyset(&mut action.sa_mask);
+        let mut previous: [libc::sigaction; 2] = std::mem::zeroed();
+        for (signal, old) in SIGNALS.iter().zip(previous.iter_mut()) {
+            if libc::sigaction(*signal, std::ptr::null(), old) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+        // Set before the handler can run
+        let _ = PREVIOUS.set(previous);
+        for signal in SIGNALS {
+            if libc::sigaction(signal, &action, std::ptr::null_mut()) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    /// Install an alternate signal stack unless the thread already has one
+    /// (Rust gives one to the threads it starts)
+    pub(super) unsafe fn ensure_alt_stack() -> Option<Box<[u8]>> {
+        let mut current: libc::stack_t = std::mem::zeroed();
+        if libc::sigaltstack(std::ptr::null(), &mut current) != 0
+            || current.ss_flags & libc::SS_DISABLE == 0
+        {
+            return None;
+        }
+        let mut stack = vec![0u8; ALT_STACK_SIZE].into_boxed_slice();
+        let alt = libc::stack_t {
+            ss_sp: stack.as_mut_ptr().cast(),
+            ss_flags: 0,
+            ss_size: ALT_STACK_SIZE,
+        };
+        (libc::sigaltstack(&alt, std::ptr::null_mut()) == 0).then_some(stack)
+    }
+
+    pub(super) unsafe fn disable_alt_stack() {
+        let alt = libc::stack_t {
+            ss_sp: std::ptr::null_mut(),
+            ss_flags: libc::SS_DISABLE,
+            ss_size: 0,
+        };
+        libc::sigaltstack(&alt, std::ptr::null_mut());
+    }
+
+    extern "C" fn on_fault(
+        signal: libc::c_int,
+        info: *mut libc::siginfo_t,
+        context: *mut libc::c_void,
+    ) {
+        unsafe {
+            let address = if info.is_null() {
+                0
+            } else {
+                (*info).si_addr() as usize
+            };
+            let (low, high) = BOUNDS.try_with(|b| b.get()).unwrap_or((0, 0));
+            if in_guard_zone(address, low, PAGE_SIZE.load(Ordering::Relaxed))
+                && !REPORTED.swap(true, Ordering::SeqCst)
+            {
+                report(high - low, crate::crash::context_pc(context));
+                if !crate::crash::is_enabled() {
+                    libc::abort();
+                }
+            }
+            chain(signal, info, context);
+        }
+    }
+
+    /// Write the overflow diagnostic to stderr, without allocating
+    unsafe fn report(stack_size: usize, pc: usize) {
+        let mut out = FdWriter(libc::STDERR_FILENO);
+        let _ = write!(out, "fatal runtime error: stack overflow");
+        let _ = crate::crash::with_function_at(pc, |function| match function {
+            Some(f) => write!(out, " in {} ({})", f.name, f.location),
+            None => Ok(()),
+        });
+        let _ = writeln!(
+            out,
+            "\n  stack size: {} KiB (raise it with --main-stack-size or Thread.spawnWithStackSize)",
+            stack_size / 1024
+        );
+    }
+
+    /// Hand a fault that isn't ours to the handler installed before
+    unsafe fn chain(signal: libc::c_int, info: *mut libc::siginfo_t, context: *mut libc::c_void) {
+        let previous = PREVIOUS.get().and_then(|actions| {
+            SIGNALS
+                .iter()
+                .position(|&s| s == signal)
+                .map(|i| actions[i])
+        });
+        match previous {
+            Some(action)
+                if action.sa_sigaction != libc::SIG_DFL && action.sa_sigaction != libc::SIG_IGN =>
+            {
+                if action.sa_flags & libc::SA_SIGINFO != 0 {
+                    let handler: extern "C" fn(
+                        libc::c_int,
+                        *mut libc::siginfo_t,
+                        *mut libc::c_void,
+                    ) = std::mem::transmute(action.sa_sigaction);
+                    handler(signal, info, context);
+                } else {
+                    let handler: extern "C" fn(libc::c_int) =
+                        std::mem::transmute(action.sa_sigaction);
+                    handler(signal);
+                }
+            }
+            // Returning re-runs the faulting instruction with the default action
+            _ => {
+                libc::signal(signal, libc::SIG_DFL);
+            }
+        }
+    }
+}
+
+/// Run `closure(env)` on a new thread with a `stack_size`-byte stack (the
+/// default size if zero or negative)
+///
+/// # Safety
+/// Same as [`crate::concurrency::rayzor_thread_spawn`]
+#[no_mangle]
+pub unsafe extern "C" fn rayzor_thread_spawn_with_stack_size(
+    closure: *const u8,
+    closure_env: *const u8,
+    stack_size: i64,
+) -> *mut u8 {
+    let stack_size = usize::try_from(stack_size).ok().filter(|&size| size > 0);
+    crate::concurrency::spawn_thread(closure, closure_env, stack_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("4096"), Ok(4096));
+        assert_eq!(parse_size("512K"), Ok(512 * 1024));
+        assert_eq!(parse_size("64m"), Ok(64 << 20));
+        assert_eq!(parse_size("1G"), Ok(1 << 30));
+        assert!(parse_size("0").is_err());
+        assert!(parse_size("8X").is_err());
+        assert!(parse_size("M").is_err());
+        assert!(parse_size("-1").is_err());
+    }
+
+    #[test]
+    fn test_guard_zone() {
+        let low = 0x7000_0000;
+        assert!(in_guard_zone(low - 8, low, 4096));
+        assert!(in_guard_zone(low + 100, low, 4096));
+        assert!(in_guard_zone(low - GUARD_WINDOW, low, 4096));
+        assert!(!in_guard_zone(low - GUARD_WINDOW - 1, low, 4096));
+        assert!(!in_guard_zone(low + 4096, low, 4096));
+        assert!(!in_guard_zone(8, 0, 4096));
+    }
+
+    extern "C" fn stack_size_at_least_16m(_env: *const u8) -> i32 {
+        (ThreadStack::size().unwrap_or(0) >= 16 << 20) as i32
+    }
+
+    #[test]
+    fn test_spawn_with_stack_size() {
+        let local = 0u8;
+        let _stack = ThreadStack::enter();
+        if let Some((low, high)) = current_bounds() {
+            let addr = &local as *const u8 as usize;
+            assert!(low < addr && addr < high);
+        }
+
+        unsafe {
+            let handle = rayzor_thread_spawn_with_stack_size(
+                stack_size_at_least_16m as *const u8,
+                std::ptr::null(),
+                32 << 20,
+            );
+            assert!(!handle.is_null());
+            let result = crate::concurrency::rayzor_thread_join(handle) as usize;
+            if cfg!(any(target_os = "linux", target_os = "macos")) {
+                assert_eq!(result, 1);
+            }
+        }
+    }
+}
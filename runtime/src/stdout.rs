@@ -0,0 +1,220 @@
+//! Standard output redirection for `trace`, `Sys.print` and friends
+//!
+//! All program output of the runtime goes through [`write_stdout`]. By
+//! default it is written to the process's stdout; an embedder can install a
+//! handler instead, and hosts running programs in-process (embedding
+//! applications, test runners) capture the output of one execution with
+//! [`StdoutCapture`]:
+//!
+//! ```c
+//! // Receives each write: a whole trace line, or one Sys.print call.
+//! void on_output(const uint8_t *data, size_t len);
+//! rayzor_set_stdout_handler(on_output);
+//! rayzor_set_stdout_tee(1); // also write to the real stdout
+//! ```
+//!
+//! Writes are serialized: output of concurrent Haxe threads is interleaved
+//! write by write, never within a line. The handler is called with the
+//! redirection lock held, so it must not print through the runtime itself.
+
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+/// Output callback: receives the bytes of one write (UTF-8)
+pub type StdoutHandler = extern "C" fn(data: *const u8, len: usize);
+
+struct Redirect {
+    handler: Option<StdoutHandler>,
+    /// Buffers of active captures, innermost last
+    captures: Vec<Vec<u8>>,
+    /// Also write redirected output to the real stdout
+    tee: bool,
+}
+
+static REDIRECT: Mutex<Redirect> = Mutex::new(Redirect {
+    handler: None,
+    captures: Vec::new(),
+    tee: false,
+});
+
+/// Whether a handler or capture is installed; skips the lock when not
+static REDIRECTED: AtomicBool = AtomicBool::new(false);
+
+fn redirect() -> MutexGuard<'static, Redirect> {
+    REDIRECT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn update_redirected(redirect: &Redirect) {
+    let active = redirect.handler.is_some() || !redirect.captures.is_empty();
+    REDIRECTED.store(active, Ordering::SeqCst);
+}
+
+fn write_real_stdout(bytes: &[u8]) {
+    let mut out = std::io::stdout().lock();
+    let _ = out.write_all(bytes);
+    let _ = out.flush();
+}
+
+/// Install a stdout handler, returning the previous one.
+///
+/// Pass null to restore writing to the process's stdout.
+#[no_mangle]
+pub extern "C" fn rayzor_set_stdout_handler(
+    handler: Option<StdoutHandler>,
+) -> Option<StdoutHandler> {
+    let mut redirect = redirect();
+    let old = std::mem::replace(&mut redirect.handler, handler);
+    update_redirected(&redirect);
+    old
+}
+
+/// Also write redirected output to the real stdout (non-zero) or not (zero)
+#[no_mangle]
+pub extern "C" fn rayzor_set_stdout_tee(tee: i32) {
+    redirect().tee = tee != 0;
+}
+
+/// Whether output currently goes to a handler or capture
+pub fn is_redirected() -> bool {
+    REDIRECTED.load(Ordering::SeqCst)
+}
+
+/// Write program output: to the innermost capture, else the handler, else
+/// the process's stdout
+pub fn write_stdout(bytes: &[u8]) {
+    if !REDIRECTED.load(Ordering::SeqCst) {
+        write_real_stdout(bytes);
+        return;
+    }
+    let mut redirect = redirect();
+    let tee = redirect.tee;
+    if let Some(buffer) = redirect.captures.last_mut() {
+        buffer.extend_from_slice(bytes);
+    } else if let Some(handler) = redirect.handler {
+        handler(bytes.as_ptr(), bytes.len());
+    } else {
+        drop(redirect);
+        write_real_stdout(bytes);
+        return;
+    }
+    if tee {
+        write_real_stdout(bytes);
+    }
+}
+
+/// Write `text` followed by a newline as one write
+pub fn write_line(text: &str) {
+    let mut line = Vec::with_capacity(text.len() + 1);
+    line.extend_from_slice(text.as_bytes());
+    line.push(b'\n');
+    write_stdout(&line);
+}
+
+/// Captures program output until [`StdoutCapture::finish`] or drop.
+///
+/// Captures nest; output goes to the innermost one. Finish them in reverse
+/// order of starting.
+pub struct StdoutCapture {
+    depth: usize,
+    previous_tee: bool,
+    finished: bool,
+}
+
+impl StdoutCapture {
+    /// Start capturing; with `tee` the output is also written to the real
+    /// stdout
+    pub fn start(tee: bool) -> Self {
+        let mut redirect = redirect();
+        redirect.captures.push(Vec::new());
+        let previous_tee = std::mem::replace(&mut redirect.tee, tee);
+        update_redirected(&redirect);
+        StdoutCapture {
+            depth: redirect.captures.len(),
+            previous_tee,
+            finished: false,
+        }
+    }
+
+    /// Stop capturing and return the output (invalid UTF-8 is replaced)
+    pub fn finish(mut self) -> String {
+        String::from_utf8_lossy(&self.stop()).into_owned()
+    }
+
+    fn stop(&mut self) -> Vec<u8> {
+        self.finished = true;
+        let mut redirect = redirect();
+        redirect.tee = self.previous_tee;
+        let buffer = if redirect.captures.len() == self.depth {
+            redirect.captures.pop().unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        update_redirected(&redirect);
+        buffer
+    }
+}
+
+impl Drop for StdoutCapture {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.stop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    static HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+    extern "C" fn count_output(_data: *const u8, len: usize) {
+        HANDLED.fetch_add(len, Ordering::SeqCst);
+    }
+
+    // One test, since the redirection state is global
+    #[test]
+    fn test_capture_and_handler() {
+        let outer = StdoutCapture::start(false);
+        write_line("stdout-test: outer");
+        let inner = StdoutCapture::start(false);
+        write_stdout(b"stdout-test: inner");
+        let inner_text = inner.finish();
+        write_line("stdout-test: outer again");
+        let outer_text = outer.finish();
+
+        assert!(inner_text.contains("stdout-test: inner"));
+        assert!(!inner_text.contains("outer"));
+        assert!(outer_text.contains("stdout-test: outer\n"));
+        assert!(outer_text.contains("stdout-test: outer again\n"));
+        assert!(!outer_text.contains("inner"));
+
+        // Threads write whole lines into the capture
+        let capture = StdoutCapture::start(false);
+        let threads: Vec<_> = (0..4)
+            .map(|t| {
+                std::thread::spawn(move || {
+                    for i in 0..50 {
+                        write_line(&format!("stdout-test: thread {} line {}", t, i));
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        let text = capture.finish();
+        let lines = text
+            .lines()
+            .filter(|l| l.starts_with("stdout-test: thread"))
+            .count();
+        assert_eq!(lines, 200);
+
+        assert!(rayzor_set_stdout_handler(Some(count_output)).is_none());
+        write_stdout(b"12345");
+        assert!(HANDLED.load(Ordering::SeqCst) >= 5);
+        assert!(rayzor_set_stdout_handler(None).is_some());
+    }
+}
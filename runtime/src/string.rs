@@ -215,47 +215,35 @@ fn haxe_string_substr(s: *const HaxeString, start: usize, len: usize) -> HaxeStr
     }
 }
 
-/// Convert to lowercase (ASCII only for now) - internal helper
+/// Convert to lowercase (Unicode-aware) - internal helper
 #[allow(dead_code)]
 fn haxe_string_to_lower(s: *const HaxeString) -> HaxeString {
-    unsafe {
-        if s.is_null() {
-            return haxe_string_new();
-        }
-
-        let str_ref = &*s;
-        let result = haxe_string_from_bytes(str_ref.ptr, str_ref.len);
-
-        for i in 0..result.len {
-            let byte = *result.ptr.add(i);
-            if byte.is_ascii_uppercase() {
-                *result.ptr.add(i) = byte + 32; // Convert to lowercase
-            }
-        }
-
-        result
-    }
+    map_str(s, str::to_lowercase)
 }
 
-/// Convert to uppercase (ASCII only for now) - internal helper
+/// Convert to uppercase (Unicode-aware) - internal helper
 #[allow(dead_code)]
 fn haxe_string_to_upper(s: *const HaxeString) -> HaxeString {
+    map_str(s, str::to_uppercase)
+}
+
+/// Apply a string transformation, copying invalid UTF-8 unchanged - internal helper
+#[allow(dead_code)]
+fn map_str(s: *const HaxeString, f: fn(&str) -> String) -> HaxeString {
     unsafe {
         if s.is_null() {
             return haxe_string_new();
         }
 
         let str_ref = &*s;
-        let result = haxe_string_from_bytes(str_ref.ptr, str_ref.len);
-
-        for i in 0..result.len {
-            let byte = *result.ptr.add(i);
-            if byte.is_ascii_lowercase() {
-                *result.ptr.add(i) = byte - 32; // Convert to uppercase
+        let bytes = std::slice::from_raw_parts(str_ref.ptr, str_ref.len);
+        match std::str::from_utf8(bytes) {
+            Ok(text) => {
+                let mapped = f(text);
+                haxe_string_from_bytes(mapped.as_ptr(), mapped.len())
             }
+            Err(_) => haxe_string_from_bytes(str_ref.ptr, str_ref.len),
         }
-
-        result
     }
 }
 
@@ -471,4 +459,25 @@ mod tests {
             haxe_string_free(&mut lower.clone());
         }
     }
+
+    #[test]
+    fn test_string_case_non_ascii() {
+        let text = "Straße Ça Δ";
+        let s = haxe_string_from_bytes(text.as_ptr(), text.len());
+
+        let upper = haxe_string_to_upper(&s);
+        let lower = haxe_string_to_lower(&s);
+
+        unsafe {
+            let upper_slice = slice::from_raw_parts(upper.ptr, upper.len);
+            let lower_slice = slice::from_raw_parts(lower.ptr, lower.len);
+
+            assert_eq!(upper_slice, "STRASSE ÇA Δ".as_bytes());
+            assert_eq!(lower_slice, "straße ça δ".as_bytes());
+
+            haxe_string_free(&mut s.clone());
+            haxe_string_free(&mut upper.clone());
+            haxe_string_free(&mut lower.clone());
+        }
+    }
 }
@@ -0,0 +1,92 @@
+//! Substring search for `String.indexOf`, `lastIndexOf` and `split`
+//!
+//! Strings are searched as UTF-8 bytes; positions are byte offsets like the
+//! rest of the string runtime. The scans go through `memchr`, which uses
+//! SSE2/AVX2 on x86_64 and NEON on aarch64:
+//!
+//! - single-byte needles (`","`, `"\""`, `"\n"`, the common case in parsers)
+//!   are a vectorized byte scan
+//! - longer needles use a vectorized prefilter on rare bytes of the needle
+//!   and only verify the candidate positions
+//!
+//! Callers handle empty needles themselves; their meaning differs between
+//! `indexOf`, `lastIndexOf` and `split`.
+
+use memchr::memmem;
+
+/// Offset of the first occurrence of `needle` at or after `start`.
+pub fn find(haystack: &[u8], needle: &[u8], start: usize) -> Option<usize> {
+    let tail = haystack.get(start..)?;
+    let pos = match needle {
+        [byte] => memchr::memchr(*byte, tail),
+        _ => memmem::find(tail, needle),
+    }?;
+    Some(start + pos)
+}
+
+/// Offset of the last occurrence of `needle` that starts at or before `start`.
+pub fn rfind(haystack: &[u8], needle: &[u8], start: usize) -> Option<usize> {
+    let end = start.saturating_add(needle.len()).min(haystack.len());
+    let head = &haystack[..end];
+    match needle {
+        [byte] => memchr::memrchr(*byte, head),
+        _ => memmem::rfind(head, needle),
+    }
+}
+
+/// Offsets of the non-overlapping occurrences of a non-empty `delimiter`,
+/// left to right: the split points of `haystack.split(delimiter)`.
+pub fn split_points(haystack: &[u8], delimiter: &[u8]) -> Vec<usize> {
+    debug_assert!(!delimiter.is_empty(), "split delimiter must not be empty");
+    match delimiter {
+        [byte] => memchr::memchr_iter(*byte, haystack).collect(),
+        _ => memmem::find_iter(haystack, delimiter).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        let s = b"{\"a\": [1, 2], \"bc\": \"x\"}";
+        assert_eq!(find(s, b"\"", 0), Some(1));
+        assert_eq!(find(s, b"\"", 2), Some(3));
+        assert_eq!(find(s, b"\"bc\"", 0), Some(14));
+        assert_eq!(find(s, b"zz", 0), None);
+        assert_eq!(find(s, b"}", s.len()), None);
+        assert_eq!(find(s, b"}", s.len() + 5), None);
+    }
+
+    #[test]
+    fn test_rfind() {
+        let s = b"abcabcab";
+        assert_eq!(rfind(s, b"ab", usize::MAX), Some(6));
+        assert_eq!(rfind(s, b"ab", 5), Some(3));
+        assert_eq!(rfind(s, b"ab", 3), Some(3));
+        assert_eq!(rfind(s, b"c", 1), None);
+        assert_eq!(rfind(s, b"abc", 7), Some(3));
+    }
+
+    #[test]
+    fn test_split_points() {
+        assert_eq!(split_points(b"a,b,,c", b","), vec![1, 3, 4]);
+        assert_eq!(split_points(b"a::b::c", b"::"), vec![1, 4]);
+        // Matches don't overlap
+        assert_eq!(split_points(b"aaaa", b"aa"), vec![0, 2]);
+        assert!(split_points(b"abc", b";").is_empty());
+    }
+
+    #[test]
+    fn test_long_haystack() {
+        // Long enough to go through the vector loops, with the match in the tail
+        let mut s = vec![b'x'; 1000];
+        s.extend_from_slice(b"needle");
+        s.extend_from_slice(&[b'y'; 37]);
+        assert_eq!(find(&s, b"needle", 0), Some(1000));
+        assert_eq!(find(&s, b"n", 10), Some(1000));
+        assert_eq!(rfind(&s, b"x", usize::MAX), Some(999));
+        assert_eq!(split_points(&s, b"needle"), vec![1000]);
+    }
+}
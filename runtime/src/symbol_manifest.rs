@@ -0,0 +1,38 @@
+//! Manifest of the symbols the runtime provides to compiled code
+//!
+//! Generated by build.rs from the `register_symbol!` table in
+//! [`plugin_impl`](crate::plugin_impl). The JIT links against that table,
+//! while AOT binaries link librayzor_runtime.a by symbol name; the build
+//! fails if a registered symbol isn't exported under its registered name, so
+//! both see the same set. `rayzor aot --check-symbols` uses the manifest to
+//! tell a stale runtime library from a symbol the runtime doesn't have.
+
+include!(concat!(env!("OUT_DIR"), "/symbol_manifest.rs"));
+
+/// Whether `name` is a symbol the runtime library exports
+pub fn is_runtime_symbol(name: &str) -> bool {
+    RUNTIME_SYMBOLS.binary_search(&name).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin_impl::RuntimeSymbol;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_manifest_matches_registry() {
+        let registered: BTreeSet<&str> = inventory::iter::<RuntimeSymbol>
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        let manifest: BTreeSet<&str> = RUNTIME_SYMBOLS
+            .iter()
+            .chain(SYSTEM_SYMBOLS)
+            .copied()
+            .collect();
+        assert_eq!(manifest, registered);
+        assert!(is_runtime_symbol("haxe_string_concat"));
+        assert!(!is_runtime_symbol("malloc"));
+    }
+}
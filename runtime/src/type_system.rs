@@ -26,8 +26,10 @@
 //!    let s = dynamic_to_string(dynamic);  // Dispatches based on type_id
 //!    ```
 
+use crate::haxe_sys::print_with_prefix;
 use log::debug;
 use std::collections::HashMap;
+use std::fmt::Write;
 use std::sync::{Once, RwLock};
 
 /// Ensures primitive types are registered exactly once
@@ -97,6 +99,9 @@ pub struct EnumVariantInfo {
     pub param_count: usize,
     /// Parameter types for this variant (empty for parameterless variants)
     pub param_types: &'static [ParamType],
+    /// Runtime type id of each enum-typed parameter, 0 for other parameters
+    /// (empty when unknown). Used to compare nested enum values by structure.
+    pub param_enum_types: &'static [u32],
 }
 
 /// Enum type metadata
@@ -124,6 +129,9 @@ pub struct ClassInfo {
     pub name: &'static str,
     /// Super class type id (None if no parent)
     pub super_type_id: Option<u32>,
+    /// Type ids of the interfaces the class implements directly, including
+    /// the interfaces those extend (inherited ones live on the super class)
+    pub interfaces: &'static [u32],
     /// Instance fields (including inherited)
     pub instance_fields: &'static [&'static str],
     /// Static fields (own class only)
@@ -338,21 +346,25 @@ pub extern "C" fn haxe_register_enum(
 // ============================================================================
 
 /// Register enum RTTI directly from MIR metadata, bypassing generated code.
-/// `variants` is a slice of (name, param_count, param_types) tuples.
+/// `variants` is a slice of (name, param_count, param_types, param_enum_types)
+/// tuples; see `EnumVariantInfo` for the meaning of each part.
 pub fn register_enum_from_mir(
     type_id: u32,
     name: &str,
-    variants: &[(String, usize, Vec<ParamType>)],
+    variants: &[(String, usize, Vec<ParamType>, Vec<u32>)],
 ) {
     let enum_name_static: &'static str = Box::leak(name.to_string().into_boxed_str());
 
     let variant_infos: Vec<EnumVariantInfo> = variants
         .iter()
-        .map(|(vname, param_count, param_types)| EnumVariantInfo {
-            name: Box::leak(vname.clone().into_boxed_str()),
-            param_count: *param_count,
-            param_types: Box::leak(param_types.clone().into_boxed_slice()),
-        })
+        .map(
+            |(vname, param_count, param_types, param_enum_types)| EnumVariantInfo {
+                name: Box::leak(vname.clone().into_boxed_str()),
+                param_count: *param_count,
+                param_types: Box::leak(param_types.clone().into_boxed_slice()),
+                param_enum_types: Box::leak(param_enum_types.clone().into_boxed_slice()),
+            },
+        )
         .collect();
 
     let variants_static: &'static [EnumVariantInfo] = Box::leak(variant_infos.into_boxed_slice());
@@ -379,12 +391,14 @@ pub fn register_enum_from_mir(
 // ============================================================================
 
 /// Register class RTTI directly from MIR metadata.
+/// `interfaces` are the ids of implemented interfaces (see `ClassInfo::interfaces`).
 /// `instance_fields` are all instance field names (including inherited).
 /// `static_fields` are own static field names.
 pub fn register_class_from_mir(
     type_id: u32,
     name: &str,
     super_type_id: Option<u32>,
+    interfaces: &[u32],
     instance_fields: &[String],
     static_fields: &[String],
 ) {
@@ -409,6 +423,7 @@ pub fn register_class_from_mir(
     let class_info = Box::leak(Box::new(ClassInfo {
         name: class_name_static,
         super_type_id,
+        interfaces: Box::leak(interfaces.to_vec().into_boxed_slice()),
         instance_fields: instance_fields_static,
         static_fields: static_fields_static,
     }));
@@ -435,7 +450,7 @@ pub fn register_class_from_mir(
 // ============================================================================
 
 /// Helper: allocate a HaxeString from a &str, using the C API.
-unsafe fn alloc_haxe_string(s: &str) -> *mut u8 {
+pub(crate) unsafe fn alloc_haxe_string(s: &str) -> *mut u8 {
     let hs_layout = std::alloc::Layout::new::<crate::haxe_string::HaxeString>();
     let hs_ptr = std::alloc::alloc(hs_layout) as *mut crate::haxe_string::HaxeString;
     if hs_ptr.is_null() {
@@ -780,6 +795,7 @@ pub extern "C" fn haxe_register_enum_finish(type_id: u32) {
                     name: Box::leak(name.into_boxed_str()),
                     param_count,
                     param_types: Box::leak(param_types.into_boxed_slice()),
+                    param_enum_types: &[],
                 })
                 .collect();
 
@@ -969,7 +985,8 @@ pub extern "C" fn haxe_enum_get_parameters(
     arr
 }
 
-/// Walk the class hierarchy to check if actual_type_id is or extends expected_type_id.
+/// Walk the class hierarchy to check if actual_type_id is, extends or
+/// implements expected_type_id.
 fn type_id_matches_with_hierarchy(actual_type_id: i64, expected_type_id: i64) -> bool {
     if actual_type_id == expected_type_id {
         return true;
@@ -977,18 +994,18 @@ fn type_id_matches_with_hierarchy(actual_type_id: i64, expected_type_id: i64) ->
     let registry = TYPE_REGISTRY.read().unwrap();
     if let Some(ref registry) = *registry {
         let mut current = TypeId(actual_type_id as u32);
-        while let Some(type_info) = registry.get(&current) {
-            if let Some(class_info) = &type_info.class_info {
-                if let Some(parent_id) = class_info.super_type_id {
-                    if parent_id as i64 == expected_type_id {
-                        return true;
-                    }
-                    current = TypeId(parent_id);
-                } else {
-                    break;
-                }
-            } else {
-                break;
+        while let Some(class_info) = registry.get(&current).and_then(|t| t.class_info) {
+            if class_info
+                .interfaces
+                .iter()
+                .any(|&iface| iface as i64 == expected_type_id)
+            {
+                return true;
+            }
+            match class_info.super_type_id {
+                Some(parent_id) if parent_id as i64 == expected_type_id => return true,
+                Some(parent_id) => current = TypeId(parent_id),
+                None => break,
             }
         }
     }
@@ -1067,10 +1084,10 @@ pub extern "C" fn haxe_type_enum_parameters(
 #[no_mangle]
 pub extern "C" fn haxe_trace_enum(type_id: i64, discriminant: i64) {
     if let Some(name) = get_enum_variant_name(TypeId(type_id as u32), discriminant) {
-        println!("{}", name);
+        print_with_prefix(name);
     } else {
         // Fallback to discriminant if enum not registered
-        println!("{}", discriminant);
+        print_with_prefix(&discriminant.to_string());
     }
 }
 
@@ -1080,7 +1097,7 @@ pub extern "C" fn haxe_trace_enum(type_id: i64, discriminant: i64) {
 #[no_mangle]
 pub extern "C" fn haxe_trace_enum_boxed(type_id: u32, ptr: *const u8) {
     if ptr.is_null() {
-        println!("null");
+        print_with_prefix("null");
         return;
     }
 
@@ -1093,7 +1110,7 @@ pub extern "C" fn haxe_trace_enum_boxed(type_id: u32, ptr: *const u8) {
             Some(info) => info,
             None => {
                 // Fallback if enum not registered
-                println!("<enum {}::{}>", type_id, tag);
+                print_with_prefix(&format!("<enum {}::{}>", type_id, tag));
                 return;
             }
         };
@@ -1103,12 +1120,12 @@ pub extern "C" fn haxe_trace_enum_boxed(type_id: u32, ptr: *const u8) {
         let param_types = variant_info.param_types;
 
         if param_count == 0 {
-            println!("{}", variant_name);
+            print_with_prefix(variant_name);
         } else {
-            print!("{}(", variant_name);
+            let mut line = format!("{}(", variant_name);
             for i in 0..param_count {
                 if i > 0 {
-                    print!(", ");
+                    line.push_str(", ");
                 }
                 // Read field at offset 8 + i * 8
                 let field_ptr = ptr.add(8 + i * 8);
@@ -1119,43 +1136,46 @@ pub extern "C" fn haxe_trace_enum_boxed(type_id: u32, ptr: *const u8) {
                 match param_type {
                     ParamType::Int => {
                         let val = *(field_ptr as *const i64);
-                        print!("{}", val);
+                        let _ = write!(line, "{}", val);
                     }
                     ParamType::Float => {
                         let val = *(field_ptr as *const f64);
-                        print!("{}", val);
+                        let _ = write!(line, "{}", val);
                     }
                     ParamType::Bool => {
                         let val = *(field_ptr as *const i64) != 0;
-                        print!("{}", val);
+                        let _ = write!(line, "{}", val);
                     }
                     ParamType::String => {
                         // Field is a pointer to HaxeString
                         let str_ptr = *(field_ptr as *const *const crate::haxe_string::HaxeString);
                         if str_ptr.is_null() {
-                            print!("null");
+                            line.push_str("null");
                         } else {
                             let haxe_str = &*str_ptr;
                             let bytes =
                                 std::slice::from_raw_parts(haxe_str.ptr as *const u8, haxe_str.len);
                             match std::str::from_utf8(bytes) {
-                                Ok(s) => print!("\"{}\"", s),
-                                Err(_) => print!("<invalid utf8>"),
+                                Ok(s) => {
+                                    let _ = write!(line, "\"{}\"", s);
+                                }
+                                Err(_) => line.push_str("<invalid utf8>"),
                             }
                         }
                     }
                     ParamType::Object => {
                         let val = *(field_ptr as *const i64);
-                        print!("<object@0x{:x}>", val);
+                        let _ = write!(line, "<object@0x{:x}>", val);
                     }
                     ParamType::Dynamic => {
                         // Generic type parameter — print raw i64 value
                         let val = *(field_ptr as *const i64);
-                        print!("{}", val);
+                        let _ = write!(line, "{}", val);
                     }
                 }
             }
-            println!(")");
+            line.push(')');
+            print_with_prefix(&line);
         }
     }
 }
@@ -1172,7 +1192,7 @@ pub extern "C" fn haxe_trace_enum_boxed_typed(
     param_count: usize,
 ) {
     if ptr.is_null() {
-        println!("null");
+        print_with_prefix("null");
         return;
     }
 
@@ -1203,49 +1223,52 @@ pub extern "C" fn haxe_trace_enum_boxed_typed(
         };
 
         if caller_types.is_empty() {
-            println!("{}", variant_name);
+            print_with_prefix(&variant_name);
         } else {
-            print!("{}(", variant_name);
+            let mut line = format!("{}(", variant_name);
             for (i, &param_type) in caller_types.iter().enumerate() {
                 if i > 0 {
-                    print!(", ");
+                    line.push_str(", ");
                 }
                 let field_ptr = ptr.add(8 + i * 8);
 
                 match param_type {
                     ParamType::Int => {
                         let val = *(field_ptr as *const i64);
-                        print!("{}", val);
+                        let _ = write!(line, "{}", val);
                     }
                     ParamType::Float => {
                         let val = *(field_ptr as *const f64);
-                        print!("{}", val);
+                        let _ = write!(line, "{}", val);
                     }
                     ParamType::Bool => {
                         let val = *(field_ptr as *const i64) != 0;
-                        print!("{}", val);
+                        let _ = write!(line, "{}", val);
                     }
                     ParamType::String => {
                         let str_ptr = *(field_ptr as *const *const crate::haxe_string::HaxeString);
                         if str_ptr.is_null() {
-                            print!("null");
+                            line.push_str("null");
                         } else {
                             let haxe_str = &*str_ptr;
                             let bytes =
                                 std::slice::from_raw_parts(haxe_str.ptr as *const u8, haxe_str.len);
                             match std::str::from_utf8(bytes) {
-                                Ok(s) => print!("\"{}\"", s),
-                                Err(_) => print!("<invalid utf8>"),
+                                Ok(s) => {
+                                    let _ = write!(line, "\"{}\"", s);
+                                }
+                                Err(_) => line.push_str("<invalid utf8>"),
                             }
                         }
                     }
                     ParamType::Object | ParamType::Dynamic => {
                         let val = *(field_ptr as *const i64);
-                        print!("{}", val);
+                        let _ = write!(line, "{}", val);
                     }
                 }
             }
-            println!(")");
+            line.push(')');
+            print_with_prefix(&line);
         }
     }
 }
@@ -1293,7 +1316,7 @@ unsafe extern "C" fn int_to_string(value_ptr: *const u8) -> StringPtr {
 
 unsafe extern "C" fn float_to_string(value_ptr: *const u8) -> StringPtr {
     let value = *(value_ptr as *const f64);
-    let s = value.to_string();
+    let s = crate::number_format::format_float(value);
     // UNSAFE: Leaking memory! Need proper string management
     let s_static = Box::leak(s.into_boxed_str());
     StringPtr {
@@ -1453,6 +1476,15 @@ pub extern "C" fn haxe_std_string(dynamic: DynamicValue) -> StringPtr {
         return unsafe { null_to_string(std::ptr::null()) };
     }
 
+    // Class instances go through their toString()
+    if let Some(hs_ptr) = unsafe { boxed_object_to_string(&dynamic) } {
+        let hs = unsafe { &*(hs_ptr as *const crate::haxe_string::HaxeString) };
+        return StringPtr {
+            ptr: hs.ptr,
+            len: hs.len,
+        };
+    }
+
     // Look up type info and call toString
     if let Some(type_info) = get_type_info(dynamic.type_id) {
         unsafe { (type_info.to_string)(dynamic.value_ptr) }
@@ -1498,6 +1530,11 @@ pub extern "C" fn haxe_std_string_ptr(dynamic_ptr: *mut u8) -> *mut crate::haxe_
             }));
         }
 
+        // Class instances go through their toString()
+        if let Some(hs_ptr) = boxed_object_to_string(&dynamic) {
+            return hs_ptr as *mut HaxeString;
+        }
+
         // Look up type info and call toString, then convert to HaxeString
         if let Some(type_info) = get_type_info(dynamic.type_id) {
             let str_ptr = (type_info.to_string)(dynamic.value_ptr);
@@ -1525,7 +1562,7 @@ pub extern "C" fn haxe_std_string_ptr(dynamic_ptr: *mut u8) -> *mut crate::haxe_
 pub extern "C" fn haxe_free_dynamic(dynamic: DynamicValue) {
     if !dynamic.value_ptr.is_null() {
         unsafe {
-            libc::free(dynamic.value_ptr as *mut libc::c_void);
+            crate::debug_alloc::free(dynamic.value_ptr);
         }
     }
 }
@@ -1536,21 +1573,13 @@ pub extern "C" fn haxe_free_dynamic(dynamic: DynamicValue) {
 
 /// Convert a Float to an Int, rounded towards 0
 /// Implements Std.int(x:Float):Int
+///
+/// NaN becomes 0 and values outside the Int32 range (including infinities)
+/// saturate, matching the inline versions in the Cranelift and LLVM backends.
 #[no_mangle]
 pub extern "C" fn haxe_std_int(x: f64) -> i64 {
-    // Truncate towards zero (same as casting in Rust)
-    // Handle special cases
-    if x.is_nan() {
-        return 0;
-    }
-    if x.is_infinite() {
-        if x.is_sign_positive() {
-            return i64::MAX;
-        } else {
-            return i64::MIN;
-        }
-    }
-    x.trunc() as i64
+    // `as` truncates toward zero, saturates and maps NaN to 0
+    x as i32 as i64
 }
 
 /// Parse a String to an Int
@@ -1931,7 +1960,7 @@ pub extern "C" fn haxe_object_get_type_id(obj_ptr: *const u8) -> i64 {
 /// Safe downcast for class instances using object headers.
 /// Reads the type_id from offset 0, walks the class hierarchy, and returns
 /// the object pointer on match or null on failure.
-/// Used by `cast(expr, Type)` for class→class safe casts.
+/// Used by `Std.downcast()` on class instances.
 #[no_mangle]
 pub extern "C" fn haxe_safe_downcast_class(obj_ptr: *mut u8, target_type_id: i64) -> *mut u8 {
     if haxe_object_is_instance(obj_ptr, target_type_id) != 0 {
@@ -1941,7 +1970,52 @@ pub extern "C" fn haxe_safe_downcast_class(obj_ptr: *mut u8, target_type_id: i64
     }
 }
 
-/// Check if an object is an instance of (or subclass of) a target type.
+/// Checked downcast for class instances: `cast(expr, Type)`.
+///
+/// Returns the object pointer if it is an instance of (or implements) the
+/// target type, and null for null. Otherwise throws a catchable
+/// `"Cannot cast <Actual> to <Target>"` String exception.
+#[no_mangle]
+pub extern "C" fn haxe_checked_cast_class(obj_ptr: *mut u8, target_type_id: i64) -> *mut u8 {
+    if obj_ptr.is_null() || haxe_object_is_instance(obj_ptr, target_type_id) != 0 {
+        return obj_ptr;
+    }
+    let actual_type_id = unsafe { *(obj_ptr as *const i64) };
+    let message = format!(
+        "Cannot cast {} to {}",
+        class_display_name(actual_type_id),
+        class_display_name(target_type_id)
+    );
+    let exception = unsafe { alloc_haxe_string(&message) };
+    crate::exception::rayzor_throw_typed(exception as i64, TYPE_STRING.0);
+    std::ptr::null_mut()
+}
+
+/// Name of a registered class for error messages, or its type id.
+fn class_display_name(type_id: i64) -> String {
+    let registry = TYPE_REGISTRY.read().unwrap();
+    registry
+        .as_ref()
+        .and_then(|registry| registry.get(&TypeId(type_id as u32)))
+        .and_then(|type_info| type_info.class_info)
+        .map_or_else(
+            || format!("<type {}>", type_id),
+            |info| info.name.to_string(),
+        )
+}
+
+/// Object pointer held by an interface value (a fat pointer whose first slot
+/// is the object), or null for null.
+#[no_mangle]
+pub extern "C" fn haxe_interface_object(fat_ptr: *const u8) -> *mut u8 {
+    if fat_ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    unsafe { *(fat_ptr as *const *mut u8) }
+}
+
+/// Check if an object is an instance of (or subclass of) a target class, or
+/// implements a target interface.
 /// Walks the class hierarchy via TYPE_REGISTRY super_type_id chain.
 #[no_mangle]
 pub extern "C" fn haxe_object_is_instance(obj_ptr: *const u8, target_type_id: i64) -> i64 {
@@ -1949,29 +2023,7 @@ pub extern "C" fn haxe_object_is_instance(obj_ptr: *const u8, target_type_id: i6
         return 0;
     }
     let actual_type_id = unsafe { *(obj_ptr as *const i64) };
-    if actual_type_id == target_type_id {
-        return 1;
-    }
-    // Walk parent chain via TYPE_REGISTRY
-    let registry = TYPE_REGISTRY.read().unwrap();
-    if let Some(ref registry) = *registry {
-        let mut current = TypeId(actual_type_id as u32);
-        while let Some(type_info) = registry.get(&current) {
-            if let Some(class_info) = &type_info.class_info {
-                if let Some(parent_id) = class_info.super_type_id {
-                    if parent_id as i64 == target_type_id {
-                        return 1;
-                    }
-                    current = TypeId(parent_id);
-                } else {
-                    break; // No parent
-                }
-            } else {
-                break; // Not a class
-            }
-        }
-    }
-    0
+    type_id_matches_with_hierarchy(actual_type_id, target_type_id) as i64
 }
 
 // ============================================================================
@@ -2026,3 +2078,87 @@ pub extern "C" fn haxe_vtable_lookup(obj_ptr: *const u8, slot_index: i32) -> i64
     }
     0
 }
+
+// ============================================================================
+// Class toString() Protocol
+// ============================================================================
+
+/// Class `toString()` registry: type_id (as u32) -> closure pointer
+/// (`{fn_code_ptr, env_ptr}`, as for vtable slots).
+/// Only classes that declare their own `toString()` are registered; subclasses
+/// inherit through the super chain.
+static TOSTRING_REGISTRY: RwLock<Option<HashMap<u32, i64>>> = RwLock::new(None);
+
+/// Register the `toString()` method of a class.
+/// Called at program startup alongside the vtable setup.
+#[no_mangle]
+pub extern "C" fn haxe_class_set_tostring(type_id: i32, closure_ptr: i64) {
+    let mut registry = TOSTRING_REGISTRY.write().unwrap();
+    let map = registry.get_or_insert_with(HashMap::new);
+    map.insert(type_id as u32, closure_ptr);
+}
+
+/// Closure of the nearest `toString()` for a class, walking the super chain.
+fn find_class_tostring(type_id: u32) -> Option<i64> {
+    let tostrings = TOSTRING_REGISTRY.read().unwrap();
+    let tostrings = tostrings.as_ref()?;
+    let types = TYPE_REGISTRY.read().unwrap();
+    let mut current = Some(type_id);
+    while let Some(id) = current {
+        if let Some(&closure_ptr) = tostrings.get(&id) {
+            return Some(closure_ptr);
+        }
+        current = types
+            .as_ref()
+            .and_then(|types| types.get(&TypeId(id)))
+            .and_then(|type_info| type_info.class_info)
+            .and_then(|class_info| class_info.super_type_id);
+    }
+    None
+}
+
+/// Whether `type_id` names a registered class.
+fn is_class_type(type_id: u32) -> bool {
+    let registry = TYPE_REGISTRY.read().unwrap();
+    registry
+        .as_ref()
+        .and_then(|registry| registry.get(&TypeId(type_id)))
+        .is_some_and(|type_info| type_info.class_info.is_some())
+}
+
+/// String form of a class instance (`Std.string(obj)`, `trace(obj)`, `"" + obj`).
+///
+/// Reads the class from the object header and calls the class's own or
+/// inherited `toString()`. Classes without one print as `ClassName@address`.
+/// Returns "null" for null.
+#[no_mangle]
+pub extern "C" fn haxe_object_to_string(obj_ptr: *const u8) -> *mut u8 {
+    unsafe {
+        if obj_ptr.is_null() {
+            return alloc_haxe_string("null");
+        }
+        let type_id = *(obj_ptr as *const i64);
+        if let Some(closure_ptr) = find_class_tostring(type_id as u32) {
+            let closure = closure_ptr as *const i64;
+            let to_string: extern "C" fn(i64, *const u8) -> *mut u8 = std::mem::transmute(*closure);
+            let result = to_string(*closure.add(1), obj_ptr);
+            return if result.is_null() {
+                alloc_haxe_string("null")
+            } else {
+                result
+            };
+        }
+        alloc_haxe_string(&format!("{}@{:p}", class_display_name(type_id), obj_ptr))
+    }
+}
+
+/// String form of a boxed class instance, or None if the box holds something
+/// else. Reference boxes may carry a type id the registry doesn't know, in
+/// which case the object header decides.
+unsafe fn boxed_object_to_string(dynamic: &DynamicValue) -> Option<*mut u8> {
+    let is_object = match get_type_info(dynamic.type_id) {
+        Some(type_info) => type_info.class_info.is_some(),
+        None => is_class_type(*(dynamic.value_ptr as *const i64) as u32),
+    };
+    is_object.then(|| haxe_object_to_string(dynamic.value_ptr))
+}
@@ -0,0 +1,80 @@
+//! The collector with generated-code allocation routed to it, as under
+//! `rayzor run --gc`. Enabling it and installing the tracking allocator are
+//! process-wide, so this runs in its own test binary.
+
+use rayzor_runtime::gc;
+use rayzor_runtime::haxe_array::{haxe_array_free, haxe_array_new, haxe_array_push_i64, HaxeArray};
+
+#[global_allocator]
+static ALLOCATOR: gc::TrackingAllocator = gc::TrackingAllocator;
+
+/// Hides an address from the conservative scan
+const MASK: usize = 0x5a5a_5a5a;
+
+/// Overwrite dead stack frames, so stale copies of a pointer don't keep
+/// its object alive
+#[inline(never)]
+fn scrub_stack() {
+    std::hint::black_box([0u8; 64 * 1024]);
+}
+
+#[inline(never)]
+fn alloc_into(slot: &mut usize) -> usize {
+    let object = gc::rayzor_gc_malloc(32);
+    *slot = object as usize;
+    object as usize ^ MASK
+}
+
+/// Whether the masked object is still allocated, without leaving its
+/// address in the caller's registers
+#[inline(never)]
+fn is_live(hidden: usize) -> bool {
+    gc::owns((std::hint::black_box(hidden) ^ MASK) as *mut u8)
+}
+
+#[test]
+fn test_container_buffers_are_roots() {
+    gc::set_enabled(true);
+
+    // An array buffer holds the only reference
+    let mut array = HaxeArray {
+        ptr: std::ptr::null_mut(),
+        len: 0,
+        cap: 0,
+        elem_size: 0,
+    };
+    haxe_array_new(&mut array, 8);
+    haxe_array_push_i64(&mut array, 0);
+    let hidden = alloc_into(unsafe { &mut *(array.ptr as *mut usize) });
+    scrub_stack();
+    gc::rayzor_gc_collect();
+    assert!(is_live(hidden));
+
+    // Still after the buffer moves as the array grows
+    for i in 1..1000 {
+        haxe_array_push_i64(&mut array, i);
+    }
+    scrub_stack();
+    gc::rayzor_gc_collect();
+    assert!(is_live(hidden));
+
+    // Dropping the reference makes it garbage
+    unsafe { *(array.ptr as *mut usize) = 0 };
+    scrub_stack();
+    assert_eq!(gc::rayzor_gc_collect(), 32);
+    assert!(!is_live(hidden));
+
+    // Blocks allocated outside `gc::tracked` are not scanned
+    let mut untracked = Box::new([0usize; 4]);
+    let hidden = alloc_into(&mut untracked[0]);
+    scrub_stack();
+    assert_eq!(gc::rayzor_gc_collect(), 32);
+    assert!(!is_live(hidden));
+
+    // Shutdown frees what is still referenced
+    let hidden = alloc_into(unsafe { &mut *(array.ptr as *mut usize) });
+    rayzor_runtime::rayzor_runtime_shutdown();
+    assert!(!is_live(hidden));
+    assert_eq!(gc::stats().objects, 0);
+    haxe_array_free(&mut array);
+}
@@ -6,6 +6,7 @@
 
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::OnceLock;
 
 /// Represents a position in source code
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -98,29 +99,37 @@ impl fmt::Display for FileId {
 pub struct SourceFile {
     pub name: String,
     pub content: String,
-    pub line_starts: Vec<usize>,
+    /// Computed on first use: a file that parses and checks cleanly never
+    /// needs line/column positions
+    line_starts: OnceLock<Vec<usize>>,
 }
 
 impl SourceFile {
-    /// Create a new source file with precomputed line starts
+    /// Create a new source file. Line starts are computed on first use.
     pub fn new(name: String, content: String) -> Self {
-        let line_starts = compute_line_starts(&content);
         Self {
             name,
             content,
-            line_starts,
+            line_starts: OnceLock::new(),
         }
     }
 
+    /// Byte offsets at which each line starts
+    pub fn line_starts(&self) -> &[usize] {
+        self.line_starts
+            .get_or_init(|| compute_line_starts(&self.content))
+    }
+
     /// Get a specific line from the source file (1-based line numbers)
     pub fn get_line(&self, line_number: usize) -> Option<&str> {
-        if line_number == 0 || line_number > self.line_starts.len() {
+        let line_starts = self.line_starts();
+        if line_number == 0 || line_number > line_starts.len() {
             return None;
         }
 
-        let start = self.line_starts[line_number - 1];
-        let end = if line_number < self.line_starts.len() {
-            self.line_starts[line_number]
+        let start = line_starts[line_number - 1];
+        let end = if line_number < line_starts.len() {
+            line_starts[line_number]
         } else {
             self.content.len()
         };
@@ -131,12 +140,13 @@ impl SourceFile {
     /// Convert a byte offset to line and column (1-based)
     pub fn offset_to_line_col(&self, offset: usize) -> (usize, usize) {
         // Binary search for the line
-        let line_index = match self.line_starts.binary_search(&offset) {
+        let line_starts = self.line_starts();
+        let line_index = match line_starts.binary_search(&offset) {
             Ok(i) => i,
             Err(i) => i.saturating_sub(1),
         };
 
-        let line_start = self.line_starts.get(line_index).copied().unwrap_or(0);
+        let line_start = line_starts.get(line_index).copied().unwrap_or(0);
         let column = offset - line_start + 1;
         let line = line_index + 1;
 
@@ -357,4 +367,14 @@ mod tests {
         assert_eq!(source_map.get_file(file2).unwrap().name, "file2.hx");
         assert_ne!(file1, file2);
     }
+
+    #[test]
+    fn test_line_starts_computed_on_demand() {
+        let file = SourceFile::new("test.hx".to_string(), "a\nbc\n\nd".to_string());
+        assert!(file.line_starts.get().is_none());
+
+        assert_eq!(file.offset_to_line_col(3), (2, 2));
+        assert_eq!(file.line_starts(), &[0, 2, 5, 6]);
+        assert_eq!(file.get_line(3), Some(""));
+    }
 }
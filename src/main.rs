@@ -17,12 +17,20 @@
 //!
 //! # Show compilation pipeline
 //! rayzor compile --show-ir Main.hx
+//!
+//! # Language server for editors
+//! rayzor lsp
 //! ```
 
 use clap::{Parser, Subcommand, ValueEnum};
+use log::info;
 use std::path::{Path, PathBuf};
 use std::process;
 
+/// Lets `run --gc` scan the runtime containers that hold GC pointers
+#[global_allocator]
+static ALLOCATOR: rayzor_runtime::gc::TrackingAllocator = rayzor_runtime::gc::TrackingAllocator;
+
 #[derive(Parser)]
 #[command(name = "rayzor")]
 #[command(version = "0.1.0")]
@@ -56,10 +64,12 @@ enum Commands {
         llvm: bool,
 
         /// Tier preset: script, application, server, benchmark, development, embedded
-        #[arg(long, value_enum, default_value = "application")]
-        preset: Preset,
+        /// (default: script for `#!` scripts, application otherwise)
+        #[arg(long, value_enum)]
+        preset: Option<Preset>,
 
-        /// Enable BLADE cache for incremental compilation
+        /// Enable BLADE cache; skips compilation entirely when the sources,
+        /// flags and compiler are unchanged since the last cached run
         #[arg(long)]
         cache: bool,
 
@@ -78,6 +88,75 @@ enum Commands {
         /// Load .rpkg packages (repeatable)
         #[arg(long = "rpkg", value_name = "FILE")]
         rpkg_files: Vec<PathBuf>,
+
+        /// Runtime safety checks for generated code
+        #[arg(long, value_enum, default_value = "unchecked")]
+        safety: Safety,
+
+        /// Instrument generated code for a sanitizer: `address` (debug
+        /// allocator) or `thread` (needs a rayzor built with ThreadSanitizer
+        /// and the runtime `tsan` feature)
+        #[arg(long, value_enum, value_name = "SANITIZER")]
+        sanitize: Option<Sanitizer>,
+
+        /// Allocate from the runtime's tracing garbage collector, which
+        /// reclaims what the compiler's drop analysis leaks
+        /// (RAYZOR_GC_THRESHOLD sets the bytes allocated between collections)
+        #[arg(long, conflicts_with = "sanitize")]
+        gc: bool,
+
+        /// After the program exits, wait for its sources to change and run
+        /// it again, re-JITing only the functions the edit affected
+        #[arg(long)]
+        watch: bool,
+
+        /// Only load stdlib modules reachable from referenced types (implied by --preset embedded)
+        #[arg(long)]
+        lazy_stdlib: bool,
+
+        /// Compile without the Haxe standard library (bare-metal profile)
+        #[arg(long, conflicts_with = "lazy_stdlib")]
+        no_stdlib: bool,
+
+        /// Compiler defines (repeatable); `-D stdlib-src` builds the stdlib
+        /// from haxe-std sources instead of the embedded precompiled one
+        #[arg(short = 'D', long = "define", value_name = "NAME")]
+        defines: Vec<String>,
+
+        /// Code generation backend: `tiered` (default tiered JIT) or a single
+        /// backend such as `cranelift`, `llvm` or one provided by a plugin
+        #[arg(long, default_value = "tiered")]
+        backend: String,
+
+        /// Write a crash report to .rayzor-crash/ if the program faults
+        /// (also enabled by RAYZOR_CRASH_REPORT=1)
+        #[arg(long)]
+        crash_report: bool,
+
+        /// Prefix trace() output with the call's `Class.method:line`
+        /// (also enabled by RAYZOR_TRACE_POS=1)
+        #[arg(long)]
+        trace_pos: bool,
+
+        /// Diagnostics format: `text`, or `json` for one JSON object per
+        /// error on stderr
+        #[arg(long, value_enum, default_value = "text")]
+        format: DiagnosticsFormat,
+
+        /// Static function to run instead of `main` (e.g. `tools.Gen.run`),
+        /// or for a bundle, one of its named entry points
+        #[arg(long, value_name = "FUNCTION")]
+        entry: Option<String>,
+
+        /// Stack size of the thread running the program, in bytes or with a
+        /// K, M or G suffix (e.g. `64M`)
+        #[arg(long, value_name = "SIZE", value_parser = rayzor_runtime::stack_guard::parse_size)]
+        main_stack_size: Option<usize>,
+
+        /// Program arguments, returned by `Sys.args()` and passed to
+        /// `main(args:Array<String>)` (after `--`)
+        #[arg(last = true, value_name = "ARGS")]
+        args: Vec<String>,
     },
 
     /// JIT compile with interactive REPL
@@ -111,9 +190,14 @@ enum Commands {
         #[arg(long)]
         show_types: bool,
 
-        /// Output format
+        /// Output format; `json` also reports errors as JSON lines on stderr
         #[arg(long, value_enum, default_value = "text")]
         format: OutputFormat,
+
+        /// Print the inferred ownership of each variable, with its moves and
+        /// borrows, as JSON
+        #[arg(long)]
+        ownership_report: bool,
     },
 
     /// Compile Haxe to intermediate representation
@@ -144,6 +228,11 @@ enum Commands {
         /// Build with optimizations (uses target/release instead of target/debug)
         #[arg(long)]
         release: bool,
+
+        /// Diagnostics format: `text`, or `json` for one JSON object per
+        /// error on stderr
+        #[arg(long, value_enum, default_value = "text")]
+        format: DiagnosticsFormat,
     },
 
     /// Build from HXML file or rayzor.toml
@@ -162,6 +251,11 @@ enum Commands {
         /// Show what would be built without building
         #[arg(long)]
         dry_run: bool,
+
+        /// Write the resolved build graph (sources, imports, defines, class
+        /// paths, cache keys, outputs) as JSON to this file instead of building
+        #[arg(long, value_name = "FILE")]
+        emit_plan: Option<PathBuf>,
     },
 
     /// Show information about the compiler
@@ -173,6 +267,26 @@ enum Commands {
         /// Show tiered JIT configuration
         #[arg(long)]
         tiers: bool,
+
+        /// List loaded plugins and the runtime symbols each provides
+        #[arg(long)]
+        plugins: bool,
+
+        /// Also load .rpkg packages for --plugins (repeatable)
+        #[arg(long = "rpkg", value_name = "FILE")]
+        rpkg_files: Vec<PathBuf>,
+    },
+
+    /// Check the environment: backends, GPU plugin, AOT toolchain, cache and
+    /// packages, with a fix for each missing piece
+    Doctor {
+        /// Also check these .rpkg packages (repeatable)
+        #[arg(long = "rpkg", value_name = "FILE")]
+        rpkg_files: Vec<PathBuf>,
+
+        /// Custom BLADE cache directory
+        #[arg(long)]
+        cache_dir: Option<PathBuf>,
     },
 
     /// Manage BLADE compilation cache
@@ -211,6 +325,31 @@ enum Commands {
         #[arg(long)]
         cache_dir: Option<PathBuf>,
 
+        /// Embed the Haxe sources, so the bundle is recompiled instead of
+        /// rejected when run by a rayzor with a different stdlib
+        #[arg(long)]
+        embed_sources: bool,
+
+        /// Replace the program's function and class names with hashes
+        /// (obfuscation, not DRM; breaks reflection by class name)
+        #[arg(long)]
+        obfuscate: bool,
+
+        /// Encrypt the bundle with the 256-bit hex key in RAYZOR_BUNDLE_KEY
+        /// (or --key-env); the same key must be set when it runs
+        #[arg(long)]
+        encrypt: bool,
+
+        /// Environment variable holding the --encrypt key
+        #[arg(long, value_name = "VAR", requires = "encrypt")]
+        key_env: Option<String>,
+
+        /// Named entry point, `name=Class.method` (repeatable). Bundles with
+        /// entry points need no main; pick one with `rayzor run --entry`, or
+        /// load it as a plugin with `rayzor.Runtime.loadModuleEntry`
+        #[arg(long = "entry", value_name = "NAME=CLASS.METHOD")]
+        entries: Vec<String>,
+
         /// Enable verbose output
         #[arg(short, long)]
         verbose: bool,
@@ -226,11 +365,12 @@ enum Commands {
         #[arg(short, long)]
         output: Option<PathBuf>,
 
-        /// Target triple for cross-compilation
+        /// Target triple for cross-compilation (wasm32-wasi builds a
+        /// WebAssembly module; needs wasi-sdk)
         #[arg(long)]
         target: Option<String>,
 
-        /// Output format: exe, obj, llvm-ir, llvm-bc, asm
+        /// Output format: exe, obj, llvm-ir, llvm-bc, asm, size-report
         #[arg(long, default_value = "exe")]
         emit: String,
 
@@ -246,6 +386,32 @@ enum Commands {
         #[arg(long)]
         strip_symbols: bool,
 
+        /// Keep unreferenced functions and data when linking
+        #[arg(long)]
+        no_gc_sections: bool,
+
+        /// Link a position-dependent executable (PIE is the default)
+        #[arg(long)]
+        no_pie: bool,
+
+        /// Leave relocated data writable after startup (full RELRO is the
+        /// default on ELF targets)
+        #[arg(long)]
+        no_relro: bool,
+
+        /// Don't add stack canaries (-fstack-protector-strong is the default)
+        #[arg(long)]
+        no_stack_protector: bool,
+
+        /// Allow an executable stack (non-executable is the default)
+        #[arg(long)]
+        no_nx: bool,
+
+        /// Fully static executable linked against musl, e.g. for scratch
+        /// container images (defaults the target to <arch>-unknown-linux-musl)
+        #[arg(long = "static")]
+        static_link: bool,
+
         /// Path to librayzor_runtime.a
         #[arg(long)]
         runtime_dir: Option<PathBuf>,
@@ -266,11 +432,45 @@ enum Commands {
         #[arg(long)]
         cache_dir: Option<PathBuf>,
 
+        /// Only check that librayzor_runtime.a provides every extern the
+        /// program references, then exit (no code generation or linking)
+        #[arg(long)]
+        check_symbols: bool,
+
         /// Enable verbose output
         #[arg(short, long)]
         verbose: bool,
     },
 
+    /// Install a built executable or bundle, with the native libraries it
+    /// needs, into a prefix (configured by [install] in rayzor.toml)
+    Install {
+        /// Executable from `rayzor aot` or .rzb from `rayzor bundle`
+        /// (default: [install] artifact, then [build] output)
+        artifact: Option<PathBuf>,
+
+        /// Install prefix; files go to <prefix>/bin and <prefix>/lib/<name>
+        /// (default: [install] prefix, then ~/.local)
+        #[arg(long)]
+        prefix: Option<PathBuf>,
+
+        /// Installed program name (default: the artifact's file stem)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Packages whose native libraries the program needs (repeatable)
+        #[arg(long = "rpkg", value_name = "FILE")]
+        rpkg_files: Vec<PathBuf>,
+
+        /// Install the rayzor-gpu plugin alongside
+        #[arg(long)]
+        gpu: bool,
+
+        /// Show what would be installed without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Initialize a new Rayzor project or workspace
     Init {
         /// Project or workspace name (also used as directory name)
@@ -295,6 +495,10 @@ enum Commands {
         #[arg(short, long)]
         list: bool,
 
+        /// Also index .rpkg packages, one <package>.bsym each (repeatable)
+        #[arg(long = "rpkg", value_name = "FILE")]
+        rpkg_files: Vec<PathBuf>,
+
         /// Custom BLADE cache directory
         #[arg(long)]
         cache_dir: Option<PathBuf>,
@@ -324,6 +528,29 @@ enum Commands {
         /// Show only CFG (control flow graph) without instructions
         #[arg(long)]
         cfg_only: bool,
+
+        /// With --cfg-only: emit a Graphviz dot graph per function. If
+        /// --output is a directory, each function is written to its own
+        /// `<qualified name>.dot` there
+        #[arg(long, requires = "cfg_only")]
+        dot: bool,
+
+        /// What to print: `mir`, or `native` machine code annotated with MIR
+        /// and Haxe source lines
+        #[arg(long, value_enum, default_value = "mir")]
+        emit: DumpEmit,
+
+        /// JIT tier for `--emit native` (0=baseline, 1=standard, 2=optimized, 3=maximum)
+        #[arg(long, default_value = "2")]
+        tier: u8,
+
+        /// Diff the MIR of two pass pipelines, e.g. `O0,O2` (`raw` = no passes)
+        #[arg(long, value_name = "A,B", conflicts_with_all = ["cfg_only", "emit"])]
+        diff: Option<String>,
+
+        /// Print the size, alignment and field offsets of each class instead of MIR
+        #[arg(long, conflicts_with_all = ["cfg_only", "emit", "diff"])]
+        layouts: bool,
     },
 
     /// Manage .rpkg packages (pack, inspect)
@@ -331,6 +558,131 @@ enum Commands {
         #[command(subcommand)]
         action: RpkgAction,
     },
+
+    /// Compare the public API of two versions of a library and report
+    /// breaking changes, e.g. to choose an .rpkg version number
+    ApiDiff {
+        /// Source directory of the old version
+        old: PathBuf,
+
+        /// Source directory of the new version
+        new: PathBuf,
+
+        /// Output format; `json` for tools and CI
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Search a project's declarations by name, find the implementations of
+    /// an interface or the callers of a method
+    #[command(group(clap::ArgGroup::new("query").required(true)))]
+    Query {
+        /// Source files or directories to search
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Declarations with this name, or `Type.member`; `*` matches any
+        /// characters
+        #[arg(long, value_name = "NAME", group = "query")]
+        symbol: Option<String>,
+
+        /// Classes implementing this interface or extending this class
+        #[arg(long, value_name = "TYPE", group = "query")]
+        implementations_of: Option<String>,
+
+        /// Call sites of this function, method or constructor (`Type.new`)
+        #[arg(long, value_name = "Type.method", group = "query")]
+        callers_of: Option<String>,
+
+        /// Output format; `json` for editors and scripts
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Export the project's call graph or module dependency graph as
+    /// Graphviz dot or JSON
+    #[command(group(clap::ArgGroup::new("graph").required(true)))]
+    Graph {
+        /// Source files or directories of the project
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Call graph: functions and methods, with an edge from each caller
+        /// to each callee
+        #[arg(long, group = "graph")]
+        call: bool,
+
+        /// Module dependency graph: source files, with an edge to each file
+        /// whose declarations they use
+        #[arg(long, group = "graph")]
+        modules: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "dot")]
+        format: GraphFormat,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Only nodes in this package or its subpackages (repeatable)
+        #[arg(long = "package", value_name = "PACKAGE")]
+        packages: Vec<String>,
+
+        /// Call graph: one node per class instead of per method
+        #[arg(long)]
+        collapse_classes: bool,
+    },
+
+    /// Read crash reports written by `run --crash-report`
+    CrashReport {
+        #[command(subcommand)]
+        action: CrashReportAction,
+    },
+
+    /// Local usage statistics (opt in with `[stats] enabled = true` or RAYZOR_STATS=1)
+    Stats {
+        #[command(subcommand)]
+        action: StatsAction,
+    },
+
+    /// Start the language server (diagnostics, hover, go to definition,
+    /// document symbols) on stdin/stdout
+    Lsp {
+        /// Use stdio; the only transport, accepted for editors that pass it
+        #[arg(long)]
+        stdio: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum StatsAction {
+    /// Show compile time, cache hit rate and tier trends
+    Show {
+        /// Only the most recent N records
+        #[arg(long)]
+        last: Option<usize>,
+
+        /// Stats file (defaults to the project's .rayzor/stats.jsonl)
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+
+    /// Delete the recorded statistics
+    Clear {
+        /// Stats file (defaults to the project's .rayzor/stats.jsonl)
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CrashReportAction {
+    /// Pretty-print a crash report
+    Inspect {
+        /// Report file (defaults to the latest one in .rayzor-crash/)
+        file: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -376,6 +728,17 @@ enum CacheAction {
         #[arg(long)]
         cache_dir: Option<PathBuf>,
     },
+
+    /// Check cached modules for corruption and remove bad entries
+    Verify {
+        /// Cache directory (defaults to .rayzor-cache)
+        #[arg(long)]
+        cache_dir: Option<PathBuf>,
+
+        /// Only report bad entries, don't remove them
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -385,6 +748,49 @@ enum OutputFormat {
     Pretty,
 }
 
+impl OutputFormat {
+    fn diagnostics(&self) -> DiagnosticsFormat {
+        match self {
+            OutputFormat::Json => DiagnosticsFormat::Json,
+            OutputFormat::Text | OutputFormat::Pretty => DiagnosticsFormat::Text,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum GraphFormat {
+    /// Graphviz dot, e.g. for `dot -Tsvg`
+    Dot,
+    /// Nodes and edges as JSON
+    Json,
+}
+
+/// How compiler errors are reported
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum DiagnosticsFormat {
+    /// Source snippets, for people
+    Text,
+    /// One JSON object per line on stderr, for editors and CI
+    Json,
+}
+
+impl DiagnosticsFormat {
+    fn to_diagnostic_format(self) -> diagnostics::DiagnosticFormat {
+        match self {
+            DiagnosticsFormat::Text => diagnostics::DiagnosticFormat::Human,
+            DiagnosticsFormat::Json => diagnostics::DiagnosticFormat::Json,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum DumpEmit {
+    /// MIR after optimization
+    Mir,
+    /// Machine code of each function, as JIT-compiled at `--tier`
+    Native,
+}
+
 #[derive(ValueEnum, Clone, Debug)]
 enum CompileStage {
     /// Stop after parsing (AST)
@@ -410,12 +816,43 @@ enum Preset {
     Server,
     /// Performance testing - immediate bailout, manual LLVM upgrade
     Benchmark,
-    /// Development and debugging - verbose logging
+    /// Development and debugging - verbose logging, deadlock detection
     Development,
     /// Resource-constrained environments - interpreter only
     Embedded,
 }
 
+/// How much of the Haxe standard library a compile pulls in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StdlibMode {
+    /// Register the whole stdlib symbol index upfront (default)
+    Full,
+    /// Parse and lower only modules reachable from referenced types
+    Lazy,
+    /// No stdlib at all, only compiler built-ins and runtime externs
+    None,
+}
+
+/// Sanitizer instrumentation for `rayzor run --sanitize`
+#[derive(ValueEnum, Clone, Debug, Copy, PartialEq, Eq)]
+enum Sanitizer {
+    /// Report data races on class fields through ThreadSanitizer
+    Thread,
+    /// Guard, poison and quarantine heap blocks to catch overflows,
+    /// use-after-free and double frees
+    Address,
+}
+
+/// Runtime safety checks inserted into generated code
+#[derive(ValueEnum, Clone, Debug, Copy, PartialEq, Eq)]
+enum Safety {
+    /// No extra checks (default)
+    Unchecked,
+    /// Null-check allocation results and route failures through the OOM
+    /// handler, and check @:requires/@:ensures contracts in release builds
+    Checked,
+}
+
 impl Preset {
     fn to_tier_preset(self) -> compiler::codegen::TierPreset {
         match self {
@@ -432,6 +869,25 @@ impl Preset {
 fn main() {
     let cli = Cli::parse();
 
+    // Verbose progress output is routed through the logger (see RAYZOR_LOG)
+    let verbose = match &cli.command {
+        Commands::Run { verbose, .. }
+        | Commands::Build { verbose, .. }
+        | Commands::Bundle { verbose, .. }
+        | Commands::Aot { verbose, .. }
+        | Commands::Preblade { verbose, .. } => *verbose,
+        _ => false,
+    };
+    compiler::logging::init_cli(verbose);
+
+    let diagnostics_format = match &cli.command {
+        Commands::Run { format, .. } | Commands::Compile { format, .. } => *format,
+        Commands::Check { format, .. }
+        | Commands::ApiDiff { format, .. }
+        | Commands::Query { format, .. } => format.diagnostics(),
+        _ => DiagnosticsFormat::Text,
+    };
+
     let result = match cli.command {
         Commands::Run {
             file,
@@ -445,10 +901,75 @@ fn main() {
             release,
             compute,
             rpkg_files,
-        } => run_file(
-            file, verbose, stats, tier, llvm, preset, cache, cache_dir, release, compute,
-            rpkg_files,
-        ),
+            safety,
+            sanitize,
+            gc,
+            watch,
+            lazy_stdlib,
+            no_stdlib,
+            defines,
+            backend,
+            crash_report,
+            trace_pos,
+            format,
+            entry,
+            main_stack_size,
+            args,
+        } => {
+            if trace_pos {
+                rayzor_runtime::haxe_sys::set_trace_positions(true);
+            }
+            let preset = preset.unwrap_or_else(|| default_run_preset(file.as_deref()));
+            if matches!(preset, Preset::Development) {
+                rayzor_runtime::deadlock::set_enabled(true);
+            }
+            let stdlib = if no_stdlib {
+                StdlibMode::None
+            } else if lazy_stdlib || matches!(preset, Preset::Embedded) {
+                StdlibMode::Lazy
+            } else {
+                StdlibMode::Full
+            };
+            let run = move || {
+                run_file(
+                    file,
+                    verbose,
+                    stats,
+                    tier,
+                    llvm,
+                    preset,
+                    cache,
+                    cache_dir,
+                    release,
+                    compute,
+                    rpkg_files,
+                    safety,
+                    sanitize,
+                    gc,
+                    watch,
+                    stdlib,
+                    &defines,
+                    &backend,
+                    crash_report,
+                    format,
+                    entry.as_deref(),
+                    args,
+                )
+            };
+            match main_stack_size {
+                Some(size) => std::thread::Builder::new()
+                    .name("main".to_string())
+                    .stack_size(size)
+                    .spawn(run)
+                    .map_err(|e| format!("Failed to start main thread: {}", e))
+                    .and_then(|thread| {
+                        thread
+                            .join()
+                            .unwrap_or_else(|_| Err("Main thread panicked".to_string()))
+                    }),
+                None => run(),
+            }
+        }
         Commands::Jit {
             file,
             tier,
@@ -460,7 +981,14 @@ fn main() {
             file,
             show_types,
             format,
-        } => check_file(file, show_types, format),
+            ownership_report,
+        } => {
+            if ownership_report {
+                print_ownership_report(file)
+            } else {
+                check_file(file, show_types, format)
+            }
+        }
         Commands::Compile {
             file,
             stage,
@@ -469,20 +997,40 @@ fn main() {
             cache,
             cache_dir,
             release,
-        } => compile_file(file, stage, show_ir, output, cache, cache_dir, release),
+            format,
+        } => compile_file(
+            file, stage, show_ir, output, cache, cache_dir, release, format,
+        ),
         Commands::Build {
             file,
             verbose,
             output,
             dry_run,
-        } => build_hxml(file, verbose, output, dry_run),
-        Commands::Info { features, tiers } => {
+            emit_plan,
+        } => match emit_plan {
+            Some(plan) => emit_build_plan(file, output, &plan),
+            None => build_hxml(file, verbose, output, dry_run),
+        },
+        Commands::Info {
+            features,
+            tiers,
+            plugins,
+            rpkg_files,
+        } => {
+            if plugins {
+                return show_plugins(&rpkg_files);
+            }
             show_info(features, tiers);
             Ok(())
         }
+        Commands::Doctor {
+            rpkg_files,
+            cache_dir,
+        } => cmd_doctor(rpkg_files, cache_dir),
         Commands::Cache { action } => match action {
             CacheAction::Stats { cache_dir } => cache_stats(cache_dir),
             CacheAction::Clear { cache_dir } => cache_clear(cache_dir),
+            CacheAction::Verify { cache_dir, dry_run } => cache_verify(cache_dir, dry_run),
         },
         Commands::Bundle {
             files,
@@ -492,6 +1040,11 @@ fn main() {
             no_compress,
             cache,
             cache_dir,
+            embed_sources,
+            obfuscate,
+            encrypt,
+            key_env,
+            entries,
             verbose,
         } => cmd_bundle(
             files,
@@ -501,6 +1054,12 @@ fn main() {
             no_compress,
             cache,
             cache_dir,
+            embed_sources,
+            obfuscate,
+            encrypt.then(|| {
+                key_env.unwrap_or_else(|| compiler::ir::bundle_crypt::BUNDLE_KEY_ENV.to_string())
+            }),
+            entries,
             verbose,
         ),
         Commands::Aot {
@@ -511,11 +1070,18 @@ fn main() {
             opt_level,
             strip,
             strip_symbols,
+            no_gc_sections,
+            no_pie,
+            no_relro,
+            no_stack_protector,
+            no_nx,
+            static_link,
             runtime_dir,
             linker,
             sysroot,
             cache,
             cache_dir,
+            check_symbols,
             verbose,
         } => cmd_aot(
             files,
@@ -525,28 +1091,51 @@ fn main() {
             opt_level,
             strip,
             strip_symbols,
+            !no_gc_sections,
+            !no_pie,
+            !no_relro,
+            !no_stack_protector,
+            !no_nx,
+            static_link,
             runtime_dir,
             linker,
             sysroot,
             cache,
             cache_dir,
+            check_symbols,
             verbose,
         ),
+        Commands::Install {
+            artifact,
+            prefix,
+            name,
+            rpkg_files,
+            gpu,
+            dry_run,
+        } => cmd_install(artifact, prefix, name, rpkg_files, gpu, dry_run),
         Commands::Init { name, workspace } => cmd_init(name, workspace),
         Commands::Preblade {
             files,
             out,
             list,
+            rpkg_files,
             cache_dir,
             verbose,
-        } => cmd_preblade(files, out, list, cache_dir, verbose),
+        } => cmd_preblade(files, out, list, rpkg_files, cache_dir, verbose),
         Commands::Dump {
             file,
             output,
             opt_level,
             function,
             cfg_only,
-        } => cmd_dump(file, output, opt_level, function, cfg_only),
+            dot,
+            emit,
+            tier,
+            diff,
+            layouts,
+        } => cmd_dump(
+            file, output, opt_level, function, cfg_only, dot, emit, tier, diff, layouts,
+        ),
         Commands::Rpkg { action } => match action {
             RpkgAction::Pack {
                 dylib,
@@ -556,10 +1145,42 @@ fn main() {
             } => cmd_rpkg_pack(dylib, haxe_dir, output, name),
             RpkgAction::Inspect { file } => cmd_rpkg_inspect(file),
         },
+        Commands::ApiDiff { old, new, format } => cmd_api_diff(old, new, format),
+        Commands::Query {
+            paths,
+            symbol,
+            implementations_of,
+            callers_of,
+            format,
+        } => cmd_query(paths, symbol, implementations_of, callers_of, format),
+        Commands::Graph {
+            paths,
+            call: _,
+            modules,
+            format,
+            output,
+            packages,
+            collapse_classes,
+        } => cmd_graph(paths, modules, format, output, packages, collapse_classes),
+        Commands::CrashReport { action } => match action {
+            CrashReportAction::Inspect { file } => cmd_crash_report_inspect(file),
+        },
+        Commands::Stats { action } => match action {
+            StatsAction::Show { last, file } => cmd_stats_show(last, file),
+            StatsAction::Clear { file } => cmd_stats_clear(file),
+        },
+        Commands::Lsp { stdio: _ } => rayzor_lsp::run_stdio(),
     };
 
     if let Err(e) = result {
-        eprintln!("Error: {}", e);
+        match diagnostics_format {
+            DiagnosticsFormat::Text => eprintln!("Error: {}", e),
+            DiagnosticsFormat::Json => eprintln!(
+                "{}",
+                diagnostics::json::JsonFormatter::new()
+                    .format_message(diagnostics::DiagnosticSeverity::Error, &e)
+            ),
+        }
         process::exit(1);
     }
 }
@@ -567,18 +1188,71 @@ fn main() {
 /// Helper function to compile Haxe source through the full pipeline to MIR
 /// Uses CompilationUnit for proper multi-file, stdlib-aware compilation
 /// Returns the primary MIR module (user code)
+#[allow(clippy::too_many_arguments)]
 fn compile_haxe_to_mir(
     source: &str,
     filename: &str,
     plugins: Vec<Box<dyn compiler::compiler_plugin::CompilerPlugin>>,
     extra_source_dirs: &[PathBuf],
+    debug_checks: bool,
+    sanitize_thread: bool,
+    stdlib: StdlibMode,
+    defines: &[String],
+    diagnostics_format: DiagnosticsFormat,
 ) -> Result<compiler::ir::IrModule, String> {
+    compile_haxe_to_mir_with_sources(
+        source,
+        filename,
+        plugins,
+        extra_source_dirs,
+        debug_checks,
+        sanitize_thread,
+        stdlib,
+        defines,
+        diagnostics_format,
+    )
+    .map(|(module, _)| module)
+}
+
+/// [`compile_haxe_to_mir`], also returning the user source files the
+/// compile read
+#[allow(clippy::too_many_arguments)]
+fn compile_haxe_to_mir_with_sources(
+    source: &str,
+    filename: &str,
+    plugins: Vec<Box<dyn compiler::compiler_plugin::CompilerPlugin>>,
+    extra_source_dirs: &[PathBuf],
+    debug_checks: bool,
+    sanitize_thread: bool,
+    stdlib: StdlibMode,
+    defines: &[String],
+    diagnostics_format: DiagnosticsFormat,
+) -> Result<(compiler::ir::IrModule, Vec<PathBuf>), String> {
     use compiler::compilation::{CompilationConfig, CompilationUnit};
+    use compiler::ir::module_init::LAZY_INIT_DEFINE;
+    use compiler::stdlib::embedded::STDLIB_SRC_DEFINE;
+    use parser::preprocessor::PreprocessorConfig;
 
     // Create compilation unit with stdlib support
+    let base = match stdlib {
+        StdlibMode::Full => CompilationConfig::default(),
+        StdlibMode::Lazy => CompilationConfig::fast(),
+        StdlibMode::None => CompilationConfig::bare_metal(),
+    };
+    // `#if debug` follows the build mode; `-D name[=value]` adds defines
+    let mut preprocessor = PreprocessorConfig::for_target(debug_checks);
+    preprocessor.define_all(defines.iter().map(String::as_str));
     let config = CompilationConfig {
-        load_stdlib: true, // Enable stdlib for full Haxe compatibility
-        ..Default::default()
+        debug_checks,
+        sanitize_thread,
+        // --trace-pos or RAYZOR_TRACE_POS=1, already applied to the runtime
+        trace_positions: rayzor_runtime::haxe_sys::trace_positions_enabled(),
+        stdlib_from_source: preprocessor.is_defined(STDLIB_SRC_DEFINE),
+        lazy_module_init: preprocessor.is_defined(LAZY_INIT_DEFINE),
+        preprocessor,
+        remote_cache: project_remote_cache(),
+        diagnostic_format: diagnostics_format.to_diagnostic_format(),
+        ..base
     };
 
     let mut unit = CompilationUnit::new(config);
@@ -600,9 +1274,8 @@ fn compile_haxe_to_mir(
     // Add the source file to the compilation unit
     unit.add_file(source, filename)?;
 
-    // Type-check pass — errors reported via diagnostics formatter
+    // Type-check pass — lower_to_tast has already printed the errors
     if let Err(errors) = unit.lower_to_tast() {
-        unit.print_compilation_errors(&errors);
         return Err(format!("Check failed with {} error(s)", errors.len()));
     }
 
@@ -616,15 +1289,25 @@ fn compile_haxe_to_mir(
     // Return the last module (user code). Import MIR modules are merged during
     // compilation (in compile_file_with_shared_state_ex's stdlib renumbering pass).
     let module = (**mir_modules.last().unwrap()).clone();
-    Ok(module)
+    Ok((module, unit.user_source_files()))
 }
 
 /// Loaded GPU plugin — keeps the dylib alive and provides both runtime symbols
 /// and a compiler plugin for method registration.
 struct GpuPlugin {
     _lib: libloading::Library,
+    path: PathBuf,
     symbols: Vec<(&'static str, *const u8)>,
     compiler_plugin: Option<compiler::compiler_plugin::NativePlugin>,
+    /// Build information, if the plugin exports `rayzor_gpu_plugin_info`
+    info: Option<GpuPluginInfo>,
+}
+
+/// Version, ABI and backends a GPU plugin was built with.
+struct GpuPluginInfo {
+    abi_version: u32,
+    version: String,
+    backends: Vec<String>,
 }
 
 /// Try to load the GPU compute plugin from the rayzor-gpu dynamic library.
@@ -641,8 +1324,11 @@ fn try_load_gpu_plugin() -> Option<GpuPlugin> {
         return None;
     };
 
-    // Try paths: next to executable, then current dir
+    // Try paths: $RAYZOR_PLUGIN_DIR (set by `rayzor install` launchers),
+    // next to executable, then current dir
     let search_paths = [
+        std::env::var_os(compiler::tools::install::PLUGIN_DIR_ENV_VAR)
+            .map(|dir| PathBuf::from(dir).join(lib_name)),
         std::env::current_exe()
             .ok()
             .and_then(|p| p.parent().map(|d| d.join(lib_name))),
@@ -699,48 +1385,283 @@ fn try_load_gpu_plugin() -> Option<GpuPlugin> {
                 }
             };
 
+            type InfoFn = unsafe extern "C" fn() -> *const rayzor_plugin::NativePluginInfo;
+            let info = unsafe {
+                lib.get::<InfoFn>(b"rayzor_gpu_plugin_info")
+                    .ok()
+                    .and_then(|info_fn| info_fn().as_ref())
+                    .map(|info| {
+                        // Only the ABI version is readable across ABI changes
+                        if info.abi_version != rayzor_plugin::NATIVE_PLUGIN_ABI_VERSION {
+                            return GpuPluginInfo {
+                                abi_version: info.abi_version,
+                                version: String::new(),
+                                backends: Vec::new(),
+                            };
+                        }
+                        GpuPluginInfo {
+                            abi_version: info.abi_version,
+                            version: info.version().to_string(),
+                            backends: info.features().into_iter().map(String::from).collect(),
+                        }
+                    })
+            };
+
             return Some(GpuPlugin {
                 _lib: lib,
+                path: path.clone(),
                 symbols,
                 compiler_plugin,
+                info,
             });
         }
     }
     None
 }
 
-fn run_bundle(file: &Path, verbose: bool, stats: bool, preset: Preset) -> Result<(), String> {
-    use compiler::codegen::tiered_backend::{TieredBackend, TieredConfig};
-    use compiler::ir::load_bundle;
+/// Names the runtime's and GPU plugin's symbols are registered under
+const RUNTIME_PLUGIN_NAME: &str = "runtime";
+const GPU_PLUGIN_NAME: &str = "rayzor_gpu";
 
-    if !file.exists() {
-        return Err(format!("Bundle not found: {}", file.display()));
+/// Collect the runtime, GPU and rpkg symbols into one table.
+///
+/// Symbols outside a plugin's namespace only produce a warning; a symbol
+/// exported by two plugins is an error naming both.
+fn plugin_symbol_table(
+    gpu: Option<&GpuPlugin>,
+    rpkgs: &[compiler::rpkg::install::RpkgPlugin],
+) -> Result<rayzor_plugin::symbols::SymbolTable, String> {
+    let mut table = rayzor_plugin::symbols::SymbolTable::new();
+    table.register_global(
+        RUNTIME_PLUGIN_NAME,
+        rayzor_runtime::get_plugin().runtime_symbols(),
+    );
+
+    let mut reports = Vec::new();
+    if let Some(gpu) = gpu {
+        let report = table.register(GPU_PLUGIN_NAME, gpu.symbols.iter().copied());
+        reports.push((GPU_PLUGIN_NAME.to_string(), report));
+    }
+    for rpkg in rpkgs {
+        let symbols = rpkg.runtime_symbols.iter().map(|(n, p)| (n.as_str(), *p));
+        let report = table.register(&rpkg.package_name, symbols);
+        reports.push((rpkg.package_name.clone(), report));
     }
 
-    let bundle = load_bundle(file).map_err(|e| format!("Failed to load bundle: {}", e))?;
+    let mut conflicts = Vec::new();
+    for (plugin, report) in reports {
+        if !report.unnamespaced.is_empty() {
+            eprintln!(
+                "warning: plugin '{}' exports {} symbol(s) outside its '{}_' namespace: {}",
+                plugin,
+                report.unnamespaced.len(),
+                rayzor_plugin::symbols::namespace_prefix(&plugin),
+                report.unnamespaced.join(", ")
+            );
+        }
+        conflicts.extend(report.conflicts.iter().map(|c| c.to_string()));
+    }
+    if !conflicts.is_empty() {
+        return Err(format!(
+            "conflicting plugin symbols:\n  {}",
+            conflicts.join("\n  ")
+        ));
+    }
+    Ok(table)
+}
 
-    let entry_func_id = bundle
-        .entry_function_id()
-        .ok_or("Bundle has no entry function")?;
+/// `[cache.remote]` of the enclosing project's rayzor.toml, if any
+fn project_remote_cache() -> Option<compiler::workspace::RemoteCacheConfig> {
+    use compiler::workspace::{self, RayzorManifest};
 
-    if verbose {
-        println!(
-            "  bundle   {} modules, entry: {}",
-            bundle.module_count(),
-            bundle.entry_function()
-        );
+    let root = workspace::find_project_root(&std::env::current_dir().ok()?)?;
+    let mut remote = match workspace::load_manifest(&root).ok()? {
+        RayzorManifest::SingleProject(project) => project.cache?.remote?,
+        RayzorManifest::Workspace(_) => return None,
+    };
+    // A shared directory given as a relative path is relative to the project
+    if !remote.url.contains("://") && Path::new(&remote.url).is_relative() {
+        remote.url = root.join(&remote.url).to_string_lossy().to_string();
     }
+    Some(remote)
+}
 
-    // Get runtime symbols
-    let plugin = rayzor_runtime::get_plugin();
-    let symbols = plugin.runtime_symbols();
-    let symbols_ref: Vec<(&str, *const u8)> = symbols.iter().map(|(n, p)| (*n, *p)).collect();
-
-    let mut config = TieredConfig::from_preset(preset.to_tier_preset());
-    config.verbosity = if verbose { 2 } else { 0 };
-    config.start_interpreted = false;
+/// Preset of `rayzor run` without --preset: Script for a `#!` script,
+/// Application otherwise
+fn default_run_preset(file: Option<&Path>) -> Preset {
+    let is_script = file.is_some_and(|file| {
+        let mut start = [0u8; 2];
+        std::fs::File::open(file)
+            .and_then(|mut f| std::io::Read::read_exact(&mut f, &mut start))
+            .is_ok_and(|()| &start == b"#!")
+    });
+    if is_script {
+        Preset::Script
+    } else {
+        Preset::Application
+    }
+}
 
-    let mut backend = TieredBackend::with_symbols(config, &symbols_ref)?;
+/// Tiered JIT configuration for `rayzor run`: the preset (or the one named
+/// in `[jit]`), starting at the JIT tier, then any `[jit]` settings from the
+/// enclosing project's rayzor.toml
+fn jit_config(
+    preset: Preset,
+    verbose: bool,
+) -> Result<compiler::codegen::tiered_backend::TieredConfig, String> {
+    use compiler::codegen::tiered_backend::TieredConfig;
+    use compiler::workspace::{self, RayzorManifest};
+
+    let cwd = std::env::current_dir().map_err(|e| format!("Failed to get cwd: {}", e))?;
+    let (jit, optimization) = match workspace::find_project_root(&cwd) {
+        Some(root) => match workspace::load_manifest(&root)? {
+            RayzorManifest::SingleProject(project) => (project.jit, project.optimization),
+            RayzorManifest::Workspace(_) => (None, None),
+        },
+        None => (None, None),
+    };
+
+    let base = match &jit {
+        Some(jit) => jit.preset()?.unwrap_or(preset.to_tier_preset()),
+        None => preset.to_tier_preset(),
+    };
+    let mut config = TieredConfig::from_preset(base);
+    config.verbosity = if verbose { 2 } else { 0 };
+    config.start_interpreted = false; // Start with JIT for immediate execution
+    if let Some(jit) = &jit {
+        jit.apply(&mut config)?;
+    }
+    if let Some(optimization) = &optimization {
+        config.pass_size_limits = optimization.size_limits()?;
+    }
+    Ok(config)
+}
+
+/// Pass size limits from `[optimization]` of the enclosing project's
+/// rayzor.toml, or the defaults
+#[cfg(feature = "llvm-backend")]
+fn project_pass_size_limits() -> Result<compiler::ir::optimization::PassSizeLimits, String> {
+    use compiler::workspace::{self, RayzorManifest};
+
+    let cwd = std::env::current_dir().map_err(|e| format!("Failed to get cwd: {}", e))?;
+    let optimization = match workspace::find_project_root(&cwd) {
+        Some(root) => match workspace::load_manifest(&root)? {
+            RayzorManifest::SingleProject(project) => project.optimization,
+            RayzorManifest::Workspace(_) => None,
+        },
+        None => None,
+    };
+    optimization.unwrap_or_default().size_limits()
+}
+
+fn run_bundle(
+    file: &Path,
+    entry: Option<&str>,
+    verbose: bool,
+    stats: bool,
+    preset: Preset,
+) -> Result<(), String> {
+    use compiler::codegen::module_loader::ModuleHost;
+    use compiler::codegen::tiered_backend::TieredBackend;
+    use compiler::ir::blade::{AbiCompatibility, BundleAbi};
+    use compiler::ir::{load_bundle, BladeError};
+    use compiler::tools::preblade::recompile_bundle;
+
+    if !file.exists() {
+        return Err(format!("Bundle not found: {}", file.display()));
+    }
+
+    let mut bundle = match load_bundle(file) {
+        Ok(bundle) => bundle,
+        Err(BladeError::UnsupportedVersion(version)) => {
+            return Err(format!(
+                "{} uses bundle format v{}, which this rayzor ({}) can't load. Rebuild it with `rayzor bundle`.",
+                file.display(),
+                version,
+                env!("CARGO_PKG_VERSION")
+            ))
+        }
+        Err(e) => return Err(format!("Failed to load bundle: {}", e)),
+    };
+
+    let built_with = bundle.build_info().abi.clone();
+    match built_with.check(&BundleAbi::current()) {
+        AbiCompatibility::Compatible => {}
+        AbiCompatibility::StdlibChanged if !bundle.sources().is_empty() => {
+            info!(
+                "  bundle   stdlib changed since {} was built; recompiling from {} embedded source(s)",
+                file.display(),
+                bundle.sources().len()
+            );
+            bundle = recompile_bundle(&bundle, verbose)?;
+        }
+        AbiCompatibility::StdlibChanged => {
+            return Err(format!(
+                "{} was built against a different stdlib (rayzor {}) and has no embedded sources to recompile. Rebuild it with `rayzor bundle`, or bundle with --embed-sources.",
+                file.display(),
+                built_with.compiler_version
+            ))
+        }
+        AbiCompatibility::Incompatible(reason) => {
+            return Err(format!(
+                "{} can't run on rayzor {}: {}. Rebuild it with `rayzor bundle`.",
+                file.display(),
+                env!("CARGO_PKG_VERSION"),
+                reason
+            ))
+        }
+    }
+
+    let entry_func_id = match entry {
+        Some(name) => match bundle.find_entry_point(name) {
+            Some(entry_point) => entry_point
+                .function_id
+                .ok_or_else(|| format!("Entry point '{}' has no function", name))?,
+            None => {
+                let names: Vec<&str> = bundle
+                    .entry_points()
+                    .iter()
+                    .map(|e| e.name.as_str())
+                    .collect();
+                return Err(format!(
+                    "{} has no entry point '{}' (available: {})",
+                    file.display(),
+                    name,
+                    if names.is_empty() {
+                        "none; add them with `rayzor bundle --entry`".to_string()
+                    } else {
+                        names.join(", ")
+                    }
+                ));
+            }
+        },
+        None => bundle
+            .entry_function_id()
+            .ok_or("Bundle has no entry function")?,
+    };
+    let entry_args = bundle
+        .modules()
+        .iter()
+        .find_map(|m| m.functions.get(&entry_func_id))
+        .map(entry_point_args)
+        .transpose()?
+        .unwrap_or_default();
+
+    info!(
+        "  bundle   {} modules, entry: {}",
+        bundle.module_count(),
+        bundle.entry_function()
+    );
+
+    // Get runtime symbols
+    let plugin = rayzor_runtime::get_plugin();
+    let symbols = plugin.runtime_symbols();
+    let symbols_ref: Vec<(&str, *const u8)> = symbols.iter().map(|(n, p)| (*n, *p)).collect();
+
+    let config = jit_config(preset, verbose)?;
+    let host = std::sync::Arc::new(ModuleHost::new(config.clone(), &symbols_ref));
+
+    let mut backend = TieredBackend::with_symbols(config, &symbols_ref)?;
 
     for module in bundle.modules().iter() {
         backend
@@ -748,6 +1669,11 @@ fn run_bundle(file: &Path, verbose: bool, stats: bool, preset: Preset) -> Result
             .map_err(|e| format!("Failed to compile module '{}': {}", module.name, e))?;
     }
 
+    // Bundles loaded with rayzor.Runtime.loadModule link against this one
+    backend.ensure_compiled()?;
+    host.register(&backend);
+    host.install();
+
     if stats {
         let backend_stats = backend.get_statistics();
         println!("  tier 0   {} functions", backend_stats.baseline_functions);
@@ -757,7 +1683,7 @@ fn run_bundle(file: &Path, verbose: bool, stats: bool, preset: Preset) -> Result
     }
 
     backend
-        .execute_function(entry_func_id, vec![])
+        .execute_function(entry_func_id, entry_args)
         .map_err(|e| format!("Execution failed: {}", e))?;
 
     backend.shutdown();
@@ -766,6 +1692,139 @@ fn run_bundle(file: &Path, verbose: bool, stats: bool, preset: Preset) -> Result
     Ok(())
 }
 
+/// Find the function `rayzor run` starts at: `entry` as a qualified name
+/// (`pack.Class.method`) or a bare function name, else `main`
+fn find_entry_function(
+    module: &compiler::ir::IrModule,
+    entry: Option<&str>,
+) -> Result<compiler::ir::IrFunctionId, String> {
+    let Some(entry) = entry else {
+        return module
+            .functions
+            .iter()
+            .find(|(_, f)| f.name == "main")
+            .map(|(id, _)| *id)
+            .ok_or_else(|| "No main function found".to_string());
+    };
+
+    if let Some((id, _)) = module
+        .functions
+        .iter()
+        .find(|(_, f)| f.qualified_name.as_deref() == Some(entry))
+    {
+        return Ok(*id);
+    }
+    let by_name: Vec<_> = module
+        .functions
+        .values()
+        .filter(|f| f.name == entry && !f.cfg.blocks.is_empty())
+        .collect();
+    match by_name.as_slice() {
+        [func] => Ok(func.id),
+        [] => Err(format!("Entry function '{}' not found", entry)),
+        _ => {
+            let names: Vec<&str> = by_name
+                .iter()
+                .filter_map(|f| f.qualified_name.as_deref())
+                .collect();
+            Err(format!(
+                "Entry function '{}' is ambiguous; use a qualified name: {}",
+                entry,
+                names.join(", ")
+            ))
+        }
+    }
+}
+
+/// Arguments of an entry point call: none, or the program arguments for
+/// `main(args:Array<String>)`
+fn entry_point_args(
+    func: &compiler::ir::IrFunction,
+) -> Result<Vec<compiler::codegen::InterpValue>, String> {
+    use compiler::codegen::InterpValue;
+    use compiler::ir::IrType;
+
+    let name = func.qualified_name.as_deref().unwrap_or(&func.name);
+    match func.signature.parameters.as_slice() {
+        [] => Ok(vec![]),
+        // Arrays of any element type lower to an opaque pointer
+        [param] if param.name != "this" && param.ty == IrType::Ptr(Box::new(IrType::Void)) => {
+            let args = rayzor_runtime::haxe_sys::haxe_sys_args();
+            Ok(vec![InterpValue::Ptr(args as usize)])
+        }
+        [param] if param.name != "this" => Err(format!(
+            "Entry function '{}' takes `{}` of type {}; an entry point takes none or `args:Array<String>`",
+            name, param.name, param.ty
+        )),
+        [_] => Err(format!(
+            "Entry function '{}' is an instance method; use a static function",
+            name
+        )),
+        params => Err(format!(
+            "Entry function '{}' takes {} parameters; an entry point takes none or `args:Array<String>`",
+            name,
+            params.len()
+        )),
+    }
+}
+
+/// The `__vtable_init__` and `__init__` functions of `module`, if present
+fn init_functions(
+    module: &compiler::ir::IrModule,
+) -> (
+    Option<compiler::ir::IrFunctionId>,
+    Option<compiler::ir::IrFunctionId>,
+) {
+    let find = |name: &str| {
+        module
+            .functions
+            .iter()
+            .find(|(_, f)| f.name == name)
+            .map(|(id, _)| *id)
+    };
+    (find("__vtable_init__"), find("__init__"))
+}
+
+/// Run a compiled program: its initializers, then its entry point
+fn execute_program(
+    backend: &mut compiler::codegen::tiered_backend::TieredBackend,
+    vtable_init: Option<compiler::ir::IrFunctionId>,
+    module_init: Option<compiler::ir::IrFunctionId>,
+    main: compiler::ir::IrFunctionId,
+    args: Vec<compiler::codegen::InterpValue>,
+) -> Result<(), String> {
+    if let Some(vtable_init_id) = vtable_init {
+        backend
+            .execute_function(vtable_init_id, vec![])
+            .map_err(|e| format!("vtable init failed: {}", e))?;
+    }
+    if let Some(init_id) = module_init {
+        backend
+            .execute_function(init_id, vec![])
+            .map_err(|e| format!("module init failed: {}", e))?;
+    }
+    backend
+        .execute_function(main, args)
+        .map_err(|e| format!("Execution failed: {}", e))?;
+    Ok(())
+}
+
+/// Block until one of `paths` is modified, created or removed
+fn wait_for_change(paths: &[PathBuf]) {
+    let stamps = || -> Vec<Option<std::time::SystemTime>> {
+        paths
+            .iter()
+            .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect()
+    };
+    let before = stamps();
+    while stamps() == before {
+        std::thread::sleep(std::time::Duration::from_millis(200));
+    }
+    // Let the editor finish writing
+    std::thread::sleep(std::time::Duration::from_millis(50));
+}
+
 #[allow(clippy::too_many_arguments)]
 fn run_file(
     file_arg: Option<PathBuf>,
@@ -774,13 +1833,56 @@ fn run_file(
     _tier: u8,
     _llvm: bool,
     preset: Preset,
-    _cache: bool,
-    _cache_dir: Option<PathBuf>,
+    cache: bool,
+    cache_dir: Option<PathBuf>,
     release: bool,
     compute: bool,
     rpkg_files: Vec<PathBuf>,
+    safety: Safety,
+    sanitize: Option<Sanitizer>,
+    gc: bool,
+    watch: bool,
+    stdlib: StdlibMode,
+    defines: &[String],
+    backend_name: &str,
+    crash_report: bool,
+    diagnostics_format: DiagnosticsFormat,
+    entry: Option<&str>,
+    program_args: Vec<String>,
 ) -> Result<(), String> {
-    use compiler::codegen::tiered_backend::{TieredBackend, TieredConfig};
+    use compiler::codegen::module_loader::ModuleHost;
+    use compiler::codegen::tiered_backend::TieredBackend;
+
+    if sanitize == Some(Sanitizer::Thread) && !rayzor_runtime::sanitizer::is_available() {
+        return Err(
+            "--sanitize thread needs a rayzor built with ThreadSanitizer \
+             (RUSTFLAGS=-Zsanitizer=thread, nightly -Zbuild-std, \
+             --features tsan)"
+                .to_string(),
+        );
+    }
+
+    // Installed before compiling so the JIT keeps MIR and disassembly. The
+    // address sanitizer names Haxe functions through the same table.
+    let sanitize_address = sanitize == Some(Sanitizer::Address);
+    if crash_report
+        || sanitize_address
+        || std::env::var_os("RAYZOR_CRASH_REPORT").is_some_and(|v| v != "0")
+    {
+        rayzor_runtime::crash::install(
+            rayzor_runtime::crash::CRASH_DIR,
+            concat!("rayzor ", env!("CARGO_PKG_VERSION")),
+        )
+        .map_err(|e| format!("Failed to install crash handler: {}", e))?;
+    }
+
+    // Report stack overflows with the Haxe function at fault; installed
+    // after the crash reporter, which still gets the faults it should report
+    rayzor_runtime::crash::enable_function_table();
+    if let Err(e) = rayzor_runtime::stack_guard::install() {
+        eprintln!("warning: failed to install stack overflow handler: {}", e);
+    }
+    let _stack = rayzor_runtime::stack_guard::ThreadStack::enter();
 
     // Resolve file: from arg or rayzor.toml
     let file = match file_arg {
@@ -796,9 +1898,15 @@ fn run_file(
         preset
     );
 
+    // Sys.args() returns the arguments after `--`, not rayzor's own
+    rayzor_runtime::haxe_sys::set_program_args(program_args);
+
     // Handle precompiled .rzb bundles
     if file.extension().is_some_and(|ext| ext == "rzb") {
-        return run_bundle(&file, verbose, stats, preset);
+        if watch {
+            return Err("--watch needs a Haxe source file, not a bundle".to_string());
+        }
+        return run_bundle(&file, entry, verbose, stats, preset);
     }
 
     #[cfg(not(feature = "llvm-backend"))]
@@ -815,16 +1923,26 @@ fn run_file(
     let source =
         std::fs::read_to_string(&file).map_err(|e| format!("Failed to read file: {}", e))?;
 
+    // Dependencies a script declares in its `//!` header
+    let script = compiler::tools::script::ScriptHeader::parse(
+        &source,
+        file.parent().unwrap_or(Path::new(".")),
+    )
+    .map_err(|e| format!("{}: {}", file.display(), e))?;
+    let mut rpkg_files = rpkg_files;
+    rpkg_files.extend(script.rpkgs);
+
     // Always try to load the GPU plugin — silently skip if the dylib isn't found.
     // The --compute flag upgrades a missing dylib from silent skip to a warning.
-    let mut gpu_plugin = match try_load_gpu_plugin() {
+    // Rebuilds under --watch can't take compiler plugins, so only --compute
+    // loads it then.
+    let gpu_wanted = compute || !watch;
+    let mut gpu_plugin = match gpu_wanted.then(try_load_gpu_plugin).flatten() {
         Some(gpu) => {
-            if verbose {
-                eprintln!(
-                    "  gpu      loaded {} symbols from rayzor-gpu plugin",
-                    gpu.symbols.len()
-                );
-            }
+            info!(
+                "  gpu      loaded {} symbols from rayzor-gpu plugin",
+                gpu.symbols.len()
+            );
             Some(gpu)
         }
         None => {
@@ -849,14 +1967,12 @@ fn run_file(
     for rpkg_path in &rpkg_files {
         match compiler::rpkg::install::RpkgPlugin::load(rpkg_path) {
             Ok(rpkg) => {
-                if verbose {
-                    eprintln!(
-                        "  rpkg     loaded '{}' ({} methods, {} hx files)",
-                        rpkg.package_name,
-                        rpkg.runtime_symbols.len(),
-                        rpkg.haxe_sources.len(),
-                    );
-                }
+                info!(
+                    "  rpkg     loaded '{}' ({} methods, {} hx files)",
+                    rpkg.package_name,
+                    rpkg.runtime_symbols.len(),
+                    rpkg.haxe_sources.len(),
+                );
                 // Write bundled .hx files to temp dir for import resolution
                 if !rpkg.haxe_sources.is_empty() {
                     let tmp_dir = std::env::temp_dir().join(format!(
@@ -891,70 +2007,175 @@ fn run_file(
             compiler_plugins.push(Box::new(cp));
         }
     }
+    if watch && !compiler_plugins.is_empty() {
+        return Err(
+            "--watch can't rebuild programs using compiler plugins (GPU compute or .rpkg packages)"
+                .to_string(),
+        );
+    }
 
-    // Compile source file to MIR (with plugins registered)
-    let mut mir_module = compile_haxe_to_mir(
-        &source,
-        file.to_str().unwrap_or("unknown"),
-        compiler_plugins,
-        &rpkg_source_dirs,
-    )?;
-
-    // Run O0 pass manager to expand Haxe `inline` functions and apply SRA
-    if std::env::var("RAYZOR_RAW_MIR").is_err() {
-        use compiler::ir::optimization::{OptimizationLevel, PassManager};
-        let mut pass_manager = PassManager::for_level(OptimizationLevel::O0);
-        let _ = pass_manager.run(&mut mir_module);
+    // Resolve --backend before compiling: plugins may contribute backends
+    let mut backends = compiler::codegen::BackendRegistry::new();
+    for plugin in &compiler_plugins {
+        backends.add_plugin(plugin.as_ref());
+    }
+    if watch && backend_name != "tiered" {
+        return Err("--watch needs the tiered backend".to_string());
+    }
+    if backend_name != "tiered" && !backends.contains(backend_name) {
+        return Err(format!(
+            "Unknown backend '{}' (available: tiered, {})",
+            backend_name,
+            backends.names().join(", ")
+        ));
     }
 
+    // Compile source file to MIR (with plugins registered). Unlike the
+    // temporary rpkg source dirs, script class paths are never removed.
+    let source_dirs: Vec<PathBuf> = rpkg_source_dirs
+        .iter()
+        .chain(&script.class_paths)
+        .cloned()
+        .collect();
+    let mut usage = compiler::tools::usage_stats::StatsRecord::new("run", &file);
+
+    // With --cache, a run whose sources, flags and compiler are unchanged
+    // executes the module the last run saved, without compiling anything
+    let raw_mir = std::env::var("RAYZOR_RAW_MIR").is_ok();
+    let run_cache = cache.then(|| {
+        let cache_dir = cache_dir.unwrap_or_else(|| {
+            compiler::compilation::CompilationConfig::get_profile_cache_dir(profile)
+        });
+        let flags = format!(
+            "{:?}",
+            (
+                release,
+                safety,
+                sanitize,
+                stdlib,
+                defines,
+                &rpkg_files,
+                gpu_plugin.as_ref().map(|gpu| &gpu.path),
+                raw_mir,
+                rayzor_runtime::haxe_sys::trace_positions_enabled(),
+            )
+        );
+        compiler::tools::run_cache::RunCache::new(&cache_dir, &file, flags)
+    });
+
+    // Compile a version of the source and run the MIR passes `rayzor run`
+    // applies; --watch rebuilds with it too
+    let build = |source: &str,
+                 plugins: Vec<Box<dyn compiler::compiler_plugin::CompilerPlugin>>|
+     -> Result<(compiler::ir::IrModule, Vec<PathBuf>), String> {
+        let (mut mir_module, sources) = compile_haxe_to_mir_with_sources(
+            source,
+            file.to_str().unwrap_or("unknown"),
+            plugins,
+            &source_dirs,
+            !release,
+            sanitize == Some(Sanitizer::Thread),
+            stdlib,
+            defines,
+            diagnostics_format,
+        )?;
+
+        // Check @:requires/@:ensures contracts; release builds leave them
+        // out unless run with --safety checked
+        if !release || safety == Safety::Checked {
+            use compiler::ir::contracts::ContractCheckPass;
+            use compiler::ir::optimization::OptimizationPass;
+            let _ = ContractCheckPass::new().run_on_module(&mut mir_module);
+        }
+
+        // Run O0 pass manager to expand Haxe `inline` functions and apply SRA
+        if !raw_mir {
+            use compiler::ir::optimization::{OptimizationLevel, PassManager};
+            let mut pass_manager = PassManager::for_level(OptimizationLevel::O0);
+            let _ = pass_manager.run(&mut mir_module);
+        }
+
+        // Guard allocation results so OOM goes through rayzor_set_oom_handler
+        if safety == Safety::Checked {
+            use compiler::ir::alloc_null_check::AllocNullCheckPass;
+            use compiler::ir::optimization::OptimizationPass;
+            let _ = AllocNullCheckPass::new().run_on_module(&mut mir_module);
+        }
+        Ok((mir_module, sources))
+    };
+
+    // Files --watch watches; a cache hit knows only the entry file
+    let mut watched = vec![file.clone()];
+    let mir_module = match run_cache.as_ref().and_then(|run_cache| run_cache.load()) {
+        Some(module) => {
+            info!("  cache    hit, skipped compilation");
+            usage.cache_hits = 1;
+            module
+        }
+        None => {
+            let compile_start = std::time::Instant::now();
+            let (mir_module, sources) = build(&source, compiler_plugins)?;
+            usage.compile_ms = compile_start.elapsed().as_millis() as u64;
+
+            if let Some(run_cache) = &run_cache {
+                info!("  cache    miss, compiled");
+                usage.cache_misses = 1;
+                // Sources extracted from an rpkg live in a per-process temp
+                // dir; the rpkg itself is hashed instead
+                let sources: Vec<PathBuf> = sources
+                    .iter()
+                    .filter(|path| !rpkg_source_dirs.iter().any(|dir| path.starts_with(dir)))
+                    .chain(rpkg_files.iter())
+                    .cloned()
+                    .collect();
+                if let Err(e) = run_cache.save(&mir_module, &sources) {
+                    eprintln!("warning: {}", e);
+                }
+            }
+            if !sources.is_empty() {
+                watched = sources;
+            }
+            mir_module
+        }
+    };
+
     let total_functions = mir_module.functions.len();
-    if verbose {
-        println!("  parse    {} ({} decls)", file.display(), total_functions);
-    }
+    info!("  parse    {} ({} decls)", file.display(), total_functions);
 
     if total_functions == 0 {
         return Err("No functions found to execute".to_string());
     }
 
-    // Find main function before consuming mir_module
-    let main_func_id = mir_module
-        .functions
-        .iter()
-        .find(|(_, f)| f.name == "main")
-        .map(|(id, _)| *id)
-        .ok_or("No main function found")?;
+    // Find the entry function before consuming mir_module
+    let main_func_id = find_entry_function(&mir_module, entry)?;
+    let entry_args = entry_point_args(&mir_module.functions[&main_func_id])?;
 
     // Find __vtable_init__ and __init__ functions (if present)
-    let vtable_init_func_id = mir_module
-        .functions
-        .iter()
-        .find(|(_, f)| f.name == "__vtable_init__")
-        .map(|(id, _)| *id);
-    let module_init_func_id = mir_module
-        .functions
-        .iter()
-        .find(|(_, f)| f.name == "__init__")
-        .map(|(id, _)| *id);
-
-    // Get runtime symbols
-    let plugin = rayzor_runtime::get_plugin();
-    let mut symbols = plugin.runtime_symbols();
-
-    // Merge GPU runtime symbols for JIT linking
-    if let Some(ref gpu) = gpu_plugin {
-        symbols.extend_from_slice(&gpu.symbols);
+    let (vtable_init_func_id, module_init_func_id) = init_functions(&mir_module);
+
+    // Runtime symbols, then GPU and rpkg symbols for JIT linking. A plugin
+    // may not shadow a symbol another plugin already provides.
+    let symbol_table = plugin_symbol_table(gpu_plugin.as_ref(), &loaded_rpkgs)?;
+    let mut symbols = symbol_table.flat();
+
+    // Route generated code's malloc/realloc/free through the debug allocator
+    if sanitize_address {
+        let debug_symbols = rayzor_runtime::debug_alloc::symbols();
+        symbols.retain(|(name, _)| !debug_symbols.iter().any(|(n, _)| n == name));
+        symbols.extend(debug_symbols);
+        rayzor_runtime::debug_alloc::set_enabled(true);
     }
 
-    // Merge rpkg runtime symbols for JIT linking
-    let rpkg_owned_symbols: Vec<(String, *const u8)> = loaded_rpkgs
-        .iter()
-        .flat_map(|r| r.runtime_symbols.clone())
-        .collect();
-    for (name, ptr) in &rpkg_owned_symbols {
-        // Leak the string to get 'static lifetime (same pattern as GPU plugin)
-        let name: &'static str = Box::leak(name.clone().into_boxed_str());
-        symbols.push((name, *ptr));
+    // Or through the garbage collector, enabled once compilation is done
+    if gc {
+        let gc_symbols = rayzor_runtime::gc::symbols();
+        symbols.retain(|(name, _)| !gc_symbols.iter().any(|(n, _)| n == name));
+        symbols.extend(gc_symbols);
     }
+    let start_runtime = || {
+        rayzor_runtime::gc::set_enabled(gc);
+        rayzor_runtime::rayzor_runtime_init();
+    };
 
     // Keep dylibs alive until backend is done
     let _gpu_plugin = gpu_plugin;
@@ -962,23 +2183,53 @@ fn run_file(
 
     let symbols_ref: Vec<(&str, *const u8)> = symbols.iter().map(|(n, p)| (*n, *p)).collect();
 
-    // Set up tiered JIT backend using the selected preset
-    let mut config = TieredConfig::from_preset(preset.to_tier_preset());
-    config.verbosity = if verbose { 2 } else { 0 };
-    config.start_interpreted = false; // Start with JIT for immediate execution
+    // A single backend selected with --backend replaces the tiered JIT
+    if backend_name != "tiered" {
+        if entry.is_some() || !entry_args.is_empty() {
+            return Err(format!(
+                "--backend {} only runs `static function main()`; use the tiered backend for --entry or main(args)",
+                backend_name
+            ));
+        }
+        let mut backend = backends.create(backend_name, &symbols_ref)?;
+        info!("  backend  {}", backend.name());
+        start_runtime();
+        compiler::codegen::backend::run_module(backend.as_mut(), &mir_module)
+            .map_err(|e| format!("Execution failed: {}", e))?;
+        rayzor_runtime::rayzor_runtime_shutdown();
+        rayzor_runtime::debug_alloc::check_heap();
+        compiler::tools::usage_stats::record(&usage);
+
+        for dir in &rpkg_source_dirs {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+        println!("✓ Complete");
+        return Ok(());
+    }
+
+    // Set up tiered JIT backend using the selected preset and rayzor.toml
+    let config = jit_config(preset, verbose)?;
+    let host = std::sync::Arc::new(ModuleHost::new(config.clone(), &symbols_ref));
 
     let mut backend = TieredBackend::with_symbols(config, &symbols_ref)?;
 
     // Compile module with tiered JIT
+    let jit_start = std::time::Instant::now();
     backend.compile_module(mir_module)?;
+    backend.ensure_compiled()?;
+    usage.jit_ms = Some(jit_start.elapsed().as_millis() as u64);
 
-    if verbose {
+    // Bundles loaded with rayzor.Runtime.loadModule link against the program
+    host.register(&backend);
+    host.install();
+
+    if log::log_enabled!(log::Level::Info) {
         let backend_stats = backend.get_statistics();
         let compiled = backend_stats.baseline_functions
             + backend_stats.standard_functions
             + backend_stats.optimized_functions
             + backend_stats.llvm_functions;
-        println!(
+        info!(
             "  jit      {} functions compiled (preset: {:?})",
             compiled, preset
         );
@@ -993,24 +2244,70 @@ fn run_file(
         println!("  tier 3   {} functions", backend_stats.llvm_functions);
     }
 
-    // Execute init functions before main
-    if let Some(vtable_init_id) = vtable_init_func_id {
-        backend
-            .execute_function(vtable_init_id, vec![])
-            .map_err(|e| format!("vtable init failed: {}", e))?;
-    }
-    if let Some(init_id) = module_init_func_id {
-        backend
-            .execute_function(init_id, vec![])
-            .map_err(|e| format!("module init failed: {}", e))?;
+    // Execute init functions, then main; under --watch a failed run waits
+    // for the fix like any other
+    start_runtime();
+    let result = execute_program(
+        &mut backend,
+        vtable_init_func_id,
+        module_init_func_id,
+        main_func_id,
+        entry_args,
+    );
+    match result {
+        Err(e) if watch => eprintln!("error: {}", e),
+        result => result?,
     }
 
-    // Execute main function
-    backend
-        .execute_function(main_func_id, vec![])
-        .map_err(|e| format!("Execution failed: {}", e))?;
+    // Tier histogram after any promotions the run triggered
+    let backend_stats = backend.get_statistics();
+    usage.tiers = [
+        backend_stats.interpreted_functions as u64,
+        backend_stats.baseline_functions as u64,
+        backend_stats.standard_functions as u64,
+        backend_stats.optimized_functions as u64,
+        backend_stats.llvm_functions as u64,
+    ];
+    compiler::tools::usage_stats::record(&usage);
+
+    // --watch: run every edit again with only the functions it affected
+    // re-JITed; the rest keeps its code and the runtime keeps its state
+    while watch {
+        println!("👀 Watching {} file(s) for changes...", watched.len());
+        wait_for_change(&watched);
+        let rebuilt = std::fs::read_to_string(&file)
+            .map_err(|e| format!("Failed to read file: {}", e))
+            .and_then(|source| build(&source, Vec::new()));
+        let (module, sources) = match rebuilt {
+            Ok(rebuilt) => rebuilt,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                continue;
+            }
+        };
+        if !sources.is_empty() {
+            watched = sources;
+        }
+        let (vtable_init, module_init) = init_functions(&module);
+        let result = find_entry_function(&module, entry)
+            .and_then(|main| Ok((main, entry_point_args(&module.functions[&main])?)))
+            .and_then(|(main, args)| {
+                let rejitted = backend.recompile_changed(module)?;
+                println!(
+                    "🔁 {} changed, re-JITed {} function(s)",
+                    file.display(),
+                    rejitted.len()
+                );
+                execute_program(&mut backend, vtable_init, module_init, main, args)
+            });
+        if let Err(e) = result {
+            eprintln!("error: {}", e);
+        }
+    }
 
     backend.shutdown();
+    rayzor_runtime::rayzor_runtime_shutdown();
+    rayzor_runtime::debug_alloc::check_heap();
 
     // Clean up temp dirs from rpkg haxe sources
     for dir in &rpkg_source_dirs {
@@ -1053,7 +2350,10 @@ fn jit_compile(
 }
 
 fn check_file(file: PathBuf, show_types: bool, format: OutputFormat) -> Result<(), String> {
-    println!("✓ Checking {}...", file.display());
+    // With --format json, stdout holds only the JSON summary
+    if !matches!(format, OutputFormat::Json) {
+        println!("✓ Checking {}...", file.display());
+    }
 
     if !file.exists() {
         return Err(format!("File not found: {}", file.display()));
@@ -1063,9 +2363,7 @@ fn check_file(file: PathBuf, show_types: bool, format: OutputFormat) -> Result<(
         std::fs::read_to_string(&file).map_err(|e| format!("Failed to read file: {}", e))?;
 
     // Parse the file
-    use parser::haxe_parser::parse_haxe_file;
-    let ast = parse_haxe_file(file.to_str().unwrap_or("unknown"), &source, false)
-        .map_err(|e| format!("Parse error: {}", e))?;
+    let ast = parse_source(&file, &source, format.diagnostics())?;
 
     match format {
         OutputFormat::Text => {
@@ -1097,11 +2395,153 @@ fn check_file(file: PathBuf, show_types: bool, format: OutputFormat) -> Result<(
     if show_types {
         println!("\nType information:");
         println!("  (Full type checking not yet implemented)");
+        print_imported_symbols(&ast);
     }
 
     Ok(())
 }
 
+/// Parse `source`. With `DiagnosticsFormat::Json`, syntax errors are printed
+/// to stderr as JSON lines with their locations.
+fn parse_source(
+    file: &Path,
+    source: &str,
+    format: DiagnosticsFormat,
+) -> Result<parser::HaxeFile, String> {
+    use parser::haxe_parser::{parse_haxe_file, parse_haxe_file_with_enhanced_errors};
+    use parser::preprocessor::{preprocess, PreprocessorConfig};
+
+    let name = file.to_str().unwrap_or("unknown");
+    let error = match parse_haxe_file(name, source, false) {
+        Ok(ast) => return Ok(ast),
+        Err(e) => format!("Parse error: {}", e),
+    };
+    if format == DiagnosticsFormat::Json {
+        // The recovering parser reports every syntax error with a span
+        let preprocessed = preprocess(source, &PreprocessorConfig::default());
+        if let Err((errors, source_map)) =
+            parse_haxe_file_with_enhanced_errors(&preprocessed, name, false)
+        {
+            if !errors.is_empty() {
+                eprint!(
+                    "{}",
+                    diagnostics::json::JsonFormatter::new()
+                        .format_diagnostics(&errors, &source_map)
+                );
+                return Err(format!("Parse failed with {} error(s)", errors.len()));
+            }
+        }
+    }
+    Err(error)
+}
+
+/// Type check `file` and print the ownership report of its variables
+fn print_ownership_report(file: PathBuf) -> Result<(), String> {
+    use compiler::pipeline::{HaxeCompilationPipeline, PipelineConfig};
+    use compiler::tools::ownership_report::OwnershipReport;
+
+    let source =
+        std::fs::read_to_string(&file).map_err(|e| format!("Failed to read file: {}", e))?;
+    let mut pipeline = HaxeCompilationPipeline::with_config(PipelineConfig {
+        emit_ownership_report: true,
+        ..Default::default()
+    });
+    let result = pipeline.compile_file(&file, &source);
+    for error in &result.errors {
+        eprintln!(
+            "{}:{}:{}: {}",
+            file.display(),
+            error.location.line,
+            error.location.column,
+            error.message
+        );
+    }
+    if result.ownership_reports.is_empty() {
+        return Err(format!(
+            "No ownership information for {} ({} error(s))",
+            file.display(),
+            result.errors.len()
+        ));
+    }
+    println!("{}", OwnershipReport::to_json(&result.ownership_reports));
+    Ok(())
+}
+
+/// Print the signatures of imported classes from the stdlib and package
+/// symbol manifests (`rayzor preblade [--rpkg ...]`), without compiling or
+/// loading any native library
+fn print_imported_symbols(ast: &parser::HaxeFile) {
+    use compiler::ir::blade::{load_symbol_manifest, BladeMethodInfo, BladeSymbolManifest};
+
+    let blade_dir = PathBuf::from(".rayzor/blade/stdlib");
+    let packages = compiler::tools::preblade::load_package_manifests(&blade_dir);
+    let embedded = compiler::stdlib::embedded::embedded_stdlib().map(|e| e.symbols());
+    let on_disk = match embedded {
+        Some(_) => None,
+        None => load_symbol_manifest(blade_dir.join("stdlib.bsym")).ok(),
+    };
+    let manifests: Vec<&BladeSymbolManifest> = packages
+        .iter()
+        .chain(embedded)
+        .chain(on_disk.as_ref())
+        .collect();
+
+    let signature = |m: &BladeMethodInfo| {
+        let params: Vec<String> = m
+            .params
+            .iter()
+            .map(|p| format!("{}:{}", p.name, p.param_type))
+            .collect();
+        let prefix = if m.is_static { "static " } else { "" };
+        let doc = m
+            .doc
+            .as_deref()
+            .and_then(|d| d.lines().next())
+            .map(|d| format!("  // {}", d))
+            .unwrap_or_default();
+        format!(
+            "{}function {}({}):{}{}",
+            prefix,
+            m.name,
+            params.join(", "),
+            m.return_type,
+            doc
+        )
+    };
+
+    for import in &ast.imports {
+        if !matches!(
+            import.mode,
+            parser::ImportMode::Normal | parser::ImportMode::Alias(_)
+        ) {
+            continue;
+        }
+        let qualified = import.path.join(".");
+        let class = manifests
+            .iter()
+            .flat_map(|m| &m.modules)
+            .flat_map(|m| &m.types.classes)
+            .find(|c| {
+                c.package.len() + 1 == import.path.len()
+                    && c.package[..] == import.path[..c.package.len()]
+                    && Some(&c.name) == import.path.last()
+            });
+        let Some(class) = class else {
+            println!("  {}: not in the symbol index", qualified);
+            continue;
+        };
+        println!("  class {}", qualified);
+        if let Some(doc) = class.doc.as_deref().and_then(|d| d.lines().next()) {
+            println!("    // {}", doc);
+        }
+        for method in class.static_methods.iter().chain(&class.methods) {
+            if method.is_public {
+                println!("    {}", signature(method));
+            }
+        }
+    }
+}
+
 fn build_hxml(
     file_arg: Option<PathBuf>,
     verbose: bool,
@@ -1129,6 +2569,127 @@ fn build_hxml(
     Err("No rayzor.toml or .hxml build file found.\nRun `rayzor init` to create a project, or specify a .hxml file.".to_string())
 }
 
+/// `rayzor build --emit-plan`: resolve the build graph the same way
+/// `build_hxml` picks its input, and write it as JSON
+fn emit_build_plan(
+    file_arg: Option<PathBuf>,
+    output_override: Option<PathBuf>,
+    plan_path: &Path,
+) -> Result<(), String> {
+    use compiler::compilation::CompilationConfig;
+    use compiler::workspace::build_plan::BuildPlan;
+
+    let hxml_arg = file_arg
+        .as_ref()
+        .filter(|f| f.extension().map(|e| e == "hxml").unwrap_or(false));
+    let cwd = std::env::current_dir().map_err(|e| format!("Failed to get cwd: {}", e))?;
+    let mut targets = if let Some(file) = hxml_arg {
+        vec![hxml_target_plan(file)?]
+    } else if let Some(root) = compiler::workspace::find_project_root(&cwd) {
+        manifest_target_plans(&root)?
+    } else if let Some(file) = &file_arg {
+        vec![hxml_target_plan(file)?]
+    } else {
+        return Err("No rayzor.toml or .hxml build file found.\nRun `rayzor init` to create a project, or specify a .hxml file.".to_string());
+    };
+
+    // Like a build, the override only applies to a single target
+    if let (Some(out), [target]) = (output_override, targets.as_mut_slice()) {
+        target.outputs = vec![out];
+    }
+
+    let stdlib_paths = CompilationConfig::discover_stdlib_paths();
+    for target in &mut targets {
+        target.resolve_sources(&stdlib_paths)?;
+    }
+
+    let plan = BuildPlan {
+        targets,
+        ..Default::default()
+    };
+    std::fs::write(plan_path, plan.to_json())
+        .map_err(|e| format!("Failed to write {}: {}", plan_path.display(), e))?;
+
+    let sources: usize = plan.targets.iter().map(|t| t.sources.len()).sum();
+    println!(
+        "✓ Build plan written to {} ({} target(s), {} source file(s))",
+        plan_path.display(),
+        plan.targets.len(),
+        sources
+    );
+    Ok(())
+}
+
+fn manifest_target_plans(
+    root: &Path,
+) -> Result<Vec<compiler::workspace::build_plan::TargetPlan>, String> {
+    use compiler::workspace::{self, build_plan::TargetPlan, RayzorManifest};
+
+    match workspace::load_manifest(root)? {
+        RayzorManifest::SingleProject(pm) => {
+            if let Some(hxml_path) = &pm.hxml {
+                return Ok(vec![hxml_target_plan(&root.join(hxml_path))?]);
+            }
+            let project = workspace::Project {
+                root: root.to_path_buf(),
+                manifest: pm,
+            };
+            let target = TargetPlan::from_project(&project)
+                .ok_or("No entry point in rayzor.toml. Set [project] entry = \"src/Main.hx\"")?;
+            if !target.entry.exists() {
+                return Err(format!("Entry file not found: {}", target.entry.display()));
+            }
+            Ok(vec![target])
+        }
+        RayzorManifest::Workspace(wm) => {
+            let mut targets = Vec::new();
+            for member in &wm.members {
+                targets.extend(manifest_target_plans(&root.join(member))?);
+            }
+            Ok(targets)
+        }
+    }
+}
+
+fn hxml_target_plan(file: &Path) -> Result<compiler::workspace::build_plan::TargetPlan, String> {
+    use compiler::hxml::HxmlConfig;
+    use compiler::workspace::build_plan::TargetPlan;
+
+    let config = HxmlConfig::from_file(&file.to_path_buf())?;
+    config.validate()?;
+    let main_class = config
+        .main_class
+        .as_ref()
+        .ok_or("No main class specified in HXML file")?;
+    let relative = format!("{}.hx", main_class.replace('.', "/"));
+    let entry = config
+        .class_paths
+        .iter()
+        .map(|cp| cp.join(&relative))
+        .find(|candidate| candidate.exists())
+        .ok_or_else(|| format!("Main class file not found in class paths: {}", main_class))?;
+
+    Ok(TargetPlan {
+        name: Some(main_class.clone()),
+        root: file.parent().map(Path::to_path_buf).unwrap_or_default(),
+        entry,
+        target: Some(format!("{:?}", config.mode).to_lowercase()),
+        class_paths: config.class_paths.clone(),
+        defines: config
+            .defines
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.clone(),
+                    value.clone().unwrap_or_else(|| "1".to_string()),
+                )
+            })
+            .collect(),
+        outputs: config.output.iter().cloned().collect(),
+        ..Default::default()
+    })
+}
+
 fn build_from_manifest(
     root: &Path,
     verbose: bool,
@@ -1177,11 +2738,30 @@ fn build_from_manifest(
 
             let output = output_override.or_else(|| project.output_path());
 
-            // Compile via the standard pipeline
+            // Compile via the standard pipeline, with the `[build]` defines
             let source = std::fs::read_to_string(&entry)
                 .map_err(|e| format!("Failed to read {}: {}", entry.display(), e))?;
-            let mir_module =
-                compile_haxe_to_mir(&source, entry.to_str().unwrap_or("unknown"), vec![], &[])?;
+            let defines: Vec<String> = workspace::build_plan::manifest_defines(
+                project
+                    .manifest
+                    .build
+                    .as_ref()
+                    .and_then(|b| b.defines.as_ref()),
+            )
+            .into_iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+            let mir_module = compile_haxe_to_mir(
+                &source,
+                entry.to_str().unwrap_or("unknown"),
+                vec![],
+                &[],
+                false,
+                false,
+                StdlibMode::Full,
+                &defines,
+                DiagnosticsFormat::Text,
+            )?;
 
             println!("  Compiled {} functions", mir_module.functions.len());
 
@@ -1288,6 +2868,7 @@ fn build_from_hxml(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn compile_file(
     file: PathBuf,
     stage: CompileStage,
@@ -1296,9 +2877,9 @@ fn compile_file(
     cache: bool,
     cache_dir: Option<PathBuf>,
     release: bool,
+    format: DiagnosticsFormat,
 ) -> Result<(), String> {
     use compiler::compilation::{CompilationConfig, CompilationUnit};
-    use parser::haxe_parser::parse_haxe_file;
 
     let profile = if release { "release" } else { "debug" };
     let target = CompilationConfig::get_target_triple();
@@ -1319,8 +2900,7 @@ fn compile_file(
         std::fs::read_to_string(&file).map_err(|e| format!("Failed to read file: {}", e))?;
 
     // Step 1: Parse
-    let ast = parse_haxe_file(file.to_str().unwrap_or("unknown"), &source, false)
-        .map_err(|e| format!("Parse error: {}", e))?;
+    let ast = parse_source(&file, &source, format)?;
 
     println!(
         "  parse    {} decls, {} imports",
@@ -1357,26 +2937,52 @@ fn compile_file(
         load_stdlib: false,
         enable_cache: cache,
         cache_dir: cache_dir_resolved,
+        remote_cache: project_remote_cache(),
         ..Default::default()
     };
 
     let unit = CompilationUnit::new(config);
 
     // For stages beyond AST, compile using our helper with caching support
+    let mut usage = compiler::tools::usage_stats::StatsRecord::new("compile", &file);
+    let compile_start = std::time::Instant::now();
     let mir_module = if cache {
         if let Some(cached) = unit.try_load_cached(&file) {
             println!("  cache    hit (loaded from BLADE cache)");
+            usage.cache_hits = 1;
             cached
         } else {
             println!("  cache    miss, compiling...");
-            let module =
-                compile_haxe_to_mir(&source, file.to_str().unwrap_or("unknown"), vec![], &[])?;
+            usage.cache_misses = 1;
+            let module = compile_haxe_to_mir(
+                &source,
+                file.to_str().unwrap_or("unknown"),
+                vec![],
+                &[],
+                false,
+                false,
+                StdlibMode::Full,
+                &[],
+                format,
+            )?;
             unit.save_to_cache(&file, &module)?;
             module
         }
     } else {
-        compile_haxe_to_mir(&source, file.to_str().unwrap_or("unknown"), vec![], &[])?
+        compile_haxe_to_mir(
+            &source,
+            file.to_str().unwrap_or("unknown"),
+            vec![],
+            &[],
+            false,
+            false,
+            StdlibMode::Full,
+            &[],
+            format,
+        )?
     };
+    usage.compile_ms = compile_start.elapsed().as_millis() as u64;
+    compiler::tools::usage_stats::record(&usage);
 
     println!("  mir      {} functions", mir_module.functions.len());
 
@@ -1452,28 +3058,362 @@ fn show_info(features: bool, tiers: bool) {
         println!();
     }
 
-    if tiers || !features {
-        println!("Tiered JIT System:");
-        println!("  Tier 0 (Baseline)  - Cranelift 'none'          - ~3ms compile, 1.0x speed");
-        println!("  Tier 1 (Standard)  - Cranelift 'speed'         - ~10ms compile, 1.5-3x speed");
-        println!("  Tier 2 (Optimized) - Cranelift 'speed_and_size' - ~30ms compile, 3-5x speed");
-
-        #[cfg(feature = "llvm-backend")]
-        println!("  Tier 3 (Maximum)   - LLVM aggressive          - ~500ms compile, 5-20x speed");
+    if tiers || !features {
+        println!("Tiered JIT System:");
+        println!("  Tier 0 (Baseline)  - Cranelift 'none'          - ~3ms compile, 1.0x speed");
+        println!("  Tier 1 (Standard)  - Cranelift 'speed'         - ~10ms compile, 1.5-3x speed");
+        println!("  Tier 2 (Optimized) - Cranelift 'speed_and_size' - ~30ms compile, 3-5x speed");
+
+        #[cfg(feature = "llvm-backend")]
+        println!("  Tier 3 (Maximum)   - LLVM aggressive          - ~500ms compile, 5-20x speed");
+
+        #[cfg(not(feature = "llvm-backend"))]
+        println!("  Tier 3 (Maximum)   - LLVM (not available)");
+
+        println!("\n  Functions automatically promote based on execution count:");
+        println!("    • 100 calls   → Tier 1");
+        println!("    • 1,000 calls → Tier 2");
+        println!("    • 5,000 calls → Tier 3");
+        println!();
+    }
+
+    println!("Examples:");
+    println!("  cargo run --example test_full_pipeline_tiered");
+    println!("  cargo run --example test_tiered_with_loop --features llvm-backend");
+}
+
+/// `rayzor info --plugins`: each plugin with the runtime symbols it owns.
+fn show_plugins(rpkg_files: &[PathBuf]) -> Result<(), String> {
+    let gpu = try_load_gpu_plugin();
+    let rpkgs = rpkg_files
+        .iter()
+        .map(|path| compiler::rpkg::install::RpkgPlugin::load(path))
+        .collect::<Result<Vec<_>, _>>()?;
+    let table = plugin_symbol_table(gpu.as_ref(), &rpkgs)?;
+
+    println!("Plugins ({} symbols total):", table.len());
+    for (plugin, mut symbols) in table.plugins() {
+        println!("\n  {} ({} symbols)", plugin, symbols.len());
+        symbols.sort_unstable();
+        for symbol in symbols {
+            if plugin == RUNTIME_PLUGIN_NAME
+                || rayzor_plugin::symbols::is_namespaced(plugin, symbol)
+            {
+                println!("    {}", symbol);
+            } else {
+                println!("    {}  (outside plugin namespace)", symbol);
+            }
+        }
+    }
+    if gpu.is_none() {
+        println!("\n  (rayzor-gpu plugin not found)");
+    }
+    Ok(())
+}
+
+/// One finding of `rayzor doctor`
+#[derive(Debug, Clone, PartialEq)]
+enum Check {
+    Ok(String),
+    /// Something missing or broken, and how to fix it
+    Problem {
+        message: String,
+        fix: String,
+    },
+    /// Context that is neither
+    Note(String),
+}
+
+/// What `rayzor doctor` found, by section
+#[derive(Debug, Default)]
+struct Doctor {
+    sections: Vec<(String, Vec<Check>)>,
+}
+
+impl Doctor {
+    fn section(&mut self, title: &str) {
+        self.sections.push((title.to_string(), Vec::new()));
+    }
+
+    fn push(&mut self, check: Check) {
+        match self.sections.last_mut() {
+            Some((_, checks)) => checks.push(check),
+            None => self.sections.push((String::new(), vec![check])),
+        }
+    }
+
+    fn ok(&mut self, message: impl std::fmt::Display) {
+        self.push(Check::Ok(message.to_string()));
+    }
+
+    fn note(&mut self, message: impl std::fmt::Display) {
+        self.push(Check::Note(message.to_string()));
+    }
+
+    fn problem(&mut self, message: impl std::fmt::Display, fix: impl std::fmt::Display) {
+        self.push(Check::Problem {
+            message: message.to_string(),
+            fix: fix.to_string(),
+        });
+    }
+
+    fn checks(&self) -> impl Iterator<Item = &Check> {
+        self.sections.iter().flat_map(|(_, checks)| checks)
+    }
+
+    fn problems(&self) -> usize {
+        self.checks()
+            .filter(|check| matches!(check, Check::Problem { .. }))
+            .count()
+    }
+
+    fn print(&self) {
+        for (title, checks) in &self.sections {
+            println!("\n{}", title);
+            for check in checks {
+                match check {
+                    Check::Ok(message) => println!("  ✓ {}", message),
+                    Check::Problem { message, fix } => {
+                        println!("  ✗ {}", message);
+                        println!("    fix: {}", fix);
+                    }
+                    Check::Note(message) => println!("  ({})", message),
+                }
+            }
+        }
+        println!();
+        match self.problems() {
+            0 => println!("No problems found."),
+            n => println!("{} problem(s) found.", n),
+        }
+    }
+}
+
+/// `rayzor doctor`: report what this installation can do, and how to fix
+/// what's missing.
+fn cmd_doctor(rpkg_files: Vec<PathBuf>, cache_dir: Option<PathBuf>) -> Result<(), String> {
+    println!("Rayzor doctor (rayzor {})", env!("CARGO_PKG_VERSION"));
+    let gpu = try_load_gpu_plugin();
+    let aot = compiler::codegen::aot_compiler::AotCompiler::default();
+    let doctor = diagnose(
+        gpu.as_ref(),
+        aot.find_linker(),
+        aot.find_runtime(),
+        cache_dir,
+        &rpkg_files,
+    );
+    doctor.print();
+    Ok(())
+}
+
+/// Run every `rayzor doctor` check
+fn diagnose(
+    gpu: Option<&GpuPlugin>,
+    linker: Result<String, String>,
+    runtime: Result<PathBuf, String>,
+    cache_dir: Option<PathBuf>,
+    rpkg_files: &[PathBuf],
+) -> Doctor {
+    let mut doctor = Doctor::default();
+    doctor.section("Backends");
+    doctor.ok("Cranelift JIT (tiers 0-2)");
+    #[cfg(feature = "llvm-backend")]
+    doctor.ok("LLVM (tier 3 JIT, AOT)");
+    #[cfg(not(feature = "llvm-backend"))]
+    doctor.problem(
+        "LLVM backend not enabled: no tier 3 JIT and no `rayzor aot`",
+        "rebuild rayzor with `cargo build --release --features llvm-backend` (needs LLVM 18)",
+    );
+
+    doctor_gpu(&mut doctor, gpu);
+    doctor_aot(&mut doctor, linker, runtime);
+    doctor_cache(&mut doctor, cache_dir);
+    doctor_packages(&mut doctor, gpu, rpkg_files);
+    doctor
+}
+
+fn doctor_gpu(doctor: &mut Doctor, gpu: Option<&GpuPlugin>) {
+    doctor.section("GPU compute");
+    let Some(gpu) = gpu else {
+        doctor.problem(
+            "rayzor-gpu plugin not found in $RAYZOR_PLUGIN_DIR, next to the rayzor binary \
+             or in the current directory",
+            "build it with `cargo build --release -p rayzor-gpu --features webgpu-backend` \
+             (or `metal-backend` on macOS) and copy the library next to rayzor",
+        );
+        return;
+    };
+
+    let Some(info) = &gpu.info else {
+        doctor.problem(
+            format!(
+                "rayzor-gpu at {} doesn't report its version or ABI",
+                gpu.path.display()
+            ),
+            "rebuild rayzor-gpu from the same checkout as rayzor",
+        );
+        return;
+    };
+    if info.abi_version != rayzor_plugin::NATIVE_PLUGIN_ABI_VERSION {
+        doctor.problem(
+            format!(
+                "rayzor-gpu at {} uses plugin ABI {}, this rayzor expects ABI {}",
+                gpu.path.display(),
+                info.abi_version,
+                rayzor_plugin::NATIVE_PLUGIN_ABI_VERSION
+            ),
+            "rebuild rayzor-gpu from the same checkout as rayzor",
+        );
+        return;
+    }
+    doctor.ok(format!(
+        "rayzor-gpu {} at {} (ABI {}, {} symbols)",
+        info.version,
+        gpu.path.display(),
+        info.abi_version,
+        gpu.symbols.len()
+    ));
+
+    if info.backends.is_empty() {
+        doctor.problem(
+            "rayzor-gpu was built without a compute backend",
+            "rebuild it with `--features webgpu-backend` or `--features metal-backend`",
+        );
+        return;
+    }
+    doctor.ok(format!("backends: {}", info.backends.join(", ")));
+
+    let is_available = gpu
+        .symbols
+        .iter()
+        .find(|(name, _)| *name == "rayzor_gpu_compute_is_available")
+        .map(|&(_, ptr)| unsafe { std::mem::transmute::<*const u8, extern "C" fn() -> i8>(ptr) });
+    match is_available.map(|f| f() != 0) {
+        Some(true) => doctor.ok("GPU device available"),
+        Some(false) => doctor.problem(
+            format!(
+                "no device available to the {} backend(s)",
+                info.backends.join("/")
+            ),
+            "install a Metal, Vulkan or DX12 driver (on headless Linux, Mesa's lavapipe works)",
+        ),
+        None => doctor.problem(
+            "rayzor-gpu doesn't export rayzor_gpu_compute_is_available",
+            "rebuild rayzor-gpu from the same checkout as rayzor",
+        ),
+    }
+}
+
+/// Check the linker and runtime library `rayzor aot` found
+fn doctor_aot(
+    doctor: &mut Doctor,
+    linker: Result<String, String>,
+    runtime: Result<PathBuf, String>,
+) {
+    doctor.section("AOT toolchain");
+    match linker {
+        Ok(linker) => doctor.ok(format!("linker: {}", linker)),
+        Err(_) => doctor.problem(
+            "no linker found (tried clang, gcc, cc)",
+            "install clang or gcc, or pass `--linker <path>` to `rayzor aot`",
+        ),
+    }
+    match runtime {
+        Ok(path) => doctor.ok(format!("runtime library: {}", path.display())),
+        Err(_) => doctor.problem(
+            "librayzor_runtime.a not found",
+            "build it with `cargo build --release -p rayzor-runtime`, or point \
+             RAYZOR_RUNTIME_DIR / `rayzor aot --runtime-dir` at it",
+        ),
+    }
+}
+
+fn doctor_cache(doctor: &mut Doctor, cache_dir: Option<PathBuf>) {
+    use compiler::compilation::{CompilationConfig, CompilationUnit};
+
+    doctor.section("BLADE cache");
+    let mut config = CompilationConfig::default();
+    if let Some(dir) = cache_dir {
+        config.cache_dir = Some(dir);
+    }
+    let unit = CompilationUnit::new(config);
+    let dir = unit.config.get_cache_dir();
+
+    if !dir.is_dir() {
+        doctor.problem(
+            format!("cache directory {} does not exist", dir.display()),
+            "create it, or pass `--cache-dir` with a writable directory",
+        );
+        return;
+    }
+    let probe = dir.join(format!(".doctor-{}", std::process::id()));
+    if let Err(e) = std::fs::write(&probe, b"") {
+        doctor.problem(
+            format!("cache directory {} is not writable: {}", dir.display(), e),
+            "fix its permissions, or pass `--cache-dir` with a writable directory",
+        );
+        return;
+    }
+    let _ = std::fs::remove_file(&probe);
 
-        #[cfg(not(feature = "llvm-backend"))]
-        println!("  Tier 3 (Maximum)   - LLVM (not available)");
+    let stats = unit.get_cache_stats();
+    let report = unit.verify_cache(false);
+    if report.bad.is_empty() {
+        doctor.ok(format!(
+            "{} ({} modules, {:.2} MB)",
+            dir.display(),
+            stats.cached_modules,
+            stats.total_size_mb()
+        ));
+    } else {
+        doctor.problem(
+            format!(
+                "{} of {} cached modules in {} are corrupt",
+                report.bad.len(),
+                report.checked,
+                dir.display()
+            ),
+            "run `rayzor cache verify` to remove them",
+        );
+    }
+}
 
-        println!("\n  Functions automatically promote based on execution count:");
-        println!("    • 100 calls   → Tier 1");
-        println!("    • 1,000 calls → Tier 2");
-        println!("    • 5,000 calls → Tier 3");
-        println!();
+fn doctor_packages(doctor: &mut Doctor, gpu: Option<&GpuPlugin>, rpkg_files: &[PathBuf]) {
+    doctor.section("Packages");
+    let mut rpkgs = Vec::new();
+    if rpkg_files.is_empty() {
+        doctor.note("no --rpkg given");
+    }
+    for path in rpkg_files {
+        match compiler::rpkg::install::RpkgPlugin::load(path) {
+            Ok(rpkg) => {
+                doctor.ok(format!(
+                    "{}: package '{}' ({} symbols, {} hx files)",
+                    path.display(),
+                    rpkg.package_name,
+                    rpkg.runtime_symbols.len(),
+                    rpkg.haxe_sources.len()
+                ));
+                rpkgs.push(rpkg);
+            }
+            Err(e) => doctor.problem(
+                e,
+                "check the path, or re-pack the package with `rayzor rpkg pack`",
+            ),
+        }
     }
 
-    println!("Examples:");
-    println!("  cargo run --example test_full_pipeline_tiered");
-    println!("  cargo run --example test_tiered_with_loop --features llvm-backend");
+    match plugin_symbol_table(gpu, &rpkgs) {
+        Ok(table) => doctor.ok(format!(
+            "{} runtime symbols from {} plugin(s), no conflicts",
+            table.len(),
+            table.plugins().count()
+        )),
+        Err(e) => doctor.problem(
+            e,
+            "prefix each package's exports with its name (see `rayzor info --plugins`)",
+        ),
+    }
 }
 
 fn cache_stats(cache_dir: Option<PathBuf>) -> Result<(), String> {
@@ -1528,6 +3468,36 @@ fn cache_clear(cache_dir: Option<PathBuf>) -> Result<(), String> {
     Ok(())
 }
 
+fn cache_verify(cache_dir: Option<PathBuf>, dry_run: bool) -> Result<(), String> {
+    use compiler::compilation::{CompilationConfig, CompilationUnit};
+
+    let mut config = CompilationConfig::default();
+    if let Some(dir) = cache_dir {
+        config.cache_dir = Some(dir);
+    }
+
+    let unit = CompilationUnit::new(config);
+    println!("🔍 Verifying BLADE cache...");
+    println!("Cache directory: {:?}", unit.config.get_cache_dir());
+
+    let report = unit.verify_cache(!dry_run);
+    for entry in &report.bad {
+        let action = if entry.pruned { "removed" } else { "kept" };
+        println!("  ✗ {} ({}): {}", entry.path.display(), action, entry.error);
+    }
+
+    println!(
+        "Checked {} module(s): {} ok, {} bad",
+        report.checked,
+        report.checked - report.bad.len(),
+        report.bad.len()
+    );
+    if dry_run && !report.bad.is_empty() {
+        println!("Run without --dry-run to remove bad entries.");
+    }
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 fn cmd_bundle(
     files: Vec<PathBuf>,
@@ -1537,11 +3507,28 @@ fn cmd_bundle(
     no_compress: bool,
     cache: bool,
     cache_dir: Option<PathBuf>,
+    embed_sources: bool,
+    obfuscate: bool,
+    key_env: Option<String>,
+    entry_points: Vec<String>,
     verbose: bool,
 ) -> Result<(), String> {
+    use compiler::ir::bundle_crypt::BundleKey;
     use compiler::ir::optimization::OptimizationLevel;
     use compiler::tools::preblade::{create_bundle, BundleConfig};
 
+    // Read the key up front, so a missing key fails before compiling
+    let encryption_key = key_env
+        .map(|var| {
+            BundleKey::from_env_var(&var)?.ok_or_else(|| {
+                format!(
+                    "--encrypt needs a key in {}: 64 hex digits, e.g. from `openssl rand -hex 32`",
+                    var
+                )
+            })
+        })
+        .transpose()?;
+
     let opt = match opt_level {
         0 => Some(OptimizationLevel::O0),
         1 => Some(OptimizationLevel::O1),
@@ -1563,6 +3550,10 @@ fn cmd_bundle(
         compress: !no_compress,
         enable_cache: cache,
         cache_dir,
+        embed_sources,
+        obfuscate,
+        encryption_key,
+        entry_points,
     };
 
     match create_bundle(&config) {
@@ -1585,13 +3576,38 @@ fn cmd_aot(
     opt_level: u8,
     strip: bool,
     strip_symbols: bool,
+    gc_sections: bool,
+    pie: bool,
+    relro: bool,
+    stack_protector: bool,
+    nx: bool,
+    static_link: bool,
     runtime_dir: Option<PathBuf>,
     linker: Option<String>,
     sysroot: Option<PathBuf>,
     _cache: bool,
     _cache_dir: Option<PathBuf>,
+    check_symbols: bool,
     verbose: bool,
 ) -> Result<(), String> {
+    // Works without the LLVM backend, so CI can run it on any build
+    if check_symbols {
+        use compiler::tools::aot_build::{check_aot_symbols, parse_opt_level};
+
+        let source_files: Vec<String> = files
+            .iter()
+            .map(|f| f.to_string_lossy().to_string())
+            .collect();
+        return check_aot_symbols(
+            &source_files,
+            target,
+            parse_opt_level(&opt_level.to_string()),
+            strip,
+            runtime_dir,
+            verbose,
+        );
+    }
+
     #[cfg(not(feature = "llvm-backend"))]
     {
         let _ = (
@@ -1602,6 +3618,12 @@ fn cmd_aot(
             opt_level,
             strip,
             strip_symbols,
+            gc_sections,
+            pie,
+            relro,
+            stack_protector,
+            nx,
+            static_link,
             &runtime_dir,
             &linker,
             &sysroot,
@@ -1625,9 +3647,10 @@ fn cmd_aot(
             "llvm-ir" => OutputFormat::LlvmIr,
             "llvm-bc" => OutputFormat::LlvmBitcode,
             "asm" => OutputFormat::Assembly,
+            "size-report" => OutputFormat::SizeReport,
             other => {
                 return Err(format!(
-                    "Unknown emit format: {}. Use: exe, obj, llvm-ir, llvm-bc, asm",
+                    "Unknown emit format: {}. Use: exe, obj, llvm-ir, llvm-bc, asm, size-report",
                     other
                 ))
             }
@@ -1653,12 +3676,19 @@ fn cmd_aot(
             opt_level: opt,
             strip,
             strip_symbols,
+            gc_sections,
+            pie,
+            relro,
+            stack_protector,
+            nx,
+            static_link,
             verbose,
             linker,
             runtime_dir,
             sysroot,
             enable_cache: _cache,
             cache_dir: _cache_dir,
+            pass_size_limits: project_pass_size_limits()?,
         };
 
         run_aot(config)
@@ -1669,6 +3699,7 @@ fn cmd_preblade(
     _files: Vec<PathBuf>,
     out: Option<PathBuf>,
     list: bool,
+    rpkg_files: Vec<PathBuf>,
     cache_dir: Option<PathBuf>,
     verbose: bool,
 ) -> Result<(), String> {
@@ -1690,6 +3721,7 @@ fn cmd_preblade(
         list_only: list,
         verbose,
         cache_dir,
+        rpkg_files,
     };
 
     match extract_stdlib_symbols(&config) {
@@ -1747,18 +3779,36 @@ fn cmd_init(name: Option<String>, workspace: bool) -> Result<(), String> {
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn cmd_dump(
     file: PathBuf,
     output: Option<PathBuf>,
     opt_level: u8,
     function_filter: Option<String>,
     cfg_only: bool,
+    dot: bool,
+    emit: DumpEmit,
+    tier: u8,
+    diff: Option<String>,
+    layouts: bool,
 ) -> Result<(), String> {
     use compiler::compilation::{CompilationConfig, CompilationUnit};
     use compiler::ir::dump;
     use compiler::ir::optimization::{OptimizationLevel, PassManager};
 
-    println!("🔍 Dumping MIR for {} (O{})...", file.display(), opt_level);
+    if emit == DumpEmit::Native {
+        println!(
+            "🔍 Dumping machine code for {} (tier {})...",
+            file.display(),
+            tier
+        );
+    } else if let Some(ref diff) = diff {
+        println!("🔍 Diffing MIR for {} ({})...", file.display(), diff);
+    } else if layouts {
+        println!("🔍 Dumping class layouts for {}...", file.display());
+    } else {
+        println!("🔍 Dumping MIR for {} (O{})...", file.display(), opt_level);
+    }
 
     if !file.exists() {
         return Err(format!("File not found: {}", file.display()));
@@ -1795,9 +3845,79 @@ fn cmd_dump(
         return Err("No MIR modules generated".to_string());
     }
 
+    if emit == DumpEmit::Native {
+        let text = dump_native(&mir_modules, &source, tier, function_filter)?;
+        if let Some(output_path) = output {
+            std::fs::write(&output_path, &text)
+                .map_err(|e| format!("Failed to write output: {}", e))?;
+            println!("✓ Machine code dumped to {}", output_path.display());
+        } else {
+            println!();
+            print!("{}", text);
+        }
+        return Ok(());
+    }
+
+    if layouts {
+        let text = dump::dump_layouts(mir_modules.last().unwrap());
+        if let Some(output_path) = output {
+            std::fs::write(&output_path, &text)
+                .map_err(|e| format!("Failed to write output: {}", e))?;
+            println!("✓ Layouts dumped to {}", output_path.display());
+        } else {
+            println!();
+            print!("{}", text);
+        }
+        return Ok(());
+    }
+
     // Get the user module (last one, after stdlib) and clone for optimization
     let mut module = (**mir_modules.last().unwrap()).clone();
 
+    if let Some(diff) = diff {
+        let (old_label, new_label) = diff
+            .split_once(',')
+            .map(|(a, b)| (a.trim(), b.trim()))
+            .ok_or_else(|| format!("--diff expects two levels like O0,O2, got '{}'", diff))?;
+        let run_pipeline = |label: &str| -> Result<compiler::ir::IrModule, String> {
+            let mut copy = module.clone();
+            let level = match label {
+                "raw" => return Ok(copy),
+                "O0" => OptimizationLevel::O0,
+                "O1" => OptimizationLevel::O1,
+                "O2" => OptimizationLevel::O2,
+                "O3" => OptimizationLevel::O3,
+                _ => {
+                    return Err(format!(
+                        "unknown level '{}' in --diff (expected raw, O0, O1, O2 or O3)",
+                        label
+                    ))
+                }
+            };
+            let _ = PassManager::for_level(level).run(&mut copy);
+            Ok(copy)
+        };
+        let old = run_pipeline(old_label)?;
+        let new = run_pipeline(new_label)?;
+        let text = compiler::ir::mir_diff::diff_modules(
+            &old,
+            &new,
+            old_label,
+            new_label,
+            function_filter.as_deref(),
+        );
+
+        if let Some(output_path) = output {
+            std::fs::write(&output_path, &text)
+                .map_err(|e| format!("Failed to write output: {}", e))?;
+            println!("✓ MIR diff written to {}", output_path.display());
+        } else {
+            println!();
+            print!("{}", text);
+        }
+        return Ok(());
+    }
+
     // Apply optimization if requested
     let opt = match opt_level {
         0 => OptimizationLevel::O0,
@@ -1899,6 +4019,71 @@ fn cmd_dump(
         let _ = pass_manager.run(&mut module);
     }
 
+    if dot {
+        let mut functions: Vec<_> = module
+            .functions
+            .values()
+            .filter(|func| {
+                function_filter
+                    .as_deref()
+                    .is_none_or(|filter| func.name.contains(filter))
+            })
+            .collect();
+        functions.sort_by_key(|func| func.id.0);
+        if functions.is_empty() {
+            return Err(match function_filter {
+                Some(filter) => format!("Function '{}' not found in module", filter),
+                None => "No functions in module".to_string(),
+            });
+        }
+
+        match output {
+            Some(dir) if dir.is_dir() => {
+                let mut used = std::collections::HashSet::new();
+                for func in &functions {
+                    // Methods of different classes share names like `new`
+                    let name = func.qualified_name.as_deref().unwrap_or(&func.name);
+                    let mut stem: String = name
+                        .chars()
+                        .map(|c| {
+                            if c.is_alphanumeric() || c == '.' {
+                                c
+                            } else {
+                                '_'
+                            }
+                        })
+                        .collect();
+                    if !used.insert(stem.clone()) {
+                        stem = format!("{}_{}", stem, func.id.0);
+                    }
+                    let path = dir.join(format!("{}.dot", stem));
+                    std::fs::write(&path, dump::dump_cfg_dot(func))
+                        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                }
+                println!(
+                    "✓ {} CFG graph(s) written to {}",
+                    functions.len(),
+                    dir.display()
+                );
+            }
+            output => {
+                let text: String = functions
+                    .iter()
+                    .map(|func| dump::dump_cfg_dot(func))
+                    .collect();
+                if let Some(output_path) = output {
+                    std::fs::write(&output_path, &text)
+                        .map_err(|e| format!("Failed to write output: {}", e))?;
+                    println!("✓ CFG graph(s) written to {}", output_path.display());
+                } else {
+                    println!();
+                    print!("{}", text);
+                }
+            }
+        }
+        return Ok(());
+    }
+
     // Generate MIR dump
     let mir_text = if cfg_only {
         // Dump only CFG structure
@@ -1951,6 +4136,73 @@ fn cmd_dump(
     Ok(())
 }
 
+/// JIT-compile every module at `tier` like the tiered backend does and
+/// list the machine code of the user module's functions
+fn dump_native(
+    mir_modules: &[std::sync::Arc<compiler::ir::IrModule>],
+    source: &str,
+    tier: u8,
+    function_filter: Option<String>,
+) -> Result<String, String> {
+    use compiler::codegen::native_listing::{format_listing, ListingFilter};
+    use compiler::codegen::{CraneliftBackend, OptimizationTier};
+    use compiler::ir::optimization::PassManager;
+
+    let tier = OptimizationTier::from_level(tier)
+        .ok_or_else(|| format!("Invalid tier {} (expected 0-3)", tier))?;
+    let user_module = mir_modules.last().unwrap();
+
+    let plugin = rayzor_runtime::get_plugin();
+    let symbols = plugin.runtime_symbols();
+    let symbols_ref: Vec<(&str, *const u8)> = symbols.iter().map(|(n, p)| (*n, *p)).collect();
+    let mut backend =
+        CraneliftBackend::with_symbols_and_opt(tier.cranelift_opt_level(), &symbols_ref)?;
+    backend.set_listing_filter(ListingFilter {
+        module: user_module.name.clone(),
+        function: function_filter.clone(),
+    });
+
+    let modules: Vec<compiler::ir::IrModule> = mir_modules
+        .iter()
+        .map(|m| {
+            let mut module = (**m).clone();
+            let _ = PassManager::for_level(tier.mir_opt_level()).run(&mut module);
+            module
+        })
+        .collect();
+    for module in &modules {
+        backend.compile_module_without_finalize(module)?;
+    }
+    backend.finalize()?;
+
+    let listings = backend.take_listings();
+    if listings.is_empty() {
+        return Err(match function_filter {
+            Some(filter) => format!("Function '{}' not found in module", filter),
+            None => "No functions compiled".to_string(),
+        });
+    }
+
+    let user_module = modules.last().unwrap();
+    let mut text = String::new();
+    for listing in &listings {
+        let Some(function) = user_module.functions.get(&listing.function) else {
+            continue;
+        };
+        text.push_str(&format!(
+            "; {} ({}:{}) - {}, {} bytes\n",
+            function.qualified_name.as_deref().unwrap_or(&function.name),
+            user_module.source_file,
+            function.source_location.line,
+            tier.description(),
+            listing.code.len()
+        ));
+        text.push_str(&format_listing(listing, function, Some(source)));
+        text.push('\n');
+    }
+    Ok(text)
+}
+
 // ---------------------------------------------------------------------------
 // rpkg commands
 // ---------------------------------------------------------------------------
@@ -1995,6 +4247,163 @@ fn cmd_rpkg_pack(
     Ok(())
 }
 
+fn cmd_crash_report_inspect(file: Option<PathBuf>) -> Result<(), String> {
+    use rayzor_runtime::crash::{latest_report, CrashReport, CRASH_DIR};
+
+    let file = match file {
+        Some(file) => file,
+        None => latest_report(Path::new(CRASH_DIR))
+            .ok_or_else(|| format!("no crash reports in {}", CRASH_DIR))?,
+    };
+    let text = std::fs::read_to_string(&file)
+        .map_err(|e| format!("failed to read {}: {}", file.display(), e))?;
+    let report = CrashReport::parse(&text).map_err(|e| format!("{}: {}", file.display(), e))?;
+
+    println!("Crash report: {}", file.display());
+    print!("{}", report.pretty());
+    Ok(())
+}
+
+/// `--file`, or the enclosing project's stats file
+fn stats_file_arg(file: Option<PathBuf>) -> Result<PathBuf, String> {
+    match file {
+        Some(file) => Ok(file),
+        None => compiler::tools::usage_stats::stats_file()
+            .map(|(path, _)| path)
+            .ok_or_else(|| "cannot determine the current directory".to_string()),
+    }
+}
+
+fn cmd_stats_show(last: Option<usize>, file: Option<PathBuf>) -> Result<(), String> {
+    use compiler::tools::usage_stats;
+
+    let explicit = file.is_some();
+    let file = stats_file_arg(file)?;
+    if !file.exists() {
+        if explicit {
+            return Err(format!("no statistics file at {}", file.display()));
+        }
+        println!("No statistics recorded yet.");
+        println!(
+            "Enable them with `[stats] enabled = true` in rayzor.toml or {}=1; \
+             they are stored locally in {} and never uploaded.",
+            usage_stats::STATS_ENV_VAR,
+            usage_stats::DEFAULT_STATS_FILE
+        );
+        return Ok(());
+    }
+
+    let mut records = usage_stats::load(&file)?;
+    if let Some(last) = last {
+        records.drain(..records.len().saturating_sub(last));
+    }
+    println!("Usage statistics: {}", file.display());
+    print!("{}", usage_stats::render(&records));
+    Ok(())
+}
+
+fn cmd_stats_clear(file: Option<PathBuf>) -> Result<(), String> {
+    let file = stats_file_arg(file)?;
+    match std::fs::remove_file(&file) {
+        Ok(()) => println!("Removed {}", file.display()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("No statistics at {}", file.display())
+        }
+        Err(e) => return Err(format!("failed to remove {}: {}", file.display(), e)),
+    }
+    Ok(())
+}
+
+fn cmd_api_diff(old: PathBuf, new: PathBuf, format: OutputFormat) -> Result<(), String> {
+    use compiler::tools::api_diff::{ApiDiff, ApiSurface};
+
+    let diff = ApiDiff::between(&ApiSurface::load(&old)?, &ApiSurface::load(&new)?);
+    match format {
+        OutputFormat::Json => println!("{}", diff.to_json()),
+        OutputFormat::Text | OutputFormat::Pretty => {
+            println!("API changes from {} to {}\n", old.display(), new.display());
+            print!("{}", diff.render());
+        }
+    }
+    Ok(())
+}
+
+fn cmd_query(
+    paths: Vec<PathBuf>,
+    symbol: Option<String>,
+    implementations_of: Option<String>,
+    callers_of: Option<String>,
+    format: OutputFormat,
+) -> Result<(), String> {
+    use compiler::tools::query::{self, ProjectIndex};
+
+    let index = ProjectIndex::load(&paths)?;
+    let matches = if let Some(pattern) = symbol {
+        index.symbols(&pattern)
+    } else if let Some(pattern) = implementations_of {
+        index.implementations_of(&pattern)?
+    } else if let Some(pattern) = callers_of {
+        index.callers_of(&pattern)?
+    } else {
+        unreachable!("clap requires one query")
+    };
+
+    match format {
+        OutputFormat::Json => println!("{}", query::to_json(&matches)),
+        OutputFormat::Text | OutputFormat::Pretty => {
+            if matches.is_empty() {
+                eprintln!("No matches");
+            }
+            for found in &matches {
+                println!("{}", found.render());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn cmd_graph(
+    paths: Vec<PathBuf>,
+    modules: bool,
+    format: GraphFormat,
+    output: Option<PathBuf>,
+    packages: Vec<String>,
+    collapse_classes: bool,
+) -> Result<(), String> {
+    use compiler::tools::graph::{Graph, GraphOptions};
+    use compiler::tools::query::ProjectIndex;
+
+    let index = ProjectIndex::load(&paths)?;
+    let options = GraphOptions {
+        packages,
+        collapse_classes,
+    };
+    let graph = if modules {
+        Graph::modules(&index, &options)
+    } else {
+        Graph::calls(&index, &options)
+    };
+    let text = match format {
+        GraphFormat::Dot => graph.to_dot(),
+        GraphFormat::Json => graph.to_json() + "\n",
+    };
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, text)
+                .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+            println!(
+                "Wrote {} nodes and {} edges to {}",
+                graph.nodes.len(),
+                graph.edges.len(),
+                path.display()
+            );
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
 fn cmd_rpkg_inspect(file: PathBuf) -> Result<(), String> {
     let loaded = compiler::rpkg::load_rpkg(&file)
         .map_err(|e| format!("failed to load {}: {}", file.display(), e))?;
@@ -2046,6 +4455,81 @@ fn cmd_rpkg_inspect(file: PathBuf) -> Result<(), String> {
     Ok(())
 }
 
+fn cmd_install(
+    artifact: Option<PathBuf>,
+    prefix: Option<PathBuf>,
+    name: Option<String>,
+    mut rpkg_files: Vec<PathBuf>,
+    gpu: bool,
+    dry_run: bool,
+) -> Result<(), String> {
+    use compiler::tools::install::{InstallOptions, InstallPlan};
+    use compiler::workspace::{self, RayzorManifest};
+
+    // [install] and [build] of the enclosing project fill in what the
+    // command line leaves out
+    let cwd = std::env::current_dir().map_err(|e| format!("Failed to get cwd: {}", e))?;
+    let project = match workspace::find_project_root(&cwd) {
+        Some(root) => match workspace::load_manifest(&root)? {
+            RayzorManifest::SingleProject(project) => Some((root, project)),
+            RayzorManifest::Workspace(_) => None,
+        },
+        None => None,
+    };
+    let (root, config, build_output) = match &project {
+        Some((root, project)) => (
+            root.as_path(),
+            project.install.clone().unwrap_or_default(),
+            project.build.as_ref().and_then(|b| b.output.clone()),
+        ),
+        None => (cwd.as_path(), Default::default(), None),
+    };
+
+    let artifact = artifact
+        .or_else(|| config.artifact.as_ref().map(|a| root.join(a)))
+        .or_else(|| build_output.map(|o| root.join(o)))
+        .ok_or(
+            "No artifact to install: pass one, or set [install] artifact or [build] output \
+             in rayzor.toml",
+        )?;
+    let prefix = match prefix.or_else(|| config.prefix.as_ref().map(|p| root.join(p))) {
+        Some(prefix) => prefix,
+        None => std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .map(|home| PathBuf::from(home).join(".local"))
+            .ok_or("No install prefix: pass --prefix or set [install] prefix")?,
+    };
+    rpkg_files.extend(config.rpkgs.iter().map(|r| root.join(r)));
+
+    let gpu_plugin = if gpu || config.gpu == Some(true) {
+        let plugin = try_load_gpu_plugin().ok_or(
+            "rayzor-gpu plugin not found; build it with \
+             `cargo build --release -p rayzor-gpu --features webgpu-backend` \
+             and copy the library next to rayzor",
+        )?;
+        Some(plugin.path.clone())
+    } else {
+        None
+    };
+
+    let plan = InstallPlan::new(&InstallOptions {
+        artifact,
+        prefix,
+        name: name.or(config.name),
+        rpkg_files,
+        gpu_plugin,
+    })?;
+    print!("{}", plan);
+    if dry_run {
+        return Ok(());
+    }
+    for warning in plan.run()? {
+        eprintln!("warning: {}", warning);
+    }
+    println!("✓ Installed {}", plan.name);
+    Ok(())
+}
+
 /// Resolve entry point from rayzor.toml in current or parent directories.
 fn resolve_entry_from_manifest() -> Result<PathBuf, String> {
     let cwd = std::env::current_dir().map_err(|e| format!("Failed to get cwd: {}", e))?;
@@ -2059,3 +4543,181 @@ fn resolve_entry_from_manifest() -> Result<PathBuf, String> {
         "No entry point in rayzor.toml. Set [project] entry = \"src/Main.hx\"".to_string()
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use compiler::codegen::InterpValue;
+    use compiler::ir::mir_builder::MirBuilder;
+    use compiler::ir::{IrFunctionId, IrModule, IrType};
+
+    /// Build one function per `(name, qualified name, parameters)`
+    fn build_module(
+        functions: &[(&str, &str, &[(&str, IrType)])],
+    ) -> (IrModule, Vec<IrFunctionId>) {
+        let mut builder = MirBuilder::new("Main");
+        let mut ids = Vec::new();
+        for (name, _, params) in functions {
+            let mut function = builder.begin_function(*name);
+            for (param, ty) in params.iter() {
+                function = function.param(*param, ty.clone());
+            }
+            let id = function.build();
+            builder.set_current_function(id);
+            let entry = builder.create_block("entry");
+            builder.set_insert_point(entry);
+            builder.ret(None);
+            ids.push(id);
+        }
+        let mut module = builder.finish();
+        for ((_, qualified, _), id) in functions.iter().zip(&ids) {
+            module.functions.get_mut(id).unwrap().qualified_name = Some(qualified.to_string());
+        }
+        (module, ids)
+    }
+
+    fn array() -> IrType {
+        IrType::Ptr(Box::new(IrType::Void))
+    }
+
+    #[test]
+    fn test_find_entry_function() {
+        let (module, ids) = build_module(&[
+            ("main", "Main.main", &[]),
+            ("run", "app.Server.run", &[]),
+            ("run", "app.Client.run", &[]),
+            ("start", "app.Client.start", &[]),
+        ]);
+
+        assert_eq!(find_entry_function(&module, None), Ok(ids[0]));
+        assert_eq!(
+            find_entry_function(&module, Some("app.Client.run")),
+            Ok(ids[2])
+        );
+        assert_eq!(find_entry_function(&module, Some("start")), Ok(ids[3]));
+
+        let ambiguous = find_entry_function(&module, Some("run")).unwrap_err();
+        assert!(ambiguous.contains("ambiguous"), "{}", ambiguous);
+        assert!(ambiguous.contains("app.Server.run"), "{}", ambiguous);
+        assert!(ambiguous.contains("app.Client.run"), "{}", ambiguous);
+
+        assert_eq!(
+            find_entry_function(&module, Some("app.Client.stop")),
+            Err("Entry function 'app.Client.stop' not found".to_string())
+        );
+        let (empty, _) = build_module(&[]);
+        assert_eq!(
+            find_entry_function(&empty, None),
+            Err("No main function found".to_string())
+        );
+    }
+
+    #[test]
+    fn test_entry_point_args() {
+        let (module, ids) = build_module(&[
+            ("main", "Main.main", &[]),
+            ("withArgs", "Main.withArgs", &[("args", array())]),
+            ("withCount", "Main.withCount", &[("count", IrType::I32)]),
+            ("method", "Main.method", &[("this", array())]),
+            ("pair", "Main.pair", &[("a", array()), ("b", array())]),
+        ]);
+        let args = |i: usize| entry_point_args(&module.functions[&ids[i]]);
+
+        assert!(args(0).unwrap().is_empty());
+        assert!(matches!(
+            args(1).unwrap().as_slice(),
+            [InterpValue::Ptr(ptr)] if *ptr != 0
+        ));
+
+        let wrong_type = args(2).unwrap_err();
+        assert!(wrong_type.contains("'Main.withCount'"), "{}", wrong_type);
+        assert!(wrong_type.contains("`count`"), "{}", wrong_type);
+        assert!(wrong_type.contains("args:Array<String>"), "{}", wrong_type);
+
+        let method = args(3).unwrap_err();
+        assert!(method.contains("instance method"), "{}", method);
+
+        let pair = args(4).unwrap_err();
+        assert!(pair.contains("takes 2 parameters"), "{}", pair);
+    }
+
+    /// The problems `doctor` found, as `(message, fix)`
+    fn problems(doctor: &Doctor) -> Vec<(&str, &str)> {
+        doctor
+            .checks()
+            .filter_map(|check| match check {
+                Check::Problem { message, fix } => Some((message.as_str(), fix.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_doctor_cache() {
+        let dir = std::env::temp_dir().join(format!("rayzor_doctor_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut missing = Doctor::default();
+        doctor_cache(&mut missing, Some(dir.clone()));
+        let [(message, fix)] = problems(&missing)[..] else {
+            panic!("{:?}", missing);
+        };
+        assert!(message.contains("does not exist"), "{}", message);
+        assert!(fix.contains("--cache-dir"), "{}", fix);
+
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut healthy = Doctor::default();
+        doctor_cache(&mut healthy, Some(dir.clone()));
+        assert_eq!(healthy.problems(), 0, "{:?}", healthy);
+        assert!(matches!(healthy.checks().next(), Some(Check::Ok(_))));
+
+        std::fs::write(dir.join("Broken.blade"), b"not a blade file").unwrap();
+        let mut corrupt = Doctor::default();
+        doctor_cache(&mut corrupt, Some(dir.clone()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let [(message, fix)] = problems(&corrupt)[..] else {
+            panic!("{:?}", corrupt);
+        };
+        assert!(message.contains("1 of 1 cached modules"), "{}", message);
+        assert!(fix.contains("rayzor cache verify"), "{}", fix);
+    }
+
+    #[test]
+    fn test_doctor_aot() {
+        let runtime = PathBuf::from("/opt/rayzor/librayzor_runtime.a");
+
+        let mut found = Doctor::default();
+        doctor_aot(&mut found, Ok("clang".to_string()), Ok(runtime.clone()));
+        assert_eq!(
+            found.checks().cloned().collect::<Vec<_>>(),
+            [
+                Check::Ok("linker: clang".to_string()),
+                Check::Ok(format!("runtime library: {}", runtime.display())),
+            ]
+        );
+
+        let mut missing = Doctor::default();
+        doctor_aot(
+            &mut missing,
+            Err("No linker found".to_string()),
+            Err("librayzor_runtime.a not found".to_string()),
+        );
+        let [(linker, linker_fix), (library, library_fix)] = problems(&missing)[..] else {
+            panic!("{:?}", missing);
+        };
+        assert!(linker.contains("no linker found"), "{}", linker);
+        assert!(
+            linker_fix.contains("install clang or gcc"),
+            "{}",
+            linker_fix
+        );
+        assert!(linker_fix.contains("--linker"), "{}", linker_fix);
+        assert!(library.contains("librayzor_runtime.a"), "{}", library);
+        assert!(
+            library_fix.contains("cargo build --release -p rayzor-runtime"),
+            "{}",
+            library_fix
+        );
+        assert_eq!(missing.problems(), 2);
+    }
+}